@@ -4,6 +4,7 @@ use std::{
     hash::{Hash, Hasher},
     net::IpAddr,
     path::{Path as StdPath, PathBuf},
+    sync::{Arc, OnceLock},
     time::{Duration, Instant},
 };
 
@@ -20,13 +21,21 @@ use axum::{
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use static_flow_shared::Article;
+use static_flow_shared::{Article, ArticleKind};
 use static_flow_store::{
     article_request_store::{
         ArticleRequestAiRunChunkRecord, ArticleRequestAiRunRecord, ArticleRequestRecord,
         NewArticleRequestInput, REQUEST_STATUS_DONE, REQUEST_STATUS_FAILED, REQUEST_STATUS_PENDING,
         REQUEST_STATUS_REJECTED, REQUEST_STATUS_RUNNING,
     },
+    article_revision_store::{
+        diff_article_revisions, fetch_article_revision_snapshots, restore_article_snapshot,
+        ArticleRevisionDiff, ArticleRevisionRecord, ArticleRevisionSummary,
+        NewArticleRevisionInput, REVISION_ORIGIN_ROLLBACK,
+    },
+    article_search::{
+        ArticleSearchFilters, ArticleSearchLanguage, SearchCursor, SearchPageRequest,
+    },
     comments_store::{
         CommentAiRunChunkRecord, CommentAiRunRecord, CommentAuditRecord, CommentDataStore,
         CommentTaskPatch, NewCommentAuditInput, NewCommentTaskInput, PublishedCommentPatch,
@@ -34,10 +43,15 @@ use static_flow_store::{
         COMMENT_STATUS_FAILED, COMMENT_STATUS_PENDING, COMMENT_STATUS_REJECTED,
         COMMENT_STATUS_RUNNING,
     },
+    image_variants::{
+        is_resizable_mime_type, render_image_variant, ImageFit, ImageVariantFormat,
+        ImageVariantSpec,
+    },
     lancedb_api::{
         ApiBehaviorBucket, ApiBehaviorEvent, ApiBehaviorOverviewResponse, ArticleListResponse,
         ArticleViewTrackResponse, ArticleViewTrendResponse, CategoriesResponse, ImageListResponse,
-        ImageSearchResponse, ImageTextSearchResponse, SearchResponse, StatsResponse, TagsResponse,
+        ImageMeta, ImageSearchResponse, ImageTextSearchResponse, SearchResponse,
+        SeriesDetailResponse, SeriesListResponse, StatsResponse, TagsResponse,
     },
     music_store::{
         AlbumInfo, ArtistInfo, MusicCommentItem, MusicCommentListResponse, MusicCommentRecord,
@@ -49,7 +63,7 @@ use static_flow_store::{
         WISH_STATUS_RUNNING,
     },
 };
-use tokio::time::sleep;
+use tokio::{sync::Semaphore, time::sleep};
 
 use crate::{
     email::{normalize_frontend_page_url_input, normalize_requester_email_input},
@@ -98,6 +112,62 @@ pub struct SearchQuery {
     pub limit: Option<usize>,
     #[serde(default)]
     pub max_distance: Option<f32>,
+    /// Comma-separated; every tag must match.
+    #[serde(default)]
+    pub tags: Option<String>,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub article_kind: Option<ArticleKind>,
+    #[serde(default)]
+    pub language: Option<ArticleSearchLanguage>,
+    #[serde(default)]
+    pub date_from: Option<String>,
+    #[serde(default)]
+    pub date_to: Option<String>,
+    #[serde(default)]
+    pub cursor: Option<String>,
+}
+
+impl SearchQuery {
+    /// Split the drill-down filters and page request out of the query
+    /// string, rejecting bad dates and cursors up front.
+    fn filters_and_page(
+        &self,
+    ) -> Result<(ArticleSearchFilters, SearchPageRequest), (StatusCode, Json<ErrorResponse>)> {
+        let filters = ArticleSearchFilters {
+            tags: self
+                .tags
+                .as_deref()
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|tag| !tag.is_empty())
+                .map(str::to_string)
+                .collect(),
+            category: self.category.clone(),
+            author: self.author.clone(),
+            article_kind: self.article_kind.clone(),
+            language: self.language,
+            date_from: self.date_from.clone(),
+            date_to: self.date_to.clone(),
+        };
+        filters
+            .validate()
+            .map_err(|err| bad_request(&err.to_string()))?;
+        let cursor = match self.cursor.as_deref().filter(|raw| !raw.is_empty()) {
+            Some(raw) => Some(
+                SearchCursor::decode(raw).ok_or_else(|| bad_request("Invalid search cursor"))?,
+            ),
+            None => None,
+        };
+        Ok((filters, SearchPageRequest {
+            limit: normalize_limit(self.limit),
+            cursor,
+        }))
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -139,6 +209,49 @@ pub struct ImageTextSearchQuery {
 #[derive(Debug, Deserialize)]
 pub struct ImageRenderQuery {
     pub thumb: Option<bool>,
+    #[serde(default, alias = "w")]
+    pub width: Option<u32>,
+    #[serde(default, alias = "h")]
+    pub height: Option<u32>,
+    #[serde(default)]
+    pub fit: Option<ImageFit>,
+    #[serde(default, alias = "q")]
+    pub quality: Option<u8>,
+    /// Explicit output format; negotiated from `Accept` when absent.
+    #[serde(default)]
+    pub format: Option<String>,
+}
+
+impl ImageRenderQuery {
+    /// Validated rendition spec, or `None` when only the stored original (or
+    /// thumbnail) was asked for.
+    fn variant_spec(
+        &self,
+        accept: Option<&str>,
+        source_mime_type: &str,
+    ) -> Result<Option<ImageVariantSpec>, (StatusCode, Json<ErrorResponse>)> {
+        if self.width.is_none()
+            && self.height.is_none()
+            && self.quality.is_none()
+            && self.format.is_none()
+        {
+            return Ok(None);
+        }
+        let format = match self.format.as_deref() {
+            Some(raw) => ImageVariantFormat::parse(raw)
+                .ok_or_else(|| bad_request("format must be one of avif, webp, jpeg, png"))?,
+            None => ImageVariantFormat::negotiate(accept, source_mime_type),
+        };
+        ImageVariantSpec::new(
+            self.width,
+            self.height,
+            self.fit.unwrap_or_default(),
+            self.quality,
+            format,
+        )
+        .map(Some)
+        .map_err(|err| bad_request(&err.to_string()))
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -732,6 +845,17 @@ pub struct AdminCleanupResponse {
 }
 
 const CACHE_TTL: Duration = Duration::from_secs(60);
+/// Concurrent image variant renders; each one is CPU-bound on the blocking
+/// pool. Override with `IMAGE_RENDER_MAX_CONCURRENCY`.
+const DEFAULT_IMAGE_RENDER_MAX_CONCURRENCY: usize = 2;
+/// Longest a request waits for a render slot before it is served the
+/// original instead.
+const IMAGE_RENDER_QUEUE_TIMEOUT: Duration = Duration::from_secs(2);
+const IMAGE_CACHE_CONTROL: &str = "public, max-age=31536000";
+/// Cache policy for an original served in place of a variant that could not
+/// be rendered right now, so the variant is retried soon.
+const IMAGE_FALLBACK_CACHE_CONTROL: &str = "public, max-age=60";
+static IMAGE_RENDER_SEMAPHORE: OnceLock<Arc<Semaphore>> = OnceLock::new();
 
 pub async fn list_articles(
     State(state): State<AppState>,
@@ -2971,6 +3095,49 @@ pub async fn list_categories(
     }))
 }
 
+pub async fn list_series(
+    State(state): State<AppState>,
+) -> Result<Json<SeriesListResponse>, (StatusCode, Json<ErrorResponse>)> {
+    if let Some(series) = read_cache(state.series_cache.as_ref()) {
+        return Ok(Json(SeriesListResponse {
+            series,
+        }));
+    }
+
+    let series = state
+        .store
+        .list_series()
+        .await
+        .map_err(|e| internal_error("Failed to fetch series", e))?;
+
+    write_cache(state.series_cache.as_ref(), series.clone());
+    Ok(Json(SeriesListResponse {
+        series,
+    }))
+}
+
+pub async fn get_series(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<SeriesDetailResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let series = state
+        .store
+        .get_series(&id)
+        .await
+        .map_err(|e| internal_error("Failed to fetch series", e))?;
+
+    match series {
+        Some(series) => Ok(Json(series)),
+        None => Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Series not found".to_string(),
+                code: 404,
+            }),
+        )),
+    }
+}
+
 pub async fn get_stats(
     State(state): State<AppState>,
 ) -> Result<Json<StatsResponse>, (StatusCode, Json<ErrorResponse>)> {
@@ -2994,24 +3161,17 @@ pub async fn search_articles(
 ) -> Result<Json<SearchResponse>, (StatusCode, Json<ErrorResponse>)> {
     let keyword = query.q.trim();
     if keyword.is_empty() {
-        return Ok(Json(SearchResponse {
-            results: vec![],
-            total: 0,
-            query: query.q,
-        }));
+        return Ok(Json(SearchResponse::empty(query.q)));
     }
+    let (filters, page) = query.filters_and_page()?;
 
     let results = state
         .store
-        .search_articles(keyword, normalize_limit(query.limit))
+        .search_articles(keyword, &filters, &page)
         .await
         .map_err(|e| internal_error("Failed to search articles", e))?;
 
-    Ok(Json(SearchResponse {
-        total: results.len(),
-        results,
-        query: query.q,
-    }))
+    Ok(Json(SearchResponse::from_page(query.q, results)))
 }
 
 pub async fn semantic_search(
@@ -3020,18 +3180,16 @@ pub async fn semantic_search(
 ) -> Result<Json<SearchResponse>, (StatusCode, Json<ErrorResponse>)> {
     let keyword = query.q.trim();
     if keyword.is_empty() {
-        return Ok(Json(SearchResponse {
-            results: vec![],
-            total: 0,
-            query: query.q,
-        }));
+        return Ok(Json(SearchResponse::empty(query.q)));
     }
+    let (filters, page) = query.filters_and_page()?;
 
     let results = state
         .store
         .semantic_search(
             keyword,
-            normalize_limit(query.limit),
+            &filters,
+            &page,
             normalize_max_distance(query.max_distance),
             query.enhanced_highlight,
             query.hybrid,
@@ -3042,11 +3200,7 @@ pub async fn semantic_search(
         .await
         .map_err(|e| internal_error("Failed to run semantic search", e))?;
 
-    Ok(Json(SearchResponse {
-        total: results.len(),
-        results,
-        query: query.q,
-    }))
+    Ok(Json(SearchResponse::from_page(query.q, results)))
 }
 
 pub async fn related_articles(
@@ -3215,38 +3369,238 @@ pub async fn search_images_by_text(
     }))
 }
 
+/// Serve a stored image, its thumbnail, or a resized / re-encoded variant.
+///
+/// Validators come from the image row alone (content-hash id plus
+/// `created_at`), so conditional requests are answered with 304 before any
+/// blob is read. Variants are looked up in the `image_variants` cache first
+/// and rendered on a bounded blocking pool on a miss. When the render queue
+/// is full or the source cannot be decoded, the original is served under its
+/// own ETag with a short TTL so the variant URL is retried later.
 pub async fn serve_image(
     State(state): State<AppState>,
     Path(filename): Path<String>,
     Query(query): Query<ImageRenderQuery>,
+    headers: HeaderMap,
 ) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
-    let image = state
+    let meta = state
         .store
-        .get_image(&filename, query.thumb.unwrap_or(false))
+        .get_image_meta(&filename)
         .await
-        .map_err(|e| internal_error("Failed to fetch image", e))?;
-
-    let image = match image {
-        Some(image) => image,
+        .map_err(|e| internal_error("Failed to fetch image", e))?
+        .ok_or_else(image_not_found)?;
+
+    let prefer_thumbnail = query.thumb.unwrap_or(false);
+    let accept = headers
+        .get(header::ACCEPT)
+        .and_then(|value| value.to_str().ok());
+    let variant = query
+        .variant_spec(accept, &meta.mime_type)?
+        .filter(|_| !prefer_thumbnail && is_resizable_mime_type(&meta.mime_type));
+    let etag = match (&variant, prefer_thumbnail) {
+        (Some(spec), _) => format!("\"{}-{}\"", meta.id, spec.cache_key()),
+        (None, true) => format!("\"{}-thumb\"", meta.id),
+        (None, false) => format!("\"{}\"", meta.id),
+    };
+    let vary_accept = variant.is_some() && query.format.is_none();
+
+    if is_not_modified(&headers, &etag, meta.created_at) {
+        return image_response_builder(&meta, &etag, IMAGE_CACHE_CONTROL, vary_accept)
+            .status(StatusCode::NOT_MODIFIED)
+            .body(Body::empty())
+            .map_err(|e| internal_error("Failed to build response", e));
+    }
+
+    let (bytes, mime_type, etag, cache_control) = match variant {
+        Some(spec) => match load_image_variant(&state, &meta, spec).await? {
+            ImageVariantBody::Rendered {
+                bytes,
+                mime_type,
+            } => (bytes, mime_type, etag, IMAGE_CACHE_CONTROL),
+            ImageVariantBody::Original {
+                bytes,
+                mime_type,
+            } => (bytes, mime_type, format!("\"{}\"", meta.id), IMAGE_FALLBACK_CACHE_CONTROL),
+        },
         None => {
-            return Err((
-                StatusCode::NOT_FOUND,
-                Json(ErrorResponse {
-                    error: "Image not found".to_string(),
-                    code: 404,
-                }),
-            ));
+            let image = state
+                .store
+                .get_image(&meta.id, prefer_thumbnail)
+                .await
+                .map_err(|e| internal_error("Failed to fetch image", e))?
+                .ok_or_else(image_not_found)?;
+            (image.bytes, image.mime_type, etag, IMAGE_CACHE_CONTROL)
         },
     };
 
-    Response::builder()
+    image_response_builder(&meta, &etag, cache_control, vary_accept)
         .status(StatusCode::OK)
-        .header(header::CONTENT_TYPE, image.mime_type)
-        .header(header::CACHE_CONTROL, "public, max-age=31536000")
-        .body(Body::from(image.bytes))
+        .header(header::CONTENT_TYPE, mime_type)
+        .body(Body::from(bytes))
         .map_err(|e| internal_error("Failed to build response", e))
 }
 
+fn image_response_builder(
+    meta: &ImageMeta,
+    etag: &str,
+    cache_control: &'static str,
+    vary_accept: bool,
+) -> axum::http::response::Builder {
+    let builder = Response::builder()
+        .header(header::CACHE_CONTROL, cache_control)
+        .header(header::ETAG, etag)
+        .header(header::LAST_MODIFIED, http_date(meta.created_at));
+    if vary_accept {
+        builder.header(header::VARY, "Accept")
+    } else {
+        builder
+    }
+}
+
+/// What [`load_image_variant`] produced: the requested rendition, or the
+/// stored original when no rendition could be made in time.
+enum ImageVariantBody {
+    Rendered { bytes: Vec<u8>, mime_type: String },
+    Original { bytes: Vec<u8>, mime_type: String },
+}
+
+async fn load_image_variant(
+    state: &AppState,
+    meta: &ImageMeta,
+    spec: ImageVariantSpec,
+) -> Result<ImageVariantBody, (StatusCode, Json<ErrorResponse>)> {
+    let variant_key = spec.cache_key();
+    match state.store.get_image_variant(&meta.id, &variant_key).await {
+        Ok(Some(cached)) => {
+            return Ok(ImageVariantBody::Rendered {
+                bytes: cached.bytes,
+                mime_type: cached.mime_type,
+            })
+        },
+        Ok(None) => {},
+        Err(err) => tracing::warn!(
+            "Failed to read cached image variant; image_id={}; variant={variant_key}; \
+             error={err:#}",
+            meta.id
+        ),
+    }
+
+    let original = state
+        .store
+        .get_image(&meta.id, false)
+        .await
+        .map_err(|e| internal_error("Failed to fetch image", e))?
+        .ok_or_else(image_not_found)?;
+    let permit = match tokio::time::timeout(
+        IMAGE_RENDER_QUEUE_TIMEOUT,
+        image_render_semaphore().clone().acquire_owned(),
+    )
+    .await
+    {
+        Ok(permit) => permit.map_err(|e| internal_error("Image render limiter closed", e))?,
+        Err(_) => {
+            tracing::warn!(
+                "Image render queue full, serving original; image_id={}; variant={variant_key}",
+                meta.id
+            );
+            return Ok(ImageVariantBody::Original {
+                bytes: original.bytes,
+                mime_type: original.mime_type,
+            });
+        },
+    };
+    let source = original.bytes;
+    let (source, rendered) = tokio::task::spawn_blocking(move || {
+        let _permit = permit;
+        let rendered = render_image_variant(&source, &spec);
+        (source, rendered)
+    })
+    .await
+    .map_err(|e| internal_error("Image render task failed", e))?;
+
+    match rendered {
+        Ok(rendered) => {
+            let bytes = rendered.bytes.clone();
+            let mime_type = rendered.mime_type.clone();
+            let store = state.store.clone();
+            let image_id = meta.id.clone();
+            tokio::spawn(async move {
+                if let Err(err) = store
+                    .put_image_variant(&image_id, &variant_key, &rendered)
+                    .await
+                {
+                    tracing::warn!(
+                        "Failed to cache image variant; image_id={image_id}; \
+                         variant={variant_key}; error={err:#}"
+                    );
+                }
+            });
+            Ok(ImageVariantBody::Rendered {
+                bytes,
+                mime_type,
+            })
+        },
+        Err(err) => {
+            tracing::warn!(
+                "Image variant render failed, serving original; image_id={}; \
+                 variant={variant_key}; error={err:#}",
+                meta.id
+            );
+            Ok(ImageVariantBody::Original {
+                bytes: source,
+                mime_type: original.mime_type,
+            })
+        },
+    }
+}
+
+fn image_render_semaphore() -> &'static Arc<Semaphore> {
+    IMAGE_RENDER_SEMAPHORE.get_or_init(|| {
+        let max_concurrency = std::env::var("IMAGE_RENDER_MAX_CONCURRENCY")
+            .ok()
+            .and_then(|value| value.parse::<usize>().ok())
+            .filter(|value| *value > 0)
+            .unwrap_or(DEFAULT_IMAGE_RENDER_MAX_CONCURRENCY);
+        Arc::new(Semaphore::new(max_concurrency))
+    })
+}
+
+/// `If-None-Match` wins over `If-Modified-Since` (RFC 9110 §13.2.2);
+/// `Last-Modified` has one-second resolution, so compare in seconds.
+fn is_not_modified(headers: &HeaderMap, etag: &str, last_modified_ms: i64) -> bool {
+    if let Some(if_none_match) = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+    {
+        return if_none_match
+            .split(',')
+            .map(|candidate| candidate.trim().trim_start_matches("W/"))
+            .any(|candidate| candidate == "*" || candidate == etag);
+    }
+    headers
+        .get(header::IF_MODIFIED_SINCE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| chrono::DateTime::parse_from_rfc2822(value).ok())
+        .is_some_and(|since| last_modified_ms.div_euclid(1000) <= since.timestamp())
+}
+
+fn http_date(timestamp_ms: i64) -> String {
+    chrono::DateTime::from_timestamp_millis(timestamp_ms)
+        .unwrap_or_default()
+        .format("%a, %d %b %Y %H:%M:%S GMT")
+        .to_string()
+}
+
+fn image_not_found() -> (StatusCode, Json<ErrorResponse>) {
+    (
+        StatusCode::NOT_FOUND,
+        Json(ErrorResponse {
+            error: "Image not found".to_string(),
+            code: 404,
+        }),
+    )
+}
+
 async fn ensure_article_exists(
     state: &AppState,
     id: &str,
@@ -5580,6 +5934,201 @@ pub async fn admin_article_request_ai_stream(
     ))
 }
 
+// Article revision admin routes
+
+#[derive(Debug, Deserialize)]
+pub struct AdminArticleRevisionListQuery {
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AdminArticleRevisionListResponse {
+    pub article_id: String,
+    pub revisions: Vec<ArticleRevisionSummary>,
+    pub total: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AdminArticleRevisionDiffQuery {
+    pub from: i32,
+    pub to: Option<i32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AdminArticleRollbackResponse {
+    pub article_id: String,
+    pub restored_from: i32,
+    pub revision: Option<ArticleRevisionSummary>,
+}
+
+fn article_revision_not_found(
+    article_id: &str,
+    revision_no: i32,
+) -> (StatusCode, Json<ErrorResponse>) {
+    (
+        StatusCode::NOT_FOUND,
+        Json(ErrorResponse {
+            error: format!("Revision {revision_no} not found for article `{article_id}`"),
+            code: 404,
+        }),
+    )
+}
+
+pub async fn admin_list_article_revisions(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(article_id): Path<String>,
+    Query(query): Query<AdminArticleRevisionListQuery>,
+) -> Result<Json<AdminArticleRevisionListResponse>, (StatusCode, Json<ErrorResponse>)> {
+    ensure_admin_access(&state, &headers)?;
+    let limit = query
+        .limit
+        .filter(|value| *value > 0)
+        .unwrap_or(50)
+        .min(500);
+    let total = state
+        .article_revision_store
+        .count_revisions(&article_id)
+        .await
+        .map_err(|e| internal_error("Failed to count article revisions", e))?;
+    let revisions = state
+        .article_revision_store
+        .list_revisions(&article_id, Some(limit))
+        .await
+        .map_err(|e| internal_error("Failed to list article revisions", e))?;
+    Ok(Json(AdminArticleRevisionListResponse {
+        article_id,
+        revisions,
+        total,
+    }))
+}
+
+pub async fn admin_get_article_revision(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path((article_id, revision_no)): Path<(String, i32)>,
+) -> Result<Json<ArticleRevisionRecord>, (StatusCode, Json<ErrorResponse>)> {
+    ensure_admin_access(&state, &headers)?;
+    let record = state
+        .article_revision_store
+        .get_revision(&article_id, revision_no)
+        .await
+        .map_err(|e| internal_error("Failed to get article revision", e))?
+        .ok_or_else(|| article_revision_not_found(&article_id, revision_no))?;
+    Ok(Json(record))
+}
+
+pub async fn admin_diff_article_revisions(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(article_id): Path<String>,
+    Query(query): Query<AdminArticleRevisionDiffQuery>,
+) -> Result<Json<ArticleRevisionDiff>, (StatusCode, Json<ErrorResponse>)> {
+    ensure_admin_access(&state, &headers)?;
+    let from = state
+        .article_revision_store
+        .get_revision(&article_id, query.from)
+        .await
+        .map_err(|e| internal_error("Failed to get article revision", e))?
+        .ok_or_else(|| article_revision_not_found(&article_id, query.from))?;
+    let to = match query.to {
+        Some(revision_no) => state
+            .article_revision_store
+            .get_revision(&article_id, revision_no)
+            .await
+            .map_err(|e| internal_error("Failed to get article revision", e))?
+            .ok_or_else(|| article_revision_not_found(&article_id, revision_no))?,
+        None => state
+            .article_revision_store
+            .latest_revision(&article_id)
+            .await
+            .map_err(|e| internal_error("Failed to get latest article revision", e))?
+            .ok_or_else(|| article_revision_not_found(&article_id, query.from))?,
+    };
+    Ok(Json(diff_article_revisions(&from, &to)))
+}
+
+pub async fn admin_rollback_article_revision(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path((article_id, revision_no)): Path<(String, i32)>,
+    Json(request): Json<AdminTaskActionRequest>,
+) -> Result<Json<AdminArticleRollbackResponse>, (StatusCode, Json<ErrorResponse>)> {
+    ensure_admin_access(&state, &headers)?;
+    let operator = request
+        .operator
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| "admin".to_string());
+    let target = state
+        .article_revision_store
+        .get_revision(&article_id, revision_no)
+        .await
+        .map_err(|e| internal_error("Failed to get article revision", e))?
+        .ok_or_else(|| article_revision_not_found(&article_id, revision_no))?;
+    let articles = state
+        .store
+        .articles_table()
+        .await
+        .map_err(|e| internal_error("Failed to open articles table", e))?;
+    let ids = [article_id.clone()];
+    let current = fetch_article_revision_snapshots(&articles, &ids)
+        .await
+        .map_err(|e| internal_error("Failed to read current article", e))?;
+    let Some(current) = current.get(&article_id) else {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("Article `{article_id}` not found"),
+                code: 404,
+            }),
+        ));
+    };
+    state
+        .article_revision_store
+        .ensure_baseline(&article_id, &operator, current)
+        .await
+        .map_err(|e| internal_error("Failed to record article baseline revision", e))?;
+
+    restore_article_snapshot(&articles, &article_id, &target.snapshot)
+        .await
+        .map_err(|e| internal_error("Failed to restore article revision", e))?;
+    let restored = fetch_article_revision_snapshots(&articles, &ids)
+        .await
+        .map_err(|e| internal_error("Failed to read restored article", e))?
+        .remove(&article_id)
+        .unwrap_or_else(|| target.snapshot.clone());
+    let revision = state
+        .article_revision_store
+        .record_revision(NewArticleRevisionInput {
+            article_id: article_id.clone(),
+            origin: REVISION_ORIGIN_ROLLBACK.to_string(),
+            revision_author: operator,
+            rollback_of: Some(revision_no),
+            snapshot: restored,
+        })
+        .await
+        .map_err(|e| internal_error("Failed to record rollback revision", e))?;
+
+    // Taxonomy, series and stats caches may hold the pre-rollback
+    // tags/category/series membership.
+    *state.tags_cache.write() = None;
+    *state.categories_cache.write() = None;
+    *state.series_cache.write() = None;
+    *state.stats_cache.write() = None;
+    tracing::info!(
+        article_id = %article_id,
+        restored_from = revision_no,
+        "article rolled back; vectors cleared until the next backfill"
+    );
+
+    Ok(Json(AdminArticleRollbackResponse {
+        article_id,
+        restored_from: revision_no,
+        revision: revision.map(|record| record.summary),
+    }))
+}
+
 #[cfg(test)]
 mod tests {
     use std::path::PathBuf;
@@ -5588,8 +6137,9 @@ mod tests {
 
     use super::{
         apply_api_behavior_config_update, apply_compaction_runtime_config_update,
-        apply_view_analytics_config_update, gpt2api_frontend_index_path, is_local_host_header,
-        normalize_public_nickname_input, parse_raw_markdown_lang, UpdateApiBehaviorConfigRequest,
+        apply_view_analytics_config_update, gpt2api_frontend_index_path, http_date,
+        is_local_host_header, is_not_modified, normalize_public_nickname_input,
+        parse_raw_markdown_lang, ImageRenderQuery, UpdateApiBehaviorConfigRequest,
         UpdateCompactionRuntimeConfigRequest, UpdateViewAnalyticsConfigRequest,
     };
     use crate::{
@@ -5606,6 +6156,63 @@ mod tests {
         assert_eq!(extract_client_ip(&headers), "198.51.100.1");
     }
 
+    #[test]
+    fn image_conditional_requests_prefer_etag_over_date() {
+        let last_modified_ms = 1_700_000_000_123;
+        let etag = "\"abc-w320-h0-contain-q75.avif\"";
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "if-none-match",
+            HeaderValue::from_static("\"other\", W/\"abc-w320-h0-contain-q75.avif\""),
+        );
+        assert!(is_not_modified(&headers, etag, last_modified_ms));
+
+        headers.insert("if-none-match", HeaderValue::from_static("\"abc\""));
+        headers.insert(
+            "if-modified-since",
+            HeaderValue::from_str(&http_date(last_modified_ms)).expect("header"),
+        );
+        assert!(!is_not_modified(&headers, etag, last_modified_ms));
+
+        headers.remove("if-none-match");
+        assert!(is_not_modified(&headers, etag, last_modified_ms));
+        assert!(!is_not_modified(&headers, etag, last_modified_ms + 1_000));
+        assert_eq!(http_date(0), "Thu, 01 Jan 1970 00:00:00 GMT");
+    }
+
+    #[test]
+    fn image_render_query_only_builds_variants_when_asked() {
+        let plain = ImageRenderQuery {
+            thumb: None,
+            width: None,
+            height: None,
+            fit: None,
+            quality: None,
+            format: None,
+        };
+        assert!(plain
+            .variant_spec(Some("image/avif"), "image/jpeg")
+            .expect("plain")
+            .is_none());
+
+        let resized = ImageRenderQuery {
+            width: Some(640),
+            ..plain
+        };
+        let spec = resized
+            .variant_spec(Some("image/avif,image/webp"), "image/jpeg")
+            .expect("valid")
+            .expect("variant");
+        assert_eq!(spec.cache_key(), "w768-h0-contain-q75.avif");
+
+        let bad_format = ImageRenderQuery {
+            format: Some("tiff".to_string()),
+            ..resized
+        };
+        assert!(bad_format.variant_spec(None, "image/jpeg").is_err());
+    }
+
     #[test]
     fn gpt2api_frontend_index_path_points_to_static_entry() {
         let path = gpt2api_frontend_index_path(PathBuf::from("/tmp/frontend/dist").as_path());
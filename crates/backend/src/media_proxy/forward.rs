@@ -5,7 +5,7 @@ use axum::{
     response::Response,
 };
 use bytes::Bytes;
-use static_flow_media_types::{PosterQuery, RawPlaybackQuery, UploadChunkQuery};
+use static_flow_media_types::{PosterQuery, RawPlaybackQuery, SubtitleQuery, UploadChunkQuery};
 
 fn join_internal_url(base_url: &reqwest::Url, relative: &str) -> Result<reqwest::Url> {
     base_url
@@ -98,6 +98,17 @@ pub async fn forward_poster_request(
     forward(upstream).await
 }
 
+pub async fn forward_subtitle_request(
+    client: &reqwest::Client,
+    base_url: &reqwest::Url,
+    query: &SubtitleQuery,
+) -> Result<Response> {
+    let upstream = client
+        .get(join_internal_url(base_url, "internal/local-media/subtitle")?)
+        .query(query);
+    forward(upstream).await
+}
+
 pub async fn forward_upload_chunk_request(
     client: &reqwest::Client,
     base_url: &reqwest::Url,
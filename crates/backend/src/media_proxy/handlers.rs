@@ -8,15 +8,17 @@ use axum::{
 use serde::de::DeserializeOwned;
 use static_flow_media_types::{
     CreateUploadTaskRequest, CreateUploadTaskResponse, ListUploadTasksQuery,
-    ListUploadTasksResponse, LocalMediaListQuery, LocalMediaListResponse, OpenPlaybackRequest,
-    PlaybackJobStatusResponse, PlaybackOpenResponse, PosterQuery, RawPlaybackQuery,
-    UploadChunkQuery, UploadTaskRecord,
+    ListUploadTasksResponse, LocalMediaCacheUsageResponse, LocalMediaListQuery,
+    LocalMediaListResponse, LocalMediaPlaybackProgress, OpenPlaybackRequest, PlaybackHistoryQuery,
+    PlaybackHistoryResponse, PlaybackJobStatusResponse, PlaybackOpenResponse, PosterQuery,
+    RawPlaybackQuery, SubtitleQuery, UpdatePlaybackProgressRequest, UploadChunkQuery,
+    UploadTaskRecord,
 };
 
 use super::{
     forward::{
         forward_hls_request, forward_mp4_request, forward_poster_request, forward_raw_request,
-        forward_upload_chunk_request,
+        forward_subtitle_request, forward_upload_chunk_request,
     },
     MediaProxyState,
 };
@@ -66,6 +68,43 @@ pub async fn open_local_media_playback(
     Ok(Json(response))
 }
 
+pub async fn update_local_media_playback_progress(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<UpdatePlaybackProgressRequest>,
+) -> HandlerResult<Json<LocalMediaPlaybackProgress>> {
+    ensure_admin_access(&state, &headers)?;
+    let media_proxy = configured_media_proxy(&state)?;
+    let response: LocalMediaPlaybackProgress = send_json(
+        media_proxy
+            .client()
+            .post(join_internal_url(
+                media_proxy.as_ref(),
+                "internal/local-media/playback/progress",
+            )?)
+            .json(&request),
+    )
+    .await?;
+    Ok(Json(response))
+}
+
+pub async fn list_local_media_playback_history(
+    State(state): State<AppState>,
+    Query(query): Query<PlaybackHistoryQuery>,
+    headers: HeaderMap,
+) -> HandlerResult<Json<PlaybackHistoryResponse>> {
+    ensure_admin_access(&state, &headers)?;
+    let media_proxy = configured_media_proxy(&state)?;
+    let response: PlaybackHistoryResponse = send_json(
+        media_proxy
+            .client()
+            .get(join_internal_url(media_proxy.as_ref(), "internal/local-media/playback/history")?)
+            .query(&query),
+    )
+    .await?;
+    Ok(Json(response))
+}
+
 pub async fn list_upload_tasks(
     State(state): State<AppState>,
     Query(query): Query<ListUploadTasksQuery>,
@@ -134,6 +173,21 @@ pub async fn get_local_media_job_status(
     Ok(Json(response))
 }
 
+pub async fn get_local_media_cache_usage(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> HandlerResult<Json<LocalMediaCacheUsageResponse>> {
+    ensure_admin_access(&state, &headers)?;
+    let media_proxy = configured_media_proxy(&state)?;
+    let response: LocalMediaCacheUsageResponse = send_json(
+        media_proxy
+            .client()
+            .get(join_internal_url(media_proxy.as_ref(), "internal/local-media/cache")?),
+    )
+    .await?;
+    Ok(Json(response))
+}
+
 pub async fn stream_local_media_raw(
     State(state): State<AppState>,
     Query(query): Query<RawPlaybackQuery>,
@@ -200,6 +254,18 @@ pub async fn stream_local_media_poster(
         .map_err(bad_gateway)
 }
 
+pub async fn stream_local_media_subtitle(
+    State(state): State<AppState>,
+    Query(query): Query<SubtitleQuery>,
+    headers: HeaderMap,
+) -> HandlerResult<Response> {
+    ensure_admin_access(&state, &headers)?;
+    let media_proxy = configured_media_proxy(&state)?;
+    forward_subtitle_request(media_proxy.client(), &media_proxy.config().base_url, &query)
+        .await
+        .map_err(bad_gateway)
+}
+
 pub async fn append_upload_chunk(
     State(state): State<AppState>,
     Path(task_id): Path<String>,
@@ -117,8 +117,11 @@ pub fn create_router(state: AppState) -> Router {
         .route("/api/comments/stats", get(handlers::get_comment_stats))
         .route("/api/tags", get(handlers::list_tags))
         .route("/api/categories", get(handlers::list_categories))
+        .route("/api/series", get(handlers::list_series))
+        .route("/api/series/:id", get(handlers::get_series))
         .route("/api/stats", get(handlers::get_stats))
         .route("/api/search", get(handlers::search_articles))
+        .route("/api/search/all", get(unified_search::search_all))
         .route("/api/semantic-search", get(handlers::semantic_search))
         .route("/api/images/random", get(handlers::random_images))
         .route("/api/images/:filename", get(handlers::serve_image))
@@ -414,6 +417,17 @@ pub fn create_router(state: AppState) -> Router {
         .route(
             "/admin/article-requests/tasks/:request_id/ai-output/stream",
             get(handlers::admin_article_request_ai_stream),
+        )
+        // Article revision routes
+        .route("/admin/articles/:id/revisions", get(handlers::admin_list_article_revisions))
+        .route("/admin/articles/:id/revisions/diff", get(handlers::admin_diff_article_revisions))
+        .route(
+            "/admin/articles/:id/revisions/:revision_no",
+            get(handlers::admin_get_article_revision),
+        )
+        .route(
+            "/admin/articles/:id/revisions/:revision_no/rollback",
+            post(handlers::admin_rollback_article_revision),
         );
 
     #[cfg(feature = "local-media")]
@@ -427,6 +441,14 @@ pub fn create_router(state: AppState) -> Router {
             "/admin/local-media/api/playback/jobs/:job_id",
             get(crate::media_proxy::handlers::get_local_media_job_status),
         )
+        .route(
+            "/admin/local-media/api/playback/progress",
+            post(crate::media_proxy::handlers::update_local_media_playback_progress),
+        )
+        .route(
+            "/admin/local-media/api/playback/history",
+            get(crate::media_proxy::handlers::list_local_media_playback_history),
+        )
         .route(
             "/admin/local-media/api/playback/raw",
             get(crate::media_proxy::handlers::stream_local_media_raw),
@@ -443,6 +465,14 @@ pub fn create_router(state: AppState) -> Router {
             "/admin/local-media/api/poster",
             get(crate::media_proxy::handlers::stream_local_media_poster),
         )
+        .route(
+            "/admin/local-media/api/subtitle",
+            get(crate::media_proxy::handlers::stream_local_media_subtitle),
+        )
+        .route(
+            "/admin/local-media/api/cache",
+            get(crate::media_proxy::handlers::get_local_media_cache_usage),
+        )
         .route(
             "/admin/local-media/api/uploads/tasks",
             post(crate::media_proxy::handlers::create_upload_task)
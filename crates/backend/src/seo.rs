@@ -259,6 +259,21 @@ fn build_article_json_ld(
             kw.join(", ")
         ));
     }
+    if let Some(series) = article.series.as_ref() {
+        ld.push_str(&format!(
+            r#",
+  "isPartOf": {{ "@type": "CreativeWorkSeries", "name": "{}", "identifier": "{}" }}"#,
+            json_escape(&series.title),
+            json_escape(&series.id),
+        ));
+        if series.position > 0 {
+            ld.push_str(&format!(
+                r#",
+  "position": {}"#,
+                series.position
+            ));
+        }
+    }
     ld.push_str("\n}\n</script>");
     ld
 }
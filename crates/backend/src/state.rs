@@ -11,10 +11,12 @@ use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 use static_flow_store::{
     article_request_store::ArticleRequestStore,
+    article_revision_store::ArticleRevisionStore,
     comments_store::CommentDataStore,
     interactive_store::InteractivePageStore,
     lancedb_api::{
-        CategoryInfo, NewApiBehaviorEventInput, StaticFlowDataStore, StatsResponse, TagInfo,
+        CategoryInfo, NewApiBehaviorEventInput, SeriesInfo, StaticFlowDataStore, StatsResponse,
+        TagInfo,
     },
     llm_gateway_store::LlmGatewayStore as Gpt2ApiContributionStore,
     music_store::MusicDataStore,
@@ -210,6 +212,7 @@ pub struct AppState {
     pub(crate) gpt2api_rs: Arc<Gpt2ApiRsState>,
     pub(crate) tags_cache: SharedListCache<TagInfo>,
     pub(crate) categories_cache: SharedListCache<CategoryInfo>,
+    pub(crate) series_cache: SharedListCache<SeriesInfo>,
     pub(crate) stats_cache: SharedValueCache<StatsResponse>,
     pub(crate) view_analytics_config: Arc<RwLock<ViewAnalyticsRuntimeConfig>>,
     pub(crate) comment_runtime_config: Arc<RwLock<CommentRuntimeConfig>>,
@@ -228,6 +231,7 @@ pub struct AppState {
     pub(crate) article_request_store: Arc<ArticleRequestStore>,
     pub(crate) article_request_worker_tx: mpsc::Sender<String>,
     pub(crate) article_request_submit_guard: Arc<PublicSubmitGuard>,
+    pub(crate) article_revision_store: Arc<ArticleRevisionStore>,
     pub(crate) gpt2api_public_submit_guard: Arc<PublicSubmitGuard>,
     pub(crate) interactive_store: Arc<InteractivePageStore>,
     pub(crate) gpt2api_contribution_store: Arc<Gpt2ApiContributionStore>,
@@ -261,6 +265,7 @@ impl AppState {
         let music_store = Arc::new(MusicDataStore::connect(music_db_uri).await?);
         let music_wish_store = Arc::new(MusicWishStore::connect(music_db_uri).await?);
         let article_request_store = Arc::new(ArticleRequestStore::connect(content_db_uri).await?);
+        let article_revision_store = Arc::new(ArticleRevisionStore::connect(content_db_uri).await?);
         let interactive_store = Arc::new(InteractivePageStore::connect(content_db_uri).await?);
         let gpt2api_contribution_store =
             Arc::new(Gpt2ApiContributionStore::connect(content_db_uri).await?);
@@ -340,6 +345,7 @@ impl AppState {
             gpt2api_rs,
             tags_cache: Arc::new(RwLock::new(None)),
             categories_cache: Arc::new(RwLock::new(None)),
+            series_cache: Arc::new(RwLock::new(None)),
             stats_cache: Arc::new(RwLock::new(None)),
             view_analytics_config: Arc::new(RwLock::new(ViewAnalyticsRuntimeConfig::default())),
             comment_runtime_config,
@@ -358,6 +364,7 @@ impl AppState {
             article_request_store,
             article_request_worker_tx,
             article_request_submit_guard: Arc::new(RwLock::new(HashMap::new())),
+            article_revision_store,
             gpt2api_public_submit_guard: Arc::new(RwLock::new(HashMap::new())),
             interactive_store,
             gpt2api_contribution_store,
@@ -0,0 +1,334 @@
+//! Cross-corpus search behind `/api/search/all`.
+//!
+//! One query fans out to articles, songs, images and interactive mirrors
+//! concurrently. Each corpus runs under its own time budget, so a slow or
+//! failing store only empties its own group (with a status saying why)
+//! instead of failing the request. The typed groups keep each corpus's own
+//! ranking; `top` interleaves them with reciprocal rank fusion, the same
+//! scheme hybrid article search uses to merge vector and lexical hits.
+
+use std::{
+    collections::HashMap,
+    future::Future,
+    time::{Duration, Instant},
+};
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use static_flow_store::{
+    article_search::{ArticleSearchFilters, SearchPageRequest},
+    interactive_store::InteractivePageSearchResult,
+    lancedb_api::{ImageInfo, SearchResult},
+    music_store::SongSearchResult,
+};
+
+use crate::{handlers::ErrorResponse, state::AppState};
+
+/// Per-corpus budget before its group is reported as timed out.
+const CORPUS_TIMEOUT: Duration = Duration::from_millis(1500);
+const DEFAULT_GROUP_LIMIT: usize = 5;
+const MAX_GROUP_LIMIT: usize = 20;
+const RRF_K: f32 = 60.0;
+
+#[derive(Debug, Deserialize)]
+pub struct UnifiedSearchQuery {
+    pub q: String,
+    /// Hits per corpus.
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnifiedSearchGroupStatus {
+    Ok,
+    /// The corpus was not queried (e.g. blank query).
+    Skipped,
+    TimedOut,
+    Failed,
+}
+
+/// One corpus's results in its native shape.
+#[derive(Debug, Clone, Serialize)]
+pub struct UnifiedSearchGroup<T> {
+    pub status: UnifiedSearchGroupStatus,
+    pub elapsed_ms: u64,
+    pub items: Vec<T>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnifiedSearchKind {
+    Article,
+    Song,
+    Image,
+    InteractivePage,
+}
+
+/// A fused cross-corpus hit. `id` is what the frontend routes on: the
+/// article id for articles and interactive mirrors, the song id, or the
+/// image filename.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UnifiedSearchHit {
+    pub kind: UnifiedSearchKind,
+    pub id: String,
+    pub title: String,
+    pub subtitle: String,
+    pub score: f32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UnifiedSearchResponse {
+    pub query: String,
+    pub top: Vec<UnifiedSearchHit>,
+    pub articles: UnifiedSearchGroup<SearchResult>,
+    pub songs: UnifiedSearchGroup<SongSearchResult>,
+    pub images: UnifiedSearchGroup<ImageInfo>,
+    pub interactive_pages: UnifiedSearchGroup<InteractivePageSearchResult>,
+}
+
+pub async fn search_all(
+    State(state): State<AppState>,
+    Query(query): Query<UnifiedSearchQuery>,
+) -> Result<Json<UnifiedSearchResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let keyword = query.q.trim().to_string();
+    let limit = query
+        .limit
+        .filter(|value| *value > 0)
+        .unwrap_or(DEFAULT_GROUP_LIMIT)
+        .min(MAX_GROUP_LIMIT);
+    if keyword.is_empty() {
+        return Ok(Json(UnifiedSearchResponse {
+            query: query.q,
+            top: vec![],
+            articles: UnifiedSearchGroup::skipped(),
+            songs: UnifiedSearchGroup::skipped(),
+            images: UnifiedSearchGroup::skipped(),
+            interactive_pages: UnifiedSearchGroup::skipped(),
+        }));
+    }
+
+    let page = SearchPageRequest {
+        limit: Some(limit),
+        cursor: None,
+    };
+    let (articles, songs, images, interactive_pages) = tokio::join!(
+        run_corpus("articles", async {
+            state
+                .store
+                .search_articles(&keyword, &ArticleSearchFilters::default(), &page)
+                .await
+                .map(|page| page.results)
+        }),
+        run_corpus(
+            "songs",
+            state
+                .music_store
+                .search_songs_hybrid(&keyword, limit, None, None, None)
+        ),
+        run_corpus(
+            "images",
+            state
+                .store
+                .search_images_by_text(&keyword, Some(limit), None)
+        ),
+        run_corpus("interactive_pages", state.interactive_store.search_pages(&keyword, limit)),
+    );
+
+    let top = fuse_ranked_lists(
+        vec![
+            articles.items.iter().map(article_hit).collect(),
+            songs.items.iter().map(song_hit).collect(),
+            images.items.iter().map(image_hit).collect(),
+            interactive_pages
+                .items
+                .iter()
+                .map(interactive_page_hit)
+                .collect(),
+        ],
+        RRF_K,
+    )
+    .into_iter()
+    .take(limit)
+    .collect();
+
+    Ok(Json(UnifiedSearchResponse {
+        query: query.q,
+        top,
+        articles,
+        songs,
+        images,
+        interactive_pages,
+    }))
+}
+
+impl<T> UnifiedSearchGroup<T> {
+    fn skipped() -> Self {
+        Self {
+            status: UnifiedSearchGroupStatus::Skipped,
+            elapsed_ms: 0,
+            items: vec![],
+        }
+    }
+}
+
+/// Run one corpus under [`CORPUS_TIMEOUT`], turning errors and timeouts into
+/// an empty group with the matching status.
+async fn run_corpus<T>(
+    corpus: &'static str,
+    search: impl Future<Output = anyhow::Result<Vec<T>>>,
+) -> UnifiedSearchGroup<T> {
+    let started = Instant::now();
+    let (status, items) = match tokio::time::timeout(CORPUS_TIMEOUT, search).await {
+        Ok(Ok(items)) => (UnifiedSearchGroupStatus::Ok, items),
+        Ok(Err(err)) => {
+            tracing::warn!("Unified search corpus failed; corpus={corpus}; error={err:#}");
+            (UnifiedSearchGroupStatus::Failed, vec![])
+        },
+        Err(_) => {
+            tracing::warn!(
+                "Unified search corpus timed out; corpus={corpus}; budget_ms={}",
+                CORPUS_TIMEOUT.as_millis()
+            );
+            (UnifiedSearchGroupStatus::TimedOut, vec![])
+        },
+    };
+    UnifiedSearchGroup {
+        status,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+        items,
+    }
+}
+
+fn article_hit(result: &SearchResult) -> UnifiedSearchHit {
+    UnifiedSearchHit {
+        kind: UnifiedSearchKind::Article,
+        id: result.id.clone(),
+        title: result.title.clone(),
+        subtitle: result.category.clone(),
+        score: 0.0,
+    }
+}
+
+fn song_hit(result: &SongSearchResult) -> UnifiedSearchHit {
+    UnifiedSearchHit {
+        kind: UnifiedSearchKind::Song,
+        id: result.id.clone(),
+        title: result.title.clone(),
+        subtitle: result.artist.clone(),
+        score: 0.0,
+    }
+}
+
+fn image_hit(image: &ImageInfo) -> UnifiedSearchHit {
+    UnifiedSearchHit {
+        kind: UnifiedSearchKind::Image,
+        id: image.filename.clone(),
+        title: image.filename.clone(),
+        subtitle: String::new(),
+        score: 0.0,
+    }
+}
+
+fn interactive_page_hit(page: &InteractivePageSearchResult) -> UnifiedSearchHit {
+    UnifiedSearchHit {
+        kind: UnifiedSearchKind::InteractivePage,
+        id: page.article_id.clone(),
+        title: page.title.clone(),
+        subtitle: page.source_host.clone(),
+        score: 0.0,
+    }
+}
+
+/// Reciprocal rank fusion: a hit at rank `r` of any list adds
+/// `1 / (k + r + 1)`, so raw scores from different corpora never need to
+/// be comparable. Hits sharing a [`fusion_key`] accumulate into the first
+/// one seen. Ties keep list order, then earlier rank.
+fn fuse_ranked_lists(lists: Vec<Vec<UnifiedSearchHit>>, rrf_k: f32) -> Vec<UnifiedSearchHit> {
+    let mut fused = HashMap::<(UnifiedSearchKind, String), (usize, UnifiedSearchHit)>::new();
+    let mut arrival = 0usize;
+    for list in lists {
+        for (rank, mut hit) in list.into_iter().enumerate() {
+            let boost = 1.0 / (rrf_k + rank as f32 + 1.0);
+            let entry = fused.entry(fusion_key(&hit)).or_insert_with(|| {
+                hit.score = 0.0;
+                arrival += 1;
+                (arrival, hit)
+            });
+            entry.1.score += boost;
+        }
+    }
+    let mut merged = fused.into_values().collect::<Vec<_>>();
+    merged.sort_by(|a, b| b.1.score.total_cmp(&a.1.score).then(a.0.cmp(&b.0)));
+    merged.into_iter().map(|(_, hit)| hit).collect()
+}
+
+/// Interactive mirrors are keyed by the article they repost, so an article
+/// matched both as text and as its mirror fuses into one hit.
+fn fusion_key(hit: &UnifiedSearchHit) -> (UnifiedSearchKind, String) {
+    let kind = match hit.kind {
+        UnifiedSearchKind::InteractivePage => UnifiedSearchKind::Article,
+        kind => kind,
+    };
+    (kind, hit.id.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fuse_ranked_lists, UnifiedSearchHit, UnifiedSearchKind};
+
+    fn hit(kind: UnifiedSearchKind, id: &str) -> UnifiedSearchHit {
+        UnifiedSearchHit {
+            kind,
+            id: id.to_string(),
+            title: id.to_string(),
+            subtitle: String::new(),
+            score: 0.0,
+        }
+    }
+
+    #[test]
+    fn rank_fusion_interleaves_corpora_by_rank() {
+        let fused = fuse_ranked_lists(
+            vec![
+                vec![hit(UnifiedSearchKind::Article, "a1"), hit(UnifiedSearchKind::Article, "a2")],
+                vec![hit(UnifiedSearchKind::Song, "s1")],
+                vec![],
+                vec![
+                    hit(UnifiedSearchKind::InteractivePage, "a2"),
+                    hit(UnifiedSearchKind::InteractivePage, "p2"),
+                ],
+            ],
+            60.0,
+        );
+        let order = fused
+            .iter()
+            .map(|hit| (hit.kind, hit.id.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(order, vec![
+            (UnifiedSearchKind::Article, "a2"),
+            (UnifiedSearchKind::Article, "a1"),
+            (UnifiedSearchKind::Song, "s1"),
+            (UnifiedSearchKind::InteractivePage, "p2"),
+        ]);
+        assert!((fused[0].score - (1.0 / 62.0 + 1.0 / 61.0)).abs() < f32::EPSILON);
+        assert!((fused[1].score - 1.0 / 61.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn rank_fusion_accumulates_duplicate_hits() {
+        let fused = fuse_ranked_lists(
+            vec![
+                vec![hit(UnifiedSearchKind::Article, "x"), hit(UnifiedSearchKind::Article, "y")],
+                vec![hit(UnifiedSearchKind::Article, "y")],
+            ],
+            60.0,
+        );
+        assert_eq!(fused[0].id, "y");
+        assert!((fused[0].score - (1.0 / 62.0 + 1.0 / 61.0)).abs() < f32::EPSILON);
+    }
+}
@@ -321,6 +321,13 @@ pub enum ApiCommands {
     ListTags,
     /// GET /api/categories
     ListCategories,
+    /// GET /api/series
+    ListSeries,
+    /// GET /api/series/:id
+    GetSeries {
+        /// Series id (the normalized `series` frontmatter key).
+        id: String,
+    },
     /// GET /api/images
     ListImages,
     /// GET /api/image-search?id=
@@ -544,7 +551,8 @@ pub enum DbCommands {
         #[arg(long)]
         dry_run: bool,
     },
-    /// Delete rows by SQL filter.
+    /// Delete rows by SQL filter. Deleting from `images` also drops the
+    /// images' cached `image_variants`.
     DeleteRows {
         /// Table name.
         table: String,
@@ -648,6 +656,37 @@ pub enum DbCommands {
         #[arg(long)]
         json: String,
     },
+    /// List recorded revisions of one article (newest first).
+    ArticleHistory {
+        /// Article id in `articles.id`.
+        #[arg(long)]
+        id: String,
+        /// Maximum revisions to print.
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Show a line diff between two revisions of one article.
+    ArticleDiff {
+        /// Article id in `articles.id`.
+        #[arg(long)]
+        id: String,
+        /// Base revision number.
+        #[arg(long)]
+        from: i32,
+        /// Target revision number (defaults to the latest revision).
+        #[arg(long)]
+        to: Option<i32>,
+    },
+    /// Restore an article's content from a recorded revision. The rollback is
+    /// itself recorded as a new revision.
+    ArticleRollback {
+        /// Article id in `articles.id`.
+        #[arg(long)]
+        id: String,
+        /// Revision number to restore.
+        #[arg(long)]
+        revision: i32,
+    },
     /// Restore a table to a specific version (checkout + restore).
     RestoreVersion {
         /// Table name.
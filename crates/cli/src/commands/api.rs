@@ -2,9 +2,13 @@ use std::{fs, path::Path};
 
 use anyhow::{bail, Context, Result};
 use serde::Serialize;
-use static_flow_store::lancedb_api::{
-    ArticleListResponse, CategoriesResponse, ImageListResponse, ImageSearchResponse,
-    ImageTextSearchResponse, SearchResponse, StaticFlowDataStore, TagsResponse,
+use static_flow_store::{
+    article_search::{ArticleSearchFilters, SearchPageRequest},
+    lancedb_api::{
+        ArticleListResponse, CategoriesResponse, ImageListResponse, ImageSearchResponse,
+        ImageTextSearchResponse, SearchResponse, SeriesListResponse, StaticFlowDataStore,
+        TagsResponse,
+    },
 };
 
 use crate::cli::ApiCommands;
@@ -61,18 +65,12 @@ pub async fn run(db_path: &Path, command: ApiCommands) -> Result<()> {
         } => {
             let keyword = q.trim();
             let response = if keyword.is_empty() {
-                SearchResponse {
-                    results: vec![],
-                    total: 0,
-                    query: q,
-                }
+                SearchResponse::empty(q)
             } else {
-                let results = store.search_articles(keyword, Some(10)).await?;
-                SearchResponse {
-                    total: results.len(),
-                    results,
-                    query: q,
-                }
+                let page = store
+                    .search_articles(keyword, &ArticleSearchFilters::default(), &first_page(10))
+                    .await?;
+                SearchResponse::from_page(q, page)
             };
             print_json(&response)
         },
@@ -82,16 +80,13 @@ pub async fn run(db_path: &Path, command: ApiCommands) -> Result<()> {
         } => {
             let keyword = q.trim();
             let response = if keyword.is_empty() {
-                SearchResponse {
-                    results: vec![],
-                    total: 0,
-                    query: q,
-                }
+                SearchResponse::empty(q)
             } else {
-                let results = store
+                let page = store
                     .semantic_search(
                         keyword,
-                        Some(10),
+                        &ArticleSearchFilters::default(),
+                        &first_page(10),
                         None,
                         enhanced_highlight,
                         false,
@@ -100,11 +95,7 @@ pub async fn run(db_path: &Path, command: ApiCommands) -> Result<()> {
                         None,
                     )
                     .await?;
-                SearchResponse {
-                    total: results.len(),
-                    results,
-                    query: q,
-                }
+                SearchResponse::from_page(q, page)
             };
             print_json(&response)
         },
@@ -120,6 +111,18 @@ pub async fn run(db_path: &Path, command: ApiCommands) -> Result<()> {
                 categories,
             })
         },
+        ApiCommands::ListSeries => {
+            let series = store.list_series().await?;
+            print_json(&SeriesListResponse {
+                series,
+            })
+        },
+        ApiCommands::GetSeries {
+            id,
+        } => match store.get_series(&id).await? {
+            Some(series) => print_json(&series),
+            None => bail!("series not found: {id}"),
+        },
         ApiCommands::ListImages => {
             let (images, total, has_more) = store.list_images_paged(None, 0).await?;
             print_json(&ImageListResponse {
@@ -198,6 +201,13 @@ pub async fn run(db_path: &Path, command: ApiCommands) -> Result<()> {
     }
 }
 
+fn first_page(limit: usize) -> SearchPageRequest {
+    SearchPageRequest {
+        limit: Some(limit),
+        cursor: None,
+    }
+}
+
 fn print_json<T: Serialize>(value: &T) -> Result<()> {
     println!("{}", serde_json::to_string_pretty(value)?);
     Ok(())
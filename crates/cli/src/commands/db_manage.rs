@@ -24,12 +24,16 @@ use lancedb::{
 use static_flow_embedding::{embed_image_bytes, embed_text_with_language, TextEmbeddingLanguage};
 use static_flow_store::{
     article_request_store::request_ai_chunks_schema,
+    article_revision_store::{
+        restore_article_snapshot, ArticleRevisionStore, REVISION_ORIGIN_ROLLBACK,
+        REVISION_ORIGIN_UPDATE_BILINGUAL, REVISION_ORIGIN_UPSERT_JSON,
+    },
     comments_store::comment_ai_chunks_schema,
     image_vector_maintenance::{
         reembed_image_vectors as reembed_image_vectors_in_table, ImageReembedOptions,
         ImageReembedScope,
     },
-    lancedb_api::api_behavior_schema,
+    lancedb_api::{api_behavior_schema, ensure_article_fts_indexes},
     llm_gateway_store::{
         now_ms, query_usage_event_rebuild_rows_from_connection, LlmGatewayStore,
         DEFAULT_LLM_GATEWAY_USAGE_EVENT_DETAIL_RETENTION_DAYS, LLM_GATEWAY_USAGE_EVENTS_TABLE,
@@ -44,8 +48,8 @@ use static_flow_store::{
 use crate::{
     cli::QueryOutputFormat,
     db::{
-        connect_db, ensure_fts_index, ensure_scalar_index, ensure_table, ensure_vector_index,
-        upsert_articles, upsert_images,
+        begin_article_revisions, connect_db, ensure_fts_index, ensure_scalar_index, ensure_table,
+        ensure_vector_index, finish_article_revisions, upsert_articles, upsert_images,
     },
     schema::{article_schema, image_schema, taxonomy_schema, ArticleRecord, ImageRecord},
     utils::rasterize_svg_for_embedding,
@@ -323,6 +327,8 @@ pub async fn update_article_bilingual(
 
     let db = connect_db(db_path).await?;
     let table = open_table(&db, "articles").await?;
+    let revision_ids = [id.to_string()];
+    let revisions = begin_article_revisions(&db, &table, &revision_ids).await?;
 
     let mut builder = table
         .update()
@@ -349,6 +355,14 @@ pub async fn update_article_bilingual(
     if result.rows_updated == 0 {
         bail!("article not found: `{id}`")
     }
+    finish_article_revisions(
+        &revisions,
+        &table,
+        &revision_ids,
+        REVISION_ORIGIN_UPDATE_BILINGUAL,
+        None,
+    )
+    .await?;
 
     tracing::info!(
         "Article bilingual update applied: id=`{}`, rows_updated={}, version={}",
@@ -373,6 +387,13 @@ pub async fn delete_rows(
 
     let db = connect_db(db_path).await?;
     let table = open_table(&db, table).await?;
+    // Cached renditions are keyed by image id; collect the ids before the
+    // rows disappear so their variants can go with them.
+    let deleted_image_ids = if table.name() == "images" {
+        select_string_column(&table, &predicate, "id").await?
+    } else {
+        vec![]
+    };
     let result = match table.delete(&predicate).await {
         Ok(result) => result,
         Err(err) => {
@@ -380,6 +401,49 @@ pub async fn delete_rows(
         },
     };
     tracing::info!("Delete applied on `{}`: version={}", table.name(), result.version);
+    delete_image_variants(&db, &deleted_image_ids).await
+}
+
+async fn select_string_column(table: &Table, predicate: &str, column: &str) -> Result<Vec<String>> {
+    let batches = table
+        .query()
+        .only_if(predicate)
+        .select(Select::columns(&[column]))
+        .execute()
+        .await?
+        .try_collect::<Vec<_>>()
+        .await?;
+    let mut values = Vec::new();
+    for batch in &batches {
+        let array = downcast_string(batch, column)?;
+        values.extend(
+            (0..array.len())
+                .filter(|row| !array.is_null(*row))
+                .map(|row| array.value(row).to_string()),
+        );
+    }
+    Ok(values)
+}
+
+async fn delete_image_variants(db: &Connection, image_ids: &[String]) -> Result<()> {
+    if image_ids.is_empty() {
+        return Ok(());
+    }
+    let Ok(variants) = db.open_table("image_variants").execute().await else {
+        return Ok(());
+    };
+    for chunk in image_ids.chunks(256) {
+        let id_list = chunk
+            .iter()
+            .map(|id| sql_string_literal(id))
+            .collect::<Vec<_>>()
+            .join(", ");
+        variants
+            .delete(&format!("image_id IN ({id_list})"))
+            .await
+            .context("failed to delete cached image variants")?;
+    }
+    tracing::info!("Deleted cached variants for {} image(s).", image_ids.len());
     Ok(())
 }
 
@@ -1002,11 +1066,80 @@ pub async fn upsert_article_json(db_path: &Path, json: &str) -> Result<()> {
 
     let db = connect_db(db_path).await?;
     let table = open_table(&db, "articles").await?;
-    upsert_articles(&table, &[record]).await?;
+    upsert_articles(&db, &table, &[record], REVISION_ORIGIN_UPSERT_JSON).await?;
     tracing::info!("Upserted one article row.");
     Ok(())
 }
 
+pub async fn article_history(db_path: &Path, id: &str, limit: usize) -> Result<()> {
+    let db = connect_db(db_path).await?;
+    let revisions = ArticleRevisionStore::from_connection(db).await?;
+    let history = revisions.list_revisions(id, Some(limit)).await?;
+    if history.is_empty() {
+        tracing::info!("No revisions recorded for article `{}`.", id);
+        return Ok(());
+    }
+
+    tracing::info!("Revisions for article `{}` (newest first):", id);
+    for revision in history {
+        let created_at = chrono::DateTime::from_timestamp_millis(revision.created_at)
+            .map(|value| value.to_rfc3339())
+            .unwrap_or_else(|| revision.created_at.to_string());
+        let rollback = revision
+            .rollback_of
+            .map(|source| format!(" | rollback_of={source}"))
+            .unwrap_or_default();
+        tracing::info!(
+            "- #{} | {} | origin={} | author={} | hash={} | changed={}{}",
+            revision.revision_no,
+            created_at,
+            revision.origin,
+            revision.revision_author,
+            &revision.source_hash[..revision.source_hash.len().min(12)],
+            revision.changed_fields.join(","),
+            rollback
+        );
+    }
+    Ok(())
+}
+
+pub async fn article_diff(db_path: &Path, id: &str, from: i32, to: Option<i32>) -> Result<()> {
+    let db = connect_db(db_path).await?;
+    let revisions = ArticleRevisionStore::from_connection(db).await?;
+    let diff = revisions.diff_revisions(id, from, to).await?;
+    print!("{}", diff.to_unified_text());
+    Ok(())
+}
+
+pub async fn article_rollback(db_path: &Path, id: &str, revision: i32) -> Result<()> {
+    let db = connect_db(db_path).await?;
+    let table = open_table(&db, "articles").await?;
+    let revision_ids = [id.to_string()];
+    let revisions = begin_article_revisions(&db, &table, &revision_ids).await?;
+    let target = revisions
+        .get_revision(id, revision)
+        .await?
+        .with_context(|| format!("revision {revision} not found for article `{id}`"))?;
+
+    restore_article_snapshot(&table, id, &target.snapshot).await?;
+    finish_article_revisions(
+        &revisions,
+        &table,
+        &revision_ids,
+        REVISION_ORIGIN_ROLLBACK,
+        Some(revision),
+    )
+    .await?;
+
+    tracing::info!(
+        "Article `{}` rolled back to revision {}. Vectors were cleared; run `sf-cli db \
+         backfill-article-vectors` to re-embed.",
+        id,
+        revision
+    );
+    Ok(())
+}
+
 pub async fn restore_table(db_path: &Path, table: &str, version: u64) -> Result<()> {
     let db = connect_db(db_path).await?;
     let table = open_table(&db, table).await?;
@@ -1518,6 +1651,11 @@ async fn ensure_indexes_for_table(db: &Connection, table_name: &str) -> Result<(
             tracing::warn!("Failed to create FTS index on `{}` ({column}): {err}", table.name());
         }
     }
+    if table_name == "articles" {
+        if let Err(err) = ensure_article_fts_indexes(&table).await {
+            tracing::warn!("Failed to create FTS indexes on `{}`: {err}", table.name());
+        }
+    }
     for column in policy.vector_indexes {
         if let Err(err) = ensure_vector_index(&table, column).await {
             tracing::warn!("Failed to create vector index on `{}` ({column}): {err}", table.name());
@@ -1940,7 +2078,8 @@ fn table_policy(table_name: &str) -> Option<TablePolicy> {
         "articles" => Some(TablePolicy {
             scalar_indexes: &["id", "category"],
             vector_indexes: &["vector_en", "vector_zh"],
-            fts_indexes: &["content"],
+            // Article FTS indexes are tokenizer-specific and managed by the store.
+            fts_indexes: &[],
             storage_options: DEFAULT_STORAGE_OPTIONS,
         }),
         "images" => Some(TablePolicy {
@@ -1949,6 +2088,13 @@ fn table_policy(table_name: &str) -> Option<TablePolicy> {
             fts_indexes: &[],
             storage_options: BLOB_V2_STORAGE_OPTIONS,
         }),
+        "image_variants" => Some(TablePolicy {
+            // Derived renditions are small, rebuildable and looked up by id.
+            scalar_indexes: &["id", "image_id"],
+            vector_indexes: &[],
+            fts_indexes: &[],
+            storage_options: DEFAULT_STORAGE_OPTIONS,
+        }),
         "taxonomies" => Some(TablePolicy {
             scalar_indexes: &["id", "kind", "key"],
             vector_indexes: &[],
@@ -2094,6 +2240,7 @@ fn all_policy_table_names() -> Vec<&'static str> {
         "comment_audit_logs",
         "comment_published",
         "comment_tasks",
+        "image_variants",
         "images",
         "interactive_assets",
         "interactive_page_locales",
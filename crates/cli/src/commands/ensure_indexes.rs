@@ -1,6 +1,7 @@
 use std::path::Path;
 
 use anyhow::{Context, Result};
+use static_flow_store::lancedb_api::ensure_article_fts_indexes;
 
 use crate::db::{connect_db, ensure_fts_index, ensure_vector_index};
 
@@ -18,8 +19,8 @@ pub async fn run(db_path: &Path) -> Result<()> {
         .await
         .context("images table not found; run `sf-cli init` first")?;
 
-    if let Err(err) = ensure_fts_index(&articles_table, "content").await {
-        tracing::warn!("Failed to create FTS index on articles: {err}");
+    if let Err(err) = ensure_article_fts_indexes(&articles_table).await {
+        tracing::warn!("Failed to create FTS indexes on articles: {err}");
     }
 
     if let Err(err) = ensure_vector_index(&articles_table, "vector_en").await {
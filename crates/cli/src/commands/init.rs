@@ -5,10 +5,12 @@ use lancedb::{
     index::scalar::FullTextSearchQuery,
     query::{ExecutableQuery, QueryBase},
 };
-use static_flow_store::interactive_store::InteractivePageStore;
+use static_flow_store::{
+    interactive_store::InteractivePageStore, lancedb_api::ensure_article_fts_indexes,
+};
 
 use crate::{
-    db::{connect_db, ensure_fts_index, ensure_table, ensure_vector_index},
+    db::{connect_db, ensure_table, ensure_vector_index},
     schema::{article_schema, image_schema, taxonomy_schema},
 };
 
@@ -20,8 +22,8 @@ pub async fn run(db_path: &Path) -> Result<()> {
     ensure_table(&db, "taxonomies", taxonomy_schema()).await?;
     let _interactive_store = InteractivePageStore::connect(&db_path.to_string_lossy()).await?;
 
-    if let Err(err) = ensure_fts_index(&articles_table, "content").await {
-        tracing::warn!("Failed to create FTS index on articles: {err}");
+    if let Err(err) = ensure_article_fts_indexes(&articles_table).await {
+        tracing::warn!("Failed to create FTS indexes on articles: {err}");
     }
 
     if let Err(err) = ensure_vector_index(&articles_table, "vector_en").await {
@@ -372,6 +372,19 @@ pub async fn run(cli: Cli) -> Result<()> {
             DbCommands::UpsertImage {
                 json,
             } => db_manage::upsert_image_json(&db_path, &json).await,
+            DbCommands::ArticleHistory {
+                id,
+                limit,
+            } => db_manage::article_history(&db_path, &id, limit).await,
+            DbCommands::ArticleDiff {
+                id,
+                from,
+                to,
+            } => db_manage::article_diff(&db_path, &id, from, to).await,
+            DbCommands::ArticleRollback {
+                id,
+                revision,
+            } => db_manage::article_rollback(&db_path, &id, revision).await,
             DbCommands::RestoreVersion {
                 table,
                 version,
@@ -9,6 +9,7 @@ use image::GenericImageView;
 use regex::Regex;
 use static_flow_embedding::{detect_language, embed_text_with_language, TextEmbeddingLanguage};
 use static_flow_shared::normalize_taxonomy_key;
+use static_flow_store::article_revision_store::REVISION_ORIGIN_SYNC_NOTES;
 
 use crate::{
     db::{
@@ -19,9 +20,9 @@ use crate::{
         article_schema, image_schema, taxonomy_schema, ArticleRecord, ImageRecord, TaxonomyRecord,
     },
     utils::{
-        collect_markdown_files, encode_thumbnail, estimate_read_time, hash_bytes,
-        markdown_filename, normalize_markdown_path, parse_markdown, rasterize_svg_for_embedding,
-        Frontmatter,
+        attach_image_placeholder, collect_markdown_files, encode_thumbnail, estimate_read_time,
+        hash_bytes, markdown_filename, normalize_markdown_path, parse_markdown,
+        rasterize_svg_for_embedding, Frontmatter,
     },
 };
 
@@ -84,7 +85,7 @@ pub async fn run(db_path: &Path, dir: &Path, options: SyncNotesOptions) -> Resul
     };
 
     let outcome =
-        sync_notes(dir, &articles_table, &images_table, &taxonomies_table, &config).await?;
+        sync_notes(dir, &db, &articles_table, &images_table, &taxonomies_table, &config).await?;
 
     if let Err(err) = ensure_vector_index(&articles_table, "vector_en").await {
         tracing::warn!("Failed to create vector index on articles (vector_en): {err}");
@@ -111,6 +112,7 @@ pub async fn run(db_path: &Path, dir: &Path, options: SyncNotesOptions) -> Resul
 
 async fn sync_notes(
     dir: &Path,
+    db: &lancedb::Connection,
     articles_table: &lancedb::Table,
     images_table: &lancedb::Table,
     taxonomies_table: &lancedb::Table,
@@ -160,6 +162,10 @@ async fn sync_notes(
             date: frontmatter_date,
             featured_image: featured_image_source,
             read_time: frontmatter_read_time,
+            series: frontmatter_series,
+            series_title,
+            series_description,
+            series_order,
         } = frontmatter;
 
         let title = frontmatter_title
@@ -202,6 +208,13 @@ async fn sync_notes(
         let date =
             frontmatter_date.unwrap_or_else(|| chrono::Utc::now().format("%Y-%m-%d").to_string());
         let read_time = frontmatter_read_time.unwrap_or_else(|| estimate_read_time(&body));
+        let series = Frontmatter::normalized_series(
+            frontmatter_series,
+            series_title,
+            series_description,
+            series_order,
+        )
+        .with_context(|| format!("invalid series in {}", markdown_path.display()))?;
 
         upsert_taxonomy_entry(
             &mut taxonomy_store,
@@ -212,6 +225,12 @@ async fn sync_notes(
         for tag in &tags {
             upsert_taxonomy_entry(&mut taxonomy_store, "tag", tag, None);
         }
+        if let Some(record) = series
+            .as_ref()
+            .and_then(|series| series.taxonomy_record(chrono::Utc::now().timestamp_millis()))
+        {
+            merge_series_taxonomy(&mut taxonomy_store, record);
+        }
 
         let (rewritten_body, mapped_images) = rewrite_image_links(
             &body,
@@ -272,6 +291,8 @@ async fn sync_notes(
             article_kind: None,
             source_url: None,
             interactive_page_id: None,
+            series_id: series.as_ref().map(|series| series.id.clone()),
+            series_order: series.as_ref().and_then(|series| series.order),
             vector_en,
             vector_zh,
             created_at: now_ms,
@@ -283,7 +304,7 @@ async fn sync_notes(
         upsert_images(images_table, chunk).await?;
     }
     for chunk in article_store.chunks(64) {
-        upsert_articles(articles_table, chunk).await?;
+        upsert_articles(db, articles_table, chunk, REVISION_ORIGIN_SYNC_NOTES).await?;
     }
     let taxonomy_records = taxonomy_store.into_values().collect::<Vec<_>>();
     for chunk in taxonomy_records.chunks(64) {
@@ -337,6 +358,28 @@ fn upsert_taxonomy_entry(
     }
 }
 
+/// Several parts of one series may each declare part of its metadata; keep
+/// any title/description already collected when a later part omits it.
+fn merge_series_taxonomy(
+    taxonomy_store: &mut HashMap<String, TaxonomyRecord>,
+    record: TaxonomyRecord,
+) {
+    match taxonomy_store.get_mut(&record.id) {
+        Some(existing) => {
+            if record.name != record.key || existing.name == existing.key {
+                existing.name = record.name;
+            }
+            if record.description.is_some() {
+                existing.description = record.description;
+            }
+            existing.updated_at = record.updated_at;
+        },
+        None => {
+            taxonomy_store.insert(record.id.clone(), record);
+        },
+    }
+}
+
 fn rewrite_image_links(
     markdown: &str,
     markdown_path: &Path,
@@ -469,6 +512,7 @@ fn build_image_record(path: &Path, config: &SyncConfig) -> Result<ImageRecord> {
                 let (w, h) = img.dimensions();
                 metadata["width"] = serde_json::json!(w);
                 metadata["height"] = serde_json::json!(h);
+                attach_image_placeholder(&mut metadata, &img, &path);
                 let thumb = if config.generate_thumbnail {
                     Some(encode_thumbnail(&img, config.thumbnail_size)?)
                 } else {
@@ -12,6 +12,7 @@ use static_flow_embedding::{
     TEXT_VECTOR_DIM_EN, TEXT_VECTOR_DIM_ZH,
 };
 use static_flow_shared::{normalize_taxonomy_key, LocalizedText};
+use static_flow_store::article_revision_store::REVISION_ORIGIN_WRITE_ARTICLE;
 
 use crate::{
     db::{
@@ -22,8 +23,8 @@ use crate::{
         article_schema, image_schema, taxonomy_schema, ArticleRecord, ImageRecord, TaxonomyRecord,
     },
     utils::{
-        encode_thumbnail, estimate_read_time, hash_bytes, parse_markdown, parse_tags, parse_vector,
-        rasterize_svg_for_embedding, Frontmatter,
+        attach_image_placeholder, encode_thumbnail, estimate_read_time, hash_bytes, parse_markdown,
+        parse_tags, parse_vector, rasterize_svg_for_embedding, Frontmatter,
     },
 };
 
@@ -121,6 +122,10 @@ pub async fn run(db_path: &Path, file: &Path, options: WriteArticleOptions) -> R
         date: frontmatter_date,
         featured_image,
         read_time,
+        series,
+        series_title,
+        series_description,
+        series_order,
     } = frontmatter;
 
     let title = title_override.unwrap_or_else(|| {
@@ -180,6 +185,9 @@ pub async fn run(db_path: &Path, file: &Path, options: WriteArticleOptions) -> R
              category_description to frontmatter)",
         )?;
 
+    let series =
+        Frontmatter::normalized_series(series, series_title, series_description, series_order)?;
+
     let image_import_config = ImageImportConfig {
         generate_thumbnail,
         thumbnail_size,
@@ -314,6 +322,8 @@ pub async fn run(db_path: &Path, file: &Path, options: WriteArticleOptions) -> R
         article_kind,
         source_url,
         interactive_page_id,
+        series_id: series.as_ref().map(|series| series.id.clone()),
+        series_order: series.as_ref().and_then(|series| series.order),
         vector_en,
         vector_zh,
         created_at: now_ms,
@@ -343,7 +353,7 @@ pub async fn run(db_path: &Path, file: &Path, options: WriteArticleOptions) -> R
         );
     }
 
-    upsert_articles(&table, &[record]).await?;
+    upsert_articles(&db, &table, &[record], REVISION_ORIGIN_WRITE_ARTICLE).await?;
 
     let mut taxonomies = Vec::new();
     push_taxonomy_record(
@@ -356,6 +366,12 @@ pub async fn run(db_path: &Path, file: &Path, options: WriteArticleOptions) -> R
     for tag in &tags {
         push_taxonomy_record(&mut taxonomies, "tag", tag, None, now_ms);
     }
+    if let Some(record) = series
+        .as_ref()
+        .and_then(|series| series.taxonomy_record(now_ms))
+    {
+        taxonomies.push(record);
+    }
     {
         let mut seen = std::collections::HashSet::new();
         taxonomies.retain(|r| seen.insert(r.id.clone()));
@@ -775,6 +791,7 @@ fn build_image_record(path: &Path, config: &ImageImportConfig) -> Result<ImageRe
                 let (w, h) = img.dimensions();
                 metadata["width"] = serde_json::json!(w);
                 metadata["height"] = serde_json::json!(h);
+                attach_image_placeholder(&mut metadata, &img, &path);
                 let thumb = if config.generate_thumbnail {
                     Some(encode_thumbnail(&img, config.thumbnail_size)?)
                 } else {
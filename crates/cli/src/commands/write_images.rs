@@ -8,8 +8,8 @@ use crate::{
     db::{connect_db, ensure_vector_index, optimize_table_indexes, upsert_images},
     schema::ImageRecord,
     utils::{
-        collect_image_files, encode_thumbnail, hash_bytes, rasterize_svg_for_embedding,
-        relative_filename,
+        attach_image_placeholder, collect_image_files, encode_thumbnail, hash_bytes,
+        rasterize_svg_for_embedding, relative_filename,
     },
 };
 
@@ -78,6 +78,7 @@ pub async fn run(
                     let format = ImageFormat::from_path(&path).ok();
                     metadata["width"] = serde_json::json!(w);
                     metadata["height"] = serde_json::json!(h);
+                    attach_image_placeholder(&mut metadata, &img, &path);
                     metadata["format"] = serde_json::json!(format.map(|f| format!("{:?}", f)));
                     let thumb = if generate_thumbnail {
                         Some(encode_thumbnail(&img, thumbnail_size)?)
@@ -16,10 +16,16 @@ use lancedb::{
     Connection, Table,
 };
 use rand::{rngs::OsRng, Rng};
+use static_flow_store::article_revision_store::{
+    fetch_article_revision_snapshots, ArticleRevisionStore, NewArticleRevisionInput,
+};
 
-use crate::schema::{
-    build_article_batch, build_image_batch, build_taxonomy_batch, ArticleRecord, ImageRecord,
-    TaxonomyRecord,
+use crate::{
+    schema::{
+        build_article_batch, build_image_batch, build_taxonomy_batch, ArticleRecord, ImageRecord,
+        TaxonomyRecord,
+    },
+    utils::revision_author,
 };
 
 const MIN_VECTOR_INDEX_TRAIN_ROWS: usize = 256;
@@ -227,10 +233,21 @@ pub async fn optimize_table_indexes(table: &Table) -> Result<()> {
     Ok(())
 }
 
-pub async fn upsert_articles(table: &Table, records: &[ArticleRecord]) -> Result<()> {
+pub async fn upsert_articles(
+    db: &Connection,
+    table: &Table,
+    records: &[ArticleRecord],
+    origin: &str,
+) -> Result<()> {
     if records.is_empty() {
         return Ok(());
     }
+    let ids = records
+        .iter()
+        .map(|record| record.id.clone())
+        .collect::<Vec<_>>();
+    let revisions = begin_article_revisions(db, table, &ids).await?;
+
     let batch = align_batch_to_table_schema(table, build_article_batch(records)?).await?;
     let schema = batch.schema();
     let batches = RecordBatchIterator::new(vec![Ok(batch)].into_iter(), schema);
@@ -239,6 +256,64 @@ pub async fn upsert_articles(table: &Table, records: &[ArticleRecord]) -> Result
     merge.when_matched_update_all(None);
     merge.when_not_matched_insert_all();
     merge.execute(Box::new(batches)).await?;
+
+    finish_article_revisions(&revisions, table, &ids, origin, None).await
+}
+
+/// Open the `article_revisions` log and snapshot the current rows of `ids`
+/// as baselines, so articles written before revision tracking existed keep
+/// their pre-change text.
+pub async fn begin_article_revisions(
+    db: &Connection,
+    table: &Table,
+    ids: &[String],
+) -> Result<ArticleRevisionStore> {
+    let revisions = ArticleRevisionStore::from_connection(db.clone()).await?;
+    let author = revision_author();
+    let existing = fetch_article_revision_snapshots(table, ids).await?;
+    for (id, snapshot) in &existing {
+        revisions.ensure_baseline(id, &author, snapshot).await?;
+    }
+    Ok(revisions)
+}
+
+/// Record what actually landed in `articles` for `ids` after a write.
+/// Unchanged articles are skipped by the revision store.
+pub async fn finish_article_revisions(
+    revisions: &ArticleRevisionStore,
+    table: &Table,
+    ids: &[String],
+    origin: &str,
+    rollback_of: Option<i32>,
+) -> Result<()> {
+    let author = revision_author();
+    let mut written = fetch_article_revision_snapshots(table, ids).await?;
+    let mut seen = HashSet::new();
+    for id in ids {
+        if !seen.insert(id.as_str()) {
+            continue;
+        }
+        let Some(snapshot) = written.remove(id) else {
+            continue;
+        };
+        let recorded = revisions
+            .record_revision(NewArticleRevisionInput {
+                article_id: id.clone(),
+                origin: origin.to_string(),
+                revision_author: author.clone(),
+                rollback_of,
+                snapshot,
+            })
+            .await?;
+        if let Some(recorded) = recorded {
+            tracing::info!(
+                "Recorded article revision: id=`{}`, revision={}, changed={}",
+                id,
+                recorded.summary.revision_no,
+                recorded.summary.changed_fields.join(",")
+            );
+        }
+    }
     Ok(())
 }
 
@@ -30,6 +30,8 @@ pub struct ArticleRecord {
     pub article_kind: Option<String>,
     pub source_url: Option<String>,
     pub interactive_page_id: Option<String>,
+    pub series_id: Option<String>,
+    pub series_order: Option<i32>,
     pub vector_en: Option<Vec<f32>>,
     pub vector_zh: Option<Vec<f32>>,
     pub created_at: i64,
@@ -79,6 +81,8 @@ pub fn article_schema() -> Arc<Schema> {
         Field::new("article_kind", DataType::Utf8, true),
         Field::new("source_url", DataType::Utf8, true),
         Field::new("interactive_page_id", DataType::Utf8, true),
+        Field::new("series_id", DataType::Utf8, true),
+        Field::new("series_order", DataType::Int32, true),
         Field::new(
             "vector_en",
             DataType::FixedSizeList(
@@ -147,6 +151,8 @@ pub fn build_article_batch(records: &[ArticleRecord]) -> Result<RecordBatch> {
     let mut article_kind_builder = StringBuilder::new();
     let mut source_url_builder = StringBuilder::new();
     let mut interactive_page_id_builder = StringBuilder::new();
+    let mut series_id_builder = StringBuilder::new();
+    let mut series_order_builder = Int32Builder::new();
     let mut vector_en_builder =
         FixedSizeListBuilder::new(Float32Builder::new(), TEXT_VECTOR_DIM_EN as i32)
             .with_field(Field::new_list_field(DataType::Float32, false));
@@ -203,6 +209,8 @@ pub fn build_article_batch(records: &[ArticleRecord]) -> Result<RecordBatch> {
         } else {
             interactive_page_id_builder.append_null();
         }
+        series_id_builder.append_option(record.series_id.as_deref());
+        series_order_builder.append_option(record.series_order);
 
         match &record.vector_en {
             Some(vector) => {
@@ -269,6 +277,8 @@ pub fn build_article_batch(records: &[ArticleRecord]) -> Result<RecordBatch> {
         Arc::new(article_kind_builder.finish()),
         Arc::new(source_url_builder.finish()),
         Arc::new(interactive_page_id_builder.finish()),
+        Arc::new(series_id_builder.finish()),
+        Arc::new(series_order_builder.finish()),
         Arc::new(vector_en_builder.finish()),
         Arc::new(vector_zh_builder.finish()),
         Arc::new(created_at_builder.finish()),
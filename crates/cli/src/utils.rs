@@ -9,7 +9,10 @@ use image::{DynamicImage, ImageFormat};
 use resvg::{tiny_skia, usvg};
 use serde::Deserialize;
 use sha2::{Digest, Sha256};
-use static_flow_shared::LocalizedText;
+use static_flow_shared::{normalize_taxonomy_key, LocalizedText};
+use static_flow_store::image_variants::compute_image_placeholder;
+
+use crate::schema::TaxonomyRecord;
 
 const SVG_EMBED_MAX_SIDE: u32 = 1024;
 
@@ -28,6 +31,41 @@ pub struct Frontmatter {
     pub date: Option<String>,
     pub featured_image: Option<String>,
     pub read_time: Option<i32>,
+    pub series: Option<String>,
+    pub series_title: Option<String>,
+    pub series_description: Option<String>,
+    pub series_order: Option<i32>,
+}
+
+/// Series membership declared in frontmatter. `id` is the normalized
+/// `series` key; `title`/`description` are only set when the article
+/// declares them, so parts that just reference a series do not clobber the
+/// metadata written by another part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeriesFrontmatter {
+    pub id: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub order: Option<i32>,
+}
+
+impl SeriesFrontmatter {
+    /// Taxonomy row carrying the series title and description, or `None`
+    /// when this article only references the series.
+    pub fn taxonomy_record(&self, now_ms: i64) -> Option<TaxonomyRecord> {
+        if self.title.is_none() && self.description.is_none() {
+            return None;
+        }
+        Some(TaxonomyRecord {
+            id: format!("series:{}", self.id),
+            kind: "series".to_string(),
+            key: self.id.clone(),
+            name: self.title.clone().unwrap_or_else(|| self.id.clone()),
+            description: self.description.clone(),
+            created_at: now_ms,
+            updated_at: now_ms,
+        })
+    }
 }
 
 impl Frontmatter {
@@ -54,6 +92,38 @@ impl Frontmatter {
         }
         merged.normalized()
     }
+
+    pub fn normalized_series(
+        series: Option<String>,
+        series_title: Option<String>,
+        series_description: Option<String>,
+        series_order: Option<i32>,
+    ) -> Result<Option<SeriesFrontmatter>> {
+        let trimmed = |value: Option<String>| {
+            value
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        let Some(series) = trimmed(series) else {
+            if series_order.is_some() {
+                anyhow::bail!("frontmatter series_order requires series");
+            }
+            return Ok(None);
+        };
+        let id = normalize_taxonomy_key(&series);
+        if id.is_empty() {
+            anyhow::bail!("frontmatter series `{series}` has no usable characters for an id");
+        }
+        if let Some(order) = series_order.filter(|order| *order < 1) {
+            anyhow::bail!("frontmatter series_order must be >= 1, got {order}");
+        }
+        Ok(Some(SeriesFrontmatter {
+            id,
+            title: trimmed(series_title),
+            description: trimmed(series_description),
+            order: series_order,
+        }))
+    }
 }
 
 pub fn parse_markdown(content: &str) -> Result<(Frontmatter, String)> {
@@ -69,6 +139,17 @@ pub fn parse_markdown(content: &str) -> Result<(Frontmatter, String)> {
     Ok((frontmatter, parsed.content))
 }
 
+/// Name recorded as the author of article revisions written by this process:
+/// `STATICFLOW_REVISION_AUTHOR`, then `$USER`, then `sf-cli`.
+pub fn revision_author() -> String {
+    ["STATICFLOW_REVISION_AUTHOR", "USER"]
+        .iter()
+        .filter_map(|key| std::env::var(key).ok())
+        .map(|value| value.trim().to_string())
+        .find(|value| !value.is_empty())
+        .unwrap_or_else(|| "sf-cli".to_string())
+}
+
 pub fn parse_tags(tags: &str) -> Vec<String> {
     tags.split(',')
         .map(|tag| tag.trim())
@@ -113,6 +194,21 @@ pub fn encode_thumbnail(image: &DynamicImage, size: u32) -> Result<Vec<u8>> {
     Ok(buffer.into_inner())
 }
 
+/// Store the blurhash / LQIP placeholder in an image's metadata JSON.
+/// Placeholders are a nicety, so failures are logged and never block ingest.
+pub fn attach_image_placeholder(
+    metadata: &mut serde_json::Value,
+    image: &DynamicImage,
+    path: &Path,
+) {
+    match compute_image_placeholder(image) {
+        Ok(placeholder) => metadata["placeholder"] = serde_json::json!(placeholder),
+        Err(err) => {
+            tracing::warn!("Failed to compute placeholder for image {}: {err:#}", path.display());
+        },
+    }
+}
+
 pub fn collect_image_files(dir: &Path, recursive: bool) -> Result<Vec<PathBuf>> {
     let mut files = Vec::new();
     let exts = ["png", "jpg", "jpeg", "gif", "webp", "bmp", "svg"];
@@ -10,7 +10,7 @@ mod tests {
     #[test]
     fn article_schema_has_expected_fields() {
         let schema = schema::article_schema();
-        assert_eq!(schema.fields().len(), 19);
+        assert_eq!(schema.fields().len(), 21);
 
         let id_field = schema.field_with_name("id").expect("id field");
         assert_eq!(id_field.data_type(), &DataType::Utf8);
@@ -70,6 +70,18 @@ mod tests {
             .expect("interactive_page_id field");
         assert_eq!(interactive_page_id.data_type(), &DataType::Utf8);
         assert!(interactive_page_id.is_nullable());
+
+        let series_id = schema
+            .field_with_name("series_id")
+            .expect("series_id field");
+        assert_eq!(series_id.data_type(), &DataType::Utf8);
+        assert!(series_id.is_nullable());
+
+        let series_order = schema
+            .field_with_name("series_order")
+            .expect("series_order field");
+        assert_eq!(series_order.data_type(), &DataType::Int32);
+        assert!(series_order.is_nullable());
     }
 
     #[test]
@@ -124,6 +136,8 @@ mod tests {
                 article_kind: Some("interactive".to_string()),
                 source_url: Some("https://example.com/post-1".to_string()),
                 interactive_page_id: Some("page-1".to_string()),
+                series_id: Some("rust-deep-dive".to_string()),
+                series_order: Some(2),
                 vector_en: Some(vec![0.1; TEXT_VECTOR_DIM_EN]),
                 vector_zh: None,
                 created_at: 1,
@@ -145,6 +159,8 @@ mod tests {
                 article_kind: None,
                 source_url: None,
                 interactive_page_id: None,
+                series_id: None,
+                series_order: None,
                 vector_en: None,
                 vector_zh: Some(vec![0.2; TEXT_VECTOR_DIM_ZH]),
                 created_at: 3,
@@ -189,6 +205,18 @@ mod tests {
         assert_eq!(featured_array.value(0), "hero.jpg");
         assert!(featured_array.is_null(1));
 
+        let series_order_idx = batch
+            .schema()
+            .index_of("series_order")
+            .expect("series_order column");
+        let series_order_array = batch
+            .column(series_order_idx)
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .expect("series_order array");
+        assert_eq!(series_order_array.value(0), 2);
+        assert!(series_order_array.is_null(1));
+
         let content_en_idx = batch
             .schema()
             .index_of("content_en")
@@ -70,6 +70,44 @@ Body content.
         assert!(body.contains("Body content."));
     }
 
+    #[test]
+    fn normalized_series_builds_key_and_validates_order() {
+        let series = utils::Frontmatter::normalized_series(
+            Some(" Rust Deep Dive ".to_string()),
+            Some("Rust 深入".to_string()),
+            Some("  ".to_string()),
+            Some(2),
+        )
+        .expect("valid series")
+        .expect("series present");
+        assert_eq!(series.id, "rust-deep-dive");
+        assert_eq!(series.title.as_deref(), Some("Rust 深入"));
+        assert_eq!(series.description, None);
+        assert_eq!(series.order, Some(2));
+
+        let record = series.taxonomy_record(7).expect("taxonomy row");
+        assert_eq!(record.id, "series:rust-deep-dive");
+        assert_eq!(record.kind, "series");
+        assert_eq!(record.name, "Rust 深入");
+
+        let reference_only = utils::Frontmatter::normalized_series(
+            Some("rust-deep-dive".to_string()),
+            None,
+            None,
+            None,
+        )
+        .expect("valid series")
+        .expect("series present");
+        assert!(reference_only.taxonomy_record(7).is_none());
+
+        assert!(utils::Frontmatter::normalized_series(None, None, None, None)
+            .expect("no series")
+            .is_none());
+        assert!(utils::Frontmatter::normalized_series(None, None, None, Some(1)).is_err());
+        assert!(utils::Frontmatter::normalized_series(Some("x".to_string()), None, None, Some(0))
+            .is_err());
+    }
+
     #[test]
     fn parse_tags_trims_and_filters_empty() {
         let tags = utils::parse_tags(" rust, wasm, ,backend ,,");
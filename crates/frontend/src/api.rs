@@ -324,6 +324,9 @@ fn normalize_local_media_playback_response(
     if let Some(player_url) = response.player_url.take() {
         response.player_url = Some(resolve_local_media_asset_url(player_url));
     }
+    for track in &mut response.subtitle_tracks {
+        track.url = resolve_local_media_asset_url(std::mem::take(&mut track.url));
+    }
     response
 }
 
@@ -788,7 +791,7 @@ pub enum LocalMediaEntryKind {
 }
 
 #[cfg(feature = "local-media")]
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct LocalMediaEntry {
     pub kind: LocalMediaEntryKind,
     pub name: String,
@@ -797,6 +800,28 @@ pub struct LocalMediaEntry {
     pub modified_at_ms: Option<i64>,
     pub extension: Option<String>,
     pub poster_url: Option<String>,
+    #[serde(default)]
+    pub progress: Option<LocalMediaPlaybackProgress>,
+}
+
+#[cfg(feature = "local-media")]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LocalMediaPlaybackProgress {
+    pub relative_path: String,
+    pub position_seconds: f64,
+    pub duration_seconds: Option<f64>,
+    pub completed: bool,
+    pub last_opened_at_ms: Option<i64>,
+    pub updated_at_ms: i64,
+}
+
+#[cfg(feature = "local-media")]
+impl LocalMediaPlaybackProgress {
+    /// Mirrors the media service: finished or barely started files start
+    /// over.
+    pub fn resume_seconds(&self) -> Option<f64> {
+        (!self.completed && self.position_seconds >= 5.0).then_some(self.position_seconds)
+    }
 }
 
 #[cfg(feature = "local-media")]
@@ -840,6 +865,42 @@ pub struct LocalMediaPlaybackOpenResponse {
     pub duration_seconds: Option<f64>,
     pub detail: Option<String>,
     pub error: Option<String>,
+    #[serde(default)]
+    pub audio_tracks: Vec<LocalMediaAudioTrack>,
+    #[serde(default)]
+    pub selected_audio_track: Option<usize>,
+    #[serde(default)]
+    pub subtitle_tracks: Vec<LocalMediaSubtitleTrack>,
+    /// Source position of the stream's time zero for trimmed artifacts.
+    #[serde(default)]
+    pub start_offset_seconds: Option<f64>,
+    /// Stream position to seek to once the player has loaded.
+    #[serde(default)]
+    pub resume_seconds: Option<f64>,
+}
+
+#[cfg(feature = "local-media")]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LocalMediaAudioTrack {
+    pub index: usize,
+    pub codec: Option<String>,
+    pub language: Option<String>,
+    pub title: Option<String>,
+    pub channels: Option<u32>,
+    pub default: bool,
+}
+
+#[cfg(feature = "local-media")]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LocalMediaSubtitleTrack {
+    pub id: String,
+    pub codec: Option<String>,
+    pub language: Option<String>,
+    pub title: Option<String>,
+    pub default: bool,
+    pub forced: bool,
+    pub external: bool,
+    pub url: String,
 }
 
 #[cfg(feature = "local-media")]
@@ -858,10 +919,27 @@ pub struct LocalMediaPlaybackJobResponse {
 #[derive(Debug, Serialize)]
 struct LocalMediaPlaybackOpenRequest<'a> {
     file: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    audio_track: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_seconds: Option<f64>,
 }
 
 #[cfg(all(feature = "local-media", not(feature = "mock")))]
-pub fn build_admin_local_media_raw_playback(file: &str) -> LocalMediaPlaybackOpenResponse {
+#[derive(Debug, Serialize)]
+struct LocalMediaPlaybackProgressRequest<'a> {
+    file: &'a str,
+    position_seconds: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration_seconds: Option<f64>,
+    completed: bool,
+}
+
+#[cfg(all(feature = "local-media", not(feature = "mock")))]
+pub fn build_admin_local_media_raw_playback(
+    file: &str,
+    start_seconds: Option<f64>,
+) -> LocalMediaPlaybackOpenResponse {
     let title = std::path::Path::new(file)
         .file_name()
         .and_then(|value| value.to_str())
@@ -882,11 +960,20 @@ pub fn build_admin_local_media_raw_playback(file: &str) -> LocalMediaPlaybackOpe
                 .to_string(),
         ),
         error: None,
+        audio_tracks: Vec::new(),
+        selected_audio_track: None,
+        subtitle_tracks: Vec::new(),
+        start_offset_seconds: None,
+        resume_seconds: start_seconds,
     }
 }
 
 #[cfg(all(feature = "local-media", feature = "mock"))]
-pub fn build_admin_local_media_raw_playback(file: &str) -> LocalMediaPlaybackOpenResponse {
+pub fn build_admin_local_media_raw_playback(
+    file: &str,
+    start_seconds: Option<f64>,
+) -> LocalMediaPlaybackOpenResponse {
+    let _ = start_seconds;
     LocalMediaPlaybackOpenResponse {
         status: LocalMediaPlaybackStatus::Failed,
         mode: Some(LocalMediaPlaybackMode::Raw),
@@ -896,6 +983,11 @@ pub fn build_admin_local_media_raw_playback(file: &str) -> LocalMediaPlaybackOpe
         duration_seconds: None,
         detail: None,
         error: Some("Local media is unavailable in mock mode".to_string()),
+        audio_tracks: Vec::new(),
+        selected_audio_track: None,
+        subtitle_tracks: Vec::new(),
+        start_offset_seconds: None,
+        resume_seconds: None,
     }
 }
 
@@ -940,10 +1032,12 @@ pub async fn fetch_admin_local_media_list(
 #[cfg(feature = "local-media")]
 pub async fn open_admin_local_media_playback(
     file: &str,
+    audio_track: Option<usize>,
+    start_seconds: Option<f64>,
 ) -> Result<LocalMediaPlaybackOpenResponse, String> {
     #[cfg(feature = "mock")]
     {
-        let _ = file;
+        let _ = (file, audio_track, start_seconds);
         Err("Local media is unavailable in mock mode".to_string())
     }
 
@@ -952,6 +1046,8 @@ pub async fn open_admin_local_media_playback(
         let response = api_post(&format!("{}/playback/open", local_media_api_base()))
             .json(&LocalMediaPlaybackOpenRequest {
                 file,
+                audio_track,
+                start_seconds,
             })
             .map_err(|err| err.to_string())?
             .send()
@@ -965,6 +1061,39 @@ pub async fn open_admin_local_media_playback(
     }
 }
 
+#[cfg(feature = "local-media")]
+pub async fn update_admin_local_media_playback_progress(
+    file: &str,
+    position_seconds: f64,
+    duration_seconds: Option<f64>,
+    completed: bool,
+) -> Result<(), String> {
+    #[cfg(feature = "mock")]
+    {
+        let _ = (file, position_seconds, duration_seconds, completed);
+        Err("Local media is unavailable in mock mode".to_string())
+    }
+
+    #[cfg(not(feature = "mock"))]
+    {
+        let response = api_post(&format!("{}/playback/progress", local_media_api_base()))
+            .json(&LocalMediaPlaybackProgressRequest {
+                file,
+                position_seconds,
+                duration_seconds,
+                completed,
+            })
+            .map_err(|err| err.to_string())?
+            .send()
+            .await
+            .map_err(|err| err.to_string())?;
+        if !response.ok() {
+            return Err(local_media_api_error(response, "Failed to save playback progress").await);
+        }
+        Ok(())
+    }
+}
+
 #[cfg(feature = "local-media")]
 pub async fn fetch_admin_local_media_job_status(
     job_id: &str,
@@ -1197,17 +1326,130 @@ pub struct SearchResult {
     pub tags: Vec<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SearchFacetCount {
+    pub name: String,
+    pub count: usize,
+}
+
+/// Tag and category counts over the whole filtered result set.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct SearchFacets {
+    #[serde(default)]
+    pub tags: Vec<SearchFacetCount>,
+    #[serde(default)]
+    pub categories: Vec<SearchFacetCount>,
+}
+
+/// Drill-down filters shared by keyword and semantic search.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SearchFilters {
+    pub tags: Vec<String>,
+    pub category: Option<String>,
+    pub author: Option<String>,
+    /// `markdown` or `interactive_repost`.
+    pub article_kind: Option<String>,
+    /// `zh` or `en`.
+    pub language: Option<String>,
+    pub date_from: Option<String>,
+    pub date_to: Option<String>,
+}
+
+impl SearchFilters {
+    pub fn is_empty(&self) -> bool {
+        self.query_params().is_empty()
+    }
+
+    /// Encoded `key=value` pairs, in the same shape for API calls and page
+    /// URLs.
+    pub fn query_params(&self) -> Vec<String> {
+        let mut params = Vec::new();
+        let tags = self
+            .tags
+            .iter()
+            .map(|tag| tag.trim())
+            .filter(|tag| !tag.is_empty())
+            .collect::<Vec<_>>();
+        if !tags.is_empty() {
+            params.push(format!("tags={}", urlencoding::encode(&tags.join(","))));
+        }
+        for (key, value) in [
+            ("category", &self.category),
+            ("author", &self.author),
+            ("article_kind", &self.article_kind),
+            ("language", &self.language),
+            ("date_from", &self.date_from),
+            ("date_to", &self.date_to),
+        ] {
+            if let Some(value) = value.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+                params.push(format!("{key}={}", urlencoding::encode(value)));
+            }
+        }
+        params
+    }
+}
+
+/// One page of search results plus facets and the cursor for the next page.
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+pub struct SearchPage {
+    pub results: Vec<SearchResult>,
+    pub total: usize,
+    /// `total` stopped at the server's candidate window.
+    #[serde(default)]
+    pub total_is_lower_bound: bool,
+    #[serde(default)]
+    pub facets: SearchFacets,
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+}
+
+#[cfg(feature = "mock")]
+fn mock_search_page(keyword: &str, filters: &SearchFilters, limit: Option<usize>) -> SearchPage {
+    let mut results = models::mock_search(keyword)
+        .into_iter()
+        .filter(|result| {
+            filters
+                .category
+                .as_deref()
+                .is_none_or(|category| result.category.eq_ignore_ascii_case(category))
+                && filters.tags.iter().all(|tag| {
+                    result
+                        .tags
+                        .iter()
+                        .any(|candidate| candidate.eq_ignore_ascii_case(tag))
+                })
+        })
+        .collect::<Vec<_>>();
+    let total = results.len();
+    if let Some(limit) = limit {
+        results.truncate(limit);
+    }
+    SearchPage {
+        results,
+        total,
+        total_is_lower_bound: false,
+        facets: SearchFacets::default(),
+        next_cursor: None,
+    }
+}
+
 #[cfg(not(feature = "mock"))]
-#[allow(
-    dead_code,
-    reason = "The client currently consumes only the result list, but the backend response still \
-              carries metadata useful for diagnostics."
-)]
-#[derive(Debug, Deserialize)]
-struct SearchResponse {
-    results: Vec<SearchResult>,
-    total: usize,
-    query: String,
+fn push_search_page_params(
+    url: &mut String,
+    filters: &SearchFilters,
+    limit: Option<usize>,
+    cursor: Option<&str>,
+) {
+    if let Some(limit) = limit {
+        url.push_str(&format!("&limit={limit}"));
+    }
+    for param in filters.query_params() {
+        url.push('&');
+        url.push_str(&param);
+    }
+    if let Some(cursor) = cursor {
+        url.push_str(&format!("&cursor={}", urlencoding::encode(cursor)));
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -1284,28 +1526,100 @@ struct ImageTextSearchResponse {
 /// 搜索文章
 pub async fn search_articles(
     keyword: &str,
+    filters: &SearchFilters,
     limit: Option<usize>,
-) -> Result<Vec<SearchResult>, String> {
+    cursor: Option<&str>,
+) -> Result<SearchPage, String> {
     if keyword.trim().is_empty() {
-        return Ok(vec![]);
+        return Ok(SearchPage::default());
     }
 
     #[cfg(feature = "mock")]
     {
-        let mut results = models::mock_search(keyword);
-        if let Some(limit) = limit {
-            results.truncate(limit);
-        }
-        Ok(results)
+        let _ = cursor;
+        Ok(mock_search_page(keyword, filters, limit))
     }
 
     #[cfg(not(feature = "mock"))]
     {
         let mut url = format!("{}/search?q={}", API_BASE, urlencoding::encode(keyword));
-        if let Some(limit) = limit {
-            url.push_str(&format!("&limit={limit}"));
+        push_search_page_params(&mut url, filters, limit, cursor);
+
+        let response = api_get(&url)
+            .send()
+            .await
+            .map_err(|e| format!("Network error: {:?}", e))?;
+
+        if !response.ok() {
+            return Err(format!("HTTP error: {}", response.status()));
         }
 
+        response
+            .json()
+            .await
+            .map_err(|e| format!("Parse error: {:?}", e))
+    }
+}
+
+/// Corpus a unified search hit came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnifiedSearchKind {
+    Article,
+    Song,
+    Image,
+    InteractivePage,
+}
+
+/// One rank-fused hit from `/api/search/all`. `id` is the routing key: the
+/// article id for articles and interactive mirrors, the song id, or the
+/// image filename.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct UnifiedSearchHit {
+    pub kind: UnifiedSearchKind,
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub subtitle: String,
+    #[serde(default)]
+    pub score: f32,
+}
+
+#[cfg(not(feature = "mock"))]
+#[derive(Debug, Deserialize)]
+struct UnifiedSearchResponse {
+    top: Vec<UnifiedSearchHit>,
+}
+
+/// Search articles, songs, images and interactive mirrors in one request.
+///
+/// Only the fused `top` list is returned; corpora that time out or fail on
+/// the backend simply contribute no hits.
+pub async fn search_all(keyword: &str, limit: usize) -> Result<Vec<UnifiedSearchHit>, String> {
+    if keyword.trim().is_empty() {
+        return Ok(vec![]);
+    }
+
+    #[cfg(feature = "mock")]
+    {
+        Ok(models::mock_search(keyword)
+            .into_iter()
+            .take(limit)
+            .map(|result| UnifiedSearchHit {
+                kind: UnifiedSearchKind::Article,
+                id: result.id,
+                title: result.title,
+                subtitle: result.category,
+                score: 0.0,
+            })
+            .collect())
+    }
+
+    #[cfg(not(feature = "mock"))]
+    {
+        let url =
+            format!("{}/search/all?q={}&limit={limit}", API_BASE, urlencoding::encode(keyword));
+
         let response = api_get(&url)
             .send()
             .await
@@ -1315,12 +1629,11 @@ pub async fn search_articles(
             return Err(format!("HTTP error: {}", response.status()));
         }
 
-        let json_response: SearchResponse = response
+        let data: UnifiedSearchResponse = response
             .json()
             .await
             .map_err(|e| format!("Parse error: {:?}", e))?;
-
-        Ok(json_response.results)
+        Ok(data.top)
     }
 }
 
@@ -1335,33 +1648,32 @@ pub async fn search_articles(
 )]
 pub async fn semantic_search_articles(
     keyword: &str,
+    filters: &SearchFilters,
     enhanced_highlight: bool,
     limit: Option<usize>,
+    cursor: Option<&str>,
     max_distance: Option<f32>,
     hybrid: bool,
     hybrid_rrf_k: Option<f32>,
     hybrid_vector_limit: Option<usize>,
     hybrid_fts_limit: Option<usize>,
-) -> Result<Vec<SearchResult>, String> {
+) -> Result<SearchPage, String> {
     if keyword.trim().is_empty() {
-        return Ok(vec![]);
+        return Ok(SearchPage::default());
     }
 
     #[cfg(feature = "mock")]
     {
         let _ = (
             enhanced_highlight,
+            cursor,
             max_distance,
             hybrid,
             hybrid_rrf_k,
             hybrid_vector_limit,
             hybrid_fts_limit,
         );
-        let mut results = models::mock_search(keyword);
-        if let Some(limit) = limit {
-            results.truncate(limit);
-        }
-        Ok(results)
+        Ok(mock_search_page(keyword, filters, limit))
     }
 
     #[cfg(not(feature = "mock"))]
@@ -1370,9 +1682,7 @@ pub async fn semantic_search_articles(
         if enhanced_highlight {
             url.push_str("&enhanced_highlight=true");
         }
-        if let Some(limit) = limit {
-            url.push_str(&format!("&limit={limit}"));
-        }
+        push_search_page_params(&mut url, filters, limit, cursor);
         if let Some(max_distance) = max_distance {
             url.push_str(&format!("&max_distance={max_distance}"));
         }
@@ -1398,12 +1708,10 @@ pub async fn semantic_search_articles(
             return Err(format!("HTTP error: {}", response.status()));
         }
 
-        let json_response: SearchResponse = response
+        response
             .json()
             .await
-            .map_err(|e| format!("Parse error: {:?}", e))?;
-
-        Ok(json_response.results)
+            .map_err(|e| format!("Parse error: {:?}", e))
     }
 }
 
@@ -5925,6 +6233,7 @@ pub struct PublicLlmGatewayUsageLookupRequest {
 pub struct PublicLlmGatewayUsageKeyView {
     pub name: String,
     pub provider_type: String,
+    pub is_sub_key: bool,
     pub quota_billable_limit: u64,
     pub usage_input_uncached_tokens: u64,
     pub usage_input_cached_tokens: u64,
@@ -6012,9 +6321,26 @@ pub struct PublicLlmGatewayUsageLookupResponse {
     pub has_more: bool,
     pub totals: AdminUsageTotalsView,
     pub events: Vec<PublicLlmGatewayUsageEventView>,
+    pub sub_keys: Vec<PublicLlmGatewayUsageSubKeyView>,
     pub generated_at: i64,
 }
 
+/// One delegated sub-key under the queried key.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct PublicLlmGatewayUsageSubKeyView {
+    pub id: String,
+    pub name: String,
+    pub status: String,
+    pub quota_billable_limit: u64,
+    pub usage_billable_tokens: u64,
+    pub remaining_billable: i64,
+    pub request_max_concurrency: Option<u64>,
+    pub request_min_start_interval_ms: Option<u64>,
+    pub last_used_at: Option<i64>,
+    pub created_at: i64,
+}
+
 /// One public usage window from the cached Codex limit snapshot.
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct LlmGatewayRateLimitWindowView {
@@ -6247,6 +6573,7 @@ fn merge_admin_codex_account_pages(
     first.accounts.append(&mut next.accounts);
     first.summary = next.summary;
     first.total = next.total;
+    first.circuit_breakers = next.circuit_breakers;
     first.generated_at = next.generated_at;
     first.has_more = next.has_more;
     first.offset = 0;
@@ -7435,6 +7762,7 @@ pub async fn fetch_public_llm_gateway_usage(
             key: PublicLlmGatewayUsageKeyView {
                 name: "mock-public-key".to_string(),
                 provider_type: "codex".to_string(),
+                is_sub_key: false,
                 quota_billable_limit: 10_000,
                 usage_input_uncached_tokens: 2_500,
                 usage_input_cached_tokens: 800,
@@ -7542,6 +7870,32 @@ pub async fn fetch_public_llm_gateway_usage(
                     created_at: 1_774_996_400_000,
                 },
             ],
+            sub_keys: vec![
+                PublicLlmGatewayUsageSubKeyView {
+                    id: "mock-sub-key-1".to_string(),
+                    name: "team-alice".to_string(),
+                    status: "active".to_string(),
+                    quota_billable_limit: 3_000,
+                    usage_billable_tokens: 1_200,
+                    remaining_billable: 1_800,
+                    request_max_concurrency: Some(2),
+                    request_min_start_interval_ms: None,
+                    last_used_at: Some(1_774_998_200_000),
+                    created_at: 1_774_900_000_000,
+                },
+                PublicLlmGatewayUsageSubKeyView {
+                    id: "mock-sub-key-2".to_string(),
+                    name: "team-bob".to_string(),
+                    status: "disabled".to_string(),
+                    quota_billable_limit: 1_000,
+                    usage_billable_tokens: 400,
+                    remaining_billable: 600,
+                    request_max_concurrency: Some(1),
+                    request_min_start_interval_ms: Some(500),
+                    last_used_at: None,
+                    created_at: 1_774_910_000_000,
+                },
+            ],
             generated_at: 1_775_000_000_000,
         })
     }
@@ -10016,6 +10370,27 @@ impl Default for AccountSummaryView {
     }
 }
 
+/// Runtime circuit breaker for one account (Kiro: routing identity) or proxy,
+/// as reported alongside the admin account lists.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+#[serde(default)]
+pub struct CircuitBreakerView {
+    /// `account` or `proxy`.
+    pub scope: String,
+    pub target: String,
+    pub account_names: Vec<String>,
+    /// `closed`, `open` or `half_open`.
+    pub state: String,
+    pub window_requests: usize,
+    pub window_failures: usize,
+    pub open_remaining_ms: Option<i64>,
+    pub trials_in_flight: usize,
+    pub trial_successes: usize,
+    pub consecutive_opens: u32,
+    pub last_failure_reason: Option<String>,
+    pub state_changed_at: Option<i64>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
 #[serde(default)]
 pub struct AccountListResponse {
@@ -10025,6 +10400,7 @@ pub struct AccountListResponse {
     pub limit: usize,
     pub offset: usize,
     pub has_more: bool,
+    pub circuit_breakers: Vec<CircuitBreakerView>,
     pub generated_at: i64,
 }
 
@@ -10094,6 +10470,7 @@ pub async fn fetch_admin_llm_gateway_accounts() -> Result<AccountListResponse, S
             limit: 0,
             offset: 0,
             has_more: false,
+            circuit_breakers: vec![],
             generated_at: 0,
         })
     }
@@ -10147,6 +10524,7 @@ pub async fn fetch_admin_llm_gateway_accounts_page_with_query(
             limit,
             offset,
             has_more: false,
+            circuit_breakers: vec![],
             generated_at: 0,
         })
     }
@@ -11062,6 +11440,7 @@ pub struct AdminKiroAccountStatusesResponse {
     pub total: usize,
     pub limit: usize,
     pub offset: usize,
+    pub circuit_breakers: Vec<CircuitBreakerView>,
     pub generated_at: i64,
 }
 
@@ -12133,6 +12512,7 @@ pub async fn fetch_admin_kiro_account_statuses(
             total: 0,
             limit: query.limit.unwrap_or(24),
             offset: query.offset.unwrap_or(0),
+            circuit_breakers: vec![],
             generated_at: 0,
         })
     }
@@ -12527,6 +12907,7 @@ mod tests {
                 active_count: 1,
                 ..AdminAccountsSummaryView::default()
             },
+            circuit_breakers: vec![],
             generated_at: 10,
         };
         let next = AccountListResponse {
@@ -12543,6 +12924,7 @@ mod tests {
                 active_count: 2,
                 ..AdminAccountsSummaryView::default()
             },
+            circuit_breakers: vec![],
             generated_at: 20,
         };
 
@@ -12673,8 +13055,9 @@ mod tests {
     #[test]
     #[cfg(not(feature = "mock"))]
     fn build_admin_local_media_raw_playback_uses_raw_mode_and_encoded_url() {
-        let response = build_admin_local_media_raw_playback("未归类/demo clip.mp4");
+        let response = build_admin_local_media_raw_playback("未归类/demo clip.mp4", Some(42.0));
         assert_eq!(response.status, LocalMediaPlaybackStatus::Ready);
+        assert_eq!(response.resume_seconds, Some(42.0));
         assert_eq!(response.mode, Some(LocalMediaPlaybackMode::Raw));
         assert_eq!(response.title, "demo clip.mp4");
         assert!(response
@@ -1,10 +1,12 @@
-//! Header search input with a debounced article-suggestion dropdown.
+//! Header omnibox: a search input with a debounced cross-corpus dropdown.
 //!
 //! Controlled by the parent (`value` / `on_change`), so the header's search
 //! and clear buttons keep working off the same state. The component owns the
-//! suggestion lifecycle: 300ms debounce, top-5 keyword results, full
-//! keyboard navigation (arrows / Enter / Escape), outside-click dismissal,
-//! and `listbox` / `aria-activedescendant` semantics.
+//! suggestion lifecycle: 300ms debounce, the top rank-fused hits from
+//! `/api/search/all` (articles, songs, images and interactive mirrors, each
+//! with its own icon and destination), full keyboard navigation (arrows /
+//! Enter / Escape), outside-click dismissal, and `listbox` /
+//! `aria-activedescendant` semantics.
 //!
 //! The dropdown is a floating acrylic panel (absolutely positioned, so it
 //! never reflows the header) that surfaces three states: a shimmering
@@ -19,7 +21,12 @@ use web_sys::{HtmlInputElement, KeyboardEvent, Node};
 use yew::prelude::*;
 use yew_router::prelude::*;
 
-use crate::{api, api::SearchResult, i18n::current::common as common_text, router::Route};
+use crate::{
+    api,
+    api::{UnifiedSearchHit, UnifiedSearchKind},
+    i18n::current::common as common_text,
+    router::Route,
+};
 
 /// Debounce window before firing the suggestion query.
 const SUGGEST_DEBOUNCE_MS: u32 = 300;
@@ -44,11 +51,20 @@ pub struct SearchSuggestProps {
     pub input_class: Classes,
 }
 
+fn hit_icon(kind: UnifiedSearchKind) -> &'static str {
+    match kind {
+        UnifiedSearchKind::Article => "fa-file-lines",
+        UnifiedSearchKind::Song => "fa-music",
+        UnifiedSearchKind::Image => "fa-image",
+        UnifiedSearchKind::InteractivePage => "fa-wand-magic-sparkles",
+    }
+}
+
 /// Search input + suggestion dropdown (desktop header).
 #[function_component(SearchSuggest)]
 pub fn search_suggest(props: &SearchSuggestProps) -> Html {
     let container_ref = use_node_ref();
-    let suggestions = use_state(Vec::<SearchResult>::new);
+    let suggestions = use_state(Vec::<UnifiedSearchHit>::new);
     let open = use_state(|| false);
     let loading = use_state(|| false);
     let highlighted = use_state(|| None::<usize>);
@@ -90,7 +106,7 @@ pub fn search_suggest(props: &SearchSuggestProps) -> Html {
                 let request_seq = request_seq.clone();
                 let timeout = Timeout::new(SUGGEST_DEBOUNCE_MS, move || {
                     wasm_bindgen_futures::spawn_local(async move {
-                        let results = api::search_articles(&query, Some(SUGGEST_LIMIT))
+                        let results = api::search_all(&query, SUGGEST_LIMIT)
                             .await
                             .unwrap_or_default();
                         if *request_seq.borrow() != request_id {
@@ -130,12 +146,28 @@ pub fn search_suggest(props: &SearchSuggestProps) -> Html {
         let navigator = navigator.clone();
         let open = open.clone();
         let suggestions = suggestions.clone();
+        let value = props.value.clone();
         Callback::from(move |index: usize| {
-            if let (Some(navigator), Some(result)) = (navigator.clone(), suggestions.get(index)) {
-                open.set(false);
-                navigator.push(&Route::ArticleDetail {
-                    id: result.id.clone(),
-                });
+            let (Some(navigator), Some(hit)) = (navigator.clone(), suggestions.get(index)) else {
+                return;
+            };
+            open.set(false);
+            match hit.kind {
+                UnifiedSearchKind::Article => navigator.push(&Route::ArticleDetail {
+                    id: hit.id.clone(),
+                }),
+                UnifiedSearchKind::Song => navigator.push(&Route::MusicPlayer {
+                    id: hit.id.clone(),
+                }),
+                UnifiedSearchKind::InteractivePage => navigator.push(&Route::ArticleInteractive {
+                    id: hit.id.clone(),
+                }),
+                // Images have no detail route; land on image search for the
+                // same query instead.
+                UnifiedSearchKind::Image => {
+                    let _ = navigator
+                        .push_with_query(&Route::Search, &[("q", value.trim()), ("mode", "image")]);
+                },
             }
         })
     };
@@ -262,13 +294,13 @@ pub fn search_suggest(props: &SearchSuggestProps) -> Html {
                                         {onmouseenter}
                                         {onclick}
                                     >
-                                        <i class={classes!("fas", "fa-file-lines", "search-suggest-icon")} aria-hidden="true" />
+                                        <i class={classes!("fas", hit_icon(result.kind), "search-suggest-icon")} aria-hidden="true" />
                                         <span class={classes!("search-suggest-title")}>
                                             { &result.title }
                                         </span>
-                                        if !result.category.is_empty() {
+                                        if !result.subtitle.is_empty() {
                                             <span class={classes!("search-suggest-meta")}>
-                                                { &result.category }
+                                                { &result.subtitle }
                                             </span>
                                         }
                                     </li>
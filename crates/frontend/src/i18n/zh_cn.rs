@@ -10,7 +10,7 @@ pub mod common {
     pub const SEARCH_PLACEHOLDER: &str = "搜索...";
     pub const LOADING: &str = "加载中...";
     pub const SEARCH_SUGGEST_LOADING: &str = "正在搜索…";
-    pub const SEARCH_SUGGEST_EMPTY: &str = "没有找到相关文章";
+    pub const SEARCH_SUGGEST_EMPTY: &str = "没有找到相关内容";
     pub const SEARCH_SUGGEST_FOOTER: &str = "回车搜索全部结果";
     pub const TERMINAL_PROMPT_CMD: &str = "$ ";
     pub const TERMINAL_PROMPT_OUTPUT: &str = "> ";
@@ -168,6 +168,19 @@ pub mod search {
         "关键词检索没命中，建议切换到 Semantic 语义检索，它更擅长找语义相关内容。";
     pub const SEMANTIC_EMPTY_CARD_DESC: &str = "未找到语义相关结果，可尝试更具体的关键词。";
     pub const SWITCH_TO_SEMANTIC_CTA: &str = "改用 Semantic 语义检索";
+    pub const FACET_ACTIVE: &str = "当前筛选";
+    pub const FACET_TAGS: &str = "标签";
+    pub const FACET_CATEGORIES: &str = "分类";
+    pub const FACET_CLEAR: &str = "清除筛选";
+    pub const FACET_AUTHOR_TEMPLATE: &str = "作者：{}";
+    pub const FACET_KIND_INTERACTIVE: &str = "交互转载";
+    pub const FACET_KIND_MARKDOWN: &str = "原创文章";
+    pub const FACET_LANGUAGE_EN: &str = "有英文版";
+    pub const FACET_LANGUAGE_ZH: &str = "中文";
+    pub const FACET_DATE_FROM_TEMPLATE: &str = "{} 之后";
+    pub const FACET_DATE_TO_TEMPLATE: &str = "{} 之前";
+    pub const LOAD_MORE_RESULTS: &str = "加载更多结果";
+    pub const LOADING_MORE_RESULTS: &str = "加载中...";
 
     pub const SEARCH_ENGINE_BADGE: &str = "// SEARCH_ENGINE";
     pub const STATUS_SCANNING: &str = "SCANNING";
@@ -319,6 +332,11 @@ pub mod article_detail_page {
     pub const RELATED_TITLE: &str = "相关推荐";
     pub const RELATED_LOADING: &str = "加载相关推荐中...";
     pub const NO_RELATED: &str = "暂无相关推荐";
+    pub const SERIES_NAV_ARIA: &str = "系列导航";
+    pub const SERIES_TITLE_TEMPLATE: &str = "系列：{}";
+    pub const SERIES_POSITION_TEMPLATE: &str = "第 {} / {} 篇";
+    pub const SERIES_PREV: &str = "上一篇";
+    pub const SERIES_NEXT: &str = "下一篇";
     pub const LANG_SWITCH_LABEL: &str = "语言";
     pub const LANG_SWITCH_ZH: &str = "中文";
     pub const LANG_SWITCH_EN: &str = "English";
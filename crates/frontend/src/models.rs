@@ -113,6 +113,7 @@ fn mock_articles_full() -> Vec<Article> {
             },
             source_url: None,
             interactive_page_id,
+            series: None,
         });
     }
 
@@ -9,7 +9,7 @@ use crate::{
         AdminUpstreamProxyConfigView,
     },
     components::{empty_state::EmptyState, pagination::Pagination},
-    pages::admin_kiro_gateway::KiroAccountCard,
+    pages::{admin_kiro_gateway::KiroAccountCard, admin_llm_gateway::CircuitBreakerPanel},
     router::Route,
 };
 
@@ -328,6 +328,7 @@ pub fn admin_kiro_account_status_page() -> Html {
                 </section>
             } else if let Some(status_response) = response.as_ref().as_ref() {
                 <>
+                    <CircuitBreakerPanel circuits={status_response.circuit_breakers.clone()} />
                     <section class={classes!("grid", "gap-4", "xl:grid-cols-2")}>
                         { for status_response.accounts.iter().map(|account| html! {
                             <KiroAccountCard
@@ -53,7 +53,7 @@ use crate::{
         AdminUpstreamProxyConfigScopeView, AdminUpstreamProxyConfigView,
         AdminUpstreamProxyEndpointCheckView, AdminUsageJournalFileView,
         AdminUsageJournalPreviewResponse, AdminUsageJournalStatusView, AdminUsageTotalsView,
-        CodexAccountImportJobDetailView, CodexAccountImportJobSummaryView,
+        CircuitBreakerView, CodexAccountImportJobDetailView, CodexAccountImportJobSummaryView,
         CreateAdminAccountGroupInput, CreateAdminUpstreamProxyConfigInput,
         LlmGatewayRateLimitBucketView, LlmGatewayRateLimitStatusResponse,
         LlmGatewayRateLimitWindowView, LlmGatewayRuntimeConfig, PatchAdminAccountGroupInput,
@@ -1238,6 +1238,79 @@ fn pretty_json_text(raw: &str) -> String {
         .unwrap_or_else(|| raw.to_string())
 }
 
+#[derive(Properties, PartialEq)]
+pub(crate) struct CircuitBreakerPanelProps {
+    pub(crate) circuits: Vec<CircuitBreakerView>,
+}
+
+#[function_component(CircuitBreakerPanel)]
+pub(crate) fn circuit_breaker_panel(props: &CircuitBreakerPanelProps) -> Html {
+    let now_ms = Date::now() as i64;
+    let tripped = props
+        .circuits
+        .iter()
+        .filter(|circuit| circuit.state != "closed")
+        .collect::<Vec<_>>();
+    if tripped.is_empty() {
+        return html! {
+            <p class={classes!("m-0", "text-xs", "text-[var(--muted)]")}>
+                { format!("熔断器：跟踪中的 {} 个 circuit 全部闭合", props.circuits.len()) }
+            </p>
+        };
+    }
+    html! {
+        <div class={classes!("rounded-xl", "border", "border-[var(--border)]", "bg-[var(--surface)]", "p-4")}>
+            <h3 class={classes!("m-0", "text-sm", "font-semibold")}>
+                { format!("熔断器（{} 个未闭合）", tripped.len()) }
+            </h3>
+            <div class={classes!("mt-3", "grid", "gap-2")}>
+                { for tripped.into_iter().map(|circuit| {
+                    let state_label = if circuit.state == "half_open" {
+                        "half-open".to_string()
+                    } else {
+                        match circuit.open_remaining_ms {
+                            Some(remaining_ms) => {
+                                format!("open · {}", format_future_duration_ms(remaining_ms))
+                            },
+                            None => circuit.state.clone(),
+                        }
+                    };
+                    html! {
+                        <div class={classes!("rounded-lg", "border", "border-[var(--border)]", "px-3", "py-2", "text-xs")}>
+                            <div class={classes!("flex", "flex-wrap", "items-center", "gap-2")}>
+                                <span class={classes!("font-semibold", "text-amber-600")}>{ state_label }</span>
+                                <span class={classes!("font-mono")}>
+                                    { format!("{} {}", circuit.scope, circuit.target) }
+                                </span>
+                                <span class={classes!("text-[var(--muted)]")}>
+                                    { format!(
+                                        "失败 {}/{} · 试探中 {} · 连续熔断 {} 次 · {} 前变更",
+                                        circuit.window_failures,
+                                        circuit.window_requests,
+                                        circuit.trials_in_flight,
+                                        circuit.consecutive_opens,
+                                        format_relative_age_from_ms(now_ms, circuit.state_changed_at),
+                                    ) }
+                                </span>
+                            </div>
+                            if circuit.scope == "proxy" && !circuit.account_names.is_empty() {
+                                <div class={classes!("mt-1", "text-[var(--muted)]")}>
+                                    { format!("账号: {}", circuit.account_names.join(", ")) }
+                                </div>
+                            }
+                            if let Some(reason) = circuit.last_failure_reason.as_deref() {
+                                <div class={classes!("mt-1", "font-mono", "break-all", "text-[var(--muted)]")}>
+                                    { reason.to_string() }
+                                </div>
+                            }
+                        </div>
+                    }
+                }) }
+            </div>
+        </div>
+    }
+}
+
 #[derive(Properties, PartialEq)]
 struct KeyEditorCardProps {
     key_item: AdminLlmGatewayKeyView,
@@ -2859,6 +2932,7 @@ pub fn admin_llm_gateway_page() -> Html {
     let accounts = use_state(Vec::<AccountSummaryView>::new);
     let accounts_summary = use_state(AdminAccountsSummaryView::default);
     let codex_rate_limit_status = use_state(|| None::<LlmGatewayRateLimitStatusResponse>);
+    let codex_circuit_breakers = use_state(Vec::<CircuitBreakerView>::new);
     let import_name = use_state(String::new);
     let import_id_token = use_state(String::new);
     let import_access_token = use_state(String::new);
@@ -3217,6 +3291,7 @@ pub fn admin_llm_gateway_page() -> Html {
         let accounts = accounts.clone();
         let accounts_summary = accounts_summary.clone();
         let codex_rate_limit_status = codex_rate_limit_status.clone();
+        let codex_circuit_breakers = codex_circuit_breakers.clone();
         let accounts_total = accounts_total.clone();
         let account_page_limit = account_page_limit.clone();
         let active_tab = active_tab.clone();
@@ -3298,6 +3373,7 @@ pub fn admin_llm_gateway_page() -> Html {
             let accounts = accounts.clone();
             let accounts_summary = accounts_summary.clone();
             let codex_rate_limit_status = codex_rate_limit_status.clone();
+            let codex_circuit_breakers = codex_circuit_breakers.clone();
             let accounts_total = accounts_total.clone();
             let account_page_limit = account_page_limit.clone();
             let active_tab = active_tab.clone();
@@ -3643,6 +3719,7 @@ pub fn admin_llm_gateway_page() -> Html {
                             accounts_total.set(accounts_resp.total);
                             account_page_limit.set(accounts_resp.limit.max(1));
                             accounts.set(accounts_resp.accounts);
+                            codex_circuit_breakers.set(accounts_resp.circuit_breakers);
                             account_proxy_inputs.set(next_proxy_inputs);
                             account_route_weight_tier_inputs.set(next_route_weight_tier_inputs);
                             account_request_max_inputs.set(next_request_max_inputs);
@@ -3654,6 +3731,7 @@ pub fn admin_llm_gateway_page() -> Html {
                             accounts_total.set(0);
                             accounts.set(Vec::new());
                             codex_rate_limit_status.set(None);
+                            codex_circuit_breakers.set(Vec::new());
                             account_proxy_inputs.set(BTreeMap::new());
                             account_route_weight_tier_inputs.set(BTreeMap::new());
                             account_request_max_inputs.set(BTreeMap::new());
@@ -8553,6 +8631,10 @@ pub fn admin_llm_gateway_page() -> Html {
                         </button>
                     </div>
 
+                    <div class={classes!("mt-3")}>
+                        <CircuitBreakerPanel circuits={(*codex_circuit_breakers).clone()} />
+                    </div>
+
                     if *show_import_form {
                     <div class={classes!("mt-3", "grid", "gap-3", "rounded-lg", "border", "border-[var(--border)]", "bg-[var(--surface-alt)]", "p-4")}>
                         <div class={classes!("grid", "gap-3", "md:grid-cols-2")}>
@@ -6,6 +6,7 @@ use yew_router::prelude::*;
 use crate::{
     api::{
         fetch_admin_local_media_list, LocalMediaEntry, LocalMediaEntryKind, LocalMediaListResponse,
+        LocalMediaPlaybackProgress,
     },
     components::empty_state::EmptyState,
     router::Route,
@@ -72,12 +73,13 @@ pub fn admin_local_media_page() -> Html {
 
     let open_player = {
         let navigator = navigator.clone();
-        Callback::from(move |file: String| {
+        Callback::from(move |(file, start): (String, Option<f64>)| {
             if let Some(nav) = navigator.clone() {
                 let _ = nav.push_with_query(
                     &Route::AdminLocalMediaPlayer,
                     &AdminLocalMediaPlayerQuery {
                         file: Some(file),
+                        start,
                     },
                 );
             }
@@ -172,17 +174,20 @@ pub fn admin_local_media_page() -> Html {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub(crate) struct AdminLocalMediaPlayerQuery {
     #[serde(default)]
     pub file: Option<String>,
+    /// Source position to resume from, taken from the saved progress.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start: Option<f64>,
 }
 
 #[derive(Properties, PartialEq, Clone)]
 struct LocalMediaCardProps {
     entry: LocalMediaEntry,
     on_open_dir: Callback<String>,
-    on_open_player: Callback<String>,
+    on_open_player: Callback<(String, Option<f64>)>,
 }
 
 #[function_component(LocalMediaCard)]
@@ -197,7 +202,11 @@ fn local_media_card(props: &LocalMediaCardProps) -> Html {
         },
         LocalMediaEntryKind::Video => {
             let open_player = props.on_open_player.clone();
-            Callback::from(move |_| open_player.emit(relative_path.clone()))
+            let resume = entry
+                .progress
+                .as_ref()
+                .and_then(|progress| progress.resume_seconds());
+            Callback::from(move |_| open_player.emit((relative_path.clone(), resume)))
         },
     };
     let action_label = match entry.kind {
@@ -239,6 +248,16 @@ fn local_media_card(props: &LocalMediaCardProps) -> Html {
                 <span class="absolute right-3 top-3 rounded-full bg-black/60 px-2.5 py-1 text-[10px] font-semibold uppercase tracking-[0.08em] text-white">
                     { action_label }
                 </span>
+                if let Some(progress) = entry.progress.as_ref() {
+                    <span class="absolute left-3 top-3 rounded-full bg-black/60 px-2.5 py-1 text-[10px] font-semibold text-white">
+                        { progress_label(progress) }
+                    </span>
+                    if let Some(ratio) = progress_ratio(progress) {
+                        <div class="absolute inset-x-0 bottom-0 h-1 bg-black/40">
+                            <div class="h-full bg-sky-500" style={format!("width: {:.1}%", ratio * 100.0)}></div>
+                        </div>
+                    }
+                }
             </div>
             <div class="p-4">
                 <div class="flex items-start gap-2 text-sm font-semibold text-[var(--text)]">
@@ -300,6 +319,36 @@ fn render_breadcrumbs(current_dir: &str, open_dir: Callback<String>) -> Html {
     }
 }
 
+fn progress_label(progress: &LocalMediaPlaybackProgress) -> String {
+    if progress.completed {
+        return "Watched".to_string();
+    }
+    match progress.resume_seconds() {
+        Some(position) => format!("Resume {}", format_position(position)),
+        None => "Opened".to_string(),
+    }
+}
+
+fn progress_ratio(progress: &LocalMediaPlaybackProgress) -> Option<f64> {
+    if progress.completed {
+        return Some(1.0);
+    }
+    let duration = progress.duration_seconds.filter(|value| *value > 0.0)?;
+    Some((progress.position_seconds / duration).clamp(0.0, 1.0))
+}
+
+fn format_position(seconds: f64) -> String {
+    let total_seconds = seconds.max(0.0) as u64;
+    let hours = total_seconds / 3600;
+    let minutes = (total_seconds % 3600) / 60;
+    let seconds = total_seconds % 60;
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
 fn format_entry_meta(size_bytes: Option<u64>, extension: Option<&str>) -> String {
     let size = size_bytes
         .map(format_bytes)
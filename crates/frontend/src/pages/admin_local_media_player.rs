@@ -11,8 +11,9 @@ use super::admin_local_media::AdminLocalMediaPlayerQuery;
 use crate::{
     api::{
         build_admin_local_media_raw_playback, fetch_admin_local_media_job_status,
-        open_admin_local_media_playback, LocalMediaPlaybackMode, LocalMediaPlaybackOpenResponse,
-        LocalMediaPlaybackStatus,
+        open_admin_local_media_playback, update_admin_local_media_playback_progress,
+        LocalMediaAudioTrack, LocalMediaPlaybackMode, LocalMediaPlaybackOpenResponse,
+        LocalMediaPlaybackStatus, LocalMediaSubtitleTrack,
     },
     router::Route,
 };
@@ -26,6 +27,10 @@ extern "C" {
         mode: &str,
         title: &str,
         storage_key: &str,
+        subtitles_json: &str,
+        resume_seconds: Option<f64>,
+        start_offset_seconds: Option<f64>,
+        on_progress: &js_sys::Function,
     );
 
     #[wasm_bindgen(js_namespace = window, js_name = sfLocalMediaPlayerUnmount)]
@@ -36,16 +41,17 @@ extern "C" {
 pub fn admin_local_media_player_page() -> Html {
     let navigator = use_navigator();
     let location = use_location();
-    let file = location
+    let query = location
         .as_ref()
-        .and_then(|loc| loc.query::<AdminLocalMediaPlayerQuery>().ok())
-        .and_then(|query| query.file)
-        .unwrap_or_default();
+        .and_then(|loc| loc.query::<AdminLocalMediaPlayerQuery>().ok());
+    let start = query.as_ref().and_then(|query| query.start);
+    let file = query.and_then(|query| query.file).unwrap_or_default();
 
     let loading = use_state(|| true);
     let error = use_state(|| None::<String>);
     let playback = use_state(|| None::<LocalMediaPlaybackOpenResponse>);
     let selected_mode = use_state(|| PlaybackOpenMode::Raw);
+    let selected_audio_track = use_state(|| None::<usize>);
     let player_host = use_node_ref();
 
     {
@@ -54,7 +60,9 @@ pub fn admin_local_media_player_page() -> Html {
         let playback = playback.clone();
         let selected_mode = selected_mode.clone();
         let file = file.clone();
-        use_effect_with((file.clone(), *selected_mode), move |(file, selected_mode)| {
+        let deps = (file.clone(), *selected_mode, *selected_audio_track, start);
+        use_effect_with(deps, move |(file, selected_mode, audio_track, start)| {
+            let start = *start;
             let has_file = !file.trim().is_empty();
             if !has_file {
                 loading.set(false);
@@ -65,12 +73,13 @@ pub fn admin_local_media_player_page() -> Html {
                 let file = file.clone();
                 match selected_mode {
                     PlaybackOpenMode::Raw => {
-                        playback.set(Some(build_admin_local_media_raw_playback(&file)));
+                        playback.set(Some(build_admin_local_media_raw_playback(&file, start)));
                         loading.set(false);
                     },
                     PlaybackOpenMode::Compatible => {
+                        let audio_track = *audio_track;
                         spawn_local(async move {
-                            match open_admin_local_media_playback(&file).await {
+                            match open_admin_local_media_playback(&file, audio_track, start).await {
                                 Ok(response) => playback.set(Some(response)),
                                 Err(err) => error.set(Some(err)),
                             }
@@ -100,19 +109,37 @@ pub fn admin_local_media_player_page() -> Html {
                         spawn_local(async move {
                             match fetch_admin_local_media_job_status(&job_id).await {
                                 Ok(job) => {
+                                    // Job status carries no track listing; keep
+                                    // the one returned when playback was opened.
+                                    let previous = (*playback).clone();
                                     let next = LocalMediaPlaybackOpenResponse {
                                         status: job.status,
                                         mode: job.mode,
                                         job_id: Some(job.job_id),
                                         player_url: job.player_url,
-                                        title: playback
-                                            .as_ref()
+                                        title: previous
                                             .as_ref()
                                             .map(|value| value.title.clone())
                                             .unwrap_or_else(|| "Preparing".to_string()),
                                         duration_seconds: job.duration_seconds,
                                         detail: job.detail,
                                         error: job.error,
+                                        audio_tracks: previous
+                                            .as_ref()
+                                            .map(|value| value.audio_tracks.clone())
+                                            .unwrap_or_default(),
+                                        selected_audio_track: previous
+                                            .as_ref()
+                                            .and_then(|value| value.selected_audio_track),
+                                        subtitle_tracks: previous
+                                            .as_ref()
+                                            .map(|value| value.subtitle_tracks.clone())
+                                            .unwrap_or_default(),
+                                        start_offset_seconds: previous
+                                            .as_ref()
+                                            .and_then(|value| value.start_offset_seconds),
+                                        resume_seconds: previous
+                                            .and_then(|value| value.resume_seconds),
                                     };
                                     playback.set(Some(next));
                                 },
@@ -142,14 +169,40 @@ pub fn admin_local_media_player_page() -> Html {
                             LocalMediaPlaybackMode::Raw => "raw",
                             LocalMediaPlaybackMode::Hls => "hls",
                         };
+                        // The bridge reports source positions, start offsets
+                        // already applied.
+                        let on_progress = {
+                            let file = file.clone();
+                            Closure::<dyn FnMut(f64, f64, bool)>::new(
+                                move |position: f64, duration: f64, completed: bool| {
+                                    let file = file.clone();
+                                    let duration = (duration.is_finite() && duration > 0.0)
+                                        .then_some(duration);
+                                    spawn_local(async move {
+                                        if let Err(err) =
+                                            update_admin_local_media_playback_progress(
+                                                &file, position, duration, completed,
+                                            )
+                                            .await
+                                        {
+                                            web_sys::console::warn_1(&err.into());
+                                        }
+                                    });
+                                },
+                            )
+                        };
                         sf_local_media_player_mount(
                             element.clone(),
                             &player_url,
                             mode_name,
                             &playback_state.title,
                             &storage_key,
+                            &subtitles_json(&playback_state.subtitle_tracks),
+                            playback_state.resume_seconds,
+                            playback_state.start_offset_seconds,
+                            on_progress.as_ref().unchecked_ref(),
                         );
-                        Some(element)
+                        Some((element, on_progress))
                     } else {
                         None
                     }
@@ -160,8 +213,11 @@ pub fn admin_local_media_player_page() -> Html {
                 None
             };
             move || {
-                if let Some(element) = mounted {
+                // Unmount first: it flushes a final progress report through
+                // the closure, which must still be alive.
+                if let Some((element, on_progress)) = mounted {
                     sf_local_media_player_unmount(element);
+                    drop(on_progress);
                 }
             }
         });
@@ -192,6 +248,54 @@ pub fn admin_local_media_player_page() -> Html {
         Callback::from(move |_| selected_mode.set(PlaybackOpenMode::Compatible))
     };
 
+    let select_audio_track = {
+        let selected_audio_track = selected_audio_track.clone();
+        Callback::from(move |index: usize| selected_audio_track.set(Some(index)))
+    };
+
+    let track_panel = match (*playback).as_ref() {
+        Some(playback)
+            if playback.audio_tracks.len() > 1 || !playback.subtitle_tracks.is_empty() =>
+        {
+            html! {
+                <div class="mb-4 rounded-[var(--radius)] border border-[var(--border)] bg-[var(--surface)] p-4 text-sm">
+                    if playback.audio_tracks.len() > 1 {
+                        <div class="flex flex-wrap items-center gap-2">
+                            <span class="text-[var(--muted)]">{ "Audio" }</span>
+                            { for playback.audio_tracks.iter().map(|track| {
+                                let index = track.index;
+                                let onclick = select_audio_track.reform(move |_| index);
+                                html! {
+                                    <button
+                                        type="button"
+                                        class={mode_button_classes(playback.selected_audio_track == Some(index))}
+                                        {onclick}
+                                    >
+                                        { audio_track_label(track) }
+                                    </button>
+                                }
+                            }) }
+                        </div>
+                    }
+                    if !playback.subtitle_tracks.is_empty() {
+                        <div class="mt-2 text-[var(--muted)]">
+                            { format!(
+                                "Subtitles: {}. Pick one from the player's subtitle menu.",
+                                playback
+                                    .subtitle_tracks
+                                    .iter()
+                                    .map(subtitle_track_label)
+                                    .collect::<Vec<_>>()
+                                    .join(", ")
+                            ) }
+                        </div>
+                    }
+                </div>
+            }
+        },
+        _ => Html::default(),
+    };
+
     let body = if *loading {
         html! {
             <div class="rounded-[var(--radius)] border border-[var(--border)] bg-[var(--surface)] p-6 text-sm text-[var(--muted)]">
@@ -256,7 +360,7 @@ pub fn admin_local_media_player_page() -> Html {
                         </div>
                         <h1 class="mt-2 text-xl font-semibold text-[var(--text)] break-all">{ file.clone() }</h1>
                         <p class="mt-1 text-sm text-[var(--muted)]">
-                            { "Default is raw browser playback. Only switch to compatibility mode when the browser cannot play the file correctly." }
+                            { "Default is raw browser playback. Only switch to compatibility mode when the browser cannot play the file correctly, or to pick another audio language and load subtitles." }
                         </p>
                     </div>
                     <div class="flex flex-wrap items-center gap-2">
@@ -281,11 +385,53 @@ pub fn admin_local_media_player_page() -> Html {
                     </div>
                 </div>
             </section>
+            { track_panel }
             { body }
         </main>
     }
 }
 
+fn audio_track_label(track: &LocalMediaAudioTrack) -> String {
+    let mut label = track
+        .title
+        .clone()
+        .or_else(|| track.language.clone())
+        .unwrap_or_else(|| format!("Track {}", track.index + 1));
+    let details = [track.codec.clone(), track.channels.map(|channels| format!("{channels}ch"))]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+    if !details.is_empty() {
+        label.push_str(&format!(" ({})", details.join(" ")));
+    }
+    label
+}
+
+fn subtitle_track_label(track: &LocalMediaSubtitleTrack) -> String {
+    track
+        .title
+        .clone()
+        .or_else(|| track.language.clone())
+        .unwrap_or_else(|| format!("Subtitle {}", track.id))
+}
+
+/// Subtitle list in the shape the player bridge expects.
+fn subtitles_json(tracks: &[LocalMediaSubtitleTrack]) -> String {
+    let entries = tracks
+        .iter()
+        .map(|track| {
+            serde_json::json!({
+                "id": track.id,
+                "url": track.url,
+                "label": subtitle_track_label(track),
+                "language": track.language,
+                "default": track.default,
+            })
+        })
+        .collect::<Vec<_>>();
+    serde_json::Value::Array(entries).to_string()
+}
+
 fn format_duration(duration_seconds: f64) -> String {
     let total_seconds = duration_seconds.max(0.0).round() as u64;
     let hours = total_seconds / 3600;
@@ -1,5 +1,7 @@
 use gloo_timers::{callback::Timeout, future::TimeoutFuture};
-use static_flow_shared::{Article, ArticleKind, ArticleListItem};
+use static_flow_shared::{
+    Article, ArticleKind, ArticleListItem, ArticleSeriesLink, ArticleSeriesNav,
+};
 use wasm_bindgen::{JsCast, JsValue};
 use web_sys::{
     window, Element, HtmlImageElement, HtmlSelectElement, HtmlTextAreaElement, KeyboardEvent, Node,
@@ -28,7 +30,7 @@ use crate::{
         tooltip::{TooltipIconButton, TooltipPosition},
         view_trend_chart::ViewTrendChart,
     },
-    i18n::{current::article_detail_page as t, fill_one},
+    i18n::{current::article_detail_page as t, fill_one, fill_two},
     router::Route,
     seo,
     utils::{image_url, markdown_for_external_export, markdown_to_html},
@@ -386,6 +388,66 @@ fn format_published_time(ts_ms: i64) -> String {
     value.replace('T', " ").trim_end_matches('Z').to_string()
 }
 
+fn render_series_nav(series: &ArticleSeriesNav) -> Html {
+    let link_classes = classes!(
+        "flex",
+        "flex-col",
+        "gap-1",
+        "flex-1",
+        "min-w-0",
+        "p-3",
+        "border",
+        "border-[var(--border)]",
+        "rounded-[8px]",
+        "bg-[var(--surface)]",
+        "text-[var(--text)]",
+        "transition-[border-color_0.2s_var(--ease-spring)]",
+        "hover:border-[var(--primary)]"
+    );
+    let render_link = |link: &ArticleSeriesLink, label: &'static str| {
+        html! {
+            <Link<Route>
+                to={Route::ArticleDetail { id: link.id.clone() }}
+                classes={link_classes.clone()}
+            >
+                <span class={classes!("text-[0.8rem]", "text-[var(--muted)]")}>{ label }</span>
+                <span class={classes!("truncate")}>{ link.title.clone() }</span>
+            </Link<Route>>
+        }
+    };
+
+    html! {
+        <nav
+            class={classes!("mt-8", "border-t", "border-[var(--border)]", "pt-5")}
+            aria-label={t::SERIES_NAV_ARIA}
+        >
+            <h2 class={classes!(
+                "m-0",
+                "mb-3",
+                "text-[1rem]",
+                "text-[var(--muted)]",
+                "tracking-[0.15em]",
+                "uppercase"
+            )}>
+                { fill_one(t::SERIES_TITLE_TEMPLATE, &series.title) }
+                if series.position > 0 && series.total > 0 {
+                    <span class={classes!("ml-2", "normal-case", "tracking-normal")}>
+                        { fill_two(t::SERIES_POSITION_TEMPLATE, series.position, series.total) }
+                    </span>
+                }
+            </h2>
+            <div class={classes!("flex", "flex-col", "sm:flex-row", "gap-3")}>
+                if let Some(prev) = series.prev.as_ref() {
+                    { render_link(prev, t::SERIES_PREV) }
+                }
+                if let Some(next) = series.next.as_ref() {
+                    { render_link(next, t::SERIES_NEXT) }
+                }
+            </div>
+        </nav>
+    }
+}
+
 #[function_component(ArticleDetailPage)]
 pub fn article_detail_page(props: &ArticleDetailProps) -> Html {
     let route = use_route::<Route>();
@@ -2566,6 +2628,10 @@ pub fn article_detail_page(props: &ArticleDetailProps) -> Html {
                             </ul>
                         </footer>
 
+                        if let Some(series) = article.series.as_ref() {
+                            { render_series_nav(series) }
+                        }
+
                         <section class={classes!(
                             "mt-10",
                             "pt-6",
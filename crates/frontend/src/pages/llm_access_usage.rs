@@ -104,6 +104,50 @@ fn normalize_public_lookup_error(raw: &str) -> String {
     normalized.to_string()
 }
 
+/// Parent key with its delegated sub-keys underneath. Sub-key usage is
+/// already included in the parent's totals.
+fn render_sub_key_tree(response: &PublicLlmGatewayUsageLookupResponse) -> Html {
+    html! {
+        <section class={classes!("rounded-lg", "border", "border-[var(--border)]", "bg-[var(--surface)]", "p-5")}>
+            <h2 class={classes!("m-0", "font-mono", "text-base", "font-bold", "text-[var(--text)]")}>
+                { "子 Key" }
+            </h2>
+            <p class={classes!("mt-2", "mb-0", "text-sm", "text-[var(--muted)]")}>
+                { format!("{} 个子 Key，用量已计入父 Key 的已用额度。", response.sub_keys.len()) }
+            </p>
+            <div class={classes!("mt-4", "font-mono", "text-sm", "font-bold", "text-[var(--text)]")}>
+                { response.key.name.clone() }
+            </div>
+            <ul class={classes!("mt-2", "mb-0", "ml-2", "grid", "gap-2", "border-l", "border-[var(--border)]", "pl-4")}>
+                { for response.sub_keys.iter().map(|sub_key| {
+                    let active = sub_key.status == "active";
+                    html! {
+                        <li class={classes!("flex", "flex-wrap", "items-center", "justify-between", "gap-3", "rounded-lg", "border", "border-[var(--border)]", "bg-[var(--surface-alt)]", "px-3", "py-2")}>
+                            <div class={classes!("flex", "items-center", "gap-2")}>
+                                <span class={classes!("font-mono", "text-sm", "font-semibold", "text-[var(--text)]")}>{ sub_key.name.clone() }</span>
+                                <span class={classes!("rounded-full", "px-2", "py-0.5", "font-mono", "text-[11px]", if active { "bg-emerald-500/10 text-emerald-700 dark:text-emerald-200" } else { "bg-[var(--surface)] text-[var(--muted)]" })}>
+                                    { if active { "active" } else { "revoked" } }
+                                </span>
+                            </div>
+                            <div class={classes!("flex", "flex-wrap", "gap-3", "font-mono", "text-xs", "text-[var(--muted)]")}>
+                                <span>{ format!("额度 {}", format_number_u64(sub_key.quota_billable_limit)) }</span>
+                                <span>{ format!("已用 {}", format_number_u64(sub_key.usage_billable_tokens)) }</span>
+                                <span>{ format!("剩余 {}", format_number_i64(sub_key.remaining_billable)) }</span>
+                                if let Some(concurrency) = sub_key.request_max_concurrency {
+                                    <span>{ format!("并发 {concurrency}") }</span>
+                                }
+                                if let Some(last_used_at) = sub_key.last_used_at {
+                                    <span>{ format!("last used {}", format_ms(last_used_at)) }</span>
+                                }
+                            </div>
+                        </li>
+                    }
+                }) }
+            </ul>
+        </section>
+    }
+}
+
 #[function_component(LlmAccessUsagePage)]
 pub fn llm_access_usage_page() -> Html {
     let key_input = use_state(String::new);
@@ -343,8 +387,15 @@ pub fn llm_access_usage_page() -> Html {
                     <article class={classes!("rounded-lg", "border", "border-[var(--border)]", "bg-[var(--surface)]", "p-4")}>
                         <div class={classes!("font-mono", "text-[11px]", "uppercase", "tracking-widest", "text-[var(--muted)]")}>{ "Key" }</div>
                         <div class={classes!("mt-2", "text-lg", "font-bold", "text-[var(--text)]")}>{ response.key.name.clone() }</div>
-                        <div class={classes!("mt-2", "inline-flex", "rounded-full", "bg-[var(--surface-alt)]", "px-2.5", "py-1", "font-mono", "text-[11px]", "font-semibold", "uppercase", "tracking-[0.12em]", "text-[var(--muted)]")}>
-                            { provider_badge_label(&response.key.provider_type) }
+                        <div class={classes!("mt-2", "flex", "flex-wrap", "gap-2")}>
+                            <span class={classes!("inline-flex", "rounded-full", "bg-[var(--surface-alt)]", "px-2.5", "py-1", "font-mono", "text-[11px]", "font-semibold", "uppercase", "tracking-[0.12em]", "text-[var(--muted)]")}>
+                                { provider_badge_label(&response.key.provider_type) }
+                            </span>
+                            if response.key.is_sub_key {
+                                <span class={classes!("inline-flex", "rounded-full", "bg-sky-500/10", "px-2.5", "py-1", "font-mono", "text-[11px]", "font-semibold", "text-sky-700", "dark:text-sky-200")}>
+                                    { "子 Key · 额度同时计入父 Key" }
+                                </span>
+                            }
                         </div>
                     </article>
                     <article class={classes!("rounded-lg", "border", "border-[var(--border)]", "bg-[var(--surface)]", "p-4")}>
@@ -361,6 +412,10 @@ pub fn llm_access_usage_page() -> Html {
                     </article>
                 </section>
 
+                if !response.sub_keys.is_empty() {
+                    { render_sub_key_tree(&response) }
+                }
+
                 <section class={classes!("rounded-lg", "border", "border-[var(--border)]", "bg-[var(--surface)]", "p-5")}>
                     <div class={classes!("flex", "items-center", "justify-between", "gap-3", "flex-wrap")}>
                         <div>
@@ -9,7 +9,7 @@ use yew_router::prelude::*;
 use crate::{
     api::{
         fetch_images_page, search_images_by_id_page, search_images_by_text_page,
-        semantic_search_articles, ImageInfo, SearchResult,
+        semantic_search_articles, ImageInfo, SearchFacets, SearchFilters, SearchPage, SearchResult,
     },
     components::{
         image_with_loading::ImageWithLoading, pagination::Pagination, raw_html::RawHtml,
@@ -135,8 +135,16 @@ pub fn search_page() -> Html {
         .as_ref()
         .and_then(|q| q.max_distance)
         .filter(|value| value.is_finite() && *value >= 0.0);
+    let filters = query
+        .as_ref()
+        .map(SearchPageQuery::filters)
+        .unwrap_or_default();
     let results = use_state(Vec::<SearchResult>::new);
+    let result_total = use_state(|| search_total_label(0, false));
+    let result_facets = use_state(SearchFacets::default);
+    let next_cursor = use_state(|| None::<String>);
     let loading = use_state(|| false);
+    let loading_more = use_state(|| false);
     let image_catalog = use_state(Vec::<ImageInfo>::new);
     let image_results = use_state(Vec::<ImageInfo>::new);
     let image_loading = use_state(|| false);
@@ -369,87 +377,93 @@ pub fn search_page() -> Html {
         );
     }
 
+    let article_request = ArticleSearchRequest {
+        keyword: keyword.clone(),
+        semantic: mode == "semantic",
+        filters: filters.clone(),
+        enhanced_highlight,
+        limit: active_limit,
+        max_distance,
+        hybrid,
+        hybrid_rrf_k,
+        hybrid_vector_limit,
+        hybrid_fts_limit,
+    };
+
     {
         let results = results.clone();
+        let result_total = result_total.clone();
+        let result_facets = result_facets.clone();
+        let next_cursor = next_cursor.clone();
         let loading = loading.clone();
-        let keyword = keyword.clone();
-        let mode = mode.clone();
 
-        use_effect_with(
-            (
-                keyword.clone(),
-                mode.clone(),
-                enhanced_highlight,
-                active_limit,
-                max_distance,
-                hybrid,
-                hybrid_rrf_k,
-                hybrid_vector_limit,
-                hybrid_fts_limit,
-            ),
-            move |(
-                kw,
-                mode,
-                enhanced_highlight,
-                active_limit,
-                max_distance,
-                hybrid,
-                hybrid_rrf_k,
-                hybrid_vector_limit,
-                hybrid_fts_limit,
-            )| {
-                if mode == "image" || mode == "music" || kw.trim().is_empty() {
-                    loading.set(false);
-                    results.set(vec![]);
-                } else {
-                    loading.set(true);
-                    let results = results.clone();
-                    let loading = loading.clone();
-                    let query_text = kw.clone();
-                    let use_semantic = mode == "semantic";
-                    let use_enhanced_highlight = *enhanced_highlight;
-                    let limit = *active_limit;
-                    let max_distance = *max_distance;
-                    let hybrid_enabled = *hybrid;
-                    let hybrid_rrf_k = *hybrid_rrf_k;
-                    let hybrid_vector_limit = *hybrid_vector_limit;
-                    let hybrid_fts_limit = *hybrid_fts_limit;
-
-                    wasm_bindgen_futures::spawn_local(async move {
-                        let response = if use_semantic {
-                            semantic_search_articles(
-                                &query_text,
-                                use_enhanced_highlight,
-                                limit,
-                                max_distance,
-                                hybrid_enabled,
-                                if hybrid_enabled { Some(hybrid_rrf_k) } else { None },
-                                if hybrid_enabled { hybrid_vector_limit } else { None },
-                                if hybrid_enabled { hybrid_fts_limit } else { None },
-                            )
-                            .await
-                        } else {
-                            crate::api::search_articles(&query_text, limit).await
-                        };
+        use_effect_with((mode.clone(), article_request.clone()), move |(mode, request)| {
+            if mode == "image" || mode == "music" || request.keyword.trim().is_empty() {
+                loading.set(false);
+                results.set(vec![]);
+                result_total.set(search_total_label(0, false));
+                result_facets.set(SearchFacets::default());
+                next_cursor.set(None);
+            } else {
+                loading.set(true);
+                let request = request.clone();
 
-                        match response {
-                            Ok(data) => {
-                                results.set(data);
-                                loading.set(false);
-                            },
-                            Err(e) => {
-                                web_sys::console::error_1(&format!("Search failed: {}", e).into());
-                                loading.set(false);
-                            },
-                        }
-                    });
-                }
+                wasm_bindgen_futures::spawn_local(async move {
+                    match request.fetch(None).await {
+                        Ok(page) => {
+                            results.set(page.results);
+                            result_total
+                                .set(search_total_label(page.total, page.total_is_lower_bound));
+                            result_facets.set(page.facets);
+                            next_cursor.set(page.next_cursor);
+                            loading.set(false);
+                        },
+                        Err(e) => {
+                            web_sys::console::error_1(&format!("Search failed: {}", e).into());
+                            loading.set(false);
+                        },
+                    }
+                });
+            }
 
-                || ()
-            },
-        );
+            || ()
+        });
     }
 
+    let on_load_more_results = {
+        let results = results.clone();
+        let next_cursor = next_cursor.clone();
+        let loading_more = loading_more.clone();
+        let article_request = article_request.clone();
+        Callback::from(move |_: MouseEvent| {
+            let Some(cursor) = (*next_cursor).clone() else {
+                return;
+            };
+            if *loading_more {
+                return;
+            }
+            loading_more.set(true);
+            let results = results.clone();
+            let next_cursor = next_cursor.clone();
+            let loading_more = loading_more.clone();
+            let request = article_request.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match request.fetch(Some(&cursor)).await {
+                    Ok(page) => {
+                        let mut merged = (*results).clone();
+                        merged.extend(page.results);
+                        results.set(merged);
+                        next_cursor.set(page.next_cursor);
+                    },
+                    Err(e) => {
+                        web_sys::console::error_1(&format!("Load more failed: {}", e).into());
+                    },
+                }
+                loading_more.set(false);
+            });
+        })
+    };
+
     // Music search effect
     {
         let music_results = music_results.clone();
@@ -989,44 +1003,53 @@ pub fn search_page() -> Html {
         })
     };
 
-    let keyword_href = build_search_href(
-        None,
-        &keyword,
-        false,
-        Some(requested_limit),
-        fetch_all,
-        None,
-        false,
-        None,
-        None,
-        None,
-        None,
+    let keyword_href = with_filter_params(
+        build_search_href(
+            None,
+            &keyword,
+            false,
+            Some(requested_limit),
+            fetch_all,
+            None,
+            false,
+            None,
+            None,
+            None,
+            None,
+        ),
+        &filters,
     );
-    let semantic_fast_href = build_search_href(
-        Some("semantic"),
-        &keyword,
-        false,
-        Some(requested_limit),
-        fetch_all,
-        max_distance,
-        hybrid,
-        Some(hybrid_rrf_k),
-        hybrid_vector_limit,
-        hybrid_fts_limit,
-        None,
+    let semantic_fast_href = with_filter_params(
+        build_search_href(
+            Some("semantic"),
+            &keyword,
+            false,
+            Some(requested_limit),
+            fetch_all,
+            max_distance,
+            hybrid,
+            Some(hybrid_rrf_k),
+            hybrid_vector_limit,
+            hybrid_fts_limit,
+            None,
+        ),
+        &filters,
     );
-    let semantic_precise_href = build_search_href(
-        Some("semantic"),
-        &keyword,
-        true,
-        Some(requested_limit),
-        fetch_all,
-        max_distance,
-        hybrid,
-        Some(hybrid_rrf_k),
-        hybrid_vector_limit,
-        hybrid_fts_limit,
-        None,
+    let semantic_precise_href = with_filter_params(
+        build_search_href(
+            Some("semantic"),
+            &keyword,
+            true,
+            Some(requested_limit),
+            fetch_all,
+            max_distance,
+            hybrid,
+            Some(hybrid_rrf_k),
+            hybrid_vector_limit,
+            hybrid_fts_limit,
+            None,
+        ),
+        &filters,
     );
     let semantic_href =
         if enhanced_highlight { semantic_precise_href.clone() } else { semantic_fast_href.clone() };
@@ -1096,32 +1119,56 @@ pub fn search_page() -> Html {
         Some("hybrid"),
     );
     let scoped_max_distance = if mode == "semantic" { max_distance } else { None };
-    let limited_href = build_search_href(
-        Some(mode.as_str()),
-        &keyword,
-        enhanced_highlight,
-        Some(requested_limit),
-        false,
-        scoped_max_distance,
-        hybrid,
-        Some(hybrid_rrf_k),
-        hybrid_vector_limit,
-        hybrid_fts_limit,
-        None,
+    let limited_href = with_filter_params(
+        build_search_href(
+            Some(mode.as_str()),
+            &keyword,
+            enhanced_highlight,
+            Some(requested_limit),
+            false,
+            scoped_max_distance,
+            hybrid,
+            Some(hybrid_rrf_k),
+            hybrid_vector_limit,
+            hybrid_fts_limit,
+            None,
+        ),
+        &filters,
     );
-    let all_results_href = build_search_href(
-        Some(mode.as_str()),
-        &keyword,
-        enhanced_highlight,
-        None,
-        true,
-        scoped_max_distance,
-        hybrid,
-        Some(hybrid_rrf_k),
-        hybrid_vector_limit,
-        hybrid_fts_limit,
-        None,
+    let all_results_href = with_filter_params(
+        build_search_href(
+            Some(mode.as_str()),
+            &keyword,
+            enhanced_highlight,
+            None,
+            true,
+            scoped_max_distance,
+            hybrid,
+            Some(hybrid_rrf_k),
+            hybrid_vector_limit,
+            hybrid_fts_limit,
+            None,
+        ),
+        &filters,
     );
+    let facets_panel = if matches!(mode.as_str(), "keyword" | "semantic") && !*loading {
+        let base_href = build_search_href(
+            Some(mode.as_str()),
+            &keyword,
+            enhanced_highlight,
+            Some(requested_limit),
+            fetch_all,
+            scoped_max_distance,
+            hybrid,
+            Some(hybrid_rrf_k),
+            hybrid_vector_limit,
+            hybrid_fts_limit,
+            None,
+        );
+        render_search_facets(&base_href, &filters, &result_facets)
+    } else {
+        Html::default()
+    };
     let hybrid_default_scope_hint = if fetch_all {
         t::HYBRID_DEFAULT_SCOPE_ALL.to_string()
     } else {
@@ -1444,14 +1491,14 @@ pub fn search_page() -> Html {
                             <span class={classes!("search-status-found")}>
                                 { fill_one(
                                     t::KEYWORD_FOUND_TEMPLATE,
-                                    results.len().to_string(),
+                                    (*result_total).clone(),
                                 ) }
                             </span>
                         } else if results.is_empty() {
                             { fill_one(t::SEMANTIC_MISS_TEMPLATE, &keyword) }
                         } else {
                             <span class={classes!("search-status-found")}>
-                                { fill_one(t::SEMANTIC_FOUND_TEMPLATE, results.len().to_string()) }
+                                { fill_one(t::SEMANTIC_FOUND_TEMPLATE, (*result_total).clone()) }
                             </span>
                         }
                     </p>
@@ -2662,6 +2709,7 @@ pub fn search_page() -> Html {
                         </div>
                     } else if !results.is_empty() {
                         <>
+                            { facets_panel.clone() }
                             { for visible_results.iter().enumerate().map(|(idx, result)| {
                                 let delay_style = format!("animation-delay: {}ms", idx * 80);
                                 html! {
@@ -2685,8 +2733,40 @@ pub fn search_page() -> Html {
                                     Html::default()
                                 }
                             }
+                            if next_cursor.is_some() && current_page == total_pages {
+                                <div class={classes!("mt-6", "flex", "justify-center")}>
+                                    <button
+                                        type="button"
+                                        onclick={on_load_more_results.clone()}
+                                        disabled={*loading_more}
+                                        class={classes!(
+                                            "inline-flex",
+                                            "items-center",
+                                            "gap-2",
+                                            "px-5",
+                                            "py-2",
+                                            "rounded-lg",
+                                            "border",
+                                            "border-[var(--primary)]/60",
+                                            "text-[var(--primary)]",
+                                            "text-sm",
+                                            "hover:bg-[var(--primary)]/10",
+                                            "disabled:opacity-50"
+                                        )}
+                                    >
+                                        if *loading_more {
+                                            <i class={classes!("fas", "fa-spinner", "fa-spin")}></i>
+                                            { t::LOADING_MORE_RESULTS }
+                                        } else {
+                                            <i class={classes!("fas", "fa-angles-down")}></i>
+                                            { t::LOAD_MORE_RESULTS }
+                                        }
+                                    </button>
+                                </div>
+                            }
                         </>
                     } else if !keyword.is_empty() {
+                        { facets_panel.clone() }
                         <div class={classes!(
                             "search-empty",
                             "text-center",
@@ -2957,6 +3037,172 @@ pub fn search_page() -> Html {
 }
 
 
+/// Result count for the status line; a count capped by the server's
+/// candidate window is shown as `N+`.
+fn search_total_label(total: usize, lower_bound: bool) -> String {
+    if lower_bound {
+        format!("{total}+")
+    } else {
+        total.to_string()
+    }
+}
+
+fn facet_chip(href: String, label: String, count: Option<usize>, active: bool) -> Html {
+    let tone = if active {
+        classes!("bg-[var(--primary)]/15", "border-[var(--primary)]", "text-[var(--primary)]")
+    } else {
+        classes!(
+            "border-[var(--primary)]/25",
+            "text-[var(--muted)]",
+            "hover:border-[var(--primary)]/60",
+            "hover:text-[var(--primary)]"
+        )
+    };
+    html! {
+        <a
+            href={href}
+            class={classes!(
+                "inline-flex",
+                "items-center",
+                "gap-1.5",
+                "px-3",
+                "py-1",
+                "rounded-full",
+                "border",
+                "text-xs",
+                "transition-colors",
+                "duration-200",
+                tone
+            )}
+        >
+            { label }
+            if let Some(count) = count {
+                <span class={classes!("opacity-60")}>{ count }</span>
+            }
+            if active {
+                <i class={classes!("fas", "fa-xmark", "opacity-70")}></i>
+            }
+        </a>
+    }
+}
+
+/// Active drill-down filters (each chip removes itself) followed by tag
+/// and category facets over the current result set (each chip narrows).
+fn render_search_facets(base_href: &str, filters: &SearchFilters, facets: &SearchFacets) -> Html {
+    let href = |filters: SearchFilters| with_filter_params(base_href.to_string(), &filters);
+    let mut active = Vec::new();
+    for tag in &filters.tags {
+        let mut next = filters.clone();
+        next.tags.retain(|candidate| candidate != tag);
+        active.push(facet_chip(href(next), format!("#{tag}"), None, true));
+    }
+    let mut push_active = |label: String, clear: fn(&mut SearchFilters)| {
+        let mut next = filters.clone();
+        clear(&mut next);
+        active.push(facet_chip(href(next), label, None, true));
+    };
+    if let Some(category) = &filters.category {
+        push_active(category.clone(), |next| next.category = None);
+    }
+    if let Some(author) = &filters.author {
+        push_active(fill_one(t::FACET_AUTHOR_TEMPLATE, author), |next| next.author = None);
+    }
+    if let Some(kind) = &filters.article_kind {
+        let label = if kind == "interactive_repost" {
+            t::FACET_KIND_INTERACTIVE
+        } else {
+            t::FACET_KIND_MARKDOWN
+        };
+        push_active(label.to_string(), |next| next.article_kind = None);
+    }
+    if let Some(language) = &filters.language {
+        let label = if language == "en" { t::FACET_LANGUAGE_EN } else { t::FACET_LANGUAGE_ZH };
+        push_active(label.to_string(), |next| next.language = None);
+    }
+    if let Some(from) = &filters.date_from {
+        push_active(fill_one(t::FACET_DATE_FROM_TEMPLATE, from), |next| next.date_from = None);
+    }
+    if let Some(to) = &filters.date_to {
+        push_active(fill_one(t::FACET_DATE_TO_TEMPLATE, to), |next| next.date_to = None);
+    }
+
+    let tag_chips = facets
+        .tags
+        .iter()
+        .filter(|facet| {
+            !filters
+                .tags
+                .iter()
+                .any(|tag| tag.eq_ignore_ascii_case(&facet.name))
+        })
+        .map(|facet| {
+            let mut next = filters.clone();
+            next.tags.push(facet.name.clone());
+            facet_chip(href(next), format!("#{}", facet.name), Some(facet.count), false)
+        })
+        .collect::<Vec<_>>();
+    let category_chips = if filters.category.is_some() {
+        vec![]
+    } else {
+        facets
+            .categories
+            .iter()
+            .map(|facet| {
+                let mut next = filters.clone();
+                next.category = Some(facet.name.clone());
+                facet_chip(href(next), facet.name.clone(), Some(facet.count), false)
+            })
+            .collect::<Vec<_>>()
+    };
+    if active.is_empty() && tag_chips.is_empty() && category_chips.is_empty() {
+        return Html::default();
+    }
+
+    let row = |label: &'static str, chips: Vec<Html>| {
+        if chips.is_empty() {
+            return Html::default();
+        }
+        html! {
+            <div class={classes!("flex", "flex-wrap", "items-center", "gap-2")}>
+                <span
+                    class={classes!("text-xs", "font-bold", "text-[var(--muted)]", "mr-1")}
+                    style="font-family: 'Space Mono', monospace;"
+                >
+                    { label }
+                </span>
+                { for chips }
+            </div>
+        }
+    };
+    let has_active = !active.is_empty();
+    html! {
+        <div class={classes!(
+            "search-facets",
+            "flex",
+            "flex-col",
+            "gap-3",
+            "mb-6",
+            "p-4",
+            "rounded-xl",
+            "border",
+            "border-[var(--primary)]/20",
+            "bg-[var(--surface)]"
+        )}>
+            { row(t::FACET_ACTIVE, active) }
+            { row(t::FACET_CATEGORIES, category_chips) }
+            { row(t::FACET_TAGS, tag_chips) }
+            if has_active {
+                <a
+                    href={href(SearchFilters::default())}
+                    class={classes!("self-start", "text-xs", "text-[var(--primary)]", "hover:underline")}
+                >
+                    { t::FACET_CLEAR }
+                </a>
+            }
+        </div>
+    }
+}
+
 fn render_search_result(result: &SearchResult) -> Html {
     let highlight_html = AttrValue::from(result.highlight.clone());
 
@@ -3127,6 +3373,83 @@ struct SearchPageQuery {
     limit: Option<usize>,
     all: Option<bool>,
     max_distance: Option<f32>,
+    tags: Option<String>,
+    category: Option<String>,
+    author: Option<String>,
+    article_kind: Option<String>,
+    language: Option<String>,
+    date_from: Option<String>,
+    date_to: Option<String>,
+}
+
+impl SearchPageQuery {
+    fn filters(&self) -> SearchFilters {
+        SearchFilters {
+            tags: self
+                .tags
+                .as_deref()
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|tag| !tag.is_empty())
+                .map(str::to_string)
+                .collect(),
+            category: self.category.clone(),
+            author: self.author.clone(),
+            article_kind: self.article_kind.clone(),
+            language: self.language.clone(),
+            date_from: self.date_from.clone(),
+            date_to: self.date_to.clone(),
+        }
+    }
+}
+
+/// Everything needed to fetch a page of keyword or semantic article
+/// results; also the effect dependency that triggers a fresh search.
+#[derive(Debug, Clone, PartialEq)]
+struct ArticleSearchRequest {
+    keyword: String,
+    semantic: bool,
+    filters: SearchFilters,
+    enhanced_highlight: bool,
+    limit: Option<usize>,
+    max_distance: Option<f32>,
+    hybrid: bool,
+    hybrid_rrf_k: f32,
+    hybrid_vector_limit: Option<usize>,
+    hybrid_fts_limit: Option<usize>,
+}
+
+impl ArticleSearchRequest {
+    async fn fetch(&self, cursor: Option<&str>) -> Result<SearchPage, String> {
+        if !self.semantic {
+            return crate::api::search_articles(&self.keyword, &self.filters, self.limit, cursor)
+                .await;
+        }
+        semantic_search_articles(
+            &self.keyword,
+            &self.filters,
+            self.enhanced_highlight,
+            self.limit,
+            cursor,
+            self.max_distance,
+            self.hybrid,
+            if self.hybrid { Some(self.hybrid_rrf_k) } else { None },
+            if self.hybrid { self.hybrid_vector_limit } else { None },
+            if self.hybrid { self.hybrid_fts_limit } else { None },
+        )
+        .await
+    }
+}
+
+/// Append drill-down filter params to a search page href.
+fn with_filter_params(href: String, filters: &SearchFilters) -> String {
+    let params = filters.query_params();
+    if params.is_empty() {
+        return href;
+    }
+    let separator = if href.contains('?') { '&' } else { '?' };
+    format!("{href}{separator}{}", params.join("&"))
 }
 
 #[allow(
@@ -1,4 +1,4 @@
-use pulldown_cmark::{html, CowStr, Event, Options, Parser, Tag};
+use pulldown_cmark::{html, CowStr, Event, Options, Parser, Tag, TagEnd};
 use url::Url;
 
 use crate::api::API_BASE;
@@ -14,7 +14,7 @@ fn html_sanitizer() -> ammonia::Builder<'static> {
     builder.add_tag_attributes("span", &["class"]);
     builder.add_tag_attributes("code", &["class"]);
     builder.add_tag_attributes("pre", &["class"]);
-    builder.add_tag_attributes("img", &["loading"]);
+    builder.add_tag_attributes("img", &["loading", "decoding", "srcset", "sizes"]);
     builder.add_tag_attributes("input", &["type", "checked", "disabled"]);
     builder.add_generic_attributes(&["id"]);
 
@@ -46,6 +46,71 @@ pub fn image_url(path: &str) -> String {
     }
 }
 
+/// Widths offered in `srcset` for backend-served images. Multiples of the
+/// backend's 32px variant step, so each maps to exactly one cached variant.
+const IMAGE_SRCSET_WIDTHS: [u32; 4] = [480, 960, 1440, 1920];
+/// Article bodies never render wider than the reading column.
+const ARTICLE_IMAGE_SIZES: &str = "(max-width: 960px) 100vw, 960px";
+
+/// Build a `srcset` of backend-resized variants for an image URL produced by
+/// [`image_url`]. External, `data:`, SVG/GIF and already-parameterized URLs
+/// have no variants and return `None`.
+pub fn image_srcset(url: &str) -> Option<String> {
+    let path = url.strip_prefix(API_BASE.trim_end_matches("/api"))?;
+    if !path.starts_with("/api/images/") || path.contains('?') {
+        return None;
+    }
+    let extension = path
+        .rsplit('.')
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    if matches!(extension.as_str(), "svg" | "gif") {
+        return None;
+    }
+    Some(
+        IMAGE_SRCSET_WIDTHS
+            .iter()
+            .map(|width| format!("{url}?w={width} {width}w"))
+            .collect::<Vec<_>>()
+            .join(", "),
+    )
+}
+
+/// A Markdown image being rewritten to a responsive `<img>`; its alt text
+/// arrives as the events between `Start(Image)` and `End(Image)`.
+struct ResponsiveImage {
+    src: String,
+    srcset: String,
+    title: String,
+    alt: String,
+}
+
+impl ResponsiveImage {
+    fn into_html(self) -> String {
+        let title = if self.title.is_empty() {
+            String::new()
+        } else {
+            format!(" title=\"{}\"", escape_html_attr(&self.title))
+        };
+        format!(
+            "<img src=\"{}\" srcset=\"{}\" sizes=\"{ARTICLE_IMAGE_SIZES}\" alt=\"{}\"{title} \
+             loading=\"lazy\" decoding=\"async\" />",
+            escape_html_attr(&self.src),
+            escape_html_attr(&self.srcset),
+            escape_html_attr(&self.alt),
+        )
+    }
+}
+
+fn escape_html_attr(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
 /// Convert Markdown content into HTML with common extensions enabled.
 /// Also transforms relative image paths to API endpoints.
 pub fn markdown_to_html(content: &str) -> String {
@@ -67,28 +132,54 @@ pub fn markdown_to_html(content: &str) -> String {
 
     let parser = Parser::new_ext(&normalized_content, options);
 
-    // Transform image paths
-    let transformed_parser = parser.map(|event| match event {
-        Event::Start(Tag::Image {
-            link_type,
-            dest_url,
-            title,
-            id,
-        }) => {
-            // Check if image path is relative (starts with "images/")
-            let new_url = CowStr::from(image_url(&dest_url));
+    // Transform image paths; backend-served images become responsive
+    // `<img srcset>` so the browser fetches a resized variant.
+    let mut responsive_image = None::<ResponsiveImage>;
+    let mut transformed = Vec::new();
+    for event in parser {
+        match event {
             Event::Start(Tag::Image {
                 link_type,
-                dest_url: new_url,
+                dest_url,
                 title,
                 id,
-            })
-        },
-        _ => event,
-    });
+            }) if responsive_image.is_none() => {
+                let src = image_url(&dest_url);
+                match image_srcset(&src) {
+                    Some(srcset) => {
+                        responsive_image = Some(ResponsiveImage {
+                            src,
+                            srcset,
+                            title: title.to_string(),
+                            alt: String::new(),
+                        });
+                    },
+                    None => transformed.push(Event::Start(Tag::Image {
+                        link_type,
+                        dest_url: CowStr::from(src),
+                        title,
+                        id,
+                    })),
+                }
+            },
+            Event::End(TagEnd::Image) if responsive_image.is_some() => {
+                let image = responsive_image.take().expect("responsive image checked");
+                transformed.push(Event::InlineHtml(CowStr::from(image.into_html())));
+            },
+            Event::Text(text) | Event::Code(text) if responsive_image.is_some() => {
+                if let Some(image) = responsive_image.as_mut() {
+                    image.alt.push_str(&text);
+                }
+            },
+            // Alt text is plain text; formatting inside it is dropped, as
+            // pulldown-cmark's own renderer does.
+            _ if responsive_image.is_some() => {},
+            _ => transformed.push(event),
+        }
+    }
 
     let mut html_output = String::new();
-    html::push_html(&mut html_output, transformed_parser);
+    html::push_html(&mut html_output, transformed.into_iter());
     sanitize_html(&html_output)
 }
 
@@ -579,7 +670,8 @@ fn escape_html_text(content: &str) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::{markdown_for_external_export_with_base, markdown_to_html};
+    use super::{image_srcset, markdown_for_external_export_with_base, markdown_to_html};
+    use crate::api::API_BASE;
 
     #[test]
     fn display_math_block_with_equals_is_not_promoted_to_heading() {
@@ -651,6 +743,20 @@ $$
         assert!(html.contains("&lt;br/&gt;"));
     }
 
+    #[test]
+    fn markdown_api_images_render_with_srcset_and_alt_text() {
+        let html = markdown_to_html("![a *diagram*](images/a.png \"Fig 1\") ![logo](images/b.svg)");
+
+        assert!(html.contains(&format!("src=\"{API_BASE}/images/a.png\"")));
+        assert!(html.contains(&format!("{API_BASE}/images/a.png?w=480 480w")));
+        assert!(html.contains("sizes=\""));
+        assert!(html.contains("alt=\"a diagram\""));
+        assert!(html.contains("title=\"Fig 1\""));
+        assert!(html.contains(&format!("src=\"{API_BASE}/images/b.svg\"")));
+        assert_eq!(html.matches("srcset=").count(), 1);
+        assert!(image_srcset("https://example.com/a.png").is_none());
+    }
+
     #[test]
     fn markdown_html_sanitizer_preserves_safe_markdown_output_attributes() {
         let markdown = r#"
@@ -44,6 +44,9 @@
       if (ctx.persist) {
         ctx.persist();
       }
+      if (ctx.flushProgress) {
+        ctx.flushProgress();
+      }
       if (ctx.player && typeof ctx.player.destroy === "function") {
         ctx.player.destroy();
       }
@@ -216,11 +219,17 @@
     }
   }
 
-  function applyRestoredState(player, storageKey) {
+  function applyRestoredState(player, storageKey, resumeSeconds, startOffsetSeconds) {
     var progressKey = storageKey + ":progress";
     var rateKey = storageKey + ":rate";
     var volumeKey = storageKey + ":volume";
-    var restoredTime = readNumber(progressKey, 0);
+    // A server-side resume point wins; the locally stored position is on
+    // the untrimmed timeline, so it only applies without a start offset.
+    var restoredTime = resumeSeconds > 0
+      ? resumeSeconds
+      : startOffsetSeconds > 0
+        ? 0
+        : readNumber(progressKey, 0);
     var restoredRate = readNumber(rateKey, 1);
     var restoredVolume = readNumber(volumeKey, 1);
 
@@ -246,11 +255,77 @@
     }
   }
 
+  function parseSubtitles(subtitlesJson) {
+    if (!subtitlesJson) return [];
+    try {
+      var parsed = JSON.parse(subtitlesJson);
+      return Array.isArray(parsed) ? parsed : [];
+    } catch (_) {
+      return [];
+    }
+  }
+
+  // Shape expected by the xgplayer texttrack plugin.
+  function buildTextTrackList(subtitles) {
+    return subtitles
+      .filter(function (item) {
+        return item && item.url;
+      })
+      .map(function (item, index) {
+        return {
+          id: item.id || String(index),
+          url: item.url,
+          language: item.language || "",
+          text: item.label || item.language || "Subtitle " + (index + 1),
+          default: !!item.default
+        };
+      });
+  }
+
   window.sfLocalMediaPlayerUnmount = function (element) {
     destroyPlayer(element);
   };
 
-  window.sfLocalMediaPlayerMount = function (element, url, mode, title, storageKey) {
+  // Sends the source position to the media service every `intervalMs` of
+  // playback and right away on pause, end and unmount.
+  function createProgressReporter(player, onProgress, startOffsetSeconds, intervalMs) {
+    var lastReportTs = 0;
+    var offset = startOffsetSeconds > 0 ? startOffsetSeconds : 0;
+    return function (force, completed) {
+      if (typeof onProgress !== "function") {
+        return;
+      }
+      var now = Date.now();
+      if (!force && now - lastReportTs < intervalMs) {
+        return;
+      }
+      try {
+        var currentTime = Number(player.currentTime || 0);
+        if (!Number.isFinite(currentTime)) {
+          return;
+        }
+        var duration = Number(player.duration || 0);
+        lastReportTs = now;
+        onProgress(
+          currentTime + offset,
+          Number.isFinite(duration) && duration > 0 ? duration + offset : 0,
+          !!completed
+        );
+      } catch (_) {}
+    };
+  }
+
+  window.sfLocalMediaPlayerMount = function (
+    element,
+    url,
+    mode,
+    title,
+    storageKey,
+    subtitlesJson,
+    resumeSeconds,
+    startOffsetSeconds,
+    onProgress
+  ) {
     destroyPlayer(element);
     if (!element) {
       throw new Error("Missing mount element");
@@ -298,8 +373,20 @@
       config.hls = config.hls || {};
     }
 
+    // Native HLS picks subtitles up from the master playlist renditions;
+    // every other path loads the WebVTT files as external text tracks.
+    var textTracks = buildTextTrackList(parseSubtitles(subtitlesJson));
+    if (textTracks.length > 0 && !(mode === "hls" && supportsNativeHls())) {
+      config.texttrack = {
+        list: textTracks,
+        isDefaultOpen: textTracks.some(function (item) {
+          return item.default;
+        })
+      };
+    }
+
     var player = new window.Player(config);
-    applyRestoredState(player, storageKey);
+    applyRestoredState(player, storageKey, Number(resumeSeconds || 0), Number(startOffsetSeconds || 0));
     var cleanupPressBoost = installLongPressRateBoost(element, player, coarse);
 
     var progressKey = storageKey + ":progress";
@@ -314,7 +401,7 @@
       lastPersistTs = now;
       try {
         var currentTime = Number(player.currentTime || 0);
-        if (Number.isFinite(currentTime) && currentTime > 3) {
+        if (Number.isFinite(currentTime) && currentTime > 3 && !(startOffsetSeconds > 0)) {
           writeNumber(progressKey, currentTime);
         }
         var currentRate = Number(player.playbackRate || 1);
@@ -328,19 +415,37 @@
       } catch (_) {}
     };
 
+    var reportProgress = createProgressReporter(player, onProgress, Number(startOffsetSeconds || 0), 10000);
+    var ended = false;
+
     if (typeof player.on === "function") {
       player.on("timeupdate", persist);
+      player.on("timeupdate", function () {
+        reportProgress(false, false);
+      });
       player.on("pause", persist);
+      player.on("pause", function () {
+        if (!ended) {
+          reportProgress(true, false);
+        }
+      });
       player.on("ratechange", persist);
       player.on("volumechange", persist);
       player.on("ended", function () {
+        ended = true;
         removeKey(progressKey);
+        reportProgress(true, true);
       });
     }
 
     element.__sfLocalMediaPlayer = {
       player: player,
       persist: persist,
+      flushProgress: function () {
+        if (!ended) {
+          reportProgress(true, false);
+        }
+      },
       cleanupPressBoost: cleanupPressBoost
     };
 
@@ -19,9 +19,19 @@ function createEnvironment({ nativeHls = false } = {}) {
     this.config = config;
     this.playbackRate = 1;
     this.volume = 1;
+    this.currentTime = 0;
+    this.duration = 0;
+    this.handlers = new Map();
     this.destroy = () => {};
-    this.on = () => {};
-    this.once = () => {};
+    this.on = (type, handler) => {
+      const current = this.handlers.get(type) || [];
+      current.push(handler);
+      this.handlers.set(type, current);
+    };
+    this.once = this.on;
+    this.emit = (type) => {
+      (this.handlers.get(type) || []).forEach((handler) => handler());
+    };
     mounts.push({ ctor: 'Player', config, instance: this });
   }
   Player.defaultPreset = { name: 'default-preset' };
@@ -120,7 +130,7 @@ function createEnvironment({ nativeHls = false } = {}) {
   const element = createNode('div');
   element.__sfLocalMediaPlayer = null;
 
-  return { window, mounts, element };
+  return { window, mounts, element, storage };
 }
 
 test('hls mode uses Player with HlsPlayer plugin instead of constructing the plugin directly', () => {
@@ -185,3 +195,108 @@ test('coarse-pointer long press switches to 2x and shows centered badge until re
   assert.equal(player.playbackRate, 1);
   assert.equal(element.children.at(-1).style.opacity, '0');
 });
+
+test('subtitles become external text tracks unless native HLS renders renditions', () => {
+  const subtitles = JSON.stringify([
+    { id: 's0', url: '/admin/local-media/api/subtitle?file=demo.mkv&track=s0', label: 'English', language: 'eng', default: true },
+    { id: 'x0', url: '/admin/local-media/api/subtitle?file=demo.mkv&track=x0', label: 'demo.zh.srt', language: 'zh', default: false },
+  ]);
+
+  const hlsJs = createEnvironment({ nativeHls: false });
+  hlsJs.window.sfLocalMediaPlayerMount(
+    hlsJs.element,
+    '/admin/local-media/api/playback/hls/demo/master.m3u8',
+    'hls',
+    'Demo',
+    'sf-local-media-progress:demo',
+    subtitles,
+  );
+  const texttrack = hlsJs.mounts[0].config.texttrack;
+  assert.equal(texttrack.list.length, 2);
+  assert.equal(texttrack.list[0].text, 'English');
+  assert.equal(texttrack.list[0].default, true);
+  assert.equal(texttrack.list[1].language, 'zh');
+  assert.equal(texttrack.isDefaultOpen, true);
+
+  const native = createEnvironment({ nativeHls: true });
+  native.window.sfLocalMediaPlayerMount(
+    native.element,
+    '/admin/local-media/api/playback/hls/demo/master.m3u8',
+    'hls',
+    'Demo',
+    'sf-local-media-progress:demo',
+    subtitles,
+  );
+  assert.equal(native.mounts[0].config.texttrack, undefined);
+
+  const raw = createEnvironment({ nativeHls: true });
+  raw.window.sfLocalMediaPlayerMount(
+    raw.element,
+    '/admin/local-media/api/playback/raw?file=demo.mp4',
+    'raw',
+    'Demo',
+    'sf-local-media-progress:demo',
+    'not json',
+  );
+  assert.equal(raw.mounts[0].config.texttrack, undefined);
+});
+
+test('resume point seeks the stream and progress is reported on the source timeline', () => {
+  const { window, mounts, element, storage } = createEnvironment({ nativeHls: false });
+  storage.set('sf-local-media-progress:demo:progress', '42');
+  const reports = [];
+
+  window.sfLocalMediaPlayerMount(
+    element,
+    '/admin/local-media/api/playback/hls/demo/index.m3u8',
+    'hls',
+    'Demo',
+    'sf-local-media-progress:demo',
+    '[]',
+    undefined,
+    600,
+    (position, duration, completed) => reports.push({ position, duration, completed }),
+  );
+
+  const player = mounts[0].instance;
+  player.emit('loadeddata');
+  // The trimmed stream starts at the offset, so the stored untrimmed
+  // position must not be applied.
+  assert.equal(player.currentTime, 0);
+
+  player.currentTime = 30;
+  player.duration = 900;
+  player.emit('pause');
+  assert.deepEqual(reports.at(-1), { position: 630, duration: 1500, completed: false });
+
+  player.emit('ended');
+  assert.equal(reports.at(-1).completed, true);
+  const count = reports.length;
+  window.sfLocalMediaPlayerUnmount(element);
+  assert.equal(reports.length, count);
+});
+
+test('server resume point wins over the locally stored position', () => {
+  const { window, mounts, element, storage } = createEnvironment({ nativeHls: false });
+  storage.set('sf-local-media-progress:demo:progress', '42');
+  const reports = [];
+
+  window.sfLocalMediaPlayerMount(
+    element,
+    '/admin/local-media/api/playback/raw?file=demo.mp4',
+    'raw',
+    'Demo',
+    'sf-local-media-progress:demo',
+    '[]',
+    125,
+    undefined,
+    (position, duration, completed) => reports.push({ position, duration, completed }),
+  );
+
+  const player = mounts[0].instance;
+  player.emit('canplay');
+  assert.equal(player.currentTime, 125);
+
+  window.sfLocalMediaPlayerUnmount(element);
+  assert.deepEqual(reports.at(-1), { position: 125, duration: 0, completed: false });
+});
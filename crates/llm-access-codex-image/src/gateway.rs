@@ -1115,6 +1115,8 @@ mod tests {
             status: KEY_STATUS_ACTIVE.to_string(),
             quota_billable_limit: 10,
             billable_tokens_used: 0,
+            parent_remaining_billable: None,
+            parent_key_id: None,
         };
         assert!(reject_key(&active).is_none());
 
@@ -5,6 +5,7 @@ use std::collections::{BTreeMap, BTreeSet};
 use axum::body::Bytes;
 use base64::{engine::general_purpose::STANDARD, Engine as _};
 use eventsource_stream::Event as SseEvent;
+use llm_access_tokenizer::{TokenCounter, TokenizerFamily};
 use serde_json::{json, Map, Value};
 
 use crate::{
@@ -1050,7 +1051,7 @@ fn anthropic_usage_from_response(source: &Value) -> Value {
         .get("output_tokens")
         .and_then(Value::as_u64)
         .or_else(|| usage.get("completion_tokens").and_then(Value::as_u64))
-        .unwrap_or(0);
+        .unwrap_or_else(|| estimate_response_output_tokens(source));
     json!({
         "input_tokens": input_total.saturating_sub(cached_tokens),
         "cache_read_input_tokens": cached_tokens,
@@ -1058,6 +1059,41 @@ fn anthropic_usage_from_response(source: &Value) -> Value {
     })
 }
 
+/// Tokenize the response output items when upstream omits usage, so
+/// Anthropic clients never see a zero `output_tokens` for a real answer.
+fn estimate_response_output_tokens(source: &Value) -> u64 {
+    let counter = TokenCounter::new(TokenizerFamily::Gpt4o);
+    let text_parts = |item: &Value, key: &str| -> u64 {
+        item.get(key)
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|part| part.get("text").and_then(Value::as_str))
+            .map(|text| counter.count_text(text))
+            .sum()
+    };
+    let Some(output_items) = source.get("output").and_then(Value::as_array) else {
+        return 0;
+    };
+    output_items
+        .iter()
+        .map(|item| {
+            let field = |key: &str| item.get(key).and_then(Value::as_str).unwrap_or_default();
+            match field("type") {
+                "message" => text_parts(item, "content"),
+                "reasoning" => text_parts(item, "summary"),
+                "function_call" => {
+                    counter.count_text(field("name")) + counter.count_text(field("arguments"))
+                },
+                "custom_tool_call" => {
+                    counter.count_text(field("name")) + counter.count_text(field("input"))
+                },
+                _ => 0,
+            }
+        })
+        .sum()
+}
+
 fn stop_reason(source: &Value) -> &'static str {
     if source
         .get("output")
@@ -1807,6 +1843,34 @@ mod tests {
         assert_eq!(mapped["stop_reason"], json!("tool_use"));
     }
 
+    #[test]
+    fn completed_anthropic_message_estimates_missing_output_usage() {
+        let value = json!({
+            "id": "resp_2",
+            "model": "gpt-5.3-codex",
+            "output": [
+                {
+                    "type": "message",
+                    "content": [{"type": "output_text", "text": "The answer is 42."}]
+                },
+                {
+                    "type": "function_call",
+                    "call_id": "call_1",
+                    "name": "lookup",
+                    "arguments": "{\"q\":\"answer\"}"
+                }
+            ]
+        });
+
+        let mapped = map_response_to_anthropic_message(&value, None);
+        assert_eq!(mapped["usage"]["input_tokens"], json!(0));
+        assert_eq!(mapped["usage"]["output_tokens"], json!(12));
+
+        let reported = json!({"output": value["output"], "usage": {"output_tokens": 7}});
+        let mapped = map_response_to_anthropic_message(&reported, None);
+        assert_eq!(mapped["usage"]["output_tokens"], json!(7));
+    }
+
     #[test]
     fn streamed_web_search_call_maps_to_server_tool_use_block() {
         let mut metadata = AnthropicStreamMetadata::default();
@@ -123,6 +123,7 @@ mod tests {
             tool_name_restore_map: Default::default(),
             billable_multiplier: 1,
             last_message_content: None,
+            applied_transforms: Vec::new(),
         }
     }
 
@@ -366,6 +367,43 @@ mod tests {
         assert_eq!(upstream["stream"], json!(true));
     }
 
+    #[test]
+    fn prepare_gateway_request_applies_key_request_transform_before_adaptation() {
+        let policy =
+            serde_json::from_value::<llm_access_core::store::RequestTransformPolicy>(json!({
+                "system_prompt_prepend": "Answer in English.",
+                "operations": [{ "op": "set", "path": "reasoning.effort", "value": "low" }],
+                "redactions": [{ "pattern": "\\d{3}-\\d{4}" }],
+            }))
+            .expect("transform policy");
+        let transform = llm_access_core::transform::CompiledRequestTransform::compile(&policy)
+            .expect("compiled transform");
+
+        let prepared = super::prepare_gateway_request_from_bytes(
+            "/v1/responses",
+            "",
+            Method::POST,
+            &axum::http::HeaderMap::new(),
+            Bytes::from_static(br#"{"model":"gpt-5.3-codex","input":"call 555-1234"}"#),
+            1024 * 1024,
+            Some(&transform),
+        )
+        .expect("transformed responses request");
+
+        let upstream: serde_json::Value =
+            serde_json::from_slice(&prepared.request_body).expect("upstream body json");
+        assert_eq!(prepared.applied_transforms, vec![
+            "set:reasoning.effort",
+            "redact:0=1",
+            "system_prompt_prepend"
+        ]);
+        assert_eq!(prepared.last_message_content.as_deref(), Some("call [REDACTED]"));
+        assert_eq!(upstream["reasoning"]["effort"], json!("low"));
+        assert_eq!(upstream["input"][0]["role"], json!("developer"));
+        assert_eq!(upstream["input"][0]["content"][0]["text"], json!("Answer in English."));
+        assert_eq!(upstream["input"][1]["content"][0]["text"], json!("call [REDACTED]"));
+    }
+
     #[tokio::test]
     async fn prepare_gateway_request_reuses_explicit_session_id_without_prompt_cache_injection() {
         let mut headers = axum::http::HeaderMap::new();
@@ -13,6 +13,7 @@ use axum::{
     http::{header, Method},
 };
 use http::HeaderMap;
+use llm_access_core::transform::{CompiledRequestTransform, RequestTransformDialect};
 use serde_json::Value;
 
 use super::{
@@ -63,6 +64,7 @@ pub async fn prepare_gateway_request(
         headers,
         body,
         max_request_body_bytes,
+        None,
     )
 }
 /// Read an Axum request body with the configured gateway byte limit.
@@ -76,6 +78,9 @@ pub async fn read_gateway_request_body(
         .map_err(|err| internal_error("Failed to read llm gateway request body", err))
 }
 /// Normalize an already-buffered OpenAI-compatible request body.
+///
+/// `request_transform` is the key's operator policy; it runs on the
+/// client-shaped body before any protocol adaptation.
 pub fn prepare_gateway_request_from_bytes(
     gateway_path: &str,
     query: &str,
@@ -83,6 +88,7 @@ pub fn prepare_gateway_request_from_bytes(
     headers: &HeaderMap,
     body: Bytes,
     max_request_body_bytes: usize,
+    request_transform: Option<&CompiledRequestTransform>,
 ) -> CodexGatewayResult<PreparedGatewayRequest> {
     let allows_get = is_models_path(gateway_path);
     let allows_post = is_supported_codex_post_path(gateway_path);
@@ -111,6 +117,13 @@ pub fn prepare_gateway_request_from_bytes(
     } else {
         None
     };
+    let applied_transforms =
+        match (request_transform, request_transform_dialect(gateway_path), json_value.as_mut()) {
+            (Some(transform), Some(dialect), Some(value)) if method == Method::POST => {
+                transform.apply(dialect, value)
+            },
+            _ => Vec::new(),
+        };
     let model = json_value
         .as_ref()
         .and_then(|value| value.get("model"))
@@ -240,9 +253,19 @@ pub fn prepare_gateway_request_from_bytes(
         tool_name_restore_map,
         billable_multiplier,
         last_message_content,
+        applied_transforms,
     })
 }
 
+fn request_transform_dialect(gateway_path: &str) -> Option<RequestTransformDialect> {
+    match gateway_path {
+        "/v1/chat/completions" => Some(RequestTransformDialect::OpenAiChat),
+        "/v1/messages" => Some(RequestTransformDialect::AnthropicMessages),
+        path if is_responses_prompt_path(path) => Some(RequestTransformDialect::Responses),
+        _ => None,
+    }
+}
+
 fn is_responses_prompt_path(gateway_path: &str) -> bool {
     matches!(gateway_path, "/v1/responses" | "/v1/responses/compact")
 }
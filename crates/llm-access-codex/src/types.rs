@@ -138,6 +138,8 @@ pub struct PreparedGatewayRequest {
     pub billable_multiplier: u64,
     /// Last text-like user content extracted from the original client body.
     pub last_message_content: Option<String>,
+    /// Labels of the operator request transforms that changed the body.
+    pub applied_transforms: Vec<String>,
 }
 
 impl PreparedGatewayRequest {
@@ -1,7 +1,9 @@
 //! Shared contracts for the standalone LLM access service.
 
+pub mod moderation;
 pub mod provider;
 pub mod proxy;
 pub mod routes;
 pub mod store;
+pub mod transform;
 pub mod usage;
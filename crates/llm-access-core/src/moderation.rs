@@ -0,0 +1,333 @@
+//! Local moderation rule engine for a [`KeyModerationPolicy`].
+//!
+//! The engine only inspects; the provider entry decides what to do with a
+//! hit (reject, queue, log) and calls the optional classifier itself.
+
+use regex::Regex;
+use serde_json::Value;
+
+use crate::{
+    store::{KeyModerationPolicy, ModerationAction, MAX_MODERATION_EXCERPT_CHARS},
+    transform::{PROMPT_ROOT_FIELDS, PROMPT_TEXT_FIELDS},
+};
+
+/// Most prompt or response text inspected per request, in bytes.
+pub const MAX_MODERATION_SCAN_BYTES: usize = 512 * 1024;
+
+/// Content part `type` values that count as attachments.
+const ATTACHMENT_PART_TYPES: &[&str] =
+    &["image", "image_url", "input_image", "document", "file", "input_file", "input_audio"];
+/// Member names whose string values carry response text.
+const RESPONSE_TEXT_FIELDS: &[&str] =
+    &["text", "content", "delta", "output_text", "refusal", "thinking"];
+
+/// Rule matches found in one request or response.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModerationFindings {
+    /// Labels such as `keyword:<word>`, `pattern:<index>`,
+    /// `attachments:<count>` or `tool:<name>`.
+    pub matched_rules: Vec<String>,
+    /// Truncated text around the first text match, or the head of the text.
+    pub excerpt: String,
+}
+
+impl ModerationFindings {
+    /// Whether any rule matched.
+    pub fn is_hit(&self) -> bool {
+        !self.matched_rules.is_empty()
+    }
+}
+
+/// A policy with its keywords lowered and patterns compiled.
+pub struct CompiledModerationPolicy {
+    /// Action taken on a hit.
+    pub action: ModerationAction,
+    /// Whether the local classifier should also be asked.
+    pub classifier_enabled: bool,
+    /// Whether response text is scanned too.
+    pub moderate_responses: bool,
+    keywords: Vec<(String, String)>,
+    patterns: Vec<Regex>,
+    max_attachments: Option<u32>,
+    blocked_tool_names: Vec<String>,
+}
+
+impl CompiledModerationPolicy {
+    /// Compile `policy`.
+    pub fn compile(policy: &KeyModerationPolicy) -> anyhow::Result<Self> {
+        let patterns = policy
+            .patterns
+            .iter()
+            .map(|pattern| {
+                Regex::new(pattern)
+                    .map_err(|err| anyhow::anyhow!("invalid moderation pattern `{pattern}`: {err}"))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(Self {
+            action: policy.action,
+            classifier_enabled: policy.classifier_enabled,
+            moderate_responses: policy.moderate_responses,
+            keywords: policy
+                .keywords
+                .iter()
+                .map(|keyword| (keyword.trim().to_string(), keyword.trim().to_lowercase()))
+                .collect(),
+            patterns,
+            max_attachments: policy.max_attachments,
+            blocked_tool_names: policy
+                .blocked_tool_names
+                .iter()
+                .map(|name| name.trim().to_ascii_lowercase())
+                .collect(),
+        })
+    }
+
+    /// Inspect a client request body of any supported dialect.
+    pub fn inspect_request(&self, body: &Value) -> ModerationFindings {
+        let text = request_prompt_text(body);
+        let mut findings = self.inspect_text(&text);
+        if let Some(max) = self.max_attachments {
+            let count = count_attachments(body);
+            if count > max as usize {
+                findings.matched_rules.push(format!("attachments:{count}"));
+            }
+        }
+        for name in declared_tool_names(body) {
+            if self.blocked_tool_names.contains(&name.to_ascii_lowercase()) {
+                findings.matched_rules.push(format!("tool:{name}"));
+            }
+        }
+        findings
+    }
+
+    /// Inspect free text with the keyword and pattern rules.
+    pub fn inspect_text(&self, text: &str) -> ModerationFindings {
+        let lowered = text.to_lowercase();
+        let mut matched_rules = Vec::new();
+        let mut first_match = None::<usize>;
+        for (keyword, needle) in &self.keywords {
+            if let Some(position) = lowered.find(needle.as_str()) {
+                matched_rules.push(format!("keyword:{keyword}"));
+                // Lowercasing can shift byte offsets for some scripts, so the
+                // position is only a hint for the excerpt window.
+                first_match = Some(first_match.map_or(position, |current| current.min(position)));
+            }
+        }
+        for (index, pattern) in self.patterns.iter().enumerate() {
+            if let Some(found) = pattern.find(text) {
+                matched_rules.push(format!("pattern:{index}"));
+                first_match =
+                    Some(first_match.map_or(found.start(), |current| current.min(found.start())));
+            }
+        }
+        ModerationFindings {
+            matched_rules,
+            excerpt: moderation_excerpt(text, first_match.unwrap_or(0)),
+        }
+    }
+}
+
+/// Prompt text of a request body, joined with newlines and capped at
+/// [`MAX_MODERATION_SCAN_BYTES`].
+pub fn request_prompt_text(body: &Value) -> String {
+    let mut text = String::new();
+    if let Value::Object(object) = body {
+        for field in PROMPT_ROOT_FIELDS.iter().chain(&["prompt"]) {
+            if let Some(value) = object.get(*field) {
+                collect_text(value, PROMPT_TEXT_FIELDS, &mut text);
+            }
+        }
+    }
+    text
+}
+
+/// Text of a buffered JSON or SSE response body, concatenated and capped at
+/// [`MAX_MODERATION_SCAN_BYTES`].
+pub fn response_text(body: &[u8]) -> String {
+    let mut text = String::new();
+    if let Ok(value) = serde_json::from_slice::<Value>(body) {
+        collect_response_text(&value, &mut text);
+        return text;
+    }
+    for line in String::from_utf8_lossy(body).lines() {
+        let Some(data) = line.strip_prefix("data:") else {
+            continue;
+        };
+        if let Ok(value) = serde_json::from_str::<Value>(data.trim()) {
+            collect_response_text(&value, &mut text);
+        }
+    }
+    text
+}
+
+/// Cut `text` to an excerpt starting shortly before byte `position`.
+pub fn moderation_excerpt(text: &str, position: usize) -> String {
+    let lead = MAX_MODERATION_EXCERPT_CHARS / 4;
+    let mut start = position.min(text.len()).saturating_sub(lead);
+    while !text.is_char_boundary(start) {
+        start -= 1;
+    }
+    text[start..]
+        .chars()
+        .take(MAX_MODERATION_EXCERPT_CHARS)
+        .collect()
+}
+
+/// Append `piece`, starting a new line first when `new_line` is set.
+fn push_text(text: &mut String, piece: &str, new_line: bool) {
+    if piece.is_empty() || text.len() >= MAX_MODERATION_SCAN_BYTES {
+        return;
+    }
+    if new_line && !text.is_empty() && !text.ends_with('\n') {
+        text.push('\n');
+    }
+    let mut end = piece.len().min(MAX_MODERATION_SCAN_BYTES - text.len());
+    while !piece.is_char_boundary(end) {
+        end -= 1;
+    }
+    text.push_str(&piece[..end]);
+}
+
+/// Collect strings that sit directly in `value`, in an array under it, or
+/// under one of `fields`.
+fn collect_text(value: &Value, fields: &[&str], text: &mut String) {
+    match value {
+        Value::String(piece) => push_text(text, piece, true),
+        Value::Array(items) => items
+            .iter()
+            .for_each(|item| collect_text(item, fields, text)),
+        Value::Object(object) => object
+            .iter()
+            .filter(|(key, _)| fields.contains(&key.as_str()))
+            .for_each(|(_, item)| collect_text(item, fields, text)),
+        _ => {},
+    }
+}
+
+fn collect_response_text(value: &Value, text: &mut String) {
+    match value {
+        Value::Array(items) => items
+            .iter()
+            .for_each(|item| collect_response_text(item, text)),
+        Value::Object(object) => {
+            for (key, item) in object {
+                match item {
+                    Value::String(piece) if RESPONSE_TEXT_FIELDS.contains(&key.as_str()) => {
+                        // Stream deltas are split mid-word, so they join
+                        // without a separator.
+                        push_text(text, piece, false)
+                    },
+                    Value::Array(_) | Value::Object(_) => collect_response_text(item, text),
+                    _ => {},
+                }
+            }
+        },
+        _ => {},
+    }
+}
+
+fn count_attachments(value: &Value) -> usize {
+    match value {
+        Value::Array(items) => items.iter().map(count_attachments).sum(),
+        Value::Object(object) => {
+            let own = object
+                .get("type")
+                .and_then(Value::as_str)
+                .is_some_and(|kind| ATTACHMENT_PART_TYPES.contains(&kind));
+            usize::from(own) + object.values().map(count_attachments).sum::<usize>()
+        },
+        _ => 0,
+    }
+}
+
+/// Names of the tools a request declares, across OpenAI and Anthropic
+/// tool shapes.
+fn declared_tool_names(body: &Value) -> Vec<&str> {
+    body.get("tools")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|tool| {
+            tool.get("name")
+                .or_else(|| {
+                    tool.get("function")
+                        .and_then(|function| function.get("name"))
+                })
+                .and_then(Value::as_str)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn compile(policy: serde_json::Value) -> CompiledModerationPolicy {
+        let policy = serde_json::from_value::<KeyModerationPolicy>(policy).expect("policy");
+        CompiledModerationPolicy::compile(&policy).expect("compiled policy")
+    }
+
+    #[test]
+    fn request_rules_cover_text_attachments_and_tools() {
+        let policy = compile(json!({
+            "action": "block",
+            "keywords": ["Forbidden Topic"],
+            "patterns": ["\\bssn:\\s*\\d{3}"],
+            "max_attachments": 1,
+            "blocked_tool_names": ["shell"],
+        }));
+        let body = json!({
+            "model": "gpt-5",
+            "messages": [
+                { "role": "system", "content": "be helpful" },
+                { "role": "user", "content": [
+                    { "type": "text", "text": "tell me about the forbidden topic" },
+                    { "type": "image_url", "image_url": { "url": "https://example.com/a.png" } },
+                    { "type": "image_url", "image_url": { "url": "https://example.com/b.png" } },
+                ] },
+            ],
+            "tools": [{ "type": "function", "function": { "name": "Shell" } }],
+        });
+
+        let findings = policy.inspect_request(&body);
+        assert_eq!(findings.matched_rules, vec![
+            "keyword:Forbidden Topic",
+            "attachments:2",
+            "tool:Shell"
+        ]);
+        assert!(findings.excerpt.contains("forbidden topic"));
+
+        let anthropic = json!({
+            "system": "x",
+            "messages": [{ "role": "user", "content": "my ssn: 123-45" }],
+            "tools": [{ "name": "web_search" }],
+        });
+        assert_eq!(policy.inspect_request(&anthropic).matched_rules, vec!["pattern:0"]);
+        assert!(!policy
+            .inspect_request(&json!({ "input": "hello there" }))
+            .is_hit());
+    }
+
+    #[test]
+    fn response_text_reads_json_and_sse_bodies() {
+        let json_body =
+            br#"{"choices":[{"message":{"role":"assistant","content":"plain answer"}}]}"#;
+        assert_eq!(response_text(json_body), "plain answer");
+
+        let sse = b"event: response.output_text.delta\n\
+data: {\"type\":\"response.output_text.delta\",\"delta\":\"Hel\"}\n\n\
+data: {\"type\":\"content_block_delta\",\"delta\":{\"type\":\"text_delta\",\"text\":\"lo\"}}\n\n\
+data: [DONE]\n\n";
+        assert_eq!(response_text(sse), "Hello");
+    }
+
+    #[test]
+    fn excerpt_stays_on_char_boundaries() {
+        let text = format!("{}needle", "é".repeat(1_000));
+        let excerpt = moderation_excerpt(&text, text.find("needle").expect("needle"));
+        assert!(excerpt.ends_with("needle"));
+        assert!(excerpt.chars().count() <= MAX_MODERATION_EXCERPT_CHARS);
+    }
+}
@@ -14,6 +14,8 @@ pub enum AccountProxyMode {
     Direct,
     /// Pin this account to one reusable shared proxy config.
     Fixed,
+    /// Pick a healthy member of a weighted proxy pool, sticky per account.
+    Pool,
 }
 
 impl AccountProxyMode {
@@ -23,6 +25,7 @@ impl AccountProxyMode {
             Self::Inherit => "inherit",
             Self::Direct => "direct",
             Self::Fixed => "fixed",
+            Self::Pool => "pool",
         }
     }
 }
@@ -37,43 +40,119 @@ pub struct AccountProxySelection {
     /// [`AccountProxyMode::Fixed`].
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub proxy_config_id: Option<String>,
+    /// Proxy pool id used when `proxy_mode` is [`AccountProxyMode::Pool`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy_pool_id: Option<String>,
 }
 
 impl AccountProxySelection {
-    /// Normalize fixed-mode ids and clear ids for non-fixed modes.
+    /// Normalize referenced ids and clear the ones the mode does not use.
     pub fn canonicalize(mut self) -> Self {
-        self.proxy_config_id = self
-            .proxy_config_id
-            .as_deref()
-            .map(str::trim)
-            .filter(|value| !value.is_empty())
-            .map(str::to_string);
+        self.proxy_config_id = normalize_reference(self.proxy_config_id.take());
+        self.proxy_pool_id = normalize_reference(self.proxy_pool_id.take());
         if self.proxy_mode != AccountProxyMode::Fixed {
             self.proxy_config_id = None;
         }
+        if self.proxy_mode != AccountProxyMode::Pool {
+            self.proxy_pool_id = None;
+        }
         self
     }
 
     /// Whether the selection inherits provider-level proxy behavior.
     pub fn is_default(&self) -> bool {
-        self.proxy_mode == AccountProxyMode::Inherit && self.proxy_config_id.is_none()
+        self.proxy_mode == AccountProxyMode::Inherit
+            && self.proxy_config_id.is_none()
+            && self.proxy_pool_id.is_none()
     }
 }
 
+fn normalize_reference(value: Option<String>) -> Option<String> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+/// One weighted member of a proxy pool.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProxyPoolMember {
+    /// Member proxy config id.
+    pub proxy_config_id: String,
+    /// Relative selection weight; zero parks the member without removing it.
+    pub weight: u32,
+}
+
+/// Pick the pool member that `sticky_key` (usually the account name) maps
+/// to, among members that `eligible` accepts.
+///
+/// Uses weighted rendezvous hashing: every key ranks every member by a
+/// stable hash, so an account keeps its proxy while that proxy stays
+/// healthy, and losing or adding a member only moves the accounts that
+/// ranked it first. Zero-weight members are never picked.
+pub fn select_proxy_pool_member<'a>(
+    members: &'a [ProxyPoolMember],
+    sticky_key: &str,
+    mut eligible: impl FnMut(&ProxyPoolMember) -> bool,
+) -> Option<&'a ProxyPoolMember> {
+    members
+        .iter()
+        .filter(|member| member.weight > 0 && eligible(member))
+        .map(|member| (rendezvous_score(sticky_key, member), member))
+        .max_by(|(left, _), (right, _)| left.total_cmp(right))
+        .map(|(_, member)| member)
+}
+
+fn rendezvous_score(sticky_key: &str, member: &ProxyPoolMember) -> f64 {
+    let hash = stable_hash(sticky_key, &member.proxy_config_id);
+    // Map the top 53 bits into (0, 1) so `ln` stays finite.
+    let unit = ((hash >> 11) as f64 + 0.5) / (1u64 << 53) as f64;
+    -f64::from(member.weight) / unit.ln()
+}
+
+/// FNV-1a over `key \0 member`, stable across processes and releases so all
+/// nodes agree on the sticky member.
+fn stable_hash(key: &str, member: &str) -> u64 {
+    let mut hash = 0xcbf2_9ce4_8422_2325_u64;
+    for byte in key.bytes().chain(std::iter::once(0)).chain(member.bytes()) {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    // FNV alone mixes the trailing bytes poorly; finish with a splitmix64
+    // avalanche so similar ids still spread evenly.
+    hash ^= hash >> 30;
+    hash = hash.wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    hash ^= hash >> 27;
+    hash = hash.wrapping_mul(0x94d0_49bb_1331_11eb);
+    hash ^ (hash >> 31)
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{AccountProxyMode, AccountProxySelection};
+    use super::{
+        select_proxy_pool_member, AccountProxyMode, AccountProxySelection, ProxyPoolMember,
+    };
+
+    fn member(id: &str, weight: u32) -> ProxyPoolMember {
+        ProxyPoolMember {
+            proxy_config_id: id.to_string(),
+            weight,
+        }
+    }
 
     #[test]
     fn clears_proxy_config_id_unless_mode_is_fixed() {
         let selection = AccountProxySelection {
             proxy_mode: AccountProxyMode::Direct,
             proxy_config_id: Some("proxy-1".to_string()),
+            proxy_pool_id: Some("pool-1".to_string()),
         }
         .canonicalize();
 
         assert_eq!(selection.proxy_mode, AccountProxyMode::Direct);
         assert_eq!(selection.proxy_config_id, None);
+        assert_eq!(selection.proxy_pool_id, None);
     }
 
     #[test]
@@ -81,9 +160,67 @@ mod tests {
         let selection = AccountProxySelection {
             proxy_mode: AccountProxyMode::Fixed,
             proxy_config_id: Some(" proxy-1 ".to_string()),
+            proxy_pool_id: None,
         }
         .canonicalize();
 
         assert_eq!(selection.proxy_config_id.as_deref(), Some("proxy-1"));
     }
+
+    #[test]
+    fn pool_mode_keeps_only_the_pool_id() {
+        let selection = AccountProxySelection {
+            proxy_mode: AccountProxyMode::Pool,
+            proxy_config_id: Some("proxy-1".to_string()),
+            proxy_pool_id: Some(" pool-1 ".to_string()),
+        }
+        .canonicalize();
+
+        assert_eq!(selection.proxy_config_id, None);
+        assert_eq!(selection.proxy_pool_id.as_deref(), Some("pool-1"));
+    }
+
+    #[test]
+    fn pool_selection_is_sticky_and_only_moves_accounts_off_ineligible_members() {
+        let members = [member("a", 1), member("b", 1), member("c", 1)];
+        let accounts = (0..200).map(|i| format!("account-{i}")).collect::<Vec<_>>();
+        let before = accounts
+            .iter()
+            .map(|account| {
+                select_proxy_pool_member(&members, account, |_| true)
+                    .expect("pool has eligible members")
+                    .proxy_config_id
+                    .clone()
+            })
+            .collect::<Vec<_>>();
+        for proxy in ["a", "b", "c"] {
+            let share = before.iter().filter(|chosen| *chosen == proxy).count();
+            assert!((40..=100).contains(&share), "{proxy} got {share} of 200");
+        }
+
+        for (account, previous) in accounts.iter().zip(&before) {
+            let chosen =
+                select_proxy_pool_member(&members, account, |member| member.proxy_config_id != "b")
+                    .expect("pool has eligible members");
+            assert_ne!(chosen.proxy_config_id, "b");
+            if previous != "b" {
+                assert_eq!(&chosen.proxy_config_id, previous);
+            }
+        }
+    }
+
+    #[test]
+    fn pool_selection_respects_weights_and_skips_parked_members() {
+        let members = [member("heavy", 9), member("light", 1), member("parked", 0)];
+        let heavy = (0..1000)
+            .filter(|i| {
+                select_proxy_pool_member(&members, &format!("account-{i}"), |_| true)
+                    .expect("pool has eligible members")
+                    .proxy_config_id
+                    == "heavy"
+            })
+            .count();
+        assert!((850..=950).contains(&heavy), "heavy got {heavy} of 1000");
+        assert!(select_proxy_pool_member(&members[2..], "account", |_| true).is_none());
+    }
 }
@@ -96,6 +96,22 @@ pub const PUBLIC_PROVIDER_ROUTES: &[RouteSpec] = &[
         method: "POST",
         path: "/v1/messages/count_tokens",
     },
+    RouteSpec {
+        method: "ANY",
+        path: "/v1/batches",
+    },
+    RouteSpec {
+        method: "ANY",
+        path: "/v1/batches/*path",
+    },
+    RouteSpec {
+        method: "ANY",
+        path: "/v1/messages/batches",
+    },
+    RouteSpec {
+        method: "ANY",
+        path: "/v1/messages/batches/*path",
+    },
     RouteSpec {
         method: "POST",
         path: "/api/kiro-gateway/v1/messages",
@@ -315,6 +331,8 @@ pub fn provider_route_requirement(path: &str) -> Option<ProviderRouteRequirement
         None
     } else if path == "/v1/messages"
         || path == "/v1/messages/count_tokens"
+        || path == "/v1/messages/batches"
+        || path.starts_with("/v1/messages/batches/")
         || path.starts_with("/cc/v1/")
         || path.starts_with("/api/kiro-gateway/v1/")
         || path.starts_with("/api/kiro-gateway/cc/v1/")
@@ -404,6 +422,12 @@ mod tests {
         assert_eq!(provider_route_requirement("/v1/models"), None);
         assert_eq!(provider_route_requirement("/v1/messages"), Some(kiro));
         assert_eq!(provider_route_requirement("/v1/messages/count_tokens"), Some(kiro));
+        assert_eq!(provider_route_requirement("/v1/batches"), Some(codex));
+        assert_eq!(provider_route_requirement("/v1/messages/batches"), Some(kiro));
+        assert_eq!(
+            provider_route_requirement("/v1/messages/batches/msgbatch_1/results"),
+            Some(kiro)
+        );
         assert_eq!(provider_route_requirement("/api/llm-gateway/v1/responses"), Some(codex));
         assert_eq!(
             provider_route_requirement("/api/llm-gateway/v1/responses/compact"),
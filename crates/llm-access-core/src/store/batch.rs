@@ -0,0 +1,216 @@
+//! Asynchronous bulk request batches.
+//!
+//! ```text
+//! client JSONL ──> llm_batches + llm_batch_items (pending)
+//!                         |
+//!        batch worker claims pending items (off-peak window)
+//!                         |
+//!        normal provider dispatch per item ──> UsageEvent per item
+//!                         |
+//!        item succeeded / failed ──> batch completed | cancelled | expired
+//! ```
+//!
+//! Items are claimed with a lease so several nodes can run the worker; a
+//! `running` item whose lease expired is claimed again.
+
+use serde::{Deserialize, Serialize};
+
+use super::routes::AuthenticatedKey;
+
+/// Most items one batch may carry.
+pub const MAX_BATCH_ITEMS: usize = 5_000;
+/// Largest accepted batch input, in bytes.
+pub const MAX_BATCH_INPUT_BYTES: usize = 32 * 1024 * 1024;
+/// Longest stored response body per item, in bytes.
+pub const MAX_BATCH_RESULT_BYTES: usize = 1024 * 1024;
+/// Time a batch may take before its unfinished items expire.
+pub const BATCH_COMPLETION_WINDOW_MS: i64 = 24 * 60 * 60 * 1000;
+
+/// Batch still has pending or running items.
+pub const BATCH_STATUS_IN_PROGRESS: &str = "in_progress";
+/// Cancel requested; running items are finishing.
+pub const BATCH_STATUS_CANCELLING: &str = "cancelling";
+/// Cancel finished.
+pub const BATCH_STATUS_CANCELLED: &str = "cancelled";
+/// Every item finished.
+pub const BATCH_STATUS_COMPLETED: &str = "completed";
+/// The completion window passed before every item ran.
+pub const BATCH_STATUS_EXPIRED: &str = "expired";
+
+/// Item waiting for the worker.
+pub const BATCH_ITEM_STATUS_PENDING: &str = "pending";
+/// Item claimed by a worker.
+pub const BATCH_ITEM_STATUS_RUNNING: &str = "running";
+/// Item answered with a 2xx response.
+pub const BATCH_ITEM_STATUS_SUCCEEDED: &str = "succeeded";
+/// Item answered with an error.
+pub const BATCH_ITEM_STATUS_FAILED: &str = "failed";
+/// Item skipped because the batch was cancelled.
+pub const BATCH_ITEM_STATUS_CANCELLED: &str = "cancelled";
+/// Item skipped because the batch expired.
+pub const BATCH_ITEM_STATUS_EXPIRED: &str = "expired";
+
+/// One batch to persist with its items.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewBatchJob {
+    /// Stable batch id.
+    pub batch_id: String,
+    /// Key that owns the batch.
+    pub key_id: String,
+    /// Client-facing protocol family (`openai` or `anthropic`).
+    pub protocol_family: String,
+    /// Provider endpoint every item targets.
+    pub endpoint: String,
+    /// Optional client metadata object, serialized.
+    pub metadata_json: Option<String>,
+    /// Items in input order.
+    pub items: Vec<NewBatchItem>,
+    /// Creation timestamp in Unix milliseconds.
+    pub created_at_ms: i64,
+    /// Expiry timestamp in Unix milliseconds.
+    pub expires_at_ms: i64,
+}
+
+/// One request line of a batch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewBatchItem {
+    /// Client-chosen id echoed in results.
+    pub custom_id: String,
+    /// Serialized JSON request body.
+    pub body_json: String,
+}
+
+/// Client-facing projection of one batch.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BatchJob {
+    /// Stable batch id.
+    pub batch_id: String,
+    /// Key that owns the batch.
+    pub key_id: String,
+    /// Client-facing protocol family.
+    pub protocol_family: String,
+    /// Provider endpoint every item targets.
+    pub endpoint: String,
+    /// One of the `BATCH_STATUS_*` values.
+    pub status: String,
+    /// Optional client metadata object, serialized.
+    pub metadata_json: Option<String>,
+    /// Item count.
+    pub total_count: i64,
+    /// Items still pending or running.
+    pub processing_count: i64,
+    /// Items answered with a 2xx response.
+    pub succeeded_count: i64,
+    /// Items answered with an error.
+    pub failed_count: i64,
+    /// Items skipped by a cancel.
+    pub cancelled_count: i64,
+    /// Items skipped by expiry.
+    pub expired_count: i64,
+    /// Creation timestamp in Unix milliseconds.
+    pub created_at_ms: i64,
+    /// First item claim in Unix milliseconds.
+    pub started_at_ms: Option<i64>,
+    /// Terminal status timestamp in Unix milliseconds.
+    pub finished_at_ms: Option<i64>,
+    /// Cancel request timestamp in Unix milliseconds.
+    pub cancel_requested_at_ms: Option<i64>,
+    /// Expiry timestamp in Unix milliseconds.
+    pub expires_at_ms: i64,
+}
+
+impl BatchJob {
+    /// Whether the batch reached a terminal status.
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self.status.as_str(),
+            BATCH_STATUS_CANCELLED | BATCH_STATUS_COMPLETED | BATCH_STATUS_EXPIRED
+        )
+    }
+}
+
+/// Stored outcome of one batch item.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BatchItemResult {
+    /// Position in the input.
+    pub item_index: i64,
+    /// Client-chosen id.
+    pub custom_id: String,
+    /// One of the `BATCH_ITEM_STATUS_*` values.
+    pub status: String,
+    /// Upstream-facing HTTP status when the item ran.
+    pub status_code: Option<i32>,
+    /// Response body, truncated to [`MAX_BATCH_RESULT_BYTES`].
+    pub response_body: Option<String>,
+    /// Error summary for failed items.
+    pub error_message: Option<String>,
+    /// Completion timestamp in Unix milliseconds.
+    pub finished_at_ms: Option<i64>,
+}
+
+/// One item handed to a worker, with the owning key as it is now.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClaimedBatchItem {
+    /// Batch id.
+    pub batch_id: String,
+    /// Position in the input.
+    pub item_index: i64,
+    /// Client-chosen id.
+    pub custom_id: String,
+    /// Provider endpoint to dispatch to.
+    pub endpoint: String,
+    /// Serialized JSON request body.
+    pub body_json: String,
+    /// Claims so far, including this one.
+    pub attempts: i32,
+    /// Owning key with its current status and usage.
+    pub key: AuthenticatedKey,
+}
+
+/// Final result a worker records for one claimed item.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchItemOutcome {
+    /// [`BATCH_ITEM_STATUS_SUCCEEDED`] or [`BATCH_ITEM_STATUS_FAILED`].
+    pub status: String,
+    /// HTTP status of the dispatched request.
+    pub status_code: Option<i32>,
+    /// Response body, already truncated.
+    pub response_body: Option<String>,
+    /// Error summary for failed items.
+    pub error_message: Option<String>,
+    /// Completion timestamp in Unix milliseconds.
+    pub finished_at_ms: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn terminal_statuses_are_finished() {
+        let mut job = BatchJob {
+            batch_id: "batch-1".to_string(),
+            key_id: "key-1".to_string(),
+            protocol_family: "openai".to_string(),
+            endpoint: "/v1/responses".to_string(),
+            status: BATCH_STATUS_IN_PROGRESS.to_string(),
+            metadata_json: None,
+            total_count: 1,
+            processing_count: 1,
+            succeeded_count: 0,
+            failed_count: 0,
+            cancelled_count: 0,
+            expired_count: 0,
+            created_at_ms: 1,
+            started_at_ms: None,
+            finished_at_ms: None,
+            cancel_requested_at_ms: None,
+            expires_at_ms: 1 + BATCH_COMPLETION_WINDOW_MS,
+        };
+        assert!(!job.is_finished());
+        job.status = BATCH_STATUS_CANCELLING.to_string();
+        assert!(!job.is_finished());
+        job.status = BATCH_STATUS_EXPIRED.to_string();
+        assert!(job.is_finished());
+    }
+}
@@ -46,6 +46,9 @@ pub struct AdminCodexAccount {
     pub proxy_mode: String,
     /// Fixed proxy config id when proxy mode is fixed.
     pub proxy_config_id: Option<String>,
+    /// Proxy pool id when proxy mode is pool.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy_pool_id: Option<String>,
     /// Effective proxy source.
     pub effective_proxy_source: String,
     /// Effective proxy URL.
@@ -273,6 +276,8 @@ pub struct AdminCodexAccountPatch {
     pub proxy_mode: Option<String>,
     /// New proxy config id.
     pub proxy_config_id: Option<Option<String>>,
+    /// New proxy pool id.
+    pub proxy_pool_id: Option<Option<String>>,
     /// New per-account request concurrency cap.
     pub request_max_concurrency: Option<Option<u64>>,
     /// New per-account request pacing interval.
@@ -441,6 +446,7 @@ mod tests {
                 super::default_codex_image_generation_max_concurrency(),
             proxy_mode: "inherit".to_string(),
             proxy_config_id: None,
+            proxy_pool_id: None,
             effective_proxy_source: "binding".to_string(),
             effective_proxy_url: None,
             effective_proxy_config_name: None,
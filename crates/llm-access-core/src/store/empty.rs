@@ -10,6 +10,7 @@ use super::{
         AdminAnthropicUpstreamChannelsPage, NewAdminAnthropicUpstreamChannel,
         ProviderAnthropicUpstreamRoute,
     },
+    batch::{BatchItemOutcome, BatchItemResult, BatchJob, ClaimedBatchItem, NewBatchJob},
     codex_account::{
         AdminAccountsSummary, AdminCodexAccount, AdminCodexAccountPatch, AdminCodexAccountsPage,
         AdminCodexImportJobDetail, AdminCodexImportJobItem, AdminCodexImportJobItemResult,
@@ -23,6 +24,10 @@ use super::{
     },
     default_kiro_pool_strategy,
     groups::{AdminAccountGroup, AdminAccountGroupPatch, NewAdminAccountGroup},
+    key_portal::{
+        ConfirmedKeyNotifyEmail, KeyPortalSettings, KeyPortalSettingsPatch, KeyQuotaAlertCandidate,
+        KeySecretRotation, NewKeyNotifyEmailConfirmation,
+    },
     keys::{
         AdminKey, AdminKeyPatch, AdminKeysPage, AdminKeysSummary, AdminPageRequest, NewAdminKey,
     },
@@ -31,6 +36,10 @@ use super::{
         AdminKiroBalanceView, AdminKiroCacheView, AdminKiroStatusCacheUpdate,
         KiroStatusRefreshTarget, NewAdminKiroAccount,
     },
+    moderation::{AdminModerationEvent, AdminModerationEventsPage},
+    openai_upstream::{
+        AdminOpenAiUpstreamChannel, AdminOpenAiUpstreamChannelPatch, NewAdminOpenAiUpstreamChannel,
+    },
     proxy::{
         default_proxy_binding, default_proxy_bindings, AdminProxyBinding, AdminProxyConfig,
         AdminProxyConfigPatch, AdminProxyTrafficSnapshot, NewAdminProxyConfig,
@@ -46,12 +55,13 @@ use super::{
         codex_auth_access_token_expires_at_ms, AuthenticatedKey, ProviderCodexAuthUpdate,
         ProviderCodexRoute, ProviderKiroAuthUpdate, ProviderKiroRoute,
     },
+    sub_keys::NewSubKey,
     traits::{
         AdminAccountGroupStore, AdminAnthropicUpstreamStore, AdminCodexAccountStore,
-        AdminConfigStore, AdminKeyStore, AdminKiroAccountStore, AdminProxyStore,
-        AdminReviewQueueStore, ProviderRouteStore, PublicAccessStore, PublicCommunityStore,
-        PublicStatusStore, PublicSubmissionStore, PublicUsageStore, UsageAnalyticsStore,
-        UsageEventSink, UsageRollupBatchSink,
+        AdminConfigStore, AdminKeyStore, AdminKiroAccountStore, AdminOpenAiUpstreamStore,
+        AdminProxyStore, AdminReviewQueueStore, BatchStore, KeyPortalStore, ProviderRouteStore,
+        PublicAccessStore, PublicCommunityStore, PublicStatusStore, PublicSubmissionStore,
+        PublicUsageStore, SubKeyStore, UsageAnalyticsStore, UsageEventSink, UsageRollupBatchSink,
     },
     usage::{
         AdminLegacyKiroProxyMigration, ProxyTrafficQuery, ProxyTrafficSnapshot, ProxyTrafficTotals,
@@ -72,6 +82,15 @@ pub struct EmptyProviderRouteStore;
 /// Empty direct Anthropic upstream store used by isolated unit tests.
 pub struct EmptyAdminAnthropicUpstreamStore;
 
+/// Empty OpenAI-compatible upstream store used by isolated unit tests.
+pub struct EmptyAdminOpenAiUpstreamStore;
+
+/// Empty sub-key store used by isolated unit tests.
+pub struct EmptySubKeyStore;
+
+/// Empty key portal store used by isolated unit tests.
+pub struct EmptyKeyPortalStore;
+
 #[async_trait]
 impl ProviderRouteStore for EmptyProviderRouteStore {
     async fn resolve_codex_route(
@@ -164,6 +183,111 @@ impl AdminAnthropicUpstreamStore for EmptyAdminAnthropicUpstreamStore {
     }
 }
 
+#[async_trait]
+impl AdminOpenAiUpstreamStore for EmptyAdminOpenAiUpstreamStore {
+    async fn list_admin_openai_upstream_channels(
+        &self,
+    ) -> anyhow::Result<Vec<AdminOpenAiUpstreamChannel>> {
+        Ok(Vec::new())
+    }
+
+    async fn create_admin_openai_upstream_channel(
+        &self,
+        _channel: NewAdminOpenAiUpstreamChannel,
+    ) -> anyhow::Result<AdminOpenAiUpstreamChannel> {
+        anyhow::bail!("openai upstream channel store is not configured")
+    }
+
+    async fn patch_admin_openai_upstream_channel(
+        &self,
+        _name: &str,
+        _patch: AdminOpenAiUpstreamChannelPatch,
+    ) -> anyhow::Result<Option<AdminOpenAiUpstreamChannel>> {
+        Ok(None)
+    }
+
+    async fn delete_admin_openai_upstream_channel(
+        &self,
+        _name: &str,
+    ) -> anyhow::Result<Option<AdminOpenAiUpstreamChannel>> {
+        Ok(None)
+    }
+}
+
+#[async_trait]
+impl SubKeyStore for EmptySubKeyStore {
+    async fn list_sub_keys(&self, _parent_key_id: &str) -> anyhow::Result<Vec<AdminKey>> {
+        Ok(Vec::new())
+    }
+
+    async fn create_sub_key(
+        &self,
+        _parent_key_id: &str,
+        _key: NewSubKey,
+    ) -> anyhow::Result<AdminKey> {
+        anyhow::bail!("sub-key store is not configured")
+    }
+
+    async fn revoke_sub_key(
+        &self,
+        _parent_key_id: &str,
+        _key_id: &str,
+    ) -> anyhow::Result<Option<AdminKey>> {
+        Ok(None)
+    }
+}
+
+#[async_trait]
+impl KeyPortalStore for EmptyKeyPortalStore {
+    async fn get_key_portal_settings(&self, _key_id: &str) -> anyhow::Result<KeyPortalSettings> {
+        Ok(KeyPortalSettings::default())
+    }
+
+    async fn patch_key_portal_settings(
+        &self,
+        _key_id: &str,
+        _patch: KeyPortalSettingsPatch,
+    ) -> anyhow::Result<KeyPortalSettings> {
+        anyhow::bail!("key portal store is not configured")
+    }
+
+    async fn rotate_key_secret(
+        &self,
+        _key_id: &str,
+        _rotation: KeySecretRotation,
+    ) -> anyhow::Result<Option<AdminKey>> {
+        Ok(None)
+    }
+
+    async fn request_key_notify_email_confirmation(
+        &self,
+        _confirmation: NewKeyNotifyEmailConfirmation,
+    ) -> anyhow::Result<bool> {
+        Ok(false)
+    }
+
+    async fn confirm_key_notify_email(
+        &self,
+        _token_hash: &str,
+        _confirmed_at_ms: i64,
+    ) -> anyhow::Result<Option<ConfirmedKeyNotifyEmail>> {
+        Ok(None)
+    }
+
+    async fn list_key_quota_alert_candidates(&self) -> anyhow::Result<Vec<KeyQuotaAlertCandidate>> {
+        Ok(Vec::new())
+    }
+
+    async fn set_key_quota_alert_level(
+        &self,
+        _key_id: &str,
+        _expected: Option<u8>,
+        _level: Option<u8>,
+    ) -> anyhow::Result<bool> {
+        Ok(false)
+    }
+}
+
 #[async_trait]
 impl PublicAccessStore for EmptyPublicAccessStore {
     async fn auth_cache_ttl_seconds(&self) -> anyhow::Result<u64> {
@@ -412,6 +536,7 @@ impl AdminKeyStore for EmptyAdminKeyStore {
             status: KEY_STATUS_ACTIVE.to_string(),
             provider_type: key.provider_type,
             public_visible: key.public_visible,
+            parent_key_id: None,
             quota_billable_limit: key.quota_billable_limit,
             usage_input_uncached_tokens: 0,
             usage_input_cached_tokens: 0,
@@ -450,6 +575,11 @@ impl AdminKeyStore for EmptyAdminKeyStore {
             kiro_cache_policy_override_json: None,
             kiro_billable_model_multipliers_override_json: None,
             kiro_anthropic_upstream_pool_mode: super::default_anthropic_upstream_pool_mode(),
+            codex_openai_upstream_pool_mode: super::default_openai_upstream_pool_mode(),
+            response_cache_policy: None,
+            request_transform_policy: None,
+            moderation_policy: None,
+            queue_policy: None,
             effective_kiro_cache_policy_json: default_kiro_cache_policy_json(),
             uses_global_kiro_cache_policy: true,
             effective_kiro_billable_model_multipliers_json:
@@ -493,6 +623,8 @@ impl AdminAccountGroupStore for EmptyAdminAccountGroupStore {
             provider_type: group.provider_type,
             name: group.name,
             account_names: group.account_names,
+            request_transform_policy: group.request_transform_policy,
+            queue_policy: group.queue_policy,
             created_at: group.created_at_ms,
             updated_at: group.created_at_ms,
         })
@@ -669,6 +801,7 @@ impl AdminCodexAccountStore for EmptyAdminCodexAccountStore {
             codex_image_generation_max_concurrency: DEFAULT_CODEX_IMAGE_GENERATION_MAX_CONCURRENCY,
             proxy_mode: "inherit".to_string(),
             proxy_config_id: None,
+            proxy_pool_id: None,
             effective_proxy_source: "none".to_string(),
             effective_proxy_url: None,
             effective_proxy_config_name: None,
@@ -874,6 +1007,7 @@ impl AdminKiroAccountStore for EmptyAdminKiroAccountStore {
             pool_strategy: default_kiro_pool_strategy(),
             proxy_mode: "inherit".to_string(),
             proxy_config_id: account.proxy_config_id,
+            proxy_pool_id: None,
             effective_proxy_source: "none".to_string(),
             effective_proxy_url: None,
             effective_proxy_config_name: None,
@@ -920,6 +1054,80 @@ impl AdminKiroAccountStore for EmptyAdminKiroAccountStore {
     }
 }
 
+/// Empty batch store used when no control database is configured.
+pub struct EmptyBatchStore;
+
+#[async_trait]
+impl BatchStore for EmptyBatchStore {
+    async fn create_batch_job(&self, _job: NewBatchJob) -> anyhow::Result<BatchJob> {
+        anyhow::bail!("batch store is not configured")
+    }
+
+    async fn list_batch_jobs(&self, _key_id: &str, _limit: usize) -> anyhow::Result<Vec<BatchJob>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_batch_job(
+        &self,
+        _key_id: &str,
+        _batch_id: &str,
+    ) -> anyhow::Result<Option<BatchJob>> {
+        Ok(None)
+    }
+
+    async fn cancel_batch_job(
+        &self,
+        _key_id: &str,
+        _batch_id: &str,
+        _now_ms: i64,
+    ) -> anyhow::Result<Option<BatchJob>> {
+        Ok(None)
+    }
+
+    async fn list_batch_item_results(
+        &self,
+        _key_id: &str,
+        _batch_id: &str,
+    ) -> anyhow::Result<Vec<BatchItemResult>> {
+        Ok(Vec::new())
+    }
+
+    async fn claim_batch_items(
+        &self,
+        _limit: usize,
+        _now_ms: i64,
+        _lease_expired_before_ms: i64,
+    ) -> anyhow::Result<Vec<ClaimedBatchItem>> {
+        Ok(Vec::new())
+    }
+
+    async fn complete_batch_item(
+        &self,
+        _batch_id: &str,
+        _item_index: i64,
+        _outcome: BatchItemOutcome,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn release_batch_item(
+        &self,
+        _batch_id: &str,
+        _item_index: i64,
+        _retry_at_ms: i64,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn expire_batch_jobs(
+        &self,
+        _now_ms: i64,
+        _lease_expired_before_ms: i64,
+    ) -> anyhow::Result<u64> {
+        Ok(0)
+    }
+}
+
 /// Empty admin review queue store used by isolated unit tests.
 pub struct EmptyAdminReviewQueueStore;
 
@@ -1053,6 +1261,28 @@ impl AdminReviewQueueStore for EmptyAdminReviewQueueStore {
     async fn delete_admin_sponsor_request(&self, _request_id: &str) -> anyhow::Result<bool> {
         Ok(false)
     }
+
+    async fn list_admin_moderation_events(
+        &self,
+        query: AdminReviewQueueQuery,
+    ) -> anyhow::Result<AdminModerationEventsPage> {
+        Ok(AdminModerationEventsPage {
+            total: 0,
+            offset: query.offset,
+            limit: query.limit,
+            has_more: false,
+            events: Vec::new(),
+        })
+    }
+
+    async fn review_admin_moderation_event(
+        &self,
+        _event_id: &str,
+        _status: &str,
+        _action: AdminReviewQueueAction,
+    ) -> anyhow::Result<Option<AdminModerationEvent>> {
+        Ok(None)
+    }
 }
 
 /// Empty status store used by isolated unit tests.
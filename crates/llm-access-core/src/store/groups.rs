@@ -3,6 +3,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use super::{KeyQueuePolicy, RequestTransformPolicy};
+
 /// Admin-facing projection of one reusable account group.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct AdminAccountGroup {
@@ -14,6 +16,12 @@ pub struct AdminAccountGroup {
     pub name: String,
     /// Account names included in the group.
     pub account_names: Vec<String>,
+    /// Request rewrites applied to every key routed through this group.
+    #[serde(default)]
+    pub request_transform_policy: Option<RequestTransformPolicy>,
+    /// Default fair queuing policy for keys routed through this group.
+    #[serde(default)]
+    pub queue_policy: Option<KeyQueuePolicy>,
     /// Creation timestamp.
     pub created_at: i64,
     /// Update timestamp.
@@ -61,6 +69,10 @@ pub struct NewAdminAccountGroup {
     pub name: String,
     /// Account names included in the group.
     pub account_names: Vec<String>,
+    /// Request rewrites applied to every key routed through this group.
+    pub request_transform_policy: Option<RequestTransformPolicy>,
+    /// Default fair queuing policy for keys routed through this group.
+    pub queue_policy: Option<KeyQueuePolicy>,
     /// Creation timestamp.
     pub created_at_ms: i64,
 }
@@ -72,6 +84,10 @@ pub struct AdminAccountGroupPatch {
     pub name: Option<String>,
     /// Replacement account list.
     pub account_names: Option<Vec<String>>,
+    /// Replacement request transform policy.
+    pub request_transform_policy: Option<Option<RequestTransformPolicy>>,
+    /// Replacement fair queuing policy.
+    pub queue_policy: Option<Option<KeyQueuePolicy>>,
     /// Update timestamp.
     pub updated_at_ms: i64,
 }
@@ -0,0 +1,263 @@
+//! Self-service key portal: settings a key holder manages for their own key.
+//!
+//! Holder caps only ever tighten what an admin granted. They are stored apart
+//! from the admin-owned key and route fields and applied at auth and
+//! request-snapshot time, so clearing a cap restores the admin value.
+
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+/// Default overlap during which a rotated-out secret keeps authenticating.
+pub const DEFAULT_KEY_SECRET_GRACE_SECONDS: u64 = 60 * 60;
+/// Longest overlap a holder may request when rotating a secret.
+pub const MAX_KEY_SECRET_GRACE_SECONDS: u64 = 7 * 24 * 60 * 60;
+/// Maximum number of quota alert recipients per key.
+pub const MAX_KEY_PORTAL_NOTIFY_EMAILS: usize = 5;
+/// How long a notify email confirmation link stays valid. A recipient that
+/// was already sent a live link is not emailed again inside this window.
+pub const KEY_NOTIFY_EMAIL_CONFIRMATION_TTL_SECONDS: u64 = 24 * 60 * 60;
+/// Maximum number of quota alert thresholds per key.
+pub const MAX_KEY_QUOTA_ALERT_THRESHOLDS: usize = 5;
+/// Maximum characters of an upstream error body shown to a key holder.
+pub const MAX_KEY_PORTAL_ERROR_BODY_CHARS: usize = 2_000;
+
+/// Holder-chosen limits layered on top of the admin configuration.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KeyPortalCaps {
+    /// Lower billable quota ceiling.
+    pub quota_billable_cap: Option<u64>,
+    /// Lower per-key concurrency ceiling.
+    pub request_max_concurrency_cap: Option<u64>,
+    /// Longer per-key pacing interval.
+    pub request_min_start_interval_ms_floor: Option<u64>,
+}
+
+impl KeyPortalCaps {
+    /// Whether no cap is set.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Effective billable quota under the holder cap.
+    pub fn cap_quota(&self, quota_billable_limit: u64) -> u64 {
+        self.quota_billable_cap
+            .map_or(quota_billable_limit, |cap| cap.min(quota_billable_limit))
+    }
+
+    /// Effective concurrency limit under the holder cap.
+    pub fn cap_concurrency(&self, request_max_concurrency: Option<u64>) -> Option<u64> {
+        match (request_max_concurrency, self.request_max_concurrency_cap) {
+            (Some(value), Some(cap)) => Some(value.min(cap)),
+            (value, cap) => value.or(cap),
+        }
+    }
+
+    /// Effective pacing interval under the holder floor.
+    pub fn floor_interval(&self, request_min_start_interval_ms: Option<u64>) -> Option<u64> {
+        match (request_min_start_interval_ms, self.request_min_start_interval_ms_floor) {
+            (Some(value), Some(floor)) => Some(value.max(floor)),
+            (value, floor) => value.or(floor),
+        }
+    }
+}
+
+/// Portal settings of one key.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KeyPortalSettings {
+    /// Holder caps.
+    pub caps: KeyPortalCaps,
+    /// Quota alert recipients chosen by the holder.
+    pub notify_emails: Vec<String>,
+    /// Recipients that followed their confirmation link. Only these get
+    /// alerts.
+    pub confirmed_notify_emails: Vec<String>,
+    /// Used-quota percentages that trigger an alert, ascending.
+    pub quota_alert_thresholds: Vec<u8>,
+    /// Highest threshold already alerted for the current usage level.
+    pub quota_alert_level: Option<u8>,
+    /// When the previous secret stops authenticating, while in its grace
+    /// window.
+    pub previous_secret_expires_at_ms: Option<i64>,
+    /// Last settings update, `0` when never saved.
+    pub updated_at_ms: i64,
+}
+
+/// Partial update of portal settings. Outer `None` leaves a field unchanged.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KeyPortalSettingsPatch {
+    /// Lower billable quota ceiling.
+    pub quota_billable_cap: Option<Option<u64>>,
+    /// Lower per-key concurrency ceiling.
+    pub request_max_concurrency_cap: Option<Option<u64>>,
+    /// Longer per-key pacing interval.
+    pub request_min_start_interval_ms_floor: Option<Option<u64>>,
+    /// Quota alert recipients.
+    pub notify_emails: Option<Vec<String>>,
+    /// Quota alert thresholds.
+    pub quota_alert_thresholds: Option<Vec<u8>>,
+    /// Update timestamp.
+    pub updated_at_ms: i64,
+}
+
+/// New secret for an existing key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeySecretRotation {
+    /// Plaintext secret.
+    pub secret: String,
+    /// SHA-256 secret hash.
+    pub key_hash: String,
+    /// When the replaced secret stops authenticating.
+    pub previous_secret_expires_at_ms: i64,
+    /// Rotation timestamp.
+    pub rotated_at_ms: i64,
+}
+
+/// Pending confirmation of one quota alert recipient.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewKeyNotifyEmailConfirmation {
+    /// Key id.
+    pub key_id: String,
+    /// Normalized recipient address.
+    pub email: String,
+    /// SHA-256 hash of the confirmation token mailed to the recipient.
+    pub token_hash: String,
+    /// When the link was issued.
+    pub requested_at_ms: i64,
+}
+
+/// A recipient that followed its confirmation link.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfirmedKeyNotifyEmail {
+    /// Key id.
+    pub key_id: String,
+    /// Confirmed recipient address.
+    pub email: String,
+}
+
+/// One key with quota alerts configured, as seen by the alert sweep.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyQuotaAlertCandidate {
+    /// Key id.
+    pub key_id: String,
+    /// Key name.
+    pub key_name: String,
+    /// Effective billable quota, holder cap applied.
+    pub quota_billable_limit: u64,
+    /// Billable tokens used so far.
+    pub billable_tokens_used: u64,
+    /// Confirmed quota alert recipients.
+    pub notify_emails: Vec<String>,
+    /// Quota alert thresholds.
+    pub quota_alert_thresholds: Vec<u8>,
+    /// Highest threshold already alerted.
+    pub quota_alert_level: Option<u8>,
+}
+
+/// Highest configured threshold the usage has reached, if any.
+pub fn quota_alert_level(used: u64, limit: u64, thresholds: &[u8]) -> Option<u8> {
+    thresholds
+        .iter()
+        .copied()
+        .filter(|threshold| {
+            limit == 0 || u128::from(used) * 100 >= u128::from(*threshold) * u128::from(limit)
+        })
+        .max()
+}
+
+/// Sort, dedupe and validate quota alert thresholds (1-100 percent).
+pub fn normalize_quota_alert_thresholds(mut thresholds: Vec<u8>) -> Result<Vec<u8>, &'static str> {
+    if thresholds.iter().any(|value| *value == 0 || *value > 100) {
+        return Err("quota_alert_thresholds must be between 1 and 100");
+    }
+    thresholds.sort_unstable();
+    thresholds.dedup();
+    if thresholds.len() > MAX_KEY_QUOTA_ALERT_THRESHOLDS {
+        return Err("too many quota_alert_thresholds");
+    }
+    Ok(thresholds)
+}
+
+static SECRET_PATTERNS: LazyLock<[Regex; 4]> = LazyLock::new(|| {
+    [
+        Regex::new(r#"(?i)("(?:api[_-]?key|authorization|access_token|refresh_token|id_token|secret|password|token)"\s*:\s*)"[^"]*""#)
+            .expect("valid secret field pattern"),
+        Regex::new(r"(?i)\bbearer\s+[A-Za-z0-9._~+/=-]+").expect("valid bearer pattern"),
+        Regex::new(r"\b(?:sk|sfk|rk|pk)[-_][A-Za-z0-9_-]{8,}").expect("valid api key pattern"),
+        Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}").expect("valid email pattern"),
+    ]
+});
+
+/// Strip credentials and email addresses from an error body and cap its
+/// length before it is shown to a key holder.
+pub fn redact_key_portal_error_body(body: &str) -> String {
+    let [fields, bearer, api_key, email] = &*SECRET_PATTERNS;
+    let redacted = fields.replace_all(body, r#"$1"[redacted]""#);
+    let redacted = bearer.replace_all(&redacted, "Bearer [redacted]");
+    let redacted = api_key.replace_all(&redacted, "[redacted]");
+    let redacted = email.replace_all(&redacted, "[email]");
+    let mut chars = redacted.chars();
+    let mut truncated = chars
+        .by_ref()
+        .take(MAX_KEY_PORTAL_ERROR_BODY_CHARS)
+        .collect::<String>();
+    if chars.next().is_some() {
+        truncated.push('…');
+    }
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        normalize_quota_alert_thresholds, quota_alert_level, redact_key_portal_error_body,
+        KeyPortalCaps,
+    };
+
+    #[test]
+    fn holder_caps_only_tighten_admin_limits() {
+        let caps = KeyPortalCaps {
+            quota_billable_cap: Some(500),
+            request_max_concurrency_cap: Some(2),
+            request_min_start_interval_ms_floor: Some(200),
+        };
+        assert_eq!(caps.cap_quota(1_000), 500);
+        assert_eq!(caps.cap_quota(100), 100);
+        assert_eq!(caps.cap_concurrency(Some(8)), Some(2));
+        assert_eq!(caps.cap_concurrency(Some(1)), Some(1));
+        assert_eq!(caps.cap_concurrency(None), Some(2));
+        assert_eq!(caps.floor_interval(Some(50)), Some(200));
+        assert_eq!(caps.floor_interval(Some(500)), Some(500));
+        assert_eq!(KeyPortalCaps::default().cap_concurrency(Some(4)), Some(4));
+        assert_eq!(KeyPortalCaps::default().floor_interval(None), None);
+    }
+
+    #[test]
+    fn quota_alert_level_picks_highest_reached_threshold() {
+        assert_eq!(quota_alert_level(0, 1_000, &[50, 80]), None);
+        assert_eq!(quota_alert_level(500, 1_000, &[50, 80]), Some(50));
+        assert_eq!(quota_alert_level(950, 1_000, &[50, 80]), Some(80));
+        assert_eq!(quota_alert_level(10, 0, &[50]), Some(50));
+        assert_eq!(quota_alert_level(10, 1_000, &[]), None);
+    }
+
+    #[test]
+    fn quota_alert_thresholds_are_sorted_and_bounded() {
+        assert_eq!(normalize_quota_alert_thresholds(vec![90, 50, 90]), Ok(vec![50, 90]));
+        assert!(normalize_quota_alert_thresholds(vec![0]).is_err());
+        assert!(normalize_quota_alert_thresholds(vec![101]).is_err());
+        assert!(normalize_quota_alert_thresholds(vec![10, 20, 30, 40, 50, 60]).is_err());
+    }
+
+    #[test]
+    fn error_bodies_are_redacted_for_key_holders() {
+        let body = r#"{"error":"bad key sk-abcdefghijklmnop","api_key":"xyz","contact":"ops@example.com","hdr":"Bearer abc.def"}"#;
+        let redacted = redact_key_portal_error_body(body);
+        assert!(!redacted.contains("sk-abcdefghijklmnop"));
+        assert!(!redacted.contains("xyz"));
+        assert!(!redacted.contains("ops@example.com"));
+        assert!(!redacted.contains("abc.def"));
+        assert!(redacted.contains(r#""api_key":"[redacted]""#));
+        assert!(redact_key_portal_error_body(&"x".repeat(5_000)).ends_with('…'));
+    }
+}
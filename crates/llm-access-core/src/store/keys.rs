@@ -5,7 +5,10 @@ use std::collections::BTreeMap;
 
 use serde::{Deserialize, Serialize};
 
-use super::{KEY_STATUS_ACTIVE, KEY_STATUS_DISABLED};
+use super::{
+    KeyModerationPolicy, KeyQueuePolicy, KeyResponseCachePolicy, RequestTransformPolicy,
+    KEY_STATUS_ACTIVE, KEY_STATUS_DISABLED,
+};
 
 const fn default_true() -> bool {
     true
@@ -28,6 +31,9 @@ pub struct AdminKey {
     pub provider_type: String,
     /// Whether the key is visible on the public access page.
     pub public_visible: bool,
+    /// Parent key id when this key is a delegated sub-key.
+    #[serde(default)]
+    pub parent_key_id: Option<String>,
     /// Billable quota limit.
     pub quota_billable_limit: u64,
     /// Accumulated uncached input tokens.
@@ -120,6 +126,22 @@ pub struct AdminKey {
     /// Direct Anthropic upstream pool mode for Kiro keys.
     #[serde(default = "super::default_anthropic_upstream_pool_mode")]
     pub kiro_anthropic_upstream_pool_mode: String,
+    /// OpenAI-compatible upstream pool mode for Codex keys.
+    #[serde(default = "super::default_openai_upstream_pool_mode")]
+    pub codex_openai_upstream_pool_mode: String,
+    /// Opt-in cache for identical requests.
+    #[serde(default)]
+    pub response_cache_policy: Option<KeyResponseCachePolicy>,
+    /// Request rewrites applied before dispatch, after any account-group
+    /// policy.
+    #[serde(default)]
+    pub request_transform_policy: Option<RequestTransformPolicy>,
+    /// Content moderation rules screened before dispatch.
+    #[serde(default)]
+    pub moderation_policy: Option<KeyModerationPolicy>,
+    /// Fair queuing weight and tier, overlaid on any account-group policy.
+    #[serde(default)]
+    pub queue_policy: Option<KeyQueuePolicy>,
     /// Effective Kiro cache policy JSON.
     pub effective_kiro_cache_policy_json: String,
     /// Whether the effective Kiro cache policy is global.
@@ -424,6 +446,16 @@ pub struct AdminKeyPatch {
     pub kiro_billable_model_multipliers_override_json: Option<Option<String>>,
     /// New direct Anthropic upstream pool mode for Kiro keys.
     pub kiro_anthropic_upstream_pool_mode: Option<String>,
+    /// New OpenAI-compatible upstream pool mode for Codex keys.
+    pub codex_openai_upstream_pool_mode: Option<String>,
+    /// New response cache policy.
+    pub response_cache_policy: Option<Option<KeyResponseCachePolicy>>,
+    /// New request transform policy.
+    pub request_transform_policy: Option<Option<RequestTransformPolicy>>,
+    /// New moderation policy.
+    pub moderation_policy: Option<Option<KeyModerationPolicy>>,
+    /// New fair queuing policy.
+    pub queue_policy: Option<Option<KeyQueuePolicy>>,
     /// Update timestamp.
     pub updated_at_ms: i64,
 }
@@ -441,6 +473,7 @@ mod tests {
             status: KEY_STATUS_ACTIVE.to_string(),
             provider_type: "codex".to_string(),
             public_visible: true,
+            parent_key_id: None,
             quota_billable_limit: 1_000,
             usage_input_uncached_tokens: 0,
             usage_input_cached_tokens: 0,
@@ -479,6 +512,11 @@ mod tests {
             kiro_cache_policy_override_json: None,
             kiro_billable_model_multipliers_override_json: None,
             kiro_anthropic_upstream_pool_mode: crate::store::default_anthropic_upstream_pool_mode(),
+            codex_openai_upstream_pool_mode: crate::store::default_openai_upstream_pool_mode(),
+            response_cache_policy: None,
+            request_transform_policy: None,
+            moderation_policy: None,
+            queue_policy: None,
             effective_kiro_cache_policy_json: "{}".to_string(),
             uses_global_kiro_cache_policy: true,
             effective_kiro_billable_model_multipliers_json: "{}".to_string(),
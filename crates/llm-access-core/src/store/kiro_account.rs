@@ -238,6 +238,9 @@ pub struct AdminKiroAccount {
     pub proxy_mode: String,
     /// Fixed proxy config id.
     pub proxy_config_id: Option<String>,
+    /// Proxy pool id when proxy mode is pool.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy_pool_id: Option<String>,
     /// Effective proxy source.
     pub effective_proxy_source: String,
     /// Effective proxy URL.
@@ -315,6 +318,8 @@ pub struct AdminKiroAccountPatch {
     pub proxy_mode: Option<String>,
     /// New fixed proxy config id.
     pub proxy_config_id: Option<Option<String>>,
+    /// New proxy pool id.
+    pub proxy_pool_id: Option<Option<String>>,
     /// Update timestamp.
     pub updated_at_ms: i64,
 }
@@ -7,17 +7,25 @@
 //! no-op impls into focused submodules, re-exported by name.
 
 mod anthropic_upstream;
+mod batch;
 mod codex_account;
 mod codex_status;
 mod config;
 mod empty;
 mod groups;
+mod key_portal;
 mod keys;
 mod kiro_account;
 mod kiro_model_routing;
+mod moderation;
+mod openai_upstream;
 mod proxy;
 mod public;
+mod queue_policy;
+mod request_transform;
+mod response_cache;
 mod routes;
+mod sub_keys;
 mod traits;
 mod usage;
 
@@ -34,6 +42,14 @@ pub use anthropic_upstream::{
     DEFAULT_ANTHROPIC_UPSTREAM_MAX_CONCURRENCY, DEFAULT_ANTHROPIC_UPSTREAM_MIN_START_INTERVAL_MS,
     DEFAULT_ANTHROPIC_UPSTREAM_WEIGHT,
 };
+pub use batch::{
+    BatchItemOutcome, BatchItemResult, BatchJob, ClaimedBatchItem, NewBatchItem, NewBatchJob,
+    BATCH_COMPLETION_WINDOW_MS, BATCH_ITEM_STATUS_CANCELLED, BATCH_ITEM_STATUS_EXPIRED,
+    BATCH_ITEM_STATUS_FAILED, BATCH_ITEM_STATUS_PENDING, BATCH_ITEM_STATUS_RUNNING,
+    BATCH_ITEM_STATUS_SUCCEEDED, BATCH_STATUS_CANCELLED, BATCH_STATUS_CANCELLING,
+    BATCH_STATUS_COMPLETED, BATCH_STATUS_EXPIRED, BATCH_STATUS_IN_PROGRESS, MAX_BATCH_INPUT_BYTES,
+    MAX_BATCH_ITEMS, MAX_BATCH_RESULT_BYTES,
+};
 pub use codex_account::{
     AdminAccountsSummary, AdminCodexAccount, AdminCodexAccountPageQuery, AdminCodexAccountPatch,
     AdminCodexAccountSortMode, AdminCodexAccountsPage, AdminCodexImportJobDetail,
@@ -52,15 +68,25 @@ pub use config::{
 };
 pub use empty::{
     EmptyAdminAccountGroupStore, EmptyAdminAnthropicUpstreamStore, EmptyAdminCodexAccountStore,
-    EmptyAdminConfigStore, EmptyAdminKeyStore, EmptyAdminKiroAccountStore, EmptyAdminProxyStore,
-    EmptyAdminReviewQueueStore, EmptyProviderRouteStore, EmptyPublicAccessStore,
+    EmptyAdminConfigStore, EmptyAdminKeyStore, EmptyAdminKiroAccountStore,
+    EmptyAdminOpenAiUpstreamStore, EmptyAdminProxyStore, EmptyAdminReviewQueueStore,
+    EmptyBatchStore, EmptyKeyPortalStore, EmptyProviderRouteStore, EmptyPublicAccessStore,
     EmptyPublicCommunityStore, EmptyPublicStatusStore, EmptyPublicSubmissionStore,
-    EmptyPublicUsageStore, EmptyUsageAnalyticsStore, NoopUsageEventSink, NoopUsageRollupBatchSink,
+    EmptyPublicUsageStore, EmptySubKeyStore, EmptyUsageAnalyticsStore, NoopUsageEventSink,
+    NoopUsageRollupBatchSink,
 };
 pub use groups::{
     AdminAccountGroup, AdminAccountGroupOption, AdminAccountGroupPatch, AdminAccountGroupsPage,
     NewAdminAccountGroup,
 };
+pub use key_portal::{
+    normalize_quota_alert_thresholds, quota_alert_level, redact_key_portal_error_body,
+    ConfirmedKeyNotifyEmail, KeyPortalCaps, KeyPortalSettings, KeyPortalSettingsPatch,
+    KeyQuotaAlertCandidate, KeySecretRotation, NewKeyNotifyEmailConfirmation,
+    DEFAULT_KEY_SECRET_GRACE_SECONDS, KEY_NOTIFY_EMAIL_CONFIRMATION_TTL_SECONDS,
+    MAX_KEY_PORTAL_ERROR_BODY_CHARS, MAX_KEY_PORTAL_NOTIFY_EMAILS, MAX_KEY_QUOTA_ALERT_THRESHOLDS,
+    MAX_KEY_SECRET_GRACE_SECONDS,
+};
 pub use keys::{
     AdminKey, AdminKeyPageQuery, AdminKeyPatch, AdminKeySortMode, AdminKeysPage, AdminKeysSummary,
     AdminKiroKeyCandidateCreditSummary, AdminPageRequest, NewAdminKey,
@@ -75,10 +101,28 @@ pub use kiro_account::{
 pub use kiro_model_routing::{
     kiro_model_group_preference, normalize_kiro_model_group_preferences, KiroModelGroupPreferences,
 };
+pub use moderation::{
+    decode_key_moderation_policy, AdminModerationEvent, AdminModerationEventsPage,
+    KeyModerationPolicy, ModerationAction, NewModerationEvent, MAX_MODERATION_BLOCKED_TOOLS,
+    MAX_MODERATION_EXCERPT_CHARS, MAX_MODERATION_KEYWORDS, MAX_MODERATION_PATTERNS,
+    MAX_MODERATION_RULE_BYTES, MODERATION_EVENT_STATUS_CONFIRMED,
+    MODERATION_EVENT_STATUS_DISMISSED, MODERATION_EVENT_STATUS_PENDING,
+};
+pub use openai_upstream::{
+    canonical_openai_upstream_pool_mode, default_openai_upstream_pool_mode,
+    normalize_openai_upstream_pool_mode, AdminOpenAiUpstreamChannel,
+    AdminOpenAiUpstreamChannelPatch, AdminOpenAiUpstreamModelsStatusUpdate,
+    AdminOpenAiUpstreamProbeTarget, AdminOpenAiUpstreamUsageRollup, NewAdminOpenAiUpstreamChannel,
+    OpenAiUpstreamChannelUsageDelta, ProviderOpenAiUpstreamResolution, ProviderOpenAiUpstreamRoute,
+    DEFAULT_OPENAI_UPSTREAM_MAX_CONCURRENCY, DEFAULT_OPENAI_UPSTREAM_MIN_START_INTERVAL_MS,
+    DEFAULT_OPENAI_UPSTREAM_WEIGHT, OPENAI_UPSTREAM_POOL_MODE_DISABLED,
+    OPENAI_UPSTREAM_POOL_MODE_ONLY, OPENAI_UPSTREAM_POOL_MODE_PREFERRED_BEFORE_CODEX,
+};
 pub use proxy::{
     default_proxy_bindings, AdminProxyBinding, AdminProxyConfig, AdminProxyConfigPatch,
-    AdminProxyEndpointCheck, AdminProxyEndpointCheckUpdate, AdminProxyTrafficSnapshot,
-    NewAdminProxyConfig,
+    AdminProxyEndpointCheck, AdminProxyEndpointCheckUpdate, AdminProxyPool, AdminProxyPoolPatch,
+    AdminProxyTrafficSnapshot, NewAdminProxyConfig, NewAdminProxyPool, ProxyPoolMember,
+    LIVE_REQUEST_PROXY_CHECK_TARGET, PROXY_UNHEALTHY_CHECK_WINDOW_MS,
 };
 pub use public::{
     AdminAccountContributionRequest, AdminAccountContributionRequestsPage, AdminReviewQueueAction,
@@ -87,18 +131,36 @@ pub use public::{
     NewPublicTokenRequest, PublicAccessKey, PublicAccountContribution, PublicSponsor,
     PublicUsageLookupKey,
 };
+pub use queue_policy::{
+    decode_key_queue_policy, KeyQueuePolicy, QueuePriority, DEFAULT_QUEUE_WEIGHT,
+    MAX_QUEUE_DEPTH_PER_KEY, MAX_QUEUE_WEIGHT,
+};
+pub use request_transform::{
+    decode_request_transform_policy, parse_request_transform_path, RequestRedactionRule,
+    RequestTransformOperation, RequestTransformPathSegment, RequestTransformPolicy,
+    DEFAULT_REQUEST_REDACTION_REPLACEMENT, MAX_REQUEST_TRANSFORM_OPERATIONS,
+    MAX_REQUEST_TRANSFORM_PATTERN_BYTES, MAX_REQUEST_TRANSFORM_REDACTIONS,
+    MAX_REQUEST_TRANSFORM_SYSTEM_PROMPT_BYTES,
+};
+pub use response_cache::{
+    enabled_response_cache_policy, KeyResponseCachePolicy, DEFAULT_RESPONSE_CACHE_TTL_SECONDS,
+    MAX_RESPONSE_CACHE_TTL_SECONDS,
+};
 pub use routes::{
     codex_access_token_expires_at_ms, codex_auth_access_token_expires_at_ms,
     codex_auth_principal_id, is_terminal_codex_auth_error, jwt_expiry_unix_ms, AuthenticatedKey,
     ProviderCodexAuthUpdate, ProviderCodexRoute, ProviderKiroAuthUpdate, ProviderKiroRoute,
     ProviderProxyConfig,
 };
+pub use sub_keys::{
+    delegable_sub_key_quota, NewSubKey, SubKeyQuotaExceeded, MAX_SUB_KEYS_PER_PARENT,
+};
 pub use traits::{
     AdminAccountGroupStore, AdminAnthropicUpstreamStore, AdminCodexAccountStore, AdminConfigStore,
-    AdminKeyStore, AdminKiroAccountStore, AdminProxyStore, AdminReviewQueueStore, ControlStore,
-    ProviderRouteStore, PublicAccessStore, PublicCommunityStore, PublicStatusStore,
-    PublicSubmissionStore, PublicUsageStore, UsageAnalyticsStore, UsageEventSink,
-    UsageRollupBatchSink,
+    AdminKeyStore, AdminKiroAccountStore, AdminOpenAiUpstreamStore, AdminProxyStore,
+    AdminReviewQueueStore, BatchStore, ControlStore, KeyPortalStore, ProviderRouteStore,
+    PublicAccessStore, PublicCommunityStore, PublicStatusStore, PublicSubmissionStore,
+    PublicUsageStore, SubKeyStore, UsageAnalyticsStore, UsageEventSink, UsageRollupBatchSink,
 };
 pub use usage::{
     AdminLegacyKiroProxyMigration, KeyUsageRollupDelta, KeyUsageRollupLastUsedCount,
@@ -0,0 +1,238 @@
+//! Per-key content moderation policy and the moderation review queue.
+//!
+//! ```text
+//! client request ──> local rules (keywords, patterns, attachments, tools)
+//!                    + optional local classifier
+//!                          |
+//!          no hit ─────────┼──────────> dispatch
+//!                          | hit
+//!          block ──> reject + queue     flag ──> queue + dispatch
+//!          log_only ──> log + dispatch
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+/// Most keyword entries one policy may carry.
+pub const MAX_MODERATION_KEYWORDS: usize = 256;
+/// Most regex entries one policy may carry.
+pub const MAX_MODERATION_PATTERNS: usize = 64;
+/// Most blocked tool names one policy may carry.
+pub const MAX_MODERATION_BLOCKED_TOOLS: usize = 64;
+/// Longest accepted keyword or pattern, in bytes.
+pub const MAX_MODERATION_RULE_BYTES: usize = 512;
+/// Longest prompt or response excerpt stored with a review queue event.
+pub const MAX_MODERATION_EXCERPT_CHARS: usize = 2_000;
+
+/// Review queue status of a freshly recorded event.
+pub const MODERATION_EVENT_STATUS_PENDING: &str = "pending";
+/// Review queue status after an admin confirmed the hit.
+pub const MODERATION_EVENT_STATUS_CONFIRMED: &str = "confirmed";
+/// Review queue status after an admin dismissed the hit.
+pub const MODERATION_EVENT_STATUS_DISMISSED: &str = "dismissed";
+
+/// What happens when a moderation rule matches.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ModerationAction {
+    /// Reject the request and queue it for review.
+    Block,
+    /// Forward the request and queue it for review.
+    #[default]
+    Flag,
+    /// Forward the request and only write a log line.
+    LogOnly,
+}
+
+impl ModerationAction {
+    /// Stable storage and diagnostics name.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Block => "block",
+            Self::Flag => "flag",
+            Self::LogOnly => "log_only",
+        }
+    }
+}
+
+/// Moderation rules applied to one managed key.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct KeyModerationPolicy {
+    /// Action taken when any rule matches.
+    #[serde(default)]
+    pub action: ModerationAction,
+    /// Case-insensitive substrings matched against prompt text.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keywords: Vec<String>,
+    /// Regexes matched against prompt text.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub patterns: Vec<String>,
+    /// Largest number of image/file attachments one request may carry.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_attachments: Option<u32>,
+    /// Tool names a request may not declare (case-insensitive).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blocked_tool_names: Vec<String>,
+    /// Also ask the configured local classifier endpoint.
+    #[serde(default)]
+    pub classifier_enabled: bool,
+    /// Also scan response text. Responses are never blocked after the
+    /// fact; a hit is queued (`block`/`flag`) or logged (`log_only`).
+    #[serde(default)]
+    pub moderate_responses: bool,
+}
+
+impl KeyModerationPolicy {
+    /// Whether the policy has anything to check.
+    pub fn has_rules(&self) -> bool {
+        !self.keywords.is_empty()
+            || !self.patterns.is_empty()
+            || self.max_attachments.is_some()
+            || !self.blocked_tool_names.is_empty()
+            || self.classifier_enabled
+    }
+
+    /// Check the admin-supplied bounds and patterns.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        for (name, entries, max) in [
+            ("keywords", &self.keywords, MAX_MODERATION_KEYWORDS),
+            ("patterns", &self.patterns, MAX_MODERATION_PATTERNS),
+            ("blocked_tool_names", &self.blocked_tool_names, MAX_MODERATION_BLOCKED_TOOLS),
+        ] {
+            if entries.len() > max {
+                anyhow::bail!("moderation_policy.{name} must have at most {max} entries");
+            }
+            if entries
+                .iter()
+                .any(|entry| entry.trim().is_empty() || entry.len() > MAX_MODERATION_RULE_BYTES)
+            {
+                anyhow::bail!(
+                    "moderation_policy.{name} entries must be 1 to {MAX_MODERATION_RULE_BYTES} \
+                     bytes"
+                );
+            }
+        }
+        for pattern in &self.patterns {
+            regex::Regex::new(pattern)
+                .map_err(|err| anyhow::anyhow!("invalid moderation pattern `{pattern}`: {err}"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Decode a persisted policy, treating absent, unparsable or rule-less
+/// policies as "no moderation".
+pub fn decode_key_moderation_policy(raw: Option<&str>) -> Option<KeyModerationPolicy> {
+    let policy = serde_json::from_str::<KeyModerationPolicy>(raw?.trim()).ok()?;
+    (policy.has_rules() && policy.validate().is_ok()).then_some(policy)
+}
+
+/// One moderation hit to append to the review queue.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewModerationEvent {
+    /// Stable event id.
+    pub event_id: String,
+    /// Key that sent the request.
+    pub key_id: String,
+    /// Key display name at the time of the hit.
+    pub key_name: String,
+    /// Provider type of the key.
+    pub provider_type: String,
+    /// Request path.
+    pub endpoint: String,
+    /// Client-requested model when known.
+    pub model: Option<String>,
+    /// `request` or `response`.
+    pub direction: String,
+    /// Policy action that applied (`block` or `flag`).
+    pub action: String,
+    /// Labels of the rules that matched.
+    pub matched_rules: Vec<String>,
+    /// Classifier labels when the classifier flagged the text.
+    pub classifier_labels: Vec<String>,
+    /// Classifier score when one was returned.
+    pub classifier_score: Option<f64>,
+    /// Truncated text around the first match.
+    pub excerpt: String,
+    /// Normalized client IP.
+    pub client_ip: String,
+    /// Creation timestamp in Unix milliseconds.
+    pub created_at_ms: i64,
+}
+
+/// Admin-facing projection of one moderation review queue event.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AdminModerationEvent {
+    /// Stable event id.
+    pub event_id: String,
+    /// Key that sent the request.
+    pub key_id: String,
+    /// Key display name at the time of the hit.
+    pub key_name: String,
+    /// Provider type of the key.
+    pub provider_type: String,
+    /// Request path.
+    pub endpoint: String,
+    /// Client-requested model when known.
+    pub model: Option<String>,
+    /// `request` or `response`.
+    pub direction: String,
+    /// Policy action that applied.
+    pub action: String,
+    /// Labels of the rules that matched.
+    pub matched_rules: Vec<String>,
+    /// Classifier labels when the classifier flagged the text.
+    pub classifier_labels: Vec<String>,
+    /// Classifier score when one was returned.
+    pub classifier_score: Option<f64>,
+    /// Truncated text around the first match.
+    pub excerpt: String,
+    /// Normalized client IP.
+    pub client_ip: String,
+    /// `pending`, `confirmed` or `dismissed`.
+    pub status: String,
+    /// Optional admin note.
+    pub admin_note: Option<String>,
+    /// Creation timestamp in Unix milliseconds.
+    pub created_at: i64,
+    /// Review timestamp in Unix milliseconds.
+    pub reviewed_at: Option<i64>,
+}
+
+/// One page of moderation review queue events.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AdminModerationEventsPage {
+    /// Total rows matching the filter.
+    pub total: usize,
+    /// Page offset.
+    pub offset: usize,
+    /// Page limit.
+    pub limit: usize,
+    /// Whether a later page exists.
+    pub has_more: bool,
+    /// Current page rows.
+    pub events: Vec<AdminModerationEvent>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_only_policies_with_valid_rules() {
+        let policy = decode_key_moderation_policy(Some(
+            r#"{"action":"block","keywords":["jailbreak"],"patterns":["(?i)ignore previous"]}"#,
+        ))
+        .expect("policy");
+        assert_eq!(policy.action, ModerationAction::Block);
+        assert!(decode_key_moderation_policy(Some(r#"{"action":"block"}"#)).is_none());
+        assert!(decode_key_moderation_policy(Some(r#"{"patterns":["("]}"#)).is_none());
+        assert!(decode_key_moderation_policy(None).is_none());
+
+        let blank = KeyModerationPolicy {
+            keywords: vec!["  ".to_string()],
+            ..KeyModerationPolicy::default()
+        };
+        assert!(blank.validate().is_err());
+        assert_eq!(KeyModerationPolicy::default().action, ModerationAction::Flag);
+    }
+}
@@ -0,0 +1,278 @@
+//! Admin/data-plane types for the Codex-owned OpenAI-compatible upstream pool.
+//!
+//! A channel is any server speaking the OpenAI HTTP API behind a base URL and
+//! a bearer key: vLLM, llama.cpp, or another vendor. Codex keys can route onto
+//! the pool before (or instead of) the Codex account pool.
+
+use serde::{Deserialize, Serialize};
+
+use super::{AdminAnthropicUpstreamUsageRollup, ProviderProxyConfig};
+
+/// Pool is disabled for the key.
+pub const OPENAI_UPSTREAM_POOL_MODE_DISABLED: &str = "disabled";
+/// Try the OpenAI-compatible pool first, then Codex when the pool has no route
+/// or returns a retryable upstream failure.
+pub const OPENAI_UPSTREAM_POOL_MODE_PREFERRED_BEFORE_CODEX: &str = "preferred_before_codex";
+/// Only use the OpenAI-compatible pool for this key.
+pub const OPENAI_UPSTREAM_POOL_MODE_ONLY: &str = "only";
+
+/// Default manual scheduler weight for one OpenAI-compatible channel.
+pub const DEFAULT_OPENAI_UPSTREAM_WEIGHT: u64 = 100;
+/// Default OpenAI-compatible channel concurrency.
+pub const DEFAULT_OPENAI_UPSTREAM_MAX_CONCURRENCY: u64 = 8;
+/// Default OpenAI-compatible channel pacing interval.
+pub const DEFAULT_OPENAI_UPSTREAM_MIN_START_INTERVAL_MS: u64 = 0;
+
+/// Return the normalized OpenAI-compatible pool mode, if supported.
+pub fn normalize_openai_upstream_pool_mode(raw: &str) -> Option<&'static str> {
+    match raw.trim() {
+        OPENAI_UPSTREAM_POOL_MODE_DISABLED => Some(OPENAI_UPSTREAM_POOL_MODE_DISABLED),
+        OPENAI_UPSTREAM_POOL_MODE_PREFERRED_BEFORE_CODEX => {
+            Some(OPENAI_UPSTREAM_POOL_MODE_PREFERRED_BEFORE_CODEX)
+        },
+        OPENAI_UPSTREAM_POOL_MODE_ONLY => Some(OPENAI_UPSTREAM_POOL_MODE_ONLY),
+        _ => None,
+    }
+}
+
+/// Default OpenAI-compatible pool mode for existing keys.
+pub fn default_openai_upstream_pool_mode() -> String {
+    OPENAI_UPSTREAM_POOL_MODE_DISABLED.to_string()
+}
+
+/// Canonicalize a possibly missing OpenAI-compatible pool mode.
+pub fn canonical_openai_upstream_pool_mode(raw: Option<&str>) -> String {
+    raw.and_then(normalize_openai_upstream_pool_mode)
+        .unwrap_or(OPENAI_UPSTREAM_POOL_MODE_DISABLED)
+        .to_string()
+}
+
+/// Admin-facing token rollup for one OpenAI-compatible channel. Shares the
+/// shape of the direct Anthropic rollup.
+pub type AdminOpenAiUpstreamUsageRollup = AdminAnthropicUpstreamUsageRollup;
+
+/// Admin-facing OpenAI-compatible channel card.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AdminOpenAiUpstreamChannel {
+    /// Stable channel name.
+    pub name: String,
+    /// Human-facing status.
+    pub status: String,
+    /// OpenAI-compatible base URL including the version segment, for example
+    /// `http://10.0.0.5:8000/v1`.
+    pub base_url: String,
+    /// Whether an API key is configured without exposing the secret.
+    pub has_api_key: bool,
+    /// Manual scheduler weight.
+    pub weight: u64,
+    /// Channel-level concurrency cap.
+    pub max_concurrency: u64,
+    /// Channel-level pacing interval.
+    pub min_start_interval_ms: u64,
+    /// `inherit`, `direct`, or `fixed`.
+    pub proxy_mode: String,
+    /// Fixed proxy config id when `proxy_mode = fixed`.
+    pub proxy_config_id: Option<String>,
+    /// Last hot-path error, if any.
+    pub last_error: Option<String>,
+    /// Latest upstream-visible model ids from a `/models` refresh. Requests for
+    /// models outside a non-empty list skip the channel.
+    #[serde(default)]
+    pub models: Vec<String>,
+    /// Latest `/models` refresh status.
+    #[serde(default)]
+    pub last_models_status: Option<String>,
+    /// Latest `/models` refresh latency.
+    #[serde(default)]
+    pub last_models_latency_ms: Option<u64>,
+    /// Latest `/models` refresh timestamp.
+    #[serde(default)]
+    pub last_models_checked_at: Option<i64>,
+    /// Latest `/models` refresh error summary.
+    #[serde(default)]
+    pub last_models_error: Option<String>,
+    /// Token rollup for this channel.
+    pub usage: AdminOpenAiUpstreamUsageRollup,
+    /// Creation timestamp.
+    pub created_at: i64,
+    /// Update timestamp.
+    pub updated_at: i64,
+}
+
+/// Internal admin probe target, including secret material and resolved proxy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdminOpenAiUpstreamProbeTarget {
+    /// Stable channel name.
+    pub name: String,
+    /// OpenAI-compatible base URL.
+    pub base_url: String,
+    /// API key sent as a bearer token. Empty for unauthenticated local servers.
+    pub api_key: String,
+    /// Resolved proxy settings for this probe request.
+    pub proxy: Option<ProviderProxyConfig>,
+    /// Proxy resolution error, when channel config exists but cannot produce a
+    /// usable proxy.
+    pub proxy_error: Option<String>,
+}
+
+/// Latest `/models` refresh state to persist for one OpenAI-compatible
+/// channel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdminOpenAiUpstreamModelsStatusUpdate {
+    /// Model ids returned by the upstream server.
+    pub model_ids: Vec<String>,
+    /// Stable status label: `ok`, `http_<code>`, or `error`.
+    pub status: String,
+    /// Observed request latency.
+    pub latency_ms: Option<u64>,
+    /// Probe timestamp.
+    pub checked_at_ms: i64,
+    /// Sanitized error summary.
+    pub error: Option<String>,
+}
+
+/// New OpenAI-compatible channel after admin request normalization.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewAdminOpenAiUpstreamChannel {
+    /// Stable channel name.
+    pub name: String,
+    /// Channel status.
+    pub status: String,
+    /// OpenAI-compatible base URL.
+    pub base_url: String,
+    /// Bearer API key; empty for unauthenticated local servers.
+    pub api_key: String,
+    /// Manual scheduler weight.
+    pub weight: u64,
+    /// Channel-level concurrency cap.
+    pub max_concurrency: u64,
+    /// Channel-level pacing interval.
+    pub min_start_interval_ms: u64,
+    /// `inherit`, `direct`, or `fixed`.
+    pub proxy_mode: String,
+    /// Fixed proxy config id when `proxy_mode = fixed`.
+    pub proxy_config_id: Option<String>,
+    /// Creation timestamp.
+    pub created_at_ms: i64,
+}
+
+/// OpenAI-compatible channel patch after admin request normalization.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AdminOpenAiUpstreamChannelPatch {
+    /// New channel status.
+    pub status: Option<String>,
+    /// New base URL.
+    pub base_url: Option<String>,
+    /// Replace API key. `None` means leave as-is; an empty string clears it.
+    pub api_key: Option<String>,
+    /// New manual scheduler weight.
+    pub weight: Option<u64>,
+    /// New channel-level concurrency cap.
+    pub max_concurrency: Option<u64>,
+    /// New channel-level pacing interval.
+    pub min_start_interval_ms: Option<u64>,
+    /// New proxy mode.
+    pub proxy_mode: Option<String>,
+    /// New fixed proxy config id.
+    pub proxy_config_id: Option<Option<String>>,
+    /// Explicitly clear last_error.
+    pub clear_last_error: bool,
+    /// Update timestamp.
+    pub updated_at_ms: i64,
+}
+
+/// Hot-path usage increment for one OpenAI-compatible channel.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OpenAiUpstreamChannelUsageDelta {
+    /// Uncached input tokens.
+    pub input_uncached_tokens: u64,
+    /// Cached input tokens.
+    pub input_cached_tokens: u64,
+    /// Output tokens.
+    pub output_tokens: u64,
+    /// Billable tokens.
+    pub billable_tokens: u64,
+    /// Whether upstream usage was missing.
+    pub usage_missing: bool,
+    /// Usage timestamp.
+    pub used_at_ms: i64,
+}
+
+/// Resolved OpenAI-compatible channel eligible for one provider request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProviderOpenAiUpstreamRoute {
+    /// Channel name.
+    pub channel_name: String,
+    /// Pool mode captured from the key route config.
+    pub pool_mode_at_event: String,
+    /// Account group id from the key route config at resolution time.
+    pub account_group_id_at_event: Option<String>,
+    /// OpenAI-compatible base URL.
+    pub base_url: String,
+    /// Bearer API key; empty for unauthenticated local servers.
+    pub api_key: String,
+    /// Manual scheduler weight.
+    pub weight: u64,
+    /// Key-level concurrency cap.
+    pub request_max_concurrency: Option<u64>,
+    /// Key-level pacing interval.
+    pub request_min_start_interval_ms: Option<u64>,
+    /// Channel-level concurrency cap.
+    pub channel_max_concurrency: u64,
+    /// Channel-level pacing interval.
+    pub channel_min_start_interval_ms: u64,
+    /// Model ids from the latest `/models` refresh; empty accepts any model.
+    pub models: Vec<String>,
+    /// JSON object mapping public model names to upstream model names.
+    pub model_name_map_json: String,
+    /// Resolved proxy settings for this upstream request.
+    pub proxy: Option<ProviderProxyConfig>,
+}
+
+impl ProviderOpenAiUpstreamRoute {
+    /// Whether the channel advertises `model`, or advertises nothing.
+    pub fn serves_model(&self, model: &str) -> bool {
+        self.models.is_empty() || self.models.iter().any(|candidate| candidate == model)
+    }
+}
+
+/// Per-request OpenAI-compatible routing plan for a Codex key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProviderOpenAiUpstreamResolution {
+    /// Canonical key-level pool mode.
+    pub pool_mode: String,
+    /// Eligible OpenAI-compatible candidates for this request.
+    pub routes: Vec<ProviderOpenAiUpstreamRoute>,
+}
+
+impl ProviderOpenAiUpstreamResolution {
+    /// Build the default disabled routing plan.
+    pub fn disabled() -> Self {
+        Self {
+            pool_mode: default_openai_upstream_pool_mode(),
+            routes: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pool_mode_canonicalizes_unknown_values_to_disabled() {
+        assert_eq!(
+            canonical_openai_upstream_pool_mode(Some(" preferred_before_codex ")),
+            OPENAI_UPSTREAM_POOL_MODE_PREFERRED_BEFORE_CODEX
+        );
+        assert_eq!(
+            canonical_openai_upstream_pool_mode(Some("only")),
+            OPENAI_UPSTREAM_POOL_MODE_ONLY
+        );
+        assert_eq!(
+            canonical_openai_upstream_pool_mode(Some("preferred_before_kiro")),
+            OPENAI_UPSTREAM_POOL_MODE_DISABLED
+        );
+        assert_eq!(canonical_openai_upstream_pool_mode(None), OPENAI_UPSTREAM_POOL_MODE_DISABLED);
+    }
+}
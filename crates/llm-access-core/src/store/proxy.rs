@@ -1,9 +1,19 @@
-//! Proxy configuration: proxy config view, endpoint health checks, provider
-//! bindings, create/patch payloads, and the default per-provider bindings.
+//! Proxy configuration: proxy config view, endpoint health checks, proxy
+//! pools, provider bindings, create/patch payloads, and the default
+//! per-provider bindings.
 
 use serde::{Deserialize, Serialize};
 
 use super::{usage::ProxyTrafficTotals, PROVIDER_CODEX, PROVIDER_KIRO};
+pub use crate::proxy::ProxyPoolMember;
+
+/// How long a failed endpoint check keeps a proxy out of pool selection.
+/// After this the proxy is eligible again until the next check says
+/// otherwise.
+pub const PROXY_UNHEALTHY_CHECK_WINDOW_MS: i64 = 10 * 60 * 1000;
+
+/// Target URL recorded for checks synthesized from live request failures.
+pub const LIVE_REQUEST_PROXY_CHECK_TARGET: &str = "live-request";
 
 /// Admin-facing projection of one reusable upstream proxy config.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -79,6 +89,64 @@ pub struct AdminProxyEndpointCheck {
     pub checked_at: i64,
 }
 
+impl AdminProxyEndpointCheck {
+    /// Whether this check, taken at `checked_at`, still marks the proxy as
+    /// unhealthy at `now_ms`.
+    ///
+    /// Transport failures, proxy authentication failures (407) and gateway
+    /// errors (502-504) count against the proxy. Other upstream statuses
+    /// prove the tunnel works even if the anonymous probe was rejected.
+    pub fn marks_proxy_unhealthy(&self, now_ms: i64) -> bool {
+        if now_ms.saturating_sub(self.checked_at) > PROXY_UNHEALTHY_CHECK_WINDOW_MS {
+            return false;
+        }
+        !self.reachable || matches!(self.status_code, Some(407 | 502..=504))
+    }
+}
+
+/// Admin-facing projection of one weighted proxy pool.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AdminProxyPool {
+    /// Proxy pool id.
+    pub id: String,
+    /// Human-readable pool name.
+    pub name: String,
+    /// Pool status.
+    pub status: String,
+    /// Weighted member proxy configs, in display order.
+    pub members: Vec<ProxyPoolMember>,
+    /// Creation timestamp.
+    pub created_at: i64,
+    /// Update timestamp.
+    pub updated_at: i64,
+}
+
+/// New proxy pool row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewAdminProxyPool {
+    /// Proxy pool id.
+    pub id: String,
+    /// Human-readable pool name.
+    pub name: String,
+    /// Weighted member proxy configs.
+    pub members: Vec<ProxyPoolMember>,
+    /// Creation timestamp.
+    pub created_at_ms: i64,
+}
+
+/// Patch for one proxy pool.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AdminProxyPoolPatch {
+    /// New pool name.
+    pub name: Option<String>,
+    /// New status.
+    pub status: Option<String>,
+    /// Replacement member list.
+    pub members: Option<Vec<ProxyPoolMember>>,
+    /// Update timestamp.
+    pub updated_at_ms: i64,
+}
+
 /// Probe result to persist for one proxy/provider endpoint.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AdminProxyEndpointCheckUpdate {
@@ -143,6 +211,10 @@ pub struct AdminProxyBinding {
     pub effective_source: String,
     /// Explicitly bound proxy config id.
     pub bound_proxy_config_id: Option<String>,
+    /// Explicitly bound proxy pool id. Accounts that inherit the binding
+    /// then pick their own sticky pool member.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bound_proxy_pool_id: Option<String>,
     /// Effective proxy config name.
     pub effective_proxy_config_name: Option<String>,
     /// Effective proxy URL.
@@ -170,6 +242,7 @@ pub fn default_proxy_binding(provider_type: &str) -> AdminProxyBinding {
         provider_type: provider_type.to_string(),
         effective_source: "none".to_string(),
         bound_proxy_config_id: None,
+        bound_proxy_pool_id: None,
         effective_proxy_config_name: None,
         effective_proxy_url: None,
         effective_proxy_username: None,
@@ -220,4 +293,23 @@ mod tests {
         assert_eq!(value["traffic_snapshot"]["retention_days"], 7);
         assert_eq!(value["traffic_snapshot"]["totals"]["total_bytes"], 40);
     }
+
+    #[test]
+    fn endpoint_check_health_expires_and_ignores_upstream_rejections() {
+        let check = |reachable, status_code| AdminProxyEndpointCheck {
+            target_url: "https://example.test".to_string(),
+            reachable,
+            status_code,
+            latency_ms: 10,
+            error_message: None,
+            checked_at: 1_000,
+        };
+        assert!(check(false, None).marks_proxy_unhealthy(2_000));
+        assert!(check(true, Some(407)).marks_proxy_unhealthy(2_000));
+        assert!(check(true, Some(502)).marks_proxy_unhealthy(2_000));
+        assert!(!check(true, Some(401)).marks_proxy_unhealthy(2_000));
+        assert!(
+            !check(false, None).marks_proxy_unhealthy(1_000 + PROXY_UNHEALTHY_CHECK_WINDOW_MS + 1)
+        );
+    }
 }
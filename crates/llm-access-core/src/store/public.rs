@@ -56,6 +56,8 @@ pub struct PublicUsageLookupKey {
     pub usage_credit_missing_events: u64,
     /// Last usage timestamp.
     pub last_used_at_ms: Option<i64>,
+    /// Parent key id when this key is a delegated sub-key.
+    pub parent_key_id: Option<String>,
 }
 
 /// Public thank-you card for an approved account contribution.
@@ -0,0 +1,173 @@
+//! Per-key and per-account-group fair queuing policy.
+//!
+//! ```text
+//! every eligible account throttled
+//!        |
+//!        v
+//! pool queue --> interactive tier (drained first)
+//!            --> standard tier    (default)
+//!            --> batch tier       (only when nothing above is waiting)
+//!
+//! within a tier, keys share slots in proportion to `weight`
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+/// Weight used when neither the key nor its account group sets one.
+pub const DEFAULT_QUEUE_WEIGHT: u32 = 1;
+
+/// Largest weight a key or group may configure.
+pub const MAX_QUEUE_WEIGHT: u32 = 1_000;
+
+/// Largest per-key queue depth a key or group may configure.
+pub const MAX_QUEUE_DEPTH_PER_KEY: u32 = 10_000;
+
+/// Scheduling tier of a key while it waits for a saturated account pool.
+#[derive(
+    Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum QueuePriority {
+    /// Latency-sensitive traffic; always served before the lower tiers.
+    Interactive,
+    /// Regular traffic.
+    #[default]
+    Standard,
+    /// Throughput traffic that only soaks up capacity nobody else is waiting
+    /// for.
+    Batch,
+}
+
+impl QueuePriority {
+    /// Stable lowercase label used in metrics and diagnostics.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Interactive => "interactive",
+            Self::Standard => "standard",
+            Self::Batch => "batch",
+        }
+    }
+}
+
+/// Fair queuing settings for one key or account group. Unset fields fall back
+/// to the group, then to the defaults.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct KeyQueuePolicy {
+    /// Share of saturated capacity relative to other waiting keys in the same
+    /// tier.
+    pub weight: Option<u32>,
+    /// Scheduling tier.
+    pub priority: Option<QueuePriority>,
+    /// Requests this key may have waiting at once; further requests get a
+    /// `429` immediately. `None` uses the service-wide limit.
+    pub max_queued_requests: Option<u32>,
+}
+
+impl KeyQueuePolicy {
+    /// Whether the policy sets nothing.
+    pub fn is_empty(&self) -> bool {
+        self.weight.is_none() && self.priority.is_none() && self.max_queued_requests.is_none()
+    }
+
+    /// Check the admin-supplied bounds.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self
+            .weight
+            .is_some_and(|weight| weight == 0 || weight > MAX_QUEUE_WEIGHT)
+        {
+            anyhow::bail!("queue_policy.weight must be between 1 and {MAX_QUEUE_WEIGHT}");
+        }
+        if self
+            .max_queued_requests
+            .is_some_and(|depth| depth == 0 || depth > MAX_QUEUE_DEPTH_PER_KEY)
+        {
+            anyhow::bail!(
+                "queue_policy.max_queued_requests must be between 1 and {MAX_QUEUE_DEPTH_PER_KEY}"
+            );
+        }
+        Ok(())
+    }
+
+    /// Overlay the key's fields on the account group's.
+    pub fn merged(group: Option<&Self>, key: Option<&Self>) -> Option<Self> {
+        let merged = match (group, key) {
+            (None, None) => return None,
+            (Some(policy), None) | (None, Some(policy)) => policy.clone(),
+            (Some(group), Some(key)) => Self {
+                weight: key.weight.or(group.weight),
+                priority: key.priority.or(group.priority),
+                max_queued_requests: key.max_queued_requests.or(group.max_queued_requests),
+            },
+        };
+        (!merged.is_empty()).then_some(merged)
+    }
+
+    /// Weight after defaults.
+    pub fn effective_weight(&self) -> u32 {
+        self.weight
+            .unwrap_or(DEFAULT_QUEUE_WEIGHT)
+            .clamp(1, MAX_QUEUE_WEIGHT)
+    }
+
+    /// Tier after defaults.
+    pub fn effective_priority(&self) -> QueuePriority {
+        self.priority.unwrap_or_default()
+    }
+}
+
+/// Decode a persisted policy, treating absent, unparsable, invalid or empty
+/// policies as "defaults".
+pub fn decode_key_queue_policy(raw: Option<&str>) -> Option<KeyQueuePolicy> {
+    let policy = serde_json::from_str::<KeyQueuePolicy>(raw?.trim()).ok()?;
+    (!policy.is_empty() && policy.validate().is_ok()).then_some(policy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_fields_override_group_fields() {
+        let group = KeyQueuePolicy {
+            weight: Some(4),
+            priority: Some(QueuePriority::Batch),
+            max_queued_requests: Some(50),
+        };
+        let key = KeyQueuePolicy {
+            priority: Some(QueuePriority::Interactive),
+            ..KeyQueuePolicy::default()
+        };
+        let merged = KeyQueuePolicy::merged(Some(&group), Some(&key)).expect("merged policy");
+        assert_eq!(merged.effective_weight(), 4);
+        assert_eq!(merged.effective_priority(), QueuePriority::Interactive);
+        assert_eq!(merged.max_queued_requests, Some(50));
+
+        assert_eq!(KeyQueuePolicy::merged(None, None), None);
+        assert_eq!(
+            KeyQueuePolicy::merged(Some(&KeyQueuePolicy::default()), None),
+            None
+        );
+        assert_eq!(KeyQueuePolicy::default().effective_weight(), DEFAULT_QUEUE_WEIGHT);
+        assert_eq!(KeyQueuePolicy::default().effective_priority(), QueuePriority::Standard);
+    }
+
+    #[test]
+    fn decodes_valid_policies_and_drops_invalid_ones() {
+        let policy = decode_key_queue_policy(Some(r#"{"weight":3,"priority":"batch"}"#))
+            .expect("valid policy");
+        assert_eq!(policy.weight, Some(3));
+        assert_eq!(policy.priority, Some(QueuePriority::Batch));
+
+        assert!(decode_key_queue_policy(None).is_none());
+        assert!(decode_key_queue_policy(Some("{}")).is_none());
+        assert!(decode_key_queue_policy(Some(r#"{"weight":0}"#)).is_none());
+        assert!(decode_key_queue_policy(Some(r#"{"priority":"urgent"}"#)).is_none());
+        assert!(KeyQueuePolicy {
+            max_queued_requests: Some(0),
+            ..KeyQueuePolicy::default()
+        }
+        .validate()
+        .is_err());
+    }
+}
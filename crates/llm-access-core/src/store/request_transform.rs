@@ -0,0 +1,331 @@
+//! Declarative request transform policy for keys and account groups.
+//!
+//! ```text
+//! account group policy ──┐
+//!                        ├── merged (group first, key second)
+//! key policy ────────────┘
+//!        |
+//!        v
+//! operations (set/remove/cap) -> redactions -> system prompt prepend/append
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Most JSON-path operations one policy may carry.
+pub const MAX_REQUEST_TRANSFORM_OPERATIONS: usize = 32;
+/// Most redaction rules one policy may carry.
+pub const MAX_REQUEST_TRANSFORM_REDACTIONS: usize = 32;
+/// Longest accepted redaction pattern, in bytes.
+pub const MAX_REQUEST_TRANSFORM_PATTERN_BYTES: usize = 512;
+/// Longest accepted injected system prompt, in bytes.
+pub const MAX_REQUEST_TRANSFORM_SYSTEM_PROMPT_BYTES: usize = 32 * 1024;
+/// Replacement used when a redaction rule omits one.
+pub const DEFAULT_REQUEST_REDACTION_REPLACEMENT: &str = "[REDACTED]";
+
+fn default_redaction_replacement() -> String {
+    DEFAULT_REQUEST_REDACTION_REPLACEMENT.to_string()
+}
+
+/// Request rewrites applied before a request is sent upstream.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RequestTransformPolicy {
+    /// Text injected ahead of the client's system prompt.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_prompt_prepend: Option<String>,
+    /// Text injected after the client's system prompt.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_prompt_append: Option<String>,
+    /// JSON-path edits, applied in order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub operations: Vec<RequestTransformOperation>,
+    /// Regex redactions over prompt text, applied in order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redactions: Vec<RequestRedactionRule>,
+}
+
+/// One JSON-path edit. Paths look like `reasoning.effort`,
+/// `$.metadata.user_id` or `messages[0].content`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum RequestTransformOperation {
+    /// Force `path` to `value`, creating missing parent objects.
+    Set {
+        /// Target path.
+        path: String,
+        /// Value written at the path.
+        value: Value,
+    },
+    /// Drop `path` when present.
+    Remove {
+        /// Target path.
+        path: String,
+    },
+    /// Lower a numeric `path` to `max` when the client sent a larger value.
+    Cap {
+        /// Target path.
+        path: String,
+        /// Largest value allowed through.
+        max: u64,
+    },
+}
+
+impl RequestTransformOperation {
+    /// Path this operation targets.
+    pub fn path(&self) -> &str {
+        match self {
+            Self::Set {
+                path, ..
+            }
+            | Self::Remove {
+                path,
+            }
+            | Self::Cap {
+                path, ..
+            } => path,
+        }
+    }
+
+    /// Short operation name used in diagnostics.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Set {
+                ..
+            } => "set",
+            Self::Remove {
+                ..
+            } => "remove",
+            Self::Cap {
+                ..
+            } => "cap",
+        }
+    }
+}
+
+/// One regex redaction over prompt text.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RequestRedactionRule {
+    /// Regex matched against prompt text.
+    pub pattern: String,
+    /// Replacement text; supports `$1`-style group references.
+    #[serde(default = "default_redaction_replacement")]
+    pub replacement: String,
+}
+
+/// One parsed path segment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RequestTransformPathSegment {
+    /// Object member.
+    Key(String),
+    /// Array index.
+    Index(usize),
+}
+
+/// Parse a dotted JSON path with optional `$` root and `[n]` indexes.
+pub fn parse_request_transform_path(
+    path: &str,
+) -> anyhow::Result<Vec<RequestTransformPathSegment>> {
+    let trimmed = path.trim();
+    let body = trimmed
+        .strip_prefix("$.")
+        .or_else(|| trimmed.strip_prefix('$'))
+        .unwrap_or(trimmed);
+    if body.is_empty() {
+        anyhow::bail!("request transform path `{path}` is empty");
+    }
+    let mut segments = Vec::new();
+    for part in body.split('.') {
+        let (name, mut rest) = match part.find('[') {
+            Some(index) => part.split_at(index),
+            None => (part, ""),
+        };
+        if name.is_empty() && (segments.is_empty() || rest.is_empty()) {
+            anyhow::bail!("request transform path `{path}` has an empty segment");
+        }
+        if !name.is_empty() {
+            segments.push(RequestTransformPathSegment::Key(name.to_string()));
+        }
+        while !rest.is_empty() {
+            let Some(close) = rest.find(']').filter(|_| rest.starts_with('[')) else {
+                anyhow::bail!("request transform path `{path}` has a malformed index");
+            };
+            let index = rest[1..close]
+                .parse::<usize>()
+                .map_err(|_| anyhow::anyhow!("request transform path `{path}` has a bad index"))?;
+            segments.push(RequestTransformPathSegment::Index(index));
+            rest = &rest[close + 1..];
+        }
+    }
+    Ok(segments)
+}
+
+impl RequestTransformPolicy {
+    /// Whether applying the policy can change a request.
+    pub fn is_empty(&self) -> bool {
+        self.system_prompt_prepend.is_none()
+            && self.system_prompt_append.is_none()
+            && self.operations.is_empty()
+            && self.redactions.is_empty()
+    }
+
+    /// Check the admin-supplied bounds, paths and patterns.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        for (name, prompt) in [
+            ("system_prompt_prepend", &self.system_prompt_prepend),
+            ("system_prompt_append", &self.system_prompt_append),
+        ] {
+            let Some(prompt) = prompt else {
+                continue;
+            };
+            if prompt.trim().is_empty() {
+                anyhow::bail!("request_transform_policy.{name} must not be blank");
+            }
+            if prompt.len() > MAX_REQUEST_TRANSFORM_SYSTEM_PROMPT_BYTES {
+                anyhow::bail!(
+                    "request_transform_policy.{name} must be at most \
+                     {MAX_REQUEST_TRANSFORM_SYSTEM_PROMPT_BYTES} bytes"
+                );
+            }
+        }
+        if self.operations.len() > MAX_REQUEST_TRANSFORM_OPERATIONS {
+            anyhow::bail!(
+                "request_transform_policy.operations must have at most \
+                 {MAX_REQUEST_TRANSFORM_OPERATIONS} entries"
+            );
+        }
+        for operation in &self.operations {
+            parse_request_transform_path(operation.path())?;
+        }
+        if self.redactions.len() > MAX_REQUEST_TRANSFORM_REDACTIONS {
+            anyhow::bail!(
+                "request_transform_policy.redactions must have at most \
+                 {MAX_REQUEST_TRANSFORM_REDACTIONS} entries"
+            );
+        }
+        for rule in &self.redactions {
+            if rule.pattern.is_empty() || rule.pattern.len() > MAX_REQUEST_TRANSFORM_PATTERN_BYTES {
+                anyhow::bail!(
+                    "request_transform_policy.redactions patterns must be 1 to \
+                     {MAX_REQUEST_TRANSFORM_PATTERN_BYTES} bytes"
+                );
+            }
+            regex::Regex::new(&rule.pattern).map_err(|err| {
+                anyhow::anyhow!("invalid redaction pattern `{}`: {err}", rule.pattern)
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Combine an account-group policy with a key policy. Group edits run
+    /// first so the key can override them; injected prompts stack with the
+    /// group text outermost.
+    pub fn merged(group: Option<&Self>, key: Option<&Self>) -> Option<Self> {
+        let merged = match (group, key) {
+            (None, None) => return None,
+            (Some(policy), None) | (None, Some(policy)) => policy.clone(),
+            (Some(group), Some(key)) => Self {
+                system_prompt_prepend: join_prompts(
+                    group.system_prompt_prepend.as_deref(),
+                    key.system_prompt_prepend.as_deref(),
+                ),
+                system_prompt_append: join_prompts(
+                    key.system_prompt_append.as_deref(),
+                    group.system_prompt_append.as_deref(),
+                ),
+                operations: group
+                    .operations
+                    .iter()
+                    .chain(&key.operations)
+                    .cloned()
+                    .collect(),
+                redactions: group
+                    .redactions
+                    .iter()
+                    .chain(&key.redactions)
+                    .cloned()
+                    .collect(),
+            },
+        };
+        (!merged.is_empty()).then_some(merged)
+    }
+}
+
+fn join_prompts(first: Option<&str>, second: Option<&str>) -> Option<String> {
+    match (first, second) {
+        (Some(first), Some(second)) => Some(format!("{first}\n\n{second}")),
+        (Some(value), None) | (None, Some(value)) => Some(value.to_string()),
+        (None, None) => None,
+    }
+}
+
+/// Decode a persisted policy, treating absent, unparsable or empty policies
+/// as "no transform".
+pub fn decode_request_transform_policy(raw: Option<&str>) -> Option<RequestTransformPolicy> {
+    let policy = serde_json::from_str::<RequestTransformPolicy>(raw?.trim()).ok()?;
+    (!policy.is_empty()).then_some(policy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_dotted_and_indexed_paths() {
+        assert_eq!(parse_request_transform_path("$.messages[0].content").expect("path"), vec![
+            RequestTransformPathSegment::Key("messages".to_string()),
+            RequestTransformPathSegment::Index(0),
+            RequestTransformPathSegment::Key("content".to_string()),
+        ]);
+        assert_eq!(parse_request_transform_path("reasoning.effort").expect("path"), vec![
+            RequestTransformPathSegment::Key("reasoning".to_string()),
+            RequestTransformPathSegment::Key("effort".to_string()),
+        ]);
+        assert!(parse_request_transform_path("$").is_err());
+        assert!(parse_request_transform_path("a..b").is_err());
+        assert!(parse_request_transform_path("a[x]").is_err());
+        assert!(parse_request_transform_path("a[1").is_err());
+    }
+
+    #[test]
+    fn validates_patterns_and_merges_group_before_key() {
+        let group = serde_json::from_str::<RequestTransformPolicy>(
+            r#"{"system_prompt_prepend":"group","system_prompt_append":"group tail",
+                "operations":[{"op":"cap","path":"max_tokens","max":1024}],
+                "redactions":[{"pattern":"\\d{3}-\\d{4}"}]}"#,
+        )
+        .expect("group policy");
+        group.validate().expect("valid group policy");
+        assert_eq!(group.redactions[0].replacement, DEFAULT_REQUEST_REDACTION_REPLACEMENT);
+        let key = RequestTransformPolicy {
+            system_prompt_prepend: Some("key".to_string()),
+            system_prompt_append: Some("key tail".to_string()),
+            operations: vec![RequestTransformOperation::Remove {
+                path: "metadata".to_string(),
+            }],
+            ..RequestTransformPolicy::default()
+        };
+
+        let merged = RequestTransformPolicy::merged(Some(&group), Some(&key)).expect("merged");
+        assert_eq!(merged.system_prompt_prepend.as_deref(), Some("group\n\nkey"));
+        assert_eq!(merged.system_prompt_append.as_deref(), Some("key tail\n\ngroup tail"));
+        assert_eq!(
+            merged
+                .operations
+                .iter()
+                .map(RequestTransformOperation::kind)
+                .collect::<Vec<_>>(),
+            vec!["cap", "remove"]
+        );
+        assert!(RequestTransformPolicy::merged(None, Some(&RequestTransformPolicy::default()))
+            .is_none());
+
+        let invalid = RequestTransformPolicy {
+            redactions: vec![RequestRedactionRule {
+                pattern: "(".to_string(),
+                replacement: String::new(),
+            }],
+            ..RequestTransformPolicy::default()
+        };
+        assert!(invalid.validate().is_err());
+    }
+}
@@ -0,0 +1,111 @@
+//! Per-key response cache policy.
+//!
+//! ```text
+//! key.response_cache_policy
+//!        |
+//!        +-- None / enabled=false --> every request goes upstream
+//!        |
+//!        +-- enabled --------------> identical requests within ttl_seconds
+//!                                    replay the stored response and bill
+//!                                    original_billable * hit_billable_ratio
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+/// Entry lifetime used when a policy omits `ttl_seconds`.
+pub const DEFAULT_RESPONSE_CACHE_TTL_SECONDS: u64 = 10 * 60;
+
+/// Longest entry lifetime a key may configure.
+pub const MAX_RESPONSE_CACHE_TTL_SECONDS: u64 = 7 * 24 * 60 * 60;
+
+const fn default_response_cache_ttl_seconds() -> u64 {
+    DEFAULT_RESPONSE_CACHE_TTL_SECONDS
+}
+
+/// Opt-in response cache settings for one managed key.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct KeyResponseCachePolicy {
+    /// Whether identical requests may be answered from the cache.
+    #[serde(default)]
+    pub enabled: bool,
+    /// How long a stored response stays eligible for replay.
+    #[serde(default = "default_response_cache_ttl_seconds")]
+    pub ttl_seconds: u64,
+    /// Fraction of the original billable tokens charged for a cache hit.
+    /// `0.0` makes hits free, `1.0` bills them like the original request.
+    #[serde(default)]
+    pub hit_billable_ratio: f64,
+}
+
+impl Default for KeyResponseCachePolicy {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            ttl_seconds: DEFAULT_RESPONSE_CACHE_TTL_SECONDS,
+            hit_billable_ratio: 0.0,
+        }
+    }
+}
+
+impl KeyResponseCachePolicy {
+    /// Check the admin-supplied bounds.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.ttl_seconds == 0 || self.ttl_seconds > MAX_RESPONSE_CACHE_TTL_SECONDS {
+            anyhow::bail!(
+                "response_cache_policy.ttl_seconds must be between 1 and \
+                 {MAX_RESPONSE_CACHE_TTL_SECONDS}"
+            );
+        }
+        if !self.hit_billable_ratio.is_finite() || !(0.0..=1.0).contains(&self.hit_billable_ratio) {
+            anyhow::bail!("response_cache_policy.hit_billable_ratio must be between 0 and 1");
+        }
+        Ok(())
+    }
+
+    /// Billable tokens charged when replaying a response whose original
+    /// request billed `original` tokens.
+    pub fn hit_billable_tokens(&self, original: u64) -> u64 {
+        (original as f64 * self.hit_billable_ratio).round() as u64
+    }
+}
+
+/// Decode a persisted policy, treating absent, unparsable or disabled
+/// policies as "no cache".
+pub fn enabled_response_cache_policy(raw: Option<&str>) -> Option<KeyResponseCachePolicy> {
+    let policy = serde_json::from_str::<KeyResponseCachePolicy>(raw?.trim()).ok()?;
+    (policy.enabled && policy.validate().is_ok()).then_some(policy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_enabled_policies_with_defaults_and_rejects_invalid_ones() {
+        let policy =
+            enabled_response_cache_policy(Some(r#"{"enabled":true}"#)).expect("enabled policy");
+        assert_eq!(policy.ttl_seconds, DEFAULT_RESPONSE_CACHE_TTL_SECONDS);
+        assert_eq!(policy.hit_billable_tokens(1_000), 0);
+
+        assert!(enabled_response_cache_policy(None).is_none());
+        assert!(enabled_response_cache_policy(Some(r#"{"enabled":false}"#)).is_none());
+        assert!(
+            enabled_response_cache_policy(Some(r#"{"enabled":true,"ttl_seconds":0}"#)).is_none()
+        );
+        assert!(enabled_response_cache_policy(Some(
+            r#"{"enabled":true,"hit_billable_ratio":1.5}"#
+        ))
+        .is_none());
+    }
+
+    #[test]
+    fn scales_hit_billable_tokens_by_ratio() {
+        let policy = KeyResponseCachePolicy {
+            enabled: true,
+            ttl_seconds: 60,
+            hit_billable_ratio: 0.25,
+        };
+        assert_eq!(policy.hit_billable_tokens(1_000), 250);
+        assert_eq!(policy.hit_billable_tokens(0), 0);
+    }
+}
@@ -26,6 +26,10 @@ pub struct AuthenticatedKey {
     pub quota_billable_limit: i64,
     /// Billable usage already consumed.
     pub billable_tokens_used: i64,
+    /// Remaining billable budget of the parent key when this is a sub-key.
+    pub parent_remaining_billable: Option<i64>,
+    /// Parent key id when this is a sub-key.
+    pub parent_key_id: Option<String>,
 }
 
 /// Resolved proxy settings for one upstream provider request.
@@ -315,9 +319,17 @@ pub struct ProviderKiroAuthUpdate {
 
 impl AuthenticatedKey {
     /// Remaining billable token budget available to this key.
+    ///
+    /// A sub-key is also capped by what is left on its parent, since its usage
+    /// rolls up into the parent's quota.
     pub fn remaining_billable(&self) -> i64 {
-        self.quota_billable_limit
-            .saturating_sub(self.billable_tokens_used)
+        let own = self
+            .quota_billable_limit
+            .saturating_sub(self.billable_tokens_used);
+        match self.parent_remaining_billable {
+            Some(parent) => own.min(parent),
+            None => own,
+        }
     }
 }
 
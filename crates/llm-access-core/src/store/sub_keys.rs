@@ -0,0 +1,68 @@
+//! Delegated sub-keys: child keys minted by a key holder from a slice of the
+//! parent's remaining quota.
+//!
+//! Trees are one level deep. A sub-key inherits the parent's provider and
+//! route configuration, its usage rolls up into the parent's quota, and it is
+//! rejected once either its own slice or the parent's budget runs out.
+
+/// Maximum number of sub-keys (active or revoked) one parent may hold.
+pub const MAX_SUB_KEYS_PER_PARENT: usize = 64;
+
+/// New sub-key row after request validation and secret generation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewSubKey {
+    /// Key id.
+    pub id: String,
+    /// Human-readable name.
+    pub name: String,
+    /// Plaintext secret.
+    pub secret: String,
+    /// SHA-256 secret hash.
+    pub key_hash: String,
+    /// Billable quota carved out of the parent's remaining budget.
+    pub quota_billable_limit: u64,
+    /// Per-key request concurrency cap.
+    pub request_max_concurrency: Option<u64>,
+    /// Per-key request pacing interval.
+    pub request_min_start_interval_ms: Option<u64>,
+    /// Creation timestamp.
+    pub created_at_ms: i64,
+}
+
+/// A sub-key asked for more quota than the parent can still delegate.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "sub-key quota {requested} exceeds the {available} billable tokens the parent can delegate"
+)]
+pub struct SubKeyQuotaExceeded {
+    /// Requested sub-key quota.
+    pub requested: u64,
+    /// Quota still available for delegation.
+    pub available: u64,
+}
+
+/// Quota a parent can still hand out: its own remaining budget minus what its
+/// active sub-keys have not spent yet.
+pub fn delegable_sub_key_quota(
+    parent_remaining_billable: i64,
+    active_children_remaining_billable: i64,
+) -> u64 {
+    u64::try_from(
+        parent_remaining_billable.saturating_sub(active_children_remaining_billable.max(0)),
+    )
+    .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::delegable_sub_key_quota;
+
+    #[test]
+    fn delegable_quota_subtracts_unspent_child_budgets() {
+        assert_eq!(delegable_sub_key_quota(1_000, 300), 700);
+        assert_eq!(delegable_sub_key_quota(1_000, 0), 1_000);
+        assert_eq!(delegable_sub_key_quota(200, 300), 0);
+        assert_eq!(delegable_sub_key_quota(-50, 0), 0);
+        assert_eq!(delegable_sub_key_quota(100, -20), 100);
+    }
+}
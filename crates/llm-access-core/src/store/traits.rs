@@ -12,6 +12,7 @@ use super::{
         AnthropicUpstreamChannelUsageDelta, NewAdminAnthropicUpstreamChannel,
         ProviderAnthropicUpstreamResolution, ProviderAnthropicUpstreamRoute,
     },
+    batch::{BatchItemOutcome, BatchItemResult, BatchJob, ClaimedBatchItem, NewBatchJob},
     codex_account::{
         apply_admin_codex_account_query, summarize_admin_accounts, AdminCodexAccount,
         AdminCodexAccountPageQuery, AdminCodexAccountPatch, AdminCodexAccountsPage,
@@ -24,6 +25,10 @@ use super::{
         AdminAccountGroup, AdminAccountGroupOption, AdminAccountGroupPatch, AdminAccountGroupsPage,
         NewAdminAccountGroup,
     },
+    key_portal::{
+        ConfirmedKeyNotifyEmail, KeyPortalSettings, KeyPortalSettingsPatch, KeyQuotaAlertCandidate,
+        KeySecretRotation, NewKeyNotifyEmailConfirmation,
+    },
     keys::{
         apply_admin_key_query, summarize_admin_keys, AdminKey, AdminKeyPageQuery, AdminKeyPatch,
         AdminKeysPage, AdminPageRequest, NewAdminKey,
@@ -33,9 +38,17 @@ use super::{
         AdminKiroBalanceView, AdminKiroStatusCacheUpdate, KiroStatusRefreshTarget,
         NewAdminKiroAccount,
     },
+    moderation::{AdminModerationEvent, AdminModerationEventsPage, NewModerationEvent},
+    openai_upstream::{
+        AdminOpenAiUpstreamChannel, AdminOpenAiUpstreamChannelPatch,
+        AdminOpenAiUpstreamModelsStatusUpdate, AdminOpenAiUpstreamProbeTarget,
+        NewAdminOpenAiUpstreamChannel, OpenAiUpstreamChannelUsageDelta,
+        ProviderOpenAiUpstreamResolution,
+    },
     proxy::{
         AdminProxyBinding, AdminProxyConfig, AdminProxyConfigPatch, AdminProxyEndpointCheckUpdate,
-        AdminProxyTrafficSnapshot, NewAdminProxyConfig,
+        AdminProxyPool, AdminProxyPoolPatch, AdminProxyTrafficSnapshot, NewAdminProxyConfig,
+        NewAdminProxyPool,
     },
     public::{
         AdminAccountContributionRequest, AdminAccountContributionRequestsPage,
@@ -48,6 +61,7 @@ use super::{
         AuthenticatedKey, ProviderCodexAuthUpdate, ProviderCodexRoute, ProviderKiroAuthUpdate,
         ProviderKiroRoute,
     },
+    sub_keys::NewSubKey,
     usage::{
         AdminLegacyKiroProxyMigration, KiroLatencyRankingQuery, KiroLatencyRankingSnapshot,
         ProxyTrafficQuery, ProxyTrafficSnapshot, UsageChartPoint, UsageEventPage, UsageEventQuery,
@@ -89,6 +103,14 @@ pub trait ControlStore: Send + Sync {
         channel_name: &str,
         delta: AnthropicUpstreamChannelUsageDelta,
     ) -> anyhow::Result<()>;
+
+    /// Increment OpenAI-compatible upstream channel counters observed on the
+    /// hot path.
+    async fn record_openai_upstream_channel_usage(
+        &self,
+        channel_name: &str,
+        delta: OpenAiUpstreamChannelUsageDelta,
+    ) -> anyhow::Result<()>;
 }
 
 /// Provider route/account resolution used by data-plane dispatch.
@@ -161,6 +183,56 @@ pub trait ProviderRouteStore: Send + Sync {
         Ok(super::default_anthropic_upstream_pool_mode())
     }
 
+    /// Resolve the key-level OpenAI-compatible pool mode and channel
+    /// candidates for one Codex key.
+    async fn resolve_openai_upstream_resolution(
+        &self,
+        _key: &AuthenticatedKey,
+    ) -> anyhow::Result<ProviderOpenAiUpstreamResolution> {
+        Ok(ProviderOpenAiUpstreamResolution::disabled())
+    }
+
+    /// Resolve the per-key response cache policy. `None` means every request
+    /// for this key goes upstream.
+    async fn resolve_response_cache_policy(
+        &self,
+        _key: &AuthenticatedKey,
+    ) -> anyhow::Result<Option<super::KeyResponseCachePolicy>> {
+        Ok(None)
+    }
+
+    /// Resolve the merged account-group and key request transform policy.
+    /// `None` means requests are forwarded as the client sent them.
+    async fn resolve_request_transform_policy(
+        &self,
+        _key: &AuthenticatedKey,
+    ) -> anyhow::Result<Option<super::RequestTransformPolicy>> {
+        Ok(None)
+    }
+
+    /// Resolve the per-key moderation policy. `None` means requests are not
+    /// screened.
+    async fn resolve_moderation_policy(
+        &self,
+        _key: &AuthenticatedKey,
+    ) -> anyhow::Result<Option<super::KeyModerationPolicy>> {
+        Ok(None)
+    }
+
+    /// Resolve the key's fair queuing policy overlaid on its account group's.
+    /// `None` means the key queues with the default weight and tier.
+    async fn resolve_queue_policy(
+        &self,
+        _key: &AuthenticatedKey,
+    ) -> anyhow::Result<Option<super::KeyQueuePolicy>> {
+        Ok(None)
+    }
+
+    /// Append one moderation hit to the review queue.
+    async fn record_moderation_event(&self, _event: NewModerationEvent) -> anyhow::Result<()> {
+        Ok(())
+    }
+
     /// Reload one active Kiro account route by account name.
     async fn resolve_kiro_account_route(
         &self,
@@ -202,6 +274,18 @@ pub trait ProviderRouteStore: Send + Sync {
     ) -> anyhow::Result<()> {
         Ok(())
     }
+
+    /// Report a transport-level failure of an upstream request sent through
+    /// the account's effective proxy. Stores that track proxy health may
+    /// take a repeatedly failing proxy out of pool selection.
+    async fn report_proxy_transport_failure(
+        &self,
+        _provider_type: &str,
+        _account_name: &str,
+        _error_message: &str,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
 }
 
 /// Admin direct Anthropic upstream channel management queries used by the Kiro
@@ -279,6 +363,125 @@ pub trait AdminAnthropicUpstreamStore: Send + Sync {
     }
 }
 
+/// Admin OpenAI-compatible upstream channel management queries used by the
+/// Codex gateway surface.
+#[async_trait]
+pub trait AdminOpenAiUpstreamStore: Send + Sync {
+    /// List all OpenAI-compatible upstream channels.
+    async fn list_admin_openai_upstream_channels(
+        &self,
+    ) -> anyhow::Result<Vec<AdminOpenAiUpstreamChannel>>;
+
+    /// Create one OpenAI-compatible upstream channel.
+    async fn create_admin_openai_upstream_channel(
+        &self,
+        channel: NewAdminOpenAiUpstreamChannel,
+    ) -> anyhow::Result<AdminOpenAiUpstreamChannel>;
+
+    /// Patch one OpenAI-compatible upstream channel.
+    async fn patch_admin_openai_upstream_channel(
+        &self,
+        name: &str,
+        patch: AdminOpenAiUpstreamChannelPatch,
+    ) -> anyhow::Result<Option<AdminOpenAiUpstreamChannel>>;
+
+    /// Delete one OpenAI-compatible upstream channel.
+    async fn delete_admin_openai_upstream_channel(
+        &self,
+        name: &str,
+    ) -> anyhow::Result<Option<AdminOpenAiUpstreamChannel>>;
+
+    /// Load an OpenAI-compatible channel for an admin probe, including secret
+    /// material. This must never be returned directly to frontend callers.
+    async fn load_admin_openai_upstream_probe_target(
+        &self,
+        _name: &str,
+    ) -> anyhow::Result<Option<AdminOpenAiUpstreamProbeTarget>> {
+        Ok(None)
+    }
+
+    /// Persist latest `/models` refresh state.
+    async fn save_admin_openai_upstream_models_status(
+        &self,
+        _name: &str,
+        _update: AdminOpenAiUpstreamModelsStatusUpdate,
+    ) -> anyhow::Result<Option<AdminOpenAiUpstreamChannel>> {
+        Ok(None)
+    }
+}
+
+/// Self-service sub-key management scoped to one parent key.
+#[async_trait]
+pub trait SubKeyStore: Send + Sync {
+    /// List the parent's sub-keys, revoked ones included.
+    async fn list_sub_keys(&self, parent_key_id: &str) -> anyhow::Result<Vec<AdminKey>>;
+
+    /// Mint one sub-key from the parent's remaining quota. Fails with
+    /// [`super::SubKeyQuotaExceeded`] when the parent cannot cover the slice.
+    async fn create_sub_key(&self, parent_key_id: &str, key: NewSubKey)
+        -> anyhow::Result<AdminKey>;
+
+    /// Disable one sub-key owned by the parent. Returns `None` when the key
+    /// does not exist or belongs to another parent.
+    async fn revoke_sub_key(
+        &self,
+        parent_key_id: &str,
+        key_id: &str,
+    ) -> anyhow::Result<Option<AdminKey>>;
+}
+
+/// Self-service portal settings and secret rotation for one key, driven by
+/// the key holder.
+#[async_trait]
+pub trait KeyPortalStore: Send + Sync {
+    /// Load the key's portal settings; defaults when none were saved.
+    async fn get_key_portal_settings(&self, key_id: &str) -> anyhow::Result<KeyPortalSettings>;
+
+    /// Apply a partial settings update.
+    async fn patch_key_portal_settings(
+        &self,
+        key_id: &str,
+        patch: KeyPortalSettingsPatch,
+    ) -> anyhow::Result<KeyPortalSettings>;
+
+    /// Replace the key's secret. The replaced secret keeps authenticating
+    /// until `previous_secret_expires_at_ms`; a secret still in an earlier
+    /// grace window stops at once.
+    async fn rotate_key_secret(
+        &self,
+        key_id: &str,
+        rotation: KeySecretRotation,
+    ) -> anyhow::Result<Option<AdminKey>>;
+
+    /// Record a confirmation link for a quota alert recipient. Returns
+    /// `false`, and stores nothing, when the address is already confirmed
+    /// for the key or was sent a link that is still valid.
+    async fn request_key_notify_email_confirmation(
+        &self,
+        confirmation: NewKeyNotifyEmailConfirmation,
+    ) -> anyhow::Result<bool>;
+
+    /// Confirm the recipient whose link carries `token_hash`, if the link is
+    /// still valid.
+    async fn confirm_key_notify_email(
+        &self,
+        token_hash: &str,
+        confirmed_at_ms: i64,
+    ) -> anyhow::Result<Option<ConfirmedKeyNotifyEmail>>;
+
+    /// Active keys with at least one confirmed alert recipient and threshold.
+    async fn list_key_quota_alert_candidates(&self) -> anyhow::Result<Vec<KeyQuotaAlertCandidate>>;
+
+    /// Move the key's alert level from `expected` to `level`. Returns `false`
+    /// when another node got there first.
+    async fn set_key_quota_alert_level(
+        &self,
+        key_id: &str,
+        expected: Option<u8>,
+        level: Option<u8>,
+    ) -> anyhow::Result<bool>;
+}
+
 /// Public read-only queries used by unauthenticated public endpoints.
 #[async_trait]
 pub trait PublicAccessStore: Send + Sync {
@@ -610,10 +813,55 @@ pub trait AdminProxyStore: Send + Sync {
         proxy_config_id: Option<String>,
     ) -> anyhow::Result<AdminProxyBinding>;
 
+    /// Bind one provider to a proxy pool, replacing any single-config
+    /// binding, or clear the pool binding.
+    async fn update_admin_proxy_pool_binding(
+        &self,
+        _provider_type: &str,
+        _proxy_pool_id: Option<String>,
+    ) -> anyhow::Result<AdminProxyBinding> {
+        anyhow::bail!("proxy pools are not supported by this store")
+    }
+
     /// Import legacy embedded Kiro proxy fields into shared proxy configs.
     async fn import_legacy_kiro_proxy_configs(
         &self,
     ) -> anyhow::Result<AdminLegacyKiroProxyMigration>;
+
+    /// List all proxy pools.
+    async fn list_admin_proxy_pools(&self) -> anyhow::Result<Vec<AdminProxyPool>> {
+        Ok(Vec::new())
+    }
+
+    /// Load one proxy pool by id.
+    async fn get_admin_proxy_pool(&self, _pool_id: &str) -> anyhow::Result<Option<AdminProxyPool>> {
+        Ok(None)
+    }
+
+    /// Create one proxy pool.
+    async fn create_admin_proxy_pool(
+        &self,
+        _pool: NewAdminProxyPool,
+    ) -> anyhow::Result<AdminProxyPool> {
+        anyhow::bail!("proxy pools are not supported by this store")
+    }
+
+    /// Patch one proxy pool by id.
+    async fn patch_admin_proxy_pool(
+        &self,
+        _pool_id: &str,
+        _patch: AdminProxyPoolPatch,
+    ) -> anyhow::Result<Option<AdminProxyPool>> {
+        anyhow::bail!("proxy pools are not supported by this store")
+    }
+
+    /// Delete one proxy pool by id and return the removed row.
+    async fn delete_admin_proxy_pool(
+        &self,
+        _pool_id: &str,
+    ) -> anyhow::Result<Option<AdminProxyPool>> {
+        anyhow::bail!("proxy pools are not supported by this store")
+    }
 }
 
 /// Admin Codex account management queries used by the current frontend.
@@ -919,6 +1167,87 @@ pub trait AdminReviewQueueStore: Send + Sync {
 
     /// Delete one sponsor request from admin review/history.
     async fn delete_admin_sponsor_request(&self, request_id: &str) -> anyhow::Result<bool>;
+
+    /// List moderation review queue events.
+    async fn list_admin_moderation_events(
+        &self,
+        query: AdminReviewQueueQuery,
+    ) -> anyhow::Result<AdminModerationEventsPage>;
+
+    /// Mark one moderation event `confirmed` or `dismissed`.
+    async fn review_admin_moderation_event(
+        &self,
+        event_id: &str,
+        status: &str,
+        action: AdminReviewQueueAction,
+    ) -> anyhow::Result<Option<AdminModerationEvent>>;
+}
+
+/// Persistent queue behind the batch endpoints and the batch worker.
+#[async_trait]
+pub trait BatchStore: Send + Sync {
+    /// Persist a batch and all of its items as pending.
+    async fn create_batch_job(&self, job: NewBatchJob) -> anyhow::Result<BatchJob>;
+
+    /// List a key's batches, newest first.
+    async fn list_batch_jobs(&self, key_id: &str, limit: usize) -> anyhow::Result<Vec<BatchJob>>;
+
+    /// Load one batch owned by `key_id`.
+    async fn get_batch_job(&self, key_id: &str, batch_id: &str)
+        -> anyhow::Result<Option<BatchJob>>;
+
+    /// Cancel pending items and move the batch to `cancelling`, or straight to
+    /// `cancelled` when nothing is running. Finished batches are returned
+    /// unchanged.
+    async fn cancel_batch_job(
+        &self,
+        key_id: &str,
+        batch_id: &str,
+        now_ms: i64,
+    ) -> anyhow::Result<Option<BatchJob>>;
+
+    /// Item outcomes of one batch owned by `key_id`, in input order.
+    async fn list_batch_item_results(
+        &self,
+        key_id: &str,
+        batch_id: &str,
+    ) -> anyhow::Result<Vec<BatchItemResult>>;
+
+    /// Claim up to `limit` runnable items: pending items that are due and
+    /// running items whose claim is older than `lease_expired_before_ms`.
+    async fn claim_batch_items(
+        &self,
+        limit: usize,
+        now_ms: i64,
+        lease_expired_before_ms: i64,
+    ) -> anyhow::Result<Vec<ClaimedBatchItem>>;
+
+    /// Record the outcome of a claimed item and finish its batch when it was
+    /// the last one.
+    async fn complete_batch_item(
+        &self,
+        batch_id: &str,
+        item_index: i64,
+        outcome: BatchItemOutcome,
+    ) -> anyhow::Result<()>;
+
+    /// Return a claimed item to the queue, runnable again at `retry_at_ms`.
+    async fn release_batch_item(
+        &self,
+        batch_id: &str,
+        item_index: i64,
+        retry_at_ms: i64,
+    ) -> anyhow::Result<()>;
+
+    /// Expire pending items of batches past their completion window, drop
+    /// running items whose claim is older than `lease_expired_before_ms` in
+    /// overdue or cancelling batches, and finish batches left with nothing to
+    /// run. Returns the number of batches finished.
+    async fn expire_batch_jobs(
+        &self,
+        now_ms: i64,
+        lease_expired_before_ms: i64,
+    ) -> anyhow::Result<u64>;
 }
 
 /// Public read-only queries for compatibility status endpoints.
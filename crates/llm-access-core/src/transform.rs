@@ -0,0 +1,556 @@
+//! Apply a [`RequestTransformPolicy`] to a client request body.
+//!
+//! Providers call [`CompiledRequestTransform::apply`] on the client-shaped
+//! JSON body before their own normalization, so operator paths match what
+//! clients send. The returned labels are recorded in
+//! `routing_diagnostics_json` under `request_transforms`.
+
+use regex::Regex;
+use serde_json::{Map, Value};
+
+use crate::store::{
+    parse_request_transform_path, RequestTransformOperation, RequestTransformPathSegment,
+    RequestTransformPolicy,
+};
+
+/// Top-level body fields that carry prompt text.
+pub(crate) const PROMPT_ROOT_FIELDS: &[&str] = &["messages", "input", "system", "instructions"];
+/// Nested member names whose string values are prompt text.
+pub(crate) const PROMPT_TEXT_FIELDS: &[&str] = &["content", "text", "output", "input"];
+
+/// Wire shape of the body being transformed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestTransformDialect {
+    /// OpenAI `/v1/chat/completions`: system text lives in leading
+    /// `system`/`developer` messages.
+    OpenAiChat,
+    /// OpenAI `/v1/responses`: system text is injected as leading
+    /// `developer` input items.
+    Responses,
+    /// Anthropic `/v1/messages`: system text lives in `system`.
+    AnthropicMessages,
+}
+
+enum CompiledOperation {
+    Set { label: String, path: Vec<RequestTransformPathSegment>, value: Value },
+    Remove { label: String, path: Vec<RequestTransformPathSegment> },
+    Cap { label: String, path: Vec<RequestTransformPathSegment>, max: u64 },
+}
+
+/// A policy with its paths parsed and patterns compiled.
+pub struct CompiledRequestTransform {
+    operations: Vec<CompiledOperation>,
+    redactions: Vec<(Regex, String)>,
+    system_prompt_prepend: Option<String>,
+    system_prompt_append: Option<String>,
+}
+
+impl CompiledRequestTransform {
+    /// Parse and compile `policy`.
+    pub fn compile(policy: &RequestTransformPolicy) -> anyhow::Result<Self> {
+        let operations = policy
+            .operations
+            .iter()
+            .map(|operation| {
+                let path = parse_request_transform_path(operation.path())?;
+                let label = format!("{}:{}", operation.kind(), operation.path().trim());
+                Ok(match operation {
+                    RequestTransformOperation::Set {
+                        value, ..
+                    } => CompiledOperation::Set {
+                        label,
+                        path,
+                        value: value.clone(),
+                    },
+                    RequestTransformOperation::Remove {
+                        ..
+                    } => CompiledOperation::Remove {
+                        label,
+                        path,
+                    },
+                    RequestTransformOperation::Cap {
+                        max, ..
+                    } => CompiledOperation::Cap {
+                        label,
+                        path,
+                        max: *max,
+                    },
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let redactions = policy
+            .redactions
+            .iter()
+            .map(|rule| Ok((Regex::new(&rule.pattern)?, rule.replacement.clone())))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(Self {
+            operations,
+            redactions,
+            system_prompt_prepend: policy.system_prompt_prepend.clone(),
+            system_prompt_append: policy.system_prompt_append.clone(),
+        })
+    }
+
+    /// Rewrite `body` in place and return one label per transform that
+    /// changed it. Non-object bodies are left untouched.
+    pub fn apply(&self, dialect: RequestTransformDialect, body: &mut Value) -> Vec<String> {
+        let mut applied = Vec::new();
+        let Some(root) = body.as_object_mut() else {
+            return applied;
+        };
+        for operation in &self.operations {
+            let (label, changed) = match operation {
+                CompiledOperation::Set {
+                    label,
+                    path,
+                    value,
+                } => (label, set_path(root, path, value.clone())),
+                CompiledOperation::Remove {
+                    label,
+                    path,
+                } => (label, remove_path(root, path)),
+                CompiledOperation::Cap {
+                    label,
+                    path,
+                    max,
+                } => (label, cap_path(root, path, *max)),
+            };
+            if changed {
+                applied.push(label.clone());
+            }
+        }
+        for (index, (pattern, replacement)) in self.redactions.iter().enumerate() {
+            let mut count = 0_usize;
+            for field in PROMPT_ROOT_FIELDS {
+                if let Some(value) = root.get_mut(*field) {
+                    count += redact_prompt_value(value, pattern, replacement);
+                }
+            }
+            if count > 0 {
+                applied.push(format!("redact:{index}={count}"));
+            }
+        }
+        if let Some(prompt) = self.system_prompt_prepend.as_deref() {
+            inject_system_prompt(root, dialect, prompt, SystemPromptPosition::Prepend);
+            applied.push("system_prompt_prepend".to_string());
+        }
+        if let Some(prompt) = self.system_prompt_append.as_deref() {
+            inject_system_prompt(root, dialect, prompt, SystemPromptPosition::Append);
+            applied.push("system_prompt_append".to_string());
+        }
+        applied
+    }
+}
+
+fn set_path(
+    root: &mut Map<String, Value>,
+    path: &[RequestTransformPathSegment],
+    value: Value,
+) -> bool {
+    let Some((RequestTransformPathSegment::Key(key), rest)) = path.split_first() else {
+        return false;
+    };
+    if !path_is_settable(root.get(key), rest) {
+        return false;
+    }
+    set_in_value(root.entry(key.clone()).or_insert(Value::Null), rest, value)
+}
+
+/// Whether `path` can be written below `value` without creating arrays:
+/// missing members may only be followed by object keys.
+fn path_is_settable(value: Option<&Value>, path: &[RequestTransformPathSegment]) -> bool {
+    let Some((segment, rest)) = path.split_first() else {
+        return true;
+    };
+    match (value, segment) {
+        (None | Some(Value::Null), _) => path
+            .iter()
+            .all(|segment| matches!(segment, RequestTransformPathSegment::Key(_))),
+        (Some(Value::Object(object)), RequestTransformPathSegment::Key(key)) => {
+            path_is_settable(object.get(key), rest)
+        },
+        (Some(Value::Array(items)), RequestTransformPathSegment::Index(index)) => items
+            .get(*index)
+            .is_some_and(|item| path_is_settable(Some(item), rest)),
+        _ => false,
+    }
+}
+
+fn set_in_value(slot: &mut Value, path: &[RequestTransformPathSegment], value: Value) -> bool {
+    let Some((segment, rest)) = path.split_first() else {
+        if *slot == value {
+            return false;
+        }
+        *slot = value;
+        return true;
+    };
+    if slot.is_null() {
+        *slot = Value::Object(Map::new());
+    }
+    let child = match (slot, segment) {
+        (Value::Object(object), RequestTransformPathSegment::Key(key)) => {
+            object.entry(key.clone()).or_insert(Value::Null)
+        },
+        (Value::Array(items), RequestTransformPathSegment::Index(index)) => {
+            match items.get_mut(*index) {
+                Some(item) => item,
+                None => return false,
+            }
+        },
+        _ => return false,
+    };
+    set_in_value(child, rest, value)
+}
+
+fn lookup_mut<'a>(
+    root: &'a mut Map<String, Value>,
+    path: &[RequestTransformPathSegment],
+) -> Option<&'a mut Value> {
+    let (first, rest) = path.split_first()?;
+    let RequestTransformPathSegment::Key(key) = first else {
+        return None;
+    };
+    let mut current = root.get_mut(key)?;
+    for segment in rest {
+        current = match segment {
+            RequestTransformPathSegment::Key(key) => current.as_object_mut()?.get_mut(key)?,
+            RequestTransformPathSegment::Index(index) => current.as_array_mut()?.get_mut(*index)?,
+        };
+    }
+    Some(current)
+}
+
+fn remove_path(root: &mut Map<String, Value>, path: &[RequestTransformPathSegment]) -> bool {
+    let Some((last, parents)) = path.split_last() else {
+        return false;
+    };
+    if parents.is_empty() {
+        return match last {
+            RequestTransformPathSegment::Key(key) => root.remove(key).is_some(),
+            RequestTransformPathSegment::Index(_) => false,
+        };
+    }
+    match (lookup_mut(root, parents), last) {
+        (Some(Value::Object(object)), RequestTransformPathSegment::Key(key)) => {
+            object.remove(key).is_some()
+        },
+        (Some(Value::Array(items)), RequestTransformPathSegment::Index(index))
+            if *index < items.len() =>
+        {
+            items.remove(*index);
+            true
+        },
+        _ => false,
+    }
+}
+
+fn cap_path(root: &mut Map<String, Value>, path: &[RequestTransformPathSegment], max: u64) -> bool {
+    let Some(value) = lookup_mut(root, path) else {
+        return false;
+    };
+    let exceeds = match value {
+        Value::Number(number) => match number.as_u64() {
+            Some(current) => current > max,
+            None => number.as_f64().is_some_and(|current| current > max as f64),
+        },
+        _ => false,
+    };
+    if exceeds {
+        *value = Value::from(max);
+    }
+    exceeds
+}
+
+fn redact_text(text: &mut String, pattern: &Regex, replacement: &str) -> usize {
+    let count = pattern.find_iter(text).count();
+    if count > 0 {
+        *text = pattern.replace_all(text, replacement).into_owned();
+    }
+    count
+}
+
+/// Redact every prompt string under a root prompt field. Strings count as
+/// prompt text when they sit directly in the root field, in an array under
+/// it, or under one of [`PROMPT_TEXT_FIELDS`].
+fn redact_prompt_value(value: &mut Value, pattern: &Regex, replacement: &str) -> usize {
+    match value {
+        Value::String(text) => redact_text(text, pattern, replacement),
+        Value::Array(items) => items
+            .iter_mut()
+            .map(|item| redact_prompt_value(item, pattern, replacement))
+            .sum(),
+        Value::Object(object) => object
+            .iter_mut()
+            .filter(|(key, _)| PROMPT_TEXT_FIELDS.contains(&key.as_str()))
+            .map(|(_, item)| redact_prompt_value(item, pattern, replacement))
+            .sum(),
+        _ => 0,
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SystemPromptPosition {
+    Prepend,
+    Append,
+}
+
+fn join_text(existing: &str, prompt: &str, position: SystemPromptPosition) -> String {
+    match position {
+        SystemPromptPosition::Prepend => format!("{prompt}\n\n{existing}"),
+        SystemPromptPosition::Append => format!("{existing}\n\n{prompt}"),
+    }
+}
+
+fn inject_system_prompt(
+    root: &mut Map<String, Value>,
+    dialect: RequestTransformDialect,
+    prompt: &str,
+    position: SystemPromptPosition,
+) {
+    match dialect {
+        RequestTransformDialect::AnthropicMessages => {
+            inject_anthropic_system_prompt(root, prompt, position)
+        },
+        RequestTransformDialect::OpenAiChat => inject_chat_system_prompt(root, prompt, position),
+        RequestTransformDialect::Responses => {
+            inject_responses_system_prompt(root, prompt, position)
+        },
+    }
+}
+
+fn inject_anthropic_system_prompt(
+    root: &mut Map<String, Value>,
+    prompt: &str,
+    position: SystemPromptPosition,
+) {
+    match root.get_mut("system") {
+        Some(Value::String(existing)) if !existing.is_empty() => {
+            *existing = join_text(existing, prompt, position);
+        },
+        Some(Value::Array(blocks)) if !blocks.is_empty() => {
+            let block = serde_json::json!({ "type": "text", "text": prompt });
+            match position {
+                SystemPromptPosition::Prepend => blocks.insert(0, block),
+                SystemPromptPosition::Append => blocks.push(block),
+            }
+        },
+        _ => {
+            root.insert("system".to_string(), Value::String(prompt.to_string()));
+        },
+    }
+}
+
+fn is_system_role(item: &Value) -> bool {
+    matches!(item.get("role").and_then(Value::as_str), Some("system" | "developer"))
+}
+
+fn inject_chat_system_prompt(
+    root: &mut Map<String, Value>,
+    prompt: &str,
+    position: SystemPromptPosition,
+) {
+    let messages = root
+        .entry("messages".to_string())
+        .or_insert_with(|| Value::Array(Vec::new()));
+    let Some(messages) = messages.as_array_mut() else {
+        return;
+    };
+    let leading = messages
+        .iter()
+        .take_while(|item| is_system_role(item))
+        .count();
+    let target = match position {
+        SystemPromptPosition::Prepend if leading > 0 => Some(0),
+        SystemPromptPosition::Append if leading > 0 => Some(leading - 1),
+        _ => None,
+    };
+    if let Some(content) = target
+        .and_then(|index| messages.get_mut(index))
+        .and_then(|message| message.get_mut("content"))
+    {
+        match content {
+            Value::String(existing) => {
+                *existing = join_text(existing, prompt, position);
+                return;
+            },
+            Value::Array(parts) => {
+                let part = serde_json::json!({ "type": "text", "text": prompt });
+                match position {
+                    SystemPromptPosition::Prepend => parts.insert(0, part),
+                    SystemPromptPosition::Append => parts.push(part),
+                }
+                return;
+            },
+            _ => {},
+        }
+    }
+    let message = serde_json::json!({ "role": "system", "content": prompt });
+    match position {
+        SystemPromptPosition::Prepend => messages.insert(0, message),
+        SystemPromptPosition::Append => messages.insert(leading, message),
+    }
+}
+
+fn inject_responses_system_prompt(
+    root: &mut Map<String, Value>,
+    prompt: &str,
+    position: SystemPromptPosition,
+) {
+    let input = root
+        .entry("input".to_string())
+        .or_insert_with(|| Value::Array(Vec::new()));
+    match input {
+        Value::Array(_) => {},
+        Value::String(text) => {
+            let text = std::mem::take(text);
+            *input = serde_json::json!([{
+                "type": "message",
+                "role": "user",
+                "content": [{ "type": "input_text", "text": text }],
+            }]);
+        },
+        Value::Object(_) => {
+            let item = input.take();
+            *input = Value::Array(vec![item]);
+        },
+        _ => return,
+    }
+    let Some(items) = input.as_array_mut() else {
+        return;
+    };
+    let message = serde_json::json!({
+        "type": "message",
+        "role": "developer",
+        "content": [{ "type": "input_text", "text": prompt }],
+    });
+    match position {
+        SystemPromptPosition::Prepend => items.insert(0, message),
+        SystemPromptPosition::Append => {
+            let leading = items.iter().take_while(|item| is_system_role(item)).count();
+            items.insert(leading, message);
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn compile(policy: Value) -> CompiledRequestTransform {
+        let policy = serde_json::from_value::<RequestTransformPolicy>(policy).expect("policy");
+        policy.validate().expect("valid policy");
+        CompiledRequestTransform::compile(&policy).expect("compiled policy")
+    }
+
+    #[test]
+    fn applies_set_remove_and_cap_operations() {
+        let transform = compile(json!({
+            "operations": [
+                { "op": "set", "path": "reasoning.effort", "value": "low" },
+                { "op": "set", "path": "$.messages[0].name", "value": "ops" },
+                { "op": "set", "path": "messages[9].name", "value": "missing" },
+                { "op": "remove", "path": "metadata.user_id" },
+                { "op": "remove", "path": "temperature" },
+                { "op": "cap", "path": "max_tokens", "max": 1024 },
+                { "op": "cap", "path": "top_k", "max": 5 },
+            ],
+        }));
+        let mut body = json!({
+            "model": "m",
+            "messages": [{ "role": "user", "content": "hi" }],
+            "metadata": { "user_id": "u-1", "tier": "gold" },
+            "max_tokens": 8192,
+            "top_k": 3,
+        });
+
+        let applied = transform.apply(RequestTransformDialect::OpenAiChat, &mut body);
+
+        assert_eq!(applied, vec![
+            "set:reasoning.effort",
+            "set:$.messages[0].name",
+            "remove:metadata.user_id",
+            "cap:max_tokens",
+        ]);
+        assert_eq!(body["reasoning"], json!({ "effort": "low" }));
+        assert_eq!(body["messages"][0]["name"], "ops");
+        assert_eq!(body["metadata"], json!({ "tier": "gold" }));
+        assert_eq!(body["max_tokens"], 1024);
+        assert_eq!(body["top_k"], 3);
+    }
+
+    #[test]
+    fn redacts_prompt_text_but_not_structure() {
+        let transform = compile(json!({
+            "redactions": [{ "pattern": "\\b\\d{3}-\\d{2}-\\d{4}\\b", "replacement": "<ssn>" }],
+        }));
+        let mut body = json!({
+            "model": "123-45-6789",
+            "system": [{ "type": "text", "text": "id 123-45-6789" }],
+            "messages": [
+                { "role": "user", "content": "mine is 123-45-6789" },
+                { "role": "user", "content": [
+                    { "type": "text", "text": "and 987-65-4321" },
+                    { "type": "tool_result", "tool_use_id": "123-45-6789", "content": "ok" },
+                ]},
+            ],
+        });
+
+        let applied = transform.apply(RequestTransformDialect::AnthropicMessages, &mut body);
+
+        assert_eq!(applied, vec!["redact:0=3"]);
+        assert_eq!(body["model"], "123-45-6789");
+        assert_eq!(body["system"][0]["text"], "id <ssn>");
+        assert_eq!(body["messages"][0]["content"], "mine is <ssn>");
+        assert_eq!(body["messages"][1]["content"][0]["text"], "and <ssn>");
+        assert_eq!(body["messages"][1]["content"][1]["tool_use_id"], "123-45-6789");
+    }
+
+    #[test]
+    fn injects_system_prompts_per_dialect() {
+        let transform = compile(json!({
+            "system_prompt_prepend": "HEAD",
+            "system_prompt_append": "TAIL",
+        }));
+
+        let mut anthropic = json!({ "system": "client", "messages": [] });
+        transform.apply(RequestTransformDialect::AnthropicMessages, &mut anthropic);
+        assert_eq!(anthropic["system"], "HEAD\n\nclient\n\nTAIL");
+
+        let mut anthropic_empty = json!({ "messages": [] });
+        transform.apply(RequestTransformDialect::AnthropicMessages, &mut anthropic_empty);
+        assert_eq!(anthropic_empty["system"], "HEAD\n\nTAIL");
+
+        let mut chat = json!({ "messages": [
+            { "role": "system", "content": "client" },
+            { "role": "user", "content": "hi" },
+        ]});
+        transform.apply(RequestTransformDialect::OpenAiChat, &mut chat);
+        assert_eq!(chat["messages"][0]["content"], "HEAD\n\nclient\n\nTAIL");
+        assert_eq!(chat["messages"].as_array().map(Vec::len), Some(2));
+
+        let mut chat_without_system = json!({ "messages": [{ "role": "user", "content": "hi" }] });
+        transform.apply(RequestTransformDialect::OpenAiChat, &mut chat_without_system);
+        assert_eq!(
+            chat_without_system["messages"],
+            json!([
+                { "role": "system", "content": "HEAD\n\nTAIL" },
+                { "role": "user", "content": "hi" },
+            ])
+        );
+
+        let mut responses = json!({ "input": "hi" });
+        let applied = transform.apply(RequestTransformDialect::Responses, &mut responses);
+        assert_eq!(applied, vec!["system_prompt_prepend", "system_prompt_append"]);
+        let roles = responses["input"]
+            .as_array()
+            .expect("input items")
+            .iter()
+            .map(|item| item["role"].as_str().unwrap_or_default())
+            .collect::<Vec<_>>();
+        assert_eq!(roles, vec!["developer", "developer", "user"]);
+        assert_eq!(responses["input"][1]["content"][0]["text"], "TAIL");
+        assert_eq!(responses["input"][2]["content"][0]["text"], "hi");
+    }
+}
@@ -24,6 +24,7 @@ mod tool_name;
 mod tool_pairing;
 mod tool_result;
 mod tools;
+mod transform;
 mod validate;
 
 use std::collections::{BTreeMap, HashMap};
@@ -36,6 +37,7 @@ pub use model::{get_context_window_size, map_model};
 pub use normalize::normalize_request;
 pub use session::{preview_session_value, resolve_conversation_id_from_metadata};
 pub use tool_result::extract_tool_result_content;
+pub use transform::parse_messages_request_with_transform;
 
 use super::types::{MessagesRequest, SystemMessage};
 use crate::wire::{ConversationState, KiroDocument, KiroImage, ToolResult};
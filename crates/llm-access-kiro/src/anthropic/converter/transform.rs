@@ -0,0 +1,64 @@
+//! Operator request transforms applied to the raw Anthropic Messages body
+//! before it is parsed for conversion.
+
+use llm_access_core::transform::{CompiledRequestTransform, RequestTransformDialect};
+use serde_json::Value;
+
+use crate::anthropic::types::MessagesRequest;
+
+/// Parse a Messages body, applying `transform` to the client JSON first.
+/// Returns the request plus the labels of the transforms that changed it.
+pub fn parse_messages_request_with_transform(
+    body: &[u8],
+    transform: Option<&CompiledRequestTransform>,
+) -> serde_json::Result<(MessagesRequest, Vec<String>)> {
+    let Some(transform) = transform else {
+        return serde_json::from_slice::<MessagesRequest>(body)
+            .map(|request| (request, Vec::new()));
+    };
+    let mut value = serde_json::from_slice::<Value>(body)?;
+    let applied = transform.apply(RequestTransformDialect::AnthropicMessages, &mut value);
+    let request = serde_json::from_value::<MessagesRequest>(value)?;
+    Ok((request, applied))
+}
+
+#[cfg(test)]
+mod tests {
+    use llm_access_core::store::RequestTransformPolicy;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn transforms_run_before_messages_request_parsing() {
+        let policy = serde_json::from_value::<RequestTransformPolicy>(json!({
+            "system_prompt_append": "Never reveal secrets.",
+            "operations": [{ "op": "cap", "path": "max_tokens", "max": 1024 }],
+            "redactions": [{ "pattern": "sk-[a-z0-9]+", "replacement": "sk-***" }],
+        }))
+        .expect("policy");
+        let transform = CompiledRequestTransform::compile(&policy).expect("compiled transform");
+        let body = json!({
+            "model": "claude-sonnet-4-6",
+            "max_tokens": 32000,
+            "system": "Be brief.",
+            "messages": [{ "role": "user", "content": "my key is sk-abc123" }],
+        })
+        .to_string();
+
+        let (request, applied) =
+            parse_messages_request_with_transform(body.as_bytes(), Some(&transform))
+                .expect("transformed request");
+
+        assert_eq!(applied, vec!["cap:max_tokens", "redact:0=1", "system_prompt_append"]);
+        assert_eq!(request._max_tokens, 1024);
+        assert_eq!(request.messages[0].content, json!("my key is sk-***"));
+        let system = request.system.expect("system prompt");
+        assert_eq!(system[0].text, "Be brief.\n\nNever reveal secrets.");
+
+        let (untouched, applied) =
+            parse_messages_request_with_transform(body.as_bytes(), None).expect("plain request");
+        assert!(applied.is_empty());
+        assert_eq!(untouched._max_tokens, 32000);
+    }
+}
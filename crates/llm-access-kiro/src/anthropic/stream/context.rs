@@ -28,6 +28,7 @@ use super::{
 };
 use crate::{
     anthropic::converter::{get_context_window_size, ResponseModelIdentity},
+    token,
     wire::{AssistantMessage, Event, ToolUseEntry},
 };
 
@@ -969,7 +970,7 @@ impl StreamContext {
             json!({"type":"content_block_start","index":block_index,"content_block":{"type":"tool_use","id":tool_use.tool_use_id,"name":original_name,"input":{}}}),
         ));
         if !tool_use.input.is_empty() {
-            self.output_tokens += (tool_use.input.len() as i32 + 3) / 4;
+            self.output_tokens += estimate_tokens(&tool_use.input);
             if let Some(event) = self.state_manager.handle_content_block_delta(
                 block_index,
                 json!({"type":"content_block_delta","index":block_index,"delta":{"type":"input_json_delta","partial_json":tool_use.input}}),
@@ -1191,18 +1192,11 @@ impl BufferedStreamContext {
     }
 }
 
-// Rough token estimate: CJK chars ~0.67 tokens each, others ~0.25 each.
+// Output token estimate for streamed text, used until Kiro reports usage.
 fn estimate_tokens(text: &str) -> i32 {
-    let mut chinese_count = 0;
-    let mut other_count = 0;
-    for ch in text.chars() {
-        if ('\u{4E00}'..='\u{9FFF}').contains(&ch) {
-            chinese_count += 1;
-        } else {
-            other_count += 1;
-        }
-    }
-    (((chinese_count * 2 + 2) / 3) + ((other_count + 3) / 4)).max(1)
+    i32::try_from(token::count_tokens(text))
+        .unwrap_or(i32::MAX)
+        .max(1)
 }
 
 // Finds the nearest valid UTF-8 char boundary at or before `target`.
@@ -9,6 +9,7 @@ use super::{
     stream::{anthropic_usage_json, SseEvent},
     types::MessagesRequest,
 };
+use crate::token;
 
 #[derive(Debug, Serialize)]
 pub struct McpRequest {
@@ -208,7 +209,7 @@ pub fn should_propagate_mcp_error_text(err_text: &str) -> bool {
 }
 
 pub fn estimate_output_tokens(summary: &str) -> i32 {
-    ((summary.chars().count() as i32) + 3) / 4
+    i32::try_from(token::count_tokens(summary)).unwrap_or(i32::MAX)
 }
 
 pub fn generate_websearch_events(
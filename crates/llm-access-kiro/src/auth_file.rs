@@ -113,6 +113,9 @@ pub struct KiroAuthRecord {
     /// Shared proxy-config id used when `proxy_mode` is `"fixed"`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub proxy_config_id: Option<String>,
+    /// Shared proxy-pool id used when `proxy_mode` is `"pool"`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy_pool_id: Option<String>,
     /// HTTP(S) proxy URL for outbound API requests.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub proxy_url: Option<String>,
@@ -178,8 +181,10 @@ impl KiroAuthRecord {
         if !self.disabled {
             self.disabled_reason = None;
         }
-        self.proxy_mode = self.proxy_selection().proxy_mode;
-        self.proxy_config_id = self.proxy_selection().proxy_config_id;
+        let proxy_selection = self.proxy_selection();
+        self.proxy_mode = proxy_selection.proxy_mode;
+        self.proxy_config_id = proxy_selection.proxy_config_id;
+        self.proxy_pool_id = proxy_selection.proxy_pool_id;
         self
     }
 
@@ -255,6 +260,7 @@ impl KiroAuthRecord {
         AccountProxySelection {
             proxy_mode: self.proxy_mode,
             proxy_config_id: self.proxy_config_id.clone(),
+            proxy_pool_id: self.proxy_pool_id.clone(),
         }
         .canonicalize()
     }
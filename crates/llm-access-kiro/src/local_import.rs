@@ -133,6 +133,7 @@ fn import_from_sqlite_blocking(
             pool_strategy: Some(default_kiro_pool_strategy()),
             proxy_mode: Default::default(),
             proxy_config_id: None,
+            proxy_pool_id: None,
             proxy_url: None,
             proxy_username: None,
             proxy_password: None,
@@ -193,6 +194,7 @@ fn import_from_sqlite_blocking(
         pool_strategy: Some(default_kiro_pool_strategy()),
         proxy_mode: Default::default(),
         proxy_config_id: None,
+        proxy_pool_id: None,
         proxy_url: None,
         proxy_username: None,
         proxy_password: None,
@@ -0,0 +1,82 @@
+//! AWS event stream frame encoder, the inverse of [`super::frame`].
+//!
+//! Only string-typed headers are emitted because that is all the Kiro
+//! upstream uses (`:message-type`, `:event-type`, `:exception-type`,
+//! `:content-type`). Encoded frames round-trip through
+//! [`super::frame::parse_frame`].
+
+use super::{crc::crc32, frame::PRELUDE_SIZE, header::HeaderValueType};
+
+/// Encode one frame carrying `headers` (name, string value) and `payload`.
+pub fn encode_frame(headers: &[(&str, &str)], payload: &[u8]) -> Vec<u8> {
+    let mut header_bytes = Vec::new();
+    for (name, value) in headers {
+        header_bytes.push(name.len() as u8);
+        header_bytes.extend_from_slice(name.as_bytes());
+        header_bytes.push(HeaderValueType::String as u8);
+        header_bytes.extend_from_slice(&(value.len() as u16).to_be_bytes());
+        header_bytes.extend_from_slice(value.as_bytes());
+    }
+    let total_length = PRELUDE_SIZE + header_bytes.len() + payload.len() + 4;
+    let mut frame = Vec::with_capacity(total_length);
+    frame.extend_from_slice(&(total_length as u32).to_be_bytes());
+    frame.extend_from_slice(&(header_bytes.len() as u32).to_be_bytes());
+    let prelude_crc = crc32(&frame);
+    frame.extend_from_slice(&prelude_crc.to_be_bytes());
+    frame.extend_from_slice(&header_bytes);
+    frame.extend_from_slice(payload);
+    let message_crc = crc32(&frame);
+    frame.extend_from_slice(&message_crc.to_be_bytes());
+    frame
+}
+
+/// Encode an `event` frame with a JSON payload, as sent for
+/// `assistantResponseEvent`, `meteringEvent` and friends.
+pub fn encode_event_frame(event_type: &str, payload: &[u8]) -> Vec<u8> {
+    encode_frame(
+        &[
+            (":message-type", "event"),
+            (":event-type", event_type),
+            (":content-type", "application/json"),
+        ],
+        payload,
+    )
+}
+
+/// Encode an `exception` frame, as sent when the upstream aborts a stream.
+pub fn encode_exception_frame(exception_type: &str, payload: &[u8]) -> Vec<u8> {
+    encode_frame(
+        &[
+            (":message-type", "exception"),
+            (":exception-type", exception_type),
+            (":content-type", "application/json"),
+        ],
+        payload,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encode_event_frame, encode_exception_frame};
+    use crate::parser::frame::parse_frame;
+
+    #[test]
+    fn encoded_frames_round_trip_through_parser() {
+        let mut buffer = encode_event_frame("assistantResponseEvent", br#"{"content":"hi"}"#);
+        buffer.extend(encode_exception_frame("ThrottlingException", br#"{"message":"slow"}"#));
+
+        let (first, consumed) = parse_frame(&buffer)
+            .expect("parse event")
+            .expect("complete event");
+        assert_eq!(first.message_type(), Some("event"));
+        assert_eq!(first.event_type(), Some("assistantResponseEvent"));
+        assert_eq!(first.payload_as_str(), r#"{"content":"hi"}"#);
+
+        let (second, rest) = parse_frame(&buffer[consumed..])
+            .expect("parse exception")
+            .expect("complete exception");
+        assert_eq!(second.message_type(), Some("exception"));
+        assert_eq!(second.headers.exception_type(), Some("ThrottlingException"));
+        assert_eq!(consumed + rest, buffer.len());
+    }
+}
@@ -1,100 +1,115 @@
-//! Token counting heuristics for Anthropic-compatible billing estimation.
+//! Token counting for Anthropic-compatible billing estimation.
 //!
-//! Provides a fast, approximate token count without loading a real tokenizer.
-//! Non-western characters (CJK, etc.) are weighted at 4 char-units each to
-//! reflect their higher byte cost in BPE tokenizers. Short texts receive a
-//! scaling factor to compensate for per-message overhead.
+//! Counts come from the embedded BPE tokenizer in `llm-access-tokenizer`,
+//! selected by model family. Every content block type is costed: text,
+//! images by header dimensions, documents, tool calls and results, and
+//! thinking blocks, plus the framing the upstream chat template adds.
 
-use super::anthropic::types::{Message, SystemMessage, Tool};
+use llm_access_tokenizer::{TokenCounter, TokenizerFamily};
 
-/// Returns `true` for characters outside the Latin/extended-Latin Unicode
-/// ranges, used to weight CJK and other multi-byte scripts more heavily.
-fn is_non_western_char(ch: char) -> bool {
-    !matches!(
-        ch,
-        '\u{0000}'..='\u{007F}'
-            | '\u{0080}'..='\u{00FF}'
-            | '\u{0100}'..='\u{024F}'
-            | '\u{1E00}'..='\u{1EFF}'
-            | '\u{2C60}'..='\u{2C7F}'
-            | '\u{A720}'..='\u{A7FF}'
-            | '\u{AB30}'..='\u{AB6F}'
-    )
-}
+use super::anthropic::types::{Message, SystemMessage, Tool};
 
-/// Estimate the token count of a single text string.
-///
-/// Uses a 4-char-unit-per-token heuristic with a scaling factor for short
-/// texts to account for tokenizer overhead on small inputs.
+/// Count the tokens of a single text string with the Claude tokenizer
+/// family.
 pub fn count_tokens(text: &str) -> u64 {
-    let char_units: f64 = text
-        .chars()
-        .map(|ch| if is_non_western_char(ch) { 4.0 } else { 1.0 })
-        .sum();
-    let tokens = char_units / 4.0;
-    let adjusted = if tokens < 100.0 {
-        tokens * 1.5
-    } else if tokens < 200.0 {
-        tokens * 1.3
-    } else if tokens < 300.0 {
-        tokens * 1.25
-    } else if tokens < 800.0 {
-        tokens * 1.2
-    } else {
-        tokens
-    };
-    adjusted.max(1.0) as u64
+    TokenCounter::new(TokenizerFamily::Claude).count_text(text)
 }
 
-/// Estimate total input tokens across system messages, conversation messages,
-/// and tool definitions. The `_model` parameter is reserved for future
-/// model-specific adjustments.
+/// Count total input tokens across system messages, conversation messages,
+/// and tool definitions for `model`.
 pub fn count_all_tokens(
-    _model: &str,
+    model: &str,
     system: Option<&[SystemMessage]>,
     messages: &[Message],
     tools: Option<&[Tool]>,
 ) -> u64 {
-    let mut total = 0;
+    let counter = TokenCounter::for_model(model);
+    let mut total = counter.request_overhead();
     if let Some(system) = system {
         for message in system {
-            total += count_tokens(&message.text);
+            total += counter.count_text(&message.text);
         }
     }
     for message in messages {
-        match &message.content {
-            serde_json::Value::String(text) => total += count_tokens(text),
-            serde_json::Value::Array(items) => {
-                for item in items {
-                    if let Some(text) = item.get("text").and_then(|value| value.as_str()) {
-                        total += count_tokens(text);
-                    }
-                }
-            },
-            _ => {},
-        }
+        total += counter.count_message(&message.content);
     }
-    if let Some(tools) = tools {
+    if let Some(tools) = tools.filter(|tools| !tools.is_empty()) {
+        total += counter.tool_prompt_overhead();
         for tool in tools {
-            total += count_tokens(&tool.name);
-            total += count_tokens(&tool.description);
-            total += count_tokens(&serde_json::to_string(&tool.input_schema).unwrap_or_default());
+            let input_schema = serde_json::to_value(&tool.input_schema).unwrap_or_default();
+            total += counter.count_tool(&tool.name, &tool.description, &input_schema);
         }
     }
     total.max(1)
 }
 
-/// Estimate the output token count from an array of content blocks
-/// (text blocks and tool_use blocks).
+/// Count the output tokens of an array of content blocks (text, thinking
+/// and tool_use blocks).
 pub fn estimate_output_tokens(content: &[serde_json::Value]) -> i32 {
-    let mut total = 0;
-    for block in content {
-        if let Some(text) = block.get("text").and_then(|value| value.as_str()) {
-            total += count_tokens(text) as i32;
-        }
-        if block.get("type").and_then(|value| value.as_str()) == Some("tool_use") {
-            total += count_tokens(&serde_json::to_string(block).unwrap_or_default()) as i32;
+    let counter = TokenCounter::new(TokenizerFamily::Claude);
+    let total: u64 = content.iter().map(|block| counter.count_block(block)).sum();
+    i32::try_from(total).unwrap_or(i32::MAX).max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+
+    use super::*;
+
+    fn user(content: serde_json::Value) -> Message {
+        Message {
+            role: "user".to_string(),
+            content,
         }
     }
-    total.max(1)
+
+    #[test]
+    fn counts_content_blocks_beyond_text() {
+        let text_only = [user(json!([{"type": "text", "text": "Summarize the result."}]))];
+        let with_tool_result = [user(json!([
+            {"type": "text", "text": "Summarize the result."},
+            {"type": "tool_result", "tool_use_id": "toolu_01", "content": "42 rows matched"},
+        ]))];
+        let base = count_all_tokens("claude-sonnet-4-6", None, &text_only, None);
+        let extended = count_all_tokens("claude-sonnet-4-6", None, &with_tool_result, None);
+        assert_eq!(extended - base, count_tokens("toolu_01") + count_tokens("42 rows matched"));
+
+        let with_image = [user(json!([
+            {"type": "image", "source": {"type": "url", "url": "https://example.com/a.png"}},
+        ]))];
+        assert!(count_all_tokens("claude-sonnet-4-6", None, &with_image, None) > 1_000);
+    }
+
+    #[test]
+    fn tools_add_definition_and_prompt_overhead() {
+        let messages = [user(json!("hello"))];
+        let tool = Tool {
+            tool_type: None,
+            name: "get_weather".to_string(),
+            description: "Look up the weather for a city.".to_string(),
+            input_schema: HashMap::from([("type".to_string(), json!("object"))]),
+            max_uses: None,
+        };
+        let without = count_all_tokens("claude-sonnet-4-6", None, &messages, None);
+        let with = count_all_tokens("claude-sonnet-4-6", None, &messages, Some(&[tool]));
+        assert!(with > without + 300);
+        assert_eq!(count_all_tokens("claude-sonnet-4-6", None, &messages, Some(&[])), without);
+    }
+
+    #[test]
+    fn output_estimate_counts_tool_use_input() {
+        let content = [
+            json!({"type": "text", "text": "Checking."}),
+            json!({"type": "tool_use", "id": "toolu_01", "name": "search", "input": {"q": "rust"}}),
+        ];
+        let expected = count_tokens("Checking.")
+            + count_tokens("toolu_01")
+            + count_tokens("search")
+            + count_tokens(r#"{"q":"rust"}"#);
+        assert_eq!(estimate_output_tokens(&content) as u64, expected);
+        assert_eq!(estimate_output_tokens(&[]), 1);
+    }
 }
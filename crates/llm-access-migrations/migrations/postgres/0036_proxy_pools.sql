@@ -0,0 +1,38 @@
+CREATE TABLE IF NOT EXISTS llm_proxy_pools (
+    proxy_pool_id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    status TEXT NOT NULL CHECK (status IN ('active', 'disabled')),
+    created_at_ms BIGINT NOT NULL CHECK (created_at_ms >= 0),
+    updated_at_ms BIGINT NOT NULL CHECK (updated_at_ms >= 0)
+);
+
+CREATE TABLE IF NOT EXISTS llm_proxy_pool_members (
+    proxy_pool_id TEXT NOT NULL REFERENCES llm_proxy_pools(proxy_pool_id) ON DELETE CASCADE,
+    proxy_config_id TEXT NOT NULL REFERENCES llm_proxy_configs(proxy_config_id) ON DELETE RESTRICT,
+    weight BIGINT NOT NULL DEFAULT 1 CHECK (weight >= 0),
+    position BIGINT NOT NULL CHECK (position >= 0),
+    PRIMARY KEY (proxy_pool_id, proxy_config_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_llm_proxy_pool_members_proxy
+    ON llm_proxy_pool_members(proxy_config_id);
+
+ALTER TABLE IF EXISTS llm_proxy_bindings
+    ALTER COLUMN proxy_config_id DROP NOT NULL;
+
+ALTER TABLE IF EXISTS llm_proxy_bindings
+    ADD COLUMN IF NOT EXISTS proxy_pool_id TEXT
+        REFERENCES llm_proxy_pools(proxy_pool_id) ON DELETE RESTRICT;
+
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1
+        FROM pg_constraint
+        WHERE conname = 'ck_llm_proxy_bindings_single_target'
+    ) THEN
+        ALTER TABLE llm_proxy_bindings
+            ADD CONSTRAINT ck_llm_proxy_bindings_single_target
+            CHECK ((proxy_config_id IS NULL) <> (proxy_pool_id IS NULL));
+    END IF;
+END $$;
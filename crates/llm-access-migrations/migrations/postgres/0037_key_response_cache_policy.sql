@@ -0,0 +1,19 @@
+ALTER TABLE IF EXISTS llm_key_route_config
+    ADD COLUMN IF NOT EXISTS response_cache_policy_json JSONB;
+
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1
+        FROM pg_constraint
+        WHERE conname = 'ck_llm_key_route_config_response_cache_policy_object'
+          AND conrelid = 'llm_key_route_config'::regclass
+    ) THEN
+        ALTER TABLE llm_key_route_config
+            ADD CONSTRAINT ck_llm_key_route_config_response_cache_policy_object
+            CHECK (
+                response_cache_policy_json IS NULL
+                OR jsonb_typeof(response_cache_policy_json) = 'object'
+            );
+    END IF;
+END $$;
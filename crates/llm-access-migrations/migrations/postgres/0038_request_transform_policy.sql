@@ -0,0 +1,35 @@
+ALTER TABLE IF EXISTS llm_key_route_config
+    ADD COLUMN IF NOT EXISTS request_transform_policy_json JSONB;
+
+ALTER TABLE IF EXISTS llm_account_groups
+    ADD COLUMN IF NOT EXISTS request_transform_policy_json JSONB;
+
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1
+        FROM pg_constraint
+        WHERE conname = 'ck_llm_key_route_config_request_transform_policy_object'
+          AND conrelid = 'llm_key_route_config'::regclass
+    ) THEN
+        ALTER TABLE llm_key_route_config
+            ADD CONSTRAINT ck_llm_key_route_config_request_transform_policy_object
+            CHECK (
+                request_transform_policy_json IS NULL
+                OR jsonb_typeof(request_transform_policy_json) = 'object'
+            );
+    END IF;
+    IF NOT EXISTS (
+        SELECT 1
+        FROM pg_constraint
+        WHERE conname = 'ck_llm_account_groups_request_transform_policy_object'
+          AND conrelid = 'llm_account_groups'::regclass
+    ) THEN
+        ALTER TABLE llm_account_groups
+            ADD CONSTRAINT ck_llm_account_groups_request_transform_policy_object
+            CHECK (
+                request_transform_policy_json IS NULL
+                OR jsonb_typeof(request_transform_policy_json) = 'object'
+            );
+    END IF;
+END $$;
@@ -0,0 +1,45 @@
+ALTER TABLE IF EXISTS llm_key_route_config
+    ADD COLUMN IF NOT EXISTS moderation_policy_json JSONB;
+
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1
+        FROM pg_constraint
+        WHERE conname = 'ck_llm_key_route_config_moderation_policy_object'
+          AND conrelid = 'llm_key_route_config'::regclass
+    ) THEN
+        ALTER TABLE llm_key_route_config
+            ADD CONSTRAINT ck_llm_key_route_config_moderation_policy_object
+            CHECK (
+                moderation_policy_json IS NULL
+                OR jsonb_typeof(moderation_policy_json) = 'object'
+            );
+    END IF;
+END $$;
+
+CREATE TABLE IF NOT EXISTS llm_moderation_events (
+    event_id TEXT PRIMARY KEY,
+    key_id TEXT NOT NULL,
+    key_name TEXT NOT NULL,
+    provider_type TEXT NOT NULL,
+    endpoint TEXT NOT NULL,
+    model TEXT,
+    direction TEXT NOT NULL CHECK (direction IN ('request', 'response')),
+    action TEXT NOT NULL CHECK (action IN ('block', 'flag')),
+    matched_rules_json JSONB NOT NULL DEFAULT '[]'::jsonb,
+    classifier_labels_json JSONB NOT NULL DEFAULT '[]'::jsonb,
+    classifier_score DOUBLE PRECISION,
+    excerpt TEXT NOT NULL,
+    client_ip TEXT NOT NULL,
+    status TEXT NOT NULL CHECK (status IN ('pending', 'confirmed', 'dismissed')),
+    admin_note TEXT,
+    created_at_ms BIGINT NOT NULL CHECK (created_at_ms >= 0),
+    reviewed_at_ms BIGINT
+);
+
+CREATE INDEX IF NOT EXISTS idx_llm_moderation_events_status_created
+    ON llm_moderation_events(status, created_at_ms);
+
+CREATE INDEX IF NOT EXISTS idx_llm_moderation_events_key_created
+    ON llm_moderation_events(key_id, created_at_ms);
@@ -0,0 +1,42 @@
+CREATE TABLE IF NOT EXISTS llm_batches (
+    batch_id TEXT PRIMARY KEY,
+    key_id TEXT NOT NULL REFERENCES llm_keys(key_id) ON DELETE CASCADE,
+    protocol_family TEXT NOT NULL CHECK (protocol_family IN ('openai', 'anthropic')),
+    endpoint TEXT NOT NULL,
+    status TEXT NOT NULL CHECK (
+        status IN ('in_progress', 'cancelling', 'cancelled', 'completed', 'expired')
+    ),
+    metadata_json JSONB,
+    created_at_ms BIGINT NOT NULL CHECK (created_at_ms >= 0),
+    started_at_ms BIGINT,
+    finished_at_ms BIGINT,
+    cancel_requested_at_ms BIGINT,
+    expires_at_ms BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_llm_batches_key_created
+    ON llm_batches(key_id, created_at_ms);
+
+CREATE INDEX IF NOT EXISTS idx_llm_batches_status_created
+    ON llm_batches(status, created_at_ms);
+
+CREATE TABLE IF NOT EXISTS llm_batch_items (
+    batch_id TEXT NOT NULL REFERENCES llm_batches(batch_id) ON DELETE CASCADE,
+    item_index BIGINT NOT NULL,
+    custom_id TEXT NOT NULL,
+    body_json JSONB NOT NULL,
+    status TEXT NOT NULL CHECK (
+        status IN ('pending', 'running', 'succeeded', 'failed', 'cancelled', 'expired')
+    ),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at_ms BIGINT NOT NULL DEFAULT 0,
+    claimed_at_ms BIGINT,
+    status_code INTEGER,
+    response_body TEXT,
+    error_message TEXT,
+    finished_at_ms BIGINT,
+    PRIMARY KEY (batch_id, item_index)
+);
+
+CREATE INDEX IF NOT EXISTS idx_llm_batch_items_batch_status
+    ON llm_batch_items(batch_id, status, item_index);
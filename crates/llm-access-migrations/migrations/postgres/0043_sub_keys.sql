@@ -0,0 +1,6 @@
+ALTER TABLE IF EXISTS llm_keys
+    ADD COLUMN IF NOT EXISTS parent_key_id TEXT REFERENCES llm_keys(key_id) ON DELETE CASCADE;
+
+CREATE INDEX IF NOT EXISTS idx_llm_keys_parent_key_id
+    ON llm_keys (parent_key_id)
+    WHERE parent_key_id IS NOT NULL;
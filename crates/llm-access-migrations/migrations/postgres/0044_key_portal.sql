@@ -0,0 +1,28 @@
+ALTER TABLE IF EXISTS llm_keys
+    ADD COLUMN IF NOT EXISTS previous_key_hash TEXT;
+
+ALTER TABLE IF EXISTS llm_keys
+    ADD COLUMN IF NOT EXISTS previous_key_hash_expires_at_ms BIGINT;
+
+CREATE INDEX IF NOT EXISTS idx_llm_keys_previous_key_hash
+    ON llm_keys (previous_key_hash)
+    WHERE previous_key_hash IS NOT NULL;
+
+CREATE TABLE IF NOT EXISTS llm_key_portal_settings (
+    key_id TEXT PRIMARY KEY REFERENCES llm_keys(key_id) ON DELETE CASCADE,
+    quota_billable_cap BIGINT CHECK (quota_billable_cap IS NULL OR quota_billable_cap >= 0),
+    request_max_concurrency_cap BIGINT CHECK (
+        request_max_concurrency_cap IS NULL OR request_max_concurrency_cap >= 1
+    ),
+    request_min_start_interval_ms_floor BIGINT CHECK (
+        request_min_start_interval_ms_floor IS NULL OR request_min_start_interval_ms_floor >= 0
+    ),
+    notify_emails JSONB NOT NULL DEFAULT '[]'::jsonb
+        CHECK (jsonb_typeof(notify_emails) = 'array'),
+    quota_alert_thresholds JSONB NOT NULL DEFAULT '[]'::jsonb
+        CHECK (jsonb_typeof(quota_alert_thresholds) = 'array'),
+    quota_alert_level SMALLINT CHECK (
+        quota_alert_level IS NULL OR quota_alert_level BETWEEN 1 AND 100
+    ),
+    updated_at_ms BIGINT NOT NULL CHECK (updated_at_ms >= 0)
+);
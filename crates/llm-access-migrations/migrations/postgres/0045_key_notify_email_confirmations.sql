@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS llm_key_notify_email_confirmations (
+    key_id TEXT NOT NULL REFERENCES llm_keys(key_id) ON DELETE CASCADE,
+    email TEXT NOT NULL,
+    token_hash TEXT,
+    requested_at_ms BIGINT NOT NULL CHECK (requested_at_ms >= 0),
+    confirmed_at_ms BIGINT CHECK (confirmed_at_ms IS NULL OR confirmed_at_ms >= 0),
+    PRIMARY KEY (key_id, email)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_llm_key_notify_email_confirmations_token_hash
+    ON llm_key_notify_email_confirmations (token_hash)
+    WHERE token_hash IS NOT NULL;
@@ -183,6 +183,56 @@ const POSTGRES_MIGRATIONS: &[SqlMigration] = &[
         name: "kiro_model_group_preferences",
         sql: include_str!("../migrations/postgres/0035_kiro_model_group_preferences.sql"),
     },
+    SqlMigration {
+        version: 36,
+        name: "proxy_pools",
+        sql: include_str!("../migrations/postgres/0036_proxy_pools.sql"),
+    },
+    SqlMigration {
+        version: 37,
+        name: "key_response_cache_policy",
+        sql: include_str!("../migrations/postgres/0037_key_response_cache_policy.sql"),
+    },
+    SqlMigration {
+        version: 38,
+        name: "request_transform_policy",
+        sql: include_str!("../migrations/postgres/0038_request_transform_policy.sql"),
+    },
+    SqlMigration {
+        version: 39,
+        name: "moderation",
+        sql: include_str!("../migrations/postgres/0039_moderation.sql"),
+    },
+    SqlMigration {
+        version: 40,
+        name: "batches",
+        sql: include_str!("../migrations/postgres/0040_batches.sql"),
+    },
+    SqlMigration {
+        version: 41,
+        name: "queue_policy",
+        sql: include_str!("../migrations/postgres/0041_queue_policy.sql"),
+    },
+    SqlMigration {
+        version: 42,
+        name: "openai_upstream_pool",
+        sql: include_str!("../migrations/postgres/0042_openai_upstream_pool.sql"),
+    },
+    SqlMigration {
+        version: 43,
+        name: "sub_keys",
+        sql: include_str!("../migrations/postgres/0043_sub_keys.sql"),
+    },
+    SqlMigration {
+        version: 44,
+        name: "key_portal",
+        sql: include_str!("../migrations/postgres/0044_key_portal.sql"),
+    },
+    SqlMigration {
+        version: 45,
+        name: "key_notify_email_confirmations",
+        sql: include_str!("../migrations/postgres/0045_key_notify_email_confirmations.sql"),
+    },
 ];
 
 /// Return target DuckDB migrations in execution order.
@@ -602,6 +652,66 @@ mod tests {
         assert!(migration.sql.contains("DEFAULT 'disabled'"));
     }
 
+    #[test]
+    fn postgres_migrations_include_openai_upstream_pool() {
+        let migrations = super::postgres_migrations();
+        let migration = migrations
+            .iter()
+            .find(|migration| migration.name == "openai_upstream_pool")
+            .expect("openai upstream pool migration exists");
+
+        assert_eq!(migration.version, 42);
+        assert!(migration.sql.contains("codex_openai_upstream_pool_mode"));
+        assert!(migration
+            .sql
+            .contains("CREATE TABLE IF NOT EXISTS llm_openai_upstream_channels"));
+        assert!(migration
+            .sql
+            .contains("llm_openai_upstream_channel_usage_rollups"));
+    }
+
+    #[test]
+    fn postgres_migrations_include_sub_keys() {
+        let migrations = super::postgres_migrations();
+        let migration = migrations
+            .iter()
+            .find(|migration| migration.name == "sub_keys")
+            .expect("sub keys migration exists");
+
+        assert_eq!(migration.version, 43);
+        assert!(migration.sql.contains("parent_key_id TEXT REFERENCES llm_keys(key_id)"));
+        assert!(migration.sql.contains("ON DELETE CASCADE"));
+    }
+
+    #[test]
+    fn postgres_migrations_include_key_portal() {
+        let migrations = super::postgres_migrations();
+        let migration = migrations
+            .iter()
+            .find(|migration| migration.name == "key_portal")
+            .expect("key portal migration exists");
+
+        assert_eq!(migration.version, 44);
+        assert!(migration.sql.contains("previous_key_hash_expires_at_ms"));
+        assert!(migration
+            .sql
+            .contains("CREATE TABLE IF NOT EXISTS llm_key_portal_settings"));
+    }
+
+    #[test]
+    fn postgres_migrations_include_key_notify_email_confirmations() {
+        let migrations = super::postgres_migrations();
+        let migration = migrations
+            .iter()
+            .find(|migration| migration.name == "key_notify_email_confirmations")
+            .expect("key notify email confirmations migration exists");
+
+        assert_eq!(migration.version, 45);
+        assert!(migration
+            .sql
+            .contains("CREATE TABLE IF NOT EXISTS llm_key_notify_email_confirmations"));
+    }
+
     #[test]
     fn postgres_migrations_include_anthropic_upstream_probe_state() {
         let migrations = super::postgres_migrations();
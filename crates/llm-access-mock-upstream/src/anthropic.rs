@@ -0,0 +1,138 @@
+//! Anthropic `/v1/messages` upstream: SSE when streaming, a message object
+//! otherwise.
+
+use std::sync::Arc;
+
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::{json, Value};
+
+use crate::{
+    estimate_tokens, json_response,
+    scenario::Scenario,
+    stream::{json_reply, paced_body, sse_event, StreamParts},
+    MockState,
+};
+
+const DEFAULT_MODEL: &str = "claude-sonnet-4-5";
+
+pub(crate) async fn messages(
+    State(state): State<Arc<MockState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let scenario = state.resolve("anthropic", &headers);
+    if let Some(rejection) = rejection(scenario) {
+        return rejection;
+    }
+    let request = serde_json::from_slice::<Value>(&body).unwrap_or(Value::Null);
+    let model = request["model"].as_str().unwrap_or(DEFAULT_MODEL);
+    let chunks = state.reply_chunks();
+    let message_id = format!("msg_mock_{}", body.len());
+    let input_tokens = estimate_tokens(body.len());
+    if request["stream"].as_bool() != Some(true) {
+        let message = json!({
+            "id": message_id,
+            "type": "message",
+            "role": "assistant",
+            "model": model,
+            "content": [{"type": "text", "text": chunks.concat()}],
+            "stop_reason": "end_turn",
+            "stop_sequence": null,
+            "usage": {"input_tokens": input_tokens, "output_tokens": chunks.len()},
+        });
+        return json_reply(message, scenario).await;
+    }
+    let parts = StreamParts {
+        prelude: vec![
+            sse_event(
+                "message_start",
+                &json!({"type": "message_start", "message": {
+                    "id": message_id,
+                    "type": "message",
+                    "role": "assistant",
+                    "model": model,
+                    "content": [],
+                    "stop_reason": null,
+                    "stop_sequence": null,
+                    "usage": {"input_tokens": input_tokens, "output_tokens": 1},
+                }}),
+            ),
+            sse_event(
+                "content_block_start",
+                &json!({
+                    "type": "content_block_start",
+                    "index": 0,
+                    "content_block": {"type": "text", "text": ""},
+                }),
+            ),
+        ],
+        content: chunks
+            .iter()
+            .map(|text| {
+                sse_event(
+                    "content_block_delta",
+                    &json!({
+                        "type": "content_block_delta",
+                        "index": 0,
+                        "delta": {"type": "text_delta", "text": text},
+                    }),
+                )
+            })
+            .collect(),
+        epilogue: vec![
+            sse_event("content_block_stop", &json!({"type": "content_block_stop", "index": 0})),
+            sse_event(
+                "message_delta",
+                &json!({
+                    "type": "message_delta",
+                    "delta": {"stop_reason": "end_turn", "stop_sequence": null},
+                    "usage": {"output_tokens": chunks.len()},
+                }),
+            ),
+            sse_event("message_stop", &json!({"type": "message_stop"})),
+        ],
+    };
+    (
+        [(header::CONTENT_TYPE, "text/event-stream")],
+        paced_body(parts, scenario, state.chunk_delay()),
+    )
+        .into_response()
+}
+
+pub(crate) async fn count_tokens(body: Bytes) -> Json<Value> {
+    Json(json!({ "input_tokens": estimate_tokens(body.len()) }))
+}
+
+fn rejection(scenario: Scenario) -> Option<Response> {
+    let (status, error_type, message, retry_after) = match scenario {
+        Scenario::RateLimited {
+            retry_after_secs,
+        } => (
+            StatusCode::TOO_MANY_REQUESTS,
+            "rate_limit_error",
+            "Number of requests has exceeded your rate limit.",
+            Some(retry_after_secs),
+        ),
+        Scenario::MonthlyQuota => (
+            StatusCode::PAYMENT_REQUIRED,
+            "billing_error",
+            "MONTHLY_REQUEST_COUNT: your credit balance is too low to access the API.",
+            None,
+        ),
+        Scenario::AuthExpired => {
+            (StatusCode::UNAUTHORIZED, "authentication_error", "OAuth token has expired.", None)
+        },
+        Scenario::ServerError => {
+            (StatusCode::SERVICE_UNAVAILABLE, "api_error", "Internal server error.", None)
+        },
+        _ => return None,
+    };
+    let body = json!({"type": "error", "error": {"type": error_type, "message": message}});
+    Some(json_response(status, &body, retry_after))
+}
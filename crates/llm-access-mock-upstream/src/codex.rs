@@ -0,0 +1,146 @@
+//! Codex `/responses` upstream: SSE when streaming, a response object
+//! otherwise.
+
+use std::sync::Arc;
+
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde_json::{json, Value};
+
+use crate::{
+    estimate_tokens, json_response,
+    scenario::Scenario,
+    stream::{json_reply, paced_body, sse_event, StreamParts},
+    MockState,
+};
+
+const DEFAULT_MODEL: &str = "gpt-5-codex";
+
+pub(crate) async fn responses(
+    State(state): State<Arc<MockState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let scenario = state.resolve("codex", &headers);
+    if let Some(rejection) = rejection(scenario) {
+        return rejection;
+    }
+    let request = serde_json::from_slice::<Value>(&body).unwrap_or(Value::Null);
+    let model = request["model"].as_str().unwrap_or(DEFAULT_MODEL);
+    let chunks = state.reply_chunks();
+    let response_id = format!("resp_mock_{}", body.len());
+    let item_id = format!("msg_mock_{}", body.len());
+    let completed = json!({
+        "id": response_id,
+        "object": "response",
+        "model": model,
+        "status": "completed",
+        "output": [{
+            "type": "message",
+            "id": item_id,
+            "role": "assistant",
+            "status": "completed",
+            "content": [{"type": "output_text", "text": chunks.concat(), "annotations": []}],
+        }],
+        "usage": {
+            "input_tokens": estimate_tokens(body.len()),
+            "input_tokens_details": {"cached_tokens": 0},
+            "output_tokens": chunks.len(),
+            "output_tokens_details": {"reasoning_tokens": 0},
+            "total_tokens": estimate_tokens(body.len()) + chunks.len() as u64,
+        },
+    });
+    if request["stream"].as_bool() == Some(false) {
+        return json_reply(completed, scenario).await;
+    }
+    let parts = StreamParts {
+        prelude: vec![sse_event(
+            "response.created",
+            &json!({
+                "type": "response.created",
+                "response": {"id": response_id, "object": "response", "model": model, "status": "in_progress"},
+            }),
+        )],
+        content: chunks
+            .iter()
+            .map(|delta| {
+                sse_event(
+                    "response.output_text.delta",
+                    &json!({
+                        "type": "response.output_text.delta",
+                        "item_id": item_id,
+                        "output_index": 0,
+                        "content_index": 0,
+                        "delta": delta,
+                    }),
+                )
+            })
+            .collect(),
+        epilogue: vec![
+            sse_event(
+                "response.output_item.done",
+                &json!({
+                    "type": "response.output_item.done",
+                    "output_index": 0,
+                    "item": completed["output"][0],
+                }),
+            ),
+            sse_event(
+                "response.completed",
+                &json!({"type": "response.completed", "response": completed}),
+            ),
+        ],
+    };
+    (
+        [(header::CONTENT_TYPE, "text/event-stream")],
+        paced_body(parts, scenario, state.chunk_delay()),
+    )
+        .into_response()
+}
+
+fn rejection(scenario: Scenario) -> Option<Response> {
+    let (status, body, retry_after) = match scenario {
+        Scenario::RateLimited {
+            retry_after_secs,
+        } => (
+            StatusCode::TOO_MANY_REQUESTS,
+            json!({"error": {
+                "type": "usage_limit_reached",
+                "message": "The usage limit has been reached",
+                "resets_in_seconds": retry_after_secs,
+            }}),
+            Some(retry_after_secs),
+        ),
+        Scenario::MonthlyQuota => (
+            StatusCode::PAYMENT_REQUIRED,
+            json!({"error": {
+                "type": "usage_limit_reached",
+                "code": "MONTHLY_REQUEST_COUNT",
+                "message": "Monthly request quota exhausted",
+            }}),
+            None,
+        ),
+        Scenario::AuthExpired => (
+            StatusCode::UNAUTHORIZED,
+            json!({"error": {
+                "code": "token_expired",
+                "message": "Provided authentication token is expired. Please try signing in again.",
+            }}),
+            None,
+        ),
+        Scenario::ServerError => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            json!({"error": {
+                "type": "server_error",
+                "message": "The server is temporarily unavailable.",
+            }}),
+            None,
+        ),
+        _ => return None,
+    };
+    Some(json_response(status, &body, retry_after))
+}
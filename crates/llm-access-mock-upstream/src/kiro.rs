@@ -0,0 +1,97 @@
+//! Kiro `/generateAssistantResponse` upstream: AWS event-stream frames.
+
+use std::sync::Arc;
+
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use llm_access_kiro::parser::encoder::encode_event_frame;
+use serde_json::{json, Value};
+
+use crate::{
+    json_response,
+    scenario::Scenario,
+    stream::{paced_body, StreamParts},
+    MockState,
+};
+
+const EVENT_STREAM_CONTENT_TYPE: &str = "application/vnd.amazon.eventstream";
+const CREDITS_PER_REQUEST: f64 = 0.01;
+
+pub(crate) async fn generate_assistant_response(
+    State(state): State<Arc<MockState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let scenario = state.resolve("kiro", &headers);
+    if let Some(rejection) = rejection(scenario) {
+        return rejection;
+    }
+    let parts = StreamParts {
+        prelude: Vec::new(),
+        content: state
+            .reply_chunks()
+            .iter()
+            .map(|content| event_frame("assistantResponseEvent", &json!({ "content": content })))
+            .collect(),
+        epilogue: vec![
+            event_frame(
+                "meteringEvent",
+                &json!({"unit": "credit", "unitPlural": "credits", "usage": CREDITS_PER_REQUEST}),
+            ),
+            event_frame(
+                "contextUsageEvent",
+                &json!({"contextUsagePercentage": context_usage_percentage(body.len())}),
+            ),
+        ],
+    };
+    (
+        [(header::CONTENT_TYPE, EVENT_STREAM_CONTENT_TYPE)],
+        paced_body(parts, scenario, state.chunk_delay()),
+    )
+        .into_response()
+}
+
+fn event_frame(event_type: &str, payload: &Value) -> Bytes {
+    Bytes::from(encode_event_frame(event_type, payload.to_string().as_bytes()))
+}
+
+/// Share of a 200k-token context window the request body would occupy.
+fn context_usage_percentage(body_len: usize) -> f64 {
+    (crate::estimate_tokens(body_len) as f64 / 2_000.0).min(100.0)
+}
+
+fn rejection(scenario: Scenario) -> Option<Response> {
+    let (status, body, retry_after) = match scenario {
+        Scenario::RateLimited {
+            retry_after_secs,
+        } => (
+            StatusCode::TOO_MANY_REQUESTS,
+            json!({"message": "Too many requests, please wait before trying again.", "reason": null}),
+            Some(retry_after_secs),
+        ),
+        Scenario::MonthlyQuota => (
+            StatusCode::PAYMENT_REQUIRED,
+            json!({
+                "message": "You have reached the limit for your monthly requests.",
+                "reason": "MONTHLY_REQUEST_COUNT",
+            }),
+            None,
+        ),
+        Scenario::AuthExpired => (
+            StatusCode::FORBIDDEN,
+            json!({"message": "The bearer token included in the request is invalid.", "reason": null}),
+            None,
+        ),
+        Scenario::ServerError => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            json!({"message": "Service is temporarily unavailable.", "reason": null}),
+            None,
+        ),
+        _ => return None,
+    };
+    Some(json_response(status, &body, retry_after))
+}
@@ -0,0 +1,498 @@
+//! Local mock upstream for the Codex, Kiro and Anthropic providers and for
+//! generic OpenAI-compatible channels.
+//!
+//! Speaks just enough of each upstream wire protocol for `llm-access` to run
+//! end to end without network access: Codex `/responses` SSE, Kiro
+//! `/generateAssistantResponse` AWS event-stream frames (encoded with
+//! [`llm_access_kiro::parser::encoder`]), the Anthropic messages API, and the
+//! OpenAI `/v1/models` and `/v1/chat/completions` endpoints.
+//! Every request is answered according to a [`scenario::Scenario`] so tests
+//! and load runs can script throttling, quota exhaustion, credential expiry,
+//! mid-stream disconnects and slow first tokens.
+
+/// Anthropic messages API responses.
+mod anthropic;
+/// Codex responses API responses.
+mod codex;
+/// Kiro event-stream responses.
+mod kiro;
+/// OpenAI-compatible models and chat completions responses.
+mod openai;
+/// Scripted scenarios and per-request scenario selection.
+pub mod scenario;
+/// Paced streaming bodies shared by the dialects.
+mod stream;
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::Arc,
+    time::Duration,
+};
+
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use parking_lot::Mutex;
+use scenario::{scenario_from_headers, Scenario};
+use serde_json::{json, Value};
+
+/// Text streamed back when no reply text is configured.
+pub const DEFAULT_REPLY_TEXT: &str = "Hello from the llm-access mock upstream.";
+
+/// Static behaviour of a mock upstream instance.
+#[derive(Debug, Clone)]
+pub struct MockConfig {
+    /// Scenario used when neither the request nor the queue picks one.
+    pub default_scenario: Scenario,
+    /// Assistant text returned by successful completions.
+    pub reply_text: String,
+    /// Pause between consecutive content chunks.
+    pub chunk_delay: Duration,
+}
+
+impl Default for MockConfig {
+    fn default() -> Self {
+        Self {
+            default_scenario: Scenario::Ok,
+            reply_text: DEFAULT_REPLY_TEXT.to_string(),
+            chunk_delay: Duration::ZERO,
+        }
+    }
+}
+
+/// Shared state behind the mock router.
+#[derive(Debug)]
+pub struct MockState {
+    config: MockConfig,
+    queued: Mutex<VecDeque<Scenario>>,
+    served: Mutex<BTreeMap<(&'static str, &'static str), u64>>,
+}
+
+impl MockState {
+    /// Create state for `config` with an empty scenario queue.
+    pub fn new(config: MockConfig) -> Self {
+        Self {
+            config,
+            queued: Mutex::new(VecDeque::new()),
+            served: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Append scenarios consumed, one per request, before the default applies.
+    pub fn enqueue(&self, scenarios: impl IntoIterator<Item = Scenario>) {
+        self.queued.lock().extend(scenarios);
+    }
+
+    /// Pick the scenario for one request and count it under `dialect`.
+    ///
+    /// Precedence: request header or scenario credential, then the queue, then
+    /// the configured default.
+    pub(crate) fn resolve(&self, dialect: &'static str, headers: &HeaderMap) -> Scenario {
+        let scenario = scenario_from_headers(headers)
+            .or_else(|| self.queued.lock().pop_front())
+            .unwrap_or(self.config.default_scenario);
+        *self
+            .served
+            .lock()
+            .entry((dialect, scenario.label()))
+            .or_default() += 1;
+        tracing::debug!(dialect, %scenario, "mock upstream request");
+        scenario
+    }
+
+    /// Reply text split into the content chunks a completion streams.
+    pub(crate) fn reply_chunks(&self) -> Vec<String> {
+        let mut chunks = self
+            .config
+            .reply_text
+            .split_inclusive(' ')
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        if chunks.is_empty() {
+            chunks.push(String::new());
+        }
+        chunks
+    }
+
+    pub(crate) fn chunk_delay(&self) -> Duration {
+        self.config.chunk_delay
+    }
+
+    fn stats(&self) -> Value {
+        let mut dialects = serde_json::Map::new();
+        for ((dialect, scenario), count) in self.served.lock().iter() {
+            let entry = dialects
+                .entry(dialect.to_string())
+                .or_insert_with(|| json!({}));
+            entry[*scenario] = json!(count);
+        }
+        json!({
+            "served": dialects,
+            "queued": self
+                .queued
+                .lock()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+        })
+    }
+}
+
+/// Build the mock router.
+///
+/// Upstream routes:
+/// - Codex: `POST /responses`, `/v1/responses` and
+///   `/backend-api/codex/responses`
+/// - Kiro: `POST /generateAssistantResponse`
+/// - Anthropic: `POST /v1/messages` and `/v1/messages/count_tokens`
+/// - OpenAI-compatible: `GET /v1/models` and `POST /v1/chat/completions`
+///
+/// Control routes: `GET /healthz`, `GET /__mock/stats`, and
+/// `POST /__mock/scenarios` with a JSON array of scenario specs to queue.
+pub fn router(state: Arc<MockState>) -> Router {
+    Router::new()
+        .route("/healthz", get(|| async { "ok" }))
+        .route("/responses", post(codex::responses))
+        .route("/v1/responses", post(codex::responses))
+        .route("/backend-api/codex/responses", post(codex::responses))
+        .route("/generateAssistantResponse", post(kiro::generate_assistant_response))
+        .route("/v1/messages", post(anthropic::messages))
+        .route("/v1/messages/count_tokens", post(anthropic::count_tokens))
+        .route("/v1/models", get(openai::models))
+        .route("/v1/chat/completions", post(openai::chat_completions))
+        .route("/__mock/stats", get(stats))
+        .route("/__mock/scenarios", post(enqueue_scenarios))
+        .with_state(state)
+}
+
+async fn stats(State(state): State<Arc<MockState>>) -> Json<Value> {
+    Json(state.stats())
+}
+
+async fn enqueue_scenarios(
+    State(state): State<Arc<MockState>>,
+    Json(specs): Json<Vec<String>>,
+) -> Response {
+    let parsed = specs
+        .iter()
+        .map(|spec| spec.parse::<Scenario>())
+        .collect::<anyhow::Result<Vec<_>>>();
+    match parsed {
+        Ok(scenarios) => {
+            let count = scenarios.len();
+            state.enqueue(scenarios);
+            Json(json!({ "queued": count })).into_response()
+        },
+        Err(err) => {
+            (StatusCode::BAD_REQUEST, Json(json!({ "error": format!("{err:#}") }))).into_response()
+        },
+    }
+}
+
+/// JSON response with an optional `Retry-After` header.
+pub(crate) fn json_response(
+    status: StatusCode,
+    body: &Value,
+    retry_after_secs: Option<u64>,
+) -> Response {
+    let mut response = (status, Json(body)).into_response();
+    if let Some(seconds) = retry_after_secs {
+        response
+            .headers_mut()
+            .insert(header::RETRY_AFTER, HeaderValue::from(seconds));
+    }
+    response
+}
+
+/// Rough token estimate used for mock usage accounting.
+pub(crate) fn estimate_tokens(bytes: usize) -> u64 {
+    (bytes as u64 / 4).max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::{
+        body::{to_bytes, Body},
+        http::{header, Request, StatusCode},
+        Router,
+    };
+    use llm_access_kiro::parser::frame::parse_frame;
+    use serde_json::{json, Value};
+    use tower::ServiceExt;
+
+    use super::{router, MockConfig, MockState};
+
+    fn test_router() -> (Router, Arc<MockState>) {
+        let state = Arc::new(MockState::new(MockConfig {
+            reply_text: "one two three".to_string(),
+            ..MockConfig::default()
+        }));
+        (router(state.clone()), state)
+    }
+
+    fn post_json(uri: &str, scenario: Option<&str>, body: Value) -> Request<Body> {
+        let mut builder = Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header(header::CONTENT_TYPE, "application/json");
+        if let Some(scenario) = scenario {
+            builder = builder.header("x-mock-scenario", scenario);
+        }
+        builder.body(Body::from(body.to_string())).expect("request")
+    }
+
+    async fn body_text(response: axum::response::Response) -> String {
+        let bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        String::from_utf8(bytes.to_vec()).expect("utf8 body")
+    }
+
+    #[tokio::test]
+    async fn codex_stream_emits_deltas_and_completed_usage() {
+        let (app, _) = test_router();
+        let response = app
+            .oneshot(post_json(
+                "/backend-api/codex/responses",
+                None,
+                json!({"model":"gpt-5-codex","stream":true,"input":"hi"}),
+            ))
+            .await
+            .expect("response");
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = body_text(response).await;
+        assert!(body.starts_with("event: response.created\n"));
+        assert_eq!(body.matches("event: response.output_text.delta").count(), 3);
+        let completed = body
+            .split("\n\n")
+            .find(|event| event.starts_with("event: response.completed"))
+            .and_then(|event| event.split_once("data: "))
+            .map(|(_, data)| serde_json::from_str::<Value>(data).expect("completed json"))
+            .expect("completed event");
+        assert_eq!(completed["response"]["usage"]["output_tokens"], json!(3));
+        assert_eq!(
+            completed["response"]["output"][0]["content"][0]["text"],
+            json!("one two three")
+        );
+    }
+
+    #[tokio::test]
+    async fn kiro_stream_frames_decode_with_the_kiro_parser() {
+        let (app, _) = test_router();
+        let response = app
+            .oneshot(post_json("/generateAssistantResponse", None, json!({"conversationState":{}})))
+            .await
+            .expect("response");
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let mut offset = 0;
+        let mut text = String::new();
+        let mut event_types = Vec::new();
+        while let Some((frame, consumed)) = parse_frame(&bytes[offset..]).expect("frame") {
+            offset += consumed;
+            let event_type = frame.event_type().unwrap_or_default().to_string();
+            if event_type == "assistantResponseEvent" {
+                let payload: Value = frame.payload_as_json().expect("payload");
+                text.push_str(payload["content"].as_str().unwrap_or_default());
+            }
+            event_types.push(event_type);
+        }
+        assert_eq!(offset, bytes.len());
+        assert_eq!(text, "one two three");
+        assert_eq!(event_types.last().map(String::as_str), Some("contextUsageEvent"));
+        assert!(event_types.iter().any(|event| event == "meteringEvent"));
+    }
+
+    #[tokio::test]
+    async fn kiro_monthly_quota_matches_the_dispatch_classifier_shape() {
+        let (app, _) = test_router();
+        let response = app
+            .oneshot(post_json("/generateAssistantResponse", Some("402"), json!({})))
+            .await
+            .expect("response");
+        assert_eq!(response.status(), StatusCode::PAYMENT_REQUIRED);
+        let body: Value = serde_json::from_str(&body_text(response).await).expect("json");
+        assert_eq!(body["reason"], json!("MONTHLY_REQUEST_COUNT"));
+    }
+
+    #[tokio::test]
+    async fn anthropic_rate_limit_sets_retry_after() {
+        let (app, _) = test_router();
+        let response = app
+            .oneshot(post_json("/v1/messages", Some("rate_limit:9"), json!({"stream":false})))
+            .await
+            .expect("response");
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(
+            response
+                .headers()
+                .get(header::RETRY_AFTER)
+                .and_then(|value| value.to_str().ok()),
+            Some("9")
+        );
+        let body: Value = serde_json::from_str(&body_text(response).await).expect("json");
+        assert_eq!(body["error"]["type"], json!("rate_limit_error"));
+    }
+
+    #[tokio::test]
+    async fn anthropic_json_and_stream_responses_carry_reply_text() {
+        let (app, _) = test_router();
+        let response = app
+            .clone()
+            .oneshot(post_json(
+                "/v1/messages",
+                None,
+                json!({"model":"claude-sonnet-4-5","stream":false,"messages":[]}),
+            ))
+            .await
+            .expect("response");
+        let body: Value = serde_json::from_str(&body_text(response).await).expect("json");
+        assert_eq!(body["content"][0]["text"], json!("one two three"));
+        assert_eq!(body["model"], json!("claude-sonnet-4-5"));
+
+        let response = app
+            .oneshot(post_json("/v1/messages", None, json!({"stream":true})))
+            .await
+            .expect("response");
+        let body = body_text(response).await;
+        assert!(body.starts_with("event: message_start\n"));
+        assert_eq!(body.matches("\"text_delta\"").count(), 3);
+        assert!(body
+            .trim_end()
+            .ends_with("data: {\"type\":\"message_stop\"}"));
+    }
+
+    #[tokio::test]
+    async fn openai_models_and_streamed_chat_completion_report_usage() {
+        let (app, _) = test_router();
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/v1/models")
+                    .body(Body::empty())
+                    .expect("request"),
+            )
+            .await
+            .expect("response");
+        let models: Value = serde_json::from_str(&body_text(response).await).expect("json");
+        assert_eq!(models["data"][0]["id"], json!("mock-model"));
+
+        let response = app
+            .oneshot(post_json(
+                "/v1/chat/completions",
+                None,
+                json!({"stream":true,"stream_options":{"include_usage":true},"messages":[]}),
+            ))
+            .await
+            .expect("response");
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = body_text(response).await;
+        let events = body
+            .split("\n\n")
+            .filter_map(|event| event.strip_prefix("data: "))
+            .collect::<Vec<_>>();
+        assert_eq!(events.last(), Some(&"[DONE]"));
+        let text = events
+            .iter()
+            .filter_map(|data| serde_json::from_str::<Value>(data).ok())
+            .filter_map(|chunk| {
+                chunk["choices"][0]["delta"]["content"]
+                    .as_str()
+                    .map(str::to_string)
+            })
+            .collect::<String>();
+        assert_eq!(text, "one two three");
+        let usage: Value = serde_json::from_str(events[events.len() - 2]).expect("usage chunk");
+        assert_eq!(usage["usage"]["completion_tokens"], json!(3));
+    }
+
+    #[tokio::test]
+    async fn disconnect_aborts_the_stream_after_the_scripted_chunks() {
+        let (app, _) = test_router();
+        let response = app
+            .oneshot(post_json("/v1/responses", Some("disconnect:1"), json!({"stream":true})))
+            .await
+            .expect("response");
+        assert_eq!(response.status(), StatusCode::OK);
+        let mut body = response.into_body().into_data_stream();
+        let mut received = String::new();
+        let mut aborted = false;
+        while let Some(chunk) = tokio_stream::StreamExt::next(&mut body).await {
+            match chunk {
+                Ok(bytes) => received.push_str(&String::from_utf8_lossy(&bytes)),
+                Err(_) => {
+                    aborted = true;
+                    break;
+                },
+            }
+        }
+        assert!(aborted);
+        assert_eq!(
+            received
+                .matches("event: response.output_text.delta")
+                .count(),
+            1
+        );
+        assert!(!received.contains("response.completed"));
+    }
+
+    #[tokio::test]
+    async fn queued_scenarios_apply_in_order_and_show_in_stats() {
+        let (app, _) = test_router();
+        let response = app
+            .clone()
+            .oneshot(post_json("/__mock/scenarios", None, json!(["auth_expired", "429"])))
+            .await
+            .expect("response");
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let mut statuses = Vec::new();
+        for _ in 0..3 {
+            let response = app
+                .clone()
+                .oneshot(post_json("/responses", None, json!({"stream":false})))
+                .await
+                .expect("response");
+            statuses.push(response.status());
+        }
+        assert_eq!(statuses, [
+            StatusCode::UNAUTHORIZED,
+            StatusCode::TOO_MANY_REQUESTS,
+            StatusCode::OK
+        ]);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/__mock/stats")
+                    .body(Body::empty())
+                    .expect("request"),
+            )
+            .await
+            .expect("response");
+        let stats: Value = serde_json::from_str(&body_text(response).await).expect("json");
+        assert_eq!(stats["served"]["codex"]["auth_expired"], json!(1));
+        assert_eq!(stats["served"]["codex"]["ok"], json!(1));
+        assert_eq!(stats["queued"], json!([]));
+    }
+
+    #[tokio::test]
+    async fn invalid_queued_scenarios_are_rejected_whole() {
+        let (app, state) = test_router();
+        let response = app
+            .oneshot(post_json("/__mock/scenarios", None, json!(["ok", "bogus"])))
+            .await
+            .expect("response");
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert!(state.queued.lock().is_empty());
+    }
+}
@@ -0,0 +1,54 @@
+//! Standalone mock upstream executable for offline and load testing.
+
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+use anyhow::Context;
+use clap::Parser;
+use llm_access_mock_upstream::{
+    router, scenario::Scenario, MockConfig, MockState, DEFAULT_REPLY_TEXT,
+};
+use tokio::net::TcpListener;
+
+const DEFAULT_BIND_ADDR: &str = "127.0.0.1:19090";
+
+#[derive(Debug, Parser)]
+#[command(name = "llm-access-mock-upstream")]
+struct Cli {
+    #[arg(long, default_value = DEFAULT_BIND_ADDR)]
+    bind: SocketAddr,
+    /// Scenario for requests that do not pick one, e.g. `ok` or
+    /// `rate_limit:10`.
+    #[arg(long, default_value = "ok")]
+    scenario: Scenario,
+    /// Scenarios consumed one per request before the default applies.
+    #[arg(long = "queue", value_delimiter = ',')]
+    queued: Vec<Scenario>,
+    #[arg(long, default_value = DEFAULT_REPLY_TEXT)]
+    reply_text: String,
+    #[arg(long, default_value_t = 0)]
+    chunk_delay_ms: u64,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(
+            std::env::var("RUST_LOG")
+                .unwrap_or_else(|_| "info,llm_access_mock_upstream=info".to_string()),
+        )
+        .try_init();
+    let cli = Cli::parse();
+    let state = Arc::new(MockState::new(MockConfig {
+        default_scenario: cli.scenario,
+        reply_text: cli.reply_text,
+        chunk_delay: Duration::from_millis(cli.chunk_delay_ms),
+    }));
+    state.enqueue(cli.queued);
+    let listener = TcpListener::bind(cli.bind)
+        .await
+        .with_context(|| format!("bind mock upstream on {}", cli.bind))?;
+    tracing::info!(bind = %cli.bind, scenario = %cli.scenario, "mock upstream listening");
+    axum::serve(listener, router(state))
+        .await
+        .context("serve mock upstream")
+}
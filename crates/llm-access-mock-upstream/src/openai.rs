@@ -0,0 +1,140 @@
+//! Generic OpenAI-compatible upstream: `/v1/models` and
+//! `/v1/chat/completions`, the surface vLLM, llama.cpp and similar servers
+//! expose.
+
+use std::sync::Arc;
+
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::{json, Value};
+
+use crate::{
+    estimate_tokens, json_response,
+    scenario::Scenario,
+    stream::{json_reply, paced_body, StreamParts},
+    MockState,
+};
+
+const DEFAULT_MODEL: &str = "mock-model";
+
+pub(crate) async fn models() -> Json<Value> {
+    Json(json!({
+        "object": "list",
+        "data": [{"id": DEFAULT_MODEL, "object": "model", "owned_by": "llm-access-mock"}],
+    }))
+}
+
+pub(crate) async fn chat_completions(
+    State(state): State<Arc<MockState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let scenario = state.resolve("openai", &headers);
+    if let Some(rejection) = rejection(scenario) {
+        return rejection;
+    }
+    let request = serde_json::from_slice::<Value>(&body).unwrap_or(Value::Null);
+    let model = request["model"].as_str().unwrap_or(DEFAULT_MODEL);
+    let chunks = state.reply_chunks();
+    let completion_id = format!("chatcmpl-mock-{}", body.len());
+    let prompt_tokens = estimate_tokens(body.len());
+    let usage = json!({
+        "prompt_tokens": prompt_tokens,
+        "completion_tokens": chunks.len(),
+        "total_tokens": prompt_tokens + chunks.len() as u64,
+    });
+    if request["stream"].as_bool() != Some(true) {
+        let completion = json!({
+            "id": completion_id,
+            "object": "chat.completion",
+            "model": model,
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": chunks.concat()},
+                "finish_reason": "stop",
+            }],
+            "usage": usage,
+        });
+        return json_reply(completion, scenario).await;
+    }
+    let chunk = |delta: Value, finish_reason: Value| {
+        sse_data(&json!({
+            "id": completion_id,
+            "object": "chat.completion.chunk",
+            "model": model,
+            "choices": [{"index": 0, "delta": delta, "finish_reason": finish_reason}],
+        }))
+    };
+    let mut epilogue = vec![chunk(json!({}), json!("stop"))];
+    if request["stream_options"]["include_usage"].as_bool() == Some(true) {
+        epilogue.push(sse_data(&json!({
+            "id": completion_id,
+            "object": "chat.completion.chunk",
+            "model": model,
+            "choices": [],
+            "usage": usage,
+        })));
+    }
+    epilogue.push(Bytes::from_static(b"data: [DONE]\n\n"));
+    let parts = StreamParts {
+        prelude: vec![chunk(json!({"role": "assistant", "content": ""}), Value::Null)],
+        content: chunks
+            .iter()
+            .map(|text| chunk(json!({"content": text}), Value::Null))
+            .collect(),
+        epilogue,
+    };
+    (
+        [(header::CONTENT_TYPE, "text/event-stream")],
+        paced_body(parts, scenario, state.chunk_delay()),
+    )
+        .into_response()
+}
+
+/// OpenAI-style streams carry bare `data:` lines without event names.
+fn sse_data(data: &Value) -> Bytes {
+    Bytes::from(format!("data: {data}\n\n"))
+}
+
+fn rejection(scenario: Scenario) -> Option<Response> {
+    let (status, error_type, code, message, retry_after) = match scenario {
+        Scenario::RateLimited {
+            retry_after_secs,
+        } => (
+            StatusCode::TOO_MANY_REQUESTS,
+            "rate_limit_error",
+            "rate_limit_exceeded",
+            "Rate limit reached for requests.",
+            Some(retry_after_secs),
+        ),
+        Scenario::MonthlyQuota => (
+            StatusCode::PAYMENT_REQUIRED,
+            "insufficient_quota",
+            "insufficient_quota",
+            "You exceeded your current quota.",
+            None,
+        ),
+        Scenario::AuthExpired => (
+            StatusCode::UNAUTHORIZED,
+            "invalid_request_error",
+            "invalid_api_key",
+            "Incorrect API key provided.",
+            None,
+        ),
+        Scenario::ServerError => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "server_error",
+            "server_error",
+            "The server is temporarily unavailable.",
+            None,
+        ),
+        _ => return None,
+    };
+    let body = json!({"error": {"type": error_type, "code": code, "message": message}});
+    Some(json_response(status, &body, retry_after))
+}
@@ -0,0 +1,237 @@
+//! Scripted upstream behaviours and how a request selects one.
+
+use std::{fmt, str::FromStr, time::Duration};
+
+use anyhow::{anyhow, Context};
+use axum::http::{header, HeaderMap};
+
+/// Request header that forces a scenario for a single request.
+pub const SCENARIO_HEADER: &str = "x-mock-scenario";
+/// Credential prefix that pins a scenario to an imported mock account, e.g. an
+/// access token of `mock:monthly_quota`.
+pub const SCENARIO_TOKEN_PREFIX: &str = "mock:";
+
+const DEFAULT_RETRY_AFTER_SECS: u64 = 30;
+const DEFAULT_DISCONNECT_AFTER_CHUNKS: usize = 2;
+const DEFAULT_SLOW_FIRST_TOKEN_MS: u64 = 5_000;
+
+/// One scripted upstream behaviour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scenario {
+    /// Return a normal successful completion.
+    Ok,
+    /// Reject with `429` and a `Retry-After` header.
+    RateLimited {
+        /// Seconds advertised in `Retry-After`.
+        retry_after_secs: u64,
+    },
+    /// Reject with `402` and a `MONTHLY_REQUEST_COUNT` quota reason.
+    MonthlyQuota,
+    /// Reject as if the account credential had expired.
+    AuthExpired,
+    /// Fail with `503` as if the upstream service were unavailable.
+    ServerError,
+    /// Send `after_chunks` content chunks, then drop the connection without a
+    /// terminal event.
+    Disconnect {
+        /// Content chunks delivered before the connection is aborted.
+        after_chunks: usize,
+    },
+    /// Hold the first content chunk back for `delay_ms`.
+    SlowFirstToken {
+        /// Delay before the first content chunk, in milliseconds.
+        delay_ms: u64,
+    },
+}
+
+impl Scenario {
+    /// Delay applied before the first content chunk.
+    pub fn first_token_delay(self) -> Duration {
+        match self {
+            Self::SlowFirstToken {
+                delay_ms,
+            } => Duration::from_millis(delay_ms),
+            _ => Duration::ZERO,
+        }
+    }
+
+    /// Number of content chunks sent before the connection is aborted.
+    pub fn disconnect_after(self) -> Option<usize> {
+        match self {
+            Self::Disconnect {
+                after_chunks,
+            } => Some(after_chunks),
+            _ => None,
+        }
+    }
+
+    /// Stable label used in stats and logs.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::RateLimited {
+                ..
+            } => "rate_limit",
+            Self::MonthlyQuota => "monthly_quota",
+            Self::AuthExpired => "auth_expired",
+            Self::ServerError => "server_error",
+            Self::Disconnect {
+                ..
+            } => "disconnect",
+            Self::SlowFirstToken {
+                ..
+            } => "slow_first_token",
+        }
+    }
+}
+
+impl fmt::Display for Scenario {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::RateLimited {
+                retry_after_secs,
+            } => write!(f, "rate_limit:{retry_after_secs}"),
+            Self::Disconnect {
+                after_chunks,
+            } => write!(f, "disconnect:{after_chunks}"),
+            Self::SlowFirstToken {
+                delay_ms,
+            } => write!(f, "slow_first_token:{delay_ms}"),
+            other => f.write_str(other.label()),
+        }
+    }
+}
+
+impl FromStr for Scenario {
+    type Err = anyhow::Error;
+
+    /// Parse `name[:arg]`, e.g. `ok`, `rate_limit:10`, `disconnect:3`,
+    /// `slow_first_token:2000`. Status-code aliases (`429`, `402`, `401`,
+    /// `503`) are accepted for the rejection scenarios.
+    fn from_str(value: &str) -> anyhow::Result<Self> {
+        let value = value.trim().to_ascii_lowercase();
+        let (name, arg) = match value.split_once(':') {
+            Some((name, arg)) => (name, Some(arg.trim())),
+            None => (value.as_str(), None),
+        };
+        let scenario = match name.trim() {
+            "ok" => Self::Ok,
+            "rate_limit" | "429" => Self::RateLimited {
+                retry_after_secs: parse_arg(arg, DEFAULT_RETRY_AFTER_SECS)?,
+            },
+            "monthly_quota" | "402" => Self::MonthlyQuota,
+            "auth_expired" | "401" => Self::AuthExpired,
+            "server_error" | "503" => Self::ServerError,
+            "disconnect" => Self::Disconnect {
+                after_chunks: parse_arg(arg, DEFAULT_DISCONNECT_AFTER_CHUNKS)?,
+            },
+            "slow_first_token" | "slow" => Self::SlowFirstToken {
+                delay_ms: parse_arg(arg, DEFAULT_SLOW_FIRST_TOKEN_MS)?,
+            },
+            other => return Err(anyhow!("unknown mock scenario `{other}`")),
+        };
+        if arg.is_some()
+            && matches!(
+                scenario,
+                Self::Ok | Self::MonthlyQuota | Self::AuthExpired | Self::ServerError
+            )
+        {
+            return Err(anyhow!("mock scenario `{name}` takes no argument"));
+        }
+        Ok(scenario)
+    }
+}
+
+fn parse_arg<T: FromStr>(arg: Option<&str>, default: T) -> anyhow::Result<T>
+where
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    match arg {
+        Some(arg) => arg
+            .parse()
+            .with_context(|| format!("invalid mock scenario argument `{arg}`")),
+        None => Ok(default),
+    }
+}
+
+/// Scenario requested by the request itself: the [`SCENARIO_HEADER`] header
+/// first, then a credential carrying [`SCENARIO_TOKEN_PREFIX`].
+///
+/// Malformed specs are ignored so a typo degrades to the queued or default
+/// scenario instead of an unexplained mock failure.
+pub fn scenario_from_headers(headers: &HeaderMap) -> Option<Scenario> {
+    if let Some(scenario) = headers
+        .get(SCENARIO_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok())
+    {
+        return Some(scenario);
+    }
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    let api_key = headers
+        .get("x-api-key")
+        .and_then(|value| value.to_str().ok());
+    bearer
+        .into_iter()
+        .chain(api_key)
+        .filter_map(|token| token.trim().strip_prefix(SCENARIO_TOKEN_PREFIX))
+        .find_map(|spec| spec.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{header, HeaderMap, HeaderValue};
+
+    use super::{scenario_from_headers, Scenario, SCENARIO_HEADER};
+
+    #[test]
+    fn scenario_specs_parse_with_aliases_and_defaults() {
+        assert_eq!("ok".parse::<Scenario>().expect("ok"), Scenario::Ok);
+        assert_eq!("429".parse::<Scenario>().expect("429"), Scenario::RateLimited {
+            retry_after_secs: 30
+        });
+        assert_eq!("Disconnect:5".parse::<Scenario>().expect("disconnect"), Scenario::Disconnect {
+            after_chunks: 5
+        });
+        assert_eq!(
+            "slow_first_token:250".parse::<Scenario>().expect("slow"),
+            Scenario::SlowFirstToken {
+                delay_ms: 250
+            }
+        );
+        assert!("monthly_quota:1".parse::<Scenario>().is_err());
+        assert!("rate_limit:soon".parse::<Scenario>().is_err());
+        assert!("explode".parse::<Scenario>().is_err());
+        assert_eq!("503".parse::<Scenario>().expect("503"), Scenario::ServerError);
+        for spec in
+            ["ok", "rate_limit:7", "monthly_quota", "auth_expired", "server_error", "disconnect:0"]
+        {
+            let scenario = spec.parse::<Scenario>().expect("spec");
+            assert_eq!(scenario.to_string(), spec);
+        }
+    }
+
+    #[test]
+    fn header_wins_over_scenario_credential() {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_static("Bearer mock:402"));
+        assert_eq!(scenario_from_headers(&headers), Some(Scenario::MonthlyQuota));
+
+        headers.insert(SCENARIO_HEADER, HeaderValue::from_static("auth_expired"));
+        assert_eq!(scenario_from_headers(&headers), Some(Scenario::AuthExpired));
+
+        let mut api_key = HeaderMap::new();
+        api_key.insert("x-api-key", HeaderValue::from_static("mock:disconnect:1"));
+        assert_eq!(
+            scenario_from_headers(&api_key),
+            Some(Scenario::Disconnect {
+                after_chunks: 1
+            })
+        );
+        api_key.insert("x-api-key", HeaderValue::from_static("sk-real-looking"));
+        assert_eq!(scenario_from_headers(&api_key), None);
+    }
+}
@@ -0,0 +1,93 @@
+//! Paced response bodies with scripted slow starts and disconnects.
+
+use std::{io, time::Duration};
+
+use axum::{
+    body::Body,
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use bytes::Bytes;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+
+use crate::scenario::Scenario;
+
+/// Encoded pieces of one streamed response.
+pub(crate) struct StreamParts {
+    /// Sent immediately, before any content (e.g. `message_start`).
+    pub prelude: Vec<Bytes>,
+    /// Content chunks subject to the scenario's pacing and disconnect point.
+    pub content: Vec<Bytes>,
+    /// Terminal events sent after every content chunk was delivered.
+    pub epilogue: Vec<Bytes>,
+}
+
+/// Stream `parts` as a response body, applying the scenario's first-token
+/// delay and aborting the body after its disconnect point.
+pub(crate) fn paced_body(parts: StreamParts, scenario: Scenario, chunk_delay: Duration) -> Body {
+    let (tx, rx) = mpsc::channel::<io::Result<Bytes>>(8);
+    tokio::spawn(async move {
+        let cut = scenario
+            .disconnect_after()
+            .map(|after| after.min(parts.content.len()));
+        for chunk in parts.prelude {
+            if tx.send(Ok(chunk)).await.is_err() {
+                return;
+            }
+        }
+        for (index, chunk) in parts.content.into_iter().enumerate() {
+            if cut == Some(index) {
+                break;
+            }
+            let delay = if index == 0 { scenario.first_token_delay() } else { chunk_delay };
+            if !delay.is_zero() {
+                tokio::time::sleep(delay).await;
+            }
+            if tx.send(Ok(chunk)).await.is_err() {
+                return;
+            }
+        }
+        if cut.is_some() {
+            let _ = tx
+                .send(Err(io::Error::new(
+                    io::ErrorKind::ConnectionAborted,
+                    "mock upstream scripted disconnect",
+                )))
+                .await;
+            return;
+        }
+        for chunk in parts.epilogue {
+            if tx.send(Ok(chunk)).await.is_err() {
+                return;
+            }
+        }
+    });
+    Body::from_stream(ReceiverStream::new(rx))
+}
+
+/// Format one server-sent event.
+pub(crate) fn sse_event(event: &str, data: &serde_json::Value) -> Bytes {
+    Bytes::from(format!("event: {event}\ndata: {data}\n\n"))
+}
+
+/// Non-streaming JSON reply under `scenario`: the first-token delay holds the
+/// whole response back, and a disconnect delivers half the body then aborts.
+pub(crate) async fn json_reply(body: serde_json::Value, scenario: Scenario) -> Response {
+    let delay = scenario.first_token_delay();
+    if !delay.is_zero() {
+        tokio::time::sleep(delay).await;
+    }
+    if scenario.disconnect_after().is_none() {
+        return Json(body).into_response();
+    }
+    let encoded = body.to_string().into_bytes();
+    let parts = StreamParts {
+        prelude: vec![Bytes::from(encoded[..encoded.len() / 2].to_vec())],
+        content: Vec::new(),
+        epilogue: Vec::new(),
+    };
+    ([(header::CONTENT_TYPE, "application/json")], paced_body(parts, scenario, Duration::ZERO))
+        .into_response()
+}
@@ -13,6 +13,8 @@ pub mod postgres;
 pub(crate) mod records;
 /// Valkey-backed request-path cache primitives.
 pub mod request_cache;
+/// Envelope encryption for credentials at rest.
+pub mod secrets;
 /// Postgres-backed archived usage catalog metadata.
 #[cfg(feature = "duckdb-runtime")]
 pub(crate) mod usage_catalog;
@@ -1,7 +1,7 @@
 //! Postgres control-plane repository for `llm-access`.
 
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     env,
     sync::Arc,
     time::{Duration, Instant},
@@ -12,8 +12,9 @@ use async_trait::async_trait;
 use llm_access_core::{
     store::{
         self as core_store, AdminKiroBalanceView, AdminKiroCacheView, AdminProxyBinding,
-        AdminProxyConfig, AdminProxyEndpointCheck, AnthropicUpstreamChannelUsageDelta,
-        AuthenticatedKey, CodexRateLimitStatus, ControlStore, KeyUsageRollupDelta,
+        AdminProxyConfig, AdminProxyEndpointCheck, AdminProxyPool,
+        AnthropicUpstreamChannelUsageDelta, AuthenticatedKey, CodexRateLimitStatus, ControlStore,
+        KeyUsageRollupDelta, OpenAiUpstreamChannelUsageDelta,
     },
     usage::UsageEvent,
 };
@@ -25,9 +26,13 @@ use sqlx_core::{
 use sqlx_postgres::{PgArguments, PgPool, PgPoolOptions, PgRow as SqlxPgRow, Postgres};
 use tokio::sync::{Mutex, RwLock};
 
-use crate::request_cache::{RequestCache, RequestCacheConfig};
+use crate::{
+    request_cache::{RequestCache, RequestCacheConfig},
+    secrets::SecretKeyring,
+};
 
 mod anthropic_upstream;
+mod batch;
 mod cache;
 mod cache_convert;
 mod codex_account;
@@ -36,18 +41,25 @@ mod config;
 mod decode;
 mod groups;
 mod json;
+mod key_portal;
 mod keys;
 mod kiro_account;
+mod moderation;
+mod openai_upstream;
 mod proxy;
+mod proxy_pool;
 mod proxy_support;
 mod public;
 mod review;
 mod routes;
+mod secret_rotation;
 mod status;
+mod sub_keys;
 mod usage;
 
 #[cfg(test)]
 use proxy_support::resolve_provider_proxy_config_from_context;
+pub use secret_rotation::SecretRotationReport;
 
 
 trait SqlxBindParam {
@@ -129,6 +141,7 @@ struct KiroRouteCandidateRow {
     pool_strategy: String,
     proxy_mode: Option<String>,
     auth_proxy_config_id: Option<String>,
+    proxy_pool_id: Option<String>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -163,6 +176,33 @@ pub(crate) struct AnthropicUpstreamChannelRow {
     last_used_at_ms: Option<i64>,
 }
 
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub(crate) struct OpenAiUpstreamChannelRow {
+    channel_name: String,
+    status: String,
+    base_url: String,
+    api_key: Option<String>,
+    weight: i64,
+    max_concurrency: i64,
+    min_start_interval_ms: i64,
+    proxy_mode: String,
+    proxy_config_id: Option<String>,
+    last_error: Option<String>,
+    model_ids: Vec<String>,
+    last_models_status: Option<String>,
+    last_models_latency_ms: Option<i64>,
+    last_models_checked_at_ms: Option<i64>,
+    last_models_error: Option<String>,
+    created_at_ms: i64,
+    updated_at_ms: i64,
+    input_uncached_tokens: i64,
+    input_cached_tokens: i64,
+    output_tokens: i64,
+    billable_tokens: i64,
+    usage_missing_events: i64,
+    last_used_at_ms: Option<i64>,
+}
+
 fn normalize_manual_usage_limit(value: f64) -> Option<f64> {
     value.is_finite().then(|| value.max(0.0))
 }
@@ -182,6 +222,30 @@ fn aggregate_usage_rollup_deltas(
     Ok(deltas.into_values().collect())
 }
 
+/// Add a copy of every sub-key delta under its parent key so delegated usage
+/// also counts against the parent's quota.
+fn with_parent_rollup_deltas(
+    deltas: Vec<KeyUsageRollupDelta>,
+    parents: &BTreeMap<String, String>,
+) -> Vec<KeyUsageRollupDelta> {
+    let mut merged = BTreeMap::<String, KeyUsageRollupDelta>::new();
+    for delta in deltas {
+        if let Some(parent_key_id) = parents.get(&delta.key_id) {
+            let mut parent_delta = delta.clone();
+            parent_delta.key_id = parent_key_id.clone();
+            merged
+                .entry(parent_key_id.clone())
+                .and_modify(|current| current.add_assign(&parent_delta))
+                .or_insert(parent_delta);
+        }
+        merged
+            .entry(delta.key_id.clone())
+            .and_modify(|current| current.add_assign(&delta))
+            .or_insert(delta);
+    }
+    merged.into_values().collect()
+}
+
 #[derive(Clone)]
 struct SqlxClient {
     pool: PgPool,
@@ -331,6 +395,8 @@ pub struct PostgresControlRepository {
     codex_status_cache: Arc<RwLock<Option<CachedCodexRateLimitStatus>>>,
     request_cache: Option<RequestCache>,
     proxy_scope: ProxyConfigScope,
+    secrets: Arc<SecretKeyring>,
+    live_proxy_failures: Arc<Mutex<BTreeMap<String, Vec<i64>>>>,
 }
 
 /// Proxy attribution resolved for one consumed usage event.
@@ -363,16 +429,26 @@ struct KiroAdminAccountViewContext {
     status_by_account: BTreeMap<String, KiroCachedStatusParts>,
     proxy_configs_by_id: BTreeMap<String, AdminProxyConfig>,
     kiro_proxy_binding: AdminProxyBinding,
+    proxy_pools: ProxyPoolContext,
 }
 
 struct CodexAdminAccountViewContext {
     proxy_configs_by_id: BTreeMap<String, AdminProxyConfig>,
     codex_proxy_binding: AdminProxyBinding,
+    proxy_pools: ProxyPoolContext,
 }
 
 struct ProviderProxyResolutionContext {
     proxy_configs_by_id: BTreeMap<String, AdminProxyConfig>,
     binding: AdminProxyBinding,
+    proxy_pools: ProxyPoolContext,
+}
+
+/// Proxy pools plus the member health seen from this node for one provider.
+#[derive(Debug, Clone, Default)]
+struct ProxyPoolContext {
+    pools_by_id: BTreeMap<String, AdminProxyPool>,
+    unhealthy_proxy_ids: BTreeSet<String>,
 }
 
 /// Node-local scope used to resolve effective proxy slot contents.
@@ -453,6 +529,7 @@ struct CodexAdminAccountListRow {
     last_usage_checked_at_ms: Option<i64>,
     last_usage_success_at_ms: Option<i64>,
     usage_error_message: Option<String>,
+    proxy_pool_id: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -487,6 +564,7 @@ struct KiroAdminAccountListRow {
     proxy_mode: Option<String>,
     proxy_config_id: Option<String>,
     auth_proxy_config_id: Option<String>,
+    proxy_pool_id: Option<String>,
     proxy_url: Option<String>,
     last_error: Option<String>,
 }
@@ -499,6 +577,8 @@ struct CodexAccountSettings {
     route_weight_tier: Option<String>,
     proxy_mode: String,
     proxy_config_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    proxy_pool_id: Option<String>,
     request_max_concurrency: Option<u64>,
     request_min_start_interval_ms: Option<u64>,
     codex_image_generation_enabled: bool,
@@ -513,6 +593,7 @@ impl Default for CodexAccountSettings {
             route_weight_tier: None,
             proxy_mode: "inherit".to_string(),
             proxy_config_id: None,
+            proxy_pool_id: None,
             request_max_concurrency: None,
             request_min_start_interval_ms: None,
             codex_image_generation_enabled: false,
@@ -607,12 +688,18 @@ impl PostgresControlRepository {
         request_cache_config: Option<RequestCacheConfig>,
         proxy_scope: ProxyConfigScope,
     ) -> anyhow::Result<Self> {
-        let request_cache = request_cache_config.map(RequestCache::new).transpose()?;
+        let secrets = Arc::new(SecretKeyring::from_env()?);
+        let request_cache = request_cache_config
+            .map(RequestCache::new)
+            .transpose()?
+            .map(|cache| cache.with_secrets(Arc::clone(&secrets)));
         Ok(Self {
             client,
             codex_status_cache: Arc::new(RwLock::new(None)),
             request_cache,
             proxy_scope,
+            secrets,
+            live_proxy_failures: Arc::new(Mutex::new(BTreeMap::new())),
         })
     }
 
@@ -771,25 +858,51 @@ impl ControlStore for PostgresControlRepository {
         )
         .await
     }
+
+    async fn record_openai_upstream_channel_usage(
+        &self,
+        channel_name: &str,
+        delta: OpenAiUpstreamChannelUsageDelta,
+    ) -> anyhow::Result<()> {
+        PostgresControlRepository::record_openai_upstream_channel_usage(self, channel_name, delta)
+            .await
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::{collections::HashSet, sync::OnceLock};
+    use std::{
+        collections::{BTreeMap, HashSet},
+        sync::OnceLock,
+    };
 
     use anyhow::Context;
     use llm_access_core::{
         provider::{ProtocolFamily, ProviderType, RouteStrategy},
         store::{
+            AdminAccountGroupPatch, AdminAccountGroupStore,
             AdminAnthropicUpstreamModelsStatusUpdate, AdminAnthropicUpstreamStore,
             AdminAnthropicUpstreamTestStatusUpdate, AdminCodexAccountPageQuery,
-            AdminCodexAccountSortMode, AdminCodexAccountStore, AdminConfigStore, AdminKeyStore,
-            AdminKiroAccountStore, AdminPageRequest, AdminProxyConfigPatch, AdminProxyStore,
-            AdminProxyTrafficSnapshot, AdminReviewQueueStore, AnthropicUpstreamChannelUsageDelta,
-            ControlStore, KeyUsageRollupDelta, NewAdminAnthropicUpstreamChannel,
-            NewAdminProxyConfig, NewPublicAccountContributionRequest, ProviderRouteStore,
-            ProxyTrafficTotals, PublicSubmissionStore, PublicUsageStore, UsageEventSink,
-            UsageRollupBatch, UsageRollupBatchSink,
+            AdminCodexAccountSortMode, AdminCodexAccountStore, AdminConfigStore, AdminKeyPatch,
+            AdminKeyStore, AdminKiroAccountStore, AdminOpenAiUpstreamChannelPatch,
+            AdminOpenAiUpstreamModelsStatusUpdate, AdminOpenAiUpstreamStore, AdminPageRequest,
+            AdminProxyConfigPatch, AdminProxyPoolPatch, AdminProxyStore, AdminProxyTrafficSnapshot,
+            AdminReviewQueueAction, AdminReviewQueueQuery, AdminReviewQueueStore,
+            AnthropicUpstreamChannelUsageDelta, BatchItemOutcome, BatchStore, ControlStore,
+            KeyModerationPolicy, KeyPortalSettingsPatch, KeyPortalStore, KeyQueuePolicy,
+            KeyResponseCachePolicy, KeySecretRotation, KeyUsageRollupDelta, ModerationAction,
+            NewAdminAccountGroup, NewAdminAnthropicUpstreamChannel, NewAdminOpenAiUpstreamChannel,
+            NewAdminProxyConfig, NewAdminProxyPool, NewBatchItem, NewBatchJob,
+            NewKeyNotifyEmailConfirmation, NewModerationEvent, NewPublicAccountContributionRequest,
+            NewSubKey, OpenAiUpstreamChannelUsageDelta, ProviderOpenAiUpstreamResolution,
+            ProviderRouteStore, ProxyPoolMember, ProxyTrafficTotals, PublicSubmissionStore,
+            PublicUsageStore, QueuePriority, RequestTransformOperation, RequestTransformPolicy,
+            SubKeyStore, UsageEventSink, UsageRollupBatch, UsageRollupBatchSink,
+            BATCH_COMPLETION_WINDOW_MS, BATCH_ITEM_STATUS_CANCELLED, BATCH_ITEM_STATUS_SUCCEEDED,
+            BATCH_STATUS_CANCELLED, BATCH_STATUS_COMPLETED, BATCH_STATUS_EXPIRED,
+            BATCH_STATUS_IN_PROGRESS, KEY_NOTIFY_EMAIL_CONFIRMATION_TTL_SECONDS,
+            MODERATION_EVENT_STATUS_CONFIRMED, MODERATION_EVENT_STATUS_DISMISSED,
+            MODERATION_EVENT_STATUS_PENDING,
         },
     };
     use serde::Serialize;
@@ -845,6 +958,9 @@ mod tests {
         client
             .batch_execute(
                 "TRUNCATE TABLE
+                    llm_batch_items,
+                    llm_batches,
+                    llm_moderation_events,
                     llm_account_import_job_items,
                     llm_account_import_jobs,
                     llm_codex_status_cache,
@@ -857,9 +973,13 @@ mod tests {
                     llm_codex_accounts,
                     llm_anthropic_upstream_channel_usage_rollups,
                     llm_anthropic_upstream_channels,
+                    llm_openai_upstream_channel_usage_rollups,
+                    llm_openai_upstream_channels,
                     llm_proxy_config_endpoint_checks,
                     llm_proxy_config_node_overrides,
                     llm_proxy_bindings,
+                    llm_proxy_pool_members,
+                    llm_proxy_pools,
                     llm_proxy_configs,
                     llm_account_groups,
                     llm_runtime_config,
@@ -1198,6 +1318,488 @@ mod tests {
         assert_eq!(key.key_name, "external");
     }
 
+    #[tokio::test]
+    async fn postgres_repository_patches_and_resolves_key_response_cache_policy() {
+        let Ok(database_url) = std::env::var("TEST_POSTGRES_URL") else {
+            eprintln!("skipping postgres integration test: TEST_POSTGRES_URL is not set");
+            return;
+        };
+        let _guard = test_db_guard().await;
+        reset_test_db(&database_url)
+            .await
+            .expect("reset postgres test database");
+        seed_test_key_bundle(&database_url)
+            .await
+            .expect("seed postgres test key bundle");
+        let repo = super::PostgresControlRepository::connect(&database_url, None)
+            .await
+            .expect("connect postgres repository");
+        let key = repo
+            .authenticate_bearer_secret("secret")
+            .await
+            .expect("lookup result")
+            .expect("key must exist");
+        assert_eq!(
+            repo.resolve_response_cache_policy(&key)
+                .await
+                .expect("resolve default policy"),
+            None
+        );
+
+        let policy = KeyResponseCachePolicy {
+            enabled: true,
+            ttl_seconds: 120,
+            hit_billable_ratio: 0.5,
+        };
+        let patched = repo
+            .patch_admin_key("key-1", AdminKeyPatch {
+                response_cache_policy: Some(Some(policy.clone())),
+                updated_at_ms: 1_700_000_000_001,
+                ..AdminKeyPatch::default()
+            })
+            .await
+            .expect("patch key")
+            .expect("key exists");
+        assert_eq!(patched.response_cache_policy, Some(policy.clone()));
+        assert_eq!(
+            repo.resolve_response_cache_policy(&key)
+                .await
+                .expect("resolve enabled policy"),
+            Some(policy)
+        );
+
+        repo.patch_admin_key("key-1", AdminKeyPatch {
+            response_cache_policy: Some(None),
+            updated_at_ms: 1_700_000_000_002,
+            ..AdminKeyPatch::default()
+        })
+        .await
+        .expect("clear policy")
+        .expect("key exists");
+        assert_eq!(
+            repo.resolve_response_cache_policy(&key)
+                .await
+                .expect("resolve cleared policy"),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn postgres_repository_merges_group_and_key_request_transform_policies() {
+        let Ok(database_url) = std::env::var("TEST_POSTGRES_URL") else {
+            eprintln!("skipping postgres integration test: TEST_POSTGRES_URL is not set");
+            return;
+        };
+        let _guard = test_db_guard().await;
+        reset_test_db(&database_url)
+            .await
+            .expect("reset postgres test database");
+        seed_test_key_bundle(&database_url)
+            .await
+            .expect("seed postgres test key bundle");
+        let repo = super::PostgresControlRepository::connect(&database_url, None)
+            .await
+            .expect("connect postgres repository");
+        let key = repo
+            .authenticate_bearer_secret("secret")
+            .await
+            .expect("lookup result")
+            .expect("key must exist");
+        assert_eq!(
+            repo.resolve_request_transform_policy(&key)
+                .await
+                .expect("resolve default policy"),
+            None
+        );
+
+        let group_policy = RequestTransformPolicy {
+            system_prompt_prepend: Some("group rules".to_string()),
+            operations: vec![RequestTransformOperation::Cap {
+                path: "max_tokens".to_string(),
+                max: 2048,
+            }],
+            ..RequestTransformPolicy::default()
+        };
+        let group = repo
+            .create_admin_account_group(NewAdminAccountGroup {
+                id: "group-transform".to_string(),
+                provider_type: "codex".to_string(),
+                name: "transform".to_string(),
+                account_names: vec!["codex-a".to_string()],
+                request_transform_policy: Some(group_policy.clone()),
+                queue_policy: None,
+                created_at_ms: 1_700_000_000_000,
+            })
+            .await
+            .expect("create group");
+        assert_eq!(group.request_transform_policy, Some(group_policy));
+        let key_policy = RequestTransformPolicy {
+            system_prompt_prepend: Some("key rules".to_string()),
+            operations: vec![RequestTransformOperation::Remove {
+                path: "metadata".to_string(),
+            }],
+            ..RequestTransformPolicy::default()
+        };
+        let patched = repo
+            .patch_admin_key("key-1", AdminKeyPatch {
+                account_group_id: Some(Some(group.id.clone())),
+                request_transform_policy: Some(Some(key_policy.clone())),
+                updated_at_ms: 1_700_000_000_001,
+                ..AdminKeyPatch::default()
+            })
+            .await
+            .expect("patch key")
+            .expect("key exists");
+        assert_eq!(patched.request_transform_policy, Some(key_policy.clone()));
+
+        let merged = repo
+            .resolve_request_transform_policy(&key)
+            .await
+            .expect("resolve merged policy")
+            .expect("merged policy");
+        assert_eq!(merged.system_prompt_prepend.as_deref(), Some("group rules\n\nkey rules"));
+        assert_eq!(
+            merged
+                .operations
+                .iter()
+                .map(RequestTransformOperation::kind)
+                .collect::<Vec<_>>(),
+            vec!["cap", "remove"]
+        );
+
+        repo.patch_admin_account_group(&group.id, AdminAccountGroupPatch {
+            request_transform_policy: Some(None),
+            updated_at_ms: 1_700_000_000_002,
+            ..AdminAccountGroupPatch::default()
+        })
+        .await
+        .expect("clear group policy")
+        .expect("group exists");
+        assert_eq!(
+            repo.resolve_request_transform_policy(&key)
+                .await
+                .expect("resolve key-only policy"),
+            Some(key_policy)
+        );
+    }
+
+    #[tokio::test]
+    async fn postgres_repository_overlays_key_queue_policy_on_group_policy() {
+        let Ok(database_url) = std::env::var("TEST_POSTGRES_URL") else {
+            eprintln!("skipping postgres integration test: TEST_POSTGRES_URL is not set");
+            return;
+        };
+        let _guard = test_db_guard().await;
+        reset_test_db(&database_url)
+            .await
+            .expect("reset postgres test database");
+        seed_test_key_bundle(&database_url)
+            .await
+            .expect("seed postgres test key bundle");
+        let repo = super::PostgresControlRepository::connect(&database_url, None)
+            .await
+            .expect("connect postgres repository");
+        let key = repo
+            .authenticate_bearer_secret("secret")
+            .await
+            .expect("lookup result")
+            .expect("key must exist");
+        assert_eq!(repo.resolve_queue_policy(&key).await.expect("default"), None);
+
+        let group = repo
+            .create_admin_account_group(NewAdminAccountGroup {
+                id: "group-queue".to_string(),
+                provider_type: "codex".to_string(),
+                name: "batch tenants".to_string(),
+                account_names: vec!["codex-a".to_string()],
+                request_transform_policy: None,
+                queue_policy: Some(KeyQueuePolicy {
+                    weight: Some(2),
+                    priority: Some(QueuePriority::Batch),
+                    max_queued_requests: None,
+                }),
+                created_at_ms: 1_700_000_000_000,
+            })
+            .await
+            .expect("create group");
+        repo.patch_admin_key("key-1", AdminKeyPatch {
+            account_group_id: Some(Some(group.id.clone())),
+            queue_policy: Some(Some(KeyQueuePolicy {
+                weight: Some(5),
+                ..KeyQueuePolicy::default()
+            })),
+            updated_at_ms: 1_700_000_000_001,
+            ..AdminKeyPatch::default()
+        })
+        .await
+        .expect("patch key")
+        .expect("key exists");
+
+        let merged = repo
+            .resolve_queue_policy(&key)
+            .await
+            .expect("resolve merged policy")
+            .expect("merged policy");
+        assert_eq!(merged.effective_weight(), 5);
+        assert_eq!(merged.effective_priority(), QueuePriority::Batch);
+    }
+
+    #[tokio::test]
+    async fn postgres_repository_queues_and_reviews_moderation_events() {
+        let Ok(database_url) = std::env::var("TEST_POSTGRES_URL") else {
+            eprintln!("skipping postgres integration test: TEST_POSTGRES_URL is not set");
+            return;
+        };
+        let _guard = test_db_guard().await;
+        reset_test_db(&database_url)
+            .await
+            .expect("reset postgres test database");
+        seed_test_key_bundle(&database_url)
+            .await
+            .expect("seed postgres test key bundle");
+        let repo = super::PostgresControlRepository::connect(&database_url, None)
+            .await
+            .expect("connect postgres repository");
+        let key = repo
+            .authenticate_bearer_secret("secret")
+            .await
+            .expect("lookup result")
+            .expect("key must exist");
+        assert_eq!(repo.resolve_moderation_policy(&key).await.expect("default"), None);
+
+        let policy = KeyModerationPolicy {
+            action: ModerationAction::Block,
+            keywords: vec!["forbidden".to_string()],
+            ..KeyModerationPolicy::default()
+        };
+        repo.patch_admin_key("key-1", AdminKeyPatch {
+            moderation_policy: Some(Some(policy.clone())),
+            updated_at_ms: 1_700_000_000_001,
+            ..AdminKeyPatch::default()
+        })
+        .await
+        .expect("patch key")
+        .expect("key exists");
+        assert_eq!(
+            repo.resolve_moderation_policy(&key)
+                .await
+                .expect("resolve policy"),
+            Some(policy)
+        );
+
+        repo.record_moderation_event(NewModerationEvent {
+            event_id: "moderation-1".to_string(),
+            key_id: key.key_id.clone(),
+            key_name: key.key_name.clone(),
+            provider_type: key.provider_type.clone(),
+            endpoint: "/v1/responses".to_string(),
+            model: Some("gpt-5".to_string()),
+            direction: "request".to_string(),
+            action: "block".to_string(),
+            matched_rules: vec!["keyword:forbidden".to_string()],
+            classifier_labels: Vec::new(),
+            classifier_score: None,
+            excerpt: "a forbidden prompt".to_string(),
+            client_ip: "127.0.0.1".to_string(),
+            created_at_ms: 1_700_000_000_002,
+        })
+        .await
+        .expect("record event");
+        let page = repo
+            .list_admin_moderation_events(AdminReviewQueueQuery {
+                status: Some(MODERATION_EVENT_STATUS_PENDING.to_string()),
+                limit: 10,
+                offset: 0,
+            })
+            .await
+            .expect("list events");
+        assert_eq!(page.total, 1);
+        assert_eq!(page.events[0].matched_rules, vec!["keyword:forbidden"]);
+
+        let reviewed = repo
+            .review_admin_moderation_event(
+                "moderation-1",
+                MODERATION_EVENT_STATUS_DISMISSED,
+                AdminReviewQueueAction {
+                    admin_note: Some("false positive".to_string()),
+                    updated_at_ms: 1_700_000_000_003,
+                },
+            )
+            .await
+            .expect("review event")
+            .expect("event exists");
+        assert_eq!(reviewed.status, MODERATION_EVENT_STATUS_DISMISSED);
+        assert_eq!(reviewed.reviewed_at, Some(1_700_000_000_003));
+        assert!(repo
+            .review_admin_moderation_event(
+                "missing",
+                MODERATION_EVENT_STATUS_CONFIRMED,
+                AdminReviewQueueAction {
+                    admin_note: None,
+                    updated_at_ms: 1_700_000_000_004,
+                }
+            )
+            .await
+            .expect("review missing")
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn postgres_repository_runs_batch_queue_lifecycle() {
+        let Ok(database_url) = std::env::var("TEST_POSTGRES_URL") else {
+            eprintln!("skipping postgres integration test: TEST_POSTGRES_URL is not set");
+            return;
+        };
+        let _guard = test_db_guard().await;
+        reset_test_db(&database_url)
+            .await
+            .expect("reset postgres test database");
+        seed_test_key_bundle(&database_url)
+            .await
+            .expect("seed postgres test key bundle");
+        let repo = super::PostgresControlRepository::connect(&database_url, None)
+            .await
+            .expect("connect postgres repository");
+        let now = 1_700_000_000_000_i64;
+        let new_job = |batch_id: &str, created_at_ms: i64, items: usize| NewBatchJob {
+            batch_id: batch_id.to_string(),
+            key_id: "key-1".to_string(),
+            protocol_family: "openai".to_string(),
+            endpoint: "/v1/responses".to_string(),
+            metadata_json: Some(r#"{"team":"eval"}"#.to_string()),
+            items: (0..items)
+                .map(|index| NewBatchItem {
+                    custom_id: format!("req-{index}"),
+                    body_json: format!(r#"{{"input":"{index}"}}"#),
+                })
+                .collect(),
+            created_at_ms,
+            expires_at_ms: created_at_ms + BATCH_COMPLETION_WINDOW_MS,
+        };
+
+        let created = repo
+            .create_batch_job(new_job("batch-1", now, 2))
+            .await
+            .expect("create batch");
+        assert_eq!(created.status, BATCH_STATUS_IN_PROGRESS);
+        assert_eq!((created.total_count, created.processing_count), (2, 2));
+        assert!(repo
+            .get_batch_job("key-other", "batch-1")
+            .await
+            .expect("foreign lookup")
+            .is_none());
+
+        let claimed = repo
+            .claim_batch_items(1, now + 1, now - 60_000)
+            .await
+            .expect("claim first item");
+        assert_eq!(claimed.len(), 1);
+        assert_eq!(claimed[0].custom_id, "req-0");
+        assert_eq!(claimed[0].attempts, 1);
+        assert_eq!(claimed[0].key.key_id, "key-1");
+        repo.release_batch_item("batch-1", 0, now + 10_000)
+            .await
+            .expect("release item");
+        let claimed = repo
+            .claim_batch_items(10, now + 2, now - 60_000)
+            .await
+            .expect("claim after release");
+        assert_eq!(claimed.len(), 1, "released item waits for its retry time");
+        assert_eq!(claimed[0].custom_id, "req-1");
+        let claimed = repo
+            .claim_batch_items(10, now + 10_000, now - 60_000)
+            .await
+            .expect("claim retried item");
+        assert_eq!(claimed.len(), 1);
+        assert_eq!(claimed[0].attempts, 2);
+        for item_index in 0..2 {
+            repo.complete_batch_item("batch-1", item_index, BatchItemOutcome {
+                status: BATCH_ITEM_STATUS_SUCCEEDED.to_string(),
+                status_code: Some(200),
+                response_body: Some(r#"{"ok":true}"#.to_string()),
+                error_message: None,
+                finished_at_ms: now + 20_000,
+            })
+            .await
+            .expect("complete item");
+        }
+        let finished = repo
+            .get_batch_job("key-1", "batch-1")
+            .await
+            .expect("load batch")
+            .expect("batch exists");
+        assert_eq!(finished.status, BATCH_STATUS_COMPLETED);
+        assert_eq!(finished.succeeded_count, 2);
+        assert_eq!(finished.started_at_ms, Some(now + 1));
+        assert_eq!(finished.finished_at_ms, Some(now + 20_000));
+        let results = repo
+            .list_batch_item_results("key-1", "batch-1")
+            .await
+            .expect("list results");
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[1].status_code, Some(200));
+
+        repo.create_batch_job(new_job("batch-2", now + 1, 3))
+            .await
+            .expect("create batch to cancel");
+        let claimed = repo
+            .claim_batch_items(1, now + 2, now - 60_000)
+            .await
+            .expect("claim before cancel");
+        assert_eq!(claimed[0].batch_id, "batch-2");
+        let cancelling = repo
+            .cancel_batch_job("key-1", "batch-2", now + 3)
+            .await
+            .expect("cancel batch")
+            .expect("batch exists");
+        assert_eq!(cancelling.status, "cancelling");
+        assert_eq!(cancelling.cancelled_count, 2);
+        assert!(repo
+            .claim_batch_items(10, now + 4, now - 60_000)
+            .await
+            .expect("claim during cancel")
+            .is_empty());
+        repo.release_batch_item("batch-2", 0, now + 5)
+            .await
+            .expect("release cancelled item");
+        let cancelled = repo
+            .get_batch_job("key-1", "batch-2")
+            .await
+            .expect("load cancelled")
+            .expect("batch exists");
+        assert_eq!(cancelled.status, BATCH_STATUS_CANCELLED);
+        assert_eq!(cancelled.cancelled_count, 3);
+        let results = repo
+            .list_batch_item_results("key-1", "batch-2")
+            .await
+            .expect("cancelled results");
+        assert!(results
+            .iter()
+            .all(|item| item.status == BATCH_ITEM_STATUS_CANCELLED));
+
+        repo.create_batch_job(new_job("batch-3", now, 1))
+            .await
+            .expect("create batch to expire");
+        let expired = repo
+            .expire_batch_jobs(now + BATCH_COMPLETION_WINDOW_MS, now)
+            .await
+            .expect("expire batches");
+        assert_eq!(expired, 1);
+        let batch = repo
+            .get_batch_job("key-1", "batch-3")
+            .await
+            .expect("load expired")
+            .expect("batch exists");
+        assert_eq!(batch.status, BATCH_STATUS_EXPIRED);
+        assert_eq!(batch.expired_count, 1);
+        let listed = repo
+            .list_batch_jobs("key-1", 10)
+            .await
+            .expect("list batches");
+        assert_eq!(listed.len(), 3);
+        assert_eq!(listed[0].batch_id, "batch-2");
+    }
+
     #[tokio::test]
     async fn postgres_repository_skips_missing_kiro_model_group_preference() {
         let Ok(database_url) = std::env::var("TEST_POSTGRES_URL") else {
@@ -1425,6 +2027,233 @@ mod tests {
         assert_eq!(probe_target.last_test_at, Some(1_700_000_000_040));
     }
 
+    #[tokio::test]
+    async fn postgres_secret_rotation_seals_upstream_channel_keys_to_their_rows() {
+        let Ok(database_url) = std::env::var("TEST_POSTGRES_URL") else {
+            eprintln!("skipping postgres integration test: TEST_POSTGRES_URL is not set");
+            return;
+        };
+        let _guard = test_db_guard().await;
+        reset_test_db(&database_url)
+            .await
+            .expect("reset postgres test database");
+        let mut repo = super::PostgresControlRepository::connect(&database_url, None)
+            .await
+            .expect("connect postgres repository");
+        for name in ["anthropic-a", "anthropic-b"] {
+            repo.create_admin_anthropic_upstream_channel(NewAdminAnthropicUpstreamChannel {
+                name: name.to_string(),
+                status: llm_access_core::store::KEY_STATUS_ACTIVE.to_string(),
+                base_url: "https://api.anthropic.com/v1".to_string(),
+                api_key: format!("sk-{name}"),
+                weight: 1,
+                max_concurrency: 1,
+                min_start_interval_ms: 0,
+                proxy_mode: "direct".to_string(),
+                proxy_config_id: None,
+                created_at_ms: 1_700_000_000_000,
+            })
+            .await
+            .expect("create plaintext anthropic upstream channel");
+        }
+        repo.create_admin_openai_upstream_channel(NewAdminOpenAiUpstreamChannel {
+            name: "openai-a".to_string(),
+            status: llm_access_core::store::KEY_STATUS_ACTIVE.to_string(),
+            base_url: "http://10.0.0.5:8000/v1".to_string(),
+            api_key: "sk-openai-a".to_string(),
+            weight: 1,
+            max_concurrency: 1,
+            min_start_interval_ms: 0,
+            proxy_mode: "direct".to_string(),
+            proxy_config_id: None,
+            created_at_ms: 1_700_000_000_000,
+        })
+        .await
+        .expect("create plaintext openai upstream channel");
+
+        let key_line = crate::secrets::generate_master_key_line("k1").expect("generate key");
+        repo.secrets = std::sync::Arc::new(
+            crate::secrets::SecretKeyring::from_key_file_contents(&key_line).expect("keyring"),
+        );
+        let report = repo.rotate_secrets().await.expect("rotate secrets");
+        assert_eq!(report.upstream_channels, 3);
+        assert_eq!(report.conflicts, 0);
+
+        let sealed_a: String = repo
+            .client
+            .query_opt(
+                "SELECT auth_json ->> 'api_key' FROM llm_anthropic_upstream_channels
+                 WHERE channel_name = 'anthropic-a'",
+                &[],
+            )
+            .await
+            .expect("read stored auth json")
+            .expect("channel row exists")
+            .get(0);
+        assert!(sealed_a.starts_with("enc:v2:k1:"));
+        let probe_target = repo
+            .load_admin_anthropic_upstream_probe_target("anthropic-a")
+            .await
+            .expect("load probe target")
+            .expect("probe target exists");
+        assert_eq!(probe_target.api_key, "sk-anthropic-a");
+        let sealed_openai: String = repo
+            .client
+            .query_opt(
+                "SELECT auth_json ->> 'api_key' FROM llm_openai_upstream_channels
+                 WHERE channel_name = 'openai-a'",
+                &[],
+            )
+            .await
+            .expect("read stored auth json")
+            .expect("channel row exists")
+            .get(0);
+        assert!(sealed_openai.starts_with("enc:v2:k1:"));
+        let openai_target = repo
+            .load_admin_openai_upstream_probe_target("openai-a")
+            .await
+            .expect("load openai probe target")
+            .expect("openai probe target exists");
+        assert_eq!(openai_target.api_key, "sk-openai-a");
+        assert_eq!(
+            repo.rotate_secrets()
+                .await
+                .expect("re-run rotation")
+                .rotated(),
+            0
+        );
+
+        repo.client
+            .execute(
+                "UPDATE llm_anthropic_upstream_channels
+                 SET auth_json = jsonb_build_object('api_key', $1::text)
+                 WHERE channel_name = 'anthropic-b'",
+                &[&sealed_a],
+            )
+            .await
+            .expect("copy sealed key into another row");
+        assert!(repo
+            .load_admin_anthropic_upstream_probe_target("anthropic-b")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn postgres_repository_manages_openai_upstream_channels_and_key_routing() {
+        let Ok(database_url) = std::env::var("TEST_POSTGRES_URL") else {
+            eprintln!("skipping postgres integration test: TEST_POSTGRES_URL is not set");
+            return;
+        };
+        let _guard = test_db_guard().await;
+        reset_test_db(&database_url)
+            .await
+            .expect("reset postgres test database");
+        seed_test_key_bundle(&database_url)
+            .await
+            .expect("seed postgres test key bundle");
+        let repo = super::PostgresControlRepository::connect(&database_url, None)
+            .await
+            .expect("connect postgres repository");
+        let key = repo
+            .authenticate_bearer_secret("secret")
+            .await
+            .expect("lookup result")
+            .expect("key must exist");
+        assert_eq!(
+            repo.resolve_openai_upstream_resolution(&key)
+                .await
+                .expect("default resolution"),
+            ProviderOpenAiUpstreamResolution::disabled()
+        );
+
+        let created = repo
+            .create_admin_openai_upstream_channel(NewAdminOpenAiUpstreamChannel {
+                name: "vllm-a".to_string(),
+                status: llm_access_core::store::KEY_STATUS_ACTIVE.to_string(),
+                base_url: "http://10.0.0.5:8000/v1".to_string(),
+                api_key: String::new(),
+                weight: 3,
+                max_concurrency: 4,
+                min_start_interval_ms: 0,
+                proxy_mode: "direct".to_string(),
+                proxy_config_id: None,
+                created_at_ms: 1_700_000_000_000,
+            })
+            .await
+            .expect("create openai upstream channel");
+        assert!(!created.has_api_key);
+        assert!(created.models.is_empty());
+        repo.save_admin_openai_upstream_models_status(
+            "vllm-a",
+            AdminOpenAiUpstreamModelsStatusUpdate {
+                model_ids: vec!["qwen3-32b".to_string()],
+                status: "ok".to_string(),
+                latency_ms: Some(12),
+                checked_at_ms: 1_700_000_000_010,
+                error: None,
+            },
+        )
+        .await
+        .expect("save models status")
+        .expect("channel exists");
+        repo.patch_admin_key("key-1", AdminKeyPatch {
+            codex_openai_upstream_pool_mode: Some("only".to_string()),
+            updated_at_ms: 1_700_000_000_020,
+            ..AdminKeyPatch::default()
+        })
+        .await
+        .expect("patch key")
+        .expect("key exists");
+
+        let resolution = repo
+            .resolve_openai_upstream_resolution(&key)
+            .await
+            .expect("resolve openai upstream routes");
+        assert_eq!(resolution.pool_mode, "only");
+        assert_eq!(resolution.routes.len(), 1);
+        let route = &resolution.routes[0];
+        assert_eq!(route.channel_name, "vllm-a");
+        assert_eq!(route.base_url, "http://10.0.0.5:8000/v1");
+        assert_eq!(route.api_key, "");
+        assert_eq!(route.weight, 3);
+        assert!(route.serves_model("qwen3-32b"));
+        assert!(!route.serves_model("gpt-5"));
+
+        repo.record_openai_upstream_channel_usage("vllm-a", OpenAiUpstreamChannelUsageDelta {
+            input_uncached_tokens: 10,
+            input_cached_tokens: 2,
+            output_tokens: 5,
+            billable_tokens: 17,
+            usage_missing: false,
+            used_at_ms: 1_700_000_000_030,
+        })
+        .await
+        .expect("record channel usage");
+        let channels = repo
+            .list_admin_openai_upstream_channels()
+            .await
+            .expect("list openai upstream channels");
+        assert_eq!(channels.len(), 1);
+        assert_eq!(channels[0].usage.billable_tokens, 17);
+        assert_eq!(channels[0].usage.last_used_at, Some(1_700_000_000_030));
+        assert_eq!(channels[0].models, vec!["qwen3-32b"]);
+
+        repo.patch_admin_openai_upstream_channel("vllm-a", AdminOpenAiUpstreamChannelPatch {
+            status: Some("disabled".to_string()),
+            updated_at_ms: 1_700_000_000_040,
+            ..AdminOpenAiUpstreamChannelPatch::default()
+        })
+        .await
+        .expect("disable channel")
+        .expect("channel exists");
+        assert!(repo
+            .resolve_openai_upstream_resolution(&key)
+            .await
+            .expect("resolve after disable")
+            .routes
+            .is_empty());
+    }
+
     #[tokio::test]
     async fn postgres_repository_applies_rollup_batches_idempotently() {
         let Ok(database_url) = std::env::var("TEST_POSTGRES_URL") else {
@@ -1497,6 +2326,249 @@ mod tests {
         client.close().await;
     }
 
+    #[tokio::test]
+    async fn postgres_repository_charges_sub_key_rollups_to_sibling_parent_budget() {
+        let Ok(database_url) = std::env::var("TEST_POSTGRES_URL") else {
+            eprintln!("skipping postgres integration test: TEST_POSTGRES_URL is not set");
+            return;
+        };
+        let _guard = test_db_guard().await;
+        reset_test_db(&database_url)
+            .await
+            .expect("reset postgres test database");
+        seed_test_key_bundle(&database_url)
+            .await
+            .expect("seed postgres test key bundle");
+        let repo = super::PostgresControlRepository::connect(&database_url, None)
+            .await
+            .expect("connect postgres repository");
+        for (id, secret) in [("key-sub-a", "sub-secret-a"), ("key-sub-b", "sub-secret-b")] {
+            repo.create_sub_key("key-1", NewSubKey {
+                id: id.to_string(),
+                name: id.to_string(),
+                secret: secret.to_string(),
+                key_hash: format!("{:x}", Sha256::digest(secret.as_bytes())),
+                quota_billable_limit: 400,
+                request_max_concurrency: None,
+                request_min_start_interval_ms: None,
+                created_at_ms: 1_700_000_000_001,
+            })
+            .await
+            .expect("create sub-key");
+        }
+        let before = repo
+            .authenticate_bearer_secret("sub-secret-b")
+            .await
+            .expect("lookup result")
+            .expect("sub-key must exist");
+        assert_eq!(before.parent_key_id.as_deref(), Some("key-1"));
+        assert_eq!(before.parent_remaining_billable, Some(1000));
+
+        repo.apply_usage_rollup_batches(&[UsageRollupBatch {
+            batch_id: "rollup-sub-a-1".to_string(),
+            source_node_id: Some("node-a".to_string()),
+            created_at_ms: 1_700_000_000_010,
+            source_event_count: 1,
+            deltas: vec![KeyUsageRollupDelta {
+                key_id: "key-sub-a".to_string(),
+                input_uncached_tokens: 20,
+                input_cached_tokens: 0,
+                output_tokens: 10,
+                billable_tokens: 30,
+                credit_total: 0.0,
+                credit_missing_events: 0,
+                last_used_at_ms: Some(1_700_000_000_020),
+            }],
+            last_used_at_ms_counts: Vec::new(),
+        }])
+        .await
+        .expect("apply sub-key rollup");
+
+        let after = repo
+            .authenticate_bearer_secret("sub-secret-b")
+            .await
+            .expect("lookup result")
+            .expect("sub-key must exist");
+        assert_eq!(after.billable_tokens_used, 0);
+        assert_eq!(after.parent_remaining_billable, Some(970));
+        let mut sub_key_ids = repo
+            .load_sub_key_ids_of_parents(&["key-1".to_string()])
+            .await
+            .expect("load sub-key ids");
+        sub_key_ids.sort();
+        assert_eq!(sub_key_ids, vec!["key-sub-a".to_string(), "key-sub-b".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn postgres_repository_alerts_only_confirmed_notify_emails() {
+        let Ok(database_url) = std::env::var("TEST_POSTGRES_URL") else {
+            eprintln!("skipping postgres integration test: TEST_POSTGRES_URL is not set");
+            return;
+        };
+        let _guard = test_db_guard().await;
+        reset_test_db(&database_url)
+            .await
+            .expect("reset postgres test database");
+        seed_test_key_bundle(&database_url)
+            .await
+            .expect("seed postgres test key bundle");
+        let repo = super::PostgresControlRepository::connect(&database_url, None)
+            .await
+            .expect("connect postgres repository");
+        repo.patch_key_portal_settings("key-1", KeyPortalSettingsPatch {
+            notify_emails: Some(vec!["a@example.com".to_string(), "b@example.com".to_string()]),
+            quota_alert_thresholds: Some(vec![50]),
+            updated_at_ms: 1_700_000_000_000,
+            ..KeyPortalSettingsPatch::default()
+        })
+        .await
+        .expect("patch key portal settings");
+        assert!(repo
+            .list_key_quota_alert_candidates()
+            .await
+            .expect("list candidates")
+            .is_empty());
+
+        let confirmation =
+            |email: &str, token_hash: &str, requested_at_ms: i64| NewKeyNotifyEmailConfirmation {
+                key_id: "key-1".to_string(),
+                email: email.to_string(),
+                token_hash: token_hash.to_string(),
+                requested_at_ms,
+            };
+        assert!(repo
+            .request_key_notify_email_confirmation(confirmation(
+                "a@example.com",
+                "hash-a",
+                1_700_000_000_000
+            ))
+            .await
+            .expect("request confirmation"));
+        assert!(!repo
+            .request_key_notify_email_confirmation(confirmation(
+                "a@example.com",
+                "hash-a-again",
+                1_700_000_001_000
+            ))
+            .await
+            .expect("request confirmation again"));
+        assert_eq!(
+            repo.confirm_key_notify_email("hash-a-again", 1_700_000_002_000)
+                .await
+                .expect("confirm unknown token"),
+            None
+        );
+        let confirmed = repo
+            .confirm_key_notify_email("hash-a", 1_700_000_002_000)
+            .await
+            .expect("confirm token")
+            .expect("token must confirm");
+        assert_eq!(confirmed.email, "a@example.com");
+
+        let ttl_ms = (KEY_NOTIFY_EMAIL_CONFIRMATION_TTL_SECONDS * 1_000) as i64;
+        assert!(repo
+            .request_key_notify_email_confirmation(confirmation(
+                "b@example.com",
+                "hash-b",
+                1_700_000_000_000
+            ))
+            .await
+            .expect("request second confirmation"));
+        assert_eq!(
+            repo.confirm_key_notify_email("hash-b", 1_700_000_000_000 + ttl_ms)
+                .await
+                .expect("confirm expired token"),
+            None
+        );
+
+        let candidates = repo
+            .list_key_quota_alert_candidates()
+            .await
+            .expect("list candidates");
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].notify_emails, vec!["a@example.com".to_string()]);
+        let settings = repo
+            .get_key_portal_settings("key-1")
+            .await
+            .expect("load settings");
+        assert_eq!(settings.confirmed_notify_emails, vec!["a@example.com".to_string()]);
+
+        repo.patch_key_portal_settings("key-1", KeyPortalSettingsPatch {
+            notify_emails: Some(vec!["b@example.com".to_string()]),
+            updated_at_ms: 1_700_000_003_000,
+            ..KeyPortalSettingsPatch::default()
+        })
+        .await
+        .expect("drop confirmed recipient");
+        assert!(repo
+            .list_key_quota_alert_candidates()
+            .await
+            .expect("list candidates")
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn postgres_repository_reports_grace_window_expiry_for_rotated_secret() {
+        let Ok(database_url) = std::env::var("TEST_POSTGRES_URL") else {
+            eprintln!("skipping postgres integration test: TEST_POSTGRES_URL is not set");
+            return;
+        };
+        let _guard = test_db_guard().await;
+        reset_test_db(&database_url)
+            .await
+            .expect("reset postgres test database");
+        seed_test_key_bundle(&database_url)
+            .await
+            .expect("seed postgres test key bundle");
+        let repo = super::PostgresControlRepository::connect(&database_url, None)
+            .await
+            .expect("connect postgres repository");
+        let grace_expires_at_ms = super::now_ms() + 60_000;
+        repo.rotate_key_secret("key-1", KeySecretRotation {
+            secret: "rotated".to_string(),
+            key_hash: super::hash_bearer_secret("rotated"),
+            previous_secret_expires_at_ms: grace_expires_at_ms,
+            rotated_at_ms: super::now_ms(),
+        })
+        .await
+        .expect("rotate key secret")
+        .expect("rotated key");
+
+        let (key, grace) = repo
+            .load_authenticated_key_by_hash(&super::hash_bearer_secret("secret"))
+            .await
+            .expect("load by previous hash")
+            .expect("previous secret within grace window");
+        assert_eq!(key.key_id, "key-1");
+        assert_eq!(grace, Some(grace_expires_at_ms));
+        let (_, grace) = repo
+            .load_authenticated_key_by_hash(&super::hash_bearer_secret("rotated"))
+            .await
+            .expect("load by current hash")
+            .expect("current secret");
+        assert_eq!(grace, None);
+
+        repo.client
+            .execute(
+                "UPDATE llm_keys
+                 SET previous_key_hash_expires_at_ms = $1
+                 WHERE key_id = 'key-1'",
+                &[&(super::now_ms() - 1)],
+            )
+            .await
+            .expect("end grace window");
+        assert!(repo
+            .authenticate_bearer_secret("secret")
+            .await
+            .expect("authenticate previous secret")
+            .is_none());
+        assert!(repo
+            .authenticate_bearer_secret("rotated")
+            .await
+            .expect("authenticate current secret")
+            .is_some());
+    }
+
     #[tokio::test]
     async fn postgres_repository_records_codex_image_usage_separately() {
         let Ok(database_url) = std::env::var("TEST_POSTGRES_URL") else {
@@ -1792,8 +2864,12 @@ mod tests {
             .await
             .expect("load edge proxy context");
         let fixed_proxy = super::resolve_provider_proxy_config_from_context(
-            "fixed",
-            Some("proxy-slot-1"),
+            super::proxy_support::AccountProxyRef {
+                proxy_mode: "fixed",
+                proxy_config_id: Some("proxy-slot-1"),
+                proxy_pool_id: None,
+                sticky_key: "account-1",
+            },
             &edge_context,
         )
         .expect("resolve fixed edge proxy")
@@ -1984,6 +3060,101 @@ mod tests {
         client.close().await;
     }
 
+    #[tokio::test]
+    async fn postgres_repository_manages_proxy_pools_and_pool_bindings() {
+        let Ok(database_url) = std::env::var("TEST_POSTGRES_URL") else {
+            eprintln!("skipping postgres integration test: TEST_POSTGRES_URL is not set");
+            return;
+        };
+        let _guard = test_db_guard().await;
+        reset_test_db(&database_url)
+            .await
+            .expect("reset postgres test database");
+        let repo = super::PostgresControlRepository::connect_with_proxy_scope(
+            &database_url,
+            None,
+            super::ProxyConfigScope::core(),
+        )
+        .await
+        .expect("connect postgres repository");
+
+        for (id, port) in [("proxy-slot-1", 1111), ("proxy-slot-2", 2222)] {
+            repo.create_admin_proxy_config(NewAdminProxyConfig {
+                id: id.to_string(),
+                name: id.to_string(),
+                proxy_url: format!("http://core.proxy:{port}"),
+                proxy_username: None,
+                proxy_password: None,
+                created_at_ms: 100,
+            })
+            .await
+            .expect("create proxy slot");
+        }
+        let members = vec![
+            ProxyPoolMember {
+                proxy_config_id: "proxy-slot-2".to_string(),
+                weight: 3,
+            },
+            ProxyPoolMember {
+                proxy_config_id: "proxy-slot-1".to_string(),
+                weight: 1,
+            },
+        ];
+        let pool = repo
+            .create_admin_proxy_pool(NewAdminProxyPool {
+                id: "pool-1".to_string(),
+                name: "pool 1".to_string(),
+                members: members.clone(),
+                created_at_ms: 200,
+            })
+            .await
+            .expect("create proxy pool");
+        assert_eq!(pool.members, members);
+        assert_eq!(pool.status, "active");
+
+        repo.update_admin_proxy_pool_binding("codex", Some("pool-1".to_string()))
+            .await
+            .expect("bind codex to pool");
+        let codex_binding = repo
+            .list_admin_proxy_bindings()
+            .await
+            .expect("list proxy bindings")
+            .into_iter()
+            .find(|binding| binding.provider_type == "codex")
+            .expect("codex binding exists");
+        assert_eq!(codex_binding.bound_proxy_pool_id.as_deref(), Some("pool-1"));
+        assert!(codex_binding.bound_proxy_config_id.is_none());
+
+        let patched = repo
+            .patch_admin_proxy_pool("pool-1", AdminProxyPoolPatch {
+                members: Some(vec![ProxyPoolMember {
+                    proxy_config_id: "proxy-slot-1".to_string(),
+                    weight: 2,
+                }]),
+                updated_at_ms: 300,
+                ..AdminProxyPoolPatch::default()
+            })
+            .await
+            .expect("patch proxy pool")
+            .expect("patched pool exists");
+        assert_eq!(patched.members.len(), 1);
+        assert_eq!(patched.updated_at, 300);
+
+        assert!(repo.delete_admin_proxy_pool("pool-1").await.is_err());
+        repo.update_admin_proxy_pool_binding("codex", None)
+            .await
+            .expect("clear codex pool binding");
+        repo.delete_admin_proxy_pool("pool-1")
+            .await
+            .expect("delete proxy pool")
+            .expect("deleted pool exists");
+        assert!(repo
+            .list_admin_proxy_pools()
+            .await
+            .expect("list proxy pools")
+            .is_empty());
+    }
+
     #[tokio::test]
     async fn postgres_repository_updates_key_usage_rollups() {
         let Ok(database_url) = std::env::var("TEST_POSTGRES_URL") else {
@@ -2210,6 +3381,48 @@ mod tests {
         assert_eq!(delta.last_used_at_ms, Some(25));
     }
 
+    #[test]
+    fn parent_rollup_deltas_copy_sub_key_usage_to_parent() {
+        let delta = |key_id: &str, billable_tokens: i64, last_used_at_ms: i64| {
+            llm_access_core::store::KeyUsageRollupDelta {
+                key_id: key_id.to_string(),
+                input_uncached_tokens: billable_tokens,
+                input_cached_tokens: 0,
+                output_tokens: 0,
+                billable_tokens,
+                credit_total: 0.0,
+                credit_missing_events: 0,
+                last_used_at_ms: Some(last_used_at_ms),
+            }
+        };
+        let parents = BTreeMap::from([
+            ("child-a".to_string(), "parent".to_string()),
+            ("child-b".to_string(), "parent".to_string()),
+        ]);
+        let deltas = super::with_parent_rollup_deltas(
+            vec![
+                delta("child-a", 10, 5),
+                delta("child-b", 7, 9),
+                delta("parent", 3, 1),
+                delta("other", 4, 2),
+            ],
+            &parents,
+        );
+        let billable = deltas
+            .iter()
+            .map(|delta| (delta.key_id.as_str(), (delta.billable_tokens, delta.last_used_at_ms)))
+            .collect::<BTreeMap<_, _>>();
+        assert_eq!(
+            billable,
+            BTreeMap::from([
+                ("child-a", (10, Some(5))),
+                ("child-b", (7, Some(9))),
+                ("other", (4, Some(2))),
+                ("parent", (20, Some(9))),
+            ])
+        );
+    }
+
     #[tokio::test]
     async fn postgres_repository_batches_key_usage_rollups() {
         let Ok(database_url) = std::env::var("TEST_POSTGRES_URL") else {
@@ -12,9 +12,11 @@ use llm_access_core::store::{
 use serde::{Deserialize, Serialize};
 
 use super::{
-    now_ms, proxy_support::resolve_provider_proxy_config_from_context, AnthropicUpstreamChannelRow,
-    PostgresControlRepository,
+    now_ms,
+    proxy_support::{resolve_provider_proxy_config_from_context, AccountProxyRef},
+    AnthropicUpstreamChannelRow, PostgresControlRepository,
 };
+use crate::secrets::{SecretKeyring, SecretRow};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct CachedAnthropicUpstreamChannelsLookup {
@@ -44,9 +46,18 @@ fn model_ids_from_json_text(channel_name: &str, raw: &str) -> Vec<String> {
     }
 }
 
-fn auth_json_for_api_key(api_key: &str) -> anyhow::Result<String> {
-    serde_json::to_string(&serde_json::json!({ "api_key": api_key }))
-        .context("serialize anthropic upstream auth json")
+pub(super) const ANTHROPIC_UPSTREAM_CHANNELS_TABLE: &str = "llm_anthropic_upstream_channels";
+
+fn auth_json_for_api_key(
+    secrets: &SecretKeyring,
+    channel_name: &str,
+    api_key: &str,
+) -> anyhow::Result<String> {
+    let auth_json = serde_json::to_string(&serde_json::json!({ "api_key": api_key }))
+        .context("serialize anthropic upstream auth json")?;
+    secrets
+        .seal_auth_json(SecretRow::new(ANTHROPIC_UPSTREAM_CHANNELS_TABLE, channel_name), &auth_json)
+        .context("seal anthropic upstream auth json")
 }
 
 fn admin_channel_from_row(row: AnthropicUpstreamChannelRow) -> AdminAnthropicUpstreamChannel {
@@ -88,12 +99,24 @@ fn admin_channel_from_row(row: AnthropicUpstreamChannelRow) -> AdminAnthropicUps
 }
 
 impl PostgresControlRepository {
-    fn decode_anthropic_upstream_channel_row(row: super::PgRow) -> AnthropicUpstreamChannelRow {
-        AnthropicUpstreamChannelRow {
-            channel_name: row.get(0),
+    fn decode_anthropic_upstream_channel_row(
+        &self,
+        row: super::PgRow,
+    ) -> anyhow::Result<AnthropicUpstreamChannelRow> {
+        let channel_name: String = row.get(0);
+        let api_key: Option<String> = row.get(3);
+        let api_key = self
+            .secrets
+            .open_optional(
+                SecretRow::new(ANTHROPIC_UPSTREAM_CHANNELS_TABLE, &channel_name),
+                api_key.as_deref(),
+            )
+            .with_context(|| format!("open api key of anthropic upstream `{channel_name}`"))?;
+        Ok(AnthropicUpstreamChannelRow {
+            channel_name,
             status: row.get(1),
             base_url: row.get(2),
-            api_key: row.get(3),
+            api_key,
             weight: row.get(4),
             max_concurrency: row.get(5),
             min_start_interval_ms: row.get(6),
@@ -121,7 +144,7 @@ impl PostgresControlRepository {
             billable_tokens: row.get(25),
             usage_missing_events: row.get(26),
             last_used_at_ms: row.get(27),
-        }
+        })
     }
 
     pub(super) async fn list_anthropic_upstream_channel_rows(
@@ -171,10 +194,9 @@ impl PostgresControlRepository {
             .query(&sql, &[])
             .await
             .context("list postgres anthropic upstream channels")?;
-        Ok(rows
-            .into_iter()
-            .map(Self::decode_anthropic_upstream_channel_row)
-            .collect())
+        rows.into_iter()
+            .map(|row| self.decode_anthropic_upstream_channel_row(row))
+            .collect()
     }
 
     pub(super) async fn load_active_anthropic_upstream_channel_rows_cached(
@@ -265,8 +287,8 @@ impl PostgresControlRepository {
             )
             .await
             .context("load postgres anthropic upstream channel")?
-            .map(Self::decode_anthropic_upstream_channel_row)
-            .map_or(Ok(None), |row| Ok(Some(row)))
+            .map(|row| self.decode_anthropic_upstream_channel_row(row))
+            .transpose()
     }
 
     pub(crate) async fn record_anthropic_upstream_channel_usage(
@@ -368,7 +390,7 @@ impl AdminAnthropicUpstreamStore for PostgresControlRepository {
         channel: NewAdminAnthropicUpstreamChannel,
     ) -> anyhow::Result<AdminAnthropicUpstreamChannel> {
         self.ensure_connection_alive()?;
-        let auth_json = auth_json_for_api_key(&channel.api_key)?;
+        let auth_json = auth_json_for_api_key(&self.secrets, &channel.name, &channel.api_key)?;
         let weight = channel.weight.min(i64::MAX as u64) as i64;
         let max_concurrency = channel.max_concurrency.min(i64::MAX as u64) as i64;
         let min_start_interval_ms = channel.min_start_interval_ms.min(i64::MAX as u64) as i64;
@@ -446,7 +468,7 @@ impl AdminAnthropicUpstreamStore for PostgresControlRepository {
         let auth_json = patch
             .api_key
             .as_deref()
-            .map(auth_json_for_api_key)
+            .map(|api_key| auth_json_for_api_key(&self.secrets, name, api_key))
             .transpose()?;
         let updated_at_ms = patch.updated_at_ms;
         self.client
@@ -523,8 +545,12 @@ impl AdminAnthropicUpstreamStore for PostgresControlRepository {
             .load_provider_proxy_resolution_context(core_store::PROVIDER_KIRO)
             .await?;
         let (proxy, proxy_error) = match resolve_provider_proxy_config_from_context(
-            &row.proxy_mode,
-            row.proxy_config_id.as_deref(),
+            AccountProxyRef {
+                proxy_mode: &row.proxy_mode,
+                proxy_config_id: row.proxy_config_id.as_deref(),
+                proxy_pool_id: None,
+                sticky_key: &row.channel_name,
+            },
             &proxy_context,
         ) {
             Ok(proxy) => (proxy, None),
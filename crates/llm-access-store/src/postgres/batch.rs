@@ -0,0 +1,445 @@
+//! Batch queue rows + the `BatchStore` impl. Claims use `FOR UPDATE SKIP
+//! LOCKED` so every node may run the batch worker.
+
+use anyhow::Context;
+use async_trait::async_trait;
+use llm_access_core::store::{
+    AuthenticatedKey, BatchItemOutcome, BatchItemResult, BatchJob, BatchStore, ClaimedBatchItem,
+    NewBatchJob,
+};
+
+use super::{PgRow, PostgresControlRepository};
+
+const BATCH_JOB_SELECT: &str = "SELECT
+        b.batch_id, b.key_id, b.protocol_family, b.endpoint, b.status, b.metadata_json::text,
+        COUNT(i.item_index),
+        COUNT(*) FILTER (WHERE i.status IN ('pending', 'running')),
+        COUNT(*) FILTER (WHERE i.status = 'succeeded'),
+        COUNT(*) FILTER (WHERE i.status = 'failed'),
+        COUNT(*) FILTER (WHERE i.status = 'cancelled'),
+        COUNT(*) FILTER (WHERE i.status = 'expired'),
+        b.created_at_ms, b.started_at_ms, b.finished_at_ms, b.cancel_requested_at_ms,
+        b.expires_at_ms
+     FROM llm_batches b
+     LEFT JOIN llm_batch_items i ON i.batch_id = b.batch_id";
+
+/// Moves drained batches to their terminal status: `cancelled` after a cancel,
+/// `expired` when any item expired, `completed` otherwise.
+const FINALIZE_DRAINED_BATCHES_SQL: &str = "UPDATE llm_batches b
+     SET status = CASE
+             WHEN b.status = 'cancelling' THEN 'cancelled'
+             WHEN EXISTS (
+                 SELECT 1 FROM llm_batch_items e
+                 WHERE e.batch_id = b.batch_id AND e.status = 'expired'
+             ) THEN 'expired'
+             ELSE 'completed'
+         END,
+         finished_at_ms = $1
+     WHERE b.status IN ('in_progress', 'cancelling')
+       AND ($2::text IS NULL OR b.batch_id = $2)
+       AND NOT EXISTS (
+           SELECT 1 FROM llm_batch_items p
+           WHERE p.batch_id = b.batch_id AND p.status IN ('pending', 'running')
+       )";
+
+fn decode_batch_job_row(row: PgRow) -> BatchJob {
+    BatchJob {
+        batch_id: row.get(0),
+        key_id: row.get(1),
+        protocol_family: row.get(2),
+        endpoint: row.get(3),
+        status: row.get(4),
+        metadata_json: row.get(5),
+        total_count: row.get(6),
+        processing_count: row.get(7),
+        succeeded_count: row.get(8),
+        failed_count: row.get(9),
+        cancelled_count: row.get(10),
+        expired_count: row.get(11),
+        created_at_ms: row.get(12),
+        started_at_ms: row.get(13),
+        finished_at_ms: row.get(14),
+        cancel_requested_at_ms: row.get(15),
+        expires_at_ms: row.get(16),
+    }
+}
+
+fn decode_claimed_batch_item_row(row: PgRow) -> ClaimedBatchItem {
+    ClaimedBatchItem {
+        batch_id: row.get(0),
+        item_index: row.get(1),
+        custom_id: row.get(2),
+        body_json: row.get(3),
+        attempts: row.get(4),
+        endpoint: row.get(5),
+        key: AuthenticatedKey {
+            key_id: row.get(6),
+            key_name: row.get(7),
+            provider_type: row.get(8),
+            protocol_family: row.get(9),
+            status: row.get(10),
+            quota_billable_limit: row.get(11),
+            billable_tokens_used: row.get(12),
+            parent_remaining_billable: row.get(13),
+            parent_key_id: row.get(14),
+        },
+    }
+}
+
+impl PostgresControlRepository {
+    pub(super) async fn load_batch_job_row(
+        &self,
+        key_id: &str,
+        batch_id: &str,
+    ) -> anyhow::Result<Option<BatchJob>> {
+        self.ensure_connection_alive()?;
+        let row = self
+            .client
+            .query_opt(
+                &format!(
+                    "{BATCH_JOB_SELECT}
+                     WHERE b.key_id = $1 AND b.batch_id = $2
+                     GROUP BY b.batch_id"
+                ),
+                &[&key_id, &batch_id],
+            )
+            .await
+            .context("load postgres batch job")?;
+        Ok(row.map(decode_batch_job_row))
+    }
+
+    async fn finalize_drained_batches(
+        &self,
+        batch_id: Option<&str>,
+        now_ms: i64,
+    ) -> anyhow::Result<u64> {
+        let batch_id = batch_id.map(str::to_string);
+        self.client
+            .execute(FINALIZE_DRAINED_BATCHES_SQL, &[&now_ms, &batch_id])
+            .await
+            .context("finalize drained postgres batches")
+    }
+}
+
+#[async_trait]
+impl BatchStore for PostgresControlRepository {
+    async fn create_batch_job(&self, job: NewBatchJob) -> anyhow::Result<BatchJob> {
+        let client = self.connect_fresh_client().await?;
+        let tx = client
+            .transaction()
+            .await
+            .context("begin postgres batch transaction")?;
+        tx.execute(
+            "INSERT INTO llm_batches (
+                batch_id, key_id, protocol_family, endpoint, status, metadata_json,
+                created_at_ms, started_at_ms, finished_at_ms, cancel_requested_at_ms,
+                expires_at_ms
+            ) VALUES (
+                $1, $2, $3, $4, 'in_progress', $5::jsonb,
+                $6, NULL, NULL, NULL,
+                $7
+            )",
+            &[
+                &job.batch_id,
+                &job.key_id,
+                &job.protocol_family,
+                &job.endpoint,
+                &job.metadata_json,
+                &job.created_at_ms,
+                &job.expires_at_ms,
+            ],
+        )
+        .await
+        .context("insert postgres batch")?;
+        for (item_index, item) in job.items.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO llm_batch_items (
+                    batch_id, item_index, custom_id, body_json, status
+                ) VALUES (
+                    $1, $2, $3, $4::jsonb, 'pending'
+                )",
+                &[&job.batch_id, &(item_index as i64), &item.custom_id, &item.body_json],
+            )
+            .await
+            .with_context(|| format!("insert postgres batch item {item_index}"))?;
+        }
+        tx.commit()
+            .await
+            .context("commit postgres batch transaction")?;
+        self.load_batch_job_row(&job.key_id, &job.batch_id)
+            .await?
+            .context("created postgres batch disappeared")
+    }
+
+    async fn list_batch_jobs(&self, key_id: &str, limit: usize) -> anyhow::Result<Vec<BatchJob>> {
+        self.ensure_connection_alive()?;
+        let rows = self
+            .client
+            .query(
+                &format!(
+                    "{BATCH_JOB_SELECT}
+                     WHERE b.key_id = $1
+                     GROUP BY b.batch_id
+                     ORDER BY b.created_at_ms DESC, b.batch_id DESC
+                     LIMIT $2"
+                ),
+                &[&key_id, &(limit as i64)],
+            )
+            .await
+            .context("list postgres batch jobs")?;
+        Ok(rows.into_iter().map(decode_batch_job_row).collect())
+    }
+
+    async fn get_batch_job(
+        &self,
+        key_id: &str,
+        batch_id: &str,
+    ) -> anyhow::Result<Option<BatchJob>> {
+        self.load_batch_job_row(key_id, batch_id).await
+    }
+
+    async fn cancel_batch_job(
+        &self,
+        key_id: &str,
+        batch_id: &str,
+        now_ms: i64,
+    ) -> anyhow::Result<Option<BatchJob>> {
+        let client = self.connect_fresh_client().await?;
+        let tx = client
+            .transaction()
+            .await
+            .context("begin postgres batch cancel transaction")?;
+        let updated = tx
+            .execute(
+                "UPDATE llm_batches
+                 SET status = 'cancelling', cancel_requested_at_ms = $3
+                 WHERE batch_id = $1 AND key_id = $2 AND status = 'in_progress'",
+                &[&batch_id, &key_id, &now_ms],
+            )
+            .await
+            .context("mark postgres batch cancelling")?;
+        if updated > 0 {
+            tx.execute(
+                "UPDATE llm_batch_items
+                 SET status = 'cancelled', finished_at_ms = $2
+                 WHERE batch_id = $1 AND status = 'pending'",
+                &[&batch_id, &now_ms],
+            )
+            .await
+            .context("cancel pending postgres batch items")?;
+        }
+        tx.commit()
+            .await
+            .context("commit postgres batch cancel transaction")?;
+        if updated > 0 {
+            self.finalize_drained_batches(Some(batch_id), now_ms)
+                .await?;
+        }
+        self.load_batch_job_row(key_id, batch_id).await
+    }
+
+    async fn list_batch_item_results(
+        &self,
+        key_id: &str,
+        batch_id: &str,
+    ) -> anyhow::Result<Vec<BatchItemResult>> {
+        self.ensure_connection_alive()?;
+        let rows = self
+            .client
+            .query(
+                "SELECT
+                    i.item_index, i.custom_id, i.status, i.status_code, i.response_body,
+                    i.error_message, i.finished_at_ms
+                 FROM llm_batch_items i
+                 JOIN llm_batches b ON b.batch_id = i.batch_id
+                 WHERE b.key_id = $1 AND i.batch_id = $2
+                 ORDER BY i.item_index",
+                &[&key_id, &batch_id],
+            )
+            .await
+            .context("list postgres batch item results")?;
+        Ok(rows
+            .into_iter()
+            .map(|row| BatchItemResult {
+                item_index: row.get(0),
+                custom_id: row.get(1),
+                status: row.get(2),
+                status_code: row.get(3),
+                response_body: row.get(4),
+                error_message: row.get(5),
+                finished_at_ms: row.get(6),
+            })
+            .collect())
+    }
+
+    async fn claim_batch_items(
+        &self,
+        limit: usize,
+        now_ms: i64,
+        lease_expired_before_ms: i64,
+    ) -> anyhow::Result<Vec<ClaimedBatchItem>> {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+        self.ensure_connection_alive()?;
+        let rows = self
+            .client
+            .query(
+                "WITH picked AS (
+                    SELECT i.batch_id, i.item_index
+                    FROM llm_batch_items i
+                    JOIN llm_batches b ON b.batch_id = i.batch_id
+                    WHERE b.status = 'in_progress'
+                      AND b.expires_at_ms > $1
+                      AND (
+                          (i.status = 'pending' AND i.next_attempt_at_ms <= $1)
+                          OR (i.status = 'running' AND i.claimed_at_ms < $2)
+                      )
+                    ORDER BY b.created_at_ms, i.batch_id, i.item_index
+                    LIMIT $3
+                    FOR UPDATE OF i SKIP LOCKED
+                 ),
+                 claimed AS (
+                    UPDATE llm_batch_items i
+                    SET status = 'running', claimed_at_ms = $1, attempts = i.attempts + 1
+                    FROM picked p
+                    WHERE i.batch_id = p.batch_id AND i.item_index = p.item_index
+                    RETURNING i.batch_id, i.item_index, i.custom_id, i.body_json::text AS body,
+                              i.attempts
+                 ),
+                 started AS (
+                    UPDATE llm_batches
+                    SET started_at_ms = $1
+                    WHERE started_at_ms IS NULL
+                      AND batch_id IN (SELECT batch_id FROM claimed)
+                 )
+                 SELECT
+                    c.batch_id, c.item_index, c.custom_id, c.body, c.attempts, b.endpoint,
+                    k.key_id, k.name, k.provider_type, k.protocol_family,
+                    CASE WHEN p.status = 'disabled' THEN p.status ELSE k.status END,
+                    LEAST(
+                        k.quota_billable_limit,
+                        COALESCE(s.quota_billable_cap, k.quota_billable_limit)
+                    ),
+                    COALESCE(u.billable_tokens, 0),
+                    LEAST(
+                        p.quota_billable_limit,
+                        COALESCE(ps.quota_billable_cap, p.quota_billable_limit)
+                    ) - COALESCE(pu.billable_tokens, 0),
+                    k.parent_key_id
+                 FROM claimed c
+                 JOIN llm_batches b ON b.batch_id = c.batch_id
+                 JOIN llm_keys k ON k.key_id = b.key_id
+                 LEFT JOIN llm_key_usage_rollups u ON u.key_id = k.key_id
+                 LEFT JOIN llm_key_portal_settings s ON s.key_id = k.key_id
+                 LEFT JOIN llm_keys p ON p.key_id = k.parent_key_id
+                 LEFT JOIN llm_key_usage_rollups pu ON pu.key_id = p.key_id
+                 LEFT JOIN llm_key_portal_settings ps ON ps.key_id = p.key_id
+                 ORDER BY b.created_at_ms, c.batch_id, c.item_index",
+                &[&now_ms, &lease_expired_before_ms, &(limit as i64)],
+            )
+            .await
+            .context("claim postgres batch items")?;
+        Ok(rows
+            .into_iter()
+            .map(decode_claimed_batch_item_row)
+            .collect())
+    }
+
+    async fn complete_batch_item(
+        &self,
+        batch_id: &str,
+        item_index: i64,
+        outcome: BatchItemOutcome,
+    ) -> anyhow::Result<()> {
+        self.ensure_connection_alive()?;
+        self.client
+            .execute(
+                "UPDATE llm_batch_items
+                 SET status = $3, status_code = $4, response_body = $5, error_message = $6,
+                     finished_at_ms = $7
+                 WHERE batch_id = $1 AND item_index = $2 AND status = 'running'",
+                &[
+                    &batch_id,
+                    &item_index,
+                    &outcome.status,
+                    &outcome.status_code,
+                    &outcome.response_body,
+                    &outcome.error_message,
+                    &outcome.finished_at_ms,
+                ],
+            )
+            .await
+            .context("complete postgres batch item")?;
+        self.finalize_drained_batches(Some(batch_id), outcome.finished_at_ms)
+            .await?;
+        Ok(())
+    }
+
+    async fn release_batch_item(
+        &self,
+        batch_id: &str,
+        item_index: i64,
+        retry_at_ms: i64,
+    ) -> anyhow::Result<()> {
+        self.ensure_connection_alive()?;
+        // A cancel that landed while the item ran turns the release into a
+        // cancellation so the batch can drain.
+        self.client
+            .execute(
+                "UPDATE llm_batch_items i
+                 SET status = CASE WHEN b.status = 'in_progress' THEN 'pending' ELSE 'cancelled' \
+                 END,
+                     next_attempt_at_ms = $3,
+                     claimed_at_ms = NULL,
+                     finished_at_ms = CASE WHEN b.status = 'in_progress' THEN NULL ELSE $3 END
+                 FROM llm_batches b
+                 WHERE b.batch_id = i.batch_id
+                   AND i.batch_id = $1 AND i.item_index = $2 AND i.status = 'running'",
+                &[&batch_id, &item_index, &retry_at_ms],
+            )
+            .await
+            .context("release postgres batch item")?;
+        self.finalize_drained_batches(Some(batch_id), retry_at_ms)
+            .await?;
+        Ok(())
+    }
+
+    async fn expire_batch_jobs(
+        &self,
+        now_ms: i64,
+        lease_expired_before_ms: i64,
+    ) -> anyhow::Result<u64> {
+        self.ensure_connection_alive()?;
+        self.client
+            .execute(
+                "UPDATE llm_batch_items i
+                 SET status = 'expired', finished_at_ms = $1
+                 FROM llm_batches b
+                 WHERE b.batch_id = i.batch_id
+                   AND b.status = 'in_progress'
+                   AND b.expires_at_ms <= $1
+                   AND (
+                       i.status = 'pending'
+                       OR (i.status = 'running' AND i.claimed_at_ms < $2)
+                   )",
+                &[&now_ms, &lease_expired_before_ms],
+            )
+            .await
+            .context("expire overdue postgres batch items")?;
+        self.client
+            .execute(
+                "UPDATE llm_batch_items i
+                 SET status = 'cancelled', finished_at_ms = $1
+                 FROM llm_batches b
+                 WHERE b.batch_id = i.batch_id
+                   AND b.status = 'cancelling'
+                   AND i.status = 'running'
+                   AND i.claimed_at_ms < $2",
+                &[&now_ms, &lease_expired_before_ms],
+            )
+            .await
+            .context("cancel stale postgres batch items")?;
+        self.finalize_drained_batches(None, now_ms).await
+    }
+}
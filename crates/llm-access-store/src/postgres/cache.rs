@@ -16,7 +16,8 @@ use super::{
     },
     decode::decode_codex_account_settings,
     json::non_negative_i64_to_u64,
-    proxy_support::resolve_provider_proxy_config_from_context,
+    now_ms,
+    proxy_support::{codex_account_proxy_ref, resolve_provider_proxy_config_from_context},
     CachedCodexRateLimitStatus, PostgresControlRepository, CODEX_STATUS_CACHE_TTL,
 };
 
@@ -141,7 +142,10 @@ impl PostgresControlRepository {
         key_hash: &str,
     ) -> anyhow::Result<Option<AuthenticatedKey>> {
         let Some(cache) = self.request_cache.as_ref() else {
-            return self.load_authenticated_key_by_hash(key_hash).await;
+            return Ok(self
+                .load_authenticated_key_by_hash(key_hash)
+                .await?
+                .map(|(key, _)| key));
         };
         let cache_key = cache.auth_key(key_hash);
         match cache
@@ -158,23 +162,30 @@ impl PostgresControlRepository {
                 );
             },
         }
-        let key = self.load_authenticated_key_by_hash(key_hash).await?;
-        let lookup = crate::request_cache::CachedAuthLookup {
-            key: key
-                .clone()
-                .map(|value| cached_authenticated_key_from_value(&value)),
+        let loaded = self.load_authenticated_key_by_hash(key_hash).await?;
+        let ttl = match &loaded {
+            None => Some(cache.negative_auth_ttl(key_hash)),
+            Some((_, None)) => Some(cache.auth_ttl(key_hash)),
+            // A rotated-out secret must stop authenticating when its grace
+            // window ends, not when a full auth TTL runs out.
+            Some((_, Some(grace_expires_at_ms))) => crate::request_cache::ttl_capped_at(
+                cache.auth_ttl(key_hash),
+                *grace_expires_at_ms,
+                now_ms(),
+            ),
         };
-        let ttl = if key.is_some() {
-            cache.auth_ttl(key_hash)
-        } else {
-            cache.negative_auth_ttl(key_hash)
-        };
-        if let Err(err) = cache.set_json(&cache_key, &lookup, ttl).await {
-            tracing::warn!(
-                key = %cache_key,
-                error = %err,
-                "request cache auth write failed"
-            );
+        let key = loaded.map(|(key, _)| key);
+        if let Some(ttl) = ttl {
+            let lookup = crate::request_cache::CachedAuthLookup {
+                key: key.as_ref().map(cached_authenticated_key_from_value),
+            };
+            if let Err(err) = cache.set_json(&cache_key, &lookup, ttl).await {
+                tracing::warn!(
+                    key = %cache_key,
+                    error = %err,
+                    "request cache auth write failed"
+                );
+            }
         }
         Ok(key)
     }
@@ -183,9 +194,9 @@ impl PostgresControlRepository {
         if key_ids.is_empty() {
             return;
         }
-        let Some(cache) = self.request_cache.as_ref() else {
+        if self.request_cache.is_none() {
             return;
-        };
+        }
         let key_hashes = match self.load_key_hashes_by_ids(key_ids).await {
             Ok(value) => value,
             Err(err) => {
@@ -193,8 +204,21 @@ impl PostgresControlRepository {
                 return;
             },
         };
+        self.invalidate_authenticated_key_cache_by_hashes(&key_hashes)
+            .await;
+    }
+
+    /// Drops cached auth lookups for `key_hashes`, for callers whose rows are
+    /// already gone by the time the cache is invalidated.
+    pub(super) async fn invalidate_authenticated_key_cache_by_hashes(&self, key_hashes: &[String]) {
+        if key_hashes.is_empty() {
+            return;
+        }
+        let Some(cache) = self.request_cache.as_ref() else {
+            return;
+        };
         let cache_keys = key_hashes
-            .values()
+            .iter()
             .map(|key_hash| cache.auth_key(key_hash))
             .collect::<Vec<_>>();
         let cache_key_refs = cache_keys.iter().map(String::as_str).collect::<Vec<_>>();
@@ -401,12 +425,13 @@ impl PostgresControlRepository {
         key_id: &str,
         generation: i64,
     ) -> anyhow::Result<Option<crate::request_cache::CachedCodexRequestSnapshot>> {
-        let Some(bundle) = self.load_key_bundle_by_id(key_id).await? else {
+        let Some(mut bundle) = self.load_key_bundle_by_id(key_id).await? else {
             return Ok(None);
         };
         if bundle.key.provider_type != core_store::PROVIDER_CODEX {
             return Ok(None);
         }
+        self.apply_key_portal_caps(&mut bundle).await?;
         let runtime_config = self
             .load_runtime_config_record_cached()
             .await?
@@ -423,6 +448,12 @@ impl PostgresControlRepository {
                     .collect(),
             )
             .await?;
+        let request_transform_policy_json = self
+            .resolve_request_transform_policy_json(core_store::PROVIDER_CODEX, &bundle.route)
+            .await?;
+        let queue_policy_json = self
+            .resolve_queue_policy_json(core_store::PROVIDER_CODEX, &bundle.route)
+            .await?;
         Ok(Some(crate::request_cache::CachedCodexRequestSnapshot {
             key: cached_authenticated_key_from_bundle(&bundle),
             generation,
@@ -454,6 +485,18 @@ impl PostgresControlRepository {
             codex_weight_plus: runtime_config.codex_weight_plus,
             codex_weight_pro5x: runtime_config.codex_weight_pro5x,
             codex_weight_pro20x: runtime_config.codex_weight_pro20x,
+            response_cache_policy_json: bundle.route.response_cache_policy_json.clone(),
+            request_transform_policy_json,
+            moderation_policy_json: bundle.route.moderation_policy_json.clone(),
+            queue_policy_json,
+            openai_upstream_pool_mode: core_store::canonical_openai_upstream_pool_mode(Some(
+                &bundle.route.codex_openai_upstream_pool_mode,
+            )),
+            model_name_map_json: bundle
+                .route
+                .model_name_map_json
+                .clone()
+                .unwrap_or_else(|| "{}".to_string()),
         }))
     }
 
@@ -462,12 +505,13 @@ impl PostgresControlRepository {
         key_id: &str,
         generation: i64,
     ) -> anyhow::Result<Option<crate::request_cache::CachedKiroRequestSnapshot>> {
-        let Some(bundle) = self.load_key_bundle_by_id(key_id).await? else {
+        let Some(mut bundle) = self.load_key_bundle_by_id(key_id).await? else {
             return Ok(None);
         };
         if bundle.key.provider_type != core_store::PROVIDER_KIRO {
             return Ok(None);
         }
+        self.apply_key_portal_caps(&mut bundle).await?;
         let runtime_config = self
             .load_runtime_config_record_cached()
             .await?
@@ -494,6 +538,12 @@ impl PostgresControlRepository {
             &runtime_config.kiro_cache_policy_json,
             bundle.route.kiro_cache_policy_override_json.as_deref(),
         )?;
+        let request_transform_policy_json = self
+            .resolve_request_transform_policy_json(core_store::PROVIDER_KIRO, &bundle.route)
+            .await?;
+        let queue_policy_json = self
+            .resolve_queue_policy_json(core_store::PROVIDER_KIRO, &bundle.route)
+            .await?;
         Ok(Some(crate::request_cache::CachedKiroRequestSnapshot {
             key: cached_authenticated_key_from_bundle(&bundle),
             generation,
@@ -560,6 +610,10 @@ impl PostgresControlRepository {
             status_refresh_interval_seconds: runtime_config
                 .kiro_status_refresh_max_interval_seconds
                 .max(0) as u64,
+            response_cache_policy_json: bundle.route.response_cache_policy_json.clone(),
+            request_transform_policy_json,
+            moderation_policy_json: bundle.route.moderation_policy_json.clone(),
+            queue_policy_json,
         }))
     }
 
@@ -581,8 +635,7 @@ impl PostgresControlRepository {
                 .map(|row| {
                     let settings = decode_codex_account_settings(&row.settings_json)?;
                     let proxy = resolve_provider_proxy_config_from_context(
-                        &settings.proxy_mode,
-                        settings.proxy_config_id.as_deref(),
+                        codex_account_proxy_ref(&row.account_name, &settings),
                         &proxy_context,
                     )?;
                     Ok((row.account_name.clone(), crate::request_cache::CachedCodexAccountView {
@@ -645,8 +698,7 @@ impl PostgresControlRepository {
         {
             let settings = decode_codex_account_settings(&row.settings_json)?;
             let proxy = resolve_provider_proxy_config_from_context(
-                &settings.proxy_mode,
-                settings.proxy_config_id.as_deref(),
+                codex_account_proxy_ref(&row.account_name, &settings),
                 &proxy_context,
             )?;
             let view = crate::request_cache::CachedCodexAccountView {
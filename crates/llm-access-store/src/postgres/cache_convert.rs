@@ -6,7 +6,8 @@ use llm_access_core::store::{AuthenticatedKey, ProviderProxyConfig};
 use llm_access_kiro::cache_policy::{resolve_effective_kiro_cache_policy, KiroCachePolicy};
 
 use super::{
-    json::non_negative_i64_to_u64, proxy_support::resolve_provider_proxy_config_from_context,
+    json::non_negative_i64_to_u64,
+    proxy_support::{resolve_provider_proxy_config_from_context, AccountProxyRef},
     KiroCachedStatusParts, KiroRouteCandidateRow, ProviderProxyResolutionContext,
 };
 use crate::records::KeyBundle;
@@ -22,6 +23,8 @@ pub fn cached_authenticated_key_from_value(
         status: key.status.clone(),
         quota_billable_limit: key.quota_billable_limit,
         billable_tokens_used: key.billable_tokens_used,
+        parent_remaining_billable: key.parent_remaining_billable,
+        parent_key_id: key.parent_key_id.clone(),
     }
 }
 
@@ -36,6 +39,8 @@ pub fn cached_authenticated_key_from_bundle(
         status: bundle.key.status.clone(),
         quota_billable_limit: bundle.key.quota_billable_limit,
         billable_tokens_used: bundle.rollup.billable_tokens,
+        parent_remaining_billable: None,
+        parent_key_id: bundle.key.parent_key_id.clone(),
     })
 }
 
@@ -50,6 +55,8 @@ pub fn authenticated_key_from_cached(
         status: key.status,
         quota_billable_limit: key.quota_billable_limit,
         billable_tokens_used: key.billable_tokens_used,
+        parent_remaining_billable: key.parent_remaining_billable,
+        parent_key_id: key.parent_key_id,
     }
 }
 
@@ -90,8 +97,12 @@ pub fn build_cached_kiro_account_view(
         .clone()
         .or_else(|| row.auth_proxy_config_id.clone());
     let proxy = resolve_provider_proxy_config_from_context(
-        &proxy_mode,
-        proxy_config_id.as_deref(),
+        AccountProxyRef {
+            proxy_mode: &proxy_mode,
+            proxy_config_id: proxy_config_id.as_deref(),
+            proxy_pool_id: row.proxy_pool_id.as_deref(),
+            sticky_key: &row.account_name,
+        },
         proxy_context,
     )?;
     Ok(crate::request_cache::CachedKiroAccountView {
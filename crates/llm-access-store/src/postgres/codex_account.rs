@@ -23,11 +23,13 @@ use super::{
         decode_codex_admin_account_list_row, decode_codex_import_job_summary_row,
     },
     json::non_negative_i64_to_u64,
-    proxy_support::resolve_provider_proxy_config_from_context,
+    proxy_support::{
+        account_proxy_view, codex_account_proxy_ref, resolve_provider_proxy_config_from_context,
+    },
     CodexAccountSettings, CodexAdminAccountListRow, CodexAdminAccountViewContext,
     PostgresControlRepository,
 };
-use crate::records::CodexAccountRecord;
+use crate::{records::CodexAccountRecord, secrets::SecretRow};
 
 impl PostgresControlRepository {
     pub(super) async fn load_codex_rate_limit_status_row(
@@ -121,17 +123,18 @@ impl PostgresControlRepository {
                     NULL::double precision,
                     NULL::bigint,
                     NULL::bigint,
-                    NULL::text
+                    NULL::text,
+                    created_at_ms,
+                    NULLIF(BTRIM(settings_json ->> 'proxy_pool_id'), '')
                  FROM llm_codex_accounts
                  ORDER BY created_at_ms DESC, account_name DESC",
                 &[],
             )
             .await
             .context("list postgres codex admin account rows")?;
-        Ok(rows
-            .into_iter()
-            .map(decode_codex_admin_account_list_row)
-            .collect())
+        rows.into_iter()
+            .map(|row| decode_codex_admin_account_list_row(row, &self.secrets))
+            .collect()
     }
 
     pub(super) async fn find_codex_account_name_by_principal_id_uncached(
@@ -153,12 +156,18 @@ impl PostgresControlRepository {
             )
             .await
             .context("scan postgres codex account principals")?;
-        Ok(rows.into_iter().find_map(|row| {
+        for row in rows {
             let name: String = row.get(0);
             let auth_json: String = row.get(1);
-            (core_store::codex_auth_principal_id(&auth_json).as_deref() == Some(principal_id))
-                .then_some(name)
-        }))
+            let auth_json = self
+                .secrets
+                .open_auth_json(SecretRow::new("llm_codex_accounts", &name), &auth_json)
+                .with_context(|| format!("open auth json of codex account `{name}`"))?;
+            if core_store::codex_auth_principal_id(&auth_json).as_deref() == Some(principal_id) {
+                return Ok(Some(name));
+            }
+        }
+        Ok(None)
     }
 
     async fn admin_codex_accounts_summary(
@@ -266,7 +275,9 @@ impl PostgresControlRepository {
                     NULL::double precision,
                     NULL::bigint,
                     NULL::bigint,
-                    NULL::text
+                    NULL::text,
+                    created_at_ms,
+                    NULLIF(BTRIM(settings_json ->> 'proxy_pool_id'), '')
                  FROM llm_codex_accounts
                  ORDER BY created_at_ms DESC, account_name DESC
                  LIMIT $1 OFFSET $2",
@@ -276,8 +287,8 @@ impl PostgresControlRepository {
             .context("list postgres codex admin account rows page")?;
         Ok((
             rows.into_iter()
-                .map(decode_codex_admin_account_list_row)
-                .collect(),
+                .map(|row| decode_codex_admin_account_list_row(row, &self.secrets))
+                .collect::<anyhow::Result<Vec<_>>>()?,
             total,
         ))
     }
@@ -424,7 +435,8 @@ impl PostgresControlRepository {
                         THEN sa.usage_error_message
                         ELSE NULL
                     END AS usage_error_message,
-                    a.created_at_ms
+                    a.created_at_ms,
+                    NULLIF(BTRIM(a.settings_json ->> 'proxy_pool_id'), '') AS proxy_pool_id
                 FROM llm_codex_accounts a
                 LEFT JOIN status_accounts sa ON sa.account_name = a.account_name
             )";
@@ -493,7 +505,9 @@ impl PostgresControlRepository {
                 secondary_remaining_percent,
                 last_usage_checked_at_ms,
                 last_usage_success_at_ms,
-                usage_error_message
+                usage_error_message,
+                created_at_ms,
+                proxy_pool_id
              FROM account_rows
              {filter_sql}
              ORDER BY {order_by}
@@ -512,8 +526,8 @@ impl PostgresControlRepository {
             .context("list postgres filtered codex admin account rows page")?;
         Ok((
             rows.into_iter()
-                .map(decode_codex_admin_account_list_row)
-                .collect(),
+                .map(|row| decode_codex_admin_account_list_row(row, &self.secrets))
+                .collect::<anyhow::Result<Vec<_>>>()?,
             total,
         ))
     }
@@ -535,7 +549,8 @@ impl PostgresControlRepository {
             )
             .await
             .context("load codex account")?;
-        Ok(row.map(decode_codex_account_row))
+        row.map(|row| decode_codex_account_row(row, &self.secrets))
+            .transpose()
     }
 
     pub(super) async fn load_codex_admin_account_view_context(
@@ -550,42 +565,28 @@ impl PostgresControlRepository {
         let codex_proxy_binding = self
             .load_admin_proxy_binding_from_configs(core_store::PROVIDER_CODEX, &proxy_configs_by_id)
             .await?;
+        let proxy_pools = self
+            .load_proxy_pool_context(core_store::PROVIDER_CODEX)
+            .await?;
         Ok(CodexAdminAccountViewContext {
             proxy_configs_by_id,
             codex_proxy_binding,
+            proxy_pools,
         })
     }
 
     pub(super) fn resolve_codex_account_proxy_view_with_context(
         &self,
+        account_name: &str,
         settings: &CodexAccountSettings,
         context: &CodexAdminAccountViewContext,
     ) -> (String, Option<String>, Option<String>) {
-        match settings.proxy_mode.as_str() {
-            "none" => ("none".to_string(), None, None),
-            "fixed" => {
-                let Some(proxy_id) = settings.proxy_config_id.as_deref() else {
-                    return ("invalid".to_string(), None, None);
-                };
-                match context.proxy_configs_by_id.get(proxy_id) {
-                    Some(proxy) if proxy.status == core_store::KEY_STATUS_ACTIVE => (
-                        "fixed".to_string(),
-                        Some(proxy.proxy_url.clone()),
-                        Some(proxy.name.clone()),
-                    ),
-                    Some(proxy) => ("invalid".to_string(), None, Some(proxy.name.clone())),
-                    None => ("invalid".to_string(), None, None),
-                }
-            },
-            _ => (
-                context.codex_proxy_binding.effective_source.clone(),
-                context.codex_proxy_binding.effective_proxy_url.clone(),
-                context
-                    .codex_proxy_binding
-                    .effective_proxy_config_name
-                    .clone(),
-            ),
-        }
+        account_proxy_view(
+            codex_account_proxy_ref(account_name, settings),
+            &context.proxy_configs_by_id,
+            &context.codex_proxy_binding,
+            &context.proxy_pools,
+        )
     }
 
     fn admin_codex_account_from_list_row_with_context(
@@ -599,6 +600,7 @@ impl PostgresControlRepository {
             route_weight_tier: row.route_weight_tier.clone(),
             proxy_mode: row.proxy_mode.clone(),
             proxy_config_id: row.proxy_config_id.clone(),
+            proxy_pool_id: row.proxy_pool_id.clone(),
             request_max_concurrency: row
                 .request_max_concurrency
                 .and_then(non_negative_i64_to_u64),
@@ -610,8 +612,8 @@ impl PostgresControlRepository {
                 .codex_image_generation_max_concurrency
                 .max(1) as u64,
         };
-        let (effective_proxy_source, effective_proxy_url, effective_proxy_config_name) =
-            self.resolve_codex_account_proxy_view_with_context(&settings, context);
+        let (effective_proxy_source, effective_proxy_url, effective_proxy_config_name) = self
+            .resolve_codex_account_proxy_view_with_context(&row.account_name, &settings, context);
         AdminCodexAccount {
             name: row.account_name.clone(),
             status: row.status.clone(),
@@ -633,6 +635,7 @@ impl PostgresControlRepository {
             codex_image_generation_max_concurrency: settings.codex_image_generation_max_concurrency,
             proxy_mode: settings.proxy_mode,
             proxy_config_id: settings.proxy_config_id,
+            proxy_pool_id: settings.proxy_pool_id,
             effective_proxy_source,
             effective_proxy_url,
             effective_proxy_config_name,
@@ -653,8 +656,12 @@ impl PostgresControlRepository {
         context: &CodexAdminAccountViewContext,
     ) -> anyhow::Result<AdminCodexAccount> {
         let settings = decode_codex_account_settings(&record.settings_json)?;
-        let (effective_proxy_source, effective_proxy_url, effective_proxy_config_name) =
-            self.resolve_codex_account_proxy_view_with_context(&settings, context);
+        let (effective_proxy_source, effective_proxy_url, effective_proxy_config_name) = self
+            .resolve_codex_account_proxy_view_with_context(
+                &record.account_name,
+                &settings,
+                context,
+            );
         Ok(AdminCodexAccount {
             name: record.account_name.clone(),
             status: record.status.clone(),
@@ -676,6 +683,7 @@ impl PostgresControlRepository {
             codex_image_generation_max_concurrency: settings.codex_image_generation_max_concurrency,
             proxy_mode: settings.proxy_mode,
             proxy_config_id: settings.proxy_config_id,
+            proxy_pool_id: settings.proxy_pool_id,
             effective_proxy_source,
             effective_proxy_url,
             effective_proxy_config_name,
@@ -702,6 +710,13 @@ impl PostgresControlRepository {
         &self,
         record: &CodexAccountRecord,
     ) -> anyhow::Result<()> {
+        let sealed_auth_json = self
+            .secrets
+            .seal_auth_json(
+                SecretRow::new("llm_codex_accounts", &record.account_name),
+                &record.auth_json,
+            )
+            .context("seal codex account auth json")?;
         self.ensure_connection_alive()?;
         self.client
             .execute(
@@ -724,7 +739,7 @@ impl PostgresControlRepository {
                     &record.account_id,
                     &record.email,
                     &record.status,
-                    &record.auth_json,
+                    &sealed_auth_json,
                     &record.settings_json,
                     &record.last_refresh_at_ms,
                     &record.last_error,
@@ -912,6 +927,9 @@ impl AdminCodexAccountStore for PostgresControlRepository {
         if let Some(value) = patch.proxy_config_id.as_ref() {
             settings.proxy_config_id = value.clone();
         }
+        if let Some(value) = patch.proxy_pool_id.as_ref() {
+            settings.proxy_pool_id = value.clone();
+        }
         if let Some(value) = patch.request_max_concurrency {
             settings.request_max_concurrency = value;
         }
@@ -992,8 +1010,7 @@ impl AdminCodexAccountStore for PostgresControlRepository {
             .load_provider_proxy_resolution_context(core_store::PROVIDER_CODEX)
             .await?;
         let proxy = resolve_provider_proxy_config_from_context(
-            &settings.proxy_mode,
-            settings.proxy_config_id.as_deref(),
+            codex_account_proxy_ref(&record.account_name, &settings),
             &proxy_context,
         )?;
         let status_by_account = self
@@ -14,9 +14,12 @@ use super::{
     CodexAccountSettings, CodexAdminAccountListRow, KiroAdminAccountListRow, PgRow,
     ProxyEndpointCheckRow,
 };
-use crate::records::{
-    CodexAccountRecord, KeyBundle, KeyRecord, KeyRouteConfig, KeyUsageRollup, KiroAccountRecord,
-    RuntimeConfigRecord,
+use crate::{
+    records::{
+        CodexAccountRecord, KeyBundle, KeyRecord, KeyRouteConfig, KeyUsageRollup,
+        KiroAccountRecord, RuntimeConfigRecord,
+    },
+    secrets::{SecretKeyring, SecretRow},
 };
 
 pub fn decode_runtime_config_row(row: PgRow) -> anyhow::Result<RuntimeConfigRecord> {
@@ -87,8 +90,9 @@ pub fn decode_runtime_config_row(row: PgRow) -> anyhow::Result<RuntimeConfigReco
     })
 }
 
-fn decode_key_bundle(row: &PgRow) -> anyhow::Result<KeyBundle> {
+fn decode_key_bundle(row: &PgRow, secrets: &SecretKeyring) -> anyhow::Result<KeyBundle> {
     let key_id: String = row.get(0);
+    let secret: String = row.get(2);
     let credit_total_raw: String = row.get(33);
     let credit_total = credit_total_raw
         .parse::<f64>()
@@ -97,13 +101,16 @@ fn decode_key_bundle(row: &PgRow) -> anyhow::Result<KeyBundle> {
         key: KeyRecord {
             key_id: key_id.clone(),
             name: row.get(1),
-            secret: row.get(2),
+            secret: secrets
+                .open(SecretRow::new("llm_keys", &key_id), &secret)
+                .with_context(|| format!("open secret of key `{key_id}`"))?,
             key_hash: row.get(3),
             status: row.get(4),
             provider_type: row.get(5),
             protocol_family: row.get(6),
             public_visible: row.get(7),
             quota_billable_limit: row.get(8),
+            parent_key_id: row.try_get_optional_string("parent_key_id")?,
             created_at_ms: row.get(9),
             updated_at_ms: row.get(10),
         },
@@ -146,6 +153,16 @@ fn decode_key_bundle(row: &PgRow) -> anyhow::Result<KeyBundle> {
                 row.try_get_optional_string("kiro_anthropic_upstream_pool_mode")?
                     .as_deref(),
             ),
+            codex_openai_upstream_pool_mode: core_store::canonical_openai_upstream_pool_mode(
+                row.try_get_optional_string("codex_openai_upstream_pool_mode")?
+                    .as_deref(),
+            ),
+            response_cache_policy_json: row
+                .try_get_optional_string("response_cache_policy_json")?,
+            request_transform_policy_json: row
+                .try_get_optional_string("request_transform_policy_json")?,
+            moderation_policy_json: row.try_get_optional_string("moderation_policy_json")?,
+            queue_policy_json: row.try_get_optional_string("queue_policy_json")?,
         },
         rollup: KeyUsageRollup {
             key_id,
@@ -164,8 +181,8 @@ fn decode_key_bundle(row: &PgRow) -> anyhow::Result<KeyBundle> {
     })
 }
 
-pub fn decode_key_bundle_row(row: PgRow) -> anyhow::Result<KeyBundle> {
-    decode_key_bundle(&row)
+pub fn decode_key_bundle_row(row: PgRow, secrets: &SecretKeyring) -> anyhow::Result<KeyBundle> {
+    decode_key_bundle(&row, secrets)
 }
 
 pub fn admin_key_from_bundle(bundle: &KeyBundle) -> AdminKey {
@@ -179,6 +196,7 @@ pub fn admin_key_from_bundle(bundle: &KeyBundle) -> AdminKey {
         status: bundle.key.status.clone(),
         provider_type: bundle.key.provider_type.clone(),
         public_visible: bundle.key.public_visible,
+        parent_key_id: bundle.key.parent_key_id.clone(),
         quota_billable_limit: quota,
         usage_input_uncached_tokens: bundle.rollup.input_uncached_tokens.max(0) as u64,
         usage_input_cached_tokens: bundle.rollup.input_cached_tokens.max(0) as u64,
@@ -232,6 +250,15 @@ pub fn admin_key_from_bundle(bundle: &KeyBundle) -> AdminKey {
             .kiro_billable_model_multipliers_override_json
             .clone(),
         kiro_anthropic_upstream_pool_mode: bundle.route.kiro_anthropic_upstream_pool_mode.clone(),
+        codex_openai_upstream_pool_mode: bundle.route.codex_openai_upstream_pool_mode.clone(),
+        response_cache_policy: decode_optional_json(
+            bundle.route.response_cache_policy_json.as_deref(),
+        ),
+        request_transform_policy: decode_optional_json(
+            bundle.route.request_transform_policy_json.as_deref(),
+        ),
+        moderation_policy: decode_optional_json(bundle.route.moderation_policy_json.as_deref()),
+        queue_policy: decode_optional_json(bundle.route.queue_policy_json.as_deref()),
         effective_kiro_cache_policy_json: bundle
             .route
             .kiro_cache_policy_override_json
@@ -265,8 +292,8 @@ fn decode_kiro_candidate_credit_summary_row(
     }
 }
 
-pub fn decode_kiro_admin_key_row(row: PgRow) -> anyhow::Result<AdminKey> {
-    let bundle = decode_key_bundle(&row)?;
+pub fn decode_kiro_admin_key_row(row: PgRow, secrets: &SecretKeyring) -> anyhow::Result<AdminKey> {
+    let bundle = decode_key_bundle(&row, secrets)?;
     let mut key = admin_key_from_bundle(&bundle);
     key.kiro_candidate_credit_summary = Some(decode_kiro_candidate_credit_summary_row(&row, 40));
     Ok(key)
@@ -281,18 +308,32 @@ pub fn decode_admin_account_group_row(row: PgRow) -> anyhow::Result<AdminAccount
         provider_type: row.get(1),
         name: row.get(2),
         account_names,
+        request_transform_policy: decode_optional_json(
+            row.try_get_optional_string("request_transform_policy_json")?
+                .as_deref(),
+        ),
+        queue_policy: decode_optional_json(
+            row.try_get_optional_string("queue_policy_json")?.as_deref(),
+        ),
         created_at: row.get(4),
         updated_at: row.get(5),
     })
 }
 
-pub fn decode_admin_proxy_config_row(row: PgRow) -> AdminProxyConfig {
-    AdminProxyConfig {
-        id: row.get(0),
+pub fn decode_admin_proxy_config_row(
+    row: PgRow,
+    secrets: &SecretKeyring,
+) -> anyhow::Result<AdminProxyConfig> {
+    let id: String = row.get(0);
+    let proxy_password: Option<String> = row.get(4);
+    Ok(AdminProxyConfig {
+        proxy_password: secrets
+            .open_optional(SecretRow::new("llm_proxy_configs", &id), proxy_password.as_deref())
+            .with_context(|| format!("open password of proxy config `{id}`"))?,
+        id,
         name: row.get(1),
         proxy_url: row.get(2),
         proxy_username: row.get(3),
-        proxy_password: row.get(4),
         status: row.get(5),
         created_at: row.get(6),
         updated_at: row.get(7),
@@ -303,7 +344,7 @@ pub fn decode_admin_proxy_config_row(row: PgRow) -> AdminProxyConfig {
         latest_codex_check: None,
         latest_kiro_check: None,
         traffic_snapshot: None,
-    }
+    })
 }
 
 pub fn decode_proxy_endpoint_check_row(row: PgRow) -> ProxyEndpointCheckRow {
@@ -324,30 +365,44 @@ pub fn decode_proxy_endpoint_check_row(row: PgRow) -> ProxyEndpointCheckRow {
     }
 }
 
-pub fn decode_codex_account_row(row: PgRow) -> CodexAccountRecord {
-    CodexAccountRecord {
-        account_name: row.get(0),
+pub fn decode_codex_account_row(
+    row: PgRow,
+    secrets: &SecretKeyring,
+) -> anyhow::Result<CodexAccountRecord> {
+    let account_name: String = row.get(0);
+    let auth_json: String = row.get(4);
+    Ok(CodexAccountRecord {
+        auth_json: secrets
+            .open_auth_json(SecretRow::new("llm_codex_accounts", &account_name), &auth_json)
+            .with_context(|| format!("open auth json of codex account `{account_name}`"))?,
+        account_name,
         account_id: row.get(1),
         email: row.get(2),
         status: row.get(3),
-        auth_json: row.get(4),
         settings_json: row.get(5),
         last_refresh_at_ms: row.get(6),
         last_error: row.get(7),
         created_at_ms: row.get(8),
         updated_at_ms: row.get(9),
-    }
+    })
 }
 
-pub fn decode_kiro_account_row(row: PgRow) -> KiroAccountRecord {
-    KiroAccountRecord {
-        account_name: row.get(0),
+pub fn decode_kiro_account_row(
+    row: PgRow,
+    secrets: &SecretKeyring,
+) -> anyhow::Result<KiroAccountRecord> {
+    let account_name: String = row.get(0);
+    let auth_json: String = row.get(6);
+    Ok(KiroAccountRecord {
+        auth_json: secrets
+            .open_auth_json(SecretRow::new("llm_kiro_accounts", &account_name), &auth_json)
+            .with_context(|| format!("open auth json of kiro account `{account_name}`"))?,
+        account_name,
         auth_method: row.get(1),
         account_id: row.get(2),
         profile_arn: row.get(3),
         user_id: row.get(4),
         status: row.get(5),
-        auth_json: row.get(6),
         max_concurrency: row.get(7),
         min_start_interval_ms: row.get(8),
         proxy_config_id: row.get(9),
@@ -355,12 +410,23 @@ pub fn decode_kiro_account_row(row: PgRow) -> KiroAccountRecord {
         last_error: row.get(11),
         created_at_ms: row.get(12),
         updated_at_ms: row.get(13),
-    }
+    })
 }
 
-pub fn decode_codex_admin_account_list_row(row: PgRow) -> CodexAdminAccountListRow {
-    CodexAdminAccountListRow {
-        account_name: row.get(0),
+pub fn decode_codex_admin_account_list_row(
+    row: PgRow,
+    secrets: &SecretKeyring,
+) -> anyhow::Result<CodexAdminAccountListRow> {
+    let account_name: String = row.get(0);
+    let access_token: Option<String> = row.get(15);
+    Ok(CodexAdminAccountListRow {
+        access_token: secrets
+            .open_optional(
+                SecretRow::new("llm_codex_accounts", &account_name),
+                access_token.as_deref(),
+            )
+            .with_context(|| format!("open access token of codex account `{account_name}`"))?,
+        account_name,
         account_id: row.get(1),
         email: row.get(2),
         status: row.get(3),
@@ -375,14 +441,14 @@ pub fn decode_codex_admin_account_list_row(row: PgRow) -> CodexAdminAccountListR
         codex_image_generation_max_concurrency: row.get(12),
         last_refresh_at_ms: row.get(13),
         last_error: row.get(14),
-        access_token: row.get(15),
         plan_type: row.get(16),
         primary_remaining_percent: row.get(17),
         secondary_remaining_percent: row.get(18),
         last_usage_checked_at_ms: row.get(19),
         last_usage_success_at_ms: row.get(20),
         usage_error_message: row.get(21),
-    }
+        proxy_pool_id: row.get(23),
+    })
 }
 
 pub fn decode_kiro_admin_account_list_row(row: PgRow) -> anyhow::Result<KiroAdminAccountListRow> {
@@ -421,6 +487,7 @@ pub fn decode_kiro_admin_account_list_row(row: PgRow) -> anyhow::Result<KiroAdmi
         proxy_mode: row.get("proxy_mode"),
         proxy_config_id: row.get("proxy_config_id"),
         auth_proxy_config_id: row.get("auth_proxy_config_id"),
+        proxy_pool_id: row.get("proxy_pool_id"),
         proxy_url: row.get("proxy_url"),
         last_error: row.get("last_error"),
     })
@@ -445,6 +512,7 @@ pub fn decode_public_usage_lookup_row(row: PgRow) -> anyhow::Result<PublicUsageL
         usage_credit_total,
         usage_credit_missing_events: row.get::<_, i64>(11).max(0) as u64,
         last_used_at_ms: row.get(12),
+        parent_key_id: row.get(13),
     })
 }
 
@@ -19,7 +19,9 @@ impl PostgresControlRepository {
             .client
             .query(
                 "SELECT group_id, provider_type, name, account_names_json::text,
-                    created_at_ms, updated_at_ms
+                    created_at_ms, updated_at_ms,
+                    request_transform_policy_json::text AS request_transform_policy_json,
+                    queue_policy_json::text AS queue_policy_json
                  FROM llm_account_groups
                  WHERE provider_type = $1
                  ORDER BY created_at_ms DESC, group_id DESC",
@@ -55,7 +57,9 @@ impl PostgresControlRepository {
             .client
             .query(
                 "SELECT group_id, provider_type, name, account_names_json::text,
-                    created_at_ms, updated_at_ms
+                    created_at_ms, updated_at_ms,
+                    request_transform_policy_json::text AS request_transform_policy_json,
+                    queue_policy_json::text AS queue_policy_json
                  FROM llm_account_groups
                  WHERE provider_type = $1
                  ORDER BY created_at_ms DESC, group_id DESC
@@ -86,7 +90,9 @@ impl PostgresControlRepository {
             .client
             .query_opt(
                 "SELECT group_id, provider_type, name, account_names_json::text,
-                    created_at_ms, updated_at_ms
+                    created_at_ms, updated_at_ms,
+                    request_transform_policy_json::text AS request_transform_policy_json,
+                    queue_policy_json::text AS queue_policy_json
                  FROM llm_account_groups
                  WHERE group_id = $1",
                 &[&group_id],
@@ -162,12 +168,26 @@ impl AdminAccountGroupStore for PostgresControlRepository {
     ) -> anyhow::Result<AdminAccountGroup> {
         let account_names_json =
             serde_json::to_string(&group.account_names).context("serialize account group names")?;
+        let request_transform_policy_json = group
+            .request_transform_policy
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()
+            .context("serialize account group request transform policy")?;
+        let queue_policy_json = group
+            .queue_policy
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()
+            .context("serialize account group queue policy")?;
         self.ensure_connection_alive()?;
         self.client
             .execute(
                 "INSERT INTO llm_account_groups (
-                    group_id, provider_type, name, account_names_json, created_at_ms, updated_at_ms
-                 ) VALUES ($1, $2, $3, $4::jsonb, $5, $6)",
+                    group_id, provider_type, name, account_names_json, created_at_ms, \
+                 updated_at_ms,
+                    request_transform_policy_json, queue_policy_json
+                 ) VALUES ($1, $2, $3, $4::jsonb, $5, $6, $7::jsonb, $8::jsonb)",
                 &[
                     &group.id,
                     &group.provider_type,
@@ -175,6 +195,8 @@ impl AdminAccountGroupStore for PostgresControlRepository {
                     &account_names_json,
                     &group.created_at_ms,
                     &group.created_at_ms,
+                    &request_transform_policy_json,
+                    &queue_policy_json,
                 ],
             )
             .await
@@ -199,16 +221,42 @@ impl AdminAccountGroupStore for PostgresControlRepository {
         if let Some(account_names) = patch.account_names.as_ref() {
             group.account_names = account_names.clone();
         }
+        if let Some(policy) = patch.request_transform_policy.as_ref() {
+            group.request_transform_policy = policy.clone();
+        }
+        if let Some(policy) = patch.queue_policy.as_ref() {
+            group.queue_policy = policy.clone();
+        }
         group.updated_at = patch.updated_at_ms;
         let account_names_json =
             serde_json::to_string(&group.account_names).context("serialize account group names")?;
+        let request_transform_policy_json = group
+            .request_transform_policy
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()
+            .context("serialize account group request transform policy")?;
+        let queue_policy_json = group
+            .queue_policy
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()
+            .context("serialize account group queue policy")?;
         self.ensure_connection_alive()?;
         self.client
             .execute(
                 "UPDATE llm_account_groups
-                 SET name = $2, account_names_json = $3::jsonb, updated_at_ms = $4
+                 SET name = $2, account_names_json = $3::jsonb, updated_at_ms = $4,
+                     request_transform_policy_json = $5::jsonb, queue_policy_json = $6::jsonb
                  WHERE group_id = $1",
-                &[&group_id, &group.name, &account_names_json, &group.updated_at],
+                &[
+                    &group_id,
+                    &group.name,
+                    &account_names_json,
+                    &group.updated_at,
+                    &request_transform_policy_json,
+                    &queue_policy_json,
+                ],
             )
             .await
             .context("patch postgres account group")?;
@@ -0,0 +1,434 @@
+//! Key-holder portal settings, secret rotation + the `KeyPortalStore` impl.
+//!
+//! Holder caps live in `llm_key_portal_settings`, apart from the admin-owned
+//! key and route rows. Auth applies the quota cap in SQL; request snapshots
+//! apply all caps through [`PostgresControlRepository::apply_key_portal_caps`].
+//! Alert recipients are only emailed once they confirmed the address in
+//! `llm_key_notify_email_confirmations`.
+
+use anyhow::Context;
+use async_trait::async_trait;
+use llm_access_core::store::{
+    self as core_store, AdminKey, ConfirmedKeyNotifyEmail, KeyPortalCaps, KeyPortalSettings,
+    KeyPortalSettingsPatch, KeyPortalStore, KeyQuotaAlertCandidate, KeySecretRotation,
+    NewKeyNotifyEmailConfirmation, KEY_NOTIFY_EMAIL_CONFIRMATION_TTL_SECONDS,
+};
+
+use super::{
+    decode::admin_key_from_bundle, json::non_negative_i64_to_u64, now_ms, PgRow,
+    PostgresControlRepository,
+};
+use crate::{records::KeyBundle, secrets::SecretRow};
+
+impl PostgresControlRepository {
+    /// Tighten a bundle's quota, concurrency and pacing by the holder caps.
+    /// Only used on request-snapshot copies, never on bundles written back.
+    pub(super) async fn apply_key_portal_caps(&self, bundle: &mut KeyBundle) -> anyhow::Result<()> {
+        let caps = self.load_key_portal_caps(&bundle.key.key_id).await?;
+        if caps.is_empty() {
+            return Ok(());
+        }
+        let quota = non_negative_i64_to_u64(bundle.key.quota_billable_limit).unwrap_or(0);
+        bundle.key.quota_billable_limit = caps.cap_quota(quota) as i64;
+        bundle.route.request_max_concurrency = caps
+            .cap_concurrency(
+                bundle
+                    .route
+                    .request_max_concurrency
+                    .and_then(non_negative_i64_to_u64),
+            )
+            .map(|value| value as i64);
+        bundle.route.request_min_start_interval_ms = caps
+            .floor_interval(
+                bundle
+                    .route
+                    .request_min_start_interval_ms
+                    .and_then(non_negative_i64_to_u64),
+            )
+            .map(|value| value as i64);
+        Ok(())
+    }
+
+    async fn load_key_portal_caps(&self, key_id: &str) -> anyhow::Result<KeyPortalCaps> {
+        self.ensure_connection_alive()?;
+        let row = self
+            .client
+            .query_opt(
+                "SELECT quota_billable_cap, request_max_concurrency_cap,
+                    request_min_start_interval_ms_floor
+                 FROM llm_key_portal_settings
+                 WHERE key_id = $1",
+                &[&key_id],
+            )
+            .await
+            .context("load postgres key portal caps")?;
+        Ok(row
+            .map(|row| decode_key_portal_caps(&row, 0))
+            .unwrap_or_default())
+    }
+
+    async fn load_confirmed_notify_emails(&self, key_id: &str) -> anyhow::Result<Vec<String>> {
+        self.ensure_connection_alive()?;
+        let rows = self
+            .client
+            .query(
+                "SELECT email
+                 FROM llm_key_notify_email_confirmations
+                 WHERE key_id = $1 AND confirmed_at_ms IS NOT NULL
+                 ORDER BY email ASC",
+                &[&key_id],
+            )
+            .await
+            .context("load postgres confirmed notify emails")?;
+        Ok(rows
+            .into_iter()
+            .map(|row| row.get::<_, String>(0))
+            .collect())
+    }
+
+    /// Drop cached auth and request state after a change that affects how
+    /// the key, or sub-keys drawing on its budget, authenticate.
+    async fn invalidate_key_portal_caches(&self, bundle: &KeyBundle) -> anyhow::Result<()> {
+        let mut key_ids = self.load_sub_key_ids(&bundle.key.key_id).await?;
+        key_ids.push(bundle.key.key_id.clone());
+        self.invalidate_authenticated_key_cache_by_ids(&key_ids)
+            .await;
+        self.invalidate_request_snapshot_cache(&bundle.key.provider_type, &bundle.key.key_id)
+            .await;
+        self.bump_dispatch_generation(&bundle.key.provider_type)
+            .await;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl KeyPortalStore for PostgresControlRepository {
+    async fn get_key_portal_settings(&self, key_id: &str) -> anyhow::Result<KeyPortalSettings> {
+        self.ensure_connection_alive()?;
+        let row = self
+            .client
+            .query_opt(
+                "SELECT
+                    s.quota_billable_cap, s.request_max_concurrency_cap,
+                    s.request_min_start_interval_ms_floor,
+                    COALESCE(s.notify_emails, '[]'::jsonb)::text,
+                    COALESCE(s.quota_alert_thresholds, '[]'::jsonb)::text,
+                    s.quota_alert_level,
+                    CASE
+                        WHEN k.previous_key_hash_expires_at_ms > $2
+                        THEN k.previous_key_hash_expires_at_ms
+                    END,
+                    COALESCE(s.updated_at_ms, 0)
+                 FROM llm_keys k
+                 LEFT JOIN llm_key_portal_settings s ON s.key_id = k.key_id
+                 WHERE k.key_id = $1",
+                &[&key_id, &now_ms()],
+            )
+            .await
+            .context("load postgres key portal settings")?;
+        let Some(row) = row else {
+            return Ok(KeyPortalSettings::default());
+        };
+        let notify_emails: Vec<String> = decode_json_list(&row.get::<_, String>(3));
+        let confirmed_notify_emails = self
+            .load_confirmed_notify_emails(key_id)
+            .await?
+            .into_iter()
+            .filter(|email| notify_emails.contains(email))
+            .collect();
+        Ok(KeyPortalSettings {
+            caps: decode_key_portal_caps(&row, 0),
+            notify_emails,
+            confirmed_notify_emails,
+            quota_alert_thresholds: decode_json_list(&row.get::<_, String>(4)),
+            quota_alert_level: decode_alert_level(row.get(5)),
+            previous_secret_expires_at_ms: row.get(6),
+            updated_at_ms: row.get(7),
+        })
+    }
+
+    async fn patch_key_portal_settings(
+        &self,
+        key_id: &str,
+        patch: KeyPortalSettingsPatch,
+    ) -> anyhow::Result<KeyPortalSettings> {
+        let bundle = self
+            .load_key_bundle_by_id(key_id)
+            .await?
+            .context("key not found")?;
+        let mut settings = self.get_key_portal_settings(key_id).await?;
+        if let Some(value) = patch.quota_billable_cap {
+            settings.caps.quota_billable_cap = value;
+        }
+        if let Some(value) = patch.request_max_concurrency_cap {
+            settings.caps.request_max_concurrency_cap = value;
+        }
+        if let Some(value) = patch.request_min_start_interval_ms_floor {
+            settings.caps.request_min_start_interval_ms_floor = value;
+        }
+        if let Some(value) = patch.notify_emails {
+            settings
+                .confirmed_notify_emails
+                .retain(|email| value.contains(email));
+            settings.notify_emails = value;
+        }
+        if let Some(value) = patch.quota_alert_thresholds {
+            if value != settings.quota_alert_thresholds {
+                // New thresholds are judged against current usage from scratch.
+                settings.quota_alert_level = None;
+            }
+            settings.quota_alert_thresholds = value;
+        }
+        settings.updated_at_ms = patch.updated_at_ms;
+        let notify_emails =
+            serde_json::to_string(&settings.notify_emails).context("serialize notify emails")?;
+        let thresholds = serde_json::to_string(&settings.quota_alert_thresholds)
+            .context("serialize quota alert thresholds")?;
+        self.client
+            .execute(
+                "INSERT INTO llm_key_portal_settings (
+                    key_id, quota_billable_cap, request_max_concurrency_cap,
+                    request_min_start_interval_ms_floor, notify_emails, quota_alert_thresholds,
+                    quota_alert_level, updated_at_ms
+                 ) VALUES ($1, $2, $3, $4, $5::jsonb, $6::jsonb, $7, $8)
+                 ON CONFLICT(key_id) DO UPDATE SET
+                    quota_billable_cap = EXCLUDED.quota_billable_cap,
+                    request_max_concurrency_cap = EXCLUDED.request_max_concurrency_cap,
+                    request_min_start_interval_ms_floor =
+                        EXCLUDED.request_min_start_interval_ms_floor,
+                    notify_emails = EXCLUDED.notify_emails,
+                    quota_alert_thresholds = EXCLUDED.quota_alert_thresholds,
+                    quota_alert_level = EXCLUDED.quota_alert_level,
+                    updated_at_ms = EXCLUDED.updated_at_ms",
+                &[
+                    &key_id,
+                    &settings.caps.quota_billable_cap.map(|value| value as i64),
+                    &settings
+                        .caps
+                        .request_max_concurrency_cap
+                        .map(|value| value as i64),
+                    &settings
+                        .caps
+                        .request_min_start_interval_ms_floor
+                        .map(|value| value as i64),
+                    &notify_emails,
+                    &thresholds,
+                    &settings.quota_alert_level.map(i16::from),
+                    &settings.updated_at_ms,
+                ],
+            )
+            .await
+            .context("upsert postgres key portal settings")?;
+        self.invalidate_key_portal_caches(&bundle).await?;
+        Ok(settings)
+    }
+
+    async fn rotate_key_secret(
+        &self,
+        key_id: &str,
+        rotation: KeySecretRotation,
+    ) -> anyhow::Result<Option<AdminKey>> {
+        let sealed_secret = self
+            .secrets
+            .seal(SecretRow::new("llm_keys", key_id), &rotation.secret)
+            .context("seal rotated llm key secret")?;
+        self.ensure_connection_alive()?;
+        // `SET previous_key_hash = k.key_hash` reads the pre-update row, so the
+        // current secret becomes the grace-window secret and whatever secret
+        // was still in an earlier grace window is dropped.
+        let row = self
+            .client
+            .query_opt(
+                "UPDATE llm_keys k
+                 SET secret = $2,
+                    key_hash = $3,
+                    previous_key_hash = k.key_hash,
+                    previous_key_hash_expires_at_ms = $4,
+                    updated_at_ms = $5
+                 FROM (
+                    SELECT key_id, previous_key_hash AS dropped_key_hash
+                    FROM llm_keys
+                    WHERE key_id = $1
+                    FOR UPDATE
+                 ) old
+                 WHERE k.key_id = old.key_id
+                 RETURNING old.dropped_key_hash",
+                &[
+                    &key_id,
+                    &sealed_secret,
+                    &rotation.key_hash,
+                    &rotation.previous_secret_expires_at_ms,
+                    &rotation.rotated_at_ms,
+                ],
+            )
+            .await
+            .context("rotate postgres llm key secret")?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        if let (Some(cache), Some(dropped_key_hash)) =
+            (self.request_cache.as_ref(), row.get::<_, Option<String>>(0))
+        {
+            if let Err(err) = cache.delete(&cache.auth_key(&dropped_key_hash)).await {
+                tracing::warn!(error = %err, "failed to invalidate dropped key secret auth cache");
+            }
+        }
+        let Some(bundle) = self.load_key_bundle_by_id(key_id).await? else {
+            return Ok(None);
+        };
+        self.invalidate_key_portal_caches(&bundle).await?;
+        Ok(Some(admin_key_from_bundle(&bundle)))
+    }
+
+    async fn request_key_notify_email_confirmation(
+        &self,
+        confirmation: NewKeyNotifyEmailConfirmation,
+    ) -> anyhow::Result<bool> {
+        self.ensure_connection_alive()?;
+        let live_after_ms = confirmation
+            .requested_at_ms
+            .saturating_sub((KEY_NOTIFY_EMAIL_CONFIRMATION_TTL_SECONDS * 1_000) as i64);
+        // A confirmed address or a still-valid link leaves the row untouched,
+        // so re-saving the list cannot re-mail the same address.
+        let updated = self
+            .client
+            .execute(
+                "INSERT INTO llm_key_notify_email_confirmations (
+                    key_id, email, token_hash, requested_at_ms, confirmed_at_ms
+                 ) VALUES ($1, $2, $3, $4, NULL)
+                 ON CONFLICT(key_id, email) DO UPDATE SET
+                    token_hash = EXCLUDED.token_hash,
+                    requested_at_ms = EXCLUDED.requested_at_ms
+                 WHERE llm_key_notify_email_confirmations.confirmed_at_ms IS NULL
+                   AND llm_key_notify_email_confirmations.requested_at_ms <= $5",
+                &[
+                    &confirmation.key_id,
+                    &confirmation.email,
+                    &confirmation.token_hash,
+                    &confirmation.requested_at_ms,
+                    &live_after_ms,
+                ],
+            )
+            .await
+            .context("upsert postgres notify email confirmation")?;
+        Ok(updated > 0)
+    }
+
+    async fn confirm_key_notify_email(
+        &self,
+        token_hash: &str,
+        confirmed_at_ms: i64,
+    ) -> anyhow::Result<Option<ConfirmedKeyNotifyEmail>> {
+        self.ensure_connection_alive()?;
+        let live_after_ms = confirmed_at_ms
+            .saturating_sub((KEY_NOTIFY_EMAIL_CONFIRMATION_TTL_SECONDS * 1_000) as i64);
+        let row = self
+            .client
+            .query_opt(
+                "UPDATE llm_key_notify_email_confirmations
+                 SET confirmed_at_ms = $2, token_hash = NULL
+                 WHERE token_hash = $1
+                   AND confirmed_at_ms IS NULL
+                   AND requested_at_ms > $3
+                 RETURNING key_id, email",
+                &[&token_hash, &confirmed_at_ms, &live_after_ms],
+            )
+            .await
+            .context("confirm postgres notify email")?;
+        Ok(row.map(|row| ConfirmedKeyNotifyEmail {
+            key_id: row.get(0),
+            email: row.get(1),
+        }))
+    }
+
+    async fn list_key_quota_alert_candidates(&self) -> anyhow::Result<Vec<KeyQuotaAlertCandidate>> {
+        self.ensure_connection_alive()?;
+        let rows = self
+            .client
+            .query(
+                "SELECT
+                    k.key_id,
+                    k.name,
+                    LEAST(
+                        k.quota_billable_limit,
+                        COALESCE(s.quota_billable_cap, k.quota_billable_limit)
+                    ),
+                    COALESCE(u.billable_tokens, 0),
+                    confirmed.emails::text,
+                    s.quota_alert_thresholds::text,
+                    s.quota_alert_level
+                 FROM llm_key_portal_settings s
+                 JOIN llm_keys k ON k.key_id = s.key_id
+                 LEFT JOIN llm_key_usage_rollups u ON u.key_id = k.key_id
+                 CROSS JOIN LATERAL (
+                    SELECT jsonb_agg(c.email ORDER BY c.email) AS emails
+                    FROM llm_key_notify_email_confirmations c
+                    WHERE c.key_id = s.key_id
+                      AND c.confirmed_at_ms IS NOT NULL
+                      AND s.notify_emails @> jsonb_build_array(c.email)
+                 ) confirmed
+                 WHERE k.status = $1
+                   AND confirmed.emails IS NOT NULL
+                   AND jsonb_array_length(s.quota_alert_thresholds) > 0
+                 ORDER BY k.key_id ASC",
+                &[&core_store::KEY_STATUS_ACTIVE],
+            )
+            .await
+            .context("list postgres key quota alert candidates")?;
+        Ok(rows
+            .into_iter()
+            .map(|row| KeyQuotaAlertCandidate {
+                key_id: row.get(0),
+                key_name: row.get(1),
+                quota_billable_limit: non_negative_i64_to_u64(row.get(2)).unwrap_or(0),
+                billable_tokens_used: non_negative_i64_to_u64(row.get(3)).unwrap_or(0),
+                notify_emails: decode_json_list(&row.get::<_, String>(4)),
+                quota_alert_thresholds: decode_json_list(&row.get::<_, String>(5)),
+                quota_alert_level: decode_alert_level(row.get(6)),
+            })
+            .collect())
+    }
+
+    async fn set_key_quota_alert_level(
+        &self,
+        key_id: &str,
+        expected: Option<u8>,
+        level: Option<u8>,
+    ) -> anyhow::Result<bool> {
+        self.ensure_connection_alive()?;
+        let updated = self
+            .client
+            .execute(
+                "UPDATE llm_key_portal_settings
+                 SET quota_alert_level = $3
+                 WHERE key_id = $1
+                   AND quota_alert_level IS NOT DISTINCT FROM $2",
+                &[&key_id, &expected.map(i16::from), &level.map(i16::from)],
+            )
+            .await
+            .context("update postgres key quota alert level")?;
+        Ok(updated > 0)
+    }
+}
+
+fn decode_key_portal_caps(row: &PgRow, start: usize) -> KeyPortalCaps {
+    KeyPortalCaps {
+        quota_billable_cap: row
+            .get::<_, Option<i64>>(start)
+            .and_then(non_negative_i64_to_u64),
+        request_max_concurrency_cap: row
+            .get::<_, Option<i64>>(start + 1)
+            .and_then(non_negative_i64_to_u64),
+        request_min_start_interval_ms_floor: row
+            .get::<_, Option<i64>>(start + 2)
+            .and_then(non_negative_i64_to_u64),
+    }
+}
+
+fn decode_json_list<T: serde::de::DeserializeOwned>(raw: &str) -> Vec<T> {
+    serde_json::from_str(raw).unwrap_or_default()
+}
+
+fn decode_alert_level(value: Option<i16>) -> Option<u8> {
+    value.and_then(|value| u8::try_from(value).ok())
+}
@@ -8,19 +8,31 @@ use llm_access_core::store::{
     self as core_store, AdminKey, AdminKeyPageQuery, AdminKeyPatch, AdminKeySortMode,
     AdminKeyStore, AdminKeysPage, AdminPageRequest, AuthenticatedKey, NewAdminKey,
 };
+use sqlx_core::query::query_with;
+use sqlx_postgres::PgConnection;
 
 use super::{
+    build_pg_arguments,
     decode::{admin_key_from_bundle, decode_key_bundle_row, decode_kiro_admin_key_row},
-    PostgresControlRepository, SqlxClient,
+    PostgresControlRepository,
+};
+use crate::{
+    records::{KeyBundle, KeyRecord, KeyRouteConfig, KeyUsageRollup},
+    secrets::SecretRow,
 };
-use crate::records::{KeyBundle, KeyRecord, KeyRouteConfig, KeyUsageRollup};
 
 impl PostgresControlRepository {
+    /// Key whose current or grace-window secret hashes to `key_hash`, with
+    /// the grace window's expiry when the match came through the rotated-out
+    /// secret.
     pub(super) async fn load_authenticated_key_by_hash(
         &self,
         key_hash: &str,
-    ) -> anyhow::Result<Option<AuthenticatedKey>> {
+    ) -> anyhow::Result<Option<(AuthenticatedKey, Option<i64>)>> {
         self.ensure_connection_alive()?;
+        // A rotated-out secret keeps matching `previous_key_hash` until its
+        // grace window ends. Holder quota caps tighten both the key and the
+        // parent budget it draws from.
         let row = self
             .client
             .query_opt(
@@ -29,45 +41,95 @@ impl PostgresControlRepository {
                     k.name,
                     k.provider_type,
                     k.protocol_family,
-                    k.status,
-                    k.quota_billable_limit,
-                    COALESCE(u.billable_tokens, 0)
+                    CASE WHEN p.status = 'disabled' THEN p.status ELSE k.status END,
+                    LEAST(
+                        k.quota_billable_limit,
+                        COALESCE(s.quota_billable_cap, k.quota_billable_limit)
+                    ),
+                    COALESCE(u.billable_tokens, 0),
+                    LEAST(
+                        p.quota_billable_limit,
+                        COALESCE(ps.quota_billable_cap, p.quota_billable_limit)
+                    ) - COALESCE(pu.billable_tokens, 0),
+                    k.parent_key_id,
+                    CASE
+                        WHEN k.key_hash = $1 THEN NULL
+                        ELSE k.previous_key_hash_expires_at_ms
+                    END
                  FROM llm_keys k
                  LEFT JOIN llm_key_usage_rollups u ON u.key_id = k.key_id
-                 WHERE k.key_hash = $1",
-                &[&key_hash],
+                 LEFT JOIN llm_key_portal_settings s ON s.key_id = k.key_id
+                 LEFT JOIN llm_keys p ON p.key_id = k.parent_key_id
+                 LEFT JOIN llm_key_usage_rollups pu ON pu.key_id = p.key_id
+                 LEFT JOIN llm_key_portal_settings ps ON ps.key_id = p.key_id
+                 WHERE k.key_hash = $1
+                    OR (k.previous_key_hash = $1 AND k.previous_key_hash_expires_at_ms > $2)",
+                &[&key_hash, &super::now_ms()],
             )
             .await
             .context("load authenticated key by hash")?;
-        Ok(row.map(|row| AuthenticatedKey {
-            key_id: row.get(0),
-            key_name: row.get(1),
-            provider_type: row.get(2),
-            protocol_family: row.get(3),
-            status: row.get(4),
-            quota_billable_limit: row.get(5),
-            billable_tokens_used: row.get::<_, i64>(6),
+        Ok(row.map(|row| {
+            let key = AuthenticatedKey {
+                key_id: row.get(0),
+                key_name: row.get(1),
+                provider_type: row.get(2),
+                protocol_family: row.get(3),
+                status: row.get(4),
+                quota_billable_limit: row.get(5),
+                billable_tokens_used: row.get::<_, i64>(6),
+                parent_remaining_billable: row.get::<_, Option<i64>>(7),
+                parent_key_id: row.get::<_, Option<String>>(8),
+            };
+            (key, row.get::<_, Option<i64>>(9))
         }))
     }
 
+    /// Current and still-present previous secret hashes of the given keys,
+    /// for auth-cache invalidation.
     pub(super) async fn load_key_hashes_by_ids(
         &self,
         key_ids: &[String],
-    ) -> anyhow::Result<BTreeMap<String, String>> {
+    ) -> anyhow::Result<Vec<String>> {
         if key_ids.is_empty() {
-            return Ok(BTreeMap::new());
+            return Ok(Vec::new());
         }
         self.ensure_connection_alive()?;
         let rows = self
             .client
             .query(
-                "SELECT key_id, key_hash
+                "SELECT key_hash, previous_key_hash
                  FROM llm_keys
                  WHERE key_id = ANY($1)",
                 &[&key_ids],
             )
             .await
             .context("load key hashes by ids")?;
+        Ok(rows
+            .into_iter()
+            .flat_map(|row| {
+                std::iter::once(row.get::<_, String>(0)).chain(row.get::<_, Option<String>>(1))
+            })
+            .collect())
+    }
+
+    pub(super) async fn load_parent_key_ids(
+        &self,
+        key_ids: &[String],
+    ) -> anyhow::Result<BTreeMap<String, String>> {
+        if key_ids.is_empty() {
+            return Ok(BTreeMap::new());
+        }
+        self.ensure_connection_alive()?;
+        let rows = self
+            .client
+            .query(
+                "SELECT key_id, parent_key_id
+                 FROM llm_keys
+                 WHERE key_id = ANY($1) AND parent_key_id IS NOT NULL",
+                &[&key_ids],
+            )
+            .await
+            .context("load parent key ids")?;
         Ok(rows
             .into_iter()
             .map(|row| (row.get::<_, String>(0), row.get::<_, String>(1)))
@@ -117,8 +179,19 @@ impl PostgresControlRepository {
                         AS kiro_cctest_text_handling_enabled,
                     r.kiro_anthropic_upstream_pool_mode
                         AS kiro_anthropic_upstream_pool_mode,
+                    r.codex_openai_upstream_pool_mode
+                        AS codex_openai_upstream_pool_mode,
                     r.kiro_model_group_preferences_json::text
-                        AS kiro_model_group_preferences_json
+                        AS kiro_model_group_preferences_json,
+                    r.response_cache_policy_json::text
+                        AS response_cache_policy_json,
+                    r.request_transform_policy_json::text
+                        AS request_transform_policy_json,
+                    r.moderation_policy_json::text
+                        AS moderation_policy_json,
+                    r.queue_policy_json::text
+                        AS queue_policy_json,
+                    k.parent_key_id AS parent_key_id
                  FROM llm_keys k
                  LEFT JOIN llm_key_route_config r ON r.key_id = k.key_id
                  LEFT JOIN llm_key_usage_rollups u ON u.key_id = k.key_id
@@ -127,7 +200,8 @@ impl PostgresControlRepository {
             )
             .await
             .context("load key bundle by id")?;
-        row.map(decode_key_bundle_row).transpose()
+        row.map(|row| decode_key_bundle_row(row, &self.secrets))
+            .transpose()
     }
 
     async fn list_key_bundles(&self) -> anyhow::Result<Vec<KeyBundle>> {
@@ -170,8 +244,19 @@ impl PostgresControlRepository {
                         AS kiro_cctest_text_handling_enabled,
                     r.kiro_anthropic_upstream_pool_mode
                         AS kiro_anthropic_upstream_pool_mode,
+                    r.codex_openai_upstream_pool_mode
+                        AS codex_openai_upstream_pool_mode,
                     r.kiro_model_group_preferences_json::text
-                        AS kiro_model_group_preferences_json
+                        AS kiro_model_group_preferences_json,
+                    r.response_cache_policy_json::text
+                        AS response_cache_policy_json,
+                    r.request_transform_policy_json::text
+                        AS request_transform_policy_json,
+                    r.moderation_policy_json::text
+                        AS moderation_policy_json,
+                    r.queue_policy_json::text
+                        AS queue_policy_json,
+                    k.parent_key_id AS parent_key_id
                  FROM llm_keys k
                  LEFT JOIN llm_key_route_config r ON r.key_id = k.key_id
                  LEFT JOIN llm_key_usage_rollups u ON u.key_id = k.key_id
@@ -181,7 +266,7 @@ impl PostgresControlRepository {
             .await
             .context("list key bundles")?;
         rows.into_iter()
-            .map(decode_key_bundle_row)
+            .map(|row| decode_key_bundle_row(row, &self.secrets))
             .collect::<anyhow::Result<Vec<_>>>()
     }
 
@@ -299,8 +384,19 @@ impl PostgresControlRepository {
                         AS kiro_cctest_text_handling_enabled,
                     r.kiro_anthropic_upstream_pool_mode
                         AS kiro_anthropic_upstream_pool_mode,
+                    r.codex_openai_upstream_pool_mode
+                        AS codex_openai_upstream_pool_mode,
                     r.kiro_model_group_preferences_json::text
-                        AS kiro_model_group_preferences_json
+                        AS kiro_model_group_preferences_json,
+                    r.response_cache_policy_json::text
+                        AS response_cache_policy_json,
+                    r.request_transform_policy_json::text
+                        AS request_transform_policy_json,
+                    r.moderation_policy_json::text
+                        AS moderation_policy_json,
+                    r.queue_policy_json::text
+                        AS queue_policy_json,
+                    k.parent_key_id AS parent_key_id
                  FROM llm_keys k
                  LEFT JOIN llm_key_route_config r ON r.key_id = k.key_id
                  LEFT JOIN llm_key_usage_rollups u ON u.key_id = k.key_id
@@ -313,7 +409,7 @@ impl PostgresControlRepository {
             .context("list postgres key bundles page")?;
         let keys = rows
             .into_iter()
-            .map(decode_key_bundle_row)
+            .map(|row| decode_key_bundle_row(row, &self.secrets))
             .map(|bundle| bundle.map(|bundle| admin_key_from_bundle(&bundle)))
             .collect::<anyhow::Result<Vec<_>>>()?;
         let summary = self.admin_keys_summary(provider_type).await?;
@@ -382,7 +478,16 @@ impl PostgresControlRepository {
                             NULLIF(r.kiro_anthropic_upstream_pool_mode, ''),
                             'disabled'
                         ) AS kiro_anthropic_upstream_pool_mode,
+                        COALESCE(
+                            NULLIF(r.codex_openai_upstream_pool_mode, ''),
+                            'disabled'
+                        ) AS codex_openai_upstream_pool_mode,
                         r.kiro_model_group_preferences_json,
+                        r.response_cache_policy_json,
+                        r.request_transform_policy_json,
+                        r.moderation_policy_json,
+                        r.queue_policy_json,
+                        k.parent_key_id,
                         g.account_names_json AS group_account_names_json,
                         COALESCE(NULLIF(r.route_strategy, ''), 'auto') AS route_strategy_norm
                     FROM llm_keys k
@@ -575,8 +680,19 @@ impl PostgresControlRepository {
                         AS kiro_cctest_text_handling_enabled,
                     page_keys.kiro_anthropic_upstream_pool_mode
                         AS kiro_anthropic_upstream_pool_mode,
+                    page_keys.codex_openai_upstream_pool_mode
+                        AS codex_openai_upstream_pool_mode,
                     page_keys.kiro_model_group_preferences_json::text
-                        AS kiro_model_group_preferences_json
+                        AS kiro_model_group_preferences_json,
+                    page_keys.response_cache_policy_json::text
+                        AS response_cache_policy_json,
+                    page_keys.request_transform_policy_json::text
+                        AS request_transform_policy_json,
+                    page_keys.moderation_policy_json::text
+                        AS moderation_policy_json,
+                    page_keys.queue_policy_json::text
+                        AS queue_policy_json,
+                    page_keys.parent_key_id AS parent_key_id
                  FROM page_keys
                  LEFT JOIN key_candidate_summary summary
                    ON summary.key_id = page_keys.key_id
@@ -587,7 +703,7 @@ impl PostgresControlRepository {
             .context("list postgres kiro key bundles page with candidate summaries")?;
         let keys = rows
             .into_iter()
-            .map(decode_kiro_admin_key_row)
+            .map(|row| decode_kiro_admin_key_row(row, &self.secrets))
             .collect::<anyhow::Result<Vec<_>>>()?;
         Ok(AdminKeysPage {
             has_more: page.has_more(keys.len(), total),
@@ -643,8 +759,19 @@ impl PostgresControlRepository {
                         AS kiro_cctest_text_handling_enabled,
                     r.kiro_anthropic_upstream_pool_mode
                         AS kiro_anthropic_upstream_pool_mode,
+                    r.codex_openai_upstream_pool_mode
+                        AS codex_openai_upstream_pool_mode,
                     r.kiro_model_group_preferences_json::text
-                        AS kiro_model_group_preferences_json
+                        AS kiro_model_group_preferences_json,
+                    r.response_cache_policy_json::text
+                        AS response_cache_policy_json,
+                    r.request_transform_policy_json::text
+                        AS request_transform_policy_json,
+                    r.moderation_policy_json::text
+                        AS moderation_policy_json,
+                    r.queue_policy_json::text
+                        AS queue_policy_json,
+                    k.parent_key_id AS parent_key_id
                  FROM llm_keys k
                  JOIN llm_key_route_config r ON r.key_id = k.key_id
                  LEFT JOIN llm_key_usage_rollups u ON u.key_id = k.key_id
@@ -668,179 +795,224 @@ impl PostgresControlRepository {
             )
             .await
             .context("find postgres key referencing account group")?;
-        row.map(decode_key_bundle_row)
+        row.map(|row| decode_key_bundle_row(row, &self.secrets))
             .transpose()
             .map(|bundle| bundle.map(|bundle| admin_key_from_bundle(&bundle)))
     }
 
-    async fn upsert_key_bundle_client(
-        client: &SqlxClient,
+    pub(super) async fn upsert_key_bundle(
+        &self,
         key: &KeyRecord,
         route: &KeyRouteConfig,
         rollup: &KeyUsageRollup,
     ) -> anyhow::Result<()> {
-        client
-            .execute(
-                "INSERT INTO llm_keys (
-                    key_id, name, secret, key_hash, status, provider_type, protocol_family,
-                    public_visible, quota_billable_limit, created_at_ms, updated_at_ms
-                 ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
-                 ON CONFLICT(key_id) DO UPDATE SET
-                    name = EXCLUDED.name,
-                    secret = EXCLUDED.secret,
-                    key_hash = EXCLUDED.key_hash,
-                    status = EXCLUDED.status,
-                    provider_type = EXCLUDED.provider_type,
-                    protocol_family = EXCLUDED.protocol_family,
-                    public_visible = EXCLUDED.public_visible,
-                    quota_billable_limit = EXCLUDED.quota_billable_limit,
-                    created_at_ms = EXCLUDED.created_at_ms,
-                    updated_at_ms = EXCLUDED.updated_at_ms",
-                &[
-                    &key.key_id,
-                    &key.name,
-                    &key.secret,
-                    &key.key_hash,
-                    &key.status,
-                    &key.provider_type,
-                    &key.protocol_family,
-                    &key.public_visible,
-                    &key.quota_billable_limit,
-                    &key.created_at_ms,
-                    &key.updated_at_ms,
-                ],
-            )
-            .await
-            .context("upsert postgres llm key")?;
-        client
-            .execute(
-                "INSERT INTO llm_key_route_config (
-                    key_id, route_strategy, fixed_account_name, auto_account_names_json,
-                    account_group_id, preferred_pool_strategy, model_name_map_json,
-                    request_max_concurrency,
-                    request_min_start_interval_ms, codex_fast_enabled,
-                    codex_strict_session_rejection_enabled,
-                    codex_image_generation_enabled,
-                    codex_image_direct_generation_enabled,
-                    kiro_request_validation_enabled, kiro_cache_estimation_enabled,
-                    kiro_zero_cache_debug_enabled, kiro_full_request_logging_enabled,
-                    kiro_remote_media_resolution_enabled, kiro_latency_routing_enabled,
-                    kiro_protected_content_validation_enabled,
-                    kiro_cctest_text_handling_enabled,
-                    kiro_cache_policy_override_json,
-                    kiro_billable_model_multipliers_override_json,
-                    kiro_anthropic_upstream_pool_mode,
-                    kiro_model_group_preferences_json
-                 ) VALUES (
-                    $1, $2, $3, $4::jsonb, $5, $6, $7::jsonb, $8, $9, $10, $11, $12,
-                    $13, $14, $15, $16, $17, $18, $19, $20, $21, $22::jsonb, $23::jsonb,
-                    $24, COALESCE($25::jsonb, '{}'::jsonb)
-                 )
-                 ON CONFLICT(key_id) DO UPDATE SET
-                    route_strategy = EXCLUDED.route_strategy,
-                    fixed_account_name = EXCLUDED.fixed_account_name,
-                    auto_account_names_json = EXCLUDED.auto_account_names_json,
-                    account_group_id = EXCLUDED.account_group_id,
-                    preferred_pool_strategy = EXCLUDED.preferred_pool_strategy,
-                    model_name_map_json = EXCLUDED.model_name_map_json,
-                    request_max_concurrency = EXCLUDED.request_max_concurrency,
-                    request_min_start_interval_ms = EXCLUDED.request_min_start_interval_ms,
-                    codex_fast_enabled = EXCLUDED.codex_fast_enabled,
-                    codex_strict_session_rejection_enabled =
-                        EXCLUDED.codex_strict_session_rejection_enabled,
-                    codex_image_generation_enabled =
-                        EXCLUDED.codex_image_generation_enabled,
-                    codex_image_direct_generation_enabled =
-                        EXCLUDED.codex_image_direct_generation_enabled,
-                    kiro_request_validation_enabled = EXCLUDED.kiro_request_validation_enabled,
-                    kiro_cache_estimation_enabled = EXCLUDED.kiro_cache_estimation_enabled,
-                    kiro_zero_cache_debug_enabled = EXCLUDED.kiro_zero_cache_debug_enabled,
-                    kiro_full_request_logging_enabled =
-                        EXCLUDED.kiro_full_request_logging_enabled,
-                    kiro_remote_media_resolution_enabled =
-                        EXCLUDED.kiro_remote_media_resolution_enabled,
-                    kiro_latency_routing_enabled =
-                        EXCLUDED.kiro_latency_routing_enabled,
-                    kiro_protected_content_validation_enabled =
-                        EXCLUDED.kiro_protected_content_validation_enabled,
-                    kiro_cctest_text_handling_enabled =
-                        EXCLUDED.kiro_cctest_text_handling_enabled,
-                    kiro_cache_policy_override_json =
-                        EXCLUDED.kiro_cache_policy_override_json,
-                    kiro_billable_model_multipliers_override_json =
-                        EXCLUDED.kiro_billable_model_multipliers_override_json,
-                    kiro_anthropic_upstream_pool_mode =
-                        EXCLUDED.kiro_anthropic_upstream_pool_mode,
-                    kiro_model_group_preferences_json =
-                        EXCLUDED.kiro_model_group_preferences_json",
-                &[
-                    &route.key_id,
-                    &route.route_strategy,
-                    &route.fixed_account_name,
-                    &route.auto_account_names_json,
-                    &route.account_group_id,
-                    &route.preferred_pool_strategy,
-                    &route.model_name_map_json,
-                    &route.request_max_concurrency,
-                    &route.request_min_start_interval_ms,
-                    &route.codex_fast_enabled,
-                    &route.codex_strict_session_rejection_enabled,
-                    &route.codex_image_generation_enabled,
-                    &route.codex_image_direct_generation_enabled,
-                    &route.kiro_request_validation_enabled,
-                    &route.kiro_cache_estimation_enabled,
-                    &route.kiro_zero_cache_debug_enabled,
-                    &route.kiro_full_request_logging_enabled,
-                    &route.kiro_remote_media_resolution_enabled,
-                    &route.kiro_latency_routing_enabled,
-                    &route.kiro_protected_content_validation_enabled,
-                    &route.kiro_cctest_text_handling_enabled,
-                    &route.kiro_cache_policy_override_json,
-                    &route.kiro_billable_model_multipliers_override_json,
-                    &route.kiro_anthropic_upstream_pool_mode,
-                    &route.kiro_model_group_preferences_json,
-                ],
-            )
+        let mut tx = self
+            .client
+            .pool
+            .begin()
             .await
-            .context("upsert postgres key route config")?;
-        client
-            .execute(
-                "INSERT INTO llm_key_usage_rollups (
-                    key_id, input_uncached_tokens, input_cached_tokens, output_tokens,
-                    billable_tokens, credit_total, credit_missing_events,
-                    codex_image_usage_tokens, codex_image_usage_missing_events,
-                    codex_image_last_used_at_ms, last_used_at_ms, updated_at_ms
-                 ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
-                 ON CONFLICT(key_id) DO UPDATE SET
-                    input_uncached_tokens = EXCLUDED.input_uncached_tokens,
-                    input_cached_tokens = EXCLUDED.input_cached_tokens,
-                    output_tokens = EXCLUDED.output_tokens,
-                    billable_tokens = EXCLUDED.billable_tokens,
-                    credit_total = EXCLUDED.credit_total,
-                    credit_missing_events = EXCLUDED.credit_missing_events,
-                    codex_image_usage_tokens = EXCLUDED.codex_image_usage_tokens,
-                    codex_image_usage_missing_events =
-                        EXCLUDED.codex_image_usage_missing_events,
-                    codex_image_last_used_at_ms = EXCLUDED.codex_image_last_used_at_ms,
-                    last_used_at_ms = EXCLUDED.last_used_at_ms,
-                    updated_at_ms = EXCLUDED.updated_at_ms",
-                &[
-                    &rollup.key_id,
-                    &rollup.input_uncached_tokens,
-                    &rollup.input_cached_tokens,
-                    &rollup.output_tokens,
-                    &rollup.billable_tokens,
-                    &rollup.credit_total.to_string(),
-                    &rollup.credit_missing_events,
-                    &rollup.codex_image_usage_tokens,
-                    &rollup.codex_image_usage_missing_events,
-                    &rollup.codex_image_last_used_at_ms,
-                    &rollup.last_used_at_ms,
-                    &rollup.updated_at_ms,
-                ],
-            )
+            .context("begin postgres key bundle transaction")?;
+        self.upsert_key_bundle_on(&mut tx, key, route, rollup)
+            .await?;
+        tx.commit()
             .await
-            .context("upsert postgres key usage rollup")?;
+            .context("commit postgres key bundle transaction")
+    }
+
+    /// Writes the key, route and rollup rows on `conn`, so a caller's open
+    /// transaction covers them.
+    pub(super) async fn upsert_key_bundle_on(
+        &self,
+        conn: &mut PgConnection,
+        key: &KeyRecord,
+        route: &KeyRouteConfig,
+        rollup: &KeyUsageRollup,
+    ) -> anyhow::Result<()> {
+        let sealed_secret = self
+            .secrets
+            .seal(SecretRow::new("llm_keys", &key.key_id), &key.secret)
+            .context("seal llm key secret")?;
+        query_with(
+            "INSERT INTO llm_keys (
+                key_id, name, secret, key_hash, status, provider_type, protocol_family,
+                public_visible, quota_billable_limit, created_at_ms, updated_at_ms,
+                parent_key_id
+             ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+             ON CONFLICT(key_id) DO UPDATE SET
+                name = EXCLUDED.name,
+                secret = EXCLUDED.secret,
+                key_hash = EXCLUDED.key_hash,
+                status = EXCLUDED.status,
+                provider_type = EXCLUDED.provider_type,
+                protocol_family = EXCLUDED.protocol_family,
+                public_visible = EXCLUDED.public_visible,
+                quota_billable_limit = EXCLUDED.quota_billable_limit,
+                created_at_ms = EXCLUDED.created_at_ms,
+                updated_at_ms = EXCLUDED.updated_at_ms",
+            build_pg_arguments(&[
+                &key.key_id,
+                &key.name,
+                &sealed_secret,
+                &key.key_hash,
+                &key.status,
+                &key.provider_type,
+                &key.protocol_family,
+                &key.public_visible,
+                &key.quota_billable_limit,
+                &key.created_at_ms,
+                &key.updated_at_ms,
+                &key.parent_key_id,
+            ])?,
+        )
+        .execute(&mut *conn)
+        .await
+        .context("upsert postgres llm key")?;
+        query_with(
+            "INSERT INTO llm_key_route_config (
+                key_id, route_strategy, fixed_account_name, auto_account_names_json,
+                account_group_id, preferred_pool_strategy, model_name_map_json,
+                request_max_concurrency,
+                request_min_start_interval_ms, codex_fast_enabled,
+                codex_strict_session_rejection_enabled,
+                codex_image_generation_enabled,
+                codex_image_direct_generation_enabled,
+                kiro_request_validation_enabled, kiro_cache_estimation_enabled,
+                kiro_zero_cache_debug_enabled, kiro_full_request_logging_enabled,
+                kiro_remote_media_resolution_enabled, kiro_latency_routing_enabled,
+                kiro_protected_content_validation_enabled,
+                kiro_cctest_text_handling_enabled,
+                kiro_cache_policy_override_json,
+                kiro_billable_model_multipliers_override_json,
+                kiro_anthropic_upstream_pool_mode,
+                kiro_model_group_preferences_json,
+                response_cache_policy_json,
+                request_transform_policy_json,
+                moderation_policy_json,
+                queue_policy_json,
+                codex_openai_upstream_pool_mode
+             ) VALUES (
+                $1, $2, $3, $4::jsonb, $5, $6, $7::jsonb, $8, $9, $10, $11, $12,
+                $13, $14, $15, $16, $17, $18, $19, $20, $21, $22::jsonb, $23::jsonb,
+                $24, COALESCE($25::jsonb, '{}'::jsonb), $26::jsonb, $27::jsonb, $28::jsonb,
+                $29::jsonb, $30
+             )
+             ON CONFLICT(key_id) DO UPDATE SET
+                route_strategy = EXCLUDED.route_strategy,
+                fixed_account_name = EXCLUDED.fixed_account_name,
+                auto_account_names_json = EXCLUDED.auto_account_names_json,
+                account_group_id = EXCLUDED.account_group_id,
+                preferred_pool_strategy = EXCLUDED.preferred_pool_strategy,
+                model_name_map_json = EXCLUDED.model_name_map_json,
+                request_max_concurrency = EXCLUDED.request_max_concurrency,
+                request_min_start_interval_ms = EXCLUDED.request_min_start_interval_ms,
+                codex_fast_enabled = EXCLUDED.codex_fast_enabled,
+                codex_strict_session_rejection_enabled =
+                    EXCLUDED.codex_strict_session_rejection_enabled,
+                codex_image_generation_enabled =
+                    EXCLUDED.codex_image_generation_enabled,
+                codex_image_direct_generation_enabled =
+                    EXCLUDED.codex_image_direct_generation_enabled,
+                kiro_request_validation_enabled = EXCLUDED.kiro_request_validation_enabled,
+                kiro_cache_estimation_enabled = EXCLUDED.kiro_cache_estimation_enabled,
+                kiro_zero_cache_debug_enabled = EXCLUDED.kiro_zero_cache_debug_enabled,
+                kiro_full_request_logging_enabled =
+                    EXCLUDED.kiro_full_request_logging_enabled,
+                kiro_remote_media_resolution_enabled =
+                    EXCLUDED.kiro_remote_media_resolution_enabled,
+                kiro_latency_routing_enabled =
+                    EXCLUDED.kiro_latency_routing_enabled,
+                kiro_protected_content_validation_enabled =
+                    EXCLUDED.kiro_protected_content_validation_enabled,
+                kiro_cctest_text_handling_enabled =
+                    EXCLUDED.kiro_cctest_text_handling_enabled,
+                kiro_cache_policy_override_json =
+                    EXCLUDED.kiro_cache_policy_override_json,
+                kiro_billable_model_multipliers_override_json =
+                    EXCLUDED.kiro_billable_model_multipliers_override_json,
+                kiro_anthropic_upstream_pool_mode =
+                    EXCLUDED.kiro_anthropic_upstream_pool_mode,
+                kiro_model_group_preferences_json =
+                    EXCLUDED.kiro_model_group_preferences_json,
+                response_cache_policy_json = EXCLUDED.response_cache_policy_json,
+                request_transform_policy_json = EXCLUDED.request_transform_policy_json,
+                moderation_policy_json = EXCLUDED.moderation_policy_json,
+                queue_policy_json = EXCLUDED.queue_policy_json,
+                codex_openai_upstream_pool_mode =
+                    EXCLUDED.codex_openai_upstream_pool_mode",
+            build_pg_arguments(&[
+                &route.key_id,
+                &route.route_strategy,
+                &route.fixed_account_name,
+                &route.auto_account_names_json,
+                &route.account_group_id,
+                &route.preferred_pool_strategy,
+                &route.model_name_map_json,
+                &route.request_max_concurrency,
+                &route.request_min_start_interval_ms,
+                &route.codex_fast_enabled,
+                &route.codex_strict_session_rejection_enabled,
+                &route.codex_image_generation_enabled,
+                &route.codex_image_direct_generation_enabled,
+                &route.kiro_request_validation_enabled,
+                &route.kiro_cache_estimation_enabled,
+                &route.kiro_zero_cache_debug_enabled,
+                &route.kiro_full_request_logging_enabled,
+                &route.kiro_remote_media_resolution_enabled,
+                &route.kiro_latency_routing_enabled,
+                &route.kiro_protected_content_validation_enabled,
+                &route.kiro_cctest_text_handling_enabled,
+                &route.kiro_cache_policy_override_json,
+                &route.kiro_billable_model_multipliers_override_json,
+                &route.kiro_anthropic_upstream_pool_mode,
+                &route.kiro_model_group_preferences_json,
+                &route.response_cache_policy_json,
+                &route.request_transform_policy_json,
+                &route.moderation_policy_json,
+                &route.queue_policy_json,
+                &route.codex_openai_upstream_pool_mode,
+            ])?,
+        )
+        .execute(&mut *conn)
+        .await
+        .context("upsert postgres key route config")?;
+        query_with(
+            "INSERT INTO llm_key_usage_rollups (
+                key_id, input_uncached_tokens, input_cached_tokens, output_tokens,
+                billable_tokens, credit_total, credit_missing_events,
+                codex_image_usage_tokens, codex_image_usage_missing_events,
+                codex_image_last_used_at_ms, last_used_at_ms, updated_at_ms
+             ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+             ON CONFLICT(key_id) DO UPDATE SET
+                input_uncached_tokens = EXCLUDED.input_uncached_tokens,
+                input_cached_tokens = EXCLUDED.input_cached_tokens,
+                output_tokens = EXCLUDED.output_tokens,
+                billable_tokens = EXCLUDED.billable_tokens,
+                credit_total = EXCLUDED.credit_total,
+                credit_missing_events = EXCLUDED.credit_missing_events,
+                codex_image_usage_tokens = EXCLUDED.codex_image_usage_tokens,
+                codex_image_usage_missing_events =
+                    EXCLUDED.codex_image_usage_missing_events,
+                codex_image_last_used_at_ms = EXCLUDED.codex_image_last_used_at_ms,
+                last_used_at_ms = EXCLUDED.last_used_at_ms,
+                updated_at_ms = EXCLUDED.updated_at_ms",
+            build_pg_arguments(&[
+                &rollup.key_id,
+                &rollup.input_uncached_tokens,
+                &rollup.input_cached_tokens,
+                &rollup.output_tokens,
+                &rollup.billable_tokens,
+                &rollup.credit_total.to_string(),
+                &rollup.credit_missing_events,
+                &rollup.codex_image_usage_tokens,
+                &rollup.codex_image_usage_missing_events,
+                &rollup.codex_image_last_used_at_ms,
+                &rollup.last_used_at_ms,
+                &rollup.updated_at_ms,
+            ])?,
+        )
+        .execute(&mut *conn)
+        .await
+        .context("upsert postgres key usage rollup")?;
         Ok(())
     }
 
@@ -979,8 +1151,19 @@ impl AdminKeyStore for PostgresControlRepository {
                     AS kiro_cctest_text_handling_enabled,
                 r.kiro_anthropic_upstream_pool_mode
                     AS kiro_anthropic_upstream_pool_mode,
+                r.codex_openai_upstream_pool_mode
+                    AS codex_openai_upstream_pool_mode,
                 r.kiro_model_group_preferences_json::text
-                    AS kiro_model_group_preferences_json
+                    AS kiro_model_group_preferences_json,
+                r.response_cache_policy_json::text
+                    AS response_cache_policy_json,
+                r.request_transform_policy_json::text
+                    AS request_transform_policy_json,
+                r.moderation_policy_json::text
+                    AS moderation_policy_json,
+                r.queue_policy_json::text
+                    AS queue_policy_json,
+                k.parent_key_id AS parent_key_id
              FROM llm_keys k
              LEFT JOIN llm_key_route_config r ON r.key_id = k.key_id
              LEFT JOIN llm_key_usage_rollups u ON u.key_id = k.key_id
@@ -1007,7 +1190,7 @@ impl AdminKeyStore for PostgresControlRepository {
             .context("list postgres filtered key bundles page")?;
         let keys = rows
             .into_iter()
-            .map(decode_key_bundle_row)
+            .map(|row| decode_key_bundle_row(row, &self.secrets))
             .map(|bundle| bundle.map(|bundle| admin_key_from_bundle(&bundle)))
             .collect::<anyhow::Result<Vec<_>>>()?;
         let summary = self.admin_keys_summary(provider_type).await?;
@@ -1041,6 +1224,7 @@ impl AdminKeyStore for PostgresControlRepository {
             protocol_family: key.protocol_family.clone(),
             public_visible: key.public_visible,
             quota_billable_limit: key.quota_billable_limit as i64,
+            parent_key_id: None,
             created_at_ms: key.created_at_ms,
             updated_at_ms: key.created_at_ms,
         };
@@ -1072,6 +1256,11 @@ impl AdminKeyStore for PostgresControlRepository {
             kiro_cache_policy_override_json: None,
             kiro_billable_model_multipliers_override_json: None,
             kiro_anthropic_upstream_pool_mode: core_store::default_anthropic_upstream_pool_mode(),
+            codex_openai_upstream_pool_mode: core_store::default_openai_upstream_pool_mode(),
+            response_cache_policy_json: None,
+            request_transform_policy_json: None,
+            moderation_policy_json: None,
+            queue_policy_json: None,
         };
         let rollup = KeyUsageRollup {
             key_id: key.id.clone(),
@@ -1087,7 +1276,7 @@ impl AdminKeyStore for PostgresControlRepository {
             last_used_at_ms: None,
             updated_at_ms: key.created_at_ms,
         };
-        Self::upsert_key_bundle_client(&self.client, &key_record, &route, &rollup).await?;
+        self.upsert_key_bundle(&key_record, &route, &rollup).await?;
         self.bump_dispatch_generation(&key.provider_type).await;
         self.load_key_bundle_by_id(&key.id)
             .await?
@@ -1195,17 +1384,51 @@ impl AdminKeyStore for PostgresControlRepository {
         if let Some(value) = patch.kiro_anthropic_upstream_pool_mode.as_ref() {
             bundle.route.kiro_anthropic_upstream_pool_mode = value.clone();
         }
+        if let Some(value) = patch.codex_openai_upstream_pool_mode.as_ref() {
+            bundle.route.codex_openai_upstream_pool_mode = value.clone();
+        }
         if let Some(value) = patch.kiro_cache_policy_override_json.as_ref() {
             bundle.route.kiro_cache_policy_override_json = value.clone();
         }
         if let Some(value) = patch.kiro_billable_model_multipliers_override_json.as_ref() {
             bundle.route.kiro_billable_model_multipliers_override_json = value.clone();
         }
+        if let Some(value) = patch.response_cache_policy.as_ref() {
+            bundle.route.response_cache_policy_json = value
+                .as_ref()
+                .map(serde_json::to_string)
+                .transpose()
+                .context("serialize postgres response cache policy")?;
+        }
+        if let Some(value) = patch.request_transform_policy.as_ref() {
+            bundle.route.request_transform_policy_json = value
+                .as_ref()
+                .map(serde_json::to_string)
+                .transpose()
+                .context("serialize postgres request transform policy")?;
+        }
+        if let Some(value) = patch.moderation_policy.as_ref() {
+            bundle.route.moderation_policy_json = value
+                .as_ref()
+                .map(serde_json::to_string)
+                .transpose()
+                .context("serialize postgres moderation policy")?;
+        }
+        if let Some(value) = patch.queue_policy.as_ref() {
+            bundle.route.queue_policy_json = value
+                .as_ref()
+                .map(serde_json::to_string)
+                .transpose()
+                .context("serialize postgres queue policy")?;
+        }
         bundle.key.updated_at_ms = patch.updated_at_ms;
         bundle.rollup.updated_at_ms = bundle.rollup.updated_at_ms.max(patch.updated_at_ms);
-        Self::upsert_key_bundle_client(&self.client, &bundle.key, &bundle.route, &bundle.rollup)
+        self.upsert_key_bundle(&bundle.key, &bundle.route, &bundle.rollup)
             .await?;
-        self.invalidate_authenticated_key_cache_by_ids(std::slice::from_ref(&bundle.key.key_id))
+        // Sub-keys inherit the parent's status and budget at auth time.
+        let mut key_ids = self.load_sub_key_ids(&bundle.key.key_id).await?;
+        key_ids.push(bundle.key.key_id.clone());
+        self.invalidate_authenticated_key_cache_by_ids(&key_ids)
             .await;
         self.invalidate_request_snapshot_cache(&bundle.key.provider_type, &bundle.key.key_id)
             .await;
@@ -1218,12 +1441,17 @@ impl AdminKeyStore for PostgresControlRepository {
         let Some(bundle) = self.load_key_bundle_by_id(key_id).await? else {
             return Ok(None);
         };
+        // Sub-keys are removed by the foreign-key cascade, so their hashes
+        // must be read before the delete to invalidate the auth cache.
+        let mut key_ids = self.load_sub_key_ids(key_id).await?;
+        key_ids.push(bundle.key.key_id.clone());
+        let key_hashes = self.load_key_hashes_by_ids(&key_ids).await?;
         self.ensure_connection_alive()?;
         self.client
             .execute("DELETE FROM llm_keys WHERE key_id = $1", &[&key_id])
             .await
             .context("delete postgres admin key")?;
-        self.invalidate_authenticated_key_cache_by_ids(std::slice::from_ref(&bundle.key.key_id))
+        self.invalidate_authenticated_key_cache_by_hashes(&key_hashes)
             .await;
         self.invalidate_request_snapshot_cache(&bundle.key.provider_type, &bundle.key.key_id)
             .await;
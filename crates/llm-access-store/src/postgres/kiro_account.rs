@@ -24,10 +24,12 @@ use super::{
         set_json_optional_string, set_json_optional_u64,
     },
     normalize_manual_usage_limit,
-    proxy_support::resolve_provider_proxy_config_from_context,
+    proxy_support::{
+        account_proxy_view, resolve_provider_proxy_config_from_context, AccountProxyRef,
+    },
     KiroAdminAccountListRow, KiroAdminAccountViewContext, PostgresControlRepository,
 };
-use crate::records::KiroAccountRecord;
+use crate::{records::KiroAccountRecord, secrets::SecretRow};
 
 fn kiro_manual_usage_limit_from_auth_json(auth: &serde_json::Value) -> Option<f64> {
     optional_json_f64_any(auth, &["manualUsageLimit", "manual_usage_limit"])
@@ -57,7 +59,9 @@ impl PostgresControlRepository {
             )
             .await
             .context("list kiro accounts")?;
-        Ok(rows.into_iter().map(decode_kiro_account_row).collect())
+        rows.into_iter()
+            .map(|row| decode_kiro_account_row(row, &self.secrets))
+            .collect()
     }
 
     async fn list_kiro_admin_account_rows(&self) -> anyhow::Result<Vec<KiroAdminAccountListRow>> {
@@ -220,6 +224,15 @@ impl PostgresControlRepository {
                         ),
                         ''
                     ) AS auth_proxy_config_id,
+                    NULLIF(
+                        BTRIM(
+                            COALESCE(
+                                auth_json ->> 'proxyPoolId',
+                                auth_json ->> 'proxy_pool_id'
+                            )
+                        ),
+                        ''
+                    ) AS proxy_pool_id,
                     NULLIF(
                         BTRIM(
                             COALESCE(
@@ -489,6 +502,15 @@ impl PostgresControlRepository {
                         ),
                         ''
                     ) AS auth_proxy_config_id,
+                    NULLIF(
+                        BTRIM(
+                            COALESCE(
+                                auth_json ->> 'proxyPoolId',
+                                auth_json ->> 'proxy_pool_id'
+                            )
+                        ),
+                        ''
+                    ) AS proxy_pool_id,
                     NULLIF(
                         BTRIM(
                             COALESCE(
@@ -668,6 +690,15 @@ impl PostgresControlRepository {
                         ),
                         ''
                     ) AS auth_proxy_config_id,
+                    NULLIF(
+                        BTRIM(
+                            COALESCE(
+                                auth_json ->> 'proxyPoolId',
+                                auth_json ->> 'proxy_pool_id'
+                            )
+                        ),
+                        ''
+                    ) AS proxy_pool_id,
                     NULLIF(
                         BTRIM(
                             COALESCE(
@@ -956,6 +987,15 @@ impl PostgresControlRepository {
                         ),
                         ''
                     ) AS auth_proxy_config_id,
+                    NULLIF(
+                        BTRIM(
+                            COALESCE(
+                                a.auth_json ->> 'proxyPoolId',
+                                a.auth_json ->> 'proxy_pool_id'
+                            )
+                        ),
+                        ''
+                    ) AS proxy_pool_id,
                     NULLIF(
                         BTRIM(
                             COALESCE(
@@ -1017,7 +1057,8 @@ impl PostgresControlRepository {
             )
             .await
             .context("load kiro account")?;
-        Ok(row.map(decode_kiro_account_row))
+        row.map(|row| decode_kiro_account_row(row, &self.secrets))
+            .transpose()
     }
 
     pub(super) async fn load_kiro_admin_account_view_context(
@@ -1042,45 +1083,29 @@ impl PostgresControlRepository {
         let kiro_proxy_binding = self
             .load_admin_proxy_binding_from_configs(core_store::PROVIDER_KIRO, &proxy_configs_by_id)
             .await?;
+        let proxy_pools = self
+            .load_proxy_pool_context(core_store::PROVIDER_KIRO)
+            .await?;
         Ok(KiroAdminAccountViewContext {
             default_cache,
             status_by_account,
             proxy_configs_by_id,
             kiro_proxy_binding,
+            proxy_pools,
         })
     }
 
     pub(super) fn resolve_kiro_account_proxy_view_with_context(
         &self,
-        proxy_mode: &str,
-        proxy_config_id: Option<&str>,
+        reference: AccountProxyRef<'_>,
         context: &KiroAdminAccountViewContext,
     ) -> (String, Option<String>, Option<String>) {
-        match proxy_mode {
-            "none" => ("none".to_string(), None, None),
-            "fixed" => {
-                let Some(proxy_id) = proxy_config_id else {
-                    return ("invalid".to_string(), None, None);
-                };
-                match context.proxy_configs_by_id.get(proxy_id) {
-                    Some(proxy) if proxy.status == core_store::KEY_STATUS_ACTIVE => (
-                        "fixed".to_string(),
-                        Some(proxy.proxy_url.clone()),
-                        Some(proxy.name.clone()),
-                    ),
-                    Some(proxy) => ("invalid".to_string(), None, Some(proxy.name.clone())),
-                    None => ("invalid".to_string(), None, None),
-                }
-            },
-            _ => (
-                context.kiro_proxy_binding.effective_source.clone(),
-                context.kiro_proxy_binding.effective_proxy_url.clone(),
-                context
-                    .kiro_proxy_binding
-                    .effective_proxy_config_name
-                    .clone(),
-            ),
-        }
+        account_proxy_view(
+            reference,
+            &context.proxy_configs_by_id,
+            &context.kiro_proxy_binding,
+            &context.proxy_pools,
+        )
     }
 
     fn admin_kiro_account_from_list_row_with_context(
@@ -1111,8 +1136,12 @@ impl PostgresControlRepository {
             .or_else(|| row.auth_proxy_config_id.clone());
         let (effective_proxy_source, effective_proxy_url, effective_proxy_config_name) = self
             .resolve_kiro_account_proxy_view_with_context(
-                &proxy_mode,
-                proxy_config_id.as_deref(),
+                AccountProxyRef {
+                    proxy_mode: &proxy_mode,
+                    proxy_config_id: proxy_config_id.as_deref(),
+                    proxy_pool_id: row.proxy_pool_id.as_deref(),
+                    sticky_key: &row.account_name,
+                },
                 context,
             );
         let disabled = row.disabled_json || row.status != core_store::KEY_STATUS_ACTIVE;
@@ -1191,6 +1220,7 @@ impl PostgresControlRepository {
             pool_strategy: row.pool_strategy.clone(),
             proxy_mode,
             proxy_config_id,
+            proxy_pool_id: row.proxy_pool_id.clone(),
             effective_proxy_source,
             effective_proxy_url,
             effective_proxy_config_name,
@@ -1224,10 +1254,15 @@ impl PostgresControlRepository {
             .proxy_config_id
             .clone()
             .or_else(|| optional_json_string_any(&auth, &["proxyConfigId", "proxy_config_id"]));
+        let proxy_pool_id = optional_json_string_any(&auth, &["proxyPoolId", "proxy_pool_id"]);
         let (effective_proxy_source, effective_proxy_url, effective_proxy_config_name) = self
             .resolve_kiro_account_proxy_view_with_context(
-                &proxy_mode,
-                proxy_config_id.as_deref(),
+                AccountProxyRef {
+                    proxy_mode: &proxy_mode,
+                    proxy_config_id: proxy_config_id.as_deref(),
+                    proxy_pool_id: proxy_pool_id.as_deref(),
+                    sticky_key: &record.account_name,
+                },
                 context,
             );
         let disabled_json = optional_json_bool_any(&auth, &["disabled"]).unwrap_or(false);
@@ -1319,6 +1354,7 @@ impl PostgresControlRepository {
             pool_strategy,
             proxy_mode,
             proxy_config_id,
+            proxy_pool_id,
             effective_proxy_source,
             effective_proxy_url,
             effective_proxy_config_name,
@@ -1340,6 +1376,13 @@ impl PostgresControlRepository {
         &self,
         record: &KiroAccountRecord,
     ) -> anyhow::Result<()> {
+        let sealed_auth_json = self
+            .secrets
+            .seal_auth_json(
+                SecretRow::new("llm_kiro_accounts", &record.account_name),
+                &record.auth_json,
+            )
+            .context("seal kiro account auth json")?;
         self.ensure_connection_alive()?;
         self.client
             .execute(
@@ -1372,7 +1415,7 @@ impl PostgresControlRepository {
                     &record.profile_arn,
                     &record.user_id,
                     &record.status,
-                    &record.auth_json,
+                    &sealed_auth_json,
                     &record.max_concurrency,
                     &record.min_start_interval_ms,
                     &record.proxy_config_id,
@@ -1606,6 +1649,9 @@ impl AdminKiroAccountStore for PostgresControlRepository {
             record.proxy_config_id = proxy_config_id.clone();
             set_json_optional_string(object, "proxyConfigId", proxy_config_id.clone());
         }
+        if let Some(proxy_pool_id) = patch.proxy_pool_id.as_ref() {
+            set_json_optional_string(object, "proxyPoolId", proxy_pool_id.clone());
+        }
         record.auth_json =
             serde_json::to_string(&auth_value).context("serialize postgres kiro auth json")?;
         record.updated_at_ms = patch.updated_at_ms;
@@ -1720,12 +1766,17 @@ impl AdminKiroAccountStore for PostgresControlRepository {
         let proxy_config_id = record.proxy_config_id.clone().or_else(|| {
             optional_json_string_any(&auth_json, &["proxyConfigId", "proxy_config_id"])
         });
+        let proxy_pool_id = optional_json_string_any(&auth_json, &["proxyPoolId", "proxy_pool_id"]);
         let proxy_context = self
             .load_provider_proxy_resolution_context(core_store::PROVIDER_KIRO)
             .await?;
         let proxy = resolve_provider_proxy_config_from_context(
-            &proxy_mode,
-            proxy_config_id.as_deref(),
+            AccountProxyRef {
+                proxy_mode: &proxy_mode,
+                proxy_config_id: proxy_config_id.as_deref(),
+                proxy_pool_id: proxy_pool_id.as_deref(),
+                sticky_key: &record.account_name,
+            },
             &proxy_context,
         )?;
         Ok(Some(ProviderKiroRoute {
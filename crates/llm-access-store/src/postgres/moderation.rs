@@ -0,0 +1,188 @@
+//! Moderation review queue rows: inserts from the data plane plus the paged
+//! admin listing and review update used by the `AdminReviewQueueStore` impl.
+
+use anyhow::Context;
+use llm_access_core::store::{
+    AdminModerationEvent, AdminModerationEventsPage, AdminReviewQueueAction, AdminReviewQueueQuery,
+    NewModerationEvent,
+};
+
+use super::{json::decode_optional_json, PgRow, PostgresControlRepository};
+
+const MODERATION_EVENT_COLUMNS: &str =
+    "event_id, key_id, key_name, provider_type, endpoint, model, direction, action, \
+     matched_rules_json::text, classifier_labels_json::text, classifier_score, excerpt, \
+     client_ip, status, admin_note, created_at_ms, reviewed_at_ms";
+
+fn decode_moderation_event_row(row: PgRow) -> AdminModerationEvent {
+    AdminModerationEvent {
+        event_id: row.get(0),
+        key_id: row.get(1),
+        key_name: row.get(2),
+        provider_type: row.get(3),
+        endpoint: row.get(4),
+        model: row.get(5),
+        direction: row.get(6),
+        action: row.get(7),
+        matched_rules: decode_optional_json(row.get::<_, Option<String>>(8).as_deref())
+            .unwrap_or_default(),
+        classifier_labels: decode_optional_json(row.get::<_, Option<String>>(9).as_deref())
+            .unwrap_or_default(),
+        classifier_score: row.get(10),
+        excerpt: row.get(11),
+        client_ip: row.get(12),
+        status: row.get(13),
+        admin_note: row.get(14),
+        created_at: row.get(15),
+        reviewed_at: row.get(16),
+    }
+}
+
+impl PostgresControlRepository {
+    pub(super) async fn insert_moderation_event(
+        &self,
+        event: &NewModerationEvent,
+    ) -> anyhow::Result<()> {
+        self.ensure_connection_alive()?;
+        let matched_rules_json = serde_json::to_string(&event.matched_rules)
+            .context("serialize moderation matched rules")?;
+        let classifier_labels_json = serde_json::to_string(&event.classifier_labels)
+            .context("serialize moderation classifier labels")?;
+        self.client
+            .execute(
+                "INSERT INTO llm_moderation_events (
+                    event_id, key_id, key_name, provider_type, endpoint, model, direction,
+                    action, matched_rules_json, classifier_labels_json, classifier_score,
+                    excerpt, client_ip, status, created_at_ms
+                 ) VALUES (
+                    $1, $2, $3, $4, $5, $6, $7, $8, $9::jsonb, $10::jsonb, $11, $12, $13,
+                    'pending', $14
+                 )
+                 ON CONFLICT(event_id) DO NOTHING",
+                &[
+                    &event.event_id,
+                    &event.key_id,
+                    &event.key_name,
+                    &event.provider_type,
+                    &event.endpoint,
+                    &event.model,
+                    &event.direction,
+                    &event.action,
+                    &matched_rules_json,
+                    &classifier_labels_json,
+                    &event.classifier_score,
+                    &event.excerpt,
+                    &event.client_ip,
+                    &event.created_at_ms,
+                ],
+            )
+            .await
+            .context("insert postgres moderation event")?;
+        Ok(())
+    }
+
+    pub(super) async fn get_moderation_event_row(
+        &self,
+        event_id: &str,
+    ) -> anyhow::Result<Option<AdminModerationEvent>> {
+        self.ensure_connection_alive()?;
+        let row = self
+            .client
+            .query_opt(
+                &format!(
+                    "SELECT {MODERATION_EVENT_COLUMNS} FROM llm_moderation_events WHERE event_id \
+                     = $1"
+                ),
+                &[&event_id],
+            )
+            .await
+            .context("load postgres moderation event")?;
+        Ok(row.map(decode_moderation_event_row))
+    }
+
+    pub(super) async fn list_moderation_event_rows(
+        &self,
+        query: AdminReviewQueueQuery,
+    ) -> anyhow::Result<AdminModerationEventsPage> {
+        let total = self
+            .count_rows(
+                "SELECT COUNT(*) FROM llm_moderation_events",
+                "SELECT COUNT(*) FROM llm_moderation_events WHERE status = $1",
+                query.status.as_deref(),
+            )
+            .await?;
+        if total == 0 || query.offset >= total {
+            return Ok(AdminModerationEventsPage {
+                total,
+                offset: query.offset,
+                limit: query.limit,
+                has_more: false,
+                events: Vec::new(),
+            });
+        }
+        let rows = if let Some(status) = query.status.as_deref() {
+            self.client
+                .query(
+                    &format!(
+                        "SELECT {MODERATION_EVENT_COLUMNS}
+                         FROM llm_moderation_events
+                         WHERE status = $1
+                         ORDER BY created_at_ms DESC, event_id DESC
+                         LIMIT $2 OFFSET $3"
+                    ),
+                    &[&status, &(query.limit as i64), &(query.offset as i64)],
+                )
+                .await
+                .context("list moderation events by status")?
+        } else {
+            self.client
+                .query(
+                    &format!(
+                        "SELECT {MODERATION_EVENT_COLUMNS}
+                         FROM llm_moderation_events
+                         ORDER BY created_at_ms DESC, event_id DESC
+                         LIMIT $1 OFFSET $2"
+                    ),
+                    &[&(query.limit as i64), &(query.offset as i64)],
+                )
+                .await
+                .context("list moderation events")?
+        };
+        let events = rows
+            .into_iter()
+            .map(decode_moderation_event_row)
+            .collect::<Vec<_>>();
+        Ok(AdminModerationEventsPage {
+            total,
+            offset: query.offset,
+            limit: query.limit,
+            has_more: query.offset.saturating_add(events.len()) < total,
+            events,
+        })
+    }
+
+    pub(super) async fn review_moderation_event_row(
+        &self,
+        event_id: &str,
+        status: &str,
+        action: AdminReviewQueueAction,
+    ) -> anyhow::Result<Option<AdminModerationEvent>> {
+        self.ensure_connection_alive()?;
+        let updated = self
+            .client
+            .execute(
+                "UPDATE llm_moderation_events
+                 SET status = $2,
+                     admin_note = $3,
+                     reviewed_at_ms = $4
+                 WHERE event_id = $1",
+                &[&event_id, &status, &action.admin_note, &action.updated_at_ms],
+            )
+            .await
+            .context("review postgres moderation event")?;
+        if updated == 0 {
+            return Ok(None);
+        }
+        self.get_moderation_event_row(event_id).await
+    }
+}
@@ -0,0 +1,540 @@
+//! OpenAI-compatible upstream channel store and lightweight usage rollups.
+
+use anyhow::Context;
+use async_trait::async_trait;
+use llm_access_core::store::{
+    self as core_store, AdminOpenAiUpstreamChannel, AdminOpenAiUpstreamChannelPatch,
+    AdminOpenAiUpstreamModelsStatusUpdate, AdminOpenAiUpstreamProbeTarget,
+    AdminOpenAiUpstreamStore, AdminOpenAiUpstreamUsageRollup, NewAdminOpenAiUpstreamChannel,
+    OpenAiUpstreamChannelUsageDelta,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    now_ms,
+    proxy_support::{resolve_provider_proxy_config_from_context, AccountProxyRef},
+    OpenAiUpstreamChannelRow, PostgresControlRepository,
+};
+use crate::secrets::{SecretKeyring, SecretRow};
+
+const OPENAI_UPSTREAM_CHANNEL_COLUMNS: &str = "c.channel_name,
+    c.status,
+    c.base_url,
+    NULLIF(c.auth_json ->> 'api_key', ''),
+    c.weight,
+    c.max_concurrency,
+    c.min_start_interval_ms,
+    c.proxy_mode,
+    c.proxy_config_id,
+    c.last_error,
+    c.model_ids::text,
+    c.last_models_status,
+    c.last_models_latency_ms,
+    c.last_models_checked_at_ms,
+    c.last_models_error,
+    c.created_at_ms,
+    c.updated_at_ms,
+    COALESCE(u.input_uncached_tokens, 0),
+    COALESCE(u.input_cached_tokens, 0),
+    COALESCE(u.output_tokens, 0),
+    COALESCE(u.billable_tokens, 0),
+    COALESCE(u.usage_missing_events, 0),
+    u.last_used_at_ms";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedOpenAiUpstreamChannelsLookup {
+    generation: i64,
+    rows: Vec<OpenAiUpstreamChannelRow>,
+}
+
+fn non_negative_i64_to_u64(value: i64) -> u64 {
+    value.max(0) as u64
+}
+
+fn optional_non_negative_i64_to_u64(value: Option<i64>) -> Option<u64> {
+    value.map(non_negative_i64_to_u64)
+}
+
+fn model_ids_from_json_text(channel_name: &str, raw: &str) -> Vec<String> {
+    match serde_json::from_str::<Vec<String>>(raw) {
+        Ok(model_ids) => model_ids,
+        Err(err) => {
+            tracing::warn!(
+                channel = %channel_name,
+                error = %err,
+                "stored OpenAI upstream model_ids JSON is not a string array"
+            );
+            Vec::new()
+        },
+    }
+}
+
+pub(super) const OPENAI_UPSTREAM_CHANNELS_TABLE: &str = "llm_openai_upstream_channels";
+
+fn auth_json_for_api_key(
+    secrets: &SecretKeyring,
+    channel_name: &str,
+    api_key: &str,
+) -> anyhow::Result<String> {
+    let auth_json = serde_json::to_string(&serde_json::json!({ "api_key": api_key }))
+        .context("serialize openai upstream auth json")?;
+    secrets
+        .seal_auth_json(SecretRow::new(OPENAI_UPSTREAM_CHANNELS_TABLE, channel_name), &auth_json)
+        .context("seal openai upstream auth json")
+}
+
+fn admin_channel_from_row(row: OpenAiUpstreamChannelRow) -> AdminOpenAiUpstreamChannel {
+    AdminOpenAiUpstreamChannel {
+        name: row.channel_name,
+        status: row.status,
+        base_url: row.base_url,
+        has_api_key: row
+            .api_key
+            .as_deref()
+            .is_some_and(|value| !value.is_empty()),
+        weight: non_negative_i64_to_u64(row.weight),
+        max_concurrency: non_negative_i64_to_u64(row.max_concurrency),
+        min_start_interval_ms: non_negative_i64_to_u64(row.min_start_interval_ms),
+        proxy_mode: row.proxy_mode,
+        proxy_config_id: row.proxy_config_id,
+        last_error: row.last_error,
+        models: row.model_ids,
+        last_models_status: row.last_models_status,
+        last_models_latency_ms: optional_non_negative_i64_to_u64(row.last_models_latency_ms),
+        last_models_checked_at: row.last_models_checked_at_ms,
+        last_models_error: row.last_models_error,
+        usage: AdminOpenAiUpstreamUsageRollup {
+            input_uncached_tokens: non_negative_i64_to_u64(row.input_uncached_tokens),
+            input_cached_tokens: non_negative_i64_to_u64(row.input_cached_tokens),
+            output_tokens: non_negative_i64_to_u64(row.output_tokens),
+            billable_tokens: non_negative_i64_to_u64(row.billable_tokens),
+            usage_missing_events: non_negative_i64_to_u64(row.usage_missing_events),
+            last_used_at: row.last_used_at_ms,
+        },
+        created_at: row.created_at_ms,
+        updated_at: row.updated_at_ms,
+    }
+}
+
+impl PostgresControlRepository {
+    fn decode_openai_upstream_channel_row(
+        &self,
+        row: super::PgRow,
+    ) -> anyhow::Result<OpenAiUpstreamChannelRow> {
+        let channel_name: String = row.get(0);
+        let api_key: Option<String> = row.get(3);
+        let api_key = self
+            .secrets
+            .open_optional(
+                SecretRow::new(OPENAI_UPSTREAM_CHANNELS_TABLE, &channel_name),
+                api_key.as_deref(),
+            )
+            .with_context(|| format!("open api key of openai upstream `{channel_name}`"))?;
+        let model_ids = model_ids_from_json_text(&channel_name, row.get::<_, String>(10).as_str());
+        Ok(OpenAiUpstreamChannelRow {
+            channel_name,
+            status: row.get(1),
+            base_url: row.get(2),
+            api_key,
+            weight: row.get(4),
+            max_concurrency: row.get(5),
+            min_start_interval_ms: row.get(6),
+            proxy_mode: row.get(7),
+            proxy_config_id: row.get(8),
+            last_error: row.get(9),
+            model_ids,
+            last_models_status: row.get(11),
+            last_models_latency_ms: row.get(12),
+            last_models_checked_at_ms: row.get(13),
+            last_models_error: row.get(14),
+            created_at_ms: row.get(15),
+            updated_at_ms: row.get(16),
+            input_uncached_tokens: row.get(17),
+            input_cached_tokens: row.get(18),
+            output_tokens: row.get(19),
+            billable_tokens: row.get(20),
+            usage_missing_events: row.get(21),
+            last_used_at_ms: row.get(22),
+        })
+    }
+
+    pub(super) async fn list_openai_upstream_channel_rows(
+        &self,
+        active_only: bool,
+    ) -> anyhow::Result<Vec<OpenAiUpstreamChannelRow>> {
+        self.ensure_connection_alive()?;
+        let status_filter = if active_only { "WHERE c.status = 'active'" } else { "" };
+        let sql = format!(
+            "SELECT {OPENAI_UPSTREAM_CHANNEL_COLUMNS}
+             FROM llm_openai_upstream_channels c
+             LEFT JOIN llm_openai_upstream_channel_usage_rollups u
+                ON u.channel_name = c.channel_name
+             {status_filter}
+             ORDER BY c.status ASC, c.weight DESC, c.channel_name ASC"
+        );
+        let rows = self
+            .client
+            .query(&sql, &[])
+            .await
+            .context("list postgres openai upstream channels")?;
+        rows.into_iter()
+            .map(|row| self.decode_openai_upstream_channel_row(row))
+            .collect()
+    }
+
+    pub(super) async fn load_active_openai_upstream_channel_rows_cached(
+        &self,
+    ) -> anyhow::Result<Vec<OpenAiUpstreamChannelRow>> {
+        let Some(cache) = self.request_cache.as_ref() else {
+            return self.list_openai_upstream_channel_rows(true).await;
+        };
+        let generation = self
+            .current_dispatch_generation(core_store::PROVIDER_CODEX)
+            .await;
+        let scope = self.proxy_scope.cache_key_segment();
+        let cache_key = cache.openai_upstream_channels_key(scope);
+        match cache
+            .get_json::<CachedOpenAiUpstreamChannelsLookup>(&cache_key)
+            .await
+        {
+            Ok(Some(lookup)) if lookup.generation == generation => return Ok(lookup.rows),
+            Ok(Some(_)) => {},
+            Ok(None) => {},
+            Err(err) => {
+                tracing::warn!(
+                    key = %cache_key,
+                    error = %err,
+                    "request cache openai upstream channel read failed; falling back to postgres"
+                );
+            },
+        }
+        let rows = self.list_openai_upstream_channel_rows(true).await?;
+        let lookup = CachedOpenAiUpstreamChannelsLookup {
+            generation,
+            rows: rows.clone(),
+        };
+        if let Err(err) = cache
+            .set_json(&cache_key, &lookup, cache.openai_upstream_channels_ttl(scope))
+            .await
+        {
+            tracing::warn!(
+                key = %cache_key,
+                error = %err,
+                "request cache openai upstream channel write failed"
+            );
+        }
+        Ok(rows)
+    }
+
+    async fn load_openai_upstream_channel_row(
+        &self,
+        channel_name: &str,
+    ) -> anyhow::Result<Option<OpenAiUpstreamChannelRow>> {
+        self.ensure_connection_alive()?;
+        let sql = format!(
+            "SELECT {OPENAI_UPSTREAM_CHANNEL_COLUMNS}
+             FROM llm_openai_upstream_channels c
+             LEFT JOIN llm_openai_upstream_channel_usage_rollups u
+                ON u.channel_name = c.channel_name
+             WHERE c.channel_name = $1"
+        );
+        self.client
+            .query_opt(&sql, &[&channel_name])
+            .await
+            .context("load postgres openai upstream channel")?
+            .map(|row| self.decode_openai_upstream_channel_row(row))
+            .transpose()
+    }
+
+    pub(crate) async fn record_openai_upstream_channel_usage(
+        &self,
+        channel_name: &str,
+        delta: OpenAiUpstreamChannelUsageDelta,
+    ) -> anyhow::Result<()> {
+        self.ensure_connection_alive()?;
+        let input_uncached_tokens = delta.input_uncached_tokens.min(i64::MAX as u64) as i64;
+        let input_cached_tokens = delta.input_cached_tokens.min(i64::MAX as u64) as i64;
+        let output_tokens = delta.output_tokens.min(i64::MAX as u64) as i64;
+        let billable_tokens = delta.billable_tokens.min(i64::MAX as u64) as i64;
+        let usage_missing_events = if delta.usage_missing { 1_i64 } else { 0_i64 };
+        let updated_at_ms = now_ms().max(delta.used_at_ms);
+        self.client
+            .execute(
+                "INSERT INTO llm_openai_upstream_channel_usage_rollups (
+                    channel_name,
+                    input_uncached_tokens,
+                    input_cached_tokens,
+                    output_tokens,
+                    billable_tokens,
+                    usage_missing_events,
+                    last_used_at_ms,
+                    updated_at_ms
+                 )
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                 ON CONFLICT (channel_name) DO UPDATE SET
+                    input_uncached_tokens =
+                        llm_openai_upstream_channel_usage_rollups.input_uncached_tokens
+                        + EXCLUDED.input_uncached_tokens,
+                    input_cached_tokens =
+                        llm_openai_upstream_channel_usage_rollups.input_cached_tokens
+                        + EXCLUDED.input_cached_tokens,
+                    output_tokens =
+                        llm_openai_upstream_channel_usage_rollups.output_tokens
+                        + EXCLUDED.output_tokens,
+                    billable_tokens =
+                        llm_openai_upstream_channel_usage_rollups.billable_tokens
+                        + EXCLUDED.billable_tokens,
+                    usage_missing_events =
+                        llm_openai_upstream_channel_usage_rollups.usage_missing_events
+                        + EXCLUDED.usage_missing_events,
+                    last_used_at_ms = GREATEST(
+                        COALESCE(llm_openai_upstream_channel_usage_rollups.last_used_at_ms, 0),
+                        EXCLUDED.last_used_at_ms
+                    ),
+                    updated_at_ms = EXCLUDED.updated_at_ms",
+                &[
+                    &channel_name,
+                    &input_uncached_tokens,
+                    &input_cached_tokens,
+                    &output_tokens,
+                    &billable_tokens,
+                    &usage_missing_events,
+                    &delta.used_at_ms,
+                    &updated_at_ms,
+                ],
+            )
+            .await
+            .context("record postgres openai upstream channel usage")?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl AdminOpenAiUpstreamStore for PostgresControlRepository {
+    async fn list_admin_openai_upstream_channels(
+        &self,
+    ) -> anyhow::Result<Vec<AdminOpenAiUpstreamChannel>> {
+        Ok(self
+            .list_openai_upstream_channel_rows(false)
+            .await?
+            .into_iter()
+            .map(admin_channel_from_row)
+            .collect())
+    }
+
+    async fn create_admin_openai_upstream_channel(
+        &self,
+        channel: NewAdminOpenAiUpstreamChannel,
+    ) -> anyhow::Result<AdminOpenAiUpstreamChannel> {
+        self.ensure_connection_alive()?;
+        let auth_json = auth_json_for_api_key(&self.secrets, &channel.name, &channel.api_key)?;
+        let weight = channel.weight.min(i64::MAX as u64) as i64;
+        let max_concurrency = channel.max_concurrency.min(i64::MAX as u64) as i64;
+        let min_start_interval_ms = channel.min_start_interval_ms.min(i64::MAX as u64) as i64;
+        self.client
+            .execute(
+                "INSERT INTO llm_openai_upstream_channels (
+                    channel_name,
+                    status,
+                    base_url,
+                    auth_json,
+                    weight,
+                    max_concurrency,
+                    min_start_interval_ms,
+                    proxy_mode,
+                    proxy_config_id,
+                    created_at_ms,
+                    updated_at_ms
+                 )
+                 VALUES ($1, $2, $3, $4::jsonb, $5, $6, $7, $8, $9, $10, $10)",
+                &[
+                    &channel.name,
+                    &channel.status,
+                    &channel.base_url,
+                    &auth_json,
+                    &weight,
+                    &max_concurrency,
+                    &min_start_interval_ms,
+                    &channel.proxy_mode,
+                    &channel.proxy_config_id,
+                    &channel.created_at_ms,
+                ],
+            )
+            .await
+            .context("create postgres openai upstream channel")?;
+        self.bump_dispatch_generation(core_store::PROVIDER_CODEX)
+            .await;
+        self.load_openai_upstream_channel_row(&channel.name)
+            .await?
+            .map(admin_channel_from_row)
+            .context("created postgres openai upstream channel disappeared")
+    }
+
+    async fn patch_admin_openai_upstream_channel(
+        &self,
+        name: &str,
+        patch: AdminOpenAiUpstreamChannelPatch,
+    ) -> anyhow::Result<Option<AdminOpenAiUpstreamChannel>> {
+        let Some(mut row) = self.load_openai_upstream_channel_row(name).await? else {
+            return Ok(None);
+        };
+        if let Some(value) = patch.status {
+            row.status = value;
+        }
+        if let Some(value) = patch.base_url {
+            row.base_url = value;
+        }
+        if let Some(value) = patch.weight {
+            row.weight = value.min(i64::MAX as u64) as i64;
+        }
+        if let Some(value) = patch.max_concurrency {
+            row.max_concurrency = value.min(i64::MAX as u64) as i64;
+        }
+        if let Some(value) = patch.min_start_interval_ms {
+            row.min_start_interval_ms = value.min(i64::MAX as u64) as i64;
+        }
+        if let Some(value) = patch.proxy_mode {
+            row.proxy_mode = value;
+        }
+        if let Some(value) = patch.proxy_config_id {
+            row.proxy_config_id = value;
+        }
+        if patch.clear_last_error {
+            row.last_error = None;
+        }
+        let auth_json = patch
+            .api_key
+            .as_deref()
+            .map(|api_key| auth_json_for_api_key(&self.secrets, name, api_key))
+            .transpose()?;
+        self.client
+            .execute(
+                "UPDATE llm_openai_upstream_channels
+                 SET status = $2,
+                     base_url = $3,
+                     auth_json = COALESCE($4::jsonb, auth_json),
+                     weight = $5,
+                     max_concurrency = $6,
+                     min_start_interval_ms = $7,
+                     proxy_mode = $8,
+                     proxy_config_id = $9,
+                     last_error = $10,
+                     updated_at_ms = $11
+                 WHERE channel_name = $1",
+                &[
+                    &name,
+                    &row.status,
+                    &row.base_url,
+                    &auth_json,
+                    &row.weight,
+                    &row.max_concurrency,
+                    &row.min_start_interval_ms,
+                    &row.proxy_mode,
+                    &row.proxy_config_id,
+                    &row.last_error,
+                    &patch.updated_at_ms,
+                ],
+            )
+            .await
+            .context("patch postgres openai upstream channel")?;
+        self.bump_dispatch_generation(core_store::PROVIDER_CODEX)
+            .await;
+        self.load_openai_upstream_channel_row(name)
+            .await
+            .map(|row| row.map(admin_channel_from_row))
+    }
+
+    async fn delete_admin_openai_upstream_channel(
+        &self,
+        name: &str,
+    ) -> anyhow::Result<Option<AdminOpenAiUpstreamChannel>> {
+        let Some(row) = self.load_openai_upstream_channel_row(name).await? else {
+            return Ok(None);
+        };
+        self.client
+            .execute("DELETE FROM llm_openai_upstream_channels WHERE channel_name = $1", &[&name])
+            .await
+            .context("delete postgres openai upstream channel")?;
+        self.bump_dispatch_generation(core_store::PROVIDER_CODEX)
+            .await;
+        Ok(Some(admin_channel_from_row(row)))
+    }
+
+    async fn load_admin_openai_upstream_probe_target(
+        &self,
+        name: &str,
+    ) -> anyhow::Result<Option<AdminOpenAiUpstreamProbeTarget>> {
+        let Some(row) = self.load_openai_upstream_channel_row(name).await? else {
+            return Ok(None);
+        };
+        let proxy_context = self
+            .load_provider_proxy_resolution_context(core_store::PROVIDER_CODEX)
+            .await?;
+        let (proxy, proxy_error) = match resolve_provider_proxy_config_from_context(
+            AccountProxyRef {
+                proxy_mode: &row.proxy_mode,
+                proxy_config_id: row.proxy_config_id.as_deref(),
+                proxy_pool_id: None,
+                sticky_key: &row.channel_name,
+            },
+            &proxy_context,
+        ) {
+            Ok(proxy) => (proxy, None),
+            Err(err) => (None, Some(err.to_string())),
+        };
+        Ok(Some(AdminOpenAiUpstreamProbeTarget {
+            name: row.channel_name,
+            base_url: row.base_url,
+            api_key: row.api_key.unwrap_or_default().trim().to_string(),
+            proxy,
+            proxy_error,
+        }))
+    }
+
+    async fn save_admin_openai_upstream_models_status(
+        &self,
+        name: &str,
+        update: AdminOpenAiUpstreamModelsStatusUpdate,
+    ) -> anyhow::Result<Option<AdminOpenAiUpstreamChannel>> {
+        self.ensure_connection_alive()?;
+        let model_ids_json = serde_json::to_string(&update.model_ids)
+            .context("serialize openai upstream model ids")?;
+        let latency_ms = update
+            .latency_ms
+            .map(|value| value.min(i64::MAX as u64) as i64);
+        let updated_at_ms = now_ms().max(update.checked_at_ms);
+        let updated = self
+            .client
+            .execute(
+                "UPDATE llm_openai_upstream_channels
+                 SET model_ids = $2::jsonb,
+                     last_models_status = $3,
+                     last_models_latency_ms = $4,
+                     last_models_checked_at_ms = $5,
+                     last_models_error = $6,
+                     updated_at_ms = $7
+                 WHERE channel_name = $1",
+                &[
+                    &name,
+                    &model_ids_json,
+                    &update.status,
+                    &latency_ms,
+                    &update.checked_at_ms,
+                    &update.error,
+                    &updated_at_ms,
+                ],
+            )
+            .await
+            .context("save postgres openai upstream models status")?;
+        if updated == 0 {
+            return Ok(None);
+        }
+        // Model lists gate channel eligibility, so cached route plans must
+        // pick up the refresh.
+        self.bump_dispatch_generation(core_store::PROVIDER_CODEX)
+            .await;
+        self.load_openai_upstream_channel_row(name)
+            .await
+            .map(|row| row.map(admin_channel_from_row))
+    }
+}
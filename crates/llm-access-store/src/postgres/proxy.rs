@@ -7,8 +7,9 @@ use anyhow::Context;
 use async_trait::async_trait;
 use llm_access_core::store::{
     self as core_store, default_proxy_bindings, AdminLegacyKiroProxyMigration, AdminProxyBinding,
-    AdminProxyConfig, AdminProxyConfigPatch, AdminProxyEndpointCheckUpdate, AdminProxyStore,
-    AdminProxyTrafficSnapshot, NewAdminProxyConfig, ProxyTrafficTotals,
+    AdminProxyConfig, AdminProxyConfigPatch, AdminProxyEndpointCheckUpdate, AdminProxyPool,
+    AdminProxyPoolPatch, AdminProxyStore, AdminProxyTrafficSnapshot, NewAdminProxyConfig,
+    NewAdminProxyPool, ProxyTrafficTotals,
 };
 
 use super::{
@@ -22,7 +23,15 @@ use super::{
     PostgresControlRepository, ProviderProxyResolutionContext, ProxyConfigNodeOverride,
     ProxyEndpointCheckRow,
 };
-use crate::records::KiroAccountRecord;
+use crate::{records::KiroAccountRecord, secrets::SecretRow};
+
+pub(super) const PROXY_OVERRIDE_SECRET_TABLE: &str = "llm_proxy_config_node_overrides";
+
+/// Secret row id of a node override, whose primary key is
+/// `(proxy_config_id, node_id)`.
+pub(super) fn proxy_override_secret_id(proxy_id: &str, node_id: &str) -> String {
+    format!("{proxy_id}/{node_id}")
+}
 
 impl PostgresControlRepository {
     async fn list_admin_proxy_config_base_rows(&self) -> anyhow::Result<Vec<AdminProxyConfig>> {
@@ -38,10 +47,9 @@ impl PostgresControlRepository {
             )
             .await
             .context("list admin proxy configs")?;
-        Ok(rows
-            .into_iter()
-            .map(decode_admin_proxy_config_row)
-            .collect())
+        rows.into_iter()
+            .map(|row| decode_admin_proxy_config_row(row, &self.secrets))
+            .collect()
     }
 
     async fn list_proxy_traffic_snapshots(
@@ -143,7 +151,8 @@ impl PostgresControlRepository {
             )
             .await
             .context("load admin proxy config")?;
-        Ok(row.map(decode_admin_proxy_config_row))
+        row.map(|row| decode_admin_proxy_config_row(row, &self.secrets))
+            .transpose()
     }
 
     async fn get_admin_proxy_config_row(
@@ -173,7 +182,9 @@ impl PostgresControlRepository {
         Ok(Some(proxy))
     }
 
-    async fn list_proxy_endpoint_check_rows(&self) -> anyhow::Result<Vec<ProxyEndpointCheckRow>> {
+    pub(super) async fn list_proxy_endpoint_check_rows(
+        &self,
+    ) -> anyhow::Result<Vec<ProxyEndpointCheckRow>> {
         self.ensure_connection_alive()?;
         let rows = self
             .client
@@ -240,19 +251,27 @@ impl PostgresControlRepository {
             )
             .await
             .context("list postgres proxy config node overrides")?;
-        Ok(rows
-            .into_iter()
+        rows.into_iter()
             .map(|row| {
-                (row.get::<_, String>(0), ProxyConfigNodeOverride {
+                let proxy_id: String = row.get(0);
+                let proxy_password: Option<String> = row.get(3);
+                let secret_id = proxy_override_secret_id(&proxy_id, &self.proxy_scope.node_id);
+                Ok((proxy_id, ProxyConfigNodeOverride {
                     proxy_url: row.get(1),
                     proxy_username: row.get(2),
-                    proxy_password: row.get(3),
+                    proxy_password: self
+                        .secrets
+                        .open_optional(
+                            SecretRow::new(PROXY_OVERRIDE_SECRET_TABLE, &secret_id),
+                            proxy_password.as_deref(),
+                        )
+                        .context("open proxy config node override password")?,
                     status: row.get(4),
                     created_at_ms: row.get(5),
                     updated_at_ms: row.get(6),
-                })
+                }))
             })
-            .collect())
+            .collect()
     }
 
     async fn get_proxy_config_node_override(
@@ -271,14 +290,25 @@ impl PostgresControlRepository {
             )
             .await
             .context("load postgres proxy config node override")?;
-        Ok(row.map(|row| ProxyConfigNodeOverride {
-            proxy_url: row.get(0),
-            proxy_username: row.get(1),
-            proxy_password: row.get(2),
-            status: row.get(3),
-            created_at_ms: row.get(4),
-            updated_at_ms: row.get(5),
-        }))
+        let secret_id = proxy_override_secret_id(proxy_id, &self.proxy_scope.node_id);
+        row.map(|row| {
+            let proxy_password: Option<String> = row.get(2);
+            Ok(ProxyConfigNodeOverride {
+                proxy_url: row.get(0),
+                proxy_username: row.get(1),
+                proxy_password: self
+                    .secrets
+                    .open_optional(
+                        SecretRow::new(PROXY_OVERRIDE_SECRET_TABLE, &secret_id),
+                        proxy_password.as_deref(),
+                    )
+                    .context("open proxy config node override password")?,
+                status: row.get(3),
+                created_at_ms: row.get(4),
+                updated_at_ms: row.get(5),
+            })
+        })
+        .transpose()
     }
 
     async fn patch_admin_proxy_config_node_override(
@@ -319,6 +349,14 @@ impl PostgresControlRepository {
             .as_ref()
             .map(|row| row.created_at_ms)
             .unwrap_or(patch.updated_at_ms);
+        let secret_id = proxy_override_secret_id(proxy_id, &self.proxy_scope.node_id);
+        let sealed_proxy_password = self
+            .secrets
+            .seal_optional(
+                SecretRow::new(PROXY_OVERRIDE_SECRET_TABLE, &secret_id),
+                proxy_password.as_deref(),
+            )
+            .context("seal proxy config node override password")?;
         self.ensure_connection_alive()?;
         self.client
             .execute(
@@ -337,7 +375,7 @@ impl PostgresControlRepository {
                     &self.proxy_scope.node_id,
                     &proxy_url,
                     &proxy_username,
-                    &proxy_password,
+                    &sealed_proxy_password,
                     &status,
                     &created_at_ms,
                     &patch.updated_at_ms,
@@ -369,6 +407,72 @@ impl PostgresControlRepository {
         proxy.can_edit_slot_metadata = self.proxy_scope.can_edit_slot_metadata();
     }
 
+    /// Store one endpoint check for this node. When the check flips the
+    /// proxy's pool health, pool members may be re-picked, so the provider's
+    /// dispatch generation is bumped to re-resolve cached account views.
+    pub(super) async fn upsert_proxy_endpoint_check(
+        &self,
+        update: AdminProxyEndpointCheckUpdate,
+    ) -> anyhow::Result<()> {
+        let now = now_ms();
+        let was_unhealthy = self
+            .list_proxy_endpoint_check_rows()
+            .await?
+            .into_iter()
+            .find(|row| {
+                row.proxy_config_id == update.proxy_config_id
+                    && row.provider_type == update.provider_type
+            })
+            .is_some_and(|row| row.check.marks_proxy_unhealthy(now));
+        let is_unhealthy = core_store::AdminProxyEndpointCheck {
+            target_url: update.target_url.clone(),
+            reachable: update.reachable,
+            status_code: update.status_code,
+            latency_ms: update.latency_ms,
+            error_message: None,
+            checked_at: update.checked_at_ms,
+        }
+        .marks_proxy_unhealthy(now);
+        let status_code = update.status_code.map(i32::from);
+        self.ensure_connection_alive()?;
+        self.client
+            .execute(
+                "INSERT INTO llm_proxy_config_endpoint_checks (
+                    proxy_config_id, node_id, provider_type, target_url, reachable,
+                    status_code, latency_ms, error_message, checked_at_ms
+                 ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                 ON CONFLICT (proxy_config_id, node_id, provider_type) DO UPDATE SET
+                    target_url = EXCLUDED.target_url,
+                    reachable = EXCLUDED.reachable,
+                    status_code = EXCLUDED.status_code,
+                    latency_ms = EXCLUDED.latency_ms,
+                    error_message = EXCLUDED.error_message,
+                    checked_at_ms = EXCLUDED.checked_at_ms",
+                &[
+                    &update.proxy_config_id,
+                    &self.proxy_scope.node_id,
+                    &update.provider_type,
+                    &update.target_url,
+                    &update.reachable,
+                    &status_code,
+                    &update.latency_ms,
+                    &update.error_message,
+                    &update.checked_at_ms,
+                ],
+            )
+            .await
+            .context("record postgres proxy endpoint check")?;
+        self.invalidate_proxy_metadata_cache().await;
+        if was_unhealthy != is_unhealthy
+            && self
+                .proxy_config_in_any_pool(&update.proxy_config_id)
+                .await?
+        {
+            self.bump_dispatch_generation(&update.provider_type).await;
+        }
+        Ok(())
+    }
+
     pub(super) async fn load_admin_proxy_binding_row(
         &self,
         provider_type: &str,
@@ -392,7 +496,7 @@ impl PostgresControlRepository {
         let binding = self
             .client
             .query_opt(
-                "SELECT provider_type, proxy_config_id, updated_at_ms
+                "SELECT provider_type, proxy_config_id, updated_at_ms, proxy_pool_id
                  FROM llm_proxy_bindings
                  WHERE provider_type = $1",
                 &[&provider_type],
@@ -407,6 +511,7 @@ impl PostgresControlRepository {
                     provider_type: provider_type.to_string(),
                     effective_source: "none".to_string(),
                     bound_proxy_config_id: None,
+                    bound_proxy_pool_id: None,
                     effective_proxy_config_name: None,
                     effective_proxy_url: None,
                     effective_proxy_username: None,
@@ -416,38 +521,67 @@ impl PostgresControlRepository {
                 }));
         };
         let provider_type: String = row.get(0);
-        let proxy_config_id: String = row.get(1);
+        let proxy_config_id: Option<String> = row.get(1);
         let updated_at_ms: i64 = row.get(2);
-        let Some(proxy) = proxy_configs_by_id.get(&proxy_config_id).cloned() else {
+        let proxy_pool_id: Option<String> = row.get(3);
+        let invalid = |provider_type: String, message: &str| AdminProxyBinding {
+            provider_type,
+            effective_source: "invalid".to_string(),
+            bound_proxy_config_id: None,
+            bound_proxy_pool_id: None,
+            effective_proxy_config_name: None,
+            effective_proxy_url: None,
+            effective_proxy_username: None,
+            effective_proxy_password: None,
+            binding_updated_at: Some(updated_at_ms),
+            error_message: Some(message.to_string()),
+        };
+        if let Some(proxy_pool_id) = proxy_pool_id {
+            let pool = self.get_admin_proxy_pool_row(&proxy_pool_id).await?;
+            let error_message = match pool.as_ref() {
+                None => Some("bound proxy pool is missing"),
+                Some(pool) if pool.status != core_store::KEY_STATUS_ACTIVE => {
+                    Some("bound proxy pool is disabled")
+                },
+                Some(_) => None,
+            };
+            // Each inheriting account picks its own member, so the binding
+            // itself has no single effective proxy URL.
             return Ok(AdminProxyBinding {
                 provider_type,
-                effective_source: "invalid".to_string(),
-                bound_proxy_config_id: Some(proxy_config_id),
-                effective_proxy_config_name: None,
+                effective_source: if error_message.is_some() { "invalid" } else { "pool" }
+                    .to_string(),
+                bound_proxy_config_id: None,
+                bound_proxy_pool_id: Some(proxy_pool_id),
+                effective_proxy_config_name: pool.map(|pool| pool.name),
                 effective_proxy_url: None,
                 effective_proxy_username: None,
                 effective_proxy_password: None,
                 binding_updated_at: Some(updated_at_ms),
-                error_message: Some("bound proxy config is missing".to_string()),
+                error_message: error_message.map(ToString::to_string),
+            });
+        }
+        let Some(proxy_config_id) = proxy_config_id else {
+            return Ok(invalid(provider_type, "proxy binding has no target"));
+        };
+        let Some(proxy) = proxy_configs_by_id.get(&proxy_config_id).cloned() else {
+            return Ok(AdminProxyBinding {
+                bound_proxy_config_id: Some(proxy_config_id),
+                ..invalid(provider_type, "bound proxy config is missing")
             });
         };
         if proxy.status != core_store::KEY_STATUS_ACTIVE {
             return Ok(AdminProxyBinding {
-                provider_type,
-                effective_source: "invalid".to_string(),
                 bound_proxy_config_id: Some(proxy.id),
                 effective_proxy_config_name: Some(proxy.name),
-                effective_proxy_url: None,
-                effective_proxy_username: None,
-                effective_proxy_password: None,
-                binding_updated_at: Some(updated_at_ms),
-                error_message: Some("bound proxy config is disabled".to_string()),
+                ..invalid(provider_type, "bound proxy config is disabled")
             });
         }
         Ok(AdminProxyBinding {
             provider_type,
             effective_source: "binding".to_string(),
             bound_proxy_config_id: Some(proxy.id),
+            bound_proxy_pool_id: None,
             effective_proxy_config_name: Some(proxy.name),
             effective_proxy_url: Some(proxy.proxy_url),
             effective_proxy_username: proxy.proxy_username,
@@ -468,9 +602,11 @@ impl PostgresControlRepository {
             .map(|proxy| (proxy.id.clone(), proxy))
             .collect::<BTreeMap<_, _>>();
         let binding = self.load_admin_proxy_binding_cached(provider_type).await?;
+        let proxy_pools = self.load_proxy_pool_context(provider_type).await?;
         Ok(ProviderProxyResolutionContext {
             proxy_configs_by_id,
             binding,
+            proxy_pools,
         })
     }
 }
@@ -494,6 +630,13 @@ impl AdminProxyStore for PostgresControlRepository {
         if !self.proxy_scope.can_edit_slot_metadata() {
             anyhow::bail!("proxy slots can only be created on the core node");
         }
+        let sealed_proxy_password = self
+            .secrets
+            .seal_optional(
+                SecretRow::new("llm_proxy_configs", &proxy.id),
+                proxy.proxy_password.as_deref(),
+            )
+            .context("seal proxy config password")?;
         self.ensure_connection_alive()?;
         self.client
             .execute(
@@ -506,7 +649,7 @@ impl AdminProxyStore for PostgresControlRepository {
                     &proxy.name,
                     &proxy.proxy_url,
                     &proxy.proxy_username,
-                    &proxy.proxy_password,
+                    &sealed_proxy_password,
                     &core_store::KEY_STATUS_ACTIVE,
                     &proxy.created_at_ms,
                     &proxy.created_at_ms,
@@ -553,6 +696,13 @@ impl AdminProxyStore for PostgresControlRepository {
             proxy.status = status.clone();
         }
         proxy.updated_at = patch.updated_at_ms;
+        let sealed_proxy_password = self
+            .secrets
+            .seal_optional(
+                SecretRow::new("llm_proxy_configs", proxy_id),
+                proxy.proxy_password.as_deref(),
+            )
+            .context("seal proxy config password")?;
         self.ensure_connection_alive()?;
         self.client
             .execute(
@@ -565,7 +715,7 @@ impl AdminProxyStore for PostgresControlRepository {
                     &proxy.name,
                     &proxy.proxy_url,
                     &proxy.proxy_username,
-                    &proxy.proxy_password,
+                    &sealed_proxy_password,
                     &proxy.status,
                     &proxy.updated_at,
                 ],
@@ -600,38 +750,9 @@ impl AdminProxyStore for PostgresControlRepository {
         {
             return Ok(None);
         }
-        let status_code = update.status_code.map(i32::from);
-        self.ensure_connection_alive()?;
-        self.client
-            .execute(
-                "INSERT INTO llm_proxy_config_endpoint_checks (
-                    proxy_config_id, node_id, provider_type, target_url, reachable,
-                    status_code, latency_ms, error_message, checked_at_ms
-                 ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
-                 ON CONFLICT (proxy_config_id, node_id, provider_type) DO UPDATE SET
-                    target_url = EXCLUDED.target_url,
-                    reachable = EXCLUDED.reachable,
-                    status_code = EXCLUDED.status_code,
-                    latency_ms = EXCLUDED.latency_ms,
-                    error_message = EXCLUDED.error_message,
-                    checked_at_ms = EXCLUDED.checked_at_ms",
-                &[
-                    &update.proxy_config_id,
-                    &self.proxy_scope.node_id,
-                    &update.provider_type,
-                    &update.target_url,
-                    &update.reachable,
-                    &status_code,
-                    &update.latency_ms,
-                    &update.error_message,
-                    &update.checked_at_ms,
-                ],
-            )
-            .await
-            .context("record postgres proxy endpoint check")?;
-        self.invalidate_proxy_metadata_cache().await;
-        self.get_admin_proxy_config_row(&update.proxy_config_id)
-            .await
+        let proxy_config_id = update.proxy_config_id.clone();
+        self.upsert_proxy_endpoint_check(update).await?;
+        self.get_admin_proxy_config_row(&proxy_config_id).await
     }
 
     async fn record_admin_proxy_traffic_snapshot(
@@ -700,6 +821,9 @@ impl AdminProxyStore for PostgresControlRepository {
         let Some(proxy) = self.get_admin_proxy_config_row(proxy_id).await? else {
             return Ok(None);
         };
+        if self.proxy_config_in_any_pool(proxy_id).await? {
+            anyhow::bail!("proxy config `{}` is still a member of a proxy pool", proxy.name);
+        }
         self.ensure_connection_alive()?;
         self.client
             .execute("DELETE FROM llm_proxy_configs WHERE proxy_config_id = $1", &[&proxy_id])
@@ -770,6 +894,7 @@ impl AdminProxyStore for PostgresControlRepository {
                         ) VALUES ($1, $2, $3)
                         ON CONFLICT(provider_type) DO UPDATE SET
                             proxy_config_id = EXCLUDED.proxy_config_id,
+                            proxy_pool_id = NULL,
                             updated_at_ms = EXCLUDED.updated_at_ms",
                         &[&provider_type, &proxy_config_id, &now_ms()],
                     )
@@ -792,6 +917,45 @@ impl AdminProxyStore for PostgresControlRepository {
         self.load_admin_proxy_binding_cached(provider_type).await
     }
 
+    async fn update_admin_proxy_pool_binding(
+        &self,
+        provider_type: &str,
+        proxy_pool_id: Option<String>,
+    ) -> anyhow::Result<AdminProxyBinding> {
+        self.update_admin_proxy_pool_binding_row(provider_type, proxy_pool_id)
+            .await
+    }
+
+    async fn list_admin_proxy_pools(&self) -> anyhow::Result<Vec<AdminProxyPool>> {
+        self.list_admin_proxy_pool_rows().await
+    }
+
+    async fn get_admin_proxy_pool(&self, pool_id: &str) -> anyhow::Result<Option<AdminProxyPool>> {
+        self.get_admin_proxy_pool_row(pool_id).await
+    }
+
+    async fn create_admin_proxy_pool(
+        &self,
+        pool: NewAdminProxyPool,
+    ) -> anyhow::Result<AdminProxyPool> {
+        self.create_admin_proxy_pool_row(pool).await
+    }
+
+    async fn patch_admin_proxy_pool(
+        &self,
+        pool_id: &str,
+        patch: AdminProxyPoolPatch,
+    ) -> anyhow::Result<Option<AdminProxyPool>> {
+        self.patch_admin_proxy_pool_row(pool_id, patch).await
+    }
+
+    async fn delete_admin_proxy_pool(
+        &self,
+        pool_id: &str,
+    ) -> anyhow::Result<Option<AdminProxyPool>> {
+        self.delete_admin_proxy_pool_row(pool_id).await
+    }
+
     async fn import_legacy_kiro_proxy_configs(
         &self,
     ) -> anyhow::Result<AdminLegacyKiroProxyMigration> {
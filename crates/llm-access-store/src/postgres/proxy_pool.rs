@@ -0,0 +1,388 @@
+//! Weighted proxy pools: pool CRUD, provider pool bindings, member health
+//! derived from endpoint checks, and live transport-failure tracking.
+//!
+//! Pool selection itself is a pure function of the pool, the member health
+//! set and the account name (see `proxy_support::select_pool_proxy_config`),
+//! so resolved proxies can keep living in the cached account views. Anything
+//! that changes a pool's outcome bumps the dispatch generation instead.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::Context;
+use llm_access_core::store::{
+    self as core_store, AdminProxyBinding, AdminProxyEndpointCheckUpdate, AdminProxyPool,
+    AdminProxyPoolPatch, NewAdminProxyPool, ProxyPoolMember, LIVE_REQUEST_PROXY_CHECK_TARGET,
+};
+use serde::Serialize;
+
+use super::{now_ms, PgRow, PostgresControlRepository, ProxyPoolContext};
+
+/// Transport failures within [`LIVE_FAILURE_WINDOW_MS`] that mark a proxy
+/// unhealthy.
+const LIVE_FAILURE_THRESHOLD: usize = 3;
+const LIVE_FAILURE_WINDOW_MS: i64 = 60 * 1000;
+
+const PROXY_POOL_SELECT_SQL: &str = "SELECT p.proxy_pool_id, p.name, p.status, p.created_at_ms,
+        p.updated_at_ms,
+        COALESCE(
+            (SELECT jsonb_agg(
+                        jsonb_build_object(
+                            'proxy_config_id', m.proxy_config_id,
+                            'weight', m.weight
+                        )
+                        ORDER BY m.position, m.proxy_config_id
+                    )::text
+             FROM llm_proxy_pool_members m
+             WHERE m.proxy_pool_id = p.proxy_pool_id),
+            '[]'
+        ) AS members_json
+     FROM llm_proxy_pools p";
+
+#[derive(Serialize)]
+struct MemberRow<'a> {
+    proxy_config_id: &'a str,
+    weight: i64,
+    position: i64,
+}
+
+fn members_json(members: &[ProxyPoolMember]) -> anyhow::Result<String> {
+    let rows = members
+        .iter()
+        .enumerate()
+        .map(|(position, member)| MemberRow {
+            proxy_config_id: &member.proxy_config_id,
+            weight: i64::from(member.weight),
+            position: position as i64,
+        })
+        .collect::<Vec<_>>();
+    serde_json::to_string(&rows).context("serialize proxy pool members")
+}
+
+fn decode_proxy_pool_row(row: PgRow) -> anyhow::Result<AdminProxyPool> {
+    let id: String = row.get(0);
+    let members_json: String = row.get(5);
+    let members = serde_json::from_str::<Vec<ProxyPoolMember>>(&members_json)
+        .with_context(|| format!("decode members of proxy pool `{id}`"))?;
+    Ok(AdminProxyPool {
+        id,
+        name: row.get(1),
+        status: row.get(2),
+        members,
+        created_at: row.get(3),
+        updated_at: row.get(4),
+    })
+}
+
+impl PostgresControlRepository {
+    pub(super) async fn list_admin_proxy_pool_rows(&self) -> anyhow::Result<Vec<AdminProxyPool>> {
+        self.ensure_connection_alive()?;
+        let rows = self
+            .client
+            .query(&format!("{PROXY_POOL_SELECT_SQL} ORDER BY p.name, p.proxy_pool_id"), &[])
+            .await
+            .context("list postgres proxy pools")?;
+        rows.into_iter().map(decode_proxy_pool_row).collect()
+    }
+
+    pub(super) async fn get_admin_proxy_pool_row(
+        &self,
+        pool_id: &str,
+    ) -> anyhow::Result<Option<AdminProxyPool>> {
+        self.ensure_connection_alive()?;
+        let row = self
+            .client
+            .query_opt(&format!("{PROXY_POOL_SELECT_SQL} WHERE p.proxy_pool_id = $1"), &[&pool_id])
+            .await
+            .context("load postgres proxy pool")?;
+        row.map(decode_proxy_pool_row).transpose()
+    }
+
+    pub(super) async fn create_admin_proxy_pool_row(
+        &self,
+        pool: NewAdminProxyPool,
+    ) -> anyhow::Result<AdminProxyPool> {
+        let members = members_json(&pool.members)?;
+        self.ensure_connection_alive()?;
+        self.client
+            .execute(
+                "WITH pool AS (
+                    INSERT INTO llm_proxy_pools (
+                        proxy_pool_id, name, status, created_at_ms, updated_at_ms
+                    ) VALUES ($1, $2, $3, $4, $4)
+                    RETURNING proxy_pool_id
+                 )
+                 INSERT INTO llm_proxy_pool_members (
+                    proxy_pool_id, proxy_config_id, weight, position
+                 )
+                 SELECT pool.proxy_pool_id, m.proxy_config_id, m.weight, m.position
+                 FROM pool,
+                    jsonb_to_recordset($5::jsonb)
+                        AS m(proxy_config_id TEXT, weight BIGINT, position BIGINT)",
+                &[
+                    &pool.id,
+                    &pool.name,
+                    &core_store::KEY_STATUS_ACTIVE,
+                    &pool.created_at_ms,
+                    &members,
+                ],
+            )
+            .await
+            .context("create postgres proxy pool")?;
+        self.get_admin_proxy_pool_row(&pool.id)
+            .await?
+            .context("created postgres proxy pool disappeared")
+    }
+
+    pub(super) async fn patch_admin_proxy_pool_row(
+        &self,
+        pool_id: &str,
+        patch: AdminProxyPoolPatch,
+    ) -> anyhow::Result<Option<AdminProxyPool>> {
+        let Some(mut pool) = self.get_admin_proxy_pool_row(pool_id).await? else {
+            return Ok(None);
+        };
+        if let Some(name) = patch.name {
+            pool.name = name;
+        }
+        if let Some(status) = patch.status {
+            pool.status = status;
+        }
+        if let Some(members) = patch.members {
+            pool.members = members;
+        }
+        let members = members_json(&pool.members)?;
+        self.ensure_connection_alive()?;
+        // One statement so a concurrent resolver never sees a half-replaced
+        // member list.
+        self.client
+            .execute(
+                "WITH incoming AS (
+                    SELECT *
+                    FROM jsonb_to_recordset($5::jsonb)
+                        AS m(proxy_config_id TEXT, weight BIGINT, position BIGINT)
+                 ),
+                 pool AS (
+                    UPDATE llm_proxy_pools
+                    SET name = $2, status = $3, updated_at_ms = $4
+                    WHERE proxy_pool_id = $1
+                    RETURNING proxy_pool_id
+                 ),
+                 removed AS (
+                    DELETE FROM llm_proxy_pool_members
+                    WHERE proxy_pool_id = $1
+                      AND proxy_config_id NOT IN (SELECT proxy_config_id FROM incoming)
+                 )
+                 INSERT INTO llm_proxy_pool_members (
+                    proxy_pool_id, proxy_config_id, weight, position
+                 )
+                 SELECT pool.proxy_pool_id, incoming.proxy_config_id, incoming.weight,
+                    incoming.position
+                 FROM pool, incoming
+                 ON CONFLICT (proxy_pool_id, proxy_config_id) DO UPDATE SET
+                    weight = EXCLUDED.weight,
+                    position = EXCLUDED.position",
+                &[&pool_id, &pool.name, &pool.status, &patch.updated_at_ms, &members],
+            )
+            .await
+            .context("patch postgres proxy pool")?;
+        self.invalidate_proxy_pool_dependents().await;
+        self.get_admin_proxy_pool_row(pool_id).await
+    }
+
+    pub(super) async fn delete_admin_proxy_pool_row(
+        &self,
+        pool_id: &str,
+    ) -> anyhow::Result<Option<AdminProxyPool>> {
+        let Some(pool) = self.get_admin_proxy_pool_row(pool_id).await? else {
+            return Ok(None);
+        };
+        self.ensure_connection_alive()?;
+        // Bindings are protected by a foreign key; account settings live in
+        // JSON, so check them explicitly.
+        let referenced: bool = self
+            .client
+            .query_one(
+                "SELECT EXISTS (
+                    SELECT 1 FROM llm_proxy_bindings WHERE proxy_pool_id = $1
+                 ) OR EXISTS (
+                    SELECT 1 FROM llm_codex_accounts
+                    WHERE NULLIF(BTRIM(settings_json ->> 'proxy_pool_id'), '') = $1
+                 ) OR EXISTS (
+                    SELECT 1 FROM llm_kiro_accounts
+                    WHERE NULLIF(
+                        BTRIM(COALESCE(auth_json ->> 'proxyPoolId', auth_json ->> \
+                 'proxy_pool_id')),
+                        ''
+                    ) = $1
+                 )",
+                &[&pool_id],
+            )
+            .await
+            .context("check postgres proxy pool references")?
+            .get(0);
+        if referenced {
+            anyhow::bail!("proxy pool `{}` is still referenced by a binding or account", pool.name);
+        }
+        self.client
+            .execute("DELETE FROM llm_proxy_pools WHERE proxy_pool_id = $1", &[&pool_id])
+            .await
+            .context("delete postgres proxy pool")?;
+        self.invalidate_proxy_pool_dependents().await;
+        Ok(Some(pool))
+    }
+
+    pub(super) async fn update_admin_proxy_pool_binding_row(
+        &self,
+        provider_type: &str,
+        proxy_pool_id: Option<String>,
+    ) -> anyhow::Result<AdminProxyBinding> {
+        self.ensure_connection_alive()?;
+        match proxy_pool_id {
+            Some(proxy_pool_id) => {
+                self.client
+                    .execute(
+                        "INSERT INTO llm_proxy_bindings (
+                            provider_type, proxy_config_id, proxy_pool_id, updated_at_ms
+                        ) VALUES ($1, NULL, $2, $3)
+                        ON CONFLICT(provider_type) DO UPDATE SET
+                            proxy_config_id = NULL,
+                            proxy_pool_id = EXCLUDED.proxy_pool_id,
+                            updated_at_ms = EXCLUDED.updated_at_ms",
+                        &[&provider_type, &proxy_pool_id, &now_ms()],
+                    )
+                    .await
+                    .context("upsert postgres proxy pool binding")?;
+            },
+            None => {
+                self.client
+                    .execute(
+                        "DELETE FROM llm_proxy_bindings
+                         WHERE provider_type = $1 AND proxy_pool_id IS NOT NULL",
+                        &[&provider_type],
+                    )
+                    .await
+                    .context("delete postgres proxy pool binding")?;
+            },
+        }
+        self.invalidate_proxy_metadata_cache().await;
+        self.invalidate_all_account_views_for_provider(provider_type)
+            .await;
+        self.bump_dispatch_generation(provider_type).await;
+        self.load_admin_proxy_binding_cached(provider_type).await
+    }
+
+    /// Pools and the member proxies that recent checks from this node mark
+    /// unhealthy for `provider_type`.
+    pub(super) async fn load_proxy_pool_context(
+        &self,
+        provider_type: &str,
+    ) -> anyhow::Result<ProxyPoolContext> {
+        let pools_by_id = self
+            .list_admin_proxy_pool_rows()
+            .await?
+            .into_iter()
+            .map(|pool| (pool.id.clone(), pool))
+            .collect::<BTreeMap<_, _>>();
+        if pools_by_id.is_empty() {
+            return Ok(ProxyPoolContext::default());
+        }
+        let now = now_ms();
+        let unhealthy_proxy_ids = self
+            .list_proxy_endpoint_check_rows()
+            .await?
+            .into_iter()
+            .filter(|row| {
+                row.provider_type == provider_type && row.check.marks_proxy_unhealthy(now)
+            })
+            .map(|row| row.proxy_config_id)
+            .collect::<BTreeSet<_>>();
+        Ok(ProxyPoolContext {
+            pools_by_id,
+            unhealthy_proxy_ids,
+        })
+    }
+
+    /// Whether `proxy_config_id` belongs to any pool, i.e. whether its health
+    /// can change which proxy an account resolves to.
+    pub(super) async fn proxy_config_in_any_pool(
+        &self,
+        proxy_config_id: &str,
+    ) -> anyhow::Result<bool> {
+        self.ensure_connection_alive()?;
+        let row = self
+            .client
+            .query_one(
+                "SELECT EXISTS (
+                    SELECT 1 FROM llm_proxy_pool_members WHERE proxy_config_id = $1
+                 )",
+                &[&proxy_config_id],
+            )
+            .await
+            .context("check proxy pool membership")?;
+        Ok(row.get(0))
+    }
+
+    /// Count a live transport failure against the account's effective proxy.
+    /// Once [`LIVE_FAILURE_THRESHOLD`] failures land within the window, the
+    /// proxy is recorded as an unreachable endpoint check, which takes it out
+    /// of pool selection until a later check succeeds or the check ages out.
+    pub(super) async fn record_live_proxy_transport_failure(
+        &self,
+        provider_type: &str,
+        account_name: &str,
+        error_message: &str,
+    ) -> anyhow::Result<()> {
+        let Some(proxy_config_id) = self
+            .resolve_usage_proxy_attribution(provider_type, account_name)
+            .await?
+            .and_then(|attribution| attribution.proxy_config_id)
+        else {
+            return Ok(());
+        };
+        let now = now_ms();
+        let tripped = {
+            let mut failures = self.live_proxy_failures.lock().await;
+            let recent = failures.entry(proxy_config_id.clone()).or_default();
+            recent.retain(|at| now.saturating_sub(*at) <= LIVE_FAILURE_WINDOW_MS);
+            recent.push(now);
+            let tripped = recent.len() >= LIVE_FAILURE_THRESHOLD;
+            if tripped {
+                recent.clear();
+            }
+            tripped
+        };
+        if !tripped {
+            return Ok(());
+        }
+        tracing::warn!(
+            provider_type,
+            proxy_config_id = %proxy_config_id,
+            "marking proxy unhealthy after repeated live transport failures: {error_message}"
+        );
+        self.upsert_proxy_endpoint_check(AdminProxyEndpointCheckUpdate {
+            proxy_config_id,
+            provider_type: provider_type.to_string(),
+            target_url: LIVE_REQUEST_PROXY_CHECK_TARGET.to_string(),
+            reachable: false,
+            status_code: None,
+            latency_ms: 0,
+            error_message: Some(format!(
+                "{LIVE_FAILURE_THRESHOLD} live transport failures within {}s: {error_message}",
+                LIVE_FAILURE_WINDOW_MS / 1000
+            )),
+            checked_at_ms: now,
+        })
+        .await
+    }
+
+    /// Pool edits can move accounts between proxies, so cached account views
+    /// and snapshots for both providers must be rebuilt.
+    async fn invalidate_proxy_pool_dependents(&self) {
+        self.invalidate_proxy_metadata_cache().await;
+        for provider in [core_store::PROVIDER_CODEX, core_store::PROVIDER_KIRO] {
+            self.invalidate_all_account_views_for_provider(provider)
+                .await;
+            self.bump_dispatch_generation(provider).await;
+        }
+    }
+}
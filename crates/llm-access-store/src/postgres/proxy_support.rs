@@ -1,10 +1,44 @@
 //! Proxy config transforms: admin<->provider conversion, node-override and
-//! endpoint-check application, and legacy-proxy JSON cleanup.
+//! endpoint-check application, pool member selection, and legacy-proxy JSON
+//! cleanup.
+
+use std::collections::BTreeMap;
 
 use anyhow::Context;
-use llm_access_core::store::{self as core_store, AdminProxyConfig, ProviderProxyConfig};
+use llm_access_core::{
+    proxy::select_proxy_pool_member,
+    store::{self as core_store, AdminProxyBinding, AdminProxyConfig, ProviderProxyConfig},
+};
+
+use super::{
+    CodexAccountSettings, ProviderProxyResolutionContext, ProxyConfigNodeOverride,
+    ProxyEndpointCheckRow, ProxyPoolContext,
+};
 
-use super::{ProviderProxyResolutionContext, ProxyConfigNodeOverride, ProxyEndpointCheckRow};
+/// Proxy reference stored on one account or channel.
+#[derive(Debug, Clone, Copy)]
+pub struct AccountProxyRef<'a> {
+    /// Stored proxy mode (`inherit`, `direct`/`none`, `fixed` or `pool`).
+    pub proxy_mode: &'a str,
+    /// Proxy config id used by `fixed`.
+    pub proxy_config_id: Option<&'a str>,
+    /// Proxy pool id used by `pool`.
+    pub proxy_pool_id: Option<&'a str>,
+    /// Account or channel name that pool selection stays sticky to.
+    pub sticky_key: &'a str,
+}
+
+pub fn codex_account_proxy_ref<'a>(
+    account_name: &'a str,
+    settings: &'a CodexAccountSettings,
+) -> AccountProxyRef<'a> {
+    AccountProxyRef {
+        proxy_mode: &settings.proxy_mode,
+        proxy_config_id: settings.proxy_config_id.as_deref(),
+        proxy_pool_id: settings.proxy_pool_id.as_deref(),
+        sticky_key: account_name,
+    }
+}
 
 fn provider_proxy_from_admin_proxy(proxy: AdminProxyConfig) -> ProviderProxyConfig {
     ProviderProxyConfig {
@@ -72,37 +106,155 @@ pub fn clear_legacy_kiro_proxy_json(
     serde_json::to_string(&value).context("serialize postgres kiro auth json after proxy cleanup")
 }
 
-pub fn resolve_provider_proxy_config_from_context(
-    proxy_mode: &str,
-    proxy_config_id: Option<&str>,
-    context: &ProviderProxyResolutionContext,
-) -> anyhow::Result<Option<ProviderProxyConfig>> {
-    match proxy_mode {
+/// Pick the member of `pool_id` that `sticky_key` sticks to. Members whose
+/// recent checks failed are skipped; when every active member is unhealthy
+/// the pool degrades to ignoring health rather than failing the account.
+pub fn select_pool_proxy_config<'a>(
+    pool_id: &str,
+    sticky_key: &str,
+    proxy_configs_by_id: &'a BTreeMap<String, AdminProxyConfig>,
+    pools: &ProxyPoolContext,
+) -> anyhow::Result<&'a AdminProxyConfig> {
+    let Some(pool) = pools.pools_by_id.get(pool_id) else {
+        anyhow::bail!("proxy pool `{pool_id}` is missing");
+    };
+    if pool.status != core_store::KEY_STATUS_ACTIVE {
+        anyhow::bail!("proxy pool `{}` is disabled", pool.name);
+    }
+    let active_member = |proxy_config_id: &str| {
+        proxy_configs_by_id
+            .get(proxy_config_id)
+            .filter(|proxy| proxy.status == core_store::KEY_STATUS_ACTIVE)
+    };
+    let chosen = select_proxy_pool_member(&pool.members, sticky_key, |member| {
+        active_member(&member.proxy_config_id).is_some()
+            && !pools.unhealthy_proxy_ids.contains(&member.proxy_config_id)
+    })
+    .or_else(|| {
+        select_proxy_pool_member(&pool.members, sticky_key, |member| {
+            active_member(&member.proxy_config_id).is_some()
+        })
+    });
+    chosen
+        .and_then(|member| active_member(&member.proxy_config_id))
+        .with_context(|| format!("proxy pool `{}` has no active members", pool.name))
+}
+
+/// The proxy config an account reference currently maps to, for views and
+/// usage attribution. `Err` means the reference is invalid; `Ok(None)` means
+/// a direct connection or an inherited binding without a proxy.
+pub fn account_proxy_config<'a>(
+    reference: AccountProxyRef<'_>,
+    proxy_configs_by_id: &'a BTreeMap<String, AdminProxyConfig>,
+    binding: &AdminProxyBinding,
+    pools: &ProxyPoolContext,
+) -> anyhow::Result<Option<&'a AdminProxyConfig>> {
+    match reference.proxy_mode {
         "none" | "direct" => Ok(None),
         "fixed" => {
-            let Some(proxy_id) = proxy_config_id else {
-                anyhow::bail!("fixed proxy mode requires proxy_config_id");
-            };
-            let Some(proxy) = context.proxy_configs_by_id.get(proxy_id).cloned() else {
-                anyhow::bail!("fixed proxy config `{proxy_id}` is missing");
-            };
+            let proxy_id = reference
+                .proxy_config_id
+                .context("fixed proxy mode requires proxy_config_id")?;
+            let proxy = proxy_configs_by_id
+                .get(proxy_id)
+                .with_context(|| format!("fixed proxy config `{proxy_id}` is missing"))?;
             if proxy.status != core_store::KEY_STATUS_ACTIVE {
                 anyhow::bail!("fixed proxy config `{}` is disabled", proxy.name);
             }
-            Ok(Some(provider_proxy_from_admin_proxy(proxy)))
+            Ok(Some(proxy))
+        },
+        "pool" => {
+            let pool_id = reference
+                .proxy_pool_id
+                .context("pool proxy mode requires proxy_pool_id")?;
+            select_pool_proxy_config(pool_id, reference.sticky_key, proxy_configs_by_id, pools)
+                .map(Some)
         },
         _ => {
-            if let Some(message) = context.binding.error_message.clone() {
+            if let Some(message) = binding.error_message.as_deref() {
                 anyhow::bail!("provider proxy binding is invalid: {message}");
             }
-            match context.binding.effective_proxy_url.clone() {
-                Some(proxy_url) => Ok(Some(ProviderProxyConfig {
-                    proxy_url,
-                    proxy_username: context.binding.effective_proxy_username.clone(),
-                    proxy_password: context.binding.effective_proxy_password.clone(),
-                })),
-                None => Ok(None),
+            if let Some(pool_id) = binding.bound_proxy_pool_id.as_deref() {
+                return select_pool_proxy_config(
+                    pool_id,
+                    reference.sticky_key,
+                    proxy_configs_by_id,
+                    pools,
+                )
+                .map(Some);
             }
+            Ok(binding
+                .bound_proxy_config_id
+                .as_deref()
+                .and_then(|proxy_id| proxy_configs_by_id.get(proxy_id)))
+        },
+    }
+}
+
+/// Admin view projection of an account proxy reference: effective source,
+/// URL and config name.
+pub fn account_proxy_view(
+    reference: AccountProxyRef<'_>,
+    proxy_configs_by_id: &BTreeMap<String, AdminProxyConfig>,
+    binding: &AdminProxyBinding,
+    pools: &ProxyPoolContext,
+) -> (String, Option<String>, Option<String>) {
+    let source = match reference.proxy_mode {
+        "none" | "direct" => return ("none".to_string(), None, None),
+        "fixed" => "fixed",
+        "pool" => "pool",
+        _ if binding.bound_proxy_pool_id.is_some() => binding.effective_source.as_str(),
+        _ => {
+            return (
+                binding.effective_source.clone(),
+                binding.effective_proxy_url.clone(),
+                binding.effective_proxy_config_name.clone(),
+            )
         },
+    };
+    match account_proxy_config(reference, proxy_configs_by_id, binding, pools) {
+        Ok(Some(proxy)) => {
+            (source.to_string(), Some(proxy.proxy_url.clone()), Some(proxy.name.clone()))
+        },
+        Ok(None) => (source.to_string(), None, None),
+        Err(_) => {
+            let name = reference
+                .proxy_config_id
+                .filter(|_| reference.proxy_mode == "fixed")
+                .and_then(|proxy_id| proxy_configs_by_id.get(proxy_id))
+                .map(|proxy| proxy.name.clone());
+            ("invalid".to_string(), None, name)
+        },
+    }
+}
+
+pub fn resolve_provider_proxy_config_from_context(
+    reference: AccountProxyRef<'_>,
+    context: &ProviderProxyResolutionContext,
+) -> anyhow::Result<Option<ProviderProxyConfig>> {
+    let references_config = match reference.proxy_mode {
+        "none" | "direct" => return Ok(None),
+        "fixed" | "pool" => true,
+        _ => context.binding.bound_proxy_pool_id.is_some(),
+    };
+    if references_config {
+        return account_proxy_config(
+            reference,
+            &context.proxy_configs_by_id,
+            &context.binding,
+            &context.proxy_pools,
+        )
+        .map(|proxy| proxy.cloned().map(provider_proxy_from_admin_proxy));
+    }
+    if let Some(message) = context.binding.error_message.clone() {
+        anyhow::bail!("provider proxy binding is invalid: {message}");
+    }
+    match context.binding.effective_proxy_url.clone() {
+        Some(proxy_url) => Ok(Some(ProviderProxyConfig {
+            proxy_url,
+            proxy_username: context.binding.effective_proxy_username.clone(),
+            proxy_password: context.binding.effective_proxy_password.clone(),
+        })),
+        None => Ok(None),
     }
 }
@@ -20,6 +20,7 @@ use super::{
     },
     hash_bearer_secret, now_ms, PostgresControlRepository,
 };
+use crate::secrets::SecretRow;
 
 impl PostgresControlRepository {
     async fn list_public_access_keys_rows(&self) -> anyhow::Result<Vec<PublicAccessKey>> {
@@ -45,20 +46,26 @@ impl PostgresControlRepository {
             )
             .await
             .context("list public access keys")?;
-        Ok(rows
-            .into_iter()
-            .map(|row| PublicAccessKey {
-                key_id: row.get(0),
-                key_name: row.get(1),
-                secret: row.get(2),
-                quota_billable_limit: row.get::<_, i64>(3).max(0) as u64,
-                usage_input_uncached_tokens: row.get::<_, i64>(4).max(0) as u64,
-                usage_input_cached_tokens: row.get::<_, i64>(5).max(0) as u64,
-                usage_output_tokens: row.get::<_, i64>(6).max(0) as u64,
-                usage_billable_tokens: row.get::<_, i64>(7).max(0) as u64,
-                last_used_at_ms: row.get(8),
+        rows.into_iter()
+            .map(|row| {
+                let key_id: String = row.get(0);
+                let secret: String = row.get(2);
+                Ok(PublicAccessKey {
+                    secret: self
+                        .secrets
+                        .open(SecretRow::new("llm_keys", &key_id), &secret)
+                        .context("open public key secret")?,
+                    key_id,
+                    key_name: row.get(1),
+                    quota_billable_limit: row.get::<_, i64>(3).max(0) as u64,
+                    usage_input_uncached_tokens: row.get::<_, i64>(4).max(0) as u64,
+                    usage_input_cached_tokens: row.get::<_, i64>(5).max(0) as u64,
+                    usage_output_tokens: row.get::<_, i64>(6).max(0) as u64,
+                    usage_billable_tokens: row.get::<_, i64>(7).max(0) as u64,
+                    last_used_at_ms: row.get(8),
+                })
             })
-            .collect())
+            .collect()
     }
 
     async fn load_public_usage_key_by_hash(
@@ -82,7 +89,8 @@ impl PostgresControlRepository {
                     COALESCE(u.billable_tokens, 0),
                     COALESCE(u.credit_total, '0'),
                     COALESCE(u.credit_missing_events, 0),
-                    u.last_used_at_ms
+                    u.last_used_at_ms,
+                    k.parent_key_id
                  FROM llm_keys k
                  LEFT JOIN llm_key_usage_rollups u ON u.key_id = k.key_id
                  WHERE k.key_hash = $1",
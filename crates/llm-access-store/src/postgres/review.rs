@@ -1,14 +1,15 @@
-//! Codex import-job loads + the `AdminReviewQueueStore` impl.
+//! Codex import-job loads + the `AdminReviewQueueStore` impl (moderation
+//! rows live in `moderation.rs`).
 
 use anyhow::Context;
 use async_trait::async_trait;
 use llm_access_core::store::{
     AdminAccountContributionRequest, AdminAccountContributionRequestsPage, AdminAccountGroupStore,
     AdminCodexAccountStore, AdminCodexImportJobItem, AdminCodexImportJobSummary, AdminKeyPatch,
-    AdminKeyStore, AdminReviewQueueAction, AdminReviewQueueQuery, AdminReviewQueueStore,
-    AdminSponsorRequest, AdminSponsorRequestsPage, AdminTokenRequest, AdminTokenRequestsPage,
-    NewAdminAccountGroup, NewAdminCodexAccount, NewAdminKey,
-    PUBLIC_ACCOUNT_CONTRIBUTION_STATUS_VALIDATED,
+    AdminKeyStore, AdminModerationEvent, AdminModerationEventsPage, AdminReviewQueueAction,
+    AdminReviewQueueQuery, AdminReviewQueueStore, AdminSponsorRequest, AdminSponsorRequestsPage,
+    AdminTokenRequest, AdminTokenRequestsPage, NewAdminAccountGroup, NewAdminCodexAccount,
+    NewAdminKey, PUBLIC_ACCOUNT_CONTRIBUTION_STATUS_VALIDATED,
 };
 
 use super::{
@@ -572,4 +573,21 @@ impl AdminReviewQueueStore for PostgresControlRepository {
             .context("delete postgres sponsor request")?;
         Ok(changed > 0)
     }
+
+    async fn list_admin_moderation_events(
+        &self,
+        query: AdminReviewQueueQuery,
+    ) -> anyhow::Result<AdminModerationEventsPage> {
+        self.list_moderation_event_rows(query).await
+    }
+
+    async fn review_admin_moderation_event(
+        &self,
+        event_id: &str,
+        status: &str,
+        action: AdminReviewQueueAction,
+    ) -> anyhow::Result<Option<AdminModerationEvent>> {
+        self.review_moderation_event_row(event_id, status, action)
+            .await
+    }
 }
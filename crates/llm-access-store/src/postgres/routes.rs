@@ -12,7 +12,7 @@ use llm_access_core::{
         AdminKiroCacheView, AdminKiroStatusCacheUpdate, AuthenticatedKey,
         ProviderAnthropicUpstreamResolution, ProviderAnthropicUpstreamRoute,
         ProviderCodexAuthUpdate, ProviderCodexRoute, ProviderKiroAuthUpdate, ProviderKiroRoute,
-        ProviderRouteStore,
+        ProviderOpenAiUpstreamResolution, ProviderOpenAiUpstreamRoute, ProviderRouteStore,
     },
 };
 
@@ -25,11 +25,14 @@ use super::{
     decode::decode_codex_account_settings,
     json::decode_optional_json,
     normalize_manual_usage_limit,
-    proxy_support::resolve_provider_proxy_config_from_context,
-    CodexRouteCandidateRow, KiroCachedStatusParts, KiroRouteCandidateRow,
-    PostgresControlRepository, ProviderProxyResolutionContext,
+    proxy_support::{resolve_provider_proxy_config_from_context, AccountProxyRef},
+    CodexRouteCandidateRow, KiroCachedStatusParts, KiroRouteCandidateRow, PgRow,
+    PostgresControlRepository, ProviderProxyResolutionContext, ProxyPoolContext,
+};
+use crate::{
+    records::{KeyRouteConfig, RuntimeConfigRecord},
+    secrets::SecretRow,
 };
-use crate::records::{KeyRouteConfig, RuntimeConfigRecord};
 
 impl PostgresControlRepository {
     pub(super) async fn list_codex_route_candidate_rows(
@@ -57,17 +60,9 @@ impl PostgresControlRepository {
             )
             .await
             .context("list postgres codex route candidates")?;
-        Ok(rows
-            .into_iter()
-            .map(|row| CodexRouteCandidateRow {
-                account_name: row.get(0),
-                status: row.get(1),
-                settings_json: row.get(2),
-                last_refresh_at_ms: row.get(3),
-                last_error: row.get(4),
-                access_token: row.get(5),
-            })
-            .collect())
+        rows.into_iter()
+            .map(|row| self.decode_codex_route_candidate_row(row))
+            .collect()
     }
 
     pub(super) async fn list_codex_route_candidate_rows_by_names(
@@ -100,17 +95,31 @@ impl PostgresControlRepository {
             )
             .await
             .context("list postgres codex route candidates by names")?;
-        Ok(rows
-            .into_iter()
-            .map(|row| CodexRouteCandidateRow {
-                account_name: row.get(0),
-                status: row.get(1),
-                settings_json: row.get(2),
-                last_refresh_at_ms: row.get(3),
-                last_error: row.get(4),
-                access_token: row.get(5),
-            })
-            .collect())
+        rows.into_iter()
+            .map(|row| self.decode_codex_route_candidate_row(row))
+            .collect()
+    }
+
+    fn decode_codex_route_candidate_row(
+        &self,
+        row: PgRow,
+    ) -> anyhow::Result<CodexRouteCandidateRow> {
+        let account_name: String = row.get(0);
+        let access_token: Option<String> = row.get(5);
+        Ok(CodexRouteCandidateRow {
+            access_token: self
+                .secrets
+                .open_optional(
+                    SecretRow::new("llm_codex_accounts", &account_name),
+                    access_token.as_deref(),
+                )
+                .with_context(|| format!("open access token of codex account `{account_name}`"))?,
+            account_name,
+            status: row.get(1),
+            settings_json: row.get(2),
+            last_refresh_at_ms: row.get(3),
+            last_error: row.get(4),
+        })
     }
 
     pub(super) async fn list_kiro_route_candidate_rows(
@@ -162,7 +171,9 @@ impl PostgresControlRepository {
                  ''),
                     NULLIF(COALESCE(auth_json ->> 'proxyMode', auth_json ->> 'proxy_mode'), ''),
                     NULLIF(COALESCE(auth_json ->> 'proxyConfigId', auth_json ->> \
-                 'proxy_config_id'), '')
+                 'proxy_config_id'), ''),
+                    NULLIF(COALESCE(auth_json ->> 'proxyPoolId', auth_json ->> 'proxy_pool_id'), \
+                 '')
                  FROM llm_kiro_accounts
                  ORDER BY account_name",
                 &[],
@@ -191,6 +202,7 @@ impl PostgresControlRepository {
                 ),
                 proxy_mode: row.get(13),
                 auth_proxy_config_id: row.get(14),
+                proxy_pool_id: row.get(15),
             })
             .collect())
     }
@@ -248,7 +260,9 @@ impl PostgresControlRepository {
                  ''),
                     NULLIF(COALESCE(auth_json ->> 'proxyMode', auth_json ->> 'proxy_mode'), ''),
                     NULLIF(COALESCE(auth_json ->> 'proxyConfigId', auth_json ->> \
-                 'proxy_config_id'), '')
+                 'proxy_config_id'), ''),
+                    NULLIF(COALESCE(auth_json ->> 'proxyPoolId', auth_json ->> 'proxy_pool_id'), \
+                 '')
                  FROM llm_kiro_accounts
                  WHERE account_name = ANY($1)
                  ORDER BY account_name",
@@ -278,6 +292,7 @@ impl PostgresControlRepository {
                 ),
                 proxy_mode: row.get(13),
                 auth_proxy_config_id: row.get(14),
+                proxy_pool_id: row.get(15),
             })
             .collect())
     }
@@ -490,6 +505,54 @@ impl PostgresControlRepository {
         }
         Ok(resolved)
     }
+
+    /// Merge the key's account-group transform policy (when the group still
+    /// exists for this provider) with the key's own policy and serialize the
+    /// result for the request snapshot.
+    pub(super) async fn resolve_request_transform_policy_json(
+        &self,
+        provider_type: &str,
+        route: &KeyRouteConfig,
+    ) -> anyhow::Result<Option<String>> {
+        let group_policy = match route.account_group_id.as_deref() {
+            Some(group_id) => self
+                .get_admin_account_group_row(group_id)
+                .await?
+                .filter(|group| group.provider_type == provider_type)
+                .and_then(|group| group.request_transform_policy),
+            None => None,
+        };
+        let key_policy = core_store::decode_request_transform_policy(
+            route.request_transform_policy_json.as_deref(),
+        );
+        core_store::RequestTransformPolicy::merged(group_policy.as_ref(), key_policy.as_ref())
+            .map(|policy| serde_json::to_string(&policy))
+            .transpose()
+            .context("serialize merged request transform policy")
+    }
+
+    /// Overlay the key's queue policy on its account group's (when the group
+    /// still exists for this provider) and serialize the result for the
+    /// request snapshot.
+    pub(super) async fn resolve_queue_policy_json(
+        &self,
+        provider_type: &str,
+        route: &KeyRouteConfig,
+    ) -> anyhow::Result<Option<String>> {
+        let group_policy = match route.account_group_id.as_deref() {
+            Some(group_id) => self
+                .get_admin_account_group_row(group_id)
+                .await?
+                .filter(|group| group.provider_type == provider_type)
+                .and_then(|group| group.queue_policy),
+            None => None,
+        };
+        let key_policy = core_store::decode_key_queue_policy(route.queue_policy_json.as_deref());
+        core_store::KeyQueuePolicy::merged(group_policy.as_ref(), key_policy.as_ref())
+            .map(|policy| serde_json::to_string(&policy))
+            .transpose()
+            .context("serialize merged queue policy")
+    }
 }
 #[async_trait]
 impl ProviderRouteStore for PostgresControlRepository {
@@ -805,9 +868,18 @@ impl ProviderRouteStore for PostgresControlRepository {
         let binding = self
             .load_admin_proxy_binding_from_configs(core_store::PROVIDER_KIRO, &proxy_configs_by_id)
             .await?;
+        // Channels cannot reference pools directly; only an inherited pool
+        // binding needs the member health.
+        let proxy_pools = if binding.bound_proxy_pool_id.is_some() {
+            self.load_proxy_pool_context(core_store::PROVIDER_KIRO)
+                .await?
+        } else {
+            ProxyPoolContext::default()
+        };
         let proxy_context = ProviderProxyResolutionContext {
             proxy_configs_by_id,
             binding,
+            proxy_pools,
         };
         let mut routes = Vec::new();
         for row in self
@@ -822,8 +894,12 @@ impl ProviderRouteStore for PostgresControlRepository {
                 continue;
             };
             let proxy = match resolve_provider_proxy_config_from_context(
-                &row.proxy_mode,
-                row.proxy_config_id.as_deref(),
+                AccountProxyRef {
+                    proxy_mode: &row.proxy_mode,
+                    proxy_config_id: row.proxy_config_id.as_deref(),
+                    proxy_pool_id: None,
+                    sticky_key: &row.channel_name,
+                },
                 &proxy_context,
             ) {
                 Ok(proxy) => proxy,
@@ -874,6 +950,156 @@ impl ProviderRouteStore for PostgresControlRepository {
         )))
     }
 
+    async fn resolve_openai_upstream_resolution(
+        &self,
+        key: &AuthenticatedKey,
+    ) -> anyhow::Result<ProviderOpenAiUpstreamResolution> {
+        let Some(snapshot) = self.load_codex_request_snapshot_cached(&key.key_id).await? else {
+            return Ok(ProviderOpenAiUpstreamResolution::disabled());
+        };
+        if snapshot.key.provider_type != core_store::PROVIDER_CODEX {
+            return Ok(ProviderOpenAiUpstreamResolution::disabled());
+        }
+        let pool_mode = core_store::canonical_openai_upstream_pool_mode(Some(
+            &snapshot.openai_upstream_pool_mode,
+        ));
+        if pool_mode == core_store::OPENAI_UPSTREAM_POOL_MODE_DISABLED {
+            return Ok(ProviderOpenAiUpstreamResolution {
+                pool_mode,
+                routes: Vec::new(),
+            });
+        }
+        let proxy_context = self
+            .load_provider_proxy_resolution_context(core_store::PROVIDER_CODEX)
+            .await?;
+        let mut routes = Vec::new();
+        for row in self
+            .load_active_openai_upstream_channel_rows_cached()
+            .await?
+        {
+            if row.status != core_store::KEY_STATUS_ACTIVE || row.weight <= 0 {
+                continue;
+            }
+            let proxy = match resolve_provider_proxy_config_from_context(
+                AccountProxyRef {
+                    proxy_mode: &row.proxy_mode,
+                    proxy_config_id: row.proxy_config_id.as_deref(),
+                    proxy_pool_id: None,
+                    sticky_key: &row.channel_name,
+                },
+                &proxy_context,
+            ) {
+                Ok(proxy) => proxy,
+                Err(err) => {
+                    tracing::warn!(
+                        channel = %row.channel_name,
+                        error = %err,
+                        "openai upstream channel proxy resolution failed; skipping channel"
+                    );
+                    continue;
+                },
+            };
+            routes.push(ProviderOpenAiUpstreamRoute {
+                channel_name: row.channel_name,
+                pool_mode_at_event: pool_mode.clone(),
+                account_group_id_at_event: snapshot.account_group_id_at_event.clone(),
+                base_url: row.base_url,
+                api_key: row.api_key.unwrap_or_default(),
+                weight: row.weight.max(0) as u64,
+                request_max_concurrency: snapshot.request_max_concurrency,
+                request_min_start_interval_ms: snapshot.request_min_start_interval_ms,
+                channel_max_concurrency: row.max_concurrency.max(1) as u64,
+                channel_min_start_interval_ms: row.min_start_interval_ms.max(0) as u64,
+                models: row.model_ids,
+                model_name_map_json: snapshot.model_name_map_json.clone(),
+                proxy,
+            });
+        }
+        Ok(ProviderOpenAiUpstreamResolution {
+            pool_mode,
+            routes,
+        })
+    }
+
+    async fn resolve_response_cache_policy(
+        &self,
+        key: &AuthenticatedKey,
+    ) -> anyhow::Result<Option<core_store::KeyResponseCachePolicy>> {
+        let raw = match key.provider_type.as_str() {
+            core_store::PROVIDER_CODEX => self
+                .load_codex_request_snapshot_cached(&key.key_id)
+                .await?
+                .and_then(|snapshot| snapshot.response_cache_policy_json),
+            core_store::PROVIDER_KIRO => self
+                .load_kiro_request_snapshot_cached(&key.key_id)
+                .await?
+                .and_then(|snapshot| snapshot.response_cache_policy_json),
+            _ => None,
+        };
+        Ok(core_store::enabled_response_cache_policy(raw.as_deref()))
+    }
+
+    async fn resolve_request_transform_policy(
+        &self,
+        key: &AuthenticatedKey,
+    ) -> anyhow::Result<Option<core_store::RequestTransformPolicy>> {
+        let raw = match key.provider_type.as_str() {
+            core_store::PROVIDER_CODEX => self
+                .load_codex_request_snapshot_cached(&key.key_id)
+                .await?
+                .and_then(|snapshot| snapshot.request_transform_policy_json),
+            core_store::PROVIDER_KIRO => self
+                .load_kiro_request_snapshot_cached(&key.key_id)
+                .await?
+                .and_then(|snapshot| snapshot.request_transform_policy_json),
+            _ => None,
+        };
+        Ok(core_store::decode_request_transform_policy(raw.as_deref()))
+    }
+
+    async fn resolve_moderation_policy(
+        &self,
+        key: &AuthenticatedKey,
+    ) -> anyhow::Result<Option<core_store::KeyModerationPolicy>> {
+        let raw = match key.provider_type.as_str() {
+            core_store::PROVIDER_CODEX => self
+                .load_codex_request_snapshot_cached(&key.key_id)
+                .await?
+                .and_then(|snapshot| snapshot.moderation_policy_json),
+            core_store::PROVIDER_KIRO => self
+                .load_kiro_request_snapshot_cached(&key.key_id)
+                .await?
+                .and_then(|snapshot| snapshot.moderation_policy_json),
+            _ => None,
+        };
+        Ok(core_store::decode_key_moderation_policy(raw.as_deref()))
+    }
+
+    async fn resolve_queue_policy(
+        &self,
+        key: &AuthenticatedKey,
+    ) -> anyhow::Result<Option<core_store::KeyQueuePolicy>> {
+        let raw = match key.provider_type.as_str() {
+            core_store::PROVIDER_CODEX => self
+                .load_codex_request_snapshot_cached(&key.key_id)
+                .await?
+                .and_then(|snapshot| snapshot.queue_policy_json),
+            core_store::PROVIDER_KIRO => self
+                .load_kiro_request_snapshot_cached(&key.key_id)
+                .await?
+                .and_then(|snapshot| snapshot.queue_policy_json),
+            _ => None,
+        };
+        Ok(core_store::decode_key_queue_policy(raw.as_deref()))
+    }
+
+    async fn record_moderation_event(
+        &self,
+        event: core_store::NewModerationEvent,
+    ) -> anyhow::Result<()> {
+        self.insert_moderation_event(&event).await
+    }
+
     async fn resolve_kiro_account_route(
         &self,
         account_name: &str,
@@ -1062,4 +1288,14 @@ impl ProviderRouteStore for PostgresControlRepository {
     ) -> anyhow::Result<()> {
         self.save_admin_kiro_status_cache(update).await
     }
+
+    async fn report_proxy_transport_failure(
+        &self,
+        provider_type: &str,
+        account_name: &str,
+        error_message: &str,
+    ) -> anyhow::Result<()> {
+        self.record_live_proxy_transport_failure(provider_type, account_name, error_message)
+            .await
+    }
 }
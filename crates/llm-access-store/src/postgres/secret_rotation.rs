@@ -0,0 +1,248 @@
+//! Online re-encryption of stored secrets under the active master key.
+//!
+//! Each row is resealed with a compare-and-swap `UPDATE ... WHERE <column> =
+//! <value read>`, so the service can keep serving and writing while a
+//! rotation runs: a row changed underneath the scan is left alone (it was
+//! just written under the active key anyway) and counted as a conflict.
+//! Resealing also moves legacy envelopes onto row-bound ones.
+
+use anyhow::Context;
+
+use super::{
+    anthropic_upstream::ANTHROPIC_UPSTREAM_CHANNELS_TABLE,
+    openai_upstream::OPENAI_UPSTREAM_CHANNELS_TABLE,
+    proxy::{proxy_override_secret_id, PROXY_OVERRIDE_SECRET_TABLE},
+    PostgresControlRepository,
+};
+use crate::secrets::SecretRow;
+
+/// Outcome of one [`PostgresControlRepository::rotate_secrets`] pass.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SecretRotationReport {
+    /// Master key every rotated value is now sealed with.
+    pub active_key_id: String,
+    /// API key secrets resealed.
+    pub keys: usize,
+    /// Proxy config and per-node override passwords resealed.
+    pub proxy_passwords: usize,
+    /// Codex accounts whose auth tokens were resealed.
+    pub codex_accounts: usize,
+    /// Kiro accounts whose auth tokens were resealed.
+    pub kiro_accounts: usize,
+    /// Upstream channels whose API keys were resealed.
+    pub upstream_channels: usize,
+    /// Rows skipped because they changed between read and update.
+    pub conflicts: usize,
+}
+
+impl SecretRotationReport {
+    /// Total number of resealed values.
+    pub fn rotated(&self) -> usize {
+        self.keys
+            + self.proxy_passwords
+            + self.codex_accounts
+            + self.kiro_accounts
+            + self.upstream_channels
+    }
+}
+
+impl PostgresControlRepository {
+    /// Reseal every stored secret that is plaintext or wrapped by a retired
+    /// master key. Safe to run while the service is live and to re-run after
+    /// an interruption.
+    pub async fn rotate_secrets(&self) -> anyhow::Result<SecretRotationReport> {
+        let active_key_id = self.secrets.active_key_id().with_context(|| {
+            format!(
+                "secret rotation needs a master key file; set {}",
+                crate::secrets::SECRET_KEY_FILE_ENV
+            )
+        })?;
+        let mut report = SecretRotationReport {
+            active_key_id,
+            ..SecretRotationReport::default()
+        };
+        self.rotate_key_secrets(&mut report).await?;
+        self.rotate_proxy_passwords(&mut report).await?;
+        self.rotate_auth_json(
+            "llm_codex_accounts",
+            "account_name",
+            &mut report.codex_accounts,
+            &mut report.conflicts,
+        )
+        .await?;
+        self.rotate_auth_json(
+            "llm_kiro_accounts",
+            "account_name",
+            &mut report.kiro_accounts,
+            &mut report.conflicts,
+        )
+        .await?;
+        self.rotate_auth_json(
+            ANTHROPIC_UPSTREAM_CHANNELS_TABLE,
+            "channel_name",
+            &mut report.upstream_channels,
+            &mut report.conflicts,
+        )
+        .await?;
+        self.rotate_auth_json(
+            OPENAI_UPSTREAM_CHANNELS_TABLE,
+            "channel_name",
+            &mut report.upstream_channels,
+            &mut report.conflicts,
+        )
+        .await?;
+        Ok(report)
+    }
+
+    async fn rotate_key_secrets(&self, report: &mut SecretRotationReport) -> anyhow::Result<()> {
+        self.ensure_connection_alive()?;
+        let rows = self
+            .client
+            .query("SELECT key_id, secret FROM llm_keys", &[])
+            .await
+            .context("scan llm key secrets for rotation")?;
+        for row in rows {
+            let key_id: String = row.get(0);
+            let stored: String = row.get(1);
+            if !self.secrets.needs_rotation(&stored) {
+                continue;
+            }
+            let resealed = self.reseal(SecretRow::new("llm_keys", &key_id), &stored)?;
+            let updated = self
+                .client
+                .execute("UPDATE llm_keys SET secret = $2 WHERE key_id = $1 AND secret = $3", &[
+                    &key_id, &resealed, &stored,
+                ])
+                .await
+                .with_context(|| format!("reseal secret of key `{key_id}`"))?;
+            count_update(updated, &mut report.keys, &mut report.conflicts);
+        }
+        Ok(())
+    }
+
+    async fn rotate_proxy_passwords(
+        &self,
+        report: &mut SecretRotationReport,
+    ) -> anyhow::Result<()> {
+        self.ensure_connection_alive()?;
+        let rows = self
+            .client
+            .query(
+                "SELECT proxy_config_id, proxy_password
+                 FROM llm_proxy_configs
+                 WHERE proxy_password IS NOT NULL",
+                &[],
+            )
+            .await
+            .context("scan proxy config passwords for rotation")?;
+        for row in rows {
+            let proxy_id: String = row.get(0);
+            let stored: String = row.get(1);
+            if !self.secrets.needs_rotation(&stored) {
+                continue;
+            }
+            let resealed = self.reseal(SecretRow::new("llm_proxy_configs", &proxy_id), &stored)?;
+            let updated = self
+                .client
+                .execute(
+                    "UPDATE llm_proxy_configs SET proxy_password = $2
+                     WHERE proxy_config_id = $1 AND proxy_password = $3",
+                    &[&proxy_id, &resealed, &stored],
+                )
+                .await
+                .with_context(|| format!("reseal password of proxy config `{proxy_id}`"))?;
+            count_update(updated, &mut report.proxy_passwords, &mut report.conflicts);
+        }
+
+        let rows = self
+            .client
+            .query(
+                "SELECT proxy_config_id, node_id, proxy_password
+                 FROM llm_proxy_config_node_overrides
+                 WHERE proxy_password IS NOT NULL",
+                &[],
+            )
+            .await
+            .context("scan proxy config node override passwords for rotation")?;
+        for row in rows {
+            let proxy_id: String = row.get(0);
+            let node_id: String = row.get(1);
+            let stored: String = row.get(2);
+            if !self.secrets.needs_rotation(&stored) {
+                continue;
+            }
+            let secret_id = proxy_override_secret_id(&proxy_id, &node_id);
+            let resealed =
+                self.reseal(SecretRow::new(PROXY_OVERRIDE_SECRET_TABLE, &secret_id), &stored)?;
+            let updated = self
+                .client
+                .execute(
+                    "UPDATE llm_proxy_config_node_overrides SET proxy_password = $3
+                     WHERE proxy_config_id = $1 AND node_id = $2 AND proxy_password = $4",
+                    &[&proxy_id, &node_id, &resealed, &stored],
+                )
+                .await
+                .with_context(|| {
+                    format!("reseal password of proxy config `{proxy_id}` on node `{node_id}`")
+                })?;
+            count_update(updated, &mut report.proxy_passwords, &mut report.conflicts);
+        }
+        Ok(())
+    }
+
+    /// Reseal the `auth_json` column of every row in `table`, whose primary
+    /// key is `key_column`.
+    async fn rotate_auth_json(
+        &self,
+        table: &'static str,
+        key_column: &'static str,
+        rotated: &mut usize,
+        conflicts: &mut usize,
+    ) -> anyhow::Result<()> {
+        self.ensure_connection_alive()?;
+        let rows = self
+            .client
+            .query(&format!("SELECT {key_column}, auth_json::text FROM {table}"), &[])
+            .await
+            .with_context(|| format!("scan {table} auth json for rotation"))?;
+        for row in rows {
+            let row_id: String = row.get(0);
+            let stored: String = row.get(1);
+            if !self.secrets.auth_json_needs_rotation(&stored) {
+                continue;
+            }
+            let secret_row = SecretRow::new(table, &row_id);
+            let resealed = self
+                .secrets
+                .open_auth_json(secret_row, &stored)
+                .and_then(|auth_json| self.secrets.seal_auth_json(secret_row, &auth_json))
+                .with_context(|| format!("reseal auth json of `{row_id}` in {table}"))?;
+            let updated = self
+                .client
+                .execute(
+                    &format!(
+                        "UPDATE {table} SET auth_json = $2::jsonb
+                         WHERE {key_column} = $1 AND auth_json = $3::jsonb"
+                    ),
+                    &[&row_id, &resealed, &stored],
+                )
+                .await
+                .with_context(|| format!("update resealed auth json of `{row_id}` in {table}"))?;
+            count_update(updated, rotated, conflicts);
+        }
+        Ok(())
+    }
+
+    fn reseal(&self, row: SecretRow<'_>, stored: &str) -> anyhow::Result<String> {
+        let plaintext = self.secrets.open(row, stored)?;
+        self.secrets.seal(row, &plaintext)
+    }
+}
+
+fn count_update(updated: u64, rotated: &mut usize, conflicts: &mut usize) {
+    if updated == 0 {
+        *conflicts += 1;
+    } else {
+        *rotated += 1;
+    }
+}
@@ -0,0 +1,217 @@
+//! Delegated sub-key reads/writes + the `SubKeyStore` impl.
+//!
+//! Minting locks the parent row with `FOR NO KEY UPDATE` so concurrent mints
+//! for one parent serialize on the quota check, and writes the child's key,
+//! route and rollup rows in that same transaction so the lock is released
+//! only once the child is committed.
+
+use anyhow::Context;
+use async_trait::async_trait;
+use llm_access_core::store::{
+    self as core_store, delegable_sub_key_quota, AdminKey, AdminKeyPatch, AdminKeyStore, NewSubKey,
+    SubKeyQuotaExceeded, SubKeyStore, MAX_SUB_KEYS_PER_PARENT,
+};
+use sqlx_core::{query::query, row::Row};
+
+use super::{decode::admin_key_from_bundle, now_ms, PostgresControlRepository};
+use crate::records::{KeyRecord, KeyRouteConfig, KeyUsageRollup};
+
+impl PostgresControlRepository {
+    pub(super) async fn load_sub_key_ids(
+        &self,
+        parent_key_id: &str,
+    ) -> anyhow::Result<Vec<String>> {
+        self.ensure_connection_alive()?;
+        let rows = self
+            .client
+            .query(
+                "SELECT key_id
+                 FROM llm_keys
+                 WHERE parent_key_id = $1
+                 ORDER BY created_at_ms ASC, key_id ASC",
+                &[&parent_key_id],
+            )
+            .await
+            .context("load postgres sub-key ids")?;
+        Ok(rows
+            .into_iter()
+            .map(|row| row.get::<_, String>(0))
+            .collect())
+    }
+
+    /// Sub-keys of any of `parent_key_ids`.
+    pub(super) async fn load_sub_key_ids_of_parents(
+        &self,
+        parent_key_ids: &[String],
+    ) -> anyhow::Result<Vec<String>> {
+        if parent_key_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.ensure_connection_alive()?;
+        let rows = self
+            .client
+            .query(
+                "SELECT key_id
+                 FROM llm_keys
+                 WHERE parent_key_id = ANY($1)",
+                &[&parent_key_ids],
+            )
+            .await
+            .context("load postgres sub-key ids of parents")?;
+        Ok(rows
+            .into_iter()
+            .map(|row| row.get::<_, String>(0))
+            .collect())
+    }
+}
+
+#[async_trait]
+impl SubKeyStore for PostgresControlRepository {
+    async fn list_sub_keys(&self, parent_key_id: &str) -> anyhow::Result<Vec<AdminKey>> {
+        let mut keys = Vec::new();
+        for key_id in self.load_sub_key_ids(parent_key_id).await? {
+            if let Some(bundle) = self.load_key_bundle_by_id(&key_id).await? {
+                keys.push(admin_key_from_bundle(&bundle));
+            }
+        }
+        Ok(keys)
+    }
+
+    async fn create_sub_key(
+        &self,
+        parent_key_id: &str,
+        key: NewSubKey,
+    ) -> anyhow::Result<AdminKey> {
+        let parent = self
+            .load_key_bundle_by_id(parent_key_id)
+            .await?
+            .context("parent key not found")?;
+        if parent.key.parent_key_id.is_some() {
+            anyhow::bail!("sub-keys cannot mint further sub-keys");
+        }
+        let mut tx = self
+            .client
+            .pool
+            .begin()
+            .await
+            .context("begin postgres sub-key transaction")?;
+        let parent_row = query(
+            "SELECT
+                LEAST(
+                    k.quota_billable_limit,
+                    COALESCE(s.quota_billable_cap, k.quota_billable_limit)
+                ) - COALESCE(u.billable_tokens, 0)
+             FROM llm_keys k
+             LEFT JOIN llm_key_usage_rollups u ON u.key_id = k.key_id
+             LEFT JOIN llm_key_portal_settings s ON s.key_id = k.key_id
+             WHERE k.key_id = $1
+             FOR NO KEY UPDATE OF k",
+        )
+        .bind(parent_key_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .context("lock postgres parent key")?
+        .context("parent key not found")?;
+        let parent_remaining: i64 = parent_row
+            .try_get(0)
+            .context("decode parent remaining billable")?;
+        let children_row = query(
+            "SELECT
+                COUNT(*),
+                COALESCE(SUM(GREATEST(
+                    c.quota_billable_limit - COALESCE(u.billable_tokens, 0),
+                    0
+                )) FILTER (WHERE c.status = $2), 0)::BIGINT
+             FROM llm_keys c
+             LEFT JOIN llm_key_usage_rollups u ON u.key_id = c.key_id
+             WHERE c.parent_key_id = $1",
+        )
+        .bind(parent_key_id)
+        .bind(core_store::KEY_STATUS_ACTIVE)
+        .fetch_one(&mut *tx)
+        .await
+        .context("load postgres sub-key allocations")?;
+        let child_count: i64 = children_row.try_get(0).context("decode sub-key count")?;
+        let children_remaining: i64 = children_row
+            .try_get(1)
+            .context("decode sub-key remaining billable")?;
+        if usize::try_from(child_count).unwrap_or(usize::MAX) >= MAX_SUB_KEYS_PER_PARENT {
+            anyhow::bail!("parent key already has {MAX_SUB_KEYS_PER_PARENT} sub-keys");
+        }
+        let available = delegable_sub_key_quota(parent_remaining, children_remaining);
+        if key.quota_billable_limit > available {
+            return Err(SubKeyQuotaExceeded {
+                requested: key.quota_billable_limit,
+                available,
+            }
+            .into());
+        }
+
+        let key_record = KeyRecord {
+            key_id: key.id.clone(),
+            name: key.name.clone(),
+            secret: key.secret.clone(),
+            key_hash: key.key_hash.clone(),
+            status: core_store::KEY_STATUS_ACTIVE.to_string(),
+            provider_type: parent.key.provider_type.clone(),
+            protocol_family: parent.key.protocol_family.clone(),
+            public_visible: false,
+            quota_billable_limit: key.quota_billable_limit as i64,
+            parent_key_id: Some(parent_key_id.to_string()),
+            created_at_ms: key.created_at_ms,
+            updated_at_ms: key.created_at_ms,
+        };
+        let route = KeyRouteConfig {
+            key_id: key.id.clone(),
+            request_max_concurrency: key.request_max_concurrency.map(|value| value as i64),
+            request_min_start_interval_ms: key
+                .request_min_start_interval_ms
+                .map(|value| value as i64),
+            ..parent.route.clone()
+        };
+        let rollup = KeyUsageRollup {
+            key_id: key.id.clone(),
+            input_uncached_tokens: 0,
+            input_cached_tokens: 0,
+            output_tokens: 0,
+            billable_tokens: 0,
+            credit_total: 0.0,
+            credit_missing_events: 0,
+            codex_image_usage_tokens: 0,
+            codex_image_usage_missing_events: 0,
+            codex_image_last_used_at_ms: None,
+            last_used_at_ms: None,
+            updated_at_ms: key.created_at_ms,
+        };
+        self.upsert_key_bundle_on(&mut tx, &key_record, &route, &rollup)
+            .await?;
+        tx.commit()
+            .await
+            .context("commit postgres sub-key transaction")?;
+        self.bump_dispatch_generation(&key_record.provider_type)
+            .await;
+        self.load_key_bundle_by_id(&key.id)
+            .await?
+            .map(|bundle| admin_key_from_bundle(&bundle))
+            .context("created postgres sub-key disappeared")
+    }
+
+    async fn revoke_sub_key(
+        &self,
+        parent_key_id: &str,
+        key_id: &str,
+    ) -> anyhow::Result<Option<AdminKey>> {
+        let Some(bundle) = self.load_key_bundle_by_id(key_id).await? else {
+            return Ok(None);
+        };
+        if bundle.key.parent_key_id.as_deref() != Some(parent_key_id) {
+            return Ok(None);
+        }
+        self.patch_admin_key(key_id, AdminKeyPatch {
+            status: Some(core_store::KEY_STATUS_DISABLED.to_string()),
+            updated_at_ms: now_ms(),
+            ..AdminKeyPatch::default()
+        })
+        .await
+    }
+}
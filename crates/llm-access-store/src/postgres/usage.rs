@@ -17,8 +17,12 @@ use sqlx_core::{query::query, query_builder::QueryBuilder, row::Row};
 use sqlx_postgres::Postgres;
 
 use super::{
-    aggregate_usage_rollup_deltas, decode::decode_codex_account_settings,
-    json::optional_json_string_any, now_ms, PostgresControlRepository, UsageProxyAttribution,
+    aggregate_usage_rollup_deltas,
+    decode::decode_codex_account_settings,
+    json::optional_json_string_any,
+    now_ms,
+    proxy_support::{account_proxy_config, codex_account_proxy_ref, AccountProxyRef},
+    with_parent_rollup_deltas, PostgresControlRepository, UsageProxyAttribution,
     USAGE_ROLLUP_BATCH_ROW_LIMIT,
 };
 
@@ -197,12 +201,25 @@ impl PostgresControlRepository {
         };
         let settings = decode_codex_account_settings(&record.settings_json)?;
         let context = self.load_codex_admin_account_view_context().await?;
-        let (proxy_source, proxy_url, proxy_config_name) =
-            self.resolve_codex_account_proxy_view_with_context(&settings, &context);
-        let proxy_config_id = match settings.proxy_mode.as_str() {
+        let (proxy_source, proxy_url, proxy_config_name) = self
+            .resolve_codex_account_proxy_view_with_context(
+                &record.account_name,
+                &settings,
+                &context,
+            );
+        let proxy_config_id = account_proxy_config(
+            codex_account_proxy_ref(&record.account_name, &settings),
+            &context.proxy_configs_by_id,
+            &context.codex_proxy_binding,
+            &context.proxy_pools,
+        )
+        .ok()
+        .flatten()
+        .map(|proxy| proxy.id.clone())
+        .or_else(|| match settings.proxy_mode.as_str() {
             "fixed" => settings.proxy_config_id.clone(),
             _ => context.codex_proxy_binding.bound_proxy_config_id.clone(),
-        };
+        });
         Ok(Some(UsageProxyAttribution {
             provider_type: core_store::PROVIDER_CODEX.to_string(),
             account_name: record.account_name,
@@ -233,17 +250,29 @@ impl PostgresControlRepository {
         let proxy_config_id = record.proxy_config_id.clone().or_else(|| {
             optional_json_string_any(&auth_json, &["proxyConfigId", "proxy_config_id"])
         });
+        let proxy_pool_id = optional_json_string_any(&auth_json, &["proxyPoolId", "proxy_pool_id"]);
+        let reference = AccountProxyRef {
+            proxy_mode: &proxy_mode,
+            proxy_config_id: proxy_config_id.as_deref(),
+            proxy_pool_id: proxy_pool_id.as_deref(),
+            sticky_key: &record.account_name,
+        };
         let context = self.load_kiro_admin_account_view_context().await?;
-        let (proxy_source, proxy_url, proxy_config_name) = self
-            .resolve_kiro_account_proxy_view_with_context(
-                &proxy_mode,
-                proxy_config_id.as_deref(),
-                &context,
-            );
-        let proxy_config_id = match proxy_mode.as_str() {
-            "fixed" => proxy_config_id,
+        let (proxy_source, proxy_url, proxy_config_name) =
+            self.resolve_kiro_account_proxy_view_with_context(reference, &context);
+        let resolved_proxy_config_id = account_proxy_config(
+            reference,
+            &context.proxy_configs_by_id,
+            &context.kiro_proxy_binding,
+            &context.proxy_pools,
+        )
+        .ok()
+        .flatten()
+        .map(|proxy| proxy.id.clone());
+        let proxy_config_id = resolved_proxy_config_id.or_else(|| match proxy_mode.as_str() {
+            "fixed" => proxy_config_id.clone(),
             _ => context.kiro_proxy_binding.bound_proxy_config_id.clone(),
-        };
+        });
         Ok(Some(UsageProxyAttribution {
             provider_type: core_store::PROVIDER_KIRO.to_string(),
             account_name: record.account_name,
@@ -262,7 +291,9 @@ impl PostgresControlRepository {
         if events.is_empty() {
             return Ok(());
         }
-        let deltas = aggregate_usage_rollup_deltas(events)?;
+        let deltas = self
+            .with_parent_rollup_deltas(aggregate_usage_rollup_deltas(events)?)
+            .await?;
         let skipped = self.upsert_usage_rollup_deltas(&deltas).await?;
         if skipped > 0 {
             tracing::warn!(
@@ -271,13 +302,47 @@ impl PostgresControlRepository {
                 "skipped postgres usage rollup deltas for missing keys"
             );
         }
-        let key_ids = deltas
+        self.invalidate_rollup_auth_cache(&deltas).await;
+        Ok(())
+    }
+
+    /// Drop cached auth for every key whose rollup changed. Sub-keys of a
+    /// changed parent go too: their cached entry carries the parent's
+    /// remaining budget, which a sibling's spend just lowered.
+    async fn invalidate_rollup_auth_cache(&self, deltas: &[KeyUsageRollupDelta]) {
+        if self.request_cache.is_none() {
+            return;
+        }
+        let mut key_ids = deltas
             .iter()
             .map(|delta| delta.key_id.clone())
             .collect::<Vec<_>>();
+        match self.load_sub_key_ids_of_parents(&key_ids).await {
+            Ok(sub_key_ids) => key_ids.extend(sub_key_ids),
+            Err(err) => {
+                tracing::warn!(error = %err, "failed to load sub-keys for auth-cache invalidation");
+            },
+        }
+        key_ids.sort();
+        key_ids.dedup();
         self.invalidate_authenticated_key_cache_by_ids(&key_ids)
             .await;
-        Ok(())
+    }
+
+    /// Expand sub-key deltas so their usage also rolls up into the parent.
+    async fn with_parent_rollup_deltas(
+        &self,
+        deltas: Vec<KeyUsageRollupDelta>,
+    ) -> anyhow::Result<Vec<KeyUsageRollupDelta>> {
+        let key_ids = deltas
+            .iter()
+            .map(|delta| delta.key_id.clone())
+            .collect::<Vec<_>>();
+        let parents = self.load_parent_key_ids(&key_ids).await?;
+        if parents.is_empty() {
+            return Ok(deltas);
+        }
+        Ok(with_parent_rollup_deltas(deltas, &parents))
     }
 
     async fn upsert_usage_rollup_deltas(
@@ -484,7 +549,9 @@ impl PostgresControlRepository {
             }
         }
 
-        let deltas = deltas_by_key.into_values().collect::<Vec<_>>();
+        let deltas = self
+            .with_parent_rollup_deltas(deltas_by_key.into_values().collect())
+            .await?;
         let mut affected_rows = 0usize;
         for chunk in deltas.chunks(USAGE_ROLLUP_BATCH_ROW_LIMIT.max(1)) {
             let mut builder = QueryBuilder::<Postgres>::new(
@@ -607,12 +674,7 @@ impl PostgresControlRepository {
             "applied postgres usage rollup batches"
         );
 
-        let key_ids = deltas
-            .iter()
-            .map(|delta| delta.key_id.clone())
-            .collect::<Vec<_>>();
-        self.invalidate_authenticated_key_cache_by_ids(&key_ids)
-            .await;
+        self.invalidate_rollup_auth_cache(&deltas).await;
         Ok(report)
     }
 }
@@ -35,6 +35,8 @@ pub struct KeyRecord {
     pub public_visible: bool,
     /// Billable quota limit.
     pub quota_billable_limit: i64,
+    /// Parent key id when this key is a delegated sub-key.
+    pub parent_key_id: Option<String>,
     /// Creation timestamp in Unix milliseconds.
     pub created_at_ms: i64,
     /// Update timestamp in Unix milliseconds.
@@ -96,6 +98,16 @@ pub struct KeyRouteConfig {
     pub kiro_billable_model_multipliers_override_json: Option<String>,
     /// Direct Anthropic upstream pool mode for Kiro keys.
     pub kiro_anthropic_upstream_pool_mode: String,
+    /// OpenAI-compatible upstream pool mode for Codex keys.
+    pub codex_openai_upstream_pool_mode: String,
+    /// Optional opt-in response cache policy JSON.
+    pub response_cache_policy_json: Option<String>,
+    /// Optional request transform policy JSON.
+    pub request_transform_policy_json: Option<String>,
+    /// Optional content moderation policy JSON.
+    pub moderation_policy_json: Option<String>,
+    /// Optional fair queuing policy JSON.
+    pub queue_policy_json: Option<String>,
 }
 
 /// API key accumulated usage rollup row.
@@ -1,4 +1,4 @@
-use std::{collections::BTreeMap, time::Duration};
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
 
 use anyhow::Context;
 use llm_access_core::store::{
@@ -12,8 +12,13 @@ use redis::Commands;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 
-use crate::records::RuntimeConfigRecord;
+use crate::{
+    records::RuntimeConfigRecord,
+    secrets::{SecretKeyring, SecretRow},
+};
 
+/// [`SecretRow`] table sealed payloads are bound under, keyed by cache key.
+const REQUEST_CACHE_SECRET_TABLE: &str = "request_cache";
 const AUTH_CACHE_TTL: Duration = Duration::from_secs(6 * 60 * 60);
 const RUNTIME_CONFIG_TTL: Duration = Duration::from_secs(6 * 60 * 60);
 const REQUEST_SNAPSHOT_TTL: Duration = Duration::from_secs(6 * 60 * 60);
@@ -23,6 +28,7 @@ const ACCOUNT_PRINCIPAL_TTL: Duration = Duration::from_secs(4 * 60 * 60);
 const CODEX_STATUS_TTL: Duration = Duration::from_secs(4 * 60 * 60);
 const PROXY_METADATA_TTL: Duration = Duration::from_secs(6 * 60 * 60);
 const ANTHROPIC_UPSTREAM_CHANNELS_TTL: Duration = Duration::from_secs(4 * 60 * 60);
+const OPENAI_UPSTREAM_CHANNELS_TTL: Duration = Duration::from_secs(4 * 60 * 60);
 const USAGE_PROXY_ATTRIBUTION_TTL: Duration = Duration::from_secs(30 * 60);
 #[cfg(feature = "duckdb-runtime")]
 const USAGE_CATALOG_LOOKUP_TTL: Duration = Duration::from_secs(15 * 60);
@@ -50,6 +56,14 @@ fn default_anthropic_upstream_pool_mode() -> String {
     llm_access_core::store::default_anthropic_upstream_pool_mode()
 }
 
+fn default_openai_upstream_pool_mode() -> String {
+    llm_access_core::store::default_openai_upstream_pool_mode()
+}
+
+fn default_model_name_map_json() -> String {
+    "{}".to_string()
+}
+
 const fn default_codex_image_generation_max_concurrency() -> u64 {
     llm_access_core::store::DEFAULT_CODEX_IMAGE_GENERATION_MAX_CONCURRENCY
 }
@@ -67,6 +81,7 @@ pub struct RequestCacheConfig {
 pub(crate) struct RequestCache {
     client: redis::Client,
     key_prefix: String,
+    secrets: Arc<SecretKeyring>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -78,6 +93,10 @@ pub(crate) struct CachedAuthenticatedKey {
     pub status: String,
     pub quota_billable_limit: i64,
     pub billable_tokens_used: i64,
+    #[serde(default)]
+    pub parent_remaining_billable: Option<i64>,
+    #[serde(default)]
+    pub parent_key_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -148,6 +167,26 @@ pub(crate) struct CachedCodexRequestSnapshot {
     pub codex_weight_plus: i64,
     pub codex_weight_pro5x: i64,
     pub codex_weight_pro20x: i64,
+    /// Raw per-key response cache policy. Defaulted so older Valkey payloads
+    /// keep caching disabled.
+    #[serde(default)]
+    pub response_cache_policy_json: Option<String>,
+    /// Merged account-group and key request transform policy.
+    #[serde(default)]
+    pub request_transform_policy_json: Option<String>,
+    /// Per-key content moderation policy.
+    #[serde(default)]
+    pub moderation_policy_json: Option<String>,
+    /// Merged account-group and key fair queuing policy.
+    #[serde(default)]
+    pub queue_policy_json: Option<String>,
+    /// Defaulted so Valkey payloads written before OpenAI-compatible routing
+    /// still decode and keep Codex-only behavior.
+    #[serde(default = "default_openai_upstream_pool_mode")]
+    pub openai_upstream_pool_mode: String,
+    /// Public-to-upstream model map applied on OpenAI-compatible channels.
+    #[serde(default = "default_model_name_map_json")]
+    pub model_name_map_json: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -201,6 +240,19 @@ pub(crate) struct CachedKiroRequestSnapshot {
     pub conversation_anchor_ttl_seconds: u64,
     pub billable_model_multipliers_json: String,
     pub status_refresh_interval_seconds: u64,
+    /// Raw per-key response cache policy. Defaulted so older Valkey payloads
+    /// keep caching disabled.
+    #[serde(default)]
+    pub response_cache_policy_json: Option<String>,
+    /// Merged account-group and key request transform policy.
+    #[serde(default)]
+    pub request_transform_policy_json: Option<String>,
+    /// Per-key content moderation policy.
+    #[serde(default)]
+    pub moderation_policy_json: Option<String>,
+    /// Merged account-group and key fair queuing policy.
+    #[serde(default)]
+    pub queue_policy_json: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -406,9 +458,18 @@ impl RequestCache {
         Ok(Self {
             client,
             key_prefix: config.key_prefix,
+            secrets: Arc::new(SecretKeyring::disabled()),
         })
     }
 
+    /// Seal every payload written from now on with `secrets`. Cached
+    /// snapshots embed decrypted account auth and proxy credentials, so the
+    /// whole value is sealed rather than picking out fields.
+    pub(crate) fn with_secrets(mut self, secrets: Arc<SecretKeyring>) -> Self {
+        self.secrets = secrets;
+        self
+    }
+
     pub(crate) fn auth_key(&self, secret_hash: &str) -> String {
         format!("{}:auth:{secret_hash}", self.key_prefix)
     }
@@ -463,6 +524,10 @@ impl RequestCache {
         format!("{}:anthropic-upstream:scope:{scope}:channels", self.key_prefix)
     }
 
+    pub(crate) fn openai_upstream_channels_key(&self, scope: &str) -> String {
+        format!("{}:openai-upstream:scope:{scope}:channels", self.key_prefix)
+    }
+
     pub(crate) fn usage_proxy_attribution_key(
         &self,
         provider: &str,
@@ -476,6 +541,10 @@ impl RequestCache {
         ANTHROPIC_UPSTREAM_CHANNELS_TTL
     }
 
+    pub(crate) fn openai_upstream_channels_ttl(&self, _scope: &str) -> Duration {
+        OPENAI_UPSTREAM_CHANNELS_TTL
+    }
+
     #[cfg(feature = "duckdb-runtime")]
     pub(crate) fn usage_catalog_generation_key(&self) -> String {
         format!("{}:{}:gen", self.key_prefix, Self::USAGE_CATALOG_CACHE_NAMESPACE)
@@ -648,7 +717,7 @@ impl RequestCache {
             .await
             .with_context(|| format!("redis GET `{key}`"))?;
         value
-            .map(|json| serde_json::from_str(&json).context("decode request cache json"))
+            .map(|json| self.decode_payload(key, &json))
             .transpose()
     }
 
@@ -665,10 +734,11 @@ impl RequestCache {
             .query_async(&mut conn)
             .await
             .context("redis MGET request cache json")?;
-        raw.into_iter()
-            .map(|value| {
+        keys.iter()
+            .zip(raw)
+            .map(|(key, value)| {
                 value
-                    .map(|json| serde_json::from_str(&json).context("decode request cache json"))
+                    .map(|json| self.decode_payload(key, &json))
                     .transpose()
             })
             .collect()
@@ -683,7 +753,7 @@ impl RequestCache {
     where
         T: Serialize,
     {
-        let payload = serde_json::to_string(value).context("encode request cache json")?;
+        let payload = self.encode_payload(key, value)?;
         let ttl_seconds = duration_to_redis_secs(ttl);
         let mut conn = self.connection().await?;
         redis::cmd("SET")
@@ -752,7 +822,7 @@ impl RequestCache {
             .get(key)
             .with_context(|| format!("redis GET `{key}`"))?;
         value
-            .map(|json| serde_json::from_str(&json).context("decode request cache json"))
+            .map(|json| self.decode_payload(key, &json))
             .transpose()
     }
 
@@ -766,7 +836,7 @@ impl RequestCache {
     where
         T: Serialize,
     {
-        let payload = serde_json::to_string(value).context("encode request cache json")?;
+        let payload = self.encode_payload(key, value)?;
         let ttl_seconds = duration_to_redis_secs(ttl);
         let mut conn = self.connection_blocking()?;
         redis::cmd("SET")
@@ -795,6 +865,29 @@ impl RequestCache {
             .with_context(|| format!("redis INCR `{key}`"))
     }
 
+    /// Payloads are bound to their cache key, so a sealed value cannot be
+    /// replayed under another key.
+    fn encode_payload<T>(&self, key: &str, value: &T) -> anyhow::Result<String>
+    where
+        T: Serialize,
+    {
+        let json = serde_json::to_string(value).context("encode request cache json")?;
+        self.secrets
+            .seal(SecretRow::new(REQUEST_CACHE_SECRET_TABLE, key), &json)
+            .context("seal request cache payload")
+    }
+
+    fn decode_payload<T>(&self, key: &str, payload: &str) -> anyhow::Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        let json = self
+            .secrets
+            .open(SecretRow::new(REQUEST_CACHE_SECRET_TABLE, key), payload)
+            .context("open sealed request cache payload")?;
+        serde_json::from_str(&json).context("decode request cache json")
+    }
+
     async fn connection(&self) -> anyhow::Result<redis::aio::MultiplexedConnection> {
         self.client
             .get_multiplexed_async_connection()
@@ -823,6 +916,14 @@ pub(crate) fn deterministic_jitter_ttl(
     Duration::from_secs_f64(base.as_secs_f64() * ratio)
 }
 
+/// `ttl` shortened to end by `deadline_ms`, or `None` when less than a second
+/// is left and the value should not be cached at all.
+pub(crate) fn ttl_capped_at(ttl: Duration, deadline_ms: i64, now_ms: i64) -> Option<Duration> {
+    let remaining_ms = u64::try_from(deadline_ms.saturating_sub(now_ms)).ok()?;
+    let remaining = Duration::from_millis(remaining_ms);
+    (remaining >= Duration::from_secs(1)).then(|| ttl.min(remaining))
+}
+
 fn duration_to_redis_secs(ttl: Duration) -> u64 {
     ttl.as_secs().max(1)
 }
@@ -840,6 +941,8 @@ mod tests {
             status: "active".to_string(),
             quota_billable_limit: 1000,
             billable_tokens_used: 10,
+            parent_remaining_billable: None,
+            parent_key_id: None,
         }
     }
 
@@ -860,6 +963,18 @@ mod tests {
         assert_eq!(a, b);
     }
 
+    #[test]
+    fn ttl_capped_at_never_outlives_the_deadline() {
+        let ttl = Duration::from_secs(6 * 3600);
+        assert_eq!(
+            super::ttl_capped_at(ttl, 1_000_000 + 90_000, 1_000_000),
+            Some(Duration::from_secs(90))
+        );
+        assert_eq!(super::ttl_capped_at(ttl, 1_000_000 + 7 * 3600 * 1_000, 1_000_000), Some(ttl));
+        assert_eq!(super::ttl_capped_at(ttl, 1_000_500, 1_000_000), None);
+        assert_eq!(super::ttl_capped_at(ttl, 999_000, 1_000_000), None);
+    }
+
     #[test]
     fn deterministic_jitter_differs_for_different_keys() {
         let a = super::deterministic_jitter_ttl(
@@ -896,6 +1011,12 @@ mod tests {
             codex_weight_plus: 2,
             codex_weight_pro5x: 3,
             codex_weight_pro20x: 4,
+            response_cache_policy_json: None,
+            request_transform_policy_json: None,
+            moderation_policy_json: None,
+            queue_policy_json: None,
+            openai_upstream_pool_mode: "only".to_string(),
+            model_name_map_json: "{}".to_string(),
         };
 
         let encoded = serde_json::to_string(&snapshot).expect("encode snapshot");
@@ -970,6 +1091,10 @@ mod tests {
             cache.anthropic_upstream_channels_key("edge-a"),
             "llma:test:anthropic-upstream:scope:edge-a:channels".to_string()
         );
+        assert_eq!(
+            cache.openai_upstream_channels_key("edge-a"),
+            "llma:test:openai-upstream:scope:edge-a:channels".to_string()
+        );
         assert_eq!(
             cache.usage_proxy_attribution_key("codex", "acct-1", "edge-a"),
             "llma:test:usage:proxy:scope:edge-a:codex:acct-1".to_string()
@@ -1010,6 +1135,8 @@ mod tests {
             status: "active".to_string(),
             quota_billable_limit: 1234,
             billable_tokens_used: 321,
+            parent_remaining_billable: None,
+            parent_key_id: None,
         };
 
         let json = serde_json::to_string(&payload).expect("serialize auth payload");
@@ -0,0 +1,630 @@
+//! Envelope encryption for upstream credentials held by the control plane.
+//!
+//! Every sealed value gets its own random data key. The value is encrypted
+//! under that data key with AES-256-GCM, and the data key is wrapped under a
+//! master key loaded from a local key file, so Postgres rows and Valkey
+//! payloads only ever hold ciphertext plus the id of the master key that
+//! wrapped it:
+//!
+//! ```text
+//! enc:v2:<key_id>:<base64url(nonce || wrapped data key)>:<base64url(nonce || ciphertext)>
+//! ```
+//!
+//! The ciphertext is authenticated against the [`SecretRow`] it belongs to
+//! (table plus primary key), so a sealed value copied into another row fails
+//! to open instead of silently handing that row someone else's credential.
+//!
+//! Values without an `enc:` prefix are legacy plaintext and open as-is, and
+//! `enc:v1:` values (bound only to the key id) still open; both stay readable
+//! until `llm-access rotate-secrets` reseals them as `enc:v2:`.
+//!
+//! The key file holds one `key_id:base64-key` line per 32-byte master key;
+//! blank lines and `#` comments are ignored. The last key listed is the
+//! active one used for new writes; earlier keys are only kept to open values
+//! they wrapped. Rotating means appending a new line on every node, then
+//! running `rotate-secrets` once. Nodes that meet a key id they do not know
+//! re-read the file, so the new key can be rolled out without a restart.
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    path::{Path, PathBuf},
+    sync::RwLock,
+};
+
+use anyhow::{anyhow, Context};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use ring::{
+    aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN},
+    rand::{SecureRandom, SystemRandom},
+};
+
+/// Env var naming the local master key file. Unset disables encryption.
+pub const SECRET_KEY_FILE_ENV: &str = "LLM_ACCESS_SECRET_KEY_FILE";
+
+const ENVELOPE_PREFIX: &str = "enc:v2:";
+const LEGACY_ENVELOPE_PREFIX: &str = "enc:v1:";
+const MASTER_KEY_LEN: usize = 32;
+
+/// Fields inside provider `auth_json` documents that carry credentials.
+/// Everything else (region, profile ARN, pool settings, ...) stays in
+/// plaintext because SQL filters and admin listings read it directly.
+const AUTH_JSON_SECRET_FIELDS: &[&str] = &[
+    "access_token",
+    "accessToken",
+    "refresh_token",
+    "refreshToken",
+    "id_token",
+    "idToken",
+    "client_secret",
+    "clientSecret",
+    "proxy_password",
+    "proxyPassword",
+    "api_key",
+    "apiKey",
+];
+
+/// The row a sealed value is stored in. Its table and primary key are bound
+/// into the envelope as associated data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SecretRow<'a> {
+    /// Table (or cache namespace) holding the value.
+    pub table: &'a str,
+    /// Primary key of the row; callers join composite keys themselves.
+    pub id: &'a str,
+}
+
+impl<'a> SecretRow<'a> {
+    /// Identity of row `id` in `table`.
+    pub fn new(table: &'a str, id: &'a str) -> Self {
+        Self {
+            table,
+            id,
+        }
+    }
+
+    fn aad(self, key_id: &str) -> Vec<u8> {
+        [key_id, self.table, self.id].join("\0").into_bytes()
+    }
+}
+
+#[derive(Default)]
+struct MasterKeys {
+    keys: BTreeMap<String, [u8; MASTER_KEY_LEN]>,
+    active: Option<String>,
+}
+
+/// Master keys used to seal and open control-plane secrets.
+///
+/// A keyring without keys is a pass-through: `seal` stores plaintext and
+/// `open` only accepts plaintext, which is the pre-encryption behavior.
+pub struct SecretKeyring {
+    path: Option<PathBuf>,
+    keys: RwLock<MasterKeys>,
+}
+
+impl fmt::Debug for SecretKeyring {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let keys = self.read_keys();
+        f.debug_struct("SecretKeyring")
+            .field("path", &self.path)
+            .field("key_ids", &keys.keys.keys().collect::<Vec<_>>())
+            .field("active", &keys.active)
+            .finish()
+    }
+}
+
+impl Default for SecretKeyring {
+    fn default() -> Self {
+        Self::disabled()
+    }
+}
+
+impl SecretKeyring {
+    /// Keyring that stores and reads plaintext.
+    pub fn disabled() -> Self {
+        Self {
+            path: None,
+            keys: RwLock::new(MasterKeys::default()),
+        }
+    }
+
+    /// Load the key file named by [`SECRET_KEY_FILE_ENV`], or return a
+    /// disabled keyring when the variable is unset.
+    pub fn from_env() -> anyhow::Result<Self> {
+        match std::env::var(SECRET_KEY_FILE_ENV) {
+            Ok(path) if !path.trim().is_empty() => Self::load_file(path.trim()),
+            _ => Ok(Self::disabled()),
+        }
+    }
+
+    /// Load master keys from `path`.
+    pub fn load_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let keys = read_key_file(&path)?;
+        Ok(Self {
+            path: Some(path),
+            keys: RwLock::new(keys),
+        })
+    }
+
+    /// Build a keyring from key file contents, without a backing file.
+    pub fn from_key_file_contents(contents: &str) -> anyhow::Result<Self> {
+        Ok(Self {
+            path: None,
+            keys: RwLock::new(parse_key_file(contents)?),
+        })
+    }
+
+    /// Whether new writes are sealed.
+    pub fn is_enabled(&self) -> bool {
+        self.read_keys().active.is_some()
+    }
+
+    /// Id of the master key used for new writes.
+    pub fn active_key_id(&self) -> Option<String> {
+        self.read_keys().active.clone()
+    }
+
+    /// Seal `plaintext` for `row` under the active master key.
+    /// Already-sealed values are returned unchanged so a value that
+    /// round-trips through a record is never wrapped twice.
+    pub fn seal(&self, row: SecretRow<'_>, plaintext: &str) -> anyhow::Result<String> {
+        if is_sealed(plaintext) {
+            return Ok(plaintext.to_string());
+        }
+        let keys = self.read_keys();
+        let Some(key_id) = keys.active.as_deref() else {
+            return Ok(plaintext.to_string());
+        };
+        let master = keys
+            .keys
+            .get(key_id)
+            .ok_or_else(|| anyhow!("active secret key `{key_id}` is missing"))?;
+        seal_with_master_key(key_id, master, &row.aad(key_id), plaintext.as_bytes())
+    }
+
+    /// Open a value stored in `row`. Plaintext passes through unchanged.
+    pub fn open(&self, row: SecretRow<'_>, stored: &str) -> anyhow::Result<String> {
+        let (envelope, legacy) = match stored.strip_prefix(ENVELOPE_PREFIX) {
+            Some(envelope) => (envelope, false),
+            None => match stored.strip_prefix(LEGACY_ENVELOPE_PREFIX) {
+                Some(envelope) => (envelope, true),
+                None => return Ok(stored.to_string()),
+            },
+        };
+        let mut parts = envelope.splitn(3, ':');
+        let (Some(key_id), Some(wrapped_key), Some(ciphertext)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            anyhow::bail!("malformed sealed secret");
+        };
+        if !self.read_keys().keys.contains_key(key_id) {
+            self.reload_for_unknown_key(key_id)?;
+        }
+        let keys = self.read_keys();
+        let master = keys
+            .keys
+            .get(key_id)
+            .ok_or_else(|| anyhow!("secret sealed with unknown key `{key_id}`"))?;
+        let data_key = decrypt(master, key_id.as_bytes(), &decode_part(wrapped_key)?)
+            .with_context(|| format!("unwrap data key sealed with `{key_id}`"))?;
+        let data_key: [u8; MASTER_KEY_LEN] = data_key
+            .try_into()
+            .map_err(|_| anyhow!("wrapped data key has the wrong length"))?;
+        let aad = if legacy { key_id.as_bytes().to_vec() } else { row.aad(key_id) };
+        let plaintext = decrypt(&data_key, &aad, &decode_part(ciphertext)?).with_context(|| {
+            format!("decrypt secret of {}/{} sealed with `{key_id}`", row.table, row.id)
+        })?;
+        String::from_utf8(plaintext).context("sealed secret is not utf-8")
+    }
+
+    /// [`Self::seal`] for nullable columns.
+    pub fn seal_optional(
+        &self,
+        row: SecretRow<'_>,
+        plaintext: Option<&str>,
+    ) -> anyhow::Result<Option<String>> {
+        plaintext.map(|value| self.seal(row, value)).transpose()
+    }
+
+    /// [`Self::open`] for nullable columns.
+    pub fn open_optional(
+        &self,
+        row: SecretRow<'_>,
+        stored: Option<&str>,
+    ) -> anyhow::Result<Option<String>> {
+        stored.map(|value| self.open(row, value)).transpose()
+    }
+
+    /// Seal the credential fields of a provider `auth_json` document stored
+    /// in `row`, leaving every other field readable.
+    pub fn seal_auth_json(&self, row: SecretRow<'_>, auth_json: &str) -> anyhow::Result<String> {
+        if !self.is_enabled() {
+            return Ok(auth_json.to_string());
+        }
+        let mut value = serde_json::from_str::<serde_json::Value>(auth_json)
+            .context("decode auth json before sealing")?;
+        map_auth_json_secrets(&mut value, &mut |secret| self.seal(row, secret))?;
+        serde_json::to_string(&value).context("encode sealed auth json")
+    }
+
+    /// Open the credential fields of a provider `auth_json` document stored
+    /// in `row`.
+    pub fn open_auth_json(&self, row: SecretRow<'_>, auth_json: &str) -> anyhow::Result<String> {
+        if !auth_json.contains(ENVELOPE_PREFIX) && !auth_json.contains(LEGACY_ENVELOPE_PREFIX) {
+            return Ok(auth_json.to_string());
+        }
+        let mut value = serde_json::from_str::<serde_json::Value>(auth_json)
+            .context("decode sealed auth json")?;
+        map_auth_json_secrets(&mut value, &mut |secret| self.open(row, secret))?;
+        serde_json::to_string(&value).context("encode opened auth json")
+    }
+
+    /// Whether `stored` should be resealed: it is plaintext, a legacy
+    /// envelope not bound to its row, or wrapped by a key other than the
+    /// active one.
+    pub fn needs_rotation(&self, stored: &str) -> bool {
+        let Some(active) = self.active_key_id() else {
+            return false;
+        };
+        sealed_key_id(stored) != Some(active.as_str())
+    }
+
+    /// [`Self::needs_rotation`] over the credential fields of `auth_json`.
+    pub fn auth_json_needs_rotation(&self, auth_json: &str) -> bool {
+        let Ok(mut value) = serde_json::from_str::<serde_json::Value>(auth_json) else {
+            return false;
+        };
+        let mut stale = false;
+        let _ = map_auth_json_secrets(&mut value, &mut |secret| {
+            stale |= self.needs_rotation(secret);
+            Ok(secret.to_string())
+        });
+        stale
+    }
+
+    fn reload_for_unknown_key(&self, key_id: &str) -> anyhow::Result<()> {
+        let Some(path) = self.path.as_ref() else {
+            return Ok(());
+        };
+        let keys = read_key_file(path)?;
+        if keys.keys.contains_key(key_id) {
+            tracing::info!(key_id, path = %path.display(), "reloaded secret key file");
+        }
+        *self
+            .keys
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = keys;
+        Ok(())
+    }
+
+    fn read_keys(&self) -> std::sync::RwLockReadGuard<'_, MasterKeys> {
+        self.keys
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Whether `value` is an envelope produced by [`SecretKeyring::seal`].
+pub fn is_sealed(value: &str) -> bool {
+    value.starts_with(ENVELOPE_PREFIX) || value.starts_with(LEGACY_ENVELOPE_PREFIX)
+}
+
+/// Produce a fresh `key_id:base64-key` line for the master key file.
+pub fn generate_master_key_line(key_id: &str) -> anyhow::Result<String> {
+    validate_key_id(key_id)?;
+    let mut key = [0u8; MASTER_KEY_LEN];
+    SystemRandom::new()
+        .fill(&mut key)
+        .map_err(|_| anyhow!("generate master key"))?;
+    Ok(format!("{key_id}:{}", URL_SAFE_NO_PAD.encode(key)))
+}
+
+fn sealed_key_id(value: &str) -> Option<&str> {
+    value
+        .strip_prefix(ENVELOPE_PREFIX)
+        .and_then(|envelope| envelope.split(':').next())
+}
+
+fn map_auth_json_secrets(
+    value: &mut serde_json::Value,
+    f: &mut dyn FnMut(&str) -> anyhow::Result<String>,
+) -> anyhow::Result<()> {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (field, entry) in map.iter_mut() {
+                match entry {
+                    serde_json::Value::String(secret)
+                        if AUTH_JSON_SECRET_FIELDS.contains(&field.as_str())
+                            && !secret.is_empty() =>
+                    {
+                        *secret = f(secret)
+                            .with_context(|| format!("transform auth json field `{field}`"))?;
+                    },
+                    _ => map_auth_json_secrets(entry, f)?,
+                }
+            }
+        },
+        serde_json::Value::Array(items) => {
+            for item in items {
+                map_auth_json_secrets(item, f)?;
+            }
+        },
+        _ => {},
+    }
+    Ok(())
+}
+
+fn read_key_file(path: &Path) -> anyhow::Result<MasterKeys> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("read secret key file `{}`", path.display()))?;
+    parse_key_file(&contents).with_context(|| format!("parse secret key file `{}`", path.display()))
+}
+
+fn parse_key_file(contents: &str) -> anyhow::Result<MasterKeys> {
+    let mut keys = MasterKeys::default();
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (key_id, encoded) = line
+            .split_once(':')
+            .ok_or_else(|| anyhow!("line {}: expected `key_id:base64-key`", index + 1))?;
+        let key_id = key_id.trim();
+        validate_key_id(key_id).with_context(|| format!("line {}", index + 1))?;
+        let key: [u8; MASTER_KEY_LEN] = decode_part(encoded.trim())
+            .with_context(|| format!("line {}", index + 1))?
+            .try_into()
+            .map_err(|_| {
+                anyhow!("line {}: master key must be {MASTER_KEY_LEN} bytes", index + 1)
+            })?;
+        anyhow::ensure!(
+            keys.keys.insert(key_id.to_string(), key).is_none(),
+            "line {}: duplicate key id `{key_id}`",
+            index + 1
+        );
+        keys.active = Some(key_id.to_string());
+    }
+    Ok(keys)
+}
+
+fn validate_key_id(key_id: &str) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        !key_id.is_empty()
+            && key_id
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '.')),
+        "key id `{key_id}` must be non-empty ASCII letters, digits, `-`, `_` or `.`"
+    );
+    Ok(())
+}
+
+fn seal_with_master_key(
+    key_id: &str,
+    master: &[u8; MASTER_KEY_LEN],
+    aad: &[u8],
+    plaintext: &[u8],
+) -> anyhow::Result<String> {
+    let mut data_key = [0u8; MASTER_KEY_LEN];
+    SystemRandom::new()
+        .fill(&mut data_key)
+        .map_err(|_| anyhow!("generate data key"))?;
+    let wrapped_key = encrypt(master, key_id.as_bytes(), &data_key)?;
+    let ciphertext = encrypt(&data_key, aad, plaintext)?;
+    Ok(format!(
+        "{ENVELOPE_PREFIX}{key_id}:{}:{}",
+        URL_SAFE_NO_PAD.encode(wrapped_key),
+        URL_SAFE_NO_PAD.encode(ciphertext)
+    ))
+}
+
+fn aead_key(key: &[u8; MASTER_KEY_LEN]) -> anyhow::Result<LessSafeKey> {
+    UnboundKey::new(&AES_256_GCM, key)
+        .map(LessSafeKey::new)
+        .map_err(|_| anyhow!("build AES-256-GCM key"))
+}
+
+/// Encrypt `data`, returning `nonce || ciphertext || tag`.
+fn encrypt(key: &[u8; MASTER_KEY_LEN], aad: &[u8], data: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let mut nonce = [0u8; NONCE_LEN];
+    SystemRandom::new()
+        .fill(&mut nonce)
+        .map_err(|_| anyhow!("generate nonce"))?;
+    let mut sealed = data.to_vec();
+    aead_key(key)?
+        .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::from(aad), &mut sealed)
+        .map_err(|_| anyhow!("AES-256-GCM seal"))?;
+    let mut out = Vec::with_capacity(NONCE_LEN + sealed.len());
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&sealed);
+    Ok(out)
+}
+
+fn decrypt(key: &[u8; MASTER_KEY_LEN], aad: &[u8], data: &[u8]) -> anyhow::Result<Vec<u8>> {
+    anyhow::ensure!(data.len() > NONCE_LEN, "sealed payload is truncated");
+    let (nonce, sealed) = data.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| anyhow!("invalid nonce"))?;
+    let mut buffer = sealed.to_vec();
+    let plaintext_len = aead_key(key)?
+        .open_in_place(nonce, Aad::from(aad), &mut buffer)
+        .map_err(|_| anyhow!("AES-256-GCM open failed; wrong key or tampered value"))?
+        .len();
+    buffer.truncate(plaintext_len);
+    Ok(buffer)
+}
+
+fn decode_part(encoded: &str) -> anyhow::Result<Vec<u8>> {
+    URL_SAFE_NO_PAD
+        .decode(encoded)
+        .context("decode base64url secret part")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        generate_master_key_line, is_sealed, seal_with_master_key, SecretKeyring, SecretRow,
+    };
+
+    const ROW: SecretRow<'static> = SecretRow {
+        table: "llm_keys",
+        id: "key-1",
+    };
+
+    fn keyring(key_ids: &[&str]) -> (SecretKeyring, String) {
+        let contents = key_ids
+            .iter()
+            .map(|key_id| generate_master_key_line(key_id).expect("generate key"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        (SecretKeyring::from_key_file_contents(&contents).expect("parse keys"), contents)
+    }
+
+    #[test]
+    fn sealed_values_round_trip_and_carry_the_key_id() {
+        let (keyring, _) = keyring(&["2026-10"]);
+
+        let sealed = keyring.seal(ROW, "sk-upstream").expect("seal");
+        assert!(sealed.starts_with("enc:v2:2026-10:"));
+        assert!(!sealed.contains("sk-upstream"));
+        assert_ne!(sealed, keyring.seal(ROW, "sk-upstream").expect("seal again"));
+        assert_eq!(keyring.open(ROW, &sealed).expect("open"), "sk-upstream");
+        assert_eq!(keyring.seal(ROW, &sealed).expect("reseal"), sealed);
+    }
+
+    #[test]
+    fn sealed_values_only_open_in_their_own_row() {
+        let (keyring, _) = keyring(&["k1"]);
+        let sealed = keyring.seal(ROW, "sk-upstream").expect("seal");
+
+        assert!(keyring
+            .open(SecretRow::new("llm_keys", "key-2"), &sealed)
+            .is_err());
+        assert!(keyring
+            .open(SecretRow::new("llm_proxy_configs", "key-1"), &sealed)
+            .is_err());
+        assert_eq!(keyring.open(ROW, &sealed).expect("open"), "sk-upstream");
+    }
+
+    #[test]
+    fn legacy_v1_values_open_anywhere_and_need_rotation() {
+        let (keyring, _) = keyring(&["k1"]);
+        let master = *keyring.read_keys().keys.get("k1").expect("master key");
+        let legacy = seal_with_master_key("k1", &master, b"k1", b"secret")
+            .expect("seal legacy")
+            .replacen("enc:v2:", "enc:v1:", 1);
+
+        assert!(is_sealed(&legacy));
+        assert_eq!(keyring.open(ROW, &legacy).expect("open legacy"), "secret");
+        assert!(keyring.needs_rotation(&legacy));
+        assert!(!keyring.needs_rotation(&keyring.seal(ROW, "secret").expect("seal")));
+    }
+
+    #[test]
+    fn plaintext_passes_through_and_disabled_keyring_stores_plaintext() {
+        let (keyring, _) = keyring(&["k1"]);
+        assert_eq!(keyring.open(ROW, "legacy-secret").expect("open plaintext"), "legacy-secret");
+
+        let disabled = SecretKeyring::disabled();
+        assert_eq!(disabled.seal(ROW, "secret").expect("seal"), "secret");
+        let sealed = keyring.seal(ROW, "secret").expect("seal");
+        assert!(disabled.open(ROW, &sealed).is_err());
+    }
+
+    #[test]
+    fn tampered_values_fail_to_open() {
+        let (keyring, _) = keyring(&["k1"]);
+        let mut sealed = keyring.seal(ROW, "secret").expect("seal");
+        let last = sealed.pop().expect("non-empty");
+        sealed.push(if last == 'A' { 'B' } else { 'A' });
+        assert!(keyring.open(ROW, &sealed).is_err());
+    }
+
+    #[test]
+    fn rotation_reseals_under_the_newest_key() {
+        let (old, old_contents) = keyring(&["old"]);
+        let sealed_old = old.seal(ROW, "secret").expect("seal old");
+
+        let contents = format!(
+            "# retired keys stay listed\n{old_contents}\n{}\n",
+            generate_master_key_line("new").expect("generate key")
+        );
+        let rotated = SecretKeyring::from_key_file_contents(&contents).expect("parse keys");
+        assert_eq!(rotated.active_key_id().as_deref(), Some("new"));
+        assert!(rotated.needs_rotation(&sealed_old));
+        assert!(rotated.needs_rotation("plaintext"));
+
+        let resealed = rotated
+            .seal(ROW, &rotated.open(ROW, &sealed_old).expect("open old"))
+            .expect("reseal");
+        assert!(!rotated.needs_rotation(&resealed));
+        assert_eq!(rotated.open(ROW, &resealed).expect("open new"), "secret");
+    }
+
+    #[test]
+    fn auth_json_seals_only_credential_fields() {
+        let (keyring, _) = keyring(&["k1"]);
+        let row = SecretRow::new("llm_codex_accounts", "acct-a");
+        let auth_json = r#"{"refreshToken":"rt","region":"us-east-1",
+            "tokens":{"access_token":"at","account_id":"acct"}}"#;
+
+        let sealed = keyring
+            .seal_auth_json(row, auth_json)
+            .expect("seal auth json");
+        let value: serde_json::Value = serde_json::from_str(&sealed).expect("sealed json");
+        assert!(is_sealed(value["refreshToken"].as_str().expect("refresh token")));
+        assert!(is_sealed(
+            value["tokens"]["access_token"]
+                .as_str()
+                .expect("access token")
+        ));
+        assert_eq!(value["region"], "us-east-1");
+        assert_eq!(value["tokens"]["account_id"], "acct");
+        assert!(!keyring.auth_json_needs_rotation(&sealed));
+        assert!(keyring.auth_json_needs_rotation(auth_json));
+
+        let opened: serde_json::Value = serde_json::from_str(
+            &keyring
+                .open_auth_json(row, &sealed)
+                .expect("open auth json"),
+        )
+        .expect("opened json");
+        assert_eq!(opened["refreshToken"], "rt");
+        assert_eq!(opened["tokens"]["access_token"], "at");
+    }
+
+    #[test]
+    fn auth_json_seals_upstream_api_keys() {
+        let (keyring, _) = keyring(&["k1"]);
+        let row = SecretRow::new("llm_anthropic_upstream_channels", "anthropic-a");
+
+        let sealed = keyring
+            .seal_auth_json(row, r#"{"api_key":"sk-ant","nested":{"apiKey":"sk-oai"}}"#)
+            .expect("seal auth json");
+        let value: serde_json::Value = serde_json::from_str(&sealed).expect("sealed json");
+        assert!(is_sealed(value["api_key"].as_str().expect("api key")));
+        assert!(is_sealed(value["nested"]["apiKey"].as_str().expect("nested api key")));
+
+        let opened: serde_json::Value = serde_json::from_str(
+            &keyring
+                .open_auth_json(row, &sealed)
+                .expect("open auth json"),
+        )
+        .expect("opened json");
+        assert_eq!(opened["api_key"], "sk-ant");
+        assert_eq!(opened["nested"]["apiKey"], "sk-oai");
+    }
+
+    #[test]
+    fn key_file_rejects_bad_lines() {
+        assert!(SecretKeyring::from_key_file_contents("no-separator").is_err());
+        assert!(SecretKeyring::from_key_file_contents("k1:c2hvcnQ").is_err());
+        assert!(SecretKeyring::from_key_file_contents("bad id:AAAA").is_err());
+        let line = generate_master_key_line("k1").expect("generate key");
+        assert!(SecretKeyring::from_key_file_contents(&format!("{line}\n{line}")).is_err());
+        assert!(!SecretKeyring::from_key_file_contents("# empty\n")
+            .expect("comments only")
+            .is_enabled());
+    }
+}
@@ -0,0 +1,273 @@
+//! Rank-based byte-pair encoder over tiktoken-format vocabularies.
+//!
+//! The vocabularies under `assets/` are the published `cl100k_base` and
+//! `o200k_base` rank files, gzip-compressed to keep the repository small.
+//! They are decoded lazily on first use and cached for the lifetime of the
+//! process.
+
+use std::{cmp::Reverse, collections::BinaryHeap, io::Read, sync::OnceLock};
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use fancy_regex::Regex;
+use flate2::read::GzDecoder;
+use rustc_hash::FxHashMap;
+
+type Rank = u32;
+
+/// Longest pretokenized piece merged as a whole. Longer pieces (long runs of
+/// letters or symbols) are merged in chunks of this size; real vocabulary
+/// tokens are far shorter, so this changes counts by at most one token per
+/// chunk boundary.
+const MAX_BPE_PIECE_BYTES: usize = 512;
+/// Input counted exactly by [`Encoding::count`]. Counting runs inline on the
+/// request path, so the tail of larger inputs is extrapolated from the
+/// token density of this prefix.
+const MAX_EXACT_COUNT_BYTES: usize = 256 * 1024;
+
+static CL100K_BASE_GZ: &[u8] = include_bytes!("../assets/cl100k_base.tiktoken.gz");
+static O200K_BASE_GZ: &[u8] = include_bytes!("../assets/o200k_base.tiktoken.gz");
+
+const CL100K_PATTERN: &str = concat!(
+    r"(?i:'s|'t|'re|'ve|'m|'ll|'d)|[^\r\n\p{L}\p{N}]?\p{L}+|\p{N}{1,3}",
+    r"| ?[^\s\p{L}\p{N}]+[\r\n]*|\s*[\r\n]+|\s+(?!\S)|\s+",
+);
+
+const O200K_PATTERN: &str = concat!(
+    r"[^\r\n\p{L}\p{N}]?[\p{Lu}\p{Lt}\p{Lm}\p{Lo}\p{M}]*[\p{Ll}\p{Lm}\p{Lo}\p{M}]+",
+    r"(?i:'s|'t|'re|'ve|'m|'ll|'d)?",
+    r"|[^\r\n\p{L}\p{N}]?[\p{Lu}\p{Lt}\p{Lm}\p{Lo}\p{M}]+[\p{Ll}\p{Lm}\p{Lo}\p{M}]*",
+    r"(?i:'s|'t|'re|'ve|'m|'ll|'d)?",
+    r"|\p{N}{1,3}| ?[^\s\p{L}\p{N}]+[\r\n/]*|\s*[\r\n]+|\s+(?!\S)|\s+",
+);
+
+/// Byte-level BPE vocabularies embedded in this crate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    /// The 100k-token vocabulary used by GPT-4 and GPT-3.5 chat models.
+    Cl100kBase,
+    /// The 200k-token vocabulary used by GPT-4o, GPT-4.1, GPT-5 and the
+    /// o-series reasoning models.
+    O200kBase,
+}
+
+impl Encoding {
+    /// Stable name of the vocabulary, matching the upstream file name.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Cl100kBase => "cl100k_base",
+            Self::O200kBase => "o200k_base",
+        }
+    }
+
+    /// Count the BPE tokens of `text`, treating special-token markers as
+    /// ordinary text. Inputs over 256 KiB are counted exactly up to that size
+    /// and extrapolated for the rest.
+    pub fn count(self, text: &str) -> usize {
+        if text.is_empty() {
+            return 0;
+        }
+        match self.vocab() {
+            Some(vocab) if text.len() > MAX_EXACT_COUNT_BYTES => {
+                let mut end = MAX_EXACT_COUNT_BYTES;
+                while !text.is_char_boundary(end) {
+                    end -= 1;
+                }
+                let prefix_tokens = vocab.count(&text[..end]);
+                (prefix_tokens as u128 * text.len() as u128).div_ceil(end as u128) as usize
+            },
+            Some(vocab) => vocab.count(text),
+            // The assets are compiled in, so this only happens if they were
+            // corrupted at build time; degrade to a byte heuristic rather
+            // than failing request accounting.
+            None => text.len().div_ceil(4),
+        }
+    }
+
+    /// Encode `text` into vocabulary ranks. Returns `None` when the embedded
+    /// vocabulary could not be loaded.
+    pub fn encode(self, text: &str) -> Option<Vec<u32>> {
+        self.vocab().map(|vocab| vocab.encode(text))
+    }
+
+    fn vocab(self) -> Option<&'static BpeVocab> {
+        static CL100K: OnceLock<Option<BpeVocab>> = OnceLock::new();
+        static O200K: OnceLock<Option<BpeVocab>> = OnceLock::new();
+        match self {
+            Self::Cl100kBase => CL100K
+                .get_or_init(|| BpeVocab::from_gzip(CL100K_BASE_GZ, CL100K_PATTERN))
+                .as_ref(),
+            Self::O200kBase => O200K
+                .get_or_init(|| BpeVocab::from_gzip(O200K_BASE_GZ, O200K_PATTERN))
+                .as_ref(),
+        }
+    }
+}
+
+struct BpeVocab {
+    ranks: FxHashMap<Vec<u8>, Rank>,
+    pattern: Regex,
+}
+
+impl BpeVocab {
+    fn from_gzip(compressed: &[u8], pattern: &str) -> Option<Self> {
+        let mut raw = String::new();
+        GzDecoder::new(compressed).read_to_string(&mut raw).ok()?;
+        let mut ranks = FxHashMap::default();
+        ranks.reserve(raw.len() / 12);
+        for line in raw.lines() {
+            let (token, rank) = line.split_once(' ')?;
+            ranks.insert(STANDARD.decode(token).ok()?, rank.parse().ok()?);
+        }
+        Some(Self {
+            ranks,
+            pattern: Regex::new(pattern).ok()?,
+        })
+    }
+
+    fn count(&self, text: &str) -> usize {
+        let mut total = 0;
+        self.for_each_piece(text, |piece| {
+            total += if self.ranks.contains_key(piece) {
+                1
+            } else {
+                byte_pair_merge(&self.ranks, piece).len() - 1
+            };
+        });
+        total
+    }
+
+    fn encode(&self, text: &str) -> Vec<Rank> {
+        let mut tokens = Vec::new();
+        self.for_each_piece(text, |piece| {
+            if let Some(rank) = self.ranks.get(piece) {
+                tokens.push(*rank);
+                return;
+            }
+            let boundaries = byte_pair_merge(&self.ranks, piece);
+            tokens.extend(boundaries.windows(2).map(|window| {
+                self.ranks
+                    .get(&piece[window[0]..window[1]])
+                    .copied()
+                    .unwrap_or(Rank::MAX)
+            }));
+        });
+        tokens
+    }
+
+    fn for_each_piece(&self, text: &str, mut visit: impl FnMut(&[u8])) {
+        let mut consumed = 0;
+        for found in self.pattern.find_iter(text) {
+            let Ok(found) = found else {
+                break;
+            };
+            for chunk in found.as_str().as_bytes().chunks(MAX_BPE_PIECE_BYTES) {
+                visit(chunk);
+            }
+            consumed = found.end();
+        }
+        // The pretokenizer only fails when fancy-regex hits its backtrack
+        // limit on pathological input; fall back to one byte per token for
+        // the remainder so the estimate stays an upper bound.
+        if consumed < text.len() && !text[consumed..].trim().is_empty() {
+            for byte in text.as_bytes()[consumed..].chunks(1) {
+                visit(byte);
+            }
+        }
+    }
+}
+
+/// Merge adjacent byte ranges of `piece` by ascending rank, leftmost first,
+/// until no mergeable pair remains. Returns the token boundaries, including a
+/// trailing sentinel at `piece.len()`.
+///
+/// Parts form a linked list keyed by their start offset and candidate pairs
+/// sit in a min-heap, so a piece of `n` bytes merges in `O(n log n)`. Heap
+/// entries whose pair has since changed are skipped when popped.
+fn byte_pair_merge(ranks: &FxHashMap<Vec<u8>, Rank>, piece: &[u8]) -> Vec<usize> {
+    let len = piece.len();
+    // `next[start]` is the end of the part beginning at `start`.
+    let mut next = (1..=len).collect::<Vec<_>>();
+    let mut prev = (0..len)
+        .map(|start| start.checked_sub(1))
+        .collect::<Vec<_>>();
+    let mut merged = vec![false; len];
+    let pair_rank = |next: &[usize], start: usize| {
+        let mid = next[start];
+        if mid >= len {
+            return Rank::MAX;
+        }
+        ranks
+            .get(&piece[start..next[mid]])
+            .copied()
+            .unwrap_or(Rank::MAX)
+    };
+    let mut rank_at = (0..len)
+        .map(|start| pair_rank(&next, start))
+        .collect::<Vec<_>>();
+    let mut heap = rank_at
+        .iter()
+        .enumerate()
+        .filter(|(_, rank)| **rank != Rank::MAX)
+        .map(|(start, rank)| Reverse((*rank, start)))
+        .collect::<BinaryHeap<_>>();
+
+    while let Some(Reverse((rank, start))) = heap.pop() {
+        if merged[start] || rank_at[start] != rank {
+            continue;
+        }
+        let removed = next[start];
+        merged[removed] = true;
+        next[start] = next[removed];
+        if next[start] < len {
+            prev[next[start]] = Some(start);
+        }
+        rank_at[start] = pair_rank(&next, start);
+        if rank_at[start] != Rank::MAX {
+            heap.push(Reverse((rank_at[start], start)));
+        }
+        if let Some(before) = prev[start] {
+            rank_at[before] = pair_rank(&next, before);
+            if rank_at[before] != Rank::MAX {
+                heap.push(Reverse((rank_at[before], before)));
+            }
+        }
+    }
+
+    let mut boundaries = Vec::new();
+    let mut start = 0;
+    while start < len {
+        boundaries.push(start);
+        start = next[start];
+    }
+    boundaries.push(len);
+    boundaries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Encoding, MAX_BPE_PIECE_BYTES};
+
+    #[test]
+    fn long_letter_runs_are_merged_in_bounded_pieces() {
+        let text = "abcdefghij".repeat(8_192);
+        let vocab = Encoding::O200kBase.vocab().expect("o200k vocabulary loads");
+        let mut pieces = 0;
+        vocab.for_each_piece(&text, |piece| {
+            assert!(piece.len() <= MAX_BPE_PIECE_BYTES, "piece of {} bytes", piece.len());
+            pieces += 1;
+        });
+        // The whole run is one pretokenizer match, so it only ever reaches the
+        // merge loop as capped chunks.
+        assert_eq!(pieces, text.len().div_ceil(MAX_BPE_PIECE_BYTES));
+        assert!(Encoding::O200kBase.count(&text) > 0);
+    }
+
+    #[test]
+    fn oversized_inputs_are_extrapolated() {
+        let sample = "lorem ipsum dolor sit amet ".repeat(200);
+        let text = sample.repeat(200);
+        let estimate = Encoding::Cl100kBase.count(&text);
+        let exact = Encoding::Cl100kBase.count(&sample) * 200;
+        assert!(estimate.abs_diff(exact) * 100 <= exact, "{estimate} vs {exact}");
+    }
+}
@@ -0,0 +1,346 @@
+//! Costing for Anthropic message content blocks.
+//!
+//! Blocks arrive as raw JSON so that every provider adapter can share the
+//! same accounting regardless of how it models requests. Framing overheads
+//! are approximations of the chat templates; the token counts of the block
+//! payloads themselves are exact for the selected vocabulary.
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde_json::Value;
+
+use crate::{
+    family::TokenizerFamily,
+    image::{
+        anthropic_image_tokens, image_dimensions, openai_image_tokens, ANTHROPIC_MAX_IMAGE_TOKENS,
+        OPENAI_DEFAULT_IMAGE_TOKENS,
+    },
+    TokenCounter,
+};
+
+/// Fixed framing per request (start-of-conversation and reply priming).
+const REQUEST_OVERHEAD_TOKENS: u64 = 3;
+/// Role header and separators around every message.
+const MESSAGE_OVERHEAD_TOKENS: u64 = 3;
+/// Wrapper around each tool definition in the rendered tool list.
+const TOOL_DEFINITION_OVERHEAD_TOKENS: u64 = 5;
+/// Anthropic injects a tool-use system prompt whenever tools are present;
+/// this is the documented size for `tool_choice` `auto`/`none`.
+const CLAUDE_TOOL_PROMPT_TOKENS: u64 = 346;
+/// Namespace framing OpenAI renders around function definitions.
+const OPENAI_TOOL_PROMPT_TOKENS: u64 = 12;
+/// Extracted text per PDF page; the upstream docs quote 1,500-3,000.
+const PDF_PAGE_TEXT_TOKENS: u64 = 2_250;
+/// Leading base64 characters decoded to sniff PNG/GIF/WebP headers.
+const IMAGE_HEADER_BASE64_CHARS: usize = 64;
+/// Fields of unknown block types that never hold model-visible text.
+const OPAQUE_BLOCK_FIELDS: &[&str] =
+    &["type", "data", "signature", "encrypted_content", "cache_control", "media_type"];
+/// Shortest unbroken base64-like string treated as an opaque payload.
+const OPAQUE_PAYLOAD_MIN_CHARS: usize = 256;
+
+impl TokenCounter {
+    /// Fixed per-request framing tokens.
+    pub fn request_overhead(&self) -> u64 {
+        REQUEST_OVERHEAD_TOKENS
+    }
+
+    /// Tokens for one conversation message: its content plus role framing.
+    pub fn count_message(&self, content: &Value) -> u64 {
+        MESSAGE_OVERHEAD_TOKENS + self.count_content(content)
+    }
+
+    /// Tokens for a message `content` field, which is either a string or an
+    /// array of content blocks.
+    pub fn count_content(&self, content: &Value) -> u64 {
+        match content {
+            Value::String(text) => self.count_text(text),
+            Value::Array(blocks) => blocks.iter().map(|block| self.count_block(block)).sum(),
+            Value::Object(_) => self.count_block(content),
+            _ => 0,
+        }
+    }
+
+    /// Tokens for a single content block of any type.
+    pub fn count_block(&self, block: &Value) -> u64 {
+        let text_field = |key: &str| block.get(key).and_then(Value::as_str).unwrap_or_default();
+        match block
+            .get("type")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+        {
+            "text" => self.count_text(text_field("text")),
+            "thinking" => self.count_text(text_field("thinking")),
+            // Opaque ciphertext; upstream strips it from prior turns before
+            // counting.
+            "redacted_thinking" => 0,
+            "image" => self.image_block_tokens(block.get("source")),
+            "document" => {
+                self.count_text(text_field("title"))
+                    + self.count_text(text_field("context"))
+                    + self.document_source_tokens(block.get("source"))
+            },
+            "tool_use" | "server_tool_use" => {
+                self.count_text(text_field("id"))
+                    + self.count_text(text_field("name"))
+                    + self.count_json(block.get("input"))
+            },
+            "search_result" => {
+                self.count_text(text_field("title"))
+                    + self.count_text(text_field("source"))
+                    + block
+                        .get("content")
+                        .map_or(0, |content| self.count_content(content))
+            },
+            kind if kind.ends_with("tool_result") => {
+                self.count_text(text_field("tool_use_id"))
+                    + block
+                        .get("content")
+                        .map_or(0, |content| self.count_content(content))
+            },
+            _ => match block.get("text").and_then(Value::as_str) {
+                Some(text) => self.count_text(text),
+                None => self.count_unknown_block(block),
+            },
+        }
+    }
+
+    /// Tokens for one tool definition: name, description and input schema.
+    pub fn count_tool(&self, name: &str, description: &str, input_schema: &Value) -> u64 {
+        TOOL_DEFINITION_OVERHEAD_TOKENS
+            + self.count_text(name)
+            + self.count_text(description)
+            + self.count_json(Some(input_schema))
+    }
+
+    /// Tokens of the system prompt the provider injects when a request
+    /// declares at least one tool.
+    pub fn tool_prompt_overhead(&self) -> u64 {
+        match self.family() {
+            TokenizerFamily::Claude => CLAUDE_TOOL_PROMPT_TOKENS,
+            TokenizerFamily::Gpt4 | TokenizerFamily::Gpt4o => OPENAI_TOOL_PROMPT_TOKENS,
+        }
+    }
+
+    /// Vision tokens for an image of known size.
+    pub fn image_tokens(&self, dims: crate::ImageDimensions) -> u64 {
+        match self.family() {
+            TokenizerFamily::Claude => anthropic_image_tokens(dims),
+            TokenizerFamily::Gpt4 | TokenizerFamily::Gpt4o => openai_image_tokens(dims),
+        }
+    }
+
+    /// Vision tokens assumed for an image whose size cannot be read, such as
+    /// URL sources. Claude uses its per-image cap so the estimate stays an
+    /// upper bound.
+    fn default_image_tokens(&self) -> u64 {
+        match self.family() {
+            TokenizerFamily::Claude => ANTHROPIC_MAX_IMAGE_TOKENS,
+            TokenizerFamily::Gpt4 | TokenizerFamily::Gpt4o => OPENAI_DEFAULT_IMAGE_TOKENS,
+        }
+    }
+
+    fn count_json(&self, value: Option<&Value>) -> u64 {
+        match value {
+            None | Some(Value::Null) => 0,
+            Some(Value::String(text)) => self.count_text(text),
+            Some(value) => self.count_text(&value.to_string()),
+        }
+    }
+
+    /// Tokens for a block type this crate does not model: its readable string
+    /// fields. Opaque payloads such as signatures, encrypted content and
+    /// base64 media are skipped, as with `redacted_thinking`, instead of
+    /// being tokenized as JSON text.
+    fn count_unknown_block(&self, value: &Value) -> u64 {
+        match value {
+            Value::String(text) if looks_like_opaque_payload(text) => 0,
+            Value::String(text) => self.count_text(text),
+            Value::Array(items) => items
+                .iter()
+                .map(|item| self.count_unknown_block(item))
+                .sum(),
+            Value::Object(fields) => fields
+                .iter()
+                .filter(|(key, _)| !OPAQUE_BLOCK_FIELDS.contains(&key.as_str()))
+                .map(|(_, value)| self.count_unknown_block(value))
+                .sum(),
+            _ => 0,
+        }
+    }
+
+    fn image_block_tokens(&self, source: Option<&Value>) -> u64 {
+        let data = source
+            .filter(|source| source.get("type").and_then(Value::as_str) == Some("base64"))
+            .and_then(|source| source.get("data"))
+            .and_then(Value::as_str);
+        let dims = data.and_then(|data| {
+            decode_base64_prefix(data, IMAGE_HEADER_BASE64_CHARS)
+                .as_deref()
+                .and_then(image_dimensions)
+                // JPEG frame headers can sit behind EXIF segments, so fall
+                // back to decoding the whole payload.
+                .or_else(|| STANDARD.decode(data.trim()).ok().as_deref().and_then(image_dimensions))
+        });
+        dims.map_or_else(|| self.default_image_tokens(), |dims| self.image_tokens(dims))
+    }
+
+    fn document_source_tokens(&self, source: Option<&Value>) -> u64 {
+        let Some(source) = source else {
+            return 0;
+        };
+        let text_field = |key: &str| source.get(key).and_then(Value::as_str).unwrap_or_default();
+        let pdf_pages = |pages: u64| pages * (PDF_PAGE_TEXT_TOKENS + self.default_image_tokens());
+        match text_field("type") {
+            "text" => self.count_text(text_field("data")),
+            "content" => source
+                .get("content")
+                .map_or(0, |content| self.count_content(content)),
+            "base64" => {
+                let Ok(bytes) = STANDARD.decode(text_field("data").trim()) else {
+                    return pdf_pages(1);
+                };
+                if text_field("media_type").starts_with("text/") {
+                    return self.count_text(&String::from_utf8_lossy(&bytes));
+                }
+                pdf_pages(pdf_page_count(&bytes).unwrap_or(1))
+            },
+            _ => pdf_pages(1),
+        }
+    }
+}
+
+/// Long unbroken runs of base64 or hex characters: encoded media, ciphertext
+/// or signatures rather than text the model reads.
+fn looks_like_opaque_payload(text: &str) -> bool {
+    text.len() >= OPAQUE_PAYLOAD_MIN_CHARS
+        && text.bytes().all(|byte| {
+            byte.is_ascii_alphanumeric() || matches!(byte, b'+' | b'/' | b'=' | b'-' | b'_')
+        })
+}
+
+fn decode_base64_prefix(data: &str, chars: usize) -> Option<Vec<u8>> {
+    let data = data.trim_start();
+    let end = data.len().min(chars) / 4 * 4;
+    STANDARD.decode(data.get(..end)?).ok()
+}
+
+/// Count `/Type /Page` leaf dictionaries. Returns `None` when the page tree
+/// lives in compressed object streams and nothing can be found.
+fn pdf_page_count(bytes: &[u8]) -> Option<u64> {
+    const NEEDLE: &[u8] = b"/Type";
+    let mut pages = 0;
+    let mut offset = 0;
+    while let Some(found) = bytes[offset..]
+        .windows(NEEDLE.len())
+        .position(|window| window == NEEDLE)
+    {
+        let mut cursor = offset + found + NEEDLE.len();
+        while bytes.get(cursor).is_some_and(u8::is_ascii_whitespace) {
+            cursor += 1;
+        }
+        let rest = &bytes[cursor.min(bytes.len())..];
+        if rest.starts_with(b"/Page") && !rest.get(5).is_some_and(u8::is_ascii_alphabetic) {
+            pages += 1;
+        }
+        offset = cursor;
+    }
+    (pages > 0).then_some(pages)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn png_base64(width: u32, height: u32) -> String {
+        let mut bytes = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+        bytes.extend_from_slice(&width.to_be_bytes());
+        bytes.extend_from_slice(&height.to_be_bytes());
+        bytes.extend_from_slice(&[8, 6, 0, 0, 0, 0, 0, 0, 0]);
+        STANDARD.encode(bytes)
+    }
+
+    #[test]
+    fn counts_every_block_type() {
+        let counter = TokenCounter::new(TokenizerFamily::Gpt4o);
+        let text = counter.count_text("What is in this image?");
+        assert!(text > 0);
+
+        let image = json!({
+            "type": "image",
+            "source": {"type": "base64", "media_type": "image/png", "data": png_base64(1024, 1024)},
+        });
+        assert_eq!(counter.count_block(&image), 765);
+        let url_image =
+            json!({"type": "image", "source": {"type": "url", "url": "https://x/y.png"}});
+        assert_eq!(counter.count_block(&url_image), OPENAI_DEFAULT_IMAGE_TOKENS);
+
+        let tool_use = json!({
+            "type": "tool_use", "id": "toolu_1", "name": "get_weather",
+            "input": {"city": "Paris"},
+        });
+        assert_eq!(
+            counter.count_block(&tool_use),
+            counter.count_text("toolu_1")
+                + counter.count_text("get_weather")
+                + counter.count_text(r#"{"city":"Paris"}"#)
+        );
+
+        let tool_result = json!({
+            "type": "tool_result", "tool_use_id": "toolu_1",
+            "content": [{"type": "text", "text": "18C and sunny"}, image.clone()],
+        });
+        assert_eq!(
+            counter.count_block(&tool_result),
+            counter.count_text("toolu_1") + counter.count_text("18C and sunny") + 765
+        );
+
+        let thinking = json!({"type": "thinking", "thinking": "Let me think.", "signature": "sig"});
+        assert_eq!(counter.count_block(&thinking), counter.count_text("Let me think."));
+
+        let document = json!({
+            "type": "document", "title": "Notes",
+            "source": {"type": "text", "media_type": "text/plain", "data": "plain body"},
+        });
+        assert_eq!(
+            counter.count_block(&document),
+            counter.count_text("Notes") + counter.count_text("plain body")
+        );
+
+        let message = json!([{"type": "text", "text": "What is in this image?"}, image]);
+        assert_eq!(counter.count_message(&message), MESSAGE_OVERHEAD_TOKENS + text + 765);
+    }
+
+    #[test]
+    fn unknown_blocks_skip_opaque_payloads() {
+        let counter = TokenCounter::new(TokenizerFamily::Gpt4o);
+        let blob = STANDARD.encode(vec![7_u8; 4_096]);
+        let block = json!({
+            "type": "future_media",
+            "title": "Quarterly report",
+            "source": {"type": "base64", "media_type": "audio/wav", "data": blob.clone()},
+            "payload": blob,
+            "signature": "sig",
+        });
+        assert_eq!(counter.count_block(&block), counter.count_text("Quarterly report"));
+    }
+
+    #[test]
+    fn estimates_pdf_documents_by_page_count() {
+        let pdf = b"%PDF-1.4\n1 0 obj << /Type /Pages /Count 2 >>\n\
+                    2 0 obj << /Type /Page >>\n3 0 obj << /Type/Page /Parent 1 0 R >>\n%%EOF";
+        assert_eq!(pdf_page_count(pdf), Some(2));
+        assert_eq!(pdf_page_count(b"%PDF-1.7 compressed"), None);
+
+        let counter = TokenCounter::new(TokenizerFamily::Claude);
+        let document = json!({
+            "type": "document",
+            "source": {"type": "base64", "media_type": "application/pdf", "data": STANDARD.encode(pdf)},
+        });
+        assert_eq!(
+            counter.count_block(&document),
+            2 * (PDF_PAGE_TEXT_TOKENS + ANTHROPIC_MAX_IMAGE_TOKENS)
+        );
+    }
+}
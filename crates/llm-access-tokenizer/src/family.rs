@@ -0,0 +1,94 @@
+//! Model-family selection.
+
+use crate::bpe::Encoding;
+
+/// Tokenizer family a model name maps to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenizerFamily {
+    /// Claude-compatible models. Anthropic does not publish the Claude 3+
+    /// vocabulary, so counts are `cl100k_base` counts scaled by
+    /// [`CLAUDE_SCALE_PERCENT`], which tracks `/v1/messages/count_tokens`
+    /// within a few percent on English prose and code.
+    Claude,
+    /// GPT-4 and GPT-3.5 era models (`cl100k_base`).
+    Gpt4,
+    /// GPT-4o and later OpenAI models (`o200k_base`).
+    Gpt4o,
+}
+
+/// Percentage applied to `cl100k_base` counts for the Claude family.
+pub const CLAUDE_SCALE_PERCENT: u64 = 115;
+
+impl TokenizerFamily {
+    /// Pick the family for a model name. Unknown names fall back to
+    /// [`TokenizerFamily::Claude`], since every Anthropic-compatible entry
+    /// point of the gateway accepts arbitrary aliases.
+    pub fn for_model(model: &str) -> Self {
+        let model = model.trim().to_ascii_lowercase();
+        let model = model.rsplit('/').next().unwrap_or_default();
+        if model.contains("claude") {
+            return Self::Claude;
+        }
+        let o200k_prefixes = ["gpt-4o", "gpt-4.1", "gpt-4.5", "gpt-5", "gpt-oss", "chatgpt-4o"];
+        if o200k_prefixes
+            .iter()
+            .any(|prefix| model.starts_with(prefix))
+            || model.contains("codex")
+            || is_o_series(model)
+        {
+            return Self::Gpt4o;
+        }
+        if model.starts_with("gpt-4")
+            || model.starts_with("gpt-3.5")
+            || model.starts_with("gpt-35")
+            || model.starts_with("text-embedding")
+        {
+            return Self::Gpt4;
+        }
+        Self::Claude
+    }
+
+    /// Vocabulary backing this family.
+    pub fn encoding(self) -> Encoding {
+        match self {
+            Self::Claude | Self::Gpt4 => Encoding::Cl100kBase,
+            Self::Gpt4o => Encoding::O200kBase,
+        }
+    }
+
+    pub(crate) fn scale(self, raw: u64) -> u64 {
+        match self {
+            Self::Claude => (raw * CLAUDE_SCALE_PERCENT).div_ceil(100),
+            Self::Gpt4 | Self::Gpt4o => raw,
+        }
+    }
+}
+
+/// `o1`, `o3-mini`, `o4-mini-high`, ...
+fn is_o_series(model: &str) -> bool {
+    let mut chars = model.chars();
+    chars.next() == Some('o')
+        && chars.next().is_some_and(|ch| ch.is_ascii_digit())
+        && chars.next().is_none_or(|ch| ch == '-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_model_names_to_families() {
+        assert_eq!(TokenizerFamily::for_model("claude-sonnet-4-6"), TokenizerFamily::Claude);
+        assert_eq!(
+            TokenizerFamily::for_model("anthropic/claude-3-5-haiku"),
+            TokenizerFamily::Claude
+        );
+        assert_eq!(TokenizerFamily::for_model("gpt-5.1-codex"), TokenizerFamily::Gpt4o);
+        assert_eq!(TokenizerFamily::for_model("GPT-4o-mini"), TokenizerFamily::Gpt4o);
+        assert_eq!(TokenizerFamily::for_model("o3-mini"), TokenizerFamily::Gpt4o);
+        assert_eq!(TokenizerFamily::for_model("gpt-4-turbo"), TokenizerFamily::Gpt4);
+        assert_eq!(TokenizerFamily::for_model("gpt-3.5-turbo"), TokenizerFamily::Gpt4);
+        assert_eq!(TokenizerFamily::for_model("omni-alias"), TokenizerFamily::Claude);
+        assert_eq!(TokenizerFamily::for_model(""), TokenizerFamily::Claude);
+    }
+}
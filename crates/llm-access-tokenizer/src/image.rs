@@ -0,0 +1,285 @@
+//! Image header parsing and vision-token costing.
+//!
+//! Only the container header is inspected, so no image decoder is needed.
+//! PNG, GIF, JPEG and WebP cover every media type accepted by the
+//! Anthropic and OpenAI vision APIs.
+
+/// Pixel dimensions read from an image header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageDimensions {
+    /// Width in pixels.
+    pub width: u32,
+    /// Height in pixels.
+    pub height: u32,
+}
+
+/// Anthropic downscales images whose long edge exceeds this many pixels.
+const ANTHROPIC_MAX_LONG_EDGE: f64 = 1568.0;
+/// Anthropic bills roughly one token per this many pixels.
+const ANTHROPIC_PIXELS_PER_TOKEN: f64 = 750.0;
+/// Upper bound Anthropic documents for a single resized image.
+pub(crate) const ANTHROPIC_MAX_IMAGE_TOKENS: u64 = 1600;
+
+/// OpenAI `detail: high` fits images into this square first...
+const OPENAI_MAX_EDGE: f64 = 2048.0;
+/// ...then scales the short edge down to this size...
+const OPENAI_SHORT_EDGE: f64 = 768.0;
+/// ...and bills per tile of this size on top of a fixed base cost.
+const OPENAI_TILE_EDGE: f64 = 512.0;
+const OPENAI_BASE_TOKENS: u64 = 85;
+const OPENAI_TILE_TOKENS: u64 = 170;
+/// Cost of a 1024x1024 image (four tiles), used when dimensions are unknown.
+pub(crate) const OPENAI_DEFAULT_IMAGE_TOKENS: u64 = OPENAI_BASE_TOKENS + 4 * OPENAI_TILE_TOKENS;
+
+/// Read the pixel dimensions from a PNG, GIF, JPEG or WebP header.
+pub fn image_dimensions(bytes: &[u8]) -> Option<ImageDimensions> {
+    png_dimensions(bytes)
+        .or_else(|| gif_dimensions(bytes))
+        .or_else(|| webp_dimensions(bytes))
+        .or_else(|| jpeg_dimensions(bytes))
+        .filter(|dims| dims.width > 0 && dims.height > 0)
+}
+
+/// Vision tokens Anthropic models charge for an image of the given size,
+/// after the API's automatic downscaling.
+pub fn anthropic_image_tokens(dims: ImageDimensions) -> u64 {
+    let (mut width, mut height) = (f64::from(dims.width), f64::from(dims.height));
+    let long_edge = width.max(height);
+    if long_edge > ANTHROPIC_MAX_LONG_EDGE {
+        let scale = ANTHROPIC_MAX_LONG_EDGE / long_edge;
+        width = (width * scale).floor().max(1.0);
+        height = (height * scale).floor().max(1.0);
+    }
+    let tokens = (width * height / ANTHROPIC_PIXELS_PER_TOKEN).ceil() as u64;
+    tokens.clamp(1, ANTHROPIC_MAX_IMAGE_TOKENS)
+}
+
+/// Vision tokens OpenAI models charge for an image of the given size at
+/// `detail: high`.
+pub fn openai_image_tokens(dims: ImageDimensions) -> u64 {
+    let (mut width, mut height) = (f64::from(dims.width), f64::from(dims.height));
+    let long_edge = width.max(height);
+    if long_edge > OPENAI_MAX_EDGE {
+        let scale = OPENAI_MAX_EDGE / long_edge;
+        width *= scale;
+        height *= scale;
+    }
+    let short_edge = width.min(height);
+    if short_edge > OPENAI_SHORT_EDGE {
+        let scale = OPENAI_SHORT_EDGE / short_edge;
+        width *= scale;
+        height *= scale;
+    }
+    let tiles =
+        (width / OPENAI_TILE_EDGE).ceil() as u64 * (height / OPENAI_TILE_EDGE).ceil() as u64;
+    OPENAI_BASE_TOKENS + OPENAI_TILE_TOKENS * tiles.max(1)
+}
+
+fn png_dimensions(bytes: &[u8]) -> Option<ImageDimensions> {
+    if !bytes.starts_with(b"\x89PNG\r\n\x1a\n") || bytes.get(12..16)? != b"IHDR" {
+        return None;
+    }
+    Some(ImageDimensions {
+        width: be_u32(bytes, 16)?,
+        height: be_u32(bytes, 20)?,
+    })
+}
+
+fn gif_dimensions(bytes: &[u8]) -> Option<ImageDimensions> {
+    if !bytes.starts_with(b"GIF87a") && !bytes.starts_with(b"GIF89a") {
+        return None;
+    }
+    Some(ImageDimensions {
+        width: u32::from(le_u16(bytes, 6)?),
+        height: u32::from(le_u16(bytes, 8)?),
+    })
+}
+
+fn webp_dimensions(bytes: &[u8]) -> Option<ImageDimensions> {
+    if bytes.get(0..4)? != b"RIFF" || bytes.get(8..12)? != b"WEBP" {
+        return None;
+    }
+    match bytes.get(12..16)? {
+        b"VP8 " => {
+            if bytes.get(23..26)? != [0x9d, 0x01, 0x2a] {
+                return None;
+            }
+            Some(ImageDimensions {
+                width: u32::from(le_u16(bytes, 26)? & 0x3fff),
+                height: u32::from(le_u16(bytes, 28)? & 0x3fff),
+            })
+        },
+        b"VP8L" => {
+            if *bytes.get(20)? != 0x2f {
+                return None;
+            }
+            let packed = u32::from_le_bytes(bytes.get(21..25)?.try_into().ok()?);
+            Some(ImageDimensions {
+                width: (packed & 0x3fff) + 1,
+                height: ((packed >> 14) & 0x3fff) + 1,
+            })
+        },
+        b"VP8X" => Some(ImageDimensions {
+            width: le_u24(bytes, 24)? + 1,
+            height: le_u24(bytes, 27)? + 1,
+        }),
+        _ => None,
+    }
+}
+
+fn jpeg_dimensions(bytes: &[u8]) -> Option<ImageDimensions> {
+    if !bytes.starts_with(&[0xff, 0xd8]) {
+        return None;
+    }
+    let mut offset = 2;
+    loop {
+        while *bytes.get(offset)? != 0xff {
+            offset += 1;
+        }
+        while *bytes.get(offset)? == 0xff {
+            offset += 1;
+        }
+        let marker = *bytes.get(offset)?;
+        offset += 1;
+        match marker {
+            // Standalone markers carry no length field.
+            0x01 | 0xd0..=0xd8 => continue,
+            0xd9 | 0xda => return None,
+            // SOF0..SOF15, excluding DHT (c4), JPG (c8) and DAC (cc).
+            0xc0..=0xcf if !matches!(marker, 0xc4 | 0xc8 | 0xcc) => {
+                return Some(ImageDimensions {
+                    height: u32::from(be_u16(bytes, offset + 3)?),
+                    width: u32::from(be_u16(bytes, offset + 5)?),
+                });
+            },
+            _ => {
+                let length = usize::from(be_u16(bytes, offset)?);
+                offset += length;
+            },
+        }
+    }
+}
+
+fn be_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(bytes.get(offset..offset + 2)?.try_into().ok()?))
+}
+
+fn be_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(bytes.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+fn le_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(bytes.get(offset..offset + 2)?.try_into().ok()?))
+}
+
+fn le_u24(bytes: &[u8], offset: usize) -> Option<u32> {
+    let raw = bytes.get(offset..offset + 3)?;
+    Some(u32::from(raw[0]) | u32::from(raw[1]) << 8 | u32::from(raw[2]) << 16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png_header(width: u32, height: u32) -> Vec<u8> {
+        let mut bytes = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+        bytes.extend_from_slice(&width.to_be_bytes());
+        bytes.extend_from_slice(&height.to_be_bytes());
+        bytes.extend_from_slice(&[8, 6, 0, 0, 0]);
+        bytes
+    }
+
+    #[test]
+    fn reads_dimensions_from_each_container() {
+        assert_eq!(
+            image_dimensions(&png_header(640, 480)),
+            Some(ImageDimensions {
+                width: 640,
+                height: 480
+            })
+        );
+
+        let mut gif = b"GIF89a".to_vec();
+        gif.extend_from_slice(&320u16.to_le_bytes());
+        gif.extend_from_slice(&200u16.to_le_bytes());
+        assert_eq!(
+            image_dimensions(&gif),
+            Some(ImageDimensions {
+                width: 320,
+                height: 200
+            })
+        );
+
+        let mut jpeg = vec![0xff, 0xd8, 0xff, 0xe0, 0x00, 0x04, 0x00, 0x00];
+        jpeg.extend_from_slice(&[0xff, 0xc0, 0x00, 0x11, 0x08]);
+        jpeg.extend_from_slice(&768u16.to_be_bytes());
+        jpeg.extend_from_slice(&1024u16.to_be_bytes());
+        assert_eq!(
+            image_dimensions(&jpeg),
+            Some(ImageDimensions {
+                width: 1024,
+                height: 768
+            })
+        );
+
+        let mut webp = b"RIFF\0\0\0\0WEBPVP8X\x0a\0\0\0\0\0\0\0".to_vec();
+        webp.extend_from_slice(&[0x7f, 0x07, 0x00, 0x37, 0x04, 0x00]);
+        assert_eq!(
+            image_dimensions(&webp),
+            Some(ImageDimensions {
+                width: 1920,
+                height: 1080
+            })
+        );
+
+        assert_eq!(image_dimensions(b"not an image"), None);
+    }
+
+    #[test]
+    fn costs_images_like_the_upstream_docs() {
+        // Anthropic: 1000x1000 => ceil(1_000_000 / 750)
+        assert_eq!(
+            anthropic_image_tokens(ImageDimensions {
+                width: 1000,
+                height: 1000
+            }),
+            1334
+        );
+        assert_eq!(
+            anthropic_image_tokens(ImageDimensions {
+                width: 200,
+                height: 200
+            }),
+            54
+        );
+        assert_eq!(
+            anthropic_image_tokens(ImageDimensions {
+                width: 4000,
+                height: 3000
+            }),
+            ANTHROPIC_MAX_IMAGE_TOKENS
+        );
+        // OpenAI: 1024x1024 => 768x768 => 4 tiles; 2048x4096 => 768x1536 => 6 tiles.
+        assert_eq!(
+            openai_image_tokens(ImageDimensions {
+                width: 1024,
+                height: 1024
+            }),
+            765
+        );
+        assert_eq!(
+            openai_image_tokens(ImageDimensions {
+                width: 2048,
+                height: 4096
+            }),
+            1105
+        );
+        assert_eq!(
+            openai_image_tokens(ImageDimensions {
+                width: 100,
+                height: 100
+            }),
+            255
+        );
+    }
+}
@@ -0,0 +1,102 @@
+//! Embedded, offline BPE tokenizer for request token accounting.
+//!
+//! The gateway reports token counts to users through
+//! `/v1/messages/count_tokens`, usage estimates for streams whose upstream
+//! never reports usage, and cache-simulation numbers. This crate provides
+//! real byte-pair-encoding counts from vendored vocabularies, selected per
+//! model family, plus costing for every Anthropic content block type:
+//! text, images (by header dimensions), documents, tool calls and results,
+//! and thinking blocks.
+
+mod bpe;
+mod content;
+mod family;
+mod image;
+
+pub use bpe::Encoding;
+pub use family::{TokenizerFamily, CLAUDE_SCALE_PERCENT};
+pub use image::{anthropic_image_tokens, image_dimensions, openai_image_tokens, ImageDimensions};
+
+/// Token counter bound to one model family.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenCounter {
+    family: TokenizerFamily,
+}
+
+impl TokenCounter {
+    /// Counter for an explicit family.
+    pub fn new(family: TokenizerFamily) -> Self {
+        Self {
+            family,
+        }
+    }
+
+    /// Counter for the family a model name maps to.
+    pub fn for_model(model: &str) -> Self {
+        Self::new(TokenizerFamily::for_model(model))
+    }
+
+    /// Family this counter estimates for.
+    pub fn family(&self) -> TokenizerFamily {
+        self.family
+    }
+
+    /// Tokens of a plain text string.
+    pub fn count_text(&self, text: &str) -> u64 {
+        self.family.scale(self.family.encoding().count(text) as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reference counts produced by OpenAI's `tiktoken` for the same inputs.
+    const CORPUS: &[(&str, usize, usize)] = &[
+        ("", 0, 0),
+        ("hello", 1, 1),
+        ("Hello, world!", 4, 4),
+        ("The quick brown fox jumps over the lazy dog.", 10, 10),
+        ("我们今天去公园散步，天气非常好。", 18, 12),
+        ("こんにちは、世界！カタカナとひらがな。", 17, 14),
+        ("fn main() {\n    println!(\"{}\", 1 + 2);\n}\n", 14, 14),
+        ("  leading and trailing spaces   \n\n\n\ttabs\r\n", 10, 10),
+        ("I'm sure they'll say we've DONE IT'S fine", 12, 9),
+        ("1234567890 3.14159 -42 1e10", 15, 15),
+        ("emoji 😀🎉👍🏽 and symbols ∑∫√ ©®", 21, 16),
+        ("Привет, мир! Ελληνικά עברית العربية हिन्दी", 33, 11),
+        (r#"{"city":"Paris","units":"metric","days":[1,2,3]}"#, 17, 17),
+        ("https://example.com/path/to/resource?query=value&x=1#frag", 16, 16),
+        ("<|endoftext|> special markers are plain text", 12, 12),
+        ("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa", 9, 9),
+        ("CamelCaseIdentifiersLikeThisOne and snake_case_ones_too", 15, 13),
+        ("Tiếng Việt có dấu: người, được, những", 19, 11),
+    ];
+
+    #[test]
+    fn bpe_counts_match_reference_corpus() {
+        for (text, cl100k, o200k) in CORPUS {
+            assert_eq!(Encoding::Cl100kBase.count(text), *cl100k, "cl100k_base: {text:?}");
+            assert_eq!(Encoding::O200kBase.count(text), *o200k, "o200k_base: {text:?}");
+        }
+        let long = "lorem ipsum dolor sit amet ".repeat(200);
+        assert_eq!(Encoding::Cl100kBase.count(&long), 1002);
+        assert_eq!(Encoding::O200kBase.count(&long), 1002);
+    }
+
+    #[test]
+    fn encode_and_count_agree() {
+        let text = "Tokens for 你好 and tool_use JSON {\"a\": [1, 2]}";
+        let tokens = Encoding::O200kBase.encode(text).expect("vocab");
+        assert_eq!(tokens.len(), Encoding::O200kBase.count(text));
+        assert_eq!(Encoding::Cl100kBase.encode("hello").expect("vocab"), vec![15339]);
+    }
+
+    #[test]
+    fn claude_family_scales_cl100k_counts() {
+        let text = "The quick brown fox jumps over the lazy dog.";
+        assert_eq!(TokenCounter::for_model("gpt-4").count_text(text), 10);
+        assert_eq!(TokenCounter::for_model("claude-sonnet-4-6").count_text(text), 12);
+        assert_eq!(TokenCounter::for_model("claude-sonnet-4-6").count_text(""), 0);
+    }
+}
@@ -53,6 +53,7 @@ use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 use crate::{
     activity::RequestActivitySnapshot,
     anthropic_upstream_probe, codex_refresh, codex_status, kiro_refresh, kiro_status,
+    openai_upstream_probe,
     process_memory::{read_current_process_memory_stats, ProcessMemoryStats},
     provider, HttpState,
 };
@@ -75,6 +76,7 @@ const ADMIN_USAGE_QUERY_GATE_WAIT: Duration = Duration::from_secs(10);
 const USAGE_WORKER_QUERY_TIMEOUT: Duration = Duration::from_secs(60);
 const PROXY_TRAFFIC_REFRESH_MAX_WINDOW_DAYS: u64 = 30;
 const PROXY_TRAFFIC_REFRESH_BUCKET_MS: i64 = 24 * 60 * 60 * 1000;
+const PROXY_POOL_RECHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
 const HOUR_MS: i64 = 60 * 60 * 1000;
 const ADMIN_ANTHROPIC_UPSTREAM_TEST_COOLDOWN_MS: i64 = 30_000;
 const MIN_RUNTIME_USAGE_EVENT_FLUSH_BATCH_SIZE: u64 = 1;
@@ -191,6 +193,12 @@ struct AdminProxyConfigScopeView {
     can_edit_slot_metadata: bool,
 }
 
+#[derive(Debug, Serialize)]
+struct AdminProxyPoolsResponse {
+    proxy_pools: Vec<core_store::AdminProxyPool>,
+    generated_at: i64,
+}
+
 #[derive(Debug, Serialize)]
 struct AdminProxyBindingsResponse {
     bindings: Vec<core_store::AdminProxyBinding>,
@@ -205,6 +213,7 @@ struct AdminAccountsResponse {
     limit: usize,
     offset: usize,
     has_more: bool,
+    circuit_breakers: Vec<provider::CircuitBreakerView>,
     generated_at: i64,
 }
 
@@ -260,12 +269,30 @@ struct AdminAnthropicUpstreamProbeResponse {
     generated_at: i64,
 }
 
+#[derive(Debug, Serialize)]
+struct AdminOpenAiUpstreamChannelsResponse {
+    channels: Vec<core_store::AdminOpenAiUpstreamChannel>,
+    generated_at: i64,
+}
+
+#[derive(Debug, Serialize)]
+struct AdminOpenAiUpstreamProbeResponse {
+    ok: bool,
+    status: String,
+    status_code: Option<u16>,
+    latency_ms: u64,
+    error: Option<String>,
+    channel: core_store::AdminOpenAiUpstreamChannel,
+    generated_at: i64,
+}
+
 #[derive(Debug, Serialize)]
 struct AdminKiroAccountStatusesResponse {
     accounts: Vec<core_store::AdminKiroAccount>,
     total: usize,
     limit: usize,
     offset: usize,
+    circuit_breakers: Vec<provider::CircuitBreakerView>,
     generated_at: i64,
 }
 
@@ -301,12 +328,28 @@ struct AdminTokenRequestsResponse {
     generated_at: i64,
 }
 
+#[derive(Debug, Serialize)]
+struct AdminModerationEventsResponse {
+    total: usize,
+    offset: usize,
+    limit: usize,
+    has_more: bool,
+    events: Vec<core_store::AdminModerationEvent>,
+    generated_at: i64,
+}
+
 #[derive(Debug, Deserialize, Default)]
 pub(crate) struct AdminListQuery {
     limit: Option<usize>,
     offset: Option<usize>,
 }
 
+#[derive(Debug, Deserialize, Default)]
+pub(crate) struct AdminRouteExplainQuery {
+    model: Option<String>,
+    session_id: Option<String>,
+}
+
 #[derive(Debug, Deserialize, Default)]
 pub(crate) struct AdminKeyListQuery {
     limit: Option<usize>,
@@ -586,6 +629,8 @@ pub(crate) struct PatchLlmGatewayKeyRequest {
     #[serde(default)]
     kiro_anthropic_upstream_pool_mode: Option<String>,
     #[serde(default)]
+    codex_openai_upstream_pool_mode: Option<String>,
+    #[serde(default)]
     model_name_map: Option<BTreeMap<String, String>>,
     #[serde(default)]
     kiro_model_group_preferences: Option<BTreeMap<String, String>>,
@@ -627,6 +672,17 @@ pub(crate) struct PatchLlmGatewayKeyRequest {
     kiro_cache_policy_override_json: Option<Option<String>>,
     #[serde(default)]
     kiro_billable_model_multipliers_override_json: Option<Option<String>>,
+    #[serde(default)]
+    response_cache_policy: Option<core_store::KeyResponseCachePolicy>,
+    /// An empty policy (`{}`) clears the key's transforms.
+    #[serde(default)]
+    request_transform_policy: Option<core_store::RequestTransformPolicy>,
+    /// A policy without rules clears the key's moderation.
+    #[serde(default)]
+    moderation_policy: Option<core_store::KeyModerationPolicy>,
+    /// An empty policy (`{}`) clears the key's queue settings.
+    #[serde(default)]
+    queue_policy: Option<core_store::KeyQueuePolicy>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -675,10 +731,57 @@ pub(crate) struct TestAdminAnthropicUpstreamChannelRequest {
     model: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub(crate) struct CreateAdminOpenAiUpstreamChannelRequest {
+    name: String,
+    base_url: String,
+    #[serde(default)]
+    api_key: Option<String>,
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    weight: Option<u64>,
+    #[serde(default)]
+    max_concurrency: Option<u64>,
+    #[serde(default)]
+    min_start_interval_ms: Option<u64>,
+    #[serde(default)]
+    proxy_mode: Option<String>,
+    #[serde(default)]
+    proxy_config_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct PatchAdminOpenAiUpstreamChannelRequest {
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    base_url: Option<String>,
+    /// An empty string clears the key for unauthenticated local servers.
+    #[serde(default)]
+    api_key: Option<String>,
+    #[serde(default)]
+    weight: Option<u64>,
+    #[serde(default)]
+    max_concurrency: Option<u64>,
+    #[serde(default)]
+    min_start_interval_ms: Option<u64>,
+    #[serde(default)]
+    proxy_mode: Option<String>,
+    #[serde(default)]
+    proxy_config_id: Option<Option<String>>,
+    #[serde(default)]
+    clear_last_error: bool,
+}
+
 #[derive(Debug, Deserialize)]
 pub(crate) struct CreateLlmGatewayAccountGroupRequest {
     name: String,
     account_names: Vec<String>,
+    #[serde(default)]
+    request_transform_policy: Option<core_store::RequestTransformPolicy>,
+    #[serde(default)]
+    queue_policy: Option<core_store::KeyQueuePolicy>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -687,6 +790,12 @@ pub(crate) struct PatchLlmGatewayAccountGroupRequest {
     name: Option<String>,
     #[serde(default)]
     account_names: Option<Vec<String>>,
+    /// An empty policy (`{}`) clears the group's transforms.
+    #[serde(default)]
+    request_transform_policy: Option<core_store::RequestTransformPolicy>,
+    /// An empty policy (`{}`) clears the group's queue settings.
+    #[serde(default)]
+    queue_policy: Option<core_store::KeyQueuePolicy>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -717,6 +826,31 @@ pub(crate) struct PatchLlmGatewayProxyConfigRequest {
 pub(crate) struct UpdateLlmGatewayProxyBindingRequest {
     #[serde(default)]
     proxy_config_id: Option<String>,
+    #[serde(default)]
+    proxy_pool_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct LlmGatewayProxyPoolMemberRequest {
+    proxy_config_id: String,
+    #[serde(default)]
+    weight: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct CreateLlmGatewayProxyPoolRequest {
+    name: String,
+    members: Vec<LlmGatewayProxyPoolMemberRequest>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct PatchLlmGatewayProxyPoolRequest {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    members: Option<Vec<LlmGatewayProxyPoolMemberRequest>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -775,6 +909,8 @@ pub(crate) struct PatchLlmGatewayAccountRequest {
     #[serde(default)]
     proxy_config_id: Option<String>,
     #[serde(default)]
+    proxy_pool_id: Option<String>,
+    #[serde(default)]
     map_gpt53_codex_to_spark: Option<bool>,
     #[serde(default)]
     auto_refresh_enabled: Option<bool>,
@@ -883,6 +1019,8 @@ pub(crate) struct PatchKiroAccountRequest {
     proxy_mode: Option<String>,
     #[serde(default)]
     proxy_config_id: Option<String>,
+    #[serde(default)]
+    proxy_pool_id: Option<String>,
 }
 
 fn deserialize_present_optional_f64<'de, D>(
@@ -1127,6 +1265,96 @@ pub(crate) async fn delete_llm_gateway_key(
     }
 }
 
+/// Dry-run the account selection for one key without dispatching a request.
+pub(crate) async fn explain_llm_gateway_key_route(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+    Path(key_id): Path<String>,
+    Query(query): Query<AdminRouteExplainQuery>,
+) -> Response {
+    if let Err(response) = ensure_admin_access(&headers) {
+        return response.into_response();
+    }
+    let admin_key = match state.admin_key_store.get_admin_key(&key_id).await {
+        Ok(Some(key)) => key,
+        Ok(None) => return not_found("LLM gateway key not found").into_response(),
+        Err(_) => return internal_error("Failed to load llm gateway key").into_response(),
+    };
+    let key = match state
+        .provider_state
+        .authenticate_bearer_secret(&admin_key.secret)
+        .await
+    {
+        Ok(Some(key)) if key.key_id == admin_key.id => key,
+        Ok(_) => return internal_error("LLM gateway key secret is not accepted").into_response(),
+        Err(_) => return internal_error("Failed to authenticate llm gateway key").into_response(),
+    };
+    let configured_account_names = match configured_route_account_names(&state, &admin_key).await {
+        Ok(names) => names,
+        Err(err) => return err.into_response(),
+    };
+    match state
+        .provider_state
+        .explain_route(
+            &key,
+            query.model.as_deref(),
+            query.session_id.as_deref(),
+            configured_account_names.as_deref(),
+        )
+        .await
+    {
+        Ok(explanation) => Json(explanation).into_response(),
+        Err(err) => {
+            tracing::warn!(key_id = %key_id, error = %err, "llm gateway route explain failed");
+            internal_error("Failed to explain llm gateway key route").into_response()
+        },
+    }
+}
+
+/// Live fair-queue state: waiters per account pool and per-key queue
+/// counters.
+pub(crate) async fn get_llm_gateway_request_queue(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+) -> Response {
+    if let Err(response) = ensure_admin_access(&headers) {
+        return response.into_response();
+    }
+    Json(state.provider_state.fair_queue_view()).into_response()
+}
+
+/// Accounts the key's route config names explicitly, mirroring the route
+/// store's resolution; `None` when the key may use every active account.
+async fn configured_route_account_names(
+    state: &HttpState,
+    key: &core_store::AdminKey,
+) -> Result<Option<Vec<String>>, AdminHttpError> {
+    let group_account_names = match key.account_group_id.as_deref() {
+        Some(group_id) => state
+            .admin_account_group_store
+            .list_admin_account_groups(&key.provider_type)
+            .await
+            .map_err(|_| internal_error("Failed to load llm gateway account groups"))?
+            .into_iter()
+            .find(|group| group.id == group_id)
+            .map(|group| group.account_names),
+        None => None,
+    };
+    Ok(match key.route_strategy.as_deref().unwrap_or("auto") {
+        "fixed" => group_account_names.or_else(|| {
+            key.fixed_account_name
+                .clone()
+                .filter(|name| !name.trim().is_empty())
+                .map(|name| vec![name])
+        }),
+        _ => group_account_names.or_else(|| {
+            key.auto_account_names
+                .clone()
+                .filter(|names| !names.is_empty())
+        }),
+    })
+}
+
 pub(crate) async fn list_llm_gateway_account_groups(
     State(state): State<HttpState>,
     headers: HeaderMap,
@@ -1178,11 +1406,22 @@ pub(crate) async fn create_llm_gateway_account_group(
         Ok(None) => return bad_request("account_names must not be empty").into_response(),
         Err(response) => return response.into_response(),
     };
+    let request_transform_policy =
+        match normalize_request_transform_policy(request.request_transform_policy) {
+            Ok(policy) => policy.flatten(),
+            Err(response) => return response.into_response(),
+        };
+    let queue_policy = match normalize_queue_policy(request.queue_policy) {
+        Ok(policy) => policy.flatten(),
+        Err(response) => return response.into_response(),
+    };
     let group = NewAdminAccountGroup {
         id: generate_id("llm-group"),
         provider_type: PROVIDER_CODEX.to_string(),
         name,
         account_names,
+        request_transform_policy,
+        queue_policy,
         created_at_ms: now_ms(),
     };
     match state
@@ -1216,9 +1455,20 @@ pub(crate) async fn patch_llm_gateway_account_group(
         Ok(value) => value.flatten(),
         Err(response) => return response.into_response(),
     };
+    let request_transform_policy =
+        match normalize_request_transform_policy(request.request_transform_policy) {
+            Ok(policy) => policy,
+            Err(response) => return response.into_response(),
+        };
+    let queue_policy = match normalize_queue_policy(request.queue_policy) {
+        Ok(policy) => policy,
+        Err(response) => return response.into_response(),
+    };
     let patch = AdminAccountGroupPatch {
         name,
         account_names,
+        request_transform_policy,
+        queue_policy,
         updated_at_ms: now_ms(),
     };
     match state
@@ -1388,6 +1638,20 @@ pub(crate) async fn delete_llm_gateway_proxy_config(
         ))
         .into_response();
     }
+    let pools = match state.admin_proxy_store.list_admin_proxy_pools().await {
+        Ok(pools) => pools,
+        Err(_) => {
+            return internal_error("Failed to inspect llm gateway proxy pools").into_response()
+        },
+    };
+    if let Some(pool) = pools.iter().find(|pool| {
+        pool.members
+            .iter()
+            .any(|member| member.proxy_config_id == proxy_id)
+    }) {
+        return conflict(&format!("proxy config is still a member of proxy pool `{}`", pool.name))
+            .into_response();
+    }
     match state
         .admin_proxy_store
         .delete_admin_proxy_config(&proxy_id)
@@ -1454,6 +1718,24 @@ pub(crate) async fn update_llm_gateway_proxy_binding(
         return response.into_response();
     }
     let proxy_config_id = normalize_optional_string_option(request.proxy_config_id.as_deref());
+    let proxy_pool_id = normalize_optional_string_option(request.proxy_pool_id.as_deref());
+    if let Some(pool_id) = proxy_pool_id {
+        if proxy_config_id.is_some() {
+            return bad_request("proxy_config_id and proxy_pool_id are mutually exclusive")
+                .into_response();
+        }
+        if let Err(response) = ensure_active_proxy_pool(&state, &pool_id).await {
+            return response.into_response();
+        }
+        return match state
+            .admin_proxy_store
+            .update_admin_proxy_pool_binding(&provider_type, Some(pool_id))
+            .await
+        {
+            Ok(binding) => Json(binding).into_response(),
+            Err(_) => internal_error("Failed to update llm gateway proxy binding").into_response(),
+        };
+    }
     if let Some(proxy_id) = proxy_config_id.as_deref() {
         let proxy = match state
             .admin_proxy_store
@@ -1480,6 +1762,184 @@ pub(crate) async fn update_llm_gateway_proxy_binding(
     }
 }
 
+async fn ensure_active_proxy_pool(state: &HttpState, pool_id: &str) -> Result<(), AdminHttpError> {
+    match state.admin_proxy_store.get_admin_proxy_pool(pool_id).await {
+        Ok(Some(pool)) if pool.status == KEY_STATUS_ACTIVE => Ok(()),
+        Ok(Some(_)) => Err(bad_request("proxy pool must be active before binding")),
+        Ok(None) => Err(not_found("LLM gateway proxy pool not found")),
+        Err(_) => Err(internal_error("Failed to load llm gateway proxy pool")),
+    }
+}
+
+async fn normalize_proxy_pool_members(
+    state: &HttpState,
+    members: Vec<LlmGatewayProxyPoolMemberRequest>,
+) -> Result<Vec<core_store::ProxyPoolMember>, AdminHttpError> {
+    if members.is_empty() {
+        return Err(bad_request("proxy pool needs at least one member"));
+    }
+    let proxy_configs = state
+        .admin_proxy_store
+        .list_admin_proxy_configs()
+        .await
+        .map_err(|_| internal_error("Failed to load llm gateway proxy configs"))?;
+    let mut normalized = Vec::<core_store::ProxyPoolMember>::with_capacity(members.len());
+    for member in members {
+        let Some(proxy_config_id) = normalize_optional_string(&member.proxy_config_id) else {
+            return Err(bad_request("proxy pool member proxy_config_id cannot be empty"));
+        };
+        if !proxy_configs
+            .iter()
+            .any(|proxy| proxy.id == proxy_config_id)
+        {
+            return Err(not_found("LLM gateway proxy config not found"));
+        }
+        if normalized
+            .iter()
+            .any(|existing| existing.proxy_config_id == proxy_config_id)
+        {
+            return Err(bad_request("proxy pool members must be unique"));
+        }
+        normalized.push(core_store::ProxyPoolMember {
+            proxy_config_id,
+            weight: member.weight.unwrap_or(1),
+        });
+    }
+    if normalized.iter().all(|member| member.weight == 0) {
+        return Err(bad_request("proxy pool needs at least one member with a positive weight"));
+    }
+    Ok(normalized)
+}
+
+pub(crate) async fn list_llm_gateway_proxy_pools(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+) -> Response {
+    if let Err(response) = ensure_admin_access(&headers) {
+        return response.into_response();
+    }
+    match state.admin_proxy_store.list_admin_proxy_pools().await {
+        Ok(proxy_pools) => Json(AdminProxyPoolsResponse {
+            proxy_pools,
+            generated_at: now_ms(),
+        })
+        .into_response(),
+        Err(_) => internal_error("Failed to list llm gateway proxy pools").into_response(),
+    }
+}
+
+pub(crate) async fn create_llm_gateway_proxy_pool(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+    Json(request): Json<CreateLlmGatewayProxyPoolRequest>,
+) -> Response {
+    if let Err(response) = ensure_admin_access(&headers) {
+        return response.into_response();
+    }
+    let name = match normalize_name(&request.name) {
+        Ok(name) => name,
+        Err(response) => return response.into_response(),
+    };
+    let members = match normalize_proxy_pool_members(&state, request.members).await {
+        Ok(members) => members,
+        Err(response) => return response.into_response(),
+    };
+    let pool = core_store::NewAdminProxyPool {
+        id: generate_id("llm-proxy-pool"),
+        name,
+        members,
+        created_at_ms: now_ms(),
+    };
+    match state.admin_proxy_store.create_admin_proxy_pool(pool).await {
+        Ok(pool) => Json(pool).into_response(),
+        Err(_) => internal_error("Failed to create llm gateway proxy pool").into_response(),
+    }
+}
+
+pub(crate) async fn patch_llm_gateway_proxy_pool(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+    Path(pool_id): Path<String>,
+    Json(request): Json<PatchLlmGatewayProxyPoolRequest>,
+) -> Response {
+    if let Err(response) = ensure_admin_access(&headers) {
+        return response.into_response();
+    }
+    let name = match request.name.as_deref().map(normalize_name).transpose() {
+        Ok(name) => name,
+        Err(response) => return response.into_response(),
+    };
+    let status = match request.status.as_deref().map(normalize_status).transpose() {
+        Ok(status) => status,
+        Err(response) => return response.into_response(),
+    };
+    let members = match request.members {
+        Some(members) => match normalize_proxy_pool_members(&state, members).await {
+            Ok(members) => Some(members),
+            Err(response) => return response.into_response(),
+        },
+        None => None,
+    };
+    let patch = core_store::AdminProxyPoolPatch {
+        name,
+        status,
+        members,
+        updated_at_ms: now_ms(),
+    };
+    match state
+        .admin_proxy_store
+        .patch_admin_proxy_pool(&pool_id, patch)
+        .await
+    {
+        Ok(Some(pool)) => Json(pool).into_response(),
+        Ok(None) => not_found("LLM gateway proxy pool not found").into_response(),
+        Err(_) => internal_error("Failed to update llm gateway proxy pool").into_response(),
+    }
+}
+
+pub(crate) async fn delete_llm_gateway_proxy_pool(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+    Path(pool_id): Path<String>,
+) -> Response {
+    if let Err(response) = ensure_admin_access(&headers) {
+        return response.into_response();
+    }
+    let bindings = match state.admin_proxy_store.list_admin_proxy_bindings().await {
+        Ok(bindings) => bindings,
+        Err(_) => {
+            return internal_error("Failed to inspect llm gateway proxy bindings").into_response()
+        },
+    };
+    if let Some(binding) = bindings
+        .iter()
+        .find(|binding| binding.bound_proxy_pool_id.as_deref() == Some(pool_id.as_str()))
+    {
+        return conflict(&format!(
+            "proxy pool is still bound to provider `{}`",
+            binding.provider_type
+        ))
+        .into_response();
+    }
+    match state
+        .admin_proxy_store
+        .delete_admin_proxy_pool(&pool_id)
+        .await
+    {
+        Ok(Some(pool)) => Json(DeleteResponse {
+            deleted: true,
+            id: pool.id,
+        })
+        .into_response(),
+        Ok(None) => not_found("LLM gateway proxy pool not found").into_response(),
+        Err(err) => {
+            tracing::warn!(proxy_pool_id = %pool_id, "failed to delete proxy pool: {err:#}");
+            conflict("Failed to delete llm gateway proxy pool; accounts may still reference it")
+                .into_response()
+        },
+    }
+}
+
 pub(crate) async fn check_llm_gateway_proxy_config(
     State(state): State<HttpState>,
     headers: HeaderMap,
@@ -1606,6 +2066,104 @@ pub(crate) async fn refresh_llm_gateway_proxy_traffic(
     }
 }
 
+/// Periodically re-check every active proxy pool member so pool selection
+/// sees fresh health, and refresh each member's traffic snapshot alongside.
+pub(crate) fn spawn_proxy_pool_health_checker(
+    proxy_store: std::sync::Arc<dyn core_store::AdminProxyStore>,
+    config_store: std::sync::Arc<dyn core_store::AdminConfigStore>,
+) {
+    tokio::spawn(async move {
+        let usage_client = reqwest::Client::new();
+        loop {
+            tokio::time::sleep(PROXY_POOL_RECHECK_INTERVAL).await;
+            if let Err(err) =
+                recheck_proxy_pools_once(proxy_store.as_ref(), config_store.as_ref(), &usage_client)
+                    .await
+            {
+                tracing::warn!("failed to re-check proxy pool members: {err:#}");
+            }
+        }
+    });
+}
+
+async fn recheck_proxy_pools_once(
+    proxy_store: &dyn core_store::AdminProxyStore,
+    config_store: &dyn core_store::AdminConfigStore,
+    usage_client: &reqwest::Client,
+) -> anyhow::Result<()> {
+    let pools = proxy_store.list_admin_proxy_pools().await?;
+    let mut proxy_ids = pools
+        .into_iter()
+        .filter(|pool| pool.status == KEY_STATUS_ACTIVE)
+        .flat_map(|pool| {
+            pool.members
+                .into_iter()
+                .map(|member| member.proxy_config_id)
+        })
+        .collect::<Vec<_>>();
+    proxy_ids.sort();
+    proxy_ids.dedup();
+    if proxy_ids.is_empty() {
+        return Ok(());
+    }
+    let config = config_store.get_admin_runtime_config().await?;
+    for proxy_id in proxy_ids {
+        let Some(proxy) = proxy_store.get_admin_proxy_config(&proxy_id).await? else {
+            continue;
+        };
+        for provider_type in [PROVIDER_CODEX, PROVIDER_KIRO] {
+            let result = match run_proxy_connectivity_check(&proxy, provider_type).await {
+                Ok(result) => result,
+                Err(err) => {
+                    tracing::debug!(
+                        proxy_config_id = %proxy_id,
+                        provider_type,
+                        "proxy pool member check failed to run: {err:#}"
+                    );
+                    continue;
+                },
+            };
+            if let Some(update) = proxy_endpoint_check_update_from_response(&result) {
+                proxy_store
+                    .record_admin_proxy_endpoint_check(update)
+                    .await?;
+            }
+        }
+        let query =
+            proxy_traffic_refresh_query(&proxy_id, config.usage_analytics_retention_days, now_ms());
+        let snapshot = match fetch_usage_worker_proxy_traffic_snapshot(
+            usage_client,
+            &config.usage_query_base_url,
+            &query,
+        )
+        .await
+        {
+            Ok(snapshot) => snapshot,
+            Err(err) => {
+                tracing::debug!(
+                    proxy_config_id = %proxy_id,
+                    "proxy pool member traffic refresh skipped: {}",
+                    err.message
+                );
+                continue;
+            },
+        };
+        let traffic_snapshot = core_store::AdminProxyTrafficSnapshot {
+            refreshed_at_ms: snapshot.generated_at_ms,
+            window_start_ms: snapshot.start_ms,
+            window_end_ms: snapshot.end_ms,
+            retention_days: proxy_traffic_refresh_window_days(
+                config.usage_analytics_retention_days,
+            ),
+            totals: snapshot.totals,
+        };
+        proxy_store
+            .record_admin_proxy_traffic_snapshot(&proxy_id, traffic_snapshot)
+            .await?;
+    }
+    Ok(())
+}
+
 pub(crate) async fn import_legacy_kiro_proxy_configs(
     State(state): State<HttpState>,
     headers: HeaderMap,
@@ -1899,6 +2457,9 @@ pub(crate) async fn list_llm_gateway_accounts(
         limit: page.limit,
         offset: page.offset,
         has_more: page.has_more,
+        circuit_breakers: state
+            .provider_state
+            .circuit_breaker_views(ProviderType::Codex),
         generated_at: now_ms(),
     })
     .into_response()
@@ -2152,6 +2713,11 @@ pub(crate) async fn patch_llm_gateway_account(
                 .into_response();
         }
     }
+    if let Some(Some(pool_id)) = patch.proxy_pool_id.as_ref() {
+        if let Err(response) = ensure_active_proxy_pool(&state, pool_id).await {
+            return response.into_response();
+        }
+    }
     match state
         .admin_codex_account_store
         .patch_admin_codex_account(&name, patch)
@@ -2180,6 +2746,7 @@ fn should_refresh_codex_public_status_after_patch(patch: &AdminCodexAccountPatch
         || patch.auto_refresh_enabled.is_some()
         || patch.proxy_mode.is_some()
         || patch.proxy_config_id.is_some()
+        || patch.proxy_pool_id.is_some()
 }
 
 async fn refresh_codex_public_status_after_account_update(
@@ -2493,9 +3060,16 @@ pub(crate) async fn probe_llm_gateway_account_models(
     };
     let client_version =
         crate::provider::resolve_codex_client_version(Some(&config.codex_client_version));
-    match validate_codex_access_token_for_import_with_client_version(&route, &auth, &client_version)
-        .await
-    {
+    let probe =
+        validate_codex_access_token_for_import_with_client_version(&route, &auth, &client_version)
+            .await;
+    let probe_error = probe.as_ref().err().map(|err| format!("{err:#}"));
+    state.provider_state.record_codex_account_probe(
+        &route,
+        probe.is_ok(),
+        probe_error.as_deref().unwrap_or_default(),
+    );
+    match probe {
         Ok(()) => Json(AdminCodexModelsProbeResponse {
             ok: true,
             message: "Codex models probe succeeded".to_string(),
@@ -2781,6 +3355,9 @@ pub(crate) async fn list_admin_kiro_account_statuses(
         total: page.total,
         limit: page.limit,
         offset: page.offset,
+        circuit_breakers: state
+            .provider_state
+            .circuit_breaker_views(ProviderType::Kiro),
         generated_at: now_ms(),
     })
     .into_response()
@@ -3091,6 +3668,184 @@ pub(crate) fn kiro_cache_simulation_config_from_admin_config(
     }
 }
 
+pub(crate) async fn list_admin_openai_upstream_channels(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+) -> Response {
+    if let Err(response) = ensure_admin_access(&headers) {
+        return response.into_response();
+    }
+    match state
+        .admin_openai_upstream_store
+        .list_admin_openai_upstream_channels()
+        .await
+    {
+        Ok(channels) => Json(AdminOpenAiUpstreamChannelsResponse {
+            channels,
+            generated_at: now_ms(),
+        })
+        .into_response(),
+        Err(_) => internal_error("Failed to list OpenAI upstream channels").into_response(),
+    }
+}
+
+pub(crate) async fn create_admin_openai_upstream_channel(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+    Json(request): Json<CreateAdminOpenAiUpstreamChannelRequest>,
+) -> Response {
+    if let Err(response) = ensure_admin_access(&headers) {
+        return response.into_response();
+    }
+    let channel = match normalize_new_openai_upstream_channel(request) {
+        Ok(channel) => channel,
+        Err(response) => return response.into_response(),
+    };
+    match state
+        .admin_openai_upstream_store
+        .list_admin_openai_upstream_channels()
+        .await
+    {
+        Ok(channels)
+            if channels
+                .iter()
+                .any(|existing| existing.name == channel.name) =>
+        {
+            return conflict("OpenAI upstream channel already exists").into_response()
+        },
+        Ok(_) => {},
+        Err(_) => {
+            return internal_error("Failed to inspect OpenAI upstream channels").into_response()
+        },
+    }
+    match state
+        .admin_openai_upstream_store
+        .create_admin_openai_upstream_channel(channel)
+        .await
+    {
+        Ok(channel) => Json(channel).into_response(),
+        Err(_) => internal_error("Failed to create OpenAI upstream channel").into_response(),
+    }
+}
+
+pub(crate) async fn patch_admin_openai_upstream_channel(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+    Json(request): Json<PatchAdminOpenAiUpstreamChannelRequest>,
+) -> Response {
+    if let Err(response) = ensure_admin_access(&headers) {
+        return response.into_response();
+    }
+    let name = match normalize_name(&name) {
+        Ok(name) => name,
+        Err(response) => return response.into_response(),
+    };
+    let patch = match normalize_openai_upstream_channel_patch(request) {
+        Ok(patch) => patch,
+        Err(response) => return response.into_response(),
+    };
+    match state
+        .admin_openai_upstream_store
+        .patch_admin_openai_upstream_channel(&name, patch)
+        .await
+    {
+        Ok(Some(channel)) => Json(channel).into_response(),
+        Ok(None) => not_found("OpenAI upstream channel not found").into_response(),
+        Err(_) => internal_error("Failed to update OpenAI upstream channel").into_response(),
+    }
+}
+
+pub(crate) async fn delete_admin_openai_upstream_channel(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+) -> Response {
+    if let Err(response) = ensure_admin_access(&headers) {
+        return response.into_response();
+    }
+    let name = match normalize_name(&name) {
+        Ok(name) => name,
+        Err(response) => return response.into_response(),
+    };
+    match state
+        .admin_openai_upstream_store
+        .delete_admin_openai_upstream_channel(&name)
+        .await
+    {
+        Ok(Some(channel)) => Json(DeleteResponse {
+            deleted: true,
+            id: channel.name,
+        })
+        .into_response(),
+        Ok(None) => not_found("OpenAI upstream channel not found").into_response(),
+        Err(_) => internal_error("Failed to delete OpenAI upstream channel").into_response(),
+    }
+}
+
+pub(crate) async fn refresh_admin_openai_upstream_models(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+) -> Response {
+    if let Err(response) = ensure_admin_access(&headers) {
+        return response.into_response();
+    }
+    let name = match normalize_name(&name) {
+        Ok(name) => name,
+        Err(response) => return response.into_response(),
+    };
+    let target = match state
+        .admin_openai_upstream_store
+        .load_admin_openai_upstream_probe_target(&name)
+        .await
+    {
+        Ok(Some(target)) => target,
+        Ok(None) => return not_found("OpenAI upstream channel not found").into_response(),
+        Err(err) => {
+            tracing::warn!(
+                channel = %name,
+                error = %err,
+                "failed to load OpenAI upstream probe target"
+            );
+            return internal_error("Failed to load OpenAI upstream channel").into_response();
+        },
+    };
+    let output = openai_upstream_probe::refresh_models(&target).await;
+    let update = core_store::AdminOpenAiUpstreamModelsStatusUpdate {
+        model_ids: output.model_ids.clone(),
+        status: output.status.clone(),
+        latency_ms: Some(output.latency_ms),
+        checked_at_ms: output.checked_at_ms,
+        error: output.error.clone(),
+    };
+    match state
+        .admin_openai_upstream_store
+        .save_admin_openai_upstream_models_status(&name, update)
+        .await
+    {
+        Ok(Some(channel)) => Json(AdminOpenAiUpstreamProbeResponse {
+            ok: output.status == "ok" && output.error.is_none(),
+            status: output.status,
+            status_code: output.status_code,
+            latency_ms: output.latency_ms,
+            error: output.error,
+            channel,
+            generated_at: now_ms(),
+        })
+        .into_response(),
+        Ok(None) => not_found("OpenAI upstream channel not found").into_response(),
+        Err(err) => {
+            tracing::warn!(
+                channel = %name,
+                error = %err,
+                "failed to save OpenAI upstream models status"
+            );
+            internal_error("Failed to save OpenAI upstream models status").into_response()
+        },
+    }
+}
+
 pub(crate) async fn import_admin_kiro_account(
     State(state): State<HttpState>,
     headers: HeaderMap,
@@ -3191,6 +3946,11 @@ pub(crate) async fn patch_admin_kiro_account(
                 .into_response();
         }
     }
+    if let Some(Some(pool_id)) = patch.proxy_pool_id.as_ref() {
+        if let Err(response) = ensure_active_proxy_pool(&state, pool_id).await {
+            return response.into_response();
+        }
+    }
     match state
         .admin_kiro_account_store
         .patch_admin_kiro_account(&name, patch)
@@ -3379,7 +4139,20 @@ pub(crate) async fn probe_admin_kiro_account_model(
         kiro_refresh::runtime_upstream_base_url(&route.api_region)
     );
     let started_at = Instant::now();
-    match provider::call_kiro_generate_for_route(
+    let probe_response =
+        |ok: bool, upstream_status_code: u16, message: String| AdminKiroModelProbeResponse {
+            ok,
+            account_name: name.clone(),
+            model: request.model.clone(),
+            api_region: route.api_region.clone(),
+            proxy_source: proxy_source.as_str().to_string(),
+            proxy_url: proxy.as_ref().map(|value| value.proxy_url.clone()),
+            upstream_status_code,
+            latency_ms: started_at.elapsed().as_millis().min(i64::MAX as u128) as i64,
+            checked_at: now_ms(),
+            message,
+        };
+    let (status, body) = match provider::call_kiro_generate_for_route(
         &route,
         state.provider_state.route_store().as_ref(),
         upstream_url,
@@ -3389,99 +4162,49 @@ pub(crate) async fn probe_admin_kiro_account_model(
     {
         Ok(response) => {
             let upstream_status_code = response.status().as_u16();
-            let bytes = match response.bytes().await {
-                Ok(bytes) => bytes,
-                Err(err) => {
-                    return (
-                        StatusCode::BAD_GATEWAY,
-                        Json(AdminKiroModelProbeResponse {
-                            ok: false,
-                            account_name: name,
-                            model: request.model,
-                            api_region: route.api_region,
-                            proxy_source: proxy_source.as_str().to_string(),
-                            proxy_url: proxy.map(|value| value.proxy_url),
-                            upstream_status_code,
-                            latency_ms: started_at.elapsed().as_millis().min(i64::MAX as u128)
-                                as i64,
-                            checked_at: now_ms(),
-                            message: format!("failed to read Kiro model probe response: {err}"),
-                        }),
-                    )
-                        .into_response();
-                },
-            };
-            let events = match provider::decode_kiro_events_from_bytes(&bytes) {
-                Ok(events) => events,
-                Err(err) => {
-                    return (
-                        StatusCode::BAD_GATEWAY,
-                        Json(AdminKiroModelProbeResponse {
-                            ok: false,
-                            account_name: name,
-                            model: request.model,
-                            api_region: route.api_region,
-                            proxy_source: proxy_source.as_str().to_string(),
-                            proxy_url: proxy.map(|value| value.proxy_url),
-                            upstream_status_code,
-                            latency_ms: started_at.elapsed().as_millis().min(i64::MAX as u128)
-                                as i64,
-                            checked_at: now_ms(),
-                            message: err,
-                        }),
-                    )
-                        .into_response();
-                },
-            };
-            if let Some(message) = kiro_probe_eventstream_error_message(&events) {
-                return (
+            match response.bytes().await {
+                Err(err) => (
                     StatusCode::BAD_GATEWAY,
-                    Json(AdminKiroModelProbeResponse {
-                        ok: false,
-                        account_name: name,
-                        model: request.model,
-                        api_region: route.api_region,
-                        proxy_source: proxy_source.as_str().to_string(),
-                        proxy_url: proxy.map(|value| value.proxy_url),
+                    probe_response(
+                        false,
                         upstream_status_code,
-                        latency_ms: started_at.elapsed().as_millis().min(i64::MAX as u128) as i64,
-                        checked_at: now_ms(),
-                        message,
-                    }),
-                )
-                    .into_response();
+                        format!("failed to read Kiro model probe response: {err}"),
+                    ),
+                ),
+                Ok(bytes) => match provider::decode_kiro_events_from_bytes(&bytes) {
+                    Err(err) => {
+                        (StatusCode::BAD_GATEWAY, probe_response(false, upstream_status_code, err))
+                    },
+                    Ok(events) => match kiro_probe_eventstream_error_message(&events) {
+                        Some(message) => (
+                            StatusCode::BAD_GATEWAY,
+                            probe_response(false, upstream_status_code, message),
+                        ),
+                        None => (
+                            StatusCode::OK,
+                            probe_response(
+                                true,
+                                upstream_status_code,
+                                "Kiro model probe succeeded".to_string(),
+                            ),
+                        ),
+                    },
+                },
             }
-            Json(AdminKiroModelProbeResponse {
-                ok: true,
-                account_name: name,
-                model: request.model,
-                api_region: route.api_region,
-                proxy_source: proxy_source.as_str().to_string(),
-                proxy_url: proxy.map(|value| value.proxy_url),
-                upstream_status_code,
-                latency_ms: started_at.elapsed().as_millis().min(i64::MAX as u128) as i64,
-                checked_at: now_ms(),
-                message: "Kiro model probe succeeded".to_string(),
-            })
-            .into_response()
         },
         Err(err) => (
             err.status(),
-            Json(AdminKiroModelProbeResponse {
-                ok: false,
-                account_name: name,
-                model: request.model,
-                api_region: route.api_region,
-                proxy_source: proxy_source.as_str().to_string(),
-                proxy_url: proxy.map(|value| value.proxy_url),
-                upstream_status_code: err.status().as_u16(),
-                latency_ms: started_at.elapsed().as_millis().min(i64::MAX as u128) as i64,
-                checked_at: now_ms(),
-                message: summarize_upstream_error_body(&err.body_text()),
-            }),
-        )
-            .into_response(),
-    }
+            probe_response(
+                false,
+                err.status().as_u16(),
+                summarize_upstream_error_body(&err.body_text()),
+            ),
+        ),
+    };
+    state
+        .provider_state
+        .record_kiro_account_probe(&route, body.ok, &body.message);
+    (status, Json(body)).into_response()
 }
 
 pub(crate) async fn list_llm_gateway_token_requests(
@@ -3641,40 +4364,120 @@ pub(crate) async fn approve_and_issue_llm_gateway_token_request(
     }
 }
 
-pub(crate) async fn reject_llm_gateway_token_request(
+pub(crate) async fn reject_llm_gateway_token_request(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+    Path(request_id): Path<String>,
+    Json(request): Json<ReviewQueueActionRequest>,
+) -> Response {
+    if let Err(response) = ensure_admin_access(&headers) {
+        return response.into_response();
+    }
+    let current = match state
+        .admin_review_queue_store
+        .get_admin_token_request(&request_id)
+        .await
+    {
+        Ok(Some(request)) => request,
+        Ok(None) => return not_found("LLM gateway token request not found").into_response(),
+        Err(_) => {
+            return internal_error("Failed to load llm gateway token request").into_response()
+        },
+    };
+    if current.status == "issued" {
+        return conflict("Issued LLM gateway token request cannot be rejected").into_response();
+    }
+    if current.status == "rejected" {
+        return conflict("LLM gateway token request is already rejected").into_response();
+    }
+    match state
+        .admin_review_queue_store
+        .reject_admin_token_request(&request_id, review_queue_action(request))
+        .await
+    {
+        Ok(Some(request)) => Json(request).into_response(),
+        Ok(None) => not_found("LLM gateway token request not found").into_response(),
+        Err(_) => internal_error("Failed to reject llm gateway token request").into_response(),
+    }
+}
+
+pub(crate) async fn list_llm_gateway_moderation_events(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+    Query(request): Query<ListReviewQueueRequest>,
+) -> Response {
+    if let Err(response) = ensure_admin_access(&headers) {
+        return response.into_response();
+    }
+    let query = normalize_review_queue_query(request);
+    match state
+        .admin_review_queue_store
+        .list_admin_moderation_events(query)
+        .await
+    {
+        Ok(page) => Json(AdminModerationEventsResponse {
+            total: page.total,
+            offset: page.offset,
+            limit: page.limit,
+            has_more: page.has_more,
+            events: page.events,
+            generated_at: now_ms(),
+        })
+        .into_response(),
+        Err(_) => internal_error("Failed to list llm gateway moderation events").into_response(),
+    }
+}
+
+pub(crate) async fn confirm_llm_gateway_moderation_event(
     State(state): State<HttpState>,
     headers: HeaderMap,
-    Path(request_id): Path<String>,
+    Path(event_id): Path<String>,
     Json(request): Json<ReviewQueueActionRequest>,
 ) -> Response {
-    if let Err(response) = ensure_admin_access(&headers) {
+    review_llm_gateway_moderation_event(
+        &state,
+        &headers,
+        &event_id,
+        core_store::MODERATION_EVENT_STATUS_CONFIRMED,
+        request,
+    )
+    .await
+}
+
+pub(crate) async fn dismiss_llm_gateway_moderation_event(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+    Path(event_id): Path<String>,
+    Json(request): Json<ReviewQueueActionRequest>,
+) -> Response {
+    review_llm_gateway_moderation_event(
+        &state,
+        &headers,
+        &event_id,
+        core_store::MODERATION_EVENT_STATUS_DISMISSED,
+        request,
+    )
+    .await
+}
+
+async fn review_llm_gateway_moderation_event(
+    state: &HttpState,
+    headers: &HeaderMap,
+    event_id: &str,
+    status: &str,
+    request: ReviewQueueActionRequest,
+) -> Response {
+    if let Err(response) = ensure_admin_access(headers) {
         return response.into_response();
     }
-    let current = match state
-        .admin_review_queue_store
-        .get_admin_token_request(&request_id)
-        .await
-    {
-        Ok(Some(request)) => request,
-        Ok(None) => return not_found("LLM gateway token request not found").into_response(),
-        Err(_) => {
-            return internal_error("Failed to load llm gateway token request").into_response()
-        },
-    };
-    if current.status == "issued" {
-        return conflict("Issued LLM gateway token request cannot be rejected").into_response();
-    }
-    if current.status == "rejected" {
-        return conflict("LLM gateway token request is already rejected").into_response();
-    }
     match state
         .admin_review_queue_store
-        .reject_admin_token_request(&request_id, review_queue_action(request))
+        .review_admin_moderation_event(event_id, status, review_queue_action(request))
         .await
     {
-        Ok(Some(request)) => Json(request).into_response(),
-        Ok(None) => not_found("LLM gateway token request not found").into_response(),
-        Err(_) => internal_error("Failed to reject llm gateway token request").into_response(),
+        Ok(Some(event)) => Json(event).into_response(),
+        Ok(None) => not_found("LLM gateway moderation event not found").into_response(),
+        Err(_) => internal_error("Failed to review llm gateway moderation event").into_response(),
     }
 }
 
@@ -3855,6 +4658,8 @@ pub(crate) async fn approve_and_issue_llm_gateway_account_contribution_request(
                 provider_type: PROVIDER_CODEX.to_string(),
                 name: name.clone(),
                 account_names: vec![imported_account_name],
+                request_transform_policy: None,
+                queue_policy: None,
                 created_at_ms: action.updated_at_ms,
             }),
             Some(NewAdminKey {
@@ -4145,11 +4950,22 @@ async fn create_account_group_for_provider(
         Ok(None) => return bad_request("account_names must not be empty").into_response(),
         Err(response) => return response.into_response(),
     };
+    let request_transform_policy =
+        match normalize_request_transform_policy(request.request_transform_policy) {
+            Ok(policy) => policy.flatten(),
+            Err(response) => return response.into_response(),
+        };
+    let queue_policy = match normalize_queue_policy(request.queue_policy) {
+        Ok(policy) => policy.flatten(),
+        Err(response) => return response.into_response(),
+    };
     let group = NewAdminAccountGroup {
         id: generate_id(id_prefix),
         provider_type: provider_type.to_string(),
         name,
         account_names,
+        request_transform_policy,
+        queue_policy,
         created_at_ms: now_ms(),
     };
     match state
@@ -4195,9 +5011,20 @@ async fn patch_account_group_for_provider(
         Ok(value) => value.flatten(),
         Err(response) => return response.into_response(),
     };
+    let request_transform_policy =
+        match normalize_request_transform_policy(request.request_transform_policy) {
+            Ok(policy) => policy,
+            Err(response) => return response.into_response(),
+        };
+    let queue_policy = match normalize_queue_policy(request.queue_policy) {
+        Ok(policy) => policy,
+        Err(response) => return response.into_response(),
+    };
     let patch = AdminAccountGroupPatch {
         name,
         account_names,
+        request_transform_policy,
+        queue_policy,
         updated_at_ms: now_ms(),
     };
     match state
@@ -6643,6 +7470,11 @@ fn normalize_key_patch(
         .as_deref()
         .map(normalize_anthropic_upstream_pool_mode_input)
         .transpose()?;
+    let codex_openai_upstream_pool_mode = request
+        .codex_openai_upstream_pool_mode
+        .as_deref()
+        .map(normalize_openai_upstream_pool_mode_input)
+        .transpose()?;
     let kiro_model_group_preferences = request
         .kiro_model_group_preferences
         .map(core_store::normalize_kiro_model_group_preferences);
@@ -6677,6 +7509,15 @@ fn normalize_key_patch(
             Some(None) => Some(None),
             None => None,
         };
+    if let Some(policy) = request.response_cache_policy.as_ref() {
+        policy
+            .validate()
+            .map_err(|err| bad_request(&err.to_string()))?;
+    }
+    let request_transform_policy =
+        normalize_request_transform_policy(request.request_transform_policy)?;
+    let moderation_policy = normalize_moderation_policy(request.moderation_policy)?;
+    let queue_policy = normalize_queue_policy(request.queue_policy)?;
     let codex_image_standalone_generation_enabled = request
         .codex_image_standalone_generation_enabled
         .or(request.codex_image_generation_enabled);
@@ -6691,6 +7532,7 @@ fn normalize_key_patch(
         auto_account_names,
         preferred_pool_strategy,
         kiro_anthropic_upstream_pool_mode,
+        codex_openai_upstream_pool_mode,
         model_name_map: request.model_name_map.map(Some),
         kiro_model_group_preferences,
         request_max_concurrency,
@@ -6711,10 +7553,54 @@ fn normalize_key_patch(
         kiro_cctest_text_handling_enabled: request.kiro_cctest_text_handling_enabled,
         kiro_cache_policy_override_json: request.kiro_cache_policy_override_json,
         kiro_billable_model_multipliers_override_json,
+        response_cache_policy: request.response_cache_policy.map(Some),
+        request_transform_policy,
+        moderation_policy,
+        queue_policy,
         updated_at_ms: now_ms(),
     })
 }
 
+/// Validate an admin-supplied transform policy; an empty policy clears it.
+fn normalize_request_transform_policy(
+    policy: Option<core_store::RequestTransformPolicy>,
+) -> Result<Option<Option<core_store::RequestTransformPolicy>>, AdminHttpError> {
+    let Some(policy) = policy else {
+        return Ok(None);
+    };
+    policy
+        .validate()
+        .map_err(|err| bad_request(&err.to_string()))?;
+    Ok(Some((!policy.is_empty()).then_some(policy)))
+}
+
+/// Validate an admin-supplied moderation policy; a rule-less policy clears
+/// it.
+fn normalize_moderation_policy(
+    policy: Option<core_store::KeyModerationPolicy>,
+) -> Result<Option<Option<core_store::KeyModerationPolicy>>, AdminHttpError> {
+    let Some(policy) = policy else {
+        return Ok(None);
+    };
+    policy
+        .validate()
+        .map_err(|err| bad_request(&err.to_string()))?;
+    Ok(Some(policy.has_rules().then_some(policy)))
+}
+
+/// Validate an admin-supplied queue policy; an empty policy clears it.
+fn normalize_queue_policy(
+    policy: Option<core_store::KeyQueuePolicy>,
+) -> Result<Option<Option<core_store::KeyQueuePolicy>>, AdminHttpError> {
+    let Some(policy) = policy else {
+        return Ok(None);
+    };
+    policy
+        .validate()
+        .map_err(|err| bad_request(&err.to_string()))?;
+    Ok(Some((!policy.is_empty()).then_some(policy)))
+}
+
 fn normalize_kiro_key_patch(
     mut request: PatchLlmGatewayKeyRequest,
 ) -> Result<AdminKeyPatch, AdminHttpError> {
@@ -6912,6 +7798,125 @@ fn normalize_anthropic_upstream_channel_patch(
     })
 }
 
+fn normalize_openai_upstream_base_url(raw: &str) -> Result<String, AdminHttpError> {
+    let trimmed = raw.trim().trim_end_matches('/');
+    llm_access_openai_pool::build_models_url(trimmed)
+        .map_err(|err| bad_request(&err.to_string().replace("openai upstream ", "")))?;
+    Ok(trimmed.to_string())
+}
+
+fn normalize_new_openai_upstream_channel(
+    request: CreateAdminOpenAiUpstreamChannelRequest,
+) -> Result<core_store::NewAdminOpenAiUpstreamChannel, AdminHttpError> {
+    let name = normalize_name(&request.name)?;
+    let status = match request.status.as_deref() {
+        Some(raw) => normalize_status(raw)?,
+        None => KEY_STATUS_ACTIVE.to_string(),
+    };
+    let base_url = normalize_openai_upstream_base_url(&request.base_url)?;
+    let api_key = request
+        .api_key
+        .as_deref()
+        .map(str::trim)
+        .unwrap_or_default()
+        .to_string();
+    let weight = request
+        .weight
+        .unwrap_or(core_store::DEFAULT_OPENAI_UPSTREAM_WEIGHT);
+    validate_range("weight", weight, 1, MAX_ANTHROPIC_UPSTREAM_WEIGHT)?;
+    let max_concurrency = request
+        .max_concurrency
+        .unwrap_or(core_store::DEFAULT_OPENAI_UPSTREAM_MAX_CONCURRENCY);
+    validate_range("max_concurrency", max_concurrency, 1, MAX_CODEX_KEY_REQUEST_MAX_CONCURRENCY)?;
+    let min_start_interval_ms = request
+        .min_start_interval_ms
+        .unwrap_or(core_store::DEFAULT_OPENAI_UPSTREAM_MIN_START_INTERVAL_MS);
+    validate_max(
+        "min_start_interval_ms",
+        min_start_interval_ms,
+        MAX_CODEX_KEY_REQUEST_MIN_START_INTERVAL_MS,
+    )?;
+    let proxy_mode = normalize_anthropic_upstream_proxy_mode(request.proxy_mode.as_deref())?;
+    let proxy_config_id = request
+        .proxy_config_id
+        .as_deref()
+        .and_then(normalize_optional_string);
+    if proxy_mode == "fixed" && proxy_config_id.is_none() {
+        return Err(bad_request("proxy_config_id is required when proxy_mode is `fixed`"));
+    }
+    Ok(core_store::NewAdminOpenAiUpstreamChannel {
+        name,
+        status,
+        base_url,
+        api_key,
+        weight,
+        max_concurrency,
+        min_start_interval_ms,
+        proxy_mode,
+        proxy_config_id,
+        created_at_ms: now_ms(),
+    })
+}
+
+fn normalize_openai_upstream_channel_patch(
+    request: PatchAdminOpenAiUpstreamChannelRequest,
+) -> Result<core_store::AdminOpenAiUpstreamChannelPatch, AdminHttpError> {
+    let status = match request.status.as_deref() {
+        Some(raw) => Some(normalize_status(raw)?),
+        None => None,
+    };
+    let base_url = request
+        .base_url
+        .as_deref()
+        .map(normalize_openai_upstream_base_url)
+        .transpose()?;
+    if let Some(weight) = request.weight {
+        validate_range("weight", weight, 1, MAX_ANTHROPIC_UPSTREAM_WEIGHT)?;
+    }
+    if let Some(max_concurrency) = request.max_concurrency {
+        validate_range(
+            "max_concurrency",
+            max_concurrency,
+            1,
+            MAX_CODEX_KEY_REQUEST_MAX_CONCURRENCY,
+        )?;
+    }
+    if let Some(min_start_interval_ms) = request.min_start_interval_ms {
+        validate_max(
+            "min_start_interval_ms",
+            min_start_interval_ms,
+            MAX_CODEX_KEY_REQUEST_MIN_START_INTERVAL_MS,
+        )?;
+    }
+    let proxy_mode = request
+        .proxy_mode
+        .as_deref()
+        .map(|raw| normalize_anthropic_upstream_proxy_mode(Some(raw)))
+        .transpose()?;
+    let proxy_config_id = request
+        .proxy_config_id
+        .map(|value| value.as_deref().and_then(normalize_optional_string));
+    if proxy_mode.as_deref() == Some("fixed")
+        && !proxy_config_id
+            .as_ref()
+            .is_some_and(|value| value.is_some())
+    {
+        return Err(bad_request("proxy_config_id is required when proxy_mode is `fixed`"));
+    }
+    Ok(core_store::AdminOpenAiUpstreamChannelPatch {
+        status,
+        base_url,
+        api_key: request.api_key.map(|raw| raw.trim().to_string()),
+        weight: request.weight,
+        max_concurrency: request.max_concurrency,
+        min_start_interval_ms: request.min_start_interval_ms,
+        proxy_mode,
+        proxy_config_id,
+        clear_last_error: request.clear_last_error,
+        updated_at_ms: now_ms(),
+    })
+}
+
 fn normalize_anthropic_upstream_test_request(
     request: TestAdminAnthropicUpstreamChannelRequest,
 ) -> Result<String, AdminHttpError> {
@@ -6969,6 +7974,17 @@ fn normalize_anthropic_upstream_pool_mode_input(raw: &str) -> Result<String, Adm
         })
 }
 
+fn normalize_openai_upstream_pool_mode_input(raw: &str) -> Result<String, AdminHttpError> {
+    core_store::normalize_openai_upstream_pool_mode(raw)
+        .map(str::to_string)
+        .ok_or_else(|| {
+            bad_request(
+                "codex_openai_upstream_pool_mode must be `disabled`, `preferred_before_codex`, or \
+                 `only`",
+            )
+        })
+}
+
 fn validate_provider_type(provider_type: &str) -> Result<(), AdminHttpError> {
     match provider_type {
         PROVIDER_CODEX | PROVIDER_KIRO => Ok(()),
@@ -7276,6 +8292,18 @@ fn normalize_account_patch(
     {
         return Err(bad_request("fixed proxy_mode requires proxy_config_id"));
     }
+    let proxy_pool_id = request
+        .proxy_pool_id
+        .as_deref()
+        .map(|value| normalize_optional_string_option(Some(value)));
+    if matches!(proxy_mode.as_deref(), Some("pool"))
+        && proxy_pool_id
+            .as_ref()
+            .and_then(|value| value.as_ref())
+            .is_none()
+    {
+        return Err(bad_request("pool proxy_mode requires proxy_pool_id"));
+    }
     let route_weight_tier = request
         .route_weight_tier
         .as_deref()
@@ -7305,6 +8333,7 @@ fn normalize_account_patch(
         route_weight_tier,
         proxy_mode,
         proxy_config_id,
+        proxy_pool_id,
         request_max_concurrency,
         request_min_start_interval_ms,
         codex_image_generation_enabled: request.codex_image_generation_enabled,
@@ -7598,6 +8627,18 @@ fn normalize_kiro_account_patch(
     {
         return Err(bad_request("fixed proxy_mode requires proxy_config_id"));
     }
+    let proxy_pool_id = request
+        .proxy_pool_id
+        .as_deref()
+        .map(|value| normalize_optional_string_option(Some(value)));
+    if matches!(proxy_mode.as_deref(), Some("pool"))
+        && proxy_pool_id
+            .as_ref()
+            .and_then(|value| value.as_ref())
+            .is_none()
+    {
+        return Err(bad_request("pool proxy_mode requires proxy_pool_id"));
+    }
     Ok(core_store::AdminKiroAccountPatch {
         status,
         max_concurrency: request.kiro_channel_max_concurrency,
@@ -7607,6 +8648,7 @@ fn normalize_kiro_account_patch(
         pool_strategy,
         proxy_mode,
         proxy_config_id,
+        proxy_pool_id,
         updated_at_ms: now_ms(),
     })
 }
@@ -7614,8 +8656,8 @@ fn normalize_kiro_account_patch(
 fn normalize_proxy_mode(raw: &str) -> Result<String, AdminHttpError> {
     let trimmed = raw.trim();
     match trimmed {
-        "inherit" | "fixed" | "none" => Ok(trimmed.to_string()),
-        _ => Err(bad_request("proxy_mode must be `inherit`, `fixed`, or `none`")),
+        "inherit" | "fixed" | "pool" | "none" => Ok(trimmed.to_string()),
+        _ => Err(bad_request("proxy_mode must be `inherit`, `fixed`, `pool`, or `none`")),
     }
 }
 
@@ -8004,6 +9046,7 @@ mod tests {
             auto_account_names: None,
             preferred_pool_strategy: None,
             kiro_anthropic_upstream_pool_mode: None,
+            codex_openai_upstream_pool_mode: None,
             model_name_map: None,
             kiro_model_group_preferences: None,
             request_max_concurrency: None,
@@ -8025,6 +9068,10 @@ mod tests {
             kiro_cctest_text_handling_enabled: None,
             kiro_cache_policy_override_json: None,
             kiro_billable_model_multipliers_override_json: None,
+            response_cache_policy: None,
+            request_transform_policy: None,
+            moderation_policy: None,
+            queue_policy: None,
         }
     }
 
@@ -8037,6 +9084,7 @@ mod tests {
             status: KEY_STATUS_ACTIVE.to_string(),
             provider_type: PROVIDER_KIRO.to_string(),
             public_visible: true,
+            parent_key_id: None,
             quota_billable_limit: 1_000_000,
             usage_input_uncached_tokens: 0,
             usage_input_cached_tokens: 0,
@@ -8075,6 +9123,11 @@ mod tests {
             kiro_cache_policy_override_json: policy_override_json,
             kiro_billable_model_multipliers_override_json: None,
             kiro_anthropic_upstream_pool_mode: core_store::default_anthropic_upstream_pool_mode(),
+            codex_openai_upstream_pool_mode: core_store::default_openai_upstream_pool_mode(),
+            response_cache_policy: None,
+            request_transform_policy: None,
+            moderation_policy: None,
+            queue_policy: None,
             effective_kiro_cache_policy_json: "{}".to_string(),
             uses_global_kiro_cache_policy: true,
             effective_kiro_billable_model_multipliers_json:
@@ -8114,6 +9167,7 @@ mod tests {
             pool_strategy: core_store::default_kiro_pool_strategy(),
             proxy_mode: "inherit".to_string(),
             proxy_config_id: None,
+            proxy_pool_id: None,
             effective_proxy_source: "inherit".to_string(),
             effective_proxy_url: None,
             effective_proxy_config_name: None,
@@ -8141,6 +9195,8 @@ mod tests {
                 .iter()
                 .map(|name| (*name).to_string())
                 .collect(),
+            request_transform_policy: None,
+            queue_policy: None,
             created_at: 1,
             updated_at: 1,
         }
@@ -8325,6 +9381,7 @@ mod tests {
             route_weight_tier: None,
             proxy_mode: None,
             proxy_config_id: None,
+            proxy_pool_id: None,
             map_gpt53_codex_to_spark: None,
             auto_refresh_enabled: Some(false),
             request_max_concurrency: None,
@@ -8810,6 +9867,7 @@ mod tests {
                 core_store::DEFAULT_CODEX_IMAGE_GENERATION_MAX_CONCURRENCY,
             proxy_mode: "inherit".to_string(),
             proxy_config_id: None,
+            proxy_pool_id: None,
             effective_proxy_source: "binding".to_string(),
             effective_proxy_url: Some("http://127.0.0.1:11118".to_string()),
             effective_proxy_config_name: Some("us-home1".to_string()),
@@ -8874,6 +9932,7 @@ mod tests {
                 core_store::DEFAULT_CODEX_IMAGE_GENERATION_MAX_CONCURRENCY,
             proxy_mode: "inherit".to_string(),
             proxy_config_id: None,
+            proxy_pool_id: None,
             effective_proxy_source: "binding".to_string(),
             effective_proxy_url: Some("http://127.0.0.1:11118".to_string()),
             effective_proxy_config_name: Some("us-home1".to_string()),
@@ -8934,6 +9993,7 @@ mod tests {
                 core_store::DEFAULT_CODEX_IMAGE_GENERATION_MAX_CONCURRENCY,
             proxy_mode: "inherit".to_string(),
             proxy_config_id: None,
+            proxy_pool_id: None,
             effective_proxy_source: "binding".to_string(),
             effective_proxy_url: Some("http://127.0.0.1:11118".to_string()),
             effective_proxy_config_name: Some("us-home1".to_string()),
@@ -9211,6 +10271,187 @@ mod tests {
         assert_eq!(error.status, StatusCode::BAD_REQUEST);
     }
 
+    #[test]
+    fn normalize_key_patch_validates_codex_openai_upstream_pool_mode() {
+        let patch = normalize_key_patch(PatchLlmGatewayKeyRequest {
+            codex_openai_upstream_pool_mode: Some(" preferred_before_codex ".to_string()),
+            ..empty_key_patch_request()
+        })
+        .expect("key patch should normalize");
+        assert_eq!(
+            patch.codex_openai_upstream_pool_mode.as_deref(),
+            Some(core_store::OPENAI_UPSTREAM_POOL_MODE_PREFERRED_BEFORE_CODEX)
+        );
+
+        let error = normalize_key_patch(PatchLlmGatewayKeyRequest {
+            codex_openai_upstream_pool_mode: Some("preferred_before_kiro".to_string()),
+            ..empty_key_patch_request()
+        })
+        .expect_err("kiro pool mode should not apply to the openai pool");
+        assert_eq!(error.status, StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn normalize_openai_upstream_channel_allows_private_http_and_blocks_metadata() {
+        let channel =
+            normalize_new_openai_upstream_channel(CreateAdminOpenAiUpstreamChannelRequest {
+                name: "local-vllm".to_string(),
+                base_url: "http://10.0.0.5:8000/v1/".to_string(),
+                api_key: None,
+                status: None,
+                weight: None,
+                max_concurrency: None,
+                min_start_interval_ms: None,
+                proxy_mode: None,
+                proxy_config_id: None,
+            })
+            .expect("private http channel should normalize");
+        assert_eq!(channel.base_url, "http://10.0.0.5:8000/v1");
+        assert_eq!(channel.api_key, "");
+        assert_eq!(channel.max_concurrency, core_store::DEFAULT_OPENAI_UPSTREAM_MAX_CONCURRENCY);
+
+        let error =
+            normalize_openai_upstream_channel_patch(PatchAdminOpenAiUpstreamChannelRequest {
+                status: None,
+                base_url: Some("http://169.254.169.254/latest".to_string()),
+                api_key: None,
+                weight: None,
+                max_concurrency: None,
+                min_start_interval_ms: None,
+                proxy_mode: None,
+                proxy_config_id: None,
+                clear_last_error: false,
+            })
+            .expect_err("metadata base_url should fail");
+        assert_eq!(error.status, StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn normalize_key_patch_validates_response_cache_policy() {
+        let policy = core_store::KeyResponseCachePolicy {
+            enabled: true,
+            ttl_seconds: 300,
+            hit_billable_ratio: 0.1,
+        };
+        let patch = normalize_key_patch(PatchLlmGatewayKeyRequest {
+            response_cache_policy: Some(policy.clone()),
+            ..empty_key_patch_request()
+        })
+        .expect("valid response cache policy");
+        assert_eq!(patch.response_cache_policy, Some(Some(policy)));
+
+        let error = normalize_key_patch(PatchLlmGatewayKeyRequest {
+            response_cache_policy: Some(core_store::KeyResponseCachePolicy {
+                enabled: true,
+                ttl_seconds: 300,
+                hit_billable_ratio: 2.0,
+            }),
+            ..empty_key_patch_request()
+        })
+        .expect_err("out-of-range ratio should fail");
+        assert_eq!(error.status, StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn normalize_key_patch_validates_and_clears_request_transform_policy() {
+        let policy =
+            serde_json::from_value::<core_store::RequestTransformPolicy>(serde_json::json!({
+                "system_prompt_prepend": "Follow the tenant style guide.",
+                "redactions": [{ "pattern": "\\b\\d{16}\\b" }],
+            }))
+            .expect("policy");
+        let patch = normalize_key_patch(PatchLlmGatewayKeyRequest {
+            request_transform_policy: Some(policy.clone()),
+            ..empty_key_patch_request()
+        })
+        .expect("valid request transform policy");
+        assert_eq!(patch.request_transform_policy, Some(Some(policy)));
+
+        let cleared = normalize_key_patch(PatchLlmGatewayKeyRequest {
+            request_transform_policy: Some(core_store::RequestTransformPolicy::default()),
+            ..empty_key_patch_request()
+        })
+        .expect("empty policy clears");
+        assert_eq!(cleared.request_transform_policy, Some(None));
+
+        let error = normalize_key_patch(PatchLlmGatewayKeyRequest {
+            request_transform_policy: Some(core_store::RequestTransformPolicy {
+                redactions: vec![core_store::RequestRedactionRule {
+                    pattern: "([".to_string(),
+                    replacement: String::new(),
+                }],
+                ..core_store::RequestTransformPolicy::default()
+            }),
+            ..empty_key_patch_request()
+        })
+        .expect_err("invalid regex should fail");
+        assert_eq!(error.status, StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn normalize_key_patch_validates_and_clears_moderation_policy() {
+        let policy = core_store::KeyModerationPolicy {
+            action: core_store::ModerationAction::Block,
+            keywords: vec!["forbidden".to_string()],
+            ..core_store::KeyModerationPolicy::default()
+        };
+        let patch = normalize_key_patch(PatchLlmGatewayKeyRequest {
+            moderation_policy: Some(policy.clone()),
+            ..empty_key_patch_request()
+        })
+        .expect("valid moderation policy");
+        assert_eq!(patch.moderation_policy, Some(Some(policy)));
+
+        let cleared = normalize_key_patch(PatchLlmGatewayKeyRequest {
+            moderation_policy: Some(core_store::KeyModerationPolicy::default()),
+            ..empty_key_patch_request()
+        })
+        .expect("rule-less policy clears");
+        assert_eq!(cleared.moderation_policy, Some(None));
+
+        let error = normalize_key_patch(PatchLlmGatewayKeyRequest {
+            moderation_policy: Some(core_store::KeyModerationPolicy {
+                patterns: vec!["(".to_string()],
+                ..core_store::KeyModerationPolicy::default()
+            }),
+            ..empty_key_patch_request()
+        })
+        .expect_err("invalid pattern should fail");
+        assert_eq!(error.status, StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn normalize_key_patch_validates_and_clears_queue_policy() {
+        let policy = core_store::KeyQueuePolicy {
+            weight: Some(4),
+            priority: Some(core_store::QueuePriority::Interactive),
+            max_queued_requests: None,
+        };
+        let patch = normalize_key_patch(PatchLlmGatewayKeyRequest {
+            queue_policy: Some(policy.clone()),
+            ..empty_key_patch_request()
+        })
+        .expect("valid queue policy");
+        assert_eq!(patch.queue_policy, Some(Some(policy)));
+
+        let cleared = normalize_key_patch(PatchLlmGatewayKeyRequest {
+            queue_policy: Some(core_store::KeyQueuePolicy::default()),
+            ..empty_key_patch_request()
+        })
+        .expect("empty policy clears");
+        assert_eq!(cleared.queue_policy, Some(None));
+
+        let error = normalize_key_patch(PatchLlmGatewayKeyRequest {
+            queue_policy: Some(core_store::KeyQueuePolicy {
+                weight: Some(0),
+                ..core_store::KeyQueuePolicy::default()
+            }),
+            ..empty_key_patch_request()
+        })
+        .expect_err("zero weight should fail");
+        assert_eq!(error.status, StatusCode::BAD_REQUEST);
+    }
+
     fn sample_create_anthropic_upstream_channel_request(
     ) -> CreateAdminAnthropicUpstreamChannelRequest {
         CreateAdminAnthropicUpstreamChannelRequest {
@@ -9327,6 +10568,7 @@ mod tests {
             manual_usage_limit: None,
             proxy_mode: None,
             proxy_config_id: None,
+            proxy_pool_id: None,
         })
         .expect("kiro account patch should normalize");
 
@@ -9344,6 +10586,7 @@ mod tests {
             pool_strategy: None,
             proxy_mode: None,
             proxy_config_id: None,
+            proxy_pool_id: None,
         })
         .expect("manual Kiro usage limit should normalize");
 
@@ -9358,6 +10601,7 @@ mod tests {
             pool_strategy: None,
             proxy_mode: None,
             proxy_config_id: None,
+            proxy_pool_id: None,
         })
         .expect("clearing manual Kiro usage limit should normalize");
 
@@ -9388,6 +10632,7 @@ mod tests {
             pool_strategy: None,
             proxy_mode: None,
             proxy_config_id: None,
+            proxy_pool_id: None,
         })
         .expect_err("negative manual Kiro usage limit should be rejected");
 
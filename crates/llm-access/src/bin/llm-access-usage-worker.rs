@@ -45,6 +45,12 @@ fn run() -> anyhow::Result<()> {
             return Ok(());
         },
         CliCommand::Serve(config) => (config.bind_addr, config.storage),
+        CliCommand::RotateSecrets(control_store) => {
+            return llm_access::rotate_secrets(&control_store);
+        },
+        CliCommand::GenerateSecretKey {
+            key_id,
+        } => return llm_access::generate_secret_key(&key_id),
     };
     llm_access::bootstrap_usage_worker_storage(&storage)?;
     let runtime = tokio::runtime::Runtime::new().context("failed to create tokio runtime")?;
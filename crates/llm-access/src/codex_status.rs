@@ -1630,6 +1630,7 @@ mod tests {
                 llm_access_core::store::DEFAULT_CODEX_IMAGE_GENERATION_MAX_CONCURRENCY,
             proxy_mode: "inherit".to_string(),
             proxy_config_id: None,
+            proxy_pool_id: None,
             effective_proxy_source: "binding".to_string(),
             effective_proxy_url: None,
             effective_proxy_config_name: None,
@@ -7,9 +7,11 @@ use std::{
 };
 
 use anyhow::{anyhow, Context};
+use llm_access_core::provider::ProviderType;
 use llm_access_store::request_cache as store_request_cache;
 
 const DEFAULT_TIERED_DUCKDB_ROLLOVER_BYTES: u64 = 64 * 1024 * 1024;
+const DEFAULT_REPLAY_LIMIT: usize = 100;
 
 /// Backing store used for the llm-access control plane.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -89,6 +91,37 @@ pub struct ServeConfig {
     pub storage: StorageConfig,
 }
 
+/// Where `replay` reads captured usage events from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplaySource {
+    /// Hot usage journal root containing `active/`, `sealed/` and `consuming/`.
+    Journal(PathBuf),
+    /// Single-file DuckDB usage database, ideally a copy of the live one.
+    DuckDb(PathBuf),
+}
+
+/// Captured-traffic replay options.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayConfig {
+    /// Event source.
+    pub source: ReplaySource,
+    /// Only replay events recorded for this key name.
+    pub key_name: Option<String>,
+    /// Only replay this event.
+    pub event_id: Option<String>,
+    /// Only replay events for this provider.
+    pub provider_type: Option<ProviderType>,
+    /// Only replay events newer than this many milliseconds.
+    pub since_ms: Option<i64>,
+    /// Maximum number of matching events to replay.
+    pub limit: usize,
+    /// JSON pointers excluded from the payload diff, on top of the defaults.
+    pub ignore_pointers: Vec<String>,
+    /// Optional upstream base URL (normally a mock upstream) to send replayed
+    /// payloads to.
+    pub send_to: Option<String>,
+}
+
 /// Parsed command-line command.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CliCommand {
@@ -96,6 +129,16 @@ pub enum CliCommand {
     Init(StorageConfig),
     /// Initialize storage, then run the HTTP server.
     Serve(ServeConfig),
+    /// Reseal stored secrets under the active master key, then exit.
+    RotateSecrets(ControlStoreConfig),
+    /// Print a fresh master key file line for `key_id`, then exit.
+    GenerateSecretKey {
+        /// Id recorded with every value the key seals.
+        key_id: String,
+    },
+    /// Re-run captured requests through the current converters and diff the
+    /// upstream payloads against what was recorded, then exit.
+    Replay(ReplayConfig),
 }
 
 impl CliCommand {
@@ -117,11 +160,127 @@ impl CliCommand {
                     storage,
                 }))
             },
+            "rotate-secrets" => Ok(Self::RotateSecrets(parse_control_store_args(args)?)),
+            "generate-secret-key" => {
+                let key_id = args.next().ok_or_else(usage_error)?;
+                if args.next().is_some() {
+                    return Err(usage_error());
+                }
+                Ok(Self::GenerateSecretKey {
+                    key_id: key_id.to_string_lossy().to_string(),
+                })
+            },
+            "replay" => Ok(Self::Replay(parse_replay_args(args)?)),
             _ => Err(usage_error()),
         }
     }
 }
 
+fn parse_control_store_args<I>(args: I) -> anyhow::Result<ControlStoreConfig>
+where
+    I: IntoIterator<Item = OsString>,
+{
+    let mut database_url_env = None;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.to_string_lossy().as_ref() {
+            "--postgres-control-database-url-env" => {
+                database_url_env = Some(
+                    args.next()
+                        .ok_or_else(|| {
+                            anyhow!("--postgres-control-database-url-env requires an env name")
+                        })?
+                        .to_string_lossy()
+                        .to_string(),
+                );
+            },
+            _ => return Err(usage_error()),
+        }
+    }
+    Ok(ControlStoreConfig {
+        database_url_env: database_url_env.ok_or_else(usage_error)?,
+    })
+}
+
+fn parse_replay_args<I>(args: I) -> anyhow::Result<ReplayConfig>
+where
+    I: IntoIterator<Item = OsString>,
+{
+    let mut source = None;
+    let mut key_name = None;
+    let mut event_id = None;
+    let mut provider_type = None;
+    let mut since_ms = None;
+    let mut limit = DEFAULT_REPLAY_LIMIT;
+    let mut ignore_pointers = Vec::new();
+    let mut send_to = None;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let flag = arg.to_string_lossy().to_string();
+        let mut value = || {
+            args.next()
+                .map(|value| value.to_string_lossy().to_string())
+                .ok_or_else(|| anyhow!("{flag} requires a value"))
+        };
+        match flag.as_str() {
+            "--journal-dir" | "--duckdb" if source.is_some() => {
+                return Err(anyhow!("--journal-dir and --duckdb are mutually exclusive"))
+            },
+            "--journal-dir" => source = Some(ReplaySource::Journal(PathBuf::from(value()?))),
+            "--duckdb" => source = Some(ReplaySource::DuckDb(PathBuf::from(value()?))),
+            "--key-name" => key_name = Some(value()?),
+            "--event-id" => event_id = Some(value()?),
+            "--provider" => {
+                let raw = value()?;
+                provider_type = Some(
+                    ProviderType::from_storage_str(&raw)
+                        .ok_or_else(|| anyhow!("unknown --provider `{raw}`"))?,
+                );
+            },
+            "--since" => since_ms = Some(parse_duration_ms(&value()?)?),
+            "--limit" => {
+                limit = value()?
+                    .parse::<usize>()
+                    .context("failed to parse --limit")?
+            },
+            "--ignore" => {
+                let pointer = value()?;
+                if !pointer.starts_with('/') {
+                    return Err(anyhow!("--ignore expects a JSON pointer such as `/model`"));
+                }
+                ignore_pointers.push(pointer);
+            },
+            "--send-to" => send_to = Some(value()?.trim_end_matches('/').to_string()),
+            _ => return Err(usage_error()),
+        }
+    }
+    Ok(ReplayConfig {
+        source: source.ok_or_else(usage_error)?,
+        key_name,
+        event_id,
+        provider_type,
+        since_ms,
+        limit,
+        ignore_pointers,
+        send_to,
+    })
+}
+
+/// Parse `90s`, `15m`, `2h`, `7d` or a bare millisecond count.
+fn parse_duration_ms(value: &str) -> anyhow::Result<i64> {
+    let (number, multiplier) = match value.as_bytes().last().copied() {
+        Some(b's') => (&value[..value.len() - 1], 1_000),
+        Some(b'm') => (&value[..value.len() - 1], 60_000),
+        Some(b'h') => (&value[..value.len() - 1], 3_600_000),
+        Some(b'd') => (&value[..value.len() - 1], 86_400_000),
+        _ => (value, 1),
+    };
+    let number = number
+        .parse::<i64>()
+        .with_context(|| format!("failed to parse duration `{value}`"))?;
+    Ok(number.saturating_mul(multiplier))
+}
+
 fn parse_serve_args<I>(args: I) -> anyhow::Result<(SocketAddr, StorageConfig)>
 where
     I: IntoIterator<Item = OsString>,
@@ -326,7 +485,11 @@ fn usage_error() -> anyhow::Error {
          --duckdb <path>\nusage: llm-access serve [--bind <addr>] --state-root <path> \
          --postgres-control-database-url-env <env> [--duckdb <path>] [--usage-journal-dir <path>] \
          [--duckdb-active-dir <path> --duckdb-archive-dir <path> --duckdb-rollover-bytes <bytes> \
-         --usage-details-dir <path>]"
+         --usage-details-dir <path>]\nusage: llm-access rotate-secrets \
+         --postgres-control-database-url-env <env>\nusage: llm-access generate-secret-key \
+         <key-id>\nusage: llm-access replay (--journal-dir <path> | --duckdb <path>) [--key-name \
+         <name>] [--event-id <id>] [--provider codex|kiro] [--since <duration>] [--limit <n>] \
+         [--ignore <json-pointer>]... [--send-to <base-url>]"
     )
 }
 
@@ -576,4 +739,73 @@ mod tests {
             .to_string()
             .contains("request cache url env and key prefix must be configured together"));
     }
+
+    #[test]
+    fn parses_secret_maintenance_commands() {
+        let command = super::CliCommand::parse([
+            "llm-access",
+            "rotate-secrets",
+            "--postgres-control-database-url-env",
+            "LLM_ACCESS_CONTROL_DATABASE_URL",
+        ])
+        .expect("parse rotate-secrets command");
+        assert_eq!(
+            command,
+            super::CliCommand::RotateSecrets(super::ControlStoreConfig {
+                database_url_env: "LLM_ACCESS_CONTROL_DATABASE_URL".to_string(),
+            })
+        );
+
+        let command = super::CliCommand::parse(["llm-access", "generate-secret-key", "2026-10"])
+            .expect("parse generate-secret-key command");
+        assert_eq!(command, super::CliCommand::GenerateSecretKey {
+            key_id: "2026-10".to_string(),
+        });
+
+        assert!(super::CliCommand::parse(["llm-access", "rotate-secrets"]).is_err());
+    }
+
+    #[test]
+    fn parses_replay_command() {
+        let command = super::CliCommand::parse([
+            "llm-access",
+            "replay",
+            "--journal-dir",
+            "/mnt/llm-access/usage-journal",
+            "--provider",
+            "kiro",
+            "--since",
+            "2h",
+            "--ignore",
+            "/profileArn",
+            "--send-to",
+            "http://127.0.0.1:19090/",
+        ])
+        .expect("parse replay command");
+        assert_eq!(
+            command,
+            super::CliCommand::Replay(super::ReplayConfig {
+                source: super::ReplaySource::Journal(PathBuf::from(
+                    "/mnt/llm-access/usage-journal"
+                )),
+                key_name: None,
+                event_id: None,
+                provider_type: Some(llm_access_core::provider::ProviderType::Kiro),
+                since_ms: Some(2 * 3_600_000),
+                limit: 100,
+                ignore_pointers: vec!["/profileArn".to_string()],
+                send_to: Some("http://127.0.0.1:19090".to_string()),
+            })
+        );
+
+        for args in [
+            &["llm-access", "replay"][..],
+            &["llm-access", "replay", "--journal-dir", "/a", "--duckdb", "/b"],
+            &["llm-access", "replay", "--duckdb", "/b", "--provider", "openai"],
+            &["llm-access", "replay", "--duckdb", "/b", "--ignore", "model"],
+            &["llm-access", "replay", "--duckdb", "/b", "--since", "soon"],
+        ] {
+            assert!(super::CliCommand::parse(args.iter().copied()).is_err(), "{args:?}");
+        }
+    }
 }
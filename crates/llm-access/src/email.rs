@@ -3,7 +3,9 @@
 use std::path::Path;
 
 use anyhow::Result;
-use llm_access_core::store::{AdminAccountContributionRequest, AdminTokenRequest, NewAdminKey};
+use llm_access_core::store::{
+    AdminAccountContributionRequest, AdminTokenRequest, KeyQuotaAlertCandidate, NewAdminKey,
+};
 use url::Url;
 
 #[derive(Clone)]
@@ -92,6 +94,54 @@ impl EmailNotifier {
             .await
     }
 
+    pub(crate) async fn send_key_quota_alert_notification(
+        &self,
+        to: &str,
+        candidate: &KeyQuotaAlertCandidate,
+        threshold: u8,
+    ) -> Result<()> {
+        let subject = format!("[StaticFlow] 你的 LLM Key 额度已使用 {threshold}%");
+        let remaining = candidate
+            .quota_billable_limit
+            .saturating_sub(candidate.billable_tokens_used);
+        let body_markdown = format!(
+            "你好，\n\n你的 LLM Key 用量已经达到额度的 {}%。\n\n## 用量信息\n- Key ID: `{}`\n- \
+             Key 名称: {}\n- 已用额度: `{}`\n- 总额度: `{}`\n- 剩余额度: `{}`\n\n这封提醒来自你在 \
+             Key 自助页面配置的额度阈值；如需停止提醒，请在自助页面清空通知邮箱。\
+             如果需要更多额度，请直接联系管理员。\n",
+            threshold,
+            candidate.key_id,
+            candidate.key_name,
+            candidate.billable_tokens_used,
+            candidate.quota_billable_limit,
+            remaining,
+        );
+        self.inner
+            .send_markdown_email(to, &subject, &body_markdown)
+            .await
+    }
+
+    /// Ask a quota alert recipient to confirm the address. The mail carries
+    /// only the key id, nothing the key holder typed.
+    pub(crate) async fn send_key_notify_email_confirmation(
+        &self,
+        to: &str,
+        key_id: &str,
+        token: &str,
+    ) -> Result<()> {
+        let subject = "[StaticFlow] 请确认 LLM Key 额度提醒邮箱";
+        let confirm_url = key_notify_email_confirmation_url(token);
+        let body_markdown = format!(
+            "你好，\n\nLLM Key `{key_id}` \
+             的持有者希望把额度提醒发送到这个邮箱。只有点击下面的链接确认后，\
+             这个邮箱才会收到提醒。\n\n- 确认链接: [{confirm_url}]({confirm_url})\n\n链接 24 \
+             小时内有效。如果这不是你的操作，忽略这封邮件即可，之后不会收到任何提醒。\n",
+        );
+        self.inner
+            .send_markdown_email(to, subject, &body_markdown)
+            .await
+    }
+
     pub(crate) async fn send_llm_sponsor_payment_instructions(
         &self,
         requester_email: &str,
@@ -123,6 +173,14 @@ fn llm_gateway_base_url(frontend_page_url: Option<&str>) -> String {
         .unwrap_or_else(|| "/api/llm-gateway/v1".to_string())
 }
 
+fn key_notify_email_confirmation_url(token: &str) -> String {
+    let path = format!("/api/llm-gateway/portal/notify-emails/confirm?token={token}");
+    std::env::var("SITE_BASE_URL")
+        .ok()
+        .map(|base| format!("{}{path}", base.trim_end_matches('/')))
+        .unwrap_or(path)
+}
+
 fn llm_access_url(frontend_page_url: Option<&str>) -> Option<String> {
     frontend_page_url.and_then(|url| build_llm_access_url(url).ok())
 }
@@ -0,0 +1,842 @@
+//! Self-service key portal authenticated by the key's own bearer secret.
+//!
+//! A key holder can inspect the key, rotate its secret with an overlap grace
+//! window, set caps tighter than the admin's, choose quota alert recipients,
+//! export usage history as CSV and read recent failed requests with redacted
+//! error bodies. Alert recipients only get alerts after following a
+//! confirmation link, and usage exports are throttled per key.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use axum::{
+    extract::{Query, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use llm_access_core::store::{
+    normalize_quota_alert_thresholds, quota_alert_level, redact_key_portal_error_body, AdminKey,
+    AuthenticatedKey, KeyPortalSettings, KeyPortalSettingsPatch, KeyPortalStore,
+    KeyQuotaAlertCandidate, KeySecretRotation, NewKeyNotifyEmailConfirmation,
+    DEFAULT_KEY_SECRET_GRACE_SECONDS, KEY_STATUS_ACTIVE, MAX_KEY_PORTAL_NOTIFY_EMAILS,
+    MAX_KEY_SECRET_GRACE_SECONDS,
+};
+use serde::{Deserialize, Deserializer, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    email::EmailNotifier,
+    provider::bearer_secret,
+    public::{fetch_usage_worker_json, json_error, now_ms},
+    usage_query::{AdminUsageEventDetailView, AdminUsageEventView, AdminUsageEventsResponse},
+    HttpState,
+};
+
+const KEY_PORTAL_USAGE_EXPORT_PAGE_SIZE: usize = 200;
+const MAX_KEY_PORTAL_USAGE_EXPORT_ROWS: usize = 10_000;
+const KEY_PORTAL_USAGE_EXPORT_INTERVAL_SECONDS: u64 = 60;
+const DEFAULT_KEY_PORTAL_ERROR_LIMIT: usize = 10;
+const MAX_KEY_PORTAL_ERROR_LIMIT: usize = 20;
+const MAX_KEY_PORTAL_REQUEST_MAX_CONCURRENCY: u64 = 1_024;
+const MAX_KEY_PORTAL_REQUEST_MIN_START_INTERVAL_MS: u64 = 300_000;
+const KEY_QUOTA_ALERT_SWEEP_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Per-key throttle for usage exports. One export fans out to up to
+/// `MAX_KEY_PORTAL_USAGE_EXPORT_ROWS / KEY_PORTAL_USAGE_EXPORT_PAGE_SIZE`
+/// usage worker pages, so a key may run one at a time and start at most one
+/// per interval.
+#[derive(Default)]
+pub(crate) struct KeyPortalExportGuard {
+    entries: Mutex<HashMap<String, KeyPortalExportEntry>>,
+}
+
+struct KeyPortalExportEntry {
+    started_at_ms: i64,
+    in_flight: bool,
+}
+
+/// Running export; clears the key's in-flight mark when dropped.
+struct KeyPortalExportPermit<'a> {
+    guard: &'a KeyPortalExportGuard,
+    key_id: String,
+}
+
+impl KeyPortalExportGuard {
+    /// Start an export for `key_id`, or return the seconds to wait.
+    fn try_start(&self, key_id: &str, now_ms: i64) -> Result<KeyPortalExportPermit<'_>, u64> {
+        let window_ms = (KEY_PORTAL_USAGE_EXPORT_INTERVAL_SECONDS * 1_000) as i64;
+        let Ok(mut entries) = self.entries.lock() else {
+            return Err(KEY_PORTAL_USAGE_EXPORT_INTERVAL_SECONDS);
+        };
+        if let Some(entry) = entries.get(key_id) {
+            let elapsed_ms = now_ms.saturating_sub(entry.started_at_ms);
+            if entry.in_flight || elapsed_ms < window_ms {
+                let remaining_ms = window_ms.saturating_sub(elapsed_ms).max(0);
+                return Err(((remaining_ms + 999) / 1_000).max(1) as u64);
+            }
+        }
+        entries.retain(|_, entry| {
+            entry.in_flight || now_ms.saturating_sub(entry.started_at_ms) < window_ms
+        });
+        entries.insert(key_id.to_string(), KeyPortalExportEntry {
+            started_at_ms: now_ms,
+            in_flight: true,
+        });
+        Ok(KeyPortalExportPermit {
+            guard: self,
+            key_id: key_id.to_string(),
+        })
+    }
+}
+
+impl Drop for KeyPortalExportPermit<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut entries) = self.guard.entries.lock() {
+            if let Some(entry) = entries.get_mut(&self.key_id) {
+                entry.in_flight = false;
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct KeyPortalResponse {
+    key: KeyPortalKeyView,
+    settings: KeyPortalSettingsView,
+    effective: KeyPortalEffectiveLimitsView,
+    generated_at: i64,
+}
+
+#[derive(Debug, Serialize)]
+struct KeyPortalKeyView {
+    id: String,
+    name: String,
+    status: String,
+    provider_type: String,
+    is_sub_key: bool,
+    quota_billable_limit: u64,
+    remaining_billable: i64,
+    request_max_concurrency: Option<u64>,
+    request_min_start_interval_ms: Option<u64>,
+    last_used_at: Option<i64>,
+    created_at: i64,
+}
+
+#[derive(Debug, Serialize)]
+struct KeyPortalSettingsView {
+    quota_billable_cap: Option<u64>,
+    request_max_concurrency_cap: Option<u64>,
+    request_min_start_interval_ms_floor: Option<u64>,
+    notify_emails: Vec<String>,
+    confirmed_notify_emails: Vec<String>,
+    quota_alert_thresholds: Vec<u8>,
+    quota_alert_level: Option<u8>,
+    previous_secret_expires_at: Option<i64>,
+    updated_at: i64,
+}
+
+/// Limits actually enforced: the admin values tightened by the holder caps.
+#[derive(Debug, Serialize)]
+struct KeyPortalEffectiveLimitsView {
+    quota_billable_limit: u64,
+    remaining_billable: i64,
+    request_max_concurrency: Option<u64>,
+    request_min_start_interval_ms: Option<u64>,
+}
+
+/// Settings update. An absent field is left unchanged; `null` clears a cap.
+#[derive(Debug, Deserialize)]
+pub(crate) struct PatchKeyPortalSettingsRequest {
+    #[serde(default, deserialize_with = "deserialize_present_optional_u64")]
+    quota_billable_cap: Option<Option<u64>>,
+    #[serde(default, deserialize_with = "deserialize_present_optional_u64")]
+    request_max_concurrency_cap: Option<Option<u64>>,
+    #[serde(default, deserialize_with = "deserialize_present_optional_u64")]
+    request_min_start_interval_ms_floor: Option<Option<u64>>,
+    #[serde(default)]
+    notify_emails: Option<Vec<String>>,
+    #[serde(default)]
+    quota_alert_thresholds: Option<Vec<u8>>,
+}
+
+fn deserialize_present_optional_u64<'de, D>(
+    deserializer: D,
+) -> Result<Option<Option<u64>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<u64>::deserialize(deserializer).map(Some)
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct RotateKeySecretRequest {
+    #[serde(default)]
+    grace_period_seconds: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+struct RotatedKeySecretResponse {
+    id: String,
+    /// Plaintext secret, returned only once.
+    secret: String,
+    previous_secret_expires_at: i64,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct KeyPortalUsageExportRequest {
+    #[serde(default)]
+    start_ms: Option<i64>,
+    #[serde(default)]
+    end_ms: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ConfirmNotifyEmailRequest {
+    token: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ConfirmedNotifyEmailResponse {
+    email: String,
+    confirmed: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct KeyPortalErrorsRequest {
+    #[serde(default)]
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+struct KeyPortalErrorsResponse {
+    events: Vec<KeyPortalErrorEventView>,
+    generated_at: i64,
+}
+
+#[derive(Debug, Serialize)]
+struct KeyPortalErrorEventView {
+    id: String,
+    endpoint: String,
+    model: Option<String>,
+    status_code: i32,
+    error_class: Option<String>,
+    error_message: Option<String>,
+    error_body: Option<String>,
+    created_at: i64,
+}
+
+pub(crate) async fn get_key_portal(State(state): State<HttpState>, headers: HeaderMap) -> Response {
+    let key = match authenticate_key_holder(&state, &headers).await {
+        Ok(key) => key,
+        Err(response) => return response,
+    };
+    match state
+        .key_portal_store
+        .get_key_portal_settings(&key.id)
+        .await
+    {
+        Ok(settings) => no_store(Json(portal_response(&key, &settings)).into_response()),
+        Err(_) => json_error(StatusCode::INTERNAL_SERVER_ERROR, "key portal store error"),
+    }
+}
+
+pub(crate) async fn patch_key_portal_settings(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+    Json(request): Json<PatchKeyPortalSettingsRequest>,
+) -> Response {
+    let key = match authenticate_key_holder(&state, &headers).await {
+        Ok(key) => key,
+        Err(response) => return response,
+    };
+    let patch = match normalize_settings_patch(request) {
+        Ok(patch) => patch,
+        Err(message) => return json_error(StatusCode::BAD_REQUEST, &message),
+    };
+    let notify_emails_changed = patch.notify_emails.is_some();
+    match state
+        .key_portal_store
+        .patch_key_portal_settings(&key.id, patch)
+        .await
+    {
+        Ok(settings) => {
+            if notify_emails_changed {
+                send_notify_email_confirmations(&state, &key.id, &settings).await;
+            }
+            no_store(Json(portal_response(&key, &settings)).into_response())
+        },
+        Err(err) => {
+            tracing::warn!(key_id = %key.id, "failed to update key portal settings: {err:#}");
+            json_error(StatusCode::INTERNAL_SERVER_ERROR, "key portal store error")
+        },
+    }
+}
+
+pub(crate) async fn rotate_key_secret(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+    request: Option<Json<RotateKeySecretRequest>>,
+) -> Response {
+    let key = match authenticate_key_holder(&state, &headers).await {
+        Ok(key) => key,
+        Err(response) => return response,
+    };
+    let grace_period_seconds = request
+        .and_then(|Json(request)| request.grace_period_seconds)
+        .unwrap_or(DEFAULT_KEY_SECRET_GRACE_SECONDS);
+    if grace_period_seconds > MAX_KEY_SECRET_GRACE_SECONDS {
+        return json_error(StatusCode::BAD_REQUEST, "grace_period_seconds is out of range");
+    }
+    let now = now_ms();
+    let secret = format!("sfk_{}", uuid::Uuid::new_v4().simple());
+    let rotation = KeySecretRotation {
+        key_hash: format!("{:x}", Sha256::digest(secret.as_bytes())),
+        secret,
+        previous_secret_expires_at_ms: now.saturating_add((grace_period_seconds * 1_000) as i64),
+        rotated_at_ms: now,
+    };
+    let previous_secret_expires_at = rotation.previous_secret_expires_at_ms;
+    match state
+        .key_portal_store
+        .rotate_key_secret(&key.id, rotation)
+        .await
+    {
+        Ok(Some(rotated)) => no_store(
+            Json(RotatedKeySecretResponse {
+                id: rotated.id,
+                secret: rotated.secret,
+                previous_secret_expires_at,
+            })
+            .into_response(),
+        ),
+        Ok(None) => json_error(StatusCode::NOT_FOUND, "key not found"),
+        Err(err) => {
+            tracing::warn!(key_id = %key.id, "failed to rotate key secret: {err:#}");
+            json_error(StatusCode::INTERNAL_SERVER_ERROR, "key portal store error")
+        },
+    }
+}
+
+pub(crate) async fn export_key_portal_usage(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+    Query(request): Query<KeyPortalUsageExportRequest>,
+) -> Response {
+    let key = match authenticate_key_holder(&state, &headers).await {
+        Ok(key) => key,
+        Err(response) => return response,
+    };
+    let _permit = match state.key_portal_export_guard.try_start(&key.id, now_ms()) {
+        Ok(permit) => permit,
+        Err(retry_after_seconds) => {
+            let mut response = json_error(
+                StatusCode::TOO_MANY_REQUESTS,
+                &format!("usage export is rate-limited; retry in {retry_after_seconds} seconds"),
+            );
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(retry_after_seconds));
+            return response;
+        },
+    };
+    let mut csv = String::from(
+        "created_at_ms,event_id,key_name,endpoint,model,status_code,input_uncached_tokens,\
+         input_cached_tokens,output_tokens,billable_tokens,latency_ms,error_class\n",
+    );
+    let mut offset = 0;
+    while offset < MAX_KEY_PORTAL_USAGE_EXPORT_ROWS {
+        let mut params = vec![
+            ("key_id", key.id.clone()),
+            ("source", "all".to_string()),
+            ("limit", KEY_PORTAL_USAGE_EXPORT_PAGE_SIZE.to_string()),
+            ("offset", offset.to_string()),
+        ];
+        if let Some(start_ms) = request.start_ms.filter(|value| *value > 0) {
+            params.push(("start_ms", start_ms.to_string()));
+        }
+        if let Some(end_ms) = request.end_ms.filter(|value| *value > 0) {
+            params.push(("end_ms", end_ms.to_string()));
+        }
+        let page = match fetch_usage_worker_json::<AdminUsageEventsResponse>(
+            &state,
+            "/admin/llm-gateway/usage",
+            &params,
+        )
+        .await
+        {
+            Ok(page) => page,
+            Err(response) => return response,
+        };
+        for event in &page.events {
+            push_usage_csv_row(&mut csv, event);
+        }
+        offset += page.events.len();
+        if !page.has_more || page.events.is_empty() {
+            break;
+        }
+    }
+    let mut response = csv.into_response();
+    let headers = response.headers_mut();
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("text/csv; charset=utf-8"));
+    if let Ok(value) =
+        HeaderValue::from_str(&format!("attachment; filename=\"{}-usage.csv\"", key.id))
+    {
+        headers.insert(header::CONTENT_DISPOSITION, value);
+    }
+    no_store(response)
+}
+
+pub(crate) async fn list_key_portal_errors(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+    Query(request): Query<KeyPortalErrorsRequest>,
+) -> Response {
+    let key = match authenticate_key_holder(&state, &headers).await {
+        Ok(key) => key,
+        Err(response) => return response,
+    };
+    let limit = request
+        .limit
+        .unwrap_or(DEFAULT_KEY_PORTAL_ERROR_LIMIT)
+        .clamp(1, MAX_KEY_PORTAL_ERROR_LIMIT);
+    let params = vec![
+        ("key_id", key.id.clone()),
+        ("source", "all".to_string()),
+        ("status_kind", "non_ok".to_string()),
+        ("limit", limit.to_string()),
+    ];
+    let page = match fetch_usage_worker_json::<AdminUsageEventsResponse>(
+        &state,
+        "/admin/llm-gateway/usage",
+        &params,
+    )
+    .await
+    {
+        Ok(page) => page,
+        Err(response) => return response,
+    };
+    let mut events = Vec::with_capacity(page.events.len());
+    for event in page.events {
+        let detail = fetch_usage_worker_json::<AdminUsageEventDetailView>(
+            &state,
+            &format!("/admin/llm-gateway/usage/{}", event.id),
+            &[],
+        )
+        .await
+        .ok();
+        events.push(KeyPortalErrorEventView {
+            id: event.id,
+            endpoint: event.endpoint,
+            model: event.model,
+            status_code: event.status_code,
+            error_class: event.error_class,
+            error_message: event
+                .error_message
+                .as_deref()
+                .map(redact_key_portal_error_body),
+            error_body: detail
+                .and_then(|detail| detail.error_body)
+                .as_deref()
+                .map(redact_key_portal_error_body),
+            created_at: event.created_at,
+        });
+    }
+    no_store(
+        Json(KeyPortalErrorsResponse {
+            events,
+            generated_at: now_ms(),
+        })
+        .into_response(),
+    )
+}
+
+/// Confirm a quota alert recipient from the link mailed to it. The token is
+/// the only credential, so this route needs no bearer secret.
+pub(crate) async fn confirm_key_notify_email(
+    State(state): State<HttpState>,
+    Query(request): Query<ConfirmNotifyEmailRequest>,
+) -> Response {
+    let token_hash = format!("{:x}", Sha256::digest(request.token.trim().as_bytes()));
+    match state
+        .key_portal_store
+        .confirm_key_notify_email(&token_hash, now_ms())
+        .await
+    {
+        Ok(Some(confirmed)) => no_store(
+            Json(ConfirmedNotifyEmailResponse {
+                email: confirmed.email,
+                confirmed: true,
+            })
+            .into_response(),
+        ),
+        Ok(None) => json_error(StatusCode::NOT_FOUND, "confirmation link is invalid or expired"),
+        Err(err) => {
+            tracing::warn!("failed to confirm key notify email: {err:#}");
+            json_error(StatusCode::INTERNAL_SERVER_ERROR, "key portal store error")
+        },
+    }
+}
+
+/// Mail a confirmation link to every recipient that has not confirmed yet.
+/// The store refuses a new link while an earlier one is still valid, so
+/// re-saving the list does not mail the same address again.
+async fn send_notify_email_confirmations(
+    state: &HttpState,
+    key_id: &str,
+    settings: &KeyPortalSettings,
+) {
+    let Some(notifier) = state.email_notifier.as_ref() else {
+        return;
+    };
+    let pending = settings
+        .notify_emails
+        .iter()
+        .filter(|email| !settings.confirmed_notify_emails.contains(email));
+    for email in pending {
+        let token = uuid::Uuid::new_v4().simple().to_string();
+        let confirmation = NewKeyNotifyEmailConfirmation {
+            key_id: key_id.to_string(),
+            email: email.clone(),
+            token_hash: format!("{:x}", Sha256::digest(token.as_bytes())),
+            requested_at_ms: now_ms(),
+        };
+        match state
+            .key_portal_store
+            .request_key_notify_email_confirmation(confirmation)
+            .await
+        {
+            Ok(true) => {},
+            Ok(false) => continue,
+            Err(err) => {
+                tracing::warn!(key_id, "failed to store notify email confirmation: {err:#}");
+                continue;
+            },
+        }
+        if let Err(err) = notifier
+            .send_key_notify_email_confirmation(email, key_id, &token)
+            .await
+        {
+            tracing::warn!(key_id, "failed to send notify email confirmation: {err:#}");
+        }
+    }
+}
+
+/// Resolve the caller's bearer secret to its active key. A secret inside its
+/// rotation grace window resolves like the current one.
+pub(crate) async fn authenticate_key_holder(
+    state: &HttpState,
+    headers: &HeaderMap,
+) -> Result<AdminKey, Response> {
+    let Some(secret) = bearer_secret(headers) else {
+        return Err(json_error(StatusCode::UNAUTHORIZED, "missing bearer token"));
+    };
+    let key: AuthenticatedKey = match state
+        .provider_state
+        .authenticate_bearer_secret(secret)
+        .await
+    {
+        Ok(Some(key)) => key,
+        Ok(None) => return Err(json_error(StatusCode::UNAUTHORIZED, "invalid bearer token")),
+        Err(_) => {
+            return Err(json_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "authentication backend error",
+            ));
+        },
+    };
+    if key.status != KEY_STATUS_ACTIVE {
+        return Err(json_error(StatusCode::FORBIDDEN, "llm key is not active"));
+    }
+    match state.admin_key_store.get_admin_key(&key.key_id).await {
+        Ok(Some(key)) => Ok(key),
+        Ok(None) => Err(json_error(StatusCode::UNAUTHORIZED, "invalid bearer token")),
+        Err(_) => Err(json_error(StatusCode::INTERNAL_SERVER_ERROR, "key store error")),
+    }
+}
+
+fn portal_response(key: &AdminKey, settings: &KeyPortalSettings) -> KeyPortalResponse {
+    let used = i64::try_from(key.quota_billable_limit)
+        .unwrap_or(i64::MAX)
+        .saturating_sub(key.remaining_billable);
+    let effective_quota = settings.caps.cap_quota(key.quota_billable_limit);
+    KeyPortalResponse {
+        key: KeyPortalKeyView {
+            id: key.id.clone(),
+            name: key.name.clone(),
+            status: key.status.clone(),
+            provider_type: key.provider_type.clone(),
+            is_sub_key: key.parent_key_id.is_some(),
+            quota_billable_limit: key.quota_billable_limit,
+            remaining_billable: key.remaining_billable,
+            request_max_concurrency: key.request_max_concurrency,
+            request_min_start_interval_ms: key.request_min_start_interval_ms,
+            last_used_at: key.last_used_at,
+            created_at: key.created_at,
+        },
+        settings: KeyPortalSettingsView {
+            quota_billable_cap: settings.caps.quota_billable_cap,
+            request_max_concurrency_cap: settings.caps.request_max_concurrency_cap,
+            request_min_start_interval_ms_floor: settings.caps.request_min_start_interval_ms_floor,
+            notify_emails: settings.notify_emails.clone(),
+            confirmed_notify_emails: settings.confirmed_notify_emails.clone(),
+            quota_alert_thresholds: settings.quota_alert_thresholds.clone(),
+            quota_alert_level: settings.quota_alert_level,
+            previous_secret_expires_at: settings.previous_secret_expires_at_ms,
+            updated_at: settings.updated_at_ms,
+        },
+        effective: KeyPortalEffectiveLimitsView {
+            quota_billable_limit: effective_quota,
+            remaining_billable: i64::try_from(effective_quota)
+                .unwrap_or(i64::MAX)
+                .saturating_sub(used),
+            request_max_concurrency: settings.caps.cap_concurrency(key.request_max_concurrency),
+            request_min_start_interval_ms: settings
+                .caps
+                .floor_interval(key.request_min_start_interval_ms),
+        },
+        generated_at: now_ms(),
+    }
+}
+
+fn normalize_settings_patch(
+    request: PatchKeyPortalSettingsRequest,
+) -> Result<KeyPortalSettingsPatch, String> {
+    if request
+        .quota_billable_cap
+        .flatten()
+        .is_some_and(|value| value > i64::MAX as u64)
+    {
+        return Err("quota_billable_cap is out of range".to_string());
+    }
+    if request
+        .request_max_concurrency_cap
+        .flatten()
+        .is_some_and(|value| value == 0 || value > MAX_KEY_PORTAL_REQUEST_MAX_CONCURRENCY)
+    {
+        return Err("request_max_concurrency_cap is out of range".to_string());
+    }
+    if request
+        .request_min_start_interval_ms_floor
+        .flatten()
+        .is_some_and(|value| value > MAX_KEY_PORTAL_REQUEST_MIN_START_INTERVAL_MS)
+    {
+        return Err("request_min_start_interval_ms_floor is out of range".to_string());
+    }
+    let notify_emails = match request.notify_emails {
+        Some(emails) => Some(normalize_notify_emails(emails)?),
+        None => None,
+    };
+    let quota_alert_thresholds = match request.quota_alert_thresholds {
+        Some(thresholds) => Some(normalize_quota_alert_thresholds(thresholds)?),
+        None => None,
+    };
+    Ok(KeyPortalSettingsPatch {
+        quota_billable_cap: request.quota_billable_cap,
+        request_max_concurrency_cap: request.request_max_concurrency_cap,
+        request_min_start_interval_ms_floor: request.request_min_start_interval_ms_floor,
+        notify_emails,
+        quota_alert_thresholds,
+        updated_at_ms: now_ms(),
+    })
+}
+
+fn normalize_notify_emails(emails: Vec<String>) -> Result<Vec<String>, String> {
+    let mut normalized = emails
+        .into_iter()
+        .filter(|email| !email.trim().is_empty())
+        .map(|email| {
+            static_flow_email::normalize_email(email).map_err(|err| format!("invalid email: {err}"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    normalized.sort();
+    normalized.dedup();
+    if normalized.len() > MAX_KEY_PORTAL_NOTIFY_EMAILS {
+        return Err("too many notify_emails".to_string());
+    }
+    Ok(normalized)
+}
+
+fn push_usage_csv_row(csv: &mut String, event: &AdminUsageEventView) {
+    let fields = [
+        event.created_at.to_string(),
+        event.id.clone(),
+        event.key_name.clone(),
+        event.endpoint.clone(),
+        event.model.clone().unwrap_or_default(),
+        event.status_code.to_string(),
+        event.input_uncached_tokens.to_string(),
+        event.input_cached_tokens.to_string(),
+        event.output_tokens.to_string(),
+        event.billable_tokens.to_string(),
+        event.latency_ms.to_string(),
+        event.error_class.clone().unwrap_or_default(),
+    ];
+    let row = fields
+        .iter()
+        .map(|field| csv_field(field))
+        .collect::<Vec<_>>()
+        .join(",");
+    csv.push_str(&row);
+    csv.push('\n');
+}
+
+/// Quote a CSV field when needed, and defuse spreadsheet formula prefixes.
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) && value.parse::<f64>().is_err() {
+        format!("'{value}")
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+fn no_store(mut response: Response) -> Response {
+    response
+        .headers_mut()
+        .insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+    response
+}
+
+/// Periodically email key holders whose usage crossed a configured quota
+/// threshold. Does nothing when no email notifier is configured.
+pub(crate) fn spawn_key_quota_alert_notifier(
+    store: Arc<dyn KeyPortalStore>,
+    notifier: Option<Arc<EmailNotifier>>,
+) {
+    let Some(notifier) = notifier else {
+        tracing::info!("key quota alerts are disabled: email notifier is not configured");
+        return;
+    };
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(KEY_QUOTA_ALERT_SWEEP_INTERVAL).await;
+            if let Err(err) = sweep_key_quota_alerts_once(store.as_ref(), notifier.as_ref()).await {
+                tracing::warn!("failed to sweep key quota alerts: {err:#}");
+            }
+        }
+    });
+}
+
+async fn sweep_key_quota_alerts_once(
+    store: &dyn KeyPortalStore,
+    notifier: &EmailNotifier,
+) -> anyhow::Result<()> {
+    for candidate in store.list_key_quota_alert_candidates().await? {
+        let level = quota_alert_level(
+            candidate.billable_tokens_used,
+            candidate.quota_billable_limit,
+            &candidate.quota_alert_thresholds,
+        );
+        if level == candidate.quota_alert_level {
+            continue;
+        }
+        // Claim the level change first so only one node sends the email.
+        if !store
+            .set_key_quota_alert_level(&candidate.key_id, candidate.quota_alert_level, level)
+            .await?
+        {
+            continue;
+        }
+        let Some(threshold) = quota_alert_to_send(candidate.quota_alert_level, level) else {
+            continue;
+        };
+        send_key_quota_alert(notifier, &candidate, threshold).await;
+    }
+    Ok(())
+}
+
+/// Threshold to email about when the alert level moves. A level that drops,
+/// for example after a quota raise, only re-arms the lower thresholds.
+fn quota_alert_to_send(previous: Option<u8>, level: Option<u8>) -> Option<u8> {
+    match (previous, level) {
+        (_, None) => None,
+        (None, Some(level)) => Some(level),
+        (Some(previous), Some(level)) => (level > previous).then_some(level),
+    }
+}
+
+async fn send_key_quota_alert(
+    notifier: &EmailNotifier,
+    candidate: &KeyQuotaAlertCandidate,
+    threshold: u8,
+) {
+    for email in &candidate.notify_emails {
+        if let Err(err) = notifier
+            .send_key_quota_alert_notification(email, candidate, threshold)
+            .await
+        {
+            tracing::warn!(
+                key_id = %candidate.key_id,
+                threshold,
+                "failed to send key quota alert email: {err:#}"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        csv_field, normalize_notify_emails, quota_alert_to_send, KeyPortalExportGuard,
+        KEY_PORTAL_USAGE_EXPORT_INTERVAL_SECONDS,
+    };
+
+    #[test]
+    fn quota_alerts_fire_only_when_the_level_rises() {
+        assert_eq!(quota_alert_to_send(None, Some(50)), Some(50));
+        assert_eq!(quota_alert_to_send(Some(50), Some(80)), Some(80));
+        assert_eq!(quota_alert_to_send(Some(80), Some(50)), None);
+        assert_eq!(quota_alert_to_send(Some(80), None), None);
+    }
+
+    #[test]
+    fn usage_exports_run_one_at_a_time_and_once_per_interval() {
+        let guard = KeyPortalExportGuard::default();
+        let window_ms = (KEY_PORTAL_USAGE_EXPORT_INTERVAL_SECONDS * 1_000) as i64;
+        let permit = guard.try_start("key-a", 0).expect("first export starts");
+        assert!(guard.try_start("key-a", window_ms * 2).is_err());
+        assert!(guard.try_start("key-b", 0).is_ok());
+        drop(permit);
+        assert_eq!(
+            guard.try_start("key-a", 1_000).err(),
+            Some(KEY_PORTAL_USAGE_EXPORT_INTERVAL_SECONDS - 1)
+        );
+        assert!(guard.try_start("key-a", window_ms).is_ok());
+    }
+
+    #[test]
+    fn csv_fields_are_quoted_and_defused() {
+        assert_eq!(csv_field("gpt-4.1"), "gpt-4.1");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("=SUM(A1)"), "'=SUM(A1)");
+        assert_eq!(csv_field("-12"), "-12");
+    }
+
+    #[test]
+    fn notify_emails_are_normalized_and_bounded() {
+        assert_eq!(
+            normalize_notify_emails(vec![
+                " a@example.com ".to_string(),
+                "a@example.com".to_string(),
+                String::new(),
+            ]),
+            Ok(vec!["a@example.com".to_string()])
+        );
+        assert!(normalize_notify_emails(vec!["not-an-email".to_string()]).is_err());
+        assert!(normalize_notify_emails(
+            (0..6)
+                .map(|index| format!("user{index}@example.com"))
+                .collect()
+        )
+        .is_err());
+    }
+}
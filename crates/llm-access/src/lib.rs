@@ -12,6 +12,7 @@ mod codex_status;
 pub mod config;
 mod email;
 mod geoip;
+mod key_portal;
 /// Local Kiro endpoints.
 pub mod kiro;
 mod kiro_cache_snapshot;
@@ -19,11 +20,13 @@ mod kiro_headers;
 mod kiro_latency;
 mod kiro_refresh;
 mod kiro_status;
+mod openai_upstream_probe;
 mod process_memory;
 /// Provider request entrypoints.
 pub mod provider;
 mod public;
 mod refresh_scheduler;
+mod replay;
 mod request_context;
 #[cfg(any(feature = "duckdb-runtime", feature = "duckdb-bundled"))]
 mod rollup_backlog;
@@ -31,6 +34,7 @@ mod rollup_backlog;
 pub mod routes;
 /// Runtime startup validation.
 pub mod runtime;
+mod sub_keys;
 mod submission;
 mod support;
 /// Usage-event helpers.
@@ -52,7 +56,7 @@ use axum::{
     http::{HeaderValue, Request},
     middleware,
     response::Response,
-    routing::{any, delete, get, post},
+    routing::{any, delete, get, patch, post},
     Json, Router,
 };
 use config::{CliCommand, ServeConfig, StorageConfig};
@@ -63,9 +67,9 @@ use llm_access_codex_image::{
 };
 use llm_access_core::store::{
     AdminAccountGroupStore, AdminAnthropicUpstreamStore, AdminCodexAccountStore, AdminConfigStore,
-    AdminKeyStore, AdminKiroAccountStore, AdminProxyStore, AdminReviewQueueStore,
-    PublicAccessStore, PublicCommunityStore, PublicStatusStore, PublicSubmissionStore,
-    PublicUsageStore,
+    AdminKeyStore, AdminKiroAccountStore, AdminOpenAiUpstreamStore, AdminProxyStore,
+    AdminReviewQueueStore, KeyPortalStore, PublicAccessStore, PublicCommunityStore,
+    PublicStatusStore, PublicSubmissionStore, PublicUsageStore, SubKeyStore,
 };
 use serde::Serialize;
 use tokio::sync::Semaphore;
@@ -91,7 +95,11 @@ struct HttpState {
     admin_codex_account_store: Arc<dyn AdminCodexAccountStore>,
     admin_kiro_account_store: Arc<dyn AdminKiroAccountStore>,
     admin_anthropic_upstream_store: Arc<dyn AdminAnthropicUpstreamStore>,
+    admin_openai_upstream_store: Arc<dyn AdminOpenAiUpstreamStore>,
     admin_review_queue_store: Arc<dyn AdminReviewQueueStore>,
+    sub_key_store: Arc<dyn SubKeyStore>,
+    key_portal_store: Arc<dyn KeyPortalStore>,
+    key_portal_export_guard: Arc<key_portal::KeyPortalExportGuard>,
     public_access_store: Arc<dyn PublicAccessStore>,
     public_community_store: Arc<dyn PublicCommunityStore>,
     public_usage_store: Arc<dyn PublicUsageStore>,
@@ -116,9 +124,59 @@ pub fn run_from_env() -> anyhow::Result<()> {
                 tokio::runtime::Runtime::new().context("failed to create tokio runtime")?;
             runtime.block_on(serve(config))
         },
+        CliCommand::RotateSecrets(control_store) => rotate_secrets(&control_store),
+        CliCommand::GenerateSecretKey {
+            key_id,
+        } => generate_secret_key(&key_id),
+        CliCommand::Replay(config) => replay::run_replay(&config),
     }
 }
 
+/// Reseal stored control-plane secrets under the active master key from
+/// `LLM_ACCESS_SECRET_KEY_FILE`. Runs against the live database; the service
+/// keeps serving while it does.
+pub fn rotate_secrets(control_store: &config::ControlStoreConfig) -> anyhow::Result<()> {
+    let database_url = std::env::var(&control_store.database_url_env).with_context(|| {
+        format!("missing control database env `{}`", control_store.database_url_env)
+    })?;
+    let report = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .context("failed to create runtime for secret rotation")?
+        .block_on(async {
+            llm_access_store::postgres::PostgresControlRepository::connect_without_migrations(
+                &database_url,
+                None,
+            )
+            .await?
+            .rotate_secrets()
+            .await
+        })?;
+    println!(
+        "resealed {} secrets under key `{}` (keys={}, proxy_passwords={}, codex_accounts={}, \
+         kiro_accounts={}, upstream_channels={}, conflicts={})",
+        report.rotated(),
+        report.active_key_id,
+        report.keys,
+        report.proxy_passwords,
+        report.codex_accounts,
+        report.kiro_accounts,
+        report.upstream_channels,
+        report.conflicts
+    );
+    if report.conflicts > 0 {
+        println!("rows changed during rotation were skipped; re-run to pick them up");
+    }
+    Ok(())
+}
+
+/// Print a new master key file line. Append it to the key file on every node
+/// to make it the active key.
+pub fn generate_secret_key(key_id: &str) -> anyhow::Result<()> {
+    println!("{}", llm_access_store::secrets::generate_master_key_line(key_id)?);
+    Ok(())
+}
+
 /// Initialize llm-access storage paths.
 pub fn bootstrap_api_storage(config: &StorageConfig) -> anyhow::Result<()> {
     runtime::validate_state_root(config)?;
@@ -194,6 +252,18 @@ fn duckdb_schema_output_path(config: &StorageConfig) -> PathBuf {
 pub fn router_with_simulator(
     runtime: runtime::LlmAccessRuntime,
 ) -> (Router, Arc<llm_access_kiro::cache_sim::KiroCacheSimulator>) {
+    let (app, provider_state) = router_with_shared_stores(runtime, None, None);
+    (app, provider_state.kiro_cache_simulator())
+}
+
+fn router_with_shared_stores(
+    runtime: runtime::LlmAccessRuntime,
+    shared_affinity: Option<(
+        Arc<provider::SharedSessionAffinityStore>,
+        provider::SharedSessionAffinityConfig,
+    )>,
+    shared_response_cache: Option<Arc<provider::SharedSessionAffinityStore>>,
+) -> (Router, provider::ProviderState) {
     let request_activity = Arc::new(activity::RequestActivityTracker::new());
     let geoip = runtime.geoip();
     let provider_state = provider::ProviderState::new_with_config_store_activity_and_latency(
@@ -203,7 +273,14 @@ pub fn router_with_simulator(
         Arc::clone(&request_activity),
         geoip.clone(),
         runtime.kiro_latency_ranker(),
-    );
+    )
+    .with_batch_store(runtime.batch_store());
+    if let Some((store, config)) = shared_affinity {
+        provider_state.attach_shared_session_affinity(store, config);
+    }
+    if let Some(store) = shared_response_cache {
+        provider_state.attach_shared_response_cache(store);
+    }
     let codex_image_gateway = Arc::new(
         CodexImageGateway::new(CodexImageGatewayConfig {
             mode: ImageGatewayMode::IntegratedCodexApi,
@@ -216,9 +293,8 @@ pub fn router_with_simulator(
         })
         .expect("create integrated codex image gateway"),
     );
-    let kiro_cache_simulator = provider_state.kiro_cache_simulator();
     let state = HttpState {
-        provider_state,
+        provider_state: provider_state.clone(),
         codex_image_gateway,
         cluster_state: runtime.cluster_state(),
         geoip,
@@ -230,7 +306,11 @@ pub fn router_with_simulator(
         admin_codex_account_store: runtime.admin_codex_account_store(),
         admin_kiro_account_store: runtime.admin_kiro_account_store(),
         admin_anthropic_upstream_store: runtime.admin_anthropic_upstream_store(),
+        admin_openai_upstream_store: runtime.admin_openai_upstream_store(),
         admin_review_queue_store: runtime.admin_review_queue_store(),
+        sub_key_store: runtime.sub_key_store(),
+        key_portal_store: runtime.key_portal_store(),
+        key_portal_export_guard: Arc::new(key_portal::KeyPortalExportGuard::default()),
         public_access_store: runtime.public_access_store(),
         public_community_store: runtime.public_community_store(),
         public_usage_store: runtime.public_usage_store(),
@@ -260,6 +340,11 @@ pub fn router_with_simulator(
             axum::routing::patch(admin::patch_llm_gateway_key)
                 .delete(admin::delete_llm_gateway_key),
         )
+        .route(
+            "/admin/llm-gateway/keys/:key_id/explain-route",
+            get(admin::explain_llm_gateway_key_route),
+        )
+        .route("/admin/llm-gateway/queue", get(admin::get_llm_gateway_request_queue))
         .route(
             "/admin/llm-gateway/account-groups",
             get(admin::list_llm_gateway_account_groups)
@@ -299,11 +384,34 @@ pub fn router_with_simulator(
             "/admin/llm-gateway/proxy-configs/import-legacy-kiro",
             post(admin::import_legacy_kiro_proxy_configs),
         )
+        .route(
+            "/admin/llm-gateway/proxy-pools",
+            get(admin::list_llm_gateway_proxy_pools).post(admin::create_llm_gateway_proxy_pool),
+        )
+        .route(
+            "/admin/llm-gateway/proxy-pools/:pool_id",
+            axum::routing::patch(admin::patch_llm_gateway_proxy_pool)
+                .delete(admin::delete_llm_gateway_proxy_pool),
+        )
         .route("/admin/llm-gateway/proxy-bindings", get(admin::list_llm_gateway_proxy_bindings))
         .route(
             "/admin/llm-gateway/proxy-bindings/:provider_type",
             post(admin::update_llm_gateway_proxy_binding),
         )
+        .route(
+            "/admin/llm-gateway/openai-upstreams",
+            get(admin::list_admin_openai_upstream_channels)
+                .post(admin::create_admin_openai_upstream_channel),
+        )
+        .route(
+            "/admin/llm-gateway/openai-upstreams/:name",
+            axum::routing::patch(admin::patch_admin_openai_upstream_channel)
+                .delete(admin::delete_admin_openai_upstream_channel),
+        )
+        .route(
+            "/admin/llm-gateway/openai-upstreams/:name/refresh-models",
+            post(admin::refresh_admin_openai_upstream_models),
+        )
         .route(
             "/admin/llm-gateway/accounts",
             get(admin::list_llm_gateway_accounts).post(admin::import_llm_gateway_account),
@@ -363,6 +471,18 @@ pub fn router_with_simulator(
             "/admin/llm-gateway/token-requests/:request_id/reject",
             post(admin::reject_llm_gateway_token_request),
         )
+        .route(
+            "/admin/llm-gateway/moderation-events",
+            get(admin::list_llm_gateway_moderation_events),
+        )
+        .route(
+            "/admin/llm-gateway/moderation-events/:event_id/confirm",
+            post(admin::confirm_llm_gateway_moderation_event),
+        )
+        .route(
+            "/admin/llm-gateway/moderation-events/:event_id/dismiss",
+            post(admin::dismiss_llm_gateway_moderation_event),
+        )
         .route(
             "/admin/llm-gateway/account-contribution-requests",
             get(admin::list_llm_gateway_account_contribution_requests),
@@ -409,6 +529,10 @@ pub fn router_with_simulator(
             "/admin/kiro-gateway/keys/:key_id",
             axum::routing::patch(admin::patch_admin_kiro_key).delete(admin::delete_admin_kiro_key),
         )
+        .route(
+            "/admin/kiro-gateway/keys/:key_id/explain-route",
+            get(admin::explain_llm_gateway_key_route),
+        )
         .route("/admin/kiro-gateway/usage", get(admin::list_admin_kiro_usage_events))
         .route("/admin/kiro-gateway/usage/:event_id", get(admin::get_admin_kiro_usage_event))
         .route(
@@ -491,6 +615,20 @@ pub fn router_with_simulator(
             "/api/llm-gateway/support-assets/:file_name",
             get(public::get_llm_gateway_support_asset),
         )
+        .route(
+            "/api/llm-gateway/sub-keys",
+            get(sub_keys::list_sub_keys).post(sub_keys::create_sub_key),
+        )
+        .route("/api/llm-gateway/sub-keys/:key_id", delete(sub_keys::revoke_sub_key))
+        .route("/api/llm-gateway/portal", get(key_portal::get_key_portal))
+        .route("/api/llm-gateway/portal/settings", patch(key_portal::patch_key_portal_settings))
+        .route("/api/llm-gateway/portal/rotate-secret", post(key_portal::rotate_key_secret))
+        .route("/api/llm-gateway/portal/usage-export", get(key_portal::export_key_portal_usage))
+        .route("/api/llm-gateway/portal/errors", get(key_portal::list_key_portal_errors))
+        .route(
+            "/api/llm-gateway/portal/notify-emails/confirm",
+            get(key_portal::confirm_key_notify_email),
+        )
         .route("/api/kiro-gateway/access", get(public::get_kiro_gateway_access))
         .route("/v1/chat/completions", post(provider_entry_handler))
         .route("/v1/responses", post(provider_entry_handler))
@@ -499,6 +637,10 @@ pub fn router_with_simulator(
         .route("/v1/images/edits", any(codex_image_handler))
         .route("/v1/messages", post(provider_entry_handler))
         .route("/v1/messages/count_tokens", post(kiro::count_tokens))
+        .route("/v1/messages/batches", any(provider_entry_handler))
+        .route("/v1/messages/batches/*path", any(provider_entry_handler))
+        .route("/v1/batches", any(provider_entry_handler))
+        .route("/v1/batches/*path", any(provider_entry_handler))
         .route("/cc/v1/messages", post(provider_entry_handler))
         .route("/api/kiro-gateway/v1/models", get(kiro::get_models))
         .route("/api/kiro-gateway/v1/messages/count_tokens", post(kiro::count_tokens))
@@ -516,7 +658,7 @@ pub fn router_with_simulator(
         .layer(middleware::from_fn(request_context::request_context_middleware))
         .layer(cors_layer())
         .with_state(state);
-    (app, kiro_cache_simulator)
+    (app, provider_state)
 }
 
 /// Build the HTTP router, discarding the cache simulator handle. Retained for
@@ -592,6 +734,14 @@ pub async fn serve(config: ServeConfig) -> anyhow::Result<()> {
             service_runtime.admin_config_store(),
             service_runtime.kiro_latency_ranker(),
         );
+        admin::spawn_proxy_pool_health_checker(
+            service_runtime.admin_proxy_store(),
+            service_runtime.admin_config_store(),
+        );
+        key_portal::spawn_key_quota_alert_notifier(
+            service_runtime.key_portal_store(),
+            service_runtime.email_notifier(),
+        );
     } else {
         tracing::info!(
             "background provider status refresh is disabled by \
@@ -604,7 +754,12 @@ pub async fn serve(config: ServeConfig) -> anyhow::Result<()> {
         .await
         .with_context(|| format!("failed to bind {}", config.bind_addr))?;
     spawn_allocator_collector();
-    let (app, kiro_cache_simulator) = router_with_simulator(service_runtime);
+    let shared_affinity = setup_shared_session_affinity(&config.storage);
+    let shared_response_cache = setup_shared_response_cache(&config.storage);
+    let (app, provider_state) =
+        router_with_shared_stores(service_runtime, shared_affinity, shared_response_cache);
+    let kiro_cache_simulator = provider_state.kiro_cache_simulator();
+    provider::spawn_batch_worker(provider_state);
     let snapshot_handle =
         setup_kiro_cache_snapshot(&config.storage, admin_config_store, kiro_cache_simulator).await;
     let app = app.into_make_service_with_connect_info::<std::net::SocketAddr>();
@@ -621,6 +776,74 @@ pub async fn serve(config: ServeConfig) -> anyhow::Result<()> {
     result
 }
 
+/// Open the cluster-shared session affinity store when a request cache is
+/// configured and at least one provider opts in. Codex sharing defaults on
+/// for clustered nodes; Kiro sharing is opt-in. Best-effort: without a usable
+/// cache, affinity stays in-process.
+fn setup_shared_session_affinity(
+    storage: &StorageConfig,
+) -> Option<(Arc<provider::SharedSessionAffinityStore>, provider::SharedSessionAffinityConfig)> {
+    let affinity_config =
+        provider::SharedSessionAffinityConfig::from_env(storage.node_identity.is_some());
+    if !affinity_config.any_enabled() {
+        return None;
+    }
+    let cache_config = match config::resolve_request_cache_config(storage) {
+        Ok(Some(cache_config)) => cache_config,
+        Ok(None) => return None,
+        Err(error) => {
+            tracing::warn!(%error, "failed to resolve request cache for shared session affinity");
+            return None;
+        },
+    };
+    match provider::SharedSessionAffinityStore::spawn(&cache_config) {
+        Ok(store) => {
+            tracing::info!(
+                codex = affinity_config.codex,
+                kiro = affinity_config.kiro,
+                "shared session affinity enabled"
+            );
+            Some((store, affinity_config))
+        },
+        Err(error) => {
+            tracing::warn!(%error, "failed to open shared session affinity store");
+            None
+        },
+    }
+}
+
+/// Open the cluster-shared mirror for the per-key response cache when
+/// `LLM_ACCESS_RESPONSE_CACHE_SHARED` opts in and a request cache is
+/// configured. Best-effort: without it, cached responses stay in-process.
+fn setup_shared_response_cache(
+    storage: &StorageConfig,
+) -> Option<Arc<provider::SharedSessionAffinityStore>> {
+    if !provider::ResponseCacheConfig::shared_from_env() {
+        return None;
+    }
+    let cache_config = match config::resolve_request_cache_config(storage) {
+        Ok(Some(cache_config)) => cache_config,
+        Ok(None) => return None,
+        Err(error) => {
+            tracing::warn!(%error, "failed to resolve request cache for shared response cache");
+            return None;
+        },
+    };
+    match provider::SharedSessionAffinityStore::spawn_segment(
+        &cache_config,
+        provider::RESPONSE_CACHE_SHARED_SEGMENT,
+    ) {
+        Ok(store) => {
+            tracing::info!("shared response cache enabled");
+            Some(store)
+        },
+        Err(error) => {
+            tracing::warn!(%error, "failed to open shared response cache store");
+            None
+        },
+    }
+}
+
 /// Wire up cross-node Kiro cache snapshot persistence. Returns the periodic
 /// task handle when a request cache is configured, restoring the simulator
 /// before serving when the feature is enabled. Best-effort throughout: a
@@ -816,6 +1039,14 @@ mod tests {
         ) -> anyhow::Result<()> {
             Ok(())
         }
+
+        async fn record_openai_upstream_channel_usage(
+            &self,
+            _channel_name: &str,
+            _delta: llm_access_core::store::OpenAiUpstreamChannelUsageDelta,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
     }
 
     fn test_router() -> axum::Router {
@@ -1278,6 +1509,70 @@ mod tests {
         assert!(body.contains("queryable key not found"));
     }
 
+    #[tokio::test]
+    async fn router_rejects_sub_key_management_without_valid_key() {
+        let missing = test_router()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/llm-gateway/sub-keys")
+                    .body(Body::empty())
+                    .expect("request"),
+            )
+            .await
+            .expect("response");
+        assert_eq!(missing.status(), StatusCode::UNAUTHORIZED);
+
+        let revoke = test_router()
+            .oneshot(
+                Request::builder()
+                    .method("DELETE")
+                    .uri("/api/llm-gateway/sub-keys/llm-key-child")
+                    .header(header::AUTHORIZATION, "Bearer unknown")
+                    .body(Body::empty())
+                    .expect("request"),
+            )
+            .await
+            .expect("response");
+        assert_eq!(revoke.status(), StatusCode::UNAUTHORIZED);
+        let body = to_bytes(revoke.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let body = String::from_utf8(body.to_vec()).expect("utf8 body");
+        assert!(body.contains("invalid bearer token"));
+    }
+
+    #[tokio::test]
+    async fn router_rejects_key_portal_without_valid_key() {
+        let missing = test_router()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/llm-gateway/portal")
+                    .body(Body::empty())
+                    .expect("request"),
+            )
+            .await
+            .expect("response");
+        assert_eq!(missing.status(), StatusCode::UNAUTHORIZED);
+
+        let rotate = test_router()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/llm-gateway/portal/rotate-secret")
+                    .header(header::AUTHORIZATION, "Bearer unknown")
+                    .body(Body::empty())
+                    .expect("request"),
+            )
+            .await
+            .expect("response");
+        assert_eq!(rotate.status(), StatusCode::UNAUTHORIZED);
+        let body = to_bytes(rotate.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let body = String::from_utf8(body.to_vec()).expect("utf8 body");
+        assert!(body.contains("invalid bearer token"));
+    }
+
     #[test]
     fn bootstrap_usage_worker_storage_skips_api_auth_and_log_directories() {
         let unique = std::time::SystemTime::now()
@@ -1759,6 +2054,7 @@ mod tests {
             ("/admin/llm-gateway/token-requests", "requests"),
             ("/admin/llm-gateway/account-contribution-requests", "requests"),
             ("/admin/llm-gateway/sponsor-requests", "requests"),
+            ("/admin/llm-gateway/moderation-events", "events"),
         ] {
             let response = test_router()
                 .oneshot(
@@ -1789,6 +2085,8 @@ mod tests {
             "/admin/llm-gateway/account-contribution-requests/missing/approve-and-issue",
             "/admin/llm-gateway/account-contribution-requests/missing/reject",
             "/admin/llm-gateway/sponsor-requests/missing/approve",
+            "/admin/llm-gateway/moderation-events/missing/confirm",
+            "/admin/llm-gateway/moderation-events/missing/dismiss",
         ] {
             let response = test_router()
                 .oneshot(
@@ -1830,6 +2128,48 @@ mod tests {
         assert_eq!(value["code"], 404);
     }
 
+    #[tokio::test]
+    async fn router_routes_admin_key_route_explain_for_local_request() {
+        for path in [
+            "/admin/llm-gateway/keys/missing/explain-route?model=gpt-5&session_id=s-1",
+            "/admin/kiro-gateway/keys/missing/explain-route",
+        ] {
+            let response = test_router()
+                .oneshot(
+                    Request::builder()
+                        .uri(path)
+                        .header(header::HOST, "localhost")
+                        .body(Body::empty())
+                        .expect("request"),
+                )
+                .await
+                .expect("response");
+
+            assert_eq!(response.status(), StatusCode::NOT_FOUND, "{path}");
+        }
+    }
+
+    #[tokio::test]
+    async fn router_routes_admin_request_queue_for_local_request() {
+        let response = test_router()
+            .oneshot(
+                Request::builder()
+                    .uri("/admin/llm-gateway/queue")
+                    .header(header::HOST, "localhost")
+                    .body(Body::empty())
+                    .expect("request"),
+            )
+            .await
+            .expect("response");
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let value: serde_json::Value = serde_json::from_slice(&body).expect("json body");
+        assert_eq!(value["pools"], serde_json::json!([]));
+    }
+
     #[tokio::test]
     async fn router_accepts_llm_gateway_account_contribution_without_provider_key() {
         let response = test_router()
@@ -0,0 +1,293 @@
+//! Admin `/models` probes for OpenAI-compatible upstream channels.
+
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use axum::body::Bytes;
+use futures_util::StreamExt;
+use llm_access_core::store::AdminOpenAiUpstreamProbeTarget;
+use llm_access_openai_pool::{
+    apply_openai_auth_headers, build_models_url, parse_model_ids_from_models_response,
+};
+use reqwest::StatusCode;
+
+use crate::provider;
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(30);
+const MAX_PROBE_RESPONSE_BYTES: usize = 1024 * 1024;
+
+#[derive(Debug, Clone)]
+pub(crate) struct ModelsProbeOutput {
+    pub model_ids: Vec<String>,
+    pub status: String,
+    pub status_code: Option<u16>,
+    pub latency_ms: u64,
+    pub checked_at_ms: i64,
+    pub error: Option<String>,
+}
+
+impl ModelsProbeOutput {
+    fn ok(model_ids: Vec<String>, started: Instant, checked_at_ms: i64, status_code: u16) -> Self {
+        Self {
+            model_ids,
+            status: "ok".to_string(),
+            status_code: Some(status_code),
+            latency_ms: elapsed_ms(started),
+            checked_at_ms,
+            error: None,
+        }
+    }
+
+    fn failure(
+        started: Instant,
+        checked_at_ms: i64,
+        status: impl Into<String>,
+        status_code: Option<u16>,
+        error: impl Into<String>,
+    ) -> Self {
+        Self {
+            model_ids: Vec::new(),
+            status: status.into(),
+            status_code,
+            latency_ms: elapsed_ms(started),
+            checked_at_ms,
+            error: Some(error.into()),
+        }
+    }
+}
+
+pub(crate) async fn refresh_models(target: &AdminOpenAiUpstreamProbeTarget) -> ModelsProbeOutput {
+    let checked_at_ms = now_ms();
+    let started = Instant::now();
+    if let Some(error) = target.proxy_error.as_deref() {
+        return ModelsProbeOutput::failure(
+            started,
+            checked_at_ms,
+            "error",
+            None,
+            sanitize_error(error),
+        );
+    }
+    let url = match build_models_url(&target.base_url) {
+        Ok(url) => url,
+        Err(err) => {
+            return ModelsProbeOutput::failure(
+                started,
+                checked_at_ms,
+                "error",
+                None,
+                sanitize_error(&err.to_string()),
+            );
+        },
+    };
+    let client = match provider::openai_upstream_client(target.proxy.as_ref()) {
+        Ok(client) => client,
+        Err(err) => {
+            return ModelsProbeOutput::failure(
+                started,
+                checked_at_ms,
+                "error",
+                None,
+                sanitize_error(&err.to_string()),
+            );
+        },
+    };
+    let request = apply_openai_auth_headers(client.get(url), &target.api_key)
+        .header(reqwest::header::ACCEPT, "application/json")
+        .timeout(PROBE_TIMEOUT);
+    let response = match request.send().await {
+        Ok(response) => response,
+        Err(err) => {
+            return ModelsProbeOutput::failure(
+                started,
+                checked_at_ms,
+                "error",
+                None,
+                sanitize_error(&err.to_string()),
+            );
+        },
+    };
+    let status = response.status();
+    let status_code = status.as_u16();
+    let body = match read_limited_response_body(response).await {
+        Ok(body) => body,
+        Err(err) => {
+            return ModelsProbeOutput::failure(
+                started,
+                checked_at_ms,
+                "error",
+                Some(status_code),
+                err,
+            );
+        },
+    };
+    if !status.is_success() {
+        return ModelsProbeOutput::failure(
+            started,
+            checked_at_ms,
+            format!("http_{status_code}"),
+            Some(status_code),
+            upstream_error_summary(status, &body),
+        );
+    }
+    match parse_model_ids_from_models_response(&body) {
+        Ok(model_ids) => ModelsProbeOutput::ok(model_ids, started, checked_at_ms, status_code),
+        Err(err) => ModelsProbeOutput::failure(
+            started,
+            checked_at_ms,
+            "error",
+            Some(status_code),
+            sanitize_error(&format!("failed to parse models response: {err}")),
+        ),
+    }
+}
+
+async fn read_limited_response_body(response: reqwest::Response) -> Result<Bytes, String> {
+    if response
+        .content_length()
+        .is_some_and(|len| len > MAX_PROBE_RESPONSE_BYTES as u64)
+    {
+        return Err("upstream probe response is too large".to_string());
+    }
+    let mut body = Vec::new();
+    let mut stream = response.bytes_stream();
+    while let Some(chunk_result) = stream.next().await {
+        let chunk = chunk_result
+            .map_err(|err| sanitize_error(&format!("failed to read response: {err}")))?;
+        if body.len().saturating_add(chunk.len()) > MAX_PROBE_RESPONSE_BYTES {
+            return Err("upstream probe response is too large".to_string());
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(Bytes::from(body))
+}
+
+fn upstream_error_summary(status: StatusCode, body: &Bytes) -> String {
+    let message = serde_json::from_slice::<serde_json::Value>(body)
+        .ok()
+        .and_then(|value| {
+            value
+                .get("error")
+                .and_then(|error| error.get("message").or(Some(error)))
+                .or_else(|| value.get("message"))
+                .and_then(serde_json::Value::as_str)
+                .map(str::to_string)
+        })
+        .unwrap_or_else(|| String::from_utf8_lossy(body).trim().to_string());
+    if message.is_empty() {
+        format!("upstream returned HTTP {}", status.as_u16())
+    } else {
+        sanitize_error(&format!("upstream returned HTTP {}: {message}", status.as_u16()))
+    }
+}
+
+fn sanitize_error(message: &str) -> String {
+    const MAX_ERROR_CHARS: usize = 500;
+    let mut sanitized = message.split_whitespace().collect::<Vec<_>>().join(" ");
+    if let Some((index, _)) = sanitized.char_indices().nth(MAX_ERROR_CHARS) {
+        sanitized.truncate(index);
+        sanitized.push_str("...");
+    }
+    if sanitized.is_empty() {
+        "upstream probe failed".to_string()
+    } else {
+        sanitized
+    }
+}
+
+fn elapsed_ms(started: Instant) -> u64 {
+    started.elapsed().as_millis().min(u128::from(u64::MAX)) as u64
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis().min(i64::MAX as u128) as i64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn refresh_models_reads_model_ids_from_local_stub_server() {
+        let body = r#"{"object":"list","data":[{"id":"qwen3-32b"},{"id":"llama-3.1-8b"}]}"#;
+        let (base_url, request) = serve_one_http_response(
+            format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: \
+                 {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .into_bytes(),
+        )
+        .await;
+
+        let output = refresh_models(&probe_target(&base_url, "sk-local")).await;
+        let request = request.await.expect("stub request");
+
+        assert_eq!(output.status, "ok");
+        assert_eq!(output.model_ids, vec!["qwen3-32b", "llama-3.1-8b"]);
+        assert!(request.starts_with("GET /v1/models "), "unexpected request: {request}");
+        assert!(request
+            .to_ascii_lowercase()
+            .contains("authorization: bearer sk-local"));
+    }
+
+    #[tokio::test]
+    async fn refresh_models_reports_http_status_and_skips_auth_without_key() {
+        let body = r#"{"error":{"message":"model server is loading"}}"#;
+        let (base_url, request) = serve_one_http_response(
+            format!(
+                "HTTP/1.1 503 Service Unavailable\r\nContent-Length: {}\r\nConnection: \
+                 close\r\n\r\n{body}",
+                body.len()
+            )
+            .into_bytes(),
+        )
+        .await;
+
+        let output = refresh_models(&probe_target(&base_url, "")).await;
+        let request = request.await.expect("stub request");
+
+        assert_eq!(output.status, "http_503");
+        assert_eq!(
+            output.error.as_deref(),
+            Some("upstream returned HTTP 503: model server is loading")
+        );
+        assert!(output.model_ids.is_empty());
+        assert!(!request.to_ascii_lowercase().contains("authorization:"));
+    }
+
+    fn probe_target(base_url: &str, api_key: &str) -> AdminOpenAiUpstreamProbeTarget {
+        AdminOpenAiUpstreamProbeTarget {
+            name: "local-vllm".to_string(),
+            base_url: base_url.to_string(),
+            api_key: api_key.to_string(),
+            proxy: None,
+            proxy_error: None,
+        }
+    }
+
+    async fn serve_one_http_response(
+        raw_response: Vec<u8>,
+    ) -> (String, tokio::task::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("addr");
+        let handle = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.expect("accept");
+            let mut request_buffer = [0u8; 4096];
+            let read = stream.read(&mut request_buffer).await.unwrap_or(0);
+            stream
+                .write_all(&raw_response)
+                .await
+                .expect("write response");
+            String::from_utf8_lossy(&request_buffer[..read]).to_string()
+        });
+        (format!("http://{addr}/v1"), handle)
+    }
+}
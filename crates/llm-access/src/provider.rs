@@ -3,7 +3,9 @@
 mod anthropic_upstream_diagnostics;
 mod anthropic_upstream_dispatch;
 mod anthropic_upstream_payload;
+mod batch;
 mod cctest;
+mod circuit_breaker;
 mod client;
 mod codex_auth;
 mod codex_dispatch;
@@ -15,8 +17,10 @@ mod codex_session_rejection;
 mod codex_sse;
 mod codex_stream_error;
 mod codex_upstream_error;
+mod compiled_policy_cache;
 mod entry;
 mod errors;
+mod fair_queue;
 mod kiro_dispatch;
 mod kiro_error;
 mod kiro_media;
@@ -26,7 +30,14 @@ mod kiro_session_affinity;
 mod kiro_summary;
 mod kiro_usage;
 mod limiter;
+mod moderation;
+mod openai_upstream_dispatch;
+mod replay;
+mod request_transform;
+mod response_cache;
+mod route_explain;
 mod route_selection;
+mod shared_session_affinity;
 mod state;
 mod stream_guards;
 mod usage_meta;
@@ -46,10 +57,14 @@ use axum::{
     http::{Request, StatusCode},
     response::Response,
 };
-pub(crate) use client::anthropic_upstream_client;
+pub(crate) use batch::spawn_batch_worker;
+pub(crate) use circuit_breaker::CircuitBreakerView;
+use circuit_breaker::CircuitBreakers;
+pub(crate) use client::{anthropic_upstream_client, openai_upstream_client};
 use client::{
-    build_anthropic_upstream_client, build_provider_client, provider_client_cache_capacity,
-    provider_client_pool_idle_timeout, provider_client_pool_max_idle_per_host,
+    build_anthropic_upstream_client, build_openai_upstream_client, build_provider_client,
+    provider_client_cache_capacity, provider_client_pool_idle_timeout,
+    provider_client_pool_max_idle_per_host,
 };
 pub(crate) use codex_auth::{
     codex_upstream_base_url, compute_codex_upstream_url, resolve_codex_client_version,
@@ -68,6 +83,7 @@ pub(crate) use codex_session_recovery::{
     CodexSessionRecovery, CodexSessionRecoveryLookup, CodexSessionRecoveryStoreResult,
 };
 use codex_session_rejection::CodexSessionRejection;
+pub(crate) use entry::bearer_secret;
 pub use entry::{provider_entry, provider_entry_handler};
 use errors::{anthropic_json_error, summarize_error_bytes};
 #[cfg(test)]
@@ -76,6 +92,8 @@ use errors::{
     kiro_text_is_content_length_exceeded, randomized_same_account_retry_delay,
     transient_invalid_model_cooldown, SameAccountRetryReason,
 };
+use fair_queue::FairQueue;
+pub(crate) use fair_queue::FairQueueView;
 pub(crate) use kiro_dispatch::call_kiro_generate_for_route;
 #[cfg(test)]
 use kiro_dispatch::{account_names_for_kiro_routing_identity, call_kiro_mcp_for_route};
@@ -97,8 +115,8 @@ use llm_access_codex::{
 };
 use llm_access_core::{
     store::{
-        AdminConfigStore, AuthenticatedKey, ControlStore, ProviderCodexRoute, ProviderKiroRoute,
-        ProviderProxyConfig, ProviderRouteStore,
+        AdminConfigStore, AuthenticatedKey, BatchStore, ControlStore, ProviderCodexRoute,
+        ProviderKiroRoute, ProviderProxyConfig, ProviderRouteStore,
     },
     usage::UsageRetryDetails,
 };
@@ -114,12 +132,17 @@ use llm_access_kiro::{
     scheduler::{KiroRequestLease, KiroRequestScheduler},
 };
 use lru::LruCache;
+pub(crate) use replay::{default_replay_ignore_pointers, replay_upstream_request, ReplayOutcome};
+pub(crate) use response_cache::{
+    ResponseCache, ResponseCacheConfig, RESPONSE_CACHE_SHARED_SEGMENT,
+};
 #[cfg(test)]
 use route_selection::{
     select_codex_route_with_account_permit, select_kiro_route_with_account_permit,
     selection_ordered_kiro_routes,
 };
 use serde_json::Value;
+pub(crate) use shared_session_affinity::{SharedSessionAffinityConfig, SharedSessionAffinityStore};
 
 use self::kiro_session_affinity::KiroSessionAffinity;
 use crate::{
@@ -199,6 +222,8 @@ pub struct ProviderState {
     kiro_cache_simulator: Arc<KiroCacheSimulator>,
     request_limiter: Arc<RequestLimiter>,
     codex_account_cooldowns: Arc<CodexAccountCooldowns>,
+    circuit_breakers: Arc<CircuitBreakers>,
+    fair_queue: Arc<FairQueue>,
     codex_session_affinity: Arc<CodexSessionAffinity>,
     codex_session_recovery: Arc<CodexSessionRecovery>,
     codex_session_rejection: Arc<CodexSessionRejection>,
@@ -206,6 +231,8 @@ pub struct ProviderState {
     kiro_session_affinity: Arc<KiroSessionAffinity>,
     kiro_latency_ranker: Arc<KiroLatencyRanker>,
     request_activity: Arc<RequestActivityTracker>,
+    response_cache: Arc<ResponseCache>,
+    batch_store: Arc<dyn BatchStore>,
     protected_thinking_signature_secret: Option<Arc<str>>,
 }
 
@@ -220,6 +247,8 @@ pub struct ProviderDispatchDeps {
     kiro_cache_simulator: Arc<KiroCacheSimulator>,
     request_limiter: Arc<RequestLimiter>,
     codex_account_cooldowns: Arc<CodexAccountCooldowns>,
+    circuit_breakers: Arc<CircuitBreakers>,
+    fair_queue: Arc<FairQueue>,
     codex_session_affinity: Arc<CodexSessionAffinity>,
     codex_session_recovery: Arc<CodexSessionRecovery>,
     codex_session_rejection: Arc<CodexSessionRejection>,
@@ -416,6 +445,8 @@ struct KiroWebsearchDispatch {
     route_store: Arc<dyn ProviderRouteStore>,
     request_limiter: Arc<RequestLimiter>,
     kiro_request_scheduler: Arc<KiroRequestScheduler>,
+    circuit_breakers: Arc<CircuitBreakers>,
+    fair_queue: Arc<FairQueue>,
     kiro_session_affinity: Arc<KiroSessionAffinity>,
     kiro_latency_ranker: Arc<KiroLatencyRanker>,
     affinity_session_id: Option<String>,
@@ -554,6 +585,13 @@ static DEFAULT_ANTHROPIC_UPSTREAM_CLIENT: std::sync::LazyLock<reqwest::Client> =
 static ANTHROPIC_UPSTREAM_CLIENT_CACHE: std::sync::LazyLock<
     Mutex<LruCache<ProviderClientCacheKey, reqwest::Client>>,
 > = std::sync::LazyLock::new(|| Mutex::new(LruCache::new(provider_client_cache_capacity())));
+static DEFAULT_OPENAI_UPSTREAM_CLIENT: std::sync::LazyLock<reqwest::Client> =
+    std::sync::LazyLock::new(|| {
+        build_openai_upstream_client(None).expect("default openai upstream client should build")
+    });
+static OPENAI_UPSTREAM_CLIENT_CACHE: std::sync::LazyLock<
+    Mutex<LruCache<ProviderClientCacheKey, reqwest::Client>>,
+> = std::sync::LazyLock::new(|| Mutex::new(LruCache::new(provider_client_cache_capacity())));
 static KIRO_REMOTE_MEDIA_CLIENT: std::sync::LazyLock<reqwest::Client> =
     std::sync::LazyLock::new(|| {
         reqwest::Client::builder()
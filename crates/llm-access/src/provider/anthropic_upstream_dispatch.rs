@@ -24,6 +24,7 @@ use llm_access_core::{
         self as core_store, AnthropicUpstreamChannelUsageDelta, AuthenticatedKey,
         ProviderAnthropicUpstreamRoute,
     },
+    transform::RequestTransformDialect,
     usage::UsageEvent,
 };
 use llm_access_kiro::anthropic::preflight::PreprocessedMessagesRequest;
@@ -39,6 +40,10 @@ use super::{
     kiro_error::kiro_json_error,
     kiro_protocol::normalized_kiro_messages_path,
     limiter::{kiro_key_limit_response, try_acquire_key_permit},
+    request_transform::{
+        record_request_transforms, request_transform_unavailable, resolve_request_transform,
+        transform_json_body,
+    },
     usage_meta::{
         capture_client_request_body_json, capture_error_bytes, capture_error_message,
         capture_upstream_request_body_json, captured_body_json,
@@ -83,6 +88,7 @@ struct DirectAnthropicDispatchContext<'a> {
     endpoint: &'a str,
     model: &'a str,
     request_headers: &'a HeaderMap,
+    request_transforms: &'a [String],
     deps: &'a ProviderDispatchDeps,
 }
 
@@ -197,9 +203,27 @@ pub(super) async fn maybe_dispatch_anthropic_upstream_pool(
         ));
     }
     capture_client_request_body_json(&mut usage_meta, &replay.body);
+    // `replay.body` stays untouched for the Kiro fallback, which applies the
+    // same transform itself.
+    let request_transform = match resolve_request_transform(deps.route_store.as_ref(), &key).await {
+        Ok(transform) => transform,
+        Err(err) => {
+            let message = request_transform_unavailable(&key, &err);
+            return AnthropicUpstreamDispatchOutcome::Handled(kiro_json_error(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "api_error",
+                &message,
+            ));
+        },
+    };
+    let (direct_body, request_transforms) = transform_json_body(
+        request_transform.as_deref(),
+        RequestTransformDialect::AnthropicMessages,
+        &replay.body,
+    );
 
     let parse_started = Instant::now();
-    let prepared = match prepare_direct_anthropic_payload(&replay.body) {
+    let prepared = match prepare_direct_anthropic_payload(&direct_body) {
         Ok(prepared) => prepared,
         Err(err) => return AnthropicUpstreamDispatchOutcome::Handled(err.into_response()),
     };
@@ -228,6 +252,7 @@ pub(super) async fn maybe_dispatch_anthropic_upstream_pool(
         endpoint: public_path,
         model: &original_model,
         request_headers: &replay.headers,
+        request_transforms: &request_transforms,
         deps: &deps,
     };
     let route_queue = order_routes_for_request(routes);
@@ -362,6 +387,7 @@ async fn dispatch_one_route(
         &route.pool_mode_at_event,
         preflight,
     ));
+    record_request_transforms(usage_meta, context.request_transforms);
     let (route_payload, mapped_model) =
         match build_route_payload(&route.model_name_map_json, &preflight.request) {
             Ok(output) => output,
@@ -0,0 +1,1096 @@
+//! OpenAI- and Anthropic-shaped batch endpoints plus the worker that drains
+//! the persistent batch queue.
+//!
+//! ```text
+//! POST /v1/batches (JSONL)        POST /v1/messages/batches (JSON or JSONL)
+//!          \                              /
+//!           +-- BatchStore::create_batch_job (items pending)
+//!
+//! spawn_batch_worker, every node, optional UTC window
+//!   expire overdue batches --> claim items --> dispatch_with_moderation
+//!     2xx             --> succeeded
+//!     429 / 503 / 529 --> released with backoff, failed after the last try
+//!     anything else   --> failed
+//! ```
+//!
+//! Items run through the same dispatch as live traffic, so key concurrency
+//! limits, moderation, response caching and `UsageEvent` accounting all apply
+//! per item.
+
+use std::{collections::HashSet, time::Duration};
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header, Method, Request, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, SecondsFormat, Timelike, Utc};
+use futures_util::{future::join_all, StreamExt};
+use llm_access_core::store::{
+    AuthenticatedKey, BatchItemOutcome, BatchItemResult, BatchJob, ClaimedBatchItem, NewBatchItem,
+    NewBatchJob, BATCH_COMPLETION_WINDOW_MS, BATCH_ITEM_STATUS_CANCELLED,
+    BATCH_ITEM_STATUS_EXPIRED, BATCH_ITEM_STATUS_FAILED, BATCH_ITEM_STATUS_SUCCEEDED,
+    BATCH_STATUS_CANCELLED, BATCH_STATUS_CANCELLING, BATCH_STATUS_COMPLETED, BATCH_STATUS_EXPIRED,
+    BATCH_STATUS_IN_PROGRESS, MAX_BATCH_INPUT_BYTES, MAX_BATCH_ITEMS, MAX_BATCH_RESULT_BYTES,
+};
+use serde_json::{json, Value};
+
+use super::{
+    entry::{is_active_key, is_quota_exhausted, key_matches_route, quota_exhausted_response},
+    errors::{anthropic_json_error, codex_error_type_for_status, codex_surface_error_response},
+    moderation::dispatch_with_moderation,
+    util::now_millis,
+    ProviderState,
+};
+
+const BATCH_WINDOW_ENV: &str = "LLM_ACCESS_BATCH_WINDOW_UTC";
+const BATCH_POLL_INTERVAL_SECONDS_ENV: &str = "LLM_ACCESS_BATCH_POLL_INTERVAL_SECONDS";
+const BATCH_WORKER_CONCURRENCY_ENV: &str = "LLM_ACCESS_BATCH_WORKER_CONCURRENCY";
+const DEFAULT_BATCH_POLL_INTERVAL: Duration = Duration::from_secs(10);
+const DEFAULT_BATCH_WORKER_CONCURRENCY: usize = 4;
+const MAX_BATCH_WORKER_CONCURRENCY: usize = 64;
+/// A claimed item not finished within this long is claimed again.
+const BATCH_ITEM_LEASE_MS: i64 = 10 * 60 * 1000;
+/// Claims before a rate-limited item is recorded as failed.
+const MAX_BATCH_ITEM_ATTEMPTS: i32 = 5;
+/// Retry delay per attempt already made.
+const BATCH_RETRY_BACKOFF_MS: i64 = 30_000;
+const BATCH_LIST_LIMIT: usize = 100;
+const OPENAI_BATCH_ENDPOINTS: &[&str] = &["/v1/responses", "/v1/chat/completions"];
+const ANTHROPIC_BATCH_ENDPOINT: &str = "/v1/messages";
+const BATCH_RESULTS_CONTENT_TYPE: &str = "application/x-jsonl";
+
+/// Wire dialect of a batch endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum BatchDialect {
+    /// `/v1/batches`, answered with OpenAI `batch` objects.
+    OpenAi,
+    /// `/v1/messages/batches`, answered with Anthropic `message_batch` objects.
+    Anthropic,
+}
+
+impl BatchDialect {
+    fn protocol_family(self) -> &'static str {
+        match self {
+            Self::OpenAi => "openai",
+            Self::Anthropic => "anthropic",
+        }
+    }
+
+    fn id_prefix(self) -> &'static str {
+        match self {
+            Self::OpenAi => "batch_",
+            Self::Anthropic => "msgbatch_",
+        }
+    }
+
+    fn results_segment(self) -> &'static str {
+        match self {
+            Self::OpenAi => "output",
+            Self::Anthropic => "results",
+        }
+    }
+
+    fn base_path(self) -> &'static str {
+        match self {
+            Self::OpenAi => "/v1/batches",
+            Self::Anthropic => "/v1/messages/batches",
+        }
+    }
+}
+
+/// One batch endpoint operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum BatchAction {
+    Create,
+    List,
+    Retrieve(String),
+    Cancel(String),
+    Results(String),
+}
+
+/// Match a batch endpoint. `Some((dialect, None))` is a batch path with an
+/// unsupported method or shape.
+pub(super) fn batch_route(
+    method: &Method,
+    path: &str,
+) -> Option<(BatchDialect, Option<BatchAction>)> {
+    let openai_path = path
+        .strip_prefix("/api/llm-gateway")
+        .or_else(|| path.strip_prefix("/api/codex-gateway"))
+        .unwrap_or(path);
+    let anthropic_path = path.strip_prefix("/api/kiro-gateway").unwrap_or(path);
+    let (dialect, rest) = if let Some(rest) = batch_path_rest(openai_path, "/v1/batches") {
+        (BatchDialect::OpenAi, rest)
+    } else if let Some(rest) = batch_path_rest(anthropic_path, "/v1/messages/batches") {
+        (BatchDialect::Anthropic, rest)
+    } else {
+        return None;
+    };
+    let segments = rest
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>();
+    let action = match (method, segments.as_slice()) {
+        (&Method::POST, []) => Some(BatchAction::Create),
+        (&Method::GET, []) => Some(BatchAction::List),
+        (&Method::GET, [batch_id]) => Some(BatchAction::Retrieve((*batch_id).to_string())),
+        (&Method::POST, [batch_id, "cancel"]) => Some(BatchAction::Cancel((*batch_id).to_string())),
+        (&Method::GET, [batch_id, tail]) if *tail == dialect.results_segment() => {
+            Some(BatchAction::Results((*batch_id).to_string()))
+        },
+        _ => None,
+    };
+    Some((dialect, action))
+}
+
+fn batch_path_rest<'a>(path: &'a str, base: &str) -> Option<&'a str> {
+    let rest = path.strip_prefix(base)?;
+    (rest.is_empty() || rest.starts_with('/')).then_some(rest)
+}
+
+/// Serve one authenticated batch endpoint request.
+pub(super) async fn handle_batch_request(
+    state: &ProviderState,
+    key: AuthenticatedKey,
+    dialect: BatchDialect,
+    action: Option<BatchAction>,
+    request: Request<Body>,
+) -> Response {
+    let Some(action) = action else {
+        return batch_error(dialect, StatusCode::METHOD_NOT_ALLOWED, "unsupported batch request");
+    };
+    let store = &state.batch_store;
+    match action {
+        BatchAction::Create => create_batch(state, key, dialect, request).await,
+        BatchAction::List => match store.list_batch_jobs(&key.key_id, BATCH_LIST_LIMIT).await {
+            Ok(jobs) => Json(batch_list_json(dialect, &jobs)).into_response(),
+            Err(err) => batch_store_error(dialect, &err),
+        },
+        BatchAction::Retrieve(batch_id) => {
+            match store.get_batch_job(&key.key_id, &batch_id).await {
+                Ok(Some(job)) => Json(batch_json(dialect, &job)).into_response(),
+                Ok(None) => batch_not_found(dialect),
+                Err(err) => batch_store_error(dialect, &err),
+            }
+        },
+        BatchAction::Cancel(batch_id) => {
+            match store
+                .cancel_batch_job(&key.key_id, &batch_id, now_millis())
+                .await
+            {
+                Ok(Some(job)) => Json(batch_json(dialect, &job)).into_response(),
+                Ok(None) => batch_not_found(dialect),
+                Err(err) => batch_store_error(dialect, &err),
+            }
+        },
+        BatchAction::Results(batch_id) => batch_results(state, &key, dialect, &batch_id).await,
+    }
+}
+
+async fn create_batch(
+    state: &ProviderState,
+    key: AuthenticatedKey,
+    dialect: BatchDialect,
+    request: Request<Body>,
+) -> Response {
+    if is_quota_exhausted(&key) {
+        return quota_exhausted_response(&key);
+    }
+    let body = match to_bytes(request.into_body(), MAX_BATCH_INPUT_BYTES).await {
+        Ok(body) => body,
+        Err(_) => {
+            return batch_error(
+                dialect,
+                StatusCode::PAYLOAD_TOO_LARGE,
+                &format!("batch input exceeds {MAX_BATCH_INPUT_BYTES} bytes"),
+            );
+        },
+    };
+    let parsed = match parse_batch_input(dialect, &body) {
+        Ok(parsed) => parsed,
+        Err(message) => return batch_error(dialect, StatusCode::BAD_REQUEST, &message),
+    };
+    let now_ms = now_millis();
+    let job = NewBatchJob {
+        batch_id: format!("{}{}", dialect.id_prefix(), uuid::Uuid::new_v4().simple()),
+        key_id: key.key_id.clone(),
+        protocol_family: dialect.protocol_family().to_string(),
+        endpoint: parsed.endpoint,
+        metadata_json: parsed.metadata_json,
+        items: parsed.items,
+        created_at_ms: now_ms,
+        expires_at_ms: now_ms + BATCH_COMPLETION_WINDOW_MS,
+    };
+    match state.batch_store.create_batch_job(job).await {
+        Ok(job) => Json(batch_json(dialect, &job)).into_response(),
+        Err(err) => batch_store_error(dialect, &err),
+    }
+}
+
+async fn batch_results(
+    state: &ProviderState,
+    key: &AuthenticatedKey,
+    dialect: BatchDialect,
+    batch_id: &str,
+) -> Response {
+    let job = match state.batch_store.get_batch_job(&key.key_id, batch_id).await {
+        Ok(Some(job)) => job,
+        Ok(None) => return batch_not_found(dialect),
+        Err(err) => return batch_store_error(dialect, &err),
+    };
+    // Anthropic only serves results for ended batches; the OpenAI output
+    // grows as items finish.
+    if dialect == BatchDialect::Anthropic && !job.is_finished() {
+        return batch_error(dialect, StatusCode::BAD_REQUEST, "batch is still processing");
+    }
+    let items = match state
+        .batch_store
+        .list_batch_item_results(&key.key_id, batch_id)
+        .await
+    {
+        Ok(items) => items,
+        Err(err) => return batch_store_error(dialect, &err),
+    };
+    let mut output = String::new();
+    for item in &items {
+        let line = match dialect {
+            BatchDialect::OpenAi => openai_result_line(batch_id, item),
+            BatchDialect::Anthropic => anthropic_result_line(item),
+        };
+        if let Some(line) = line {
+            output.push_str(&line.to_string());
+            output.push('\n');
+        }
+    }
+    ([(header::CONTENT_TYPE, BATCH_RESULTS_CONTENT_TYPE)], output).into_response()
+}
+
+/// Validated batch input ready to persist.
+#[derive(Debug, PartialEq)]
+struct ParsedBatchInput {
+    endpoint: String,
+    metadata_json: Option<String>,
+    items: Vec<NewBatchItem>,
+}
+
+/// Accept either a JSONL file (one request per line) or a JSON object
+/// `{"requests": [...], "metadata": {...}}` carrying the same request lines.
+fn parse_batch_input(dialect: BatchDialect, body: &[u8]) -> Result<ParsedBatchInput, String> {
+    let text = std::str::from_utf8(body).map_err(|_| "batch input must be UTF-8".to_string())?;
+    let (lines, metadata) = match serde_json::from_str::<Value>(text) {
+        Ok(Value::Object(mut object)) if object.contains_key("requests") => {
+            let Some(Value::Array(requests)) = object.remove("requests") else {
+                return Err("`requests` must be an array".to_string());
+            };
+            let metadata = match object.remove("metadata") {
+                None | Some(Value::Null) => None,
+                Some(Value::Object(metadata)) => Some(Value::Object(metadata).to_string()),
+                Some(_) => return Err("`metadata` must be an object".to_string()),
+            };
+            (requests, metadata)
+        },
+        _ => {
+            let mut lines = Vec::new();
+            for (line_index, line) in text.lines().enumerate() {
+                let line = line.trim();
+                if line.is_empty() {
+                    continue;
+                }
+                let value = serde_json::from_str::<Value>(line)
+                    .map_err(|_| format!("line {}: invalid JSON", line_index + 1))?;
+                lines.push(value);
+            }
+            (lines, None)
+        },
+    };
+    if lines.is_empty() {
+        return Err("batch input has no requests".to_string());
+    }
+    if lines.len() > MAX_BATCH_ITEMS {
+        return Err(format!("batch input has more than {MAX_BATCH_ITEMS} requests"));
+    }
+
+    let mut endpoint = None::<String>;
+    let mut custom_ids = HashSet::new();
+    let mut items = Vec::with_capacity(lines.len());
+    for (index, line) in lines.into_iter().enumerate() {
+        let position = index + 1;
+        let Value::Object(mut line) = line else {
+            return Err(format!("request {position}: must be a JSON object"));
+        };
+        let custom_id = match line.get("custom_id").and_then(Value::as_str) {
+            Some(custom_id) if !custom_id.trim().is_empty() => custom_id.to_string(),
+            _ => return Err(format!("request {position}: `custom_id` is required")),
+        };
+        if !custom_ids.insert(custom_id.clone()) {
+            return Err(format!("request {position}: duplicate custom_id `{custom_id}`"));
+        }
+        let (line_endpoint, body) = match dialect {
+            BatchDialect::OpenAi => {
+                let method = line.get("method").and_then(Value::as_str).unwrap_or("POST");
+                if !method.eq_ignore_ascii_case("POST") {
+                    return Err(format!("request {position}: only POST is supported"));
+                }
+                let url = line.get("url").and_then(Value::as_str).unwrap_or_default();
+                let Some(url) = OPENAI_BATCH_ENDPOINTS
+                    .iter()
+                    .find(|endpoint| **endpoint == url)
+                else {
+                    return Err(format!(
+                        "request {position}: `url` must be one of {}",
+                        OPENAI_BATCH_ENDPOINTS.join(", ")
+                    ));
+                };
+                (*url, line.remove("body"))
+            },
+            BatchDialect::Anthropic => (ANTHROPIC_BATCH_ENDPOINT, line.remove("params")),
+        };
+        match endpoint.as_deref() {
+            None => endpoint = Some(line_endpoint.to_string()),
+            Some(current) if current != line_endpoint => {
+                return Err(format!("request {position}: every request must use `{current}`"));
+            },
+            Some(_) => {},
+        }
+        let Some(Value::Object(mut body)) = body else {
+            let field = if dialect == BatchDialect::OpenAi { "body" } else { "params" };
+            return Err(format!("request {position}: `{field}` must be an object"));
+        };
+        // Results are stored whole, so items never stream.
+        body.insert("stream".to_string(), Value::Bool(false));
+        body.remove("stream_options");
+        items.push(NewBatchItem {
+            custom_id,
+            body_json: Value::Object(body).to_string(),
+        });
+    }
+    Ok(ParsedBatchInput {
+        endpoint: endpoint.unwrap_or_default(),
+        metadata_json: metadata,
+        items,
+    })
+}
+
+fn batch_json(dialect: BatchDialect, job: &BatchJob) -> Value {
+    match dialect {
+        BatchDialect::OpenAi => openai_batch_json(job),
+        BatchDialect::Anthropic => anthropic_batch_json(job),
+    }
+}
+
+fn batch_list_json(dialect: BatchDialect, jobs: &[BatchJob]) -> Value {
+    let data = jobs
+        .iter()
+        .map(|job| batch_json(dialect, job))
+        .collect::<Vec<_>>();
+    let first_id = jobs.first().map(|job| job.batch_id.clone());
+    let last_id = jobs.last().map(|job| job.batch_id.clone());
+    match dialect {
+        BatchDialect::OpenAi => json!({
+            "object": "list",
+            "data": data,
+            "first_id": first_id,
+            "last_id": last_id,
+            "has_more": false,
+        }),
+        BatchDialect::Anthropic => json!({
+            "data": data,
+            "first_id": first_id,
+            "last_id": last_id,
+            "has_more": false,
+        }),
+    }
+}
+
+fn openai_batch_json(job: &BatchJob) -> Value {
+    let finished_as = |status: &str| {
+        (job.status == status)
+            .then_some(job.finished_at_ms)
+            .flatten()
+            .map(ms_to_seconds)
+    };
+    let metadata = job
+        .metadata_json
+        .as_deref()
+        .and_then(|metadata| serde_json::from_str::<Value>(metadata).ok());
+    json!({
+        "id": job.batch_id,
+        "object": "batch",
+        "endpoint": job.endpoint,
+        "errors": null,
+        "input_file_id": null,
+        "completion_window": "24h",
+        "status": job.status,
+        "output_file_id": null,
+        "error_file_id": null,
+        "output_url": format!("{}/{}/output", BatchDialect::OpenAi.base_path(), job.batch_id),
+        "created_at": ms_to_seconds(job.created_at_ms),
+        "in_progress_at": job.started_at_ms.map(ms_to_seconds),
+        "expires_at": ms_to_seconds(job.expires_at_ms),
+        "finalizing_at": null,
+        "completed_at": finished_as(BATCH_STATUS_COMPLETED),
+        "failed_at": null,
+        "expired_at": finished_as(BATCH_STATUS_EXPIRED),
+        "cancelling_at": job.cancel_requested_at_ms.map(ms_to_seconds),
+        "cancelled_at": finished_as(BATCH_STATUS_CANCELLED),
+        "request_counts": {
+            "total": job.total_count,
+            "completed": job.succeeded_count,
+            "failed": job.failed_count,
+        },
+        "metadata": metadata,
+    })
+}
+
+fn anthropic_batch_json(job: &BatchJob) -> Value {
+    let processing_status = match job.status.as_str() {
+        BATCH_STATUS_IN_PROGRESS => "in_progress",
+        BATCH_STATUS_CANCELLING => "canceling",
+        _ => "ended",
+    };
+    let results_url = job
+        .is_finished()
+        .then(|| format!("{}/{}/results", BatchDialect::Anthropic.base_path(), job.batch_id));
+    json!({
+        "id": job.batch_id,
+        "type": "message_batch",
+        "processing_status": processing_status,
+        "request_counts": {
+            "processing": job.processing_count,
+            "succeeded": job.succeeded_count,
+            "errored": job.failed_count,
+            "canceled": job.cancelled_count,
+            "expired": job.expired_count,
+        },
+        "ended_at": job.finished_at_ms.and_then(ms_to_rfc3339),
+        "created_at": ms_to_rfc3339(job.created_at_ms),
+        "expires_at": ms_to_rfc3339(job.expires_at_ms),
+        "archived_at": null,
+        "cancel_initiated_at": job.cancel_requested_at_ms.and_then(ms_to_rfc3339),
+        "results_url": results_url,
+    })
+}
+
+/// One OpenAI batch output line; `None` for items that have not finished.
+fn openai_result_line(batch_id: &str, item: &BatchItemResult) -> Option<Value> {
+    let (response, error) = match (item.status.as_str(), item.status_code) {
+        (BATCH_ITEM_STATUS_SUCCEEDED | BATCH_ITEM_STATUS_FAILED, Some(status_code)) => (
+            json!({
+                "status_code": status_code,
+                "request_id": "",
+                "body": response_body_value(item.response_body.as_deref()),
+            }),
+            Value::Null,
+        ),
+        (BATCH_ITEM_STATUS_FAILED, None) => {
+            (Value::Null, json!({ "code": "request_failed", "message": item.error_message }))
+        },
+        (BATCH_ITEM_STATUS_CANCELLED, _) => {
+            (Value::Null, json!({ "code": "batch_cancelled", "message": "batch was cancelled" }))
+        },
+        (BATCH_ITEM_STATUS_EXPIRED, _) => (
+            Value::Null,
+            json!({ "code": "batch_expired", "message": "batch completion window passed" }),
+        ),
+        _ => return None,
+    };
+    Some(json!({
+        "id": format!("batch_req_{batch_id}_{}", item.item_index),
+        "custom_id": item.custom_id,
+        "response": response,
+        "error": error,
+    }))
+}
+
+/// One Anthropic batch results line; `None` for items that have not finished.
+fn anthropic_result_line(item: &BatchItemResult) -> Option<Value> {
+    let result = match item.status.as_str() {
+        BATCH_ITEM_STATUS_SUCCEEDED => json!({
+            "type": "succeeded",
+            "message": response_body_value(item.response_body.as_deref()),
+        }),
+        BATCH_ITEM_STATUS_FAILED => {
+            let error = item
+                .response_body
+                .as_deref()
+                .and_then(|body| serde_json::from_str::<Value>(body).ok())
+                .filter(|body| body.get("error").is_some())
+                .unwrap_or_else(|| {
+                    json!({
+                        "type": "error",
+                        "error": {
+                            "type": "api_error",
+                            "message": item.error_message.as_deref().unwrap_or("request failed"),
+                        },
+                    })
+                });
+            json!({ "type": "errored", "error": error })
+        },
+        BATCH_ITEM_STATUS_CANCELLED => json!({ "type": "canceled" }),
+        BATCH_ITEM_STATUS_EXPIRED => json!({ "type": "expired" }),
+        _ => return None,
+    };
+    Some(json!({ "custom_id": item.custom_id, "result": result }))
+}
+
+fn response_body_value(body: Option<&str>) -> Value {
+    match body {
+        Some(body) => {
+            serde_json::from_str(body).unwrap_or_else(|_| Value::String(body.to_string()))
+        },
+        None => Value::Null,
+    }
+}
+
+fn ms_to_seconds(ms: i64) -> i64 {
+    ms / 1000
+}
+
+fn ms_to_rfc3339(ms: i64) -> Option<String> {
+    DateTime::<Utc>::from_timestamp_millis(ms)
+        .map(|value| value.to_rfc3339_opts(SecondsFormat::Millis, true))
+}
+
+fn batch_error(dialect: BatchDialect, status: StatusCode, message: &str) -> Response {
+    match dialect {
+        BatchDialect::OpenAi => codex_surface_error_response(dialect.base_path(), status, message),
+        BatchDialect::Anthropic => {
+            let error_type = if status == StatusCode::NOT_FOUND {
+                "not_found_error"
+            } else {
+                codex_error_type_for_status(status)
+            };
+            anthropic_json_error(status, error_type, message)
+        },
+    }
+}
+
+fn batch_not_found(dialect: BatchDialect) -> Response {
+    batch_error(dialect, StatusCode::NOT_FOUND, "batch not found")
+}
+
+fn batch_store_error(dialect: BatchDialect, err: &anyhow::Error) -> Response {
+    tracing::warn!("batch store request failed: {err:#}");
+    batch_error(dialect, StatusCode::INTERNAL_SERVER_ERROR, "batch store error")
+}
+
+/// Daily UTC window, `HH:MM-HH:MM`, that may wrap past midnight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct BatchWindow {
+    start_minute: u32,
+    end_minute: u32,
+}
+
+impl BatchWindow {
+    fn parse(value: &str) -> Option<Self> {
+        let (start, end) = value.trim().split_once('-')?;
+        Some(Self {
+            start_minute: parse_minute_of_day(start)?,
+            end_minute: parse_minute_of_day(end)?,
+        })
+    }
+
+    fn contains(self, minute_of_day: u32) -> bool {
+        match self.start_minute.cmp(&self.end_minute) {
+            std::cmp::Ordering::Less => {
+                (self.start_minute..self.end_minute).contains(&minute_of_day)
+            },
+            std::cmp::Ordering::Greater => {
+                minute_of_day >= self.start_minute || minute_of_day < self.end_minute
+            },
+            std::cmp::Ordering::Equal => true,
+        }
+    }
+}
+
+fn parse_minute_of_day(value: &str) -> Option<u32> {
+    let (hour, minute) = value.trim().split_once(':')?;
+    let hour = hour.parse::<u32>().ok().filter(|hour| *hour < 24)?;
+    let minute = minute.parse::<u32>().ok().filter(|minute| *minute < 60)?;
+    Some(hour * 60 + minute)
+}
+
+/// Worker settings read once at startup.
+struct BatchWorkerConfig {
+    window: Option<BatchWindow>,
+    poll_interval: Duration,
+    concurrency: usize,
+}
+
+impl BatchWorkerConfig {
+    fn from_env() -> Self {
+        let window = std::env::var(BATCH_WINDOW_ENV).ok().and_then(|value| {
+            let window = BatchWindow::parse(&value);
+            if window.is_none() && !value.trim().is_empty() {
+                tracing::warn!(value, "ignoring invalid {BATCH_WINDOW_ENV}");
+            }
+            window
+        });
+        let poll_interval = std::env::var(BATCH_POLL_INTERVAL_SECONDS_ENV)
+            .ok()
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .filter(|value| *value > 0)
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_BATCH_POLL_INTERVAL);
+        let concurrency = std::env::var(BATCH_WORKER_CONCURRENCY_ENV)
+            .ok()
+            .and_then(|value| value.trim().parse::<usize>().ok())
+            .unwrap_or(DEFAULT_BATCH_WORKER_CONCURRENCY)
+            .min(MAX_BATCH_WORKER_CONCURRENCY);
+        Self {
+            window,
+            poll_interval,
+            concurrency,
+        }
+    }
+
+    fn window_open(&self) -> bool {
+        let now = Utc::now();
+        self.window
+            .is_none_or(|window| window.contains(now.hour() * 60 + now.minute()))
+    }
+}
+
+/// Drain the batch queue in the background. Claims are leased, so every node
+/// may run the worker; `LLM_ACCESS_BATCH_WORKER_CONCURRENCY=0` turns it off.
+pub(crate) fn spawn_batch_worker(state: ProviderState) {
+    let config = BatchWorkerConfig::from_env();
+    if config.concurrency == 0 {
+        tracing::info!("batch worker is disabled by {BATCH_WORKER_CONCURRENCY_ENV}");
+        return;
+    }
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(config.poll_interval).await;
+            if let Err(err) = run_batch_worker_once(&state, &config).await {
+                tracing::warn!("batch worker pass failed: {err:#}");
+            }
+        }
+    });
+}
+
+async fn run_batch_worker_once(
+    state: &ProviderState,
+    config: &BatchWorkerConfig,
+) -> anyhow::Result<()> {
+    let now_ms = now_millis();
+    state
+        .batch_store
+        .expire_batch_jobs(now_ms, now_ms - BATCH_ITEM_LEASE_MS)
+        .await?;
+    while config.window_open() {
+        let now_ms = now_millis();
+        let items = state
+            .batch_store
+            .claim_batch_items(config.concurrency, now_ms, now_ms - BATCH_ITEM_LEASE_MS)
+            .await?;
+        let claimed = items.len();
+        join_all(
+            items
+                .into_iter()
+                .map(|item| run_claimed_batch_item(state, item)),
+        )
+        .await;
+        if claimed < config.concurrency {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// What the worker does with a claimed item after running it.
+#[derive(Debug, PartialEq)]
+enum BatchItemRun {
+    Finished(BatchItemOutcome),
+    Retry { retry_at_ms: i64 },
+}
+
+async fn run_claimed_batch_item(state: &ProviderState, item: ClaimedBatchItem) {
+    let recorded = match execute_batch_item(state, &item).await {
+        BatchItemRun::Finished(outcome) => {
+            state
+                .batch_store
+                .complete_batch_item(&item.batch_id, item.item_index, outcome)
+                .await
+        },
+        BatchItemRun::Retry {
+            retry_at_ms,
+        } => {
+            state
+                .batch_store
+                .release_batch_item(&item.batch_id, item.item_index, retry_at_ms)
+                .await
+        },
+    };
+    if let Err(err) = recorded {
+        tracing::warn!(
+            batch_id = %item.batch_id,
+            item_index = item.item_index,
+            "failed to record batch item: {err:#}"
+        );
+    }
+}
+
+async fn execute_batch_item(state: &ProviderState, item: &ClaimedBatchItem) -> BatchItemRun {
+    let key = item.key.clone();
+    let rejected = if !is_active_key(&key) {
+        Some("llm key is not active")
+    } else if !key_matches_route(&key, &item.endpoint) {
+        Some("llm key does not match provider route")
+    } else if is_quota_exhausted(&key) {
+        Some("key quota exhausted")
+    } else {
+        None
+    };
+    if let Some(message) = rejected {
+        return BatchItemRun::Finished(failed_outcome(None, None, message.to_string()));
+    }
+    let request = match Request::builder()
+        .method(Method::POST)
+        .uri(&item.endpoint)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(item.body_json.clone()))
+    {
+        Ok(request) => request,
+        Err(err) => {
+            return BatchItemRun::Finished(failed_outcome(
+                None,
+                None,
+                format!("failed to build batch request: {err}"),
+            ));
+        },
+    };
+
+    let _activity_guard = state.request_activity.start(&key.key_id);
+    let response = dispatch_with_moderation(state, key, request).await;
+    let status = response.status();
+    if is_retryable_batch_status(status) && item.attempts < MAX_BATCH_ITEM_ATTEMPTS {
+        return BatchItemRun::Retry {
+            retry_at_ms: now_millis() + BATCH_RETRY_BACKOFF_MS * i64::from(item.attempts),
+        };
+    }
+    let status_code = Some(i32::from(status.as_u16()));
+    let body = match read_capped_body(response.into_body(), MAX_BATCH_RESULT_BYTES).await {
+        Ok(Some(body)) => body,
+        Ok(None) => {
+            return BatchItemRun::Finished(failed_outcome(
+                status_code,
+                None,
+                format!("response body exceeds {MAX_BATCH_RESULT_BYTES} bytes"),
+            ));
+        },
+        Err(message) => {
+            return BatchItemRun::Finished(failed_outcome(status_code, None, message));
+        },
+    };
+    if status.is_success() {
+        BatchItemRun::Finished(BatchItemOutcome {
+            status: BATCH_ITEM_STATUS_SUCCEEDED.to_string(),
+            status_code,
+            response_body: Some(body),
+            error_message: None,
+            finished_at_ms: now_millis(),
+        })
+    } else {
+        BatchItemRun::Finished(failed_outcome(
+            status_code,
+            Some(body),
+            format!("request failed with status {}", status.as_u16()),
+        ))
+    }
+}
+
+fn failed_outcome(
+    status_code: Option<i32>,
+    response_body: Option<String>,
+    message: String,
+) -> BatchItemOutcome {
+    BatchItemOutcome {
+        status: BATCH_ITEM_STATUS_FAILED.to_string(),
+        status_code,
+        response_body,
+        error_message: Some(message),
+        finished_at_ms: now_millis(),
+    }
+}
+
+fn is_retryable_batch_status(status: StatusCode) -> bool {
+    matches!(status.as_u16(), 429 | 503 | 529)
+}
+
+/// Read a response body, keeping at most `limit` bytes. Oversized bodies are
+/// still drained so stream-end usage accounting runs; they yield `Ok(None)`.
+async fn read_capped_body(body: Body, limit: usize) -> Result<Option<String>, String> {
+    let mut stream = body.into_data_stream();
+    let mut buffer = Vec::new();
+    let mut overflowed = false;
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|err| format!("failed to read response body: {err}"))?;
+        if overflowed {
+            continue;
+        }
+        if buffer.len() + chunk.len() > limit {
+            overflowed = true;
+            buffer = Vec::new();
+        } else {
+            buffer.extend_from_slice(&chunk);
+        }
+    }
+    if overflowed {
+        Ok(None)
+    } else {
+        Ok(Some(String::from_utf8_lossy(&buffer).into_owned()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use async_trait::async_trait;
+    use llm_access_core::store::EmptyProviderRouteStore;
+
+    use super::*;
+    use crate::provider::{tests::NoopControlStore, ProviderDispatchDeps, ProviderDispatcher};
+
+    #[test]
+    fn batch_route_matches_both_dialects_and_aliases() {
+        assert_eq!(
+            batch_route(&Method::POST, "/v1/batches"),
+            Some((BatchDialect::OpenAi, Some(BatchAction::Create)))
+        );
+        assert_eq!(
+            batch_route(&Method::GET, "/api/llm-gateway/v1/batches/batch_1/output"),
+            Some((BatchDialect::OpenAi, Some(BatchAction::Results("batch_1".to_string()))))
+        );
+        assert_eq!(
+            batch_route(&Method::POST, "/api/kiro-gateway/v1/messages/batches/msgbatch_1/cancel"),
+            Some((BatchDialect::Anthropic, Some(BatchAction::Cancel("msgbatch_1".to_string()))))
+        );
+        assert_eq!(
+            batch_route(&Method::GET, "/v1/messages/batches/msgbatch_1/output"),
+            Some((BatchDialect::Anthropic, None))
+        );
+        assert_eq!(batch_route(&Method::POST, "/v1/batchesx"), None);
+        assert_eq!(batch_route(&Method::POST, "/v1/messages"), None);
+    }
+
+    #[test]
+    fn openai_input_requires_one_supported_endpoint() {
+        let input = concat!(
+            r#"{"custom_id":"a","method":"POST","url":"/v1/responses","body":{"model":"gpt-5","stream":true}}"#,
+            "\n\n",
+            r#"{"custom_id":"b","url":"/v1/responses","body":{"model":"gpt-5"}}"#,
+            "\n",
+        );
+        let parsed = parse_batch_input(BatchDialect::OpenAi, input.as_bytes()).expect("parse");
+        assert_eq!(parsed.endpoint, "/v1/responses");
+        assert_eq!(parsed.items.len(), 2);
+        assert_eq!(parsed.items[0].body_json, r#"{"model":"gpt-5","stream":false}"#);
+
+        let mixed = concat!(
+            r#"{"custom_id":"a","url":"/v1/responses","body":{}}"#,
+            "\n",
+            r#"{"custom_id":"b","url":"/v1/chat/completions","body":{}}"#,
+        );
+        assert!(parse_batch_input(BatchDialect::OpenAi, mixed.as_bytes())
+            .expect_err("mixed endpoints")
+            .contains("every request"));
+        let duplicate = concat!(
+            r#"{"custom_id":"a","url":"/v1/responses","body":{}}"#,
+            "\n",
+            r#"{"custom_id":"a","url":"/v1/responses","body":{}}"#,
+        );
+        assert!(parse_batch_input(BatchDialect::OpenAi, duplicate.as_bytes())
+            .expect_err("duplicate ids")
+            .contains("duplicate custom_id"));
+        let unsupported = r#"{"custom_id":"a","url":"/v1/embeddings","body":{}}"#;
+        assert!(parse_batch_input(BatchDialect::OpenAi, unsupported.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn anthropic_input_accepts_requests_object_and_jsonl() {
+        let object = r#"{"requests":[{"custom_id":"a","params":{"model":"claude","max_tokens":8}}],"metadata":{"job":"translate"}}"#;
+        let parsed = parse_batch_input(BatchDialect::Anthropic, object.as_bytes()).expect("parse");
+        assert_eq!(parsed.endpoint, "/v1/messages");
+        assert_eq!(parsed.metadata_json.as_deref(), Some(r#"{"job":"translate"}"#));
+        assert_eq!(parsed.items[0].custom_id, "a");
+
+        let jsonl = r#"{"custom_id":"a","params":{"model":"claude"}}"#;
+        let parsed = parse_batch_input(BatchDialect::Anthropic, jsonl.as_bytes()).expect("jsonl");
+        assert_eq!(parsed.items.len(), 1);
+        assert!(parse_batch_input(BatchDialect::Anthropic, br#"{"custom_id":"a"}"#).is_err());
+        assert!(parse_batch_input(BatchDialect::Anthropic, b"").is_err());
+    }
+
+    fn job(status: &str) -> BatchJob {
+        BatchJob {
+            batch_id: "msgbatch_1".to_string(),
+            key_id: "key-1".to_string(),
+            protocol_family: "anthropic".to_string(),
+            endpoint: "/v1/messages".to_string(),
+            status: status.to_string(),
+            metadata_json: None,
+            total_count: 3,
+            processing_count: 0,
+            succeeded_count: 1,
+            failed_count: 1,
+            cancelled_count: 1,
+            expired_count: 0,
+            created_at_ms: 1_700_000_000_000,
+            started_at_ms: Some(1_700_000_001_000),
+            finished_at_ms: Some(1_700_000_002_000),
+            cancel_requested_at_ms: Some(1_700_000_001_500),
+            expires_at_ms: 1_700_000_000_000 + BATCH_COMPLETION_WINDOW_MS,
+        }
+    }
+
+    #[test]
+    fn batch_objects_follow_each_dialect() {
+        let anthropic = anthropic_batch_json(&job(BATCH_STATUS_CANCELLED));
+        assert_eq!(anthropic["processing_status"], "ended");
+        assert_eq!(anthropic["request_counts"]["canceled"], 1);
+        assert_eq!(anthropic["created_at"], "2023-11-14T22:13:20.000Z");
+        assert_eq!(anthropic["results_url"], "/v1/messages/batches/msgbatch_1/results");
+        let in_progress = anthropic_batch_json(&job(BATCH_STATUS_CANCELLING));
+        assert_eq!(in_progress["processing_status"], "canceling");
+        assert!(in_progress["results_url"].is_null());
+
+        let openai = openai_batch_json(&job(BATCH_STATUS_COMPLETED));
+        assert_eq!(openai["object"], "batch");
+        assert_eq!(openai["completed_at"], 1_700_000_002);
+        assert!(openai["cancelled_at"].is_null());
+        assert_eq!(openai["request_counts"]["total"], 3);
+    }
+
+    #[test]
+    fn result_lines_follow_each_dialect() {
+        let succeeded = BatchItemResult {
+            item_index: 0,
+            custom_id: "a".to_string(),
+            status: BATCH_ITEM_STATUS_SUCCEEDED.to_string(),
+            status_code: Some(200),
+            response_body: Some(r#"{"id":"msg_1"}"#.to_string()),
+            error_message: None,
+            finished_at_ms: Some(1),
+        };
+        let line = openai_result_line("batch_1", &succeeded).expect("line");
+        assert_eq!(line["id"], "batch_req_batch_1_0");
+        assert_eq!(line["response"]["body"]["id"], "msg_1");
+        let line = anthropic_result_line(&succeeded).expect("line");
+        assert_eq!(line["result"]["type"], "succeeded");
+        assert_eq!(line["result"]["message"]["id"], "msg_1");
+
+        let cancelled = BatchItemResult {
+            status: BATCH_ITEM_STATUS_CANCELLED.to_string(),
+            status_code: None,
+            response_body: None,
+            ..succeeded.clone()
+        };
+        assert_eq!(
+            openai_result_line("batch_1", &cancelled).expect("line")["error"]["code"],
+            "batch_cancelled"
+        );
+        assert_eq!(anthropic_result_line(&cancelled).expect("line")["result"]["type"], "canceled");
+        let pending = BatchItemResult {
+            status: "pending".to_string(),
+            ..cancelled
+        };
+        assert!(openai_result_line("batch_1", &pending).is_none());
+    }
+
+    #[test]
+    fn batch_window_wraps_midnight() {
+        let window = BatchWindow::parse("22:00-06:30").expect("window");
+        assert!(window.contains(23 * 60));
+        assert!(window.contains(6 * 60 + 29));
+        assert!(!window.contains(12 * 60));
+        let day = BatchWindow::parse(" 09:00 - 17:00 ").expect("window");
+        assert!(day.contains(9 * 60));
+        assert!(!day.contains(17 * 60));
+        assert!(BatchWindow::parse("25:00-01:00").is_none());
+        assert!(BatchWindow::parse("09:00").is_none());
+    }
+
+    struct StatusDispatcher(StatusCode);
+
+    #[async_trait]
+    impl ProviderDispatcher for StatusDispatcher {
+        async fn dispatch(
+            &self,
+            _key: AuthenticatedKey,
+            request: Request<Body>,
+            _deps: ProviderDispatchDeps,
+        ) -> Response {
+            let body = to_bytes(request.into_body(), usize::MAX)
+                .await
+                .expect("request body");
+            (self.0, body).into_response()
+        }
+    }
+
+    fn claimed(attempts: i32) -> ClaimedBatchItem {
+        ClaimedBatchItem {
+            batch_id: "batch_1".to_string(),
+            item_index: 0,
+            custom_id: "a".to_string(),
+            endpoint: "/v1/responses".to_string(),
+            body_json: r#"{"model":"gpt-5","stream":false}"#.to_string(),
+            attempts,
+            key: AuthenticatedKey {
+                key_id: "key-1".to_string(),
+                key_name: "batch".to_string(),
+                provider_type: "codex".to_string(),
+                protocol_family: "openai".to_string(),
+                status: "active".to_string(),
+                quota_billable_limit: 1_000,
+                billable_tokens_used: 0,
+                parent_remaining_billable: None,
+                parent_key_id: None,
+            },
+        }
+    }
+
+    fn state(status: StatusCode) -> ProviderState {
+        ProviderState::with_dispatcher(
+            Arc::new(NoopControlStore),
+            Arc::new(EmptyProviderRouteStore),
+            Arc::new(StatusDispatcher(status)),
+        )
+    }
+
+    #[tokio::test]
+    async fn worker_dispatches_items_and_retries_rate_limits() {
+        let BatchItemRun::Finished(outcome) =
+            execute_batch_item(&state(StatusCode::OK), &claimed(1)).await
+        else {
+            panic!("expected a finished item");
+        };
+        assert_eq!(outcome.status, BATCH_ITEM_STATUS_SUCCEEDED);
+        assert_eq!(outcome.response_body.as_deref(), Some(r#"{"model":"gpt-5","stream":false}"#));
+
+        let limited = state(StatusCode::TOO_MANY_REQUESTS);
+        assert!(matches!(
+            execute_batch_item(&limited, &claimed(1)).await,
+            BatchItemRun::Retry { .. }
+        ));
+        let BatchItemRun::Finished(outcome) =
+            execute_batch_item(&limited, &claimed(MAX_BATCH_ITEM_ATTEMPTS)).await
+        else {
+            panic!("expected the last attempt to finish");
+        };
+        assert_eq!(outcome.status, BATCH_ITEM_STATUS_FAILED);
+        assert_eq!(outcome.status_code, Some(429));
+
+        let mut exhausted = claimed(1);
+        exhausted.key.billable_tokens_used = 1_000;
+        let BatchItemRun::Finished(outcome) =
+            execute_batch_item(&state(StatusCode::OK), &exhausted).await
+        else {
+            panic!("expected a rejected item");
+        };
+        assert_eq!(outcome.error_message.as_deref(), Some("key quota exhausted"));
+    }
+}
@@ -0,0 +1,921 @@
+//! Per-account and per-proxy circuit breakers for request routing.
+//!
+//! ```text
+//!            failure rate >= threshold over the window
+//!   closed -------------------------------------------> open
+//!     ^                                                   |
+//!     |                                     open interval |
+//!     | enough trial successes                  elapses   v
+//!     +--------------------------------------------- half-open
+//!                       trial failure: reopen with a doubled interval
+//! ```
+//!
+//! Request-path cooldowns react to a single classified failure with a fixed
+//! pause. The breaker instead watches the failure rate of everything sent
+//! through an account (or a proxy) and, once tripped, keeps routing away from
+//! it until a small number of trial requests succeed. Admin model probes count
+//! as trials, so an operator can close a circuit without waiting for client
+//! traffic. State is local to the process and evaluated lazily on read.
+
+use std::{
+    collections::{BTreeSet, HashMap, VecDeque},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use llm_access_core::{
+    provider::ProviderType,
+    store::{ProviderCodexRoute, ProviderKiroRoute},
+};
+use serde::Serialize;
+
+use super::{
+    util::{clamp_duration_ms, now_millis},
+    ProviderState,
+};
+
+const CIRCUIT_BREAKER_ENABLED_ENV: &str = "LLM_ACCESS_CIRCUIT_BREAKER_ENABLED";
+const CIRCUIT_BREAKER_WINDOW_SECONDS_ENV: &str = "LLM_ACCESS_CIRCUIT_BREAKER_WINDOW_SECONDS";
+const CIRCUIT_BREAKER_MIN_REQUESTS_ENV: &str = "LLM_ACCESS_CIRCUIT_BREAKER_MIN_REQUESTS";
+const CIRCUIT_BREAKER_FAILURE_RATE_PERCENT_ENV: &str =
+    "LLM_ACCESS_CIRCUIT_BREAKER_FAILURE_RATE_PERCENT";
+const CIRCUIT_BREAKER_OPEN_SECONDS_ENV: &str = "LLM_ACCESS_CIRCUIT_BREAKER_OPEN_SECONDS";
+const CIRCUIT_BREAKER_MAX_OPEN_SECONDS_ENV: &str = "LLM_ACCESS_CIRCUIT_BREAKER_MAX_OPEN_SECONDS";
+const CIRCUIT_BREAKER_HALF_OPEN_TRIALS_ENV: &str = "LLM_ACCESS_CIRCUIT_BREAKER_HALF_OPEN_TRIALS";
+const DEFAULT_CIRCUIT_BREAKER_WINDOW_SECONDS: u64 = 60;
+const DEFAULT_CIRCUIT_BREAKER_MIN_REQUESTS: usize = 10;
+const DEFAULT_CIRCUIT_BREAKER_FAILURE_RATE_PERCENT: usize = 50;
+const DEFAULT_CIRCUIT_BREAKER_OPEN_SECONDS: u64 = 30;
+const DEFAULT_CIRCUIT_BREAKER_MAX_OPEN_SECONDS: u64 = 10 * 60;
+const DEFAULT_CIRCUIT_BREAKER_HALF_OPEN_TRIALS: usize = 1;
+/// A half-open trial that never reports back (client hung up, the upstream
+/// answered with a client error) frees its slot after this long.
+const CIRCUIT_BREAKER_TRIAL_TIMEOUT: Duration = Duration::from_secs(2 * 60);
+/// Upper bound on remembered outcomes per circuit, whatever the window.
+const CIRCUIT_BREAKER_MAX_WINDOW_OUTCOMES: usize = 4_096;
+const CIRCUIT_BREAKER_MAX_REASON_CHARS: usize = 240;
+
+/// Thresholds shared by every circuit in the process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct CircuitBreakerConfig {
+    enabled: bool,
+    window: Duration,
+    min_requests: usize,
+    failure_rate_percent: usize,
+    open_duration: Duration,
+    max_open_duration: Duration,
+    half_open_trials: usize,
+}
+
+impl CircuitBreakerConfig {
+    pub(crate) fn from_env() -> Self {
+        let var = |name: &str| std::env::var(name).ok();
+        Self::from_raw(
+            var(CIRCUIT_BREAKER_ENABLED_ENV).as_deref(),
+            var(CIRCUIT_BREAKER_WINDOW_SECONDS_ENV).as_deref(),
+            var(CIRCUIT_BREAKER_MIN_REQUESTS_ENV).as_deref(),
+            var(CIRCUIT_BREAKER_FAILURE_RATE_PERCENT_ENV).as_deref(),
+            var(CIRCUIT_BREAKER_OPEN_SECONDS_ENV).as_deref(),
+            var(CIRCUIT_BREAKER_MAX_OPEN_SECONDS_ENV).as_deref(),
+            var(CIRCUIT_BREAKER_HALF_OPEN_TRIALS_ENV).as_deref(),
+        )
+    }
+
+    fn from_raw(
+        enabled: Option<&str>,
+        window_seconds: Option<&str>,
+        min_requests: Option<&str>,
+        failure_rate_percent: Option<&str>,
+        open_seconds: Option<&str>,
+        max_open_seconds: Option<&str>,
+        half_open_trials: Option<&str>,
+    ) -> Self {
+        let parse = |raw: Option<&str>, default: u64| {
+            raw.and_then(|value| value.trim().parse::<u64>().ok())
+                .filter(|value| *value > 0)
+                .unwrap_or(default)
+        };
+        let parse_usize = |raw: Option<&str>, default: usize| {
+            raw.and_then(|value| value.trim().parse::<usize>().ok())
+                .filter(|value| *value > 0)
+                .unwrap_or(default)
+        };
+        let open_duration =
+            Duration::from_secs(parse(open_seconds, DEFAULT_CIRCUIT_BREAKER_OPEN_SECONDS));
+        Self {
+            enabled: enabled.is_none_or(|raw| {
+                !matches!(raw.trim().to_ascii_lowercase().as_str(), "0" | "false" | "no" | "off")
+            }),
+            window: Duration::from_secs(parse(
+                window_seconds,
+                DEFAULT_CIRCUIT_BREAKER_WINDOW_SECONDS,
+            )),
+            min_requests: parse_usize(min_requests, DEFAULT_CIRCUIT_BREAKER_MIN_REQUESTS),
+            failure_rate_percent: parse_usize(
+                failure_rate_percent,
+                DEFAULT_CIRCUIT_BREAKER_FAILURE_RATE_PERCENT,
+            )
+            .min(100),
+            open_duration,
+            max_open_duration: Duration::from_secs(parse(
+                max_open_seconds,
+                DEFAULT_CIRCUIT_BREAKER_MAX_OPEN_SECONDS,
+            ))
+            .max(open_duration),
+            half_open_trials: parse_usize(
+                half_open_trials,
+                DEFAULT_CIRCUIT_BREAKER_HALF_OPEN_TRIALS,
+            ),
+        }
+    }
+
+    /// Open interval after the `consecutive_opens`-th trip in a row.
+    fn open_duration_for(&self, consecutive_opens: u32) -> Duration {
+        let doublings = consecutive_opens.saturating_sub(1).min(16);
+        self.open_duration
+            .saturating_mul(1_u32 << doublings)
+            .min(self.max_open_duration)
+    }
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self::from_raw(None, None, None, None, None, None, None)
+    }
+}
+
+/// What a circuit guards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum CircuitScope {
+    Account,
+    Proxy,
+}
+
+impl CircuitScope {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Account => "account",
+            Self::Proxy => "proxy",
+        }
+    }
+}
+
+/// Externally visible circuit state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum CircuitStatus {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+/// Routing decision for one route.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CircuitAdmission {
+    /// Every circuit on the route is closed.
+    Allow,
+    /// A circuit on the route is half-open with a free trial slot. Call
+    /// [`CircuitBreakers::begin_trial`] once the request is committed to it.
+    Trial,
+    /// A circuit on the route is open, or all of its trial slots are taken.
+    Reject { remaining: Duration },
+}
+
+/// The circuits one upstream request goes through: its account and, when the
+/// route uses one, its proxy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CircuitTarget {
+    provider_type: ProviderType,
+    account_target: String,
+    account_name: String,
+    proxy_url: Option<String>,
+}
+
+impl CircuitTarget {
+    pub(crate) fn codex(route: &ProviderCodexRoute) -> Self {
+        Self {
+            provider_type: ProviderType::Codex,
+            account_target: route.account_name.clone(),
+            account_name: route.account_name.clone(),
+            proxy_url: route.proxy.as_ref().map(|proxy| proxy.proxy_url.clone()),
+        }
+    }
+
+    /// Kiro accounts sharing one upstream identity share one circuit, like
+    /// they share scheduler cooldowns.
+    pub(crate) fn kiro(route: &ProviderKiroRoute) -> Self {
+        Self {
+            provider_type: ProviderType::Kiro,
+            account_target: route.routing_identity.clone(),
+            account_name: route.account_name.clone(),
+            proxy_url: route.proxy.as_ref().map(|proxy| proxy.proxy_url.clone()),
+        }
+    }
+
+    /// Codex account circuit only, for failures seen before the route is
+    /// hydrated.
+    pub(crate) fn codex_account(account_name: &str) -> Self {
+        Self {
+            provider_type: ProviderType::Codex,
+            account_target: account_name.to_string(),
+            account_name: account_name.to_string(),
+            proxy_url: None,
+        }
+    }
+
+    pub(crate) fn account_name(&self) -> &str {
+        &self.account_name
+    }
+
+    fn account_key(&self) -> String {
+        circuit_key(self.provider_type, CircuitScope::Account, &self.account_target)
+    }
+
+    fn proxy_key(&self) -> Option<String> {
+        self.proxy_url
+            .as_deref()
+            .map(|proxy_url| circuit_key(self.provider_type, CircuitScope::Proxy, proxy_url))
+    }
+
+    fn keys(&self) -> impl Iterator<Item = String> {
+        std::iter::once(self.account_key()).chain(self.proxy_key())
+    }
+}
+
+fn circuit_key(provider_type: ProviderType, scope: CircuitScope, target: &str) -> String {
+    format!("{}:{}:{target}", provider_type.as_storage_str(), scope.as_str())
+}
+
+/// Admin view of one circuit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct CircuitBreakerView {
+    pub(crate) scope: CircuitScope,
+    /// Codex account name, Kiro routing identity, or proxy URL.
+    pub(crate) target: String,
+    /// Account names whose traffic fed this circuit.
+    pub(crate) account_names: Vec<String>,
+    pub(crate) state: CircuitStatus,
+    pub(crate) window_requests: usize,
+    pub(crate) window_failures: usize,
+    pub(crate) open_remaining_ms: Option<i64>,
+    pub(crate) trials_in_flight: usize,
+    pub(crate) trial_successes: usize,
+    pub(crate) consecutive_opens: u32,
+    pub(crate) last_failure_reason: Option<String>,
+    pub(crate) state_changed_at: Option<i64>,
+}
+
+enum CircuitState {
+    Closed,
+    /// Tripped. Once `until` passes the circuit is half-open: up to
+    /// `half_open_trials` requests may run at once, and that many successes
+    /// close it.
+    Open {
+        until: Instant,
+        trials: VecDeque<Instant>,
+        successes: usize,
+    },
+}
+
+struct Circuit {
+    provider_type: ProviderType,
+    scope: CircuitScope,
+    target: String,
+    account_names: BTreeSet<String>,
+    /// `(finished_at, succeeded)` for requests inside the window.
+    outcomes: VecDeque<(Instant, bool)>,
+    state: CircuitState,
+    consecutive_opens: u32,
+    last_failure_reason: Option<String>,
+    state_changed_at: Option<i64>,
+}
+
+impl Circuit {
+    fn new(provider_type: ProviderType, scope: CircuitScope, target: String) -> Self {
+        Self {
+            provider_type,
+            scope,
+            target,
+            account_names: BTreeSet::new(),
+            outcomes: VecDeque::new(),
+            state: CircuitState::Closed,
+            consecutive_opens: 0,
+            last_failure_reason: None,
+            state_changed_at: None,
+        }
+    }
+
+    fn prune(&mut self, config: &CircuitBreakerConfig, now: Instant) {
+        while self
+            .outcomes
+            .front()
+            .is_some_and(|(at, _)| now.saturating_duration_since(*at) > config.window)
+        {
+            self.outcomes.pop_front();
+        }
+        if let CircuitState::Open {
+            trials, ..
+        } = &mut self.state
+        {
+            trials.retain(|started| {
+                now.saturating_duration_since(*started) < CIRCUIT_BREAKER_TRIAL_TIMEOUT
+            });
+        }
+    }
+
+    fn push_outcome(&mut self, now: Instant, succeeded: bool) {
+        if self.outcomes.len() >= CIRCUIT_BREAKER_MAX_WINDOW_OUTCOMES {
+            self.outcomes.pop_front();
+        }
+        self.outcomes.push_back((now, succeeded));
+    }
+
+    fn status(&self, now: Instant) -> CircuitStatus {
+        match &self.state {
+            CircuitState::Closed => CircuitStatus::Closed,
+            CircuitState::Open {
+                until, ..
+            } if *until > now => CircuitStatus::Open,
+            CircuitState::Open {
+                ..
+            } => CircuitStatus::HalfOpen,
+        }
+    }
+
+    fn admission(&self, config: &CircuitBreakerConfig, now: Instant) -> CircuitAdmission {
+        match &self.state {
+            CircuitState::Closed => CircuitAdmission::Allow,
+            CircuitState::Open {
+                until, ..
+            } if *until > now => CircuitAdmission::Reject {
+                remaining: until.saturating_duration_since(now),
+            },
+            CircuitState::Open {
+                trials, ..
+            } => {
+                let live_trials = trials.iter().filter(|started| {
+                    now.saturating_duration_since(**started) < CIRCUIT_BREAKER_TRIAL_TIMEOUT
+                });
+                if live_trials.clone().count() < config.half_open_trials {
+                    CircuitAdmission::Trial
+                } else {
+                    let oldest = live_trials.min().copied().unwrap_or(now);
+                    CircuitAdmission::Reject {
+                        remaining: CIRCUIT_BREAKER_TRIAL_TIMEOUT
+                            .saturating_sub(now.saturating_duration_since(oldest)),
+                    }
+                }
+            },
+        }
+    }
+
+    fn trip(&mut self, config: &CircuitBreakerConfig, now: Instant) {
+        self.consecutive_opens = self.consecutive_opens.saturating_add(1);
+        let open_for = config.open_duration_for(self.consecutive_opens);
+        self.state = CircuitState::Open {
+            until: now + open_for,
+            trials: VecDeque::new(),
+            successes: 0,
+        };
+        self.state_changed_at = Some(now_millis());
+        tracing::warn!(
+            provider_type = self.provider_type.as_storage_str(),
+            scope = self.scope.as_str(),
+            target = %self.target,
+            consecutive_opens = self.consecutive_opens,
+            open_ms = clamp_duration_ms(open_for),
+            reason = self.last_failure_reason.as_deref().unwrap_or_default(),
+            "circuit breaker opened"
+        );
+    }
+
+    fn close(&mut self) {
+        self.state = CircuitState::Closed;
+        self.outcomes.clear();
+        self.consecutive_opens = 0;
+        self.state_changed_at = Some(now_millis());
+        tracing::info!(
+            provider_type = self.provider_type.as_storage_str(),
+            scope = self.scope.as_str(),
+            target = %self.target,
+            "circuit breaker closed"
+        );
+    }
+
+    /// Count one half-open trial success; `early` lets an admin probe close a
+    /// circuit whose open interval has not elapsed yet.
+    fn trial_succeeded(&mut self, config: &CircuitBreakerConfig, now: Instant, early: bool) {
+        let CircuitState::Open {
+            until,
+            trials,
+            successes,
+        } = &mut self.state
+        else {
+            return;
+        };
+        if *until > now && !early {
+            // Started before the circuit tripped; says nothing about recovery.
+            return;
+        }
+        trials.pop_front();
+        *successes += 1;
+        if *successes >= config.half_open_trials {
+            self.close();
+        }
+    }
+
+    fn record_success(&mut self, config: &CircuitBreakerConfig, now: Instant) {
+        self.prune(config, now);
+        self.push_outcome(now, true);
+        self.trial_succeeded(config, now, false);
+    }
+
+    fn record_failure(&mut self, config: &CircuitBreakerConfig, now: Instant, reason: &str) {
+        self.prune(config, now);
+        self.push_outcome(now, false);
+        self.last_failure_reason = Some(
+            reason
+                .chars()
+                .take(CIRCUIT_BREAKER_MAX_REASON_CHARS)
+                .collect(),
+        );
+        match self.status(now) {
+            CircuitStatus::Closed => {
+                let total = self.outcomes.len();
+                let failures = self
+                    .outcomes
+                    .iter()
+                    .filter(|(_, succeeded)| !succeeded)
+                    .count();
+                if total >= config.min_requests
+                    && failures * 100 >= config.failure_rate_percent * total
+                {
+                    self.trip(config, now);
+                }
+            },
+            CircuitStatus::HalfOpen => self.trip(config, now),
+            CircuitStatus::Open => {},
+        }
+    }
+
+    fn release_trial(&mut self, config: &CircuitBreakerConfig, now: Instant) {
+        self.prune(config, now);
+        if let CircuitState::Open {
+            until,
+            trials,
+            ..
+        } = &mut self.state
+        {
+            if *until <= now {
+                trials.pop_front();
+            }
+        }
+    }
+
+    fn is_idle(&self) -> bool {
+        matches!(self.state, CircuitState::Closed) && self.outcomes.is_empty()
+    }
+
+    fn view(&self, now: Instant) -> CircuitBreakerView {
+        let (open_remaining_ms, trials_in_flight, trial_successes) = match &self.state {
+            CircuitState::Closed => (None, 0, 0),
+            CircuitState::Open {
+                until,
+                trials,
+                successes,
+            } => (
+                Some(clamp_duration_ms(until.saturating_duration_since(now))),
+                trials.len(),
+                *successes,
+            ),
+        };
+        CircuitBreakerView {
+            scope: self.scope,
+            target: self.target.clone(),
+            account_names: self.account_names.iter().cloned().collect(),
+            state: self.status(now),
+            window_requests: self.outcomes.len(),
+            window_failures: self
+                .outcomes
+                .iter()
+                .filter(|(_, succeeded)| !succeeded)
+                .count(),
+            open_remaining_ms,
+            trials_in_flight,
+            trial_successes,
+            consecutive_opens: self.consecutive_opens,
+            last_failure_reason: self.last_failure_reason.clone(),
+            state_changed_at: self.state_changed_at,
+        }
+    }
+}
+
+/// Process-wide circuit registry keyed by provider, scope and target.
+pub(crate) struct CircuitBreakers {
+    config: CircuitBreakerConfig,
+    circuits: Mutex<HashMap<String, Circuit>>,
+}
+
+impl Default for CircuitBreakers {
+    fn default() -> Self {
+        Self::new(CircuitBreakerConfig::from_env())
+    }
+}
+
+impl CircuitBreakers {
+    pub(crate) fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            circuits: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether routing may send a request down `target` right now. Reading
+    /// does not reserve a trial slot, so rejected candidates cost nothing.
+    pub(crate) fn admission(&self, target: &CircuitTarget) -> CircuitAdmission {
+        if !self.config.enabled {
+            return CircuitAdmission::Allow;
+        }
+        let Ok(circuits) = self.circuits.lock() else {
+            return CircuitAdmission::Allow;
+        };
+        let now = Instant::now();
+        target
+            .keys()
+            .filter_map(|key| circuits.get(&key))
+            .map(|circuit| circuit.admission(&self.config, now))
+            .fold(CircuitAdmission::Allow, |current, next| match (current, next) {
+                (
+                    CircuitAdmission::Reject {
+                        remaining: left,
+                    },
+                    CircuitAdmission::Reject {
+                        remaining: right,
+                    },
+                ) => CircuitAdmission::Reject {
+                    remaining: left.max(right),
+                },
+                (
+                    reject @ CircuitAdmission::Reject {
+                        ..
+                    },
+                    _,
+                )
+                | (
+                    _,
+                    reject @ CircuitAdmission::Reject {
+                        ..
+                    },
+                ) => reject,
+                (CircuitAdmission::Trial, _) | (_, CircuitAdmission::Trial) => {
+                    CircuitAdmission::Trial
+                },
+                _ => CircuitAdmission::Allow,
+            })
+    }
+
+    /// Reserve a trial slot on every half-open circuit of `target`.
+    pub(crate) fn begin_trial(&self, target: &CircuitTarget) {
+        self.with_circuits(target, false, |config, circuit, now| {
+            circuit.prune(config, now);
+            if let CircuitState::Open {
+                until,
+                trials,
+                ..
+            } = &mut circuit.state
+            {
+                if *until <= now {
+                    trials.push_back(now);
+                }
+            }
+        });
+    }
+
+    /// The upstream accepted a request sent down `target`.
+    pub(crate) fn record_success(&self, target: &CircuitTarget) {
+        self.with_circuits(target, false, |config, circuit, now| {
+            circuit.record_success(config, now);
+        });
+    }
+
+    /// A request failed because of the account; when `proxy_failed` the proxy
+    /// circuit is charged too.
+    pub(crate) fn record_failure(&self, target: &CircuitTarget, proxy_failed: bool, reason: &str) {
+        self.with_circuits(target, false, |config, circuit, now| {
+            if proxy_failed || circuit.scope == CircuitScope::Account {
+                circuit.record_failure(config, now, reason);
+            } else {
+                circuit.release_trial(config, now);
+            }
+        });
+    }
+
+    /// A proxy-only failure, such as a connect error, that says nothing about
+    /// the account behind it.
+    pub(crate) fn record_proxy_failure(&self, target: &CircuitTarget, reason: &str) {
+        self.with_circuits(target, false, |config, circuit, now| {
+            if circuit.scope == CircuitScope::Proxy {
+                circuit.record_failure(config, now, reason);
+            }
+        });
+    }
+
+    /// The upstream answered but the outcome says nothing about the health of
+    /// the route (a client error, an exhausted quota); frees the trial slot.
+    pub(crate) fn record_inconclusive(&self, target: &CircuitTarget) {
+        self.with_circuits(target, true, |config, circuit, now| {
+            circuit.release_trial(config, now);
+        });
+    }
+
+    /// Feed an admin model probe. A successful probe counts as a trial success
+    /// even before the open interval elapses; a failed one reopens an already
+    /// tripped account circuit. Closed circuits ignore probes so operator
+    /// activity does not skew the request failure rate.
+    pub(crate) fn record_probe(&self, target: &CircuitTarget, ok: bool, reason: &str) {
+        self.with_circuits(target, true, |config, circuit, now| {
+            if matches!(circuit.state, CircuitState::Closed) {
+                return;
+            }
+            circuit.prune(config, now);
+            if ok {
+                circuit.trial_succeeded(config, now, true);
+            } else if circuit.scope == CircuitScope::Account {
+                circuit.last_failure_reason = Some(
+                    reason
+                        .chars()
+                        .take(CIRCUIT_BREAKER_MAX_REASON_CHARS)
+                        .collect(),
+                );
+                circuit.trip(config, now);
+            }
+        });
+    }
+
+    /// Circuits of one provider that are tripped or saw traffic inside the
+    /// window, tripped ones first.
+    pub(crate) fn snapshot(&self, provider_type: ProviderType) -> Vec<CircuitBreakerView> {
+        let Ok(mut circuits) = self.circuits.lock() else {
+            return Vec::new();
+        };
+        let now = Instant::now();
+        circuits.retain(|_, circuit| {
+            circuit.prune(&self.config, now);
+            !circuit.is_idle()
+        });
+        let mut views = circuits
+            .values()
+            .filter(|circuit| circuit.provider_type == provider_type)
+            .map(|circuit| circuit.view(now))
+            .collect::<Vec<_>>();
+        views.sort_by(|left, right| {
+            (left.state == CircuitStatus::Closed)
+                .cmp(&(right.state == CircuitStatus::Closed))
+                .then_with(|| left.scope.as_str().cmp(right.scope.as_str()))
+                .then_with(|| left.target.cmp(&right.target))
+        });
+        views
+    }
+
+    /// Run `update` on every circuit of `target`, creating missing ones unless
+    /// `existing_only`.
+    fn with_circuits(
+        &self,
+        target: &CircuitTarget,
+        existing_only: bool,
+        mut update: impl FnMut(&CircuitBreakerConfig, &mut Circuit, Instant),
+    ) {
+        if !self.config.enabled {
+            return;
+        }
+        let Ok(mut circuits) = self.circuits.lock() else {
+            return;
+        };
+        let now = Instant::now();
+        let scoped_keys =
+            std::iter::once((CircuitScope::Account, target.account_key(), &target.account_target))
+                .chain(target.proxy_url.as_ref().and_then(|proxy_url| {
+                    target
+                        .proxy_key()
+                        .map(|key| (CircuitScope::Proxy, key, proxy_url))
+                }));
+        for (scope, key, circuit_target) in scoped_keys {
+            let circuit = if existing_only {
+                match circuits.get_mut(&key) {
+                    Some(circuit) => circuit,
+                    None => continue,
+                }
+            } else {
+                circuits.entry(key).or_insert_with(|| {
+                    Circuit::new(target.provider_type, scope, circuit_target.clone())
+                })
+            };
+            circuit.account_names.insert(target.account_name.clone());
+            update(&self.config, circuit, now);
+        }
+    }
+}
+
+impl ProviderState {
+    /// Circuit breaker state for the admin account status pages.
+    pub(crate) fn circuit_breaker_views(
+        &self,
+        provider_type: ProviderType,
+    ) -> Vec<CircuitBreakerView> {
+        self.circuit_breakers.snapshot(provider_type)
+    }
+
+    /// Feed an admin Codex account probe into the account's circuits.
+    pub(crate) fn record_codex_account_probe(
+        &self,
+        route: &ProviderCodexRoute,
+        ok: bool,
+        message: &str,
+    ) {
+        self.circuit_breakers
+            .record_probe(&CircuitTarget::codex(route), ok, message);
+    }
+
+    /// Feed an admin Kiro model probe into the circuits of the probed route.
+    pub(crate) fn record_kiro_account_probe(
+        &self,
+        route: &ProviderKiroRoute,
+        ok: bool,
+        message: &str,
+    ) {
+        self.circuit_breakers
+            .record_probe(&CircuitTarget::kiro(route), ok, message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config() -> CircuitBreakerConfig {
+        CircuitBreakerConfig::from_raw(
+            None,
+            Some("60"),
+            Some("4"),
+            Some("50"),
+            Some("30"),
+            Some("120"),
+            Some("1"),
+        )
+    }
+
+    fn target(proxy_url: Option<&str>) -> CircuitTarget {
+        CircuitTarget {
+            provider_type: ProviderType::Codex,
+            account_target: "codex-a".to_string(),
+            account_name: "codex-a".to_string(),
+            proxy_url: proxy_url.map(str::to_string),
+        }
+    }
+
+    /// Pretend the open interval already elapsed.
+    fn expire_open_interval(breakers: &CircuitBreakers) {
+        let mut circuits = breakers.circuits.lock().expect("circuits lock");
+        for circuit in circuits.values_mut() {
+            if let CircuitState::Open {
+                until, ..
+            } = &mut circuit.state
+            {
+                *until = Instant::now() - Duration::from_millis(1);
+            }
+        }
+    }
+
+    #[test]
+    fn config_from_raw_applies_defaults_and_bounds() {
+        let config = CircuitBreakerConfig::default();
+        assert!(config.enabled);
+        assert_eq!(config.min_requests, DEFAULT_CIRCUIT_BREAKER_MIN_REQUESTS);
+        assert_eq!(config.open_duration, Duration::from_secs(30));
+
+        let config = CircuitBreakerConfig::from_raw(
+            Some("off"),
+            None,
+            Some("0"),
+            Some("250"),
+            Some("900"),
+            Some("60"),
+            None,
+        );
+        assert!(!config.enabled);
+        assert_eq!(config.min_requests, DEFAULT_CIRCUIT_BREAKER_MIN_REQUESTS);
+        assert_eq!(config.failure_rate_percent, 100);
+        assert_eq!(config.max_open_duration, Duration::from_secs(900));
+    }
+
+    #[test]
+    fn open_duration_doubles_up_to_the_cap() {
+        let config = test_config();
+        assert_eq!(config.open_duration_for(1), Duration::from_secs(30));
+        assert_eq!(config.open_duration_for(2), Duration::from_secs(60));
+        assert_eq!(config.open_duration_for(3), Duration::from_secs(120));
+        assert_eq!(config.open_duration_for(9), Duration::from_secs(120));
+    }
+
+    #[test]
+    fn circuit_opens_only_after_min_requests_at_the_failure_rate() {
+        let breakers = CircuitBreakers::new(test_config());
+        let target = target(None);
+        breakers.record_success(&target);
+        breakers.record_failure(&target, false, "upstream status 502");
+        breakers.record_failure(&target, false, "upstream status 502");
+        assert_eq!(breakers.admission(&target), CircuitAdmission::Allow);
+
+        breakers.record_success(&target);
+        assert_eq!(breakers.admission(&target), CircuitAdmission::Allow);
+        breakers.record_failure(&target, false, "upstream status 503");
+        assert!(matches!(breakers.admission(&target), CircuitAdmission::Reject { .. }));
+
+        let views = breakers.snapshot(ProviderType::Codex);
+        assert_eq!(views.len(), 1);
+        assert_eq!(views[0].state, CircuitStatus::Open);
+        assert_eq!(views[0].window_failures, 3);
+        assert_eq!(views[0].last_failure_reason.as_deref(), Some("upstream status 503"));
+        assert!(breakers.snapshot(ProviderType::Kiro).is_empty());
+    }
+
+    #[test]
+    fn half_open_limits_trials_and_closes_on_success() {
+        let breakers = CircuitBreakers::new(test_config());
+        let target = target(None);
+        for _ in 0..4 {
+            breakers.record_failure(&target, false, "transport error");
+        }
+        expire_open_interval(&breakers);
+        assert_eq!(breakers.admission(&target), CircuitAdmission::Trial);
+        breakers.begin_trial(&target);
+        assert!(matches!(breakers.admission(&target), CircuitAdmission::Reject { .. }));
+
+        breakers.record_success(&target);
+        assert_eq!(breakers.admission(&target), CircuitAdmission::Allow);
+        let views = breakers.snapshot(ProviderType::Codex);
+        assert!(views.is_empty(), "closed circuit with a cleared window is dropped");
+    }
+
+    #[test]
+    fn half_open_trial_failure_reopens_with_a_longer_interval() {
+        let breakers = CircuitBreakers::new(test_config());
+        let target = target(None);
+        for _ in 0..4 {
+            breakers.record_failure(&target, false, "transport error");
+        }
+        expire_open_interval(&breakers);
+        breakers.begin_trial(&target);
+        breakers.record_failure(&target, false, "transport error");
+
+        let views = breakers.snapshot(ProviderType::Codex);
+        assert_eq!(views[0].state, CircuitStatus::Open);
+        assert_eq!(views[0].consecutive_opens, 2);
+        assert!(views[0].open_remaining_ms.expect("open interval") > 30_000);
+    }
+
+    #[test]
+    fn proxy_circuit_only_counts_proxy_failures() {
+        let breakers = CircuitBreakers::new(test_config());
+        let proxied = target(Some("http://proxy-a:8080"));
+        for _ in 0..4 {
+            breakers.record_failure(&proxied, false, "upstream status 500");
+        }
+        assert!(breakers
+            .snapshot(ProviderType::Codex)
+            .iter()
+            .all(|view| view.scope == CircuitScope::Account));
+
+        let other_account = CircuitTarget {
+            account_target: "codex-b".to_string(),
+            account_name: "codex-b".to_string(),
+            ..proxied.clone()
+        };
+        for _ in 0..4 {
+            breakers.record_proxy_failure(&other_account, "proxy connect failed");
+        }
+        assert!(matches!(breakers.admission(&other_account), CircuitAdmission::Reject { .. }));
+        let views = breakers.snapshot(ProviderType::Codex);
+        let proxy = views
+            .iter()
+            .find(|view| view.scope == CircuitScope::Proxy)
+            .expect("proxy circuit is tracked");
+        assert_eq!(proxy.state, CircuitStatus::Open);
+        assert_eq!(proxy.account_names, vec!["codex-b".to_string()]);
+    }
+
+    #[test]
+    fn successful_probe_closes_an_open_circuit_early() {
+        let breakers = CircuitBreakers::new(test_config());
+        let target = target(None);
+        for _ in 0..4 {
+            breakers.record_failure(&target, false, "upstream status 502");
+        }
+        breakers.record_probe(&target, true, "Kiro model probe succeeded");
+        assert_eq!(breakers.admission(&target), CircuitAdmission::Allow);
+
+        breakers.record_probe(&target, false, "probe failed");
+        assert_eq!(breakers.admission(&target), CircuitAdmission::Allow);
+    }
+}
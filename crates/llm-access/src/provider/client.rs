@@ -6,11 +6,12 @@ use llm_access_core::store::ProviderProxyConfig;
 
 use super::{
     kiro_media, ProviderClientCacheKey, ANTHROPIC_UPSTREAM_CLIENT_CACHE, CCTEST_PROXY_CLIENT,
-    DEFAULT_ANTHROPIC_UPSTREAM_CLIENT, DEFAULT_PROVIDER_CLIENT,
+    DEFAULT_ANTHROPIC_UPSTREAM_CLIENT, DEFAULT_OPENAI_UPSTREAM_CLIENT, DEFAULT_PROVIDER_CLIENT,
     DEFAULT_PROVIDER_CLIENT_CACHE_CAPACITY, DEFAULT_PROVIDER_CLIENT_POOL_IDLE_TIMEOUT_SECONDS,
     DEFAULT_PROVIDER_CLIENT_POOL_MAX_IDLE_PER_HOST, MAX_PROVIDER_CLIENT_CACHE_CAPACITY,
     MAX_PROVIDER_CLIENT_POOL_IDLE_TIMEOUT_SECONDS, MAX_PROVIDER_CLIENT_POOL_MAX_IDLE_PER_HOST,
-    MIN_PROVIDER_CLIENT_POOL_IDLE_TIMEOUT_SECONDS, PROVIDER_CLIENT_CACHE,
+    MIN_PROVIDER_CLIENT_POOL_IDLE_TIMEOUT_SECONDS, OPENAI_UPSTREAM_CLIENT_CACHE,
+    PROVIDER_CLIENT_CACHE,
 };
 
 pub fn build_provider_client(
@@ -29,6 +30,16 @@ pub fn build_anthropic_upstream_client(
     Ok(apply_provider_proxy(builder, proxy)?.build()?)
 }
 
+/// OpenAI-compatible channels usually live on a private network, so this
+/// client keeps the system resolver; redirects stay disabled so a channel
+/// cannot bounce requests onto another host.
+pub fn build_openai_upstream_client(
+    proxy: Option<&ProviderProxyConfig>,
+) -> anyhow::Result<reqwest::Client> {
+    let builder = provider_client_builder().redirect(reqwest::redirect::Policy::none());
+    Ok(apply_provider_proxy(builder, proxy)?.build()?)
+}
+
 fn provider_client_builder() -> reqwest::ClientBuilder {
     reqwest::Client::builder()
         .pool_idle_timeout(provider_client_pool_idle_timeout())
@@ -95,6 +106,29 @@ pub fn anthropic_upstream_client(
     Ok(client)
 }
 
+pub fn openai_upstream_client(
+    proxy: Option<&ProviderProxyConfig>,
+) -> anyhow::Result<reqwest::Client> {
+    let Some(proxy_config) = proxy else {
+        return Ok(DEFAULT_OPENAI_UPSTREAM_CLIENT.clone());
+    };
+    let cache_key = provider_client_cache_key(proxy_config);
+    {
+        let mut cache = OPENAI_UPSTREAM_CLIENT_CACHE
+            .lock()
+            .expect("openai upstream client cache lock");
+        if let Some(client) = cache.get(&cache_key).cloned() {
+            return Ok(client);
+        }
+    }
+    let client = build_openai_upstream_client(Some(proxy_config))?;
+    OPENAI_UPSTREAM_CLIENT_CACHE
+        .lock()
+        .expect("openai upstream client cache lock")
+        .put(cache_key, client.clone());
+    Ok(client)
+}
+
 fn provider_client_cache_key(proxy_config: &ProviderProxyConfig) -> ProviderClientCacheKey {
     ProviderClientCacheKey {
         proxy_url: proxy_config.proxy_url.clone(),
@@ -33,12 +33,16 @@ use llm_access_codex::{
     },
     types::{ChatStreamMetadata, CodexResolvedSessionSource, GatewayResponseAdapter},
 };
-use llm_access_core::store::{AuthenticatedKey, ProviderCodexRoute};
+use llm_access_core::{
+    provider::ProviderType,
+    store::{AuthenticatedKey, ProviderCodexRoute, ProviderRouteStore, PROVIDER_CODEX},
+};
 use rand::Rng;
 use serde_json::{json, Value};
 
 use super::{
     build_codex_affinity_id,
+    circuit_breaker::{CircuitBreakers, CircuitTarget},
     client::provider_client,
     codex_auth::{
         add_codex_upstream_headers, codex_upstream_base_url, compute_codex_upstream_url,
@@ -63,7 +67,14 @@ use super::{
         extract_error_message_from_json_value, randomized_same_account_retry_delay,
         summarize_error_bytes, SameAccountRetryReason,
     },
+    fair_queue::{key_queue_entry, record_queue_wait},
     limiter::{codex_key_limit_response, try_acquire_key_permit},
+    openai_upstream_dispatch::{
+        maybe_dispatch_openai_upstream_pool, OpenAiUpstreamDispatchOutcome,
+    },
+    request_transform::{
+        record_request_transforms, request_transform_unavailable, resolve_request_transform,
+    },
     route_selection::{hydrate_codex_route_for_dispatch, select_codex_route_with_account_permit},
     usage_meta::{
         capture_client_request_body_json, capture_codex_dispatch_request_json,
@@ -86,6 +97,11 @@ pub async fn dispatch_codex_proxy(
     request: Request<Body>,
     deps: ProviderDispatchDeps,
 ) -> Response {
+    let request =
+        match maybe_dispatch_openai_upstream_pool(key.clone(), request, deps.clone()).await {
+            OpenAiUpstreamDispatchOutcome::Handled(response) => return response,
+            OpenAiUpstreamDispatchOutcome::Fallback(request) => request,
+        };
     let ProviderDispatchDeps {
         route_store,
         control_store,
@@ -93,6 +109,8 @@ pub async fn dispatch_codex_proxy(
         admin_config_store,
         request_limiter,
         codex_account_cooldowns,
+        circuit_breakers,
+        fair_queue,
         codex_session_affinity,
         codex_session_recovery,
         codex_session_rejection,
@@ -157,6 +175,19 @@ pub async fn dispatch_codex_proxy(
         )
         .await;
     }
+    // Fail closed: a key with redactions configured must never reach the
+    // upstream untransformed because its policy could not be loaded.
+    let request_transform = match resolve_request_transform(route_store.as_ref(), &key).await {
+        Ok(transform) => transform,
+        Err(err) => {
+            let message = request_transform_unavailable(&key, &err);
+            return codex_surface_error_response(
+                &gateway_path,
+                StatusCode::SERVICE_UNAVAILABLE,
+                &message,
+            );
+        },
+    };
 
     let body_read_started = Instant::now();
     let body = match to_bytes(request.into_body(), MAX_PROVIDER_PROXY_BODY_BYTES).await {
@@ -194,6 +225,7 @@ pub async fn dispatch_codex_proxy(
         &request_headers,
         body.clone(),
         MAX_PROVIDER_PROXY_BODY_BYTES,
+        request_transform.as_deref(),
     ) {
         Ok(prepared) => prepared,
         Err(err) => {
@@ -279,9 +311,14 @@ pub async fn dispatch_codex_proxy(
             return codex_surface_error_response(&gateway_path, StatusCode::BAD_REQUEST, &message);
         }
     }
-    let preferred_account_name = codex_affinity_id.as_ref().and_then(|affinity_id| {
-        codex_session_affinity.lookup(affinity_id, &runtime_config.affinity)
-    });
+    let preferred_account_name = match codex_affinity_id.as_ref() {
+        Some(affinity_id) => {
+            codex_session_affinity
+                .resolve(affinity_id, &runtime_config.affinity)
+                .await
+        },
+        None => None,
+    };
     let session_counts =
         (routes.len() > 1 && codex_affinity_id.is_some() && preferred_account_name.is_none())
             .then(|| codex_session_affinity.account_session_counts(&runtime_config.affinity));
@@ -300,6 +337,7 @@ pub async fn dispatch_codex_proxy(
         })
         .to_string(),
     );
+    record_request_transforms(&mut usage_meta, &prepared.applied_transforms);
     let method = match reqwest::Method::from_bytes(prepared.method.as_str().as_bytes()) {
         Ok(method) => method,
         Err(_) => return (StatusCode::METHOD_NOT_ALLOWED, "unsupported method").into_response(),
@@ -313,6 +351,14 @@ pub async fn dispatch_codex_proxy(
         Ok(permit) => permit,
         Err(rejection) => return codex_key_limit_response(&rejection),
     };
+    let mut queue_entry = key_queue_entry(
+        &fair_queue,
+        route_store.as_ref(),
+        &key,
+        ProviderType::Codex,
+        routes.iter().map(|route| route.account_name.as_str()),
+    )
+    .await;
     let account_attempt_limit = runtime_config.account_attempt_limit;
     let mut key_permit = Some(key_permit);
     let mut failed_accounts = HashSet::new();
@@ -322,6 +368,8 @@ pub async fn dispatch_codex_proxy(
         let (route, account_permit) = match select_codex_route_with_account_permit(
             &request_limiter,
             &codex_account_cooldowns,
+            &circuit_breakers,
+            &mut queue_entry,
             &routes,
             &failed_accounts,
             preferred_account_name.as_deref(),
@@ -333,6 +381,7 @@ pub async fn dispatch_codex_proxy(
             Err(response) => return response,
         };
         usage_meta.add_routing_wait(clamp_duration_ms(route_started.elapsed()));
+        record_queue_wait(&mut usage_meta, &queue_entry);
         attempt_count = attempt_count.saturating_add(1);
         let selected_account_name = route.account_name.clone();
         let route = match hydrate_codex_route_for_dispatch(route, route_store.as_ref()).await {
@@ -340,7 +389,9 @@ pub async fn dispatch_codex_proxy(
             Err(_) => {
                 mark_codex_transient_request_failure_cooldown(
                     &codex_account_cooldowns,
-                    &selected_account_name,
+                    &circuit_breakers,
+                    &CircuitTarget::codex_account(&selected_account_name),
+                    "route hydration failed",
                 );
                 usage_meta.mark_failover();
                 failed_accounts.insert(selected_account_name);
@@ -369,7 +420,9 @@ pub async fn dispatch_codex_proxy(
             Err(_) => {
                 mark_codex_transient_request_failure_cooldown(
                     &codex_account_cooldowns,
-                    &route.account_name,
+                    &circuit_breakers,
+                    &CircuitTarget::codex(&route),
+                    "auth context unavailable",
                 );
                 usage_meta.mark_failover();
                 failed_accounts.insert(route.account_name.clone());
@@ -406,7 +459,9 @@ pub async fn dispatch_codex_proxy(
             Err(_) => {
                 mark_codex_transient_request_failure_cooldown(
                     &codex_account_cooldowns,
-                    &route.account_name,
+                    &circuit_breakers,
+                    &CircuitTarget::codex(&route),
+                    "upstream client build failed",
                 );
                 usage_meta.mark_failover();
                 failed_accounts.insert(route.account_name.clone());
@@ -432,10 +487,19 @@ pub async fn dispatch_codex_proxy(
                 usage_meta.mark_upstream_headers();
                 response
             },
-            Err(_) => {
+            Err(err) => {
+                report_codex_proxy_transport_failure(
+                    route_store.as_ref(),
+                    &circuit_breakers,
+                    &route,
+                    &err,
+                )
+                .await;
                 mark_codex_transient_request_failure_cooldown(
                     &codex_account_cooldowns,
-                    &route.account_name,
+                    &circuit_breakers,
+                    &CircuitTarget::codex(&route),
+                    "upstream transport error",
                 );
                 usage_meta.mark_failover();
                 failed_accounts.insert(route.account_name.clone());
@@ -477,10 +541,19 @@ pub async fn dispatch_codex_proxy(
                             usage_meta.mark_upstream_headers();
                             response
                         },
-                        Err(_) => {
+                        Err(err) => {
+                            report_codex_proxy_transport_failure(
+                                route_store.as_ref(),
+                                &circuit_breakers,
+                                &route,
+                                &err,
+                            )
+                            .await;
                             mark_codex_transient_request_failure_cooldown(
                                 &codex_account_cooldowns,
-                                &route.account_name,
+                                &circuit_breakers,
+                                &CircuitTarget::codex(&route),
+                                "upstream transport error",
                             );
                             usage_meta.mark_failover();
                             failed_accounts.insert(route.account_name.clone());
@@ -498,7 +571,9 @@ pub async fn dispatch_codex_proxy(
                 Err(_) => {
                     mark_codex_transient_request_failure_cooldown(
                         &codex_account_cooldowns,
-                        &route.account_name,
+                        &circuit_breakers,
+                        &CircuitTarget::codex(&route),
+                        "auth refresh failed",
                     );
                     usage_meta.mark_failover();
                     failed_accounts.insert(route.account_name.clone());
@@ -537,7 +612,9 @@ pub async fn dispatch_codex_proxy(
             .await;
             mark_codex_transient_request_failure_cooldown(
                 &codex_account_cooldowns,
-                &route.account_name,
+                &circuit_breakers,
+                &CircuitTarget::codex(&route),
+                "upstream auth rejected",
             );
             if attempt_count < account_attempt_limit
                 && routes.iter().any(|candidate| {
@@ -582,6 +659,7 @@ pub async fn dispatch_codex_proxy(
             .await;
         }
         if response.status().is_success() {
+            let circuit_target = CircuitTarget::codex(&route);
             remember_codex_affinity(
                 codex_session_affinity.as_ref(),
                 codex_affinity_id.as_ref(),
@@ -616,8 +694,12 @@ pub async fn dispatch_codex_proxy(
                 account_attempt_limit,
                 attempt_count,
                 &codex_account_cooldowns,
+                &circuit_breakers,
             ) {
-                CodexLoopStep::Respond(response) => return response,
+                CodexLoopStep::Respond(response) => {
+                    circuit_breakers.record_success(&circuit_target);
+                    return response;
+                },
                 CodexLoopStep::Surface {
                     error,
                     ctx,
@@ -661,10 +743,19 @@ pub async fn dispatch_codex_proxy(
                         usage_meta.mark_upstream_headers();
                         response
                     },
-                    Err(_) => {
+                    Err(err) => {
+                        report_codex_proxy_transport_failure(
+                            route_store.as_ref(),
+                            &circuit_breakers,
+                            &route,
+                            &err,
+                        )
+                        .await;
                         mark_codex_transient_request_failure_cooldown(
                             &codex_account_cooldowns,
-                            &route.account_name,
+                            &circuit_breakers,
+                            &CircuitTarget::codex(&route),
+                            "upstream transport error",
                         );
                         usage_meta.mark_failover();
                         failed_accounts.insert(route.account_name.clone());
@@ -679,6 +770,7 @@ pub async fn dispatch_codex_proxy(
                     },
                 };
                 if response.status().is_success() {
+                    let circuit_target = CircuitTarget::codex(&route);
                     remember_codex_affinity(
                         codex_session_affinity.as_ref(),
                         codex_affinity_id.as_ref(),
@@ -710,8 +802,12 @@ pub async fn dispatch_codex_proxy(
                         account_attempt_limit,
                         attempt_count,
                         &codex_account_cooldowns,
+                        &circuit_breakers,
                     ) {
-                        CodexLoopStep::Respond(response) => return response,
+                        CodexLoopStep::Respond(response) => {
+                            circuit_breakers.record_success(&circuit_target);
+                            return response;
+                        },
                         CodexLoopStep::Surface {
                             error,
                             ctx,
@@ -776,10 +872,19 @@ pub async fn dispatch_codex_proxy(
                     usage_meta.mark_upstream_headers();
                     response
                 },
-                Err(_) => {
+                Err(err) => {
+                    report_codex_proxy_transport_failure(
+                        route_store.as_ref(),
+                        &circuit_breakers,
+                        &route,
+                        &err,
+                    )
+                    .await;
                     mark_codex_transient_request_failure_cooldown(
                         &codex_account_cooldowns,
-                        &route.account_name,
+                        &circuit_breakers,
+                        &CircuitTarget::codex(&route),
+                        "upstream transport error",
                     );
                     usage_meta.mark_failover();
                     failed_accounts.insert(route.account_name.clone());
@@ -794,6 +899,7 @@ pub async fn dispatch_codex_proxy(
                 },
             };
             if response.status().is_success() {
+                let circuit_target = CircuitTarget::codex(&route);
                 remember_codex_affinity(
                     codex_session_affinity.as_ref(),
                     codex_affinity_id.as_ref(),
@@ -825,8 +931,12 @@ pub async fn dispatch_codex_proxy(
                     account_attempt_limit,
                     attempt_count,
                     &codex_account_cooldowns,
+                    &circuit_breakers,
                 ) {
-                    CodexLoopStep::Respond(response) => return response,
+                    CodexLoopStep::Respond(response) => {
+                        circuit_breakers.record_success(&circuit_target);
+                        return response;
+                    },
                     CodexLoopStep::Surface {
                         error,
                         ctx,
@@ -871,6 +981,7 @@ pub async fn dispatch_codex_proxy(
             CodexErrorDisposition::ReturnToClient {
                 strict_session_block,
             } => {
+                circuit_breakers.record_inconclusive(&CircuitTarget::codex(&route));
                 if strict_session_block {
                     maybe_remember_codex_session_rejection(
                         codex_session_rejection.as_ref(),
@@ -886,6 +997,11 @@ pub async fn dispatch_codex_proxy(
                 cooldown,
             } => {
                 codex_account_cooldowns.mark_account_cooldown(&route.account_name, cooldown);
+                circuit_breakers.record_failure(
+                    &CircuitTarget::codex(&route),
+                    false,
+                    &codex_circuit_failure_reason(&classified_error),
+                );
             },
             CodexErrorDisposition::Failover
             | CodexErrorDisposition::RetrySameAccount {
@@ -893,7 +1009,9 @@ pub async fn dispatch_codex_proxy(
             } => {
                 mark_codex_transient_request_failure_cooldown(
                     &codex_account_cooldowns,
-                    &route.account_name,
+                    &circuit_breakers,
+                    &CircuitTarget::codex(&route),
+                    &codex_circuit_failure_reason(&classified_error),
                 );
             },
         }
@@ -1335,6 +1453,7 @@ fn classify_codex_upstream_outcome(
     account_attempt_limit: usize,
     attempt_count: usize,
     codex_account_cooldowns: &Arc<CodexAccountCooldowns>,
+    circuit_breakers: &CircuitBreakers,
 ) -> CodexLoopStep {
     let (error, ctx) = match outcome {
         CodexUpstreamOutcome::Responded(response) => return CodexLoopStep::Respond(response),
@@ -1345,10 +1464,12 @@ fn classify_codex_upstream_outcome(
     };
     let disposition = codex_error_disposition(&error);
     let account_name = ctx.route.account_name.clone();
+    let circuit_target = CircuitTarget::codex(&ctx.route);
     match disposition {
         CodexErrorDisposition::ReturnToClient {
             strict_session_block,
         } => {
+            circuit_breakers.record_inconclusive(&circuit_target);
             if strict_session_block {
                 maybe_remember_codex_session_rejection(
                     ctx.codex_session_rejection.as_ref(),
@@ -1364,12 +1485,22 @@ fn classify_codex_upstream_outcome(
             cooldown,
         } => {
             codex_account_cooldowns.mark_account_cooldown(&account_name, cooldown);
+            circuit_breakers.record_failure(
+                &circuit_target,
+                false,
+                &codex_circuit_failure_reason(&error),
+            );
         },
         CodexErrorDisposition::Failover
         | CodexErrorDisposition::RetrySameAccount {
             ..
         } => {
-            mark_codex_transient_request_failure_cooldown(codex_account_cooldowns, &account_name);
+            mark_codex_transient_request_failure_cooldown(
+                codex_account_cooldowns,
+                circuit_breakers,
+                &circuit_target,
+                &codex_circuit_failure_reason(&error),
+            );
         },
     }
     if !matches!(disposition, CodexErrorDisposition::ReturnToClient { .. })
@@ -1851,11 +1982,42 @@ fn randomized_codex_transient_account_failure_cooldown<R: Rng + ?Sized>(rng: &mu
 }
 fn mark_codex_transient_request_failure_cooldown(
     codex_account_cooldowns: &Arc<CodexAccountCooldowns>,
-    account_name: &str,
+    circuit_breakers: &CircuitBreakers,
+    circuit_target: &CircuitTarget,
+    reason: &str,
 ) {
     let cooldown = randomized_codex_transient_account_failure_cooldown(&mut rand::thread_rng());
-    codex_account_cooldowns.mark_account_cooldown(account_name, cooldown);
+    codex_account_cooldowns.mark_account_cooldown(circuit_target.account_name(), cooldown);
+    circuit_breakers.record_failure(circuit_target, false, reason);
 }
+
+fn codex_circuit_failure_reason(error: &CodexClassifiedUpstreamError) -> String {
+    format!("upstream status {}: {}", error.status.as_u16(), error.message)
+}
+
+/// Count a proxy connect failure so pool selection can steer the account away
+/// from a proxy that keeps dropping connections.
+async fn report_codex_proxy_transport_failure(
+    route_store: &dyn ProviderRouteStore,
+    circuit_breakers: &CircuitBreakers,
+    route: &ProviderCodexRoute,
+    err: &reqwest::Error,
+) {
+    if route.proxy.is_none() || !err.is_connect() {
+        return;
+    }
+    circuit_breakers.record_proxy_failure(&CircuitTarget::codex(route), "proxy connect failed");
+    if let Err(report_err) = route_store
+        .report_proxy_transport_failure(PROVIDER_CODEX, &route.account_name, &err.to_string())
+        .await
+    {
+        tracing::debug!(
+            account_name = %route.account_name,
+            "failed to report codex proxy transport failure: {report_err:#}"
+        );
+    }
+}
+
 pub fn codex_status_from_error_json_value(value: &Value) -> Option<StatusCode> {
     for pointer in ["/error/status", "/status", "/response/error/status"] {
         if let Some(status) = value.pointer(pointer).and_then(Value::as_u64) {
@@ -1,9 +1,13 @@
-//! In-process Codex account affinity keyed by resolved session ids.
+//! Codex account affinity keyed by resolved session ids.
+//!
+//! Entries live in an in-process LRU. When a shared store is attached, they
+//! are mirrored into Valkey so every cluster node routes a session to the
+//! same account; the LRU remains the fallback when Valkey is unreachable.
 
 use std::{
     collections::HashMap,
     num::NonZeroUsize,
-    sync::Mutex,
+    sync::{Arc, Mutex, OnceLock},
     time::{Duration, Instant},
 };
 
@@ -16,6 +20,8 @@ use llm_access_core::store::{
 };
 use lru::LruCache;
 
+use super::shared_session_affinity::SharedSessionAffinityStore;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) struct CodexAffinityRuntimeConfig {
     pub session_enabled: bool,
@@ -79,6 +85,15 @@ pub(crate) enum CodexAffinitySource {
     Derived,
 }
 
+impl CodexAffinitySource {
+    fn shared_namespace(self) -> &'static str {
+        match self {
+            Self::Explicit => "codex:explicit",
+            Self::Derived => "codex:derived",
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) struct CodexAffinityId {
     pub key: String,
@@ -99,6 +114,7 @@ struct CodexSessionAffinityInner {
 
 pub(crate) struct CodexSessionAffinity {
     inner: Mutex<CodexSessionAffinityInner>,
+    shared: OnceLock<Arc<SharedSessionAffinityStore>>,
 }
 
 impl Default for CodexSessionAffinity {
@@ -115,9 +131,37 @@ impl CodexSessionAffinity {
                 capacity,
                 entries: LruCache::new(NonZeroUsize::new(capacity).expect("capacity is non-zero")),
             }),
+            shared: OnceLock::new(),
         }
     }
 
+    /// Mirror entries into a cluster-shared store. Only the first attach
+    /// takes effect.
+    pub(crate) fn attach_shared(&self, store: Arc<SharedSessionAffinityStore>) {
+        let _ = self.shared.set(store);
+    }
+
+    /// Resolve the account for `affinity_id`, preferring the shared store so
+    /// that sessions stick across cluster nodes. Falls back to the local LRU
+    /// on a shared miss or when the shared store is unavailable.
+    pub(crate) async fn resolve(
+        &self,
+        affinity_id: &CodexAffinityId,
+        config: &CodexAffinityRuntimeConfig,
+    ) -> Option<String> {
+        if !config.can_store_source(affinity_id.source) {
+            return None;
+        }
+        if let Some(shared) = self.shared.get() {
+            let namespace = affinity_id.source.shared_namespace();
+            if let Some(account_name) = shared.lookup(namespace, &affinity_id.key).await {
+                self.remember_local(affinity_id, &account_name, config);
+                return Some(account_name);
+            }
+        }
+        self.lookup(affinity_id, config)
+    }
+
     pub(crate) fn lookup(
         &self,
         affinity_id: &CodexAffinityId,
@@ -146,6 +190,23 @@ impl CodexSessionAffinity {
         if !config.can_store_source(affinity_id.source) {
             return;
         }
+        self.remember_local(affinity_id, account_name, config);
+        if let Some(shared) = self.shared.get() {
+            shared.remember(
+                affinity_id.source.shared_namespace(),
+                &affinity_id.key,
+                account_name,
+                config.ttl_for_source(affinity_id.source),
+            );
+        }
+    }
+
+    fn remember_local(
+        &self,
+        affinity_id: &CodexAffinityId,
+        account_name: &str,
+        config: &CodexAffinityRuntimeConfig,
+    ) {
         let mut inner = self.reconfigure(config);
         inner
             .entries
@@ -0,0 +1,89 @@
+//! Per-key cache of compiled moderation and request-transform policies.
+//!
+//! Compiling builds every rule `Regex`, which is too costly to repeat for each
+//! request. The store still resolves the policy per request, and an entry is
+//! only reused while that policy equals the one it was compiled from, so an
+//! admin edit takes effect on the next request.
+
+use std::{
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+};
+
+use lru::LruCache;
+
+const COMPILED_POLICY_CACHE_MAX_ENTRIES: usize = 4_096;
+
+pub(super) struct CompiledPolicyCache<P, C> {
+    entries: Mutex<LruCache<String, (P, Arc<C>)>>,
+}
+
+impl<P: Clone + PartialEq, C> CompiledPolicyCache<P, C> {
+    pub(super) fn new() -> Self {
+        let capacity =
+            NonZeroUsize::new(COMPILED_POLICY_CACHE_MAX_ENTRIES).expect("capacity is non-zero");
+        Self {
+            entries: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    /// Compiled `policy` of `key_id`, compiling only when the key's policy
+    /// changed since it was last compiled.
+    pub(super) fn get_or_compile(
+        &self,
+        key_id: &str,
+        policy: &P,
+        compile: impl FnOnce(&P) -> anyhow::Result<C>,
+    ) -> anyhow::Result<Arc<C>> {
+        if let Ok(mut entries) = self.entries.lock() {
+            if let Some((cached, compiled)) = entries.get(key_id) {
+                if cached == policy {
+                    return Ok(Arc::clone(compiled));
+                }
+            }
+        }
+        let compiled = Arc::new(compile(policy)?);
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.put(key_id.to_string(), (policy.clone(), Arc::clone(&compiled)));
+        }
+        Ok(compiled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, sync::Arc};
+
+    use super::CompiledPolicyCache;
+
+    #[test]
+    fn compiled_policies_are_reused_until_the_policy_changes() {
+        let cache = CompiledPolicyCache::<String, String>::new();
+        let compiles = Cell::new(0);
+        let compile = |policy: &String| {
+            compiles.set(compiles.get() + 1);
+            Ok(policy.to_uppercase())
+        };
+
+        let first = cache
+            .get_or_compile("key-a", &"deny".to_string(), compile)
+            .expect("compile");
+        let again = cache
+            .get_or_compile("key-a", &"deny".to_string(), compile)
+            .expect("cached");
+        assert!(Arc::ptr_eq(&first, &again));
+        assert_eq!(compiles.get(), 1);
+
+        let changed = cache
+            .get_or_compile("key-a", &"allow".to_string(), compile)
+            .expect("recompile");
+        assert_eq!(changed.as_str(), "ALLOW");
+        cache
+            .get_or_compile("key-b", &"allow".to_string(), compile)
+            .expect("other key");
+        assert_eq!(compiles.get(), 3);
+        assert!(cache
+            .get_or_compile("key-c", &"bad".to_string(), |_| anyhow::bail!("invalid"))
+            .is_err());
+    }
+}
@@ -14,7 +14,11 @@ use llm_access_core::{
 };
 
 use super::{
-    codex_auth::normalized_codex_gateway_path, kiro_error::kiro_json_error, ProviderState,
+    batch::{batch_route, handle_batch_request},
+    codex_auth::normalized_codex_gateway_path,
+    kiro_error::kiro_json_error,
+    moderation::dispatch_with_moderation,
+    ProviderState,
 };
 
 /// Axum entrypoint for provider requests.
@@ -48,15 +52,16 @@ pub async fn provider_entry(state: ProviderState, request: Request<Body>) -> Res
     if !key_matches_route(&key, request.uri().path()) {
         return (StatusCode::FORBIDDEN, "llm key does not match provider route").into_response();
     }
+    // Batch reads stay available after quota runs out; creation checks it.
+    if let Some((dialect, action)) = batch_route(request.method(), request.uri().path()) {
+        return handle_batch_request(&state, key, dialect, action, request).await;
+    }
     if is_quota_exhausted(&key) {
         return quota_exhausted_response(&key);
     }
 
     let _activity_guard = state.request_activity.start(&key.key_id);
-    state
-        .dispatcher
-        .dispatch(key, request, state.dispatch_deps())
-        .await
+    dispatch_with_moderation(&state, key, request).await
 }
 fn presented_secret<'a>(headers: &'a HeaderMap, path: &str) -> Option<&'a str> {
     if accepts_anthropic_api_key_header(path) {
@@ -86,7 +91,7 @@ fn x_api_key_secret(headers: &HeaderMap) -> Option<&str> {
         Some(value)
     }
 }
-fn bearer_secret(headers: &HeaderMap) -> Option<&str> {
+pub(crate) fn bearer_secret(headers: &HeaderMap) -> Option<&str> {
     let value = headers.get(header::AUTHORIZATION)?.to_str().ok()?;
     let (scheme, token) = value.split_once(' ')?;
     if !scheme.eq_ignore_ascii_case("bearer") {
@@ -0,0 +1,835 @@
+//! Weighted fair queuing across keys for saturated account pools.
+//!
+//! ```text
+//! select route -- nobody queued for the pool? --yes--> try accounts --ok--> dispatch
+//!                         | no                              | every account throttled
+//!                         v                                 v
+//!              join the pool queue (429 when full) <--------+
+//!                         |
+//!                         v
+//!   wait until every waiter ahead tried this round --> try accounts --ok--> leave, dispatch
+//!                         ^                                 | throttled
+//!                         |                                 v
+//!                         +---- new round <---- pass the turn, wait for capacity
+//! ```
+//!
+//! Without a queue, whichever waiter polls first after a permit frees wins,
+//! so a key firing many parallel requests crowds everyone else out of a busy
+//! pool. A pool (the account set a request may route to) instead orders its
+//! waiters by priority tier and then by a virtual finish tag: every queued
+//! request of a key with weight `w` advances that key's tag by `1/w`, so
+//! within a tier keys drain in proportion to their weights and a lower tier
+//! only gets capacity nobody above it is waiting for. A waiter polls the
+//! accounts only once everyone ahead of it has had a try in the current
+//! round, which hands freed capacity to the head of the queue. State is
+//! local to the process.
+
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeMap, HashMap},
+    hash::{Hash, Hasher},
+    pin::pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use llm_access_core::{
+    provider::ProviderType,
+    store::{
+        AuthenticatedKey, KeyQueuePolicy, ProviderRouteStore, QueuePriority, DEFAULT_QUEUE_WEIGHT,
+    },
+};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tokio::sync::Notify;
+
+use super::{util::clamp_duration_ms, ProviderState, ProviderUsageMetadata};
+
+const FAIR_QUEUE_ENABLED_ENV: &str = "LLM_ACCESS_FAIR_QUEUE_ENABLED";
+const FAIR_QUEUE_MAX_DEPTH_ENV: &str = "LLM_ACCESS_FAIR_QUEUE_MAX_DEPTH";
+const FAIR_QUEUE_MAX_DEPTH_PER_KEY_ENV: &str = "LLM_ACCESS_FAIR_QUEUE_MAX_DEPTH_PER_KEY";
+const DEFAULT_FAIR_QUEUE_MAX_DEPTH: usize = 512;
+const DEFAULT_FAIR_QUEUE_MAX_DEPTH_PER_KEY: usize = 64;
+/// Virtual service time of one request at weight 1.
+const VIRTUAL_REQUEST_COST: u64 = 1_000_000;
+/// A waiter stuck behind one that never comes back for its try (a lost
+/// wakeup, a stalled task) takes its turn out of order after this long.
+const FAIR_QUEUE_STALL_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Queue limits shared by every pool in the process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct FairQueueConfig {
+    enabled: bool,
+    max_depth: usize,
+    max_depth_per_key: usize,
+}
+
+impl FairQueueConfig {
+    pub(crate) fn from_env() -> Self {
+        let var = |name: &str| std::env::var(name).ok();
+        Self::from_raw(
+            var(FAIR_QUEUE_ENABLED_ENV).as_deref(),
+            var(FAIR_QUEUE_MAX_DEPTH_ENV).as_deref(),
+            var(FAIR_QUEUE_MAX_DEPTH_PER_KEY_ENV).as_deref(),
+        )
+    }
+
+    fn from_raw(
+        enabled: Option<&str>,
+        max_depth: Option<&str>,
+        max_depth_per_key: Option<&str>,
+    ) -> Self {
+        let parse = |raw: Option<&str>, default: usize| {
+            raw.and_then(|value| value.trim().parse::<usize>().ok())
+                .filter(|value| *value > 0)
+                .unwrap_or(default)
+        };
+        Self {
+            enabled: enabled.is_none_or(|raw| {
+                !matches!(raw.trim().to_ascii_lowercase().as_str(), "0" | "false" | "no" | "off")
+            }),
+            max_depth: parse(max_depth, DEFAULT_FAIR_QUEUE_MAX_DEPTH),
+            max_depth_per_key: parse(max_depth_per_key, DEFAULT_FAIR_QUEUE_MAX_DEPTH_PER_KEY),
+        }
+    }
+}
+
+impl Default for FairQueueConfig {
+    fn default() -> Self {
+        Self::from_raw(None, None, None)
+    }
+}
+
+/// Why a request was refused a place in the queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FairQueueRejection {
+    /// The pool already holds the process-wide maximum of waiters.
+    PoolFull { limit: usize },
+    /// The key already has its maximum number of requests waiting.
+    KeyFull { limit: usize },
+}
+
+impl FairQueueRejection {
+    pub(crate) fn message(&self) -> String {
+        match self {
+            Self::PoolFull {
+                limit,
+            } => format!("account pool request queue is full: max_queued_requests={limit}"),
+            Self::KeyFull {
+                limit,
+            } => format!("key request queue is full: max_queued_requests={limit}"),
+        }
+    }
+}
+
+struct Waiter {
+    id: u64,
+    key_id: String,
+    priority: QueuePriority,
+    start_tag: u64,
+    finish_tag: u64,
+    /// Round in which this waiter last polled the accounts and found them
+    /// all throttled.
+    tried_round: Option<u64>,
+    enqueued_at: Instant,
+}
+
+struct PoolQueue {
+    provider_type: ProviderType,
+    account_count: usize,
+    /// Sorted by `(priority, finish_tag, id)`.
+    waiters: Vec<Waiter>,
+    /// Per-tier virtual clock: the start tag of the last request served.
+    virtual_time: [u64; 3],
+    key_finish: HashMap<String, u64>,
+    round: u64,
+    notify: Arc<Notify>,
+}
+
+impl PoolQueue {
+    fn new(provider_type: ProviderType, account_count: usize) -> Self {
+        Self {
+            provider_type,
+            account_count,
+            waiters: Vec::new(),
+            virtual_time: [0; 3],
+            key_finish: HashMap::new(),
+            round: 0,
+            notify: Arc::new(Notify::new()),
+        }
+    }
+
+    fn is_turn(&self, id: u64) -> bool {
+        for waiter in &self.waiters {
+            if waiter.id == id {
+                return true;
+            }
+            if waiter.tried_round != Some(self.round) {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn waiter_mut(&mut self, id: u64) -> Option<&mut Waiter> {
+        self.waiters.iter_mut().find(|waiter| waiter.id == id)
+    }
+}
+
+#[derive(Default)]
+struct KeyQueueStats {
+    provider_type: Option<ProviderType>,
+    priority: QueuePriority,
+    weight: u32,
+    served: u64,
+    rejected: u64,
+    total_wait_ms: i64,
+    max_wait_ms: i64,
+}
+
+#[derive(Default)]
+struct FairQueueInner {
+    pools: HashMap<String, PoolQueue>,
+    keys: HashMap<String, KeyQueueStats>,
+}
+
+/// Live queue state of one account pool for the admin API.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct FairQueuePoolView {
+    provider_type: &'static str,
+    pool: String,
+    account_count: usize,
+    waiting: usize,
+    waiting_by_priority: BTreeMap<&'static str, usize>,
+    oldest_wait_ms: i64,
+}
+
+/// Queue counters of one key for the admin API. Counters cover requests that
+/// had to queue; requests that found capacity immediately are not counted.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct FairQueueKeyView {
+    provider_type: &'static str,
+    key_id: String,
+    priority: &'static str,
+    weight: u32,
+    waiting: usize,
+    served: u64,
+    rejected: u64,
+    avg_wait_ms: i64,
+    max_wait_ms: i64,
+}
+
+/// Snapshot of every queue in the process.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct FairQueueView {
+    enabled: bool,
+    max_depth: usize,
+    max_depth_per_key: usize,
+    pools: Vec<FairQueuePoolView>,
+    keys: Vec<FairQueueKeyView>,
+}
+
+/// Process-wide fair queue registry keyed by provider and account pool.
+pub(crate) struct FairQueue {
+    config: FairQueueConfig,
+    next_waiter_id: AtomicU64,
+    inner: Mutex<FairQueueInner>,
+}
+
+impl Default for FairQueue {
+    fn default() -> Self {
+        Self::new(FairQueueConfig::from_env())
+    }
+}
+
+impl FairQueue {
+    pub(crate) fn new(config: FairQueueConfig) -> Self {
+        Self {
+            config,
+            next_waiter_id: AtomicU64::new(0),
+            inner: Mutex::new(FairQueueInner::default()),
+        }
+    }
+
+    /// Per-request handle for routing `key_id` over `account_names`. The
+    /// handle lives across failover attempts so its wait accumulates.
+    pub(crate) fn entry<'a>(
+        self: &Arc<Self>,
+        provider_type: ProviderType,
+        key_id: &str,
+        policy: Option<&KeyQueuePolicy>,
+        account_names: impl IntoIterator<Item = &'a str>,
+    ) -> FairQueueEntry {
+        let mut entry = FairQueueEntry::detached();
+        entry.provider_type = provider_type;
+        entry.key_id = key_id.to_string();
+        if let Some(policy) = policy {
+            entry.weight = policy.effective_weight();
+            entry.priority = policy.effective_priority();
+        }
+        if !self.config.enabled {
+            return entry;
+        }
+        let mut account_names = account_names.into_iter().collect::<Vec<_>>();
+        account_names.sort_unstable();
+        account_names.dedup();
+        let mut hasher = DefaultHasher::new();
+        account_names.hash(&mut hasher);
+        entry.pool = format!("{}:{:016x}", provider_type.as_storage_str(), hasher.finish());
+        entry.account_count = account_names.len();
+        entry.max_queued = policy
+            .and_then(|policy| policy.max_queued_requests)
+            .map(|depth| depth as usize)
+            .unwrap_or(self.config.max_depth_per_key);
+        entry.queue = Some(Arc::clone(self));
+        entry
+    }
+
+    pub(crate) fn snapshot(&self) -> FairQueueView {
+        let mut view = FairQueueView {
+            enabled: self.config.enabled,
+            max_depth: self.config.max_depth,
+            max_depth_per_key: self.config.max_depth_per_key,
+            pools: Vec::new(),
+            keys: Vec::new(),
+        };
+        let Ok(inner) = self.inner.lock() else {
+            return view;
+        };
+        let mut waiting_by_key = HashMap::<String, usize>::new();
+        for (pool_id, pool) in &inner.pools {
+            let mut waiting_by_priority = BTreeMap::new();
+            for waiter in &pool.waiters {
+                *waiting_by_priority
+                    .entry(waiter.priority.as_str())
+                    .or_insert(0) += 1;
+                *waiting_by_key
+                    .entry(stats_key(pool.provider_type, &waiter.key_id))
+                    .or_insert(0) += 1;
+            }
+            view.pools.push(FairQueuePoolView {
+                provider_type: pool.provider_type.as_storage_str(),
+                pool: pool_id.clone(),
+                account_count: pool.account_count,
+                waiting: pool.waiters.len(),
+                waiting_by_priority,
+                oldest_wait_ms: pool
+                    .waiters
+                    .iter()
+                    .map(|waiter| clamp_duration_ms(waiter.enqueued_at.elapsed()))
+                    .max()
+                    .unwrap_or(0),
+            });
+        }
+        for (stats_id, stats) in &inner.keys {
+            let provider_type = stats.provider_type.unwrap_or(ProviderType::Codex);
+            view.keys.push(FairQueueKeyView {
+                provider_type: provider_type.as_storage_str(),
+                key_id: stats_id
+                    .split_once(':')
+                    .map(|(_, key_id)| key_id)
+                    .unwrap_or(stats_id)
+                    .to_string(),
+                priority: stats.priority.as_str(),
+                weight: stats.weight,
+                waiting: waiting_by_key.get(stats_id).copied().unwrap_or(0),
+                served: stats.served,
+                rejected: stats.rejected,
+                avg_wait_ms: stats
+                    .total_wait_ms
+                    .checked_div(i64::try_from(stats.served).unwrap_or(i64::MAX))
+                    .unwrap_or(0),
+                max_wait_ms: stats.max_wait_ms,
+            });
+        }
+        view.pools.sort_by(|left, right| {
+            (left.provider_type, &left.pool).cmp(&(right.provider_type, &right.pool))
+        });
+        view.keys.sort_by(|left, right| {
+            (left.provider_type, &left.key_id).cmp(&(right.provider_type, &right.key_id))
+        });
+        view
+    }
+
+    fn is_turn(&self, pool_id: &str, waiter_id: Option<u64>) -> bool {
+        let Some(waiter_id) = waiter_id else {
+            return true;
+        };
+        let Ok(inner) = self.inner.lock() else {
+            return true;
+        };
+        inner
+            .pools
+            .get(pool_id)
+            .is_none_or(|pool| pool.is_turn(waiter_id))
+    }
+}
+
+fn stats_key(provider_type: ProviderType, key_id: &str) -> String {
+    format!("{}:{key_id}", provider_type.as_storage_str())
+}
+
+fn tier_index(priority: QueuePriority) -> usize {
+    match priority {
+        QueuePriority::Interactive => 0,
+        QueuePriority::Standard => 1,
+        QueuePriority::Batch => 2,
+    }
+}
+
+/// One request's place in its pool queue. Requests only join the queue once
+/// the pool is saturated; dropping the entry gives the place up.
+pub(crate) struct FairQueueEntry {
+    queue: Option<Arc<FairQueue>>,
+    provider_type: ProviderType,
+    pool: String,
+    account_count: usize,
+    key_id: String,
+    priority: QueuePriority,
+    weight: u32,
+    max_queued: usize,
+    waiter_id: Option<u64>,
+    waited: Duration,
+}
+
+impl FairQueueEntry {
+    /// An entry that never queues: throttled requests just poll again, as
+    /// they did before fair queuing existed.
+    pub(crate) fn detached() -> Self {
+        Self {
+            queue: None,
+            provider_type: ProviderType::Codex,
+            pool: String::new(),
+            account_count: 0,
+            key_id: String::new(),
+            priority: QueuePriority::default(),
+            weight: DEFAULT_QUEUE_WEIGHT,
+            max_queued: 0,
+            waiter_id: None,
+            waited: Duration::ZERO,
+        }
+    }
+
+    /// Total time spent queued so far.
+    pub(crate) fn waited(&self) -> Duration {
+        self.waited
+    }
+
+    /// Wait until this request may poll the accounts. A request that is not
+    /// queued goes straight through unless others are already waiting for
+    /// the pool, in which case it joins the queue behind them.
+    pub(crate) async fn wait_turn(&mut self) -> Result<(), FairQueueRejection> {
+        let Some(queue) = self.queue.clone() else {
+            return Ok(());
+        };
+        let stalled_at = Instant::now() + FAIR_QUEUE_STALL_TIMEOUT;
+        loop {
+            let notify = {
+                let Ok(mut inner) = queue.inner.lock() else {
+                    return Ok(());
+                };
+                if self.waiter_id.is_none() {
+                    if inner
+                        .pools
+                        .get(&self.pool)
+                        .is_none_or(|pool| pool.waiters.is_empty())
+                    {
+                        return Ok(());
+                    }
+                    self.join(&queue, &mut inner)?;
+                }
+                match inner.pools.get(&self.pool) {
+                    Some(pool) => Arc::clone(&pool.notify),
+                    None => return Ok(()),
+                }
+            };
+            let mut notified = pin!(notify.notified());
+            notified.as_mut().enable();
+            if queue.is_turn(&self.pool, self.waiter_id) {
+                return Ok(());
+            }
+            let remaining = stalled_at.saturating_duration_since(Instant::now());
+            if tokio::time::timeout(remaining, notified).await.is_err() {
+                tracing::debug!(
+                    key_id = %self.key_id,
+                    pool = %self.pool,
+                    "fair queue waiter stalled behind the queue head; polling out of order"
+                );
+                return Ok(());
+            }
+        }
+    }
+
+    /// Every account was throttled: keep (or take) a place in the queue and
+    /// let the next waiter try. Refuses when the pool or key queue is full.
+    pub(crate) fn yield_turn(&mut self) -> Result<(), FairQueueRejection> {
+        let Some(queue) = self.queue.clone() else {
+            return Ok(());
+        };
+        let Ok(mut inner) = queue.inner.lock() else {
+            return Ok(());
+        };
+        if self.waiter_id.is_none() {
+            self.join(&queue, &mut inner)?;
+        }
+        if let (Some(waiter_id), Some(pool)) = (self.waiter_id, inner.pools.get_mut(&self.pool)) {
+            let round = pool.round;
+            if let Some(waiter) = pool.waiter_mut(waiter_id) {
+                waiter.tried_round = Some(round);
+            }
+            pool.notify.notify_waiters();
+        }
+        Ok(())
+    }
+
+    /// Capacity may have freed up: start a new round from the queue head.
+    pub(crate) fn next_round(&self) {
+        let (Some(queue), Some(waiter_id)) = (self.queue.as_ref(), self.waiter_id) else {
+            return;
+        };
+        let Ok(mut inner) = queue.inner.lock() else {
+            return;
+        };
+        let Some(pool) = inner.pools.get_mut(&self.pool) else {
+            return;
+        };
+        let round = pool.round;
+        if pool
+            .waiter_mut(waiter_id)
+            .is_some_and(|waiter| waiter.tried_round == Some(round))
+        {
+            pool.round = round.wrapping_add(1);
+            pool.notify.notify_waiters();
+        }
+    }
+
+    /// The request got an account: leave the queue and let the next waiter
+    /// try.
+    pub(crate) fn finish(&mut self) {
+        self.leave(true);
+    }
+
+    fn join(
+        &mut self,
+        queue: &FairQueue,
+        inner: &mut FairQueueInner,
+    ) -> Result<(), FairQueueRejection> {
+        let stats = inner
+            .keys
+            .entry(stats_key(self.provider_type, &self.key_id))
+            .or_default();
+        stats.provider_type = Some(self.provider_type);
+        stats.priority = self.priority;
+        stats.weight = self.weight;
+        let pool = inner
+            .pools
+            .entry(self.pool.clone())
+            .or_insert_with(|| PoolQueue::new(self.provider_type, self.account_count));
+        let rejection = if pool.waiters.len() >= queue.config.max_depth {
+            Some(FairQueueRejection::PoolFull {
+                limit: queue.config.max_depth,
+            })
+        } else if pool
+            .waiters
+            .iter()
+            .filter(|waiter| waiter.key_id == self.key_id)
+            .count()
+            >= self.max_queued
+        {
+            Some(FairQueueRejection::KeyFull {
+                limit: self.max_queued,
+            })
+        } else {
+            None
+        };
+        if let Some(rejection) = rejection {
+            stats.rejected = stats.rejected.saturating_add(1);
+            if pool.waiters.is_empty() {
+                inner.pools.remove(&self.pool);
+            }
+            return Err(rejection);
+        }
+        let tier = tier_index(self.priority);
+        let start_tag = pool.virtual_time[tier].max(
+            pool.key_finish
+                .get(&self.key_id)
+                .copied()
+                .unwrap_or_default(),
+        );
+        let finish_tag =
+            start_tag.saturating_add(VIRTUAL_REQUEST_COST / u64::from(self.weight.max(1)));
+        pool.key_finish.insert(self.key_id.clone(), finish_tag);
+        let id = queue.next_waiter_id.fetch_add(1, Ordering::Relaxed);
+        let position = pool.waiters.partition_point(|waiter| {
+            (waiter.priority, waiter.finish_tag, waiter.id) < (self.priority, finish_tag, id)
+        });
+        pool.waiters.insert(position, Waiter {
+            id,
+            key_id: self.key_id.clone(),
+            priority: self.priority,
+            start_tag,
+            finish_tag,
+            tried_round: None,
+            enqueued_at: Instant::now(),
+        });
+        self.waiter_id = Some(id);
+        Ok(())
+    }
+
+    fn leave(&mut self, served: bool) {
+        let (Some(queue), Some(waiter_id)) = (self.queue.as_ref(), self.waiter_id.take()) else {
+            return;
+        };
+        let Ok(mut inner) = queue.inner.lock() else {
+            return;
+        };
+        let inner = &mut *inner;
+        let Some(pool) = inner.pools.get_mut(&self.pool) else {
+            return;
+        };
+        let Some(position) = pool
+            .waiters
+            .iter()
+            .position(|waiter| waiter.id == waiter_id)
+        else {
+            return;
+        };
+        let waiter = pool.waiters.remove(position);
+        let waited = waiter.enqueued_at.elapsed();
+        self.waited = self.waited.saturating_add(waited);
+        if served {
+            let tier = tier_index(waiter.priority);
+            pool.virtual_time[tier] = pool.virtual_time[tier].max(waiter.start_tag);
+            if let Some(stats) = inner
+                .keys
+                .get_mut(&stats_key(self.provider_type, &waiter.key_id))
+            {
+                let waited_ms = clamp_duration_ms(waited);
+                stats.served = stats.served.saturating_add(1);
+                stats.total_wait_ms = stats.total_wait_ms.saturating_add(waited_ms);
+                stats.max_wait_ms = stats.max_wait_ms.max(waited_ms);
+            }
+        } else if pool.key_finish.get(&waiter.key_id) == Some(&waiter.finish_tag) {
+            // Give back the share an abandoned request reserved.
+            pool.key_finish.insert(waiter.key_id, waiter.start_tag);
+        }
+        pool.notify.notify_waiters();
+        if pool.waiters.is_empty() {
+            inner.pools.remove(&self.pool);
+        }
+    }
+}
+
+impl Drop for FairQueueEntry {
+    fn drop(&mut self) {
+        self.leave(false);
+    }
+}
+
+/// Add the queue wait and tier to the routing diagnostics once a request
+/// actually queued. The wait is already part of `routing_wait_ms`.
+pub(super) fn record_queue_wait(meta: &mut ProviderUsageMetadata, entry: &FairQueueEntry) {
+    if entry.waited.is_zero() {
+        return;
+    }
+    let mut diagnostics = meta
+        .routing_diagnostics_json
+        .as_deref()
+        .and_then(|raw| serde_json::from_str::<Map<String, Value>>(raw).ok())
+        .unwrap_or_default();
+    diagnostics.insert("queue_wait_ms".to_string(), Value::from(clamp_duration_ms(entry.waited)));
+    diagnostics.insert("queue_priority".to_string(), Value::from(entry.priority.as_str()));
+    diagnostics.insert("queue_weight".to_string(), Value::from(entry.weight));
+    meta.routing_diagnostics_json = Some(Value::Object(diagnostics).to_string());
+}
+
+/// Build the request's queue handle from the key's queue policy. Queuing
+/// only decides who goes first, so a policy that fails to load falls back to
+/// the defaults instead of failing the request.
+pub(super) async fn key_queue_entry<'a>(
+    fair_queue: &Arc<FairQueue>,
+    route_store: &dyn ProviderRouteStore,
+    key: &AuthenticatedKey,
+    provider_type: ProviderType,
+    account_names: impl IntoIterator<Item = &'a str>,
+) -> FairQueueEntry {
+    let policy = match route_store.resolve_queue_policy(key).await {
+        Ok(policy) => policy,
+        Err(err) => {
+            tracing::warn!(
+                key_id = %key.key_id,
+                error = %err,
+                "failed to load key queue policy; queuing with defaults"
+            );
+            None
+        },
+    };
+    fair_queue.entry(provider_type, &key.key_id, policy.as_ref(), account_names)
+}
+
+impl ProviderState {
+    /// Fair queue state for the admin API.
+    pub(crate) fn fair_queue_view(&self) -> FairQueueView {
+        self.fair_queue.snapshot()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_queue(max_depth: &str, max_depth_per_key: &str) -> Arc<FairQueue> {
+        Arc::new(FairQueue::new(FairQueueConfig::from_raw(
+            None,
+            Some(max_depth),
+            Some(max_depth_per_key),
+        )))
+    }
+
+    fn policy(weight: u32, priority: QueuePriority) -> KeyQueuePolicy {
+        KeyQueuePolicy {
+            weight: Some(weight),
+            priority: Some(priority),
+            max_queued_requests: None,
+        }
+    }
+
+    fn queued_keys(queue: &FairQueue) -> Vec<String> {
+        let inner = queue.inner.lock().expect("queue lock");
+        inner
+            .pools
+            .values()
+            .flat_map(|pool| pool.waiters.iter().map(|waiter| waiter.key_id.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn config_from_raw_applies_defaults() {
+        let config = FairQueueConfig::default();
+        assert!(config.enabled);
+        assert_eq!(config.max_depth, DEFAULT_FAIR_QUEUE_MAX_DEPTH);
+        assert_eq!(config.max_depth_per_key, DEFAULT_FAIR_QUEUE_MAX_DEPTH_PER_KEY);
+
+        let config = FairQueueConfig::from_raw(Some("off"), Some("0"), Some("8"));
+        assert!(!config.enabled);
+        assert_eq!(config.max_depth, DEFAULT_FAIR_QUEUE_MAX_DEPTH);
+        assert_eq!(config.max_depth_per_key, 8);
+    }
+
+    #[test]
+    fn waiters_are_ordered_by_tier_then_weighted_finish_tag() {
+        let queue = test_queue("64", "64");
+        let accounts = ["a", "b"];
+        let heavy = policy(3, QueuePriority::Standard);
+        let light = policy(1, QueuePriority::Standard);
+        let batch = policy(100, QueuePriority::Batch);
+        let interactive = policy(1, QueuePriority::Interactive);
+        let mut entries = Vec::new();
+        for (key_id, policy) in [
+            ("batch", &batch),
+            ("light", &light),
+            ("light", &light),
+            ("heavy", &heavy),
+            ("heavy", &heavy),
+            ("heavy", &heavy),
+            ("interactive", &interactive),
+        ] {
+            let mut entry = queue.entry(ProviderType::Codex, key_id, Some(policy), accounts);
+            entry.yield_turn().expect("queued");
+            entries.push(entry);
+        }
+
+        assert_eq!(queued_keys(&queue), [
+            "interactive",
+            "heavy",
+            "heavy",
+            "heavy",
+            "light",
+            "light",
+            "batch"
+        ]);
+
+        drop(entries);
+        assert!(queue.inner.lock().expect("queue lock").pools.is_empty());
+    }
+
+    #[test]
+    fn full_queues_reject_and_count_rejections() {
+        let queue = test_queue("3", "2");
+        let mut entries = Vec::new();
+        for _ in 0..2 {
+            let mut entry = queue.entry(ProviderType::Kiro, "busy", None, ["a"]);
+            entry.yield_turn().expect("queued");
+            entries.push(entry);
+        }
+        let mut overflow = queue.entry(ProviderType::Kiro, "busy", None, ["a"]);
+        assert_eq!(
+            overflow.yield_turn(),
+            Err(FairQueueRejection::KeyFull {
+                limit: 2
+            })
+        );
+
+        let roomy = KeyQueuePolicy {
+            max_queued_requests: Some(10),
+            ..KeyQueuePolicy::default()
+        };
+        let mut other = queue.entry(ProviderType::Kiro, "other", Some(&roomy), ["a"]);
+        other.yield_turn().expect("queued");
+        let mut pool_overflow = queue.entry(ProviderType::Kiro, "other", Some(&roomy), ["a"]);
+        assert_eq!(
+            pool_overflow.yield_turn(),
+            Err(FairQueueRejection::PoolFull {
+                limit: 3
+            })
+        );
+
+        let view = queue.snapshot();
+        assert_eq!(view.pools.len(), 1);
+        assert_eq!(view.pools[0].waiting, 3);
+        let busy = view
+            .keys
+            .iter()
+            .find(|key| key.key_id == "busy")
+            .expect("busy key stats");
+        assert_eq!((busy.waiting, busy.rejected), (2, 1));
+    }
+
+    #[tokio::test]
+    async fn newcomers_wait_behind_queued_requests_until_they_pass() {
+        let queue = test_queue("64", "64");
+        let mut head = queue.entry(ProviderType::Codex, "first", None, ["a"]);
+        head.yield_turn().expect("queued");
+        head.next_round();
+
+        let mut newcomer = queue.entry(ProviderType::Codex, "second", None, ["a"]);
+        let blocked = tokio::time::timeout(Duration::from_millis(50), newcomer.wait_turn()).await;
+        assert!(blocked.is_err(), "newcomer must not overtake the queue head");
+
+        head.wait_turn().await.expect("head turn");
+        head.yield_turn().expect("head passes");
+        tokio::time::timeout(Duration::from_secs(1), newcomer.wait_turn())
+            .await
+            .expect("newcomer turn after head passed")
+            .expect("newcomer queued");
+
+        head.finish();
+        assert!(head.waited() > Duration::ZERO);
+        let mut meta = ProviderUsageMetadata::synthetic_request("POST", "/v1/responses");
+        record_queue_wait(&mut meta, &head);
+        let diagnostics = serde_json::from_str::<Value>(
+            meta.routing_diagnostics_json
+                .as_deref()
+                .expect("diagnostics"),
+        )
+        .expect("json");
+        assert_eq!(diagnostics["queue_priority"], "standard");
+
+        let detached = FairQueueEntry::detached();
+        let mut meta = ProviderUsageMetadata::synthetic_request("POST", "/v1/responses");
+        record_queue_wait(&mut meta, &detached);
+        assert!(meta.routing_diagnostics_json.is_none());
+    }
+}
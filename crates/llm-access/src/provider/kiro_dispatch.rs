@@ -14,12 +14,16 @@ use axum::{
 };
 use futures_util::StreamExt;
 use llm_access_codex::request::external_origin;
-use llm_access_core::store::{AuthenticatedKey, ProviderKiroRoute, ProviderRouteStore};
+use llm_access_core::{
+    provider::ProviderType,
+    store::{AuthenticatedKey, ProviderKiroRoute, ProviderRouteStore, PROVIDER_KIRO},
+};
 use llm_access_kiro::{
     anthropic::{
         converter::{
-            convert_normalized_request_with_resolved_session, normalize_request, ConversionResult,
-            ResponseModelIdentity, SessionIdSource,
+            convert_normalized_request_with_resolved_session, normalize_request,
+            parse_messages_request_with_transform, ConversionResult, ResponseModelIdentity,
+            SessionIdSource,
         },
         protected_content::validate_protected_content,
         stream::anthropic_usage_json,
@@ -39,6 +43,7 @@ use super::{
         maybe_dispatch_anthropic_upstream_pool, AnthropicUpstreamDispatchOutcome,
     },
     cctest::{self, build_direct_replay_body, bytes_to_string, CctestProbeMatch},
+    circuit_breaker::{CircuitBreakers, CircuitTarget},
     client::{cctest_proxy_client, provider_client},
     errors::{
         anthropic_json_error, anthropic_json_error_body, is_monthly_request_limit,
@@ -48,6 +53,7 @@ use super::{
         proxy_cooldown_key_for_route, randomized_same_account_retry_delay,
         retry_after_header_duration, transient_invalid_model_cooldown, SameAccountRetryReason,
     },
+    fair_queue::{key_queue_entry, record_queue_wait},
     kiro_error::{
         kiro_bedrock_anthropic_error, kiro_bedrock_anthropic_error_body,
         kiro_conversion_error_response, kiro_json_error, kiro_upstream_error_response,
@@ -69,6 +75,9 @@ use super::{
         record_kiro_selection_failure, record_kiro_usage, record_kiro_websearch_usage,
     },
     limiter::{kiro_key_limit_response, try_acquire_key_permit},
+    request_transform::{
+        record_request_transforms, request_transform_unavailable, resolve_request_transform,
+    },
     route_selection::{hydrate_kiro_route_for_dispatch, select_kiro_route_with_account_permit},
     stream_guards::{non_stream_kiro_response, stream_kiro_upstream_response},
     usage_meta::{
@@ -87,7 +96,7 @@ use crate::kiro_refresh;
 const INCONSISTENT_ROUTE_CONFIGURATION_MESSAGE: &str = "Route configuration is inconsistent.";
 const KIRO_SAME_ACCOUNT_MAX_ATTEMPTS: usize = 3;
 
-fn kiro_model_group_preferred_account_names_for_model(
+pub(super) fn kiro_model_group_preferred_account_names_for_model(
     routes: &[ProviderKiroRoute],
     requested_model: &str,
 ) -> Option<HashSet<String>> {
@@ -179,6 +188,8 @@ pub async fn dispatch_kiro_proxy(
         kiro_cache_simulator,
         request_limiter,
         kiro_request_scheduler,
+        circuit_breakers,
+        fair_queue,
         kiro_session_affinity,
         kiro_latency_ranker,
         protected_thinking_signature_secret,
@@ -286,17 +297,30 @@ pub async fn dispatch_kiro_proxy(
             .await;
         }
     }
-    let parse_started = Instant::now();
-    let mut payload = match serde_json::from_slice::<MessagesRequest>(&body) {
-        Ok(payload) => payload,
+    // Fail closed: a key with redactions configured must never reach the
+    // upstream untransformed because its policy could not be loaded.
+    let request_transform = match resolve_request_transform(route_store.as_ref(), &key).await {
+        Ok(transform) => transform,
         Err(err) => {
-            return kiro_json_error(
-                StatusCode::BAD_REQUEST,
-                "invalid_request_error",
-                &format!("failed to parse request JSON: {err}"),
-            )
+            let message = request_transform_unavailable(&key, &err);
+            return kiro_json_error(StatusCode::SERVICE_UNAVAILABLE, "api_error", &message);
         },
     };
+    let parse_started = Instant::now();
+    let mut payload =
+        match parse_messages_request_with_transform(&body, request_transform.as_deref()) {
+            Ok((payload, applied_transforms)) => {
+                record_request_transforms(&mut usage_meta, &applied_transforms);
+                payload
+            },
+            Err(err) => {
+                return kiro_json_error(
+                    StatusCode::BAD_REQUEST,
+                    "invalid_request_error",
+                    &format!("failed to parse request JSON: {err}"),
+                )
+            },
+        };
     usage_meta.mark_pre_handler_done(clamp_duration_ms(parse_started.elapsed()));
     usage_meta.last_message_content = extract_last_message_from_kiro_messages(&payload);
     let requested_model = payload.model.clone();
@@ -459,6 +483,8 @@ pub async fn dispatch_kiro_proxy(
             route_store,
             request_limiter,
             kiro_request_scheduler,
+            circuit_breakers,
+            fair_queue,
             kiro_session_affinity,
             kiro_latency_ranker,
             affinity_session_id,
@@ -543,9 +569,10 @@ pub async fn dispatch_kiro_proxy(
     };
     let mut key_permit = Some(key_permit);
     let mut failed_accounts = HashSet::new();
-    let preferred_account_name = affinity_session_id
-        .as_deref()
-        .and_then(|session_id| kiro_session_affinity.lookup(&key.key_id, session_id));
+    let preferred_account_name = match affinity_session_id.as_deref() {
+        Some(session_id) => kiro_session_affinity.resolve(&key.key_id, session_id).await,
+        None => None,
+    };
     log_kiro_model_group_preference_routing(
         &key,
         public_path,
@@ -561,10 +588,20 @@ pub async fn dispatch_kiro_proxy(
     let session_counts =
         (routes.len() > 1 && affinity_session_id.is_some() && preferred_account_name.is_none())
             .then(|| kiro_session_affinity.account_session_counts());
+    let mut queue_entry = key_queue_entry(
+        &fair_queue,
+        route_store.as_ref(),
+        &key,
+        ProviderType::Kiro,
+        routes.iter().map(|route| route.account_name.as_str()),
+    )
+    .await;
     loop {
         let route_started = Instant::now();
         let (route, account_permit) = match select_kiro_route_with_account_permit(
             &kiro_request_scheduler,
+            &circuit_breakers,
+            &mut queue_entry,
             &routes,
             &failed_accounts,
             kiro_latency_ranker.as_ref(),
@@ -589,6 +626,7 @@ pub async fn dispatch_kiro_proxy(
             },
         };
         usage_meta.add_routing_wait(clamp_duration_ms(route_started.elapsed()));
+        record_queue_wait(&mut usage_meta, &queue_entry);
         let selected_account_name = route.account_name.clone();
         let route = match hydrate_kiro_route_for_dispatch(route, route_store.as_ref()).await {
             Ok(route) => route,
@@ -724,6 +762,7 @@ pub async fn dispatch_kiro_proxy(
                     &mut failed_accounts,
                     route_store.as_ref(),
                     &kiro_request_scheduler,
+                    &circuit_breakers,
                 )
                 .await
                 {
@@ -787,6 +826,7 @@ pub async fn dispatch_kiro_proxy(
             },
         };
         if !response.status().is_success() {
+            circuit_breakers.record_inconclusive(&CircuitTarget::kiro(&route));
             let upstream_status = response.status();
             let content_type = response
                 .headers()
@@ -874,6 +914,7 @@ pub async fn dispatch_kiro_proxy(
                         &mut failed_accounts,
                         route_store.as_ref(),
                         &kiro_request_scheduler,
+                        &circuit_breakers,
                     )
                     .await
                     {
@@ -925,6 +966,7 @@ pub async fn dispatch_kiro_proxy(
                     return failure.into_response();
                 },
             };
+            circuit_breakers.record_success(&CircuitTarget::kiro(&route));
             remember_kiro_session_affinity(
                 kiro_session_affinity.as_ref(),
                 &key.key_id,
@@ -958,6 +1000,7 @@ pub async fn dispatch_kiro_proxy(
             };
             return stream_kiro_upstream_response(stream_response, response_ctx);
         }
+        circuit_breakers.record_success(&CircuitTarget::kiro(&route));
         let affinity_update =
             affinity_session_id
                 .clone()
@@ -1293,6 +1336,8 @@ async fn dispatch_kiro_websearch(input: KiroWebsearchDispatch) -> Response {
         route_store,
         request_limiter,
         kiro_request_scheduler,
+        circuit_breakers,
+        fair_queue,
         kiro_session_affinity,
         kiro_latency_ranker,
         affinity_session_id,
@@ -1331,9 +1376,10 @@ async fn dispatch_kiro_websearch(input: KiroWebsearchDispatch) -> Response {
 
     let mut key_permit = Some(key_permit);
     let mut failed_accounts = HashSet::new();
-    let preferred_account_name = affinity_session_id
-        .as_deref()
-        .and_then(|session_id| kiro_session_affinity.lookup(&key.key_id, session_id));
+    let preferred_account_name = match affinity_session_id.as_deref() {
+        Some(session_id) => kiro_session_affinity.resolve(&key.key_id, session_id).await,
+        None => None,
+    };
     log_kiro_model_group_preference_routing(
         &key,
         "/mcp",
@@ -1349,10 +1395,20 @@ async fn dispatch_kiro_websearch(input: KiroWebsearchDispatch) -> Response {
     let session_counts =
         (routes.len() > 1 && affinity_session_id.is_some() && preferred_account_name.is_none())
             .then(|| kiro_session_affinity.account_session_counts());
+    let mut queue_entry = key_queue_entry(
+        &fair_queue,
+        route_store.as_ref(),
+        &key,
+        ProviderType::Kiro,
+        routes.iter().map(|route| route.account_name.as_str()),
+    )
+    .await;
     loop {
         let route_started = Instant::now();
         let (route, account_permit) = match select_kiro_route_with_account_permit(
             &kiro_request_scheduler,
+            &circuit_breakers,
+            &mut queue_entry,
             &routes,
             &failed_accounts,
             kiro_latency_ranker.as_ref(),
@@ -1377,6 +1433,7 @@ async fn dispatch_kiro_websearch(input: KiroWebsearchDispatch) -> Response {
             },
         };
         usage_meta.add_routing_wait(clamp_duration_ms(route_started.elapsed()));
+        record_queue_wait(&mut usage_meta, &queue_entry);
         let selected_account_name = route.account_name.clone();
         let route = match hydrate_kiro_route_for_dispatch(route, route_store.as_ref()).await {
             Ok(route) => route,
@@ -1409,6 +1466,7 @@ async fn dispatch_kiro_websearch(input: KiroWebsearchDispatch) -> Response {
                 route_usage_meta.mark_upstream_headers();
                 route_usage_meta.mark_post_headers_body();
                 route_usage_meta.mark_stream_finish();
+                circuit_breakers.record_success(&CircuitTarget::kiro(&route));
                 remember_kiro_session_affinity(
                     kiro_session_affinity.as_ref(),
                     &key.key_id,
@@ -1442,6 +1500,7 @@ async fn dispatch_kiro_websearch(input: KiroWebsearchDispatch) -> Response {
                     &mut failed_accounts,
                     route_store.as_ref(),
                     &kiro_request_scheduler,
+                    &circuit_breakers,
                 )
                 .await
                 {
@@ -1827,6 +1886,7 @@ async fn call_kiro_generate_for_route_inner(
         {
             Ok(response) => response,
             Err(err) => {
+                report_kiro_proxy_transport_failure(route_store, route, &err).await;
                 last_failure = Some(KiroRouteFailure::synthetic(
                     StatusCode::BAD_GATEWAY,
                     format!("kiro upstream transport failure: {err}"),
@@ -2071,6 +2131,29 @@ async fn send_kiro_generate_request(
     .send()
     .await?)
 }
+/// Count a proxy connect failure so pool selection can steer the account away
+/// from a proxy that keeps dropping connections.
+async fn report_kiro_proxy_transport_failure(
+    route_store: &dyn ProviderRouteStore,
+    route: &ProviderKiroRoute,
+    err: &anyhow::Error,
+) {
+    let is_connect_failure = err
+        .downcast_ref::<reqwest::Error>()
+        .is_some_and(reqwest::Error::is_connect);
+    if route.proxy.is_none() || !is_connect_failure {
+        return;
+    }
+    if let Err(report_err) = route_store
+        .report_proxy_transport_failure(PROVIDER_KIRO, &route.account_name, &format!("{err:#}"))
+        .await
+    {
+        tracing::debug!(
+            account_name = %route.account_name,
+            "failed to report kiro proxy transport failure: {report_err:#}"
+        );
+    }
+}
 async fn send_kiro_mcp_request(
     route: &ProviderKiroRoute,
     call_ctx: &kiro_refresh::KiroCallContext,
@@ -2122,9 +2205,12 @@ async fn should_failover_after_kiro_route_failure(
     failed_accounts: &mut HashSet<String>,
     route_store: &dyn ProviderRouteStore,
     scheduler: &KiroRequestScheduler,
+    circuit_breakers: &CircuitBreakers,
 ) -> bool {
+    let circuit_target = CircuitTarget::kiro(route);
     match failure.kind {
         KiroRouteFailureKind::QuotaExhausted => {
+            circuit_breakers.record_inconclusive(&circuit_target);
             let error_message = failure.body_text();
             for account_name in
                 account_names_for_kiro_routing_identity(routes, &route.routing_identity)
@@ -2141,6 +2227,11 @@ async fn should_failover_after_kiro_route_failure(
             mark_proxy,
         } => {
             let error_message = failure.body_text();
+            circuit_breakers.record_failure(
+                &circuit_target,
+                mark_proxy,
+                &kiro_circuit_failure_reason(failure),
+            );
             scheduler.mark_account_cooldown(
                 &route.routing_identity,
                 cooldown,
@@ -2154,12 +2245,23 @@ async fn should_failover_after_kiro_route_failure(
             true
         },
         KiroRouteFailureKind::RetryNext => {
+            circuit_breakers.record_failure(
+                &circuit_target,
+                false,
+                &kiro_circuit_failure_reason(failure),
+            );
             failed_accounts.insert(route.account_name.clone());
             has_remaining_kiro_candidate(routes, failed_accounts, &route.account_name)
         },
-        KiroRouteFailureKind::Fatal => false,
+        KiroRouteFailureKind::Fatal => {
+            circuit_breakers.record_inconclusive(&circuit_target);
+            false
+        },
     }
 }
+fn kiro_circuit_failure_reason(failure: &KiroRouteFailure) -> String {
+    format!("upstream status {}: {}", failure.status.as_u16(), failure.body_text())
+}
 pub fn account_names_for_kiro_routing_identity(
     routes: &[ProviderKiroRoute],
     routing_identity: &str,
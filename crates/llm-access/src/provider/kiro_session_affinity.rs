@@ -3,12 +3,14 @@ use std::{
     collections::HashMap,
     fmt::Write as _,
     num::NonZeroUsize,
-    sync::Mutex,
+    sync::{Arc, Mutex, OnceLock},
     time::{Duration, Instant},
 };
 
 use lru::LruCache;
 
+use super::shared_session_affinity::SharedSessionAffinityStore;
+
 const DEFAULT_KIRO_SESSION_AFFINITY_MAX_ENTRIES: usize = 4_096;
 const MAX_KIRO_SESSION_AFFINITY_MAX_ENTRIES: usize = 65_536;
 const DEFAULT_KIRO_SESSION_AFFINITY_TTL_SECONDS: u64 = 6 * 60 * 60;
@@ -29,6 +31,7 @@ struct KiroSessionAffinityEntry {
 pub(super) struct KiroSessionAffinity {
     entries: Mutex<LruCache<Box<str>, KiroSessionAffinityEntry>>,
     ttl: Duration,
+    shared: OnceLock<Arc<SharedSessionAffinityStore>>,
 }
 
 impl KiroSessionAffinity {
@@ -56,11 +59,37 @@ impl KiroSessionAffinity {
         Self {
             entries: Mutex::new(LruCache::new(capacity)),
             ttl,
+            shared: OnceLock::new(),
         }
     }
 
+    /// Opt into the cluster-shared store; entries are mirrored with this
+    /// map's TTL. Only the first attach takes effect.
+    pub(super) fn attach_shared(&self, store: Arc<SharedSessionAffinityStore>) {
+        let _ = self.shared.set(store);
+    }
+
     pub(super) fn remember(&self, key_id: &str, session_id: &str, account_name: &str) {
         self.remember_at(key_id, session_id, account_name, Instant::now());
+        if let Some(shared) = self.shared.get() {
+            if let Some(key) = affinity_key(key_id, session_id) {
+                shared.remember("kiro", &key, account_name.trim(), self.ttl);
+            }
+        }
+    }
+
+    /// Like [`Self::lookup`], but consults the shared store first when one is
+    /// attached. A shared hit refreshes the local entry; a miss or an
+    /// unavailable shared store falls back to the local LRU.
+    pub(super) async fn resolve(&self, key_id: &str, session_id: &str) -> Option<String> {
+        if let Some(shared) = self.shared.get() {
+            let key = affinity_key(key_id, session_id)?;
+            if let Some(account_name) = shared.lookup("kiro", &key).await {
+                self.remember_at(key_id, session_id, &account_name, Instant::now());
+                return Some(account_name);
+            }
+        }
+        self.lookup(key_id, session_id)
     }
 
     fn remember_at(&self, key_id: &str, session_id: &str, account_name: &str, now: Instant) {
@@ -236,4 +265,21 @@ mod tests {
         let counts = affinity.account_session_counts_at(now + Duration::from_secs(121));
         assert_eq!(counts.get("account-a").copied(), Some(1));
     }
+
+    #[tokio::test]
+    async fn resolve_falls_back_to_local_when_shared_store_is_down() {
+        let affinity = KiroSessionAffinity::new(4, Duration::from_secs(60));
+        let store = SharedSessionAffinityStore::spawn(
+            &llm_access_store::request_cache::RequestCacheConfig {
+                url: "redis://127.0.0.1:1/".to_string(),
+                key_prefix: "sftest".to_string(),
+            },
+        )
+        .expect("store");
+        affinity.attach_shared(store);
+        affinity.remember("key-a", "session-a", "account-a");
+
+        assert_eq!(affinity.resolve("key-a", "session-a").await.as_deref(), Some("account-a"));
+        assert_eq!(affinity.resolve("key-a", "session-b").await, None);
+    }
 }
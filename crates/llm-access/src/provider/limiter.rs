@@ -12,8 +12,8 @@ use axum::{
 use llm_access_core::store::AuthenticatedKey;
 
 use super::{
-    kiro_error::kiro_json_error, ActiveCooldown, CodexAccountCooldowns, LimitPermit,
-    LimitRejection, RequestLimiter,
+    fair_queue::FairQueueRejection, kiro_error::kiro_json_error, ActiveCooldown,
+    CodexAccountCooldowns, LimitPermit, LimitRejection, RequestLimiter,
 };
 
 impl Drop for LimitPermit {
@@ -178,3 +178,13 @@ pub fn kiro_key_limit_response(rejection: &LimitRejection) -> Response {
         ),
     )
 }
+pub fn codex_queue_full_response(rejection: &FairQueueRejection) -> Response {
+    (StatusCode::TOO_MANY_REQUESTS, rejection.message()).into_response()
+}
+pub fn kiro_queue_full_response(rejection: &FairQueueRejection) -> Response {
+    kiro_json_error(
+        StatusCode::TOO_MANY_REQUESTS,
+        "rate_limit_error",
+        &format!("Kiro {}", rejection.message()),
+    )
+}
@@ -0,0 +1,560 @@
+//! Per-key content moderation in front of provider dispatch.
+//!
+//! ```text
+//! provider_entry
+//!   |
+//!   +-- key has no moderation policy --> response cache / dispatch
+//!   |
+//!   +-- buffer body, local rules + optional classifier
+//!         |
+//!         +-- block    --> queue event, reject with 400
+//!         +-- flag     --> queue event, dispatch
+//!         +-- log_only --> log, dispatch
+//!         |
+//!         +-- moderate_responses --> tee response, scan after the stream ends
+//! ```
+//!
+//! The classifier is an optional local HTTP endpoint speaking the OpenAI
+//! moderation shape (`POST {"input": ...}` answering `results[].flagged`).
+//! It fails open: a timeout or bad answer only logs a warning and the local
+//! rules decide alone.
+
+use std::{
+    sync::{Arc, LazyLock, OnceLock},
+    time::Duration,
+};
+
+use axum::{
+    body::{to_bytes, Body, Bytes},
+    http::{Method, Request, StatusCode},
+    response::{IntoResponse, Response},
+};
+use futures_util::StreamExt;
+use llm_access_codex::request::extract_client_ip_from_headers;
+use llm_access_core::{
+    moderation::{
+        request_prompt_text, response_text, CompiledModerationPolicy, ModerationFindings,
+        MAX_MODERATION_SCAN_BYTES,
+    },
+    provider::ProviderType,
+    store::{
+        AuthenticatedKey, KeyModerationPolicy, ModerationAction, NewModerationEvent,
+        ProviderRouteStore,
+    },
+};
+use serde_json::Value;
+
+use super::{
+    compiled_policy_cache::CompiledPolicyCache, errors::codex_surface_error_response,
+    kiro_error::kiro_json_error, response_cache::dispatch_with_response_cache, util::now_millis,
+    ProviderState, DEFAULT_PROVIDER_CLIENT, MAX_PROVIDER_PROXY_BODY_BYTES,
+};
+
+const MODERATION_CLASSIFIER_URL_ENV: &str = "LLM_ACCESS_MODERATION_CLASSIFIER_URL";
+const MODERATION_CLASSIFIER_TIMEOUT_MS_ENV: &str = "LLM_ACCESS_MODERATION_CLASSIFIER_TIMEOUT_MS";
+const DEFAULT_MODERATION_CLASSIFIER_TIMEOUT_MS: u64 = 2_000;
+/// Most response bytes kept for the post-stream scan. SSE framing makes the
+/// raw body several times larger than the text it carries.
+const MAX_MODERATION_RESPONSE_CAPTURE_BYTES: usize = 4 * MAX_MODERATION_SCAN_BYTES;
+/// Rule label recorded when the classifier flagged the text.
+const CLASSIFIER_RULE: &str = "classifier";
+const MODERATION_BLOCKED_MESSAGE: &str = "request was blocked by the content moderation policy";
+
+static MODERATION_CLASSIFIER: OnceLock<Option<ModerationClassifier>> = OnceLock::new();
+static COMPILED_MODERATION_POLICIES: LazyLock<
+    CompiledPolicyCache<KeyModerationPolicy, CompiledModerationPolicy>,
+> = LazyLock::new(CompiledPolicyCache::new);
+
+/// Where and how long to ask the local classifier.
+struct ModerationClassifier {
+    url: String,
+    timeout: Duration,
+}
+
+impl ModerationClassifier {
+    fn from_env() -> Option<Self> {
+        let url = std::env::var(MODERATION_CLASSIFIER_URL_ENV).ok()?;
+        let url = url.trim();
+        if url.is_empty() {
+            return None;
+        }
+        let timeout_ms = std::env::var(MODERATION_CLASSIFIER_TIMEOUT_MS_ENV)
+            .ok()
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .filter(|value| *value > 0)
+            .unwrap_or(DEFAULT_MODERATION_CLASSIFIER_TIMEOUT_MS);
+        Some(Self {
+            url: url.to_string(),
+            timeout: Duration::from_millis(timeout_ms),
+        })
+    }
+
+    fn configured() -> Option<&'static Self> {
+        MODERATION_CLASSIFIER.get_or_init(Self::from_env).as_ref()
+    }
+}
+
+/// Classifier answer for one text.
+#[derive(Debug, Default, PartialEq)]
+struct ClassifierVerdict {
+    flagged: bool,
+    labels: Vec<String>,
+    score: Option<f64>,
+}
+
+/// Everything about the request that an event needs besides the findings.
+#[derive(Clone)]
+struct ModerationContext {
+    key_id: String,
+    key_name: String,
+    provider_type: String,
+    endpoint: String,
+    model: Option<String>,
+    client_ip: String,
+}
+
+/// Dispatch an authenticated request through the key's moderation policy.
+pub(super) async fn dispatch_with_moderation(
+    state: &ProviderState,
+    key: AuthenticatedKey,
+    request: Request<Body>,
+) -> Response {
+    let path = request.uri().path().to_string();
+    let policy = match state.route_store.resolve_moderation_policy(&key).await {
+        Ok(Some(policy)) => policy,
+        Ok(None) => return dispatch_with_response_cache(state, key, request).await,
+        Err(err) => return moderation_unavailable(&key, &path, &err),
+    };
+    let policy = match COMPILED_MODERATION_POLICIES.get_or_compile(
+        &key.key_id,
+        &policy,
+        CompiledModerationPolicy::compile,
+    ) {
+        Ok(policy) => policy,
+        Err(err) => return moderation_unavailable(&key, &path, &err),
+    };
+    if request.method() != Method::POST {
+        return dispatch_with_response_cache(state, key, request).await;
+    }
+
+    let (parts, body) = request.into_parts();
+    let body = match to_bytes(body, MAX_PROVIDER_PROXY_BODY_BYTES).await {
+        Ok(body) => body,
+        Err(_) => return (StatusCode::BAD_REQUEST, "request body is too large").into_response(),
+    };
+    // Bodies that are not JSON are left to the dispatcher's own error path.
+    let value = serde_json::from_slice::<Value>(&body).unwrap_or(Value::Null);
+    let context = ModerationContext {
+        key_id: key.key_id.clone(),
+        key_name: key.key_name.clone(),
+        provider_type: key.provider_type.clone(),
+        endpoint: path.clone(),
+        model: value
+            .get("model")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        client_ip: extract_client_ip_from_headers(&parts.headers),
+    };
+
+    let findings = policy.inspect_request(&value);
+    let verdict =
+        if policy.classifier_enabled { classify(&request_prompt_text(&value)).await } else { None };
+    if let Some((findings, verdict)) = moderation_hit(findings, verdict) {
+        let blocked = policy.action == ModerationAction::Block;
+        handle_hit(
+            state.route_store.as_ref(),
+            &context,
+            "request",
+            policy.action,
+            findings,
+            verdict,
+        )
+        .await;
+        if blocked {
+            return moderation_blocked_response(&key, &path);
+        }
+    }
+
+    let request = Request::from_parts(parts, Body::from(body));
+    let response = dispatch_with_response_cache(state, key, request).await;
+    if policy.moderate_responses {
+        tee_response_for_moderation(response, Arc::clone(&state.route_store), policy, context)
+    } else {
+        response
+    }
+}
+
+/// Combine local findings and the classifier verdict; `None` when neither
+/// matched.
+fn moderation_hit(
+    mut findings: ModerationFindings,
+    verdict: Option<ClassifierVerdict>,
+) -> Option<(ModerationFindings, ClassifierVerdict)> {
+    let verdict = verdict.unwrap_or_default();
+    if verdict.flagged {
+        findings.matched_rules.push(CLASSIFIER_RULE.to_string());
+    }
+    findings.is_hit().then_some((findings, verdict))
+}
+
+/// Queue (`block`/`flag`) or log (`log_only`) one hit.
+async fn handle_hit(
+    route_store: &dyn ProviderRouteStore,
+    context: &ModerationContext,
+    direction: &str,
+    action: ModerationAction,
+    findings: ModerationFindings,
+    verdict: ClassifierVerdict,
+) {
+    if action == ModerationAction::LogOnly {
+        tracing::info!(
+            key_id = %context.key_id,
+            endpoint = %context.endpoint,
+            direction,
+            matched_rules = ?findings.matched_rules,
+            "content moderation rule matched"
+        );
+        return;
+    }
+    let event = NewModerationEvent {
+        event_id: format!("llm-moderation-{}", uuid::Uuid::new_v4()),
+        key_id: context.key_id.clone(),
+        key_name: context.key_name.clone(),
+        provider_type: context.provider_type.clone(),
+        endpoint: context.endpoint.clone(),
+        model: context.model.clone(),
+        direction: direction.to_string(),
+        action: action.as_str().to_string(),
+        matched_rules: findings.matched_rules,
+        classifier_labels: verdict.labels,
+        classifier_score: verdict.score,
+        excerpt: findings.excerpt,
+        client_ip: context.client_ip.clone(),
+        created_at_ms: now_millis(),
+    };
+    if let Err(err) = route_store.record_moderation_event(event).await {
+        tracing::warn!(
+            key_id = %context.key_id,
+            error = %format!("{err:#}"),
+            "failed to record moderation event"
+        );
+    }
+}
+
+/// Stream the response to the client and scan its text once it finished.
+/// Responses are already on the wire by then, so a hit is only queued.
+fn tee_response_for_moderation(
+    response: Response,
+    route_store: Arc<dyn ProviderRouteStore>,
+    policy: Arc<CompiledModerationPolicy>,
+    context: ModerationContext,
+) -> Response {
+    let (parts, body) = response.into_parts();
+    if !parts.status.is_success() {
+        return Response::from_parts(parts, body);
+    }
+    let mut upstream = body.into_data_stream();
+    let stream = async_stream::stream! {
+        let mut captured = Vec::new();
+        while let Some(chunk) = upstream.next().await {
+            match chunk {
+                Ok(bytes) => {
+                    let room = MAX_MODERATION_RESPONSE_CAPTURE_BYTES.saturating_sub(captured.len());
+                    captured.extend_from_slice(&bytes[..bytes.len().min(room)]);
+                    yield Ok::<Bytes, axum::Error>(bytes);
+                },
+                Err(err) => {
+                    yield Err(err);
+                    return;
+                },
+            }
+        }
+        tokio::spawn(async move {
+            let text = response_text(&captured);
+            let findings = policy.inspect_text(&text);
+            let verdict = if policy.classifier_enabled { classify(&text).await } else { None };
+            if let Some((findings, verdict)) = moderation_hit(findings, verdict) {
+                handle_hit(
+                    route_store.as_ref(),
+                    &context,
+                    "response",
+                    policy.action,
+                    findings,
+                    verdict,
+                )
+                .await;
+            }
+        });
+    };
+    Response::from_parts(parts, Body::from_stream(stream))
+}
+
+/// Ask the configured classifier about `text`. `None` when no classifier is
+/// configured or it did not answer usably.
+async fn classify(text: &str) -> Option<ClassifierVerdict> {
+    let classifier = ModerationClassifier::configured()?;
+    if text.trim().is_empty() {
+        return None;
+    }
+    let result = async {
+        let response = DEFAULT_PROVIDER_CLIENT
+            .post(&classifier.url)
+            .timeout(classifier.timeout)
+            .json(&serde_json::json!({ "input": text }))
+            .send()
+            .await?
+            .error_for_status()?;
+        anyhow::Ok(response.json::<Value>().await?)
+    }
+    .await;
+    match result.map(|value| parse_classifier_verdict(&value)) {
+        Ok(Some(verdict)) => Some(verdict),
+        Ok(None) => {
+            tracing::warn!("moderation classifier returned an unrecognized answer; ignoring");
+            None
+        },
+        Err(err) => {
+            tracing::warn!(
+                error = %format!("{err:#}"),
+                "moderation classifier request failed; ignoring"
+            );
+            None
+        },
+    }
+}
+
+fn parse_classifier_verdict(value: &Value) -> Option<ClassifierVerdict> {
+    let results = value.get("results")?.as_array()?;
+    let mut verdict = ClassifierVerdict::default();
+    for result in results {
+        verdict.flagged |= result.get("flagged")?.as_bool()?;
+        if let Some(categories) = result.get("categories").and_then(Value::as_object) {
+            verdict.labels.extend(
+                categories
+                    .iter()
+                    .filter(|(_, flagged)| flagged.as_bool() == Some(true))
+                    .map(|(label, _)| label.clone()),
+            );
+        }
+        let top_score = result
+            .get("category_scores")
+            .and_then(Value::as_object)
+            .into_iter()
+            .flat_map(|scores| scores.values().filter_map(Value::as_f64))
+            .reduce(f64::max);
+        verdict.score = match (verdict.score, top_score) {
+            (Some(current), Some(score)) => Some(current.max(score)),
+            (current, score) => current.or(score),
+        };
+    }
+    verdict.labels.sort();
+    verdict.labels.dedup();
+    Some(verdict)
+}
+
+fn moderation_blocked_response(key: &AuthenticatedKey, path: &str) -> Response {
+    if ProviderType::from_storage_str(&key.provider_type) == Some(ProviderType::Kiro) {
+        kiro_json_error(
+            StatusCode::BAD_REQUEST,
+            "invalid_request_error",
+            MODERATION_BLOCKED_MESSAGE,
+        )
+    } else {
+        codex_surface_error_response(path, StatusCode::BAD_REQUEST, MODERATION_BLOCKED_MESSAGE)
+    }
+}
+
+fn moderation_unavailable(key: &AuthenticatedKey, path: &str, err: &anyhow::Error) -> Response {
+    tracing::error!(
+        key_id = %key.key_id,
+        error = %format!("{err:#}"),
+        "failed to resolve moderation policy; rejecting request"
+    );
+    let message = "content moderation policy is unavailable";
+    if ProviderType::from_storage_str(&key.provider_type) == Some(ProviderType::Kiro) {
+        kiro_json_error(StatusCode::SERVICE_UNAVAILABLE, "api_error", message)
+    } else {
+        codex_surface_error_response(path, StatusCode::SERVICE_UNAVAILABLE, message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use llm_access_core::store::{
+        KeyModerationPolicy, ProviderCodexAuthUpdate, ProviderCodexRoute, ProviderKiroAuthUpdate,
+        ProviderKiroRoute,
+    };
+    use serde_json::json;
+
+    use super::*;
+    use crate::provider::{tests::NoopControlStore, ProviderDispatchDeps, ProviderDispatcher};
+
+    #[test]
+    fn classifier_verdict_reads_openai_moderation_shape() {
+        let verdict = parse_classifier_verdict(&json!({
+            "results": [{
+                "flagged": true,
+                "categories": { "violence": true, "hate": false },
+                "category_scores": { "violence": 0.91, "hate": 0.02 },
+            }]
+        }))
+        .expect("verdict");
+        assert_eq!(verdict, ClassifierVerdict {
+            flagged: true,
+            labels: vec!["violence".to_string()],
+            score: Some(0.91),
+        });
+        assert!(parse_classifier_verdict(&json!({ "flagged": true })).is_none());
+    }
+
+    struct ModerationRouteStore {
+        policy: KeyModerationPolicy,
+        events: Mutex<Vec<NewModerationEvent>>,
+    }
+
+    #[async_trait]
+    impl ProviderRouteStore for ModerationRouteStore {
+        async fn resolve_codex_route(
+            &self,
+            _key: &AuthenticatedKey,
+        ) -> anyhow::Result<Option<ProviderCodexRoute>> {
+            Ok(None)
+        }
+
+        async fn resolve_codex_account_route(
+            &self,
+            _account_name: &str,
+        ) -> anyhow::Result<Option<ProviderCodexRoute>> {
+            Ok(None)
+        }
+
+        async fn resolve_kiro_route(
+            &self,
+            _key: &AuthenticatedKey,
+        ) -> anyhow::Result<Option<ProviderKiroRoute>> {
+            Ok(None)
+        }
+
+        async fn save_kiro_auth_update(
+            &self,
+            _update: ProviderKiroAuthUpdate,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn save_codex_auth_update(
+            &self,
+            _update: ProviderCodexAuthUpdate,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn resolve_moderation_policy(
+            &self,
+            _key: &AuthenticatedKey,
+        ) -> anyhow::Result<Option<KeyModerationPolicy>> {
+            Ok(Some(self.policy.clone()))
+        }
+
+        async fn record_moderation_event(&self, event: NewModerationEvent) -> anyhow::Result<()> {
+            self.events.lock().expect("events mutex").push(event);
+            Ok(())
+        }
+    }
+
+    struct EchoDispatcher;
+
+    #[async_trait]
+    impl ProviderDispatcher for EchoDispatcher {
+        async fn dispatch(
+            &self,
+            _key: AuthenticatedKey,
+            _request: Request<Body>,
+            _deps: ProviderDispatchDeps,
+        ) -> Response {
+            (StatusCode::OK, r#"{"output_text":"the secret recipe"}"#).into_response()
+        }
+    }
+
+    fn key() -> AuthenticatedKey {
+        AuthenticatedKey {
+            key_id: "key-1".to_string(),
+            key_name: "moderated".to_string(),
+            provider_type: "codex".to_string(),
+            protocol_family: "openai".to_string(),
+            status: "active".to_string(),
+            quota_billable_limit: 1_000_000,
+            billable_tokens_used: 0,
+            parent_remaining_billable: None,
+            parent_key_id: None,
+        }
+    }
+
+    fn request(prompt: &str) -> Request<Body> {
+        Request::builder()
+            .method(Method::POST)
+            .uri("/v1/responses")
+            .header("x-forwarded-for", "203.0.113.9")
+            .body(Body::from(json!({ "model": "gpt-5", "input": prompt }).to_string()))
+            .expect("request")
+    }
+
+    fn state(policy: KeyModerationPolicy) -> (ProviderState, Arc<ModerationRouteStore>) {
+        let store = Arc::new(ModerationRouteStore {
+            policy,
+            events: Mutex::new(Vec::new()),
+        });
+        let state = ProviderState::with_dispatcher(
+            Arc::new(NoopControlStore),
+            store.clone(),
+            Arc::new(EchoDispatcher),
+        );
+        (state, store)
+    }
+
+    #[tokio::test]
+    async fn block_rejects_and_flag_forwards_with_queued_events() {
+        let (blocking, store) = state(KeyModerationPolicy {
+            action: ModerationAction::Block,
+            keywords: vec!["Forbidden".to_string()],
+            ..KeyModerationPolicy::default()
+        });
+        let response = dispatch_with_moderation(&blocking, key(), request("a forbidden ask")).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let events = store.events.lock().expect("events mutex").clone();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].action, "block");
+        assert_eq!(events[0].matched_rules, vec!["keyword:Forbidden"]);
+        assert_eq!(events[0].model.as_deref(), Some("gpt-5"));
+        assert_eq!(events[0].client_ip, "203.0.113.9");
+
+        let clean = dispatch_with_moderation(&blocking, key(), request("hello")).await;
+        assert_eq!(clean.status(), StatusCode::OK);
+        assert_eq!(store.events.lock().expect("events mutex").len(), 1);
+
+        let (flagging, store) = state(KeyModerationPolicy {
+            keywords: vec!["secret".to_string()],
+            moderate_responses: true,
+            ..KeyModerationPolicy::default()
+        });
+        let response = dispatch_with_moderation(&flagging, key(), request("hello")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        assert!(String::from_utf8_lossy(&body).contains("secret recipe"));
+        for _ in 0..50 {
+            if !store.events.lock().expect("events mutex").is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let events = store.events.lock().expect("events mutex").clone();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].direction, "response");
+        assert_eq!(events[0].action, "flag");
+    }
+}
@@ -0,0 +1,911 @@
+//! OpenAI-compatible upstream channel dispatch for Codex-owned keys.
+
+use std::{
+    collections::BTreeMap,
+    io,
+    sync::{LazyLock, Mutex},
+    time::Instant,
+};
+
+use async_stream::stream;
+use axum::{
+    body::{to_bytes, Body, Bytes},
+    http::{header, HeaderMap, Method, Request, Response, StatusCode, Uri, Version},
+    response::IntoResponse,
+};
+use futures_util::StreamExt;
+use llm_access_anthropic_pool::{SmoothWeightedRoundRobin, WeightedChannel};
+use llm_access_codex::{response::extract_usage_from_bytes, types::UsageBreakdown};
+use llm_access_core::{
+    provider::{ProtocolFamily, ProviderType},
+    store::{
+        self as core_store, AuthenticatedKey, OpenAiUpstreamChannelUsageDelta,
+        ProviderOpenAiUpstreamRoute,
+    },
+    transform::RequestTransformDialect,
+    usage::UsageEvent,
+};
+use llm_access_openai_pool::{apply_openai_auth_headers, build_endpoint_url, FORWARDED_ENDPOINTS};
+use serde_json::{json, Value};
+
+use super::{
+    client::openai_upstream_client,
+    codex_auth::normalized_codex_gateway_path,
+    codex_sse::missing_codex_usage,
+    errors::codex_surface_error_response,
+    limiter::{codex_key_limit_response, try_acquire_key_permit},
+    request_transform::{
+        record_request_transforms, request_transform_unavailable, resolve_request_transform,
+        transform_json_body,
+    },
+    usage_meta::{
+        capture_client_request_body_json, capture_error_bytes, capture_error_message,
+        capture_upstream_request_body_json, captured_body_json,
+    },
+    util::{clamp_duration_ms, clamp_u64_to_i64, now_millis},
+    ProviderDispatchDeps, ProviderUsageMetadata, MAX_PROVIDER_PROXY_BODY_BYTES,
+};
+
+static OPENAI_UPSTREAM_SCHEDULER: LazyLock<Mutex<SmoothWeightedRoundRobin>> =
+    LazyLock::new(|| Mutex::new(SmoothWeightedRoundRobin::default()));
+const MAX_OPENAI_UPSTREAM_RESPONSE_BYTES: usize = MAX_PROVIDER_PROXY_BODY_BYTES;
+
+pub(super) enum OpenAiUpstreamDispatchOutcome {
+    Handled(axum::response::Response),
+    Fallback(Request<Body>),
+}
+
+#[derive(Clone)]
+struct ReplayableRequest {
+    method: Method,
+    uri: Uri,
+    version: Version,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+impl ReplayableRequest {
+    fn rebuild(&self) -> Request<Body> {
+        let mut request = Request::builder()
+            .method(self.method.clone())
+            .uri(self.uri.clone())
+            .version(self.version)
+            .body(Body::from(self.body.clone()))
+            .expect("replayable provider request should build");
+        *request.headers_mut() = self.headers.clone();
+        request
+    }
+}
+
+struct OpenAiUpstreamDispatchContext<'a> {
+    key: &'a AuthenticatedKey,
+    endpoint: &'static str,
+    public_path: &'a str,
+    model: &'a str,
+    payload: &'a Value,
+    request_headers: &'a HeaderMap,
+    request_transforms: &'a [String],
+    deps: &'a ProviderDispatchDeps,
+}
+
+#[derive(Clone)]
+struct OpenAiUpstreamUsageContext {
+    key: AuthenticatedKey,
+    public_path: String,
+    model: String,
+    mapped_model: Option<String>,
+    deps: ProviderDispatchDeps,
+}
+
+pub(super) async fn maybe_dispatch_openai_upstream_pool(
+    key: AuthenticatedKey,
+    request: Request<Body>,
+    deps: ProviderDispatchDeps,
+) -> OpenAiUpstreamDispatchOutcome {
+    let path = request.uri().path().to_string();
+    let resolution = match deps
+        .route_store
+        .resolve_openai_upstream_resolution(&key)
+        .await
+    {
+        Ok(resolution) => resolution,
+        Err(err) => {
+            tracing::warn!(
+                key_id = %key.key_id,
+                error = %err,
+                "openai upstream route resolution failed"
+            );
+            return OpenAiUpstreamDispatchOutcome::Handled(codex_surface_error_response(
+                &path,
+                StatusCode::SERVICE_UNAVAILABLE,
+                "OpenAI-compatible upstream route resolution failed",
+            ));
+        },
+    };
+    let mode = resolution.pool_mode;
+    if mode == core_store::OPENAI_UPSTREAM_POOL_MODE_DISABLED {
+        return OpenAiUpstreamDispatchOutcome::Fallback(request);
+    }
+    let only = mode == core_store::OPENAI_UPSTREAM_POOL_MODE_ONLY;
+    let routes = resolution.routes;
+
+    let Some(endpoint) = forwarded_openai_endpoint(&path) else {
+        if !only {
+            return OpenAiUpstreamDispatchOutcome::Fallback(request);
+        }
+        if normalized_codex_gateway_path(&path) == Some("/v1/models")
+            && request.method() == Method::GET
+        {
+            return OpenAiUpstreamDispatchOutcome::Handled(
+                axum::Json(openai_upstream_models_response(&routes)).into_response(),
+            );
+        }
+        return OpenAiUpstreamDispatchOutcome::Handled(codex_surface_error_response(
+            &path,
+            StatusCode::NOT_FOUND,
+            "endpoint is not available on OpenAI-compatible upstream channels",
+        ));
+    };
+    if request.method() != Method::POST {
+        return if only {
+            OpenAiUpstreamDispatchOutcome::Handled(codex_surface_error_response(
+                &path,
+                StatusCode::METHOD_NOT_ALLOWED,
+                "unsupported method",
+            ))
+        } else {
+            OpenAiUpstreamDispatchOutcome::Fallback(request)
+        };
+    }
+    if routes.is_empty() {
+        return if only {
+            OpenAiUpstreamDispatchOutcome::Handled(codex_surface_error_response(
+                &path,
+                StatusCode::SERVICE_UNAVAILABLE,
+                "OpenAI-compatible upstream route is not configured",
+            ))
+        } else {
+            OpenAiUpstreamDispatchOutcome::Fallback(request)
+        };
+    }
+
+    let method = request.method().clone();
+    let uri = request.uri().clone();
+    let version = request.version();
+    let headers = request.headers().clone();
+    let body_read_started = Instant::now();
+    let body = match to_bytes(request.into_body(), MAX_PROVIDER_PROXY_BODY_BYTES).await {
+        Ok(body) => body,
+        Err(_) => {
+            return OpenAiUpstreamDispatchOutcome::Handled(codex_surface_error_response(
+                &path,
+                StatusCode::BAD_REQUEST,
+                "request body is too large",
+            ))
+        },
+    };
+    let replay = ReplayableRequest {
+        method,
+        uri,
+        version,
+        headers,
+        body,
+    };
+    let mut usage_meta = ProviderUsageMetadata::from_request_parts(
+        &replay.method,
+        &replay.uri,
+        &replay.headers,
+        &deps.geoip,
+    )
+    .await
+    .with_request_body(&replay.body, clamp_duration_ms(body_read_started.elapsed()));
+    capture_client_request_body_json(&mut usage_meta, &replay.body);
+
+    // `replay.body` stays untouched for the Codex fallback, which applies the
+    // same transform itself.
+    let request_transform = match resolve_request_transform(deps.route_store.as_ref(), &key).await {
+        Ok(transform) => transform,
+        Err(err) => {
+            let message = request_transform_unavailable(&key, &err);
+            return OpenAiUpstreamDispatchOutcome::Handled(codex_surface_error_response(
+                &path,
+                StatusCode::SERVICE_UNAVAILABLE,
+                &message,
+            ));
+        },
+    };
+    let dialect = if endpoint == "responses" {
+        RequestTransformDialect::Responses
+    } else {
+        RequestTransformDialect::OpenAiChat
+    };
+    let (transformed_body, request_transforms) =
+        transform_json_body(request_transform.as_deref(), dialect, &replay.body);
+    let parse_started = Instant::now();
+    let payload = serde_json::from_slice::<Value>(&transformed_body)
+        .ok()
+        .filter(Value::is_object);
+    let model = payload
+        .as_ref()
+        .and_then(|value| value.get("model"))
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string);
+    let (Some(payload), Some(model)) = (payload, model) else {
+        return if only {
+            OpenAiUpstreamDispatchOutcome::Handled(codex_surface_error_response(
+                &path,
+                StatusCode::BAD_REQUEST,
+                "request body must be a JSON object with a model",
+            ))
+        } else {
+            OpenAiUpstreamDispatchOutcome::Fallback(replay.rebuild())
+        };
+    };
+    usage_meta.mark_pre_handler_done(clamp_duration_ms(parse_started.elapsed()));
+
+    let routes = routes
+        .into_iter()
+        .filter(|route| {
+            let mapped_model = mapped_model_name(&route.model_name_map_json, &model);
+            route.serves_model(mapped_model.as_deref().unwrap_or(&model))
+        })
+        .collect::<Vec<_>>();
+    if routes.is_empty() {
+        return if only {
+            OpenAiUpstreamDispatchOutcome::Handled(codex_surface_error_response(
+                &path,
+                StatusCode::NOT_FOUND,
+                &format!("no OpenAI-compatible upstream channel serves model `{model}`"),
+            ))
+        } else {
+            OpenAiUpstreamDispatchOutcome::Fallback(replay.rebuild())
+        };
+    }
+
+    let context = OpenAiUpstreamDispatchContext {
+        key: &key,
+        endpoint,
+        public_path: &path,
+        model: &model,
+        payload: &payload,
+        request_headers: &replay.headers,
+        request_transforms: &request_transforms,
+        deps: &deps,
+    };
+    let mut last_failure: Option<axum::response::Response> = None;
+    for route in order_routes_for_request(routes) {
+        let response = dispatch_one_route(&context, &route, &mut usage_meta).await;
+        if !is_retryable_openai_upstream_status(response.status()) {
+            return OpenAiUpstreamDispatchOutcome::Handled(response);
+        }
+        last_failure = Some(response);
+        usage_meta.mark_failover();
+        usage_meta.error_message = None;
+        usage_meta.error_class = None;
+        usage_meta.error_body = None;
+        usage_meta.response_body = None;
+    }
+
+    if only {
+        OpenAiUpstreamDispatchOutcome::Handled(last_failure.unwrap_or_else(|| {
+            codex_surface_error_response(
+                &path,
+                StatusCode::SERVICE_UNAVAILABLE,
+                "OpenAI-compatible upstream route is not available",
+            )
+        }))
+    } else {
+        OpenAiUpstreamDispatchOutcome::Fallback(replay.rebuild())
+    }
+}
+
+/// Map a public gateway path onto an endpoint forwarded to channels, relative
+/// to the channel's versioned base URL.
+fn forwarded_openai_endpoint(path: &str) -> Option<&'static str> {
+    let relative = match path
+        .strip_prefix("/api/llm-gateway")
+        .or_else(|| path.strip_prefix("/api/codex-gateway"))
+    {
+        Some(alias) => alias.strip_prefix("/v1").unwrap_or(alias),
+        None => path.strip_prefix("/v1")?,
+    };
+    let relative = relative.strip_prefix('/')?;
+    FORWARDED_ENDPOINTS
+        .iter()
+        .copied()
+        .find(|endpoint| *endpoint == relative)
+}
+
+/// Models list for `only` keys, built from the channels' latest `/models`
+/// refresh instead of the Codex catalog.
+fn openai_upstream_models_response(routes: &[ProviderOpenAiUpstreamRoute]) -> Value {
+    let mut data = Vec::new();
+    let mut seen = std::collections::BTreeSet::new();
+    for route in routes {
+        for model in &route.models {
+            if seen.insert(model.as_str()) {
+                data.push(json!({
+                    "id": model,
+                    "object": "model",
+                    "owned_by": route.channel_name,
+                }));
+            }
+        }
+    }
+    json!({ "object": "list", "data": data })
+}
+
+fn order_routes_for_request(
+    mut routes: Vec<ProviderOpenAiUpstreamRoute>,
+) -> Vec<ProviderOpenAiUpstreamRoute> {
+    let mut ordered = Vec::with_capacity(routes.len());
+    if let Some(route) = select_weighted_route_global(&mut routes) {
+        ordered.push(route);
+    }
+    let mut local_scheduler = SmoothWeightedRoundRobin::default();
+    while let Some(route) = select_weighted_route(&mut local_scheduler, &mut routes) {
+        ordered.push(route);
+    }
+    ordered
+}
+
+fn select_weighted_route_global(
+    routes: &mut Vec<ProviderOpenAiUpstreamRoute>,
+) -> Option<ProviderOpenAiUpstreamRoute> {
+    let mut scheduler = OPENAI_UPSTREAM_SCHEDULER
+        .lock()
+        .expect("openai upstream scheduler lock");
+    select_weighted_route(&mut scheduler, routes)
+}
+
+fn select_weighted_route(
+    scheduler: &mut SmoothWeightedRoundRobin,
+    routes: &mut Vec<ProviderOpenAiUpstreamRoute>,
+) -> Option<ProviderOpenAiUpstreamRoute> {
+    if routes.is_empty() {
+        return None;
+    }
+    let weighted = routes
+        .iter()
+        .map(|route| WeightedChannel {
+            name: route.channel_name.clone(),
+            weight: route.weight,
+        })
+        .collect::<Vec<_>>();
+    let selected_name = scheduler.select(&weighted).map(str::to_string);
+    let index = selected_name
+        .and_then(|name| routes.iter().position(|route| route.channel_name == name))
+        .unwrap_or(0);
+    Some(routes.remove(index))
+}
+
+async fn dispatch_one_route(
+    context: &OpenAiUpstreamDispatchContext<'_>,
+    route: &ProviderOpenAiUpstreamRoute,
+    usage_meta: &mut ProviderUsageMetadata,
+) -> axum::response::Response {
+    let _key_permit = match try_acquire_key_permit(
+        &context.deps.request_limiter,
+        context.key,
+        route.request_max_concurrency,
+        route.request_min_start_interval_ms,
+    ) {
+        Ok(permit) => permit,
+        Err(rejection) => return codex_key_limit_response(&rejection),
+    };
+    let _channel_permit = match context.deps.request_limiter.try_acquire(
+        format!("openai-upstream-channel:{}", route.channel_name),
+        Some(route.channel_max_concurrency),
+        Some(route.channel_min_start_interval_ms),
+    ) {
+        Ok(permit) => permit,
+        Err(rejection) => return codex_key_limit_response(&rejection),
+    };
+
+    let upstream_url = match build_endpoint_url(&route.base_url, context.endpoint) {
+        Ok(url) => url,
+        Err(err) => {
+            tracing::warn!(
+                channel = %route.channel_name,
+                error = %err,
+                "openai upstream url is invalid"
+            );
+            return codex_surface_error_response(
+                context.public_path,
+                StatusCode::SERVICE_UNAVAILABLE,
+                "OpenAI-compatible upstream URL is invalid",
+            );
+        },
+    };
+    let client = match openai_upstream_client(route.proxy.as_ref()) {
+        Ok(client) => client,
+        Err(err) => {
+            tracing::warn!(
+                channel = %route.channel_name,
+                error = %err,
+                "openai upstream client build failed"
+            );
+            return codex_surface_error_response(
+                context.public_path,
+                StatusCode::SERVICE_UNAVAILABLE,
+                "OpenAI-compatible upstream client build failed",
+            );
+        },
+    };
+    usage_meta.routing_diagnostics_json = Some(
+        json!({
+            "upstream_pool": "openai_compatible",
+            "channel_name": route.channel_name,
+            "pool_mode": route.pool_mode_at_event,
+        })
+        .to_string(),
+    );
+    record_request_transforms(usage_meta, context.request_transforms);
+    let (route_payload, mapped_model) =
+        build_route_payload(&route.model_name_map_json, context.endpoint, context.payload);
+    let upstream_body = Bytes::from(route_payload.to_string());
+    capture_upstream_request_body_json(usage_meta, &upstream_body);
+    let mut request = apply_openai_auth_headers(client.post(upstream_url), &route.api_key)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(upstream_body.clone());
+    for header_name in ["accept", "user-agent"] {
+        if let Some(value) = context.request_headers.get(header_name) {
+            request = request.header(header_name, value.clone());
+        }
+    }
+    let usage_context = OpenAiUpstreamUsageContext {
+        key: context.key.clone(),
+        public_path: context.public_path.to_string(),
+        model: context.model.to_string(),
+        mapped_model,
+        deps: context.deps.clone(),
+    };
+    let response = match request.send().await {
+        Ok(response) => response,
+        Err(err) => {
+            capture_error_message(usage_meta, &err.to_string());
+            usage_meta.capture_error_class("upstream_transport_error");
+            let status = StatusCode::BAD_GATEWAY;
+            record_openai_upstream_usage(
+                &usage_context,
+                route,
+                status,
+                missing_codex_usage(),
+                usage_meta,
+                Some(upstream_body),
+            )
+            .await;
+            return codex_surface_error_response(
+                context.public_path,
+                status,
+                "OpenAI-compatible upstream request failed",
+            );
+        },
+    };
+    usage_meta.mark_upstream_headers();
+    let status = response.status();
+    let headers = response.headers().clone();
+    if status.is_success() && is_event_stream(&headers) {
+        return build_streaming_downstream_response(
+            status,
+            &headers,
+            response,
+            usage_context,
+            route.clone(),
+            usage_meta.clone(),
+            upstream_body,
+        );
+    }
+
+    let body = match read_limited_response_body(response).await {
+        Ok(body) => body,
+        Err(err) => {
+            capture_error_message(usage_meta, &err);
+            usage_meta.capture_error_class("upstream_body_error");
+            let status = StatusCode::BAD_GATEWAY;
+            record_openai_upstream_usage(
+                &usage_context,
+                route,
+                status,
+                missing_codex_usage(),
+                usage_meta,
+                Some(upstream_body),
+            )
+            .await;
+            return codex_surface_error_response(
+                context.public_path,
+                status,
+                "OpenAI-compatible upstream response read failed",
+            );
+        },
+    };
+    usage_meta.mark_post_headers_body();
+    usage_meta.mark_stream_finish();
+    let usage = if status.is_success() {
+        extract_usage_from_bytes(&body).unwrap_or_else(missing_codex_usage)
+    } else {
+        capture_error_bytes(usage_meta, &body);
+        usage_meta.capture_error_class("upstream_error");
+        missing_codex_usage()
+    };
+    record_openai_upstream_usage(
+        &usage_context,
+        route,
+        status,
+        usage,
+        usage_meta,
+        Some(upstream_body),
+    )
+    .await;
+    build_downstream_response(status, &headers, body)
+}
+
+/// Apply the key's model name map and, for streamed completions, ask the
+/// channel to report usage in the final chunk so the request can be billed.
+fn build_route_payload(
+    model_name_map_json: &str,
+    endpoint: &str,
+    payload: &Value,
+) -> (Value, Option<String>) {
+    let mut payload = payload.clone();
+    let mapped_model = payload
+        .get("model")
+        .and_then(Value::as_str)
+        .and_then(|model| mapped_model_name(model_name_map_json, model));
+    let Some(object) = payload.as_object_mut() else {
+        return (payload, mapped_model);
+    };
+    if let Some(mapped_model) = mapped_model.as_deref() {
+        object.insert("model".to_string(), Value::from(mapped_model));
+    }
+    let streamed = object.get("stream").and_then(Value::as_bool) == Some(true);
+    if streamed
+        && matches!(endpoint, "chat/completions" | "completions")
+        && !object.contains_key("stream_options")
+    {
+        object.insert("stream_options".to_string(), json!({ "include_usage": true }));
+    }
+    (payload, mapped_model)
+}
+
+fn mapped_model_name(model_name_map_json: &str, model: &str) -> Option<String> {
+    serde_json::from_str::<BTreeMap<String, String>>(model_name_map_json)
+        .ok()?
+        .remove(model)
+}
+
+async fn read_limited_response_body(response: reqwest::Response) -> Result<Bytes, String> {
+    if response
+        .content_length()
+        .is_some_and(|len| len > MAX_OPENAI_UPSTREAM_RESPONSE_BYTES as u64)
+    {
+        return Err("OpenAI-compatible upstream response is too large".to_string());
+    }
+    let mut body = Vec::new();
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk
+            .map_err(|err| format!("failed to read OpenAI-compatible upstream body: {err}"))?;
+        if body.len().saturating_add(chunk.len()) > MAX_OPENAI_UPSTREAM_RESPONSE_BYTES {
+            return Err("OpenAI-compatible upstream response is too large".to_string());
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(Bytes::from(body))
+}
+
+fn is_event_stream(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| {
+            value
+                .split(';')
+                .any(|part| part.trim().eq_ignore_ascii_case("text/event-stream"))
+        })
+}
+
+fn is_retryable_openai_upstream_status(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+fn build_streaming_downstream_response(
+    status: StatusCode,
+    headers: &HeaderMap,
+    response: reqwest::Response,
+    usage_context: OpenAiUpstreamUsageContext,
+    route: ProviderOpenAiUpstreamRoute,
+    mut usage_meta: ProviderUsageMetadata,
+    upstream_body: Bytes,
+) -> axum::response::Response {
+    let mut body_stream = response.bytes_stream();
+    let body = stream! {
+        let mut usage = None;
+        let mut pending_line = String::new();
+        while let Some(chunk_result) = body_stream.next().await {
+            let chunk = match chunk_result {
+                Ok(chunk) => chunk,
+                Err(err) => {
+                    capture_error_message(&mut usage_meta, &err.to_string());
+                    usage_meta.capture_error_class("upstream_body_error");
+                    usage_meta.mark_stream_internal_incomplete();
+                    record_openai_upstream_usage(
+                        &usage_context,
+                        &route,
+                        StatusCode::BAD_GATEWAY,
+                        missing_codex_usage(),
+                        &usage_meta,
+                        Some(upstream_body.clone()),
+                    )
+                    .await;
+                    yield Err(io::Error::other(format!(
+                        "failed to read OpenAI-compatible upstream stream: {err}"
+                    )));
+                    return;
+                },
+            };
+            usage_meta.observe_stream_write(chunk.len(), None);
+            observe_openai_sse_chunk(&chunk, &mut pending_line, &mut usage);
+            yield Ok::<Bytes, io::Error>(chunk);
+        }
+        observe_openai_sse_tail(&mut pending_line, &mut usage);
+        usage_meta.mark_post_headers_body();
+        usage_meta.mark_stream_completed_cleanly();
+        record_openai_upstream_usage(
+            &usage_context,
+            &route,
+            status,
+            usage.unwrap_or_else(missing_codex_usage),
+            &usage_meta,
+            Some(upstream_body),
+        )
+        .await;
+    };
+    let mut builder = Response::builder().status(status);
+    for (name, value) in headers {
+        if is_hop_by_hop_header(name.as_str()) || *name == header::CONTENT_LENGTH {
+            continue;
+        }
+        builder = builder.header(name, value);
+    }
+    builder
+        .body(Body::from_stream(body))
+        .unwrap_or_else(|_| StatusCode::BAD_GATEWAY.into_response())
+}
+
+fn observe_openai_sse_chunk(
+    chunk: &Bytes,
+    pending_line: &mut String,
+    usage: &mut Option<UsageBreakdown>,
+) {
+    pending_line.push_str(&String::from_utf8_lossy(chunk));
+    while let Some(line_end) = pending_line.find('\n') {
+        let mut line = pending_line[..line_end].to_string();
+        if line.ends_with('\r') {
+            line.pop();
+        }
+        observe_openai_sse_line(&line, usage);
+        pending_line.drain(..=line_end);
+    }
+}
+
+fn observe_openai_sse_tail(pending_line: &mut String, usage: &mut Option<UsageBreakdown>) {
+    if pending_line.is_empty() {
+        return;
+    }
+    let line = std::mem::take(pending_line);
+    observe_openai_sse_line(&line, usage);
+}
+
+fn observe_openai_sse_line(line: &str, usage: &mut Option<UsageBreakdown>) {
+    let Some(data) = line.trim_start().strip_prefix("data:") else {
+        return;
+    };
+    let data = data.trim();
+    if data.is_empty() || data == "[DONE]" {
+        return;
+    }
+    if let Some(observed) = extract_usage_from_bytes(data.as_bytes()) {
+        *usage = Some(observed);
+    }
+}
+
+async fn record_openai_upstream_usage(
+    context: &OpenAiUpstreamUsageContext,
+    route: &ProviderOpenAiUpstreamRoute,
+    status: StatusCode,
+    usage: UsageBreakdown,
+    meta: &ProviderUsageMetadata,
+    upstream_request_body_json: Option<Bytes>,
+) {
+    let billable_tokens = if usage.usage_missing { 0 } else { usage.billable_tokens() };
+    let used_at_ms = now_millis();
+    let event = UsageEvent {
+        event_id: format!("llm-usage-{}", uuid::Uuid::new_v4()),
+        created_at_ms: used_at_ms,
+        provider_type: ProviderType::Codex,
+        protocol_family: ProtocolFamily::OpenAi,
+        key_id: context.key.key_id.clone(),
+        key_name: context.key.key_name.clone(),
+        account_name: Some(route.channel_name.clone()),
+        account_group_id_at_event: route.account_group_id_at_event.clone(),
+        route_strategy_at_event: None,
+        request_method: meta.request_method.clone(),
+        request_url: meta.request_url.clone(),
+        endpoint: context.public_path.clone(),
+        model: Some(context.model.clone()),
+        mapped_model: context.mapped_model.clone(),
+        status_code: status.as_u16() as i64,
+        request_body_bytes: meta.request_body_bytes,
+        quota_failover_count: meta.quota_failover_count,
+        retry: meta.retry.clone(),
+        routing_diagnostics_json: meta.routing_diagnostics_json.clone(),
+        input_uncached_tokens: clamp_u64_to_i64(usage.input_uncached_tokens),
+        input_cached_tokens: clamp_u64_to_i64(usage.input_cached_tokens),
+        output_tokens: clamp_u64_to_i64(usage.output_tokens),
+        billable_tokens: clamp_u64_to_i64(billable_tokens),
+        credit_usage: None,
+        usage_missing: usage.usage_missing,
+        credit_usage_missing: true,
+        client_ip: meta.client_ip.clone(),
+        ip_region: meta.ip_region.clone(),
+        request_headers_json: meta.request_headers_json.clone(),
+        last_message_content: meta.last_message_content.clone(),
+        client_request_body_json: captured_body_json(&meta.client_request_body_json),
+        upstream_request_body_json: captured_body_json(&upstream_request_body_json),
+        full_request_json: None,
+        error_message: meta.error_message.clone(),
+        error_class: meta.error_class.clone(),
+        session_blocked: meta.session_blocked,
+        response_image_count: None,
+        error_body: meta.error_body.clone(),
+        response_body: meta.response_body.clone(),
+        timing: meta.to_timing(),
+        stream: meta.to_stream_details(),
+    };
+    if let Err(err) = context
+        .deps
+        .control_store
+        .apply_usage_rollup_owned(event)
+        .await
+    {
+        tracing::warn!(
+            key_id = %context.key.key_id,
+            channel = %route.channel_name,
+            error = %err,
+            "failed to record openai upstream usage event"
+        );
+    }
+    let delta = OpenAiUpstreamChannelUsageDelta {
+        input_uncached_tokens: usage.input_uncached_tokens,
+        input_cached_tokens: usage.input_cached_tokens,
+        output_tokens: usage.output_tokens,
+        billable_tokens,
+        usage_missing: usage.usage_missing,
+        used_at_ms,
+    };
+    if let Err(err) = context
+        .deps
+        .control_store
+        .record_openai_upstream_channel_usage(&route.channel_name, delta)
+        .await
+    {
+        tracing::warn!(
+            key_id = %context.key.key_id,
+            channel = %route.channel_name,
+            error = %err,
+            "failed to record openai upstream channel usage"
+        );
+    }
+}
+
+fn build_downstream_response(
+    status: StatusCode,
+    headers: &HeaderMap,
+    body: Bytes,
+) -> axum::response::Response {
+    let mut builder = Response::builder().status(status);
+    for (name, value) in headers {
+        if is_hop_by_hop_header(name.as_str()) {
+            continue;
+        }
+        builder = builder.header(name, value);
+    }
+    builder
+        .body(Body::from(body))
+        .unwrap_or_else(|_| StatusCode::BAD_GATEWAY.into_response())
+}
+
+fn is_hop_by_hop_header(name: &str) -> bool {
+    matches!(
+        name,
+        "connection"
+            | "keep-alive"
+            | "proxy-authenticate"
+            | "proxy-authorization"
+            | "te"
+            | "trailer"
+            | "transfer-encoding"
+            | "upgrade"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Bytes, http::StatusCode};
+    use serde_json::json;
+
+    use super::{
+        build_route_payload, forwarded_openai_endpoint, is_retryable_openai_upstream_status,
+        observe_openai_sse_chunk, observe_openai_sse_tail,
+    };
+
+    #[test]
+    fn maps_public_paths_onto_forwarded_endpoints() {
+        assert_eq!(forwarded_openai_endpoint("/v1/chat/completions"), Some("chat/completions"));
+        assert_eq!(forwarded_openai_endpoint("/v1/responses"), Some("responses"));
+        assert_eq!(forwarded_openai_endpoint("/api/llm-gateway/v1/embeddings"), Some("embeddings"));
+        assert_eq!(
+            forwarded_openai_endpoint("/api/codex-gateway/completions"),
+            Some("completions")
+        );
+        assert_eq!(forwarded_openai_endpoint("/v1/responses/compact"), None);
+        assert_eq!(forwarded_openai_endpoint("/v1/messages"), None);
+        assert_eq!(forwarded_openai_endpoint("/chat/completions"), None);
+    }
+
+    #[test]
+    fn route_payload_maps_model_and_requests_stream_usage() {
+        let payload = json!({"model": "public", "stream": true, "messages": []});
+
+        let (mapped, mapped_model) =
+            build_route_payload(r#"{"public":"qwen3-32b"}"#, "chat/completions", &payload);
+        assert_eq!(mapped_model.as_deref(), Some("qwen3-32b"));
+        assert_eq!(mapped["model"], "qwen3-32b");
+        assert_eq!(mapped["stream_options"]["include_usage"], true);
+        assert_eq!(payload["model"], "public");
+
+        let (unmapped, mapped_model) = build_route_payload("{}", "responses", &payload);
+        assert_eq!(mapped_model, None);
+        assert_eq!(unmapped["model"], "public");
+        assert!(unmapped.get("stream_options").is_none());
+    }
+
+    #[test]
+    fn collects_chat_completion_stream_usage_across_chunk_boundaries() {
+        let mut usage = None;
+        let mut pending_line = String::new();
+
+        observe_openai_sse_chunk(
+            &Bytes::from_static(
+                b"data: {\"choices\":[{\"delta\":{\"content\":\"hi\"}}]}\n\ndata: {\"choices\":[],",
+            ),
+            &mut pending_line,
+            &mut usage,
+        );
+        observe_openai_sse_chunk(
+            &Bytes::from_static(
+                b"\"usage\":{\"prompt_tokens\":12,\"completion_tokens\":3,\
+                  \"prompt_tokens_details\":{\"cached_tokens\":2}}}\n\ndata: [DONE]",
+            ),
+            &mut pending_line,
+            &mut usage,
+        );
+        observe_openai_sse_tail(&mut pending_line, &mut usage);
+
+        let usage = usage.expect("stream usage");
+        assert_eq!(usage.input_uncached_tokens, 10);
+        assert_eq!(usage.input_cached_tokens, 2);
+        assert_eq!(usage.output_tokens, 3);
+        assert!(!usage.usage_missing);
+    }
+
+    #[test]
+    fn classifies_openai_upstream_retryable_statuses() {
+        assert!(is_retryable_openai_upstream_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_retryable_openai_upstream_status(StatusCode::BAD_GATEWAY));
+        assert!(!is_retryable_openai_upstream_status(StatusCode::NOT_FOUND));
+    }
+}
@@ -0,0 +1,218 @@
+//! Offline re-conversion of captured usage events for `llm-access replay`.
+//!
+//! Only the deterministic part of each dispatch path is reproduced: request
+//! parsing, the converters and the upstream-shaping policies that do not
+//! depend on live route state. Key request transforms, per-route flags (spark
+//! mapping, request validation, cctest handling, remote media resolution) and
+//! session recovery are left out, so their effects show up as differences.
+
+use axum::{
+    body::Bytes,
+    http::{HeaderMap, HeaderName, HeaderValue, Method},
+};
+use llm_access_codex::request::{
+    align_responses_store_with_upstream, apply_codex_fast_policy,
+    inject_codex_resolved_session_into_request_body, prepare_gateway_request_from_bytes,
+};
+use llm_access_core::{provider::ProviderType, usage::UsageEvent};
+use llm_access_kiro::{
+    anthropic::{
+        converter::{convert_normalized_request_with_resolved_session, normalize_request},
+        types::MessagesRequest,
+        websearch,
+    },
+    wire::KiroRequest,
+};
+use serde_json::Value;
+
+use super::{
+    codex_auth::{codex_upstream_base_url, compute_codex_upstream_url},
+    kiro_media::strip_kiro_remote_media_sources,
+    kiro_model::{override_kiro_thinking_from_model_name, resolve_kiro_request_session},
+    MAX_PROVIDER_PROXY_BODY_BYTES,
+};
+
+/// Upstream path used for every converted Kiro generate request.
+const KIRO_GENERATE_PATH: &str = "/generateAssistantResponse";
+
+/// Upstream request rebuilt from a captured event.
+#[derive(Debug, Clone)]
+pub(crate) struct ReplayedUpstreamRequest {
+    /// Upstream path the payload would be posted to, relative to the provider
+    /// base URL.
+    pub(crate) path: String,
+    /// Upstream request body produced by the current converters.
+    pub(crate) body: Bytes,
+}
+
+/// Result of re-converting one captured event.
+#[derive(Debug, Clone)]
+pub(crate) enum ReplayOutcome {
+    /// The event was converted again.
+    Replayed(ReplayedUpstreamRequest),
+    /// The event cannot be replayed offline; the reason is operator-facing.
+    Skipped(&'static str),
+}
+
+/// Pointers whose values legitimately differ between two conversions of the
+/// same request.
+pub(crate) fn default_replay_ignore_pointers(
+    provider_type: ProviderType,
+) -> &'static [&'static str] {
+    match provider_type {
+        // Anchor-based recovery may swap in a previously seen conversation id.
+        ProviderType::Kiro => &["/conversationState/conversationId"],
+        ProviderType::Codex => &[],
+    }
+}
+
+/// Rebuild the upstream request for `event` with the current converters.
+pub(crate) fn replay_upstream_request(event: &UsageEvent) -> anyhow::Result<ReplayOutcome> {
+    let Some(client_body) = event.client_request_body_json.as_deref() else {
+        return Ok(ReplayOutcome::Skipped("no captured client request body"));
+    };
+    let headers = captured_request_headers(&event.request_headers_json)?;
+    match event.provider_type {
+        ProviderType::Codex => replay_codex_request(event, &headers, client_body),
+        ProviderType::Kiro => replay_kiro_request(event, &headers, client_body),
+    }
+}
+
+fn replay_codex_request(
+    event: &UsageEvent,
+    headers: &HeaderMap,
+    client_body: &str,
+) -> anyhow::Result<ReplayOutcome> {
+    let upstream_base = codex_upstream_base_url();
+    let codex_error = |err: llm_access_codex::error::CodexGatewayError| {
+        anyhow::anyhow!("codex request rejected ({}): {}", err.status, err.message)
+    };
+    let prepared = prepare_gateway_request_from_bytes(
+        &event.endpoint,
+        "",
+        Method::POST,
+        headers,
+        Bytes::copy_from_slice(client_body.as_bytes()),
+        MAX_PROVIDER_PROXY_BODY_BYTES,
+        None,
+    )
+    .map_err(codex_error)?;
+    let prepared = apply_codex_fast_policy(&prepared, true).map_err(codex_error)?;
+    let prepared =
+        align_responses_store_with_upstream(&prepared, &upstream_base).map_err(codex_error)?;
+    let prepared =
+        inject_codex_resolved_session_into_request_body(&prepared).map_err(codex_error)?;
+    let upstream_url = compute_codex_upstream_url(&upstream_base, &prepared.upstream_path);
+    let path = upstream_url
+        .strip_prefix(upstream_base.trim_end_matches('/'))
+        .unwrap_or(&prepared.upstream_path)
+        .to_string();
+    Ok(ReplayOutcome::Replayed(ReplayedUpstreamRequest {
+        path,
+        body: prepared.request_body,
+    }))
+}
+
+fn replay_kiro_request(
+    event: &UsageEvent,
+    headers: &HeaderMap,
+    client_body: &str,
+) -> anyhow::Result<ReplayOutcome> {
+    // Anthropic-pool and MCP websearch requests never reach the converter, and
+    // neither records a `conversationState` payload.
+    let recorded = event
+        .upstream_request_body_json
+        .as_deref()
+        .and_then(|body| serde_json::from_str::<Value>(body).ok());
+    let Some(recorded) = recorded.filter(|body| body.get("conversationState").is_some()) else {
+        return Ok(ReplayOutcome::Skipped("no captured kiro conversation payload"));
+    };
+    let mut payload = serde_json::from_str::<MessagesRequest>(client_body)
+        .map_err(|err| anyhow::anyhow!("failed to parse captured request JSON: {err}"))?;
+    // The usage model is the effective model, i.e. after route model mapping.
+    if let Some(model) = event.model.as_deref() {
+        payload.model = model.to_string();
+    }
+    if websearch::should_route_mcp_web_search(&payload) {
+        return Ok(ReplayOutcome::Skipped("mcp websearch request"));
+    }
+    websearch::remove_web_search_tools(&mut payload);
+    let resolved_session = resolve_kiro_request_session(headers, payload.metadata.as_ref());
+    strip_kiro_remote_media_sources(&mut payload);
+    override_kiro_thinking_from_model_name(&mut payload);
+    let normalized =
+        normalize_request(&payload).map_err(|err| anyhow::anyhow!("normalize failed: {err}"))?;
+    let conversion =
+        convert_normalized_request_with_resolved_session(normalized, true, resolved_session, true)
+            .map_err(|err| anyhow::anyhow!("conversion failed: {err}"))?;
+    let body = serde_json::to_vec(&KiroRequest {
+        conversation_state: conversion.conversation_state,
+        profile_arn: recorded
+            .get("profileArn")
+            .and_then(Value::as_str)
+            .map(ToString::to_string),
+    })?;
+    Ok(ReplayOutcome::Replayed(ReplayedUpstreamRequest {
+        path: KIRO_GENERATE_PATH.to_string(),
+        body: Bytes::from(body),
+    }))
+}
+
+/// Rebuild a header map from the captured `{"name": ["value", ...]}` JSON.
+fn captured_request_headers(raw: &str) -> anyhow::Result<HeaderMap> {
+    let captured = serde_json::from_str::<std::collections::BTreeMap<String, Vec<String>>>(raw)
+        .map_err(|err| anyhow::anyhow!("failed to parse captured request headers: {err}"))?;
+    let mut headers = HeaderMap::new();
+    for (name, values) in captured {
+        let Ok(name) = HeaderName::from_bytes(name.as_bytes()) else {
+            continue;
+        };
+        for value in values {
+            if let Ok(value) = HeaderValue::from_str(&value) {
+                headers.append(name.clone(), value);
+            }
+        }
+    }
+    Ok(headers)
+}
+
+#[cfg(test)]
+mod tests {
+    use llm_access_core::{provider::ProviderType, usage::UsageEvent};
+
+    use super::{captured_request_headers, replay_upstream_request, ReplayOutcome};
+
+    #[test]
+    fn captured_headers_keep_repeated_values_and_drop_invalid_names() {
+        let headers =
+            captured_request_headers(r#"{"x-a":["1","2"],"bad name":["x"],"session_id":["s"]}"#)
+                .expect("headers");
+        assert_eq!(headers.get_all("x-a").iter().count(), 2);
+        assert_eq!(headers.get("session_id").and_then(|v| v.to_str().ok()), Some("s"));
+        assert_eq!(headers.len(), 3);
+    }
+
+    #[test]
+    fn kiro_events_without_conversation_payload_are_skipped() {
+        let event = UsageEvent {
+            provider_type: ProviderType::Kiro,
+            endpoint: "/v1/messages".to_string(),
+            request_headers_json: "{}".to_string(),
+            client_request_body_json: Some(r#"{"model":"claude-sonnet-4","messages":[]}"#.into()),
+            upstream_request_body_json: Some(r#"{"model":"claude-sonnet-4"}"#.into()),
+            ..UsageEvent::default()
+        };
+        assert!(matches!(
+            replay_upstream_request(&event).expect("replay"),
+            ReplayOutcome::Skipped(_)
+        ));
+        let uncaptured = UsageEvent {
+            client_request_body_json: None,
+            ..event
+        };
+        assert!(matches!(
+            replay_upstream_request(&uncaptured).expect("replay"),
+            ReplayOutcome::Skipped("no captured client request body")
+        ));
+    }
+}
@@ -0,0 +1,100 @@
+//! Per-key request transform resolution and usage diagnostics.
+
+use std::sync::{Arc, LazyLock};
+
+use axum::body::Bytes;
+use llm_access_core::{
+    store::{AuthenticatedKey, ProviderRouteStore, RequestTransformPolicy},
+    transform::{CompiledRequestTransform, RequestTransformDialect},
+};
+use serde_json::{Map, Value};
+
+use super::{compiled_policy_cache::CompiledPolicyCache, ProviderUsageMetadata};
+
+static COMPILED_REQUEST_TRANSFORMS: LazyLock<
+    CompiledPolicyCache<RequestTransformPolicy, CompiledRequestTransform>,
+> = LazyLock::new(CompiledPolicyCache::new);
+
+/// Resolve and compile the merged group/key transform for `key`.
+pub(super) async fn resolve_request_transform(
+    route_store: &dyn ProviderRouteStore,
+    key: &AuthenticatedKey,
+) -> anyhow::Result<Option<Arc<CompiledRequestTransform>>> {
+    let Some(policy) = route_store.resolve_request_transform_policy(key).await? else {
+        return Ok(None);
+    };
+    COMPILED_REQUEST_TRANSFORMS
+        .get_or_compile(&key.key_id, &policy, CompiledRequestTransform::compile)
+        .map(Some)
+}
+
+/// Apply `transform` to a JSON body, returning a rewritten copy and the
+/// applied labels. Bodies that are not JSON are returned untouched so the
+/// caller's own parser reports the error.
+pub(super) fn transform_json_body(
+    transform: Option<&CompiledRequestTransform>,
+    dialect: RequestTransformDialect,
+    body: &Bytes,
+) -> (Bytes, Vec<String>) {
+    let Some(transform) = transform else {
+        return (body.clone(), Vec::new());
+    };
+    let Ok(mut value) = serde_json::from_slice::<Value>(body) else {
+        return (body.clone(), Vec::new());
+    };
+    let applied = transform.apply(dialect, &mut value);
+    if applied.is_empty() {
+        return (body.clone(), applied);
+    }
+    match serde_json::to_vec(&value) {
+        Ok(rewritten) => (Bytes::from(rewritten), applied),
+        Err(_) => (body.clone(), Vec::new()),
+    }
+}
+
+/// Log a fail-closed transform resolution error and return the client message.
+pub(super) fn request_transform_unavailable(key: &AuthenticatedKey, err: &anyhow::Error) -> String {
+    tracing::error!(
+        key_id = %key.key_id,
+        error = %format!("{err:#}"),
+        "failed to resolve request transform policy"
+    );
+    "request transform policy is unavailable".to_string()
+}
+
+/// Record which transforms changed the request in the routing diagnostics.
+pub(super) fn record_request_transforms(meta: &mut ProviderUsageMetadata, applied: &[String]) {
+    if applied.is_empty() {
+        return;
+    }
+    let mut diagnostics = meta
+        .routing_diagnostics_json
+        .as_deref()
+        .and_then(|raw| serde_json::from_str::<Map<String, Value>>(raw).ok())
+        .unwrap_or_default();
+    diagnostics.insert("request_transforms".to_string(), Value::from(applied.to_vec()));
+    meta.routing_diagnostics_json = Some(Value::Object(diagnostics).to_string());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_transforms_merge_into_existing_diagnostics() {
+        let mut meta = ProviderUsageMetadata::synthetic_request("POST", "/v1/responses");
+        record_request_transforms(&mut meta, &[]);
+        assert!(meta.routing_diagnostics_json.is_none());
+
+        meta.routing_diagnostics_json = Some(r#"{"codex_affinity_hit":true}"#.to_string());
+        record_request_transforms(&mut meta, &["cap:max_output_tokens".to_string()]);
+        let diagnostics = serde_json::from_str::<Value>(
+            meta.routing_diagnostics_json
+                .as_deref()
+                .expect("diagnostics"),
+        )
+        .expect("json");
+        assert_eq!(diagnostics["codex_affinity_hit"], true);
+        assert_eq!(diagnostics["request_transforms"][0], "cap:max_output_tokens");
+    }
+}
@@ -0,0 +1,1055 @@
+//! Opt-in per-key response cache for identical requests.
+//!
+//! ```text
+//! provider_entry
+//!   |
+//!   +-- key has no enabled policy / not a cacheable POST --> dispatch
+//!   |
+//!   +-- canonical request hash
+//!         |
+//!         +-- hit  --> replay stored body, record discounted usage event
+//!         |
+//!         +-- miss --> dispatch with usage capture + body tee
+//!                        |
+//!                        +-- clean 200 with usage --> store for ttl_seconds
+//! ```
+//!
+//! The hash covers the key id, the request path and an allowlist of request
+//! fields that decide the upstream answer (model, messages, tools, sampling
+//! parameters, ...). Requests that depend on server-side state such as
+//! `previous_response_id` never use the cache. Entries live in a local LRU
+//! bounded by entry count and total body bytes, optionally mirrored into
+//! Valkey so cluster nodes share hits.
+
+use std::{
+    fmt::Write as _,
+    num::NonZeroUsize,
+    sync::{Arc, Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use axum::{
+    body::{to_bytes, Body, Bytes},
+    http::{header, request::Parts, HeaderMap, HeaderValue, Method, Request, StatusCode},
+    response::{IntoResponse, Response},
+};
+use base64::Engine as _;
+use futures_util::StreamExt;
+use llm_access_core::{
+    provider::{ProtocolFamily, ProviderType},
+    store::{
+        AnthropicUpstreamChannelUsageDelta, AuthenticatedKey, ControlStore, KeyResponseCachePolicy,
+        OpenAiUpstreamChannelUsageDelta,
+    },
+    usage::UsageEvent,
+};
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use super::{
+    shared_session_affinity::{parse_bool, SharedSessionAffinityStore},
+    util::{clamp_duration_ms, clamp_u64_to_i64, clamp_usize_to_i64, now_millis},
+    ProviderState, ProviderUsageMetadata, MAX_PROVIDER_PROXY_BODY_BYTES,
+};
+
+const RESPONSE_CACHE_MAX_ENTRIES_ENV: &str = "LLM_ACCESS_RESPONSE_CACHE_MAX_ENTRIES";
+const RESPONSE_CACHE_MAX_BYTES_ENV: &str = "LLM_ACCESS_RESPONSE_CACHE_MAX_BYTES";
+const RESPONSE_CACHE_MAX_ENTRY_BYTES_ENV: &str = "LLM_ACCESS_RESPONSE_CACHE_MAX_ENTRY_BYTES";
+const RESPONSE_CACHE_SHARED_ENV: &str = "LLM_ACCESS_RESPONSE_CACHE_SHARED";
+const DEFAULT_RESPONSE_CACHE_MAX_ENTRIES: usize = 1_024;
+const DEFAULT_RESPONSE_CACHE_MAX_BYTES: usize = 64 * 1024 * 1024;
+const DEFAULT_RESPONSE_CACHE_MAX_ENTRY_BYTES: usize = 1024 * 1024;
+/// Valkey key segment used when entries are mirrored cluster-wide.
+pub(crate) const RESPONSE_CACHE_SHARED_SEGMENT: &str = "response";
+/// Namespace inside the shared segment; bump when the entry format changes.
+const RESPONSE_CACHE_SHARED_NAMESPACE: &str = "v1";
+/// Response header telling clients whether the body was replayed.
+const RESPONSE_CACHE_STATUS_HEADER: &str = "x-llm-access-response-cache";
+/// `special_request_type` recorded in routing diagnostics for cache hits.
+const RESPONSE_CACHE_HIT_REQUEST_TYPE: &str = "response_cache_hit";
+
+/// Top-level request fields that decide the upstream answer. Everything else
+/// (metadata, end-user ids, prompt-cache hints, stream options) is left out
+/// of the hash.
+const RESPONSE_CACHE_KEY_FIELDS: &[&str] = &[
+    "frequency_penalty",
+    "include",
+    "input",
+    "instructions",
+    "logit_bias",
+    "logprobs",
+    "max_completion_tokens",
+    "max_output_tokens",
+    "max_tokens",
+    "messages",
+    "modalities",
+    "model",
+    "n",
+    "parallel_tool_calls",
+    "presence_penalty",
+    "reasoning",
+    "reasoning_effort",
+    "response_format",
+    "seed",
+    "stop",
+    "stop_sequences",
+    "stream",
+    "system",
+    "temperature",
+    "text",
+    "thinking",
+    "tool_choice",
+    "tools",
+    "top_k",
+    "top_logprobs",
+    "top_p",
+];
+
+/// Request fields that tie the answer to server-side state.
+const STATEFUL_REQUEST_FIELDS: &[&str] = &["previous_response_id", "conversation", "background"];
+
+/// Bounds for the in-process cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ResponseCacheConfig {
+    max_entries: usize,
+    max_bytes: usize,
+    max_entry_bytes: usize,
+}
+
+impl ResponseCacheConfig {
+    pub(crate) fn from_env() -> Self {
+        Self::from_raw(
+            std::env::var(RESPONSE_CACHE_MAX_ENTRIES_ENV)
+                .ok()
+                .as_deref(),
+            std::env::var(RESPONSE_CACHE_MAX_BYTES_ENV).ok().as_deref(),
+            std::env::var(RESPONSE_CACHE_MAX_ENTRY_BYTES_ENV)
+                .ok()
+                .as_deref(),
+        )
+    }
+
+    fn from_raw(
+        max_entries: Option<&str>,
+        max_bytes: Option<&str>,
+        max_entry_bytes: Option<&str>,
+    ) -> Self {
+        let parse = |raw: Option<&str>, default: usize| {
+            raw.and_then(|value| value.trim().parse::<usize>().ok())
+                .filter(|value| *value > 0)
+                .unwrap_or(default)
+        };
+        let max_bytes = parse(max_bytes, DEFAULT_RESPONSE_CACHE_MAX_BYTES);
+        Self {
+            max_entries: parse(max_entries, DEFAULT_RESPONSE_CACHE_MAX_ENTRIES),
+            max_bytes,
+            max_entry_bytes: parse(max_entry_bytes, DEFAULT_RESPONSE_CACHE_MAX_ENTRY_BYTES)
+                .min(max_bytes),
+        }
+    }
+
+    /// Whether entries should be mirrored into the shared request cache.
+    /// Off by default: replayed bodies can be large.
+    pub(crate) fn shared_from_env() -> bool {
+        std::env::var(RESPONSE_CACHE_SHARED_ENV)
+            .ok()
+            .is_some_and(|raw| parse_bool(&raw))
+    }
+}
+
+impl Default for ResponseCacheConfig {
+    fn default() -> Self {
+        Self::from_raw(None, None, None)
+    }
+}
+
+/// Usage of the request that produced a cached response. Replayed on hits so
+/// the hit event carries the original token counts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct CachedResponseUsage {
+    provider_type: ProviderType,
+    protocol_family: ProtocolFamily,
+    account_name: Option<String>,
+    endpoint: String,
+    model: Option<String>,
+    mapped_model: Option<String>,
+    input_uncached_tokens: i64,
+    input_cached_tokens: i64,
+    output_tokens: i64,
+    billable_tokens: i64,
+}
+
+impl CachedResponseUsage {
+    fn from_event(event: &UsageEvent) -> Self {
+        Self {
+            provider_type: event.provider_type,
+            protocol_family: event.protocol_family,
+            account_name: event.account_name.clone(),
+            endpoint: event.endpoint.clone(),
+            model: event.model.clone(),
+            mapped_model: event.mapped_model.clone(),
+            input_uncached_tokens: event.input_uncached_tokens,
+            input_cached_tokens: event.input_cached_tokens,
+            output_tokens: event.output_tokens,
+            billable_tokens: event.billable_tokens,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CachedResponse {
+    status: u16,
+    content_type: Option<String>,
+    body: Bytes,
+    usage: CachedResponseUsage,
+    stored_at_ms: i64,
+    expires_at_ms: i64,
+}
+
+/// Valkey encoding of [`CachedResponse`].
+#[derive(Serialize, Deserialize)]
+struct SharedCachedResponse {
+    status: u16,
+    content_type: Option<String>,
+    body_base64: String,
+    usage: CachedResponseUsage,
+    stored_at_ms: i64,
+    expires_at_ms: i64,
+}
+
+impl CachedResponse {
+    fn encode_shared(&self) -> Option<String> {
+        serde_json::to_string(&SharedCachedResponse {
+            status: self.status,
+            content_type: self.content_type.clone(),
+            body_base64: base64::engine::general_purpose::STANDARD.encode(&self.body),
+            usage: self.usage.clone(),
+            stored_at_ms: self.stored_at_ms,
+            expires_at_ms: self.expires_at_ms,
+        })
+        .ok()
+    }
+
+    fn decode_shared(raw: &str) -> Option<Self> {
+        let shared = serde_json::from_str::<SharedCachedResponse>(raw).ok()?;
+        let body = base64::engine::general_purpose::STANDARD
+            .decode(shared.body_base64)
+            .ok()?;
+        Some(Self {
+            status: shared.status,
+            content_type: shared.content_type,
+            body: Bytes::from(body),
+            usage: shared.usage,
+            stored_at_ms: shared.stored_at_ms,
+            expires_at_ms: shared.expires_at_ms,
+        })
+    }
+}
+
+struct LocalResponseCache {
+    entries: LruCache<String, Arc<CachedResponse>>,
+    bytes: usize,
+}
+
+impl LocalResponseCache {
+    fn get(&mut self, key: &str, now_ms: i64) -> Option<Arc<CachedResponse>> {
+        let entry = Arc::clone(self.entries.get(key)?);
+        if entry.expires_at_ms > now_ms {
+            return Some(entry);
+        }
+        self.remove(key);
+        None
+    }
+
+    fn insert(&mut self, key: String, entry: Arc<CachedResponse>, max_bytes: usize) {
+        self.remove(&key);
+        self.bytes = self.bytes.saturating_add(entry.body.len());
+        if let Some((_, evicted)) = self.entries.push(key, entry) {
+            self.bytes = self.bytes.saturating_sub(evicted.body.len());
+        }
+        while self.bytes > max_bytes {
+            let Some((_, evicted)) = self.entries.pop_lru() else {
+                break;
+            };
+            self.bytes = self.bytes.saturating_sub(evicted.body.len());
+        }
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(removed) = self.entries.pop(key) {
+            self.bytes = self.bytes.saturating_sub(removed.body.len());
+        }
+    }
+}
+
+/// Bounded response store shared by all keys that opt in.
+pub(crate) struct ResponseCache {
+    config: ResponseCacheConfig,
+    local: Mutex<LocalResponseCache>,
+    shared: OnceLock<Arc<SharedSessionAffinityStore>>,
+}
+
+impl Default for ResponseCache {
+    fn default() -> Self {
+        Self::new(ResponseCacheConfig::from_env())
+    }
+}
+
+impl ResponseCache {
+    pub(crate) fn new(config: ResponseCacheConfig) -> Self {
+        Self {
+            config,
+            local: Mutex::new(LocalResponseCache {
+                entries: LruCache::new(
+                    NonZeroUsize::new(config.max_entries.max(1)).expect("capacity is non-zero"),
+                ),
+                bytes: 0,
+            }),
+            shared: OnceLock::new(),
+        }
+    }
+
+    /// Mirror entries into a cluster-shared store. Only the first attach
+    /// takes effect.
+    pub(crate) fn attach_shared(&self, store: Arc<SharedSessionAffinityStore>) {
+        let _ = self.shared.set(store);
+    }
+
+    async fn lookup(&self, key: &str, now_ms: i64) -> Option<Arc<CachedResponse>> {
+        if let Some(entry) = self
+            .local
+            .lock()
+            .expect("response cache mutex")
+            .get(key, now_ms)
+        {
+            return Some(entry);
+        }
+        let shared = self.shared.get()?;
+        let entry = shared
+            .lookup(RESPONSE_CACHE_SHARED_NAMESPACE, key)
+            .await
+            .and_then(|raw| CachedResponse::decode_shared(&raw))
+            .filter(|entry| entry.expires_at_ms > now_ms)?;
+        let entry = Arc::new(entry);
+        self.local.lock().expect("response cache mutex").insert(
+            key.to_string(),
+            Arc::clone(&entry),
+            self.config.max_bytes,
+        );
+        Some(entry)
+    }
+
+    fn insert(&self, key: String, entry: CachedResponse, ttl: Duration) {
+        if entry.body.len() > self.config.max_entry_bytes {
+            return;
+        }
+        if let Some(shared) = self.shared.get() {
+            if let Some(encoded) = entry.encode_shared() {
+                shared.remember(RESPONSE_CACHE_SHARED_NAMESPACE, &key, &encoded, ttl);
+            }
+        }
+        self.local.lock().expect("response cache mutex").insert(
+            key,
+            Arc::new(entry),
+            self.config.max_bytes,
+        );
+    }
+}
+
+/// Dispatch an authenticated request, answering from or filling the response
+/// cache when the key opts in.
+pub(super) async fn dispatch_with_response_cache(
+    state: &ProviderState,
+    key: AuthenticatedKey,
+    request: Request<Body>,
+) -> Response {
+    let Some(policy) = response_cache_policy_for_request(state, &key, &request).await else {
+        return state
+            .dispatcher
+            .dispatch(key, request, state.dispatch_deps())
+            .await;
+    };
+    let (parts, body) = request.into_parts();
+    let body_started = Instant::now();
+    let body = match to_bytes(body, MAX_PROVIDER_PROXY_BODY_BYTES).await {
+        Ok(body) => body,
+        Err(_) => return (StatusCode::BAD_REQUEST, "request body is too large").into_response(),
+    };
+    let body_read_ms = clamp_duration_ms(body_started.elapsed());
+    let Some(cache_key) = response_cache_key(&key.key_id, parts.uri.path(), &body) else {
+        let request = Request::from_parts(parts, Body::from(body));
+        return state
+            .dispatcher
+            .dispatch(key, request, state.dispatch_deps())
+            .await;
+    };
+    if !cache_control_has(&parts.headers, "no-cache") {
+        if let Some(entry) = state.response_cache.lookup(&cache_key, now_millis()).await {
+            return replay_cached_response(
+                state,
+                &key,
+                &policy,
+                &parts,
+                &body,
+                body_read_ms,
+                &entry,
+            )
+            .await;
+        }
+    }
+
+    let fill = Arc::new(PendingResponseFill {
+        cache: Arc::clone(&state.response_cache),
+        cache_key,
+        ttl: Duration::from_secs(policy.ttl_seconds),
+        state: Mutex::new(PendingFillState::default()),
+    });
+    let mut deps = state.dispatch_deps();
+    deps.control_store = Arc::new(ResponseCacheUsageCapture {
+        inner: deps.control_store,
+        fill: Arc::clone(&fill),
+    });
+    let request = Request::from_parts(parts, Body::from(body));
+    let response = state.dispatcher.dispatch(key, request, deps).await;
+    tee_response_into_cache(response, fill)
+}
+
+async fn response_cache_policy_for_request(
+    state: &ProviderState,
+    key: &AuthenticatedKey,
+    request: &Request<Body>,
+) -> Option<KeyResponseCachePolicy> {
+    if request.method() != Method::POST || cache_control_has(request.headers(), "no-store") {
+        return None;
+    }
+    // Only bodies that the dispatcher would accept anyway are buffered here,
+    // so oversized or unknown-length uploads keep their normal error path.
+    let content_length = request
+        .headers()
+        .get(header::CONTENT_LENGTH)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<usize>()
+        .ok()?;
+    if content_length > MAX_PROVIDER_PROXY_BODY_BYTES {
+        return None;
+    }
+    match state.route_store.resolve_response_cache_policy(key).await {
+        Ok(policy) => policy.filter(|policy| policy.enabled),
+        Err(err) => {
+            tracing::warn!(
+                key_id = %key.key_id,
+                error = %format!("{err:#}"),
+                "failed to resolve response cache policy; bypassing cache"
+            );
+            None
+        },
+    }
+}
+
+async fn replay_cached_response(
+    state: &ProviderState,
+    key: &AuthenticatedKey,
+    policy: &KeyResponseCachePolicy,
+    parts: &Parts,
+    body: &Bytes,
+    body_read_ms: i64,
+    entry: &CachedResponse,
+) -> Response {
+    let mut meta = ProviderUsageMetadata::from_request_parts(
+        &parts.method,
+        &parts.uri,
+        &parts.headers,
+        &state.geoip,
+    )
+    .await;
+    meta.request_body_bytes = Some(clamp_usize_to_i64(body.len()));
+    meta.request_body_read_ms = Some(body_read_ms);
+    let original_billable = entry.usage.billable_tokens.max(0) as u64;
+    let event = UsageEvent {
+        event_id: format!("llm-usage-{}", uuid::Uuid::new_v4()),
+        created_at_ms: now_millis(),
+        provider_type: entry.usage.provider_type,
+        protocol_family: entry.usage.protocol_family,
+        key_id: key.key_id.clone(),
+        key_name: key.key_name.clone(),
+        request_method: meta.request_method.clone(),
+        request_url: meta.request_url.clone(),
+        endpoint: entry.usage.endpoint.clone(),
+        model: entry.usage.model.clone(),
+        mapped_model: entry.usage.mapped_model.clone(),
+        status_code: i64::from(entry.status),
+        request_body_bytes: meta.request_body_bytes,
+        routing_diagnostics_json: Some(
+            serde_json::json!({
+                "special_request_type": RESPONSE_CACHE_HIT_REQUEST_TYPE,
+                "response_cache_stored_at_ms": entry.stored_at_ms,
+                "response_cache_source_account": entry.usage.account_name,
+                "response_cache_original_billable_tokens": entry.usage.billable_tokens,
+                "response_cache_hit_billable_ratio": policy.hit_billable_ratio,
+            })
+            .to_string(),
+        ),
+        input_uncached_tokens: entry.usage.input_uncached_tokens,
+        input_cached_tokens: entry.usage.input_cached_tokens,
+        output_tokens: entry.usage.output_tokens,
+        billable_tokens: clamp_u64_to_i64(policy.hit_billable_tokens(original_billable)),
+        client_ip: meta.client_ip.clone(),
+        ip_region: meta.ip_region.clone(),
+        request_headers_json: meta.request_headers_json.clone(),
+        timing: meta.to_timing(),
+        ..UsageEvent::default()
+    };
+    if let Err(err) = state.control_store.apply_usage_rollup_owned(event).await {
+        tracing::warn!(
+            key_id = %key.key_id,
+            error = %format!("{err:#}"),
+            "failed to record response cache hit usage"
+        );
+    }
+
+    let mut response = Response::new(Body::from(entry.body.clone()));
+    *response.status_mut() = StatusCode::from_u16(entry.status).unwrap_or(StatusCode::OK);
+    if let Some(content_type) = entry
+        .content_type
+        .as_deref()
+        .and_then(|value| HeaderValue::from_str(value).ok())
+    {
+        response
+            .headers_mut()
+            .insert(header::CONTENT_TYPE, content_type);
+    }
+    response
+        .headers_mut()
+        .insert(RESPONSE_CACHE_STATUS_HEADER, HeaderValue::from_static("hit"));
+    response
+}
+
+/// Stream the upstream response to the client while keeping a copy. The
+/// entry is stored only once the body finished cleanly and a successful
+/// usage event has been observed.
+fn tee_response_into_cache(response: Response, fill: Arc<PendingResponseFill>) -> Response {
+    let (mut parts, body) = response.into_parts();
+    parts
+        .headers
+        .insert(RESPONSE_CACHE_STATUS_HEADER, HeaderValue::from_static("miss"));
+    if parts.status != StatusCode::OK || cache_control_has(&parts.headers, "no-store") {
+        return Response::from_parts(parts, body);
+    }
+    let status = parts.status.as_u16();
+    let content_type = parts
+        .headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let max_entry_bytes = fill.cache.config.max_entry_bytes;
+    let mut upstream = body.into_data_stream();
+    let stream = async_stream::stream! {
+        let mut captured = Vec::new();
+        let mut fits = true;
+        while let Some(chunk) = upstream.next().await {
+            match chunk {
+                Ok(bytes) => {
+                    if fits && captured.len() + bytes.len() <= max_entry_bytes {
+                        captured.extend_from_slice(&bytes);
+                    } else if fits {
+                        fits = false;
+                        captured = Vec::new();
+                    }
+                    yield Ok::<Bytes, axum::Error>(bytes);
+                },
+                Err(err) => {
+                    yield Err(err);
+                    return;
+                },
+            }
+        }
+        if fits {
+            fill.complete_body(status, content_type, Bytes::from(captured));
+        }
+    };
+    Response::from_parts(parts, Body::from_stream(stream))
+}
+
+#[derive(Default)]
+struct PendingFillState {
+    body: Option<(u16, Option<String>, Bytes)>,
+    usage: Option<CachedResponseUsage>,
+    /// Set once the entry was stored or the attempt was found unusable.
+    settled: bool,
+}
+
+/// One in-flight cache fill. The response body and the usage event arrive in
+/// either order; whichever completes the pair stores the entry.
+struct PendingResponseFill {
+    cache: Arc<ResponseCache>,
+    cache_key: String,
+    ttl: Duration,
+    state: Mutex<PendingFillState>,
+}
+
+impl PendingResponseFill {
+    fn observe_usage(&self, event: &UsageEvent) {
+        if !(200..300).contains(&event.status_code) {
+            return;
+        }
+        let mut state = self.state.lock().expect("response cache fill mutex");
+        if state.settled || state.usage.is_some() {
+            return;
+        }
+        let clean = !event.usage_missing
+            && event.error_message.is_none()
+            && event.stream.stream_completed_cleanly != Some(false);
+        if !clean {
+            state.settled = true;
+            return;
+        }
+        state.usage = Some(CachedResponseUsage::from_event(event));
+        self.store_when_ready(&mut state);
+    }
+
+    fn complete_body(&self, status: u16, content_type: Option<String>, body: Bytes) {
+        let mut state = self.state.lock().expect("response cache fill mutex");
+        if state.settled {
+            return;
+        }
+        state.body = Some((status, content_type, body));
+        self.store_when_ready(&mut state);
+    }
+
+    fn store_when_ready(&self, state: &mut PendingFillState) {
+        if state.body.is_none() || state.usage.is_none() {
+            return;
+        }
+        let (Some((status, content_type, body)), Some(usage)) =
+            (state.body.take(), state.usage.take())
+        else {
+            return;
+        };
+        state.settled = true;
+        let stored_at_ms = now_millis();
+        self.cache.insert(
+            self.cache_key.clone(),
+            CachedResponse {
+                status,
+                content_type,
+                body,
+                usage,
+                stored_at_ms,
+                expires_at_ms: stored_at_ms.saturating_add(clamp_duration_ms(self.ttl)),
+            },
+            self.ttl,
+        );
+    }
+}
+
+/// Control store wrapper handed to the dispatcher on a cache miss so the
+/// fill can see the usage event of the request it is caching.
+struct ResponseCacheUsageCapture {
+    inner: Arc<dyn ControlStore>,
+    fill: Arc<PendingResponseFill>,
+}
+
+#[async_trait]
+impl ControlStore for ResponseCacheUsageCapture {
+    async fn authenticate_bearer_secret(
+        &self,
+        secret: &str,
+    ) -> anyhow::Result<Option<AuthenticatedKey>> {
+        self.inner.authenticate_bearer_secret(secret).await
+    }
+
+    async fn apply_usage_rollup(&self, event: &UsageEvent) -> anyhow::Result<()> {
+        self.fill.observe_usage(event);
+        self.inner.apply_usage_rollup(event).await
+    }
+
+    async fn apply_usage_rollup_owned(&self, event: UsageEvent) -> anyhow::Result<()> {
+        self.fill.observe_usage(&event);
+        self.inner.apply_usage_rollup_owned(event).await
+    }
+
+    async fn record_codex_image_key_usage(
+        &self,
+        key_id: &str,
+        usage_tokens: Option<u64>,
+        used_at_ms: i64,
+    ) -> anyhow::Result<()> {
+        self.inner
+            .record_codex_image_key_usage(key_id, usage_tokens, used_at_ms)
+            .await
+    }
+
+    async fn record_anthropic_upstream_channel_usage(
+        &self,
+        channel_name: &str,
+        delta: AnthropicUpstreamChannelUsageDelta,
+    ) -> anyhow::Result<()> {
+        self.inner
+            .record_anthropic_upstream_channel_usage(channel_name, delta)
+            .await
+    }
+
+    async fn record_openai_upstream_channel_usage(
+        &self,
+        channel_name: &str,
+        delta: OpenAiUpstreamChannelUsageDelta,
+    ) -> anyhow::Result<()> {
+        self.inner
+            .record_openai_upstream_channel_usage(channel_name, delta)
+            .await
+    }
+}
+
+/// Hash the parts of a request that decide its answer. Returns `None` for
+/// bodies that are not generation requests or that depend on server state.
+fn response_cache_key(key_id: &str, path: &str, body: &[u8]) -> Option<String> {
+    let value = serde_json::from_slice::<Value>(body).ok()?;
+    let object = value.as_object()?;
+    object.get("model")?.as_str()?;
+    if !object.contains_key("messages") && !object.contains_key("input") {
+        return None;
+    }
+    let stateful = STATEFUL_REQUEST_FIELDS.iter().any(|field| {
+        object
+            .get(*field)
+            .is_some_and(|value| !matches!(value, Value::Null | Value::Bool(false)))
+    });
+    if stateful {
+        return None;
+    }
+    let mut canonical = String::new();
+    canonical.push('{');
+    let mut first = true;
+    for field in RESPONSE_CACHE_KEY_FIELDS {
+        let Some(value) = object.get(*field) else {
+            continue;
+        };
+        if !first {
+            canonical.push(',');
+        }
+        first = false;
+        write_canonical_json(&Value::String((*field).to_string()), &mut canonical);
+        canonical.push(':');
+        write_canonical_json(value, &mut canonical);
+    }
+    canonical.push('}');
+
+    let mut hasher = Sha256::new();
+    for part in [key_id, path, canonical.as_str()] {
+        hasher.update(part.len().to_le_bytes());
+        hasher.update(part.as_bytes());
+    }
+    let mut key = String::with_capacity(64);
+    for byte in hasher.finalize() {
+        let _ = write!(key, "{byte:02x}");
+    }
+    Some(key)
+}
+
+/// Serialize with object keys sorted so field order never changes the hash.
+fn write_canonical_json(value: &Value, out: &mut String) {
+    match value {
+        Value::Object(object) => {
+            let mut entries = object.iter().collect::<Vec<_>>();
+            entries.sort_unstable_by(|(left, _), (right, _)| left.cmp(right));
+            out.push('{');
+            for (index, (name, value)) in entries.into_iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                write_canonical_json(&Value::String(name.clone()), out);
+                out.push(':');
+                write_canonical_json(value, out);
+            }
+            out.push('}');
+        },
+        Value::Array(items) => {
+            out.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                write_canonical_json(item, out);
+            }
+            out.push(']');
+        },
+        scalar => out.push_str(&scalar.to_string()),
+    }
+}
+
+fn cache_control_has(headers: &HeaderMap, directive: &str) -> bool {
+    headers
+        .get_all(header::CACHE_CONTROL)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|item| item.split('=').next())
+        .any(|name| name.trim().eq_ignore_ascii_case(directive))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use llm_access_core::store::{
+        ProviderCodexAuthUpdate, ProviderCodexRoute, ProviderKiroAuthUpdate, ProviderKiroRoute,
+        ProviderRouteStore,
+    };
+
+    use super::*;
+    use crate::provider::{tests::RecordingControlStore, ProviderDispatchDeps, ProviderDispatcher};
+
+    fn sample_usage() -> CachedResponseUsage {
+        CachedResponseUsage {
+            provider_type: ProviderType::Codex,
+            protocol_family: ProtocolFamily::OpenAi,
+            account_name: Some("codex-a".to_string()),
+            endpoint: "/v1/responses".to_string(),
+            model: Some("gpt-5".to_string()),
+            mapped_model: None,
+            input_uncached_tokens: 100,
+            input_cached_tokens: 0,
+            output_tokens: 20,
+            billable_tokens: 120,
+        }
+    }
+
+    fn sample_entry(body: &'static str, expires_at_ms: i64) -> Arc<CachedResponse> {
+        Arc::new(CachedResponse {
+            status: 200,
+            content_type: Some("application/json".to_string()),
+            body: Bytes::from_static(body.as_bytes()),
+            usage: sample_usage(),
+            stored_at_ms: 0,
+            expires_at_ms,
+        })
+    }
+
+    #[test]
+    fn cache_key_ignores_field_order_and_non_semantic_fields() {
+        let a = br#"{"model":"gpt-5","input":[{"role":"user","content":"hi"}],"temperature":0,
+            "metadata":{"trace":"a"},"user":"u1"}"#;
+        let b = br#"{"user":"u2","temperature":0,"input":[{"content":"hi","role":"user"}],
+            "model":"gpt-5","prompt_cache_key":"p"}"#;
+        let key = response_cache_key("key-1", "/v1/responses", a).expect("cacheable");
+        assert_eq!(Some(key.clone()), response_cache_key("key-1", "/v1/responses", b));
+        assert_ne!(Some(key.clone()), response_cache_key("key-2", "/v1/responses", a));
+        assert_ne!(Some(key.clone()), response_cache_key("key-1", "/v1/chat/completions", a));
+        let other_sampling = br#"{"model":"gpt-5","input":[{"role":"user","content":"hi"}],
+            "temperature":1}"#;
+        assert_ne!(Some(key), response_cache_key("key-1", "/v1/responses", other_sampling));
+    }
+
+    #[test]
+    fn cache_key_skips_stateful_and_non_generation_requests() {
+        assert!(response_cache_key(
+            "key-1",
+            "/v1/responses",
+            br#"{"model":"gpt-5","input":"hi","previous_response_id":"resp_1"}"#
+        )
+        .is_none());
+        assert!(response_cache_key(
+            "key-1",
+            "/v1/responses",
+            br#"{"model":"gpt-5","input":"hi","background":false}"#
+        )
+        .is_some());
+        assert!(
+            response_cache_key("key-1", "/v1/images", br#"{"model":"x","prompt":"p"}"#).is_none()
+        );
+        assert!(response_cache_key("key-1", "/v1/responses", b"not json").is_none());
+    }
+
+    #[test]
+    fn cache_control_directives_are_matched_case_insensitively() {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("max-age=0, No-Store"));
+        assert!(cache_control_has(&headers, "no-store"));
+        assert!(!cache_control_has(&headers, "no-cache"));
+    }
+
+    #[test]
+    fn config_clamps_entry_size_to_total_budget() {
+        let config = ResponseCacheConfig::from_raw(Some("8"), Some("100"), Some("1000"));
+        assert_eq!(config.max_entries, 8);
+        assert_eq!(config.max_entry_bytes, 100);
+        let defaults = ResponseCacheConfig::from_raw(Some("0"), Some("nope"), None);
+        assert_eq!(defaults, ResponseCacheConfig::default());
+    }
+
+    #[test]
+    fn local_cache_evicts_by_byte_budget_and_expires_entries() {
+        let mut cache = LocalResponseCache {
+            entries: LruCache::new(NonZeroUsize::new(8).expect("non-zero")),
+            bytes: 0,
+        };
+        cache.insert("a".to_string(), sample_entry("aaaa", 1_000), 10);
+        cache.insert("b".to_string(), sample_entry("bbbb", 1_000), 10);
+        cache.insert("c".to_string(), sample_entry("cccc", 1_000), 10);
+        assert!(cache.get("a", 0).is_none());
+        assert!(cache.get("b", 0).is_some());
+        assert_eq!(cache.bytes, 8);
+        assert!(cache.get("c", 1_000).is_none());
+        assert_eq!(cache.bytes, 4);
+    }
+
+    #[test]
+    fn shared_encoding_round_trips() {
+        let entry = sample_entry("{\"ok\":true}", 5_000);
+        let decoded =
+            CachedResponse::decode_shared(&entry.encode_shared().expect("encode")).expect("decode");
+        assert_eq!(&decoded, entry.as_ref());
+    }
+
+    struct CachePolicyRouteStore;
+
+    #[async_trait]
+    impl ProviderRouteStore for CachePolicyRouteStore {
+        async fn resolve_codex_route(
+            &self,
+            _key: &AuthenticatedKey,
+        ) -> anyhow::Result<Option<ProviderCodexRoute>> {
+            Ok(None)
+        }
+
+        async fn resolve_codex_account_route(
+            &self,
+            _account_name: &str,
+        ) -> anyhow::Result<Option<ProviderCodexRoute>> {
+            Ok(None)
+        }
+
+        async fn resolve_kiro_route(
+            &self,
+            _key: &AuthenticatedKey,
+        ) -> anyhow::Result<Option<ProviderKiroRoute>> {
+            Ok(None)
+        }
+
+        async fn save_kiro_auth_update(
+            &self,
+            _update: ProviderKiroAuthUpdate,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn save_codex_auth_update(
+            &self,
+            _update: ProviderCodexAuthUpdate,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn resolve_response_cache_policy(
+            &self,
+            _key: &AuthenticatedKey,
+        ) -> anyhow::Result<Option<KeyResponseCachePolicy>> {
+            Ok(Some(KeyResponseCachePolicy {
+                enabled: true,
+                ttl_seconds: 60,
+                hit_billable_ratio: 0.25,
+            }))
+        }
+    }
+
+    struct CountingDispatcher {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl ProviderDispatcher for CountingDispatcher {
+        async fn dispatch(
+            &self,
+            key: AuthenticatedKey,
+            _request: Request<Body>,
+            deps: ProviderDispatchDeps,
+        ) -> Response {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            deps.control_store
+                .apply_usage_rollup_owned(UsageEvent {
+                    key_id: key.key_id,
+                    account_name: Some("codex-a".to_string()),
+                    endpoint: "/v1/responses".to_string(),
+                    model: Some("gpt-5".to_string()),
+                    status_code: 200,
+                    input_uncached_tokens: 300,
+                    output_tokens: 100,
+                    billable_tokens: 400,
+                    ..UsageEvent::default()
+                })
+                .await
+                .expect("record usage");
+            let mut response = Response::new(Body::from(r#"{"output":"hello"}"#));
+            response
+                .headers_mut()
+                .insert(header::CONTENT_TYPE, HeaderValue::from_static("application/json"));
+            response
+        }
+    }
+
+    fn cacheable_request(cache_control: Option<&'static str>) -> Request<Body> {
+        let body = r#"{"model":"gpt-5","input":"hi"}"#;
+        let mut builder = Request::builder()
+            .method(Method::POST)
+            .uri("/v1/responses")
+            .header(header::AUTHORIZATION, "Bearer codex-secret")
+            .header(header::CONTENT_LENGTH, body.len());
+        if let Some(value) = cache_control {
+            builder = builder.header(header::CACHE_CONTROL, value);
+        }
+        builder.body(Body::from(body)).expect("request")
+    }
+
+    #[tokio::test]
+    async fn identical_requests_replay_cached_response_with_discounted_usage() {
+        let store = Arc::new(RecordingControlStore::default());
+        let dispatcher = Arc::new(CountingDispatcher {
+            calls: AtomicUsize::new(0),
+        });
+        let state = ProviderState::with_dispatcher(
+            store.clone(),
+            Arc::new(CachePolicyRouteStore),
+            dispatcher.clone(),
+        );
+
+        let first = crate::provider::provider_entry(state.clone(), cacheable_request(None)).await;
+        assert_eq!(first.headers()[RESPONSE_CACHE_STATUS_HEADER], "miss");
+        let first_body = to_bytes(first.into_body(), usize::MAX)
+            .await
+            .expect("first body");
+
+        let second = crate::provider::provider_entry(state.clone(), cacheable_request(None)).await;
+        assert_eq!(second.headers()[RESPONSE_CACHE_STATUS_HEADER], "hit");
+        assert_eq!(second.headers()[header::CONTENT_TYPE], "application/json");
+        let second_body = to_bytes(second.into_body(), usize::MAX)
+            .await
+            .expect("second body");
+        assert_eq!(first_body, second_body);
+        assert_eq!(dispatcher.calls.load(Ordering::SeqCst), 1);
+
+        let events = store.usage_events.lock().expect("usage events").clone();
+        assert_eq!(events.len(), 2);
+        let hit = &events[1];
+        assert_eq!(hit.billable_tokens, 100);
+        assert_eq!(hit.input_uncached_tokens, 300);
+        assert_eq!(hit.account_name, None);
+        let diagnostics: Value = serde_json::from_str(
+            hit.routing_diagnostics_json
+                .as_deref()
+                .expect("hit diagnostics"),
+        )
+        .expect("diagnostics json");
+        assert_eq!(diagnostics["special_request_type"], RESPONSE_CACHE_HIT_REQUEST_TYPE);
+        assert_eq!(diagnostics["response_cache_original_billable_tokens"], 400);
+
+        let bypass =
+            crate::provider::provider_entry(state, cacheable_request(Some("no-store"))).await;
+        assert!(bypass.headers().get(RESPONSE_CACHE_STATUS_HEADER).is_none());
+        assert_eq!(dispatcher.calls.load(Ordering::SeqCst), 2);
+    }
+}
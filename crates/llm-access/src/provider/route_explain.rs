@@ -0,0 +1,643 @@
+//! Dry-run route explanation for one key (`explain-route` admin endpoint).
+//!
+//! Mirrors the candidate ordering of `select_kiro_route_with_account_permit`
+//! and `select_codex_route_with_account_permit` against live runtime state
+//! (cooldowns, circuit breakers, session affinity, latency bands) without
+//! acquiring permits or dispatching anything.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use llm_access_codex::types::CodexResolvedSessionSource;
+use llm_access_core::{
+    provider::ProviderType,
+    store::{
+        self as core_store, is_terminal_codex_auth_error, AuthenticatedKey, ProviderCodexRoute,
+        ProviderKiroRoute,
+    },
+};
+use llm_access_kiro::scheduler::KiroRequestScheduler;
+use serde::Serialize;
+
+use super::{
+    circuit_breaker::{CircuitAdmission, CircuitBreakers, CircuitTarget},
+    codex_auth::load_codex_dispatch_runtime_config,
+    codex_session_affinity::build_codex_affinity_id,
+    entry::{is_active_key, is_quota_exhausted},
+    errors::proxy_cooldown_key_for_route,
+    kiro_dispatch::kiro_model_group_preferred_account_names_for_model,
+    route_selection::selection_ordered_kiro_routes_with_model_preference,
+    util::now_millis,
+    CodexAccountCooldowns, ProviderState,
+};
+use crate::kiro_latency::KiroLatencyRanker;
+
+const NOT_RETURNED_BY_ROUTE_STORE_REASON: &str = "not returned by the route store (inactive, \
+                                                  disabled, quota exhausted or below the minimum \
+                                                  credit balance)";
+const LOCAL_LIMITS_NOTE: &str = "local concurrency and pacing limits are not evaluated; a busy \
+                                 candidate is waited on at dispatch time rather than skipped";
+
+/// What the dispatcher would do with one candidate account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum RouteCandidateDecision {
+    /// First candidate the dispatcher would try.
+    Selected,
+    /// Eligible failover candidate.
+    Kept,
+    /// Eligible, but sorted behind healthier or preferred candidates.
+    Demoted,
+    /// Skipped by the dispatcher.
+    Excluded,
+}
+
+/// One candidate account in dispatch order.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct RouteCandidateExplanation {
+    /// 1-based position in the order the dispatcher walks candidates.
+    pub(crate) rank: usize,
+    /// Account name.
+    pub(crate) account_name: String,
+    /// Kiro routing identity shared by aliases of one upstream account.
+    pub(crate) routing_identity: Option<String>,
+    /// Kiro pool the account belongs to.
+    pub(crate) pool_strategy: Option<String>,
+    /// Dispatcher decision.
+    pub(crate) decision: RouteCandidateDecision,
+    /// Human-readable reasons behind the decision and position.
+    pub(crate) reasons: Vec<String>,
+    /// Remaining account cooldown.
+    pub(crate) cooldown_remaining_ms: Option<u64>,
+    /// Remaining cooldown of the account's outbound proxy.
+    pub(crate) proxy_cooldown_remaining_ms: Option<u64>,
+    /// Sessions currently bound to the account, when new-session spread is
+    /// active.
+    pub(crate) session_count: Option<usize>,
+    /// Kiro latency band; lower is faster.
+    pub(crate) latency_band: Option<i64>,
+    /// Last cached Kiro credit balance.
+    pub(crate) remaining_credits: Option<f64>,
+}
+
+/// Direct Anthropic pool configuration consulted before Kiro accounts.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct AnthropicUpstreamPoolExplanation {
+    /// Canonical key-level pool mode.
+    pub(crate) mode: String,
+    /// Eligible direct upstream channels.
+    pub(crate) channels: Vec<String>,
+}
+
+/// Dry-run routing decision for one key.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct RouteExplanation {
+    /// Key id.
+    pub(crate) key_id: String,
+    /// Key name.
+    pub(crate) key_name: String,
+    /// Provider type of the key.
+    pub(crate) provider_type: String,
+    /// Why the request would be rejected before account selection.
+    pub(crate) blocked_reason: Option<String>,
+    /// Route strategy captured from the resolved routes.
+    pub(crate) route_strategy: Option<String>,
+    /// Account group captured from the resolved routes.
+    pub(crate) account_group_id: Option<String>,
+    /// Model supplied by the caller.
+    pub(crate) requested_model: Option<String>,
+    /// Model after the key's model-name mapping.
+    pub(crate) effective_model: Option<String>,
+    /// Session id supplied by the caller.
+    pub(crate) session_id: Option<String>,
+    /// Account the session is currently bound to.
+    pub(crate) affinity_account_name: Option<String>,
+    /// Whether the session would be balanced onto the least-bound account.
+    pub(crate) new_session_spread: bool,
+    /// Model-group preferred accounts for the requested model.
+    pub(crate) model_preferred_account_names: Option<Vec<String>>,
+    /// Key-level preferred Kiro pool.
+    pub(crate) preferred_pool_strategy: Option<String>,
+    /// Direct Anthropic pool consulted before Kiro accounts.
+    pub(crate) anthropic_upstream_pool: Option<AnthropicUpstreamPoolExplanation>,
+    /// Account the dispatcher would try first.
+    pub(crate) selected_account_name: Option<String>,
+    /// Candidates in dispatch order, followed by configured accounts the route
+    /// store did not return.
+    pub(crate) candidates: Vec<RouteCandidateExplanation>,
+    /// Caveats about what the dry run does not model.
+    pub(crate) notes: Vec<String>,
+    /// Generation timestamp in milliseconds.
+    pub(crate) generated_at: i64,
+}
+
+impl ProviderState {
+    /// Explain how a request for `key` would be routed right now.
+    ///
+    /// `configured_account_names` lists the accounts the key's route config
+    /// names explicitly; ones the route store filters out are reported as
+    /// excluded. Session affinity is read through the shared store when one
+    /// is attached, exactly like a real request.
+    pub(crate) async fn explain_route(
+        &self,
+        key: &AuthenticatedKey,
+        model: Option<&str>,
+        session_id: Option<&str>,
+        configured_account_names: Option<&[String]>,
+    ) -> anyhow::Result<RouteExplanation> {
+        let model = model.map(str::trim).filter(|value| !value.is_empty());
+        let session_id = session_id.map(str::trim).filter(|value| !value.is_empty());
+        let blocked_reason = if !is_active_key(key) {
+            Some("key is not active".to_string())
+        } else if is_quota_exhausted(key) {
+            Some("key quota exhausted".to_string())
+        } else {
+            None
+        };
+        let mut explanation = RouteExplanation {
+            key_id: key.key_id.clone(),
+            key_name: key.key_name.clone(),
+            provider_type: key.provider_type.clone(),
+            blocked_reason,
+            route_strategy: None,
+            account_group_id: None,
+            requested_model: model.map(ToString::to_string),
+            effective_model: model.map(ToString::to_string),
+            session_id: session_id.map(ToString::to_string),
+            affinity_account_name: None,
+            new_session_spread: false,
+            model_preferred_account_names: None,
+            preferred_pool_strategy: None,
+            anthropic_upstream_pool: None,
+            selected_account_name: None,
+            candidates: Vec::new(),
+            notes: vec![LOCAL_LIMITS_NOTE.to_string()],
+            generated_at: now_millis(),
+        };
+        let returned_account_names = match ProviderType::from_storage_str(&key.provider_type) {
+            Some(ProviderType::Kiro) => {
+                self.explain_kiro_route(key, model, session_id, &mut explanation)
+                    .await?
+            },
+            Some(ProviderType::Codex) => {
+                self.explain_codex_route(key, session_id, &mut explanation)
+                    .await?
+            },
+            None => anyhow::bail!("unsupported provider type `{}`", key.provider_type),
+        };
+        append_unreturned_accounts(
+            &mut explanation.candidates,
+            configured_account_names.unwrap_or_default(),
+            &returned_account_names,
+        );
+        if explanation.blocked_reason.is_some() {
+            clear_selected_candidate(&mut explanation.candidates);
+        }
+        explanation.selected_account_name = explanation
+            .candidates
+            .iter()
+            .find(|candidate| candidate.decision == RouteCandidateDecision::Selected)
+            .map(|candidate| candidate.account_name.clone());
+        Ok(explanation)
+    }
+
+    async fn explain_kiro_route(
+        &self,
+        key: &AuthenticatedKey,
+        model: Option<&str>,
+        session_id: Option<&str>,
+        explanation: &mut RouteExplanation,
+    ) -> anyhow::Result<HashSet<String>> {
+        let resolution = self
+            .route_store
+            .resolve_anthropic_upstream_resolution(key)
+            .await?;
+        if resolution.pool_mode != core_store::ANTHROPIC_UPSTREAM_POOL_MODE_DISABLED {
+            if resolution.pool_mode == core_store::ANTHROPIC_UPSTREAM_POOL_MODE_ONLY {
+                explanation.blocked_reason.get_or_insert_with(|| {
+                    "key routes only through the direct Anthropic pool".to_string()
+                });
+            } else {
+                explanation.notes.push(
+                    "direct Anthropic channels are tried before the Kiro candidates below"
+                        .to_string(),
+                );
+            }
+            explanation.anthropic_upstream_pool = Some(AnthropicUpstreamPoolExplanation {
+                mode: resolution.pool_mode,
+                channels: resolution
+                    .routes
+                    .into_iter()
+                    .map(|route| route.channel_name)
+                    .collect(),
+            });
+        }
+        let routes = self.route_store.resolve_kiro_route_candidates(key).await?;
+        let Some(first) = routes.first() else {
+            return Ok(HashSet::new());
+        };
+        explanation.route_strategy = Some(first.route_strategy_at_event.as_storage_str().into());
+        explanation.account_group_id = first.account_group_id_at_event.clone();
+        explanation.preferred_pool_strategy = Some(
+            core_store::normalize_kiro_pool_strategy(&first.preferred_pool_strategy)
+                .unwrap_or(core_store::KIRO_POOL_STRATEGY_BALANCED)
+                .to_string(),
+        );
+        if let Some(model) = model {
+            explanation.effective_model =
+                Some(kiro_effective_model(&first.model_name_map_json, model)?);
+        }
+        let model_preferred_account_names = model
+            .and_then(|model| kiro_model_group_preferred_account_names_for_model(&routes, model));
+        explanation.model_preferred_account_names =
+            model_preferred_account_names.as_ref().map(|names| {
+                let mut names = names.iter().cloned().collect::<Vec<_>>();
+                names.sort();
+                names
+            });
+        let affinity_account_name = match session_id {
+            Some(session_id) => {
+                self.kiro_session_affinity
+                    .resolve(&key.key_id, session_id)
+                    .await
+            },
+            None => None,
+        };
+        let session_counts =
+            (routes.len() > 1 && session_id.is_some() && affinity_account_name.is_none())
+                .then(|| self.kiro_session_affinity.account_session_counts());
+        explanation.new_session_spread = session_counts.is_some();
+        explanation.candidates = explain_kiro_candidates(
+            &routes,
+            &self.kiro_request_scheduler,
+            &self.circuit_breakers,
+            &self.kiro_latency_ranker,
+            affinity_account_name.as_deref(),
+            model_preferred_account_names.as_ref(),
+            session_counts.as_ref(),
+        );
+        explanation.affinity_account_name = affinity_account_name;
+        Ok(routes.into_iter().map(|route| route.account_name).collect())
+    }
+
+    async fn explain_codex_route(
+        &self,
+        key: &AuthenticatedKey,
+        session_id: Option<&str>,
+        explanation: &mut RouteExplanation,
+    ) -> anyhow::Result<HashSet<String>> {
+        let routes = self.route_store.resolve_codex_route_candidates(key).await?;
+        let Some(first) = routes.first() else {
+            return Ok(HashSet::new());
+        };
+        explanation.route_strategy = Some(first.route_strategy_at_event.as_storage_str().into());
+        explanation.account_group_id = first.account_group_id_at_event.clone();
+        explanation
+            .notes
+            .push("codex account selection does not depend on the model".to_string());
+        let runtime_config = load_codex_dispatch_runtime_config(self.admin_config_store.as_ref())
+            .await
+            .map_err(|_| anyhow::anyhow!("runtime config store error"))?;
+        let affinity_id = build_codex_affinity_id(
+            &key.key_id,
+            session_id,
+            Some(CodexResolvedSessionSource::HeaderSessionId),
+        );
+        let affinity_account_name = match affinity_id.as_ref() {
+            Some(affinity_id) => {
+                self.codex_session_affinity
+                    .resolve(affinity_id, &runtime_config.affinity)
+                    .await
+            },
+            None => None,
+        };
+        let session_counts = (routes.len() > 1
+            && affinity_id.is_some()
+            && affinity_account_name.is_none())
+        .then(|| {
+            self.codex_session_affinity
+                .account_session_counts(&runtime_config.affinity)
+        });
+        explanation.new_session_spread = session_counts.is_some();
+        explanation.candidates = explain_codex_candidates(
+            &routes,
+            &self.codex_account_cooldowns,
+            &self.circuit_breakers,
+            affinity_account_name.as_deref(),
+            session_counts.as_ref(),
+        );
+        explanation.affinity_account_name = affinity_account_name;
+        Ok(routes.into_iter().map(|route| route.account_name).collect())
+    }
+}
+
+/// Explain Kiro candidates in the order `select_kiro_route_with_account_permit`
+/// walks them.
+pub(super) fn explain_kiro_candidates(
+    routes: &[ProviderKiroRoute],
+    scheduler: &KiroRequestScheduler,
+    circuit_breakers: &CircuitBreakers,
+    latency_ranker: &KiroLatencyRanker,
+    affinity_account_name: Option<&str>,
+    model_preferred_account_names: Option<&HashSet<String>>,
+    session_counts: Option<&HashMap<String, usize>>,
+) -> Vec<RouteCandidateExplanation> {
+    let now_ms = now_millis();
+    let proxy_cooldowns = scheduler.proxy_cooldown_snapshot();
+    let proxy_cooldown_for = |route: &ProviderKiroRoute| {
+        proxy_cooldown_key_for_route(route).and_then(|key| proxy_cooldowns.get(&key).cloned())
+    };
+    let model_preference_active =
+        model_preferred_account_names.is_some_and(|account_names| !account_names.is_empty());
+    let preferred_pool_strategy = routes.first().map(|route| {
+        core_store::normalize_kiro_pool_strategy(&route.preferred_pool_strategy)
+            .unwrap_or(core_store::KIRO_POOL_STRATEGY_BALANCED)
+    });
+    let identity_session_counts = session_counts.map(|counts| {
+        routes
+            .iter()
+            .fold(HashMap::new(), |mut by_identity, route| {
+                *by_identity
+                    .entry(route.routing_identity.as_str())
+                    .or_insert(0) += counts.get(&route.account_name).copied().unwrap_or(0);
+                by_identity
+            })
+    });
+    let affinity_route = affinity_account_name
+        .and_then(|name| routes.iter().find(|route| route.account_name == name));
+    let affinity_skip_reason = affinity_route.and_then(|route| {
+        if model_preference_active {
+            Some("session affinity ignored: a model-group preference is active")
+        } else if proxy_cooldown_for(route).is_some() {
+            Some("session affinity ignored: the account's proxy is cooling down")
+        } else {
+            None
+        }
+    });
+    let prioritized_route = affinity_route.filter(|_| affinity_skip_reason.is_none());
+    let ordered = prioritized_route.into_iter().chain(
+        selection_ordered_kiro_routes_with_model_preference(
+            routes,
+            scheduler,
+            latency_ranker,
+            now_ms,
+            model_preferred_account_names,
+            session_counts,
+        )
+        .into_iter()
+        .filter(|route| !prioritized_route.is_some_and(|seen| std::ptr::eq(seen, *route))),
+    );
+
+    let mut candidates = Vec::with_capacity(routes.len());
+    let mut selected = false;
+    for (index, route) in ordered.enumerate() {
+        let mut reasons = Vec::new();
+        let mut demoted = false;
+        let pool_strategy = core_store::normalize_kiro_pool_strategy(&route.pool_strategy)
+            .unwrap_or(core_store::KIRO_POOL_STRATEGY_BALANCED);
+        if affinity_account_name == Some(route.account_name.as_str()) {
+            reasons.push(
+                affinity_skip_reason
+                    .unwrap_or("session affinity: the session is bound to this account")
+                    .to_string(),
+            );
+        }
+        let cooldown = scheduler.cooldown_for_account(&route.routing_identity);
+        if let Some(cooldown) = cooldown.as_ref() {
+            reasons.push(format!(
+                "account cooling down for {}s: {}",
+                cooldown.remaining.as_secs().max(1),
+                cooldown.reason
+            ));
+        }
+        let proxy_cooldown = proxy_cooldown_for(route);
+        if let Some(proxy_cooldown) = proxy_cooldown.as_ref() {
+            demoted = true;
+            reasons.push(format!("proxy cooling down: {}", proxy_cooldown.reason));
+        }
+        let circuit_open = push_circuit_reason(
+            &mut reasons,
+            circuit_breakers.admission(&CircuitTarget::kiro(route)),
+        );
+        if model_preference_active {
+            if model_preferred_account_names
+                .is_some_and(|names| names.contains(&route.account_name))
+            {
+                reasons.push("preferred by the model group for this model".to_string());
+            } else {
+                demoted = true;
+                reasons.push("not in the model group preferred for this model".to_string());
+            }
+        }
+        if let Some(preferred) =
+            preferred_pool_strategy.filter(|preferred| *preferred != pool_strategy)
+        {
+            demoted = true;
+            reasons.push(format!("outside the key's preferred `{preferred}` pool"));
+        }
+        let session_count = identity_session_counts.as_ref().map(|counts| {
+            counts
+                .get(route.routing_identity.as_str())
+                .copied()
+                .unwrap_or(0)
+        });
+        if let Some(count) = session_count {
+            reasons.push(format!("{count} bound sessions (new-session spread)"));
+        }
+        let decision = if cooldown.is_some() || circuit_open {
+            RouteCandidateDecision::Excluded
+        } else if !selected {
+            selected = true;
+            RouteCandidateDecision::Selected
+        } else if demoted {
+            RouteCandidateDecision::Demoted
+        } else {
+            RouteCandidateDecision::Kept
+        };
+        candidates.push(RouteCandidateExplanation {
+            rank: index + 1,
+            account_name: route.account_name.clone(),
+            routing_identity: Some(route.routing_identity.clone()),
+            pool_strategy: Some(pool_strategy.to_string()),
+            decision,
+            reasons,
+            cooldown_remaining_ms: cooldown.map(|cooldown| duration_ms(cooldown.remaining)),
+            proxy_cooldown_remaining_ms: proxy_cooldown
+                .map(|cooldown| duration_ms(cooldown.remaining)),
+            session_count,
+            latency_band: latency_ranker.route_score_band(route, now_ms),
+            remaining_credits: route.cached_remaining_credits,
+        });
+    }
+    candidates
+}
+
+/// Explain Codex candidates in the order
+/// `select_codex_route_with_account_permit` walks them.
+pub(super) fn explain_codex_candidates(
+    routes: &[ProviderCodexRoute],
+    cooldowns: &CodexAccountCooldowns,
+    circuit_breakers: &CircuitBreakers,
+    affinity_account_name: Option<&str>,
+    session_counts: Option<&HashMap<String, usize>>,
+) -> Vec<RouteCandidateExplanation> {
+    let affinity_route = affinity_account_name
+        .and_then(|name| routes.iter().find(|route| route.account_name == name))
+        .filter(|route| {
+            terminal_auth_error(route).is_none()
+                && cooldowns
+                    .cooldown_for_account(&route.account_name)
+                    .is_none()
+                && !matches!(
+                    circuit_breakers.admission(&CircuitTarget::codex(route)),
+                    CircuitAdmission::Reject { .. }
+                )
+        });
+    let mut ordered = routes.iter().enumerate().collect::<Vec<_>>();
+    if let Some(session_counts) = session_counts {
+        ordered.sort_by_key(|(index, route)| {
+            (
+                session_counts
+                    .get(&route.account_name)
+                    .copied()
+                    .unwrap_or_default(),
+                *index,
+            )
+        });
+    }
+    let ordered = affinity_route.into_iter().chain(
+        ordered
+            .into_iter()
+            .map(|(_, route)| route)
+            .filter(|route| !affinity_route.is_some_and(|seen| std::ptr::eq(seen, *route))),
+    );
+
+    let mut candidates = Vec::with_capacity(routes.len());
+    let mut selected = false;
+    for (index, route) in ordered.enumerate() {
+        let mut reasons = Vec::new();
+        if affinity_account_name == Some(route.account_name.as_str()) {
+            reasons.push(if affinity_route.is_some() {
+                "session affinity: the session is bound to this account".to_string()
+            } else {
+                "session affinity ignored: the account is unavailable".to_string()
+            });
+        }
+        let cooldown = cooldowns.cooldown_for_account(&route.account_name);
+        let excluded = if let Some(error) = terminal_auth_error(route) {
+            reasons.push(format!("terminal auth error: {error}"));
+            true
+        } else if let Some(cooldown) = cooldown.as_ref() {
+            reasons.push(format!(
+                "request-path cooldown for {}s",
+                cooldown.remaining.as_secs().max(1)
+            ));
+            true
+        } else {
+            push_circuit_reason(
+                &mut reasons,
+                circuit_breakers.admission(&CircuitTarget::codex(route)),
+            )
+        };
+        let session_count =
+            session_counts.map(|counts| counts.get(&route.account_name).copied().unwrap_or(0));
+        if let Some(count) = session_count {
+            reasons.push(format!("{count} bound sessions (new-session spread)"));
+        }
+        let decision = if excluded {
+            RouteCandidateDecision::Excluded
+        } else if !selected {
+            selected = true;
+            RouteCandidateDecision::Selected
+        } else {
+            RouteCandidateDecision::Kept
+        };
+        candidates.push(RouteCandidateExplanation {
+            rank: index + 1,
+            account_name: route.account_name.clone(),
+            routing_identity: None,
+            pool_strategy: None,
+            decision,
+            reasons,
+            cooldown_remaining_ms: cooldown.map(|cooldown| duration_ms(cooldown.remaining)),
+            proxy_cooldown_remaining_ms: None,
+            session_count,
+            latency_band: None,
+            remaining_credits: None,
+        });
+    }
+    candidates
+}
+
+/// Describe the candidate's circuit breaker; true when it keeps the
+/// candidate out of rotation.
+fn push_circuit_reason(reasons: &mut Vec<String>, admission: CircuitAdmission) -> bool {
+    match admission {
+        CircuitAdmission::Allow => false,
+        CircuitAdmission::Trial => {
+            reasons.push("circuit breaker half-open: the next request is a trial".to_string());
+            false
+        },
+        CircuitAdmission::Reject {
+            remaining,
+        } => {
+            reasons.push(format!("circuit breaker open for {}s", remaining.as_secs().max(1)));
+            true
+        },
+    }
+}
+
+fn append_unreturned_accounts(
+    candidates: &mut Vec<RouteCandidateExplanation>,
+    configured_account_names: &[String],
+    returned_account_names: &HashSet<String>,
+) {
+    let mut seen = HashSet::new();
+    for account_name in configured_account_names {
+        if returned_account_names.contains(account_name) || !seen.insert(account_name) {
+            continue;
+        }
+        candidates.push(RouteCandidateExplanation {
+            rank: candidates.len() + 1,
+            account_name: account_name.clone(),
+            routing_identity: None,
+            pool_strategy: None,
+            decision: RouteCandidateDecision::Excluded,
+            reasons: vec![NOT_RETURNED_BY_ROUTE_STORE_REASON.to_string()],
+            cooldown_remaining_ms: None,
+            proxy_cooldown_remaining_ms: None,
+            session_count: None,
+            latency_band: None,
+            remaining_credits: None,
+        });
+    }
+}
+
+fn terminal_auth_error(route: &ProviderCodexRoute) -> Option<&str> {
+    route
+        .cached_error_message
+        .as_deref()
+        .filter(|message| is_terminal_codex_auth_error(message))
+}
+
+fn clear_selected_candidate(candidates: &mut [RouteCandidateExplanation]) {
+    for candidate in candidates {
+        if candidate.decision == RouteCandidateDecision::Selected {
+            candidate.decision = RouteCandidateDecision::Kept;
+        }
+    }
+}
+
+/// Same lookup as `apply_kiro_model_mapping`, without a parsed request.
+fn kiro_effective_model(model_name_map_json: &str, model: &str) -> anyhow::Result<String> {
+    let trimmed = model_name_map_json.trim();
+    if trimmed.is_empty() || trimmed == "{}" {
+        return Ok(model.to_string());
+    }
+    let map = serde_json::from_str::<BTreeMap<String, String>>(trimmed)
+        .map_err(|err| anyhow::anyhow!("Kiro model mapping configuration is invalid: {err}"))?;
+    Ok(map.get(model).cloned().unwrap_or_else(|| model.to_string()))
+}
+
+fn duration_ms(duration: std::time::Duration) -> u64 {
+    duration.as_millis().min(u128::from(u64::MAX)) as u64
+}
@@ -23,11 +23,13 @@ use llm_access_core::{
 use llm_access_kiro::scheduler::{KiroRequestLease, KiroRequestScheduler};
 
 use super::{
+    circuit_breaker::{CircuitAdmission, CircuitBreakers, CircuitTarget},
     codex_dispatch::dispatch_codex_proxy,
     errors::proxy_cooldown_key_for_route,
+    fair_queue::FairQueueEntry,
     kiro_dispatch::dispatch_kiro_proxy,
     kiro_error::{kiro_json_error, AWS_BEDROCK_ALL_ACCOUNTS_COOLING_DOWN_MESSAGE},
-    limiter::wait_for_limit,
+    limiter::{codex_queue_full_response, kiro_queue_full_response, wait_for_limit},
     util::now_millis,
     CodexAccountCooldowns, DefaultProviderDispatcher, LimitPermit, LimitRejection,
     ProviderDispatchDeps, ProviderDispatcher, RequestLimiter,
@@ -62,9 +64,16 @@ fn shortest_wait(current: Option<Duration>, next: Duration) -> Option<Duration>
     })
 }
 
+#[allow(
+    clippy::too_many_arguments,
+    reason = "mirrors the dispatcher's per-request routing inputs, which the route tests pass \
+              directly"
+)]
 pub async fn select_codex_route_with_account_permit(
     limiter: &Arc<RequestLimiter>,
     codex_account_cooldowns: &Arc<CodexAccountCooldowns>,
+    circuit_breakers: &CircuitBreakers,
+    queue_entry: &mut FairQueueEntry,
     routes: &[ProviderCodexRoute],
     failed_accounts: &HashSet<String>,
     preferred_account_name: Option<&str>,
@@ -76,6 +85,9 @@ pub async fn select_codex_route_with_account_permit(
         );
     }
     loop {
+        if let Err(rejection) = queue_entry.wait_turn().await {
+            return Err(codex_queue_full_response(&rejection));
+        }
         let mut saw_limit = false;
         let mut saw_account_cooldown = false;
         let mut saw_terminal_auth_error = false;
@@ -92,7 +104,12 @@ pub async fn select_codex_route_with_account_permit(
             let has_cooldown = codex_account_cooldowns
                 .cooldown_for_account(&preferred_route.account_name)
                 .is_some();
-            if !has_terminal_auth_error && !has_cooldown {
+            let circuit_target = CircuitTarget::codex(preferred_route);
+            let admission = circuit_breakers.admission(&circuit_target);
+            if !has_terminal_auth_error
+                && !has_cooldown
+                && !matches!(admission, CircuitAdmission::Reject { .. })
+            {
                 if let Ok(permit) = limiter.try_acquire(
                     format!(
                         "account:{}:{}",
@@ -102,6 +119,10 @@ pub async fn select_codex_route_with_account_permit(
                     preferred_route.account_request_max_concurrency,
                     preferred_route.account_request_min_start_interval_ms,
                 ) {
+                    if admission == CircuitAdmission::Trial {
+                        circuit_breakers.begin_trial(&circuit_target);
+                    }
+                    queue_entry.finish();
                     return Ok((preferred_route.clone(), permit));
                 }
             }
@@ -150,12 +171,32 @@ pub async fn select_codex_route_with_account_permit(
                 );
                 continue;
             }
+            let circuit_target = CircuitTarget::codex(route);
+            let admission = circuit_breakers.admission(&circuit_target);
+            if let CircuitAdmission::Reject {
+                remaining,
+            } = admission
+            {
+                saw_account_cooldown = true;
+                tracing::debug!(
+                    account = %route.account_name,
+                    circuit_remaining_ms = remaining.as_millis() as u64,
+                    "skipping codex account with an open circuit breaker"
+                );
+                continue;
+            }
             match limiter.try_acquire(
                 format!("account:{}:{}", ProviderType::Codex.as_storage_str(), route.account_name),
                 route.account_request_max_concurrency,
                 route.account_request_min_start_interval_ms,
             ) {
-                Ok(permit) => return Ok((route.clone(), permit)),
+                Ok(permit) => {
+                    if admission == CircuitAdmission::Trial {
+                        circuit_breakers.begin_trial(&circuit_target);
+                    }
+                    queue_entry.finish();
+                    return Ok((route.clone(), permit));
+                },
                 Err(rejection) => {
                     saw_limit = true;
                     if shortest_wait
@@ -181,7 +222,11 @@ pub async fn select_codex_route_with_account_permit(
                 .into_response());
         }
         if saw_limit {
+            if let Err(rejection) = queue_entry.yield_turn() {
+                return Err(codex_queue_full_response(&rejection));
+            }
             wait_for_limit(shortest_wait.as_ref()).await;
+            queue_entry.next_round();
             continue;
         }
         if saw_account_cooldown {
@@ -198,8 +243,16 @@ pub async fn select_codex_route_with_account_permit(
             .into_response());
     }
 }
+
+#[allow(
+    clippy::too_many_arguments,
+    reason = "mirrors the dispatcher's per-request routing inputs, which the route tests pass \
+              directly"
+)]
 pub async fn select_kiro_route_with_account_permit(
     scheduler: &Arc<KiroRequestScheduler>,
+    circuit_breakers: &CircuitBreakers,
+    queue_entry: &mut FairQueueEntry,
     routes: &[ProviderKiroRoute],
     failed_accounts: &HashSet<String>,
     latency_ranker: &KiroLatencyRanker,
@@ -218,6 +271,9 @@ pub async fn select_kiro_route_with_account_permit(
         model_preferred_account_names.is_some_and(|account_names| !account_names.is_empty());
     let queued_at = Instant::now();
     loop {
+        if let Err(rejection) = queue_entry.wait_turn().await {
+            return Err(kiro_queue_full_response(&rejection));
+        }
         let mut saw_local_limit = false;
         let mut shortest_local_wait: Option<Duration> = None;
         let mut saw_upstream_cooldown = false;
@@ -239,11 +295,19 @@ pub async fn select_kiro_route_with_account_permit(
             })
         {
             saw_candidate = true;
+            let circuit_target = CircuitTarget::kiro(preferred_route);
+            let admission = circuit_breakers.admission(&circuit_target);
             if let Some(cooldown) =
                 scheduler.cooldown_for_account(&preferred_route.routing_identity)
             {
                 saw_upstream_cooldown = true;
                 shortest_upstream_wait = shortest_wait(shortest_upstream_wait, cooldown.remaining);
+            } else if let CircuitAdmission::Reject {
+                remaining,
+            } = admission
+            {
+                saw_upstream_cooldown = true;
+                shortest_upstream_wait = shortest_wait(shortest_upstream_wait, remaining);
             } else if let Ok(permit) = scheduler.try_acquire(
                 &preferred_route.routing_identity,
                 preferred_route
@@ -254,6 +318,10 @@ pub async fn select_kiro_route_with_account_permit(
                     .unwrap_or(llm_access_core::store::DEFAULT_KIRO_CHANNEL_MIN_START_INTERVAL_MS),
                 queued_at,
             ) {
+                if admission == CircuitAdmission::Trial {
+                    circuit_breakers.begin_trial(&circuit_target);
+                }
+                queue_entry.finish();
                 return Ok((preferred_route.clone(), permit));
             }
         }
@@ -274,6 +342,16 @@ pub async fn select_kiro_route_with_account_permit(
                 shortest_upstream_wait = shortest_wait(shortest_upstream_wait, cooldown.remaining);
                 continue;
             }
+            let circuit_target = CircuitTarget::kiro(route);
+            let admission = circuit_breakers.admission(&circuit_target);
+            if let CircuitAdmission::Reject {
+                remaining,
+            } = admission
+            {
+                saw_upstream_cooldown = true;
+                shortest_upstream_wait = shortest_wait(shortest_upstream_wait, remaining);
+                continue;
+            }
             match scheduler.try_acquire(
                 &route.routing_identity,
                 route
@@ -284,7 +362,13 @@ pub async fn select_kiro_route_with_account_permit(
                     .unwrap_or(llm_access_core::store::DEFAULT_KIRO_CHANNEL_MIN_START_INTERVAL_MS),
                 queued_at,
             ) {
-                Ok(permit) => return Ok((route.clone(), permit)),
+                Ok(permit) => {
+                    if admission == CircuitAdmission::Trial {
+                        circuit_breakers.begin_trial(&circuit_target);
+                    }
+                    queue_entry.finish();
+                    return Ok((route.clone(), permit));
+                },
                 Err(rejection) => {
                     saw_local_limit = true;
                     if let Some(wait) = rejection.wait {
@@ -311,7 +395,11 @@ pub async fn select_kiro_route_with_account_permit(
                 (None, Some(upstream)) if saw_upstream_cooldown => Some(upstream),
                 (None, _) => None,
             };
+            if let Err(rejection) = queue_entry.yield_turn() {
+                return Err(kiro_queue_full_response(&rejection));
+            }
             scheduler.wait_for_available(wait).await;
+            queue_entry.next_round();
             continue;
         }
         if saw_candidate && saw_upstream_cooldown {
@@ -431,7 +519,7 @@ pub fn selection_ordered_kiro_routes<'a>(
     )
 }
 
-fn selection_ordered_kiro_routes_with_model_preference<'a>(
+pub fn selection_ordered_kiro_routes_with_model_preference<'a>(
     routes: &'a [ProviderKiroRoute],
     scheduler: &KiroRequestScheduler,
     latency_ranker: &KiroLatencyRanker,
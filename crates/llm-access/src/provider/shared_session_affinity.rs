@@ -0,0 +1,344 @@
+//! Cluster-shared session affinity over Valkey.
+//!
+//! The Codex and Kiro affinity maps live in per-process LRUs, so when several
+//! cluster nodes serve the same key, one session can bounce between upstream
+//! accounts and lose its prompt cache. This store mirrors affinity entries
+//! into Valkey under the request-cache key prefix, with the same TTLs as the
+//! local maps. The local LRU stays authoritative for the request path:
+//! writes are queued to a background task, and lookups consult Valkey with a
+//! short timeout. After any Valkey error the store backs off and every
+//! lookup falls back to the local LRU until the backoff expires.
+//!
+//! The response cache mirrors its entries through a second instance opened
+//! under its own key segment.
+
+use std::{
+    fmt::Write as _,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context;
+use llm_access_store::request_cache::RequestCacheConfig;
+use redis::aio::ConnectionManager;
+use sha2::{Digest, Sha256};
+use tokio::sync::{mpsc, OnceCell};
+
+const CODEX_SHARED_AFFINITY_ENV: &str = "LLM_ACCESS_CODEX_SESSION_AFFINITY_SHARED";
+const KIRO_SHARED_AFFINITY_ENV: &str = "LLM_ACCESS_KIRO_SESSION_AFFINITY_SHARED";
+/// Upper bound on a Valkey round trip on the request path.
+const SHARED_AFFINITY_LOOKUP_TIMEOUT: Duration = Duration::from_millis(250);
+/// How long to skip Valkey after an error before trying again.
+const SHARED_AFFINITY_RETRY_BACKOFF: Duration = Duration::from_secs(30);
+/// Pending writes beyond this are dropped; the local LRU still has them.
+const SHARED_AFFINITY_WRITE_QUEUE: usize = 1_024;
+
+/// Which affinity maps mirror into the shared store.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct SharedSessionAffinityConfig {
+    pub codex: bool,
+    pub kiro: bool,
+}
+
+impl SharedSessionAffinityConfig {
+    /// Codex sharing defaults on for clustered deployments, since that is
+    /// where sessions split across nodes; Kiro must opt in explicitly.
+    pub(crate) fn from_env(clustered: bool) -> Self {
+        Self::from_raw(
+            std::env::var(CODEX_SHARED_AFFINITY_ENV).ok().as_deref(),
+            std::env::var(KIRO_SHARED_AFFINITY_ENV).ok().as_deref(),
+            clustered,
+        )
+    }
+
+    fn from_raw(codex: Option<&str>, kiro: Option<&str>, clustered: bool) -> Self {
+        Self {
+            codex: codex.map_or(clustered, parse_bool),
+            kiro: kiro.is_some_and(parse_bool),
+        }
+    }
+
+    pub(crate) fn any_enabled(self) -> bool {
+        self.codex || self.kiro
+    }
+}
+
+pub(super) fn parse_bool(raw: &str) -> bool {
+    matches!(raw.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on")
+}
+
+struct SharedAffinityWrite {
+    key: String,
+    account_name: String,
+    ttl: Duration,
+}
+
+/// Valkey mirror for session-to-account affinity entries.
+pub(crate) struct SharedSessionAffinityStore {
+    client: redis::Client,
+    connection: OnceCell<ConnectionManager>,
+    key_prefix: String,
+    segment: &'static str,
+    unavailable_until: Mutex<Option<Instant>>,
+    writes: mpsc::Sender<SharedAffinityWrite>,
+}
+
+impl std::fmt::Debug for SharedSessionAffinityStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedSessionAffinityStore")
+            .field("key_prefix", &self.key_prefix)
+            .field("segment", &self.segment)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SharedSessionAffinityStore {
+    /// Open the store from the shared request-cache config and spawn its
+    /// background writer. Must be called inside a Tokio runtime.
+    pub(crate) fn spawn(config: &RequestCacheConfig) -> anyhow::Result<std::sync::Arc<Self>> {
+        Self::spawn_segment(config, "affinity")
+    }
+
+    /// Like [`Self::spawn`], but stores entries under `segment` instead of
+    /// the affinity key space.
+    pub(crate) fn spawn_segment(
+        config: &RequestCacheConfig,
+        segment: &'static str,
+    ) -> anyhow::Result<std::sync::Arc<Self>> {
+        let client = redis::Client::open(config.url.clone())
+            .with_context(|| format!("open session affinity redis client `{}`", config.url))?;
+        let (writes, mut pending) = mpsc::channel(SHARED_AFFINITY_WRITE_QUEUE);
+        let store = std::sync::Arc::new(Self {
+            client,
+            connection: OnceCell::new(),
+            key_prefix: config.key_prefix.clone(),
+            segment,
+            unavailable_until: Mutex::new(None),
+            writes,
+        });
+        let weak = std::sync::Arc::downgrade(&store);
+        tokio::spawn(async move {
+            while let Some(write) = pending.recv().await {
+                let Some(store) = weak.upgrade() else { break };
+                store.store(write).await;
+            }
+        });
+        Ok(store)
+    }
+
+    /// Look up the account bound to `key` in `namespace`. Returns `None` on a
+    /// miss, while backing off, or when Valkey fails or times out.
+    pub(crate) async fn lookup(&self, namespace: &str, key: &str) -> Option<String> {
+        if !self.available() {
+            return None;
+        }
+        let data_key = self.data_key(namespace, key);
+        let result = tokio::time::timeout(SHARED_AFFINITY_LOOKUP_TIMEOUT, async {
+            let mut conn = self.connection().await?;
+            redis::cmd("GET")
+                .arg(&data_key)
+                .query_async::<Option<String>>(&mut conn)
+                .await
+                .with_context(|| format!("redis GET `{data_key}`"))
+        })
+        .await;
+        match result {
+            Ok(Ok(account_name)) => account_name.filter(|name| !name.trim().is_empty()),
+            Ok(Err(err)) => {
+                self.mark_unavailable(&format!("{err:#}"));
+                None
+            },
+            Err(_) => {
+                self.mark_unavailable("lookup timed out");
+                None
+            },
+        }
+    }
+
+    /// Queue a write of `key -> account_name` with `ttl`. Never blocks; the
+    /// entry is dropped when the queue is full or Valkey is backing off.
+    pub(crate) fn remember(&self, namespace: &str, key: &str, account_name: &str, ttl: Duration) {
+        if ttl.is_zero() || !self.available() {
+            return;
+        }
+        let write = SharedAffinityWrite {
+            key: self.data_key(namespace, key),
+            account_name: account_name.to_string(),
+            ttl,
+        };
+        if self.writes.try_send(write).is_err() {
+            tracing::debug!("shared session affinity write queue is full; keeping entry local");
+        }
+    }
+
+    async fn store(&self, write: SharedAffinityWrite) {
+        if !self.available() {
+            return;
+        }
+        let result = tokio::time::timeout(SHARED_AFFINITY_LOOKUP_TIMEOUT, async {
+            let mut conn = self.connection().await?;
+            redis::cmd("SET")
+                .arg(&write.key)
+                .arg(&write.account_name)
+                .arg("EX")
+                .arg(write.ttl.as_secs().max(1))
+                .query_async::<()>(&mut conn)
+                .await
+                .with_context(|| format!("redis SET `{}`", write.key))
+        })
+        .await;
+        match result {
+            Ok(Ok(())) => {},
+            Ok(Err(err)) => self.mark_unavailable(&format!("{err:#}")),
+            Err(_) => self.mark_unavailable("write timed out"),
+        }
+    }
+
+    async fn connection(&self) -> anyhow::Result<ConnectionManager> {
+        self.connection
+            .get_or_try_init(|| async {
+                self.client
+                    .get_connection_manager()
+                    .await
+                    .context("connect session affinity redis")
+            })
+            .await
+            .cloned()
+    }
+
+    fn available(&self) -> bool {
+        let mut unavailable_until = self
+            .unavailable_until
+            .lock()
+            .expect("shared session affinity backoff mutex");
+        match *unavailable_until {
+            Some(until) if Instant::now() < until => false,
+            Some(_) => {
+                *unavailable_until = None;
+                true
+            },
+            None => true,
+        }
+    }
+
+    fn mark_unavailable(&self, reason: &str) {
+        let mut unavailable_until = self
+            .unavailable_until
+            .lock()
+            .expect("shared session affinity backoff mutex");
+        if unavailable_until.is_none() {
+            tracing::warn!(
+                reason,
+                backoff_seconds = SHARED_AFFINITY_RETRY_BACKOFF.as_secs(),
+                "shared session affinity is unavailable; falling back to local affinity"
+            );
+        }
+        *unavailable_until = Some(Instant::now() + SHARED_AFFINITY_RETRY_BACKOFF);
+    }
+
+    /// Session ids are client-controlled and can be long, so the Valkey key
+    /// carries a digest of the local affinity key instead of the raw value.
+    fn data_key(&self, namespace: &str, key: &str) -> String {
+        let digest = Sha256::digest(key.as_bytes());
+        let mut data_key = String::with_capacity(
+            self.key_prefix.len() + self.segment.len() + namespace.len() + 8 + digest.len() * 2,
+        );
+        let _ = write!(data_key, "{}:{}:{namespace}:", self.key_prefix, self.segment);
+        for byte in digest {
+            let _ = write!(data_key, "{byte:02x}");
+        }
+        data_key
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unreachable_store() -> std::sync::Arc<SharedSessionAffinityStore> {
+        SharedSessionAffinityStore::spawn(&RequestCacheConfig {
+            url: "redis://127.0.0.1:1/".to_string(),
+            key_prefix: "sftest".to_string(),
+        })
+        .expect("store")
+    }
+
+    #[test]
+    fn config_defaults_codex_to_cluster_mode_and_kiro_to_off() {
+        let clustered = SharedSessionAffinityConfig::from_raw(None, None, true);
+        assert!(clustered.codex);
+        assert!(!clustered.kiro);
+        let single = SharedSessionAffinityConfig::from_raw(None, None, false);
+        assert!(!single.any_enabled());
+        let opted_in = SharedSessionAffinityConfig::from_raw(Some("off"), Some(" TRUE "), true);
+        assert!(!opted_in.codex);
+        assert!(opted_in.kiro);
+    }
+
+    #[tokio::test]
+    async fn data_keys_are_namespaced_digests() {
+        let store = unreachable_store();
+        let key = store.data_key("codex:explicit", "5:key-asession-a");
+        assert!(key.starts_with("sftest:affinity:codex:explicit:"));
+        assert_eq!(key.len(), "sftest:affinity:codex:explicit:".len() + 64);
+        assert_ne!(key, store.data_key("codex:derived", "5:key-asession-a"));
+        assert_ne!(key, store.data_key("codex:explicit", "5:key-asession-b"));
+    }
+
+    #[tokio::test]
+    async fn segment_stores_use_their_own_key_space() {
+        let store = SharedSessionAffinityStore::spawn_segment(
+            &RequestCacheConfig {
+                url: "redis://127.0.0.1:1/".to_string(),
+                key_prefix: "sftest".to_string(),
+            },
+            "response",
+        )
+        .expect("store");
+        assert!(store
+            .data_key("v1", "entry")
+            .starts_with("sftest:response:v1:"));
+    }
+
+    #[tokio::test]
+    async fn unreachable_valkey_backs_off_instead_of_failing_lookups() {
+        let store = unreachable_store();
+        assert_eq!(store.lookup("kiro", "5:key-asession-a").await, None);
+        assert!(!store.available());
+        store.remember("kiro", "5:key-asession-a", "account-a", Duration::from_secs(60));
+        assert_eq!(store.lookup("kiro", "5:key-asession-a").await, None);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a local Valkey/Redis reachable via LLM_ACCESS_TEST_VALKEY_URL"]
+    async fn affinity_round_trips_through_valkey() {
+        let Ok(url) = std::env::var("LLM_ACCESS_TEST_VALKEY_URL") else {
+            eprintln!("skipping: LLM_ACCESS_TEST_VALKEY_URL is not set");
+            return;
+        };
+        let store = SharedSessionAffinityStore::spawn(&RequestCacheConfig {
+            url,
+            key_prefix: format!("sftest:{}", uuid::Uuid::new_v4()),
+        })
+        .expect("store");
+        store.remember("codex:explicit", "5:key-asession-a", "account-a", Duration::from_secs(60));
+        for _ in 0..50 {
+            if store
+                .lookup("codex:explicit", "5:key-asession-a")
+                .await
+                .is_some()
+            {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(
+            store
+                .lookup("codex:explicit", "5:key-asession-a")
+                .await
+                .as_deref(),
+            Some("account-a")
+        );
+        assert_eq!(store.lookup("kiro", "5:key-asession-a").await, None);
+    }
+}
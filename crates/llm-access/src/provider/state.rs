@@ -9,10 +9,11 @@ use axum::{
     response::{IntoResponse, Response},
 };
 use llm_access_core::store::{
-    AdminConfigStore, AdminKiroStatusCacheUpdate, AuthenticatedKey, ControlStore,
-    EmptyAdminConfigStore, ProviderAnthropicUpstreamRoute, ProviderCodexAuthUpdate,
-    ProviderCodexRoute, ProviderKiroAuthUpdate, ProviderKiroRoute, ProviderProxyConfig,
-    ProviderRouteStore,
+    AdminConfigStore, AdminKiroStatusCacheUpdate, AuthenticatedKey, BatchStore, ControlStore,
+    EmptyAdminConfigStore, EmptyBatchStore, KeyModerationPolicy, KeyQueuePolicy,
+    KeyResponseCachePolicy, NewModerationEvent, ProviderAnthropicUpstreamRoute,
+    ProviderCodexAuthUpdate, ProviderCodexRoute, ProviderKiroAuthUpdate, ProviderKiroRoute,
+    ProviderProxyConfig, ProviderRouteStore, RequestTransformPolicy,
 };
 use llm_access_kiro::{
     cache_sim::{KiroCacheRuntimeStats, KiroCacheSimulationConfig, KiroCacheSimulator},
@@ -20,11 +21,15 @@ use llm_access_kiro::{
 };
 
 use super::{
+    circuit_breaker::CircuitBreakers,
     codex_session_affinity::CodexSessionAffinity,
     codex_session_recovery::CodexSessionRecovery,
     codex_session_rejection::CodexSessionRejection,
     entry::{is_active_key, is_quota_exhausted, key_matches_route, quota_exhausted_response},
+    fair_queue::FairQueue,
     kiro_session_affinity::KiroSessionAffinity,
+    response_cache::ResponseCache,
+    shared_session_affinity::{SharedSessionAffinityConfig, SharedSessionAffinityStore},
     CodexAccountCooldowns, DefaultProviderDispatcher, ForcedProxyRouteStore, ProviderDispatchDeps,
     ProviderDispatcher, ProviderState, RequestLimiter,
 };
@@ -136,6 +141,8 @@ impl ProviderState {
             kiro_cache_simulator: Arc::new(KiroCacheSimulator::default()),
             request_limiter: Arc::new(RequestLimiter::default()),
             codex_account_cooldowns: Arc::new(CodexAccountCooldowns::default()),
+            circuit_breakers: Arc::new(CircuitBreakers::default()),
+            fair_queue: Arc::new(FairQueue::default()),
             codex_session_affinity: Arc::new(CodexSessionAffinity::default()),
             codex_session_recovery: Arc::new(CodexSessionRecovery::default()),
             codex_session_rejection: Arc::new(CodexSessionRejection::default()),
@@ -143,14 +150,44 @@ impl ProviderState {
             kiro_session_affinity: Arc::new(KiroSessionAffinity::from_env()),
             kiro_latency_ranker,
             request_activity,
+            response_cache: Arc::new(ResponseCache::default()),
+            batch_store: Arc::new(EmptyBatchStore),
             protected_thinking_signature_secret: protected_thinking_signature_secret_from_env(),
         }
     }
 
+    /// Back the batch endpoints and worker with a persistent queue.
+    pub(crate) fn with_batch_store(mut self, batch_store: Arc<dyn BatchStore>) -> Self {
+        self.batch_store = batch_store;
+        self
+    }
+
     pub(crate) fn route_store(&self) -> Arc<dyn ProviderRouteStore> {
         Arc::clone(&self.route_store)
     }
 
+    /// Mirror session affinity into a cluster-shared store for the providers
+    /// enabled in `config`; the in-process LRUs remain the fallback.
+    pub(crate) fn attach_shared_session_affinity(
+        &self,
+        store: Arc<SharedSessionAffinityStore>,
+        config: SharedSessionAffinityConfig,
+    ) {
+        if config.codex {
+            self.codex_session_affinity
+                .attach_shared(Arc::clone(&store));
+        }
+        if config.kiro {
+            self.kiro_session_affinity.attach_shared(store);
+        }
+    }
+
+    /// Mirror response cache entries into a cluster-shared store; the
+    /// in-process LRU remains the fallback.
+    pub(crate) fn attach_shared_response_cache(&self, store: Arc<SharedSessionAffinityStore>) {
+        self.response_cache.attach_shared(store);
+    }
+
     /// Shared Kiro cache simulator, exposed so the serve loop can snapshot it
     /// to Valkey and restore it on startup.
     pub(crate) fn kiro_cache_simulator(&self) -> Arc<KiroCacheSimulator> {
@@ -207,6 +244,8 @@ impl ProviderState {
             kiro_cache_simulator: Arc::clone(&self.kiro_cache_simulator),
             request_limiter: Arc::clone(&self.request_limiter),
             codex_account_cooldowns: Arc::clone(&self.codex_account_cooldowns),
+            circuit_breakers: Arc::clone(&self.circuit_breakers),
+            fair_queue: Arc::clone(&self.fair_queue),
             codex_session_affinity: Arc::clone(&self.codex_session_affinity),
             codex_session_recovery: Arc::clone(&self.codex_session_recovery),
             codex_session_rejection: Arc::clone(&self.codex_session_rejection),
@@ -317,6 +356,38 @@ impl ProviderRouteStore for ForcedProxyRouteStore {
         self.inner.resolve_anthropic_upstream_pool_mode(key).await
     }
 
+    async fn resolve_response_cache_policy(
+        &self,
+        key: &AuthenticatedKey,
+    ) -> anyhow::Result<Option<KeyResponseCachePolicy>> {
+        self.inner.resolve_response_cache_policy(key).await
+    }
+
+    async fn resolve_request_transform_policy(
+        &self,
+        key: &AuthenticatedKey,
+    ) -> anyhow::Result<Option<RequestTransformPolicy>> {
+        self.inner.resolve_request_transform_policy(key).await
+    }
+
+    async fn resolve_moderation_policy(
+        &self,
+        key: &AuthenticatedKey,
+    ) -> anyhow::Result<Option<KeyModerationPolicy>> {
+        self.inner.resolve_moderation_policy(key).await
+    }
+
+    async fn resolve_queue_policy(
+        &self,
+        key: &AuthenticatedKey,
+    ) -> anyhow::Result<Option<KeyQueuePolicy>> {
+        self.inner.resolve_queue_policy(key).await
+    }
+
+    async fn record_moderation_event(&self, event: NewModerationEvent) -> anyhow::Result<()> {
+        self.inner.record_moderation_event(event).await
+    }
+
     async fn resolve_kiro_account_route(
         &self,
         account_name: &str,
@@ -32,9 +32,10 @@ use serde_json::json;
 use tokio::sync::Notify;
 
 use super::{
-    build_codex_affinity_id, select_codex_route_with_account_permit, CodexAccountCooldowns,
-    CodexAffinityId, CodexAffinityRuntimeConfig, CodexAffinitySource, CodexSessionAffinity,
-    ProviderDispatcher, RequestLimiter,
+    build_codex_affinity_id, circuit_breaker::CircuitBreakers, fair_queue::FairQueueEntry,
+    select_codex_route_with_account_permit, CodexAccountCooldowns, CodexAffinityId,
+    CodexAffinityRuntimeConfig, CodexAffinitySource, CodexSessionAffinity, ProviderDispatcher,
+    RequestLimiter,
 };
 
 const SAMPLE_PNG_BASE64: &str =
@@ -907,6 +908,8 @@ impl ControlStore for TestStore {
             status: status.to_string(),
             quota_billable_limit: 100,
             billable_tokens_used,
+            parent_remaining_billable: None,
+            parent_key_id: None,
         }))
     }
 
@@ -933,6 +936,60 @@ impl ControlStore for TestStore {
     ) -> anyhow::Result<()> {
         Ok(())
     }
+
+    async fn record_openai_upstream_channel_usage(
+        &self,
+        _channel_name: &str,
+        _delta: llm_access_core::store::OpenAiUpstreamChannelUsageDelta,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+/// Control store that authenticates nothing and drops every write, for tests
+/// that only exercise dispatch.
+pub(super) struct NoopControlStore;
+
+#[async_trait]
+impl ControlStore for NoopControlStore {
+    async fn authenticate_bearer_secret(
+        &self,
+        _secret: &str,
+    ) -> anyhow::Result<Option<AuthenticatedKey>> {
+        Ok(None)
+    }
+
+    async fn apply_usage_rollup(
+        &self,
+        _event: &llm_access_core::usage::UsageEvent,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn record_codex_image_key_usage(
+        &self,
+        _key_id: &str,
+        _usage_tokens: Option<u64>,
+        _used_at_ms: i64,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn record_anthropic_upstream_channel_usage(
+        &self,
+        _channel_name: &str,
+        _delta: llm_access_core::store::AnthropicUpstreamChannelUsageDelta,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn record_openai_upstream_channel_usage(
+        &self,
+        _channel_name: &str,
+        _delta: llm_access_core::store::OpenAiUpstreamChannelUsageDelta,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
 }
 
 #[derive(Default)]
@@ -970,6 +1027,14 @@ impl ControlStore for FailingStore {
     ) -> anyhow::Result<()> {
         Ok(())
     }
+
+    async fn record_openai_upstream_channel_usage(
+        &self,
+        _channel_name: &str,
+        _delta: llm_access_core::store::OpenAiUpstreamChannelUsageDelta,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
 }
 
 struct StaticAdminConfigStore {
@@ -1339,8 +1404,8 @@ struct CapturedKiroRequest {
 }
 
 #[derive(Default)]
-struct RecordingControlStore {
-    usage_events: Mutex<Vec<llm_access_core::usage::UsageEvent>>,
+pub(super) struct RecordingControlStore {
+    pub(super) usage_events: Mutex<Vec<llm_access_core::usage::UsageEvent>>,
 }
 
 #[async_trait]
@@ -1362,6 +1427,8 @@ impl ControlStore for RecordingControlStore {
             status: "active".to_string(),
             quota_billable_limit: 1000,
             billable_tokens_used: 0,
+            parent_remaining_billable: None,
+            parent_key_id: None,
         }))
     }
 
@@ -1392,6 +1459,14 @@ impl ControlStore for RecordingControlStore {
     ) -> anyhow::Result<()> {
         Ok(())
     }
+
+    async fn record_openai_upstream_channel_usage(
+        &self,
+        _channel_name: &str,
+        _delta: llm_access_core::store::OpenAiUpstreamChannelUsageDelta,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
 }
 
 async fn wait_for_usage_event_count(store: &RecordingControlStore, expected: usize) {
@@ -1713,6 +1788,8 @@ async fn forced_proxy_route_store_overrides_candidates_and_hydrated_routes() {
         status: "active".to_string(),
         quota_billable_limit: 100,
         billable_tokens_used: 0,
+        parent_remaining_billable: None,
+        parent_key_id: None,
     };
 
     let codex_candidates = store
@@ -1872,6 +1949,8 @@ async fn kiro_selection_prefers_sticky_account_when_immediately_available() {
 
     let (route, _permit) = super::select_kiro_route_with_account_permit(
         &scheduler,
+        &CircuitBreakers::default(),
+        &mut FairQueueEntry::detached(),
         &routes,
         &HashSet::new(),
         &ranker,
@@ -1885,6 +1964,62 @@ async fn kiro_selection_prefers_sticky_account_when_immediately_available() {
     assert_eq!(route.account_name, "beta");
 }
 
+#[tokio::test]
+async fn kiro_selection_skips_open_circuits_and_reports_cooling_down_when_none_remain() {
+    use super::circuit_breaker::CircuitTarget;
+
+    let scheduler = llm_access_kiro::scheduler::KiroRequestScheduler::new();
+    let breakers = CircuitBreakers::default();
+    let routes = vec![
+        kiro_route_for_selection("alpha", "user-alpha", 90.0, None),
+        kiro_route_for_selection("beta", "user-beta", 10.0, None),
+    ];
+    let ranker = crate::kiro_latency::KiroLatencyRanker::default();
+    for _ in 0..10 {
+        breakers.record_failure(&CircuitTarget::kiro(&routes[1]), false, "upstream status 500");
+    }
+
+    let (route, permit) = super::select_kiro_route_with_account_permit(
+        &scheduler,
+        &breakers,
+        &mut FairQueueEntry::detached(),
+        &routes,
+        &HashSet::new(),
+        &ranker,
+        Some("beta"),
+        None,
+        None,
+    )
+    .await
+    .expect("alpha should be selected while beta's circuit is open");
+    assert_eq!(route.account_name, "alpha");
+    drop(permit);
+
+    for _ in 0..10 {
+        breakers.record_failure(&CircuitTarget::kiro(&routes[0]), false, "upstream status 500");
+    }
+    let response = match super::select_kiro_route_with_account_permit(
+        &scheduler,
+        &breakers,
+        &mut FairQueueEntry::detached(),
+        &routes,
+        &HashSet::new(),
+        &ranker,
+        None,
+        None,
+        None,
+    )
+    .await
+    {
+        Ok(_) => panic!("every circuit is open"),
+        Err(response) => response,
+    };
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert!(response
+        .headers()
+        .contains_key(axum::http::header::RETRY_AFTER));
+}
+
 #[tokio::test]
 async fn kiro_selection_model_group_preference_outranks_sticky_account() {
     let scheduler = llm_access_kiro::scheduler::KiroRequestScheduler::new();
@@ -1897,6 +2032,8 @@ async fn kiro_selection_model_group_preference_outranks_sticky_account() {
 
     let (route, _permit) = super::select_kiro_route_with_account_permit(
         &scheduler,
+        &CircuitBreakers::default(),
+        &mut FairQueueEntry::detached(),
         &routes,
         &HashSet::new(),
         &ranker,
@@ -1922,6 +2059,8 @@ async fn kiro_selection_model_group_preference_empty_set_keeps_sticky_account()
 
     let (route, _permit) = super::select_kiro_route_with_account_permit(
         &scheduler,
+        &CircuitBreakers::default(),
+        &mut FairQueueEntry::detached(),
         &routes,
         &HashSet::new(),
         &ranker,
@@ -1950,6 +2089,8 @@ async fn kiro_selection_model_group_preference_soft_falls_back_when_locally_thro
 
     let (route, _permit) = super::select_kiro_route_with_account_permit(
         &scheduler,
+        &CircuitBreakers::default(),
+        &mut FairQueueEntry::detached(),
         &routes,
         &HashSet::new(),
         &ranker,
@@ -1977,6 +2118,8 @@ async fn kiro_selection_skips_sticky_account_when_locally_throttled() {
 
     let (route, _permit) = super::select_kiro_route_with_account_permit(
         &scheduler,
+        &CircuitBreakers::default(),
+        &mut FairQueueEntry::detached(),
         &routes,
         &HashSet::new(),
         &ranker,
@@ -2013,6 +2156,8 @@ async fn kiro_selection_returns_rate_limit_when_all_accounts_are_upstream_cooled
         Duration::from_millis(100),
         super::select_kiro_route_with_account_permit(
             &scheduler,
+            &CircuitBreakers::default(),
+            &mut FairQueueEntry::detached(),
             &routes,
             &HashSet::new(),
             &ranker,
@@ -2044,6 +2189,120 @@ async fn kiro_selection_returns_rate_limit_when_all_accounts_are_upstream_cooled
     assert!(!raw.to_ascii_lowercase().contains("kiro"));
 }
 
+#[test]
+fn kiro_route_explain_puts_affinity_first_and_excludes_cooled_down_accounts() {
+    use super::route_explain::{explain_kiro_candidates, RouteCandidateDecision};
+
+    let scheduler = llm_access_kiro::scheduler::KiroRequestScheduler::new();
+    let ranker = crate::kiro_latency::KiroLatencyRanker::default();
+    let routes = vec![
+        kiro_route_for_pool_selection("alpha", "user-alpha", 50.0, "balanced", "balanced"),
+        kiro_route_for_pool_selection("beta", "user-beta", 90.0, "credit_first", "balanced"),
+        kiro_route_for_pool_selection("gamma", "user-gamma", 10.0, "balanced", "balanced"),
+    ];
+    scheduler.mark_account_cooldown("user-gamma", Duration::from_secs(60), "upstream rate limited");
+
+    let candidates = explain_kiro_candidates(
+        &routes,
+        &scheduler,
+        &CircuitBreakers::default(),
+        &ranker,
+        Some("beta"),
+        None,
+        None,
+    );
+
+    let order = candidates
+        .iter()
+        .map(|candidate| (candidate.account_name.as_str(), candidate.decision))
+        .collect::<Vec<_>>();
+    assert_eq!(order, vec![
+        ("beta", RouteCandidateDecision::Selected),
+        ("alpha", RouteCandidateDecision::Kept),
+        ("gamma", RouteCandidateDecision::Excluded),
+    ]);
+    assert!(candidates[0]
+        .reasons
+        .iter()
+        .any(|reason| reason.starts_with("session affinity:")));
+    assert!(candidates[0]
+        .reasons
+        .iter()
+        .any(|reason| reason.contains("preferred `balanced` pool")));
+    assert!(candidates[2].cooldown_remaining_ms.is_some());
+    assert!(candidates[2]
+        .reasons
+        .iter()
+        .any(|reason| reason.contains("upstream rate limited")));
+}
+
+#[test]
+fn kiro_route_explain_ignores_affinity_under_model_group_preference() {
+    use super::route_explain::{explain_kiro_candidates, RouteCandidateDecision};
+
+    let scheduler = llm_access_kiro::scheduler::KiroRequestScheduler::new();
+    let ranker = crate::kiro_latency::KiroLatencyRanker::default();
+    let routes = vec![
+        kiro_route_for_selection("alpha", "user-alpha", 90.0, None),
+        kiro_route_for_selection("beta", "user-beta", 10.0, None),
+    ];
+    let preferred = HashSet::from(["beta".to_string()]);
+
+    let candidates = explain_kiro_candidates(
+        &routes,
+        &scheduler,
+        &CircuitBreakers::default(),
+        &ranker,
+        Some("alpha"),
+        Some(&preferred),
+        None,
+    );
+
+    assert_eq!(candidates[0].account_name, "beta");
+    assert_eq!(candidates[0].decision, RouteCandidateDecision::Selected);
+    assert_eq!(candidates[1].account_name, "alpha");
+    assert_eq!(candidates[1].decision, RouteCandidateDecision::Demoted);
+    assert!(candidates[1]
+        .reasons
+        .iter()
+        .any(|reason| reason == "session affinity ignored: a model-group preference is active"));
+}
+
+#[test]
+fn codex_route_explain_spreads_new_sessions_and_skips_cooled_down_accounts() {
+    use super::route_explain::{explain_codex_candidates, RouteCandidateDecision};
+
+    let cooldowns = CodexAccountCooldowns::default();
+    cooldowns.mark_account_cooldown("alpha", Duration::from_secs(60));
+    let routes = vec![
+        codex_route_for_account("alpha", "token-alpha"),
+        codex_route_for_account("beta", "token-beta"),
+        codex_route_for_account("gamma", "token-gamma"),
+    ];
+    let session_counts = HashMap::from([("beta".to_string(), 3), ("gamma".to_string(), 1)]);
+
+    let candidates = explain_codex_candidates(
+        &routes,
+        &cooldowns,
+        &CircuitBreakers::default(),
+        None,
+        Some(&session_counts),
+    );
+
+    let order = candidates
+        .iter()
+        .map(|candidate| {
+            (candidate.account_name.as_str(), candidate.decision, candidate.session_count)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(order, vec![
+        ("alpha", RouteCandidateDecision::Excluded, Some(0)),
+        ("gamma", RouteCandidateDecision::Selected, Some(1)),
+        ("beta", RouteCandidateDecision::Kept, Some(3)),
+    ]);
+    assert!(candidates[0].cooldown_remaining_ms.is_some());
+}
+
 #[tokio::test]
 async fn kiro_selection_returns_rate_limit_when_preferred_account_is_upstream_cooled_down() {
     let scheduler = llm_access_kiro::scheduler::KiroRequestScheduler::new();
@@ -2059,6 +2318,8 @@ async fn kiro_selection_returns_rate_limit_when_preferred_account_is_upstream_co
         Duration::from_millis(100),
         super::select_kiro_route_with_account_permit(
             &scheduler,
+            &CircuitBreakers::default(),
+            &mut FairQueueEntry::detached(),
             &routes,
             &HashSet::new(),
             &ranker,
@@ -2106,6 +2367,8 @@ async fn kiro_selection_rechecks_when_upstream_cooldown_expires_before_local_slo
         Duration::from_millis(250),
         super::select_kiro_route_with_account_permit(
             &scheduler,
+            &CircuitBreakers::default(),
+            &mut FairQueueEntry::detached(),
             &routes,
             &HashSet::new(),
             &ranker,
@@ -2247,6 +2510,8 @@ async fn kiro_selection_falls_back_when_preferred_pool_is_throttled() {
 
     let (route, _permit) = super::select_kiro_route_with_account_permit(
         &scheduler,
+        &CircuitBreakers::default(),
+        &mut FairQueueEntry::detached(),
         &routes,
         &HashSet::new(),
         &ranker,
@@ -2283,6 +2548,8 @@ async fn kiro_selection_keeps_sticky_account_across_pool_preference() {
 
     let (route, _permit) = super::select_kiro_route_with_account_permit(
         &scheduler,
+        &CircuitBreakers::default(),
+        &mut FairQueueEntry::detached(),
         &routes,
         &HashSet::new(),
         &ranker,
@@ -8502,6 +8769,8 @@ async fn codex_route_selection_skips_terminal_auth_error_routes() {
     let (route, _permit) = select_codex_route_with_account_permit(
         &limiter,
         &cooldowns,
+        &CircuitBreakers::default(),
+        &mut FairQueueEntry::detached(),
         &[blocked, healthy],
         &HashSet::new(),
         None,
@@ -8523,6 +8792,8 @@ async fn codex_route_selection_prefers_affinity_account_when_usable() {
     let (route, _permit) = select_codex_route_with_account_permit(
         &limiter,
         &cooldowns,
+        &CircuitBreakers::default(),
+        &mut FairQueueEntry::detached(),
         &[first, preferred],
         &HashSet::new(),
         Some("codex-b"),
@@ -8547,6 +8818,8 @@ async fn codex_route_selection_spreads_new_sessions_to_least_bound_account() {
     let (route, _permit) = select_codex_route_with_account_permit(
         &limiter,
         &cooldowns,
+        &CircuitBreakers::default(),
+        &mut FairQueueEntry::detached(),
         &[alpha, beta],
         &HashSet::new(),
         None,
@@ -8642,6 +8915,35 @@ fn codex_session_affinity_ignores_derived_sessions_when_fallback_affinity_is_dis
     assert!(affinity.account_session_counts(&config).is_empty());
 }
 
+#[tokio::test]
+async fn codex_route_selection_skips_preferred_account_with_open_circuit() {
+    use super::circuit_breaker::CircuitTarget;
+
+    let limiter = Arc::new(RequestLimiter::default());
+    let cooldowns = Arc::new(CodexAccountCooldowns::default());
+    let breakers = CircuitBreakers::default();
+    let tripped = codex_route_for_account("codex-a", "upstream-token-a");
+    let healthy = codex_route_for_account("codex-b", "upstream-token-b");
+    for _ in 0..10 {
+        breakers.record_failure(&CircuitTarget::codex(&tripped), false, "upstream status 502");
+    }
+
+    let (route, _permit) = select_codex_route_with_account_permit(
+        &limiter,
+        &cooldowns,
+        &breakers,
+        &mut FairQueueEntry::detached(),
+        &[tripped, healthy],
+        &HashSet::new(),
+        Some("codex-a"),
+        None,
+    )
+    .await
+    .expect("healthy account should be selected");
+
+    assert_eq!(route.account_name, "codex-b");
+}
+
 #[tokio::test]
 async fn codex_route_selection_returns_bad_gateway_when_all_routes_have_terminal_auth_errors() {
     let limiter = Arc::new(RequestLimiter::default());
@@ -8662,6 +8964,8 @@ async fn codex_route_selection_returns_bad_gateway_when_all_routes_have_terminal
     let response = match select_codex_route_with_account_permit(
         &limiter,
         &cooldowns,
+        &CircuitBreakers::default(),
+        &mut FairQueueEntry::detached(),
         &[blocked_a, blocked_b],
         &HashSet::new(),
         None,
@@ -8681,6 +8985,55 @@ async fn codex_route_selection_returns_bad_gateway_when_all_routes_have_terminal
     assert!(body.contains("all eligible codex accounts failed for this request"));
 }
 
+#[tokio::test]
+async fn codex_route_selection_rejects_fast_when_key_queue_is_full() {
+    use llm_access_core::{provider::ProviderType, store::KeyQueuePolicy};
+
+    use super::fair_queue::{FairQueue, FairQueueConfig};
+
+    let limiter = Arc::new(RequestLimiter::default());
+    let cooldowns = Arc::new(CodexAccountCooldowns::default());
+    let mut route = codex_route_for_account("codex-a", "upstream-token-a");
+    route.account_request_max_concurrency = Some(1);
+    let _busy = limiter
+        .try_acquire("account:codex:codex-a".to_string(), Some(1), None)
+        .expect("saturate the only account");
+    let queue = Arc::new(FairQueue::new(FairQueueConfig::default()));
+    let policy = KeyQueuePolicy {
+        max_queued_requests: Some(1),
+        ..KeyQueuePolicy::default()
+    };
+    let mut waiting = queue.entry(ProviderType::Codex, "key-a", Some(&policy), ["codex-a"]);
+    waiting.yield_turn().expect("first request queues");
+    let mut overflow = queue.entry(ProviderType::Codex, "key-a", Some(&policy), ["codex-a"]);
+
+    let response = match tokio::time::timeout(
+        Duration::from_secs(1),
+        select_codex_route_with_account_permit(
+            &limiter,
+            &cooldowns,
+            &CircuitBreakers::default(),
+            &mut overflow,
+            &[route],
+            &HashSet::new(),
+            None,
+            None,
+        ),
+    )
+    .await
+    .expect("a full queue must not block")
+    {
+        Ok(_) => panic!("the saturated account has no free permit"),
+        Err(response) => response,
+    };
+
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body");
+    assert!(String::from_utf8_lossy(&body).contains("key request queue is full"));
+}
+
 #[test]
 fn codex_account_cooldown_marks_only_extend_existing_window() {
     let cooldowns = CodexAccountCooldowns::default();
@@ -10509,6 +10862,91 @@ async fn kiro_dispatch_rate_limit_marks_cooldown_and_fails_over_without_same_acc
     assert!(event.retry.same_account_retry_reasons.is_empty());
 }
 
+#[tokio::test]
+async fn kiro_dispatch_fails_over_mock_upstream_server_error_and_records_usage() {
+    let _guard = crate::KIRO_UPSTREAM_ENV_LOCK
+        .lock()
+        .expect("kiro upstream env lock");
+    let mock =
+        Arc::new(llm_access_mock_upstream::MockState::new(llm_access_mock_upstream::MockConfig {
+            reply_text: "hello back".to_string(),
+            ..llm_access_mock_upstream::MockConfig::default()
+        }));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind mock upstream");
+    let upstream_base = format!("http://{}", listener.local_addr().expect("local addr"));
+    let app = llm_access_mock_upstream::router(mock);
+    tokio::spawn(async move {
+        axum::serve(listener, app)
+            .await
+            .expect("serve mock upstream");
+    });
+    std::env::set_var("KIRO_UPSTREAM_BASE_URL", &upstream_base);
+
+    let store = Arc::new(RecordingControlStore::default());
+    let state = super::ProviderState::new(
+        store.clone(),
+        Arc::new(StaticMultiKiroRouteStore {
+            codex_route: codex_route_for_account("codex-a", "upstream-token"),
+            kiro_routes: vec![
+                kiro_route_for_account("kiro-unavailable", "mock:server_error"),
+                kiro_route_for_account("kiro-healthy", "mock:ok"),
+            ],
+        }),
+    );
+    let response = super::provider_entry(
+        state,
+        Request::builder()
+            .method("POST")
+            .uri("/api/kiro-gateway/v1/messages")
+            .header("x-api-key", "valid-secret")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(
+                r#"{
+                        "model": "claude-sonnet-4-6",
+                        "max_tokens": 128,
+                        "messages": [{"role": "user", "content": "hello"}],
+                        "stream": false
+                    }"#,
+            ))
+            .expect("request"),
+    )
+    .await;
+
+    std::env::remove_var("KIRO_UPSTREAM_BASE_URL");
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("response body");
+    let body: serde_json::Value = serde_json::from_slice(&body).expect("json response");
+    assert_eq!(body["content"][0]["text"], json!("hello back"));
+
+    let stats = reqwest::get(format!("{upstream_base}/__mock/stats"))
+        .await
+        .expect("mock stats request")
+        .json::<serde_json::Value>()
+        .await
+        .expect("mock stats json");
+    assert_eq!(stats["served"]["kiro"]["ok"], json!(1));
+    assert!(
+        stats["served"]["kiro"]["server_error"]
+            .as_u64()
+            .unwrap_or_default()
+            >= 1
+    );
+
+    wait_for_usage_event_count(store.as_ref(), 1).await;
+    let events = store.usage_events.lock().expect("usage events");
+    assert_eq!(events.len(), 1);
+    let event = &events[0];
+    assert_eq!(event.status_code, 200);
+    assert_eq!(event.account_name.as_deref(), Some("kiro-healthy"));
+    assert_eq!(event.quota_failover_count, 1);
+    assert!(event.output_tokens > 0);
+}
+
 #[test]
 fn kiro_billable_tokens_discounts_cached_input_like_legacy_gateway() {
     let usage = super::KiroUsageSummary {
@@ -10537,6 +10975,8 @@ async fn kiro_websearch_usage_omits_heavy_payload_on_success() {
         status: "active".to_string(),
         quota_billable_limit: 1_000,
         billable_tokens_used: 0,
+        parent_remaining_billable: None,
+        parent_key_id: None,
     };
     let meta = super::ProviderUsageMetadata {
         started_at: Instant::now(),
@@ -10618,6 +11058,8 @@ async fn kiro_websearch_usage_captures_heavy_payload_on_error_by_default() {
         status: "active".to_string(),
         quota_billable_limit: 1_000,
         billable_tokens_used: 0,
+        parent_remaining_billable: None,
+        parent_key_id: None,
     };
     let meta = super::ProviderUsageMetadata {
         started_at: Instant::now(),
@@ -10707,6 +11149,8 @@ async fn kiro_usage_captures_full_payload_when_key_full_request_logging_enabled(
         status: "active".to_string(),
         quota_billable_limit: 1_000,
         billable_tokens_used: 0,
+        parent_remaining_billable: None,
+        parent_key_id: None,
     };
     let mut route = static_kiro_route();
     route.full_request_logging_enabled = true;
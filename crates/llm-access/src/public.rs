@@ -16,6 +16,7 @@ use llm_access_core::store::{
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 use crate::{
+    sub_keys::SubKeyView,
     usage_query::{
         AdminUsageEventView, AdminUsageEventsResponse, AdminUsageTotalsView, UsageChartResponse,
     },
@@ -101,6 +102,7 @@ struct PublicLlmGatewayUsageLookupResponse {
     has_more: bool,
     totals: AdminUsageTotalsView,
     events: Vec<PublicLlmGatewayUsageEventView>,
+    sub_keys: Vec<SubKeyView>,
     generated_at: i64,
 }
 
@@ -108,6 +110,7 @@ struct PublicLlmGatewayUsageLookupResponse {
 struct PublicLlmGatewayUsageKeyView {
     name: String,
     provider_type: String,
+    is_sub_key: bool,
     quota_billable_limit: u64,
     usage_input_uncached_tokens: u64,
     usage_input_cached_tokens: u64,
@@ -255,6 +258,7 @@ impl From<PublicUsageLookupKey> for PublicLlmGatewayUsageKeyView {
         Self {
             name: value.key_name,
             provider_type: value.provider_type,
+            is_sub_key: value.parent_key_id.is_some(),
             quota_billable_limit: value.quota_billable_limit,
             usage_input_uncached_tokens: value.usage_input_uncached_tokens,
             usage_input_cached_tokens: value.usage_input_cached_tokens,
@@ -479,6 +483,16 @@ pub(crate) async fn post_llm_gateway_public_usage_query(
         .unwrap_or(PUBLIC_USAGE_LOOKUP_DEFAULT_LIMIT)
         .clamp(1, PUBLIC_USAGE_LOOKUP_MAX_LIMIT);
     let key_id = key.key_id.clone();
+    let sub_keys = if key.parent_key_id.is_some() {
+        Vec::new()
+    } else {
+        match state.sub_key_store.list_sub_keys(&key_id).await {
+            Ok(sub_keys) => sub_keys,
+            Err(_) => {
+                return json_error(StatusCode::INTERNAL_SERVER_ERROR, "public usage store error");
+            },
+        }
+    };
     let chart_start = public_usage_chart_window_start(now);
     let chart_params = vec![
         ("key_id", key_id.clone()),
@@ -539,6 +553,7 @@ pub(crate) async fn post_llm_gateway_public_usage_query(
             .iter()
             .map(PublicLlmGatewayUsageEventView::from)
             .collect(),
+        sub_keys: sub_keys.iter().map(SubKeyView::from).collect(),
         generated_at: now,
     })
     .into_response();
@@ -548,7 +563,7 @@ pub(crate) async fn post_llm_gateway_public_usage_query(
     response
 }
 
-async fn fetch_usage_worker_json<T>(
+pub(crate) async fn fetch_usage_worker_json<T>(
     state: &HttpState,
     path: &str,
     query: &[(&str, String)],
@@ -749,7 +764,7 @@ fn public_usage_lookup_not_found() -> Response {
     json_error(StatusCode::NOT_FOUND, "queryable key not found")
 }
 
-fn json_error(status: StatusCode, message: &str) -> Response {
+pub(crate) fn json_error(status: StatusCode, message: &str) -> Response {
     (
         status,
         Json(ErrorResponse {
@@ -843,7 +858,7 @@ fn external_origin(headers: &HeaderMap) -> Option<String> {
     Some(format!("{scheme}://{host}"))
 }
 
-fn now_ms() -> i64 {
+pub(crate) fn now_ms() -> i64 {
     let millis = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .map(|duration| duration.as_millis())
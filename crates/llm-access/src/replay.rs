@@ -0,0 +1,461 @@
+//! `llm-access replay`: re-run captured requests through the current
+//! converters and diff the upstream payloads against what was recorded.
+//!
+//! Events come from the hot usage journal or a DuckDB usage database and only
+//! carry request bodies when full request logging was enabled for the route.
+//! The DuckDB source opens the file directly, so point it at a copy rather than
+//! the database a running usage worker holds.
+
+use std::{
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, Context};
+use llm_access_core::usage::UsageEvent;
+use llm_usage_journal::{collect_journal_file_lists, JournalReader};
+use serde_json::Value;
+
+use crate::{
+    config::{ReplayConfig, ReplaySource},
+    provider::{default_replay_ignore_pointers, replay_upstream_request, ReplayOutcome},
+};
+
+/// Differences printed per event before the rest are summarized.
+const MAX_PRINTED_DIFFERENCES: usize = 20;
+/// Longest JSON value rendering printed for one difference.
+const MAX_PRINTED_VALUE_CHARS: usize = 120;
+#[cfg(any(feature = "duckdb-runtime", feature = "duckdb-bundled"))]
+const DUCKDB_PAGE_SIZE: usize = 200;
+
+/// Replay the events selected by `config` and print one line per event.
+///
+/// Fails when any event differs from its recording or cannot be converted, so
+/// the command can gate a deploy.
+pub fn run_replay(config: &ReplayConfig) -> anyhow::Result<()> {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .context("failed to create runtime for replay")?
+        .block_on(replay_events(config))
+}
+
+async fn replay_events(config: &ReplayConfig) -> anyhow::Result<()> {
+    let filter = ReplayFilter::from_config(config, now_ms());
+    let events = match &config.source {
+        ReplaySource::Journal(root) => load_journal_events(root, &filter)?,
+        ReplaySource::DuckDb(path) => load_duckdb_events(path, &filter).await?,
+    };
+    let client = config.send_to.as_ref().map(|_| reqwest::Client::new());
+    let mut report = ReplayReport::default();
+    for event in &events {
+        let Some(recorded) = event.upstream_request_body_json.as_deref() else {
+            report.skipped += 1;
+            println!("skip {} no captured upstream request body", event.event_id);
+            continue;
+        };
+        let replayed = match replay_upstream_request(event) {
+            Ok(ReplayOutcome::Replayed(replayed)) => replayed,
+            Ok(ReplayOutcome::Skipped(reason)) => {
+                report.skipped += 1;
+                println!("skip {} {reason}", event.event_id);
+                continue;
+            },
+            Err(err) => {
+                report.errors += 1;
+                println!("error {} {err:#}", event.event_id);
+                continue;
+            },
+        };
+        let mut ignore = default_replay_ignore_pointers(event.provider_type)
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        ignore.extend(config.ignore_pointers.iter().cloned());
+        let differences = match (
+            serde_json::from_str::<Value>(recorded),
+            serde_json::from_slice::<Value>(&replayed.body),
+        ) {
+            (Ok(recorded), Ok(replayed)) => diff_json(&recorded, &replayed, &ignore),
+            (Err(err), _) | (_, Err(err)) => {
+                report.errors += 1;
+                println!("error {} payload is not JSON: {err}", event.event_id);
+                continue;
+            },
+        };
+        let label = format!(
+            "{} {} {} {}",
+            event.event_id,
+            event.provider_type.as_storage_str(),
+            event.endpoint,
+            event.model.as_deref().unwrap_or("-")
+        );
+        if differences.is_empty() {
+            report.matched += 1;
+            println!("match {label}");
+        } else {
+            report.differed += 1;
+            println!("diff {label} ({} differences)", differences.len());
+            for difference in differences.iter().take(MAX_PRINTED_DIFFERENCES) {
+                println!("  {difference}");
+            }
+            if differences.len() > MAX_PRINTED_DIFFERENCES {
+                println!("  ... {} more", differences.len() - MAX_PRINTED_DIFFERENCES);
+            }
+        }
+        if let (Some(client), Some(base_url)) = (&client, &config.send_to) {
+            let url = format!("{base_url}{}", replayed.path);
+            match client
+                .post(&url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(replayed.body.clone())
+                .send()
+                .await
+            {
+                Ok(response) => {
+                    println!("sent {} status={} url={url}", event.event_id, response.status())
+                },
+                Err(err) => {
+                    report.errors += 1;
+                    println!("error {} send to {url} failed: {err}", event.event_id);
+                },
+            }
+        }
+    }
+    println!(
+        "replayed {} events: matched={} differed={} skipped={} errors={}",
+        events.len(),
+        report.matched,
+        report.differed,
+        report.skipped,
+        report.errors
+    );
+    if report.differed > 0 || report.errors > 0 {
+        return Err(anyhow!(
+            "replay found {} differing and {} failing events",
+            report.differed,
+            report.errors
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Default)]
+struct ReplayReport {
+    matched: usize,
+    differed: usize,
+    skipped: usize,
+    errors: usize,
+}
+
+/// Event selection shared by both sources.
+#[derive(Debug, Clone)]
+struct ReplayFilter {
+    key_name: Option<String>,
+    event_id: Option<String>,
+    provider_type: Option<llm_access_core::provider::ProviderType>,
+    start_ms: Option<i64>,
+    limit: usize,
+}
+
+impl ReplayFilter {
+    fn from_config(config: &ReplayConfig, now_ms: i64) -> Self {
+        Self {
+            key_name: config.key_name.clone(),
+            event_id: config.event_id.clone(),
+            provider_type: config.provider_type,
+            start_ms: config.since_ms.map(|since| now_ms.saturating_sub(since)),
+            limit: config.limit,
+        }
+    }
+
+    fn matches(&self, event: &UsageEvent) -> bool {
+        self.key_name
+            .as_deref()
+            .is_none_or(|key_name| event.key_name == key_name)
+            && self
+                .event_id
+                .as_deref()
+                .is_none_or(|event_id| event.event_id == event_id)
+            && self
+                .provider_type
+                .is_none_or(|provider_type| event.provider_type == provider_type)
+            && self
+                .start_ms
+                .is_none_or(|start_ms| event.created_at_ms >= start_ms)
+    }
+
+    /// Keep the newest `limit` events, oldest first so output follows traffic
+    /// order.
+    fn finish(&self, mut events: Vec<UsageEvent>) -> Vec<UsageEvent> {
+        events.sort_by(|left, right| right.created_at_ms.cmp(&left.created_at_ms));
+        events.truncate(self.limit);
+        events.reverse();
+        events
+    }
+}
+
+fn load_journal_events(root: &Path, filter: &ReplayFilter) -> anyhow::Result<Vec<UsageEvent>> {
+    let files = collect_journal_file_lists(root)?;
+    let mut events = Vec::new();
+    for file in files
+        .sealed
+        .iter()
+        .chain(&files.consuming)
+        .chain(&files.active)
+    {
+        // Active files may end in a partially written batch; keep going with
+        // whatever else is readable.
+        let batches = match JournalReader::open(Path::new(&file.path))
+            .and_then(|reader| reader.read_all_batches())
+        {
+            Ok(batches) => batches,
+            Err(err) => {
+                eprintln!("skipping unreadable journal file `{}`: {err:#}", file.path);
+                continue;
+            },
+        };
+        events.extend(
+            batches
+                .into_iter()
+                .flat_map(|batch| batch.events)
+                .map(|event| event.into_usage_event())
+                .filter(|event| filter.matches(event)),
+        );
+    }
+    Ok(filter.finish(events))
+}
+
+#[cfg(any(feature = "duckdb-runtime", feature = "duckdb-bundled"))]
+async fn load_duckdb_events(path: &Path, filter: &ReplayFilter) -> anyhow::Result<Vec<UsageEvent>> {
+    use llm_access_core::store::{UsageAnalyticsStore, UsageEventQuery, UsageEventSource};
+    use llm_access_store::duckdb::DuckDbUsageRepository;
+
+    let repository = DuckDbUsageRepository::open_path(path)
+        .with_context(|| format!("failed to open duckdb `{}`", path.display()))?;
+    if let Some(event_id) = filter.event_id.as_deref() {
+        let event = repository.get_usage_event(event_id).await?;
+        return Ok(filter.finish(
+            event
+                .into_iter()
+                .filter(|event| filter.matches(event))
+                .collect(),
+        ));
+    }
+    let mut events = Vec::new();
+    let mut offset = 0;
+    // Pages are newest first, so stop once enough matches are collected.
+    while events.len() < filter.limit {
+        let page = repository
+            .list_usage_events(UsageEventQuery {
+                key_id: None,
+                provider_type: filter
+                    .provider_type
+                    .map(|provider_type| provider_type.as_storage_str().to_string()),
+                model: None,
+                account_name: None,
+                endpoint: None,
+                status_code: None,
+                status_kind: None,
+                source: UsageEventSource::All,
+                start_ms: filter.start_ms,
+                end_ms: None,
+                limit: DUCKDB_PAGE_SIZE,
+                offset,
+            })
+            .await?;
+        for summary in page.events.iter().filter(|event| filter.matches(event)) {
+            // Page rows omit the captured bodies; fetch the full event.
+            if let Some(event) = repository.get_usage_event(&summary.event_id).await? {
+                events.push(event);
+            }
+        }
+        if !page.has_more || page.events.is_empty() {
+            break;
+        }
+        offset += page.events.len();
+    }
+    Ok(filter.finish(events))
+}
+
+#[cfg(not(any(feature = "duckdb-runtime", feature = "duckdb-bundled")))]
+async fn load_duckdb_events(
+    _path: &Path,
+    _filter: &ReplayFilter,
+) -> anyhow::Result<Vec<UsageEvent>> {
+    Err(anyhow!("replay --duckdb requires a build with duckdb support"))
+}
+
+/// One differing location between the recorded and replayed payloads.
+#[derive(Debug, Clone, PartialEq)]
+struct JsonDifference {
+    pointer: String,
+    recorded: Option<Value>,
+    replayed: Option<Value>,
+}
+
+impl std::fmt::Display for JsonDifference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let pointer = if self.pointer.is_empty() { "/" } else { &self.pointer };
+        write!(
+            f,
+            "{pointer}: {} -> {}",
+            render_value(self.recorded.as_ref()),
+            render_value(self.replayed.as_ref())
+        )
+    }
+}
+
+fn render_value(value: Option<&Value>) -> String {
+    let Some(value) = value else {
+        return "<missing>".to_string();
+    };
+    let rendered = value.to_string();
+    if rendered.chars().count() <= MAX_PRINTED_VALUE_CHARS {
+        return rendered;
+    }
+    let mut truncated = rendered
+        .chars()
+        .take(MAX_PRINTED_VALUE_CHARS)
+        .collect::<String>();
+    truncated.push_str("...");
+    truncated
+}
+
+/// Structural diff keyed by JSON pointer. Subtrees under any `ignore` pointer
+/// are not compared.
+fn diff_json(recorded: &Value, replayed: &Value, ignore: &[String]) -> Vec<JsonDifference> {
+    let mut differences = Vec::new();
+    diff_json_at(&mut String::new(), Some(recorded), Some(replayed), ignore, &mut differences);
+    differences
+}
+
+fn diff_json_at(
+    pointer: &mut String,
+    recorded: Option<&Value>,
+    replayed: Option<&Value>,
+    ignore: &[String],
+    differences: &mut Vec<JsonDifference>,
+) {
+    if ignore.iter().any(|ignored| {
+        pointer
+            .strip_prefix(ignored.as_str())
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+    }) {
+        return;
+    }
+    match (recorded, replayed) {
+        (Some(Value::Object(left)), Some(Value::Object(right))) => {
+            let mut keys = left.keys().chain(right.keys()).collect::<Vec<_>>();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let len = pointer.len();
+                pointer.push('/');
+                pointer.push_str(&key.replace('~', "~0").replace('/', "~1"));
+                diff_json_at(pointer, left.get(key), right.get(key), ignore, differences);
+                pointer.truncate(len);
+            }
+        },
+        (Some(Value::Array(left)), Some(Value::Array(right))) => {
+            for index in 0..left.len().max(right.len()) {
+                let len = pointer.len();
+                pointer.push('/');
+                pointer.push_str(&index.to_string());
+                diff_json_at(pointer, left.get(index), right.get(index), ignore, differences);
+                pointer.truncate(len);
+            }
+        },
+        (left, right) if left != right => differences.push(JsonDifference {
+            pointer: pointer.clone(),
+            recorded: left.cloned(),
+            replayed: right.cloned(),
+        }),
+        _ => {},
+    }
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use llm_access_core::{provider::ProviderType, usage::UsageEvent};
+    use serde_json::json;
+
+    use super::{diff_json, ReplayFilter};
+
+    #[test]
+    fn diff_reports_escaped_pointers_and_honours_ignores() {
+        let recorded = json!({
+            "conversationState": {"conversationId": "a", "history": [1, 2]},
+            "a/b": {"m~n": true},
+            "profileArn": "arn"
+        });
+        let replayed = json!({
+            "conversationState": {"conversationId": "b", "history": [1, 3, 4]},
+            "a/b": {"m~n": false},
+        });
+
+        let pointers = diff_json(&recorded, &replayed, &[])
+            .into_iter()
+            .map(|difference| difference.pointer)
+            .collect::<Vec<_>>();
+        assert_eq!(pointers, vec![
+            "/a~1b/m~0n",
+            "/conversationState/conversationId",
+            "/conversationState/history/1",
+            "/conversationState/history/2",
+            "/profileArn",
+        ]);
+
+        let ignored = diff_json(&recorded, &replayed, &[
+            "/conversationState/conversationId".to_string(),
+            "/conversationState/history".to_string(),
+            "/profile".to_string(),
+        ]);
+        assert_eq!(ignored.len(), 2);
+        assert_eq!(ignored[1].pointer, "/profileArn");
+        assert_eq!(ignored[1].to_string(), "/profileArn: \"arn\" -> <missing>");
+        assert!(diff_json(&recorded, &recorded, &[]).is_empty());
+    }
+
+    #[test]
+    fn filter_matches_fields_and_keeps_newest_in_traffic_order() {
+        let filter = ReplayFilter {
+            key_name: Some("ci".to_string()),
+            event_id: None,
+            provider_type: Some(ProviderType::Kiro),
+            start_ms: Some(100),
+            limit: 2,
+        };
+        let event = |event_id: &str, created_at_ms: i64| UsageEvent {
+            event_id: event_id.to_string(),
+            created_at_ms,
+            provider_type: ProviderType::Kiro,
+            key_name: "ci".to_string(),
+            ..UsageEvent::default()
+        };
+        assert!(filter.matches(&event("a", 100)));
+        assert!(!filter.matches(&event("a", 99)));
+        assert!(!filter.matches(&UsageEvent {
+            key_name: "other".to_string(),
+            ..event("a", 200)
+        }));
+        assert!(!filter.matches(&UsageEvent {
+            provider_type: ProviderType::Codex,
+            ..event("a", 200)
+        }));
+
+        let kept = filter.finish(vec![event("old", 100), event("newest", 300), event("mid", 200)]);
+        let ids = kept
+            .iter()
+            .map(|event| event.event_id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["mid", "newest"]);
+    }
+}
@@ -12,18 +12,21 @@ use anyhow::{anyhow, Context};
 use async_trait::async_trait;
 use llm_access_core::store::{
     AdminAccountGroupStore, AdminAnthropicUpstreamStore, AdminCodexAccountStore, AdminConfigStore,
-    AdminKeyStore, AdminKiroAccountStore, AdminProxyStore, AdminReviewQueueStore, ControlStore,
-    EmptyAdminAccountGroupStore, EmptyAdminAnthropicUpstreamStore, EmptyAdminCodexAccountStore,
-    EmptyAdminConfigStore, EmptyAdminKeyStore, EmptyAdminKiroAccountStore, EmptyAdminProxyStore,
-    EmptyAdminReviewQueueStore, EmptyProviderRouteStore, EmptyPublicAccessStore,
-    EmptyPublicCommunityStore, EmptyPublicStatusStore, EmptyPublicSubmissionStore,
-    EmptyPublicUsageStore, ProviderRouteStore, PublicAccessStore, PublicCommunityStore,
-    PublicStatusStore, PublicSubmissionStore, PublicUsageStore, DEFAULT_CODEX_CLIENT_VERSION,
+    AdminKeyStore, AdminKiroAccountStore, AdminOpenAiUpstreamStore, AdminProxyStore,
+    AdminReviewQueueStore, BatchStore, ControlStore, EmptyAdminAccountGroupStore,
+    EmptyAdminAnthropicUpstreamStore, EmptyAdminCodexAccountStore, EmptyAdminConfigStore,
+    EmptyAdminKeyStore, EmptyAdminKiroAccountStore, EmptyAdminOpenAiUpstreamStore,
+    EmptyAdminProxyStore, EmptyAdminReviewQueueStore, EmptyBatchStore, EmptyKeyPortalStore,
+    EmptyProviderRouteStore, EmptyPublicAccessStore, EmptyPublicCommunityStore,
+    EmptyPublicStatusStore, EmptyPublicSubmissionStore, EmptyPublicUsageStore, EmptySubKeyStore,
+    KeyPortalStore, ProviderRouteStore, PublicAccessStore, PublicCommunityStore, PublicStatusStore,
+    PublicSubmissionStore, PublicUsageStore, SubKeyStore, DEFAULT_CODEX_CLIENT_VERSION,
 };
 #[cfg(any(feature = "duckdb-runtime", feature = "duckdb-bundled"))]
 use llm_access_core::store::{
     AdminKey, AdminKeyPatch, AdminKeysPage, AdminPageRequest, AdminRuntimeConfig, AuthenticatedKey,
-    KeyUsageRollupDelta, NewAdminKey, PublicAccessKey, PublicUsageLookupKey, UsageEventSink,
+    BatchItemOutcome, BatchItemResult, BatchJob, ClaimedBatchItem, KeyUsageRollupDelta,
+    NewAdminKey, NewBatchJob, PublicAccessKey, PublicUsageLookupKey, UsageEventSink,
     UsageRollupBatch, UsageRollupBatchSink, UsageRollupDigestMismatch,
 };
 #[cfg(any(feature = "duckdb-runtime", feature = "duckdb-bundled"))]
@@ -73,7 +76,11 @@ pub struct LlmAccessRuntime {
     admin_codex_account_store: Arc<dyn AdminCodexAccountStore>,
     admin_kiro_account_store: Arc<dyn AdminKiroAccountStore>,
     admin_anthropic_upstream_store: Arc<dyn AdminAnthropicUpstreamStore>,
+    admin_openai_upstream_store: Arc<dyn AdminOpenAiUpstreamStore>,
     admin_review_queue_store: Arc<dyn AdminReviewQueueStore>,
+    sub_key_store: Arc<dyn SubKeyStore>,
+    key_portal_store: Arc<dyn KeyPortalStore>,
+    batch_store: Arc<dyn BatchStore>,
     public_access_store: Arc<dyn PublicAccessStore>,
     public_community_store: Arc<dyn PublicCommunityStore>,
     public_usage_store: Arc<dyn PublicUsageStore>,
@@ -104,7 +111,11 @@ struct LlmAccessStores {
     admin_codex_account_store: Arc<dyn AdminCodexAccountStore>,
     admin_kiro_account_store: Arc<dyn AdminKiroAccountStore>,
     admin_anthropic_upstream_store: Arc<dyn AdminAnthropicUpstreamStore>,
+    admin_openai_upstream_store: Arc<dyn AdminOpenAiUpstreamStore>,
     admin_review_queue_store: Arc<dyn AdminReviewQueueStore>,
+    sub_key_store: Arc<dyn SubKeyStore>,
+    key_portal_store: Arc<dyn KeyPortalStore>,
+    batch_store: Arc<dyn BatchStore>,
     public_access_store: Arc<dyn PublicAccessStore>,
     public_community_store: Arc<dyn PublicCommunityStore>,
     public_usage_store: Arc<dyn PublicUsageStore>,
@@ -132,7 +143,11 @@ trait RuntimeRepository:
     + AdminCodexAccountStore
     + AdminKiroAccountStore
     + AdminAnthropicUpstreamStore
+    + AdminOpenAiUpstreamStore
     + AdminReviewQueueStore
+    + SubKeyStore
+    + KeyPortalStore
+    + BatchStore
     + PublicAccessStore
     + PublicCommunityStore
     + PublicUsageStore
@@ -155,7 +170,11 @@ impl<T> RuntimeRepository for T where
         + AdminCodexAccountStore
         + AdminKiroAccountStore
         + AdminAnthropicUpstreamStore
+        + AdminOpenAiUpstreamStore
         + AdminReviewQueueStore
+        + SubKeyStore
+        + KeyPortalStore
+        + BatchStore
         + PublicAccessStore
         + PublicCommunityStore
         + PublicUsageStore
@@ -178,7 +197,11 @@ trait RuntimeRepository:
     + AdminCodexAccountStore
     + AdminKiroAccountStore
     + AdminAnthropicUpstreamStore
+    + AdminOpenAiUpstreamStore
     + AdminReviewQueueStore
+    + SubKeyStore
+    + KeyPortalStore
+    + BatchStore
     + PublicAccessStore
     + PublicCommunityStore
     + PublicUsageStore
@@ -200,7 +223,11 @@ impl<T> RuntimeRepository for T where
         + AdminCodexAccountStore
         + AdminKiroAccountStore
         + AdminAnthropicUpstreamStore
+        + AdminOpenAiUpstreamStore
         + AdminReviewQueueStore
+        + SubKeyStore
+        + KeyPortalStore
+        + BatchStore
         + PublicAccessStore
         + PublicCommunityStore
         + PublicUsageStore
@@ -259,7 +286,11 @@ impl LlmAccessRuntime {
             admin_codex_account_store: Arc::new(EmptyAdminCodexAccountStore),
             admin_kiro_account_store: Arc::new(EmptyAdminKiroAccountStore),
             admin_anthropic_upstream_store: Arc::new(EmptyAdminAnthropicUpstreamStore),
+            admin_openai_upstream_store: Arc::new(EmptyAdminOpenAiUpstreamStore),
             admin_review_queue_store: Arc::new(EmptyAdminReviewQueueStore),
+            sub_key_store: Arc::new(EmptySubKeyStore),
+            key_portal_store: Arc::new(EmptyKeyPortalStore),
+            batch_store: Arc::new(EmptyBatchStore),
             public_access_store: Arc::new(EmptyPublicAccessStore),
             public_community_store: Arc::new(EmptyPublicCommunityStore),
             public_usage_store: Arc::new(EmptyPublicUsageStore),
@@ -293,7 +324,11 @@ impl LlmAccessRuntime {
             admin_codex_account_store: stores.admin_codex_account_store,
             admin_kiro_account_store: stores.admin_kiro_account_store,
             admin_anthropic_upstream_store: stores.admin_anthropic_upstream_store,
+            admin_openai_upstream_store: stores.admin_openai_upstream_store,
             admin_review_queue_store: stores.admin_review_queue_store,
+            sub_key_store: stores.sub_key_store,
+            key_portal_store: stores.key_portal_store,
+            batch_store: stores.batch_store,
             public_access_store: stores.public_access_store,
             public_community_store: stores.public_community_store,
             public_usage_store: stores.public_usage_store,
@@ -428,7 +463,17 @@ impl LlmAccessRuntime {
         let admin_kiro_account_store: Arc<dyn AdminKiroAccountStore> = repository.clone();
         let admin_anthropic_upstream_store: Arc<dyn AdminAnthropicUpstreamStore> =
             repository.clone();
+        let admin_openai_upstream_store: Arc<dyn AdminOpenAiUpstreamStore> = repository.clone();
         let admin_review_queue_store: Arc<dyn AdminReviewQueueStore> = repository.clone();
+        let sub_key_store: Arc<dyn SubKeyStore> = repository.clone();
+        let key_portal_store: Arc<dyn KeyPortalStore> = repository.clone();
+        #[cfg(any(feature = "duckdb-runtime", feature = "duckdb-bundled"))]
+        let batch_store: Arc<dyn BatchStore> = Arc::new(UsageAccountingBatchStore {
+            batch_store: repository.clone(),
+            usage_accounting: usage_accounting.clone(),
+        });
+        #[cfg(not(any(feature = "duckdb-runtime", feature = "duckdb-bundled")))]
+        let batch_store: Arc<dyn BatchStore> = repository.clone();
         #[cfg(any(feature = "duckdb-runtime", feature = "duckdb-bundled"))]
         let public_access_store: Arc<dyn PublicAccessStore> =
             Arc::new(UsageAccountingPublicAccessStore {
@@ -460,7 +505,11 @@ impl LlmAccessRuntime {
             admin_codex_account_store,
             admin_kiro_account_store,
             admin_anthropic_upstream_store,
+            admin_openai_upstream_store,
             admin_review_queue_store,
+            sub_key_store,
+            key_portal_store,
+            batch_store,
             public_access_store,
             public_community_store,
             public_usage_store,
@@ -548,11 +597,31 @@ impl LlmAccessRuntime {
         Arc::clone(&self.admin_anthropic_upstream_store)
     }
 
+    /// Admin OpenAI-compatible upstream store used by LLM gateway endpoints.
+    pub fn admin_openai_upstream_store(&self) -> Arc<dyn AdminOpenAiUpstreamStore> {
+        Arc::clone(&self.admin_openai_upstream_store)
+    }
+
     /// Admin review queue store used by local admin endpoints.
     pub fn admin_review_queue_store(&self) -> Arc<dyn AdminReviewQueueStore> {
         Arc::clone(&self.admin_review_queue_store)
     }
 
+    /// Sub-key store used by the self-service sub-key endpoints.
+    pub fn sub_key_store(&self) -> Arc<dyn SubKeyStore> {
+        Arc::clone(&self.sub_key_store)
+    }
+
+    /// Key portal store used by the self-service key portal endpoints.
+    pub fn key_portal_store(&self) -> Arc<dyn KeyPortalStore> {
+        Arc::clone(&self.key_portal_store)
+    }
+
+    /// Batch queue store used by the batch endpoints and worker.
+    pub fn batch_store(&self) -> Arc<dyn BatchStore> {
+        Arc::clone(&self.batch_store)
+    }
+
     /// Public access store used by unauthenticated public endpoints.
     pub fn public_access_store(&self) -> Arc<dyn PublicAccessStore> {
         Arc::clone(&self.public_access_store)
@@ -926,6 +995,10 @@ fn usage_rollup_retry_backoff(flush_interval: Duration) -> Duration {
 struct UsageAccounting {
     tx: mpsc::Sender<Vec<UsageEvent>>,
     pending_rollups: Arc<PendingUsageRollups>,
+    /// Sub-keys seen at auth time, by parent. Pending rollups are keyed by the
+    /// key that served the request, so a parent's budget has to add up the
+    /// pending spend of its children itself.
+    sub_keys_by_parent: RwLock<HashMap<String, HashSet<String>>>,
 }
 
 #[cfg(any(feature = "duckdb-runtime", feature = "duckdb-bundled"))]
@@ -955,20 +1028,75 @@ impl UsageAccounting {
             Arc::new(Self {
                 tx,
                 pending_rollups,
+                sub_keys_by_parent: RwLock::new(HashMap::new()),
             }),
             handle,
         ))
     }
 
     fn overlay_authenticated_key(&self, mut key: AuthenticatedKey) -> AuthenticatedKey {
-        if let Some(delta) = self.pending_rollups.delta_for_key(&key.key_id) {
-            key.billable_tokens_used = key
-                .billable_tokens_used
-                .saturating_add(delta.billable_tokens);
+        key.billable_tokens_used = key
+            .billable_tokens_used
+            .saturating_add(self.pending_billable_tokens(&key.key_id));
+        match key.parent_key_id.as_deref() {
+            Some(parent_key_id) => {
+                self.remember_sub_key(parent_key_id, &key.key_id);
+                let parent_pending = self
+                    .pending_billable_tokens(parent_key_id)
+                    .saturating_add(self.pending_sub_key_billable_tokens(parent_key_id));
+                if let Some(remaining) = key.parent_remaining_billable.as_mut() {
+                    *remaining = remaining.saturating_sub(parent_pending);
+                }
+            },
+            None => {
+                key.billable_tokens_used = key
+                    .billable_tokens_used
+                    .saturating_add(self.pending_sub_key_billable_tokens(&key.key_id));
+            },
         }
         key
     }
 
+    fn pending_billable_tokens(&self, key_id: &str) -> i64 {
+        self.pending_rollups
+            .delta_for_key(key_id)
+            .map_or(0, |delta| delta.billable_tokens)
+    }
+
+    /// Pending spend of every known sub-key of `parent_key_id`.
+    fn pending_sub_key_billable_tokens(&self, parent_key_id: &str) -> i64 {
+        let Ok(sub_keys_by_parent) = self.sub_keys_by_parent.read() else {
+            return 0;
+        };
+        sub_keys_by_parent
+            .get(parent_key_id)
+            .into_iter()
+            .flatten()
+            .fold(0i64, |total, sub_key_id| {
+                total.saturating_add(self.pending_billable_tokens(sub_key_id))
+            })
+    }
+
+    fn remember_sub_key(&self, parent_key_id: &str, sub_key_id: &str) {
+        let known = self
+            .sub_keys_by_parent
+            .read()
+            .is_ok_and(|sub_keys_by_parent| {
+                sub_keys_by_parent
+                    .get(parent_key_id)
+                    .is_some_and(|sub_keys| sub_keys.contains(sub_key_id))
+            });
+        if known {
+            return;
+        }
+        if let Ok(mut sub_keys_by_parent) = self.sub_keys_by_parent.write() {
+            sub_keys_by_parent
+                .entry(parent_key_id.to_string())
+                .or_default()
+                .insert(sub_key_id.to_string());
+        }
+    }
+
     fn overlay_admin_key(&self, mut key: AdminKey) -> AdminKey {
         if let Some(delta) = self.pending_rollups.delta_for_key(&key.id) {
             key.usage_input_uncached_tokens =
@@ -1980,6 +2108,16 @@ impl ControlStore for UsageAccountingControlStore {
             .record_anthropic_upstream_channel_usage(channel_name, delta)
             .await
     }
+
+    async fn record_openai_upstream_channel_usage(
+        &self,
+        channel_name: &str,
+        delta: llm_access_core::store::OpenAiUpstreamChannelUsageDelta,
+    ) -> anyhow::Result<()> {
+        self.control_store
+            .record_openai_upstream_channel_usage(channel_name, delta)
+            .await
+    }
 }
 
 #[cfg(any(feature = "duckdb-runtime", feature = "duckdb-bundled"))]
@@ -2059,6 +2197,103 @@ impl AdminKeyStore for UsageAccountingAdminKeyStore {
     }
 }
 
+#[cfg(any(feature = "duckdb-runtime", feature = "duckdb-bundled"))]
+struct UsageAccountingBatchStore {
+    batch_store: Arc<dyn BatchStore>,
+    usage_accounting: Arc<UsageAccounting>,
+}
+
+#[cfg(any(feature = "duckdb-runtime", feature = "duckdb-bundled"))]
+#[async_trait]
+impl BatchStore for UsageAccountingBatchStore {
+    async fn create_batch_job(&self, job: NewBatchJob) -> anyhow::Result<BatchJob> {
+        self.batch_store.create_batch_job(job).await
+    }
+
+    async fn list_batch_jobs(&self, key_id: &str, limit: usize) -> anyhow::Result<Vec<BatchJob>> {
+        self.batch_store.list_batch_jobs(key_id, limit).await
+    }
+
+    async fn get_batch_job(
+        &self,
+        key_id: &str,
+        batch_id: &str,
+    ) -> anyhow::Result<Option<BatchJob>> {
+        self.batch_store.get_batch_job(key_id, batch_id).await
+    }
+
+    async fn cancel_batch_job(
+        &self,
+        key_id: &str,
+        batch_id: &str,
+        now_ms: i64,
+    ) -> anyhow::Result<Option<BatchJob>> {
+        self.batch_store
+            .cancel_batch_job(key_id, batch_id, now_ms)
+            .await
+    }
+
+    async fn list_batch_item_results(
+        &self,
+        key_id: &str,
+        batch_id: &str,
+    ) -> anyhow::Result<Vec<BatchItemResult>> {
+        self.batch_store
+            .list_batch_item_results(key_id, batch_id)
+            .await
+    }
+
+    async fn claim_batch_items(
+        &self,
+        limit: usize,
+        now_ms: i64,
+        lease_expired_before_ms: i64,
+    ) -> anyhow::Result<Vec<ClaimedBatchItem>> {
+        Ok(self
+            .batch_store
+            .claim_batch_items(limit, now_ms, lease_expired_before_ms)
+            .await?
+            .into_iter()
+            .map(|mut item| {
+                item.key = self.usage_accounting.overlay_authenticated_key(item.key);
+                item
+            })
+            .collect())
+    }
+
+    async fn complete_batch_item(
+        &self,
+        batch_id: &str,
+        item_index: i64,
+        outcome: BatchItemOutcome,
+    ) -> anyhow::Result<()> {
+        self.batch_store
+            .complete_batch_item(batch_id, item_index, outcome)
+            .await
+    }
+
+    async fn release_batch_item(
+        &self,
+        batch_id: &str,
+        item_index: i64,
+        retry_at_ms: i64,
+    ) -> anyhow::Result<()> {
+        self.batch_store
+            .release_batch_item(batch_id, item_index, retry_at_ms)
+            .await
+    }
+
+    async fn expire_batch_jobs(
+        &self,
+        now_ms: i64,
+        lease_expired_before_ms: i64,
+    ) -> anyhow::Result<u64> {
+        self.batch_store
+            .expire_batch_jobs(now_ms, lease_expired_before_ms)
+            .await
+    }
+}
+
 #[cfg(any(feature = "duckdb-runtime", feature = "duckdb-bundled"))]
 struct UsageAccountingPublicAccessStore {
     public_access_store: Arc<dyn PublicAccessStore>,
@@ -2167,9 +2402,11 @@ mod tests {
     use llm_access_core::{
         provider::{ProtocolFamily, ProviderType},
         store::{
-            AdminRuntimeConfig, AnthropicUpstreamChannelUsageDelta, AuthenticatedKey, ControlStore,
-            KeyUsageRollupDelta, UsageEventSink, UsageRollupApplyReport, UsageRollupBatch,
-            UsageRollupBatchSink, UsageRollupDigestMismatch,
+            AdminRuntimeConfig, AnthropicUpstreamChannelUsageDelta, AuthenticatedKey,
+            BatchItemOutcome, BatchItemResult, BatchJob, BatchStore, ClaimedBatchItem,
+            ControlStore, KeyUsageRollupDelta, NewBatchJob, OpenAiUpstreamChannelUsageDelta,
+            UsageEventSink, UsageRollupApplyReport, UsageRollupBatch, UsageRollupBatchSink,
+            UsageRollupDigestMismatch,
         },
         usage::UsageEvent,
     };
@@ -2414,6 +2651,7 @@ mod tests {
     struct StaticControlStore {
         key: AuthenticatedKey,
         anthropic_channel_usage: Arc<Mutex<Vec<(String, AnthropicUpstreamChannelUsageDelta)>>>,
+        openai_channel_usage: Arc<Mutex<Vec<(String, OpenAiUpstreamChannelUsageDelta)>>>,
     }
 
     #[cfg(any(feature = "duckdb-runtime", feature = "duckdb-bundled"))]
@@ -2450,6 +2688,99 @@ mod tests {
                 .push((channel_name.to_string(), delta));
             Ok(())
         }
+
+        async fn record_openai_upstream_channel_usage(
+            &self,
+            channel_name: &str,
+            delta: OpenAiUpstreamChannelUsageDelta,
+        ) -> anyhow::Result<()> {
+            self.openai_channel_usage
+                .lock()
+                .await
+                .push((channel_name.to_string(), delta));
+            Ok(())
+        }
+    }
+
+    #[cfg(any(feature = "duckdb-runtime", feature = "duckdb-bundled"))]
+    struct StaticBatchStore {
+        item: ClaimedBatchItem,
+    }
+
+    #[cfg(any(feature = "duckdb-runtime", feature = "duckdb-bundled"))]
+    #[async_trait::async_trait]
+    impl BatchStore for StaticBatchStore {
+        async fn create_batch_job(&self, _job: NewBatchJob) -> anyhow::Result<BatchJob> {
+            anyhow::bail!("static batch store does not create batches")
+        }
+
+        async fn list_batch_jobs(
+            &self,
+            _key_id: &str,
+            _limit: usize,
+        ) -> anyhow::Result<Vec<BatchJob>> {
+            Ok(Vec::new())
+        }
+
+        async fn get_batch_job(
+            &self,
+            _key_id: &str,
+            _batch_id: &str,
+        ) -> anyhow::Result<Option<BatchJob>> {
+            Ok(None)
+        }
+
+        async fn cancel_batch_job(
+            &self,
+            _key_id: &str,
+            _batch_id: &str,
+            _now_ms: i64,
+        ) -> anyhow::Result<Option<BatchJob>> {
+            Ok(None)
+        }
+
+        async fn list_batch_item_results(
+            &self,
+            _key_id: &str,
+            _batch_id: &str,
+        ) -> anyhow::Result<Vec<BatchItemResult>> {
+            Ok(Vec::new())
+        }
+
+        async fn claim_batch_items(
+            &self,
+            _limit: usize,
+            _now_ms: i64,
+            _lease_expired_before_ms: i64,
+        ) -> anyhow::Result<Vec<ClaimedBatchItem>> {
+            Ok(vec![self.item.clone()])
+        }
+
+        async fn complete_batch_item(
+            &self,
+            _batch_id: &str,
+            _item_index: i64,
+            _outcome: BatchItemOutcome,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn release_batch_item(
+            &self,
+            _batch_id: &str,
+            _item_index: i64,
+            _retry_at_ms: i64,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn expire_batch_jobs(
+            &self,
+            _now_ms: i64,
+            _lease_expired_before_ms: i64,
+        ) -> anyhow::Result<u64> {
+            Ok(0)
+        }
     }
 
     #[cfg(any(feature = "duckdb-runtime", feature = "duckdb-bundled"))]
@@ -2647,6 +2978,8 @@ mod tests {
             status: "active".to_string(),
             quota_billable_limit: 100,
             billable_tokens_used: 5,
+            parent_remaining_billable: None,
+            parent_key_id: None,
         }
     }
 
@@ -3066,6 +3399,7 @@ mod tests {
             Arc::new(StaticControlStore {
                 key: sample_authenticated_key(),
                 anthropic_channel_usage: Arc::new(Mutex::new(Vec::new())),
+                openai_channel_usage: Arc::new(Mutex::new(Vec::new())),
             }),
             accounting.clone(),
         );
@@ -3099,6 +3433,111 @@ mod tests {
         ]]);
     }
 
+    #[cfg(any(feature = "duckdb-runtime", feature = "duckdb-bundled"))]
+    #[tokio::test]
+    async fn usage_accounting_overlays_claimed_batch_item_keys() {
+        let rollup_sink = Arc::new(RecordingUsageRollupSink::default());
+        let analytics_sink = Arc::new(RecordingUsageEventSink::default());
+        let (_journal_root, journal_sink) = test_journal_sink();
+        let (_backlog_root, rollup_backlog) = test_rollup_backlog();
+        let runtime_config = Arc::new(RwLock::new(AdminRuntimeConfig {
+            usage_event_flush_batch_size: 2,
+            usage_event_flush_interval_seconds: 3600,
+            usage_event_flush_max_buffer_bytes: 8 * 1024 * 1024,
+            ..AdminRuntimeConfig::default()
+        }));
+        let (accounting, _handle) = super::UsageAccounting::new(
+            rollup_sink,
+            journal_sink,
+            analytics_sink,
+            runtime_config,
+            rollup_backlog,
+            super::PendingUsageRollups::default(),
+            Some("node-test".to_string()),
+        )
+        .expect("usage accounting");
+        let batch_store = super::UsageAccountingBatchStore {
+            batch_store: Arc::new(StaticBatchStore {
+                item: ClaimedBatchItem {
+                    batch_id: "batch-1".to_string(),
+                    item_index: 0,
+                    custom_id: "req-1".to_string(),
+                    endpoint: "/cc/v1/messages".to_string(),
+                    body_json: "{}".to_string(),
+                    attempts: 1,
+                    key: sample_authenticated_key(),
+                },
+            }),
+            usage_accounting: accounting.clone(),
+        };
+
+        accounting
+            .append_usage_event(&sample_usage_event("evt-1"))
+            .await
+            .expect("enqueue event");
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let items = batch_store
+            .claim_batch_items(1, 0, 0)
+            .await
+            .expect("claim batch items");
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].key.billable_tokens_used, 17);
+    }
+
+    #[cfg(any(feature = "duckdb-runtime", feature = "duckdb-bundled"))]
+    #[tokio::test]
+    async fn usage_accounting_charges_pending_sub_key_usage_to_siblings_and_parent() {
+        let rollup_sink = Arc::new(RecordingUsageRollupSink::default());
+        let analytics_sink = Arc::new(RecordingUsageEventSink::default());
+        let (_journal_root, journal_sink) = test_journal_sink();
+        let (_backlog_root, rollup_backlog) = test_rollup_backlog();
+        let runtime_config = Arc::new(RwLock::new(AdminRuntimeConfig {
+            usage_event_flush_batch_size: 2,
+            usage_event_flush_interval_seconds: 3600,
+            usage_event_flush_max_buffer_bytes: 8 * 1024 * 1024,
+            ..AdminRuntimeConfig::default()
+        }));
+        let (accounting, _handle) = super::UsageAccounting::new(
+            rollup_sink,
+            journal_sink,
+            analytics_sink,
+            runtime_config,
+            rollup_backlog,
+            super::PendingUsageRollups::default(),
+            Some("node-test".to_string()),
+        )
+        .expect("usage accounting");
+        let sub_key = |key_id: &str| AuthenticatedKey {
+            key_id: key_id.to_string(),
+            quota_billable_limit: 1_000,
+            billable_tokens_used: 0,
+            parent_remaining_billable: Some(20),
+            parent_key_id: Some("key-parent".to_string()),
+            ..sample_authenticated_key()
+        };
+        accounting.overlay_authenticated_key(sub_key("key-sub-a"));
+        accounting.overlay_authenticated_key(sub_key("key-sub-b"));
+
+        let mut event = sample_usage_event("evt-1");
+        event.key_id = "key-sub-a".to_string();
+        accounting
+            .append_usage_event(&event)
+            .await
+            .expect("enqueue event");
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let sibling = accounting.overlay_authenticated_key(sub_key("key-sub-b"));
+        assert_eq!(sibling.billable_tokens_used, 0);
+        assert_eq!(sibling.parent_remaining_billable, Some(8));
+        let parent = accounting.overlay_authenticated_key(AuthenticatedKey {
+            key_id: "key-parent".to_string(),
+            billable_tokens_used: 0,
+            ..sample_authenticated_key()
+        });
+        assert_eq!(parent.billable_tokens_used, 12);
+    }
+
     #[cfg(any(feature = "duckdb-runtime", feature = "duckdb-bundled"))]
     #[tokio::test]
     async fn usage_accounting_control_store_forwards_anthropic_channel_usage() {
@@ -3122,6 +3561,7 @@ mod tests {
             Arc::new(StaticControlStore {
                 key: sample_authenticated_key(),
                 anthropic_channel_usage: recorded_usage.clone(),
+                openai_channel_usage: Arc::new(Mutex::new(Vec::new())),
             }),
             accounting,
         );
@@ -3142,6 +3582,50 @@ mod tests {
         assert_eq!(recorded_usage.lock().await.as_slice(), &[("yl".to_string(), delta)]);
     }
 
+    #[cfg(any(feature = "duckdb-runtime", feature = "duckdb-bundled"))]
+    #[tokio::test]
+    async fn usage_accounting_control_store_forwards_openai_channel_usage() {
+        let rollup_sink = Arc::new(RecordingUsageRollupSink::default());
+        let analytics_sink = Arc::new(RecordingUsageEventSink::default());
+        let (_journal_root, journal_sink) = test_journal_sink();
+        let (_backlog_root, rollup_backlog) = test_rollup_backlog();
+        let runtime_config = Arc::new(RwLock::new(AdminRuntimeConfig::default()));
+        let (accounting, _handle) = super::UsageAccounting::new(
+            rollup_sink,
+            journal_sink,
+            analytics_sink,
+            runtime_config,
+            rollup_backlog,
+            super::PendingUsageRollups::default(),
+            Some("node-test".to_string()),
+        )
+        .expect("usage accounting");
+        let recorded_usage = Arc::new(Mutex::new(Vec::new()));
+        let control_store = super::UsageAccountingControlStore::new(
+            Arc::new(StaticControlStore {
+                key: sample_authenticated_key(),
+                anthropic_channel_usage: Arc::new(Mutex::new(Vec::new())),
+                openai_channel_usage: recorded_usage.clone(),
+            }),
+            accounting,
+        );
+        let delta = OpenAiUpstreamChannelUsageDelta {
+            input_uncached_tokens: 11,
+            input_cached_tokens: 3,
+            output_tokens: 5,
+            billable_tokens: 36,
+            usage_missing: false,
+            used_at_ms: 1_700_000_000_123,
+        };
+
+        control_store
+            .record_openai_upstream_channel_usage("vllm-a", delta)
+            .await
+            .expect("forward channel usage");
+
+        assert_eq!(recorded_usage.lock().await.as_slice(), &[("vllm-a".to_string(), delta)]);
+    }
+
     #[cfg(any(feature = "duckdb-runtime", feature = "duckdb-bundled"))]
     #[tokio::test]
     async fn usage_accounting_loads_initial_rollup_backlog_into_overlay() {
@@ -3185,6 +3669,7 @@ mod tests {
             Arc::new(StaticControlStore {
                 key: sample_authenticated_key(),
                 anthropic_channel_usage: Arc::new(Mutex::new(Vec::new())),
+                openai_channel_usage: Arc::new(Mutex::new(Vec::new())),
             }),
             accounting,
         );
@@ -0,0 +1,296 @@
+//! Self-service sub-key endpoints authenticated by the parent key's bearer
+//! secret.
+//!
+//! A key holder mints sub-keys from its own remaining quota, lists them and
+//! revokes them without going through an admin. Sub-keys cannot mint further
+//! sub-keys.
+
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use llm_access_core::store::{
+    delegable_sub_key_quota, AdminKey, NewSubKey, SubKeyQuotaExceeded, KEY_STATUS_ACTIVE,
+    MAX_SUB_KEYS_PER_PARENT,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    key_portal::authenticate_key_holder,
+    public::{json_error, now_ms},
+    HttpState,
+};
+
+const MAX_SUB_KEY_NAME_CHARS: usize = 80;
+const MAX_SUB_KEY_REQUEST_MAX_CONCURRENCY: u64 = 1_024;
+const MAX_SUB_KEY_REQUEST_MIN_START_INTERVAL_MS: u64 = 300_000;
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct CreateSubKeyRequest {
+    name: String,
+    quota_billable_limit: u64,
+    #[serde(default)]
+    request_max_concurrency: Option<u64>,
+    #[serde(default)]
+    request_min_start_interval_ms: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+struct SubKeysResponse {
+    parent: SubKeyParentView,
+    sub_keys: Vec<SubKeyView>,
+    max_sub_keys: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct SubKeyParentView {
+    id: String,
+    name: String,
+    quota_billable_limit: u64,
+    remaining_billable: i64,
+    delegable_billable: u64,
+    request_max_concurrency: Option<u64>,
+    request_min_start_interval_ms: Option<u64>,
+}
+
+/// One sub-key as shown to its parent's holder. Never carries the secret.
+#[derive(Debug, Serialize)]
+pub(crate) struct SubKeyView {
+    id: String,
+    name: String,
+    status: String,
+    quota_billable_limit: u64,
+    usage_billable_tokens: u64,
+    remaining_billable: i64,
+    request_max_concurrency: Option<u64>,
+    request_min_start_interval_ms: Option<u64>,
+    last_used_at: Option<i64>,
+    created_at: i64,
+}
+
+#[derive(Debug, Serialize)]
+struct CreatedSubKeyResponse {
+    #[serde(flatten)]
+    key: SubKeyView,
+    /// Plaintext secret, returned only once at creation.
+    secret: String,
+}
+
+impl From<&AdminKey> for SubKeyView {
+    fn from(value: &AdminKey) -> Self {
+        Self {
+            id: value.id.clone(),
+            name: value.name.clone(),
+            status: value.status.clone(),
+            quota_billable_limit: value.quota_billable_limit,
+            usage_billable_tokens: used_billable(value),
+            remaining_billable: value.remaining_billable,
+            request_max_concurrency: value.request_max_concurrency,
+            request_min_start_interval_ms: value.request_min_start_interval_ms,
+            last_used_at: value.last_used_at,
+            created_at: value.created_at,
+        }
+    }
+}
+
+pub(crate) async fn list_sub_keys(State(state): State<HttpState>, headers: HeaderMap) -> Response {
+    let parent = match authenticate_parent(&state, &headers).await {
+        Ok(parent) => parent,
+        Err(response) => return response,
+    };
+    let sub_keys = match state.sub_key_store.list_sub_keys(&parent.id).await {
+        Ok(sub_keys) => sub_keys,
+        Err(_) => return json_error(StatusCode::INTERNAL_SERVER_ERROR, "sub-key store error"),
+    };
+    Json(SubKeysResponse {
+        parent: parent_view(&parent, &sub_keys),
+        sub_keys: sub_keys.iter().map(SubKeyView::from).collect(),
+        max_sub_keys: MAX_SUB_KEYS_PER_PARENT,
+    })
+    .into_response()
+}
+
+pub(crate) async fn create_sub_key(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+    Json(request): Json<CreateSubKeyRequest>,
+) -> Response {
+    let parent = match authenticate_parent(&state, &headers).await {
+        Ok(parent) => parent,
+        Err(response) => return response,
+    };
+    let name = request.name.trim();
+    if name.is_empty() || name.chars().count() > MAX_SUB_KEY_NAME_CHARS {
+        return json_error(StatusCode::BAD_REQUEST, "name must be 1-80 characters");
+    }
+    if request.quota_billable_limit == 0 || request.quota_billable_limit > i64::MAX as u64 {
+        return json_error(StatusCode::BAD_REQUEST, "quota_billable_limit is out of range");
+    }
+    let sub_keys = match state.sub_key_store.list_sub_keys(&parent.id).await {
+        Ok(sub_keys) if sub_keys.len() >= MAX_SUB_KEYS_PER_PARENT => {
+            return json_error(StatusCode::CONFLICT, "sub-key limit reached");
+        },
+        Ok(sub_keys) => sub_keys,
+        Err(_) => return json_error(StatusCode::INTERNAL_SERVER_ERROR, "sub-key store error"),
+    };
+    let delegated_concurrency = sub_keys
+        .iter()
+        .filter(|key| key.status == KEY_STATUS_ACTIVE)
+        .filter_map(|key| key.request_max_concurrency)
+        .fold(0_u64, u64::saturating_add);
+    let (request_max_concurrency, request_min_start_interval_ms) = match resolve_sub_key_limits(
+        (parent.request_max_concurrency, parent.request_min_start_interval_ms),
+        delegated_concurrency,
+        request.request_max_concurrency,
+        request.request_min_start_interval_ms,
+    ) {
+        Ok(limits) => limits,
+        Err(message) => return json_error(StatusCode::BAD_REQUEST, message),
+    };
+    let secret = format!("sfk_{}", uuid::Uuid::new_v4().simple());
+    let key = NewSubKey {
+        id: format!("llm-key-{}", uuid::Uuid::new_v4().simple()),
+        name: name.to_string(),
+        key_hash: format!("{:x}", Sha256::digest(secret.as_bytes())),
+        secret,
+        quota_billable_limit: request.quota_billable_limit,
+        request_max_concurrency,
+        request_min_start_interval_ms,
+        created_at_ms: now_ms(),
+    };
+    match state.sub_key_store.create_sub_key(&parent.id, key).await {
+        Ok(key) => Json(CreatedSubKeyResponse {
+            key: SubKeyView::from(&key),
+            secret: key.secret,
+        })
+        .into_response(),
+        Err(err) => match err.downcast_ref::<SubKeyQuotaExceeded>() {
+            Some(exceeded) => json_error(StatusCode::CONFLICT, &exceeded.to_string()),
+            None => {
+                tracing::warn!(parent_key_id = %parent.id, "failed to create sub-key: {err:#}");
+                json_error(StatusCode::INTERNAL_SERVER_ERROR, "sub-key store error")
+            },
+        },
+    }
+}
+
+pub(crate) async fn revoke_sub_key(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+    Path(key_id): Path<String>,
+) -> Response {
+    let parent = match authenticate_parent(&state, &headers).await {
+        Ok(parent) => parent,
+        Err(response) => return response,
+    };
+    match state
+        .sub_key_store
+        .revoke_sub_key(&parent.id, &key_id)
+        .await
+    {
+        Ok(Some(key)) => Json(SubKeyView::from(&key)).into_response(),
+        Ok(None) => json_error(StatusCode::NOT_FOUND, "sub-key not found"),
+        Err(_) => json_error(StatusCode::INTERNAL_SERVER_ERROR, "sub-key store error"),
+    }
+}
+
+/// Resolve the caller's bearer secret to an active top-level key.
+async fn authenticate_parent(state: &HttpState, headers: &HeaderMap) -> Result<AdminKey, Response> {
+    let parent = authenticate_key_holder(state, headers).await?;
+    if parent.parent_key_id.is_some() {
+        return Err(json_error(StatusCode::FORBIDDEN, "sub-keys cannot manage sub-keys"));
+    }
+    Ok(parent)
+}
+
+fn parent_view(parent: &AdminKey, sub_keys: &[AdminKey]) -> SubKeyParentView {
+    let children_remaining = sub_keys
+        .iter()
+        .filter(|key| key.status == KEY_STATUS_ACTIVE)
+        .map(|key| key.remaining_billable.max(0))
+        .fold(0_i64, i64::saturating_add);
+    SubKeyParentView {
+        id: parent.id.clone(),
+        name: parent.name.clone(),
+        quota_billable_limit: parent.quota_billable_limit,
+        remaining_billable: parent.remaining_billable,
+        delegable_billable: delegable_sub_key_quota(parent.remaining_billable, children_remaining),
+        request_max_concurrency: parent.request_max_concurrency,
+        request_min_start_interval_ms: parent.request_min_start_interval_ms,
+    }
+}
+
+fn used_billable(key: &AdminKey) -> u64 {
+    let limit = i64::try_from(key.quota_billable_limit).unwrap_or(i64::MAX);
+    u64::try_from(limit.saturating_sub(key.remaining_billable)).unwrap_or(0)
+}
+
+/// Default a sub-key's caps to the parent's and reject caps looser than the
+/// parent's: more concurrency or a shorter pacing interval. Each sub-key has
+/// its own limiter, so the parent's concurrency cap is split between its
+/// active sub-keys: `delegated_concurrency` is what siblings already hold, and
+/// a new sub-key defaults to the rest.
+fn resolve_sub_key_limits(
+    (parent_max_concurrency, parent_min_start_interval_ms): (Option<u64>, Option<u64>),
+    delegated_concurrency: u64,
+    request_max_concurrency: Option<u64>,
+    request_min_start_interval_ms: Option<u64>,
+) -> Result<(Option<u64>, Option<u64>), &'static str> {
+    let available_concurrency = parent_max_concurrency
+        .map(|parent_value| parent_value.saturating_sub(delegated_concurrency));
+    if available_concurrency == Some(0) {
+        return Err("the parent key's concurrency cap is fully delegated to sub-keys");
+    }
+    let max_concurrency = request_max_concurrency.or(available_concurrency);
+    if let Some(value) = max_concurrency {
+        if value == 0 || value > MAX_SUB_KEY_REQUEST_MAX_CONCURRENCY {
+            return Err("request_max_concurrency is out of range");
+        }
+        if available_concurrency.is_some_and(|available| value > available) {
+            return Err("request_max_concurrency exceeds the parent key's undelegated cap");
+        }
+    }
+    let min_start_interval_ms = request_min_start_interval_ms.or(parent_min_start_interval_ms);
+    if let Some(value) = min_start_interval_ms {
+        if value > MAX_SUB_KEY_REQUEST_MIN_START_INTERVAL_MS {
+            return Err("request_min_start_interval_ms is out of range");
+        }
+        if parent_min_start_interval_ms.is_some_and(|parent_value| value < parent_value) {
+            return Err("request_min_start_interval_ms is shorter than the parent key's pacing");
+        }
+    }
+    Ok((max_concurrency, min_start_interval_ms))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::resolve_sub_key_limits;
+
+    #[test]
+    fn sub_key_limits_inherit_and_never_loosen_parent_caps() {
+        assert_eq!(
+            resolve_sub_key_limits((Some(8), Some(100)), 0, None, None),
+            Ok((Some(8), Some(100)))
+        );
+        assert_eq!(
+            resolve_sub_key_limits((Some(8), Some(100)), 0, Some(2), Some(500)),
+            Ok((Some(2), Some(500)))
+        );
+        assert!(resolve_sub_key_limits((Some(8), None), 0, Some(9), None).is_err());
+        assert!(resolve_sub_key_limits((None, Some(100)), 0, None, Some(50)).is_err());
+        assert!(resolve_sub_key_limits((None, None), 0, Some(0), None).is_err());
+        assert_eq!(resolve_sub_key_limits((None, None), 0, Some(4), None), Ok((Some(4), None)));
+    }
+
+    #[test]
+    fn sibling_sub_keys_split_the_parent_concurrency_cap() {
+        assert_eq!(resolve_sub_key_limits((Some(8), None), 5, None, None), Ok((Some(3), None)));
+        assert_eq!(resolve_sub_key_limits((Some(8), None), 5, Some(3), None), Ok((Some(3), None)));
+        assert!(resolve_sub_key_limits((Some(8), None), 5, Some(4), None).is_err());
+        assert!(resolve_sub_key_limits((Some(8), None), 8, None, None).is_err());
+        assert_eq!(resolve_sub_key_limits((None, None), 64, Some(4), None), Ok((Some(4), None)));
+    }
+}
@@ -193,14 +193,14 @@ pub(crate) struct AdminUsageEventView {
 
 /// Usage detail response.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-struct AdminUsageEventDetailView {
+pub(crate) struct AdminUsageEventDetailView {
     #[serde(flatten)]
-    event: AdminUsageEventView,
+    pub(crate) event: AdminUsageEventView,
     request_headers_json: String,
     client_request_body_json: Option<String>,
     upstream_request_body_json: Option<String>,
     full_request_json: Option<String>,
-    error_body: Option<String>,
+    pub(crate) error_body: Option<String>,
     response_body: Option<String>,
 }
 
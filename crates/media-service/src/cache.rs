@@ -4,9 +4,14 @@ use std::{
 };
 
 use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 
-use crate::types::PlaybackMode;
+use crate::types::{LocalMediaCacheKind, PlaybackMode};
+
+/// File inside every artifact directory recording which source produced it,
+/// so eviction can find artifacts whose source was deleted or replaced.
+pub const CACHE_MANIFEST_FILE: &str = ".source.json";
 
 #[derive(Debug, Clone)]
 pub struct CacheKeyInput<'a> {
@@ -22,8 +27,12 @@ pub struct HlsCachePaths {
     pub job_id: String,
     pub dir: PathBuf,
     pub playlist: PathBuf,
+    /// Multivariant playlist that adds subtitle renditions to `playlist`;
+    /// only written when the source has text subtitles.
+    pub master_playlist: PathBuf,
     pub ready_marker: PathBuf,
     pub error_marker: PathBuf,
+    pub manifest: PathBuf,
 }
 
 #[derive(Debug, Clone)]
@@ -33,6 +42,7 @@ pub struct Mp4CachePaths {
     pub video: PathBuf,
     pub ready_marker: PathBuf,
     pub error_marker: PathBuf,
+    pub manifest: PathBuf,
 }
 
 #[derive(Debug, Clone)]
@@ -49,6 +59,27 @@ pub struct PosterCachePaths {
     pub image: PathBuf,
     pub ready_marker: PathBuf,
     pub error_marker: PathBuf,
+    pub manifest: PathBuf,
+}
+
+#[derive(Debug, Clone)]
+pub struct SubtitleCacheKeyInput<'a> {
+    /// The file the subtitle is read from: the media file for embedded
+    /// streams, the sidecar itself otherwise.
+    pub relative_path: &'a str,
+    pub file_size: u64,
+    pub modified_at_ms: i64,
+    pub stream_index: usize,
+    pub profile: &'a str,
+}
+
+#[derive(Debug, Clone)]
+pub struct SubtitleCachePaths {
+    pub dir: PathBuf,
+    pub vtt: PathBuf,
+    pub ready_marker: PathBuf,
+    pub error_marker: PathBuf,
+    pub manifest: PathBuf,
 }
 
 pub fn build_cache_key(input: &CacheKeyInput<'_>) -> String {
@@ -71,8 +102,10 @@ pub fn hls_cache_paths(cache_root: &Path, job_id: &str) -> HlsCachePaths {
     HlsCachePaths {
         job_id: job_id.to_string(),
         playlist: dir.join("index.m3u8"),
+        master_playlist: dir.join("master.m3u8"),
         ready_marker: dir.join(".ready"),
         error_marker: dir.join(".error.txt"),
+        manifest: dir.join(CACHE_MANIFEST_FILE),
         dir,
     }
 }
@@ -84,6 +117,7 @@ pub fn mp4_cache_paths(cache_root: &Path, job_id: &str) -> Mp4CachePaths {
         video: dir.join("output.mp4"),
         ready_marker: dir.join(".ready"),
         error_marker: dir.join(".error.txt"),
+        manifest: dir.join(CACHE_MANIFEST_FILE),
         dir,
     }
 }
@@ -106,10 +140,56 @@ pub fn poster_cache_paths(cache_root: &Path, cache_key: &str) -> PosterCachePath
         image: dir.join("poster.jpg"),
         ready_marker: dir.join(".ready"),
         error_marker: dir.join(".error.txt"),
+        manifest: dir.join(CACHE_MANIFEST_FILE),
         dir,
     }
 }
 
+pub fn build_subtitle_cache_key(input: &SubtitleCacheKeyInput<'_>) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(input.relative_path.as_bytes());
+    hasher.update(b"\0");
+    hasher.update(input.file_size.to_le_bytes());
+    hasher.update(input.modified_at_ms.to_le_bytes());
+    hasher.update(b"subtitle");
+    hasher.update(input.stream_index.to_le_bytes());
+    hasher.update(b"\0");
+    hasher.update(input.profile.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+pub fn subtitle_cache_paths(cache_root: &Path, cache_key: &str) -> SubtitleCachePaths {
+    let dir = cache_root.join(cache_key);
+    SubtitleCachePaths {
+        vtt: dir.join("subtitle.vtt"),
+        ready_marker: dir.join(".ready"),
+        error_marker: dir.join(".error.txt"),
+        manifest: dir.join(CACHE_MANIFEST_FILE),
+        dir,
+    }
+}
+
+/// Source identity persisted next to cached artifacts.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CacheManifest {
+    pub kind: LocalMediaCacheKind,
+    pub relative_path: String,
+    pub file_size: u64,
+    pub modified_at_ms: i64,
+}
+
+pub async fn write_cache_manifest(path: &Path, manifest: &CacheManifest) -> Result<()> {
+    let bytes = serde_json::to_vec(manifest).context("failed to encode cache manifest")?;
+    tokio::fs::write(path, bytes)
+        .await
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+pub fn read_cache_manifest(path: &Path) -> Option<CacheManifest> {
+    let bytes = std::fs::read(path).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
 pub async fn source_modified_at_ms(path: &Path) -> Result<i64> {
     let metadata = tokio::fs::metadata(path)
         .await
@@ -127,7 +207,10 @@ pub async fn source_modified_at_ms(path: &Path) -> Result<i64> {
 mod tests {
     use std::path::PathBuf;
 
-    use super::{mp4_cache_paths, poster_cache_paths};
+    use super::{
+        build_subtitle_cache_key, mp4_cache_paths, poster_cache_paths, subtitle_cache_paths,
+        SubtitleCacheKeyInput,
+    };
 
     #[test]
     fn poster_cache_paths_use_jpg_artifacts() {
@@ -137,6 +220,7 @@ mod tests {
         assert_eq!(paths.image, cache_root.join("poster-key").join("poster.jpg"));
         assert_eq!(paths.ready_marker, cache_root.join("poster-key").join(".ready"));
         assert_eq!(paths.error_marker, cache_root.join("poster-key").join(".error.txt"));
+        assert_eq!(paths.manifest, cache_root.join("poster-key").join(".source.json"));
     }
 
     #[test]
@@ -148,4 +232,23 @@ mod tests {
         assert_eq!(paths.ready_marker, cache_root.join("video-key").join(".ready"));
         assert_eq!(paths.error_marker, cache_root.join("video-key").join(".error.txt"));
     }
+
+    #[test]
+    fn subtitle_cache_key_differs_per_stream() {
+        let input = |stream_index| SubtitleCacheKeyInput {
+            relative_path: "show/episode.mkv",
+            file_size: 42,
+            modified_at_ms: 7,
+            stream_index,
+            profile: "webvtt-v1",
+        };
+        let first = build_subtitle_cache_key(&input(0));
+        assert_eq!(first.len(), 64);
+        assert_ne!(first, build_subtitle_cache_key(&input(1)));
+
+        let cache_root = PathBuf::from("/tmp/local-media-cache");
+        let paths = subtitle_cache_paths(&cache_root, &first);
+        assert_eq!(paths.vtt, cache_root.join(&first).join("subtitle.vtt"));
+        assert_eq!(paths.manifest, cache_root.join(&first).join(".source.json"));
+    }
 }
@@ -0,0 +1,493 @@
+//! Disk accounting and eviction for the playback and poster cache.
+//!
+//! Every artifact directory under the cache root is named by its cache key.
+//! A periodic sweep removes directories whose source file is gone or was
+//! replaced, directories idle for longer than the configured max age, and
+//! then least recently used directories until the cache fits its byte
+//! budget. Directories owned by a running `PlaybackJobHandle` are never
+//! touched.
+
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result};
+use tokio::fs;
+
+use crate::{
+    cache::{read_cache_manifest, CacheManifest, CACHE_MANIFEST_FILE},
+    path_guard::sanitize_relative_media_path,
+    types::{
+        LocalMediaCacheEntry, LocalMediaCacheEntryState, LocalMediaCacheKind,
+        LocalMediaCacheUsageResponse,
+    },
+    LocalMediaState,
+};
+
+/// Entries still being written without a job handle (posters) are left
+/// alone for this long after their last write.
+const IN_FLIGHT_GRACE_MS: i64 = 30 * 60 * 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvictionReason {
+    /// The source file was deleted or changed, so the key is unreachable.
+    Orphaned,
+    /// Not accessed within the configured max age.
+    Expired,
+    /// Least recently used while the cache exceeded its byte budget.
+    OverBudget,
+}
+
+impl EvictionReason {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Orphaned => "orphaned",
+            Self::Expired => "expired",
+            Self::OverBudget => "over_budget",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheSweepReport {
+    pub evicted_entries: usize,
+    pub freed_bytes: u64,
+    pub remaining_bytes: u64,
+}
+
+#[derive(Debug, Clone)]
+struct ScannedEntry {
+    dir: PathBuf,
+    usage: LocalMediaCacheEntry,
+    last_written_at_ms: i64,
+}
+
+impl ScannedEntry {
+    fn protected(&self, now_ms: i64) -> bool {
+        self.usage.active
+            || (self.usage.state == LocalMediaCacheEntryState::Preparing
+                && now_ms.saturating_sub(self.last_written_at_ms) < IN_FLIGHT_GRACE_MS)
+    }
+}
+
+/// Report cache usage per job, largest entries first.
+pub async fn cache_usage(state: &LocalMediaState) -> Result<LocalMediaCacheUsageResponse> {
+    let mut entries = scan_cache(state)
+        .await?
+        .into_iter()
+        .map(|entry| entry.usage)
+        .collect::<Vec<_>>();
+    entries.sort_by(|left, right| {
+        right
+            .size_bytes
+            .cmp(&left.size_bytes)
+            .then_with(|| left.job_id.cmp(&right.job_id))
+    });
+    Ok(LocalMediaCacheUsageResponse {
+        total_bytes: entries.iter().map(|entry| entry.size_bytes).sum(),
+        max_bytes: state.config().cache_max_bytes,
+        max_age_seconds: state.config().cache_max_age_seconds,
+        entries,
+    })
+}
+
+/// Run one eviction pass over the cache directory.
+pub async fn sweep_cache(state: &LocalMediaState) -> Result<CacheSweepReport> {
+    let entries = scan_cache(state).await?;
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    let evictions = plan_evictions(
+        &entries,
+        now_ms,
+        state.config().cache_max_bytes,
+        state.config().cache_max_age_seconds,
+    );
+
+    let mut report = CacheSweepReport {
+        remaining_bytes: entries.iter().map(|entry| entry.usage.size_bytes).sum(),
+        ..CacheSweepReport::default()
+    };
+    for (index, reason) in evictions {
+        let entry = &entries[index];
+        // The job map is checked again right before deleting, since a new
+        // playback request may have claimed the key since the scan.
+        if state.jobs().contains_key(&entry.usage.job_id) {
+            continue;
+        }
+        match fs::remove_dir_all(&entry.dir).await {
+            Ok(()) => {
+                state.forget_cache_entry(&entry.usage.job_id);
+                report.evicted_entries += 1;
+                report.freed_bytes += entry.usage.size_bytes;
+                report.remaining_bytes = report
+                    .remaining_bytes
+                    .saturating_sub(entry.usage.size_bytes);
+                tracing::info!(
+                    job_id = %entry.usage.job_id,
+                    reason = reason.as_str(),
+                    size_bytes = entry.usage.size_bytes,
+                    source = entry.usage.source_relative_path.as_deref().unwrap_or_default(),
+                    "evicted local media cache entry"
+                );
+            },
+            Err(err) => {
+                tracing::warn!(
+                    job_id = %entry.usage.job_id,
+                    error = %err,
+                    "failed to evict local media cache entry"
+                );
+            },
+        }
+    }
+    Ok(report)
+}
+
+/// Sweep once at startup, then on every interval tick or when a finished
+/// job requests it.
+pub fn spawn_cache_sweeper(state: Arc<LocalMediaState>) {
+    let interval = Duration::from_secs(state.config().cache_sweep_interval_seconds);
+    tokio::spawn(async move {
+        loop {
+            match sweep_cache(&state).await {
+                Ok(report) if report.evicted_entries > 0 => {
+                    tracing::info!(
+                        evicted_entries = report.evicted_entries,
+                        freed_bytes = report.freed_bytes,
+                        remaining_bytes = report.remaining_bytes,
+                        "local media cache sweep finished"
+                    );
+                },
+                Ok(_) => {},
+                Err(err) => {
+                    tracing::warn!(error = %err, "local media cache sweep failed");
+                },
+            }
+            tokio::select! {
+                () = tokio::time::sleep(interval) => {},
+                () = state.cache_sweep_requested().notified() => {},
+            }
+        }
+    });
+}
+
+fn plan_evictions(
+    entries: &[ScannedEntry],
+    now_ms: i64,
+    max_bytes: Option<u64>,
+    max_age_seconds: Option<u64>,
+) -> Vec<(usize, EvictionReason)> {
+    let max_age_ms = max_age_seconds
+        .map(|seconds| i64::try_from(seconds.saturating_mul(1000)).unwrap_or(i64::MAX));
+    let mut evictions = Vec::new();
+    let mut retained = Vec::new();
+    for (index, entry) in entries.iter().enumerate() {
+        if entry.protected(now_ms) {
+            retained.push(index);
+            continue;
+        }
+        let idle_ms = now_ms.saturating_sub(entry.usage.last_accessed_at_ms);
+        if entry.usage.source_missing {
+            evictions.push((index, EvictionReason::Orphaned));
+        } else if max_age_ms.is_some_and(|max_age_ms| idle_ms > max_age_ms) {
+            evictions.push((index, EvictionReason::Expired));
+        } else {
+            retained.push(index);
+        }
+    }
+
+    let Some(max_bytes) = max_bytes else {
+        return evictions;
+    };
+    let mut total = retained
+        .iter()
+        .map(|index| entries[*index].usage.size_bytes)
+        .sum::<u64>();
+    let mut candidates = retained
+        .into_iter()
+        .filter(|index| !entries[*index].protected(now_ms))
+        .collect::<Vec<_>>();
+    candidates.sort_by_key(|index| entries[*index].usage.last_accessed_at_ms);
+    for index in candidates {
+        if total <= max_bytes {
+            break;
+        }
+        total = total.saturating_sub(entries[index].usage.size_bytes);
+        evictions.push((index, EvictionReason::OverBudget));
+    }
+    evictions
+}
+
+async fn scan_cache(state: &LocalMediaState) -> Result<Vec<ScannedEntry>> {
+    let cache_dir = state.cache_dir().to_path_buf();
+    let root_dir = state.root_dir().to_path_buf();
+    let active = state
+        .jobs()
+        .iter()
+        .map(|entry| entry.key().clone())
+        .collect::<HashSet<_>>();
+    let mut entries =
+        tokio::task::spawn_blocking(move || scan_cache_blocking(&cache_dir, &root_dir, &active))
+            .await
+            .context("failed to join local media cache scan task")??;
+    for entry in &mut entries {
+        if let Some(last_access) = state.cache_last_access_ms(&entry.usage.job_id) {
+            entry.usage.last_accessed_at_ms = entry.usage.last_accessed_at_ms.max(last_access);
+        }
+    }
+    Ok(entries)
+}
+
+fn scan_cache_blocking(
+    cache_dir: &Path,
+    root_dir: &Path,
+    active: &HashSet<String>,
+) -> Result<Vec<ScannedEntry>> {
+    let read_dir = match std::fs::read_dir(cache_dir) {
+        Ok(read_dir) => read_dir,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => {
+            return Err(err)
+                .with_context(|| format!("failed to read cache directory {}", cache_dir.display()))
+        },
+    };
+    let mut entries = Vec::new();
+    for entry in read_dir.flatten() {
+        let Ok(job_id) = entry.file_name().into_string() else {
+            continue;
+        };
+        // Only directories named like a cache key are ours to account for
+        // and delete; anything else in the cache root is left untouched.
+        if !is_cache_key(&job_id) || !entry.file_type().is_ok_and(|kind| kind.is_dir()) {
+            continue;
+        }
+        let dir = entry.path();
+        let (size_bytes, file_count, last_written_at_ms) = directory_usage(&dir);
+        let manifest = read_cache_manifest(&dir.join(CACHE_MANIFEST_FILE));
+        let source_missing = manifest
+            .as_ref()
+            .is_some_and(|manifest| !source_matches(root_dir, manifest));
+        entries.push(ScannedEntry {
+            usage: LocalMediaCacheEntry {
+                kind: manifest
+                    .as_ref()
+                    .map(|manifest| manifest.kind)
+                    .unwrap_or_else(|| guess_kind(&dir)),
+                state: entry_state(&dir),
+                source_relative_path: manifest.map(|manifest| manifest.relative_path),
+                source_missing,
+                active: active.contains(&job_id),
+                size_bytes,
+                file_count,
+                last_accessed_at_ms: last_written_at_ms,
+                job_id,
+            },
+            dir,
+            last_written_at_ms,
+        });
+    }
+    Ok(entries)
+}
+
+fn is_cache_key(name: &str) -> bool {
+    name.len() == 64
+        && name
+            .bytes()
+            .all(|byte| matches!(byte, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+/// Total bytes, file count, and newest mtime (unix ms) below `dir`.
+fn directory_usage(dir: &Path) -> (u64, u64, i64) {
+    let mut size_bytes = 0;
+    let mut file_count = 0;
+    let mut newest_ms = 0;
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        let Ok(read_dir) = std::fs::read_dir(&current) else {
+            continue;
+        };
+        for entry in read_dir.flatten() {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                pending.push(entry.path());
+                continue;
+            }
+            size_bytes += metadata.len();
+            file_count += 1;
+            if let Some(modified_ms) = metadata
+                .modified()
+                .ok()
+                .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+                .and_then(|duration| i64::try_from(duration.as_millis()).ok())
+            {
+                newest_ms = newest_ms.max(modified_ms);
+            }
+        }
+    }
+    (size_bytes, file_count, newest_ms)
+}
+
+fn source_matches(root_dir: &Path, manifest: &CacheManifest) -> bool {
+    let Ok(relative) = sanitize_relative_media_path(&manifest.relative_path) else {
+        return false;
+    };
+    let Ok(metadata) = std::fs::metadata(root_dir.join(relative)) else {
+        return false;
+    };
+    let modified_ms = metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_millis().try_into().unwrap_or(i64::MAX));
+    metadata.is_file()
+        && metadata.len() == manifest.file_size
+        && modified_ms == Some(manifest.modified_at_ms)
+}
+
+fn entry_state(dir: &Path) -> LocalMediaCacheEntryState {
+    if dir.join(".error.txt").exists() {
+        LocalMediaCacheEntryState::Failed
+    } else if dir.join(".ready").exists() {
+        LocalMediaCacheEntryState::Ready
+    } else {
+        LocalMediaCacheEntryState::Preparing
+    }
+}
+
+/// Entries created before manifests were written carry no kind; infer it
+/// from the artifact they hold.
+fn guess_kind(dir: &Path) -> LocalMediaCacheKind {
+    if dir.join("index.m3u8").exists() {
+        LocalMediaCacheKind::Hls
+    } else if dir.join("output.mp4").exists() {
+        LocalMediaCacheKind::Mp4
+    } else if dir.join("poster.jpg").exists() {
+        LocalMediaCacheKind::Poster
+    } else if dir.join("subtitle.vtt").exists() {
+        LocalMediaCacheKind::Subtitle
+    } else {
+        LocalMediaCacheKind::Unknown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::{jobs::PlaybackJobHandle, types::PlaybackMode};
+
+    const KEY_A: &str = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+    const KEY_B: &str = "bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+    const KEY_C: &str = "cccccccccccccccccccccccccccccccccccccccccccccccccccccccccccccccc";
+
+    fn scanned(job_id: &str, size_bytes: u64, last_accessed_at_ms: i64) -> ScannedEntry {
+        ScannedEntry {
+            dir: PathBuf::from(job_id),
+            usage: LocalMediaCacheEntry {
+                job_id: job_id.to_string(),
+                kind: LocalMediaCacheKind::Mp4,
+                state: LocalMediaCacheEntryState::Ready,
+                source_relative_path: None,
+                source_missing: false,
+                active: false,
+                size_bytes,
+                file_count: 1,
+                last_accessed_at_ms,
+            },
+            last_written_at_ms: last_accessed_at_ms,
+        }
+    }
+
+    fn write_entry(cache_dir: &Path, key: &str, bytes: usize, manifest: Option<&CacheManifest>) {
+        let dir = cache_dir.join(key);
+        fs::create_dir_all(&dir).expect("create entry");
+        fs::write(dir.join("output.mp4"), vec![0_u8; bytes]).expect("write artifact");
+        fs::write(dir.join(".ready"), b"ready").expect("write ready marker");
+        if let Some(manifest) = manifest {
+            fs::write(dir.join(CACHE_MANIFEST_FILE), serde_json::to_vec(manifest).expect("json"))
+                .expect("write manifest");
+        }
+    }
+
+    #[test]
+    fn plan_evicts_least_recently_used_until_within_budget() {
+        let entries = vec![
+            scanned(KEY_A, 400, 3_000),
+            scanned(KEY_B, 400, 1_000),
+            scanned(KEY_C, 400, 2_000),
+        ];
+        let plan = plan_evictions(&entries, 4_000, Some(500), None);
+        assert_eq!(plan, vec![(1, EvictionReason::OverBudget), (2, EvictionReason::OverBudget)]);
+    }
+
+    #[test]
+    fn plan_skips_active_and_recently_written_entries() {
+        let mut active = scanned(KEY_A, 1_000, 0);
+        active.usage.active = true;
+        let mut preparing = scanned(KEY_B, 1_000, 0);
+        preparing.usage.state = LocalMediaCacheEntryState::Preparing;
+        preparing.last_written_at_ms = 5_000;
+        let mut orphaned = scanned(KEY_C, 10, 9_000);
+        orphaned.usage.source_missing = true;
+
+        let plan = plan_evictions(&[active, preparing, orphaned], 10_000, Some(1), Some(1));
+        assert_eq!(plan, vec![(2, EvictionReason::Orphaned)]);
+    }
+
+    #[test]
+    fn plan_expires_idle_entries_by_age() {
+        let entries = vec![scanned(KEY_A, 10, 0), scanned(KEY_B, 10, 9_500)];
+        let plan = plan_evictions(&entries, 10_000, None, Some(5));
+        assert_eq!(plan, vec![(0, EvictionReason::Expired)]);
+    }
+
+    #[tokio::test]
+    async fn sweep_removes_orphans_and_reports_usage() {
+        let root = tempdir().expect("root tempdir");
+        let cache = tempdir().expect("cache tempdir");
+        let source = root.path().join("clip.mkv");
+        fs::write(&source, b"video").expect("write source");
+        let state =
+            LocalMediaState::new_for_test(root.path().to_path_buf(), cache.path().to_path_buf());
+        let live = CacheManifest {
+            kind: LocalMediaCacheKind::Mp4,
+            relative_path: "clip.mkv".to_string(),
+            file_size: 5,
+            modified_at_ms: crate::cache::source_modified_at_ms(&source)
+                .await
+                .expect("source mtime"),
+        };
+        let gone = CacheManifest {
+            relative_path: "deleted.mkv".to_string(),
+            ..live.clone()
+        };
+        write_entry(cache.path(), KEY_A, 100, Some(&live));
+        write_entry(cache.path(), KEY_B, 300, Some(&gone));
+        write_entry(cache.path(), KEY_C, 200, Some(&gone));
+        fs::create_dir_all(cache.path().join("not-a-cache-key")).expect("foreign dir");
+        state.jobs().insert(
+            KEY_C.to_string(),
+            PlaybackJobHandle::new(KEY_C.to_string(), PlaybackMode::Raw, None, None),
+        );
+
+        let usage = cache_usage(&state).await.expect("usage");
+        assert_eq!(usage.entries.len(), 3);
+        assert_eq!(usage.entries[0].job_id, KEY_B);
+        assert!(usage.entries[0].source_missing);
+        assert!(usage.entries[1].active);
+        assert!(!usage.entries[2].source_missing);
+
+        let report = sweep_cache(&state).await.expect("sweep");
+        assert_eq!(report.evicted_entries, 1);
+        assert!(!cache.path().join(KEY_B).exists());
+        assert!(cache.path().join(KEY_A).exists());
+        assert!(cache.path().join(KEY_C).exists());
+        assert!(cache.path().join("not-a-cache-key").exists());
+    }
+}
@@ -6,7 +6,9 @@ const DEFAULT_CACHE_DIR: &str = "tmp/local-media-cache";
 const DEFAULT_MAX_REMUX_JOBS: usize = 2;
 const DEFAULT_MAX_TRANSCODE_JOBS: usize = 1;
 const DEFAULT_MAX_POSTER_JOBS: usize = 2;
+const DEFAULT_MAX_SUBTITLE_JOBS: usize = 2;
 const DEFAULT_LIST_PAGE_SIZE: usize = 120;
+const DEFAULT_CACHE_SWEEP_INTERVAL_SECONDS: u64 = 10 * 60;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct LocalMediaConfig {
@@ -17,7 +19,15 @@ pub struct LocalMediaConfig {
     pub max_remux_jobs: usize,
     pub max_transcode_jobs: usize,
     pub max_poster_jobs: usize,
+    pub max_subtitle_jobs: usize,
     pub list_page_size: usize,
+    /// Total size the cache directory may grow to before least recently
+    /// used artifacts are evicted. `None` disables the byte budget.
+    pub cache_max_bytes: Option<u64>,
+    /// Artifacts not accessed for this long are evicted. `None` keeps them
+    /// until the byte budget or an orphan sweep removes them.
+    pub cache_max_age_seconds: Option<u64>,
+    pub cache_sweep_interval_seconds: u64,
     pub ffmpeg_bin: Option<PathBuf>,
     pub ffprobe_bin: Option<PathBuf>,
 }
@@ -48,8 +58,20 @@ fn read_local_media_config_from_map(
         "STATICFLOW_LOCAL_MEDIA_MAX_POSTER_JOBS",
         DEFAULT_MAX_POSTER_JOBS,
     )?;
+    let max_subtitle_jobs = parse_usize_env(
+        env_map,
+        "STATICFLOW_LOCAL_MEDIA_MAX_SUBTITLE_JOBS",
+        DEFAULT_MAX_SUBTITLE_JOBS,
+    )?;
     let list_page_size =
         parse_usize_env(env_map, "STATICFLOW_LOCAL_MEDIA_LIST_PAGE_SIZE", DEFAULT_LIST_PAGE_SIZE)?;
+    let cache_max_bytes =
+        parse_optional_u64_env(env_map, "STATICFLOW_LOCAL_MEDIA_CACHE_MAX_BYTES")?;
+    let cache_max_age_seconds =
+        parse_optional_u64_env(env_map, "STATICFLOW_LOCAL_MEDIA_CACHE_MAX_AGE_SECONDS")?;
+    let cache_sweep_interval_seconds =
+        parse_optional_u64_env(env_map, "STATICFLOW_LOCAL_MEDIA_CACHE_SWEEP_INTERVAL_SECONDS")?
+            .unwrap_or(DEFAULT_CACHE_SWEEP_INTERVAL_SECONDS);
     let ffmpeg_bin = env_path(env_map, "STATICFLOW_FFMPEG_BIN");
     let ffprobe_bin = env_path(env_map, "STATICFLOW_FFPROBE_BIN");
 
@@ -61,7 +83,11 @@ fn read_local_media_config_from_map(
         max_remux_jobs,
         max_transcode_jobs,
         max_poster_jobs,
+        max_subtitle_jobs,
         list_page_size,
+        cache_max_bytes,
+        cache_max_age_seconds,
+        cache_sweep_interval_seconds,
         ffmpeg_bin,
         ffprobe_bin,
     })
@@ -91,6 +117,21 @@ fn parse_usize_env(env_map: &BTreeMap<String, String>, key: &str, default: usize
     Ok(parsed)
 }
 
+fn parse_optional_u64_env(env_map: &BTreeMap<String, String>, key: &str) -> Result<Option<u64>> {
+    let value = match env_map.get(key) {
+        Some(value) if !value.trim().is_empty() => value,
+        _ => return Ok(None),
+    };
+    let parsed = value
+        .trim()
+        .parse::<u64>()
+        .with_context(|| format!("failed to parse {key} as u64"))?;
+    if parsed == 0 {
+        anyhow::bail!("{key} must be greater than zero");
+    }
+    Ok(Some(parsed))
+}
+
 fn env_path(env_map: &BTreeMap<String, String>, key: &str) -> Option<PathBuf> {
     env_map
         .get(key)
@@ -121,9 +162,31 @@ mod tests {
         assert_eq!(cfg.max_remux_jobs, 2);
         assert_eq!(cfg.max_transcode_jobs, 1);
         assert_eq!(cfg.max_poster_jobs, 2);
+        assert_eq!(cfg.max_subtitle_jobs, 2);
         assert!(cfg.enabled);
         assert!(cfg.auto_download_ffmpeg);
         assert_eq!(cfg.cache_dir, PathBuf::from("tmp/local-media-cache"));
+        assert_eq!(cfg.cache_max_bytes, None);
+        assert_eq!(cfg.cache_max_age_seconds, None);
+        assert_eq!(cfg.cache_sweep_interval_seconds, 600);
+    }
+
+    #[test]
+    fn read_local_media_config_from_env_accepts_cache_budget() {
+        let cfg = read_local_media_config_for_test(&[
+            ("STATICFLOW_LOCAL_MEDIA_CACHE_MAX_BYTES", "21474836480"),
+            ("STATICFLOW_LOCAL_MEDIA_CACHE_MAX_AGE_SECONDS", "604800"),
+        ])
+        .expect("config should parse");
+        assert_eq!(cfg.cache_max_bytes, Some(20 * 1024 * 1024 * 1024));
+        assert_eq!(cfg.cache_max_age_seconds, Some(7 * 24 * 60 * 60));
+
+        let err =
+            read_local_media_config_for_test(&[("STATICFLOW_LOCAL_MEDIA_CACHE_MAX_BYTES", "0")])
+                .expect_err("zero budget must be rejected");
+        assert!(err
+            .to_string()
+            .contains("STATICFLOW_LOCAL_MEDIA_CACHE_MAX_BYTES"));
     }
 
     #[test]
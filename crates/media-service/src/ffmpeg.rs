@@ -52,6 +52,30 @@ fn ensure_binary_paths_blocking(config: &LocalMediaConfig) -> Result<BinaryPaths
     })
 }
 
+/// Which source streams end up in a playback artifact and where in the
+/// source it begins.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StreamSelection {
+    /// Index among the source's audio streams; `None` drops audio.
+    pub audio_track: Option<usize>,
+    /// Whole seconds of source to skip. Stream copies cut at the preceding
+    /// keyframe, so playback may begin slightly earlier.
+    pub start_seconds: Option<u64>,
+}
+
+impl StreamSelection {
+    fn apply_input_args(&self, command: &mut Command, source: &Path) {
+        if let Some(start_seconds) = self.start_seconds.filter(|value| *value > 0) {
+            command.arg("-ss").arg(start_seconds.to_string());
+        }
+        command.arg("-i").arg(source).arg("-map").arg("0:v:0");
+        if let Some(audio_track) = self.audio_track {
+            command.arg("-map").arg(format!("0:a:{audio_track}?"));
+        }
+        command.arg("-sn").arg("-dn");
+    }
+}
+
 fn binary_works(path: &Path) -> bool {
     std::process::Command::new(path)
         .arg("-version")
@@ -68,7 +92,7 @@ pub fn build_hls_command(
     source: &Path,
     output_dir: &Path,
     strategy: PlaybackStrategy,
-    has_audio: bool,
+    selection: StreamSelection,
 ) -> Command {
     let mut command = Command::new(&bins.ffmpeg);
     command
@@ -79,15 +103,9 @@ pub fn build_hls_command(
         .arg("-loglevel")
         .arg("error")
         .arg("-nostdin")
-        .arg("-y")
-        .arg("-i")
-        .arg(source)
-        .arg("-map")
-        .arg("0:v:0")
-        .arg("-map")
-        .arg("0:a:0?")
-        .arg("-sn")
-        .arg("-dn");
+        .arg("-y");
+    selection.apply_input_args(&mut command, source);
+    let has_audio = selection.audio_track.is_some();
 
     match strategy {
         PlaybackStrategy::Raw {
@@ -150,7 +168,7 @@ pub fn build_mp4_remux_command(
     bins: &BinaryPaths,
     source: &Path,
     output_path: &Path,
-    has_audio: bool,
+    selection: StreamSelection,
 ) -> Command {
     let mut command = Command::new(&bins.ffmpeg);
     command
@@ -161,19 +179,11 @@ pub fn build_mp4_remux_command(
         .arg("-loglevel")
         .arg("error")
         .arg("-nostdin")
-        .arg("-y")
-        .arg("-i")
-        .arg(source)
-        .arg("-map")
-        .arg("0:v:0")
-        .arg("-map")
-        .arg("0:a:0?")
-        .arg("-sn")
-        .arg("-dn")
-        .arg("-c:v")
-        .arg("copy");
+        .arg("-y");
+    selection.apply_input_args(&mut command, source);
+    command.arg("-c:v").arg("copy");
 
-    if has_audio {
+    if selection.audio_track.is_some() {
         command.arg("-c:a").arg("copy");
     } else {
         command.arg("-an");
@@ -216,11 +226,46 @@ pub fn build_poster_command(
     command
 }
 
+/// Convert one text subtitle stream of `source` to WebVTT. Sidecar files
+/// are passed as `source` with `stream_index` 0.
+pub fn build_subtitle_command(
+    bins: &BinaryPaths,
+    source: &Path,
+    stream_index: usize,
+    output_path: &Path,
+) -> Command {
+    let mut command = Command::new(&bins.ffmpeg);
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .arg("-hide_banner")
+        .arg("-loglevel")
+        .arg("error")
+        .arg("-nostdin")
+        .arg("-y")
+        .arg("-i")
+        .arg(source)
+        .arg("-map")
+        .arg(format!("0:s:{stream_index}"))
+        .arg("-vn")
+        .arg("-an")
+        .arg("-c:s")
+        .arg("webvtt")
+        .arg("-f")
+        .arg("webvtt")
+        .arg(output_path);
+    command
+}
+
 #[cfg(test)]
 mod tests {
     use std::path::PathBuf;
 
-    use super::{build_hls_command, build_mp4_remux_command, build_poster_command, BinaryPaths};
+    use super::{
+        build_hls_command, build_mp4_remux_command, build_poster_command, build_subtitle_command,
+        BinaryPaths, StreamSelection,
+    };
     use crate::probe::PlaybackStrategy;
 
     #[test]
@@ -234,7 +279,10 @@ mod tests {
             PathBuf::from("/tmp/input.mkv").as_path(),
             PathBuf::from("/tmp/output").as_path(),
             PlaybackStrategy::HlsCopy,
-            true,
+            StreamSelection {
+                audio_track: Some(0),
+                start_seconds: None,
+            },
         );
         let args = command
             .as_std()
@@ -248,6 +296,37 @@ mod tests {
             .any(|pair| pair == ["-hls_playlist_type", "event"]));
         assert!(!args.iter().any(|arg| arg == "temp_file"));
         assert!(!args.iter().any(|arg| arg == "vod"));
+        assert!(!args.iter().any(|arg| arg == "-ss"));
+    }
+
+    #[test]
+    fn build_hls_command_seeks_input_before_decoding_for_start_offset() {
+        let bins = BinaryPaths {
+            ffmpeg: PathBuf::from("ffmpeg"),
+            ffprobe: PathBuf::from("ffprobe"),
+        };
+        let command = build_hls_command(
+            &bins,
+            PathBuf::from("/tmp/input.mkv").as_path(),
+            PathBuf::from("/tmp/output").as_path(),
+            PlaybackStrategy::HlsTranscode,
+            StreamSelection {
+                audio_track: None,
+                start_seconds: Some(754),
+            },
+        );
+        let args = command
+            .as_std()
+            .get_args()
+            .map(|value| value.to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+
+        let seek = args.iter().position(|arg| arg == "-ss").expect("-ss");
+        let input = args.iter().position(|arg| arg == "-i").expect("-i");
+        assert_eq!(args[seek + 1], "754");
+        assert!(seek < input);
+        assert!(args.iter().any(|arg| arg == "-an"));
+        assert!(!args.iter().any(|arg| arg.starts_with("0:a:")));
     }
 
     #[test]
@@ -260,7 +339,10 @@ mod tests {
             &bins,
             PathBuf::from("/tmp/input.mkv").as_path(),
             PathBuf::from("/tmp/output.mp4").as_path(),
-            true,
+            StreamSelection {
+                audio_track: Some(1),
+                start_seconds: None,
+            },
         );
         let args = command
             .as_std()
@@ -270,6 +352,7 @@ mod tests {
 
         assert!(args.windows(2).any(|pair| pair == ["-c:v", "copy"]));
         assert!(args.windows(2).any(|pair| pair == ["-c:a", "copy"]));
+        assert!(args.windows(2).any(|pair| pair == ["-map", "0:a:1?"]));
         assert!(args
             .windows(2)
             .any(|pair| pair == ["-movflags", "+faststart"]));
@@ -301,4 +384,28 @@ mod tests {
             .windows(2)
             .any(|pair| pair == ["-vf", "scale='min(960,iw)':-2"]));
     }
+
+    #[test]
+    fn build_subtitle_command_converts_selected_stream_to_webvtt() {
+        let bins = BinaryPaths {
+            ffmpeg: PathBuf::from("ffmpeg"),
+            ffprobe: PathBuf::from("ffprobe"),
+        };
+        let command = build_subtitle_command(
+            &bins,
+            PathBuf::from("/tmp/input.mkv").as_path(),
+            2,
+            PathBuf::from("/tmp/subtitle.vtt.part").as_path(),
+        );
+        let args = command
+            .as_std()
+            .get_args()
+            .map(|value| value.to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+
+        assert!(args.windows(2).any(|pair| pair == ["-map", "0:s:2"]));
+        assert!(args.windows(2).any(|pair| pair == ["-c:s", "webvtt"]));
+        assert!(args.windows(2).any(|pair| pair == ["-f", "webvtt"]));
+        assert_eq!(args.last().map(String::as_str), Some("/tmp/subtitle.vtt.part"));
+    }
 }
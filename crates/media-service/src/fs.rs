@@ -32,11 +32,23 @@ pub async fn list_directory(
     entries.sort_by(compare_entries);
 
     let total = entries.len();
-    let paged = entries
+    let mut paged = entries
         .into_iter()
         .skip(offset)
         .take(limit)
         .collect::<Vec<_>>();
+    let mut progress = state
+        .playback_history()
+        .get_many(
+            paged
+                .iter()
+                .filter(|entry| entry.kind == LocalMediaEntryKind::Video)
+                .map(|entry| entry.relative_path.as_str()),
+        )
+        .await;
+    for entry in &mut paged {
+        entry.progress = progress.remove(&entry.relative_path);
+    }
     let parent_dir = parent_relative_dir(&current_dir);
 
     Ok(LocalMediaListResponse {
@@ -126,6 +138,7 @@ fn build_entry_from_dir_entry(
             modified_at_ms,
             extension: None,
             poster_url: None,
+            progress: None,
         });
     }
 
@@ -158,6 +171,7 @@ fn build_entry_from_dir_entry(
             .and_then(|value| value.to_str())
             .map(|value| value.to_ascii_lowercase()),
         poster_url: Some(poster_url),
+        progress: None,
     })
 }
 
@@ -10,17 +10,21 @@ use axum::{
 use serde::Serialize;
 
 use crate::{
+    cache_eviction::cache_usage,
     fs::{list_directory, normalize_relative_path},
     playback::{
         get_job_status, open_playback, stream_hls_artifact, stream_mp4_artifact, stream_raw_file,
     },
     poster::stream_or_generate_poster,
     state::LocalMediaState,
+    subtitle::stream_or_generate_subtitle,
     types::{
         CreateUploadTaskRequest, CreateUploadTaskResponse, ListUploadTasksQuery,
-        ListUploadTasksResponse, LocalMediaListQuery, LocalMediaListResponse, OpenPlaybackRequest,
-        PlaybackJobStatusResponse, PlaybackOpenResponse, PosterQuery, RawPlaybackQuery,
-        UploadChunkQuery, UploadChunkResponse, UploadTaskRecord,
+        ListUploadTasksResponse, LocalMediaCacheUsageResponse, LocalMediaListQuery,
+        LocalMediaListResponse, LocalMediaPlaybackProgress, OpenPlaybackRequest,
+        PlaybackHistoryQuery, PlaybackHistoryResponse, PlaybackJobStatusResponse,
+        PlaybackOpenResponse, PosterQuery, RawPlaybackQuery, SubtitleQuery,
+        UpdatePlaybackProgressRequest, UploadChunkQuery, UploadChunkResponse, UploadTaskRecord,
     },
     upload::UploadError,
 };
@@ -55,12 +59,46 @@ pub async fn open_local_media_playback(
     let normalized_file = normalize_relative_path(&request.file).map_err(internal_error)?;
     let response = open_playback(state, OpenPlaybackRequest {
         file: normalized_file,
+        audio_track: request.audio_track,
+        start_seconds: request.start_seconds,
     })
     .await
     .map_err(internal_error)?;
     Ok(Json(response))
 }
 
+pub async fn update_local_media_playback_progress(
+    State(state): State<Arc<LocalMediaState>>,
+    Json(request): Json<UpdatePlaybackProgressRequest>,
+) -> HandlerResult<Json<LocalMediaPlaybackProgress>> {
+    let normalized_file = normalize_relative_path(&request.file).map_err(internal_error)?;
+    if normalized_file.is_empty() {
+        return Err(error_response(StatusCode::BAD_REQUEST, "Missing media file"));
+    }
+    let progress = state
+        .playback_history()
+        .record_position(
+            &UpdatePlaybackProgressRequest {
+                file: normalized_file,
+                ..request
+            },
+            chrono::Utc::now().timestamp_millis(),
+        )
+        .await
+        .map_err(|err| error_response(StatusCode::BAD_REQUEST, err.to_string()))?;
+    Ok(Json(progress))
+}
+
+pub async fn list_local_media_playback_history(
+    State(state): State<Arc<LocalMediaState>>,
+    Query(query): Query<PlaybackHistoryQuery>,
+) -> HandlerResult<Json<PlaybackHistoryResponse>> {
+    let limit = query.limit.unwrap_or(50).clamp(1, 500);
+    Ok(Json(PlaybackHistoryResponse {
+        entries: state.playback_history().recent(limit).await,
+    }))
+}
+
 pub async fn get_local_media_job_status(
     State(state): State<Arc<LocalMediaState>>,
     Path(job_id): Path<String>,
@@ -121,6 +159,23 @@ pub async fn stream_local_media_poster(
         .map_err(internal_error)
 }
 
+pub async fn stream_local_media_subtitle(
+    State(state): State<Arc<LocalMediaState>>,
+    Query(query): Query<SubtitleQuery>,
+) -> HandlerResult<Response> {
+    let normalized_file = normalize_relative_path(&query.file).map_err(internal_error)?;
+    stream_or_generate_subtitle(state, &normalized_file, &query.track)
+        .await
+        .map_err(internal_error)
+}
+
+pub async fn get_local_media_cache_usage(
+    State(state): State<Arc<LocalMediaState>>,
+) -> HandlerResult<Json<LocalMediaCacheUsageResponse>> {
+    let usage = cache_usage(state.as_ref()).await.map_err(internal_error)?;
+    Ok(Json(usage))
+}
+
 pub async fn create_upload_task(
     State(state): State<Arc<LocalMediaState>>,
     Json(request): Json<CreateUploadTaskRequest>,
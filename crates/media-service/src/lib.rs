@@ -6,6 +6,8 @@
 
 /// Cache path management and cache key generation.
 pub mod cache;
+/// Cache disk accounting and eviction.
+pub mod cache_eviction;
 /// Media service configuration loaded from the environment.
 pub mod config;
 /// FFmpeg and ffprobe discovery plus command construction.
@@ -20,6 +22,8 @@ pub mod jobs;
 pub mod path_guard;
 /// Playback opening, raw streaming, and HLS serving.
 pub mod playback;
+/// Disk-backed playback positions and watch history.
+pub mod playback_store;
 /// Poster extraction and streaming.
 pub mod poster;
 /// Media probing and playback-mode decisions.
@@ -28,6 +32,8 @@ pub mod probe;
 pub mod routes;
 /// Shared service state and initialization.
 pub mod state;
+/// Subtitle discovery, WebVTT conversion, and HLS subtitle renditions.
+pub mod subtitle;
 /// Shared request and response types.
 pub mod types;
 /// Resumable upload lifecycle and chunk append logic.
@@ -3,7 +3,7 @@
 use std::env;
 
 use anyhow::Result;
-use static_flow_media::{routes, state::LocalMediaState};
+use static_flow_media::{cache_eviction, routes, state::LocalMediaState};
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -11,6 +11,7 @@ async fn main() -> Result<()> {
     let state = LocalMediaState::from_env()
         .await?
         .ok_or_else(|| anyhow::anyhow!("local media root is not configured"))?;
+    cache_eviction::spawn_cache_sweeper(state.clone());
     let host = env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
     let port = env::var("PORT").unwrap_or_else(|_| "39085".to_string());
     let bind_addr = format!("{host}:{port}");
@@ -18,17 +18,19 @@ use tokio_util::io::ReaderStream;
 
 use crate::{
     cache::{
-        build_cache_key, hls_cache_paths, mp4_cache_paths, source_modified_at_ms, CacheKeyInput,
-        HlsCachePaths, Mp4CachePaths,
+        build_cache_key, hls_cache_paths, mp4_cache_paths, source_modified_at_ms,
+        write_cache_manifest, CacheKeyInput, CacheManifest, HlsCachePaths, Mp4CachePaths,
     },
-    ffmpeg::{build_hls_command, build_mp4_remux_command, ensure_binary_paths},
+    ffmpeg::{build_hls_command, build_mp4_remux_command, ensure_binary_paths, StreamSelection},
     jobs::PlaybackJobHandle,
     path_guard::resolve_media_path,
     probe::{
         cache_profile_for_strategy, choose_playback_strategy, mode_for_strategy, probe_media,
-        PlaybackStrategy,
+        MediaProbe, PlaybackStrategy,
     },
+    subtitle::{discover_sidecar_subtitles, subtitle_tracks, write_hls_subtitle_renditions},
     types::{
+        LocalMediaCacheKind, LocalMediaPlaybackProgress, LocalMediaSubtitleTrack,
         OpenPlaybackRequest, PlaybackJobStatusResponse, PlaybackMode, PlaybackOpenResponse,
         PlaybackStatus,
     },
@@ -50,8 +52,15 @@ pub async fn open_playback(
     }
 
     let bins = ensure_binary_paths(state.config()).await?;
-    let probe = probe_media(&bins, &source_path).await?;
+    let mut probe = probe_media(&bins, &source_path).await?;
+    if let Some(audio_track) = request.audio_track {
+        if !probe.select_audio_track(audio_track) {
+            anyhow::bail!("audio track {audio_track} does not exist");
+        }
+    }
     let strategy = choose_playback_strategy(&source_path, &probe);
+    let sidecars = discover_sidecar_subtitles(&source_path).await;
+    let subtitle_tracks = subtitle_tracks(&request.file, &probe, &sidecars);
     let title = source_path
         .file_name()
         .and_then(|value| value.to_str())
@@ -59,12 +68,28 @@ pub async fn open_playback(
         .unwrap_or_else(|| request.file.clone());
     let duration_seconds = probe.duration_seconds;
     let detail = Some(initial_playback_detail(&state, strategy));
+    let start_seconds = request
+        .start_seconds
+        .filter(|value| {
+            value.is_finite() && *value >= LocalMediaPlaybackProgress::MIN_RESUME_SECONDS
+        })
+        .filter(|value| duration_seconds.is_none_or(|duration| *value < duration));
+    if let Err(err) = state
+        .playback_history()
+        .record_open(&request.file, duration_seconds, chrono::Utc::now().timestamp_millis())
+        .await
+    {
+        tracing::warn!(file = %request.file, error = %err, "failed to record local media playback open");
+    }
 
     tracing::info!(
         file = %request.file,
         title = %title,
         strategy = %strategy_name(strategy),
         duration_seconds = duration_seconds.unwrap_or_default(),
+        audio_track = probe.selected_audio_track,
+        subtitle_tracks = subtitle_tracks.len(),
+        start_seconds = start_seconds.unwrap_or_default(),
         "open local media playback"
     );
 
@@ -76,7 +101,8 @@ pub async fn open_playback(
             "/admin/local-media/api/playback/raw?file={}",
             urlencoding::encode(&request.file)
         );
-        return Ok(PlaybackOpenResponse {
+        let with_tracks = playback_decorator(&probe, &subtitle_tracks, start_seconds, None);
+        return Ok(with_tracks(PlaybackOpenResponse {
             status: PlaybackStatus::Ready,
             mode: Some(PlaybackMode::Raw),
             job_id: None,
@@ -85,55 +111,120 @@ pub async fn open_playback(
             duration_seconds,
             detail: None,
             error: None,
-        });
+            audio_tracks: Vec::new(),
+            selected_audio_track: None,
+            subtitle_tracks: Vec::new(),
+            start_offset_seconds: None,
+            resume_seconds: None,
+        }));
     }
 
     let modified_at_ms = source_modified_at_ms(&source_path).await?;
     let mode = mode_for_strategy(strategy);
-    let cache_key = build_cache_key(&CacheKeyInput {
-        relative_path: &request.file,
+    let profile = playback_cache_profile(strategy, &probe);
+    let cache_key_for = |profile: &str| {
+        build_cache_key(&CacheKeyInput {
+            relative_path: &request.file,
+            file_size: source_metadata.len(),
+            modified_at_ms,
+            mode,
+            profile,
+        })
+    };
+    let full_cache_key = cache_key_for(&profile);
+    // A full artifact that exists or is being prepared is reused with a
+    // client-side seek; only otherwise is a trimmed one cut at the offset.
+    let start_offset = start_seconds
+        .filter(|_| !full_artifact_available(&state, strategy, &full_cache_key))
+        .map(|value| value.floor() as u64);
+    let cache_key = match start_offset {
+        Some(offset) => cache_key_for(&format!("{profile}:ss{offset}")),
+        None => full_cache_key,
+    };
+    let selection = StreamSelection {
+        audio_track: probe.has_audio().then_some(probe.selected_audio_track),
+        start_seconds: start_offset,
+    };
+    // Sidecar and embedded subtitles are timed against the full source, so
+    // trimmed artifacts go without them.
+    let subtitle_tracks = if start_offset.is_some() { Vec::new() } else { subtitle_tracks };
+    let with_tracks = playback_decorator(
+        &probe,
+        &subtitle_tracks,
+        start_seconds.filter(|_| start_offset.is_none()),
+        start_offset,
+    );
+    let manifest = |kind| CacheManifest {
+        kind,
+        relative_path: request.file.clone(),
         file_size: source_metadata.len(),
         modified_at_ms,
-        mode,
-        profile: cache_profile_for_strategy(strategy),
-    });
+    };
     if let PlaybackStrategy::Mp4Remux = strategy {
         let cache_paths = mp4_cache_paths(state.cache_dir(), &cache_key);
         if cached_mp4_is_ready(&cache_paths) {
-            return Ok(ready_mp4_response(&cache_paths, title, duration_seconds));
+            state.touch_cache_entry(&cache_key);
+            return Ok(with_tracks(ready_mp4_response(&cache_paths, title, duration_seconds)));
         }
 
         if let Some(existing) = state.jobs().get(&cache_key) {
             let snapshot = existing.snapshot().await;
-            return Ok(open_response_from_snapshot(snapshot, title));
+            return Ok(with_tracks(open_response_from_snapshot(snapshot, title)));
         }
 
         let job =
             PlaybackJobHandle::new(cache_key.clone(), PlaybackMode::Raw, duration_seconds, detail);
         let job_snapshot = job.snapshot().await;
         state.jobs().insert(cache_key.clone(), job.clone());
-        spawn_mp4_remux_job(state, job, bins, source_path, cache_paths, probe.has_audio());
+        record_cache_manifest(
+            &cache_paths.dir,
+            &cache_paths.manifest,
+            manifest(LocalMediaCacheKind::Mp4),
+        )
+        .await;
+        spawn_mp4_remux_job(state, job, bins, source_path, cache_paths, selection);
 
-        return Ok(open_response_from_snapshot(job_snapshot, title));
+        return Ok(with_tracks(open_response_from_snapshot(job_snapshot, title)));
     }
 
     let cache_paths = hls_cache_paths(state.cache_dir(), &cache_key);
+    // Rewritten on every open so sidecars added after the first open show up
+    // without invalidating the cached segments.
+    if start_offset.is_none() {
+        if let Err(err) = write_hls_subtitle_renditions(
+            &cache_paths,
+            &subtitle_tracks,
+            duration_seconds,
+            probe.bit_rate,
+        )
+        .await
+        {
+            tracing::warn!(job_id = %cache_key, error = %err, "failed to write HLS subtitle renditions");
+        }
+    }
     if cached_hls_is_ready(&cache_paths) {
-        return Ok(ready_hls_response(&cache_paths, title, duration_seconds, detail));
+        state.touch_cache_entry(&cache_key);
+        return Ok(with_tracks(ready_hls_response(&cache_paths, title, duration_seconds, detail)));
     }
 
     if let Some(existing) = state.jobs().get(&cache_key) {
         let snapshot = existing.snapshot().await;
-        return Ok(open_response_from_snapshot(snapshot, title));
+        return Ok(with_tracks(open_response_from_snapshot(snapshot, title)));
     }
 
     let job =
         PlaybackJobHandle::new(cache_key.clone(), PlaybackMode::Hls, duration_seconds, detail);
     let job_snapshot = job.snapshot().await;
     state.jobs().insert(cache_key.clone(), job.clone());
-    spawn_hls_job(state, job, bins, source_path, cache_paths, strategy, probe.has_audio());
-
-    Ok(open_response_from_snapshot(job_snapshot, title))
+    record_cache_manifest(
+        &cache_paths.dir,
+        &cache_paths.manifest,
+        manifest(LocalMediaCacheKind::Hls),
+    )
+    .await;
+    spawn_hls_job(state, job, bins, source_path, cache_paths, strategy, selection);
+
+    Ok(with_tracks(open_response_from_snapshot(job_snapshot, title)))
 }
 
 pub async fn get_job_status(
@@ -180,6 +271,7 @@ pub async fn stream_mp4_artifact(
     if !requested.exists() {
         anyhow::bail!("requested MP4 artifact does not exist");
     }
+    state.touch_cache_entry(job_id);
 
     let mut response = stream_file_with_range(&requested, "video/mp4", headers).await?;
     response
@@ -202,6 +294,7 @@ pub async fn stream_hls_artifact(
     if !requested.exists() {
         anyhow::bail!("requested HLS artifact does not exist");
     }
+    state.touch_cache_entry(job_id);
 
     let content_type = match requested.extension().and_then(|value| value.to_str()) {
         Some("m3u8") => "application/vnd.apple.mpegurl",
@@ -222,7 +315,7 @@ fn spawn_hls_job(
     source_path: PathBuf,
     cache_paths: HlsCachePaths,
     strategy: PlaybackStrategy,
-    has_audio: bool,
+    selection: StreamSelection,
 ) {
     tokio::spawn(async move {
         if state.transcode_limiter().available_permits() == 0 {
@@ -261,7 +354,7 @@ fn spawn_hls_job(
         let _ = fs::remove_file(&cache_paths.error_marker).await;
 
         let mut command =
-            build_hls_command(&bins, &source_path, &cache_paths.dir, strategy, has_audio);
+            build_hls_command(&bins, &source_path, &cache_paths.dir, strategy, selection);
         match command.spawn() {
             Ok(child) => {
                 match wait_for_child_with_bounded_stderr(child, MAX_CHILD_STDERR_BYTES).await {
@@ -272,7 +365,7 @@ fn spawn_hls_job(
                             tracing::error!(job_id = %cache_paths.job_id, error = %message, "HLS playback preparation failed");
                             job.mark_failed(message).await;
                         } else {
-                            job.mark_ready(hls_player_url(&cache_paths.job_id)).await;
+                            job.mark_ready(hls_player_url(&cache_paths)).await;
                             tracing::info!(job_id = %cache_paths.job_id, "HLS playback is ready");
                         }
                     },
@@ -303,6 +396,8 @@ fn spawn_hls_job(
         }
 
         state.jobs().remove(job.job_id());
+        state.touch_cache_entry(job.job_id());
+        state.request_cache_sweep();
     });
 }
 
@@ -312,7 +407,7 @@ fn spawn_mp4_remux_job(
     bins: crate::ffmpeg::BinaryPaths,
     source_path: PathBuf,
     cache_paths: Mp4CachePaths,
-    has_audio: bool,
+    selection: StreamSelection,
 ) {
     tokio::spawn(async move {
         if state.remux_limiter().available_permits() == 0 {
@@ -351,7 +446,7 @@ fn spawn_mp4_remux_job(
         let _ = fs::remove_file(&cache_paths.error_marker).await;
 
         let mut command =
-            build_mp4_remux_command(&bins, &source_path, &cache_paths.video, has_audio);
+            build_mp4_remux_command(&bins, &source_path, &cache_paths.video, selection);
         match command.spawn() {
             Ok(child) => {
                 match wait_for_child_with_bounded_stderr(child, MAX_CHILD_STDERR_BYTES).await {
@@ -393,9 +488,23 @@ fn spawn_mp4_remux_job(
         }
 
         state.jobs().remove(job.job_id());
+        state.touch_cache_entry(job.job_id());
+        state.request_cache_sweep();
     });
 }
 
+/// Best-effort: a missing manifest only means eviction cannot tell whether
+/// the source still exists, so the entry falls back to age/budget eviction.
+async fn record_cache_manifest(dir: &Path, path: &Path, manifest: CacheManifest) {
+    let result = match fs::create_dir_all(dir).await {
+        Ok(()) => write_cache_manifest(path, &manifest).await,
+        Err(err) => Err(err).with_context(|| format!("failed to create {}", dir.display())),
+    };
+    if let Err(err) = result {
+        tracing::warn!(error = %err, "failed to record local media cache manifest");
+    }
+}
+
 fn open_response_from_snapshot(
     snapshot: PlaybackJobStatusResponse,
     title: String,
@@ -409,6 +518,11 @@ fn open_response_from_snapshot(
         duration_seconds: snapshot.duration_seconds,
         detail: snapshot.detail,
         error: snapshot.error,
+        audio_tracks: Vec::new(),
+        selected_audio_track: None,
+        subtitle_tracks: Vec::new(),
+        start_offset_seconds: None,
+        resume_seconds: None,
     }
 }
 
@@ -422,11 +536,16 @@ fn ready_hls_response(
         status: PlaybackStatus::Ready,
         mode: Some(PlaybackMode::Hls),
         job_id: Some(cache_paths.job_id.clone()),
-        player_url: Some(hls_player_url(&cache_paths.job_id)),
+        player_url: Some(hls_player_url(cache_paths)),
         title,
         duration_seconds,
         detail,
         error: None,
+        audio_tracks: Vec::new(),
+        selected_audio_track: None,
+        subtitle_tracks: Vec::new(),
+        start_offset_seconds: None,
+        resume_seconds: None,
     }
 }
 
@@ -444,11 +563,58 @@ fn ready_mp4_response(
         duration_seconds,
         detail: Some(playback_detail(PlaybackStrategy::Mp4Remux).to_string()),
         error: None,
+        audio_tracks: Vec::new(),
+        selected_audio_track: None,
+        subtitle_tracks: Vec::new(),
+        start_offset_seconds: None,
+        resume_seconds: None,
+    }
+}
+
+/// Fills in the per-open fields the snapshot and cache helpers leave empty.
+fn playback_decorator<'a>(
+    probe: &'a MediaProbe,
+    subtitle_tracks: &'a [LocalMediaSubtitleTrack],
+    resume_seconds: Option<f64>,
+    start_offset: Option<u64>,
+) -> impl Fn(PlaybackOpenResponse) -> PlaybackOpenResponse + 'a {
+    move |mut response| {
+        response.audio_tracks = probe.audio_tracks.clone();
+        response.selected_audio_track = probe.has_audio().then_some(probe.selected_audio_track);
+        response.subtitle_tracks = subtitle_tracks.to_vec();
+        response.start_offset_seconds = start_offset.map(|offset| offset as f64);
+        response.resume_seconds = resume_seconds;
+        response
+    }
+}
+
+fn full_artifact_available(state: &LocalMediaState, strategy: PlaybackStrategy, key: &str) -> bool {
+    if state.jobs().get(key).is_some() {
+        return true;
+    }
+    match strategy {
+        PlaybackStrategy::Mp4Remux => cached_mp4_is_ready(&mp4_cache_paths(state.cache_dir(), key)),
+        _ => cached_hls_is_ready(&hls_cache_paths(state.cache_dir(), key)),
     }
 }
 
-fn hls_player_url(job_id: &str) -> String {
-    format!("/admin/local-media/api/playback/hls/{job_id}/index.m3u8")
+/// Points at the master playlist when subtitle renditions were written so
+/// players discover them; the media playlist otherwise.
+fn hls_player_url(cache_paths: &HlsCachePaths) -> String {
+    let playlist = if cache_paths.master_playlist.exists() { "master.m3u8" } else { "index.m3u8" };
+    format!("/admin/local-media/api/playback/hls/{}/{playlist}", cache_paths.job_id)
+}
+
+/// The strategy profile, suffixed with the audio stream when it is not the
+/// default one so each selection gets its own artifacts while existing
+/// caches for the default stay valid.
+fn playback_cache_profile(strategy: PlaybackStrategy, probe: &MediaProbe) -> String {
+    let profile = cache_profile_for_strategy(strategy);
+    if probe.uses_alternate_audio() {
+        format!("{profile}:a{}", probe.selected_audio_track)
+    } else {
+        profile.to_string()
+    }
 }
 
 fn mp4_player_url(job_id: &str) -> String {
@@ -539,7 +705,7 @@ async fn cached_hls_status(cache_paths: &HlsCachePaths) -> Option<PlaybackJobSta
             job_id: cache_paths.job_id.clone(),
             status: PlaybackStatus::Ready,
             mode: Some(PlaybackMode::Hls),
-            player_url: Some(hls_player_url(&cache_paths.job_id)),
+            player_url: Some(hls_player_url(cache_paths)),
             duration_seconds: None,
             detail: Some(playback_detail(PlaybackStrategy::HlsTranscode).to_string()),
             error: None,
@@ -564,7 +730,7 @@ async fn cached_hls_status(cache_paths: &HlsCachePaths) -> Option<PlaybackJobSta
             job_id: cache_paths.job_id.clone(),
             status: PlaybackStatus::Ready,
             mode: Some(PlaybackMode::Hls),
-            player_url: Some(hls_player_url(&cache_paths.job_id)),
+            player_url: Some(hls_player_url(cache_paths)),
             duration_seconds: None,
             detail: Some(playback_detail(PlaybackStrategy::HlsTranscode).to_string()),
             error: None,
@@ -0,0 +1,256 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use static_flow_media_types::{LocalMediaPlaybackProgress, UpdatePlaybackProgressRequest};
+use tokio::{fs, sync::Mutex};
+
+/// Least recently touched entries beyond this are dropped so the file
+/// stays small enough to rewrite on every update.
+const MAX_ENTRIES: usize = 5000;
+/// Positions this far into a file count as watched to the end, which
+/// skips credits without requiring the `ended` event.
+const COMPLETION_RATIO: f64 = 0.95;
+
+pub fn history_json_path(history_dir: &Path) -> PathBuf {
+    history_dir.join("history.json")
+}
+
+/// Per-file playback positions, kept in memory and mirrored to one JSON
+/// file under the media root.
+pub struct PlaybackHistory {
+    path: PathBuf,
+    entries: Mutex<BTreeMap<String, LocalMediaPlaybackProgress>>,
+}
+
+impl PlaybackHistory {
+    pub async fn load(history_dir: &Path) -> Result<Self> {
+        let path = history_json_path(history_dir);
+        let entries = match fs::read(&path).await {
+            Ok(bytes) => serde_json::from_slice::<Vec<LocalMediaPlaybackProgress>>(&bytes)
+                .with_context(|| format!("failed to decode {}", path.display()))?
+                .into_iter()
+                .map(|entry| (entry.relative_path.clone(), entry))
+                .collect(),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(err) => {
+                return Err(err).with_context(|| format!("failed to read {}", path.display()))
+            },
+        };
+        Ok(Self {
+            path,
+            entries: Mutex::new(entries),
+        })
+    }
+
+    #[cfg(test)]
+    pub(crate) fn empty(history_dir: &Path) -> Self {
+        Self {
+            path: history_json_path(history_dir),
+            entries: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    pub async fn get(&self, relative_path: &str) -> Option<LocalMediaPlaybackProgress> {
+        self.entries.lock().await.get(relative_path).cloned()
+    }
+
+    /// Look up several files under one lock, e.g. for a directory page.
+    pub async fn get_many<'a>(
+        &self,
+        relative_paths: impl IntoIterator<Item = &'a str>,
+    ) -> BTreeMap<String, LocalMediaPlaybackProgress> {
+        let entries = self.entries.lock().await;
+        relative_paths
+            .into_iter()
+            .filter_map(|path| {
+                entries
+                    .get(path)
+                    .map(|entry| (path.to_string(), entry.clone()))
+            })
+            .collect()
+    }
+
+    /// Most recently opened or updated first.
+    pub async fn recent(&self, limit: usize) -> Vec<LocalMediaPlaybackProgress> {
+        let mut entries = self
+            .entries
+            .lock()
+            .await
+            .values()
+            .cloned()
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|entry| std::cmp::Reverse(last_touched_ms(entry)));
+        entries.truncate(limit);
+        entries
+    }
+
+    pub async fn record_open(
+        &self,
+        relative_path: &str,
+        duration_seconds: Option<f64>,
+        now_ms: i64,
+    ) -> Result<LocalMediaPlaybackProgress> {
+        let mut entries = self.entries.lock().await;
+        let entry = entries.entry(relative_path.to_string()).or_insert_with(|| {
+            LocalMediaPlaybackProgress {
+                relative_path: relative_path.to_string(),
+                position_seconds: 0.0,
+                duration_seconds: None,
+                completed: false,
+                last_opened_at_ms: None,
+                updated_at_ms: now_ms,
+            }
+        });
+        entry.last_opened_at_ms = Some(now_ms);
+        if duration_seconds.is_some() {
+            entry.duration_seconds = duration_seconds;
+        }
+        let progress = entry.clone();
+        self.persist(&mut entries).await?;
+        Ok(progress)
+    }
+
+    pub async fn record_position(
+        &self,
+        update: &UpdatePlaybackProgressRequest,
+        now_ms: i64,
+    ) -> Result<LocalMediaPlaybackProgress> {
+        if !update.position_seconds.is_finite() || update.position_seconds < 0.0 {
+            anyhow::bail!("playback position must be a non-negative number");
+        }
+        let mut entries = self.entries.lock().await;
+        let entry =
+            entries
+                .entry(update.file.clone())
+                .or_insert_with(|| LocalMediaPlaybackProgress {
+                    relative_path: update.file.clone(),
+                    position_seconds: 0.0,
+                    duration_seconds: None,
+                    completed: false,
+                    last_opened_at_ms: None,
+                    updated_at_ms: now_ms,
+                });
+        if let Some(duration) = update
+            .duration_seconds
+            .filter(|value| value.is_finite() && *value > 0.0)
+        {
+            entry.duration_seconds = Some(duration);
+        }
+        entry.position_seconds = update.position_seconds;
+        entry.completed = update.completed
+            || entry
+                .duration_seconds
+                .is_some_and(|duration| update.position_seconds >= duration * COMPLETION_RATIO);
+        entry.updated_at_ms = now_ms;
+        let progress = entry.clone();
+        self.persist(&mut entries).await?;
+        Ok(progress)
+    }
+
+    async fn persist(
+        &self,
+        entries: &mut BTreeMap<String, LocalMediaPlaybackProgress>,
+    ) -> Result<()> {
+        if entries.len() > MAX_ENTRIES {
+            let mut by_age = entries
+                .values()
+                .map(|entry| (last_touched_ms(entry), entry.relative_path.clone()))
+                .collect::<Vec<_>>();
+            by_age.sort();
+            for (_, relative_path) in by_age.into_iter().take(entries.len() - MAX_ENTRIES) {
+                entries.remove(&relative_path);
+            }
+        }
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)
+                .await
+                .with_context(|| format!("failed to create {}", dir.display()))?;
+        }
+        let bytes = serde_json::to_vec(&entries.values().collect::<Vec<_>>())
+            .context("failed to encode playback history")?;
+        // Write-then-rename so a crash mid-write never truncates the history.
+        let temp_path = self.path.with_extension("json.part");
+        fs::write(&temp_path, bytes)
+            .await
+            .with_context(|| format!("failed to write {}", temp_path.display()))?;
+        fs::rename(&temp_path, &self.path)
+            .await
+            .with_context(|| format!("failed to replace {}", self.path.display()))
+    }
+}
+
+fn last_touched_ms(entry: &LocalMediaPlaybackProgress) -> i64 {
+    entry
+        .last_opened_at_ms
+        .unwrap_or_default()
+        .max(entry.updated_at_ms)
+}
+
+#[cfg(test)]
+mod tests {
+    use static_flow_media_types::UpdatePlaybackProgressRequest;
+
+    use super::PlaybackHistory;
+
+    fn update(file: &str, position_seconds: f64) -> UpdatePlaybackProgressRequest {
+        UpdatePlaybackProgressRequest {
+            file: file.to_string(),
+            position_seconds,
+            duration_seconds: Some(1000.0),
+            completed: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn positions_survive_reload_and_mark_completion() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let history = PlaybackHistory::load(temp.path()).await.expect("load");
+        history
+            .record_open("show/ep1.mkv", Some(1000.0), 10)
+            .await
+            .expect("open");
+        let progress = history
+            .record_position(&update("show/ep1.mkv", 420.5), 20)
+            .await
+            .expect("update");
+        assert_eq!(progress.resume_seconds(), Some(420.5));
+        assert_eq!(progress.last_opened_at_ms, Some(10));
+
+        let finished = history
+            .record_position(&update("show/ep2.mkv", 960.0), 30)
+            .await
+            .expect("update");
+        assert!(finished.completed);
+        assert_eq!(finished.resume_seconds(), None);
+
+        let reloaded = PlaybackHistory::load(temp.path()).await.expect("reload");
+        let ep1 = reloaded.get("show/ep1.mkv").await.expect("ep1");
+        assert_eq!(ep1.position_seconds, 420.5);
+        assert_eq!(ep1.duration_seconds, Some(1000.0));
+        let recent = reloaded
+            .recent(10)
+            .await
+            .into_iter()
+            .map(|entry| entry.relative_path)
+            .collect::<Vec<_>>();
+        assert_eq!(recent, vec!["show/ep2.mkv", "show/ep1.mkv"]);
+    }
+
+    #[tokio::test]
+    async fn record_position_rejects_invalid_positions() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let history = PlaybackHistory::load(temp.path()).await.expect("load");
+        assert!(history
+            .record_position(&update("clip.mp4", f64::NAN), 1)
+            .await
+            .is_err());
+        assert!(history
+            .record_position(&update("clip.mp4", -1.0), 1)
+            .await
+            .is_err());
+        assert!(history.get("clip.mp4").await.is_none());
+    }
+}
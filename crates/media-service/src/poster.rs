@@ -11,11 +11,13 @@ use tokio_util::io::ReaderStream;
 
 use crate::{
     cache::{
-        build_poster_cache_key, poster_cache_paths, source_modified_at_ms, PosterCacheKeyInput,
+        build_poster_cache_key, poster_cache_paths, source_modified_at_ms, write_cache_manifest,
+        CacheManifest, PosterCacheKeyInput,
     },
     ffmpeg::{build_poster_command, ensure_binary_paths},
     path_guard::resolve_media_path,
     probe::probe_media,
+    types::LocalMediaCacheKind,
     LocalMediaState,
 };
 
@@ -34,13 +36,15 @@ pub async fn stream_or_generate_poster(
         anyhow::bail!("requested media path is not a file");
     }
 
+    let modified_at_ms = source_modified_at_ms(&source_path).await?;
     let cache_key = build_poster_cache_key(&PosterCacheKeyInput {
         relative_path,
         file_size: metadata.len(),
-        modified_at_ms: source_modified_at_ms(&source_path).await?,
+        modified_at_ms,
         profile: POSTER_PROFILE,
     });
     let cache_paths = poster_cache_paths(state.cache_dir(), &cache_key);
+    state.touch_cache_entry(&cache_key);
 
     if poster_ready(&cache_paths) {
         return stream_jpeg(&cache_paths.image).await;
@@ -62,6 +66,15 @@ pub async fn stream_or_generate_poster(
         .with_context(|| format!("failed to create {}", cache_paths.dir.display()))?;
     let _ = fs::remove_file(&cache_paths.error_marker).await;
     let _ = fs::remove_file(&cache_paths.ready_marker).await;
+    let manifest = CacheManifest {
+        kind: LocalMediaCacheKind::Poster,
+        relative_path: relative_path.to_string(),
+        file_size: metadata.len(),
+        modified_at_ms,
+    };
+    if let Err(err) = write_cache_manifest(&cache_paths.manifest, &manifest).await {
+        tracing::warn!(cache_key = %cache_key, error = %err, "failed to write cache manifest");
+    }
 
     let bins = ensure_binary_paths(state.config()).await?;
     let probe = probe_media(&bins, &source_path).await?;
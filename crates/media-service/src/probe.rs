@@ -4,19 +4,82 @@ use anyhow::{Context, Result};
 use serde::Deserialize;
 use tokio::process::Command;
 
-use crate::{ffmpeg::BinaryPaths, types::PlaybackMode};
+use crate::{
+    ffmpeg::BinaryPaths,
+    types::{LocalMediaAudioTrack, PlaybackMode},
+};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct MediaProbe {
     pub video_codec: Option<String>,
+    /// Codec of the audio stream playback maps, see `selected_audio_track`.
     pub audio_codec: Option<String>,
     pub duration_seconds: Option<f64>,
+    pub bit_rate: Option<u64>,
+    pub audio_tracks: Vec<LocalMediaAudioTrack>,
+    /// Index among the audio streams, not the container stream index.
+    pub selected_audio_track: usize,
+    pub subtitle_streams: Vec<SubtitleStream>,
 }
 
 impl MediaProbe {
     pub fn has_audio(&self) -> bool {
         self.audio_codec.is_some()
     }
+
+    /// Switch playback to another audio stream. Returns `false` and keeps
+    /// the current selection when `index` does not exist.
+    pub fn select_audio_track(&mut self, index: usize) -> bool {
+        let Some(track) = self.audio_tracks.get(index) else {
+            return false;
+        };
+        self.audio_codec = track.codec.clone();
+        self.selected_audio_track = index;
+        true
+    }
+
+    /// The stream players pick on their own: the one flagged default, or
+    /// the first when none is.
+    pub fn default_audio_track(&self) -> usize {
+        self.audio_tracks
+            .iter()
+            .position(|track| track.default)
+            .unwrap_or(0)
+    }
+
+    /// Whether playback uses an audio stream other than the default one,
+    /// which browsers cannot switch to when streaming the file directly.
+    pub fn uses_alternate_audio(&self) -> bool {
+        self.selected_audio_track != self.default_audio_track()
+    }
+
+    pub fn text_subtitle_streams(&self) -> impl Iterator<Item = &SubtitleStream> {
+        self.subtitle_streams
+            .iter()
+            .filter(|stream| stream.is_text())
+    }
+}
+
+/// Embedded subtitle stream as reported by ffprobe.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubtitleStream {
+    /// Index among the subtitle streams, usable as `0:s:{index}`.
+    pub index: usize,
+    pub codec: Option<String>,
+    pub language: Option<String>,
+    pub title: Option<String>,
+    pub default: bool,
+    pub forced: bool,
+}
+
+impl SubtitleStream {
+    /// Bitmap formats (PGS, VobSub) cannot be converted to WebVTT.
+    pub fn is_text(&self) -> bool {
+        matches!(
+            self.codec.as_deref(),
+            Some("subrip" | "srt" | "ass" | "ssa" | "webvtt" | "mov_text" | "text")
+        )
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -38,11 +101,31 @@ struct FfprobePayload {
 struct FfprobeStream {
     codec_type: Option<String>,
     codec_name: Option<String>,
+    channels: Option<u32>,
+    #[serde(default)]
+    tags: FfprobeTags,
+    #[serde(default)]
+    disposition: FfprobeDisposition,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct FfprobeTags {
+    language: Option<String>,
+    title: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct FfprobeDisposition {
+    #[serde(default)]
+    default: u8,
+    #[serde(default)]
+    forced: u8,
 }
 
 #[derive(Debug, Deserialize)]
 struct FfprobeFormat {
     duration: Option<String>,
+    bit_rate: Option<String>,
 }
 
 pub async fn probe_media(bins: &BinaryPaths, input_path: &Path) -> Result<MediaProbe> {
@@ -68,26 +151,63 @@ pub async fn probe_media(bins: &BinaryPaths, input_path: &Path) -> Result<MediaP
 
     let payload: FfprobePayload = serde_json::from_slice(&output.stdout)
         .with_context(|| format!("failed to parse ffprobe output for {}", input_path.display()))?;
+    Ok(media_probe_from_payload(payload))
+}
 
+fn media_probe_from_payload(payload: FfprobePayload) -> MediaProbe {
     let mut video_codec = None;
-    let mut audio_codec = None;
+    let mut audio_tracks = Vec::new();
+    let mut subtitle_streams = Vec::new();
     for stream in payload.streams {
         match stream.codec_type.as_deref() {
             Some("video") if video_codec.is_none() => video_codec = stream.codec_name.clone(),
-            Some("audio") if audio_codec.is_none() => audio_codec = stream.codec_name.clone(),
+            Some("audio") => audio_tracks.push(LocalMediaAudioTrack {
+                index: audio_tracks.len(),
+                codec: stream.codec_name,
+                language: normalize_tag(stream.tags.language),
+                title: normalize_tag(stream.tags.title),
+                channels: stream.channels,
+                default: stream.disposition.default != 0,
+            }),
+            Some("subtitle") => subtitle_streams.push(SubtitleStream {
+                index: subtitle_streams.len(),
+                codec: stream.codec_name,
+                language: normalize_tag(stream.tags.language),
+                title: normalize_tag(stream.tags.title),
+                default: stream.disposition.default != 0,
+                forced: stream.disposition.forced != 0,
+            }),
             _ => {},
         }
     }
 
-    Ok(MediaProbe {
+    let format = payload.format;
+    let mut probe = MediaProbe {
         video_codec,
-        audio_codec,
-        duration_seconds: payload
-            .format
-            .and_then(|format| format.duration)
+        audio_codec: None,
+        duration_seconds: format
+            .as_ref()
+            .and_then(|format| format.duration.as_deref())
             .and_then(|value| value.parse::<f64>().ok())
             .filter(|value| value.is_finite() && *value > 0.0),
-    })
+        bit_rate: format
+            .as_ref()
+            .and_then(|format| format.bit_rate.as_deref())
+            .and_then(|value| value.parse::<u64>().ok())
+            .filter(|value| *value > 0),
+        audio_tracks,
+        selected_audio_track: 0,
+        subtitle_streams,
+    };
+    probe.select_audio_track(probe.default_audio_track());
+    probe
+}
+
+/// ffprobe reports `und` for streams without a language tag.
+fn normalize_tag(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty() && value != "und")
 }
 
 pub fn choose_playback_strategy(path: &Path, probe: &MediaProbe) -> PlaybackStrategy {
@@ -98,16 +218,21 @@ pub fn choose_playback_strategy(path: &Path, probe: &MediaProbe) -> PlaybackStra
         .unwrap_or_default();
     let video = probe.video_codec.as_deref().unwrap_or_default();
     let audio = probe.audio_codec.as_deref();
+    // Direct playback always gets the container's default audio stream.
+    let direct_allowed = !probe.uses_alternate_audio();
 
-    let direct_mp4 =
-        matches!(ext.as_str(), "mp4" | "m4v") && video == "h264" && audio_is_mp4_safe(audio);
+    let direct_mp4 = direct_allowed
+        && matches!(ext.as_str(), "mp4" | "m4v")
+        && video == "h264"
+        && audio_is_mp4_safe(audio);
     if direct_mp4 {
         return PlaybackStrategy::Raw {
             mime_type: "video/mp4",
         };
     }
 
-    let direct_webm = ext == "webm"
+    let direct_webm = direct_allowed
+        && ext == "webm"
         && matches!(video, "vp8" | "vp9" | "av1")
         && matches!(audio, None | Some("opus") | Some("vorbis"));
     if direct_webm {
@@ -152,7 +277,31 @@ pub fn mode_for_strategy(strategy: PlaybackStrategy) -> PlaybackMode {
 mod tests {
     use std::path::Path;
 
-    use super::{choose_playback_strategy, MediaProbe, PlaybackStrategy};
+    use super::{
+        choose_playback_strategy, media_probe_from_payload, FfprobePayload, MediaProbe,
+        PlaybackStrategy,
+    };
+
+    fn multi_language_probe() -> MediaProbe {
+        let payload: FfprobePayload = serde_json::from_str(
+            r#"{
+                "streams": [
+                    {"codec_type": "video", "codec_name": "h264"},
+                    {"codec_type": "audio", "codec_name": "aac", "channels": 2,
+                     "tags": {"language": "jpn"}, "disposition": {"default": 1}},
+                    {"codec_type": "audio", "codec_name": "ac3", "channels": 6,
+                     "tags": {"language": "eng", "title": "Surround"}},
+                    {"codec_type": "subtitle", "codec_name": "ass",
+                     "tags": {"language": "chi"}, "disposition": {"default": 1}},
+                    {"codec_type": "subtitle", "codec_name": "hdmv_pgs_subtitle",
+                     "tags": {"language": "und"}, "disposition": {"forced": 1}}
+                ],
+                "format": {"duration": "1440.5", "bit_rate": "4000000"}
+            }"#,
+        )
+        .expect("ffprobe payload");
+        media_probe_from_payload(payload)
+    }
 
     #[test]
     fn choose_playback_strategy_prefers_incremental_hls_for_mp4_safe_mkv() {
@@ -160,9 +309,50 @@ mod tests {
             video_codec: Some("h264".to_string()),
             audio_codec: Some("aac".to_string()),
             duration_seconds: Some(123.0),
+            ..MediaProbe::default()
         };
 
         let strategy = choose_playback_strategy(Path::new("demo.mkv"), &probe);
         assert_eq!(strategy, PlaybackStrategy::HlsCopy);
     }
+
+    #[test]
+    fn media_probe_enumerates_audio_and_subtitle_streams() {
+        let probe = multi_language_probe();
+
+        assert_eq!(probe.audio_tracks.len(), 2);
+        assert_eq!(probe.audio_tracks[1].language.as_deref(), Some("eng"));
+        assert_eq!(probe.audio_tracks[1].title.as_deref(), Some("Surround"));
+        assert_eq!(probe.audio_tracks[1].channels, Some(6));
+        assert_eq!(probe.selected_audio_track, 0);
+        assert_eq!(probe.audio_codec.as_deref(), Some("aac"));
+        assert_eq!(probe.bit_rate, Some(4_000_000));
+
+        assert_eq!(probe.subtitle_streams.len(), 2);
+        assert_eq!(probe.subtitle_streams[1].language, None);
+        assert!(probe.subtitle_streams[1].forced);
+        let text = probe.text_subtitle_streams().collect::<Vec<_>>();
+        assert_eq!(text.len(), 1);
+        assert_eq!(text[0].index, 0);
+    }
+
+    #[test]
+    fn selecting_alternate_audio_drives_the_strategy() {
+        let mut probe = multi_language_probe();
+        assert_eq!(
+            choose_playback_strategy(Path::new("demo.mp4"), &probe),
+            PlaybackStrategy::Raw {
+                mime_type: "video/mp4",
+            }
+        );
+
+        assert!(probe.select_audio_track(1));
+        assert_eq!(probe.audio_codec.as_deref(), Some("ac3"));
+        assert_eq!(
+            choose_playback_strategy(Path::new("demo.mp4"), &probe),
+            PlaybackStrategy::HlsTranscode
+        );
+        assert!(!probe.select_audio_track(5));
+        assert_eq!(probe.selected_audio_track, 1);
+    }
 }
@@ -29,6 +29,14 @@ pub fn create_router(state: Arc<LocalMediaState>) -> Router {
             "/internal/local-media/playback/jobs/:job_id",
             get(handlers::get_local_media_job_status),
         )
+        .route(
+            "/internal/local-media/playback/progress",
+            post(handlers::update_local_media_playback_progress),
+        )
+        .route(
+            "/internal/local-media/playback/history",
+            get(handlers::list_local_media_playback_history),
+        )
         .route("/internal/local-media/playback/raw", get(handlers::stream_local_media_raw))
         .route(
             "/internal/local-media/playback/hls/:job_id/:file_name",
@@ -39,6 +47,8 @@ pub fn create_router(state: Arc<LocalMediaState>) -> Router {
             get(handlers::stream_local_media_mp4_artifact),
         )
         .route("/internal/local-media/poster", get(handlers::stream_local_media_poster))
+        .route("/internal/local-media/subtitle", get(handlers::stream_local_media_subtitle))
+        .route("/internal/local-media/cache", get(handlers::get_local_media_cache_usage))
         .route(
             "/internal/local-media/uploads/tasks",
             post(handlers::create_upload_task).get(handlers::list_upload_tasks),
@@ -7,11 +7,15 @@ use std::{
 
 use anyhow::{Context, Result};
 use dashmap::DashMap;
-use tokio::{fs as tokio_fs, sync::Semaphore};
+use tokio::{
+    fs as tokio_fs,
+    sync::{Notify, Semaphore},
+};
 
 use crate::{
     config::{read_local_media_config_from_env, LocalMediaConfig},
     jobs::PlaybackJobHandle,
+    playback_store::PlaybackHistory,
 };
 
 #[derive(Clone)]
@@ -22,8 +26,14 @@ pub struct LocalMediaState {
     remux_limiter: Arc<Semaphore>,
     transcode_limiter: Arc<Semaphore>,
     poster_limiter: Arc<Semaphore>,
+    subtitle_limiter: Arc<Semaphore>,
     jobs: Arc<DashMap<String, Arc<PlaybackJobHandle>>>,
     upload_locks: Arc<DashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+    /// Last access time (unix ms) per cache entry since startup; eviction
+    /// falls back to artifact mtimes for entries not yet seen.
+    cache_access: Arc<DashMap<String, i64>>,
+    cache_sweep: Arc<Notify>,
+    playback_history: Arc<PlaybackHistory>,
 }
 
 impl LocalMediaState {
@@ -66,12 +76,19 @@ impl LocalMediaState {
                 )
             })?;
 
+        let playback_history = PlaybackHistory::load(&playback_history_dir(&root_dir))
+            .await
+            .context("failed to load local media playback history")?;
+
         tracing::info!(
             root_dir = %root_dir.display(),
             cache_dir = %cache_dir.display(),
             max_remux_jobs = config.max_remux_jobs,
             max_transcode_jobs = config.max_transcode_jobs,
             max_poster_jobs = config.max_poster_jobs,
+            max_subtitle_jobs = config.max_subtitle_jobs,
+            cache_max_bytes = ?config.cache_max_bytes,
+            cache_max_age_seconds = ?config.cache_max_age_seconds,
             auto_download_ffmpeg = config.auto_download_ffmpeg,
             "local media feature initialized"
         );
@@ -80,8 +97,12 @@ impl LocalMediaState {
             remux_limiter: Arc::new(Semaphore::new(config.max_remux_jobs)),
             transcode_limiter: Arc::new(Semaphore::new(config.max_transcode_jobs)),
             poster_limiter: Arc::new(Semaphore::new(config.max_poster_jobs)),
+            subtitle_limiter: Arc::new(Semaphore::new(config.max_subtitle_jobs)),
             jobs: Arc::new(DashMap::new()),
             upload_locks: Arc::new(DashMap::new()),
+            cache_access: Arc::new(DashMap::new()),
+            cache_sweep: Arc::new(Notify::new()),
+            playback_history: Arc::new(playback_history),
             config,
             root_dir,
             cache_dir,
@@ -112,10 +133,41 @@ impl LocalMediaState {
         &self.poster_limiter
     }
 
+    pub fn subtitle_limiter(&self) -> &Arc<Semaphore> {
+        &self.subtitle_limiter
+    }
+
     pub fn jobs(&self) -> &Arc<DashMap<String, Arc<PlaybackJobHandle>>> {
         &self.jobs
     }
 
+    /// Record that a cache entry was just served or produced.
+    pub fn touch_cache_entry(&self, job_id: &str) {
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        self.cache_access.insert(job_id.to_string(), now_ms);
+    }
+
+    pub fn cache_last_access_ms(&self, job_id: &str) -> Option<i64> {
+        self.cache_access.get(job_id).map(|entry| *entry)
+    }
+
+    pub fn forget_cache_entry(&self, job_id: &str) {
+        self.cache_access.remove(job_id);
+    }
+
+    /// Wake the cache sweeper early, e.g. after a job wrote new artifacts.
+    pub fn request_cache_sweep(&self) {
+        self.cache_sweep.notify_one();
+    }
+
+    pub fn cache_sweep_requested(&self) -> &Notify {
+        &self.cache_sweep
+    }
+
+    pub fn playback_history(&self) -> &PlaybackHistory {
+        &self.playback_history
+    }
+
     pub fn upload_root(&self) -> PathBuf {
         self.root_dir.join(".static-flow").join("uploads")
     }
@@ -132,6 +184,12 @@ impl LocalMediaState {
     }
 }
 
+/// Lives beside the upload state under the hidden service directory, which
+/// directory listings skip.
+fn playback_history_dir(root_dir: &Path) -> PathBuf {
+    root_dir.join(".static-flow").join("playback")
+}
+
 #[cfg(test)]
 impl LocalMediaState {
     pub fn new_for_test(root_dir: PathBuf, cache_dir: PathBuf) -> Arc<Self> {
@@ -139,8 +197,12 @@ impl LocalMediaState {
             remux_limiter: Arc::new(Semaphore::new(2)),
             transcode_limiter: Arc::new(Semaphore::new(1)),
             poster_limiter: Arc::new(Semaphore::new(1)),
+            subtitle_limiter: Arc::new(Semaphore::new(1)),
             jobs: Arc::new(DashMap::new()),
             upload_locks: Arc::new(DashMap::new()),
+            cache_access: Arc::new(DashMap::new()),
+            cache_sweep: Arc::new(Notify::new()),
+            playback_history: Arc::new(PlaybackHistory::empty(&playback_history_dir(&root_dir))),
             config: LocalMediaConfig {
                 enabled: true,
                 root: Some(root_dir.clone()),
@@ -149,7 +211,11 @@ impl LocalMediaState {
                 max_remux_jobs: 2,
                 max_transcode_jobs: 1,
                 max_poster_jobs: 1,
+                max_subtitle_jobs: 1,
                 list_page_size: 120,
+                cache_max_bytes: None,
+                cache_max_age_seconds: None,
+                cache_sweep_interval_seconds: 600,
                 ffmpeg_bin: None,
                 ffprobe_bin: None,
             },
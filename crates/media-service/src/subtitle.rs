@@ -0,0 +1,528 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{Context, Result};
+use axum::{
+    body::Body,
+    http::{header, StatusCode},
+    response::Response,
+};
+use tokio::{
+    fs::{self, File},
+    io::AsyncReadExt,
+};
+use tokio_util::io::ReaderStream;
+
+use crate::{
+    cache::{
+        build_subtitle_cache_key, source_modified_at_ms, subtitle_cache_paths,
+        write_cache_manifest, CacheManifest, HlsCachePaths, SubtitleCacheKeyInput,
+        SubtitleCachePaths,
+    },
+    ffmpeg::{build_subtitle_command, ensure_binary_paths},
+    path_guard::resolve_media_path,
+    probe::MediaProbe,
+    types::{LocalMediaCacheKind, LocalMediaSubtitleTrack},
+    LocalMediaState,
+};
+
+const SUBTITLE_PROFILE: &str = "webvtt-v1";
+const SUBTITLE_STDERR_LIMIT: usize = 32 * 1024;
+const SIDECAR_EXTENSIONS: &[&str] = &["srt", "ass", "ssa"];
+const SUBTITLE_GROUP_ID: &str = "subs";
+/// Used when ffprobe reports no container bit rate; HLS requires a value
+/// and players only use it to rank variants, of which there is one.
+const FALLBACK_BANDWIDTH: u64 = 5_000_000;
+
+/// Subtitle file next to a media file sharing its stem, e.g.
+/// `Movie.srt` or `Movie.en.ass` for `Movie.mkv`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SidecarSubtitle {
+    pub path: PathBuf,
+    pub file_name: String,
+    pub codec: String,
+    pub language: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubtitleTrackId {
+    /// Index among the media file's subtitle streams.
+    Embedded(usize),
+    /// Index into the sorted sidecar list.
+    Sidecar(usize),
+}
+
+impl SubtitleTrackId {
+    pub fn parse(value: &str) -> Option<Self> {
+        let (kind, index) = value.split_at_checked(1)?;
+        let index = index.parse::<usize>().ok()?;
+        match kind {
+            "s" => Some(Self::Embedded(index)),
+            "x" => Some(Self::Sidecar(index)),
+            _ => None,
+        }
+    }
+
+    pub fn as_string(self) -> String {
+        match self {
+            Self::Embedded(index) => format!("s{index}"),
+            Self::Sidecar(index) => format!("x{index}"),
+        }
+    }
+}
+
+pub async fn discover_sidecar_subtitles(source_path: &Path) -> Vec<SidecarSubtitle> {
+    let (Some(dir), Some(stem)) =
+        (source_path.parent(), source_path.file_stem().and_then(|value| value.to_str()))
+    else {
+        return Vec::new();
+    };
+    let Ok(mut read_dir) = fs::read_dir(dir).await else {
+        return Vec::new();
+    };
+    let mut sidecars = Vec::new();
+    while let Ok(Some(entry)) = read_dir.next_entry().await {
+        let Ok(file_name) = entry.file_name().into_string() else {
+            continue;
+        };
+        let Some(sidecar) = parse_sidecar_name(stem, &file_name) else {
+            continue;
+        };
+        if !entry.file_type().await.is_ok_and(|kind| kind.is_file()) {
+            continue;
+        }
+        sidecars.push(SidecarSubtitle {
+            path: entry.path(),
+            ..sidecar
+        });
+    }
+    sidecars.sort_by(|left, right| left.file_name.cmp(&right.file_name));
+    sidecars
+}
+
+fn parse_sidecar_name(stem: &str, file_name: &str) -> Option<SidecarSubtitle> {
+    let (rest, extension) = file_name.rsplit_once('.')?;
+    let extension = extension.to_ascii_lowercase();
+    if !SIDECAR_EXTENSIONS.contains(&extension.as_str()) {
+        return None;
+    }
+    let language = if rest == stem {
+        None
+    } else {
+        let qualifier = rest.strip_prefix(stem)?.strip_prefix('.')?;
+        Some(qualifier.split('.').next()?.trim().to_string()).filter(|value| !value.is_empty())
+    };
+    Some(SidecarSubtitle {
+        path: PathBuf::new(),
+        file_name: file_name.to_string(),
+        codec: if extension == "srt" { "subrip".to_string() } else { extension },
+        language,
+    })
+}
+
+/// Text subtitle tracks offered for `relative_path`: convertible embedded
+/// streams first, then sidecar files.
+pub fn subtitle_tracks(
+    relative_path: &str,
+    probe: &MediaProbe,
+    sidecars: &[SidecarSubtitle],
+) -> Vec<LocalMediaSubtitleTrack> {
+    let embedded = probe.text_subtitle_streams().map(|stream| {
+        let id = SubtitleTrackId::Embedded(stream.index).as_string();
+        LocalMediaSubtitleTrack {
+            url: subtitle_url(relative_path, &id),
+            id,
+            codec: stream.codec.clone(),
+            language: stream.language.clone(),
+            title: stream.title.clone(),
+            default: stream.default,
+            forced: stream.forced,
+            external: false,
+        }
+    });
+    let external = sidecars.iter().enumerate().map(|(index, sidecar)| {
+        let id = SubtitleTrackId::Sidecar(index).as_string();
+        LocalMediaSubtitleTrack {
+            url: subtitle_url(relative_path, &id),
+            id,
+            codec: Some(sidecar.codec.clone()),
+            language: sidecar.language.clone(),
+            title: Some(sidecar.file_name.clone()),
+            default: false,
+            forced: false,
+            external: true,
+        }
+    });
+    embedded.chain(external).collect()
+}
+
+pub fn subtitle_url(relative_path: &str, track_id: &str) -> String {
+    format!(
+        "/admin/local-media/api/subtitle?file={}&track={}",
+        urlencoding::encode(relative_path),
+        urlencoding::encode(track_id)
+    )
+}
+
+pub fn subtitle_label(track: &LocalMediaSubtitleTrack) -> String {
+    track
+        .title
+        .clone()
+        .or_else(|| track.language.clone())
+        .unwrap_or_else(|| format!("Subtitle {}", track.id))
+}
+
+/// Write one single-segment playlist per subtitle track plus a master
+/// playlist exposing them as renditions of `index.m3u8`. Without a known
+/// duration the renditions cannot be described, so nothing is written.
+pub async fn write_hls_subtitle_renditions(
+    cache_paths: &HlsCachePaths,
+    tracks: &[LocalMediaSubtitleTrack],
+    duration_seconds: Option<f64>,
+    bit_rate: Option<u64>,
+) -> Result<()> {
+    let Some(duration_seconds) = duration_seconds.filter(|_| !tracks.is_empty()) else {
+        let _ = fs::remove_file(&cache_paths.master_playlist).await;
+        return Ok(());
+    };
+    fs::create_dir_all(&cache_paths.dir)
+        .await
+        .with_context(|| format!("failed to create {}", cache_paths.dir.display()))?;
+    for track in tracks {
+        let path = cache_paths.dir.join(subtitle_playlist_name(&track.id));
+        fs::write(&path, render_subtitle_playlist(&track.url, duration_seconds))
+            .await
+            .with_context(|| format!("failed to write {}", path.display()))?;
+    }
+    fs::write(
+        &cache_paths.master_playlist,
+        render_master_playlist(tracks, bit_rate.unwrap_or(FALLBACK_BANDWIDTH)),
+    )
+    .await
+    .with_context(|| format!("failed to write {}", cache_paths.master_playlist.display()))
+}
+
+fn subtitle_playlist_name(track_id: &str) -> String {
+    format!("subtitles_{track_id}.m3u8")
+}
+
+fn render_subtitle_playlist(vtt_url: &str, duration_seconds: f64) -> String {
+    format!(
+        "#EXTM3U\n#EXT-X-VERSION:3\n#EXT-X-TARGETDURATION:{}\n#EXT-X-MEDIA-SEQUENCE:0\n#\
+         EXT-X-PLAYLIST-TYPE:VOD\n#EXTINF:{duration_seconds:.3},\n{vtt_url}\n#EXT-X-ENDLIST\n",
+        duration_seconds.ceil() as u64
+    )
+}
+
+fn render_master_playlist(tracks: &[LocalMediaSubtitleTrack], bandwidth: u64) -> String {
+    let mut playlist = String::from("#EXTM3U\n#EXT-X-VERSION:3\n");
+    let default_id = tracks
+        .iter()
+        .find(|track| track.default)
+        .map(|track| track.id.as_str());
+    for track in tracks {
+        let is_default = Some(track.id.as_str()) == default_id;
+        let mut line = format!(
+            "#EXT-X-MEDIA:TYPE=SUBTITLES,GROUP-ID=\"{SUBTITLE_GROUP_ID}\",NAME=\"{}\"",
+            quoted_attribute(&subtitle_label(track))
+        );
+        if let Some(language) = track.language.as_deref() {
+            line.push_str(&format!(",LANGUAGE=\"{}\"", quoted_attribute(language)));
+        }
+        line.push_str(&format!(
+            ",DEFAULT={},AUTOSELECT={},FORCED={},URI=\"{}\"\n",
+            yes_no(is_default),
+            yes_no(is_default || track.forced),
+            yes_no(track.forced),
+            subtitle_playlist_name(&track.id)
+        ));
+        playlist.push_str(&line);
+    }
+    playlist.push_str(&format!(
+        "#EXT-X-STREAM-INF:BANDWIDTH={bandwidth},SUBTITLES=\"{SUBTITLE_GROUP_ID}\"\nindex.m3u8\n"
+    ));
+    playlist
+}
+
+/// HLS quoted strings cannot contain double quotes or line breaks.
+fn quoted_attribute(value: &str) -> String {
+    value
+        .chars()
+        .map(|ch| match ch {
+            '"' => '\'',
+            '\r' | '\n' => ' ',
+            ch => ch,
+        })
+        .collect()
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "YES"
+    } else {
+        "NO"
+    }
+}
+
+pub async fn stream_or_generate_subtitle(
+    state: Arc<LocalMediaState>,
+    relative_path: &str,
+    track: &str,
+) -> Result<Response> {
+    let track = SubtitleTrackId::parse(track).context("invalid subtitle track id")?;
+    let media_path = resolve_media_path(state.root_dir(), relative_path)?;
+    let (input_path, input_relative_path, stream_index) = match track {
+        SubtitleTrackId::Embedded(index) => (media_path, relative_path.to_string(), index),
+        SubtitleTrackId::Sidecar(index) => {
+            let sidecar = discover_sidecar_subtitles(&media_path)
+                .await
+                .into_iter()
+                .nth(index)
+                .context("requested subtitle sidecar does not exist")?;
+            let sidecar_relative = match relative_path.rsplit_once('/') {
+                Some((parent, _)) => format!("{parent}/{}", sidecar.file_name),
+                None => sidecar.file_name.clone(),
+            };
+            (sidecar.path, sidecar_relative, 0)
+        },
+    };
+    let metadata = fs::metadata(&input_path)
+        .await
+        .with_context(|| format!("failed to stat {}", input_path.display()))?;
+    if !metadata.is_file() {
+        anyhow::bail!("requested subtitle source is not a file");
+    }
+
+    let modified_at_ms = source_modified_at_ms(&input_path).await?;
+    let cache_key = build_subtitle_cache_key(&SubtitleCacheKeyInput {
+        relative_path: &input_relative_path,
+        file_size: metadata.len(),
+        modified_at_ms,
+        stream_index,
+        profile: SUBTITLE_PROFILE,
+    });
+    let cache_paths = subtitle_cache_paths(state.cache_dir(), &cache_key);
+    state.touch_cache_entry(&cache_key);
+
+    if subtitle_ready(&cache_paths) {
+        return stream_vtt(&cache_paths.vtt).await;
+    }
+
+    let _permit = state
+        .subtitle_limiter()
+        .clone()
+        .acquire_owned()
+        .await
+        .context("failed to acquire subtitle conversion permit")?;
+
+    if subtitle_ready(&cache_paths) {
+        return stream_vtt(&cache_paths.vtt).await;
+    }
+
+    fs::create_dir_all(&cache_paths.dir)
+        .await
+        .with_context(|| format!("failed to create {}", cache_paths.dir.display()))?;
+    let _ = fs::remove_file(&cache_paths.error_marker).await;
+    let _ = fs::remove_file(&cache_paths.ready_marker).await;
+    let manifest = CacheManifest {
+        kind: LocalMediaCacheKind::Subtitle,
+        relative_path: input_relative_path,
+        file_size: metadata.len(),
+        modified_at_ms,
+    };
+    if let Err(err) = write_cache_manifest(&cache_paths.manifest, &manifest).await {
+        tracing::warn!(cache_key = %cache_key, error = %err, "failed to write cache manifest");
+    }
+
+    let bins = ensure_binary_paths(state.config()).await?;
+    let temp_output = cache_paths.dir.join("subtitle.vtt.part");
+    let mut command = build_subtitle_command(&bins, &input_path, stream_index, &temp_output);
+    let result = run_subtitle_command(&mut command, &temp_output, &cache_paths).await;
+    state.request_cache_sweep();
+    match result {
+        Ok(()) => stream_vtt(&cache_paths.vtt).await,
+        Err(err) => {
+            let _ = fs::write(&cache_paths.error_marker, err.to_string()).await;
+            Err(err)
+        },
+    }
+}
+
+async fn run_subtitle_command(
+    command: &mut tokio::process::Command,
+    temp_output: &Path,
+    cache_paths: &SubtitleCachePaths,
+) -> Result<()> {
+    let mut child = command
+        .spawn()
+        .context("failed to spawn ffmpeg for subtitle conversion")?;
+    let stderr_task = child.stderr.take().map(|mut stderr| {
+        tokio::spawn(async move {
+            let mut output = Vec::new();
+            let mut chunk = [0_u8; 4096];
+            loop {
+                let read = stderr
+                    .read(&mut chunk)
+                    .await
+                    .context("failed to read ffmpeg subtitle stderr")?;
+                if read == 0 {
+                    break;
+                }
+                output.extend_from_slice(&chunk[..read]);
+                if output.len() > SUBTITLE_STDERR_LIMIT {
+                    let overflow = output.len() - SUBTITLE_STDERR_LIMIT;
+                    output.drain(..overflow);
+                }
+            }
+            Ok::<String, anyhow::Error>(String::from_utf8_lossy(&output).into_owned())
+        })
+    });
+    let status = child
+        .wait()
+        .await
+        .context("failed to wait for ffmpeg subtitle conversion")?;
+    let stderr = match stderr_task {
+        Some(task) => task
+            .await
+            .context("failed to join ffmpeg subtitle stderr task")??,
+        None => String::new(),
+    };
+    if !status.success() {
+        let stderr = stderr.trim().to_string();
+        if stderr.is_empty() {
+            anyhow::bail!("ffmpeg subtitle conversion exited with status {}", status);
+        }
+        anyhow::bail!("ffmpeg subtitle conversion failed: {stderr}");
+    }
+
+    fs::rename(temp_output, &cache_paths.vtt)
+        .await
+        .with_context(|| format!("failed to finalize subtitle {}", cache_paths.vtt.display()))?;
+    fs::write(&cache_paths.ready_marker, b"ready")
+        .await
+        .with_context(|| format!("failed to write {}", cache_paths.ready_marker.display()))?;
+    Ok(())
+}
+
+fn subtitle_ready(paths: &SubtitleCachePaths) -> bool {
+    paths.vtt.exists() && paths.ready_marker.exists() && !paths.error_marker.exists()
+}
+
+async fn stream_vtt(path: &Path) -> Result<Response> {
+    let metadata = fs::metadata(path)
+        .await
+        .with_context(|| format!("failed to stat {}", path.display()))?;
+    let file = File::open(path)
+        .await
+        .with_context(|| format!("failed to open {}", path.display()))?;
+    let stream = ReaderStream::new(file);
+    Ok(Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "text/vtt; charset=utf-8")
+        .header(header::CONTENT_LENGTH, metadata.len().to_string())
+        .header(header::CACHE_CONTROL, "private, max-age=3600")
+        .body(Body::from_stream(stream))
+        .expect("valid vtt response"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::tempdir;
+
+    use super::{
+        discover_sidecar_subtitles, render_master_playlist, subtitle_tracks, SubtitleTrackId,
+    };
+    use crate::probe::{MediaProbe, SubtitleStream};
+
+    #[test]
+    fn subtitle_track_ids_round_trip() {
+        assert_eq!(SubtitleTrackId::parse("s2"), Some(SubtitleTrackId::Embedded(2)));
+        assert_eq!(SubtitleTrackId::parse("x0"), Some(SubtitleTrackId::Sidecar(0)));
+        assert_eq!(SubtitleTrackId::parse("a1"), None);
+        assert_eq!(SubtitleTrackId::parse("s"), None);
+        assert_eq!(SubtitleTrackId::parse("x-1"), None);
+        assert_eq!(SubtitleTrackId::Sidecar(3).as_string(), "x3");
+    }
+
+    #[tokio::test]
+    async fn discover_sidecar_subtitles_matches_stem_and_reads_language() {
+        let dir = tempdir().expect("tempdir");
+        for name in [
+            "Movie.mkv",
+            "Movie.srt",
+            "Movie.en.ass",
+            "Movie.zh.forced.SRT",
+            "Movie 2.srt",
+            "Other.srt",
+            "Movie.nfo",
+        ] {
+            fs::write(dir.path().join(name), b"").expect("write fixture");
+        }
+
+        let sidecars = discover_sidecar_subtitles(&dir.path().join("Movie.mkv")).await;
+        let summary = sidecars
+            .iter()
+            .map(|sidecar| {
+                (sidecar.file_name.as_str(), sidecar.codec.as_str(), sidecar.language.as_deref())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(summary, vec![
+            ("Movie.en.ass", "ass", Some("en")),
+            ("Movie.srt", "subrip", None),
+            ("Movie.zh.forced.SRT", "subrip", Some("zh")),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn subtitle_tracks_skip_bitmap_streams_and_append_sidecars() {
+        let dir = tempdir().expect("tempdir");
+        fs::write(dir.path().join("ep.mkv"), b"").expect("media");
+        fs::write(dir.path().join("ep.ja.srt"), b"").expect("sidecar");
+        let probe = MediaProbe {
+            subtitle_streams: vec![
+                SubtitleStream {
+                    index: 0,
+                    codec: Some("hdmv_pgs_subtitle".to_string()),
+                    language: Some("eng".to_string()),
+                    title: None,
+                    default: false,
+                    forced: false,
+                },
+                SubtitleStream {
+                    index: 1,
+                    codec: Some("subrip".to_string()),
+                    language: Some("chi".to_string()),
+                    title: Some("Simplified".to_string()),
+                    default: true,
+                    forced: false,
+                },
+            ],
+            ..MediaProbe::default()
+        };
+        let sidecars = discover_sidecar_subtitles(&dir.path().join("ep.mkv")).await;
+
+        let tracks = subtitle_tracks("show/ep.mkv", &probe, &sidecars);
+        let ids = tracks
+            .iter()
+            .map(|track| track.id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["s1", "x0"]);
+        assert!(tracks[1].external);
+        assert_eq!(tracks[1].language.as_deref(), Some("ja"));
+        assert_eq!(tracks[0].url, "/admin/local-media/api/subtitle?file=show%2Fep.mkv&track=s1");
+
+        let master = render_master_playlist(&tracks, 1_000);
+        assert!(master.contains(
+            "#EXT-X-MEDIA:TYPE=SUBTITLES,GROUP-ID=\"subs\",NAME=\"Simplified\",LANGUAGE=\"chi\",\
+             DEFAULT=YES,AUTOSELECT=YES,FORCED=NO,URI=\"subtitles_s1.m3u8\""
+        ));
+        assert!(master.contains("NAME=\"ep.ja.srt\",LANGUAGE=\"ja\",DEFAULT=NO"));
+        assert!(
+            master.ends_with("#EXT-X-STREAM-INF:BANDWIDTH=1000,SUBTITLES=\"subs\"\nindex.m3u8\n")
+        );
+    }
+}
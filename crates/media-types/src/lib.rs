@@ -15,7 +15,7 @@ pub enum LocalMediaEntryKind {
     Video,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct LocalMediaEntry {
     pub kind: LocalMediaEntryKind,
     pub name: String,
@@ -24,6 +24,31 @@ pub struct LocalMediaEntry {
     pub modified_at_ms: Option<i64>,
     pub extension: Option<String>,
     pub poster_url: Option<String>,
+    #[serde(default)]
+    pub progress: Option<LocalMediaPlaybackProgress>,
+}
+
+/// Watch state remembered per media file.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LocalMediaPlaybackProgress {
+    pub relative_path: String,
+    pub position_seconds: f64,
+    pub duration_seconds: Option<f64>,
+    pub completed: bool,
+    pub last_opened_at_ms: Option<i64>,
+    pub updated_at_ms: i64,
+}
+
+impl LocalMediaPlaybackProgress {
+    /// Positions closer to the start than this are not worth resuming.
+    pub const MIN_RESUME_SECONDS: f64 = 5.0;
+
+    /// Where playback should continue, `None` once finished or barely
+    /// started.
+    pub fn resume_seconds(&self) -> Option<f64> {
+        (!self.completed && self.position_seconds >= Self::MIN_RESUME_SECONDS)
+            .then_some(self.position_seconds)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -66,6 +91,13 @@ pub struct LocalMediaListQuery {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OpenPlaybackRequest {
     pub file: String,
+    /// Index among the source's audio streams; `None` keeps the default.
+    #[serde(default)]
+    pub audio_track: Option<usize>,
+    /// Source position to start from. Transcoded and remuxed playback
+    /// begins the artifact there; direct playback seeks client-side.
+    #[serde(default)]
+    pub start_seconds: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -93,6 +125,42 @@ pub struct PlaybackOpenResponse {
     pub duration_seconds: Option<f64>,
     pub detail: Option<String>,
     pub error: Option<String>,
+    #[serde(default)]
+    pub audio_tracks: Vec<LocalMediaAudioTrack>,
+    #[serde(default)]
+    pub selected_audio_track: Option<usize>,
+    #[serde(default)]
+    pub subtitle_tracks: Vec<LocalMediaSubtitleTrack>,
+    /// Source position of the stream's time zero when the artifact was cut
+    /// at a start offset; add it to player time when reporting progress.
+    #[serde(default)]
+    pub start_offset_seconds: Option<f64>,
+    /// Stream position the player should seek to once loaded.
+    #[serde(default)]
+    pub resume_seconds: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LocalMediaAudioTrack {
+    pub index: usize,
+    pub codec: Option<String>,
+    pub language: Option<String>,
+    pub title: Option<String>,
+    pub channels: Option<u32>,
+    pub default: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LocalMediaSubtitleTrack {
+    /// `s{n}` for embedded subtitle streams, `x{n}` for sidecar files.
+    pub id: String,
+    pub codec: Option<String>,
+    pub language: Option<String>,
+    pub title: Option<String>,
+    pub default: bool,
+    pub forced: bool,
+    pub external: bool,
+    pub url: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -116,6 +184,36 @@ pub struct PosterQuery {
     pub file: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdatePlaybackProgressRequest {
+    pub file: String,
+    /// Position in the source timeline, start offsets already applied.
+    pub position_seconds: f64,
+    #[serde(default)]
+    pub duration_seconds: Option<f64>,
+    /// Mark the file finished regardless of position, e.g. on `ended`.
+    #[serde(default)]
+    pub completed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlaybackHistoryQuery {
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlaybackHistoryResponse {
+    /// Most recently opened first.
+    pub entries: Vec<LocalMediaPlaybackProgress>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubtitleQuery {
+    pub file: String,
+    pub track: String,
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum UploadTaskStatus {
@@ -187,6 +285,45 @@ pub struct UploadChunkResponse {
     pub task: UploadTaskRecord,
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LocalMediaCacheKind {
+    Hls,
+    Mp4,
+    Poster,
+    Subtitle,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LocalMediaCacheEntryState {
+    Ready,
+    Preparing,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LocalMediaCacheEntry {
+    pub job_id: String,
+    pub kind: LocalMediaCacheKind,
+    pub state: LocalMediaCacheEntryState,
+    pub source_relative_path: Option<String>,
+    pub source_missing: bool,
+    pub active: bool,
+    pub size_bytes: u64,
+    pub file_count: u64,
+    pub last_accessed_at_ms: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LocalMediaCacheUsageResponse {
+    pub total_bytes: u64,
+    pub max_bytes: Option<u64>,
+    pub max_age_seconds: Option<u64>,
+    pub entries: Vec<LocalMediaCacheEntry>,
+}
+
 #[cfg(test)]
 mod upload_type_tests {
     use super::*;
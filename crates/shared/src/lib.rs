@@ -84,6 +84,45 @@ pub struct Article {
     pub source_url: Option<String>,
     #[serde(default)]
     pub interactive_page_id: Option<String>,
+    #[serde(default)]
+    pub series: Option<ArticleSeriesNav>,
+}
+
+/// Link to a neighbouring article inside the same series.
+#[allow(
+    missing_docs,
+    reason = "This DTO has two stable serialized fields whose meaning is evident from the \
+              type-level docs."
+)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArticleSeriesLink {
+    pub id: String,
+    pub title: String,
+}
+
+/// Series membership of one article plus previous/next navigation.
+///
+/// `position` is 1-based within the ordered series and `total` counts every
+/// article in it; both stay `0` when only the raw membership was loaded.
+#[allow(
+    missing_docs,
+    reason = "Fields mirror the serialized navigation payload; the type-level docs cover the \
+              non-obvious counters."
+)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArticleSeriesNav {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub order: Option<u32>,
+    #[serde(default)]
+    pub position: usize,
+    #[serde(default)]
+    pub total: usize,
+    #[serde(default)]
+    pub prev: Option<ArticleSeriesLink>,
+    #[serde(default)]
+    pub next: Option<ArticleSeriesLink>,
 }
 
 /// Article summary payload used for list and feed endpoints.
@@ -0,0 +1,1189 @@
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::{Context, Result};
+use arrow_array::{
+    builder::{Int32Builder, ListBuilder, StringBuilder, TimestampMillisecondBuilder},
+    new_null_array, Array, ArrayRef, Int32Array, ListArray, RecordBatch, RecordBatchIterator,
+    RecordBatchReader, StringArray, TimestampMillisecondArray,
+};
+use arrow_schema::{DataType, Field, Schema, TimeUnit};
+use chrono::Utc;
+use futures::TryStreamExt;
+use lancedb::{
+    connect,
+    query::{ExecutableQuery, QueryBase, Select},
+    Connection, Table,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::lance_schema_encoding::{compressed_utf8_field, low_cardinality_utf8_field};
+
+pub const ARTICLE_REVISIONS_TABLE: &str = "article_revisions";
+
+pub const REVISION_ORIGIN_BASELINE: &str = "baseline";
+pub const REVISION_ORIGIN_WRITE_ARTICLE: &str = "write_article";
+pub const REVISION_ORIGIN_SYNC_NOTES: &str = "sync_notes";
+pub const REVISION_ORIGIN_UPSERT_JSON: &str = "upsert_json";
+pub const REVISION_ORIGIN_UPDATE_BILINGUAL: &str = "update_bilingual";
+pub const REVISION_ORIGIN_ROLLBACK: &str = "rollback";
+
+/// Article columns captured in every revision snapshot. Vectors and
+/// timestamps are derived data and are intentionally left out.
+pub const ARTICLE_REVISION_TRACKED_FIELDS: &[&str] = &[
+    "title",
+    "content",
+    "content_en",
+    "summary",
+    "detailed_summary",
+    "tags",
+    "category",
+    "author",
+    "date",
+    "featured_image",
+    "read_time",
+    "article_kind",
+    "source_url",
+    "interactive_page_id",
+    "series_id",
+    "series_order",
+];
+
+/// Lines kept around each change when rendering a field diff.
+const DIFF_CONTEXT_LINES: usize = 3;
+const MAX_RECORD_REVISION_ATTEMPTS: usize = 5;
+/// Upper bound of the LCS table; larger inputs degrade to a whole-block
+/// replacement instead of burning memory on a quadratic diff.
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// Content-bearing article columns at one point in time.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ArticleRevisionSnapshot {
+    pub title: String,
+    pub content: String,
+    pub content_en: Option<String>,
+    pub summary: String,
+    pub detailed_summary: Option<String>,
+    pub tags: Vec<String>,
+    pub category: String,
+    pub author: String,
+    pub date: String,
+    pub featured_image: Option<String>,
+    pub read_time: i32,
+    pub article_kind: Option<String>,
+    pub source_url: Option<String>,
+    pub interactive_page_id: Option<String>,
+    // Skipped when unset so snapshots taken before series existed keep their
+    // source hash.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub series_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub series_order: Option<i32>,
+}
+
+impl ArticleRevisionSnapshot {
+    /// Stable SHA-256 over the serialized snapshot. Two snapshots with the
+    /// same hash carry identical article content.
+    pub fn source_hash(&self) -> String {
+        let encoded = serde_json::to_vec(self).unwrap_or_default();
+        let digest = Sha256::digest(&encoded);
+        digest.iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+
+    /// Names of tracked fields that differ from `previous`. A missing
+    /// previous snapshot reports every field as changed.
+    pub fn changed_fields(&self, previous: Option<&Self>) -> Vec<String> {
+        ARTICLE_REVISION_TRACKED_FIELDS
+            .iter()
+            .filter(|field| match previous {
+                Some(previous) => self.field_text(field) != previous.field_text(field),
+                None => true,
+            })
+            .map(|field| field.to_string())
+            .collect()
+    }
+
+    /// Text rendering of one tracked field, used for change detection and
+    /// line diffs. Tags are rendered one per line.
+    pub fn field_text(&self, field: &str) -> String {
+        match field {
+            "title" => self.title.clone(),
+            "content" => self.content.clone(),
+            "content_en" => self.content_en.clone().unwrap_or_default(),
+            "summary" => self.summary.clone(),
+            "detailed_summary" => self.detailed_summary.clone().unwrap_or_default(),
+            "tags" => self.tags.join("\n"),
+            "category" => self.category.clone(),
+            "author" => self.author.clone(),
+            "date" => self.date.clone(),
+            "featured_image" => self.featured_image.clone().unwrap_or_default(),
+            "read_time" => self.read_time.to_string(),
+            "article_kind" => self.article_kind.clone().unwrap_or_default(),
+            "source_url" => self.source_url.clone().unwrap_or_default(),
+            "interactive_page_id" => self.interactive_page_id.clone().unwrap_or_default(),
+            "series_id" => self.series_id.clone().unwrap_or_default(),
+            "series_order" => self
+                .series_order
+                .map(|order| order.to_string())
+                .unwrap_or_default(),
+            _ => String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewArticleRevisionInput {
+    pub article_id: String,
+    pub origin: String,
+    pub revision_author: String,
+    pub rollback_of: Option<i32>,
+    pub snapshot: ArticleRevisionSnapshot,
+}
+
+/// Listing view of a revision without the heavy snapshot payload.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ArticleRevisionSummary {
+    pub revision_id: String,
+    pub article_id: String,
+    pub revision_no: i32,
+    pub origin: String,
+    pub revision_author: String,
+    pub source_hash: String,
+    pub changed_fields: Vec<String>,
+    pub rollback_of: Option<i32>,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ArticleRevisionRecord {
+    #[serde(flatten)]
+    pub summary: ArticleRevisionSummary,
+    pub snapshot: ArticleRevisionSnapshot,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ArticleDiffOp {
+    Equal,
+    Insert,
+    Delete,
+    /// Collapsed run of unchanged lines; `text` holds the skipped count.
+    Skip,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ArticleDiffLine {
+    pub op: ArticleDiffOp,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ArticleFieldDiff {
+    pub field: String,
+    pub lines: Vec<ArticleDiffLine>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ArticleRevisionDiff {
+    pub article_id: String,
+    pub from_revision: i32,
+    pub to_revision: i32,
+    pub fields: Vec<ArticleFieldDiff>,
+}
+
+impl ArticleRevisionDiff {
+    /// Render the diff in a unified-diff-like text form for terminals.
+    pub fn to_unified_text(&self) -> String {
+        let mut out = format!(
+            "article `{}`: revision {} -> {}\n",
+            self.article_id, self.from_revision, self.to_revision
+        );
+        if self.fields.is_empty() {
+            out.push_str("(no changes)\n");
+            return out;
+        }
+        for field in &self.fields {
+            out.push_str(&format!("@@ {} @@\n", field.field));
+            for line in &field.lines {
+                match line.op {
+                    ArticleDiffOp::Equal => out.push_str(&format!(" {}\n", line.text)),
+                    ArticleDiffOp::Insert => out.push_str(&format!("+{}\n", line.text)),
+                    ArticleDiffOp::Delete => out.push_str(&format!("-{}\n", line.text)),
+                    ArticleDiffOp::Skip => {
+                        out.push_str(&format!("  ... {} unchanged lines ...\n", line.text))
+                    },
+                }
+            }
+        }
+        out
+    }
+}
+
+pub struct ArticleRevisionStore {
+    db: Connection,
+    revisions_table: String,
+}
+
+impl ArticleRevisionStore {
+    pub fn connection(&self) -> &Connection {
+        &self.db
+    }
+
+    pub async fn connect(db_uri: &str) -> Result<Self> {
+        let db = connect(db_uri)
+            .execute()
+            .await
+            .context("failed to connect article-revision LanceDB")?;
+        Self::from_connection(db).await
+    }
+
+    /// Reuse an already opened content DB connection (the CLI write paths
+    /// hold one for the `articles` table anyway).
+    pub async fn from_connection(db: Connection) -> Result<Self> {
+        let store = Self {
+            db,
+            revisions_table: ARTICLE_REVISIONS_TABLE.to_string(),
+        };
+        ensure_table(&store.db, &store.revisions_table, article_revisions_schema()).await?;
+        Ok(store)
+    }
+
+    async fn revisions_table(&self) -> Result<Table> {
+        self.db
+            .open_table(&self.revisions_table)
+            .execute()
+            .await
+            .with_context(|| format!("failed to open table `{}`", self.revisions_table))
+    }
+
+    /// Append a revision when the snapshot differs from the latest stored
+    /// one. Returns `None` when the content is unchanged.
+    ///
+    /// The revision number is read-then-written, so a concurrent writer (the
+    /// CLI and an admin rollback) may claim the same number first. The insert
+    /// only lands when its `revision_id` is new; otherwise the latest revision
+    /// is read again and the next number tried.
+    pub async fn record_revision(
+        &self,
+        input: NewArticleRevisionInput,
+    ) -> Result<Option<ArticleRevisionRecord>> {
+        let source_hash = input.snapshot.source_hash();
+        let table = self.revisions_table().await?;
+        for _ in 0..MAX_RECORD_REVISION_ATTEMPTS {
+            let latest = self.latest_revision(&input.article_id).await?;
+            if latest
+                .as_ref()
+                .is_some_and(|latest| latest.summary.source_hash == source_hash)
+            {
+                return Ok(None);
+            }
+
+            let revision_no = latest
+                .as_ref()
+                .map(|latest| latest.summary.revision_no + 1)
+                .unwrap_or(1);
+            let changed_fields = input
+                .snapshot
+                .changed_fields(latest.as_ref().map(|latest| &latest.snapshot));
+            let record = ArticleRevisionRecord {
+                summary: ArticleRevisionSummary {
+                    revision_id: revision_id(&input.article_id, revision_no),
+                    article_id: input.article_id.clone(),
+                    revision_no,
+                    origin: input.origin.clone(),
+                    revision_author: input.revision_author.clone(),
+                    source_hash: source_hash.clone(),
+                    changed_fields,
+                    rollback_of: input.rollback_of.clone(),
+                    created_at: Utc::now().timestamp_millis(),
+                },
+                snapshot: input.snapshot.clone(),
+            };
+            if insert_revision_record_if_absent(&table, &record).await? {
+                return Ok(Some(record));
+            }
+        }
+        anyhow::bail!(
+            "failed to record a revision for article `{}`: revision numbers kept being taken by \
+             concurrent writers",
+            input.article_id
+        )
+    }
+
+    /// Record the content an article had before this feature existed, so the
+    /// first tracked change still keeps the previous text around.
+    pub async fn ensure_baseline(
+        &self,
+        article_id: &str,
+        revision_author: &str,
+        snapshot: &ArticleRevisionSnapshot,
+    ) -> Result<()> {
+        if self.count_revisions(article_id).await? > 0 {
+            return Ok(());
+        }
+        self.record_revision(NewArticleRevisionInput {
+            article_id: article_id.to_string(),
+            origin: REVISION_ORIGIN_BASELINE.to_string(),
+            revision_author: revision_author.to_string(),
+            rollback_of: None,
+            snapshot: snapshot.clone(),
+        })
+        .await?;
+        Ok(())
+    }
+
+    pub async fn count_revisions(&self, article_id: &str) -> Result<usize> {
+        let table = self.revisions_table().await?;
+        let filter = format!("article_id = '{}'", escape_literal(article_id));
+        let total = table
+            .count_rows(Some(filter))
+            .await
+            .context("failed to count article revisions")?;
+        Ok(total)
+    }
+
+    /// Revisions of one article, newest first.
+    pub async fn list_revisions(
+        &self,
+        article_id: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<ArticleRevisionSummary>> {
+        let table = self.revisions_table().await?;
+        let filter = format!("article_id = '{}'", escape_literal(article_id));
+        let mut rows = query_revisions(&table, &filter, false).await?;
+        rows.sort_by(|left, right| right.summary.revision_no.cmp(&left.summary.revision_no));
+        if let Some(limit) = limit {
+            rows.truncate(limit);
+        }
+        Ok(rows.into_iter().map(|row| row.summary).collect())
+    }
+
+    pub async fn get_revision(
+        &self,
+        article_id: &str,
+        revision_no: i32,
+    ) -> Result<Option<ArticleRevisionRecord>> {
+        let table = self.revisions_table().await?;
+        let filter =
+            format!("revision_id = '{}'", escape_literal(&revision_id(article_id, revision_no)));
+        let rows = query_revisions(&table, &filter, true).await?;
+        Ok(rows.into_iter().next())
+    }
+
+    pub async fn latest_revision(&self, article_id: &str) -> Result<Option<ArticleRevisionRecord>> {
+        let Some(latest_no) = self
+            .list_revisions(article_id, Some(1))
+            .await?
+            .first()
+            .map(|summary| summary.revision_no)
+        else {
+            return Ok(None);
+        };
+        self.get_revision(article_id, latest_no).await
+    }
+
+    /// Diff two stored revisions. `to_revision = None` compares against the
+    /// latest revision.
+    pub async fn diff_revisions(
+        &self,
+        article_id: &str,
+        from_revision: i32,
+        to_revision: Option<i32>,
+    ) -> Result<ArticleRevisionDiff> {
+        let from = self
+            .get_revision(article_id, from_revision)
+            .await?
+            .with_context(|| format!("revision {from_revision} not found for `{article_id}`"))?;
+        let to = match to_revision {
+            Some(to_revision) => self
+                .get_revision(article_id, to_revision)
+                .await?
+                .with_context(|| format!("revision {to_revision} not found for `{article_id}`"))?,
+            None => self
+                .latest_revision(article_id)
+                .await?
+                .with_context(|| format!("no revisions recorded for `{article_id}`"))?,
+        };
+        Ok(diff_article_revisions(&from, &to))
+    }
+}
+
+/// Field-by-field line diff between two revisions of the same article.
+pub fn diff_article_revisions(
+    from: &ArticleRevisionRecord,
+    to: &ArticleRevisionRecord,
+) -> ArticleRevisionDiff {
+    let fields = ARTICLE_REVISION_TRACKED_FIELDS
+        .iter()
+        .filter_map(|field| {
+            let before = from.snapshot.field_text(field);
+            let after = to.snapshot.field_text(field);
+            (before != after).then(|| ArticleFieldDiff {
+                field: field.to_string(),
+                lines: collapse_unchanged(diff_lines(&before, &after)),
+            })
+        })
+        .collect();
+    ArticleRevisionDiff {
+        article_id: to.summary.article_id.clone(),
+        from_revision: from.summary.revision_no,
+        to_revision: to.summary.revision_no,
+        fields,
+    }
+}
+
+/// Load the tracked columns of the given article ids from the `articles`
+/// table, keyed by article id. Missing ids are simply absent.
+pub async fn fetch_article_revision_snapshots(
+    articles: &Table,
+    ids: &[String],
+) -> Result<HashMap<String, ArticleRevisionSnapshot>> {
+    if ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let id_list = ids
+        .iter()
+        .map(|id| format!("'{}'", escape_literal(id)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    // Tables created before series existed lack those columns; snapshot them
+    // as unset instead of failing the whole write.
+    let schema = articles.schema().await?;
+    let mut columns = vec!["id"];
+    columns.extend(
+        ARTICLE_REVISION_TRACKED_FIELDS
+            .iter()
+            .copied()
+            .filter(|field| schema.index_of(field).is_ok()),
+    );
+    let batches = articles
+        .query()
+        .only_if(format!("id IN ({id_list})"))
+        .select(Select::columns(columns.as_slice()))
+        .execute()
+        .await?
+        .try_collect::<Vec<_>>()
+        .await?;
+
+    let mut snapshots = HashMap::new();
+    for batch in &batches {
+        let id = string_col(batch, "id")?;
+        let title = string_col(batch, "title")?;
+        let content = string_col(batch, "content")?;
+        let content_en = string_col(batch, "content_en")?;
+        let summary = string_col(batch, "summary")?;
+        let detailed_summary = string_col(batch, "detailed_summary")?;
+        let tags = list_col(batch, "tags")?;
+        let category = string_col(batch, "category")?;
+        let author = string_col(batch, "author")?;
+        let date = string_col(batch, "date")?;
+        let featured_image = string_col(batch, "featured_image")?;
+        let read_time = int32_col(batch, "read_time")?;
+        let article_kind = string_col(batch, "article_kind")?;
+        let source_url = string_col(batch, "source_url")?;
+        let interactive_page_id = string_col(batch, "interactive_page_id")?;
+        let series_id = optional_string_col(batch, "series_id");
+        let series_order = optional_int32_col(batch, "series_order");
+
+        for row in 0..batch.num_rows() {
+            snapshots.insert(id.value(row).to_string(), ArticleRevisionSnapshot {
+                title: title.value(row).to_string(),
+                content: content.value(row).to_string(),
+                content_en: nullable_str(content_en, row),
+                summary: summary.value(row).to_string(),
+                detailed_summary: nullable_str(detailed_summary, row),
+                tags: string_list_value(tags, row),
+                category: category.value(row).to_string(),
+                author: author.value(row).to_string(),
+                date: date.value(row).to_string(),
+                featured_image: nullable_str(featured_image, row),
+                read_time: read_time.value(row),
+                article_kind: nullable_str(article_kind, row),
+                source_url: nullable_str(source_url, row),
+                interactive_page_id: nullable_str(interactive_page_id, row),
+                series_id: series_id.and_then(|column| nullable_str(column, row)),
+                series_order: series_order
+                    .filter(|column| !column.is_null(row))
+                    .map(|column| column.value(row)),
+            });
+        }
+    }
+    Ok(snapshots)
+}
+
+/// Overwrite the tracked columns of one article row with `snapshot`.
+///
+/// Vector columns are reset to NULL because they were embedded from the
+/// replaced text; `sf-cli db backfill-article-vectors` recomputes them.
+pub async fn restore_article_snapshot(
+    articles: &Table,
+    article_id: &str,
+    snapshot: &ArticleRevisionSnapshot,
+) -> Result<()> {
+    let batches = articles
+        .query()
+        .only_if(format!("id = '{}'", escape_literal(article_id)))
+        .limit(1)
+        .execute()
+        .await?
+        .try_collect::<Vec<_>>()
+        .await?;
+    let Some(current) = batches.into_iter().find(|batch| batch.num_rows() > 0) else {
+        anyhow::bail!("article not found: `{article_id}`");
+    };
+    let current = current.slice(0, 1);
+    let schema = current.schema();
+
+    let mut arrays: Vec<ArrayRef> = Vec::with_capacity(schema.fields().len());
+    for (idx, field) in schema.fields().iter().enumerate() {
+        let replaced: Option<ArrayRef> = match field.name().as_str() {
+            "title" => Some(utf8_value(Some(&snapshot.title))),
+            "content" => Some(utf8_value(Some(&snapshot.content))),
+            "content_en" => Some(utf8_value(snapshot.content_en.as_deref())),
+            "summary" => Some(utf8_value(Some(&snapshot.summary))),
+            "detailed_summary" => Some(utf8_value(snapshot.detailed_summary.as_deref())),
+            "tags" => {
+                let mut builder = ListBuilder::new(StringBuilder::new());
+                for tag in &snapshot.tags {
+                    builder.values().append_value(tag);
+                }
+                builder.append(true);
+                Some(Arc::new(builder.finish()))
+            },
+            "category" => Some(utf8_value(Some(&snapshot.category))),
+            "author" => Some(utf8_value(Some(&snapshot.author))),
+            "date" => Some(utf8_value(Some(&snapshot.date))),
+            "featured_image" => Some(utf8_value(snapshot.featured_image.as_deref())),
+            "read_time" => Some(Arc::new(Int32Array::from(vec![snapshot.read_time]))),
+            "article_kind" => Some(utf8_value(snapshot.article_kind.as_deref())),
+            "source_url" => Some(utf8_value(snapshot.source_url.as_deref())),
+            "interactive_page_id" => Some(utf8_value(snapshot.interactive_page_id.as_deref())),
+            "series_id" => Some(utf8_value(snapshot.series_id.as_deref())),
+            "series_order" => Some(Arc::new(Int32Array::from(vec![snapshot.series_order]))),
+            "vector_en" | "vector_zh" => Some(new_null_array(field.data_type(), 1)),
+            "updated_at" => {
+                Some(Arc::new(TimestampMillisecondArray::from(vec![Utc::now().timestamp_millis()])))
+            },
+            _ => None,
+        };
+        arrays.push(replaced.unwrap_or_else(|| current.column(idx).clone()));
+    }
+
+    let batch = RecordBatch::try_new(schema.clone(), arrays)
+        .context("failed to build restored article row")?;
+    let batches = RecordBatchIterator::new(vec![Ok(batch)].into_iter(), schema);
+    let mut merge = articles.merge_insert(&["id"]);
+    merge.when_matched_update_all(None);
+    merge.execute(Box::new(batches)).await?;
+    Ok(())
+}
+
+pub fn article_revisions_schema() -> Arc<Schema> {
+    Arc::new(Schema::new(vec![
+        Field::new("revision_id", DataType::Utf8, false),
+        Field::new("article_id", DataType::Utf8, false),
+        Field::new("revision_no", DataType::Int32, false),
+        low_cardinality_utf8_field("origin", false),
+        low_cardinality_utf8_field("revision_author", false),
+        Field::new("source_hash", DataType::Utf8, false),
+        Field::new("changed_fields", DataType::Utf8, false),
+        Field::new("rollback_of", DataType::Int32, true),
+        compressed_utf8_field("snapshot_json", false),
+        Field::new("created_at", DataType::Timestamp(TimeUnit::Millisecond, None), false),
+    ]))
+}
+
+fn revision_id(article_id: &str, revision_no: i32) -> String {
+    format!("{article_id}@{revision_no:06}")
+}
+
+fn build_revision_batch(record: &ArticleRevisionRecord) -> Result<RecordBatch> {
+    let summary = &record.summary;
+    let mut revision_id = StringBuilder::new();
+    let mut article_id = StringBuilder::new();
+    let mut revision_no = Int32Builder::new();
+    let mut origin = StringBuilder::new();
+    let mut revision_author = StringBuilder::new();
+    let mut source_hash = StringBuilder::new();
+    let mut changed_fields = StringBuilder::new();
+    let mut rollback_of = Int32Builder::new();
+    let mut snapshot_json = StringBuilder::new();
+    let mut created_at = TimestampMillisecondBuilder::new();
+
+    revision_id.append_value(&summary.revision_id);
+    article_id.append_value(&summary.article_id);
+    revision_no.append_value(summary.revision_no);
+    origin.append_value(&summary.origin);
+    revision_author.append_value(&summary.revision_author);
+    source_hash.append_value(&summary.source_hash);
+    changed_fields.append_value(summary.changed_fields.join(","));
+    rollback_of.append_option(summary.rollback_of);
+    snapshot_json.append_value(
+        serde_json::to_string(&record.snapshot).context("failed to encode revision snapshot")?,
+    );
+    created_at.append_value(summary.created_at);
+
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(revision_id.finish()),
+        Arc::new(article_id.finish()),
+        Arc::new(revision_no.finish()),
+        Arc::new(origin.finish()),
+        Arc::new(revision_author.finish()),
+        Arc::new(source_hash.finish()),
+        Arc::new(changed_fields.finish()),
+        Arc::new(rollback_of.finish()),
+        Arc::new(snapshot_json.finish()),
+        Arc::new(created_at.finish()),
+    ];
+    Ok(RecordBatch::try_new(article_revisions_schema(), columns)?)
+}
+
+/// Insert the revision unless its `revision_id` already exists. Returns
+/// whether the row was inserted.
+async fn insert_revision_record_if_absent(
+    table: &Table,
+    record: &ArticleRevisionRecord,
+) -> Result<bool> {
+    let batch = build_revision_batch(record)?;
+    let schema = batch.schema();
+    let batches = RecordBatchIterator::new(vec![Ok(batch)].into_iter(), schema);
+    let mut merge = table.merge_insert(&["revision_id"]);
+    merge.when_not_matched_insert_all();
+    let result = merge
+        .execute(Box::new(batches))
+        .await
+        .context("failed to insert article revision")?;
+    Ok(result.num_inserted_rows > 0)
+}
+
+async fn query_revisions(
+    table: &Table,
+    filter: &str,
+    with_snapshot: bool,
+) -> Result<Vec<ArticleRevisionRecord>> {
+    let mut columns = vec![
+        "revision_id",
+        "article_id",
+        "revision_no",
+        "origin",
+        "revision_author",
+        "source_hash",
+        "changed_fields",
+        "rollback_of",
+        "created_at",
+    ];
+    if with_snapshot {
+        columns.push("snapshot_json");
+    }
+    let batches = table
+        .query()
+        .only_if(filter)
+        .select(Select::columns(columns.as_slice()))
+        .execute()
+        .await?
+        .try_collect::<Vec<_>>()
+        .await?;
+
+    let mut rows = Vec::new();
+    for batch in batches {
+        let c_revision_id = string_col(&batch, "revision_id")?;
+        let c_article_id = string_col(&batch, "article_id")?;
+        let c_revision_no = int32_col(&batch, "revision_no")?;
+        let c_origin = string_col(&batch, "origin")?;
+        let c_revision_author = string_col(&batch, "revision_author")?;
+        let c_source_hash = string_col(&batch, "source_hash")?;
+        let c_changed_fields = string_col(&batch, "changed_fields")?;
+        let c_rollback_of = int32_col(&batch, "rollback_of")?;
+        let c_created_at = ts_col(&batch, "created_at")?;
+        let c_snapshot_json =
+            if with_snapshot { Some(string_col(&batch, "snapshot_json")?) } else { None };
+
+        for i in 0..batch.num_rows() {
+            let snapshot = match c_snapshot_json {
+                Some(column) => serde_json::from_str(column.value(i)).with_context(|| {
+                    format!("invalid snapshot_json for revision `{}`", c_revision_id.value(i))
+                })?,
+                None => ArticleRevisionSnapshot::default(),
+            };
+            rows.push(ArticleRevisionRecord {
+                summary: ArticleRevisionSummary {
+                    revision_id: c_revision_id.value(i).to_string(),
+                    article_id: c_article_id.value(i).to_string(),
+                    revision_no: c_revision_no.value(i),
+                    origin: c_origin.value(i).to_string(),
+                    revision_author: c_revision_author.value(i).to_string(),
+                    source_hash: c_source_hash.value(i).to_string(),
+                    changed_fields: c_changed_fields
+                        .value(i)
+                        .split(',')
+                        .filter(|field| !field.is_empty())
+                        .map(str::to_string)
+                        .collect(),
+                    rollback_of: (!c_rollback_of.is_null(i)).then(|| c_rollback_of.value(i)),
+                    created_at: c_created_at.value(i),
+                },
+                snapshot,
+            });
+        }
+    }
+    Ok(rows)
+}
+
+/// Line-level LCS diff. Common prefix/suffix are stripped first so typical
+/// small edits stay cheap even on long articles.
+fn diff_lines(before: &str, after: &str) -> Vec<ArticleDiffLine> {
+    let old = before.lines().collect::<Vec<_>>();
+    let new = after.lines().collect::<Vec<_>>();
+
+    let prefix = old
+        .iter()
+        .zip(new.iter())
+        .take_while(|(left, right)| left == right)
+        .count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(left, right)| left == right)
+        .count();
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    let mut out = old[..prefix]
+        .iter()
+        .map(|line| diff_line(ArticleDiffOp::Equal, line))
+        .collect::<Vec<_>>();
+
+    if old_mid.len().saturating_mul(new_mid.len()) > MAX_DIFF_CELLS {
+        out.extend(
+            old_mid
+                .iter()
+                .map(|line| diff_line(ArticleDiffOp::Delete, line)),
+        );
+        out.extend(
+            new_mid
+                .iter()
+                .map(|line| diff_line(ArticleDiffOp::Insert, line)),
+        );
+    } else {
+        let (n, m) = (old_mid.len(), new_mid.len());
+        let mut lcs = vec![0u32; (n + 1) * (m + 1)];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                lcs[i * (m + 1) + j] = if old_mid[i] == new_mid[j] {
+                    lcs[(i + 1) * (m + 1) + j + 1] + 1
+                } else {
+                    lcs[(i + 1) * (m + 1) + j].max(lcs[i * (m + 1) + j + 1])
+                };
+            }
+        }
+        let (mut i, mut j) = (0, 0);
+        while i < n && j < m {
+            if old_mid[i] == new_mid[j] {
+                out.push(diff_line(ArticleDiffOp::Equal, old_mid[i]));
+                i += 1;
+                j += 1;
+            } else if lcs[(i + 1) * (m + 1) + j] >= lcs[i * (m + 1) + j + 1] {
+                out.push(diff_line(ArticleDiffOp::Delete, old_mid[i]));
+                i += 1;
+            } else {
+                out.push(diff_line(ArticleDiffOp::Insert, new_mid[j]));
+                j += 1;
+            }
+        }
+        out.extend(
+            old_mid[i..]
+                .iter()
+                .map(|line| diff_line(ArticleDiffOp::Delete, line)),
+        );
+        out.extend(
+            new_mid[j..]
+                .iter()
+                .map(|line| diff_line(ArticleDiffOp::Insert, line)),
+        );
+    }
+
+    out.extend(
+        old[old.len() - suffix..]
+            .iter()
+            .map(|line| diff_line(ArticleDiffOp::Equal, line)),
+    );
+    out
+}
+
+/// Keep [`DIFF_CONTEXT_LINES`] unchanged lines around each change and fold
+/// the rest into `Skip` markers.
+fn collapse_unchanged(lines: Vec<ArticleDiffLine>) -> Vec<ArticleDiffLine> {
+    let changed = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.op != ArticleDiffOp::Equal)
+        .map(|(idx, _)| idx)
+        .collect::<Vec<_>>();
+    let keep = |idx: usize| {
+        changed
+            .iter()
+            .any(|changed_idx| idx.abs_diff(*changed_idx) <= DIFF_CONTEXT_LINES)
+    };
+
+    let mut out = Vec::new();
+    let mut skipped = 0usize;
+    for (idx, line) in lines.into_iter().enumerate() {
+        if line.op != ArticleDiffOp::Equal || keep(idx) {
+            if skipped > 0 {
+                out.push(ArticleDiffLine {
+                    op: ArticleDiffOp::Skip,
+                    text: skipped.to_string(),
+                });
+                skipped = 0;
+            }
+            out.push(line);
+        } else {
+            skipped += 1;
+        }
+    }
+    if skipped > 0 {
+        out.push(ArticleDiffLine {
+            op: ArticleDiffOp::Skip,
+            text: skipped.to_string(),
+        });
+    }
+    out
+}
+
+fn diff_line(op: ArticleDiffOp, text: &str) -> ArticleDiffLine {
+    ArticleDiffLine {
+        op,
+        text: text.to_string(),
+    }
+}
+
+async fn ensure_table(db: &Connection, name: &str, schema: Arc<Schema>) -> Result<Table> {
+    match db.open_table(name).execute().await {
+        Ok(t) => Ok(t),
+        Err(_) => {
+            let batch = RecordBatch::new_empty(schema.clone());
+            let batches = RecordBatchIterator::new(vec![Ok(batch)].into_iter(), schema.clone());
+            db.create_table(name, Box::new(batches) as Box<dyn RecordBatchReader + Send>)
+                .storage_option("new_table_enable_stable_row_ids", "true")
+                .storage_option("new_table_enable_v2_manifest_paths", "true")
+                .execute()
+                .await
+                .with_context(|| format!("failed to create table {name}"))?;
+            db.open_table(name)
+                .execute()
+                .await
+                .with_context(|| format!("failed to open table {name}"))
+        },
+    }
+}
+
+fn utf8_value(value: Option<&str>) -> ArrayRef {
+    Arc::new(StringArray::from(vec![value]))
+}
+
+fn escape_literal(s: &str) -> String {
+    s.replace('\'', "''")
+}
+
+fn string_col<'a>(batch: &'a RecordBatch, name: &str) -> Result<&'a StringArray> {
+    batch
+        .column_by_name(name)
+        .with_context(|| format!("missing column: {name}"))?
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .with_context(|| format!("column {name} is not Utf8"))
+}
+
+fn int32_col<'a>(batch: &'a RecordBatch, name: &str) -> Result<&'a Int32Array> {
+    batch
+        .column_by_name(name)
+        .with_context(|| format!("missing column: {name}"))?
+        .as_any()
+        .downcast_ref::<Int32Array>()
+        .with_context(|| format!("column {name} is not Int32"))
+}
+
+fn optional_string_col<'a>(batch: &'a RecordBatch, name: &str) -> Option<&'a StringArray> {
+    batch
+        .column_by_name(name)
+        .and_then(|column| column.as_any().downcast_ref::<StringArray>())
+}
+
+fn optional_int32_col<'a>(batch: &'a RecordBatch, name: &str) -> Option<&'a Int32Array> {
+    batch
+        .column_by_name(name)
+        .and_then(|column| column.as_any().downcast_ref::<Int32Array>())
+}
+
+fn list_col<'a>(batch: &'a RecordBatch, name: &str) -> Result<&'a ListArray> {
+    batch
+        .column_by_name(name)
+        .with_context(|| format!("missing column: {name}"))?
+        .as_any()
+        .downcast_ref::<ListArray>()
+        .with_context(|| format!("column {name} is not List"))
+}
+
+fn ts_col<'a>(batch: &'a RecordBatch, name: &str) -> Result<&'a TimestampMillisecondArray> {
+    batch
+        .column_by_name(name)
+        .with_context(|| format!("missing column: {name}"))?
+        .as_any()
+        .downcast_ref::<TimestampMillisecondArray>()
+        .with_context(|| format!("column {name} is not Timestamp"))
+}
+
+fn nullable_str(arr: &StringArray, i: usize) -> Option<String> {
+    if arr.is_null(i) {
+        None
+    } else {
+        Some(arr.value(i).to_string())
+    }
+}
+
+fn string_list_value(arr: &ListArray, i: usize) -> Vec<String> {
+    if arr.is_null(i) {
+        return vec![];
+    }
+    let values = arr.value(i);
+    let Some(values) = values.as_any().downcast_ref::<StringArray>() else {
+        return vec![];
+    };
+    (0..values.len())
+        .filter(|idx| !values.is_null(*idx))
+        .map(|idx| values.value(idx).to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use anyhow::{Context, Result};
+
+    use super::*;
+
+    fn snapshot(content: &str) -> ArticleRevisionSnapshot {
+        ArticleRevisionSnapshot {
+            title: "Title".to_string(),
+            content: content.to_string(),
+            summary: "Summary".to_string(),
+            tags: vec!["rust".to_string()],
+            category: "Notes".to_string(),
+            author: "ackingliu".to_string(),
+            date: "2026-01-01".to_string(),
+            read_time: 3,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn changed_fields_reports_only_differences() {
+        let before = snapshot("a\nb");
+        let mut after = snapshot("a\nc");
+        after.tags.push("lancedb".to_string());
+
+        assert_eq!(after.changed_fields(Some(&before)), vec!["content", "tags"]);
+        assert_eq!(after.changed_fields(None).len(), ARTICLE_REVISION_TRACKED_FIELDS.len());
+        assert_ne!(before.source_hash(), after.source_hash());
+        assert_eq!(before.source_hash(), snapshot("a\nb").source_hash());
+    }
+
+    #[test]
+    fn series_fields_are_tracked_without_changing_legacy_hashes() {
+        let before = snapshot("body");
+        let mut after = before.clone();
+        after.series_id = Some("rust-deep-dive".to_string());
+        after.series_order = Some(2);
+
+        assert_eq!(after.changed_fields(Some(&before)), vec!["series_id", "series_order"]);
+        assert_ne!(before.source_hash(), after.source_hash());
+        let encoded = serde_json::to_string(&before).expect("encode snapshot");
+        assert!(!encoded.contains("series"));
+    }
+
+    #[test]
+    fn diff_lines_marks_inserts_and_deletes() {
+        let lines = diff_lines("one\ntwo\nthree", "one\nTWO\nthree\nfour");
+        let ops = lines.iter().map(|line| line.op).collect::<Vec<_>>();
+        assert_eq!(ops, vec![
+            ArticleDiffOp::Equal,
+            ArticleDiffOp::Delete,
+            ArticleDiffOp::Insert,
+            ArticleDiffOp::Equal,
+            ArticleDiffOp::Insert,
+        ]);
+    }
+
+    #[test]
+    fn collapse_unchanged_folds_distant_context() {
+        let before = (0..20).map(|i| format!("line {i}")).collect::<Vec<_>>();
+        let mut after = before.clone();
+        after[10] = "changed".to_string();
+        let lines = collapse_unchanged(diff_lines(&before.join("\n"), &after.join("\n")));
+
+        assert_eq!(lines.first().map(|line| line.op), Some(ArticleDiffOp::Skip));
+        assert_eq!(lines.first().map(|line| line.text.as_str()), Some("7"));
+        assert_eq!(lines.last().map(|line| line.op), Some(ArticleDiffOp::Skip));
+        assert_eq!(
+            lines
+                .iter()
+                .filter(|line| line.op == ArticleDiffOp::Equal)
+                .count(),
+            2 * DIFF_CONTEXT_LINES
+        );
+    }
+
+    async fn single_article_table(
+        db_path: &std::path::Path,
+        snapshot: &ArticleRevisionSnapshot,
+    ) -> Result<Table> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Utf8, false),
+            Field::new("title", DataType::Utf8, false),
+            Field::new("content", DataType::Utf8, false),
+            Field::new("content_en", DataType::Utf8, true),
+            Field::new("summary", DataType::Utf8, false),
+            Field::new("detailed_summary", DataType::Utf8, true),
+            Field::new(
+                "tags",
+                DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))),
+                true,
+            ),
+            Field::new("category", DataType::Utf8, false),
+            Field::new("author", DataType::Utf8, false),
+            Field::new("date", DataType::Utf8, false),
+            Field::new("featured_image", DataType::Utf8, true),
+            Field::new("read_time", DataType::Int32, false),
+            Field::new("article_kind", DataType::Utf8, true),
+            Field::new("source_url", DataType::Utf8, true),
+            Field::new("interactive_page_id", DataType::Utf8, true),
+            Field::new("series_id", DataType::Utf8, true),
+            Field::new("series_order", DataType::Int32, true),
+        ]));
+        let mut tags = ListBuilder::new(StringBuilder::new());
+        for tag in &snapshot.tags {
+            tags.values().append_value(tag);
+        }
+        tags.append(true);
+        let batch = RecordBatch::try_new(schema.clone(), vec![
+            utf8_value(Some("post-001")),
+            utf8_value(Some(&snapshot.title)),
+            utf8_value(Some(&snapshot.content)),
+            utf8_value(snapshot.content_en.as_deref()),
+            utf8_value(Some(&snapshot.summary)),
+            utf8_value(snapshot.detailed_summary.as_deref()),
+            Arc::new(tags.finish()),
+            utf8_value(Some(&snapshot.category)),
+            utf8_value(Some(&snapshot.author)),
+            utf8_value(Some(&snapshot.date)),
+            utf8_value(snapshot.featured_image.as_deref()),
+            Arc::new(Int32Array::from(vec![snapshot.read_time])),
+            utf8_value(snapshot.article_kind.as_deref()),
+            utf8_value(snapshot.source_url.as_deref()),
+            utf8_value(snapshot.interactive_page_id.as_deref()),
+            utf8_value(snapshot.series_id.as_deref()),
+            Arc::new(Int32Array::from(vec![snapshot.series_order])),
+        ])?;
+        let batches = RecordBatchIterator::new(vec![Ok(batch)].into_iter(), schema);
+        Ok(connect(&db_path.display().to_string())
+            .execute()
+            .await?
+            .create_table("articles", Box::new(batches) as Box<dyn RecordBatchReader + Send>)
+            .execute()
+            .await?)
+    }
+
+    #[tokio::test]
+    async fn rollback_restores_series_membership() -> Result<()> {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .context("failed to get system time")?
+            .as_nanos();
+        let db_path = std::env::temp_dir().join(format!("sf-article-revision-series-{unique}"));
+        tokio::fs::create_dir_all(&db_path)
+            .await
+            .with_context(|| format!("failed to create {}", db_path.display()))?;
+        let mut in_series = snapshot("body");
+        in_series.series_id = Some("rust-deep-dive".to_string());
+        in_series.series_order = Some(2);
+        let table = single_article_table(&db_path, &in_series).await?;
+        let ids = vec!["post-001".to_string()];
+
+        let fetched = fetch_article_revision_snapshots(&table, &ids).await?;
+        assert_eq!(fetched.get("post-001"), Some(&in_series));
+
+        let detached = snapshot("body");
+        restore_article_snapshot(&table, "post-001", &detached).await?;
+        let fetched = fetch_article_revision_snapshots(&table, &ids).await?;
+        assert_eq!(fetched.get("post-001"), Some(&detached));
+
+        restore_article_snapshot(&table, "post-001", &in_series).await?;
+        let fetched = fetch_article_revision_snapshots(&table, &ids).await?;
+        assert_eq!(fetched.get("post-001"), Some(&in_series));
+
+        let _ = tokio::fs::remove_dir_all(&db_path).await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn record_revision_skips_unchanged_content_and_diffs_history() -> Result<()> {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .context("failed to get system time")?
+            .as_nanos();
+        let db_path = std::env::temp_dir().join(format!("sf-article-revisions-{unique}"));
+        tokio::fs::create_dir_all(&db_path)
+            .await
+            .with_context(|| format!("failed to create {}", db_path.display()))?;
+        let store = ArticleRevisionStore::connect(&db_path.display().to_string()).await?;
+
+        let input = |content: &str| NewArticleRevisionInput {
+            article_id: "post-001".to_string(),
+            origin: REVISION_ORIGIN_WRITE_ARTICLE.to_string(),
+            revision_author: "tester".to_string(),
+            rollback_of: None,
+            snapshot: snapshot(content),
+        };
+        let first = store
+            .record_revision(input("hello\nworld"))
+            .await?
+            .context("first revision must be recorded")?;
+        assert_eq!(first.summary.revision_no, 1);
+        assert!(store
+            .record_revision(input("hello\nworld"))
+            .await?
+            .is_none());
+
+        let second = store
+            .record_revision(input("hello\nthere"))
+            .await?
+            .context("changed content must be recorded")?;
+        assert_eq!(second.summary.revision_no, 2);
+        assert_eq!(second.summary.changed_fields, vec!["content"]);
+
+        let history = store.list_revisions("post-001", None).await?;
+        assert_eq!(
+            history
+                .iter()
+                .map(|summary| summary.revision_no)
+                .collect::<Vec<_>>(),
+            vec![2, 1]
+        );
+
+        let table = store.revisions_table().await?;
+        let mut racing = second.clone();
+        racing.snapshot = snapshot("hello\nrace");
+        assert!(!insert_revision_record_if_absent(&table, &racing).await?);
+        assert_eq!(store.count_revisions("post-001").await?, 2);
+
+        let diff = store.diff_revisions("post-001", 1, None).await?;
+        assert_eq!(diff.to_revision, 2);
+        assert_eq!(diff.fields.len(), 1);
+        assert!(diff.to_unified_text().contains("+there"));
+
+        let _ = tokio::fs::remove_dir_all(&db_path).await;
+        Ok(())
+    }
+}
@@ -0,0 +1,815 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use chrono::NaiveDate;
+use lancedb::index::scalar::{
+    BooleanQuery, FtsIndexBuilder, FtsQuery, FullTextSearchQuery, MatchQuery, Occur, Operator,
+};
+use serde::{Deserialize, Serialize};
+use static_flow_shared::ArticleKind;
+
+/// How an article column is tokenized for its FTS index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArticleFtsTokenizer {
+    /// Lower-cased character bigrams. Works for CJK text without a
+    /// segmentation dictionary and turns keyword matches into substring
+    /// matches, which also covers prefix queries.
+    Ngram,
+    /// Whitespace/punctuation words with English stemming and stop words.
+    English,
+}
+
+/// One searchable article column and its ranking weight.
+#[derive(Debug, Clone, Copy)]
+pub struct ArticleFtsField {
+    pub column: &'static str,
+    pub boost: f32,
+    pub tokenizer: ArticleFtsTokenizer,
+}
+
+/// Searchable columns in descending weight. Title hits dominate, tags and
+/// the summary outrank body matches, and the English translation competes
+/// with the original body on equal terms.
+pub const ARTICLE_FTS_FIELDS: &[ArticleFtsField] = &[
+    ArticleFtsField {
+        column: "title",
+        boost: 4.0,
+        tokenizer: ArticleFtsTokenizer::Ngram,
+    },
+    ArticleFtsField {
+        column: "tags",
+        boost: 3.0,
+        tokenizer: ArticleFtsTokenizer::Ngram,
+    },
+    ArticleFtsField {
+        column: "summary",
+        boost: 2.0,
+        tokenizer: ArticleFtsTokenizer::Ngram,
+    },
+    ArticleFtsField {
+        column: "content",
+        boost: 1.0,
+        tokenizer: ArticleFtsTokenizer::Ngram,
+    },
+    ArticleFtsField {
+        column: "content_en",
+        boost: 1.0,
+        tokenizer: ArticleFtsTokenizer::English,
+    },
+];
+
+impl ArticleFtsField {
+    /// Index name that marks an index as built with these parameters, so
+    /// legacy default-tokenizer indexes on the same column get replaced.
+    pub fn index_name(&self) -> String {
+        format!("{}_fts_v2", self.column)
+    }
+
+    pub fn index_params(&self) -> FtsIndexBuilder {
+        let params = FtsIndexBuilder::default()
+            .with_position(true)
+            .lower_case(true)
+            .ascii_folding(true);
+        match self.tokenizer {
+            ArticleFtsTokenizer::Ngram => params
+                .base_tokenizer("ngram".to_string())
+                .ngram_min_length(2)
+                .ngram_max_length(2)
+                .ngram_prefix_only(false)
+                .stem(false)
+                .remove_stop_words(false),
+            ArticleFtsTokenizer::English => params
+                .base_tokenizer("simple".to_string())
+                .stem(true)
+                .remove_stop_words(true),
+        }
+    }
+}
+
+/// A keyword query split into its syntax parts:
+/// `"exact phrase"`, `prefix*`, `-excluded` / `-"excluded phrase"`, and
+/// plain terms.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ArticleSearchQuery {
+    pub terms: Vec<String>,
+    pub phrases: Vec<String>,
+    pub prefixes: Vec<String>,
+    pub excluded: Vec<String>,
+}
+
+/// Searchable text of one article, borrowed from a result row.
+#[derive(Debug, Clone, Copy)]
+pub struct ArticleSearchDocument<'a> {
+    pub title: &'a str,
+    pub summary: &'a str,
+    pub content: &'a str,
+    pub content_en: Option<&'a str>,
+    pub tags: &'a [String],
+}
+
+/// Why an article matched: its weighted score, the strongest field and the
+/// query part to highlight there.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArticleSearchMatch {
+    pub score: f32,
+    pub column: &'static str,
+    pub needle: String,
+}
+
+impl ArticleSearchQuery {
+    pub fn parse(input: &str) -> Self {
+        let mut query = Self::default();
+        let mut chars = input.chars().peekable();
+        while let Some(&ch) = chars.peek() {
+            if ch.is_whitespace() {
+                chars.next();
+                continue;
+            }
+            let negated = ch == '-';
+            if negated {
+                chars.next();
+            }
+            if chars.peek() == Some(&'"') {
+                chars.next();
+                let phrase = chars
+                    .by_ref()
+                    .take_while(|value| *value != '"')
+                    .collect::<String>();
+                let phrase = phrase.split_whitespace().collect::<Vec<_>>().join(" ");
+                if phrase.is_empty() {
+                    continue;
+                }
+                if negated {
+                    query.excluded.push(phrase);
+                } else {
+                    query.phrases.push(phrase);
+                }
+                continue;
+            }
+            let mut word = String::new();
+            while let Some(&value) = chars.peek() {
+                if value.is_whitespace() {
+                    break;
+                }
+                word.push(value);
+                chars.next();
+            }
+            if negated {
+                if !word.is_empty() {
+                    query.excluded.push(word);
+                }
+            } else if let Some(prefix) = word.strip_suffix('*') {
+                let prefix = prefix.trim_end_matches('*');
+                if !prefix.is_empty() {
+                    query.prefixes.push(prefix.to_string());
+                }
+            } else if !word.is_empty() {
+                query.terms.push(word);
+            }
+        }
+        query
+    }
+
+    /// Whether anything positive is left to search for.
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty() && self.phrases.is_empty() && self.prefixes.is_empty()
+    }
+
+    fn positive_needles(&self) -> impl Iterator<Item = &str> {
+        self.phrases
+            .iter()
+            .chain(&self.terms)
+            .chain(&self.prefixes)
+            .map(String::as_str)
+    }
+
+    /// Candidate query over the indexed fields: every positive part is a
+    /// boosted per-field match, exclusions are `MUST_NOT` clauses. Phrase
+    /// and prefix semantics are enforced afterwards by [`Self::score`],
+    /// because bigram tokens cannot express them exactly.
+    pub fn fts_query(&self, fields: &[&ArticleFtsField]) -> Option<FullTextSearchQuery> {
+        if self.is_empty() || fields.is_empty() {
+            return None;
+        }
+        let mut clauses = Vec::new();
+        for field in fields {
+            for needle in self.positive_needles() {
+                clauses.push((Occur::Should, field_match(field, needle)));
+            }
+            for needle in &self.excluded {
+                clauses.push((Occur::MustNot, field_match(field, needle)));
+            }
+        }
+        Some(FullTextSearchQuery::new_query(FtsQuery::Boolean(BooleanQuery::new(clauses))))
+    }
+
+    /// Weighted match of `doc` against the query, `None` when an exclusion
+    /// hits, a phrase is missing, or nothing positive matches.
+    pub fn score(&self, doc: &ArticleSearchDocument<'_>) -> Option<ArticleSearchMatch> {
+        let fields = ARTICLE_FTS_FIELDS
+            .iter()
+            .map(|field| (field, doc.text(field.column).to_lowercase()))
+            .collect::<Vec<_>>();
+
+        if self.excluded.iter().any(|needle| {
+            let needle = needle.to_lowercase();
+            fields.iter().any(|(_, text)| text.contains(&needle))
+        }) {
+            return None;
+        }
+        if !self.phrases.iter().all(|phrase| {
+            let phrase = phrase.to_lowercase();
+            fields.iter().any(|(_, text)| text.contains(&phrase))
+        }) {
+            return None;
+        }
+
+        let mut total = 0.0;
+        let mut best: Option<ArticleSearchMatch> = None;
+        for (field, text) in &fields {
+            let mut field_score = 0.0;
+            let mut first_needle = None;
+            for (needle, is_prefix) in self
+                .phrases
+                .iter()
+                .chain(&self.terms)
+                .map(|needle| (needle, false))
+                .chain(self.prefixes.iter().map(|needle| (needle, true)))
+            {
+                let lowered = needle.to_lowercase();
+                let hit = if is_prefix {
+                    contains_word_prefix(text, &lowered)
+                } else {
+                    text.contains(&lowered)
+                };
+                if hit {
+                    field_score += field.boost;
+                    first_needle.get_or_insert(needle);
+                }
+            }
+            total += field_score;
+            if let Some(needle) = first_needle {
+                if best
+                    .as_ref()
+                    .is_none_or(|current| field_score > current.score)
+                {
+                    best = Some(ArticleSearchMatch {
+                        score: field_score,
+                        column: field.column,
+                        needle: needle.clone(),
+                    });
+                }
+            }
+        }
+        best.map(|best| ArticleSearchMatch {
+            score: total,
+            ..best
+        })
+    }
+}
+
+impl ArticleSearchDocument<'_> {
+    pub fn text(&self, column: &str) -> String {
+        match column {
+            "title" => self.title.to_string(),
+            "summary" => self.summary.to_string(),
+            "content" => self.content.to_string(),
+            "content_en" => self.content_en.unwrap_or_default().to_string(),
+            "tags" => self.tags.join(" "),
+            _ => String::new(),
+        }
+    }
+}
+
+fn field_match(field: &ArticleFtsField, needle: &str) -> FtsQuery {
+    FtsQuery::Match(
+        MatchQuery::new(needle.to_string())
+            .with_column(Some(field.column.to_string()))
+            .with_boost(field.boost)
+            .with_operator(Operator::And),
+    )
+}
+
+/// Whether some word in `text` starts with `prefix`. CJK prefixes match
+/// anywhere since those scripts have no word boundaries.
+fn contains_word_prefix(text: &str, prefix: &str) -> bool {
+    text.match_indices(prefix).any(|(start, _)| {
+        start == 0
+            || prefix
+                .chars()
+                .next()
+                .is_some_and(|ch| !ch.is_alphanumeric() || !ch.is_ascii())
+            || text[..start]
+                .chars()
+                .next_back()
+                .is_none_or(|ch| !ch.is_alphanumeric())
+    })
+}
+
+/// Language an article can be read in. Every article carries a Chinese
+/// body; English availability depends on `content_en`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArticleSearchLanguage {
+    Zh,
+    En,
+}
+
+/// Structured narrowing for keyword and semantic search. Pushed down to
+/// LanceDB as one `only_if` predicate so FTS, vector and scan paths all see
+/// the same candidate set.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ArticleSearchFilters {
+    /// Every listed tag must be present (case-insensitive).
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub article_kind: Option<ArticleKind>,
+    #[serde(default)]
+    pub language: Option<ArticleSearchLanguage>,
+    /// Inclusive `YYYY-MM-DD` bounds on the article date.
+    #[serde(default)]
+    pub date_from: Option<String>,
+    #[serde(default)]
+    pub date_to: Option<String>,
+}
+
+impl ArticleSearchFilters {
+    /// Reject malformed or inverted date bounds before they reach a query.
+    pub fn validate(&self) -> Result<()> {
+        let parse = |label: &str, value: &Option<String>| -> Result<Option<NaiveDate>> {
+            non_blank(value)
+                .map(|value| {
+                    NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| {
+                        anyhow::anyhow!("{label} must be a YYYY-MM-DD date, got `{value}`")
+                    })
+                })
+                .transpose()
+        };
+        let from = parse("date_from", &self.date_from)?;
+        let to = parse("date_to", &self.date_to)?;
+        if let (Some(from), Some(to)) = (from, to) {
+            anyhow::ensure!(from <= to, "date_from must not be after date_to");
+        }
+        Ok(())
+    }
+
+    /// LanceDB SQL predicate for these filters, `None` when unfiltered.
+    pub fn predicate(&self) -> Option<String> {
+        let mut clauses = self
+            .tags
+            .iter()
+            .map(|tag| tag.trim())
+            .filter(|tag| !tag.is_empty())
+            .map(tag_predicate)
+            .collect::<Vec<_>>();
+        if let Some(category) = non_blank(&self.category) {
+            clauses.push(category_predicate(category));
+        }
+        if let Some(author) = non_blank(&self.author) {
+            clauses.push(format!("lower(author) = '{}'", escape_literal(&author.to_lowercase())));
+        }
+        match self.article_kind {
+            // Rows written before `article_kind` existed are markdown.
+            Some(ArticleKind::Markdown) => {
+                clauses.push("(article_kind IS NULL OR article_kind = 'markdown')".to_string())
+            },
+            Some(ArticleKind::InteractiveRepost) => {
+                clauses.push("article_kind = 'interactive_repost'".to_string())
+            },
+            None => {},
+        }
+        match self.language {
+            Some(ArticleSearchLanguage::En) => {
+                clauses.push("(content_en IS NOT NULL AND content_en != '')".to_string())
+            },
+            Some(ArticleSearchLanguage::Zh) => {
+                clauses.push("(content IS NOT NULL AND content != '')".to_string())
+            },
+            None => {},
+        }
+        if let Some(from) = non_blank(&self.date_from) {
+            clauses.push(format!("date >= '{}'", escape_literal(from)));
+        }
+        if let Some(to) = non_blank(&self.date_to) {
+            clauses.push(format!("date <= '{}'", escape_literal(to)));
+        }
+        (!clauses.is_empty()).then(|| clauses.join(" AND "))
+    }
+}
+
+/// Tag membership, matching the stored spelling or its lower-case form.
+pub(crate) fn tag_predicate(tag: &str) -> String {
+    let escaped_tag = escape_literal(tag);
+    let escaped_lower = escape_literal(&tag.to_lowercase());
+    if escaped_tag == escaped_lower {
+        format!("list_contains(tags, '{escaped_tag}')")
+    } else {
+        format!("(list_contains(tags, '{escaped_tag}') OR list_contains(tags, '{escaped_lower}'))")
+    }
+}
+
+pub(crate) fn category_predicate(category: &str) -> String {
+    format!("lower(category) = '{}'", escape_literal(&category.to_lowercase()))
+}
+
+fn non_blank(value: &Option<String>) -> Option<&str> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+fn escape_literal(value: &str) -> String {
+    value.replace('\'', "''")
+}
+
+/// Upper bound on tag facets returned with one result set.
+const MAX_TAG_FACETS: usize = 24;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchFacetCount {
+    pub name: String,
+    pub count: usize,
+}
+
+/// Tag and category counts over the whole filtered result set, not only
+/// the current page.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SearchFacets {
+    pub tags: Vec<SearchFacetCount>,
+    pub categories: Vec<SearchFacetCount>,
+}
+
+impl SearchFacets {
+    /// Count `(tags, category)` pairs. Names merge case-insensitively and
+    /// keep the first spelling seen; an article counts once per tag.
+    pub fn collect<'a>(articles: impl IntoIterator<Item = (&'a [String], &'a str)>) -> Self {
+        let mut tags = HashMap::<String, SearchFacetCount>::new();
+        let mut categories = HashMap::<String, SearchFacetCount>::new();
+        for (article_tags, category) in articles {
+            let mut seen = Vec::<String>::new();
+            for tag in article_tags {
+                let key = tag.trim().to_lowercase();
+                if !seen.contains(&key) {
+                    bump_facet(&mut tags, tag);
+                    seen.push(key);
+                }
+            }
+            bump_facet(&mut categories, category);
+        }
+        let sorted = |counts: HashMap<String, SearchFacetCount>| {
+            let mut counts = counts.into_values().collect::<Vec<_>>();
+            counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+            counts
+        };
+        let mut tags = sorted(tags);
+        tags.truncate(MAX_TAG_FACETS);
+        Self {
+            tags,
+            categories: sorted(categories),
+        }
+    }
+}
+
+fn bump_facet(counts: &mut HashMap<String, SearchFacetCount>, name: &str) {
+    let name = name.trim();
+    if name.is_empty() {
+        return;
+    }
+    counts
+        .entry(name.to_lowercase())
+        .or_insert_with(|| SearchFacetCount {
+            name: name.to_string(),
+            count: 0,
+        })
+        .count += 1;
+}
+
+/// Opaque resume point for a ranked result list: the offset of the next
+/// result and the id of the last one served. Resuming after `last_id`
+/// keeps pages from repeating or skipping rows when scores shift slightly
+/// between requests; the offset is the fallback once that row is gone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchCursor {
+    pub offset: usize,
+    pub last_id: String,
+}
+
+impl SearchCursor {
+    pub fn encode(&self) -> String {
+        format!("{}:{}", self.offset, self.last_id)
+            .bytes()
+            .map(|byte| format!("{byte:02x}"))
+            .collect()
+    }
+
+    pub fn decode(raw: &str) -> Option<Self> {
+        let bytes = raw
+            .as_bytes()
+            .chunks(2)
+            .map(|pair| {
+                let pair = std::str::from_utf8(pair)
+                    .ok()
+                    .filter(|pair| pair.len() == 2)?;
+                u8::from_str_radix(pair, 16).ok()
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let text = String::from_utf8(bytes).ok()?;
+        let (offset, last_id) = text.split_once(':')?;
+        Some(Self {
+            offset: offset.parse().ok()?,
+            last_id: last_id.to_string(),
+        })
+    }
+
+    fn resume_index<'a>(&self, ids: impl Iterator<Item = &'a str>) -> usize {
+        ids.enumerate()
+            .find(|(_, id)| *id == self.last_id)
+            .map(|(index, _)| index + 1)
+            .unwrap_or(self.offset)
+    }
+}
+
+/// Page size and resume point for a search request.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchPageRequest {
+    pub limit: Option<usize>,
+    pub cursor: Option<SearchCursor>,
+}
+
+impl SearchPageRequest {
+    /// Slice one page out of a ranked list, returning the page and the
+    /// cursor for the next one when more rows remain.
+    pub fn slice<T>(&self, ranked: Vec<T>, id: impl Fn(&T) -> &str) -> (Vec<T>, Option<String>) {
+        let total = ranked.len();
+        let start = self
+            .cursor
+            .as_ref()
+            .map(|cursor| cursor.resume_index(ranked.iter().map(&id)))
+            .unwrap_or(0)
+            .min(total);
+        let end = self
+            .limit
+            .map(|limit| start.saturating_add(limit).min(total))
+            .unwrap_or(total);
+        let page = ranked
+            .into_iter()
+            .skip(start)
+            .take(end - start)
+            .collect::<Vec<_>>();
+        let next_cursor = (end < total).then(|| page.last()).flatten().map(|last| {
+            SearchCursor {
+                offset: end,
+                last_id: id(last).to_string(),
+            }
+            .encode()
+        });
+        (page, next_cursor)
+    }
+}
+
+/// The sample posts under `content/`, for ranking regression tests.
+#[cfg(test)]
+pub(crate) mod sample_posts {
+    pub(crate) struct SamplePost {
+        pub id: &'static str,
+        pub title: String,
+        pub summary: String,
+        pub tags: Vec<String>,
+        pub content: String,
+    }
+
+    /// Minimal front matter reader: enough for the single-line `title`,
+    /// `summary` and `tags` keys.
+    fn post(id: &'static str, raw: &str) -> SamplePost {
+        let (front_matter, content) = raw
+            .strip_prefix("---\n")
+            .and_then(|rest| rest.split_once("\n---\n"))
+            .unwrap_or(("", raw));
+        let value = |key: &str| {
+            front_matter
+                .lines()
+                .find_map(|line| line.strip_prefix(&format!("{key}: ")))
+                .unwrap_or_default()
+                .trim()
+                .to_string()
+        };
+        let tags = value("tags")
+            .trim_matches(|ch| ch == '[' || ch == ']')
+            .split(',')
+            .map(|tag| tag.trim().trim_matches('"').to_string())
+            .filter(|tag| !tag.is_empty())
+            .collect();
+        SamplePost {
+            id,
+            title: value("title").trim_matches('"').to_string(),
+            summary: value("summary").trim_matches('"').to_string(),
+            tags,
+            content: content.to_string(),
+        }
+    }
+
+    pub(crate) fn load() -> Vec<SamplePost> {
+        vec![
+            post(
+                "es-cluster-coordination-evolution",
+                include_str!("../../../content/es-cluster-coordination-evolution.md"),
+            ),
+            post(
+                "lance-stable-row-id-deep-dive",
+                include_str!("../../../content/lance-stable-row-id-deep-dive.md"),
+            ),
+            post("post-001", include_str!("../../../content/post-001.md")),
+            post("post-002", include_str!("../../../content/post-002.md")),
+            post("post-005", include_str!("../../../content/post-005.md")),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use static_flow_shared::ArticleKind;
+
+    use super::{
+        sample_posts, ArticleSearchDocument, ArticleSearchFilters, ArticleSearchLanguage,
+        ArticleSearchQuery, SearchCursor, SearchFacets, SearchPageRequest,
+    };
+
+    fn rank(query: &str) -> Vec<&'static str> {
+        let query = ArticleSearchQuery::parse(query);
+        let mut scored = sample_posts::load()
+            .into_iter()
+            .filter_map(|post| {
+                let doc = ArticleSearchDocument {
+                    title: &post.title,
+                    summary: &post.summary,
+                    content: &post.content,
+                    content_en: None,
+                    tags: &post.tags,
+                };
+                query.score(&doc).map(|matched| (post.id, matched.score))
+            })
+            .collect::<Vec<_>>();
+        scored.sort_by(|left, right| right.1.total_cmp(&left.1).then(left.0.cmp(right.0)));
+        scored.into_iter().map(|(id, _)| id).collect()
+    }
+
+    #[test]
+    fn parse_splits_phrases_prefixes_and_exclusions() {
+        let query =
+            ArticleSearchQuery::parse(r#"  "stable row id" lance* -"zen discovery" -yew 向量 "#);
+        assert_eq!(query.phrases, vec!["stable row id"]);
+        assert_eq!(query.prefixes, vec!["lance"]);
+        assert_eq!(query.excluded, vec!["zen discovery", "yew"]);
+        assert_eq!(query.terms, vec!["向量"]);
+        assert!(ArticleSearchQuery::parse(r#"-only "" *"#).is_empty());
+    }
+
+    #[test]
+    fn highlight_comes_from_the_strongest_matching_field() {
+        let tags = vec!["raft".to_string()];
+        let doc = ArticleSearchDocument {
+            title: "集群协调",
+            summary: "summary",
+            content: "Raft 与协调",
+            content_en: Some("coordination"),
+            tags: &tags,
+        };
+        let matched = ArticleSearchQuery::parse("协调")
+            .score(&doc)
+            .expect("match");
+        assert_eq!(matched.column, "title");
+        assert_eq!(matched.score, 5.0);
+        let matched = ArticleSearchQuery::parse("coordination")
+            .score(&doc)
+            .expect("match");
+        assert_eq!(matched.column, "content_en");
+    }
+
+    #[test]
+    fn prefixes_respect_word_boundaries_for_latin_text() {
+        let tags = Vec::new();
+        let doc = ArticleSearchDocument {
+            title: "Stable row ids",
+            summary: "",
+            content: "unstable",
+            content_en: None,
+            tags: &tags,
+        };
+        assert!(ArticleSearchQuery::parse("stab*").score(&doc).is_some());
+        assert!(ArticleSearchQuery::parse("tab*").score(&doc).is_none());
+    }
+
+    #[test]
+    fn ranking_on_sample_posts_prefers_title_and_tag_hits() {
+        assert_eq!(rank("协调").first(), Some(&"es-cluster-coordination-evolution"));
+        assert_eq!(rank("Raft").first(), Some(&"es-cluster-coordination-evolution"));
+        assert_eq!(rank("\"Stable Row ID\""), vec!["lance-stable-row-id-deep-dive"]);
+        assert_eq!(rank("rust").first(), Some(&"post-001"));
+        assert!(!rank("rust -yew").contains(&"post-001"));
+        assert_eq!(rank("elastic*").first(), Some(&"es-cluster-coordination-evolution"));
+        assert_eq!(rank("示例文章 AI").first(), Some(&"post-005"));
+    }
+
+    #[test]
+    fn filters_push_down_as_one_predicate() {
+        let filters = ArticleSearchFilters {
+            tags: vec!["Rust".to_string(), " ".to_string()],
+            category: Some("Web".to_string()),
+            author: Some("O'Neil".to_string()),
+            article_kind: Some(ArticleKind::Markdown),
+            language: Some(ArticleSearchLanguage::En),
+            date_from: Some("2024-01-01".to_string()),
+            date_to: None,
+        };
+        assert_eq!(
+            filters.predicate().as_deref(),
+            Some(
+                "(list_contains(tags, 'Rust') OR list_contains(tags, 'rust')) AND lower(category) \
+                 = 'web' AND lower(author) = 'o''neil' AND (article_kind IS NULL OR article_kind \
+                 = 'markdown') AND (content_en IS NOT NULL AND content_en != '') AND date >= \
+                 '2024-01-01'"
+            )
+        );
+        assert_eq!(ArticleSearchFilters::default().predicate(), None);
+        let zh = ArticleSearchFilters {
+            language: Some(ArticleSearchLanguage::Zh),
+            ..Default::default()
+        };
+        assert_eq!(zh.predicate().as_deref(), Some("(content IS NOT NULL AND content != '')"));
+        assert!(filters.validate().is_ok());
+
+        let inverted = ArticleSearchFilters {
+            date_from: Some("2024-02-01".to_string()),
+            date_to: Some("2024-01-01".to_string()),
+            ..Default::default()
+        };
+        assert!(inverted.validate().is_err());
+        let malformed = ArticleSearchFilters {
+            date_to: Some("2024/01/01".to_string()),
+            ..Default::default()
+        };
+        assert!(malformed.validate().is_err());
+    }
+
+    #[test]
+    fn facets_merge_case_and_count_each_article_once() {
+        let rust = vec!["Rust".to_string(), "rust".to_string(), "wasm".to_string()];
+        let web = vec!["rust".to_string(), "css".to_string()];
+        let facets = SearchFacets::collect([
+            (rust.as_slice(), "Rust"),
+            (web.as_slice(), "Web"),
+            (&[][..], "rust"),
+        ]);
+        let tags = facets
+            .tags
+            .iter()
+            .map(|facet| (facet.name.as_str(), facet.count))
+            .collect::<Vec<_>>();
+        assert_eq!(tags, vec![("Rust", 2), ("css", 1), ("wasm", 1)]);
+        let categories = facets
+            .categories
+            .iter()
+            .map(|facet| (facet.name.as_str(), facet.count))
+            .collect::<Vec<_>>();
+        assert_eq!(categories, vec![("Rust", 2), ("Web", 1)]);
+    }
+
+    #[test]
+    fn cursor_pages_resume_after_last_served_id() {
+        let ids = ["a", "b", "c", "d", "e"];
+        let first = SearchPageRequest {
+            limit: Some(2),
+            cursor: None,
+        };
+        let (page, next) = first.slice(ids.to_vec(), |id| id);
+        assert_eq!(page, vec!["a", "b"]);
+        let cursor = SearchCursor::decode(&next.expect("more pages")).expect("valid cursor");
+        assert_eq!(cursor, SearchCursor {
+            offset: 2,
+            last_id: "b".to_string(),
+        });
+
+        // A row ranked ahead of the boundary between requests does not
+        // repeat "b" on the next page.
+        let second = SearchPageRequest {
+            limit: Some(2),
+            cursor: Some(cursor.clone()),
+        };
+        let (page, _) = second.slice(vec!["x", "a", "b", "c", "d", "e"], |id| id);
+        assert_eq!(page, vec!["c", "d"]);
+
+        // Once the boundary row is gone, the offset takes over.
+        let (page, next) = second.slice(vec!["a", "c", "d", "e"], |id| id);
+        assert_eq!(page, vec!["d", "e"]);
+        assert_eq!(next, None);
+
+        assert_eq!(SearchCursor::decode("zz"), None);
+        assert_eq!(SearchCursor::decode("abc"), None);
+    }
+}
@@ -0,0 +1,475 @@
+//! Derived image renditions and ingest-time placeholders.
+//!
+//! `/api/images/:filename` can ask for a resized, re-encoded copy of a stored
+//! original. A request is first normalized into an [`ImageVariantSpec`]:
+//! edges snap up to the next entry of [`VARIANT_EDGES`] and quality snaps to
+//! the nearest entry of [`VARIANT_QUALITIES`], so arbitrary parameters cannot
+//! flood the `image_variants` cache table with near-duplicates. The spec's
+//! [`ImageVariantSpec::cache_key`] names the cached blob and doubles as the
+//! response ETag suffix.
+//!
+//! Output formats follow the `Accept` header: AVIF whenever the client takes
+//! it, lossless WebP only for sources that are lossless already (the
+//! encoder available here has no lossy mode), and otherwise the source's own
+//! family (PNG for PNG sources, JPEG for everything else).
+
+use std::io::Cursor;
+
+use anyhow::{anyhow, Context, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64_STANDARD, Engine as _};
+use image::{
+    codecs::{avif::AvifEncoder, jpeg::JpegEncoder, png::PngEncoder, webp::WebPEncoder},
+    imageops::FilterType,
+    DynamicImage, GenericImageView, ImageReader, Limits,
+};
+use serde::{Deserialize, Serialize};
+
+/// Largest edge, in pixels, a derived variant may request.
+pub const MAX_VARIANT_EDGE: u32 = 2048;
+/// Edges a variant may be rendered at; requests round up to the next one.
+pub const VARIANT_EDGES: [u32; 9] = [64, 128, 256, 384, 512, 768, 1024, 1536, MAX_VARIANT_EDGE];
+/// Encoder qualities a variant may use; requests snap to the nearest one.
+pub const VARIANT_QUALITIES: [u8; 4] = [40, 60, 75, 90];
+pub const DEFAULT_VARIANT_QUALITY: u8 = 75;
+/// Decode guards against decompression bombs in stored originals.
+const MAX_SOURCE_EDGE: u32 = 12_000;
+const MAX_SOURCE_DECODE_BYTES: u64 = 256 * 1024 * 1024;
+const AVIF_ENCODE_SPEED: u8 = 8;
+const BLURHASH_COMPONENTS_X: u32 = 4;
+const BLURHASH_COMPONENTS_Y: u32 = 3;
+const BLURHASH_SAMPLE_EDGE: u32 = 32;
+const LQIP_EDGE: u32 = 16;
+const LQIP_JPEG_QUALITY: u8 = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageFit {
+    /// Scale to fit inside the box, keeping aspect ratio.
+    #[default]
+    Contain,
+    /// Scale to cover the box, then center-crop the overflow.
+    Cover,
+    /// Stretch to exactly the box.
+    Fill,
+}
+
+impl ImageFit {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Contain => "contain",
+            Self::Cover => "cover",
+            Self::Fill => "fill",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageVariantFormat {
+    Avif,
+    Webp,
+    Jpeg,
+    Png,
+}
+
+impl ImageVariantFormat {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "avif" => Some(Self::Avif),
+            "webp" => Some(Self::Webp),
+            "jpg" | "jpeg" => Some(Self::Jpeg),
+            "png" => Some(Self::Png),
+            _ => None,
+        }
+    }
+
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            Self::Avif => "image/avif",
+            Self::Webp => "image/webp",
+            Self::Jpeg => "image/jpeg",
+            Self::Png => "image/png",
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Avif => "avif",
+            Self::Webp => "webp",
+            Self::Jpeg => "jpg",
+            Self::Png => "png",
+        }
+    }
+
+    /// Pick the output format for a source of `source_mime_type` given the
+    /// client's `Accept` header. See the module docs for the preference
+    /// order.
+    pub fn negotiate(accept: Option<&str>, source_mime_type: &str) -> Self {
+        let accept = accept.unwrap_or_default();
+        let lossless_source = matches!(source_mime_type, "image/png" | "image/webp" | "image/gif");
+        if accepts_mime(accept, "image/avif") {
+            Self::Avif
+        } else if lossless_source && accepts_mime(accept, "image/webp") {
+            Self::Webp
+        } else if source_mime_type == "image/png" {
+            Self::Png
+        } else {
+            Self::Jpeg
+        }
+    }
+}
+
+/// Whether a source of this MIME type can be decoded and re-encoded.
+/// SVGs are vector already and GIFs would lose their animation, so both are
+/// always served as stored.
+pub fn is_resizable_mime_type(mime_type: &str) -> bool {
+    matches!(mime_type, "image/jpeg" | "image/png" | "image/webp")
+}
+
+/// A validated, normalized rendition request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageVariantSpec {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub fit: ImageFit,
+    pub quality: u8,
+    pub format: ImageVariantFormat,
+}
+
+impl ImageVariantSpec {
+    /// Validate raw request values. Zero or oversized edges are rejected;
+    /// edges and quality snap onto their allowlists. `cover` and `fill` need
+    /// both edges and fall back to `contain` otherwise.
+    pub fn new(
+        width: Option<u32>,
+        height: Option<u32>,
+        fit: ImageFit,
+        quality: Option<u8>,
+        format: ImageVariantFormat,
+    ) -> Result<Self> {
+        let width = width.map(|value| snap_edge("width", value)).transpose()?;
+        let height = height.map(|value| snap_edge("height", value)).transpose()?;
+        Ok(Self {
+            width,
+            height,
+            fit: if width.is_some() && height.is_some() { fit } else { ImageFit::Contain },
+            quality: snap_quality(quality.unwrap_or(DEFAULT_VARIANT_QUALITY)),
+            format,
+        })
+    }
+
+    /// Stable name for this rendition, used as cache key and ETag suffix.
+    pub fn cache_key(&self) -> String {
+        format!(
+            "w{}-h{}-{}-q{}.{}",
+            self.width.unwrap_or(0),
+            self.height.unwrap_or(0),
+            self.fit.as_str(),
+            self.quality,
+            self.format.extension()
+        )
+    }
+
+    /// Box to resize a `source_width` x `source_height` original into.
+    /// Never upscales: `contain` clamps each edge to the source, while
+    /// `cover` / `fill` shrink an oversized box to the largest box of the
+    /// same shape that fits inside the source.
+    fn target_box(&self, source_width: u32, source_height: u32) -> (u32, u32) {
+        match (self.fit, self.width, self.height) {
+            (ImageFit::Cover | ImageFit::Fill, Some(width), Some(height)) => {
+                let scale = (source_width as f64 / width as f64)
+                    .min(source_height as f64 / height as f64)
+                    .min(1.0);
+                (
+                    ((width as f64 * scale).round() as u32).max(1),
+                    ((height as f64 * scale).round() as u32).max(1),
+                )
+            },
+            _ => (
+                self.width.unwrap_or(source_width).min(source_width),
+                self.height.unwrap_or(source_height).min(source_height),
+            ),
+        }
+    }
+}
+
+fn snap_edge(name: &str, value: u32) -> Result<u32> {
+    if value == 0 || value > MAX_VARIANT_EDGE {
+        anyhow::bail!("{name} must be between 1 and {MAX_VARIANT_EDGE}");
+    }
+    Ok(VARIANT_EDGES
+        .into_iter()
+        .find(|edge| *edge >= value)
+        .unwrap_or(MAX_VARIANT_EDGE))
+}
+
+/// Nearest allowed quality; ties go to the higher one.
+fn snap_quality(value: u8) -> u8 {
+    VARIANT_QUALITIES
+        .into_iter()
+        .rev()
+        .min_by_key(|quality| quality.abs_diff(value))
+        .unwrap_or(DEFAULT_VARIANT_QUALITY)
+}
+
+/// `true` when `accept` names `mime_type` explicitly with a nonzero q-value.
+/// Wildcards are deliberately ignored: browsers send `image/*` and `*/*` on
+/// every image request, including ones that cannot decode AVIF.
+fn accepts_mime(accept: &str, mime_type: &str) -> bool {
+    accept.split(',').any(|range| {
+        let mut parts = range.split(';').map(str::trim);
+        let media = parts.next().unwrap_or_default();
+        let rejected = parts.any(|param| {
+            param
+                .strip_prefix("q=")
+                .and_then(|value| value.parse::<f32>().ok())
+                .is_some_and(|q| q <= 0.0)
+        });
+        !rejected && media.eq_ignore_ascii_case(mime_type)
+    })
+}
+
+#[derive(Debug, Clone)]
+pub struct RenderedImageVariant {
+    pub bytes: Vec<u8>,
+    pub mime_type: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Decode `source`, resize it per `spec` and re-encode it. CPU-bound; call
+/// from a blocking task.
+pub fn render_image_variant(
+    source: &[u8],
+    spec: &ImageVariantSpec,
+) -> Result<RenderedImageVariant> {
+    let image = decode_limited(source)?;
+    let (source_width, source_height) = image.dimensions();
+    let (width, height) = spec.target_box(source_width, source_height);
+    let resized = if (width, height) == (source_width, source_height) {
+        image
+    } else {
+        match spec.fit {
+            ImageFit::Contain => image.resize(width, height, FilterType::Lanczos3),
+            ImageFit::Cover => image.resize_to_fill(width, height, FilterType::Lanczos3),
+            ImageFit::Fill => image.resize_exact(width, height, FilterType::Lanczos3),
+        }
+    };
+    let (width, height) = resized.dimensions();
+    Ok(RenderedImageVariant {
+        bytes: encode_image(&resized, spec.format, spec.quality)?,
+        mime_type: spec.format.mime_type().to_string(),
+        width,
+        height,
+    })
+}
+
+fn decode_limited(source: &[u8]) -> Result<DynamicImage> {
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_SOURCE_EDGE);
+    limits.max_image_height = Some(MAX_SOURCE_EDGE);
+    limits.max_alloc = Some(MAX_SOURCE_DECODE_BYTES);
+    let mut reader = ImageReader::new(Cursor::new(source))
+        .with_guessed_format()
+        .context("failed to sniff image format")?;
+    reader.limits(limits);
+    reader.decode().context("failed to decode source image")
+}
+
+fn encode_image(image: &DynamicImage, format: ImageVariantFormat, quality: u8) -> Result<Vec<u8>> {
+    // JPEG has no alpha channel; the other encoders take 8-bit RGB(A).
+    let pixels = if image.color().has_alpha() && format != ImageVariantFormat::Jpeg {
+        DynamicImage::ImageRgba8(image.to_rgba8())
+    } else {
+        DynamicImage::ImageRgb8(image.to_rgb8())
+    };
+    let mut buffer = Vec::new();
+    match format {
+        ImageVariantFormat::Avif => pixels.write_with_encoder(AvifEncoder::new_with_speed_quality(
+            &mut buffer,
+            AVIF_ENCODE_SPEED,
+            quality,
+        )),
+        ImageVariantFormat::Webp => {
+            pixels.write_with_encoder(WebPEncoder::new_lossless(&mut buffer))
+        },
+        ImageVariantFormat::Jpeg => {
+            pixels.write_with_encoder(JpegEncoder::new_with_quality(&mut buffer, quality))
+        },
+        ImageVariantFormat::Png => pixels.write_with_encoder(PngEncoder::new(&mut buffer)),
+    }
+    .with_context(|| format!("failed to encode {} variant", format.extension()))?;
+    Ok(buffer)
+}
+
+/// Low-quality placeholder stored in `images.metadata` at ingest.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImagePlaceholder {
+    pub blurhash: String,
+    /// Tiny JPEG as a `data:` URI, usable directly as a CSS background.
+    pub lqip: String,
+}
+
+/// Compute the blurhash and LQIP placeholder for an already decoded image.
+pub fn compute_image_placeholder(image: &DynamicImage) -> Result<ImagePlaceholder> {
+    let sample = image
+        .thumbnail(BLURHASH_SAMPLE_EDGE, BLURHASH_SAMPLE_EDGE)
+        .to_rgba8();
+    let (sample_width, sample_height) = sample.dimensions();
+    let blurhash = blurhash::encode(
+        BLURHASH_COMPONENTS_X,
+        BLURHASH_COMPONENTS_Y,
+        sample_width,
+        sample_height,
+        sample.as_raw(),
+    )
+    .map_err(|err| anyhow!("failed to compute blurhash: {err:?}"))?;
+
+    let lqip_bytes = encode_image(
+        &image.thumbnail(LQIP_EDGE, LQIP_EDGE),
+        ImageVariantFormat::Jpeg,
+        LQIP_JPEG_QUALITY,
+    )?;
+    Ok(ImagePlaceholder {
+        blurhash,
+        lqip: format!("data:image/jpeg;base64,{}", BASE64_STANDARD.encode(lqip_bytes)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{DynamicImage, GenericImageView, ImageFormat, RgbImage};
+
+    use super::{
+        compute_image_placeholder, render_image_variant, ImageFit, ImageVariantFormat,
+        ImageVariantSpec,
+    };
+
+    fn png_fixture(width: u32, height: u32) -> Vec<u8> {
+        let image = RgbImage::from_fn(width, height, |x, y| image::Rgb([x as u8, y as u8, 128]));
+        let mut buffer = std::io::Cursor::new(Vec::new());
+        DynamicImage::ImageRgb8(image)
+            .write_to(&mut buffer, ImageFormat::Png)
+            .expect("encode fixture");
+        buffer.into_inner()
+    }
+
+    #[test]
+    fn variant_spec_snaps_edges_and_rejects_oversized_requests() {
+        let spec = ImageVariantSpec::new(
+            Some(300),
+            None,
+            ImageFit::Contain,
+            Some(5),
+            ImageVariantFormat::Jpeg,
+        )
+        .expect("valid spec");
+        assert_eq!(spec.width, Some(384));
+        assert_eq!(spec.quality, 40);
+        assert_eq!(spec.cache_key(), "w384-h0-contain-q40.jpg");
+
+        let nearby = [(Some(1500), Some(70)), (Some(1536), Some(78)), (Some(1025), Some(80))]
+            .into_iter()
+            .map(|(width, quality)| {
+                ImageVariantSpec::new(
+                    width,
+                    None,
+                    ImageFit::Contain,
+                    quality,
+                    ImageVariantFormat::Jpeg,
+                )
+                .expect("valid spec")
+                .cache_key()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(nearby, vec![
+            "w1536-h0-contain-q75.jpg",
+            "w1536-h0-contain-q75.jpg",
+            "w1536-h0-contain-q75.jpg",
+        ]);
+
+        assert!(ImageVariantSpec::new(
+            Some(0),
+            None,
+            ImageFit::Contain,
+            None,
+            ImageVariantFormat::Png
+        )
+        .is_err());
+        assert!(ImageVariantSpec::new(
+            None,
+            Some(4096),
+            ImageFit::Cover,
+            None,
+            ImageVariantFormat::Png
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn format_negotiation_prefers_avif_and_keeps_webp_for_lossless_sources() {
+        let chrome = Some("image/avif,image/webp,image/apng,image/*,*/*;q=0.8");
+        let safari_old = Some("image/webp,image/png,image/*;q=0.8,*/*;q=0.5");
+        let no_avif = Some("image/avif;q=0,image/webp");
+        assert_eq!(ImageVariantFormat::negotiate(chrome, "image/jpeg"), ImageVariantFormat::Avif);
+        assert_eq!(
+            ImageVariantFormat::negotiate(safari_old, "image/png"),
+            ImageVariantFormat::Webp
+        );
+        assert_eq!(
+            ImageVariantFormat::negotiate(safari_old, "image/jpeg"),
+            ImageVariantFormat::Jpeg
+        );
+        assert_eq!(ImageVariantFormat::negotiate(no_avif, "image/jpeg"), ImageVariantFormat::Jpeg);
+        assert_eq!(ImageVariantFormat::negotiate(None, "image/png"), ImageVariantFormat::Png);
+        assert_eq!(
+            ImageVariantFormat::negotiate(Some("*/*"), "image/png"),
+            ImageVariantFormat::Png
+        );
+    }
+
+    #[test]
+    fn render_variant_downscales_without_upscaling() {
+        let source = png_fixture(200, 100);
+
+        let contain =
+            ImageVariantSpec::new(Some(64), None, ImageFit::Contain, None, ImageVariantFormat::Png)
+                .expect("spec");
+        let rendered = render_image_variant(&source, &contain).expect("render");
+        assert_eq!((rendered.width, rendered.height), (64, 32));
+        assert_eq!(rendered.mime_type, "image/png");
+
+        let cover = ImageVariantSpec::new(
+            Some(64),
+            Some(64),
+            ImageFit::Cover,
+            None,
+            ImageVariantFormat::Jpeg,
+        )
+        .expect("spec");
+        let rendered = render_image_variant(&source, &cover).expect("render");
+        assert_eq!((rendered.width, rendered.height), (64, 64));
+        let decoded = image::load_from_memory(&rendered.bytes).expect("decode jpeg");
+        assert_eq!(decoded.dimensions(), (64, 64));
+
+        let larger = ImageVariantSpec::new(
+            Some(512),
+            None,
+            ImageFit::Contain,
+            None,
+            ImageVariantFormat::Png,
+        )
+        .expect("spec");
+        let rendered = render_image_variant(&source, &larger).expect("render");
+        assert_eq!((rendered.width, rendered.height), (200, 100));
+    }
+
+    #[test]
+    fn placeholder_has_blurhash_and_inline_jpeg() {
+        let image = image::load_from_memory(&png_fixture(64, 48)).expect("decode fixture");
+        let placeholder = compute_image_placeholder(&image).expect("placeholder");
+        // 4x3 components: 1 size + 1 max-AC + 4 DC + 2 * 11 AC characters.
+        assert_eq!(placeholder.blurhash.len(), 28);
+        assert!(placeholder.lqip.starts_with("data:image/jpeg;base64,"));
+    }
+}
@@ -104,6 +104,17 @@ pub struct InteractiveAssetMeta {
     pub updated_at: i64,
 }
 
+/// A mirrored page matched by keyword, linked back to its article.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InteractivePageSearchResult {
+    pub page_id: String,
+    pub article_id: String,
+    pub title: String,
+    pub source_host: String,
+    pub source_url: String,
+    pub score: f32,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct InteractiveAssetBlob {
     pub meta: InteractiveAssetMeta,
@@ -494,6 +505,96 @@ impl InteractivePageStore {
         batches_to_interactive_page_locales(&batch_list)
     }
 
+    /// Keyword match over servable mirrors: every whitespace-separated term
+    /// must hit the original title, a translated locale title, or the
+    /// source URL. Title hits weigh most. Mirrors number in the hundreds at
+    /// most, so this scans instead of keeping an FTS index.
+    pub async fn search_pages(
+        &self,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<InteractivePageSearchResult>> {
+        let terms = query
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .collect::<Vec<_>>();
+        if terms.is_empty() || limit == 0 {
+            return Ok(vec![]);
+        }
+
+        let pages = self
+            .pages_table()
+            .await?
+            .query()
+            .only_if(format!(
+                "status != '{INTERACTIVE_PAGE_STATUS_BLOCKED}' AND mirror_policy != \
+                 '{MIRROR_POLICY_REJECTED}'"
+            ))
+            .select(Select::columns(&["id", "article_id", "source_url", "source_host", "title"]))
+            .execute()
+            .await?
+            .try_collect::<Vec<_>>()
+            .await?;
+        let locales = self
+            .page_locales_table()
+            .await?
+            .query()
+            .select(Select::columns(&["page_id", "title"]))
+            .execute()
+            .await?
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        let mut locale_titles = HashMap::<String, Vec<String>>::new();
+        for batch in &locales {
+            let page_ids = required_str_col(batch, "page_id")?;
+            let titles = required_str_col(batch, "title")?;
+            for row in 0..batch.num_rows() {
+                locale_titles
+                    .entry(page_ids.value(row).to_string())
+                    .or_default()
+                    .push(titles.value(row).to_lowercase());
+            }
+        }
+
+        let mut results = Vec::new();
+        for batch in &pages {
+            let ids = required_str_col(batch, "id")?;
+            let article_ids = required_str_col(batch, "article_id")?;
+            let urls = required_str_col(batch, "source_url")?;
+            let hosts = required_str_col(batch, "source_host")?;
+            let titles = required_str_col(batch, "title")?;
+            for row in 0..batch.num_rows() {
+                let page_id = ids.value(row);
+                let title = titles.value(row);
+                let translated = locale_titles
+                    .get(page_id)
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+                let Some(score) =
+                    score_interactive_page(&terms, title, translated, urls.value(row))
+                else {
+                    continue;
+                };
+                results.push(InteractivePageSearchResult {
+                    page_id: page_id.to_string(),
+                    article_id: article_ids.value(row).to_string(),
+                    title: title.to_string(),
+                    source_host: hosts.value(row).to_string(),
+                    source_url: urls.value(row).to_string(),
+                    score,
+                });
+            }
+        }
+        results.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.page_id.cmp(&b.page_id))
+        });
+        results.truncate(limit);
+        Ok(results)
+    }
+
     pub async fn list_assets_for_page(&self, page_id: &str) -> Result<Vec<InteractiveAssetMeta>> {
         let table = self.assets_table().await?;
         let esc_page = escape_literal(page_id);
@@ -949,6 +1050,33 @@ fn dedicated_blob_field(name: &str, nullable: bool) -> Field {
     field.with_metadata(metadata)
 }
 
+/// `None` unless every term matches somewhere; `translated` holds
+/// lower-cased locale titles.
+fn score_interactive_page(
+    terms: &[String],
+    title: &str,
+    translated: &[String],
+    source_url: &str,
+) -> Option<f32> {
+    let title = title.to_lowercase();
+    let source_url = source_url.to_lowercase();
+    terms.iter().try_fold(0.0, |score, term| {
+        let hit = if title.contains(term.as_str()) {
+            3.0
+        } else if translated
+            .iter()
+            .any(|locale| locale.contains(term.as_str()))
+        {
+            2.0
+        } else if source_url.contains(term.as_str()) {
+            1.0
+        } else {
+            return None;
+        };
+        Some(score + hit)
+    })
+}
+
 fn escape_literal(value: &str) -> String {
     value.replace('\'', "''")
 }
@@ -956,3 +1084,28 @@ fn escape_literal(value: &str) -> String {
 pub fn now_ms() -> i64 {
     Utc::now().timestamp_millis()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::score_interactive_page;
+
+    #[test]
+    fn interactive_page_terms_must_all_match_and_titles_weigh_most() {
+        let terms = ["raft".to_string(), "visual".to_string()];
+        let translated = ["raft 可视化 visual guide".to_string()];
+        assert_eq!(
+            score_interactive_page(
+                &terms,
+                "Raft Visualization",
+                &[],
+                "https://thesecretlivesofdata.com/raft"
+            ),
+            Some(6.0)
+        );
+        assert_eq!(
+            score_interactive_page(&terms, "共识算法", &translated, "https://example.com/raft"),
+            Some(4.0)
+        );
+        assert_eq!(score_interactive_page(&terms, "Raft", &[], "https://example.com"), None);
+    }
+}
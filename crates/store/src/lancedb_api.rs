@@ -19,7 +19,7 @@ use fs2::FileExt;
 use futures::TryStreamExt;
 use lancedb::{
     connect,
-    index::scalar::FullTextSearchQuery,
+    index::Index,
     query::{ExecutableQuery, QueryBase, Select},
     Connection, Table,
 };
@@ -29,11 +29,18 @@ use static_flow_embedding::{
     TextEmbeddingModel,
 };
 use static_flow_shared::{
-    normalize_taxonomy_key, Article, ArticleKind, ArticleListItem, LocalizedText,
+    normalize_taxonomy_key, Article, ArticleKind, ArticleListItem, ArticleSeriesLink,
+    ArticleSeriesNav, LocalizedText,
 };
 use tokio::sync::RwLock;
 
 use crate::{
+    article_search::{
+        category_predicate, tag_predicate, ArticleFtsField, ArticleSearchDocument,
+        ArticleSearchFilters, ArticleSearchMatch, ArticleSearchQuery, SearchFacets,
+        SearchPageRequest, ARTICLE_FTS_FIELDS,
+    },
+    image_variants::RenderedImageVariant,
     lance_schema_encoding::low_cardinality_utf8_field,
     optimize::{
         check_opened_table_and_compact, compact_table_with_fallback, prune_table_versions,
@@ -56,7 +63,69 @@ pub struct SearchResult {
 pub struct SearchResponse {
     pub results: Vec<SearchResult>,
     pub total: usize,
+    /// `total` stopped at the candidate window; more articles may match.
+    #[serde(default)]
+    pub total_is_lower_bound: bool,
     pub query: String,
+    #[serde(default)]
+    pub facets: SearchFacets,
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+}
+
+impl SearchResponse {
+    pub fn empty(query: String) -> Self {
+        Self {
+            results: vec![],
+            total: 0,
+            total_is_lower_bound: false,
+            query,
+            facets: SearchFacets::default(),
+            next_cursor: None,
+        }
+    }
+
+    pub fn from_page(query: String, page: SearchResultPage) -> Self {
+        Self {
+            results: page.results,
+            total: page.total,
+            total_is_lower_bound: page.total_is_lower_bound,
+            query,
+            facets: page.facets,
+            next_cursor: page.next_cursor,
+        }
+    }
+}
+
+/// One page of ranked search results. `total` and `facets` describe the
+/// whole filtered candidate window, not just this page. When the window
+/// filled up, `total_is_lower_bound` is set and paging ends at the window.
+#[derive(Debug, Clone)]
+pub struct SearchResultPage {
+    pub results: Vec<SearchResult>,
+    pub total: usize,
+    pub total_is_lower_bound: bool,
+    pub facets: SearchFacets,
+    pub next_cursor: Option<String>,
+}
+
+impl SearchResultPage {
+    fn from_ranked(ranked: Vec<SearchResult>, page: &SearchPageRequest) -> Self {
+        let total = ranked.len();
+        let facets = SearchFacets::collect(
+            ranked
+                .iter()
+                .map(|result| (result.tags.as_slice(), result.category.as_str())),
+        );
+        let (results, next_cursor) = page.slice(ranked, |result| result.id.as_str());
+        Self {
+            results,
+            total,
+            total_is_lower_bound: total >= SEARCH_CANDIDATE_WINDOW,
+            facets,
+            next_cursor,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -129,6 +198,38 @@ pub struct CategoriesResponse {
     pub categories: Vec<CategoryInfo>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SeriesInfo {
+    pub id: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub count: usize,
+    pub first_article_id: Option<String>,
+    pub latest_date: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SeriesListResponse {
+    pub series: Vec<SeriesInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SeriesArticleItem {
+    /// 1-based position after ordering by `series_order`, then date.
+    pub position: usize,
+    pub order: Option<u32>,
+    #[serde(flatten)]
+    pub article: ArticleListItem,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SeriesDetailResponse {
+    pub id: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub articles: Vec<SeriesArticleItem>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct StatsResponse {
     pub total_articles: usize,
@@ -238,18 +339,33 @@ pub struct ImageBlob {
     pub mime_type: String,
 }
 
+/// Identity of a stored image without its bytes, enough to answer
+/// conditional requests before any blob is read.
+#[derive(Debug, Clone)]
+pub struct ImageMeta {
+    /// Content hash of the original; stable validator for ETags.
+    pub id: String,
+    pub filename: String,
+    pub mime_type: String,
+    pub created_at: i64,
+}
+
+/// Cached renditions kept per image; the oldest are evicted past this.
+pub const MAX_IMAGE_VARIANTS_PER_IMAGE: usize = 16;
+
 pub const CONTENT_TABLE_NAMES: &[&str] =
-    &["articles", "images", "taxonomies", "article_views", "api_behavior_events"];
+    &["articles", "images", "image_variants", "taxonomies", "article_views", "api_behavior_events"];
 pub const CONTENT_COMPACTION_TABLE_NAMES: &[&str] =
-    &["articles", "images", "taxonomies", "article_views", "api_behavior_events"];
+    &["articles", "images", "image_variants", "taxonomies", "article_views", "api_behavior_events"];
 pub const CONTENT_BACKGROUND_COMPACTION_TABLE_NAMES: &[&str] =
-    &["articles", "images", "taxonomies"];
+    &["articles", "images", "image_variants", "taxonomies"];
 
 pub struct StaticFlowDataStore {
     db: Connection,
     db_uri: String,
     articles_table: String,
     images_table: String,
+    image_variants_table: String,
     taxonomies_table: String,
     article_views_table: String,
     api_behavior_table: String,
@@ -273,6 +389,7 @@ impl StaticFlowDataStore {
             db_uri: db_uri.to_string(),
             articles_table: "articles".to_string(),
             images_table: "images".to_string(),
+            image_variants_table: "image_variants".to_string(),
             taxonomies_table: "taxonomies".to_string(),
             article_views_table: "article_views".to_string(),
             api_behavior_table: "api_behavior_events".to_string(),
@@ -309,6 +426,8 @@ impl StaticFlowDataStore {
     async fn bootstrap_tables(&self) -> Result<()> {
         self.bootstrap_article_views_table().await?;
         self.bootstrap_api_behavior_table().await?;
+        self.bootstrap_aux_table(&self.image_variants_table, image_variant_schema(), false)
+            .await?;
         Ok(())
     }
 
@@ -417,6 +536,10 @@ impl StaticFlowDataStore {
         self.open_table(&self.api_behavior_table).await
     }
 
+    async fn image_variants_table(&self) -> Result<Table> {
+        self.open_table(&self.image_variants_table).await
+    }
+
     pub async fn append_api_behavior_event(&self, input: NewApiBehaviorEventInput) -> Result<()> {
         self.append_api_behavior_events(vec![input]).await
     }
@@ -938,7 +1061,12 @@ impl StaticFlowDataStore {
             "id equality filter (no scalar index configured)",
         );
         let started = Instant::now();
-        let article = fetch_article_detail(&table, id).await?;
+        let mut article = fetch_article_detail(&table, id).await?;
+        if let Some(article) = article.as_mut() {
+            if let Some(stub) = article.series.take() {
+                article.series = Some(self.resolve_series_navigation(&table, id, stub).await?);
+            }
+        }
         log_query_result(
             "get_article",
             path,
@@ -1049,6 +1177,135 @@ impl StaticFlowDataStore {
         Ok(categories)
     }
 
+    pub async fn list_series(&self) -> Result<Vec<SeriesInfo>> {
+        let table = self.articles_table().await?;
+        let path = "series_filter_scan_plus_taxonomy_lookup";
+        log_query_path("list_series", path, path, "series_id IS NOT NULL projection scan");
+
+        let started = Instant::now();
+        let members = fetch_series_members(&table, None).await?;
+        let metadata = self.series_metadata(None).await?;
+
+        let mut grouped: HashMap<String, Vec<SeriesMember>> = HashMap::new();
+        for member in members {
+            grouped
+                .entry(member.series_id.clone())
+                .or_default()
+                .push(member);
+        }
+
+        let mut series = grouped
+            .into_iter()
+            .map(|(id, mut members)| {
+                sort_series_members(&mut members);
+                let meta = metadata.get(&id);
+                SeriesInfo {
+                    title: meta
+                        .map(|meta| meta.title.clone())
+                        .unwrap_or_else(|| id.clone()),
+                    description: meta.and_then(|meta| meta.description.clone()),
+                    count: members.len(),
+                    first_article_id: members.first().map(|member| member.article.id.clone()),
+                    latest_date: members
+                        .iter()
+                        .map(|member| member.article.date.clone())
+                        .max()
+                        .unwrap_or_default(),
+                    id,
+                }
+            })
+            .collect::<Vec<_>>();
+        series.sort_by(|a, b| {
+            b.latest_date
+                .cmp(&a.latest_date)
+                .then_with(|| a.id.cmp(&b.id))
+        });
+
+        log_query_result("list_series", path, series.len(), started.elapsed().as_millis());
+        Ok(series)
+    }
+
+    pub async fn get_series(&self, id: &str) -> Result<Option<SeriesDetailResponse>> {
+        let series_id = normalize_taxonomy_key(id);
+        if series_id.is_empty() {
+            return Ok(None);
+        }
+        let table = self.articles_table().await?;
+        let path = "series_filter_scan";
+        log_query_path("get_series", path, path, "series_id equality filter");
+
+        let started = Instant::now();
+        let mut members = fetch_series_members(&table, Some(&series_id)).await?;
+        if members.is_empty() {
+            log_query_result("get_series", path, 0, started.elapsed().as_millis());
+            return Ok(None);
+        }
+        sort_series_members(&mut members);
+        let meta = self
+            .series_metadata(Some(&series_id))
+            .await?
+            .remove(&series_id);
+
+        let articles = members
+            .into_iter()
+            .enumerate()
+            .map(|(index, member)| SeriesArticleItem {
+                position: index + 1,
+                order: member.order,
+                article: member.article,
+            })
+            .collect::<Vec<_>>();
+        log_query_result("get_series", path, articles.len(), started.elapsed().as_millis());
+        Ok(Some(SeriesDetailResponse {
+            title: meta
+                .as_ref()
+                .map(|meta| meta.title.clone())
+                .unwrap_or_else(|| series_id.clone()),
+            description: meta.and_then(|meta| meta.description),
+            id: series_id,
+            articles,
+        }))
+    }
+
+    /// Expand the membership stub read from the article row into full
+    /// navigation: series title, 1-based position, and neighbouring parts.
+    async fn resolve_series_navigation(
+        &self,
+        table: &Table,
+        article_id: &str,
+        stub: ArticleSeriesNav,
+    ) -> Result<ArticleSeriesNav> {
+        let mut members = fetch_series_members(table, Some(&stub.id)).await?;
+        sort_series_members(&mut members);
+        let meta = self.series_metadata(Some(&stub.id)).await?.remove(&stub.id);
+        let link = |member: &SeriesMember| ArticleSeriesLink {
+            id: member.article.id.clone(),
+            title: member.article.title.clone(),
+        };
+
+        let index = members
+            .iter()
+            .position(|member| member.article.id == article_id);
+        Ok(ArticleSeriesNav {
+            title: meta.map(|meta| meta.title).unwrap_or(stub.title),
+            position: index.map(|index| index + 1).unwrap_or_default(),
+            total: members.len(),
+            prev: index
+                .and_then(|index| index.checked_sub(1))
+                .and_then(|index| members.get(index))
+                .map(link),
+            next: index.and_then(|index| members.get(index + 1)).map(link),
+            ..stub
+        })
+    }
+
+    async fn series_metadata(&self, key: Option<&str>) -> Result<HashMap<String, SeriesMetadata>> {
+        match self.taxonomies_table().await? {
+            Some(table) => fetch_series_metadata(&table, key).await,
+            None => Ok(HashMap::new()),
+        }
+    }
+
     pub async fn fetch_stats(&self) -> Result<StatsResponse> {
         let table = self.articles_table().await?;
 
@@ -1110,23 +1367,24 @@ impl StaticFlowDataStore {
     pub async fn search_articles(
         &self,
         keyword: &str,
-        limit: Option<usize>,
-    ) -> Result<Vec<SearchResult>> {
+        filters: &ArticleSearchFilters,
+        page: &SearchPageRequest,
+    ) -> Result<SearchResultPage> {
         let table = self.articles_table().await?;
+        let filter = filters.predicate();
+        let window = Some(SEARCH_CANDIDATE_WINDOW);
         let fts_index = inspect_index_for_column(&table, "content", true).await;
         let primary_path = if fts_index.is_some() { "fts_index" } else { "fts_without_index" };
         let primary_reason = format!(
-            "{}; result_limit={}",
+            "{}; candidate_window={SEARCH_CANDIDATE_WINDOW}; filter={}",
             index_reason("content", fts_index.as_ref()),
-            limit
-                .map(|value| value.to_string())
-                .unwrap_or_else(|| "none".to_string())
+            filter.as_deref().unwrap_or("none")
         );
 
         log_query_path("search_articles.primary", primary_path, "fts_index", &primary_reason);
 
         let primary_started = Instant::now();
-        match search_with_fts(&table, keyword, limit).await {
+        let ranked = match search_with_fts(&table, keyword, filter.as_deref(), window).await {
             Ok(results) if !results.is_empty() => {
                 log_query_result(
                     "search_articles.primary",
@@ -1134,7 +1392,7 @@ impl StaticFlowDataStore {
                     results.len(),
                     primary_started.elapsed().as_millis(),
                 );
-                Ok(results)
+                results
             },
             Ok(_) => {
                 log_query_result(
@@ -1153,14 +1411,15 @@ impl StaticFlowDataStore {
                 );
 
                 let fallback_started = Instant::now();
-                let fallback_results = fallback_search(&table, keyword, limit).await?;
+                let fallback_results =
+                    fallback_search(&table, keyword, filter.as_deref(), window).await?;
                 log_query_result(
                     "search_articles.fallback",
                     fallback_path,
                     fallback_results.len(),
                     fallback_started.elapsed().as_millis(),
                 );
-                Ok(fallback_results)
+                fallback_results
             },
             Err(err) => {
                 log_query_result(
@@ -1180,16 +1439,18 @@ impl StaticFlowDataStore {
                 );
 
                 let fallback_started = Instant::now();
-                let fallback_results = fallback_search(&table, keyword, limit).await?;
+                let fallback_results =
+                    fallback_search(&table, keyword, filter.as_deref(), window).await?;
                 log_query_result(
                     "search_articles.fallback",
                     fallback_path,
                     fallback_results.len(),
                     fallback_started.elapsed().as_millis(),
                 );
-                Ok(fallback_results)
+                fallback_results
             },
-        }
+        };
+        Ok(SearchResultPage::from_ranked(ranked, page))
     }
 
     #[allow(
@@ -1200,20 +1461,24 @@ impl StaticFlowDataStore {
     pub async fn semantic_search(
         &self,
         keyword: &str,
-        limit: Option<usize>,
+        filters: &ArticleSearchFilters,
+        page: &SearchPageRequest,
         max_distance: Option<f32>,
         enhanced_highlight: bool,
         hybrid: bool,
         hybrid_rrf_k: Option<f32>,
         hybrid_vector_limit: Option<usize>,
         hybrid_fts_limit: Option<usize>,
-    ) -> Result<Vec<SearchResult>> {
+    ) -> Result<SearchResultPage> {
         let table = self.articles_table().await?;
         let total_started = Instant::now();
-        let effective_vector_limit = if hybrid { hybrid_vector_limit.or(limit) } else { limit };
+        let filter = filters.predicate();
+        let window = Some(SEARCH_CANDIDATE_WINDOW);
+        let effective_vector_limit = if hybrid { hybrid_vector_limit.or(window) } else { window };
         let vector_selection = run_semantic_vector_search_with_fallback(
             &table,
             keyword,
+            filter.as_deref(),
             effective_vector_limit,
             max_distance,
             enhanced_highlight,
@@ -1223,11 +1488,12 @@ impl StaticFlowDataStore {
         let search_language = vector_selection.search_language;
         let query_embedding = vector_selection.query_embedding;
         let mut rows = vector_selection.rows;
+        let mut window_filled = effective_vector_limit.is_some_and(|limit| rows.len() >= limit);
         let mut selected_column = vector_selection.selected_column;
         let mut selected_path = vector_selection.selected_path;
 
         if hybrid {
-            let lexical_limit = hybrid_fts_limit.or(limit);
+            let lexical_limit = hybrid_fts_limit.or(window);
             let fts_index = inspect_index_for_column(&table, "content", true).await;
             let lexical_primary_path =
                 if fts_index.is_some() { "fts_index" } else { "fts_without_index" };
@@ -1246,71 +1512,79 @@ impl StaticFlowDataStore {
             );
 
             let lexical_started = Instant::now();
-            let lexical_rows = match search_with_fts_rows(&table, keyword, lexical_limit).await {
-                Ok(rows) => {
-                    log_query_result(
-                        "semantic_search.hybrid.lexical_primary",
-                        lexical_primary_path,
-                        rows.len(),
-                        lexical_started.elapsed().as_millis(),
-                    );
-                    if rows.is_empty() {
+            let lexical_rows =
+                match search_with_fts_rows(&table, keyword, filter.as_deref(), lexical_limit).await
+                {
+                    Ok(rows) => {
+                        log_query_result(
+                            "semantic_search.hybrid.lexical_primary",
+                            lexical_primary_path,
+                            rows.len(),
+                            lexical_started.elapsed().as_millis(),
+                        );
+                        if rows.is_empty() {
+                            let fallback_path = "scan_fallback";
+                            log_query_path(
+                                "semantic_search.hybrid.lexical_fallback",
+                                fallback_path,
+                                "fts_index",
+                                "fts returned 0 rows in hybrid lexical path; fallback to scan",
+                            );
+                            let fallback_started = Instant::now();
+                            let fallback_rows = fallback_search_rows(
+                                &table,
+                                keyword,
+                                filter.as_deref(),
+                                lexical_limit,
+                            )
+                            .await?;
+                            log_query_result(
+                                "semantic_search.hybrid.lexical_fallback",
+                                fallback_path,
+                                fallback_rows.len(),
+                                fallback_started.elapsed().as_millis(),
+                            );
+                            fallback_rows
+                        } else {
+                            rows
+                        }
+                    },
+                    Err(err) => {
+                        log_query_result(
+                            "semantic_search.hybrid.lexical_primary",
+                            lexical_primary_path,
+                            0,
+                            lexical_started.elapsed().as_millis(),
+                        );
                         let fallback_path = "scan_fallback";
                         log_query_path(
                             "semantic_search.hybrid.lexical_fallback",
                             fallback_path,
                             "fts_index",
-                            "fts returned 0 rows in hybrid lexical path; fallback to scan",
+                            &format!("fts query failed in hybrid lexical path; error={err}"),
                         );
                         let fallback_started = Instant::now();
-                        let fallback_rows =
-                            fallback_search_rows(&table, keyword, lexical_limit).await?;
+                        let rows =
+                            fallback_search_rows(&table, keyword, filter.as_deref(), lexical_limit)
+                                .await?;
                         log_query_result(
                             "semantic_search.hybrid.lexical_fallback",
                             fallback_path,
-                            fallback_rows.len(),
+                            rows.len(),
                             fallback_started.elapsed().as_millis(),
                         );
-                        fallback_rows
-                    } else {
                         rows
-                    }
-                },
-                Err(err) => {
-                    log_query_result(
-                        "semantic_search.hybrid.lexical_primary",
-                        lexical_primary_path,
-                        0,
-                        lexical_started.elapsed().as_millis(),
-                    );
-                    let fallback_path = "scan_fallback";
-                    log_query_path(
-                        "semantic_search.hybrid.lexical_fallback",
-                        fallback_path,
-                        "fts_index",
-                        &format!("fts query failed in hybrid lexical path; error={err}"),
-                    );
-                    let fallback_started = Instant::now();
-                    let rows = fallback_search_rows(&table, keyword, lexical_limit).await?;
-                    log_query_result(
-                        "semantic_search.hybrid.lexical_fallback",
-                        fallback_path,
-                        rows.len(),
-                        fallback_started.elapsed().as_millis(),
-                    );
-                    rows
-                },
-            };
+                    },
+                };
 
             let rrf_k = hybrid_rrf_k
                 .filter(|value| value.is_finite() && *value > 0.0)
                 .unwrap_or(60.0);
+            window_filled |= lexical_limit.is_some_and(|limit| lexical_rows.len() >= limit);
+            let lexical_rows = lexical_rows.into_iter().map(|(row, _)| row).collect();
             rows = fuse_hybrid_rrf(rows, lexical_rows, rrf_k);
-            if let Some(limit) = limit {
-                rows.truncate(limit);
-            }
             selected_path = "hybrid_rrf";
-            selected_column = "hybrid(vector_en/vector_zh + content_fts)";
+            selected_column = "hybrid(vector_en/vector_zh + article_fts)";
             tracing::info!(
                 "Hybrid semantic fusion applied; query=semantic_search; rrf_k={rrf_k}; \
                  vector_window={}; lexical_window={}; fused_rows={}",
@@ -1335,6 +1609,13 @@ impl StaticFlowDataStore {
             highlight_reason,
         );
 
+        let total = rows.len();
+        let facets = SearchFacets::collect(
+            rows.iter()
+                .map(|row| (row.tags.as_slice(), row.category.as_str())),
+        );
+        let (rows, next_cursor) = page.slice(rows, |row| row.id.as_str());
+
         let highlight_started = Instant::now();
         let results = rows
             .into_iter()
@@ -1373,7 +1654,13 @@ impl StaticFlowDataStore {
             total_started.elapsed().as_millis()
         );
 
-        Ok(results)
+        Ok(SearchResultPage {
+            results,
+            total,
+            total_is_lower_bound: window_filled,
+            facets,
+            next_cursor,
+        })
     }
 
     pub async fn related_articles(&self, id: &str, limit: usize) -> Result<Vec<ArticleListItem>> {
@@ -1773,8 +2060,112 @@ impl StaticFlowDataStore {
             filename,
         }))
     }
-}
 
+    pub async fn get_image_meta(&self, id_or_filename: &str) -> Result<Option<ImageMeta>> {
+        let table = self.images_table().await?;
+        let escaped = escape_literal(id_or_filename);
+        let batches = table
+            .query()
+            .only_if(format!("filename = '{escaped}' OR id = '{escaped}'"))
+            .limit(1)
+            .select(Select::columns(&["id", "filename", "created_at"]))
+            .execute()
+            .await?
+            .try_collect::<Vec<_>>()
+            .await?;
+        for batch in &batches {
+            if batch.num_rows() == 0 {
+                continue;
+            }
+            let filename = value_string(string_array(batch, "filename")?, 0);
+            return Ok(Some(ImageMeta {
+                id: value_string(string_array(batch, "id")?, 0),
+                mime_type: image_mime_type(&filename).to_string(),
+                filename,
+                created_at: timestamp_ms_array(batch, "created_at")?.value(0),
+            }));
+        }
+        Ok(None)
+    }
+
+    /// Look up a cached rendition of `image_id` by its variant cache key.
+    pub async fn get_image_variant(
+        &self,
+        image_id: &str,
+        variant_key: &str,
+    ) -> Result<Option<ImageBlob>> {
+        let table = self.image_variants_table().await?;
+        let id = escape_literal(&image_variant_row_id(image_id, variant_key));
+        let batches = table
+            .query()
+            .only_if(format!("id = '{id}'"))
+            .limit(1)
+            .select(Select::columns(&["variant_key", "mime_type", "data"]))
+            .execute()
+            .await?
+            .try_collect::<Vec<_>>()
+            .await?;
+        for batch in &batches {
+            if batch.num_rows() == 0 {
+                continue;
+            }
+            let Some(bytes) = binary_like_value(batch, "data", 0)? else {
+                continue;
+            };
+            return Ok(Some(ImageBlob {
+                bytes,
+                filename: value_string(string_array(batch, "variant_key")?, 0),
+                mime_type: value_string(string_array(batch, "mime_type")?, 0),
+            }));
+        }
+        Ok(None)
+    }
+
+    /// Cache a rendition, then evict the image's oldest renditions beyond
+    /// [`MAX_IMAGE_VARIANTS_PER_IMAGE`].
+    pub async fn put_image_variant(
+        &self,
+        image_id: &str,
+        variant_key: &str,
+        variant: &RenderedImageVariant,
+    ) -> Result<()> {
+        let table = self.image_variants_table().await?;
+        let batch = build_image_variant_batch(image_id, variant_key, variant)?;
+        let schema = batch.schema();
+        let batches = RecordBatchIterator::new(vec![Ok(batch)].into_iter(), schema);
+
+        let mut merge = table.merge_insert(&["id"]);
+        merge.when_matched_update_all(None);
+        merge.when_not_matched_insert_all();
+        merge.execute(Box::new(batches)).await?;
+
+        let filter = format!("image_id = '{}'", escape_literal(image_id));
+        let batches = table
+            .query()
+            .only_if(filter)
+            .select(Select::columns(&["id", "created_at"]))
+            .execute()
+            .await?
+            .try_collect::<Vec<_>>()
+            .await?;
+        let mut cached = Vec::new();
+        for batch in &batches {
+            let ids = string_array(batch, "id")?;
+            let created_at = timestamp_ms_array(batch, "created_at")?;
+            for row in 0..batch.num_rows() {
+                cached.push((value_string(ids, row), created_at.value(row)));
+            }
+        }
+        let evicted = image_variants_to_evict(cached, MAX_IMAGE_VARIANTS_PER_IMAGE);
+        if !evicted.is_empty() {
+            table
+                .delete(&format!("id IN ({})", quoted_literal_list(&evicted)))
+                .await
+                .context("failed to evict image variants")?;
+        }
+        Ok(())
+    }
+}
 
 #[derive(Debug, Clone)]
 struct IndexDiagnostic {
@@ -1903,6 +2294,117 @@ async fn fetch_category_descriptions(table: &Table) -> Result<HashMap<String, St
     Ok(descriptions)
 }
 
+struct SeriesMetadata {
+    title: String,
+    description: Option<String>,
+}
+
+async fn fetch_series_metadata(
+    table: &Table,
+    key: Option<&str>,
+) -> Result<HashMap<String, SeriesMetadata>> {
+    let filter = match key {
+        Some(key) => format!("kind = 'series' AND key = '{}'", escape_literal(key)),
+        None => "kind = 'series'".to_string(),
+    };
+    let batches = table
+        .query()
+        .only_if(filter)
+        .select(Select::columns(&["key", "name", "description"]))
+        .execute()
+        .await?;
+
+    let batch_list = batches.try_collect::<Vec<_>>().await?;
+    let mut metadata = HashMap::new();
+    for batch in &batch_list {
+        let key = string_array(batch, "key")?;
+        let name = string_array(batch, "name")?;
+        let description = string_array(batch, "description")?;
+        for row in 0..batch.num_rows() {
+            metadata.insert(value_string(key, row), SeriesMetadata {
+                title: value_string(name, row),
+                description: value_string_opt(description, row)
+                    .map(|value| value.trim().to_string())
+                    .filter(|value| !value.is_empty()),
+            });
+        }
+    }
+    Ok(metadata)
+}
+
+struct SeriesMember {
+    series_id: String,
+    order: Option<u32>,
+    article: ArticleListItem,
+}
+
+/// Explicit `series_order` first, then unordered parts by date and id so a
+/// series without orders still reads oldest-to-newest.
+fn sort_series_members(members: &mut [SeriesMember]) {
+    members.sort_by(|a, b| {
+        a.order
+            .is_none()
+            .cmp(&b.order.is_none())
+            .then_with(|| a.order.cmp(&b.order))
+            .then_with(|| a.article.date.cmp(&b.article.date))
+            .then_with(|| a.article.id.cmp(&b.article.id))
+    });
+}
+
+async fn fetch_series_members(table: &Table, series_id: Option<&str>) -> Result<Vec<SeriesMember>> {
+    let filter = match series_id {
+        Some(series_id) => format!("series_id = '{}'", escape_literal(series_id)),
+        None => "series_id IS NOT NULL".to_string(),
+    };
+    let query = table.query().only_if(filter).select(Select::columns(&[
+        "id",
+        "title",
+        "summary",
+        "tags",
+        "category",
+        "author",
+        "date",
+        "featured_image",
+        "read_time",
+        "article_kind",
+        "interactive_page_id",
+        "series_id",
+        "series_order",
+    ]));
+    let batch_list = match query.execute().await {
+        Ok(batches) => batches.try_collect::<Vec<_>>().await?,
+        Err(err) => {
+            let err_text = err.to_string();
+            if !(err_text.contains("series_id") || err_text.contains("series_order")) {
+                return Err(err.into());
+            }
+            tracing::warn!(
+                "Article table has no series columns yet (legacy schema); treating as no series: \
+                 {err_text}"
+            );
+            return Ok(Vec::new());
+        },
+    };
+
+    let mut members = Vec::new();
+    for batch in &batch_list {
+        let series_ids = string_array(batch, "series_id")?;
+        let orders = int32_array(batch, "series_order")?;
+        let articles = batches_to_article_list(std::slice::from_ref(batch))?;
+        for (row, article) in articles.into_iter().enumerate() {
+            let Some(series_id) = value_string_opt(series_ids, row) else {
+                continue;
+            };
+            members.push(SeriesMember {
+                series_id,
+                order: (!orders.is_null(row)).then(|| orders.value(row).max(0) as u32),
+                article,
+            });
+        }
+    }
+    Ok(members)
+}
+
 async fn fetch_article_list(
     table: &Table,
     tag: Option<&str>,
@@ -1911,23 +2413,11 @@ async fn fetch_article_list(
     let mut filters = Vec::new();
 
     if let Some(tag) = tag {
-        let tag_lower = tag.to_lowercase();
-        let escaped_tag = escape_literal(tag);
-        let escaped_lower = escape_literal(&tag_lower);
-        let tag_filter = if escaped_tag == escaped_lower {
-            format!("list_contains(tags, '{}')", escaped_tag)
-        } else {
-            format!(
-                "(list_contains(tags, '{}') OR list_contains(tags, '{}'))",
-                escaped_tag, escaped_lower
-            )
-        };
-        filters.push(tag_filter);
+        filters.push(tag_predicate(tag));
     }
 
     if let Some(category) = category {
-        let category_lower = category.to_lowercase();
-        filters.push(format!("lower(category) = '{}'", escape_literal(&category_lower)));
+        filters.push(category_predicate(category));
     }
 
     let mut query = table.query();
@@ -2024,6 +2514,8 @@ async fn fetch_article_detail(table: &Table, id: &str) -> Result<Option<Article>
         "article_kind",
         "source_url",
         "interactive_page_id",
+        "series_id",
+        "series_order",
     ];
     let base_columns = [
         "id",
@@ -2052,6 +2544,8 @@ async fn fetch_article_detail(table: &Table, id: &str) -> Result<Option<Article>
                 || err_text.contains("article_kind")
                 || err_text.contains("source_url")
                 || err_text.contains("interactive_page_id")
+                || err_text.contains("series_id")
+                || err_text.contains("series_order")
                 || err_text.contains("missing column");
             if !has_missing_new_columns {
                 return Err(err.into());
@@ -2213,6 +2707,7 @@ fn is_pure_english_query(keyword: &str) -> bool {
 async fn run_semantic_vector_search_with_fallback(
     table: &Table,
     keyword: &str,
+    filter: Option<&str>,
     limit: Option<usize>,
     max_distance: Option<f32>,
     enhanced_highlight: bool,
@@ -2249,6 +2744,7 @@ async fn run_semantic_vector_search_with_fallback(
         table,
         primary_column,
         query_embedding.as_slice(),
+        filter,
         limit,
         max_distance,
     )
@@ -2288,6 +2784,7 @@ async fn run_semantic_vector_search_with_fallback(
             table,
             fallback_column,
             fallback_embedding.as_slice(),
+            filter,
             limit,
             max_distance,
         )
@@ -2321,10 +2818,14 @@ async fn run_semantic_vector_search(
     table: &Table,
     vector_column: &str,
     query_embedding: &[f32],
+    filter: Option<&str>,
     limit: Option<usize>,
     max_distance: Option<f32>,
 ) -> Result<Vec<SearchArticleRow>> {
-    let filter = format!("{vector_column} IS NOT NULL");
+    let filter = match filter {
+        Some(filter) => format!("{vector_column} IS NOT NULL AND ({filter})"),
+        None => format!("{vector_column} IS NOT NULL"),
+    };
     let candidate_count = table.count_rows(Some(filter.clone())).await? as usize;
     if candidate_count == 0 {
         return Ok(vec![]);
@@ -2371,121 +2872,264 @@ struct SearchArticleRow {
     title: String,
     summary: String,
     content: String,
+    content_en: Option<String>,
     tags: Vec<String>,
     category: String,
     date: String,
 }
 
+impl SearchArticleRow {
+    fn document(&self) -> ArticleSearchDocument<'_> {
+        ArticleSearchDocument {
+            title: &self.title,
+            summary: &self.summary,
+            content: &self.content,
+            content_en: self.content_en.as_deref(),
+            tags: &self.tags,
+        }
+    }
+
+    /// Highlight taken from the field that matched best.
+    fn into_search_result(self, matched: &ArticleSearchMatch) -> SearchResult {
+        let source = match matched.column {
+            // A tag hit has no prose around it; show the summary.
+            "tags" => self.summary.clone(),
+            column => self.document().text(column),
+        };
+        SearchResult {
+            highlight: extract_highlight(&source, &matched.needle),
+            id: self.id,
+            title: self.title,
+            summary: self.summary,
+            category: self.category,
+            date: self.date,
+            tags: self.tags,
+        }
+    }
+}
+
+/// Ranked candidates fetched per search before filtering into pages; facet
+/// counts and `total` cover this window, and a full window is reported as
+/// a lower bound.
+const SEARCH_CANDIDATE_WINDOW: usize = 200;
+
+/// FTS candidates are re-checked for phrase, prefix and exclusion
+/// semantics, so fetch some headroom beyond the requested limit.
+const FTS_VERIFY_HEADROOM: usize = 2;
+
+/// Largest FTS candidate window fetched while collecting enough verified
+/// rows; the window doubles from `limit * FTS_VERIFY_HEADROOM` up to this.
+const MAX_FTS_VERIFY_CANDIDATES: usize = 2_000;
+
+/// Create the per-field FTS indexes described by [`ARTICLE_FTS_FIELDS`],
+/// replacing legacy default-tokenizer indexes on the same columns. Fields
+/// missing from older schemas are skipped; a failing field does not stop
+/// the others.
+pub async fn ensure_article_fts_indexes(table: &Table) -> Result<()> {
+    let schema = table.schema().await?;
+    let indices = table.list_indices().await?;
+    let mut failures = Vec::new();
+    for field in ARTICLE_FTS_FIELDS {
+        if schema.field_with_name(field.column).is_err() {
+            continue;
+        }
+        let index_name = field.index_name();
+        if indices.iter().any(|index| index.name == index_name) {
+            continue;
+        }
+        // Build the replacement first so a failed build leaves the legacy
+        // index serving the column.
+        if let Err(err) = table
+            .create_index(&[field.column], Index::FTS(field.index_params()))
+            .name(index_name.clone())
+            .execute()
+            .await
+        {
+            failures.push(format!("{}: {err}", field.column));
+            continue;
+        }
+        for stale in indices.iter().filter(|index| {
+            index.columns.len() == 1
+                && index.columns[0] == field.column
+                && index.name != index_name
+                && is_fts_index_type(&index.index_type)
+        }) {
+            if let Err(err) = table.drop_index(&stale.name).await {
+                failures.push(format!("drop {}: {err}", stale.name));
+            }
+        }
+    }
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        anyhow::bail!("failed to build article FTS indexes: {}", failures.join("; "))
+    }
+}
+
+/// Article fields that currently have an FTS index, any tokenizer.
+async fn indexed_article_fts_fields(table: &Table) -> Result<Vec<&'static ArticleFtsField>> {
+    let indices = table.list_indices().await?;
+    Ok(ARTICLE_FTS_FIELDS
+        .iter()
+        .filter(|field| {
+            indices.iter().any(|index| {
+                index.columns.len() == 1
+                    && index.columns[0] == field.column
+                    && is_fts_index_type(&index.index_type)
+            })
+        })
+        .collect())
+}
+
+/// Row columns for search results; `content_en` only exists on newer
+/// article schemas.
+async fn search_row_columns(table: &Table) -> Result<Vec<&'static str>> {
+    let mut columns = vec!["id", "title", "summary", "content", "tags", "category", "date"];
+    if table.schema().await?.field_with_name("content_en").is_ok() {
+        columns.push("content_en");
+    }
+    Ok(columns)
+}
+
 async fn search_with_fts_rows(
     table: &Table,
     keyword: &str,
+    filter: Option<&str>,
     limit: Option<usize>,
-) -> Result<Vec<SearchArticleRow>> {
+) -> Result<Vec<(SearchArticleRow, ArticleSearchMatch)>> {
     if limit == Some(0) {
         return Ok(vec![]);
     }
 
-    let mut query = table
-        .query()
-        .full_text_search(FullTextSearchQuery::new(keyword.to_string()));
-    if let Some(limit) = limit {
-        query = query.limit(limit);
+    let parsed = ArticleSearchQuery::parse(keyword);
+    let fields = indexed_article_fts_fields(table).await?;
+    let Some(fts_query) = parsed.fts_query(&fields) else {
+        if parsed.is_empty() {
+            return Ok(vec![]);
+        }
+        anyhow::bail!("no article FTS index available");
+    };
+
+    let mut columns = search_row_columns(table).await?;
+    columns.push("_score");
+    let mut window = limit.map(|limit| {
+        limit
+            .saturating_mul(FTS_VERIFY_HEADROOM)
+            .min(MAX_FTS_VERIFY_CANDIDATES)
+    });
+    loop {
+        let mut query = table.query().full_text_search(fts_query.clone());
+        if let Some(filter) = filter {
+            query = query.only_if(filter);
+        }
+        if let Some(window) = window {
+            query = query.limit(window);
+        }
+        let batch_list = query
+            .select(Select::columns(&columns))
+            .execute()
+            .await?
+            .try_collect::<Vec<_>>()
+            .await?;
+        let candidates = batches_to_search_rows(&batch_list)?;
+        let fetched = candidates.len();
+        let mut rows = verify_fts_candidates(&parsed, candidates);
+        match (limit, window) {
+            (Some(limit), Some(current)) => {
+                match next_fts_verify_window(limit, current, fetched, rows.len()) {
+                    Some(next) => window = Some(next),
+                    None => {
+                        rows.truncate(limit);
+                        return Ok(rows);
+                    },
+                }
+            },
+            _ => return Ok(rows),
+        }
     }
+}
 
-    let batches = query
-        .select(Select::columns(&[
-            "id", "title", "summary", "content", "tags", "category", "date", "_score",
-        ]))
-        .execute()
-        .await?;
+/// Keep the FTS candidates that pass the phrase, prefix and exclusion
+/// checks, ranked by boosted field score. Equal scores keep FTS order.
+fn verify_fts_candidates(
+    parsed: &ArticleSearchQuery,
+    candidates: Vec<SearchArticleRow>,
+) -> Vec<(SearchArticleRow, ArticleSearchMatch)> {
+    let mut rows = candidates
+        .into_iter()
+        .filter_map(|row| parsed.score(&row.document()).map(|matched| (row, matched)))
+        .collect::<Vec<_>>();
+    rows.sort_by(|a, b| b.1.score.total_cmp(&a.1.score));
+    rows
+}
 
-    let batch_list = batches.try_collect::<Vec<_>>().await?;
-    batches_to_search_rows(&batch_list)
+/// Next candidate window when verification left fewer than `limit` rows and
+/// the FTS index may still hold more candidates; `None` when done.
+fn next_fts_verify_window(
+    limit: usize,
+    window: usize,
+    fetched: usize,
+    verified: usize,
+) -> Option<usize> {
+    if verified >= limit || fetched < window || window >= MAX_FTS_VERIFY_CANDIDATES {
+        return None;
+    }
+    Some(window.saturating_mul(2).min(MAX_FTS_VERIFY_CANDIDATES))
 }
 
 async fn search_with_fts(
     table: &Table,
     keyword: &str,
+    filter: Option<&str>,
     limit: Option<usize>,
 ) -> Result<Vec<SearchResult>> {
-    let rows = search_with_fts_rows(table, keyword, limit).await?;
+    let rows = search_with_fts_rows(table, keyword, filter, limit).await?;
 
     Ok(rows
         .into_iter()
-        .map(|row| SearchResult {
-            highlight: extract_highlight(&row.content, keyword),
-            id: row.id,
-            title: row.title,
-            summary: row.summary,
-            category: row.category,
-            date: row.date,
-            tags: row.tags,
-        })
+        .map(|(row, matched)| row.into_search_result(&matched))
         .collect())
 }
 
 async fn fallback_search_rows(
     table: &Table,
     keyword: &str,
+    filter: Option<&str>,
     limit: Option<usize>,
-) -> Result<Vec<SearchArticleRow>> {
-    let batches = table
-        .query()
-        .select(Select::columns(&["id", "title", "summary", "content", "tags", "category", "date"]))
-        .execute()
-        .await?;
+) -> Result<Vec<(SearchArticleRow, ArticleSearchMatch)>> {
+    let columns = search_row_columns(table).await?;
+    let mut query = table.query();
+    if let Some(filter) = filter {
+        query = query.only_if(filter);
+    }
+    let batches = query.select(Select::columns(&columns)).execute().await?;
 
     let batch_list = batches.try_collect::<Vec<_>>().await?;
     let rows = batches_to_search_rows(&batch_list)?;
 
-    let keyword_lower = keyword.to_lowercase();
-    let mut scored = Vec::new();
-    for row in rows {
-        let mut score = 0;
-        if row.title.to_lowercase().contains(&keyword_lower) {
-            score += 10;
-        }
-        if row.summary.to_lowercase().contains(&keyword_lower) {
-            score += 5;
-        }
-        if row.content.to_lowercase().contains(&keyword_lower) {
-            score += 1;
-        }
-        for tag in &row.tags {
-            if tag.to_lowercase().contains(&keyword_lower) {
-                score += 3;
-            }
-        }
-        if score > 0 {
-            scored.push((row, score));
-        }
-    }
+    let parsed = ArticleSearchQuery::parse(keyword);
+    let mut scored = rows
+        .into_iter()
+        .filter_map(|row| parsed.score(&row.document()).map(|matched| (row, matched)))
+        .collect::<Vec<_>>();
 
-    scored.sort_by(|a, b| b.1.cmp(&a.1));
-    let mut rows = scored.into_iter().map(|(row, _)| row).collect::<Vec<_>>();
+    scored.sort_by(|a, b| b.1.score.total_cmp(&a.1.score));
     if let Some(limit) = limit {
-        rows.truncate(limit);
+        scored.truncate(limit);
     }
-    Ok(rows)
+    Ok(scored)
 }
 
 async fn fallback_search(
     table: &Table,
     keyword: &str,
+    filter: Option<&str>,
     limit: Option<usize>,
 ) -> Result<Vec<SearchResult>> {
-    let rows = fallback_search_rows(table, keyword, limit).await?;
+    let rows = fallback_search_rows(table, keyword, filter, limit).await?;
     Ok(rows
         .into_iter()
-        .map(|row| SearchResult {
-            highlight: extract_highlight(&row.content, keyword),
-            id: row.id,
-            title: row.title,
-            summary: row.summary,
-            category: row.category,
-            date: row.date,
-            tags: row.tags,
-        })
+        .map(|(row, matched)| row.into_search_result(&matched))
         .collect())
 }
 
@@ -2553,6 +3197,7 @@ fn batches_to_search_rows(batches: &[RecordBatch]) -> Result<Vec<SearchArticleRo
         let tags = list_array(batch, "tags")?;
         let category = string_array(batch, "category")?;
         let date = string_array(batch, "date")?;
+        let content_en = optional_string_array(batch, "content_en");
 
         for row in 0..batch.num_rows() {
             rows.push(SearchArticleRow {
@@ -2560,6 +3205,7 @@ fn batches_to_search_rows(batches: &[RecordBatch]) -> Result<Vec<SearchArticleRo
                 title: value_string(title, row),
                 summary: value_string(summary, row),
                 content: value_string(content, row),
+                content_en: content_en.and_then(|array| value_string_opt(array, row)),
                 tags: value_string_list(tags, row),
                 category: value_string(category, row),
                 date: value_string(date, row),
@@ -2627,6 +3273,8 @@ fn batches_to_articles(batches: &[RecordBatch]) -> Result<Vec<Article>> {
         let article_kind = optional_string_array(batch, "article_kind");
         let source_url = optional_string_array(batch, "source_url");
         let interactive_page_id = optional_string_array(batch, "interactive_page_id");
+        let series_id = optional_string_array(batch, "series_id");
+        let series_order = optional_int32_array(batch, "series_order");
 
         for row in 0..batch.num_rows() {
             articles.push(Article {
@@ -2652,6 +3300,19 @@ fn batches_to_articles(batches: &[RecordBatch]) -> Result<Vec<Article>> {
                 source_url: source_url.and_then(|array| value_string_opt(array, row)),
                 interactive_page_id: interactive_page_id
                     .and_then(|array| value_string_opt(array, row)),
+                series: series_id
+                    .and_then(|array| value_string_opt(array, row))
+                    .map(|id| ArticleSeriesNav {
+                        title: id.clone(),
+                        id,
+                        order: series_order
+                            .filter(|array| !array.is_null(row))
+                            .map(|array| array.value(row).max(0) as u32),
+                        position: 0,
+                        total: 0,
+                        prev: None,
+                        next: None,
+                    }),
             });
         }
     }
@@ -2733,6 +3394,60 @@ fn normalize_daily_window(days: usize, max_days: usize) -> usize {
     days.clamp(1, upper)
 }
 
+fn image_variant_schema() -> Arc<Schema> {
+    Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("image_id", DataType::Utf8, false),
+        Field::new("variant_key", DataType::Utf8, false),
+        low_cardinality_utf8_field("mime_type", false),
+        Field::new("data", DataType::Binary, false),
+        Field::new("width", DataType::Int32, false),
+        Field::new("height", DataType::Int32, false),
+        Field::new("created_at", DataType::Timestamp(TimeUnit::Millisecond, None), false),
+    ]))
+}
+
+fn image_variant_row_id(image_id: &str, variant_key: &str) -> String {
+    format!("{image_id}:{variant_key}")
+}
+
+/// Ids of the oldest `(id, created_at)` renditions beyond `cap`.
+fn image_variants_to_evict(mut cached: Vec<(String, i64)>, cap: usize) -> Vec<String> {
+    if cached.len() <= cap {
+        return vec![];
+    }
+    cached.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+    let excess = cached.len() - cap;
+    cached.into_iter().take(excess).map(|(id, _)| id).collect()
+}
+
+fn quoted_literal_list(values: &[String]) -> String {
+    values
+        .iter()
+        .map(|value| format!("'{}'", escape_literal(value)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn build_image_variant_batch(
+    image_id: &str,
+    variant_key: &str,
+    variant: &RenderedImageVariant,
+) -> Result<RecordBatch> {
+    let schema = image_variant_schema();
+    let arrays: Vec<ArrayRef> = vec![
+        Arc::new(StringArray::from(vec![image_variant_row_id(image_id, variant_key)])),
+        Arc::new(StringArray::from(vec![image_id])),
+        Arc::new(StringArray::from(vec![variant_key])),
+        Arc::new(StringArray::from(vec![variant.mime_type.as_str()])),
+        Arc::new(BinaryArray::from(vec![variant.bytes.as_slice()])),
+        Arc::new(Int32Array::from(vec![variant.width as i32])),
+        Arc::new(Int32Array::from(vec![variant.height as i32])),
+        Arc::new(TimestampMillisecondArray::from(vec![Utc::now().timestamp_millis()])),
+    ];
+    Ok(RecordBatch::try_new(schema, arrays)?)
+}
+
 fn article_view_schema() -> Arc<Schema> {
     Arc::new(Schema::new(vec![
         Field::new("id", DataType::Utf8, false),
@@ -3099,6 +3814,14 @@ fn int32_array<'a>(batch: &'a RecordBatch, name: &str) -> Result<&'a arrow_array
         .with_context(|| format!("column {name} is not Int32Array"))
 }
 
+fn optional_int32_array<'a>(batch: &'a RecordBatch, name: &str) -> Option<&'a Int32Array> {
+    batch
+        .schema()
+        .index_of(name)
+        .ok()
+        .and_then(|idx| batch.column(idx).as_any().downcast_ref::<Int32Array>())
+}
+
 fn timestamp_ms_array<'a>(
     batch: &'a RecordBatch,
     name: &str,
@@ -3867,15 +4590,206 @@ mod tests {
 
     use super::{
         alternate_embedding_language, api_behavior_schema, choose_primary_search_language,
-        cosine_similarity, extract_highlight, extract_semantic_highlight,
-        find_case_insensitive_match_range, is_pure_english_query,
-        quarantine_zero_byte_lance_tail_files, semantic_query_tokens,
+        cosine_similarity, ensure_article_fts_indexes, extract_highlight,
+        extract_semantic_highlight, find_case_insensitive_match_range, image_variants_to_evict,
+        is_pure_english_query, next_fts_verify_window, quarantine_zero_byte_lance_tail_files,
+        search_with_fts_rows, semantic_query_tokens, sort_series_members,
         split_text_by_sentence_or_size, table_uses_stable_row_ids, vector_column_for_language,
-        zero_byte_tail_quarantine_root, CompactAction, NewApiBehaviorEventInput,
+        verify_fts_candidates, zero_byte_tail_quarantine_root, ArticleListItem, ArticleSearchQuery,
+        CompactAction, NewApiBehaviorEventInput, SearchArticleRow, SeriesMember,
         StaticFlowDataStore, TextEmbeddingLanguage, CONTENT_COMPACTION_TABLE_NAMES,
-        CONTENT_TABLE_NAMES,
+        CONTENT_TABLE_NAMES, MAX_FTS_VERIFY_CANDIDATES,
     };
 
+    async fn sample_articles_table(uri: &str) -> lancedb::Table {
+        use arrow_array::{
+            builder::{ListBuilder, StringBuilder},
+            ArrayRef, RecordBatch, RecordBatchIterator, RecordBatchReader, StringArray,
+        };
+        use arrow_schema::{DataType, Field, Schema};
+
+        let posts = crate::article_search::sample_posts::load();
+        let strings = |values: Vec<&str>| Arc::new(StringArray::from(values)) as ArrayRef;
+        let mut tags = ListBuilder::new(StringBuilder::new());
+        for post in &posts {
+            for tag in &post.tags {
+                tags.values().append_value(tag);
+            }
+            tags.append(true);
+        }
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Utf8, false),
+            Field::new("title", DataType::Utf8, false),
+            Field::new("summary", DataType::Utf8, false),
+            Field::new("content", DataType::Utf8, false),
+            Field::new("content_en", DataType::Utf8, true),
+            Field::new(
+                "tags",
+                DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))),
+                true,
+            ),
+            Field::new("category", DataType::Utf8, false),
+            Field::new("date", DataType::Utf8, false),
+        ]));
+        let batch = RecordBatch::try_new(schema.clone(), vec![
+            strings(posts.iter().map(|post| post.id).collect()),
+            strings(posts.iter().map(|post| post.title.as_str()).collect()),
+            strings(posts.iter().map(|post| post.summary.as_str()).collect()),
+            strings(posts.iter().map(|post| post.content.as_str()).collect()),
+            Arc::new(StringArray::from(vec![None::<&str>; posts.len()])) as ArrayRef,
+            Arc::new(tags.finish()) as ArrayRef,
+            strings(vec!["Tech"; posts.len()]),
+            strings(vec!["2024-01-01"; posts.len()]),
+        ])
+        .expect("sample batch");
+        let batches = RecordBatchIterator::new(vec![Ok(batch)].into_iter(), schema);
+        lancedb::connect(uri)
+            .execute()
+            .await
+            .expect("connect temp db")
+            .create_table("articles", Box::new(batches) as Box<dyn RecordBatchReader + Send>)
+            .execute()
+            .await
+            .expect("create articles table")
+    }
+
+    fn series_member(id: &str, date: &str, order: Option<u32>) -> SeriesMember {
+        SeriesMember {
+            series_id: "rust-deep-dive".to_string(),
+            order,
+            article: ArticleListItem {
+                id: id.to_string(),
+                title: id.to_string(),
+                summary: String::new(),
+                tags: vec![],
+                category: "Tech".to_string(),
+                author: "Ada".to_string(),
+                date: date.to_string(),
+                featured_image: None,
+                read_time: 1,
+                article_kind: Default::default(),
+                interactive_page_id: None,
+            },
+        }
+    }
+
+    #[test]
+    fn series_members_sort_by_order_then_date() {
+        let mut members = vec![
+            series_member("extra-b", "2024-03-01", None),
+            series_member("part-2", "2024-01-01", Some(2)),
+            series_member("extra-a", "2024-02-01", None),
+            series_member("part-1", "2024-05-01", Some(1)),
+        ];
+        sort_series_members(&mut members);
+        let ids = members
+            .iter()
+            .map(|member| member.article.id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["part-1", "part-2", "extra-a", "extra-b"]);
+    }
+
+    #[tokio::test]
+    async fn article_fts_ranks_sample_posts_across_fields() {
+        let dir = temp_db_dir();
+        fs::create_dir_all(&dir).expect("create temp db dir");
+        let table = sample_articles_table(&dir.to_string_lossy()).await;
+        ensure_article_fts_indexes(&table)
+            .await
+            .expect("build article fts indexes");
+
+        let top = |rows: Vec<(super::SearchArticleRow, super::ArticleSearchMatch)>| {
+            rows.into_iter()
+                .map(|(row, matched)| (row.id, matched.column))
+                .collect::<Vec<_>>()
+        };
+        let rows = top(search_with_fts_rows(&table, "协调", None, Some(3))
+            .await
+            .expect("search"));
+        assert_eq!(rows[0], ("es-cluster-coordination-evolution".to_string(), "title"));
+        let rows = top(search_with_fts_rows(&table, "\"Stable Row ID\"", None, Some(3))
+            .await
+            .expect("search"));
+        assert_eq!(rows, vec![("lance-stable-row-id-deep-dive".to_string(), "title")]);
+        let rows = top(search_with_fts_rows(&table, "rust -yew", None, Some(5))
+            .await
+            .expect("search"));
+        assert!(rows.iter().all(|(id, _)| id != "post-001"));
+        let rows = top(search_with_fts_rows(&table, "elastic*", None, Some(3))
+            .await
+            .expect("search"));
+        assert_eq!(rows[0].0, "es-cluster-coordination-evolution");
+        let rust_only = crate::article_search::ArticleSearchFilters {
+            tags: vec!["Rust".to_string()],
+            ..Default::default()
+        }
+        .predicate();
+        let rows = top(search_with_fts_rows(&table, "rust", rust_only.as_deref(), Some(5))
+            .await
+            .expect("search"));
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].0, "post-001");
+
+        fs::remove_dir_all(&dir).expect("cleanup temp db dir");
+    }
+
+    fn search_row(id: &str, title: &str, content: &str) -> SearchArticleRow {
+        SearchArticleRow {
+            id: id.to_string(),
+            title: title.to_string(),
+            summary: String::new(),
+            content: content.to_string(),
+            content_en: None,
+            tags: vec![],
+            category: "Tech".to_string(),
+            date: "2024-01-01".to_string(),
+        }
+    }
+
+    #[test]
+    fn verified_fts_rows_are_ranked_by_boosted_score() {
+        let parsed = ArticleSearchQuery::parse("\"row id\" -draft");
+        let rows = verify_fts_candidates(&parsed, vec![
+            search_row("body-hit", "Notes", "stable row id internals"),
+            search_row("excluded", "Row id draft", "row id"),
+            search_row("phrase-missing", "Row", "id"),
+            search_row("title-hit", "Row ID design", "row id in lance"),
+        ]);
+        let ids = rows
+            .iter()
+            .map(|(row, _)| row.id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["title-hit", "body-hit"]);
+        assert_eq!(rows[0].1.column, "title");
+    }
+
+    #[test]
+    fn fts_verify_window_grows_until_limit_or_exhaustion() {
+        assert_eq!(next_fts_verify_window(10, 20, 20, 4), Some(40));
+        assert_eq!(next_fts_verify_window(10, 20, 20, 10), None);
+        assert_eq!(next_fts_verify_window(10, 20, 13, 4), None);
+        assert_eq!(
+            next_fts_verify_window(10, MAX_FTS_VERIFY_CANDIDATES / 2 + 1, usize::MAX, 0),
+            Some(MAX_FTS_VERIFY_CANDIDATES)
+        );
+        assert_eq!(
+            next_fts_verify_window(10, MAX_FTS_VERIFY_CANDIDATES, MAX_FTS_VERIFY_CANDIDATES, 0),
+            None
+        );
+    }
+
+    #[test]
+    fn image_variant_eviction_drops_oldest_past_cap() {
+        let cached = vec![
+            ("img:c".to_string(), 30),
+            ("img:a".to_string(), 10),
+            ("img:d".to_string(), 40),
+            ("img:b".to_string(), 10),
+        ];
+        assert_eq!(image_variants_to_evict(cached.clone(), 4), Vec::<String>::new());
+        assert_eq!(image_variants_to_evict(cached, 2), vec!["img:a", "img:b"]);
+    }
+
     #[test]
     fn content_compaction_tables_include_api_behavior_events() {
         assert!(CONTENT_TABLE_NAMES.contains(&"api_behavior_events"));
@@ -13,6 +13,14 @@
 )]
 pub mod comments_store;
 
+/// Article full-text index layout, query syntax, and field-weighted scoring.
+#[allow(
+    missing_docs,
+    reason = "Search DTOs and index field descriptors are shared with the lancedb_api module and \
+              documented where they are consumed."
+)]
+pub mod article_search;
+
 /// Content database queries and article/image API data structures.
 #[allow(
     missing_docs,
@@ -45,6 +53,14 @@ pub mod music_wish_store;
 )]
 pub mod article_request_store;
 
+/// Article revision history, diffs, and rollback helpers.
+#[allow(
+    missing_docs,
+    reason = "The revision store exports snapshot and diff DTOs shared by the CLI and backend; \
+              item-level docs live next to the non-obvious helpers."
+)]
+pub mod article_revision_store;
+
 /// Interactive page mirror storage and asset management helpers.
 #[allow(
     missing_docs,
@@ -70,6 +86,14 @@ pub mod optimize;
 
 mod lance_schema_encoding;
 
+/// Resized / re-encoded image renditions and ingest-time placeholders.
+#[allow(
+    missing_docs,
+    reason = "Rendition specs and placeholder DTOs are consumed by the image endpoint and CLI \
+              ingest; the module docs describe the negotiation and limits."
+)]
+pub mod image_variants;
+
 /// Maintenance routines for rebuilding image embedding vectors.
 #[allow(
     missing_docs,